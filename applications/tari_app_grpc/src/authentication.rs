@@ -0,0 +1,100 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::sync::Arc;
+
+use tonic::{service::Interceptor, Request, Status};
+
+const AUTHORIZATION_HEADER: &str = "authorization";
+
+/// Rejects gRPC requests that do not carry the configured API token as a bearer token. All requests are allowed when
+/// no token is configured.
+#[derive(Clone, Default)]
+pub struct BearerTokenInterceptor {
+    expected_header: Option<Arc<Vec<u8>>>,
+}
+
+impl BearerTokenInterceptor {
+    pub fn new(api_token: Option<&str>) -> Self {
+        Self {
+            expected_header: api_token.map(|token| Arc::new(format!("Bearer {}", token).into_bytes())),
+        }
+    }
+}
+
+impl Interceptor for BearerTokenInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let expected = match self.expected_header.as_ref() {
+            Some(expected) => expected,
+            None => return Ok(request),
+        };
+        match request.metadata().get(AUTHORIZATION_HEADER) {
+            Some(value) if constant_time_eq(value.as_bytes(), expected) => Ok(request),
+            Some(_) => Err(Status::unauthenticated("Invalid API token")),
+            None => Err(Status::unauthenticated("Missing API token")),
+        }
+    }
+}
+
+/// Compares the tokens without exiting early, so that the time taken does not reveal how much of a guess was correct
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn request_with_header(value: Option<&str>) -> Request<()> {
+        let mut request = Request::new(());
+        if let Some(value) = value {
+            request
+                .metadata_mut()
+                .insert(AUTHORIZATION_HEADER, value.parse().unwrap());
+        }
+        request
+    }
+
+    #[test]
+    fn it_allows_all_requests_without_a_token() {
+        let mut interceptor = BearerTokenInterceptor::new(None);
+        assert!(interceptor.call(request_with_header(None)).is_ok());
+        assert!(interceptor.call(request_with_header(Some("Bearer anything"))).is_ok());
+    }
+
+    #[test]
+    fn it_requires_the_configured_token() {
+        let mut interceptor = BearerTokenInterceptor::new(Some("secret"));
+        assert!(interceptor.call(request_with_header(Some("Bearer secret"))).is_ok());
+        let err = interceptor
+            .call(request_with_header(Some("Bearer secreT")))
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+        let err = interceptor.call(request_with_header(Some("secret"))).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+        let err = interceptor.call(request_with_header(None)).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+    }
+}
@@ -20,9 +20,12 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use tari_core::base_node::state_machine_service::states::{
-    StateInfo,
-    StateInfo::{BlockSync, BlockSyncStarting, HeaderSync, HorizonSync, Listening, StartUp},
+use tari_core::base_node::{
+    state_machine_service::states::{
+        StateInfo,
+        StateInfo::{BlockSync, BlockSyncStarting, HeaderSync, HorizonSync, Listening, StartUp},
+    },
+    sync::HorizonSyncStatus,
 };
 
 use crate::tari_rpc as grpc;
@@ -52,3 +55,26 @@ impl From<&StateInfo> for grpc::BaseNodeState {
         }
     }
 }
+
+impl From<&HorizonSyncStatus> for grpc::HorizonSyncProgress {
+    fn from(status: &HorizonSyncStatus) -> Self {
+        let (stage, current, total) = match status {
+            HorizonSyncStatus::Starting => (grpc::HorizonSyncStage::HorizonStarting, 0, 0),
+            HorizonSyncStatus::Kernels { current, total, .. } => {
+                (grpc::HorizonSyncStage::HorizonKernels, *current, *total)
+            },
+            HorizonSyncStatus::Outputs { current, total, .. } => {
+                (grpc::HorizonSyncStage::HorizonOutputs, *current, *total)
+            },
+            HorizonSyncStatus::Verifying { current, total } => {
+                (grpc::HorizonSyncStage::HorizonVerifying, *current, *total)
+            },
+            HorizonSyncStatus::Finalizing => (grpc::HorizonSyncStage::HorizonFinalizing, 0, 0),
+        };
+        Self {
+            stage: stage.into(),
+            current,
+            total,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{fs, io, path::Path};
+
+use tari_common::configuration::GrpcTlsConfig;
+use tonic::transport::{Certificate, Identity, ServerTlsConfig};
+
+/// Loads the certificates and key in `config` into a TLS config for a tonic server
+pub fn server_tls_config(config: &GrpcTlsConfig) -> Result<ServerTlsConfig, io::Error> {
+    let cert = read_pem(&config.cert_file)?;
+    let key = read_pem(&config.key_file)?;
+    let mut tls_config = ServerTlsConfig::new().identity(Identity::from_pem(cert, key));
+    if let Some(client_ca_file) = config.client_ca_file.as_ref() {
+        tls_config = tls_config.client_ca_root(Certificate::from_pem(read_pem(client_ca_file)?));
+    }
+    Ok(tls_config)
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>, io::Error> {
+    fs::read(path).map_err(|e| io::Error::new(e.kind(), format!("Could not read '{}': {}", path.display(), e)))
+}
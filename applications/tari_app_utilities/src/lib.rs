@@ -22,6 +22,7 @@
 
 pub mod common_cli_args;
 pub mod identity_management;
+pub mod telemetry;
 pub mod utilities;
 
 pub mod consts {
@@ -0,0 +1,244 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Exports the `tracing` spans of an application, such as the RPC and bounded executor spans, to a Jaeger agent or an
+//! OpenTelemetry (OTLP) collector.
+//!
+//! ```toml
+//! [tracing]
+//! enabled = true
+//! exporter = "otlp"
+//! otlp_endpoint = "http://localhost:4317"
+//! default_sampling_rate = 0.1
+//! # Span names starting with these prefixes use the given sampling rate
+//! sampling_rates = { "rpc::server" = 1.0, "bounded_executor" = 0.01 }
+//! ```
+
+use std::{collections::HashMap, env, process};
+
+use opentelemetry::{
+    global,
+    sdk::{
+        trace::{self, Sampler, SamplingResult, ShouldSample},
+        Resource,
+    },
+    trace::{Link, SpanKind, TraceId, TraceResult},
+    Context,
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use serde::{Deserialize, Serialize};
+use tari_common::SubConfigPath;
+use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+use crate::consts;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TracingExporter {
+    /// Export to a Jaeger agent on the default agent address (localhost:6831)
+    Jaeger,
+    /// Export to an OpenTelemetry collector using the OTLP gRPC protocol
+    Otlp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TracingConfig {
+    override_from: Option<String>,
+    /// Export tracing spans. This can also be enabled with the `--tracing` command line flag.
+    /// Default: false
+    pub enabled: bool,
+    /// Default: jaeger
+    pub exporter: TracingExporter,
+    /// The endpoint of the OpenTelemetry collector when using the otlp exporter.
+    /// Default: http://localhost:4317
+    pub otlp_endpoint: String,
+    /// The fraction of traces that are sampled when a span name does not match any prefix in `sampling_rates`.
+    /// Default: 1.0
+    pub default_sampling_rate: f64,
+    /// Sampling rates for spans whose names start with the given prefix, e.g. `rpc::server`. The longest matching
+    /// prefix is used.
+    /// Default: empty
+    pub sampling_rates: HashMap<String, f64>,
+}
+
+impl Default for TracingConfig {
+    fn default() -> Self {
+        Self {
+            override_from: None,
+            enabled: false,
+            exporter: TracingExporter::Jaeger,
+            otlp_endpoint: "http://localhost:4317".to_string(),
+            default_sampling_rate: 1.0,
+            sampling_rates: HashMap::new(),
+        }
+    }
+}
+
+impl SubConfigPath for TracingConfig {
+    fn main_key_prefix() -> &'static str {
+        "tracing"
+    }
+}
+
+/// Installs a global tracing subscriber that exports spans according to the given config. Call
+/// `opentelemetry::global::shutdown_tracer_provider` before exiting to flush any remaining spans.
+pub fn install(service_name: &'static str, config: &TracingConfig) -> TraceResult<()> {
+    let trace_config = trace::config()
+        .with_sampler(Sampler::ParentBased(Box::new(SpanNameSampler::new(
+            config.default_sampling_rate,
+            &config.sampling_rates,
+        ))))
+        .with_resource(Resource::new(vec![
+            KeyValue::new("service.name", service_name),
+            KeyValue::new("pid", process::id().to_string()),
+            KeyValue::new(
+                "current_exe",
+                env::current_exe()
+                    .ok()
+                    .and_then(|p| p.to_str().map(ToString::to_string))
+                    .unwrap_or_default(),
+            ),
+            KeyValue::new("version", consts::APP_VERSION),
+        ]));
+
+    let tracer = match config.exporter {
+        TracingExporter::Jaeger => {
+            // To run:
+            // docker run -d -p6831:6831/udp -p6832:6832/udp -p16686:16686 -p14268:14268 jaegertracing/all-in-one:latest
+            // To view the UI after starting the container (default):
+            // http://localhost:16686
+            global::set_text_map_propagator(opentelemetry_jaeger::Propagator::new());
+            opentelemetry_jaeger::new_pipeline()
+                .with_service_name(service_name)
+                .with_trace_config(trace_config)
+                .install_batch(opentelemetry::runtime::Tokio)?
+        },
+        TracingExporter::Otlp => opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(config.otlp_endpoint.clone()),
+            )
+            .with_trace_config(trace_config)
+            .install_batch(opentelemetry::runtime::Tokio)?,
+    };
+
+    let telemetry = tracing_opentelemetry::layer().with_tracer(tracer);
+    let subscriber = Registry::default().with(telemetry);
+    tracing::subscriber::set_global_default(subscriber)
+        .expect("Tracing could not be set. Try running without `--tracing-enabled`");
+    Ok(())
+}
+
+/// Flushes any remaining spans to the exporter
+pub fn shutdown() {
+    global::shutdown_tracer_provider();
+}
+
+/// Samples root spans at a rate determined by the longest matching span name prefix
+#[derive(Debug, Clone)]
+struct SpanNameSampler {
+    default_sampler: Sampler,
+    /// Ordered by descending prefix length so that the longest match is found first
+    prefix_samplers: Vec<(String, Sampler)>,
+}
+
+impl SpanNameSampler {
+    fn new(default_rate: f64, rates: &HashMap<String, f64>) -> Self {
+        let mut prefix_samplers = rates
+            .iter()
+            .map(|(prefix, rate)| (prefix.clone(), ratio_sampler(*rate)))
+            .collect::<Vec<_>>();
+        prefix_samplers.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+        Self {
+            default_sampler: ratio_sampler(default_rate),
+            prefix_samplers,
+        }
+    }
+
+    fn sampler_for(&self, name: &str) -> &Sampler {
+        self.prefix_samplers
+            .iter()
+            .find(|(prefix, _)| name.starts_with(prefix.as_str()))
+            .map(|(_, sampler)| sampler)
+            .unwrap_or(&self.default_sampler)
+    }
+}
+
+fn ratio_sampler(rate: f64) -> Sampler {
+    if rate >= 1.0 {
+        Sampler::AlwaysOn
+    } else if rate <= 0.0 {
+        Sampler::AlwaysOff
+    } else {
+        Sampler::TraceIdRatioBased(rate)
+    }
+}
+
+impl ShouldSample for SpanNameSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        self.sampler_for(name)
+            .should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_uses_the_longest_matching_prefix() {
+        let mut rates = HashMap::new();
+        rates.insert("rpc".to_string(), 0.5);
+        rates.insert("rpc::server".to_string(), 0.0);
+        rates.insert("bounded_executor".to_string(), 1.0);
+        let sampler = SpanNameSampler::new(0.1, &rates);
+
+        assert!(matches!(
+            sampler.sampler_for("rpc::server::handle_req"),
+            Sampler::AlwaysOff
+        ));
+        assert!(matches!(
+            sampler.sampler_for("rpc::client::perform_client_handshake"),
+            Sampler::TraceIdRatioBased(_)
+        ));
+        assert!(matches!(
+            sampler.sampler_for("bounded_executor::do_work"),
+            Sampler::AlwaysOn
+        ));
+        assert!(matches!(
+            sampler.sampler_for("noise::upgrade_socket"),
+            Sampler::TraceIdRatioBased(r) if (r - 0.1).abs() < f64::EPSILON
+        ));
+    }
+}
@@ -28,7 +28,13 @@ use tari_common::{
     configuration::bootstrap::ApplicationType,
     exit_codes::{ExitCode, ExitError},
 };
-use tari_comms::{peer_manager::Peer, protocol::rpc::RpcServer, NodeIdentity, UnspawnedCommsNode};
+use tari_comms::{
+    peer_manager::{NodeId, Peer},
+    protocol::rpc::{AdaptiveChunkingConfig, RpcChunkingConfig, RpcServer},
+    types::CommsPublicKey,
+    NodeIdentity,
+    UnspawnedCommsNode,
+};
 use tari_comms_dht::Dht;
 use tari_core::{
     base_node,
@@ -43,6 +49,7 @@ use tari_core::{
     consensus::ConsensusManager,
     mempool,
     mempool::{service::MempoolHandle, Mempool, MempoolServiceInitializer, MempoolSyncInitializer},
+    proof_of_work::randomx_factory::RandomXFactory,
     transactions::CryptoFactories,
 };
 use tari_p2p::{
@@ -57,6 +64,7 @@ use tari_p2p::{
 };
 use tari_service_framework::{ServiceHandles, StackBuilder};
 use tari_shutdown::ShutdownSignal;
+use tari_utilities::hex::Hex;
 
 use crate::ApplicationConfig;
 
@@ -71,6 +79,7 @@ pub struct BaseNodeBootstrapper<'a, B> {
     pub mempool: Mempool,
     pub rules: ConsensusManager,
     pub factories: CryptoFactories,
+    pub randomx_factory: RandomXFactory,
     pub interrupt_signal: ShutdownSignal,
 }
 
@@ -97,7 +106,27 @@ where B: BlockchainBackend + 'static
 
         debug!(target: LOG_TARGET, "{} sync peer(s) configured", sync_peers.len());
 
-        let mempool_sync = MempoolSyncInitializer::new(mempool_config, self.mempool.clone());
+        let reserved_rpc_peers = p2p_config
+            .rpc_reserved_session_public_keys
+            .iter()
+            .map(|s| CommsPublicKey::from_hex(s).map(|pk| NodeId::from_public_key(&pk)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ExitError::new(ExitCode::ConfigError, &e))?;
+        if base_node_config.seed_node.enabled {
+            info!(
+                target: LOG_TARGET,
+                "Seed node mode is enabled. Block and transaction relay is disabled."
+            );
+        }
+        if base_node_config.standby {
+            info!(
+                target: LOG_TARGET,
+                "Node is starting in standby. RPC sessions and block and transaction relay are disabled until the \
+                 node is promoted."
+            );
+        }
+
+        let mempool_sync = MempoolSyncInitializer::new(mempool_config.clone(), self.mempool.clone());
         let mempool_protocol = mempool_sync.get_protocol_extension();
 
         let tor_identity = load_from_json(&base_node_config.tor_identity_file)
@@ -125,8 +154,12 @@ where B: BlockchainBackend + 'static
                 self.mempool.clone(),
                 self.rules.clone(),
                 base_node_config.messaging_request_timeout,
+                !base_node_config.seed_node.enabled,
+                base_node_config.peer_auto_ban.clone(),
+                base_node_config.standby,
             ))
             .add_initializer(MempoolServiceInitializer::new(
+                mempool_config,
                 self.mempool.clone(),
                 peer_message_subscriptions.clone(),
             ))
@@ -139,12 +172,15 @@ where B: BlockchainBackend + 'static
                 },
                 peer_message_subscriptions,
             ))
-            .add_initializer(ChainMetadataServiceInitializer)
+            .add_initializer(ChainMetadataServiceInitializer::new(
+                base_node_config.tip_divergence.clone(),
+            ))
             .add_initializer(BaseNodeStateMachineInitializer::new(
                 self.db.clone().into(),
                 base_node_config.state_machine.clone(),
                 self.rules,
                 self.factories,
+                self.randomx_factory,
             ))
             .build()
             .await?;
@@ -154,14 +190,22 @@ where B: BlockchainBackend + 'static
             .expect("P2pInitializer was not added to the stack or did not add UnspawnedCommsNode");
 
         let comms = comms.add_protocol_extension(mempool_protocol);
-        let comms = Self::setup_rpc_services(comms, &handles, self.db.into(), &p2p_config);
+        let comms = Self::setup_rpc_services(
+            comms,
+            &handles,
+            self.db.into(),
+            &p2p_config,
+            reserved_rpc_peers,
+            base_node_config.standby,
+        );
         let comms = initialization::spawn_comms_using_transport(comms, p2p_config.transport.clone())
             .await
             .map_err(|e| ExitError::new(ExitCode::NetworkError, &e))?;
         // Save final node identity after comms has initialized. This is required because the public_address can be
         // changed by comms during initialization when using tor.
         match p2p_config.transport.transport_type {
-            TransportType::Tcp => {}, // Do not overwrite TCP public_address in the base_node_id!
+            // Do not overwrite TCP or QUIC public_address in the base_node_id!
+            TransportType::Tcp | TransportType::Quic => {},
             _ => {
                 identity_management::save_as_json(&base_node_config.identity_file, &*comms.node_identity())
                     .map_err(|e| ExitError::new(ExitCode::IdentityError, &e))?;
@@ -182,11 +226,20 @@ where B: BlockchainBackend + 'static
         handles: &ServiceHandles,
         db: AsyncBlockchainDb<B>,
         config: &P2pConfig,
+        reserved_rpc_peers: Vec<NodeId>,
+        standby: bool,
     ) -> UnspawnedCommsNode {
         let dht = handles.expect_handle::<Dht>();
         let base_node_service = handles.expect_handle::<LocalNodeCommsInterface>();
+        let mut rpc_chunking = RpcChunkingConfig::with_chunk_size(config.rpc_chunk_size);
+        if config.rpc_adaptive_chunking {
+            rpc_chunking = rpc_chunking.with_adaptive(AdaptiveChunkingConfig::default());
+        }
         let rpc_server = RpcServer::builder()
             .with_maximum_simultaneous_sessions(config.rpc_max_simultaneous_sessions)
+            .with_reserved_sessions(reserved_rpc_peers, config.rpc_max_reserved_sessions)
+            .with_accepting_sessions(!standby)
+            .with_chunking(rpc_chunking)
             .finish();
 
         // Add your RPC services here ‍🏴‍☠️️☮️🌊
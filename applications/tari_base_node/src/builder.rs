@@ -30,7 +30,12 @@ use tari_common::{
 use tari_comms::{peer_manager::NodeIdentity, protocol::rpc::RpcServerHandle, CommsNode};
 use tari_comms_dht::Dht;
 use tari_core::{
-    base_node::{state_machine_service::states::StatusInfo, LocalNodeCommsInterface, StateMachineHandle},
+    base_node::{
+        state_machine_service::states::StatusInfo,
+        LocalNodeCommsInterface,
+        StandbyHandle,
+        StateMachineHandle,
+    },
     chain_storage::{create_lmdb_database, BlockchainDatabase, ChainStorageError, LMDBDatabase, Validators},
     consensus::ConsensusManager,
     mempool::{service::LocalMempoolService, Mempool},
@@ -49,11 +54,16 @@ use tari_core::{
     },
 };
 use tari_p2p::{auto_update::SoftwareUpdaterHandle, services::liveness::LivenessHandle};
-use tari_service_framework::ServiceHandles;
+use tari_service_framework::{control::ServiceControlHandle, ServiceHandles};
 use tari_shutdown::ShutdownSignal;
 use tokio::sync::watch;
 
-use crate::{bootstrap::BaseNodeBootstrapper, config::DatabaseType, ApplicationConfig};
+use crate::{
+    bootstrap::BaseNodeBootstrapper,
+    config::DatabaseType,
+    peer_history::{PeerHistoryRecorder, PeerHistoryStore},
+    ApplicationConfig,
+};
 
 const LOG_TARGET: &str = "c::bn::initialization";
 
@@ -67,6 +77,7 @@ pub struct BaseNodeContext {
     base_node_comms: CommsNode,
     base_node_dht: Dht,
     base_node_handles: ServiceHandles,
+    peer_history: Option<PeerHistoryStore>,
 }
 
 impl BaseNodeContext {
@@ -133,6 +144,16 @@ impl BaseNodeContext {
         self.base_node_handles.expect_handle()
     }
 
+    /// Returns the handle used to promote the node out of standby
+    pub fn standby(&self) -> StandbyHandle {
+        self.base_node_handles.expect_handle()
+    }
+
+    /// Returns the handle used to stop, start and restart individual services at runtime
+    pub fn service_control(&self) -> ServiceControlHandle {
+        self.base_node_handles.expect_handle()
+    }
+
     /// Returns a BlockchainDatabase handle
     pub fn blockchain_db(&self) -> BlockchainDatabase<LMDBDatabase> {
         self.blockchain_db.clone()
@@ -155,6 +176,11 @@ impl BaseNodeContext {
             .get_status_info_watch()
     }
 
+    /// Returns the peer session history store, if peer history is enabled
+    pub fn peer_history(&self) -> Option<PeerHistoryStore> {
+        self.peer_history.clone()
+    }
+
     pub fn get_report_grpc_error(&self) -> bool {
         self.config.base_node.report_grpc_error
     }
@@ -208,7 +234,9 @@ async fn build_node_context(
         target: LOG_TARGET,
         "Building base node context for {}  network", app_config.base_node.network
     );
-    let rules = ConsensusManager::builder(app_config.base_node.network).build();
+    let rules = ConsensusManager::builder(app_config.base_node.network)
+        .build()
+        .map_err(|err| ExitError::new(ExitCode::ConfigError, &err))?;
     let factories = CryptoFactories::default();
     let randomx_factory = RandomXFactory::new(app_config.base_node.max_randomx_vms);
     let validators = Validators::new(
@@ -226,7 +254,7 @@ async fn build_node_context(
         rules.clone(),
         validators,
         app_config.base_node.storage.clone(),
-        DifficultyCalculator::new(rules.clone(), randomx_factory),
+        DifficultyCalculator::new(rules.clone(), randomx_factory.clone()),
     )
     .map_err(|err| {
         if let ChainStorageError::DatabaseResyncRequired(reason) = err {
@@ -263,6 +291,7 @@ async fn build_node_context(
         mempool,
         rules: rules.clone(),
         factories: factories.clone(),
+        randomx_factory,
         interrupt_signal: interrupt_signal.clone(),
     }
     .bootstrap()
@@ -271,6 +300,16 @@ async fn build_node_context(
     let base_node_comms = base_node_handles.expect_handle::<CommsNode>();
     let base_node_dht = base_node_handles.expect_handle::<Dht>();
 
+    let peer_history = if app_config.base_node.peer_history.enabled {
+        let config = &app_config.base_node.peer_history;
+        let store = PeerHistoryStore::open(&config.db_path, config)
+            .map_err(|err| ExitError::new(ExitCode::DatabaseError, &err))?;
+        PeerHistoryRecorder::new(store.clone(), base_node_comms.connectivity()).spawn(interrupt_signal);
+        Some(store)
+    } else {
+        None
+    };
+
     Ok(BaseNodeContext {
         config: app_config,
         consensus_rules: rules,
@@ -278,5 +317,6 @@ async fn build_node_context(
         base_node_comms,
         base_node_dht,
         base_node_handles,
+        peer_history,
     })
 }
@@ -20,9 +20,13 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::path::PathBuf;
+
 use clap::Parser;
 use tari_app_utilities::common_cli_args::CommonCliArgs;
 
+use crate::commands::output_format::OutputFormat;
+
 const DEFAULT_NETWORK: &str = "dibbler";
 
 #[derive(Parser, Debug)]
@@ -41,12 +45,28 @@ pub(crate) struct Cli {
     // TODO: Should be a command rather
     #[clap(long, alias = "rebuild_db")]
     pub rebuild_db: bool,
+    /// List the chain database migrations that are pending and check that they apply, without changing the database
+    #[clap(long)]
+    pub migrations_dry_run: bool,
+    /// Copy the chain database into a fresh environment to reclaim the space freed by reorgs and pruning, then exit
+    #[clap(long)]
+    pub compact_db: bool,
     /// Run in non-interactive mode, with no UI.
     #[clap(short, long, alias = "non-interactive", env = "TARI_NON_INTERACTIVE")]
     pub non_interactive_mode: bool,
     /// Watch a command in the non-interactive mode.
     #[clap(long)]
     pub watch: Option<String>,
+    /// Execute the commands in a file, one per line, and exit. Use `-` to read the commands from stdin.
+    #[clap(long, parse(from_os_str))]
+    pub commands_file: Option<PathBuf>,
+    /// Keep executing the commands in the commands file after a command fails. The exit code still reports the
+    /// failure.
+    #[clap(long, requires = "commands_file")]
+    pub continue_on_error: bool,
+    /// The output format of commands that support structured output (text or json)
+    #[clap(long, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
     /// Supply a network (overrides existing configuration)
     #[clap(long, default_value = DEFAULT_NETWORK, env = "TARI_NETWORK")]
     pub network: String,
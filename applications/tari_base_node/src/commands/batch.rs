@@ -0,0 +1,104 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::path::Path;
+
+use anyhow::anyhow;
+use tari_common::exit_codes::{ExitCode, ExitError};
+use tokio::{
+    fs,
+    io::{self, AsyncReadExt},
+};
+
+use crate::commands::command::CommandContext;
+
+/// Executes the commands in a file, or in stdin if the path is `-`, one command per line. Empty lines and lines
+/// starting with `#` are ignored. Unless `continue_on_error` is set, execution stops at the first failed command.
+/// Commands that ask for confirmation fail unless they are given the flag that skips the confirmation.
+pub async fn run_commands_file(
+    context: &mut CommandContext,
+    path: &Path,
+    continue_on_error: bool,
+) -> Result<(), ExitError> {
+    let script = if path == Path::new("-") {
+        let mut script = String::new();
+        io::stdin().read_to_string(&mut script).await.map(|_| script)
+    } else {
+        fs::read_to_string(path).await
+    }
+    .map_err(|err| ExitError::new(ExitCode::IOError, format!("Could not read {}: {}", path.display(), err)))?;
+
+    context.disable_confirmation_prompts();
+    run_commands(context, &script, continue_on_error).await
+}
+
+async fn run_commands(context: &mut CommandContext, script: &str, continue_on_error: bool) -> Result<(), ExitError> {
+    let mut num_failed = 0;
+    for (line_number, line) in command_lines(script) {
+        println!("> {}", line);
+        let result = match context.handle_command_str(line).await {
+            Ok(None) => Ok(()),
+            Ok(Some(_)) => Err(anyhow!("`watch` can't be used when executing commands from a file")),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            eprintln!("Command on line {} failed: {}", line_number, err);
+            if !continue_on_error {
+                return Err(ExitError::new(
+                    ExitCode::CommandError,
+                    format!("Command `{}` on line {} failed: {}", line, line_number, err),
+                ));
+            }
+            num_failed += 1;
+        }
+    }
+
+    if num_failed > 0 {
+        return Err(ExitError::new(
+            ExitCode::CommandError,
+            format!("{} command(s) failed", num_failed),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the line number and command of each line that contains a command
+fn command_lines(script: &str) -> impl Iterator<Item = (usize, &str)> {
+    script
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_skips_empty_lines_and_comments() {
+        let script =
+            "# Configure a fresh node\n\ndial-peer abc\n   \n  # trailing comment\n  ban-peer def --duration 1h  \n";
+        let lines = command_lines(script).collect::<Vec<_>>();
+        assert_eq!(lines, vec![(3, "dial-peer abc"), (6, "ban-peer def --duration 1h")]);
+    }
+}
@@ -7,9 +7,11 @@ use crossterm::{
     cursor,
     event::{Event, EventStream, KeyCode, KeyEvent, KeyModifiers},
     terminal,
+    terminal::ClearType,
 };
 use futures::{FutureExt, StreamExt};
 use rustyline::{config::OutputStreamType, error::ReadlineError, CompletionType, Config, EditMode, Editor};
+use tari_comms::utils::datetime::format_duration;
 use tari_shutdown::ShutdownSignal;
 use tokio::{signal, time};
 
@@ -17,6 +19,7 @@ use crate::{
     commands::{
         cli,
         command::{CommandContext, WatchCommand},
+        completion::CompletionSource,
         parser::Parser,
         reader::CommandReader,
     },
@@ -27,6 +30,7 @@ pub struct CliLoop {
     context: CommandContext,
     reader: CommandReader,
     commands: Vec<String>,
+    completions: CompletionSource,
     watch_task: Option<WatchCommand>,
     non_interactive: bool,
     first_signal: bool,
@@ -38,6 +42,7 @@ impl CliLoop {
     pub fn new(context: CommandContext, watch_command: Option<String>, non_interactive: bool) -> Self {
         let parser = Parser::new();
         let commands = parser.get_commands();
+        let completions = parser.completion_source();
         let cli_config = Config::builder()
             .history_ignore_space(true)
             .completion_type(CompletionType::List)
@@ -64,6 +69,7 @@ impl CliLoop {
             context,
             reader,
             commands,
+            completions,
             watch_task: Some(watch_task),
             non_interactive,
             first_signal: false,
@@ -120,20 +126,30 @@ impl CliLoop {
             let mut software_update_notif = self.context.software_updater.new_update_notifier().clone();
             let config = self.context.config.clone();
             let line = command.line();
-            let interval = command
-                .interval
-                .map(Duration::from_secs)
-                .unwrap_or(config.base_node.status_line_interval);
+            let interval = command.interval_or(config.base_node.status_line_interval);
+            let redraw = match command.watched_command() {
+                Ok(watched) => watched.is_redrawn_when_watched(),
+                Err(err) => {
+                    println!("Wrong command to watch `{}`. Failed with: {}", line, err);
+                    return;
+                },
+            };
+            if redraw {
+                Self::redraw_header(line, interval);
+            }
             if let Err(err) = self.context.handle_command_str(line).await {
                 println!("Wrong command to watch `{}`. Failed with: {}", line, err);
             } else {
                 let mut events = EventStream::new();
                 loop {
                     terminal::enable_raw_mode().ok();
-                    let interval = time::sleep(interval);
+                    let sleep = time::sleep(interval);
                     tokio::select! {
-                        _ = interval => {
+                        _ = sleep => {
                             terminal::disable_raw_mode().ok();
+                            if redraw {
+                                Self::redraw_header(line, interval);
+                            }
                             if let Err(err) = self.context.handle_command_str(line).await {
                                 println!("Watched command `{}` failed: {}", line, err);
                             }
@@ -172,11 +188,10 @@ impl CliLoop {
             let mut interrupt = signal::ctrl_c().fuse().boxed();
             let config = &self.context.config;
             let line = command.line();
-            let interval = command
-                .interval
-                .map(Duration::from_secs)
-                .unwrap_or(config.base_node.status_line_interval);
-            if let Err(err) = self.context.handle_command_str(line).await {
+            let interval = command.interval_or(config.base_node.status_line_interval);
+            if let Err(err) = command.watched_command() {
+                println!("Wrong command to watch `{}`. Failed with: {}", line, err);
+            } else if let Err(err) = self.context.handle_command_str(line).await {
                 println!("Wrong command to watch `{}`. Failed with: {}", line, err);
             } else {
                 loop {
@@ -197,6 +212,12 @@ impl CliLoop {
         }
     }
 
+    /// Clears the terminal so that the output of a watched command replaces its previous output
+    fn redraw_header(line: &str, interval: Duration) {
+        crossterm::execute!(io::stdout(), terminal::Clear(ClearType::All), cursor::MoveTo(0, 0)).ok();
+        println!("Every {}: {}    (Ctrl-C to stop)\n", format_duration(interval), line);
+    }
+
     async fn handle_line(&mut self, line: String) {
         // Reset the interruption flag if the command entered.
         self.first_signal = false;
@@ -213,6 +234,7 @@ impl CliLoop {
     }
 
     async fn execute_command(&mut self) {
+        self.completions.update(self.context.completion_data().await);
         tokio::select! {
             res = self.reader.next_command() => {
                 if let Some(event) = res {
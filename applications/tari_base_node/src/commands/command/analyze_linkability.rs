@@ -0,0 +1,162 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::cmp;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use clap::Parser;
+use tari_core::privacy_analysis::{HistogramBucket, LinkabilityAnalyzer, LinkabilityReport};
+
+use super::{CommandContext, HandleCommand};
+use crate::{
+    commands::output_format::{print_json, OutputFormat},
+    table::Table,
+};
+
+/// The number of blocks requested from the database at a time
+const BLOCK_BATCH_SIZE: u64 = 100;
+/// The number of blocks analysed when no start height is given
+const DEFAULT_NUM_BLOCKS: u64 = 1_000;
+
+/// Computes anonymized linkability metrics (one-sided payment clustering, key reuse, spend ages) for a range of blocks.
+/// The report only contains aggregate counts. Outputs spent from before the start height are reported as unknown.
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// start height (default: 1000 blocks before the end height)
+    #[clap(long)]
+    start: Option<u64>,
+    /// end height (default: chain tip)
+    #[clap(long)]
+    end: Option<u64>,
+}
+
+#[async_trait]
+impl HandleCommand<Args> for CommandContext {
+    async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
+        self.analyze_linkability(args.start, args.end).await
+    }
+}
+
+impl CommandContext {
+    pub async fn analyze_linkability(&mut self, start: Option<u64>, end: Option<u64>) -> Result<(), Error> {
+        let metadata = self.node_service.get_metadata().await?;
+        let end = cmp::min(
+            end.unwrap_or_else(|| metadata.height_of_longest_chain()),
+            metadata.height_of_longest_chain(),
+        );
+        let start = start.unwrap_or_else(|| end.saturating_sub(DEFAULT_NUM_BLOCKS - 1));
+        if start > end {
+            return Err(anyhow!("Start height {} is greater than end height {}", start, end));
+        }
+        if start < metadata.pruned_height() {
+            return Err(anyhow!(
+                "Start height {} is below the pruned height {} of this node",
+                start,
+                metadata.pruned_height()
+            ));
+        }
+
+        let mut analyzer = LinkabilityAnalyzer::new();
+        let mut height = start;
+        while height <= end {
+            let batch_end = cmp::min(height + BLOCK_BATCH_SIZE - 1, end);
+            let blocks = self.node_service.get_blocks(height..=batch_end).await?;
+            if blocks.is_empty() {
+                return Err(anyhow!("No blocks found from height {}", height));
+            }
+            for block in &blocks {
+                analyzer.add_block(block.header().height, &block.block().body);
+            }
+            height = batch_end + 1;
+        }
+
+        let report = analyzer.finish();
+        match self.output_format {
+            OutputFormat::Text => print_linkability_report(&report),
+            OutputFormat::Json => print_json(&report)?,
+        }
+        Ok(())
+    }
+}
+
+fn print_linkability_report(report: &LinkabilityReport) {
+    println!(
+        "Linkability report for blocks #{} - #{}",
+        report.start_height, report.end_height
+    );
+    let mut table = Table::new();
+    table.set_titles(vec!["Metric", "Value"]);
+    table.add_row(row!["Blocks", report.num_blocks]);
+    table.add_row(row!["Transactions", report.num_transactions]);
+    table.add_row(row!["Inputs", report.num_inputs]);
+    table.add_row(row!["Outputs", report.num_outputs]);
+    table.add_row(row!["Single transaction blocks", report.single_transaction_blocks]);
+    table.add_row(row![
+        "Mean transactions per block",
+        format!("{:.2}", report.mean_transactions_per_block)
+    ]);
+    table.add_row(row!["One-sided outputs", report.one_sided.num_outputs]);
+    table.add_row(row!["One-sided recipients", report.one_sided.num_recipients]);
+    table.add_row(row![
+        "Reused one-sided recipients",
+        report.one_sided.num_reused_recipients
+    ]);
+    table.add_row(row![
+        "Outputs to reused recipients",
+        report.one_sided.outputs_to_reused_recipients
+    ]);
+    table.add_row(row!["Largest recipient cluster", report.one_sided.largest_cluster]);
+    table.add_row(row!["Custom script outputs", report.custom_script_outputs]);
+    table.add_row(row![
+        "Reused sender offset key outputs",
+        report.reused_sender_offset_outputs
+    ]);
+    table.add_row(row!["Coinbase spends", report.spend_age.coinbase_spends]);
+    table.add_row(row!["Spends of unknown age", report.spend_age.unknown]);
+    table.print_stdout();
+
+    println!();
+    println!("One-sided recipient cluster sizes");
+    print_histogram("Outputs", &report.one_sided.cluster_size_histogram);
+    println!();
+    println!("Spend ages");
+    print_histogram("Blocks", &report.spend_age.histogram);
+}
+
+fn print_histogram(unit: &'static str, buckets: &[HistogramBucket]) {
+    let mut table = Table::new();
+    table.set_titles(vec![unit, "Count"]);
+    let mut lower_bound = 0;
+    for bucket in buckets {
+        let range = match bucket.upper_bound {
+            Some(upper_bound) => {
+                let range = format!("{} - {}", lower_bound, upper_bound);
+                lower_bound = upper_bound + 1;
+                range
+            },
+            None => format!("{}+", lower_bound),
+        };
+        table.add_row(row![range, bucket.count]);
+    }
+    table.print_stdout();
+}
@@ -26,26 +26,31 @@ use anyhow::Error;
 use async_trait::async_trait;
 use clap::Parser;
 use tari_app_utilities::utilities::UniNodeId;
-use tari_comms::peer_manager::NodeId;
+use tari_comms::{peer_manager::NodeId, utils::datetime::format_duration};
 use thiserror::Error;
 
 use super::{CommandContext, HandleCommand};
+use crate::commands::parser::HumanDuration;
 
 /// Bans a peer
 #[derive(Debug, Parser)]
 pub struct ArgsBan {
     /// hex public key or emoji id
+    #[clap(value_name = "NODE_ID")]
     node_id: UniNodeId,
-    /// length of time to ban the peer for in seconds
-    #[clap(default_value_t = std::u64::MAX)]
-    length: u64,
+    /// length of time to ban the peer for e.g. 30m, 24h or 7d. The peer is banned permanently if not given
+    #[clap(long, short)]
+    duration: Option<HumanDuration>,
 }
 
 #[async_trait]
 impl HandleCommand<ArgsBan> for CommandContext {
     async fn handle_command(&mut self, args: ArgsBan) -> Result<(), Error> {
         let node_id = args.node_id.into();
-        let duration = Duration::from_secs(args.length);
+        let duration = args
+            .duration
+            .map(|d| d.0)
+            .unwrap_or_else(|| Duration::from_secs(u64::MAX));
         self.ban_peer(node_id, duration, true).await
     }
 }
@@ -54,18 +59,15 @@ impl HandleCommand<ArgsBan> for CommandContext {
 #[derive(Debug, Parser)]
 pub struct ArgsUnban {
     /// hex public key or emoji id
+    #[clap(value_name = "NODE_ID")]
     node_id: UniNodeId,
-    /// length of time to ban the peer for in seconds
-    #[clap(default_value_t = std::u64::MAX)]
-    length: u64,
 }
 
 #[async_trait]
 impl HandleCommand<ArgsUnban> for CommandContext {
     async fn handle_command(&mut self, args: ArgsUnban) -> Result<(), Error> {
         let node_id = args.node_id.into();
-        let duration = Duration::from_secs(args.length);
-        self.ban_peer(node_id, duration, false).await
+        self.ban_peer(node_id, Duration::ZERO, false).await
     }
 }
 
@@ -76,6 +78,8 @@ enum ArgsError {
 }
 
 impl CommandContext {
+    /// Bans or unbans the peer. Bans are persisted in the peer database and are applied through the connectivity
+    /// manager, which disconnects the peer and thereby terminates all of its substreams and RPC sessions.
     pub async fn ban_peer(&mut self, node_id: NodeId, duration: Duration, must_ban: bool) -> Result<(), Error> {
         if self.base_node_identity.node_id() == &node_id {
             Err(ArgsError::BanSelf.into())
@@ -83,7 +87,11 @@ impl CommandContext {
             self.connectivity
                 .ban_peer_until(node_id.clone(), duration, "UI manual ban".to_string())
                 .await?;
-            println!("Peer was banned in base node.");
+            if duration.as_secs() == u64::MAX {
+                println!("Peer was banned permanently in base node.");
+            } else {
+                println!("Peer was banned in base node for {}.", format_duration(duration));
+            }
             Ok(())
         } else {
             self.peer_manager.unban_peer(&node_id).await?;
@@ -35,6 +35,7 @@ pub struct Args {
     /// (it should be at least 2 if end parameter is not set)
     start: u64,
     /// end height
+    #[clap(value_name = "END_HEIGHT")]
     end: Option<u64>,
 }
 
@@ -0,0 +1,335 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{io, path::PathBuf, sync::Arc};
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use clap::Parser;
+use tari_core::{
+    base_node::comms_interface::BlockEvent,
+    blocks::Block,
+    chain_storage::BlockAddResult,
+    consensus::{ConsensusDecoding, ConsensusEncoding},
+};
+use tari_utilities::{hex::Hex, Hashable};
+use thiserror::Error;
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, BufWriter},
+};
+
+use super::{CommandContext, HandleCommand};
+
+/// Identifies a chain archive file
+const ARCHIVE_MAGIC: &[u8; 8] = b"TARIBLKS";
+const ARCHIVE_VERSION: u8 = 1;
+/// Upper bound on the size of a single encoded block, guards against allocating for a corrupt length prefix
+const MAX_RECORD_SIZE: usize = 32 * 1024 * 1024;
+const FETCH_BATCH_SIZE: u64 = 100;
+const PROGRESS_INTERVAL: u64 = 100;
+
+/// Exports a range of blocks from the main chain to a chain archive file
+#[derive(Debug, Parser)]
+pub struct ArgsExport {
+    /// The height of the first block to export
+    #[clap(value_name = "FROM_HEIGHT")]
+    from: u64,
+    /// The height of the last block to export
+    #[clap(value_name = "TO_HEIGHT")]
+    to: u64,
+    /// The path of the archive file to create
+    #[clap(long)]
+    file: PathBuf,
+}
+
+/// Imports the blocks in a chain archive file. Every block is fully validated before it is added to the chain.
+#[derive(Debug, Parser)]
+pub struct ArgsImport {
+    /// The path of the archive file to import
+    path: PathBuf,
+}
+
+#[async_trait]
+impl HandleCommand<ArgsExport> for CommandContext {
+    async fn handle_command(&mut self, args: ArgsExport) -> Result<(), Error> {
+        self.export_blocks(args.from, args.to, args.file).await
+    }
+}
+
+#[async_trait]
+impl HandleCommand<ArgsImport> for CommandContext {
+    async fn handle_command(&mut self, args: ArgsImport) -> Result<(), Error> {
+        self.import_blocks(args.path).await
+    }
+}
+
+#[derive(Error, Debug)]
+enum ArchiveError {
+    #[error("Not a chain archive file")]
+    InvalidMagic,
+    #[error("Unsupported chain archive version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Archive was created on a different network (network byte {found}, expected {expected})")]
+    NetworkMismatch { expected: u8, found: u8 },
+    #[error("Archive record of {0} bytes exceeds the maximum block size")]
+    RecordTooLarge(usize),
+    #[error("Archive record contains {0} trailing bytes after the block")]
+    TrailingBytes(usize),
+    #[error("Archive is truncated in the middle of a record")]
+    Truncated,
+}
+
+impl CommandContext {
+    /// Function to process the export-blocks command
+    pub async fn export_blocks(&mut self, from: u64, to: u64, path: PathBuf) -> Result<(), Error> {
+        if from > to {
+            return Err(anyhow!("From height {} is greater than to height {}", from, to));
+        }
+        let tip = self.blockchain_db.get_chain_metadata().await?.height_of_longest_chain();
+        if to > tip {
+            return Err(anyhow!("To height {} is greater than the chain tip {}", to, tip));
+        }
+
+        let mut writer = BufWriter::new(File::create(&path).await?);
+        write_archive_header(&mut writer, self.config.network().as_byte()).await?;
+
+        let total = to - from + 1;
+        let mut num_exported = 0u64;
+        let mut num_bytes = 0usize;
+        let mut height = from;
+        while height <= to {
+            let batch_end = height.saturating_add(FETCH_BATCH_SIZE - 1).min(to);
+            let blocks = self.blockchain_db.fetch_blocks(height..=batch_end).await?;
+            for historical_block in blocks {
+                let block_height = historical_block.header().height;
+                let block = historical_block
+                    .try_into_block()
+                    .map_err(|_| anyhow!("Block {} has been pruned and cannot be exported", block_height))?;
+                let mut buf = Vec::new();
+                block.consensus_encode(&mut buf)?;
+                num_bytes += write_record(&mut writer, &buf).await?;
+                num_exported += 1;
+                if num_exported % PROGRESS_INTERVAL == 0 {
+                    println!("Exported {} of {} blocks", num_exported, total);
+                }
+            }
+            height = batch_end + 1;
+        }
+        writer.flush().await?;
+
+        println!(
+            "Exported {} blocks (heights {} to {}, {} bytes) to {}",
+            num_exported,
+            from,
+            to,
+            num_bytes,
+            path.display()
+        );
+        Ok(())
+    }
+
+    /// Function to process the import-blocks command
+    pub async fn import_blocks(&mut self, path: PathBuf) -> Result<(), Error> {
+        let mut reader = BufReader::new(File::open(&path).await?);
+        read_archive_header(&mut reader, self.config.network().as_byte()).await?;
+
+        let mut num_imported = 0u64;
+        let mut num_existing = 0u64;
+        while let Some(record) = read_record(&mut reader).await? {
+            let mut remaining = record.as_slice();
+            let block = Block::consensus_decode(&mut remaining)?;
+            if !remaining.is_empty() {
+                return Err(ArchiveError::TrailingBytes(remaining.len()).into());
+            }
+            let height = block.header.height;
+            let block = Arc::new(block);
+            match self.blockchain_db.add_block(block.clone()).await {
+                Ok(BlockAddResult::BlockExists) => num_existing += 1,
+                Ok(BlockAddResult::OrphanBlock) => {
+                    return Err(anyhow!(
+                        "Block {} ({}) does not connect to the local chain, aborting import after {} blocks",
+                        height,
+                        block.hash().to_hex(),
+                        num_imported
+                    ));
+                },
+                Ok(result) => {
+                    num_imported += 1;
+                    self.node_service
+                        .publish_block_event(BlockEvent::ValidBlockAdded(block, result));
+                },
+                Err(err) => {
+                    return Err(anyhow!(
+                        "Block {} failed validation, aborting import after {} blocks: {}",
+                        height,
+                        num_imported,
+                        err
+                    ));
+                },
+            }
+            if (num_imported + num_existing) % PROGRESS_INTERVAL == 0 {
+                println!(
+                    "Imported {} blocks, skipped {} existing blocks",
+                    num_imported, num_existing
+                );
+            }
+        }
+
+        let tip = self.blockchain_db.fetch_tip_header().await?;
+        println!(
+            "Imported {} blocks and skipped {} existing blocks from {}. Chain tip is now #{} ({})",
+            num_imported,
+            num_existing,
+            path.display(),
+            tip.height(),
+            tip.hash().to_hex()
+        );
+        Ok(())
+    }
+}
+
+async fn write_archive_header<W: AsyncWrite + Unpin>(writer: &mut W, network: u8) -> Result<(), io::Error> {
+    writer.write_all(ARCHIVE_MAGIC).await?;
+    writer.write_u8(ARCHIVE_VERSION).await?;
+    writer.write_u8(network).await
+}
+
+async fn read_archive_header<R: AsyncRead + Unpin>(reader: &mut R, network: u8) -> Result<(), Error> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic).await?;
+    if &magic != ARCHIVE_MAGIC {
+        return Err(ArchiveError::InvalidMagic.into());
+    }
+    let version = reader.read_u8().await?;
+    if version != ARCHIVE_VERSION {
+        return Err(ArchiveError::UnsupportedVersion(version).into());
+    }
+    let found = reader.read_u8().await?;
+    if found != network {
+        return Err(ArchiveError::NetworkMismatch {
+            expected: network,
+            found,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Writes a little-endian u32 length prefix followed by the record, returning the number of bytes written
+async fn write_record<W: AsyncWrite + Unpin>(writer: &mut W, record: &[u8]) -> Result<usize, Error> {
+    if record.len() > MAX_RECORD_SIZE {
+        return Err(ArchiveError::RecordTooLarge(record.len()).into());
+    }
+    writer.write_u32_le(record.len() as u32).await?;
+    writer.write_all(record).await?;
+    Ok(record.len() + 4)
+}
+
+/// Reads the next length-prefixed record, returning `None` at the end of the archive. An archive that ends part way
+/// through a record is truncated.
+async fn read_record<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Option<Vec<u8>>, Error> {
+    let mut prefix = [0u8; 4];
+    let mut num_read = 0;
+    while num_read < prefix.len() {
+        match reader.read(&mut prefix[num_read..]).await? {
+            0 if num_read == 0 => return Ok(None),
+            0 => return Err(ArchiveError::Truncated.into()),
+            n => num_read += n,
+        }
+    }
+    let len = u32::from_le_bytes(prefix) as usize;
+    if len > MAX_RECORD_SIZE {
+        return Err(ArchiveError::RecordTooLarge(len).into());
+    }
+    let mut record = vec![0u8; len];
+    match reader.read_exact(&mut record).await {
+        Ok(_) => Ok(Some(record)),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Err(ArchiveError::Truncated.into()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn it_round_trips_archive_records() {
+        let mut archive = Vec::new();
+        write_archive_header(&mut archive, 7).await.unwrap();
+        write_record(&mut archive, b"first").await.unwrap();
+        write_record(&mut archive, &[]).await.unwrap();
+        write_record(&mut archive, b"third").await.unwrap();
+
+        let mut reader = archive.as_slice();
+        read_archive_header(&mut reader, 7).await.unwrap();
+        assert_eq!(read_record(&mut reader).await.unwrap().unwrap(), b"first");
+        assert!(read_record(&mut reader).await.unwrap().unwrap().is_empty());
+        assert_eq!(read_record(&mut reader).await.unwrap().unwrap(), b"third");
+        assert!(read_record(&mut reader).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn it_rejects_archives_from_other_networks() {
+        let mut archive = Vec::new();
+        write_archive_header(&mut archive, 1).await.unwrap();
+        let err = read_archive_header(&mut archive.as_slice(), 2).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ArchiveError>(),
+            Some(ArchiveError::NetworkMismatch { expected: 2, found: 1 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_oversized_records() {
+        let mut archive = Vec::new();
+        archive.extend_from_slice(&u32::MAX.to_le_bytes());
+        let err = read_record(&mut archive.as_slice()).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ArchiveError>(),
+            Some(ArchiveError::RecordTooLarge(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_truncated_records() {
+        let mut archive = Vec::new();
+        write_record(&mut archive, b"first").await.unwrap();
+
+        // Part of the length prefix
+        let err = read_record(&mut &archive[..2]).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ArchiveError>(),
+            Some(ArchiveError::Truncated)
+        ));
+
+        // Part of the record
+        let err = read_record(&mut &archive[..archive.len() - 1]).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ArchiveError>(),
+            Some(ArchiveError::Truncated)
+        ));
+
+        assert!(read_record(&mut &archive[..0]).await.unwrap().is_none());
+    }
+}
@@ -23,63 +23,53 @@
 use anyhow::Error;
 use async_trait::async_trait;
 use clap::Parser;
-use tokio::io::{self, AsyncWriteExt};
 
 use super::{CommandContext, HandleCommand};
-use crate::LOG_TARGET;
 
-/// Checks the blockchain database for missing blocks and headers
+/// Checks the blockchain database for MMR root mismatches, orphaned entries and inconsistent indexes
 #[derive(Debug, Parser)]
-pub struct Args {}
+pub struct Args {
+    /// Rewind the blockchain to the last consistent height if any block is inconsistent
+    #[clap(long)]
+    fix: bool,
+}
 
 #[async_trait]
 impl HandleCommand<Args> for CommandContext {
-    async fn handle_command(&mut self, _: Args) -> Result<(), Error> {
-        self.check_db().await
+    async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
+        self.check_db(args.fix).await
     }
 }
 
 impl CommandContext {
     /// Function to process the check-db command
-    pub async fn check_db(&mut self) -> Result<(), Error> {
-        let meta = self.node_service.get_metadata().await?;
-        let mut height = meta.height_of_longest_chain();
-        let mut missing_blocks = Vec::new();
-        let mut missing_headers = Vec::new();
-        print!("Searching for height: ");
-        // We need to check every header, but not every block.
-        let horizon_height = meta.horizon_block(height);
-        while height > 0 {
-            print!("{}", height);
-            io::stdout().flush().await?;
-            // we can only check till the pruning horizon, 0 is archive node so it needs to check every block.
-            if height > horizon_height {
-                match self.node_service.get_block(height).await {
-                    Err(err) => {
-                        // We need to check the data itself, as FetchMatchingBlocks will suppress any error, only
-                        // logging it.
-                        log::error!(target: LOG_TARGET, "{}", err);
-                        missing_blocks.push(height);
-                    },
-                    Ok(Some(_)) => {},
-                    Ok(None) => missing_blocks.push(height),
-                };
-            }
-            height -= 1;
-            let next_header = self.node_service.get_header(height).await.ok().flatten();
-            if next_header.is_none() {
-                // this header is missing, so we stop here and need to ask for this header
-                missing_headers.push(height);
-            };
-            print!("\x1B[{}D\x1B[K", (height + 1).to_string().chars().count());
-        }
-        println!("Complete");
-        for missing_block in missing_blocks {
-            println!("Missing block at height: {}", missing_block);
+    pub async fn check_db(&mut self, fix: bool) -> Result<(), Error> {
+        println!("Checking the blockchain database. This may take a while...");
+        let report = self.blockchain_db.check_integrity().await?;
+        println!("{}", report);
+        if !fix || report.is_consistent() {
+            return Ok(());
         }
-        for missing_header_height in missing_headers {
-            println!("Missing header at height: {}", missing_header_height)
+
+        let height = match report.last_consistent_height() {
+            Some(height) if height < report.tip_height => height,
+            _ => {
+                println!("No block is inconsistent. The remaining issues cannot be repaired by rewinding.");
+                return Ok(());
+            },
+        };
+        println!(
+            "This will remove {} block(s) (heights {} to {}) from the blockchain database. Continue? [y/N]",
+            report.tip_height - height,
+            height + 1,
+            report.tip_height
+        );
+        if !self.read_confirmation().await? {
+            println!("Repair cancelled");
+            return Ok(());
         }
+        self.rewind_blockchain(height).await?;
+        println!("Rewound blockchain to height {}", height);
         Ok(())
     }
 }
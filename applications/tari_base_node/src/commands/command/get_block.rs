@@ -36,6 +36,7 @@ pub struct Args {
     /// The height or hash of the block to fetch
     /// from the main chain. The genesis block
     /// has height zero.
+    #[clap(value_name = "HEIGHT_OR_HASH")]
     value: TypeOrHex<u64>,
     /// Supported options are 'json' and 'text'. 'text' is the default if omitted.
     #[clap(default_value_t)]
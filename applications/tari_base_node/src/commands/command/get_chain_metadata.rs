@@ -23,8 +23,11 @@
 use anyhow::Error;
 use async_trait::async_trait;
 use clap::Parser;
+use serde::Serialize;
+use tari_utilities::hex::Hex;
 
 use super::{CommandContext, HandleCommand};
+use crate::commands::output_format::{print_json, OutputFormat};
 
 /// Gets your base node chain meta data
 #[derive(Debug, Parser)]
@@ -40,7 +43,26 @@ impl HandleCommand<Args> for CommandContext {
 impl CommandContext {
     pub async fn get_chain_meta(&mut self) -> Result<(), Error> {
         let data = self.node_service.get_metadata().await?;
-        println!("{}", data);
+        match self.output_format {
+            OutputFormat::Text => println!("{}", data),
+            OutputFormat::Json => print_json(&ChainMetadataOutput {
+                height_of_longest_chain: data.height_of_longest_chain(),
+                best_block: data.best_block().to_hex(),
+                pruning_horizon: data.pruning_horizon(),
+                pruned_height: data.pruned_height(),
+                // Serialized as a string as it does not fit into the number type of most JSON parsers
+                accumulated_difficulty: data.accumulated_difficulty().to_string(),
+            })?,
+        }
         Ok(())
     }
 }
+
+#[derive(Debug, Serialize)]
+struct ChainMetadataOutput {
+    height_of_longest_chain: u64,
+    best_block: String,
+    pruning_horizon: u64,
+    pruned_height: u64,
+    accumulated_difficulty: String,
+}
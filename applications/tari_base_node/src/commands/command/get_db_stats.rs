@@ -27,7 +27,7 @@ use clap::Parser;
 use super::{CommandContext, HandleCommand};
 use crate::table::Table;
 
-/// Gets your base node database stats
+/// Gets your base node database stats, including map size utilization and freelist usage
 #[derive(Debug, Parser)]
 pub struct Args {}
 
@@ -78,6 +78,15 @@ impl CommandContext {
             stats.root().psize as usize,
             stats.env_info()
         );
+        println!(
+            "Map size utilization: {:.2}%, pages: {} allocated, {} used, ~{} free ({:.2} MiB). Free pages are reused \
+             by later writes and can be reclaimed by running the node with --compact-db.",
+            stats.map_size_utilization() * 100.0,
+            stats.num_allocated_pages(),
+            stats.num_used_pages(),
+            stats.num_free_pages(),
+            (stats.num_free_pages() * stats.root().psize as usize) as f32 / BYTES_PER_MB as f32
+        );
 
         println!();
         println!("Totalling DB entry sizes. This may take a few seconds...");
@@ -0,0 +1,155 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use clap::Parser;
+use tari_app_utilities::utilities::UniNodeId;
+use tari_comms::peer_manager::NodeId;
+use tari_comms_dht::broadcast_strategy::BroadcastStrategy;
+
+use super::{CommandContext, HandleCommand};
+use crate::{table::Table, utils::format_duration_basic};
+
+/// Displays this node's DHT neighbourhood and the peers recently chosen to propagate messages
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// Only show propagation choices for messages destined for this peer (hex public key or emoji id)
+    #[clap(long, short)]
+    peer: Option<UniNodeId>,
+}
+
+#[async_trait]
+impl HandleCommand<Args> for CommandContext {
+    async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
+        self.get_dht_neighbourhood(args.peer.map(Into::into)).await
+    }
+}
+
+impl CommandContext {
+    pub async fn get_dht_neighbourhood(&mut self, peer: Option<NodeId>) -> Result<(), Error> {
+        let info = self.dht_requester.get_neighbourhood_info().await?;
+        let now = Utc::now().naive_utc();
+        let format_age = |dt: chrono::NaiveDateTime| {
+            now.signed_duration_since(dt)
+                .to_std()
+                .map(format_duration_basic)
+                .unwrap_or_else(|_| "now".to_string())
+        };
+
+        println!("Node ID: {}", info.node_id);
+        println!();
+        let mut table = Table::new();
+        table.set_titles(vec!["Bucket", "Peers", "Connected", "Offline", "Banned"]);
+        for bucket in &info.buckets {
+            table.add_row(row![
+                bucket.index,
+                bucket.num_peers,
+                bucket.num_connected,
+                bucket.num_offline,
+                bucket.num_banned
+            ]);
+        }
+        table.print_stdout();
+
+        println!();
+        println!("Closest {} peers:", info.neighbours.len());
+        let mut table = Table::new();
+        table.set_titles(vec![
+            "NodeId",
+            "Bucket",
+            "Role",
+            "Status",
+            "Last Seen",
+            "Last Connected",
+            "Failed Attempts",
+        ]);
+        for neighbour in &info.neighbours {
+            let status = if neighbour.is_banned {
+                "BANNED"
+            } else if neighbour.is_connected {
+                "CONNECTED"
+            } else if neighbour.is_offline {
+                "OFFLINE"
+            } else {
+                "--"
+            };
+            table.add_row(row![
+                neighbour.node_id,
+                neighbour.bucket,
+                neighbour.features.as_role_str(),
+                status,
+                neighbour
+                    .last_seen
+                    .map(format_age)
+                    .unwrap_or_else(|| "Never".to_string()),
+                neighbour
+                    .last_connected_at
+                    .map(format_age)
+                    .unwrap_or_else(|| "Never".to_string()),
+                neighbour.failed_attempts
+            ]);
+        }
+        table.print_stdout();
+
+        if let Some(ref node_id) = peer {
+            println!();
+            let selected = self
+                .dht_requester
+                .select_peers(BroadcastStrategy::Propagate(node_id.clone().into(), Vec::new()))
+                .await?;
+            println!("Peers that would currently be selected to propagate to {}:", node_id);
+            for node_id in selected {
+                println!("  {}", node_id);
+            }
+        }
+
+        let selections = info
+            .recent_selections
+            .iter()
+            .filter(|record| peer.is_none() || record.target == peer)
+            .collect::<Vec<_>>();
+        println!();
+        if selections.is_empty() {
+            println!("No recent propagation choices");
+            return Ok(());
+        }
+        println!("Recent propagation choices:");
+        let mut table = Table::new();
+        table.set_titles(vec!["Age", "Strategy", "Selected Peers"]);
+        for record in selections {
+            table.add_row(row![
+                format_age(record.selected_at),
+                record.strategy,
+                record
+                    .selected_peers
+                    .iter()
+                    .map(|n| n.short_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ]);
+        }
+        table.print_stdout();
+        Ok(())
+    }
+}
@@ -35,6 +35,7 @@ use super::{CommandContext, HandleCommand, TypeOrHex};
 #[derive(Debug, Parser)]
 pub struct Args {
     /// Partial NodeId | PublicKey | EmojiId
+    #[clap(value_name = "NODE_ID")]
     value: String,
 }
 
@@ -0,0 +1,92 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use clap::Parser;
+
+use super::{CommandContext, HandleCommand};
+use crate::{table::Table, utils::format_duration_basic};
+
+/// Displays store and forward storage usage and the peers whose stored messages take up the most space
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// The number of peers to list
+    #[clap(short, long, default_value = "10")]
+    peers: usize,
+}
+
+#[async_trait]
+impl HandleCommand<Args> for CommandContext {
+    async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
+        self.get_saf_stats(args.peers).await
+    }
+}
+
+impl CommandContext {
+    pub async fn get_saf_stats(&mut self, num_peers: usize) -> Result<(), Error> {
+        const BYTES_PER_MB: f32 = 1024.0 * 1024.0;
+
+        let stats = self.saf_requester.get_stats(num_peers).await?;
+        println!(
+            "Stored messages: {}/{}",
+            stats.usage.num_messages, stats.limits.num_messages
+        );
+        println!(
+            "Stored size: {:.2}/{:.2} MiB",
+            stats.usage.num_bytes as f32 / BYTES_PER_MB,
+            stats.limits.num_bytes as f32 / BYTES_PER_MB
+        );
+        if let Some(oldest) = stats.oldest_stored_at {
+            let age = Utc::now().naive_utc().signed_duration_since(oldest);
+            println!(
+                "Oldest message: {}",
+                format_duration_basic(age.to_std().unwrap_or_default())
+            );
+        }
+        println!(
+            "Per-peer quota: {} messages, {:.2} MiB",
+            stats.peer_limits.num_messages,
+            stats.peer_limits.num_bytes as f32 / BYTES_PER_MB
+        );
+
+        if stats.top_source_peers.is_empty() {
+            return Ok(());
+        }
+        println!();
+        let mut table = Table::new();
+        table.set_titles(vec!["Source Peer", "Messages", "Size (MiB)", "% of peer quota"]);
+        for peer in stats.top_source_peers {
+            let messages_used = peer.usage.num_messages as f32 / stats.peer_limits.num_messages.max(1) as f32;
+            let bytes_used = peer.usage.num_bytes as f32 / stats.peer_limits.num_bytes.max(1) as f32;
+            table.add_row(row![
+                peer.source_peer,
+                peer.usage.num_messages,
+                format!("{:.2}", peer.usage.num_bytes as f32 / BYTES_PER_MB),
+                format!("{:.2}%", messages_used.max(bytes_used) * 100.0)
+            ]);
+        }
+        table.print_stdout();
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::Duration;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use clap::Parser;
+use tari_core::proof_of_work::PowAlgorithm;
+use tari_utilities::epoch_time::EpochTime;
+
+use super::{CommandContext, HandleCommand};
+use crate::utils::format_duration_basic;
+
+/// Display the current and target difficulty, estimated hash rate and time to the next difficulty adjustment for a
+/// proof of work algorithm, e.g. get-target-difficulty monero
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// The proof of work algorithm: monero|sha3|sha3x
+    pow_algo: PowAlgorithm,
+    /// The block height to estimate from. Defaults to the chain tip.
+    #[clap(value_name = "HEIGHT")]
+    height: Option<u64>,
+}
+
+#[async_trait]
+impl HandleCommand<Args> for CommandContext {
+    async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
+        self.get_target_difficulty(args.pow_algo, args.height).await
+    }
+}
+
+impl CommandContext {
+    pub async fn get_target_difficulty(&mut self, pow_algo: PowAlgorithm, height: Option<u64>) -> Result<(), Error> {
+        let estimate = self
+            .node_service
+            .get_target_difficulty_estimate(pow_algo, height)
+            .await?;
+        let time_to_next_adjustment = estimate.time_to_next_adjustment(EpochTime::now());
+        println!(
+            "Target difficulty for {} at height #{}",
+            estimate.pow_algo, estimate.height
+        );
+        println!("Current difficulty: {}", estimate.current_difficulty);
+        println!("Target difficulty: {}", estimate.target_difficulty);
+        println!("Estimated hash rate: {} H/s", estimate.estimated_hash_rate());
+        println!("Target block interval: {}s", estimate.target_block_interval);
+        println!(
+            "Time to next adjustment: {}",
+            format_duration_basic(Duration::from_secs(time_to_next_adjustment))
+        );
+        Ok(())
+    }
+}
@@ -42,8 +42,10 @@ use super::{CommandContext, HandleCommand};
 #[derive(Debug, Parser)]
 pub struct Args {
     /// start height
+    #[clap(value_name = "START_HEIGHT")]
     start_height: u64,
     /// end height
+    #[clap(value_name = "END_HEIGHT")]
     end_height: u64,
     /// dump file
     #[clap(default_value = "header-data.csv")]
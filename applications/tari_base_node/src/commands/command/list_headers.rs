@@ -34,6 +34,7 @@ pub struct Args {
     /// number of headers starting from the chain tip back or the first header height (if the last set too)
     start: u64,
     /// last header height
+    #[clap(value_name = "END_HEIGHT")]
     end: Option<u64>,
 }
 
@@ -20,33 +20,93 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::str::FromStr;
+
 use anyhow::Error;
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use clap::Parser;
-use tari_comms::peer_manager::PeerQuery;
+use serde::Serialize;
+use tari_comms::{
+    peer_manager::{Peer, PeerBanState, PeerQuery, PeerQuerySortBy},
+    transports::TransportKind,
+};
 use tari_core::base_node::state_machine_service::states::PeerMetadata;
+use tari_utilities::hex::Hex;
 
 use super::{CommandContext, HandleCommand};
-use crate::{table::Table, utils::format_duration_basic};
+use crate::{
+    commands::output_format::{print_json, OutputFormat},
+    table::Table,
+    utils::format_duration_basic,
+};
 
 /// Lists the peers that this node knows about
 #[derive(Debug, Parser)]
 pub struct Args {
+    /// Only list peers with this role (basenode or wallet)
     filter: Option<String>,
+    /// Only list banned peers
+    #[clap(long, conflicts_with = "exclude-banned")]
+    banned: bool,
+    /// Do not list banned peers
+    #[clap(long)]
+    exclude_banned: bool,
+    /// Only list peers seen within the given number of hours
+    #[clap(long)]
+    seen_within: Option<u32>,
+    /// Only list peers with an address using this transport (tcp, onion, ws, quic)
+    #[clap(long)]
+    transport: Option<TransportKind>,
+    /// The order in which to list peers (node-id, last-seen or recently-added)
+    #[clap(long, default_value = "node-id")]
+    sort: PeerSortOrder,
+    /// The number of matching peers to skip
+    #[clap(long, default_value = "0")]
+    offset: usize,
+    /// The maximum number of peers to list
+    #[clap(long, default_value = "100")]
+    limit: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PeerSortOrder {
+    NodeId,
+    LastSeen,
+    RecentlyAdded,
+}
+
+impl FromStr for PeerSortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "node-id" | "node_id" | "nodeid" => Ok(PeerSortOrder::NodeId),
+            "last-seen" | "last_seen" | "lastseen" => Ok(PeerSortOrder::LastSeen),
+            "recently-added" | "recently_added" | "added" => Ok(PeerSortOrder::RecentlyAdded),
+            s => Err(format!("Invalid sort order '{}'", s)),
+        }
+    }
 }
 
 #[async_trait]
 impl HandleCommand<Args> for CommandContext {
     async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
-        self.list_peers(args.filter).await
+        self.list_peers(args).await
     }
 }
 
 impl CommandContext {
-    pub async fn list_peers(&self, filter: Option<String>) -> Result<(), Error> {
-        let mut query = PeerQuery::new();
-        if let Some(f) = filter {
+    pub async fn list_peers(&self, args: Args) -> Result<(), Error> {
+        let mut query = PeerQuery::new()
+            .sort_by(match args.sort {
+                PeerSortOrder::NodeId => PeerQuerySortBy::NodeId,
+                PeerSortOrder::LastSeen => PeerQuerySortBy::LastConnected,
+                PeerSortOrder::RecentlyAdded => PeerQuerySortBy::RecentlyAdded,
+            })
+            .offset(args.offset)
+            .limit(args.limit);
+        if let Some(f) = args.filter {
             let filter = f.to_lowercase();
             query = query.select_where(move |p| match filter.as_str() {
                 "basenode" | "basenodes" | "base_node" | "base-node" | "bn" => p.features.is_node(),
@@ -54,7 +114,23 @@ impl CommandContext {
                 _ => false,
             })
         }
+        if args.banned {
+            query = query.ban_state(PeerBanState::Banned);
+        }
+        if args.exclude_banned {
+            query = query.ban_state(PeerBanState::NotBanned);
+        }
+        if let Some(hours) = args.seen_within {
+            query = query.last_seen_after(Utc::now().naive_utc() - Duration::hours(i64::from(hours)));
+        }
+        if let Some(transport) = args.transport {
+            query = query.with_transport(transport);
+        }
         let peers = self.peer_manager.perform_query(query).await?;
+        if self.output_format == OutputFormat::Json {
+            let peers = peers.iter().map(PeerOutput::from).collect::<Vec<_>>();
+            return print_json(&peers);
+        }
         let num_peers = peers.len();
         println!();
         let mut table = Table::new();
@@ -132,7 +208,47 @@ impl CommandContext {
         }
         table.print_stdout();
 
-        println!("{} peer(s) known by this node", num_peers);
+        if num_peers == args.limit {
+            println!(
+                "{} peer(s) listed. Use --offset {} to list more.",
+                num_peers,
+                args.offset + num_peers
+            );
+        } else {
+            println!("{} peer(s) listed", num_peers);
+        }
         Ok(())
     }
 }
+
+#[derive(Debug, Serialize)]
+struct PeerOutput {
+    node_id: String,
+    public_key: String,
+    is_wallet: bool,
+    user_agent: String,
+    is_offline: bool,
+    last_seen: Option<String>,
+    banned_until: Option<String>,
+    banned_reason: Option<String>,
+    chain_height: Option<u64>,
+}
+
+impl From<&Peer> for PeerOutput {
+    fn from(peer: &Peer) -> Self {
+        Self {
+            node_id: peer.node_id.to_hex(),
+            public_key: peer.public_key.to_hex(),
+            is_wallet: peer.features.is_client(),
+            user_agent: peer.user_agent.clone(),
+            is_offline: peer.is_offline(),
+            last_seen: peer.last_seen().map(|dt| dt.to_string()),
+            banned_until: peer.banned_until().map(|dt| dt.to_string()),
+            banned_reason: Some(peer.banned_reason.clone()).filter(|_| peer.is_banned()),
+            chain_height: peer
+                .get_metadata(1)
+                .and_then(|v| bincode::deserialize::<PeerMetadata>(v).ok())
+                .map(|metadata| metadata.metadata.height_of_longest_chain()),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{cmp::Reverse, time::Duration};
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use strum::{Display, EnumString};
+use tari_common_types::types::PrivateKey;
+use tari_core::mempool::UnconfirmedTxSummary;
+use tari_utilities::hex::Hex;
+
+use super::{CommandContext, HandleCommand};
+use crate::{
+    commands::{
+        output_format::{print_json, OutputFormat},
+        parser::FromHex,
+    },
+    table::Table,
+    utils::format_duration_basic,
+};
+
+/// The upper bounds (exclusive) of the fee per gram histogram buckets. The last bucket has no upper bound.
+const FEE_PER_GRAM_BUCKETS: [u64; 7] = [1, 5, 10, 25, 50, 100, 250];
+const HISTOGRAM_BAR_WIDTH: usize = 40;
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(subcommand)]
+    command: MempoolCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum MempoolCommand {
+    /// List the transactions in the unconfirmed pool
+    List(ListArgs),
+    /// Show mempool stats and the fee per gram distribution of the unconfirmed pool
+    Stats,
+    /// Evict a transaction from the unconfirmed pool. The transaction may be added again if it is received from a
+    /// peer.
+    Remove(RemoveArgs),
+}
+
+#[derive(Debug, Parser)]
+struct ListArgs {
+    /// Sort by fee per gram (highest first) or by age (oldest first)
+    #[clap(long, default_value_t = SortBy::Fee)]
+    sort: SortBy,
+}
+
+#[derive(Debug, Parser)]
+struct RemoveArgs {
+    /// The excess signature of the transaction's first kernel, as shown by `mempool list`
+    excess_sig: FromHex<PrivateKey>,
+}
+
+#[derive(Debug, Clone, Copy, Display, EnumString)]
+enum SortBy {
+    #[strum(serialize = "fee")]
+    Fee,
+    #[strum(serialize = "age")]
+    Age,
+}
+
+#[async_trait]
+impl HandleCommand<Args> for CommandContext {
+    async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
+        match args.command {
+            MempoolCommand::List(args) => self.list_mempool_transactions(args.sort).await,
+            MempoolCommand::Stats => self.mempool_stats_with_histogram().await,
+            MempoolCommand::Remove(args) => self.remove_mempool_transaction(args.excess_sig.0).await,
+        }
+    }
+}
+
+impl CommandContext {
+    /// Function to process the mempool list command
+    async fn list_mempool_transactions(&mut self, sort: SortBy) -> Result<(), Error> {
+        let mut txs = self.mempool_service.get_unconfirmed_transactions().await?;
+        match sort {
+            SortBy::Fee => txs.sort_by_key(|tx| Reverse(tx.fee_per_gram())),
+            SortBy::Age => txs.sort_by_key(|tx| Reverse(tx.age)),
+        }
+
+        if self.output_format == OutputFormat::Json {
+            let txs = txs.iter().map(MempoolTxOutput::from).collect::<Vec<_>>();
+            return print_json(&txs);
+        }
+
+        let mut table = Table::new();
+        table.set_titles(vec![
+            "Excess sig",
+            "Fee",
+            "Weight",
+            "Fee/g",
+            "Age",
+            "Inputs",
+            "Outputs",
+            "Kernels",
+        ]);
+        for tx in &txs {
+            table.add_row(row![
+                tx.excess_sig.get_signature().to_hex(),
+                tx.fee,
+                tx.weight,
+                tx.fee_per_gram(),
+                format_duration_basic(tx.age),
+                tx.num_inputs,
+                tx.num_outputs,
+                tx.num_kernels,
+            ]);
+        }
+        table.enable_row_count().print_stdout();
+        Ok(())
+    }
+
+    /// Function to process the mempool stats command
+    async fn mempool_stats_with_histogram(&mut self) -> Result<(), Error> {
+        let stats = self.mempool_service.get_mempool_stats().await?;
+        let txs = self.mempool_service.get_unconfirmed_transactions().await?;
+        let histogram = FeeHistogram::new(&txs);
+
+        if self.output_format == OutputFormat::Json {
+            return print_json(&MempoolStatsOutput {
+                total_txs: stats.total_txs,
+                unconfirmed_txs: stats.unconfirmed_txs,
+                reorg_txs: stats.reorg_txs,
+                total_weight: stats.total_weight,
+                min_fee_per_gram: stats.min_fee_per_gram,
+                fee_per_gram_histogram: histogram.buckets,
+            });
+        }
+
+        println!("{}", stats);
+        if txs.is_empty() {
+            return Ok(());
+        }
+        let mut fees_per_gram = txs.iter().map(|tx| tx.fee_per_gram()).collect::<Vec<_>>();
+        fees_per_gram.sort_unstable();
+        println!(
+            "Fee per gram: min {}, median {}, max {}",
+            fees_per_gram[0],
+            fees_per_gram[fees_per_gram.len() / 2],
+            fees_per_gram[fees_per_gram.len() - 1]
+        );
+
+        let max_count = histogram.buckets.iter().map(|b| b.count).max().unwrap_or(0);
+        let mut table = Table::new();
+        table.set_titles(vec!["Fee/g", "Txs", "Weight", ""]);
+        for bucket in &histogram.buckets {
+            let bar_len = (bucket.count * HISTOGRAM_BAR_WIDTH).checked_div(max_count).unwrap_or(0);
+            table.add_row(row![bucket.label(), bucket.count, bucket.weight, "#".repeat(bar_len)]);
+        }
+        table.print_stdout();
+        Ok(())
+    }
+
+    /// Function to process the mempool remove command
+    async fn remove_mempool_transaction(&mut self, excess_sig: PrivateKey) -> Result<(), Error> {
+        if !self.mempool_service.remove_transaction(excess_sig.clone()).await? {
+            return Err(anyhow!(
+                "No transaction with excess signature {} in the unconfirmed pool",
+                excess_sig.to_hex()
+            ));
+        }
+        println!("Removed transaction {} from the mempool", excess_sig.to_hex());
+        Ok(())
+    }
+}
+
+struct FeeHistogram {
+    buckets: Vec<FeeBucket>,
+}
+
+impl FeeHistogram {
+    fn new(txs: &[UnconfirmedTxSummary]) -> Self {
+        let mut lower = 0;
+        let mut buckets = FEE_PER_GRAM_BUCKETS
+            .iter()
+            .map(|&upper| {
+                let bucket = FeeBucket::new(lower, Some(upper));
+                lower = upper;
+                bucket
+            })
+            .collect::<Vec<_>>();
+        buckets.push(FeeBucket::new(lower, None));
+
+        for tx in txs {
+            let fee_per_gram = tx.fee_per_gram();
+            let idx = FEE_PER_GRAM_BUCKETS
+                .iter()
+                .position(|&upper| fee_per_gram < upper)
+                .unwrap_or(FEE_PER_GRAM_BUCKETS.len());
+            buckets[idx].count += 1;
+            buckets[idx].weight += tx.weight;
+        }
+        Self { buckets }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct FeeBucket {
+    min_fee_per_gram: u64,
+    max_fee_per_gram: Option<u64>,
+    count: usize,
+    weight: u64,
+}
+
+impl FeeBucket {
+    fn new(min_fee_per_gram: u64, max_fee_per_gram: Option<u64>) -> Self {
+        Self {
+            min_fee_per_gram,
+            max_fee_per_gram,
+            count: 0,
+            weight: 0,
+        }
+    }
+
+    fn label(&self) -> String {
+        match self.max_fee_per_gram {
+            Some(max) => format!("{}-{}", self.min_fee_per_gram, max - 1),
+            None => format!("{}+", self.min_fee_per_gram),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct MempoolTxOutput {
+    excess_sig: String,
+    fee: u64,
+    weight: u64,
+    fee_per_gram: u64,
+    age_secs: u64,
+    inputs: usize,
+    outputs: usize,
+    kernels: usize,
+}
+
+impl From<&UnconfirmedTxSummary> for MempoolTxOutput {
+    fn from(tx: &UnconfirmedTxSummary) -> Self {
+        Self {
+            excess_sig: tx.excess_sig.get_signature().to_hex(),
+            fee: tx.fee.as_u64(),
+            weight: tx.weight,
+            fee_per_gram: tx.fee_per_gram(),
+            age_secs: tx.age.as_secs(),
+            inputs: tx.num_inputs,
+            outputs: tx.num_outputs,
+            kernels: tx.num_kernels,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct MempoolStatsOutput {
+    total_txs: usize,
+    unconfirmed_txs: usize,
+    reorg_txs: usize,
+    total_weight: u64,
+    min_fee_per_gram: u64,
+    fee_per_gram_histogram: Vec<FeeBucket>,
+}
@@ -20,8 +20,10 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+mod analyze_linkability;
 mod ban_peer;
 mod block_timing;
+mod chain_archive;
 mod check_db;
 mod check_for_updates;
 mod dial_peer;
@@ -29,24 +31,31 @@ mod discover_peer;
 mod get_block;
 mod get_chain_metadata;
 mod get_db_stats;
+mod get_dht_neighbourhood;
 mod get_mempool_state;
 mod get_mempool_stats;
 mod get_network_stats;
 mod get_peer;
+mod get_saf_stats;
 mod get_state_info;
+mod get_target_difficulty;
 mod header_stats;
 mod list_banned_peers;
 mod list_connections;
 mod list_headers;
 mod list_peers;
 mod list_reorgs;
+mod mempool;
+mod peer_history;
 mod period_stats;
 mod ping_peer;
+mod promote;
 mod quit;
 mod reset_offline_peers;
 mod rewind_blockchain;
 mod search_kernel;
 mod search_utxo;
+mod service;
 mod status;
 mod unban_all_peers;
 mod version;
@@ -65,26 +74,34 @@ use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use strum::{EnumVariantNames, VariantNames};
 use tari_comms::{
     connectivity::ConnectivityRequester,
-    peer_manager::{Peer, PeerManager, PeerManagerError, PeerQuery},
+    peer_manager::{Peer, PeerBanState, PeerManager, PeerManagerError, PeerQuery},
     protocol::rpc::RpcServerHandle,
     NodeIdentity,
 };
-use tari_comms_dht::{DhtDiscoveryRequester, MetricsCollectorHandle};
+use tari_comms_dht::{
+    store_forward::StoreAndForwardRequester,
+    DhtDiscoveryRequester,
+    DhtRequester,
+    MetricsCollectorHandle,
+};
 use tari_core::{
-    base_node::{state_machine_service::states::StatusInfo, LocalNodeCommsInterface},
+    base_node::{state_machine_service::states::StatusInfo, LocalNodeCommsInterface, StandbyHandle},
     blocks::ChainHeader,
     chain_storage::{async_db::AsyncBlockchainDb, LMDBDatabase},
     consensus::ConsensusManager,
     mempool::service::LocalMempoolService,
 };
 use tari_p2p::{auto_update::SoftwareUpdaterHandle, services::liveness::LivenessHandle};
+use tari_service_framework::control::ServiceControlHandle;
 use tari_shutdown::Shutdown;
+use tari_utilities::hex::Hex;
 use tokio::{sync::watch, time};
 pub use watch_command::WatchCommand;
 
 use crate::{
     builder::BaseNodeContext,
-    commands::{nom_parser::ParsedCommand, parser::FromHex},
+    commands::{completion::CompletionData, nom_parser::ParsedCommand, output_format::OutputFormat, parser::FromHex},
+    peer_history::PeerHistoryStore,
     ApplicationConfig,
 };
 
@@ -101,34 +118,47 @@ pub enum Command {
     CheckForUpdates(check_for_updates::Args),
     Status(status::Args),
     GetChainMetadata(get_chain_metadata::Args),
+    #[clap(alias = "db-stats")]
     GetDbStats(get_db_stats::Args),
     GetPeer(get_peer::Args),
     ListPeers(list_peers::Args),
     DialPeer(dial_peer::Args),
     PingPeer(ping_peer::Args),
+    Promote(promote::Args),
     ResetOfflinePeers(reset_offline_peers::Args),
     RewindBlockchain(rewind_blockchain::Args),
+    Rewind(rewind_blockchain::ArgsRewind),
     BanPeer(ban_peer::ArgsBan),
     UnbanPeer(ban_peer::ArgsUnban),
     UnbanAllPeers(unban_all_peers::Args),
+    #[clap(alias = "list-banned")]
     ListBannedPeers(list_banned_peers::Args),
     ListConnections(list_connections::Args),
     ListHeaders(list_headers::Args),
     CheckDb(check_db::Args),
     PeriodStats(period_stats::Args),
+    PeerHistory(peer_history::Args),
     HeaderStats(header_stats::Args),
     BlockTiming(block_timing::Args),
+    AnalyzeLinkability(analyze_linkability::Args),
     ListReorgs(list_reorgs::Args),
     DiscoverPeer(discover_peer::Args),
     GetBlock(get_block::Args),
+    ExportBlocks(chain_archive::ArgsExport),
+    ImportBlocks(chain_archive::ArgsImport),
     SearchUtxo(search_utxo::Args),
     SearchKernel(search_kernel::Args),
+    Service(service::Args),
     GetMempoolStats(get_mempool_stats::Args),
     GetMempoolState(get_mempool_state::Args),
     GetMempoolTx(get_mempool_state::ArgsTx),
+    Mempool(mempool::Args),
     Whoami(whoami::Args),
     GetStateInfo(get_state_info::Args),
     GetNetworkStats(get_network_stats::Args),
+    GetSafStats(get_saf_stats::Args),
+    GetDhtNeighbourhood(get_dht_neighbourhood::Args),
+    GetTargetDifficulty(get_target_difficulty::Args),
     Quit(quit::Args),
     Exit(quit::Args),
     Watch(watch_command::Args),
@@ -138,6 +168,45 @@ impl Command {
     pub fn variants() -> Vec<String> {
         Command::VARIANTS.iter().map(|s| s.to_string()).collect()
     }
+
+    /// Chain archive commands stream an arbitrary number of blocks and are not subject to the command timeout
+    fn is_long_running(&self) -> bool {
+        matches!(self, Command::ExportBlocks(_) | Command::ImportBlocks(_))
+    }
+
+    /// Commands that only read the state of the node and are safe to repeat with `watch`
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            Command::Version(_) |
+                Command::Status(_) |
+                Command::GetChainMetadata(_) |
+                Command::GetDbStats(_) |
+                Command::GetPeer(_) |
+                Command::ListPeers(_) |
+                Command::ListBannedPeers(_) |
+                Command::ListConnections(_) |
+                Command::ListHeaders(_) |
+                Command::HeaderStats(_) |
+                Command::BlockTiming(_) |
+                Command::AnalyzeLinkability(_) |
+                Command::ListReorgs(_) |
+                Command::GetMempoolStats(_) |
+                Command::GetMempoolState(_) |
+                Command::GetMempoolTx(_) |
+                Command::Whoami(_) |
+                Command::GetStateInfo(_) |
+                Command::GetNetworkStats(_) |
+                Command::GetSafStats(_) |
+                Command::GetDhtNeighbourhood(_) |
+                Command::GetTargetDifficulty(_)
+        )
+    }
+
+    /// The status line is appended on each refresh, the output of every other watched command replaces the last one
+    pub fn is_redrawn_when_watched(&self) -> bool {
+        !matches!(self, Command::Status(_))
+    }
 }
 
 #[async_trait]
@@ -150,17 +219,24 @@ pub struct CommandContext {
     consensus_rules: ConsensusManager,
     blockchain_db: AsyncBlockchainDb<LMDBDatabase>,
     discovery_service: DhtDiscoveryRequester,
+    dht_requester: DhtRequester,
     dht_metrics_collector: MetricsCollectorHandle,
+    saf_requester: StoreAndForwardRequester,
     rpc_server: RpcServerHandle,
+    standby: StandbyHandle,
     base_node_identity: Arc<NodeIdentity>,
     peer_manager: Arc<PeerManager>,
     connectivity: ConnectivityRequester,
     liveness: LivenessHandle,
     node_service: LocalNodeCommsInterface,
     mempool_service: LocalMempoolService,
+    peer_history: Option<PeerHistoryStore>,
+    service_control: ServiceControlHandle,
+    output_format: OutputFormat,
     state_machine_info: watch::Receiver<StatusInfo>,
     pub software_updater: SoftwareUpdaterHandle,
     last_time_full: Instant,
+    confirmation_prompts: bool,
     pub shutdown: Shutdown,
 }
 
@@ -171,25 +247,48 @@ impl CommandContext {
             consensus_rules: ctx.consensus_rules().clone(),
             blockchain_db: ctx.blockchain_db().into(),
             discovery_service: ctx.base_node_dht().discovery_service_requester(),
+            dht_requester: ctx.base_node_dht().dht_requester(),
             dht_metrics_collector: ctx.base_node_dht().metrics_collector(),
+            saf_requester: ctx.base_node_dht().store_and_forward_requester(),
             rpc_server: ctx.rpc_server(),
+            standby: ctx.standby(),
             base_node_identity: ctx.base_node_identity(),
             peer_manager: ctx.base_node_comms().peer_manager(),
             connectivity: ctx.base_node_comms().connectivity(),
             liveness: ctx.liveness(),
             node_service: ctx.local_node(),
             mempool_service: ctx.local_mempool(),
+            peer_history: ctx.peer_history(),
+            service_control: ctx.service_control(),
+            output_format: OutputFormat::default(),
             state_machine_info: ctx.get_state_machine_info_channel(),
             software_updater: ctx.software_updater(),
             last_time_full: Instant::now(),
+            confirmation_prompts: true,
             shutdown,
         }
     }
 
+    /// Makes commands that ask for confirmation fail instead of reading the answer from stdin, e.g. when executing
+    /// commands from a file
+    pub fn disable_confirmation_prompts(&mut self) {
+        self.confirmation_prompts = false;
+    }
+
+    /// Sets the format in which commands that support structured output print their output
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
     pub async fn handle_command_str(&mut self, line: &str) -> Result<Option<WatchCommand>, Error> {
         let args: Args = line.parse()?;
         if let Command::Watch(command) = args.command {
+            command.watched_command()?;
             Ok(Some(command))
+        } else if args.command.is_long_running() {
+            self.handle_command(args.command).await?;
+            Ok(None)
         } else {
             let fut = self.handle_command(args.command);
             time::timeout(Duration::from_secs(70), fut).await??;
@@ -221,28 +320,39 @@ impl HandleCommand<Command> for CommandContext {
             Command::GetPeer(args) => self.handle_command(args).await,
             Command::GetStateInfo(args) => self.handle_command(args).await,
             Command::GetNetworkStats(args) => self.handle_command(args).await,
+            Command::GetSafStats(args) => self.handle_command(args).await,
+            Command::GetDhtNeighbourhood(args) => self.handle_command(args).await,
+            Command::GetTargetDifficulty(args) => self.handle_command(args).await,
             Command::ListPeers(args) => self.handle_command(args).await,
             Command::DialPeer(args) => self.handle_command(args).await,
             Command::PingPeer(args) => self.handle_command(args).await,
+            Command::Promote(args) => self.handle_command(args).await,
             Command::BanPeer(args) => self.handle_command(args).await,
             Command::UnbanPeer(args) => self.handle_command(args).await,
             Command::ResetOfflinePeers(args) => self.handle_command(args).await,
             Command::RewindBlockchain(args) => self.handle_command(args).await,
+            Command::Rewind(args) => self.handle_command(args).await,
             Command::UnbanAllPeers(args) => self.handle_command(args).await,
             Command::ListHeaders(args) => self.handle_command(args).await,
             Command::CheckDb(args) => self.handle_command(args).await,
             Command::PeriodStats(args) => self.handle_command(args).await,
+            Command::PeerHistory(args) => self.handle_command(args).await,
             Command::HeaderStats(args) => self.handle_command(args).await,
             Command::BlockTiming(args) => self.handle_command(args).await,
+            Command::AnalyzeLinkability(args) => self.handle_command(args).await,
             Command::ListReorgs(args) => self.handle_command(args).await,
             Command::DiscoverPeer(args) => self.handle_command(args).await,
             Command::GetBlock(args) => self.handle_command(args).await,
+            Command::ExportBlocks(args) => self.handle_command(args).await,
+            Command::ImportBlocks(args) => self.handle_command(args).await,
             Command::SearchUtxo(args) => self.handle_command(args).await,
             Command::SearchKernel(args) => self.handle_command(args).await,
+            Command::Service(args) => self.handle_command(args).await,
             Command::ListConnections(args) => self.handle_command(args).await,
             Command::GetMempoolStats(args) => self.handle_command(args).await,
             Command::GetMempoolState(args) => self.handle_command(args).await,
             Command::GetMempoolTx(args) => self.handle_command(args).await,
+            Command::Mempool(args) => self.handle_command(args).await,
             Command::Whoami(args) => self.handle_command(args).await,
             Command::ListBannedPeers(args) => self.handle_command(args).await,
             Command::Quit(args) | Command::Exit(args) => self.handle_command(args).await,
@@ -252,9 +362,39 @@ impl HandleCommand<Command> for CommandContext {
 }
 
 impl CommandContext {
+    /// Fetches the values used to complete command arguments in the interactive console. Node ids are offered for
+    /// connected and banned peers.
+    pub async fn completion_data(&mut self) -> CompletionData {
+        let mut node_ids = self
+            .connectivity
+            .get_active_connections()
+            .await
+            .map(|conns| {
+                conns
+                    .iter()
+                    .map(|conn| conn.peer_node_id().to_hex())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if let Ok(peers) = self.fetch_banned_peers().await {
+            node_ids.extend(peers.iter().map(|peer| peer.node_id.to_hex()));
+        }
+        node_ids.sort();
+        node_ids.dedup();
+
+        let tip_height = self
+            .blockchain_db
+            .get_chain_metadata()
+            .await
+            .map(|metadata| metadata.height_of_longest_chain())
+            .unwrap_or_default();
+
+        CompletionData { node_ids, tip_height }
+    }
+
     async fn fetch_banned_peers(&self) -> Result<Vec<Peer>, PeerManagerError> {
         let pm = &self.peer_manager;
-        let query = PeerQuery::new().select_where(|p| p.is_banned());
+        let query = PeerQuery::new().ban_state(PeerBanState::Banned);
         pm.perform_query(query).await
     }
 
@@ -0,0 +1,137 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{cmp::Reverse, time::Duration};
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use clap::Parser;
+use tari_app_utilities::utilities::UniNodeId;
+use tari_comms::peer_manager::NodeId;
+use tari_utilities::epoch_time::EpochTime;
+
+use super::{CommandContext, HandleCommand};
+use crate::{peer_history::PeerHistoryStore, table::Table, utils::format_duration_basic};
+
+/// Display the recorded connection sessions with a peer. If no peer is given, list the peers that frequently
+/// reconnected in the given period.
+/// This feature must be enabled by
+/// setting `enabled = true` in
+/// the [base_node.peer_history] section of your config.
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// hex public key or emoji id
+    #[clap(value_name = "NODE_ID")]
+    node_id: Option<UniNodeId>,
+    /// The minimum number of sessions for a peer to be listed
+    #[clap(long, default_value = "5")]
+    min_sessions: usize,
+    /// The period in hours over which sessions are counted
+    #[clap(long, default_value = "24")]
+    hours: u64,
+}
+
+#[async_trait]
+impl HandleCommand<Args> for CommandContext {
+    async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
+        match args.node_id {
+            Some(node_id) => self.peer_history(&node_id.into()),
+            None => self.list_flapping_peers(args.min_sessions, Duration::from_secs(args.hours * 60 * 60)),
+        }
+    }
+}
+
+impl CommandContext {
+    fn peer_history_store(&self) -> Result<&PeerHistoryStore, Error> {
+        self.peer_history.as_ref().ok_or_else(|| {
+            anyhow!(
+                "Peer history is turned off. Add `enabled = true` to the [base_node.peer_history] section of your \
+                 config to turn it on."
+            )
+        })
+    }
+
+    pub fn list_flapping_peers(&self, min_sessions: usize, period: Duration) -> Result<(), Error> {
+        let store = self.peer_history_store()?;
+        let since = EpochTime::now().as_u64().saturating_sub(period.as_secs());
+        let mut peers = store.find_peers_with_sessions_since(since, min_sessions)?;
+        peers.sort_by_key(|(_, history)| Reverse(history.num_sessions_since(since)));
+
+        let mut table = Table::new();
+        table.set_titles(vec!["NodeId", "Sessions", "Last seen"]);
+        for (node_id, history) in peers {
+            table.add_row(row![
+                node_id,
+                history.num_sessions_since(since),
+                format_timestamp(history.last_seen())
+            ]);
+        }
+        table.enable_row_count().print_stdout();
+        Ok(())
+    }
+
+    pub fn peer_history(&self, node_id: &NodeId) -> Result<(), Error> {
+        let store = self.peer_history_store()?;
+        let history = match store.get_history(node_id)? {
+            Some(history) => history,
+            None => {
+                println!("No sessions recorded for peer {}", node_id);
+                return Ok(());
+            },
+        };
+
+        let mut table = Table::new();
+        table.set_titles(vec![
+            "Connected",
+            "Disconnected",
+            "Duration",
+            "Direction",
+            "Address",
+            "Bytes in/out",
+            "Protocols",
+        ]);
+        for session in &history.sessions {
+            table.add_row(row![
+                format_timestamp(session.connected_at),
+                session
+                    .disconnected_at
+                    .map(format_timestamp)
+                    .unwrap_or_else(|| "--".to_string()),
+                session
+                    .duration_secs()
+                    .map(|secs| format_duration_basic(Duration::from_secs(secs)))
+                    .unwrap_or_else(|| "--".to_string()),
+                if session.is_inbound { "inbound" } else { "outbound" },
+                session.address,
+                format!("{}/{}", session.bytes_read, session.bytes_written),
+                session.protocols.join(", "),
+            ]);
+        }
+        table.enable_row_count().print_stdout();
+        Ok(())
+    }
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(timestamp as i64, 0), Utc).to_rfc2822()
+}
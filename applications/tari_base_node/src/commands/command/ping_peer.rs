@@ -34,6 +34,7 @@ use super::{CommandContext, HandleCommand};
 #[derive(Debug, Parser)]
 pub struct Args {
     /// hex public key or emoji id
+    #[clap(value_name = "NODE_ID")]
     node_id: UniNodeId,
 }
 
@@ -20,10 +20,14 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use anyhow::Error;
+use std::io;
+
+use anyhow::{anyhow, Error};
 use async_trait::async_trait;
 use clap::Parser;
 use tari_core::base_node::comms_interface::BlockEvent;
+use tari_utilities::hex::Hex;
+use tokio::task;
 
 use super::{CommandContext, HandleCommand};
 
@@ -31,9 +35,22 @@ use super::{CommandContext, HandleCommand};
 #[derive(Debug, Parser)]
 pub struct Args {
     /// new_height must be less than the current height
+    #[clap(value_name = "NEW_HEIGHT")]
     new_height: u64,
 }
 
+/// Rewinds the blockchain to the given height. Removed blocks are kept as orphans and their transactions are returned
+/// to the mempool.
+#[derive(Debug, Parser)]
+pub struct ArgsRewind {
+    /// The height of the new tip, must be less than the current height
+    #[clap(value_name = "HEIGHT")]
+    height: u64,
+    /// Do not ask for confirmation before rewinding
+    #[clap(long)]
+    force: bool,
+}
+
 #[async_trait]
 impl HandleCommand<Args> for CommandContext {
     async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
@@ -41,6 +58,13 @@ impl HandleCommand<Args> for CommandContext {
     }
 }
 
+#[async_trait]
+impl HandleCommand<ArgsRewind> for CommandContext {
+    async fn handle_command(&mut self, args: ArgsRewind) -> Result<(), Error> {
+        self.rewind_to_height(args.height, args.force).await
+    }
+}
+
 impl CommandContext {
     pub async fn rewind_blockchain(&self, new_height: u64) -> Result<(), Error> {
         let blocks = self.blockchain_db.rewind_to_height(new_height).await?;
@@ -50,4 +74,59 @@ impl CommandContext {
         }
         Ok(())
     }
+
+    /// Function to process the rewind command
+    pub async fn rewind_to_height(&mut self, height: u64, force: bool) -> Result<(), Error> {
+        let tip_height = self.node_service.get_metadata().await?.height_of_longest_chain();
+        if height >= tip_height {
+            return Err(anyhow!(
+                "Height {} must be less than the current tip height {}",
+                height,
+                tip_height
+            ));
+        }
+
+        if !force {
+            if !self.confirmation_prompts {
+                return Err(anyhow!(
+                    "Rewinding requires confirmation. Use --force to rewind when executing commands from a file"
+                ));
+            }
+            println!(
+                "This will remove {} block(s) (heights {} to {}) from the blockchain database. Continue? [y/N]",
+                tip_height - height,
+                height + 1,
+                tip_height
+            );
+            if !self.read_confirmation().await? {
+                println!("Rewind cancelled");
+                return Ok(());
+            }
+        }
+
+        self.rewind_blockchain(height).await?;
+        let metadata = self.node_service.get_metadata().await?;
+        println!(
+            "Rewound blockchain to height {}. New tip: #{} {}",
+            height,
+            metadata.height_of_longest_chain(),
+            metadata.best_block().to_hex()
+        );
+        Ok(())
+    }
+
+    /// Reads a yes/no answer from stdin. Fails if confirmation prompts are disabled.
+    pub(super) async fn read_confirmation(&self) -> Result<bool, Error> {
+        if !self.confirmation_prompts {
+            return Err(anyhow!(
+                "This command asks for confirmation, which is not possible when executing commands from a file"
+            ));
+        }
+        let line = task::spawn_blocking(|| {
+            let mut line = String::new();
+            io::stdin().read_line(&mut line).map(|_| line)
+        })
+        .await??;
+        Ok(matches!(line.trim().to_lowercase().as_str(), "y" | "yes"))
+    }
 }
@@ -0,0 +1,96 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use anyhow::Error;
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+
+use super::{CommandContext, HandleCommand};
+use crate::table::Table;
+
+/// Stop, start or restart individual services without restarting the node
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(subcommand)]
+    command: ServiceCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ServiceCommand {
+    /// List the services that can be controlled at runtime and their status
+    List,
+    /// Stop a running service
+    Stop(ServiceArgs),
+    /// Start a stopped service
+    Start(ServiceArgs),
+    /// Stop a service, if it is running, and start it again with a fresh state
+    Restart(ServiceArgs),
+}
+
+#[derive(Debug, Parser)]
+struct ServiceArgs {
+    /// The name of the service, as shown by `service list`
+    name: String,
+}
+
+#[async_trait]
+impl HandleCommand<Args> for CommandContext {
+    async fn handle_command(&mut self, args: Args) -> Result<(), Error> {
+        match args.command {
+            ServiceCommand::List => {
+                self.list_services();
+                Ok(())
+            },
+            ServiceCommand::Stop(args) => {
+                self.service_control.stop(&args.name).await?;
+                println!("Service `{}` stopped.", args.name);
+                Ok(())
+            },
+            ServiceCommand::Start(args) => {
+                self.service_control.start(&args.name).await?;
+                println!("Service `{}` started.", args.name);
+                Ok(())
+            },
+            ServiceCommand::Restart(args) => {
+                self.service_control.restart(&args.name).await?;
+                println!("Service `{}` restarted.", args.name);
+                Ok(())
+            },
+        }
+    }
+}
+
+impl CommandContext {
+    fn list_services(&self) {
+        let services = self.service_control.services();
+        if services.is_empty() {
+            println!("No services can be controlled at runtime.");
+            return;
+        }
+        let mut table = Table::new();
+        table.set_titles(vec!["Service", "Status"]);
+        for (name, status) in services {
+            table.add_row(row![name, status]);
+        }
+        table.print_stdout();
+    }
+}
@@ -20,17 +20,24 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::time::{Duration, Instant};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
 
 use anyhow::{anyhow, Error};
 use async_trait::async_trait;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use clap::Parser;
+use serde::Serialize;
 use tari_app_utilities::consts;
 use tari_comms::connectivity::ConnectivitySelection;
 
 use super::{CommandContext, HandleCommand};
-use crate::commands::status_line::{StatusLine, StatusLineOutput};
+use crate::commands::{
+    output_format::{print_json, OutputFormat},
+    status_line::{StatusLine, StatusLineOutput},
+};
 
 /// Prints out the status of this node
 #[derive(Debug, Parser)]
@@ -54,10 +61,16 @@ impl CommandContext {
             full_log = true;
         }
 
+        let state = self.state_machine_info.borrow().state_info.short_desc();
+        let synced = self.state_machine_info.borrow().state_info.is_synced();
         let mut status_line = StatusLine::new();
         status_line.add_field("", format!("v{}", consts::APP_VERSION_NUMBER));
         status_line.add_field("", self.config.network());
-        status_line.add_field("State", self.state_machine_info.borrow().state_info.short_desc());
+        status_line.add_field("State", &state);
+        status_line.add_field("Synced", if synced { "Yes" } else { "No" });
+        if self.standby.is_standby() {
+            status_line.add_field("", "Standby");
+        }
 
         let metadata = self.node_service.get_metadata().await?;
         let height = metadata.height_of_longest_chain();
@@ -83,17 +96,16 @@ impl CommandContext {
             .consensus_rules
             .consensus_constants(metadata.height_of_longest_chain());
         let mempool_stats = self.mempool_service.get_mempool_stats().await?;
+        let mempool_blocks = if mempool_stats.total_weight == 0 {
+            0
+        } else {
+            1 + mempool_stats.total_weight / constants.get_max_block_transaction_weight()
+        };
         status_line.add_field(
             "Mempool",
             format!(
                 "{}tx ({}g, +/- {}blks)",
-                mempool_stats.unconfirmed_txs,
-                mempool_stats.total_weight,
-                if mempool_stats.total_weight == 0 {
-                    0
-                } else {
-                    1 + mempool_stats.total_weight / constants.get_max_block_transaction_weight()
-                },
+                mempool_stats.unconfirmed_txs, mempool_stats.total_weight, mempool_blocks,
             ),
         );
 
@@ -110,30 +122,65 @@ impl CommandContext {
             .get_total_message_count_in_timespan(Duration::from_secs(60))
             .await?;
         status_line.add_field("Messages (last 60s)", num_messages);
+        let num_saf_messages = self.saf_requester.count_stored_messages().await?;
+        status_line.add_field("SAF", num_saf_messages);
 
         let num_active_rpc_sessions = self.rpc_server.get_num_active_sessions().await?;
-        status_line.add_field(
-            "Rpc",
-            format!(
-                "{}/{}",
-                num_active_rpc_sessions, self.config.base_node.p2p.rpc_max_simultaneous_sessions
-            ),
-        );
+        let max_rpc_sessions = self.config.base_node.p2p.rpc_max_simultaneous_sessions;
+        let mut rpc_sessions_by_protocol = BTreeMap::<String, usize>::new();
+        for session in self.rpc_server.list_sessions().await? {
+            *rpc_sessions_by_protocol
+                .entry(String::from_utf8_lossy(&session.protocol).into_owned())
+                .or_default() += 1;
+        }
+        if rpc_sessions_by_protocol.is_empty() {
+            status_line.add_field("Rpc", format!("{}/{}", num_active_rpc_sessions, max_rpc_sessions));
+        } else {
+            let per_protocol = rpc_sessions_by_protocol
+                .iter()
+                .map(|(protocol, count)| format!("{}: {}", protocol, count))
+                .collect::<Vec<_>>()
+                .join(", ");
+            status_line.add_field(
+                "Rpc",
+                format!("{}/{} ({})", num_active_rpc_sessions, max_rpc_sessions, per_protocol),
+            );
+        }
+        let randomx_vm_count = self.state_machine_info.borrow().randomx_vm_cnt;
+        let randomx_vm_flags = format!("{:?}", self.state_machine_info.borrow().randomx_vm_flags);
         if full_log {
             status_line.add_field(
                 "RandomX",
-                format!(
-                    "#{} with flags {:?}",
-                    self.state_machine_info.borrow().randomx_vm_cnt,
-                    self.state_machine_info.borrow().randomx_vm_flags
-                ),
+                format!("#{} with flags {}", randomx_vm_count, randomx_vm_flags),
             );
         }
 
         let target = "base_node::app::status";
         match output {
             StatusLineOutput::StdOutAndLog => {
-                println!("{}", status_line);
+                match self.output_format {
+                    OutputFormat::Text => println!("{}", status_line),
+                    OutputFormat::Json => print_json(&StatusOutput {
+                        version: consts::APP_VERSION_NUMBER,
+                        network: self.config.network().to_string(),
+                        state,
+                        synced,
+                        tip_height: height,
+                        tip_timestamp: last_block_time.to_rfc3339(),
+                        mempool_txs: mempool_stats.unconfirmed_txs,
+                        mempool_weight: mempool_stats.total_weight,
+                        mempool_blocks,
+                        connections: conns.len(),
+                        banned_peers: banned_peers.len(),
+                        messages_last_60s: num_messages,
+                        saf_messages: num_saf_messages,
+                        rpc_sessions: num_active_rpc_sessions,
+                        max_rpc_sessions,
+                        rpc_sessions_by_protocol,
+                        randomx_vm_count,
+                        randomx_vm_flags,
+                    })?,
+                }
                 log::info!(target: target, "{}", status_line);
             },
             StatusLineOutput::Log => log::info!(target: target, "{}", status_line),
@@ -141,3 +188,25 @@ impl CommandContext {
         Ok(())
     }
 }
+
+#[derive(Debug, Serialize)]
+struct StatusOutput {
+    version: &'static str,
+    network: String,
+    state: String,
+    synced: bool,
+    tip_height: u64,
+    tip_timestamp: String,
+    mempool_txs: usize,
+    mempool_weight: u64,
+    mempool_blocks: u64,
+    connections: usize,
+    banned_peers: usize,
+    messages_last_60s: usize,
+    saf_messages: usize,
+    rpc_sessions: usize,
+    max_rpc_sessions: usize,
+    rpc_sessions_by_protocol: BTreeMap<String, usize>,
+    randomx_vm_count: usize,
+    randomx_vm_flags: String,
+}
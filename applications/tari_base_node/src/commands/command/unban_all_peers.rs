@@ -23,7 +23,7 @@
 use anyhow::Error;
 use async_trait::async_trait;
 use clap::Parser;
-use tari_comms::peer_manager::PeerQuery;
+use tari_comms::peer_manager::{PeerBanState, PeerQuery};
 
 use super::{CommandContext, HandleCommand};
 
@@ -40,7 +40,7 @@ impl HandleCommand<Args> for CommandContext {
 
 impl CommandContext {
     pub async fn unban_all_peers(&self) -> Result<(), Error> {
-        let query = PeerQuery::new().select_where(|p| p.is_banned());
+        let query = PeerQuery::new().ban_state(PeerBanState::Banned);
         let peers = self.peer_manager.perform_query(query).await?;
         let num_peers = peers.len();
         for peer in peers {
@@ -23,9 +23,11 @@
 use anyhow::Error;
 use async_trait::async_trait;
 use clap::Parser;
+use serde::Serialize;
 use tari_app_utilities::consts;
 
 use super::{CommandContext, HandleCommand};
+use crate::commands::output_format::{print_json, OutputFormat};
 
 /// Gets the current application version
 #[derive(Debug, Parser)]
@@ -41,6 +43,26 @@ impl HandleCommand<Args> for CommandContext {
 impl CommandContext {
     /// Function process the version command
     pub fn print_version(&self) -> Result<(), Error> {
+        if self.output_format == OutputFormat::Json {
+            let available_update = self
+                .software_updater
+                .new_update_notifier()
+                .borrow()
+                .as_ref()
+                .map(|update| AvailableUpdate {
+                    version: update.version().to_string(),
+                    app: update.app().to_string(),
+                    download_url: update.download_url().to_string(),
+                    sha: update.to_hash_hex(),
+                });
+            return print_json(&VersionOutput {
+                version: consts::APP_VERSION,
+                author: consts::APP_AUTHOR,
+                avx2: cfg!(feature = "avx2"),
+                available_update,
+            });
+        }
+
         println!("Version: {}", consts::APP_VERSION);
         println!("Author: {}", consts::APP_AUTHOR);
         println!("Avx2: {}", if cfg!(feature = "avx2") { "enabled" } else { "disabled" });
@@ -57,3 +79,19 @@ impl CommandContext {
         Ok(())
     }
 }
+
+#[derive(Debug, Serialize)]
+struct VersionOutput {
+    version: &'static str,
+    author: &'static str,
+    avx2: bool,
+    available_update: Option<AvailableUpdate>,
+}
+
+#[derive(Debug, Serialize)]
+struct AvailableUpdate {
+    version: String,
+    app: String,
+    download_url: String,
+    sha: String,
+}
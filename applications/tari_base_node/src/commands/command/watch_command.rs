@@ -20,13 +20,14 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::fmt;
+use std::{fmt, time::Duration};
 
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use async_trait::async_trait;
 use clap::Parser;
 
-use super::{CommandContext, HandleCommand};
+use super::{Command, CommandContext, HandleCommand};
+use crate::commands::parser::HumanDuration;
 
 pub type WatchCommand = Args;
 
@@ -37,13 +38,15 @@ impl fmt::Display for WatchCommand {
 }
 
 const DEFAULT_WATCH: &str = "status";
+/// The shortest interval between refreshes, so that an interval of zero does not make the watch loop spin
+const MIN_WATCH_INTERVAL: Duration = Duration::from_secs(1);
 
-/// Repeat a command within an interval.
+/// Repeat a read-only command within an interval.
 #[derive(Debug, Parser)]
 pub struct Args {
-    /// Interval in seconds
+    /// Interval between refreshes e.g. `5s` or `1m`. A number without a unit is interpreted as seconds.
     #[clap(short, long)]
-    pub interval: Option<u64>,
+    pub interval: Option<HumanDuration>,
     /// The command to perform. `status` if empty.
     #[clap(default_value = DEFAULT_WATCH)]
     pub command: String,
@@ -71,6 +74,28 @@ impl Args {
     pub fn line(&self) -> &str {
         &self.command
     }
+
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval.map(|interval| interval.0)
+    }
+
+    /// Returns the interval between refreshes, or `default` if no interval was given. Intervals shorter than one second
+    /// are raised to one second.
+    pub fn interval_or(&self, default: Duration) -> Duration {
+        self.interval().unwrap_or(default).max(MIN_WATCH_INTERVAL)
+    }
+
+    /// Parses the watched command, rejecting commands that change the state of the node
+    pub fn watched_command(&self) -> Result<Command, Error> {
+        let args = self.command.parse::<super::Args>()?;
+        if !args.command.is_read_only() {
+            return Err(anyhow!(
+                "`{}` is not a read-only command and can't be watched",
+                self.command
+            ));
+        }
+        Ok(args.command)
+    }
 }
 
 #[async_trait]
@@ -79,3 +104,37 @@ impl HandleCommand<Args> for CommandContext {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_the_interval_with_units() {
+        let args = Args::try_parse_from(&["watch", "--interval", "5s", "list-connections"]).unwrap();
+        assert_eq!(args.interval(), Some(Duration::from_secs(5)));
+        assert_eq!(args.line(), "list-connections");
+
+        let args = Args::try_parse_from(&["watch", "-i", "10"]).unwrap();
+        assert_eq!(args.interval(), Some(Duration::from_secs(10)));
+        assert_eq!(args.line(), DEFAULT_WATCH);
+    }
+
+    #[test]
+    fn it_clamps_the_interval_to_the_minimum() {
+        let args = Args::try_parse_from(&["watch", "--interval", "0s"]).unwrap();
+        assert_eq!(args.interval_or(Duration::from_secs(5)), MIN_WATCH_INTERVAL);
+        let args = Args::try_parse_from(&["watch"]).unwrap();
+        assert_eq!(args.interval_or(Duration::ZERO), MIN_WATCH_INTERVAL);
+        assert_eq!(args.interval_or(Duration::from_secs(5)), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn it_only_watches_read_only_commands() {
+        assert!(Args::new("get-mempool-stats").watched_command().is_ok());
+        assert!(Args::new("status --output log").watched_command().is_ok());
+        assert!(Args::new("unban-all-peers").watched_command().is_err());
+        assert!(Args::new("watch status").watched_command().is_err());
+        assert!(Args::new("not-a-command").watched_command().is_err());
+    }
+}
@@ -0,0 +1,232 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::sync::{Arc, RwLock};
+
+use clap::{Arg, Command as ClapCommand, CommandFactory};
+
+use super::command::Args;
+
+/// The number of block heights at and below the chain tip that are offered as completions
+const NUM_HEIGHT_COMPLETIONS: u64 = 10;
+
+/// The kind of value an argument expects. This is determined by the `value_name` given to the argument in its clap
+/// definition e.g. `#[clap(value_name = "NODE_ID")]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompletionKind {
+    NodeId,
+    BlockHeight,
+}
+
+impl CompletionKind {
+    fn from_arg(arg: &Arg<'_>) -> Option<Self> {
+        let value_name = arg.get_value_names()?.first()?;
+        if *value_name == "NODE_ID" {
+            Some(CompletionKind::NodeId)
+        } else if value_name.contains("HEIGHT") {
+            Some(CompletionKind::BlockHeight)
+        } else {
+            None
+        }
+    }
+}
+
+/// Values used to complete command arguments.
+#[derive(Debug, Clone, Default)]
+pub struct CompletionData {
+    pub node_ids: Vec<String>,
+    pub tip_height: u64,
+}
+
+/// Provides context-aware completions for the interactive console. Rustyline requests completions synchronously, so
+/// the completion data is a snapshot that is refreshed from the node before each prompt.
+#[derive(Debug, Clone, Default)]
+pub struct CompletionSource {
+    data: Arc<RwLock<CompletionData>>,
+}
+
+impl CompletionSource {
+    /// Replaces the completion data snapshot
+    pub fn update(&self, data: CompletionData) {
+        if let Ok(mut lock) = self.data.write() {
+            *lock = data;
+        }
+    }
+
+    /// Returns the position in `line` from which the word under the cursor starts, and the candidates that complete
+    /// it. The first word is completed from `commands`, subsequent words from the argument definitions of that
+    /// command.
+    pub fn complete(&self, commands: &[String], line: &str, pos: usize) -> (usize, Vec<String>) {
+        let line = &line[..pos];
+        let word_start = line.rfind(char::is_whitespace).map(|i| i + 1).unwrap_or(0);
+        let word = &line[word_start..];
+        let mut preceding = line[..word_start].split_whitespace();
+        match preceding.next() {
+            Some(command) => {
+                let preceding = preceding.collect::<Vec<_>>();
+                (word_start, self.complete_argument(command, &preceding, word))
+            },
+            None => {
+                let completions = commands.iter().filter(|cmd| cmd.starts_with(word)).cloned().collect();
+                (0, completions)
+            },
+        }
+    }
+
+    fn complete_argument(&self, command: &str, preceding: &[&str], word: &str) -> Vec<String> {
+        let args = Args::command();
+        let command = match args.find_subcommand(command) {
+            Some(command) => command,
+            None => return Vec::new(),
+        };
+
+        if word.starts_with('-') {
+            return command
+                .get_arguments()
+                .filter_map(|arg| arg.get_long())
+                .map(|long| format!("--{}", long))
+                .filter(|long| long.starts_with(word))
+                .collect();
+        }
+
+        let kind = match find_current_arg(command, preceding).and_then(CompletionKind::from_arg) {
+            Some(kind) => kind,
+            None => return Vec::new(),
+        };
+        let data = match self.data.read() {
+            Ok(data) => data,
+            Err(_) => return Vec::new(),
+        };
+        match kind {
+            CompletionKind::NodeId => data
+                .node_ids
+                .iter()
+                .filter(|node_id| node_id.starts_with(word))
+                .cloned()
+                .collect(),
+            CompletionKind::BlockHeight => (data.tip_height.saturating_sub(NUM_HEIGHT_COMPLETIONS - 1)..=
+                data.tip_height)
+                .rev()
+                .map(|height| height.to_string())
+                .filter(|height| height.starts_with(word))
+                .collect(),
+        }
+    }
+}
+
+/// Returns the argument of `command` that the word following `preceding` is a value for
+fn find_current_arg<'a, 'help>(command: &'a ClapCommand<'help>, preceding: &[&str]) -> Option<&'a Arg<'help>> {
+    let mut num_positionals = 0;
+    let mut pending_option = None;
+    for word in preceding {
+        if pending_option.take().is_some() {
+            continue;
+        }
+        if let Some(long) = word.strip_prefix("--") {
+            if !long.contains('=') {
+                pending_option = command
+                    .get_arguments()
+                    .find(|arg| arg.get_long() == Some(long))
+                    .filter(|arg| arg.is_takes_value_set());
+            }
+        } else if let Some(short) = word.strip_prefix('-') {
+            let mut chars = short.chars();
+            if let (Some(short), None) = (chars.next(), chars.next()) {
+                pending_option = command
+                    .get_arguments()
+                    .find(|arg| arg.get_short() == Some(short))
+                    .filter(|arg| arg.is_takes_value_set());
+            }
+        } else {
+            num_positionals += 1;
+        }
+    }
+
+    pending_option.or_else(|| command.get_positionals().nth(num_positionals))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn source() -> CompletionSource {
+        let source = CompletionSource::default();
+        source.update(CompletionData {
+            node_ids: vec!["aabbcc".to_string(), "aaddee".to_string(), "ff0011".to_string()],
+            tip_height: 1000,
+        });
+        source
+    }
+
+    #[test]
+    fn it_completes_command_names() {
+        let commands = vec!["ban-peer".to_string(), "block-timing".to_string(), "status".to_string()];
+        let (start, completions) = source().complete(&commands, "b", 1);
+        assert_eq!(start, 0);
+        assert_eq!(completions, vec!["ban-peer", "block-timing"]);
+    }
+
+    #[test]
+    fn it_completes_node_ids() {
+        let line = "ban-peer aa";
+        let (start, completions) = source().complete(&[], line, line.len());
+        assert_eq!(start, 9);
+        assert_eq!(completions, vec!["aabbcc", "aaddee"]);
+
+        let line = "unban-peer ";
+        let (_, completions) = source().complete(&[], line, line.len());
+        assert_eq!(completions.len(), 3);
+    }
+
+    #[test]
+    fn it_completes_heights_near_the_tip() {
+        let line = "list-headers 10 ";
+        let (_, completions) = source().complete(&[], line, line.len());
+        assert_eq!(completions.len(), NUM_HEIGHT_COMPLETIONS as usize);
+        assert_eq!(completions[0], "1000");
+
+        let line = "list-headers 999 100";
+        let (_, completions) = source().complete(&[], line, line.len());
+        assert_eq!(completions, vec!["1000"]);
+    }
+
+    #[test]
+    fn it_completes_option_names_and_skips_option_values() {
+        let line = "ban-peer aabbcc --d";
+        let (_, completions) = source().complete(&[], line, line.len());
+        assert_eq!(completions, vec!["--duration"]);
+
+        let line = "export-blocks --file blocks.dat 10 ";
+        let (_, completions) = source().complete(&[], line, line.len());
+        assert_eq!(completions[0], "1000");
+    }
+
+    #[test]
+    fn it_does_not_complete_unknown_arguments() {
+        let line = "ban-peer aabbcc ";
+        let (_, completions) = source().complete(&[], line, line.len());
+        assert!(completions.is_empty());
+        let line = "not-a-command ";
+        let (_, completions) = source().complete(&[], line, line.len());
+        assert!(completions.is_empty());
+    }
+}
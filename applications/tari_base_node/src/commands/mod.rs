@@ -20,10 +20,13 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+pub mod batch;
 pub mod cli;
 pub mod cli_loop;
 pub mod command;
+pub mod completion;
 pub mod nom_parser;
+pub mod output_format;
 pub mod parser;
 pub mod reader;
 pub mod status_line;
@@ -20,13 +20,12 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
 use rustyline::{
     completion::Completer,
     error::ReadlineError,
     hint::{Hinter, HistoryHinter},
-    line_buffer::LineBuffer,
     Context,
 };
 use rustyline_derive::{Helper, Highlighter, Validator};
@@ -34,7 +33,7 @@ use strum::{Display, EnumString};
 use tari_utilities::hex::{Hex, HexError};
 use thiserror::Error;
 
-use super::command::Command;
+use super::{command::Command, completion::CompletionSource};
 
 #[derive(Debug, Error)]
 #[error("invalid format '{0}'")]
@@ -64,30 +63,52 @@ impl<T: Hex> FromStr for FromHex<T> {
     }
 }
 
+#[derive(Debug, Error)]
+#[error("invalid duration '{0}', expected a number followed by one of s, m, h, d or w (e.g. 24h)")]
+pub struct DurationParseError(String);
+
+/// A duration given as a number with an optional unit suffix e.g. `90`, `30m`, `24h` or `7d`. A number without a
+/// suffix is interpreted as seconds.
+#[derive(Debug, Clone, Copy)]
+pub struct HumanDuration(pub Duration);
+
+impl FromStr for HumanDuration {
+    type Err = DurationParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split_at = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (value, unit) = s.split_at(split_at);
+        let value = value.parse::<u64>().map_err(|_| DurationParseError(s.to_string()))?;
+        let multiplier = match unit {
+            "" | "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => return Err(DurationParseError(s.to_string())),
+        };
+        let secs = value
+            .checked_mul(multiplier)
+            .ok_or_else(|| DurationParseError(s.to_string()))?;
+        Ok(Self(Duration::from_secs(secs)))
+    }
+}
+
 /// This is used to parse commands from the user and execute them
 #[derive(Helper, Validator, Highlighter)]
 pub struct Parser {
     commands: Vec<String>,
+    completions: CompletionSource,
     hinter: HistoryHinter,
 }
 
-/// This will go through all instructions and look for potential matches
+/// This will go through all instructions and their arguments and look for potential matches
 impl Completer for Parser {
     type Candidate = String;
 
     fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Result<(usize, Vec<String>), ReadlineError> {
-        let completions = self
-            .commands
-            .iter()
-            .filter(|cmd| cmd.starts_with(line))
-            .cloned()
-            .collect();
-
-        Ok((pos, completions))
-    }
-
-    fn update(&self, line: &mut LineBuffer, _: usize, elected: &str) {
-        line.update(elected, elected.len());
+        Ok(self.completions.complete(&self.commands, line, pos))
     }
 }
 
@@ -105,6 +126,7 @@ impl Parser {
     pub fn new() -> Self {
         Parser {
             commands: Command::variants(),
+            completions: CompletionSource::default(),
             hinter: HistoryHinter {},
         }
     }
@@ -113,4 +135,42 @@ impl Parser {
     pub fn get_commands(&self) -> Vec<String> {
         self.commands.clone()
     }
+
+    /// Returns the source of argument completions, which is shared with the parser once it is given to rustyline
+    pub fn completion_source(&self) -> CompletionSource {
+        self.completions.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_human_durations() {
+        assert_eq!("90".parse::<HumanDuration>().unwrap().0, Duration::from_secs(90));
+        assert_eq!("30s".parse::<HumanDuration>().unwrap().0, Duration::from_secs(30));
+        assert_eq!("15m".parse::<HumanDuration>().unwrap().0, Duration::from_secs(15 * 60));
+        assert_eq!(
+            "24h".parse::<HumanDuration>().unwrap().0,
+            Duration::from_secs(24 * 60 * 60)
+        );
+        assert_eq!(
+            "7d".parse::<HumanDuration>().unwrap().0,
+            Duration::from_secs(7 * 24 * 60 * 60)
+        );
+        assert_eq!(
+            "2w".parse::<HumanDuration>().unwrap().0,
+            Duration::from_secs(14 * 24 * 60 * 60)
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_durations() {
+        assert!("".parse::<HumanDuration>().is_err());
+        assert!("h".parse::<HumanDuration>().is_err());
+        assert!("10y".parse::<HumanDuration>().is_err());
+        assert!("-1h".parse::<HumanDuration>().is_err());
+        assert!("18446744073709551615w".parse::<HumanDuration>().is_err());
+    }
 }
@@ -27,15 +27,16 @@ use std::{
 
 use config::Config;
 use serde::{Deserialize, Serialize};
+use tari_app_utilities::telemetry::TracingConfig;
 use tari_common::{
-    configuration::{serializers, CommonConfig, Network, StringList},
+    configuration::{serializers, CommonConfig, GrpcSecurityConfig, Network, StringList},
     ConfigurationError,
     DefaultConfigLoader,
     SubConfigPath,
 };
 use tari_comms::multiaddr::Multiaddr;
 use tari_core::{
-    base_node::BaseNodeStateMachineConfig,
+    base_node::{chain_metadata_service::TipDivergenceConfig, BaseNodeStateMachineConfig, PeerAutoBanConfig},
     chain_storage::BlockchainDatabaseConfig,
     mempool::MempoolConfig,
 };
@@ -53,6 +54,7 @@ pub struct ApplicationConfig {
     pub peer_seeds: PeerSeedsConfig,
     #[cfg(feature = "metrics")]
     pub metrics: MetricsConfig,
+    pub tracing: TracingConfig,
 }
 
 impl ApplicationConfig {
@@ -64,9 +66,11 @@ impl ApplicationConfig {
             base_node: BaseNodeConfig::load_from(cfg)?,
             #[cfg(feature = "metrics")]
             metrics: MetricsConfig::load_from(cfg)?,
+            tracing: TracingConfig::load_from(cfg)?,
         };
 
         config.base_node.set_base_path(config.common.base_path());
+        config.base_node.apply_seed_node_profile();
         Ok(config)
     }
 
@@ -81,6 +85,8 @@ pub struct BaseNodeConfig {
     override_from: Option<String>,
     pub network: Network,
     pub grpc_address: Option<Multiaddr>,
+    /// TLS and API token authentication for the gRPC server
+    pub grpc_security: GrpcSecurityConfig,
     pub identity_file: PathBuf,
     pub use_libtor: bool,
     pub tor_identity_file: PathBuf,
@@ -105,6 +111,13 @@ pub struct BaseNodeConfig {
     #[serde(with = "serializers::seconds")]
     pub metadata_auto_ping_interval: Duration,
     pub state_machine: BaseNodeStateMachineConfig,
+    pub tip_divergence: TipDivergenceConfig,
+    pub peer_auto_ban: PeerAutoBanConfig,
+    pub seed_node: SeedNodeConfig,
+    /// Start the node in warm standby. The node stays synced but does not serve RPC sessions or relay blocks and
+    /// transactions until it is promoted.
+    pub standby: bool,
+    pub peer_history: PeerHistoryConfig,
     pub resize_terminal_on_startup: bool,
     pub report_grpc_error: bool,
 }
@@ -115,6 +128,7 @@ impl Default for BaseNodeConfig {
             override_from: None,
             network: Network::LocalNet,
             grpc_address: Some("/ip4/127.0.0.1/tcp/18142".parse().unwrap()),
+            grpc_security: GrpcSecurityConfig::default(),
             identity_file: PathBuf::from("config/base_node_id.json"),
             use_libtor: false,
             tor_identity_file: PathBuf::from("config/tor_id.json"),
@@ -136,6 +150,11 @@ impl Default for BaseNodeConfig {
             buffer_rate_limit: 10,
             metadata_auto_ping_interval: Duration::from_secs(30),
             state_machine: Default::default(),
+            tip_divergence: Default::default(),
+            peer_auto_ban: Default::default(),
+            seed_node: Default::default(),
+            standby: false,
+            peer_history: Default::default(),
             resize_terminal_on_startup: true,
             report_grpc_error: false,
         }
@@ -162,8 +181,84 @@ impl BaseNodeConfig {
         if !self.lmdb_path.is_absolute() {
             self.lmdb_path = self.data_dir.join(self.lmdb_path.as_path());
         }
+        if !self.peer_history.db_path.is_absolute() {
+            self.peer_history.db_path = self.data_dir.join(self.peer_history.db_path.as_path());
+        }
+        self.grpc_security.set_base_path(base_path.as_ref());
         self.p2p.set_base_path(base_path);
     }
+
+    /// If seed node mode is enabled, overrides the p2p and mempool settings with those tuned for a seed node
+    pub fn apply_seed_node_profile(&mut self) {
+        if !self.seed_node.enabled {
+            return;
+        }
+        let seed_node = &self.seed_node;
+        self.p2p.max_simultaneous_inbound_connects = seed_node.max_simultaneous_inbound_connects;
+        self.p2p.connection_reaper_min_inactive_age = seed_node.idle_connection_max_age;
+        self.p2p.connection_pool_refresh_interval = seed_node.connection_pool_refresh_interval;
+        self.p2p.dht.peer_list_cache_ttl = Some(seed_node.peer_list_cache_ttl);
+        // Seed nodes only serve peers and headers, so there is no need to sync or relay mempool transactions
+        self.mempool.service.relay_transactions = false;
+        self.mempool.service.initial_sync_num_peers = 0;
+    }
+}
+
+/// Configuration for running the base node as a seed node. Seed nodes accept many short-lived inbound connections from
+/// nodes joining the network, serve them peer lists and do not relay blocks or transactions.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SeedNodeConfig {
+    /// Enable seed node mode. When enabled, the settings below override the equivalent p2p and mempool settings.
+    pub enabled: bool,
+    /// The maximum number of inbound connection attempts handled at the same time
+    pub max_simultaneous_inbound_connects: usize,
+    /// Connections that have been inactive for this long are disconnected, freeing up capacity for new peers
+    #[serde(with = "serializers::seconds")]
+    pub idle_connection_max_age: Duration,
+    /// Interval at which idle connections are reaped
+    #[serde(with = "serializers::seconds")]
+    pub connection_pool_refresh_interval: Duration,
+    /// How long the peer list served to peers is cached for
+    #[serde(with = "serializers::seconds")]
+    pub peer_list_cache_ttl: Duration,
+}
+
+impl Default for SeedNodeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_simultaneous_inbound_connects: 1000,
+            idle_connection_max_age: Duration::from_secs(2 * 60),
+            connection_pool_refresh_interval: Duration::from_secs(20),
+            peer_list_cache_ttl: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Configuration for the on-disk history of peer connection sessions
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PeerHistoryConfig {
+    /// Record the connection sessions with each peer
+    pub enabled: bool,
+    /// The path to the peer history database, relative to the base node data directory
+    pub db_path: PathBuf,
+    /// The maximum number of peers to keep history for. The least recently seen peers are removed first.
+    pub max_peers: usize,
+    /// The maximum number of sessions to keep for each peer
+    pub max_sessions_per_peer: usize,
+}
+
+impl Default for PeerHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            db_path: PathBuf::from("peer_history"),
+            max_peers: 1000,
+            max_sessions_per_peer: 100,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,25 +34,25 @@ use tari_app_grpc::{
 };
 use tari_app_utilities::consts;
 use tari_common_types::types::{Commitment, PublicKey, Signature};
-use tari_comms::{Bytes, CommsNode};
+use tari_comms::{protocol::rpc::RpcServerHandle, Bytes, CommsNode};
 use tari_core::{
     base_node::{
-        comms_interface::CommsInterfaceError,
+        comms_interface::{BlockEvent, CommsInterfaceError, ReorgEvent},
         state_machine_service::states::StateInfo,
         LocalNodeCommsInterface,
         StateMachineHandle,
     },
-    blocks::{Block, BlockHeader, NewBlockTemplate},
-    chain_storage::{ChainStorageError, PrunedOutput},
+    blocks::{Block, BlockHeader, HistoricalBlock, NewBlockTemplate},
+    chain_storage::{async_db::AsyncBlockchainDb, BlockAddResult, ChainStorageError, LMDBDatabase, PrunedOutput},
     consensus::{emission::Emission, ConsensusDecoding, ConsensusEncoding, ConsensusManager, NetworkConsensus},
     iterators::NonOverlappingIntegerPairIter,
-    mempool::{service::LocalMempoolService, TxStorageResponse},
+    mempool::{service::LocalMempoolService, MempoolEvent, TxStorageResponse},
     proof_of_work::PowAlgorithm,
     transactions::{aggregated_body::AggregateBody, transaction_components::Transaction},
 };
 use tari_p2p::{auto_update::SoftwareUpdaterHandle, services::liveness::LivenessHandle};
-use tari_utilities::{hex::Hex, message_format::MessageFormat, ByteArray, Hashable};
-use tokio::task;
+use tari_utilities::{epoch_time::EpochTime, hex::Hex, message_format::MessageFormat, ByteArray, Hashable};
+use tokio::{sync::broadcast, task};
 use tonic::{Request, Response, Status};
 
 use crate::{
@@ -60,13 +60,23 @@ use crate::{
     grpc::{
         blocks::{block_fees, block_heights, block_size, GET_BLOCKS_MAX_HEIGHTS, GET_BLOCKS_PAGE_SIZE},
         hash_rate::HashRateMovingAverage,
+        health,
         helpers::{mean, median},
+        pagination::{
+            BlockFieldSelection,
+            PageRange,
+            BLOCKS_PAGE_MAX_SIZE,
+            HEADERS_PAGE_MAX_SIZE,
+            KERNELS_PAGE_BLOCK_BATCH_SIZE,
+            KERNELS_PAGE_MAX_SIZE,
+        },
     },
 };
 
 const LOG_TARGET: &str = "tari::base_node::grpc";
 const GET_TOKENS_IN_CIRCULATION_MAX_HEIGHTS: usize = 1_000_000;
 const GET_TOKENS_IN_CIRCULATION_PAGE_SIZE: usize = 1_000;
+const SUBSCRIPTION_CHANNEL_SIZE: usize = 100;
 // The maximum number of difficulty ints that can be requested at a time. These will be streamed to the
 // client, so memory is not really a concern here, but a malicious client could request a large
 // number here to keep the node busy
@@ -82,6 +92,8 @@ const LIST_HEADERS_PAGE_SIZE: usize = 10;
 const LIST_HEADERS_DEFAULT_NUM_HEADERS: u64 = 10;
 
 const BLOCK_TIMING_MAX_BLOCKS: u64 = 10_000;
+// The maximum number of hashes that can be requested at a time from GetHeadersByHashes and GetBlocksByHashes
+const GET_BY_HASHES_MAX_HASHES: usize = 1_000;
 
 pub struct BaseNodeGrpcServer {
     node_service: LocalNodeCommsInterface,
@@ -92,6 +104,9 @@ pub struct BaseNodeGrpcServer {
     software_updater: SoftwareUpdaterHandle,
     comms: CommsNode,
     liveness: LivenessHandle,
+    rpc_server: RpcServerHandle,
+    blockchain_db: AsyncBlockchainDb<LMDBDatabase>,
+    max_rpc_sessions: usize,
     report_grpc_error: bool,
 }
 
@@ -106,6 +121,9 @@ impl BaseNodeGrpcServer {
             software_updater: ctx.software_updater(),
             comms: ctx.base_node_comms().clone(),
             liveness: ctx.liveness(),
+            rpc_server: ctx.rpc_server(),
+            blockchain_db: ctx.blockchain_db().into(),
+            max_rpc_sessions: ctx.config().base_node.p2p.rpc_max_simultaneous_sessions,
             report_grpc_error: ctx.get_report_grpc_error(),
         }
     }
@@ -113,6 +131,22 @@ impl BaseNodeGrpcServer {
     pub fn report_error_flag(&self) -> bool {
         self.report_grpc_error
     }
+
+    async fn resolve_page(
+        &self,
+        request: &tari_rpc::HistoricalPageRequest,
+        max_page_size: u64,
+    ) -> Result<Option<PageRange>, Status> {
+        let report_error_flag = self.report_error_flag();
+        let tip = self
+            .node_service
+            .clone()
+            .get_metadata()
+            .await
+            .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?
+            .height_of_longest_chain();
+        PageRange::from_request(request, tip, max_page_size).map_err(|e| report_error(report_error_flag, e))
+    }
 }
 
 pub fn report_error(report: bool, status: Status) -> Status {
@@ -130,10 +164,84 @@ pub async fn get_heights(
     block_heights(handler, request.start_height, request.end_height, request.from_tip).await
 }
 
+fn block_header_response(consensus_rules: &ConsensusManager, block: HistoricalBlock) -> tari_rpc::BlockHeaderResponse {
+    let (block, acc_data, confirmations, _) = block.dissolve();
+    let total_block_reward = consensus_rules.calculate_coinbase_and_fees(block.header.height, block.body.kernels());
+
+    tari_rpc::BlockHeaderResponse {
+        difficulty: acc_data.achieved_difficulty.into(),
+        num_transactions: block.body.kernels().len() as u32,
+        confirmations,
+        header: Some(block.header.into()),
+        reward: total_block_reward.into(),
+    }
+}
+
+fn validate_requested_hashes(hashes: &[Vec<u8>]) -> Result<(), Status> {
+    if hashes.is_empty() {
+        return Err(Status::invalid_argument("hashes cannot be empty"));
+    }
+    if hashes.len() > GET_BY_HASHES_MAX_HASHES {
+        return Err(Status::invalid_argument(format!(
+            "Too many hashes requested (max: {}, got: {})",
+            GET_BY_HASHES_MAX_HASHES,
+            hashes.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Looks up the blocks for `hashes` a page at a time and streams a response for each hash, in the order requested.
+/// `to_response` is given `None` for hashes that do not match a block.
+fn stream_blocks_by_hashes<T, F>(
+    mut handler: LocalNodeCommsInterface,
+    hashes: Vec<Vec<u8>>,
+    report_error_flag: bool,
+    to_response: F,
+) -> mpsc::Receiver<Result<T, Status>>
+where
+    T: Send + 'static,
+    F: Fn(Vec<u8>, Option<HistoricalBlock>) -> Result<T, Status> + Send + 'static,
+{
+    let (mut tx, rx) = mpsc::channel(GET_BLOCKS_PAGE_SIZE);
+    task::spawn(async move {
+        for page in hashes.chunks(GET_BLOCKS_PAGE_SIZE) {
+            let mut blocks = match handler.get_blocks_by_hashes(page.to_vec()).await {
+                Ok(blocks) => blocks,
+                Err(err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Error communicating with local base node: {:?}", err,
+                    );
+                    let _result = tx
+                        .send(Err(report_error(report_error_flag, Status::internal(err.to_string()))))
+                        .await;
+                    return;
+                },
+            };
+
+            for hash in page {
+                let block = blocks
+                    .iter()
+                    .position(|b| b.hash() == hash)
+                    .map(|i| blocks.swap_remove(i));
+                let resp = to_response(hash.clone(), block).map_err(|err| report_error(report_error_flag, err));
+                if let Err(err) = tx.send(resp).await {
+                    warn!(target: LOG_TARGET, "Error sending response via GRPC: {}", err);
+                    return;
+                }
+            }
+        }
+    });
+    rx
+}
+
 #[tonic::async_trait]
 impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
     type FetchMatchingUtxosStream = mpsc::Receiver<Result<tari_rpc::FetchMatchingUtxosResponse, Status>>;
+    type GetBlocksByHashesStream = mpsc::Receiver<Result<tari_rpc::GetBlocksByHashesResponse, Status>>;
     type GetBlocksStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
+    type GetHeadersByHashesStream = mpsc::Receiver<Result<tari_rpc::GetHeadersByHashesResponse, Status>>;
     type GetMempoolTransactionsStream = mpsc::Receiver<Result<tari_rpc::GetMempoolTransactionsResponse, Status>>;
     type GetNetworkDifficultyStream = mpsc::Receiver<Result<tari_rpc::NetworkDifficultyResponse, Status>>;
     type GetPeersStream = mpsc::Receiver<Result<tari_rpc::GetPeersResponse, Status>>;
@@ -143,6 +251,8 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
     type ListHeadersStream = mpsc::Receiver<Result<tari_rpc::BlockHeader, Status>>;
     type SearchKernelsStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
     type SearchUtxosStream = mpsc::Receiver<Result<tari_rpc::HistoricalBlock, Status>>;
+    type SubscribeBlocksStream = mpsc::Receiver<Result<tari_rpc::BlockEvent, Status>>;
+    type SubscribeMempoolStream = mpsc::Receiver<Result<tari_rpc::MempoolEvent, Status>>;
 
     async fn get_network_difficulty(
         &self,
@@ -212,7 +322,7 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
                     // update the moving average calculation with the header data
                     let current_hash_rate_moving_average = match pow_algo {
                         PowAlgorithm::Monero => &mut monero_hash_rate_moving_average,
-                        PowAlgorithm::Sha3 => &mut sha3_hash_rate_moving_average,
+                        PowAlgorithm::Sha3 | PowAlgorithm::Sha3x => &mut sha3_hash_rate_moving_average,
                     };
                     current_hash_rate_moving_average.add(current_height, current_difficulty);
 
@@ -729,8 +839,9 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
         })?;
         let mut handler = self.node_service.clone();
 
+        let changed_since_token = Some(request.changed_since_token).filter(|token| *token != 0);
         let new_template = handler
-            .get_new_block_template(algo, request.max_weight)
+            .get_new_block_template_if_changed(algo, request.max_weight, changed_since_token)
             .await
             .map_err(|e| {
                 warn!(
@@ -742,21 +853,34 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             })?;
 
         let status_watch = self.state_machine_handle.get_status_info_watch();
-        let pow = algo as i32;
-        let response = tari_rpc::NewBlockTemplateResponse {
-            miner_data: Some(tari_rpc::MinerData {
-                reward: new_template.reward.into(),
-                target_difficulty: new_template.target_difficulty.as_u64(),
-                total_fees: new_template.total_fees.into(),
-                algo: Some(tari_rpc::PowAlgo { pow_algo: pow }),
-            }),
-            new_block_template: Some(
-                new_template
-                    .try_into()
-                    .map_err(|e| report_error(report_error_flag, Status::internal(e)))?,
-            ),
-
-            initial_sync_achieved: (*status_watch.borrow()).bootstrapped,
+        let initial_sync_achieved = (*status_watch.borrow()).bootstrapped;
+        let response = match new_template {
+            Some((new_template, token)) => {
+                let pow = algo as i32;
+                tari_rpc::NewBlockTemplateResponse {
+                    miner_data: Some(tari_rpc::MinerData {
+                        reward: new_template.reward.into(),
+                        target_difficulty: new_template.target_difficulty.as_u64(),
+                        total_fees: new_template.total_fees.into(),
+                        algo: Some(tari_rpc::PowAlgo { pow_algo: pow }),
+                    }),
+                    new_block_template: Some(
+                        new_template
+                            .try_into()
+                            .map_err(|e| report_error(report_error_flag, Status::internal(e)))?,
+                    ),
+                    initial_sync_achieved,
+                    template_token: token,
+                    template_unchanged: false,
+                }
+            },
+            None => tari_rpc::NewBlockTemplateResponse {
+                miner_data: None,
+                new_block_template: None,
+                initial_sync_achieved,
+                template_token: request.changed_since_token,
+                template_unchanged: true,
+            },
         };
 
         debug!(target: LOG_TARGET, "Sending GetNewBlockTemplate response to client");
@@ -956,21 +1080,8 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             error!(target: LOG_TARGET, "Error submitting:{}", e);
             report_error(report_error_flag, Status::internal(e.to_string()))
         })?;
-        let response = match res {
-            TxStorageResponse::UnconfirmedPool => tari_rpc::SubmitTransactionResponse {
-                result: tari_rpc::SubmitTransactionResult::Accepted.into(),
-            },
-            TxStorageResponse::ReorgPool | TxStorageResponse::NotStoredAlreadySpent => {
-                tari_rpc::SubmitTransactionResponse {
-                    result: tari_rpc::SubmitTransactionResult::AlreadyMined.into(),
-                }
-            },
-            TxStorageResponse::NotStored |
-            TxStorageResponse::NotStoredOrphan |
-            TxStorageResponse::NotStoredConsensus |
-            TxStorageResponse::NotStoredTimeLocked => tari_rpc::SubmitTransactionResponse {
-                result: tari_rpc::SubmitTransactionResult::Rejected.into(),
-            },
+        let response = tari_rpc::SubmitTransactionResponse {
+            result: submit_transaction_result(&res).into(),
         };
 
         debug!(target: LOG_TARGET, "Sending SubmitTransaction response to client");
@@ -1049,7 +1160,8 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             TxStorageResponse::NotStored |
             TxStorageResponse::NotStoredConsensus |
             TxStorageResponse::NotStoredOrphan |
-            TxStorageResponse::NotStoredTimeLocked => tari_rpc::TransactionStateResponse {
+            TxStorageResponse::NotStoredTimeLocked |
+            TxStorageResponse::NotStoredFeeTooLow => tari_rpc::TransactionStateResponse {
                 result: tari_rpc::TransactionLocation::NotStored.into(),
             },
         };
@@ -1061,6 +1173,56 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
         Ok(Response::new(response))
     }
 
+    async fn get_kernel_location(
+        &self,
+        request: Request<tari_rpc::GetKernelLocationRequest>,
+    ) -> Result<Response<tari_rpc::GetKernelLocationResponse>, Status> {
+        let report_error_flag = self.report_error_flag();
+        let request = request.into_inner();
+        let excess_sig: Signature = request
+            .excess_sig
+            .ok_or_else(|| {
+                report_error(
+                    report_error_flag,
+                    Status::invalid_argument("excess_sig not provided".to_string()),
+                )
+            })?
+            .try_into()
+            .map_err(|_| {
+                report_error(
+                    report_error_flag,
+                    Status::invalid_argument("excess_sig could not be converted".to_string()),
+                )
+            })?;
+        debug!(target: LOG_TARGET, "Incoming GRPC request for GetKernelLocation");
+        let mut node_handler = self.node_service.clone();
+
+        let location = node_handler
+            .get_kernel_location_by_excess_sig(excess_sig)
+            .await
+            .map_err(|e| {
+                error!(target: LOG_TARGET, "Error submitting query:{}", e);
+                report_error(report_error_flag, Status::internal(e.to_string()))
+            })?;
+        let location = match location {
+            Some(location) => location,
+            None => return Ok(Response::new(tari_rpc::GetKernelLocationResponse::default())),
+        };
+
+        let metadata = node_handler.get_metadata().await.map_err(|e| {
+            error!(target: LOG_TARGET, "Error submitting query:{}", e);
+            report_error(report_error_flag, Status::internal(e.to_string()))
+        })?;
+        Ok(Response::new(tari_rpc::GetKernelLocationResponse {
+            is_mined: true,
+            confirmations: metadata.height_of_longest_chain().saturating_sub(location.block_height),
+            block_hash: location.block_hash,
+            block_height: location.block_height,
+            mmr_position: u64::from(location.mmr_position),
+            kernel_hash: location.kernel_hash,
+        }))
+    }
+
     async fn get_peers(
         &self,
         _request: Request<tari_rpc::GetPeersRequest>,
@@ -1191,6 +1353,72 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
         Ok(Response::new(rx))
     }
 
+    async fn get_headers_by_hashes(
+        &self,
+        request: Request<tari_rpc::GetHeadersByHashesRequest>,
+    ) -> Result<Response<Self::GetHeadersByHashesStream>, Status> {
+        let report_error_flag = self.report_error_flag();
+        let tari_rpc::GetHeadersByHashesRequest { hashes } = request.into_inner();
+        debug!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for GetHeadersByHashes: {} hash(es)",
+            hashes.len()
+        );
+        validate_requested_hashes(&hashes).map_err(|err| report_error(report_error_flag, err))?;
+
+        let consensus_rules = self.consensus_rules.clone();
+        let rx = stream_blocks_by_hashes(
+            self.node_service.clone(),
+            hashes,
+            report_error_flag,
+            move |hash, block| {
+                Ok(tari_rpc::GetHeadersByHashesResponse {
+                    hash,
+                    found: block.is_some(),
+                    header: block.map(|b| block_header_response(&consensus_rules, b)),
+                })
+            },
+        );
+
+        debug!(
+            target: LOG_TARGET,
+            "Sending GetHeadersByHashes response stream to client"
+        );
+        Ok(Response::new(rx))
+    }
+
+    async fn get_blocks_by_hashes(
+        &self,
+        request: Request<tari_rpc::GetBlocksByHashesRequest>,
+    ) -> Result<Response<Self::GetBlocksByHashesStream>, Status> {
+        let report_error_flag = self.report_error_flag();
+        let tari_rpc::GetBlocksByHashesRequest { hashes } = request.into_inner();
+        debug!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for GetBlocksByHashes: {} hash(es)",
+            hashes.len()
+        );
+        validate_requested_hashes(&hashes).map_err(|err| report_error(report_error_flag, err))?;
+
+        let rx = stream_blocks_by_hashes(self.node_service.clone(), hashes, report_error_flag, |hash, block| {
+            let block = block
+                .map(tari_rpc::HistoricalBlock::try_from)
+                .transpose()
+                .map_err(|err| Status::internal(format!("Could not provide block: {}", err)))?;
+            Ok(tari_rpc::GetBlocksByHashesResponse {
+                hash,
+                found: block.is_some(),
+                block,
+            })
+        });
+
+        debug!(
+            target: LOG_TARGET,
+            "Sending GetBlocksByHashes response stream to client"
+        );
+        Ok(Response::new(rx))
+    }
+
     async fn get_tip_info(
         &self,
         _request: Request<tari_rpc::Empty>,
@@ -1535,7 +1763,9 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
         heights = heights
             .drain(..cmp::min(heights.len(), GET_TOKENS_IN_CIRCULATION_MAX_HEIGHTS))
             .collect();
-        let consensus_manager = ConsensusManager::builder(self.network.as_network()).build();
+        let consensus_manager = ConsensusManager::builder(self.network.as_network())
+            .build()
+            .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?;
 
         let (mut tx, rx) = mpsc::channel(GET_TOKENS_IN_CIRCULATION_PAGE_SIZE);
         task::spawn(async move {
@@ -1606,21 +1836,31 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
                 tip_height: 0,
                 local_height: 0,
                 state: tari_rpc::SyncState::HeaderStarting.into(),
+                horizon_progress: None,
             },
             StateInfo::HeaderSync(Some(info)) => tari_rpc::SyncProgressResponse {
                 tip_height: info.tip_height,
                 local_height: info.local_height,
                 state: tari_rpc::SyncState::Header.into(),
+                horizon_progress: None,
             },
             StateInfo::BlockSyncStarting => tari_rpc::SyncProgressResponse {
                 tip_height: 0,
                 local_height: 0,
                 state: tari_rpc::SyncState::BlockStarting.into(),
+                horizon_progress: None,
             },
             StateInfo::BlockSync(info) => tari_rpc::SyncProgressResponse {
                 tip_height: info.tip_height,
                 local_height: info.local_height,
                 state: tari_rpc::SyncState::Block.into(),
+                horizon_progress: None,
+            },
+            StateInfo::HorizonSync(info) => tari_rpc::SyncProgressResponse {
+                tip_height: 0,
+                local_height: 0,
+                state: tari_rpc::SyncState::Horizon.into(),
+                horizon_progress: Some((&info.status).into()),
             },
             _ => tari_rpc::SyncProgressResponse {
                 tip_height: 0,
@@ -1630,6 +1870,7 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
                 } else {
                     tari_rpc::SyncState::Startup.into()
                 },
+                horizon_progress: None,
             },
         };
         Ok(Response::new(response))
@@ -1674,22 +1915,7 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             .map_err(|err| report_error(report_error_flag, Status::internal(err.to_string())))?;
 
         match block {
-            Some(block) => {
-                let (block, acc_data, confirmations, _) = block.dissolve();
-                let total_block_reward = self
-                    .consensus_rules
-                    .calculate_coinbase_and_fees(block.header.height, block.body.kernels());
-
-                let resp = tari_rpc::BlockHeaderResponse {
-                    difficulty: acc_data.achieved_difficulty.into(),
-                    num_transactions: block.body.kernels().len() as u32,
-                    confirmations,
-                    header: Some(block.header.into()),
-                    reward: total_block_reward.into(),
-                };
-
-                Ok(Response::new(resp))
-            },
+            Some(block) => Ok(Response::new(block_header_response(&self.consensus_rules, block))),
             None => Err(report_error(
                 report_error_flag,
                 Status::not_found(format!("Header not found with hash `{}`", hash_hex)),
@@ -1709,22 +1935,7 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             .map_err(|err| Status::internal(err.to_string()))?;
 
         match block {
-            Some(block) => {
-                let (block, acc_data, confirmations, _) = block.dissolve();
-                let total_block_reward = self
-                    .consensus_rules
-                    .calculate_coinbase_and_fees(block.header.height, block.body.kernels());
-
-                let resp = tari_rpc::BlockHeaderResponse {
-                    difficulty: acc_data.achieved_difficulty.into(),
-                    num_transactions: block.body.kernels().len() as u32,
-                    confirmations,
-                    header: Some(block.header.into()),
-                    reward: total_block_reward.into(),
-                };
-
-                Ok(Response::new(resp))
-            },
+            Some(block) => Ok(Response::new(block_header_response(&self.consensus_rules, block))),
             None => Err(Status::not_found(format!("Header not found with height `{}`", height))),
         }
     }
@@ -1820,10 +2031,423 @@ impl tari_rpc::base_node_server::BaseNode for BaseNodeGrpcServer {
             unconfirmed_txs: mempool_stats.unconfirmed_txs as u64,
             reorg_txs: mempool_stats.reorg_txs as u64,
             total_weight: mempool_stats.total_weight,
+            min_fee_per_gram: mempool_stats.min_fee_per_gram,
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn get_fee_estimates(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<tari_rpc::GetFeeEstimatesResponse>, Status> {
+        let report_error_flag = self.report_error_flag();
+        debug!(target: LOG_TARGET, "Incoming GRPC request for GetFeeEstimates");
+        let mut handler = self.node_service.clone();
+
+        let fee_estimates = handler.get_fee_estimates().await.map_err(|e| {
+            error!(target: LOG_TARGET, "Error fetching fee estimates: {}", e);
+            report_error(report_error_flag, Status::internal(e.to_string()))
+        })?;
+
+        let response = tari_rpc::GetFeeEstimatesResponse {
+            tip_height: fee_estimates.tip_height,
+            estimates: fee_estimates
+                .estimates
+                .into_iter()
+                .map(|estimate| tari_rpc::FeeEstimate {
+                    target_blocks: estimate.target_blocks,
+                    fee_per_gram: estimate.fee_per_gram.as_u64(),
+                })
+                .collect(),
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn get_target_difficulty(
+        &self,
+        request: Request<tari_rpc::GetTargetDifficultyRequest>,
+    ) -> Result<Response<tari_rpc::GetTargetDifficultyResponse>, Status> {
+        let report_error_flag = self.report_error_flag();
+        let request = request.into_inner();
+        debug!(target: LOG_TARGET, "Incoming GRPC request for GetTargetDifficulty");
+        let pow_algo = request
+            .algo
+            .and_then(|algo| u64::try_from(algo.pow_algo).ok())
+            .and_then(|algo| PowAlgorithm::try_from(algo).ok())
+            .ok_or_else(|| {
+                report_error(
+                    report_error_flag,
+                    Status::invalid_argument("No valid pow algo selected".to_string()),
+                )
+            })?;
+        let height = if request.height == 0 {
+            None
+        } else {
+            Some(request.height)
+        };
+
+        let mut handler = self.node_service.clone();
+        let estimate = handler
+            .get_target_difficulty_estimate(pow_algo, height)
+            .await
+            .map_err(|e| {
+                warn!(target: LOG_TARGET, "Could not get target difficulty estimate: {}", e);
+                report_error(report_error_flag, Status::internal(e.to_string()))
+            })?;
+
+        let response = tari_rpc::GetTargetDifficultyResponse {
+            algo: Some(tari_rpc::PowAlgo {
+                pow_algo: estimate.pow_algo as i32,
+            }),
+            height: estimate.height,
+            current_difficulty: estimate.current_difficulty.as_u64(),
+            target_difficulty: estimate.target_difficulty.as_u64(),
+            estimated_hash_rate: estimate.estimated_hash_rate(),
+            target_block_interval: estimate.target_block_interval,
+            seconds_to_next_adjustment: estimate.time_to_next_adjustment(EpochTime::now()),
+            last_block_timestamp: estimate.last_block_timestamp.as_u64(),
         };
 
         Ok(Response::new(response))
     }
+
+    async fn subscribe_blocks(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<Self::SubscribeBlocksStream>, Status> {
+        let report_error_flag = self.report_error_flag();
+        debug!(target: LOG_TARGET, "Incoming GRPC request for SubscribeBlocks");
+        let mut block_events = self.node_service.get_block_event_stream();
+        let mut reorg_events = self.node_service.get_reorg_event_stream();
+        let (mut tx, rx) = mpsc::channel(SUBSCRIPTION_CHANNEL_SIZE);
+
+        task::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    event = block_events.recv() => event.map(|event| new_tip_event(&event)),
+                    event = reorg_events.recv() => event.map(|event| reorg_event(&event).map(Some)),
+                };
+                let event = match event {
+                    Ok(Ok(Some(event))) => Ok(event),
+                    Ok(Ok(None)) => continue,
+                    Ok(Err(err)) => {
+                        warn!(target: LOG_TARGET, "Error converting block event for GRPC: {}", err);
+                        continue;
+                    },
+                    Err(broadcast::error::RecvError::Lagged(n)) => Err(report_error(
+                        report_error_flag,
+                        Status::data_loss(format!("Subscriber fell behind and missed {} block events", n)),
+                    )),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let is_err = event.is_err();
+                if tx.send(event).await.is_err() || is_err {
+                    break;
+                }
+            }
+            debug!(target: LOG_TARGET, "SubscribeBlocks stream ended");
+        });
+
+        Ok(Response::new(rx))
+    }
+
+    async fn subscribe_mempool(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<Self::SubscribeMempoolStream>, Status> {
+        let report_error_flag = self.report_error_flag();
+        debug!(target: LOG_TARGET, "Incoming GRPC request for SubscribeMempool");
+        let mut mempool_events = self.mempool_service.get_mempool_event_stream();
+        let (mut tx, rx) = mpsc::channel(SUBSCRIPTION_CHANNEL_SIZE);
+
+        task::spawn(async move {
+            loop {
+                let event = match mempool_events.recv().await {
+                    Ok(event) => match mempool_event(&event) {
+                        Ok(event) => Ok(event),
+                        Err(err) => {
+                            warn!(target: LOG_TARGET, "Error converting mempool event for GRPC: {}", err);
+                            continue;
+                        },
+                    },
+                    Err(broadcast::error::RecvError::Lagged(n)) => Err(report_error(
+                        report_error_flag,
+                        Status::data_loss(format!("Subscriber fell behind and missed {} mempool events", n)),
+                    )),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let is_err = event.is_err();
+                if tx.send(event).await.is_err() || is_err {
+                    break;
+                }
+            }
+            debug!(target: LOG_TARGET, "SubscribeMempool stream ended");
+        });
+
+        Ok(Response::new(rx))
+    }
+
+    async fn get_headers_page(
+        &self,
+        request: Request<tari_rpc::HistoricalPageRequest>,
+    ) -> Result<Response<tari_rpc::GetHeadersPageResponse>, Status> {
+        let report_error_flag = self.report_error_flag();
+        let request = request.into_inner();
+        debug!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for GetHeadersPage: {:?}", request
+        );
+        let mut handler = self.node_service.clone();
+        let page = match self.resolve_page(&request, HEADERS_PAGE_MAX_SIZE).await? {
+            Some(page) => page,
+            None => return Ok(Response::new(Default::default())),
+        };
+
+        let heights = page.heights();
+        let headers = handler
+            .get_headers(heights.clone())
+            .await
+            .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?;
+        let next_cursor = page.next_cursor(*heights.end());
+        let headers = headers.into_iter().map(|h| h.header().clone().into()).collect();
+
+        Ok(Response::new(tari_rpc::GetHeadersPageResponse { headers, next_cursor }))
+    }
+
+    async fn get_blocks_page(
+        &self,
+        request: Request<tari_rpc::HistoricalPageRequest>,
+    ) -> Result<Response<tari_rpc::GetBlocksPageResponse>, Status> {
+        let report_error_flag = self.report_error_flag();
+        let request = request.into_inner();
+        debug!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for GetBlocksPage: {:?}", request
+        );
+        let selection =
+            BlockFieldSelection::from_fields(&request.fields).map_err(|e| report_error(report_error_flag, e))?;
+        let mut handler = self.node_service.clone();
+        let page = match self.resolve_page(&request, BLOCKS_PAGE_MAX_SIZE).await? {
+            Some(page) => page,
+            None => return Ok(Response::new(Default::default())),
+        };
+
+        let heights = page.heights();
+        let blocks = handler
+            .get_blocks(heights.clone())
+            .await
+            .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?;
+        let next_cursor = page.next_cursor(*heights.end());
+        let blocks = blocks
+            .into_iter()
+            .map(|block| {
+                let mut block = tari_rpc::HistoricalBlock::try_from(block)
+                    .map_err(|e| report_error(report_error_flag, Status::internal(e)))?;
+                if let Some(block) = block.block.as_mut() {
+                    selection.apply(block);
+                }
+                Ok(block)
+            })
+            .collect::<Result<_, Status>>()?;
+
+        Ok(Response::new(tari_rpc::GetBlocksPageResponse { blocks, next_cursor }))
+    }
+
+    async fn get_kernels_page(
+        &self,
+        request: Request<tari_rpc::HistoricalPageRequest>,
+    ) -> Result<Response<tari_rpc::GetKernelsPageResponse>, Status> {
+        let report_error_flag = self.report_error_flag();
+        let request = request.into_inner();
+        debug!(
+            target: LOG_TARGET,
+            "Incoming GRPC request for GetKernelsPage: {:?}", request
+        );
+        let mut handler = self.node_service.clone();
+        let page = match self.resolve_page(&request, KERNELS_PAGE_MAX_SIZE).await? {
+            Some(page) => page,
+            None => return Ok(Response::new(Default::default())),
+        };
+
+        let mut kernels = Vec::new();
+        let mut next_cursor = None;
+        let mut skip = page.offset as usize;
+        let mut height = page.start;
+        'fill: while height <= page.end {
+            let batch_end = cmp::min(page.end, height.saturating_add(KERNELS_PAGE_BLOCK_BATCH_SIZE - 1));
+            let blocks = handler
+                .get_blocks(height..=batch_end)
+                .await
+                .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?;
+            for block in blocks {
+                let block_height = block.header().height;
+                for (index, kernel) in block.block().body.kernels().iter().enumerate().skip(skip) {
+                    // The cursor is only set once there is a kernel left over, so a full final page has none
+                    if kernels.len() as u64 == page.page_size {
+                        next_cursor = Some(tari_rpc::PageCursor {
+                            height: block_height,
+                            offset: index as u32,
+                        });
+                        break 'fill;
+                    }
+                    kernels.push(tari_rpc::KernelAtHeight {
+                        height: block_height,
+                        block_hash: block.hash().clone(),
+                        kernel: Some(kernel.clone().into()),
+                    });
+                }
+                skip = 0;
+            }
+            height = batch_end + 1;
+        }
+
+        Ok(Response::new(tari_rpc::GetKernelsPageResponse { kernels, next_cursor }))
+    }
+
+    async fn get_node_health(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<tari_rpc::NodeHealthResponse>, Status> {
+        let report_error_flag = self.report_error_flag();
+        debug!(target: LOG_TARGET, "Incoming GRPC request for GetNodeHealth");
+
+        // Sync
+        let mut handler = self.node_service.clone();
+        let meta = handler
+            .get_metadata()
+            .await
+            .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?;
+        let tip_height = meta.height_of_longest_chain();
+        let tip_age_secs = handler
+            .get_header(tip_height)
+            .await
+            .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?
+            .map(|h| EpochTime::now().as_u64().saturating_sub(h.header().timestamp.as_u64()))
+            .unwrap_or(0);
+        let status_info = self.state_machine_handle.get_status_info_watch().borrow().clone();
+        let is_synced = status_info.state_info.is_synced();
+        let is_syncing = !is_synced && !matches!(status_info.state_info, StateInfo::StartUp);
+        let state: tari_rpc::BaseNodeState = (&status_info.state_info).into();
+        let sync = tari_rpc::SyncHealth {
+            verdict: health::sync_verdict(is_synced, is_syncing, tip_age_secs) as i32,
+            state: state as i32,
+            state_description: status_info.state_info.short_desc(),
+            initial_sync_achieved: status_info.bootstrapped,
+            local_height: tip_height,
+            tip_age_secs,
+        };
+
+        // Peers
+        let connectivity_status = self
+            .comms
+            .connectivity()
+            .get_connectivity_status()
+            .await
+            .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?;
+        let latency = self
+            .liveness
+            .clone()
+            .get_network_avg_latency()
+            .await
+            .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?;
+        let status = tari_rpc::ConnectivityStatus::from(connectivity_status);
+        let peers = tari_rpc::PeerHealth {
+            verdict: health::peer_verdict(status) as i32,
+            status: status as i32,
+            num_node_connections: connectivity_status.num_connected_nodes() as u32,
+            avg_latency_ms: latency
+                .map(|l| u32::try_from(l.as_millis()).unwrap_or(u32::MAX))
+                .unwrap_or(0),
+        };
+
+        // Mempool
+        let mempool_stats = self
+            .mempool_service
+            .clone()
+            .get_mempool_stats()
+            .await
+            .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?;
+        let max_block_weight = self
+            .consensus_rules
+            .consensus_constants(tip_height)
+            .get_max_block_transaction_weight();
+        let backlog_blocks = health::mempool_backlog_blocks(mempool_stats.total_weight, max_block_weight);
+        let mempool = tari_rpc::MempoolHealth {
+            verdict: health::mempool_verdict(backlog_blocks) as i32,
+            total_txs: mempool_stats.total_txs as u64,
+            unconfirmed_txs: mempool_stats.unconfirmed_txs as u64,
+            reorg_txs: mempool_stats.reorg_txs as u64,
+            total_weight: mempool_stats.total_weight,
+            backlog_blocks,
+        };
+
+        // Database
+        let db_stats = self
+            .blockchain_db
+            .get_stats()
+            .await
+            .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?;
+        let used_bytes = db_stats.db_stats().iter().map(|s| s.total_page_size()).sum::<usize>() as u64;
+        let map_size_bytes = db_stats.env_info().mapsize as u64;
+        let used_percent = health::database_used_percent(used_bytes, map_size_bytes);
+        let database = tari_rpc::DatabaseHealth {
+            verdict: health::database_verdict(used_percent) as i32,
+            used_bytes,
+            map_size_bytes,
+            used_percent,
+        };
+
+        // RPC
+        let active_sessions = self
+            .rpc_server
+            .get_num_active_sessions()
+            .await
+            .map_err(|e| report_error(report_error_flag, Status::internal(e.to_string())))?;
+        let rpc = tari_rpc::RpcHealth {
+            verdict: health::rpc_verdict(active_sessions, self.max_rpc_sessions) as i32,
+            active_sessions: active_sessions as u32,
+            max_sessions: self.max_rpc_sessions as u32,
+        };
+
+        // Version
+        let update_version = self
+            .software_updater
+            .new_update_notifier()
+            .borrow()
+            .as_ref()
+            .map(|update| update.version().to_string());
+        let version = tari_rpc::VersionHealth {
+            verdict: health::version_verdict(update_version.is_some()) as i32,
+            version: consts::APP_VERSION.to_string(),
+            has_update: update_version.is_some(),
+            update_version: update_version.unwrap_or_default(),
+        };
+
+        let overall = health::overall_verdict(
+            [
+                sync.verdict,
+                peers.verdict,
+                mempool.verdict,
+                database.verdict,
+                rpc.verdict,
+                version.verdict,
+            ]
+            .iter()
+            .filter_map(|v| tari_rpc::HealthVerdict::from_i32(*v)),
+        );
+
+        Ok(Response::new(tari_rpc::NodeHealthResponse {
+            overall: overall as i32,
+            sync: Some(sync),
+            peers: Some(peers),
+            mempool: Some(mempool),
+            database: Some(database),
+            rpc: Some(rpc),
+            version: Some(version),
+        }))
+    }
 }
 
 enum BlockGroupType {
@@ -1892,3 +2516,57 @@ async fn get_block_group(
         calc_type: calc_type_response,
     }))
 }
+
+fn submit_transaction_result(response: &TxStorageResponse) -> tari_rpc::SubmitTransactionResult {
+    match response {
+        TxStorageResponse::UnconfirmedPool => tari_rpc::SubmitTransactionResult::Accepted,
+        TxStorageResponse::ReorgPool | TxStorageResponse::NotStoredAlreadySpent => {
+            tari_rpc::SubmitTransactionResult::AlreadyMined
+        },
+        // The transaction may be accepted later with a higher fee or once the mempool has space
+        TxStorageResponse::NotStoredFeeTooLow => tari_rpc::SubmitTransactionResult::NotProcessableAtThisTime,
+        TxStorageResponse::NotStored |
+        TxStorageResponse::NotStoredOrphan |
+        TxStorageResponse::NotStoredConsensus |
+        TxStorageResponse::NotStoredTimeLocked => tari_rpc::SubmitTransactionResult::Rejected,
+    }
+}
+
+/// Returns the new chain tip for block events that changed it, or None for events that did not
+fn new_tip_event(event: &BlockEvent) -> Result<Option<tari_rpc::BlockEvent>, String> {
+    let tip = match event {
+        BlockEvent::ValidBlockAdded(block, BlockAddResult::Ok(_)) => Block::clone(block),
+        BlockEvent::ValidBlockAdded(_, BlockAddResult::ChainReorg { added, .. }) => match added.last() {
+            Some(block) => block.block().clone(),
+            None => return Ok(None),
+        },
+        BlockEvent::BlockSyncComplete(tip) => tip.block().clone(),
+        _ => return Ok(None),
+    };
+    Ok(Some(tari_rpc::BlockEvent {
+        event: Some(tari_rpc::block_event::Event::NewTip(tip.try_into()?)),
+    }))
+}
+
+fn reorg_event(event: &ReorgEvent) -> Result<tari_rpc::BlockEvent, String> {
+    let returned_transactions = event
+        .returned_transactions
+        .iter()
+        .map(|tx| tari_rpc::Transaction::try_from(tx.clone()))
+        .collect::<Result<_, _>>()?;
+    Ok(tari_rpc::BlockEvent {
+        event: Some(tari_rpc::block_event::Event::Reorg(tari_rpc::ReorgEvent {
+            fork_height: event.fork_height,
+            removed_block_hashes: event.removed_blocks.clone(),
+            added_block_hashes: event.added_blocks.clone(),
+            returned_transactions,
+        })),
+    })
+}
+
+fn mempool_event(event: &MempoolEvent) -> Result<tari_rpc::MempoolEvent, String> {
+    Ok(tari_rpc::MempoolEvent {
+        transaction: Some(event.transaction.clone().try_into()?),
+        result: submit_transaction_result(&event.response).into(),
+    })
+}
@@ -45,7 +45,7 @@ impl HashRateMovingAverage {
     pub fn new(pow_algo: PowAlgorithm, consensus_manager: ConsensusManager) -> Self {
         let window_size = match pow_algo {
             PowAlgorithm::Monero => MONERO_HASH_RATE_MOVING_AVERAGE_WINDOW,
-            PowAlgorithm::Sha3 => SHA3_HASH_RATE_MOVING_AVERAGE_WINDOW,
+            PowAlgorithm::Sha3 | PowAlgorithm::Sha3x => SHA3_HASH_RATE_MOVING_AVERAGE_WINDOW,
         };
         let hash_rates = VecDeque::with_capacity(window_size);
 
@@ -182,7 +182,8 @@ mod test {
     fn create_hash_rate_ma(pow_algo: PowAlgorithm) -> HashRateMovingAverage {
         let consensus_manager = ConsensusManagerBuilder::new(Network::Dibbler)
             .add_consensus_constants(ConsensusConstants::dibbler()[0].clone())
-            .build();
+            .build()
+            .unwrap();
         HashRateMovingAverage::new(pow_algo, consensus_manager)
     }
 
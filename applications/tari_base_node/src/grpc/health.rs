@@ -0,0 +1,179 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Verdicts for the sections of the `GetNodeHealth` response. These are kept free of any node handles so that the
+//! thresholds can be tested in isolation.
+
+use tari_app_grpc::tari_rpc::{ConnectivityStatus, HealthVerdict};
+
+/// A synced node whose tip is older than this is likely cut off from the network.
+pub const STALE_TIP_SECS: u64 = 60 * 60;
+/// The number of full blocks of unconfirmed transactions before the mempool is considered backed up.
+pub const MEMPOOL_BACKLOG_WARNING_BLOCKS: u64 = 5;
+pub const DATABASE_WARNING_PERCENT: f64 = 90.0;
+pub const DATABASE_CRITICAL_PERCENT: f64 = 98.0;
+pub const RPC_SESSIONS_WARNING_PERCENT: usize = 80;
+
+pub fn sync_verdict(is_synced: bool, is_syncing: bool, tip_age_secs: u64) -> HealthVerdict {
+    match (is_synced, is_syncing) {
+        (true, _) if tip_age_secs > STALE_TIP_SECS => HealthVerdict::HealthWarning,
+        (true, _) => HealthVerdict::HealthOk,
+        (false, true) => HealthVerdict::HealthWarning,
+        (false, false) => HealthVerdict::HealthCritical,
+    }
+}
+
+pub fn peer_verdict(status: ConnectivityStatus) -> HealthVerdict {
+    match status {
+        ConnectivityStatus::Online => HealthVerdict::HealthOk,
+        ConnectivityStatus::Degraded => HealthVerdict::HealthWarning,
+        ConnectivityStatus::Initializing | ConnectivityStatus::Offline => HealthVerdict::HealthCritical,
+    }
+}
+
+/// Returns the number of full blocks required to clear `unconfirmed_weight` from the mempool.
+pub fn mempool_backlog_blocks(unconfirmed_weight: u64, max_block_weight: u64) -> u64 {
+    if max_block_weight == 0 {
+        return 0;
+    }
+    (unconfirmed_weight + max_block_weight - 1) / max_block_weight
+}
+
+pub fn mempool_verdict(backlog_blocks: u64) -> HealthVerdict {
+    if backlog_blocks > MEMPOOL_BACKLOG_WARNING_BLOCKS {
+        HealthVerdict::HealthWarning
+    } else {
+        HealthVerdict::HealthOk
+    }
+}
+
+pub fn database_used_percent(used_bytes: u64, map_size_bytes: u64) -> f64 {
+    if map_size_bytes == 0 {
+        return 0.0;
+    }
+    used_bytes as f64 / map_size_bytes as f64 * 100.0
+}
+
+pub fn database_verdict(used_percent: f64) -> HealthVerdict {
+    if used_percent >= DATABASE_CRITICAL_PERCENT {
+        HealthVerdict::HealthCritical
+    } else if used_percent >= DATABASE_WARNING_PERCENT {
+        HealthVerdict::HealthWarning
+    } else {
+        HealthVerdict::HealthOk
+    }
+}
+
+/// A `max_sessions` of zero means that sessions are unlimited.
+pub fn rpc_verdict(active_sessions: usize, max_sessions: usize) -> HealthVerdict {
+    if max_sessions == 0 {
+        return HealthVerdict::HealthOk;
+    }
+    if active_sessions >= max_sessions {
+        HealthVerdict::HealthCritical
+    } else if active_sessions * 100 >= max_sessions * RPC_SESSIONS_WARNING_PERCENT {
+        HealthVerdict::HealthWarning
+    } else {
+        HealthVerdict::HealthOk
+    }
+}
+
+pub fn version_verdict(has_update: bool) -> HealthVerdict {
+    if has_update {
+        HealthVerdict::HealthWarning
+    } else {
+        HealthVerdict::HealthOk
+    }
+}
+
+/// The overall verdict is the worst of the section verdicts.
+pub fn overall_verdict<I: IntoIterator<Item = HealthVerdict>>(verdicts: I) -> HealthVerdict {
+    verdicts.into_iter().max().unwrap_or(HealthVerdict::HealthOk)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sync_verdicts() {
+        assert_eq!(sync_verdict(true, false, 120), HealthVerdict::HealthOk);
+        assert_eq!(
+            sync_verdict(true, false, STALE_TIP_SECS + 1),
+            HealthVerdict::HealthWarning
+        );
+        assert_eq!(sync_verdict(false, true, 0), HealthVerdict::HealthWarning);
+        assert_eq!(sync_verdict(false, false, 0), HealthVerdict::HealthCritical);
+    }
+
+    #[test]
+    fn mempool_backlog() {
+        assert_eq!(mempool_backlog_blocks(0, 100), 0);
+        assert_eq!(mempool_backlog_blocks(1, 100), 1);
+        assert_eq!(mempool_backlog_blocks(600, 100), 6);
+        assert_eq!(mempool_backlog_blocks(600, 0), 0);
+        assert_eq!(mempool_verdict(5), HealthVerdict::HealthOk);
+        assert_eq!(mempool_verdict(6), HealthVerdict::HealthWarning);
+    }
+
+    #[test]
+    fn database_verdicts() {
+        assert_eq!(database_used_percent(50, 0), 0.0);
+        assert_eq!(
+            database_verdict(database_used_percent(50, 100)),
+            HealthVerdict::HealthOk
+        );
+        assert_eq!(
+            database_verdict(database_used_percent(95, 100)),
+            HealthVerdict::HealthWarning
+        );
+        assert_eq!(
+            database_verdict(database_used_percent(99, 100)),
+            HealthVerdict::HealthCritical
+        );
+    }
+
+    #[test]
+    fn rpc_verdicts() {
+        assert_eq!(rpc_verdict(10, 0), HealthVerdict::HealthOk);
+        assert_eq!(rpc_verdict(79, 100), HealthVerdict::HealthOk);
+        assert_eq!(rpc_verdict(80, 100), HealthVerdict::HealthWarning);
+        assert_eq!(rpc_verdict(100, 100), HealthVerdict::HealthCritical);
+    }
+
+    #[test]
+    fn overall_is_worst() {
+        assert_eq!(overall_verdict(vec![]), HealthVerdict::HealthOk);
+        assert_eq!(
+            overall_verdict(vec![
+                HealthVerdict::HealthOk,
+                HealthVerdict::HealthCritical,
+                HealthVerdict::HealthWarning
+            ]),
+            HealthVerdict::HealthCritical
+        );
+        assert_eq!(
+            overall_verdict(vec![peer_verdict(ConnectivityStatus::Degraded), version_verdict(false)]),
+            HealthVerdict::HealthWarning
+        );
+    }
+}
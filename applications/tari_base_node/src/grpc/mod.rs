@@ -23,4 +23,6 @@
 pub mod base_node_grpc_server;
 pub mod blocks;
 pub mod hash_rate;
+pub mod health;
 pub mod helpers;
+pub mod pagination;
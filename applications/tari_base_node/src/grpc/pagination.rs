@@ -0,0 +1,241 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Page limits and cursor handling for the paginated historical queries (`GetHeadersPage`, `GetBlocksPage` and
+//! `GetKernelsPage`).
+
+use std::{cmp, ops::RangeInclusive};
+
+use tari_app_grpc::tari_rpc::{self, BlockField, HistoricalPageRequest, PageCursor};
+use tonic::Status;
+
+/// The maximum number of headers in a page
+pub const HEADERS_PAGE_MAX_SIZE: u64 = 1000;
+/// The maximum number of blocks in a page
+pub const BLOCKS_PAGE_MAX_SIZE: u64 = 100;
+/// The maximum number of kernels in a page
+pub const KERNELS_PAGE_MAX_SIZE: u64 = 1000;
+/// The number of blocks fetched from the base node at a time while filling a page of kernels
+pub const KERNELS_PAGE_BLOCK_BATCH_SIZE: u64 = 10;
+
+/// The part of a historical query that a single page is taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRange {
+    /// The height of the first item in the page
+    pub start: u64,
+    /// The number of items at `start` that were returned in earlier pages
+    pub offset: u32,
+    /// The last height of the query, inclusive
+    pub end: u64,
+    /// The maximum number of items in the page
+    pub page_size: u64,
+}
+
+impl PageRange {
+    /// Resolves the page requested by `request`, clamping the page size to `max_page_size` and the range to the
+    /// chain tip. Returns None if there is nothing left to return.
+    pub fn from_request(request: &HistoricalPageRequest, tip: u64, max_page_size: u64) -> Result<Option<Self>, Status> {
+        if request.end_height > 0 && request.start_height > request.end_height {
+            return Err(Status::invalid_argument("Start height was greater than end height"));
+        }
+        let end = match request.end_height {
+            0 => tip,
+            end_height => cmp::min(end_height, tip),
+        };
+        let (start, offset) = match request.cursor.as_ref() {
+            Some(cursor) if cursor.height < request.start_height => {
+                return Err(Status::invalid_argument("Cursor is before the start height"));
+            },
+            Some(cursor) => (cursor.height, cursor.offset),
+            None => (request.start_height, 0),
+        };
+        if start > end {
+            return Ok(None);
+        }
+        let page_size = match u64::from(request.page_size) {
+            0 => max_page_size,
+            page_size => cmp::min(page_size, max_page_size),
+        };
+        Ok(Some(Self {
+            start,
+            offset,
+            end,
+            page_size,
+        }))
+    }
+
+    /// The heights in this page for queries that return a single item per height
+    pub fn heights(&self) -> RangeInclusive<u64> {
+        self.start..=cmp::min(self.end, self.start.saturating_add(self.page_size - 1))
+    }
+
+    /// The cursor for the page following one that ended with the item at `last_height`, or None if there are no
+    /// heights left
+    pub fn next_cursor(&self, last_height: u64) -> Option<PageCursor> {
+        if last_height >= self.end {
+            return None;
+        }
+        Some(PageCursor {
+            height: last_height + 1,
+            offset: 0,
+        })
+    }
+}
+
+/// The block fields that a `GetBlocksPage` request asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockFieldSelection {
+    pub header: bool,
+    pub inputs: bool,
+    pub outputs: bool,
+    pub kernels: bool,
+}
+
+impl BlockFieldSelection {
+    /// Selects the given `BlockField`s, or every field if none are given
+    pub fn from_fields(fields: &[i32]) -> Result<Self, Status> {
+        if fields.is_empty() {
+            return Ok(Self {
+                header: true,
+                inputs: true,
+                outputs: true,
+                kernels: true,
+            });
+        }
+        let mut selection = Self {
+            header: false,
+            inputs: false,
+            outputs: false,
+            kernels: false,
+        };
+        for field in fields {
+            match BlockField::from_i32(*field) {
+                Some(BlockField::Header) => selection.header = true,
+                Some(BlockField::Inputs) => selection.inputs = true,
+                Some(BlockField::Outputs) => selection.outputs = true,
+                Some(BlockField::Kernels) => selection.kernels = true,
+                None => return Err(Status::invalid_argument(format!("Unknown block field {}", field))),
+            }
+        }
+        Ok(selection)
+    }
+
+    /// Removes the fields that were not selected from `block`
+    pub fn apply(&self, block: &mut tari_rpc::Block) {
+        if !self.header {
+            block.header = None;
+        }
+        if let Some(body) = block.body.as_mut() {
+            if !self.inputs {
+                body.inputs.clear();
+            }
+            if !self.outputs {
+                body.outputs.clear();
+            }
+            if !self.kernels {
+                body.kernels.clear();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn request(
+        start_height: u64,
+        end_height: u64,
+        page_size: u32,
+        cursor: Option<PageCursor>,
+    ) -> HistoricalPageRequest {
+        HistoricalPageRequest {
+            start_height,
+            end_height,
+            page_size,
+            cursor,
+            fields: vec![],
+        }
+    }
+
+    #[test]
+    fn it_clamps_the_page_to_the_tip_and_limit() {
+        let page = PageRange::from_request(&request(5, 0, 0, None), 500, 100)
+            .unwrap()
+            .unwrap();
+        assert_eq!(page.heights(), 5..=104);
+        assert_eq!(page.next_cursor(104), Some(PageCursor { height: 105, offset: 0 }));
+
+        let page = PageRange::from_request(&request(450, 1000, 80, None), 500, 100)
+            .unwrap()
+            .unwrap();
+        assert_eq!(page.heights(), 450..=500);
+        assert_eq!(page.next_cursor(500), None);
+    }
+
+    #[test]
+    fn it_resumes_from_the_cursor() {
+        let cursor = PageCursor { height: 20, offset: 3 };
+        let page = PageRange::from_request(&request(10, 30, 5, Some(cursor)), 500, 100)
+            .unwrap()
+            .unwrap();
+        assert_eq!(page.start, 20);
+        assert_eq!(page.offset, 3);
+        assert_eq!(page.heights(), 20..=24);
+
+        let cursor = PageCursor { height: 31, offset: 0 };
+        assert!(PageRange::from_request(&request(10, 30, 5, Some(cursor)), 500, 100)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn it_rejects_invalid_ranges() {
+        assert!(PageRange::from_request(&request(10, 5, 0, None), 500, 100).is_err());
+        let cursor = PageCursor { height: 5, offset: 0 };
+        assert!(PageRange::from_request(&request(10, 20, 0, Some(cursor)), 500, 100).is_err());
+    }
+
+    #[test]
+    fn it_removes_unselected_block_fields() {
+        let selection = BlockFieldSelection::from_fields(&[]).unwrap();
+        assert!(selection.header && selection.inputs && selection.outputs && selection.kernels);
+
+        let selection = BlockFieldSelection::from_fields(&[BlockField::Kernels as i32]).unwrap();
+        let mut block = tari_rpc::Block {
+            header: Some(Default::default()),
+            body: Some(tari_rpc::AggregateBody {
+                inputs: vec![Default::default()],
+                outputs: vec![Default::default()],
+                kernels: vec![Default::default()],
+            }),
+        };
+        selection.apply(&mut block);
+        assert!(block.header.is_none());
+        let body = block.body.unwrap();
+        assert!(body.inputs.is_empty());
+        assert!(body.outputs.is_empty());
+        assert_eq!(body.kernels.len(), 1);
+
+        assert!(BlockFieldSelection::from_fields(&[99]).is_err());
+    }
+}
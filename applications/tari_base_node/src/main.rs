@@ -60,11 +60,11 @@
 /// `unban-peer` - Removes a ban for a peer
 /// `list-connections` - Lists active connections to this Base Node
 /// `list-headers` - Lists header information. Either the first header height and the last header height needs to be
-/// specified, or the amount of headers from the top `check-db` - Checks the blockchain database for missing blocks and
-/// headers `calc-timing` - Calculates the time average time taken to mine a given range of blocks
-/// `discover-peer` - Attempts to discover a peer on the network, a public key or emoji id needs to be specified
-/// `get-block` - Retrieves a block, the height of the block needs to be specified
-/// `get-mempool-stats` - Displays information about the mempool
+/// specified, or the amount of headers from the top `check-db` - Checks the blockchain database for MMR root
+/// mismatches and inconsistent indexes, `--fix` rewinds to the last consistent height `calc-timing` - Calculates the
+/// time average time taken to mine a given range of blocks `discover-peer` - Attempts to discover a peer on the
+/// network, a public key or emoji id needs to be specified `get-block` - Retrieves a block, the height of the block
+/// needs to be specified `get-mempool-stats` - Displays information about the mempool
 /// `get-mempool-state` - Displays state information for the mempool
 /// `whoami` - Displays identity information about this Base Node and it's wallet
 /// `quit` - Exits the Base Node
@@ -82,19 +82,20 @@ mod config;
 mod grpc;
 #[cfg(feature = "metrics")]
 mod metrics;
+mod peer_history;
 mod recovery;
 mod utils;
 
-use std::{env, process, str::FromStr, sync::Arc};
+use std::{process, str::FromStr, sync::Arc};
 
 use clap::Parser;
-use commands::{cli_loop::CliLoop, command::CommandContext};
+use commands::{batch, cli_loop::CliLoop, command::CommandContext};
 use futures::FutureExt;
 use log::*;
-use opentelemetry::{self, global, KeyValue};
-use tari_app_utilities::{consts, identity_management::setup_node_identity, utilities::setup_runtime};
+use tari_app_grpc::{authentication::BearerTokenInterceptor, tls::server_tls_config};
+use tari_app_utilities::{consts, identity_management::setup_node_identity, telemetry, utilities::setup_runtime};
 use tari_common::{
-    configuration::{bootstrap::ApplicationType, Network},
+    configuration::{bootstrap::ApplicationType, GrpcSecurityConfig, Network},
     exit_codes::{ExitCode, ExitError},
     initialize_logging,
     load_configuration,
@@ -110,7 +111,6 @@ use tari_libtor::tor::Tor;
 use tari_shutdown::{Shutdown, ShutdownSignal};
 use tokio::task;
 use tonic::transport::Server;
-use tracing_subscriber::{layer::SubscriberExt, Registry};
 
 use crate::{cli::Cli, config::ApplicationConfig};
 
@@ -162,6 +162,14 @@ fn main_inner() -> Result<(), ExitError> {
         return Ok(());
     }
 
+    if cli.migrations_dry_run {
+        return recovery::dry_run_migrations(&config.base_node);
+    }
+
+    if cli.compact_db {
+        return recovery::compact_db(&config.base_node);
+    }
+
     // The shutdown trigger for the system
     let shutdown = Shutdown::new();
 
@@ -185,7 +193,7 @@ fn main_inner() -> Result<(), ExitError> {
     runtime.block_on(run_node(node_identity, Arc::new(config), cli, shutdown))?;
 
     // Shutdown and send any traces
-    global::shutdown_tracer_provider();
+    telemetry::shutdown();
 
     Ok(())
 }
@@ -197,8 +205,10 @@ async fn run_node(
     cli: Cli,
     shutdown: Shutdown,
 ) -> Result<(), ExitError> {
-    if cli.tracing_enabled {
-        enable_tracing();
+    if cli.tracing_enabled || config.tracing.enabled {
+        if let Err(err) = telemetry::install("tari::base_node", &config.tracing) {
+            warn!(target: LOG_TARGET, "Failed to enable tracing: {}", err);
+        }
     }
 
     #[cfg(feature = "metrics")]
@@ -229,11 +239,22 @@ async fn run_node(
     if let Some(address) = config.base_node.grpc_address.clone() {
         // Go, GRPC, go go
         let grpc = crate::grpc::base_node_grpc_server::BaseNodeGrpcServer::from_base_node_context(&ctx);
-        task::spawn(run_grpc(grpc, address, shutdown.to_signal()));
+        task::spawn(run_grpc(
+            grpc,
+            address,
+            config.base_node.grpc_security.clone(),
+            shutdown.to_signal(),
+        ));
     }
 
     // Run, node, run!
-    let context = CommandContext::new(&ctx, shutdown);
+    let mut context = CommandContext::new(&ctx, shutdown).with_output_format(cli.output);
+    if let Some(path) = cli.commands_file {
+        let result = batch::run_commands_file(&mut context, &path, cli.continue_on_error).await;
+        context.shutdown.trigger();
+        ctx.run().await;
+        return result;
+    }
     let main_loop = CliLoop::new(context, cli.watch, cli.non_interactive_mode);
     if cli.non_interactive_mode {
         println!("Node started in non-interactive mode (pid = {})", process::id());
@@ -257,41 +278,31 @@ async fn run_node(
     Ok(())
 }
 
-fn enable_tracing() {
-    // To run:
-    // docker run -d -p6831:6831/udp -p6832:6832/udp -p16686:16686 -p14268:14268 jaegertracing/all-in-one:latest
-    // To view the UI after starting the container (default):
-    // http://localhost:16686
-    global::set_text_map_propagator(opentelemetry_jaeger::Propagator::new());
-    let tracer = opentelemetry_jaeger::new_pipeline()
-        .with_service_name("tari::base_node")
-        .with_tags(vec![
-            KeyValue::new("pid", process::id().to_string()),
-            KeyValue::new(
-                "current_exe",
-                env::current_exe().unwrap().to_str().unwrap_or_default().to_owned(),
-            ),
-            KeyValue::new("version", consts::APP_VERSION),
-        ])
-        .install_batch(opentelemetry::runtime::Tokio)
-        .unwrap();
-    let telemetry = tracing_opentelemetry::layer().with_tracer(tracer);
-    let subscriber = Registry::default().with(telemetry);
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("Tracing could not be set. Try running without `--tracing-enabled`");
-}
-
 /// Runs the gRPC server
 async fn run_grpc(
     grpc: crate::grpc::base_node_grpc_server::BaseNodeGrpcServer,
     grpc_address: Multiaddr,
+    security: GrpcSecurityConfig,
     interrupt_signal: ShutdownSignal,
 ) -> Result<(), anyhow::Error> {
     info!(target: LOG_TARGET, "Starting GRPC on {}", grpc_address);
 
     let grpc_address = multiaddr_to_socketaddr(&grpc_address)?;
-    Server::builder()
-        .add_service(tari_app_grpc::tari_rpc::base_node_server::BaseNodeServer::new(grpc))
+    if !grpc_address.ip().is_loopback() && security.api_token.is_none() {
+        warn!(
+            target: LOG_TARGET,
+            "GRPC is listening on {} without an API token. Set base_node.grpc_security.api_token to restrict access.",
+            grpc_address
+        );
+    }
+    let mut server = Server::builder();
+    if let Some(tls) = security.tls.as_ref() {
+        info!(target: LOG_TARGET, "GRPC TLS is enabled");
+        server = server.tls_config(server_tls_config(tls)?)?;
+    }
+    let interceptor = BearerTokenInterceptor::new(security.api_token.as_deref());
+    server
+        .add_service(tari_app_grpc::tari_rpc::base_node_server::BaseNodeServer::with_interceptor(grpc, interceptor))
         .serve_with_shutdown(grpc_address, interrupt_signal.map(|_| ()))
         .await
         .map_err(|err| {
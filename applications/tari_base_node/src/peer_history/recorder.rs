@@ -0,0 +1,181 @@
+// Copyright 2022, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::collections::HashMap;
+
+use log::*;
+use tari_comms::{
+    connectivity::{ConnectivityEvent, ConnectivityRequester},
+    peer_manager::NodeId,
+    PeerConnection,
+};
+use tari_shutdown::ShutdownSignal;
+use tari_utilities::epoch_time::EpochTime;
+use tokio::{
+    sync::{broadcast, mpsc},
+    task,
+};
+
+use super::{PeerHistoryStore, PeerSession};
+
+const LOG_TARGET: &str = "base_node::peer_history";
+const SESSION_UPDATE_BUFFER_SIZE: usize = 100;
+
+/// Listens for connectivity events and records the session with each peer in the [PeerHistoryStore]. The store is
+/// written to by a blocking worker so that LMDB calls do not block the async runtime.
+pub struct PeerHistoryRecorder {
+    store: PeerHistoryStore,
+    connectivity: ConnectivityRequester,
+    active_sessions: HashMap<NodeId, (PeerConnection, u64)>,
+}
+
+enum SessionUpdate {
+    Started(NodeId, PeerSession),
+    Ended(NodeId, u64, PeerSession),
+}
+
+impl PeerHistoryRecorder {
+    pub fn new(store: PeerHistoryStore, connectivity: ConnectivityRequester) -> Self {
+        Self {
+            store,
+            connectivity,
+            active_sessions: HashMap::new(),
+        }
+    }
+
+    pub fn spawn(self, shutdown_signal: ShutdownSignal) {
+        task::spawn(self.run(shutdown_signal));
+    }
+
+    async fn run(mut self, mut shutdown_signal: ShutdownSignal) {
+        let (updates_tx, updates_rx) = mpsc::channel(SESSION_UPDATE_BUFFER_SIZE);
+        let worker = task::spawn_blocking({
+            let store = self.store.clone();
+            move || write_session_updates(&store, updates_rx)
+        });
+
+        let mut connectivity_events = self.connectivity.get_event_subscription();
+        loop {
+            tokio::select! {
+                event = connectivity_events.recv() => {
+                    match event {
+                        Ok(event) => self.handle_connectivity_event(event, &updates_tx).await,
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!(target: LOG_TARGET, "Peer history recorder lagged {} connectivity event(s)", n);
+                        },
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                },
+                _ = shutdown_signal.wait() => break,
+            }
+        }
+
+        // Record the sessions that are still active so that the history reflects when the node went offline
+        let node_ids = self.active_sessions.keys().cloned().collect::<Vec<_>>();
+        for node_id in node_ids {
+            self.end_session(&node_id, &updates_tx).await;
+        }
+        // Wait for the worker to write the remaining updates
+        drop(updates_tx);
+        if let Err(err) = worker.await {
+            error!(target: LOG_TARGET, "Peer history worker failed: {}", err);
+        }
+        debug!(target: LOG_TARGET, "Peer history recorder has shut down");
+    }
+
+    async fn handle_connectivity_event(&mut self, event: ConnectivityEvent, updates: &mpsc::Sender<SessionUpdate>) {
+        #[allow(clippy::enum_glob_use)]
+        use ConnectivityEvent::*;
+        match event {
+            PeerConnected(conn) => {
+                let node_id = conn.peer_node_id().clone();
+                // A new connection may replace an existing connection without a disconnect event
+                self.end_session(&node_id, updates).await;
+                let connected_at = EpochTime::now().as_u64();
+                let session = to_session(&conn, connected_at, None);
+                send_update(updates, SessionUpdate::Started(node_id.clone(), session)).await;
+                self.active_sessions.insert(node_id, (conn, connected_at));
+            },
+            PeerDisconnected(node_id) => {
+                self.end_session(&node_id, updates).await;
+            },
+            _ => {},
+        }
+    }
+
+    async fn end_session(&mut self, node_id: &NodeId, updates: &mpsc::Sender<SessionUpdate>) {
+        if let Some((conn, connected_at)) = self.active_sessions.remove(node_id) {
+            let session = to_session(&conn, connected_at, Some(EpochTime::now().as_u64()));
+            send_update(updates, SessionUpdate::Ended(node_id.clone(), connected_at, session)).await;
+        }
+    }
+}
+
+async fn send_update(updates: &mpsc::Sender<SessionUpdate>, update: SessionUpdate) {
+    if updates.send(update).await.is_err() {
+        error!(
+            target: LOG_TARGET,
+            "Peer history worker has stopped. Session not recorded."
+        );
+    }
+}
+
+/// Writes session updates to the store in the order that they were received until the sender is dropped
+fn write_session_updates(store: &PeerHistoryStore, mut updates: mpsc::Receiver<SessionUpdate>) {
+    while let Some(update) = updates.blocking_recv() {
+        match update {
+            SessionUpdate::Started(node_id, session) => {
+                if let Err(err) = store.insert_session(&node_id, session) {
+                    error!(
+                        target: LOG_TARGET,
+                        "Failed to record session for peer {}: {}", node_id, err
+                    );
+                }
+            },
+            SessionUpdate::Ended(node_id, connected_at, session) => {
+                if let Err(err) = store.end_session(&node_id, connected_at, session) {
+                    error!(
+                        target: LOG_TARGET,
+                        "Failed to record end of session for peer {}: {}", node_id, err
+                    );
+                }
+            },
+        }
+    }
+}
+
+fn to_session(conn: &PeerConnection, connected_at: u64, disconnected_at: Option<u64>) -> PeerSession {
+    let stats = conn.connection_stats();
+    PeerSession {
+        address: conn.address().to_string(),
+        is_inbound: conn.direction().is_inbound(),
+        connected_at,
+        disconnected_at,
+        bytes_read: stats.bytes_read(),
+        bytes_written: stats.bytes_written(),
+        protocols: stats
+            .protocols()
+            .iter()
+            .map(|p| String::from_utf8_lossy(p).to_string())
+            .collect(),
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright 2022, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    collections::{BinaryHeap, VecDeque},
+    fs,
+    io,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use tari_comms::peer_manager::NodeId;
+use tari_storage::{
+    lmdb_store::{db, LMDBBuilder, LMDBConfig, LMDBDatabase, LMDBError},
+    IterationResult,
+};
+use thiserror::Error;
+
+use crate::config::PeerHistoryConfig;
+
+const PEER_HISTORY_DB_NAME: &str = "peer_history";
+/// When the store is full, this fraction of `max_peers` is removed in addition to the peers that must be removed, so
+/// that the database is scanned once for every batch of new peers rather than for every new peer.
+const PRUNE_BATCH_DIVISOR: usize = 10;
+
+#[derive(Debug, Error)]
+pub enum PeerHistoryError {
+    #[error("Peer history database error: {0}")]
+    DatabaseError(#[from] LMDBError),
+    #[error("Failed to create peer history database directory: {0}")]
+    IoError(#[from] io::Error),
+    #[error("Failed to encode peer history key: {0}")]
+    KeyEncodingError(#[from] bincode::Error),
+    #[error("Peer history database was not created")]
+    DatabaseNotFound,
+}
+
+/// A single connection session with a peer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerSession {
+    pub address: String,
+    pub is_inbound: bool,
+    /// Unix timestamp of when the connection was established
+    pub connected_at: u64,
+    /// Unix timestamp of when the connection was closed. This is None if the session is still active or if the node
+    /// shut down before the disconnect was recorded.
+    pub disconnected_at: Option<u64>,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    pub protocols: Vec<String>,
+}
+
+impl PeerSession {
+    pub fn duration_secs(&self) -> Option<u64> {
+        self.disconnected_at
+            .map(|disconnected_at| disconnected_at.saturating_sub(self.connected_at))
+    }
+}
+
+/// The most recent sessions with a peer, oldest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PeerSessionHistory {
+    pub sessions: VecDeque<PeerSession>,
+}
+
+impl PeerSessionHistory {
+    /// Returns the time of the most recent activity with the peer
+    pub fn last_seen(&self) -> u64 {
+        self.sessions
+            .back()
+            .map(|s| s.disconnected_at.unwrap_or(s.connected_at))
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of sessions that started at or after `since`
+    pub fn num_sessions_since(&self, since: u64) -> usize {
+        self.sessions.iter().filter(|s| s.connected_at >= since).count()
+    }
+
+    fn push(&mut self, session: PeerSession, max_sessions: usize) {
+        self.sessions.push_back(session);
+        while self.sessions.len() > max_sessions {
+            self.sessions.pop_front();
+        }
+    }
+
+    fn end_session(&mut self, connected_at: u64, ended: PeerSession) -> bool {
+        match self
+            .sessions
+            .iter_mut()
+            .rev()
+            .find(|s| s.connected_at == connected_at && s.disconnected_at.is_none())
+        {
+            Some(session) => {
+                *session = ended;
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+/// LMDB-backed store of [PeerSessionHistory] keyed by node id. The number of sessions kept per peer and the number of
+/// peers are bounded, the least recently seen peers are removed first.
+#[derive(Clone)]
+pub struct PeerHistoryStore {
+    db: LMDBDatabase,
+    max_peers: usize,
+    max_sessions_per_peer: usize,
+}
+
+impl PeerHistoryStore {
+    pub fn open<P: AsRef<Path>>(path: P, config: &PeerHistoryConfig) -> Result<Self, PeerHistoryError> {
+        fs::create_dir_all(path.as_ref())?;
+        let store = LMDBBuilder::new()
+            .set_path(path)
+            .set_env_config(LMDBConfig::default())
+            .set_max_number_of_databases(1)
+            .add_database(PEER_HISTORY_DB_NAME, db::CREATE)
+            .build()?;
+        let db = store
+            .get_handle(PEER_HISTORY_DB_NAME)
+            .ok_or(PeerHistoryError::DatabaseNotFound)?;
+        Ok(Self {
+            db,
+            max_peers: config.max_peers,
+            max_sessions_per_peer: config.max_sessions_per_peer,
+        })
+    }
+
+    /// Returns the session history for the peer, if any sessions have been recorded
+    pub fn get_history(&self, node_id: &NodeId) -> Result<Option<PeerSessionHistory>, PeerHistoryError> {
+        let history = self.db.get(key(node_id)?.as_slice())?;
+        Ok(history)
+    }
+
+    /// Returns the session history of all peers that have had at least `min_sessions` sessions since `since`. This can
+    /// be used to find flapping peers.
+    pub fn find_peers_with_sessions_since(
+        &self,
+        since: u64,
+        min_sessions: usize,
+    ) -> Result<Vec<(NodeId, PeerSessionHistory)>, PeerHistoryError> {
+        let mut peers = Vec::new();
+        self.db.for_each::<NodeId, PeerSessionHistory, _>(|result| {
+            if let Ok((node_id, history)) = result {
+                if history.num_sessions_since(since) >= min_sessions {
+                    peers.push((node_id, history));
+                }
+            }
+            IterationResult::Continue
+        })?;
+        Ok(peers)
+    }
+
+    /// Records the start of a new session with the peer
+    pub fn insert_session(&self, node_id: &NodeId, session: PeerSession) -> Result<(), PeerHistoryError> {
+        let history = self.get_history(node_id)?;
+        if history.is_none() {
+            self.prune_peers()?;
+        }
+        let mut history = history.unwrap_or_default();
+        history.push(session, self.max_sessions_per_peer);
+        self.db.insert(key(node_id)?.as_slice(), &history)?;
+        Ok(())
+    }
+
+    /// Updates the session with the peer that started at `connected_at` with the final session details
+    pub fn end_session(
+        &self,
+        node_id: &NodeId,
+        connected_at: u64,
+        session: PeerSession,
+    ) -> Result<(), PeerHistoryError> {
+        let mut history = self.get_history(node_id)?.unwrap_or_default();
+        if !history.end_session(connected_at, session.clone()) {
+            // The session was pruned or never recorded
+            history.push(session, self.max_sessions_per_peer);
+        }
+        self.db.insert(key(node_id)?.as_slice(), &history)?;
+        Ok(())
+    }
+
+    /// Removes a batch of the least recently seen peers so that there is space for new peers
+    fn prune_peers(&self) -> Result<(), PeerHistoryError> {
+        let num_peers = self.db.len()?;
+        if num_peers < self.max_peers {
+            return Ok(());
+        }
+        let num_to_remove = num_peers + 1 - self.max_peers + self.max_peers / PRUNE_BATCH_DIVISOR;
+        // Only the least recently seen peers are kept in memory while iterating
+        let mut oldest = BinaryHeap::with_capacity(num_to_remove + 1);
+        self.db.for_each::<NodeId, PeerSessionHistory, _>(|result| {
+            if let Ok((node_id, history)) = result {
+                oldest.push((history.last_seen(), node_id));
+                if oldest.len() > num_to_remove {
+                    oldest.pop();
+                }
+            }
+            IterationResult::Continue
+        })?;
+        for (_, node_id) in oldest {
+            self.db.remove(key(&node_id)?.as_slice())?;
+        }
+        Ok(())
+    }
+}
+
+/// Keys are bincode encoded so that they can be decoded when iterating over the database
+fn key(node_id: &NodeId) -> Result<Vec<u8>, bincode::Error> {
+    bincode::serialize(node_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session(connected_at: u64) -> PeerSession {
+        PeerSession {
+            address: "/ip4/127.0.0.1/tcp/18189".to_string(),
+            is_inbound: true,
+            connected_at,
+            disconnected_at: None,
+            bytes_read: 0,
+            bytes_written: 0,
+            protocols: vec![],
+        }
+    }
+
+    #[test]
+    fn it_bounds_the_number_of_sessions() {
+        let mut history = PeerSessionHistory::default();
+        for i in 0..5 {
+            history.push(session(i), 3);
+        }
+        assert_eq!(history.sessions.len(), 3);
+        assert_eq!(history.sessions.front().unwrap().connected_at, 2);
+        assert_eq!(history.num_sessions_since(3), 2);
+    }
+
+    #[test]
+    fn it_ends_the_matching_session() {
+        let mut history = PeerSessionHistory::default();
+        history.push(session(10), 10);
+        history.push(session(20), 10);
+        let ended = PeerSession {
+            disconnected_at: Some(25),
+            bytes_read: 100,
+            ..session(20)
+        };
+        assert!(history.end_session(20, ended.clone()));
+        assert_eq!(history.sessions.back().unwrap(), &ended);
+        assert_eq!(history.last_seen(), 25);
+        assert!(history.sessions.front().unwrap().disconnected_at.is_none());
+        assert!(!history.end_session(30, ended));
+    }
+}
@@ -37,8 +37,10 @@ use tari_common::{
 use tari_core::{
     chain_storage::{
         async_db::AsyncBlockchainDb,
+        compact_lmdb_database,
         create_lmdb_database,
         create_recovery_lmdb_database,
+        dry_run_lmdb_migrations,
         BlockchainBackend,
         BlockchainDatabase,
         BlockchainDatabaseConfig,
@@ -72,6 +74,35 @@ pub fn initiate_recover_db(config: &BaseNodeConfig) -> Result<(), ExitError> {
     Ok(())
 }
 
+/// Reports the schema migrations that would be applied to the chain database, without changing it
+pub fn dry_run_migrations(config: &BaseNodeConfig) -> Result<(), ExitError> {
+    let report = match &config.db_type {
+        DatabaseType::Lmdb => dry_run_lmdb_migrations(&config.lmdb_path, config.lmdb.clone(), |progress| {
+            println!("{}", progress)
+        }),
+    }
+    .map_err(|err| {
+        error!(target: LOG_TARGET, "{}", err);
+        ExitError::new(ExitCode::DatabaseError, &err)
+    })?;
+    println!("{}", report);
+    Ok(())
+}
+
+/// Copies the chain database into a fresh environment to reclaim free space. The node must not be running.
+pub fn compact_db(config: &BaseNodeConfig) -> Result<(), ExitError> {
+    println!("Compacting the blockchain database. This may take a while...");
+    let report = match &config.db_type {
+        DatabaseType::Lmdb => compact_lmdb_database(&config.lmdb_path, config.lmdb.clone()),
+    }
+    .map_err(|err| {
+        error!(target: LOG_TARGET, "{}", err);
+        ExitError::new(ExitCode::DatabaseError, &err)
+    })?;
+    println!("{}", report);
+    Ok(())
+}
+
 pub async fn run_recovery(node_config: &BaseNodeConfig) -> Result<(), anyhow::Error> {
     println!("Starting recovery mode");
     let (temp_db, main_db, temp_path) = match &node_config.db_type {
@@ -89,7 +120,7 @@ pub async fn run_recovery(node_config: &BaseNodeConfig) -> Result<(), anyhow::Er
             (temp, backend, temp_path)
         },
     };
-    let rules = ConsensusManager::builder(node_config.network).build();
+    let rules = ConsensusManager::builder(node_config.network).build()?;
     let factories = CryptoFactories::default();
     let randomx_factory = RandomXFactory::new(node_config.max_randomx_vms);
     let validators = Validators::new(
@@ -128,7 +159,7 @@ async fn do_recovery<D: BlockchainBackend + 'static>(
     source_backend: D,
 ) -> Result<(), anyhow::Error> {
     // We dont care about the values, here, so we just use mock validators, and a mainnet CM.
-    let rules = ConsensusManager::builder(Network::LocalNet).build();
+    let rules = ConsensusManager::builder(Network::LocalNet).build()?;
     let validators = Validators::new(
         MockValidator::new(true),
         MockValidator::new(true),
@@ -57,6 +57,8 @@ impl GrpcValidatorNodeClient {
     let req = grpc::InvokeReadMethodRequest {
       asset_public_key: Vec::from(asset_public_key.as_bytes()),
       template_id,
+      // Use the current version of the template
+      template_version: 0,
       method,
       args,
     };
@@ -88,6 +90,8 @@ impl GrpcValidatorNodeClient {
     let req = grpc::InvokeMethodRequest {
       asset_public_key: Vec::from(asset_public_key.as_bytes()),
       template_id,
+      // Use the current version of the template
+      template_version: 0,
       method,
       args,
     };
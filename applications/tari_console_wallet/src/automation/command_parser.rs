@@ -27,14 +27,17 @@ use std::{
     str::FromStr,
 };
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use tari_app_utilities::utilities::{parse_emoji_id_or_public_key, parse_hash};
-use tari_common_types::types::PublicKey;
+use tari_common_types::types::{PrivateKey, PublicKey};
 use tari_comms::multiaddr::Multiaddr;
 use tari_core::transactions::tari_amount::MicroTari;
 use tari_utilities::hex::Hex;
 
-use crate::automation::{commands::WalletCommand, error::ParseError};
+use crate::automation::{
+    commands::{WalletCommand, EXPORT_TRANSACTION_STATUSES},
+    error::ParseError,
+};
 
 #[derive(Debug, Clone)]
 pub struct ParsedCommand {
@@ -52,10 +55,17 @@ impl Display for ParsedCommand {
             SendOneSided => "send-one-sided",
             MakeItRain => "make-it-rain",
             CoinSplit => "coin-split",
+            CoinSplitEven => "coin-split-even",
+            CoinJoin => "coin-join",
+            Tx => "tx",
+            WatchOnly => "watch-only",
+            Contacts => "contacts",
+            ScheduledPayment => "scheduled-payment",
             DiscoverPeer => "discover-peer",
             Whois => "whois",
             ExportUtxos => "export-utxos",
             ExportSpentUtxos => "export-spent-utxos",
+            ExportTransactions => "export-transactions",
             CountUtxos => "count-utxos",
             SetBaseNode => "set-base-node",
             SetCustomBaseNode => "set-custom-base-node",
@@ -63,11 +73,19 @@ impl Display for ParsedCommand {
             InitShaAtomicSwap => "init-sha-atomic-swap",
             FinaliseShaAtomicSwap => "finalise-sha-atomic-swap",
             ClaimShaAtomicSwapRefund => "claim-sha-atomic-swap-refund",
+            InitHtlc => "init-htlc",
+            FetchHtlcPreImage => "fetch-htlc-pre-image",
             RegisterAsset => "register-asset",
             MintTokens => "mint-tokens",
             CreateInitialCheckpoint => "create-initial-checkpoint",
             CreateCommitteeDefinition => "create-committee-definition",
             RevalidateWalletDb => "revalidate-wallet-db",
+            ExportUnsignedTx => "export-unsigned-tx",
+            SignOfflineTx => "sign-offline-tx",
+            BroadcastSignedTx => "broadcast-signed-tx",
+            SendToVault => "send-to-vault",
+            ListVaults => "list-vaults",
+            RecoverVault => "recover-vault",
         };
 
         let args = self
@@ -94,6 +112,8 @@ pub enum ParsedArgument {
     Address(Multiaddr),
     Negotiated(bool),
     Hash(Vec<u8>),
+    /// A contact from the address book, given on the command line as `@alias`
+    ContactAlias(String),
 }
 
 impl Display for ParsedArgument {
@@ -112,6 +132,7 @@ impl Display for ParsedArgument {
             Address(v) => write!(f, "{}", v),
             Negotiated(v) => write!(f, "{}", v),
             Hash(v) => write!(f, "{}", v.to_hex()),
+            ContactAlias(v) => write!(f, "@{}", v),
         }
     }
 }
@@ -131,10 +152,17 @@ pub fn parse_command(command: &str) -> Result<ParsedCommand, ParseError> {
         SendOneSided => parse_send_tari(args)?,
         MakeItRain => parse_make_it_rain(args)?,
         CoinSplit => parse_coin_split(args)?,
+        CoinSplitEven => parse_coin_split_even(args)?,
+        CoinJoin => parse_coin_join(args)?,
+        Tx => parse_tx(args)?,
+        WatchOnly => parse_watch_only(args)?,
+        Contacts => parse_contacts(args)?,
+        ScheduledPayment => parse_scheduled_payment(args)?,
         DiscoverPeer => parse_public_key(args)?,
         Whois => parse_whois(args)?,
         ExportUtxos => parse_export_utxos(args)?,
         ExportSpentUtxos => parse_export_spent_utxos(args)?,
+        ExportTransactions => parse_export_transactions(args)?,
         CountUtxos => Vec::new(),
         SetBaseNode => parse_public_key_and_address(args)?,
         SetCustomBaseNode => parse_public_key_and_address(args)?,
@@ -142,12 +170,22 @@ pub fn parse_command(command: &str) -> Result<ParsedCommand, ParseError> {
         InitShaAtomicSwap => parse_init_sha_atomic_swap(args)?,
         FinaliseShaAtomicSwap => parse_finalise_sha_atomic_swap(args)?,
         ClaimShaAtomicSwapRefund => parse_claim_htlc_refund_refund(args)?,
+        InitHtlc => parse_init_htlc(args)?,
+        FetchHtlcPreImage => parse_claim_htlc_refund_refund(args)?,
         RegisterAsset => parser_builder(args).text().build()?,
         // mint-tokens pub_key nft_id1 nft_id2
         MintTokens => parser_builder(args).pub_key().text_array().build()?,
         CreateInitialCheckpoint => parser_builder(args).pub_key().text().build()?,
         CreateCommitteeDefinition => parser_builder(args).pub_key().pub_key_array().build()?,
         RevalidateWalletDb => Vec::new(),
+        ExportUnsignedTx => parse_export_unsigned_tx(args)?,
+        // sign-offline-tx unsigned_file signed_file
+        SignOfflineTx => parser_builder(args).text().text().build()?,
+        // broadcast-signed-tx unsigned_file signed_file
+        BroadcastSignedTx => parser_builder(args).text().text().build()?,
+        SendToVault => parse_send_to_vault(args)?,
+        ListVaults => Vec::new(),
+        RecoverVault => parse_recover_vault(args)?,
     };
 
     Ok(ParsedCommand { command, args })
@@ -319,6 +357,90 @@ fn parse_claim_htlc_refund_refund(mut args: SplitWhitespace) -> Result<Vec<Parse
     Ok(parsed_args)
 }
 
+fn parse_init_htlc(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    let amount = MicroTari::from_str(amount)?;
+    parsed_args.push(ParsedArgument::Amount(amount));
+
+    // public key/emoji id
+    let pubkey = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("public key or emoji id".to_string()))?;
+    let pubkey = parse_emoji_id_or_public_key(pubkey).ok_or(ParseError::PublicKey)?;
+    parsed_args.push(ParsedArgument::PublicKey(pubkey));
+
+    // pre-image hash
+    let hash = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("pre-image hash".to_string()))?;
+    let hash = parse_hash(hash).ok_or(ParseError::Hash)?;
+    parsed_args.push(ParsedArgument::Hash(hash));
+
+    // lock height
+    let lock_height = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("lock height".to_string()))?;
+    let lock_height = lock_height.parse::<u64>()?;
+    parsed_args.push(ParsedArgument::Int(lock_height));
+
+    // message
+    let message = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(message));
+
+    Ok(parsed_args)
+}
+
+fn parse_send_to_vault(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    let amount = MicroTari::from_str(amount)?;
+    parsed_args.push(ParsedArgument::Amount(amount));
+
+    // unlock height
+    let unlock_height = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("unlock height".to_string()))?;
+    let unlock_height = unlock_height.parse::<u64>()?;
+    parsed_args.push(ParsedArgument::Int(unlock_height));
+
+    // recovery public key/emoji id
+    let pubkey = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("recovery public key or emoji id".to_string()))?;
+    let pubkey = parse_emoji_id_or_public_key(pubkey).ok_or(ParseError::PublicKey)?;
+    parsed_args.push(ParsedArgument::PublicKey(pubkey));
+
+    // message
+    let message = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(message));
+
+    Ok(parsed_args)
+}
+
+fn parse_recover_vault(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+    // hash
+    let hash = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("Output hash".to_string()))?;
+    let hash = parse_hash(hash).ok_or(ParseError::Hash)?;
+    parsed_args.push(ParsedArgument::Hash(hash));
+
+    // recovery private key
+    let key = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("recovery private key".to_string()))?;
+    PrivateKey::from_hex(key).map_err(|_| ParseError::Invalid("recovery private key".to_string()))?;
+    parsed_args.push(ParsedArgument::Text(key.to_string()));
+
+    Ok(parsed_args)
+}
+
 fn parse_make_it_rain(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
     let mut parsed_args = Vec::new();
 
@@ -402,6 +524,35 @@ fn parse_send_tari(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, Par
     let amount = MicroTari::from_str(amount)?;
     parsed_args.push(ParsedArgument::Amount(amount));
 
+    // public key/emoji id/@contact
+    parsed_args.push(parse_recipient(args.next())?);
+
+    // message
+    let message = args.collect::<Vec<&str>>().join(" ");
+    parsed_args.push(ParsedArgument::Text(message));
+
+    Ok(parsed_args)
+}
+
+fn parse_recipient(recipient: Option<&str>) -> Result<ParsedArgument, ParseError> {
+    let recipient = recipient.ok_or_else(|| ParseError::Empty("public key, emoji id or @contact".to_string()))?;
+    match recipient.strip_prefix('@') {
+        Some(alias) if !alias.is_empty() => Ok(ParsedArgument::ContactAlias(alias.to_string())),
+        _ => {
+            let pubkey = parse_emoji_id_or_public_key(recipient).ok_or(ParseError::PublicKey)?;
+            Ok(ParsedArgument::PublicKey(pubkey))
+        },
+    }
+}
+
+fn parse_export_unsigned_tx(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+
+    // amount
+    let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+    let amount = MicroTari::from_str(amount)?;
+    parsed_args.push(ParsedArgument::Amount(amount));
+
     // public key/emoji id
     let pubkey = args
         .next()
@@ -409,6 +560,10 @@ fn parse_send_tari(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, Par
     let pubkey = parse_emoji_id_or_public_key(pubkey).ok_or(ParseError::PublicKey)?;
     parsed_args.push(ParsedArgument::PublicKey(pubkey));
 
+    // file name
+    let file_name = args.next().ok_or_else(|| ParseError::Empty("file name".to_string()))?;
+    parsed_args.push(ParsedArgument::Text(file_name.to_string()));
+
     // message
     let message = args.collect::<Vec<&str>>().join(" ");
     parsed_args.push(ParsedArgument::Text(message));
@@ -464,6 +619,76 @@ fn parse_export_spent_utxos(mut args: SplitWhitespace) -> Result<Vec<ParsedArgum
     Ok(parsed_args)
 }
 
+/// Parses the optional `--format`, `--from`, `--to`, `--status` and `--output` flags of `export-transactions` into
+/// pairs of the flag and its value
+fn parse_export_transactions(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = Vec::new();
+    let mut format = "csv";
+    let mut output = None;
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| ParseError::Empty(format!("value for {}", flag)))?;
+        let parsed_value = match flag {
+            "--format" => match value {
+                "csv" | "json" => {
+                    format = value;
+                    continue;
+                },
+                _ => {
+                    return Err(ParseError::Invalid(format!(
+                        "export format must be csv or json, not {}",
+                        value
+                    )))
+                },
+            },
+            "--from" => ParsedArgument::Date(parse_date(value, false)?),
+            "--to" => ParsedArgument::Date(parse_date(value, true)?),
+            "--status" => {
+                if !EXPORT_TRANSACTION_STATUSES.contains(&value) {
+                    return Err(ParseError::Invalid(format!(
+                        "unknown transaction status {}, expected one of {}",
+                        value,
+                        EXPORT_TRANSACTION_STATUSES.join(", ")
+                    )));
+                }
+                ParsedArgument::Text(value.to_string())
+            },
+            "--output" => {
+                output = Some(value.to_string());
+                continue;
+            },
+            _ => return Err(ParseError::WalletCommand(format!("export-transactions {}", flag))),
+        };
+        parsed_args.push(ParsedArgument::Text(flag.to_string()));
+        parsed_args.push(parsed_value);
+    }
+
+    parsed_args.push(ParsedArgument::Text("--format".to_string()));
+    parsed_args.push(ParsedArgument::Text(format.to_string()));
+    parsed_args.push(ParsedArgument::Text("--output".to_string()));
+    parsed_args.push(ParsedArgument::Text(
+        output.unwrap_or_else(|| format!("transactions.{}", format)),
+    ));
+    Ok(parsed_args)
+}
+
+/// Parses an RFC 3339 time, or a `YYYY-MM-DD` date taken as the start of the day, or the end of the day if
+/// `end_of_day` is set
+fn parse_date(date: &str, end_of_day: bool) -> Result<DateTime<Utc>, ParseError> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(date) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")?;
+    let time = if end_of_day {
+        date.and_hms_nano(23, 59, 59, 999_999_999)
+    } else {
+        date.and_hms(0, 0, 0)
+    };
+    Ok(DateTime::from_utc(time, Utc))
+}
+
 fn parse_coin_split(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
     let mut parsed_args = vec![];
 
@@ -483,14 +708,187 @@ fn parse_coin_split(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, Pa
     Ok(parsed_args)
 }
 
+fn parse_coin_split_even(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = vec![];
+
+    let num_splits = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("split_count".to_string()))?;
+    let num_splits = num_splits.parse::<u64>()?;
+    parsed_args.push(ParsedArgument::Int(num_splits));
+    let fee_per_gram = MicroTari::from_str(args.next().unwrap_or("5"))?;
+    parsed_args.push(ParsedArgument::Amount(fee_per_gram));
+    Ok(parsed_args)
+}
+
+fn parse_coin_join(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = vec![];
+
+    let dust_threshold = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("dust_threshold".to_string()))?;
+    let dust_threshold = MicroTari::from_str(dust_threshold)?;
+    parsed_args.push(ParsedArgument::Amount(dust_threshold));
+    let max_inputs = args.next().unwrap_or("100").parse::<u64>()?;
+    parsed_args.push(ParsedArgument::Int(max_inputs));
+    let fee_per_gram = MicroTari::from_str(args.next().unwrap_or("5"))?;
+    parsed_args.push(ParsedArgument::Amount(fee_per_gram));
+    Ok(parsed_args)
+}
+
+fn parse_tx(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let mut parsed_args = vec![];
+
+    let subcommand = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("tx subcommand".to_string()))?;
+    match subcommand {
+        "bump-fee" => {
+            parsed_args.push(ParsedArgument::Text(subcommand.to_string()));
+            let tx_id = args.next().ok_or_else(|| ParseError::Empty("tx_id".to_string()))?;
+            parsed_args.push(ParsedArgument::Int(tx_id.parse::<u64>()?));
+            let fee_per_gram = args
+                .next()
+                .ok_or_else(|| ParseError::Empty("new_fee_per_gram".to_string()))?;
+            parsed_args.push(ParsedArgument::Amount(MicroTari::from_str(fee_per_gram)?));
+        },
+        _ => return Err(ParseError::WalletCommand(format!("tx {}", subcommand))),
+    }
+    Ok(parsed_args)
+}
+
+fn parse_watch_only(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let subcommand = args
+        .next()
+        .ok_or_else(|| ParseError::Empty("watch-only subcommand".to_string()))?;
+    let mut parsed_args = vec![ParsedArgument::Text(subcommand.to_string())];
+    match subcommand {
+        // watch-only export [label]
+        "export" => {
+            let label = args.next().unwrap_or("watch-only");
+            parsed_args.push(ParsedArgument::Text(label.to_string()));
+        },
+        // watch-only import label spend_public_key rewind_public_key rewind_blinding_public_key
+        "import" => parsed_args.extend(parser_builder(args).text().pub_key().pub_key().pub_key().build()?),
+        // watch-only remove spend_public_key
+        "remove" => parsed_args.extend(parser_builder(args).pub_key().build()?),
+        "balance" => {},
+        _ => return Err(ParseError::WalletCommand(format!("watch-only {}", subcommand))),
+    }
+    Ok(parsed_args)
+}
+
+fn parse_contacts(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let subcommand = args.next().unwrap_or("list");
+    let mut parsed_args = vec![ParsedArgument::Text(subcommand.to_string())];
+    match subcommand {
+        "list" => {},
+        // contacts add alias public_key/emoji_id
+        "add" => parsed_args.extend(parser_builder(args).text().pub_key().build()?),
+        // contacts rename alias new_alias
+        "rename" => parsed_args.extend(parser_builder(args).text().text().build()?),
+        // contacts remove alias
+        "remove" => parsed_args.extend(parser_builder(args).text().build()?),
+        _ => return Err(ParseError::WalletCommand(format!("contacts {}", subcommand))),
+    }
+    Ok(parsed_args)
+}
+
+fn parse_scheduled_payment(mut args: SplitWhitespace) -> Result<Vec<ParsedArgument>, ParseError> {
+    let subcommand = args.next().unwrap_or("list");
+    let mut parsed_args = vec![ParsedArgument::Text(subcommand.to_string())];
+    match subcommand {
+        "list" => {},
+        // scheduled-payment cancel payment_id
+        "cancel" => {
+            let payment_id = args.next().ok_or_else(|| ParseError::Empty("payment id".to_string()))?;
+            parsed_args.push(ParsedArgument::Int(payment_id.parse::<u64>()?));
+        },
+        // scheduled-payment add standard|one-sided amount recipient start_time interval|once [count|forever] [message]
+        "add" => {
+            let payment_type = args
+                .next()
+                .ok_or_else(|| ParseError::Empty("payment type".to_string()))?;
+            if payment_type != "standard" && payment_type != "one-sided" {
+                return Err(ParseError::Invalid(format!(
+                    "payment type must be standard or one-sided, not {}",
+                    payment_type
+                )));
+            }
+            parsed_args.push(ParsedArgument::Text(payment_type.to_string()));
+
+            let amount = args.next().ok_or_else(|| ParseError::Empty("amount".to_string()))?;
+            parsed_args.push(ParsedArgument::Amount(MicroTari::from_str(amount)?));
+            parsed_args.push(parse_recipient(args.next())?);
+
+            // start time utc or 'now'
+            let start_time = args.next().ok_or_else(|| ParseError::Empty("start time".to_string()))?;
+            let start_time = if start_time == "now" {
+                Utc::now()
+            } else {
+                DateTime::parse_from_rfc3339(start_time)?.with_timezone(&Utc)
+            };
+            parsed_args.push(ParsedArgument::Date(start_time));
+
+            // The interval in seconds, and the number of payments, where 0 means a single payment and no limit
+            // respectively
+            let interval = args.next().ok_or_else(|| ParseError::Empty("interval".to_string()))?;
+            if interval == "once" {
+                parsed_args.push(ParsedArgument::Int(0));
+                parsed_args.push(ParsedArgument::Int(1));
+            } else {
+                parsed_args.push(ParsedArgument::Int(parse_interval(interval)?));
+                let count = args
+                    .next()
+                    .ok_or_else(|| ParseError::Empty("number of payments".to_string()))?;
+                let count = match count {
+                    "forever" => 0,
+                    count => match count.parse::<u64>()? {
+                        0 => return Err(ParseError::Invalid("number of payments must be at least 1".to_string())),
+                        count => count,
+                    },
+                };
+                parsed_args.push(ParsedArgument::Int(count));
+            }
+
+            // message
+            let message = args.collect::<Vec<&str>>().join(" ");
+            parsed_args.push(ParsedArgument::Text(message));
+        },
+        _ => return Err(ParseError::WalletCommand(format!("scheduled-payment {}", subcommand))),
+    }
+    Ok(parsed_args)
+}
+
+/// Parses an interval such as `90s`, `30m`, `12h`, `1d` or `2w` into seconds
+fn parse_interval(interval: &str) -> Result<u64, ParseError> {
+    let split = interval
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| ParseError::Invalid(format!("interval {} is missing a unit (s, m, h, d or w)", interval)))?;
+    let (value, unit) = interval.split_at(split);
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(ParseError::Invalid(format!("unknown interval unit {}", unit))),
+    };
+    match value.parse::<u64>()?.checked_mul(multiplier) {
+        Some(0) | None => Err(ParseError::Invalid(format!("invalid interval {}", interval))),
+        Some(seconds) => Ok(seconds),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
 
     use rand::rngs::OsRng;
-    use tari_common_types::types::PublicKey;
+    use tari_common_types::{emoji::EmojiId, types::PublicKey};
     use tari_core::transactions::tari_amount::MicroTari;
     use tari_crypto::keys::PublicKey as PublicKeyTrait;
+    use tari_utilities::hex::Hex;
 
     use crate::automation::{
         command_parser::{parse_command, ParsedArgument},
@@ -536,11 +934,11 @@ mod test {
             panic!("Parsed message is not the same as provided.");
         }
 
-        let command_str = format!("send-tari 999ut {}", public_key);
+        let command_str = format!("send-tari 999uT {}", public_key);
         let parsed = parse_command(&command_str).unwrap();
 
         if let ParsedArgument::Amount(amount) = parsed.args[0].clone() {
-            assert_eq!(amount, MicroTari::from_str("999ut").unwrap());
+            assert_eq!(amount, MicroTari::from_str("999uT").unwrap());
         } else {
             panic!("Parsed MicroTari amount not the same as provided.");
         }
@@ -625,4 +1023,210 @@ mod test {
             },
         }
     }
+
+    #[test]
+    fn test_parse_offline_signing_commands() {
+        let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+
+        let command_str = format!("export-unsigned-tx 999T {} unsigned.txt cold payment", public_key);
+        let parsed = parse_command(&command_str).unwrap();
+        assert!(matches!(parsed.args[0], ParsedArgument::Amount(a) if a == MicroTari::from_str("999T").unwrap()));
+        assert!(matches!(&parsed.args[1], ParsedArgument::PublicKey(k) if *k == public_key));
+        assert!(matches!(&parsed.args[2], ParsedArgument::Text(f) if f == "unsigned.txt"));
+        assert!(matches!(&parsed.args[3], ParsedArgument::Text(m) if m == "cold payment"));
+
+        let command_str = format!("export-unsigned-tx 999T {}", public_key);
+        assert!(parse_command(&command_str).is_err());
+
+        let parsed = parse_command("sign-offline-tx unsigned.txt signed.txt").unwrap();
+        assert_eq!(parsed.args.len(), 2);
+        assert!(parse_command("sign-offline-tx unsigned.txt").is_err());
+
+        let parsed = parse_command("broadcast-signed-tx unsigned.txt signed.txt").unwrap();
+        assert!(matches!(&parsed.args[0], ParsedArgument::Text(f) if f == "unsigned.txt"));
+        assert!(matches!(&parsed.args[1], ParsedArgument::Text(f) if f == "signed.txt"));
+        assert!(parse_command("broadcast-signed-tx signed.txt").is_err());
+    }
+
+    #[test]
+    fn test_parse_vault_commands() {
+        let (secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+
+        let command_str = format!("send-to-vault 10T 5000 {} savings", public_key);
+        let parsed = parse_command(&command_str).unwrap();
+        assert!(matches!(parsed.args[0], ParsedArgument::Amount(a) if a == MicroTari::from_str("10T").unwrap()));
+        assert!(matches!(parsed.args[1], ParsedArgument::Int(5000)));
+        assert!(matches!(&parsed.args[2], ParsedArgument::PublicKey(k) if *k == public_key));
+        assert!(matches!(&parsed.args[3], ParsedArgument::Text(m) if m == "savings"));
+        assert!(parse_command(&format!("send-to-vault 10T soon {}", public_key)).is_err());
+
+        let parsed = parse_command("list-vaults").unwrap();
+        assert!(parsed.args.is_empty());
+
+        let hash = "00".repeat(32);
+        let command_str = format!("recover-vault {} {}", hash, secret_key.to_hex());
+        let parsed = parse_command(&command_str).unwrap();
+        assert!(matches!(&parsed.args[0], ParsedArgument::Hash(h) if h.to_hex() == hash));
+        assert!(matches!(&parsed.args[1], ParsedArgument::Text(k) if *k == secret_key.to_hex()));
+        assert!(parse_command(&format!("recover-vault {} not-a-key", hash)).is_err());
+    }
+
+    #[test]
+    fn test_parse_coin_split_even_and_join() {
+        let parsed = parse_command("coin-split-even 8").unwrap();
+        assert!(matches!(parsed.args[0], ParsedArgument::Int(8)));
+        assert!(matches!(parsed.args[1], ParsedArgument::Amount(a) if a == MicroTari::from(5)));
+        assert!(parse_command("coin-split-even").is_err());
+
+        let parsed = parse_command("coin-join 1000 50 10").unwrap();
+        assert!(matches!(parsed.args[0], ParsedArgument::Amount(a) if a == MicroTari::from(1000)));
+        assert!(matches!(parsed.args[1], ParsedArgument::Int(50)));
+        assert!(matches!(parsed.args[2], ParsedArgument::Amount(a) if a == MicroTari::from(10)));
+
+        let parsed = parse_command("coin-join 1000").unwrap();
+        assert!(matches!(parsed.args[1], ParsedArgument::Int(100)));
+        assert!(parse_command("coin-join 1000 many").is_err());
+    }
+
+    #[test]
+    fn test_parse_tx_bump_fee() {
+        let parsed = parse_command("tx bump-fee 12345 25").unwrap();
+        assert!(matches!(&parsed.args[0], ParsedArgument::Text(s) if s == "bump-fee"));
+        assert!(matches!(parsed.args[1], ParsedArgument::Int(12345)));
+        assert!(matches!(parsed.args[2], ParsedArgument::Amount(a) if a == MicroTari::from(25)));
+        assert_eq!(parsed.to_string(), "tx bump-fee 12345 25 µT");
+
+        assert!(parse_command("tx bump-fee 12345").is_err());
+        assert!(parse_command("tx bump-fee abc 25").is_err());
+        assert!(parse_command("tx rebroadcast 12345").is_err());
+        assert!(parse_command("tx").is_err());
+    }
+
+    #[test]
+    fn test_parse_watch_only() {
+        let keys = (0..3)
+            .map(|_| PublicKey::random_keypair(&mut OsRng).1)
+            .collect::<Vec<_>>();
+        let command = format!(
+            "watch-only import savings {} {} {}",
+            keys[0].to_hex(),
+            keys[1].to_hex(),
+            keys[2].to_hex()
+        );
+        let parsed = parse_command(&command).unwrap();
+        assert!(matches!(&parsed.args[0], ParsedArgument::Text(s) if s == "import"));
+        assert!(matches!(&parsed.args[1], ParsedArgument::Text(s) if s == "savings"));
+        for (arg, key) in parsed.args[2..].iter().zip(keys.iter()) {
+            assert!(matches!(arg, ParsedArgument::PublicKey(k) if k == key));
+        }
+
+        let parsed = parse_command("watch-only export").unwrap();
+        assert!(matches!(&parsed.args[1], ParsedArgument::Text(s) if s == "watch-only"));
+        let parsed = parse_command(&format!("watch-only remove {}", keys[0].to_hex())).unwrap();
+        assert!(matches!(&parsed.args[1], ParsedArgument::PublicKey(k) if *k == keys[0]));
+        assert_eq!(parse_command("watch-only balance").unwrap().args.len(), 1);
+
+        assert!(parse_command(&format!("watch-only import savings {}", keys[0].to_hex())).is_err());
+        assert!(parse_command("watch-only remove not_a_key").is_err());
+        assert!(parse_command("watch-only spend").is_err());
+        assert!(parse_command("watch-only").is_err());
+    }
+
+    #[test]
+    fn test_parse_contacts() {
+        let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+        let emoji_id = EmojiId::from_pubkey(&public_key);
+
+        let parsed = parse_command(&format!("contacts add alice {}", emoji_id)).unwrap();
+        assert!(matches!(&parsed.args[0], ParsedArgument::Text(s) if s == "add"));
+        assert!(matches!(&parsed.args[1], ParsedArgument::Text(s) if s == "alice"));
+        assert!(matches!(&parsed.args[2], ParsedArgument::PublicKey(k) if *k == public_key));
+        assert!(matches!(&parse_command("contacts").unwrap().args[0], ParsedArgument::Text(s) if s == "list"));
+        assert_eq!(parse_command("contacts rename alice bob").unwrap().args.len(), 3);
+        assert_eq!(parse_command("contacts remove alice").unwrap().args.len(), 2);
+
+        // An emoji ID without its checksum emoji is rejected
+        let truncated_emoji_id = emoji_id.to_string().chars().take(32).collect::<String>();
+        assert!(parse_command(&format!("contacts add alice {}", truncated_emoji_id)).is_err());
+        assert!(parse_command("contacts add alice").is_err());
+        assert!(parse_command("contacts forget alice").is_err());
+
+        let parsed = parse_command("send-tari 1T @alice for lunch").unwrap();
+        assert!(matches!(&parsed.args[1], ParsedArgument::ContactAlias(s) if s == "alice"));
+        assert!(matches!(&parsed.args[2], ParsedArgument::Text(s) if s == "for lunch"));
+        assert!(parse_command("send-one-sided 1T @").is_err());
+    }
+
+    #[test]
+    fn test_parse_export_transactions() {
+        let parsed = parse_command("export-transactions").unwrap();
+        assert_eq!(parsed.args.len(), 4);
+        assert!(matches!(&parsed.args[1], ParsedArgument::Text(s) if s == "csv"));
+        assert!(matches!(&parsed.args[3], ParsedArgument::Text(s) if s == "transactions.csv"));
+
+        let parsed = parse_command(
+            "export-transactions --format json --from 2022-01-01 --to 2022-03-31 --status mined-confirmed --output \
+             q1.json",
+        )
+        .unwrap();
+        assert!(matches!(&parsed.args[0], ParsedArgument::Text(s) if s == "--from"));
+        assert!(matches!(&parsed.args[1], ParsedArgument::Date(d) if d.to_rfc3339() == "2022-01-01T00:00:00+00:00"));
+        assert!(matches!(&parsed.args[2], ParsedArgument::Text(s) if s == "--to"));
+        let end_of_day = "2022-03-31T23:59:59.999999999+00:00";
+        assert!(matches!(&parsed.args[3], ParsedArgument::Date(d) if d.to_rfc3339() == end_of_day));
+        assert!(matches!(&parsed.args[5], ParsedArgument::Text(s) if s == "mined-confirmed"));
+        assert!(matches!(&parsed.args[7], ParsedArgument::Text(s) if s == "json"));
+        assert!(matches!(&parsed.args[9], ParsedArgument::Text(s) if s == "q1.json"));
+
+        let parsed = parse_command("export-transactions --to 2022-03-31T12:00:00+02:00").unwrap();
+        assert!(matches!(&parsed.args[1], ParsedArgument::Date(d) if d.to_rfc3339() == "2022-03-31T10:00:00+00:00"));
+
+        assert!(parse_command("export-transactions --format xml").is_err());
+        assert!(parse_command("export-transactions --status lost").is_err());
+        assert!(parse_command("export-transactions --from yesterday").is_err());
+        assert!(parse_command("export-transactions --from").is_err());
+        assert!(parse_command("export-transactions --limit 10").is_err());
+    }
+
+    #[test]
+    fn test_parse_scheduled_payment() {
+        let (_secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+
+        let command = format!(
+            "scheduled-payment add one-sided 100T {} 2022-05-01T09:00:00Z 30d 12 monthly salary",
+            public_key.to_hex()
+        );
+        let parsed = parse_command(&command).unwrap();
+        assert!(matches!(&parsed.args[0], ParsedArgument::Text(s) if s == "add"));
+        assert!(matches!(&parsed.args[1], ParsedArgument::Text(s) if s == "one-sided"));
+        assert!(matches!(&parsed.args[2], ParsedArgument::Amount(a) if *a == MicroTari::from_str("100T").unwrap()));
+        assert!(matches!(&parsed.args[3], ParsedArgument::PublicKey(k) if *k == public_key));
+        assert!(matches!(&parsed.args[4], ParsedArgument::Date(d) if d.to_rfc3339() == "2022-05-01T09:00:00+00:00"));
+        assert!(matches!(parsed.args[5], ParsedArgument::Int(2_592_000)));
+        assert!(matches!(parsed.args[6], ParsedArgument::Int(12)));
+        assert!(matches!(&parsed.args[7], ParsedArgument::Text(s) if s == "monthly salary"));
+
+        let parsed = parse_command("scheduled-payment add standard 5T @alice now once rent").unwrap();
+        assert!(matches!(&parsed.args[3], ParsedArgument::ContactAlias(s) if s == "alice"));
+        assert!(matches!(parsed.args[5], ParsedArgument::Int(0)));
+        assert!(matches!(parsed.args[6], ParsedArgument::Int(1)));
+        assert!(matches!(&parsed.args[7], ParsedArgument::Text(s) if s == "rent"));
+
+        let parsed = parse_command("scheduled-payment add standard 5T @alice now 1w forever").unwrap();
+        assert!(matches!(parsed.args[5], ParsedArgument::Int(604_800)));
+        assert!(matches!(parsed.args[6], ParsedArgument::Int(0)));
+
+        assert!(matches!(&parse_command("scheduled-payment").unwrap().args[0], ParsedArgument::Text(s) if s == "list"));
+        assert!(matches!(
+            parse_command("scheduled-payment cancel 42").unwrap().args[1],
+            ParsedArgument::Int(42)
+        ));
+
+        assert!(parse_command("scheduled-payment add interactive 5T @alice now once").is_err());
+        assert!(parse_command("scheduled-payment add standard 5T @alice now 30").is_err());
+        assert!(parse_command("scheduled-payment add standard 5T @alice now 0d forever").is_err());
+        assert!(parse_command("scheduled-payment add standard 5T @alice now 1d 0").is_err());
+        assert!(parse_command("scheduled-payment add standard 5T @alice now 1d").is_err());
+        assert!(parse_command("scheduled-payment cancel").is_err());
+    }
 }
@@ -21,19 +21,27 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    convert::{TryFrom, TryInto},
+    fs,
     fs::File,
     io::{LineWriter, Write},
     str::FromStr,
     time::{Duration, Instant},
 };
 
-use chrono::Utc;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use digest::Digest;
 use futures::FutureExt;
 use log::*;
+use serde::Serialize;
 use sha2::Sha256;
 use strum_macros::{Display, EnumIter, EnumString};
-use tari_common_types::{array::copy_into_fixed_array, emoji::EmojiId, transaction::TxId, types::PublicKey};
+use tari_common_types::{
+    array::copy_into_fixed_array,
+    emoji::EmojiId,
+    transaction::{TransactionDirection, TransactionStatus, TxId},
+    types::{PrivateKey, PublicKey},
+};
 use tari_comms::{
     connectivity::{ConnectivityEvent, ConnectivityRequester},
     multiaddr::Multiaddr,
@@ -48,10 +56,26 @@ use tari_crypto::{keys::PublicKey as PublicKeyTrait, ristretto::pedersen::Peders
 use tari_utilities::{hex::Hex, ByteArray, Hashable};
 use tari_wallet::{
     assets::KEY_MANAGER_ASSET_BRANCH,
+    contacts_service::{
+        error::{ContactsServiceError, ContactsServiceStorageError},
+        handle::ContactsServiceHandle,
+        storage::database::Contact,
+    },
     error::WalletError,
     key_manager_service::KeyManagerInterface,
-    output_manager_service::handle::OutputManagerHandle,
-    transaction_service::handle::{TransactionEvent, TransactionServiceHandle},
+    output_manager_service::{handle::OutputManagerHandle, storage::models::WatchOnlyKey},
+    transaction_service::{
+        handle::{TransactionEvent, TransactionServiceHandle},
+        offline_signing::{OfflineTransaction, DEFAULT_MAX_CHUNK_LEN},
+        storage::models::{
+            CompletedTransaction,
+            InboundTransaction,
+            OutboundTransaction,
+            PaymentSchedule,
+            ScheduledPayment,
+            ScheduledPaymentType,
+        },
+    },
     WalletConfig,
     WalletSqlite,
 };
@@ -68,6 +92,22 @@ use crate::{
 
 pub const LOG_TARGET: &str = "wallet::automation::commands";
 
+/// The statuses that `export-transactions` can filter on
+pub const EXPORT_TRANSACTION_STATUSES: [&str; 12] = [
+    "completed",
+    "broadcast",
+    "mined-unconfirmed",
+    "mined-confirmed",
+    "imported",
+    "pending",
+    "coinbase",
+    "rejected",
+    "faux-unconfirmed",
+    "faux-confirmed",
+    "queued",
+    "cancelled",
+];
+
 /// Enum representing commands used by the wallet
 #[derive(Clone, PartialEq, Debug, Display, EnumIter, EnumString)]
 #[strum(serialize_all = "kebab_case")]
@@ -77,10 +117,17 @@ pub enum WalletCommand {
     SendOneSided,
     MakeItRain,
     CoinSplit,
+    CoinSplitEven,
+    CoinJoin,
+    Tx,
+    WatchOnly,
+    Contacts,
+    ScheduledPayment,
     DiscoverPeer,
     Whois,
     ExportUtxos,
     ExportSpentUtxos,
+    ExportTransactions,
     CountUtxos,
     SetBaseNode,
     SetCustomBaseNode,
@@ -88,11 +135,19 @@ pub enum WalletCommand {
     InitShaAtomicSwap,
     FinaliseShaAtomicSwap,
     ClaimShaAtomicSwapRefund,
+    InitHtlc,
+    FetchHtlcPreImage,
     RegisterAsset,
     MintTokens,
     CreateInitialCheckpoint,
     CreateCommitteeDefinition,
     RevalidateWalletDb,
+    ExportUnsignedTx,
+    SignOfflineTx,
+    BroadcastSignedTx,
+    SendToVault,
+    ListVaults,
+    RecoverVault,
 }
 
 #[derive(Debug, EnumString, PartialEq, Clone, Copy)]
@@ -225,6 +280,124 @@ pub async fn claim_htlc_refund(
     Ok(tx_id)
 }
 
+/// publishes an HTLC transaction locked to a hash chosen by the counterparty of an atomic swap
+pub async fn init_htlc(
+    mut wallet_transaction_service: TransactionServiceHandle,
+    fee_per_gram: u64,
+    args: Vec<ParsedArgument>,
+) -> Result<(TxId, TransactionOutput), CommandError> {
+    use ParsedArgument::{Amount, Hash, Int, PublicKey, Text};
+    let amount = match args[0].clone() {
+        Amount(mtari) => Ok(mtari),
+        _ => Err(CommandError::Argument),
+    }?;
+    let dest_pubkey = match args[1].clone() {
+        PublicKey(key) => Ok(key),
+        _ => Err(CommandError::Argument),
+    }?;
+    let hash: [u8; 32] = match args[2].clone() {
+        Hash(hash) => hash.as_slice().try_into().map_err(|_| CommandError::Argument),
+        _ => Err(CommandError::Argument),
+    }?;
+    let lock_height = match args[3].clone() {
+        Int(height) => Ok(height),
+        _ => Err(CommandError::Argument),
+    }?;
+    let message = match args[4].clone() {
+        Text(msg) => Ok(msg),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let (tx_id, output) = wallet_transaction_service
+        .send_htlc_transaction(dest_pubkey, amount, hash, lock_height, fee_per_gram * uT, message)
+        .await
+        .map_err(CommandError::TransactionServiceError)?;
+    Ok((tx_id, output))
+}
+
+/// fetches the pre-image revealed by the counterparty claiming an HTLC output
+pub async fn fetch_htlc_pre_image(
+    mut output_service: OutputManagerHandle,
+    args: Vec<ParsedArgument>,
+) -> Result<Option<PublicKey>, CommandError> {
+    use ParsedArgument::Hash;
+    let output = match args[0].clone() {
+        Hash(output) => Ok(output),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    Ok(output_service.fetch_htlc_pre_image(output).await?)
+}
+
+/// Lock funds in a vault output that only this wallet can spend after the unlock height, or the holder of the
+/// recovery key at any time
+pub async fn send_to_vault(
+    mut output_service: OutputManagerHandle,
+    mut transaction_service: TransactionServiceHandle,
+    fee_per_gram: u64,
+    args: Vec<ParsedArgument>,
+) -> Result<TxId, CommandError> {
+    use ParsedArgument::{Amount, Int, PublicKey, Text};
+    let amount = match args[0].clone() {
+        Amount(mtari) => Ok(mtari),
+        _ => Err(CommandError::Argument),
+    }?;
+    let unlock_height = match args[1].clone() {
+        Int(height) => Ok(height),
+        _ => Err(CommandError::Argument),
+    }?;
+    let recovery_public_key = match args[2].clone() {
+        PublicKey(key) => Ok(key),
+        _ => Err(CommandError::Argument),
+    }?;
+    let message = match args[3].clone() {
+        Text(msg) => Ok(msg),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let tx_id = TxId::new_random();
+    let (_fee, tx) = output_service
+        .create_vault_transaction(
+            tx_id,
+            amount,
+            unlock_height,
+            recovery_public_key,
+            fee_per_gram * uT,
+            message.clone(),
+        )
+        .await?;
+    transaction_service
+        .submit_transaction(tx_id, tx, amount, message)
+        .await?;
+    Ok(tx_id)
+}
+
+/// Sweep a vault output back into the wallet using the recovery key, without waiting for the unlock height
+pub async fn recover_vault(
+    mut output_service: OutputManagerHandle,
+    mut transaction_service: TransactionServiceHandle,
+    fee_per_gram: u64,
+    args: Vec<ParsedArgument>,
+) -> Result<TxId, CommandError> {
+    use ParsedArgument::{Hash, Text};
+    let output = match args[0].clone() {
+        Hash(output) => Ok(output),
+        _ => Err(CommandError::Argument),
+    }?;
+    let recovery_private_key = match args[1].clone() {
+        Text(key) => Ok(PrivateKey::from_hex(&key)?),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let (tx_id, _fee, amount, tx) = output_service
+        .create_vault_recovery_transaction(output, recovery_private_key, fee_per_gram * uT)
+        .await?;
+    transaction_service
+        .submit_transaction(tx_id, tx, amount, "Recovered vault".into())
+        .await?;
+    Ok(tx_id)
+}
+
 /// Send a one-sided transaction to a recipient
 pub async fn send_one_sided(
     mut wallet_transaction_service: TransactionServiceHandle,
@@ -238,6 +411,121 @@ pub async fn send_one_sided(
         .map_err(CommandError::TransactionServiceError)
 }
 
+/// Selects the inputs of a payment to be signed by an offline wallet and writes the unsigned transaction to a file, one
+/// chunk per line
+async fn export_unsigned_transaction(
+    mut wallet_transaction_service: TransactionServiceHandle,
+    fee_per_gram: u64,
+    args: Vec<ParsedArgument>,
+) -> Result<(), CommandError> {
+    use ParsedArgument::{Amount, PublicKey, Text};
+    let amount = match args[0].clone() {
+        Amount(mtari) => Ok(mtari),
+        _ => Err(CommandError::Argument),
+    }?;
+    let destination = match args[1].clone() {
+        PublicKey(key) => Ok(key),
+        _ => Err(CommandError::Argument),
+    }?;
+    let file_path = match args[2].clone() {
+        Text(path) => Ok(path),
+        _ => Err(CommandError::Argument),
+    }?;
+    let message = match args[3].clone() {
+        Text(msg) => Ok(msg),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let unsigned = wallet_transaction_service
+        .prepare_offline_transaction(destination, amount, fee_per_gram * uT, message)
+        .await?;
+    let num_chunks = write_offline_transaction(&OfflineTransaction::Unsigned(unsigned), &file_path)?;
+    println!(
+        "Unsigned transaction written to {} ({} chunk(s)). Sign it with `sign-offline-tx` on the offline wallet.",
+        file_path, num_chunks
+    );
+    Ok(())
+}
+
+/// Builds and signs exactly the transaction in an unsigned transaction file and writes the signed transaction to a
+/// file. This is run on the offline wallet, which holds the spending keys. Nothing is broadcast.
+async fn sign_offline_transaction(
+    mut wallet_transaction_service: TransactionServiceHandle,
+    args: Vec<ParsedArgument>,
+) -> Result<(), CommandError> {
+    let (input_path, output_path) = match (args[0].clone(), args[1].clone()) {
+        (ParsedArgument::Text(input), ParsedArgument::Text(output)) => Ok((input, output)),
+        _ => Err(CommandError::Argument),
+    }?;
+    let unsigned = match read_offline_transaction(&input_path)? {
+        OfflineTransaction::Unsigned(tx) => tx,
+        OfflineTransaction::Signed(_) => {
+            return Err(CommandError::OfflineTransaction(format!(
+                "{} already contains a signed transaction",
+                input_path
+            )))
+        },
+    };
+
+    let signed = wallet_transaction_service.sign_offline_transaction(unsigned).await?;
+    let num_chunks = write_offline_transaction(&OfflineTransaction::Signed(signed), &output_path)?;
+    println!(
+        "Signed transaction written to {} ({} chunk(s)). Broadcast it with `broadcast-signed-tx` on the online wallet.",
+        output_path, num_chunks
+    );
+    Ok(())
+}
+
+/// Imports a transaction signed by an offline wallet and submits it to the network, once it has been checked against
+/// the unsigned transaction that was exported by this wallet
+async fn broadcast_signed_transaction(
+    mut wallet_transaction_service: TransactionServiceHandle,
+    args: Vec<ParsedArgument>,
+) -> Result<TxId, CommandError> {
+    let (unsigned_path, signed_path) = match (args[0].clone(), args[1].clone()) {
+        (ParsedArgument::Text(unsigned), ParsedArgument::Text(signed)) => Ok((unsigned, signed)),
+        _ => Err(CommandError::Argument),
+    }?;
+    let unsigned = match read_offline_transaction(&unsigned_path)? {
+        OfflineTransaction::Unsigned(tx) => tx,
+        OfflineTransaction::Signed(_) => {
+            return Err(CommandError::OfflineTransaction(format!(
+                "{} does not contain an unsigned transaction",
+                unsigned_path
+            )))
+        },
+    };
+    let signed = match read_offline_transaction(&signed_path)? {
+        OfflineTransaction::Signed(tx) => tx,
+        OfflineTransaction::Unsigned(_) => {
+            return Err(CommandError::OfflineTransaction(format!(
+                "{} contains an unsigned transaction, sign it with `sign-offline-tx` first",
+                signed_path
+            )))
+        },
+    };
+
+    let tx_id = signed.tx_id;
+    wallet_transaction_service
+        .submit_offline_transaction(unsigned, signed)
+        .await?;
+    println!("Signed transaction {} submitted", tx_id);
+    Ok(tx_id)
+}
+
+fn write_offline_transaction(tx: &OfflineTransaction, file_path: &str) -> Result<usize, CommandError> {
+    let chunks = tx
+        .to_chunks(DEFAULT_MAX_CHUNK_LEN)
+        .map_err(|e| CommandError::OfflineTransaction(e.to_string()))?;
+    fs::write(file_path, chunks.join("\n")).map_err(|e| CommandError::OfflineTransaction(e.to_string()))?;
+    Ok(chunks.len())
+}
+
+fn read_offline_transaction(file_path: &str) -> Result<OfflineTransaction, CommandError> {
+    let contents = fs::read_to_string(file_path).map_err(|e| CommandError::OfflineTransaction(e.to_string()))?;
+    OfflineTransaction::from_chunks(contents.lines()).map_err(|e| CommandError::OfflineTransaction(e.to_string()))
+}
+
 pub async fn coin_split(
     args: &[ParsedArgument],
     output_service: &mut OutputManagerHandle,
@@ -269,6 +557,290 @@ pub async fn coin_split(
     Ok(tx_id)
 }
 
+pub async fn coin_split_even(
+    args: &[ParsedArgument],
+    output_service: &mut OutputManagerHandle,
+    transaction_service: &mut TransactionServiceHandle,
+) -> Result<TxId, CommandError> {
+    use ParsedArgument::{Amount, Int};
+    let num_splits = match args[0] {
+        Int(s) => Ok(s),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let fee_per_gram = match args[1] {
+        Amount(s) => Ok(s),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let (tx_id, tx, amount) = output_service
+        .create_even_coin_split(num_splits as usize, fee_per_gram, None)
+        .await?;
+    transaction_service
+        .submit_transaction(tx_id, tx, amount, "Coin split".into())
+        .await?;
+
+    Ok(tx_id)
+}
+
+pub async fn coin_join(
+    args: &[ParsedArgument],
+    output_service: &mut OutputManagerHandle,
+    transaction_service: &mut TransactionServiceHandle,
+) -> Result<TxId, CommandError> {
+    use ParsedArgument::{Amount, Int};
+    let dust_threshold = match args[0] {
+        Amount(s) => Ok(s),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let max_inputs = match args[1] {
+        Int(s) => Ok(s),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let fee_per_gram = match args[2] {
+        Amount(s) => Ok(s),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let (tx_id, tx, amount) = output_service
+        .create_coin_join(dust_threshold, max_inputs as usize, fee_per_gram)
+        .await?;
+    transaction_service
+        .submit_transaction(tx_id, tx, amount, "Coin join".into())
+        .await?;
+
+    Ok(tx_id)
+}
+
+pub async fn bump_fee(
+    args: &[ParsedArgument],
+    transaction_service: &mut TransactionServiceHandle,
+) -> Result<TxId, CommandError> {
+    use ParsedArgument::{Amount, Int};
+    let tx_id = match args[1] {
+        Int(id) => Ok(TxId::from(id)),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let fee_per_gram = match args[2] {
+        Amount(s) => Ok(s),
+        _ => Err(CommandError::Argument),
+    }?;
+
+    let new_tx_id = transaction_service.bump_transaction_fee(tx_id, fee_per_gram).await?;
+    Ok(new_tx_id)
+}
+
+pub async fn watch_only(args: &[ParsedArgument], wallet: &WalletSqlite) -> Result<(), CommandError> {
+    use ParsedArgument::{PublicKey, Text};
+    match args {
+        [Text(subcommand), Text(label)] if subcommand == "export" => {
+            let key = wallet.clone().export_watch_only_key(label.clone()).await?;
+            println!("Run the following on the watching wallet:");
+            println!(
+                "watch-only import {} {} {} {}",
+                key.label,
+                key.spend_public_key.to_hex(),
+                key.rewind_public_key.to_hex(),
+                key.rewind_blinding_public_key.to_hex()
+            );
+        },
+        [Text(subcommand), Text(label), PublicKey(spend_key), PublicKey(rewind_key), PublicKey(rewind_blinding_key)]
+            if subcommand == "import" =>
+        {
+            wallet
+                .clone()
+                .add_watch_only_key(WatchOnlyKey {
+                    spend_public_key: spend_key.clone(),
+                    rewind_public_key: rewind_key.clone(),
+                    rewind_blinding_public_key: rewind_blinding_key.clone(),
+                    label: label.clone(),
+                })
+                .await?;
+            println!(
+                "Watching {}. Its outputs will be found when the wallet next scans the blockchain.",
+                label
+            );
+        },
+        [Text(subcommand), PublicKey(spend_public_key)] if subcommand == "remove" => {
+            wallet
+                .output_manager_service
+                .clone()
+                .remove_watch_only_key(spend_public_key.clone())
+                .await?;
+            println!("No longer watching {}", spend_public_key);
+        },
+        [Text(subcommand)] if subcommand == "balance" => {
+            let balances = wallet.output_manager_service.clone().get_watch_only_balances().await?;
+            if balances.is_empty() {
+                println!("No wallets are being watched");
+            }
+            for balance in balances {
+                println!("{}", balance);
+            }
+        },
+        _ => return Err(CommandError::Argument),
+    }
+    Ok(())
+}
+
+pub async fn contacts(args: &[ParsedArgument], wallet: &WalletSqlite) -> Result<(), CommandError> {
+    use ParsedArgument::{PublicKey, Text};
+    let mut contacts_service = wallet.contacts_service.clone();
+    match args {
+        [Text(subcommand)] if subcommand == "list" => {
+            let contacts = contacts_service.get_contacts().await?;
+            if contacts.is_empty() {
+                println!("The address book is empty");
+            }
+            for contact in contacts {
+                let last_transaction = match (contact.last_transaction_id, contact.last_transaction_at) {
+                    (Some(tx_id), Some(timestamp)) => format!("last transaction {} at {}", tx_id, timestamp),
+                    _ => "no transactions".to_string(),
+                };
+                println!(
+                    "{}: {} ({})",
+                    contact.alias,
+                    EmojiId::from_pubkey(&contact.public_key),
+                    last_transaction
+                );
+            }
+        },
+        [Text(subcommand), Text(alias), PublicKey(public_key)] if subcommand == "add" => {
+            if public_key == wallet.comms.node_identity().public_key() {
+                return Err(CommandError::InvalidContact(
+                    "this wallet cannot be added to its own address book".to_string(),
+                ));
+            }
+            contacts_service
+                .upsert_contact(Contact::new(alias.clone(), public_key.clone(), None, None))
+                .await?;
+            println!("Saved {} as @{}", EmojiId::from_pubkey(public_key), alias);
+        },
+        [Text(subcommand), Text(alias), Text(new_alias)] if subcommand == "rename" => {
+            let mut contact = get_contact_by_alias(&mut contacts_service, alias).await?;
+            contact.alias = new_alias.clone();
+            contacts_service.upsert_contact(contact).await?;
+            println!("Renamed @{} to @{}", alias, new_alias);
+        },
+        [Text(subcommand), Text(alias)] if subcommand == "remove" => {
+            let contact = get_contact_by_alias(&mut contacts_service, alias).await?;
+            contacts_service.remove_contact(contact.public_key).await?;
+            println!("Removed @{}", alias);
+        },
+        _ => return Err(CommandError::Argument),
+    }
+    Ok(())
+}
+
+pub async fn scheduled_payment(
+    args: &[ParsedArgument],
+    transaction_service: &mut TransactionServiceHandle,
+    fee_per_gram: u64,
+) -> Result<(), CommandError> {
+    use ParsedArgument::{Amount, Date, Int, PublicKey, Text};
+    match args {
+        [Text(subcommand)] if subcommand == "list" => {
+            let payments = transaction_service.get_scheduled_payments().await?;
+            if payments.is_empty() {
+                println!("There are no scheduled payments");
+            }
+            for payment in payments {
+                let schedule = match (payment.schedule.interval, payment.schedule.remaining_payments) {
+                    (None, _) => "once".to_string(),
+                    (Some(interval), Some(remaining)) => {
+                        format!("every {}s, {} remaining", interval.as_secs(), remaining)
+                    },
+                    (Some(interval), None) => format!("every {}s until cancelled", interval.as_secs()),
+                };
+                println!(
+                    "{}: {} {} to {} ({}), next at {}, {} sent, {}{}",
+                    payment.id,
+                    payment.payment_type,
+                    payment.amount,
+                    payment.destination_public_key,
+                    schedule,
+                    payment.schedule.next_payment_at,
+                    payment.num_payments_made,
+                    payment.status,
+                    payment.last_error.map(|e| format!(" ({})", e)).unwrap_or_default()
+                );
+            }
+        },
+        [Text(subcommand), Int(payment_id)] if subcommand == "cancel" => {
+            transaction_service.cancel_scheduled_payment(*payment_id).await?;
+            println!("Cancelled scheduled payment {}", payment_id);
+        },
+        [Text(sub), Text(kind), Amount(amount), PublicKey(dest), Date(start), Int(interval), Int(count), Text(msg)]
+            if sub == "add" =>
+        {
+            let payment_type = if kind == "one-sided" {
+                ScheduledPaymentType::OneSided
+            } else {
+                ScheduledPaymentType::Standard
+            };
+            let schedule = if *interval == 0 {
+                PaymentSchedule::once(start.naive_utc())
+            } else {
+                PaymentSchedule::recurring(
+                    start.naive_utc(),
+                    Duration::from_secs(*interval),
+                    Some(u32::try_from(*count).unwrap_or(u32::MAX)).filter(|c| *c > 0),
+                )
+            };
+            let payment_id = transaction_service
+                .schedule_payment(ScheduledPayment::new(
+                    dest.clone(),
+                    *amount,
+                    fee_per_gram * uT,
+                    msg.clone(),
+                    payment_type,
+                    schedule,
+                ))
+                .await?;
+            println!(
+                "Scheduled payment {}. Payments are only made while the wallet is running.",
+                payment_id
+            );
+        },
+        _ => return Err(CommandError::Argument),
+    }
+    Ok(())
+}
+
+async fn get_contact_by_alias(
+    contacts_service: &mut ContactsServiceHandle,
+    alias: &str,
+) -> Result<Contact, CommandError> {
+    match contacts_service.get_contact_by_alias(alias.to_string()).await {
+        Ok(contact) => Ok(contact),
+        Err(ContactsServiceError::ContactsServiceStorageError(ContactsServiceStorageError::ValueNotFound(_))) => Err(
+            CommandError::InvalidContact(format!("there is no contact with the alias @{}", alias)),
+        ),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Replaces `@alias` arguments with the public key of the contact they refer to
+async fn resolve_contact_aliases(
+    args: Vec<ParsedArgument>,
+    contacts_service: &mut ContactsServiceHandle,
+) -> Result<Vec<ParsedArgument>, CommandError> {
+    let mut resolved = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg {
+            ParsedArgument::ContactAlias(alias) => {
+                let contact = get_contact_by_alias(contacts_service, &alias).await?;
+                resolved.push(ParsedArgument::PublicKey(contact.public_key));
+            },
+            arg => resolved.push(arg),
+        }
+    }
+    Ok(resolved)
+}
+
 async fn wait_for_comms(connectivity_requester: &ConnectivityRequester) -> Result<(), CommandError> {
     let mut connectivity = connectivity_requester.get_event_subscription();
     print!("Waiting for connectivity... ");
@@ -633,6 +1205,7 @@ pub async fn command_runner(
 
     let mut transaction_service = wallet.transaction_service.clone();
     let mut output_service = wallet.output_manager_service.clone();
+    let mut contacts_service = wallet.contacts_service.clone();
     let dht_service = wallet.dht_service.discovery_service_requester().clone();
     let connectivity_requester = wallet.comms.connectivity();
     let mut online = false;
@@ -645,8 +1218,9 @@ pub async fn command_runner(
 
     #[allow(clippy::enum_glob_use)]
     use WalletCommand::*;
-    for (idx, parsed) in commands.into_iter().enumerate() {
+    for (idx, mut parsed) in commands.into_iter().enumerate() {
         println!("\n{}. {}\n", idx + 1, parsed);
+        parsed.args = resolve_contact_aliases(parsed.args, &mut contacts_service).await?;
 
         match parsed.command {
             GetBalance => match output_service.clone().get_balance().await {
@@ -680,6 +1254,28 @@ pub async fn command_runner(
                 tx_ids.push(tx_id);
                 println!("Coin split succeeded");
             },
+            CoinSplitEven => {
+                let tx_id =
+                    coin_split_even(&parsed.args, &mut output_service, &mut transaction_service.clone()).await?;
+                tx_ids.push(tx_id);
+                println!("Coin split succeeded");
+            },
+            CoinJoin => {
+                let tx_id = coin_join(&parsed.args, &mut output_service, &mut transaction_service.clone()).await?;
+                tx_ids.push(tx_id);
+                println!("Coin join succeeded");
+            },
+            Tx => match &parsed.args[0] {
+                ParsedArgument::Text(subcommand) if subcommand == "bump-fee" => {
+                    let tx_id = bump_fee(&parsed.args, &mut transaction_service).await?;
+                    tx_ids.push(tx_id);
+                    println!("Fee bumped, replacement transaction {}", tx_id);
+                },
+                _ => return Err(CommandError::Argument),
+            },
+            WatchOnly => watch_only(&parsed.args, &wallet).await?,
+            Contacts => contacts(&parsed.args, &wallet).await?,
+            ScheduledPayment => scheduled_payment(&parsed.args, &mut transaction_service, config.fee_per_gram).await?,
             Whois => {
                 let public_key = match parsed.args[0].clone() {
                     ParsedArgument::PublicKey(key) => Ok(Box::new(key)),
@@ -720,6 +1316,7 @@ pub async fn command_runner(
                 println!("Total number of UTXOs: {}", count);
                 println!("Total value of UTXOs: {}", sum);
             },
+            ExportTransactions => export_transactions(&parsed.args, &wallet).await?,
             CountUtxos => {
                 let utxos = output_service.get_unspent_outputs().await?;
                 let count = utxos.len();
@@ -781,11 +1378,77 @@ pub async fn command_runner(
                 debug!(target: LOG_TARGET, "claiming tari HTLC tx_id {}", tx_id);
                 tx_ids.push(tx_id);
             },
+            SendToVault => {
+                let tx_id = send_to_vault(
+                    output_service.clone(),
+                    transaction_service.clone(),
+                    config.fee_per_gram,
+                    parsed.args,
+                )
+                .await?;
+                debug!(target: LOG_TARGET, "send-to-vault tx_id {}", tx_id);
+                tx_ids.push(tx_id);
+            },
+            ListVaults => {
+                let tip_height = wallet
+                    .base_node_service
+                    .clone()
+                    .get_chain_metadata()
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(|metadata| metadata.height_of_longest_chain());
+                let vaults = output_service.get_vault_outputs().await?;
+                let locked: MicroTari = vaults
+                    .iter()
+                    .filter(|v| tip_height.map(|h| !v.is_unlocked_at(h + 1)).unwrap_or(true))
+                    .map(|v| v.value)
+                    .sum();
+                for vault in &vaults {
+                    let state = match tip_height {
+                        Some(h) if vault.is_unlocked_at(h + 1) => "unlocked".to_string(),
+                        Some(h) => format!("{} blocks remaining", vault.vault.unlock_height - (h + 1)),
+                        None => "unknown".to_string(),
+                    };
+                    println!(
+                        "{}, {}, unlock height {} ({}), recovery key {}",
+                        vault.hash.to_hex(),
+                        vault.value,
+                        vault.vault.unlock_height,
+                        state,
+                        vault.vault.recovery_public_key
+                    );
+                }
+                println!("Total number of vaults: {}", vaults.len());
+                println!("Total locked in vaults: {}", locked);
+            },
+            RecoverVault => {
+                let tx_id = recover_vault(
+                    output_service.clone(),
+                    transaction_service.clone(),
+                    config.fee_per_gram,
+                    parsed.args,
+                )
+                .await?;
+                debug!(target: LOG_TARGET, "recover-vault tx_id {}", tx_id);
+                tx_ids.push(tx_id);
+            },
             ClaimShaAtomicSwapRefund => {
                 let tx_id = claim_htlc_refund(output_service.clone(), transaction_service.clone(), parsed.args).await?;
                 debug!(target: LOG_TARGET, "claiming tari HTLC tx_id {}", tx_id);
                 tx_ids.push(tx_id);
             },
+            InitHtlc => {
+                let (tx_id, output) =
+                    init_htlc(transaction_service.clone(), config.fee_per_gram, parsed.clone().args).await?;
+                debug!(target: LOG_TARGET, "tari HTLC tx_id {}", tx_id);
+                println!("Output hash: {}", output.hash().to_hex());
+                tx_ids.push(tx_id);
+            },
+            FetchHtlcPreImage => match fetch_htlc_pre_image(output_service.clone(), parsed.args).await? {
+                Some(pre_image) => println!("pre_image hex: {}", pre_image.to_hex()),
+                None => println!("The HTLC output has not been claimed"),
+            },
             RegisterAsset => {
                 let name = parsed.args[0].to_string();
                 let message = format!("Register asset: {}", name);
@@ -917,6 +1580,16 @@ pub async fn command_runner(
                     .await
                     .map_err(CommandError::TransactionServiceError)?;
             },
+            ExportUnsignedTx => {
+                export_unsigned_transaction(transaction_service.clone(), config.fee_per_gram, parsed.args).await?;
+            },
+            SignOfflineTx => {
+                sign_offline_transaction(transaction_service.clone(), parsed.args).await?;
+            },
+            BroadcastSignedTx => {
+                let tx_id = broadcast_signed_transaction(transaction_service.clone(), parsed.args).await?;
+                tx_ids.push(tx_id);
+            },
         }
     }
 
@@ -958,6 +1631,227 @@ pub async fn command_runner(
     Ok(())
 }
 
+/// A transaction of any kind as exported for accounting tools. Amounts and fees are in µT.
+#[derive(Debug, Serialize)]
+struct ExportedTransaction {
+    tx_id: u64,
+    timestamp: String,
+    direction: String,
+    status: String,
+    amount: u64,
+    fee: u64,
+    source_public_key: String,
+    destination_public_key: String,
+    confirmations: Option<u64>,
+    mined_height: Option<u64>,
+    mined_in_block: Option<String>,
+    kernel_excesses: Vec<String>,
+    message: String,
+    cancellation_reason: Option<String>,
+    #[serde(skip)]
+    time: NaiveDateTime,
+}
+
+impl ExportedTransaction {
+    fn from_completed(tx: CompletedTransaction) -> Self {
+        let status = match tx.cancelled {
+            Some(_) => "cancelled".to_string(),
+            None => export_status(&tx.status).to_string(),
+        };
+        Self {
+            tx_id: tx.tx_id.as_u64(),
+            timestamp: DateTime::<Utc>::from_utc(tx.timestamp, Utc).to_rfc3339(),
+            direction: tx.direction.to_string(),
+            status,
+            amount: tx.amount.as_u64(),
+            fee: tx.fee.as_u64(),
+            source_public_key: tx.source_public_key.to_hex(),
+            destination_public_key: tx.destination_public_key.to_hex(),
+            confirmations: tx.confirmations,
+            mined_height: tx.mined_height,
+            mined_in_block: tx.mined_in_block.map(|hash| hash.to_hex()),
+            kernel_excesses: tx
+                .transaction
+                .body
+                .kernels()
+                .iter()
+                .map(|kernel| kernel.excess.to_hex())
+                .collect(),
+            message: tx.message,
+            cancellation_reason: tx.cancelled.map(|reason| reason.to_string()),
+            time: tx.timestamp,
+        }
+    }
+
+    fn from_inbound(tx: InboundTransaction, own_public_key: &PublicKey) -> Self {
+        Self {
+            tx_id: tx.tx_id.as_u64(),
+            timestamp: DateTime::<Utc>::from_utc(tx.timestamp, Utc).to_rfc3339(),
+            direction: TransactionDirection::Inbound.to_string(),
+            status: if tx.cancelled {
+                "cancelled"
+            } else {
+                export_status(&tx.status)
+            }
+            .to_string(),
+            amount: tx.amount.as_u64(),
+            fee: 0,
+            source_public_key: tx.source_public_key.to_hex(),
+            destination_public_key: own_public_key.to_hex(),
+            confirmations: None,
+            mined_height: None,
+            mined_in_block: None,
+            kernel_excesses: Vec::new(),
+            message: tx.message,
+            cancellation_reason: None,
+            time: tx.timestamp,
+        }
+    }
+
+    fn from_outbound(tx: OutboundTransaction, own_public_key: &PublicKey) -> Self {
+        Self {
+            tx_id: tx.tx_id.as_u64(),
+            timestamp: DateTime::<Utc>::from_utc(tx.timestamp, Utc).to_rfc3339(),
+            direction: TransactionDirection::Outbound.to_string(),
+            status: if tx.cancelled {
+                "cancelled"
+            } else {
+                export_status(&tx.status)
+            }
+            .to_string(),
+            amount: tx.amount.as_u64(),
+            fee: tx.fee.as_u64(),
+            source_public_key: own_public_key.to_hex(),
+            destination_public_key: tx.destination_public_key.to_hex(),
+            confirmations: None,
+            mined_height: None,
+            mined_in_block: None,
+            kernel_excesses: Vec::new(),
+            message: tx.message,
+            cancellation_reason: None,
+            time: tx.timestamp,
+        }
+    }
+}
+
+fn export_status(status: &TransactionStatus) -> &'static str {
+    match status {
+        TransactionStatus::Completed => "completed",
+        TransactionStatus::Broadcast => "broadcast",
+        TransactionStatus::MinedUnconfirmed => "mined-unconfirmed",
+        TransactionStatus::MinedConfirmed => "mined-confirmed",
+        TransactionStatus::Imported => "imported",
+        TransactionStatus::Pending => "pending",
+        TransactionStatus::Coinbase => "coinbase",
+        TransactionStatus::Rejected => "rejected",
+        TransactionStatus::FauxUnconfirmed => "faux-unconfirmed",
+        TransactionStatus::FauxConfirmed => "faux-confirmed",
+        TransactionStatus::Queued => "queued",
+    }
+}
+
+/// Exports the complete transaction history, including pending and cancelled transactions, ordered by time
+pub async fn export_transactions(args: &[ParsedArgument], wallet: &WalletSqlite) -> Result<(), CommandError> {
+    use ParsedArgument::{Date, Text};
+    let mut from = None;
+    let mut to = None;
+    let mut status = None;
+    let mut format = "csv";
+    let mut output = "transactions.csv";
+    for pair in args.chunks(2) {
+        match pair {
+            [Text(flag), Date(date)] if flag == "--from" => from = Some(date.naive_utc()),
+            [Text(flag), Date(date)] if flag == "--to" => to = Some(date.naive_utc()),
+            [Text(flag), Text(value)] if flag == "--status" => status = Some(value.as_str()),
+            [Text(flag), Text(value)] if flag == "--format" => format = value.as_str(),
+            [Text(flag), Text(value)] if flag == "--output" => output = value.as_str(),
+            _ => return Err(CommandError::Argument),
+        }
+    }
+
+    let mut transaction_service = wallet.transaction_service.clone();
+    let own_public_key = wallet.comms.node_identity().public_key().clone();
+    let mut completed = transaction_service.get_completed_transactions().await?;
+    completed.extend(transaction_service.get_cancelled_completed_transactions().await?);
+    let mut inbound = transaction_service.get_pending_inbound_transactions().await?;
+    inbound.extend(transaction_service.get_cancelled_pending_inbound_transactions().await?);
+    let mut outbound = transaction_service.get_pending_outbound_transactions().await?;
+    outbound.extend(
+        transaction_service
+            .get_cancelled_pending_outbound_transactions()
+            .await?,
+    );
+
+    let mut transactions = completed
+        .into_values()
+        .map(ExportedTransaction::from_completed)
+        .chain(
+            inbound
+                .into_values()
+                .map(|tx| ExportedTransaction::from_inbound(tx, &own_public_key)),
+        )
+        .chain(
+            outbound
+                .into_values()
+                .map(|tx| ExportedTransaction::from_outbound(tx, &own_public_key)),
+        )
+        .collect::<Vec<_>>();
+    transactions.retain(|tx| {
+        from.map(|from| tx.time >= from).unwrap_or(true) &&
+            to.map(|to| tx.time <= to).unwrap_or(true) &&
+            status.map(|status| tx.status == status).unwrap_or(true)
+    });
+    transactions.sort_by(|a, b| a.time.cmp(&b.time).then(a.tx_id.cmp(&b.tx_id)));
+
+    if format == "json" {
+        let file = File::create(output).map_err(|e| CommandError::TransactionExport(e.to_string()))?;
+        serde_json::to_writer_pretty(file, &transactions)
+            .map_err(|e| CommandError::TransactionExport(e.to_string()))?;
+    } else {
+        write_transactions_to_csv_file(&transactions, output)?;
+    }
+    println!("Exported {} transaction(s) to {}", transactions.len(), output);
+    Ok(())
+}
+
+fn write_transactions_to_csv_file(transactions: &[ExportedTransaction], file_path: &str) -> Result<(), CommandError> {
+    // Quote every field, escaping embedded quotes by doubling them as RFC 4180 requires
+    fn field<T: ToString>(value: T) -> String {
+        format!("\"{}\"", value.to_string().replace('"', "\"\""))
+    }
+    fn optional<T: ToString>(value: &Option<T>) -> String {
+        field(value.as_ref().map(|v| v.to_string()).unwrap_or_default())
+    }
+
+    let file = File::create(file_path).map_err(|e| CommandError::CSVFile(e.to_string()))?;
+    let mut csv_file = LineWriter::new(file);
+    writeln!(
+        csv_file,
+        r##""tx_id","timestamp","direction","status","amount","fee","source_public_key","destination_public_key","confirmations","mined_height","mined_in_block","kernel_excesses","message","cancellation_reason""##
+    )
+    .map_err(|e| CommandError::CSVFile(e.to_string()))?;
+    for tx in transactions {
+        let fields = [
+            field(tx.tx_id),
+            field(&tx.timestamp),
+            field(&tx.direction),
+            field(&tx.status),
+            field(tx.amount),
+            field(tx.fee),
+            field(&tx.source_public_key),
+            field(&tx.destination_public_key),
+            optional(&tx.confirmations),
+            optional(&tx.mined_height),
+            optional(&tx.mined_in_block),
+            field(tx.kernel_excesses.join(";")),
+            field(&tx.message),
+            optional(&tx.cancellation_reason),
+        ];
+        writeln!(csv_file, "{}", fields.join(",")).map_err(|e| CommandError::CSVFile(e.to_string()))?;
+    }
+    Ok(())
+}
+
 fn write_utxos_to_csv_file(utxos: Vec<UnblindedOutput>, file_path: String) -> Result<(), CommandError> {
     let factory = PedersenCommitmentFactory::default();
     let file = File::create(file_path).map_err(|e| CommandError::CSVFile(e.to_string()))?;
@@ -27,6 +27,7 @@ use tari_common::exit_codes::{ExitCode, ExitError};
 use tari_core::transactions::{tari_amount::MicroTariError, transaction_components::TransactionError};
 use tari_utilities::hex::HexError;
 use tari_wallet::{
+    contacts_service::error::ContactsServiceError,
     error::{WalletError, WalletStorageError},
     key_manager_service::KeyManagerServiceError,
     output_manager_service::error::OutputManagerError,
@@ -52,6 +53,10 @@ pub enum CommandError {
     OutputManagerError(#[from] OutputManagerError),
     #[error("Key manager error: `{0}`")]
     KeyManagerError(#[from] KeyManagerServiceError),
+    #[error("Contacts service error: `{0}`")]
+    ContactsServiceError(#[from] ContactsServiceError),
+    #[error("Invalid contact: {0}")]
+    InvalidContact(String),
     #[error("Tokio join error `{0}`")]
     Join(#[from] JoinError),
     #[error("Config error `{0}`")]
@@ -60,6 +65,10 @@ pub enum CommandError {
     Comms(String),
     #[error("CSV file error `{0}`")]
     CSVFile(String),
+    #[error("Transaction export error `{0}`")]
+    TransactionExport(String),
+    #[error("Offline transaction error `{0}`")]
+    OfflineTransaction(String),
     #[error("Wallet error `{0}`")]
     WalletError(#[from] WalletError),
     #[error("Wallet storage error `{0}`")]
@@ -21,6 +21,7 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use config::Config;
+use tari_app_utilities::telemetry::TracingConfig;
 use tari_common::{configuration::CommonConfig, ConfigurationError, DefaultConfigLoader};
 use tari_p2p::PeerSeedsConfig;
 use tari_wallet::WalletConfig;
@@ -29,6 +30,7 @@ pub struct ApplicationConfig {
     pub common: CommonConfig,
     pub wallet: WalletConfig,
     pub peer_seeds: PeerSeedsConfig,
+    pub tracing: TracingConfig,
 }
 
 impl ApplicationConfig {
@@ -37,6 +39,7 @@ impl ApplicationConfig {
             common: CommonConfig::load_from(cfg)?,
             wallet: WalletConfig::load_from(cfg)?,
             peer_seeds: PeerSeedsConfig::load_from(cfg)?,
+            tracing: TracingConfig::load_from(cfg)?,
         };
 
         config.wallet.set_base_path(config.common.base_path());
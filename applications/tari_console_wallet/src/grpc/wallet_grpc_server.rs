@@ -35,6 +35,9 @@ use tari_app_grpc::{
         ClaimHtlcRefundResponse,
         ClaimShaAtomicSwapRequest,
         ClaimShaAtomicSwapResponse,
+        CoinJoinRequest,
+        CoinJoinResponse,
+        CoinSplitEvenRequest,
         CoinSplitRequest,
         CoinSplitResponse,
         CreateCommitteeDefinitionRequest,
@@ -43,6 +46,7 @@ use tari_app_grpc::{
         CreateFollowOnAssetCheckpointResponse,
         CreateInitialAssetCheckpointRequest,
         CreateInitialAssetCheckpointResponse,
+        ExportWatchOnlyKeyRequest,
         GetBalanceRequest,
         GetBalanceResponse,
         GetCoinbaseRequest,
@@ -50,6 +54,7 @@ use tari_app_grpc::{
         GetCompletedTransactionsRequest,
         GetCompletedTransactionsResponse,
         GetConnectivityRequest,
+        GetContactsResponse,
         GetIdentityRequest,
         GetIdentityResponse,
         GetOwnedAssetsResponse,
@@ -58,12 +63,20 @@ use tari_app_grpc::{
         GetUnspentAmountsResponse,
         GetVersionRequest,
         GetVersionResponse,
+        GetWatchOnlyBalancesResponse,
+        GetWatchOnlyOutputsResponse,
         ImportUtxosRequest,
         ImportUtxosResponse,
+        ImportWatchOnlyKeyRequest,
+        ImportWatchOnlyKeyResponse,
         MintTokensRequest,
         MintTokensResponse,
         RegisterAssetRequest,
         RegisterAssetResponse,
+        RemoveContactRequest,
+        RemoveContactResponse,
+        RemoveWatchOnlyKeyRequest,
+        RemoveWatchOnlyKeyResponse,
         RevalidateRequest,
         RevalidateResponse,
         SendShaAtomicSwapRequest,
@@ -76,10 +89,17 @@ use tari_app_grpc::{
         TransferRequest,
         TransferResponse,
         TransferResult,
+        TransferToManyRequest,
+        TransferToManyResponse,
+        UpsertContactRequest,
+        UpsertContactResponse,
+        ValidateAddressRequest,
+        ValidateAddressResponse,
     },
 };
 use tari_common_types::{
     array::copy_into_fixed_array,
+    emoji::EmojiId,
     types::{BlockHash, PublicKey, Signature},
 };
 use tari_comms::{multiaddr::Multiaddr, types::CommsPublicKey, CommsNode};
@@ -91,7 +111,16 @@ use tari_crypto::ristretto::RistrettoPublicKey;
 use tari_utilities::{hex::Hex, ByteArray, Hashable};
 use tari_wallet::{
     connectivity_service::{OnlineStatus, WalletConnectivityInterface},
-    output_manager_service::handle::OutputManagerHandle,
+    contacts_service::{
+        error::{ContactsServiceError, ContactsServiceStorageError},
+        storage::database::Contact,
+    },
+    error::WalletError,
+    output_manager_service::{
+        error::OutputManagerError,
+        handle::{OutputManagerHandle, PaymentRecipient},
+        storage::models::WatchOnlyKey,
+    },
     transaction_service::{handle::TransactionServiceHandle, storage::models},
     WalletSqlite,
 };
@@ -201,6 +230,44 @@ impl wallet_server::Wallet for WalletGrpcServer {
         Ok(Response::new(SetBaseNodeResponse {}))
     }
 
+    async fn validate_address(
+        &self,
+        request: Request<ValidateAddressRequest>,
+    ) -> Result<Response<ValidateAddressResponse>, Status> {
+        let message = request.into_inner();
+        let address = message.address.trim().replace('|', "");
+        let network = self.wallet.network.as_network().to_string();
+
+        let public_key = EmojiId::str_to_pubkey(&address).or_else(|_| PublicKey::from_hex(&address));
+        let resp = match public_key {
+            Ok(public_key) => ValidateAddressResponse {
+                is_valid: true,
+                public_key: public_key.to_vec(),
+                public_key_hex: public_key.to_hex(),
+                emoji_id: EmojiId::from_pubkey(&public_key).to_string(),
+                network,
+                ..Default::default()
+            },
+            Err(_) => {
+                let is_checksum_error = EmojiId::is_checksum_error(&address);
+                ValidateAddressResponse {
+                    is_valid: false,
+                    network,
+                    is_checksum_error,
+                    suggestions: EmojiId::suggestions(&address).iter().map(ToString::to_string).collect(),
+                    error_message: if is_checksum_error {
+                        "Emoji id checksum is incorrect".to_string()
+                    } else {
+                        "Address is not a valid emoji id or public key".to_string()
+                    },
+                    ..Default::default()
+                }
+            },
+        };
+
+        Ok(Response::new(resp))
+    }
+
     async fn get_balance(&self, _request: Request<GetBalanceRequest>) -> Result<Response<GetBalanceResponse>, Status> {
         let mut output_service = self.get_output_manager_service();
         let balance;
@@ -508,6 +575,53 @@ impl wallet_server::Wallet for WalletGrpcServer {
         Ok(Response::new(TransferResponse { results }))
     }
 
+    async fn transfer_to_many(
+        &self,
+        request: Request<TransferToManyRequest>,
+    ) -> Result<Response<TransferToManyResponse>, Status> {
+        let message = request.into_inner();
+        let recipients = message
+            .recipients
+            .into_iter()
+            .enumerate()
+            .map(|(idx, dest)| -> Result<_, String> {
+                let address = CommsPublicKey::from_hex(&dest.address)
+                    .map_err(|_| format!("Destination address at index {} is malformed", idx))?;
+                Ok(PaymentRecipient {
+                    address,
+                    amount: dest.amount.into(),
+                    payment_id: dest.payment_id,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Status::invalid_argument)?;
+
+        let mut transaction_service = self.get_transaction_service();
+        let response = match transaction_service
+            .send_one_sided_to_many_transaction(recipients, message.fee_per_gram.into(), message.message)
+            .await
+        {
+            Ok(tx_id) => TransferToManyResponse {
+                transaction_id: tx_id.into(),
+                is_success: true,
+                failure_message: Default::default(),
+            },
+            Err(err) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to send transaction to many recipients: {}", err
+                );
+                TransferToManyResponse {
+                    transaction_id: Default::default(),
+                    is_success: false,
+                    failure_message: err.to_string(),
+                }
+            },
+        };
+
+        Ok(Response::new(response))
+    }
+
     async fn get_transaction_info(
         &self,
         request: Request<GetTransactionInfoRequest>,
@@ -623,6 +737,243 @@ impl wallet_server::Wallet for WalletGrpcServer {
         Ok(Response::new(CoinSplitResponse { tx_id: tx_id.into() }))
     }
 
+    async fn coin_split_even(
+        &self,
+        request: Request<CoinSplitEvenRequest>,
+    ) -> Result<Response<CoinSplitResponse>, Status> {
+        let message = request.into_inner();
+
+        let lock_height = if message.lock_height == 0 {
+            None
+        } else {
+            Some(message.lock_height)
+        };
+
+        let mut wallet = self.wallet.clone();
+
+        let tx_id = wallet
+            .coin_split_even(
+                message.split_count as usize,
+                MicroTari::from(message.fee_per_gram),
+                message.message,
+                lock_height,
+            )
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        Ok(Response::new(CoinSplitResponse { tx_id: tx_id.into() }))
+    }
+
+    async fn coin_join(&self, request: Request<CoinJoinRequest>) -> Result<Response<CoinJoinResponse>, Status> {
+        let message = request.into_inner();
+
+        let mut wallet = self.wallet.clone();
+
+        let tx_id = wallet
+            .coin_join(
+                MicroTari::from(message.dust_threshold),
+                message.max_inputs as usize,
+                MicroTari::from(message.fee_per_gram),
+                message.message,
+            )
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        Ok(Response::new(CoinJoinResponse { tx_id: tx_id.into() }))
+    }
+
+    async fn export_watch_only_key(
+        &self,
+        request: Request<ExportWatchOnlyKeyRequest>,
+    ) -> Result<Response<tari_rpc::WatchOnlyKey>, Status> {
+        let message = request.into_inner();
+        let mut wallet = self.wallet.clone();
+
+        let key = wallet
+            .export_watch_only_key(message.label)
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
+        Ok(Response::new(tari_rpc::WatchOnlyKey {
+            spend_public_key: key.spend_public_key.to_vec(),
+            rewind_public_key: key.rewind_public_key.to_vec(),
+            rewind_blinding_public_key: key.rewind_blinding_public_key.to_vec(),
+            label: key.label,
+        }))
+    }
+
+    async fn import_watch_only_key(
+        &self,
+        request: Request<ImportWatchOnlyKeyRequest>,
+    ) -> Result<Response<ImportWatchOnlyKeyResponse>, Status> {
+        let key = request
+            .into_inner()
+            .key
+            .ok_or_else(|| Status::invalid_argument("Watch-only key not provided"))?;
+        let public_key = |bytes: &[u8], name: &str| {
+            PublicKey::from_bytes(bytes).map_err(|e| Status::invalid_argument(format!("Invalid {}: {}", name, e)))
+        };
+        let key = WatchOnlyKey {
+            spend_public_key: public_key(&key.spend_public_key, "spend public key")?,
+            rewind_public_key: public_key(&key.rewind_public_key, "rewind public key")?,
+            rewind_blinding_public_key: public_key(&key.rewind_blinding_public_key, "rewind blinding public key")?,
+            label: key.label,
+        };
+
+        let mut wallet = self.wallet.clone();
+        wallet.add_watch_only_key(key).await.map_err(|e| match e {
+            WalletError::OutputManagerError(OutputManagerError::InvalidWatchOnlyKey(_)) => {
+                Status::invalid_argument(e.to_string())
+            },
+            _ => Status::internal(e.to_string()),
+        })?;
+
+        Ok(Response::new(ImportWatchOnlyKeyResponse {}))
+    }
+
+    async fn remove_watch_only_key(
+        &self,
+        request: Request<RemoveWatchOnlyKeyRequest>,
+    ) -> Result<Response<RemoveWatchOnlyKeyResponse>, Status> {
+        let message = request.into_inner();
+        let spend_public_key = PublicKey::from_bytes(&message.spend_public_key)
+            .map_err(|e| Status::invalid_argument(format!("Invalid spend public key: {}", e)))?;
+
+        let mut output_manager = self.get_output_manager_service();
+        output_manager
+            .remove_watch_only_key(spend_public_key)
+            .await
+            .map_err(|e| Status::not_found(e.to_string()))?;
+
+        Ok(Response::new(RemoveWatchOnlyKeyResponse {}))
+    }
+
+    async fn get_watch_only_balances(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<GetWatchOnlyBalancesResponse>, Status> {
+        let mut output_manager = self.get_output_manager_service();
+        let balances = output_manager
+            .get_watch_only_balances()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(GetWatchOnlyBalancesResponse {
+            balances: balances
+                .into_iter()
+                .map(|b| tari_rpc::WatchOnlyBalance {
+                    spend_public_key: b.spend_public_key.to_vec(),
+                    label: b.label,
+                    received: b.received.into(),
+                    num_outputs: b.num_outputs as u64,
+                    num_outputs_with_unknown_value: b.num_outputs_with_unknown_value as u64,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_watch_only_outputs(
+        &self,
+        _: Request<tari_rpc::Empty>,
+    ) -> Result<Response<GetWatchOnlyOutputsResponse>, Status> {
+        let mut output_manager = self.get_output_manager_service();
+        let outputs = output_manager
+            .get_watch_only_outputs()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(GetWatchOnlyOutputsResponse {
+            outputs: outputs
+                .into_iter()
+                .map(|o| tari_rpc::WatchOnlyOutput {
+                    commitment: o.commitment.to_vec(),
+                    spend_public_key: o.spend_public_key.to_vec(),
+                    value: o.value.map(u64::from).unwrap_or_default(),
+                    value_known: o.value.is_some(),
+                    mined_height: o.mined_height,
+                    mined_in_block: o.mined_in_block,
+                    unsignable: true,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_contacts(&self, _: Request<tari_rpc::Empty>) -> Result<Response<GetContactsResponse>, Status> {
+        let mut contacts_service = self.wallet.contacts_service.clone();
+        let contacts = contacts_service
+            .get_contacts()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(GetContactsResponse {
+            contacts: contacts.into_iter().map(convert_contact).collect(),
+        }))
+    }
+
+    async fn upsert_contact(
+        &self,
+        request: Request<UpsertContactRequest>,
+    ) -> Result<Response<UpsertContactResponse>, Status> {
+        let message = request.into_inner();
+        let address = message.address.trim().replace('|', "");
+        let public_key = EmojiId::str_to_pubkey(&address)
+            .or_else(|_| PublicKey::from_hex(&address))
+            .map_err(|_| {
+                if EmojiId::is_checksum_error(&address) {
+                    Status::invalid_argument("Emoji id checksum is incorrect")
+                } else {
+                    Status::invalid_argument("Address is not a valid emoji id or public key")
+                }
+            })?;
+        if public_key == *self.wallet.comms.node_identity().public_key() {
+            return Err(Status::invalid_argument(
+                "This wallet cannot be added to its own address book",
+            ));
+        }
+
+        let mut contacts_service = self.wallet.contacts_service.clone();
+        contacts_service
+            .upsert_contact(Contact::new(message.alias, public_key.clone(), None, None))
+            .await
+            .map_err(|e| match e {
+                ContactsServiceError::InvalidAlias(_) | ContactsServiceError::DuplicateAlias(_) => {
+                    Status::invalid_argument(e.to_string())
+                },
+                _ => Status::internal(e.to_string()),
+            })?;
+        let contact = contacts_service
+            .get_contact(public_key)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(UpsertContactResponse {
+            contact: Some(convert_contact(contact)),
+        }))
+    }
+
+    async fn remove_contact(
+        &self,
+        request: Request<RemoveContactRequest>,
+    ) -> Result<Response<RemoveContactResponse>, Status> {
+        let message = request.into_inner();
+        let mut contacts_service = self.wallet.contacts_service.clone();
+        let contact = contacts_service
+            .get_contact_by_alias(message.alias)
+            .await
+            .map_err(|e| match e {
+                ContactsServiceError::ContactsServiceStorageError(ContactsServiceStorageError::ValueNotFound(_)) => {
+                    Status::not_found(e.to_string())
+                },
+                _ => Status::internal(e.to_string()),
+            })?;
+        contacts_service
+            .remove_contact(contact.public_key)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(RemoveContactResponse {}))
+    }
+
     async fn import_utxos(
         &self,
         request: Request<ImportUtxosRequest>,
@@ -981,6 +1332,17 @@ impl wallet_server::Wallet for WalletGrpcServer {
     }
 }
 
+fn convert_contact(contact: Contact) -> tari_rpc::Contact {
+    tari_rpc::Contact {
+        alias: contact.alias,
+        public_key: contact.public_key.to_vec(),
+        emoji_id: EmojiId::from_pubkey(&contact.public_key).to_string(),
+        last_seen: contact.last_seen.map(naive_datetime_to_timestamp),
+        last_transaction_id: contact.last_transaction_id.map(u64::from).unwrap_or_default(),
+        last_transaction_timestamp: contact.last_transaction_at.map(naive_datetime_to_timestamp),
+    }
+}
+
 fn convert_wallet_transaction_into_transaction_info(
     tx: models::WalletTransaction,
     wallet_pk: &CommsPublicKey,
@@ -125,26 +125,47 @@ pub async fn wallet_recovery(
             Ok(UtxoScannerEvent::ConnectedToBaseNode(_, latency)) => {
                 println!("OK (latency = {:.2?})", latency);
             },
+            Ok(UtxoScannerEvent::ResumedFromCheckpoint {
+                height,
+                num_outputs_found,
+            }) => {
+                let s = format!(
+                    "Resuming recovery from block {} ({} outputs found so far).",
+                    height, num_outputs_found
+                );
+                println!("{}", s);
+                info!(target: LOG_TARGET, "{}", s);
+            },
+            Ok(UtxoScannerEvent::RestartedFromBirthday { checkpoint_height }) => {
+                let s = format!(
+                    "Recovery checkpoint at block {} is not on the base node's chain, restarting recovery.",
+                    checkpoint_height
+                );
+                println!("{}", s);
+                warn!(target: LOG_TARGET, "{}", s);
+            },
             Ok(UtxoScannerEvent::Progress {
                 current_height,
                 tip_height,
+                num_outputs_found,
+                estimated_time_remaining,
+                ..
             }) => {
                 let percentage_progress = ((current_height as f32) * 100f32 / (tip_height as f32)).round() as u32;
-                debug!(
-                    target: LOG_TARGET,
-                    "{}: Recovery process {}% complete (Block {} of {}).",
-                    Local::now(),
-                    percentage_progress,
-                    current_height,
-                    tip_height
-                );
-                println!(
-                    "{}: Recovery process {}% complete (Block {} of {}).",
+                let eta = estimated_time_remaining
+                    .map(|eta| format!("{:.0?}", eta))
+                    .unwrap_or_else(|| "unknown".to_string());
+                let s = format!(
+                    "{}: Recovery process {}% complete (Block {} of {}, {} outputs found, {} remaining).",
                     Local::now(),
                     percentage_progress,
                     current_height,
-                    tip_height
+                    tip_height,
+                    num_outputs_found,
+                    eta
                 );
+                debug!(target: LOG_TARGET, "{}", s);
+                println!("{}", s);
             },
             Ok(UtxoScannerEvent::ScanningRoundFailed {
                 num_retries,
@@ -35,6 +35,7 @@ use crate::{
     ui::{
         components::{
             assets_tab::AssetsTab,
+            balance_history_tab::BalanceHistoryTab,
             base_node::BaseNode,
             contacts_tab::ContactsTab,
             events_component::EventsComponent,
@@ -90,6 +91,7 @@ impl<B: Backend> App<B> {
 
         let tabs = TabsContainer::<B>::new(title.clone())
             .add("Transactions".into(), Box::new(TransactionsTab::new()))
+            .add("History".into(), Box::new(BalanceHistoryTab::new()))
             .add("Send".into(), Box::new(SendTab::new(&app_state)))
             .add("Receive".into(), Box::new(ReceiveTab::new()))
             .add("Contacts".into(), Box::new(ContactsTab::new()))
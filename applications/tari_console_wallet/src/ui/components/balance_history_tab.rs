@@ -0,0 +1,107 @@
+// Copyright 2022 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+// This tab charts the wallet balance over time. The buckets come precomputed from the AppState, which rebuilds them
+// whenever the transaction list changes.
+
+use tari_core::transactions::tari_amount::T;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols,
+    text::{Span, Spans},
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::ui::{
+    components::Component,
+    state::{AppState, BalanceHistoryPeriod},
+};
+
+pub struct BalanceHistoryTab {
+    period: BalanceHistoryPeriod,
+}
+
+impl BalanceHistoryTab {
+    pub fn new() -> Self {
+        Self {
+            period: BalanceHistoryPeriod::Day,
+        }
+    }
+
+    fn draw_chart<B>(&self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
+    where B: Backend {
+        let buckets = app_state.get_balance_history(self.period);
+        let title = format!("{} Balance", self.period);
+        let block = Block::default().borders(Borders::ALL).title(Span::styled(
+            title.as_str(),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        ));
+        if buckets.is_empty() {
+            let paragraph = Paragraph::new("No mined transactions yet").block(block);
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        let points = buckets
+            .iter()
+            .enumerate()
+            .map(|(i, bucket)| (i as f64, f64::from(bucket.balance) / f64::from(T)))
+            .collect::<Vec<_>>();
+        let max_balance = points.iter().map(|(_, y)| *y).fold(0.0, f64::max);
+        let max_x = (buckets.len() - 1).max(1) as f64;
+        let first = buckets[0].start.format("%Y-%m-%d").to_string();
+        let last = buckets[buckets.len() - 1].start.format("%Y-%m-%d").to_string();
+
+        let datasets = vec![Dataset::default()
+            .name("Balance (T)")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Cyan))
+            .data(&points)];
+        let chart = Chart::new(datasets)
+            .block(block)
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, max_x])
+                    .labels(vec![Span::raw(first), Span::raw(last)]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, max_balance * 1.1])
+                    .labels(vec![
+                        Span::raw("0"),
+                        Span::raw(format!("{:.2}", max_balance / 2.0)),
+                        Span::raw(format!("{:.2}", max_balance)),
+                    ]),
+            );
+        f.render_widget(chart, area);
+    }
+}
+
+impl<B: Backend> Component<B> for BalanceHistoryTab {
+    fn draw(&mut self, f: &mut Frame<B>, area: Rect, app_state: &AppState) {
+        let areas = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Min(10)].as_ref())
+            .split(area);
+
+        let instructions = Paragraph::new(Spans::from(vec![
+            Span::raw("Press "),
+            Span::styled("P", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" to switch between daily and weekly balances"),
+        ]))
+        .wrap(Wrap { trim: false });
+        f.render_widget(instructions, areas[0]);
+        self.draw_chart(f, areas[1], app_state);
+    }
+
+    fn on_key(&mut self, _app_state: &mut AppState, c: char) {
+        if c == 'p' {
+            self.period = self.period.toggle();
+        }
+    }
+}
@@ -22,6 +22,7 @@
 
 pub mod assets_tab;
 pub mod balance;
+pub mod balance_history_tab;
 pub mod base_node;
 mod component;
 pub mod log_tab;
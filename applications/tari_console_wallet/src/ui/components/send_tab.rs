@@ -31,6 +31,7 @@ pub struct SendTab {
     to_field: String,
     amount_field: String,
     fee_field: String,
+    fee_field_edited: bool,
     message_field: String,
     error_message: Option<String>,
     success_message: Option<String>,
@@ -50,7 +51,8 @@ impl SendTab {
             show_contacts: false,
             to_field: String::new(),
             amount_field: String::new(),
-            fee_field: app_state.get_default_fee_per_gram().as_u64().to_string(),
+            fee_field: app_state.get_suggested_fee_per_gram().as_u64().to_string(),
+            fee_field_edited: false,
             message_field: String::new(),
             error_message: None,
             success_message: None,
@@ -63,7 +65,7 @@ impl SendTab {
         }
     }
 
-    fn draw_send_form<B>(&self, f: &mut Frame<B>, area: Rect, _app_state: &AppState)
+    fn draw_send_form<B>(&self, f: &mut Frame<B>, area: Rect, app_state: &AppState)
     where B: Backend {
         let block = Block::default().borders(Borders::ALL).title(Span::styled(
             "Send Transaction",
@@ -149,7 +151,7 @@ impl SendTab {
                 SendInputMode::Fee => Style::default().fg(Color::Magenta),
                 _ => Style::default(),
             })
-            .block(Block::default().borders(Borders::ALL).title("(F)ee-per-gram (uT):"));
+            .block(Block::default().borders(Borders::ALL).title(fee_field_title(app_state)));
         f.render_widget(fee_input, amount_fee_layout[1]);
 
         let message_input = Paragraph::new(self.message_field.as_ref())
@@ -344,7 +346,8 @@ impl SendTab {
                                 self.to_field = "".to_string();
                                 self.amount_field = "".to_string();
                                 self.selected_unique_id = None;
-                                self.fee_field = app_state.get_default_fee_per_gram().as_u64().to_string();
+                                self.fee_field = app_state.get_suggested_fee_per_gram().as_u64().to_string();
+                                self.fee_field_edited = false;
                                 self.message_field = "".to_string();
                                 self.send_input_mode = SendInputMode::None;
                                 self.send_result_watch = Some(rx);
@@ -394,6 +397,7 @@ impl SendTab {
                     c => {
                         if c.is_numeric() {
                             self.fee_field.push(c);
+                            self.fee_field_edited = true;
                         }
                         return KeyHandled::Handled;
                     },
@@ -669,6 +673,7 @@ impl<B: Backend> Component<B> for SendTab {
             },
             SendInputMode::Fee => {
                 let _ = self.fee_field.pop();
+                self.fee_field_edited = true;
             },
             SendInputMode::Message => {
                 let _ = self.message_field.pop();
@@ -676,6 +681,32 @@ impl<B: Backend> Component<B> for SendTab {
             SendInputMode::None => {},
         }
     }
+
+    fn on_tick(&mut self, app_state: &mut AppState) {
+        // Keep the prefilled fee up to date with the latest estimate until the user changes it
+        if !self.fee_field_edited {
+            self.fee_field = app_state.get_suggested_fee_per_gram().as_u64().to_string();
+        }
+    }
+}
+
+/// The fee field title, including the base node's fee suggestions for each confirmation window if available
+fn fee_field_title(app_state: &AppState) -> String {
+    match app_state.get_fee_estimates() {
+        Some(estimates) if !estimates.estimates.is_empty() => {
+            let suggestions = estimates
+                .estimates
+                .iter()
+                .map(|e| match e.target_blocks {
+                    1 => format!("next block: {}", e.fee_per_gram.as_u64()),
+                    n => format!("{} blocks: {}", n, e.fee_per_gram.as_u64()),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("(F)ee-per-gram (uT) [{}]:", suggestions)
+        },
+        _ => "(F)ee-per-gram (uT):".to_string(),
+    }
 }
 
 #[derive(PartialEq, Debug)]
@@ -43,16 +43,22 @@ use tari_comms::{
     types::CommsPublicKey,
     NodeIdentity,
 };
-use tari_core::transactions::{
-    tari_amount::{uT, MicroTari},
-    weight::TransactionWeight,
+use tari_core::{
+    base_node::proto::wallet_rpc::FeeEstimates,
+    transactions::{
+        tari_amount::{uT, MicroTari},
+        weight::TransactionWeight,
+    },
 };
 use tari_crypto::ristretto::RistrettoPublicKey;
 use tari_shutdown::ShutdownSignal;
 use tari_utilities::hex::Hex;
 use tari_wallet::{
     assets::Asset,
-    base_node_service::{handle::BaseNodeEventReceiver, service::BaseNodeState},
+    base_node_service::{
+        handle::{BaseNodeEventReceiver, BaseNodeServiceHandle},
+        service::BaseNodeState,
+    },
     connectivity_service::{OnlineStatus, WalletConnectivityHandle, WalletConnectivityInterface},
     contacts_service::{handle::ContactsLivenessEvent, storage::database::Contact},
     output_manager_service::{handle::OutputManagerEventReceiver, service::Balance},
@@ -73,6 +79,7 @@ use crate::{
     notifier::Notifier,
     ui::{
         state::{
+            balance_history::{BalanceHistory, BalanceHistoryBucket, BalanceHistoryPeriod},
             debouncer::BalanceEnquiryDebouncer,
             tasks::{send_one_sided_transaction_task, send_transaction_task},
             wallet_event_monitor::WalletEventMonitor,
@@ -85,6 +92,8 @@ use crate::{
 };
 
 const LOG_TARGET: &str = "wallet::console_wallet::app_state";
+/// The confirmation window, in blocks, of the fee estimate used to prefill the fee when sending a transaction
+const SUGGESTED_FEE_TARGET_BLOCKS: u64 = 6;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -131,7 +140,11 @@ impl AppState {
 
     pub async fn start_event_monitor(&self, notifier: Notifier) {
         let balance_enquiry_debounce_tx = self.balance_enquiry_debouncer.clone().get_sender();
-        let event_monitor = WalletEventMonitor::new(self.inner.clone(), balance_enquiry_debounce_tx);
+        let event_monitor = WalletEventMonitor::new(
+            self.inner.clone(),
+            balance_enquiry_debounce_tx,
+            self.wallet_config.notifications.clone(),
+        );
         tokio::spawn(event_monitor.run(notifier));
     }
 
@@ -431,6 +444,10 @@ impl AppState {
         }
     }
 
+    pub fn get_balance_history(&self, period: BalanceHistoryPeriod) -> &[BalanceHistoryBucket] {
+        self.cached_data.balance_history.buckets(period)
+    }
+
     pub fn get_confirmations(&self, tx_id: TxId) -> Option<&u64> {
         (&self.cached_data.confirmations).get(&tx_id)
     }
@@ -547,6 +564,19 @@ impl AppState {
         self.wallet_config.fee_per_gram.into()
     }
 
+    pub fn get_fee_estimates(&self) -> Option<&FeeEstimates> {
+        self.cached_data.fee_estimates.as_ref()
+    }
+
+    /// The fee per gram to prefill when sending a transaction. This is the base node's estimate for the transaction to
+    /// be mined within a few blocks, or the configured default if no estimate is available.
+    pub fn get_suggested_fee_per_gram(&self) -> MicroTari {
+        self.get_fee_estimates()
+            .and_then(|estimates| estimates.for_target(SUGGESTED_FEE_TARGET_BLOCKS))
+            .map(|estimate| estimate.fee_per_gram)
+            .unwrap_or_else(|| self.get_default_fee_per_gram())
+    }
+
     pub async fn get_network(&self) -> Network {
         self.inner.read().await.get_network()
     }
@@ -667,6 +697,7 @@ impl AppStateInner {
             .iter()
             .map(|tx| CompletedTransactionInfo::from_completed_transaction(tx.clone(), &self.get_transaction_weight()))
             .collect();
+        self.data.balance_history = BalanceHistory::from_transactions(&self.data.completed_txs);
         self.updated = true;
         Ok(())
     }
@@ -740,6 +771,7 @@ impl AppStateInner {
                         .partial_cmp(&a.timestamp)
                         .expect("Should be able to compare timestamps")
                 });
+                self.data.balance_history = BalanceHistory::from_transactions(&self.data.completed_txs);
             },
         }
         self.refresh_assets_state().await?;
@@ -823,6 +855,17 @@ impl AppStateInner {
         Ok(())
     }
 
+    pub fn get_fee_estimates(&self) -> Option<&FeeEstimates> {
+        self.data.fee_estimates.as_ref()
+    }
+
+    pub async fn refresh_fee_estimates(&mut self, estimates: FeeEstimates) -> Result<(), UiError> {
+        self.data.fee_estimates = Some(estimates);
+        self.updated = true;
+
+        Ok(())
+    }
+
     pub async fn refresh_base_node_peer(&mut self, peer: Peer) -> Result<(), UiError> {
         self.data.base_node_selected = peer;
         self.updated = true;
@@ -858,6 +901,10 @@ impl AppStateInner {
         self.wallet.base_node_service.get_event_stream()
     }
 
+    pub fn get_base_node_service(&self) -> BaseNodeServiceHandle {
+        self.wallet.base_node_service.clone()
+    }
+
     pub async fn set_base_node_peer(&mut self, peer: Peer) -> Result<(), UiError> {
         self.wallet
             .set_base_node_peer(
@@ -1094,12 +1141,14 @@ struct AppStateData {
     owned_tokens: Vec<Token>,
     pending_txs: Vec<CompletedTransactionInfo>,
     completed_txs: Vec<CompletedTransactionInfo>,
+    balance_history: BalanceHistory,
     confirmations: HashMap<TxId, u64>,
     my_identity: MyIdentity,
     contacts: Vec<UiContact>,
     connected_peers: Vec<Peer>,
     balance: Balance,
     base_node_state: BaseNodeState,
+    fee_estimates: Option<FeeEstimates>,
     base_node_selected: Peer,
     base_node_previous: Peer,
     base_node_list: Vec<(String, Peer)>,
@@ -1169,12 +1218,14 @@ impl AppStateData {
             owned_tokens: Vec::new(),
             pending_txs: Vec::new(),
             completed_txs: Vec::new(),
+            balance_history: BalanceHistory::default(),
             confirmations: HashMap::new(),
             my_identity: identity,
             contacts: Vec::new(),
             connected_peers: Vec::new(),
             balance: Balance::zero(),
             base_node_state: BaseNodeState::default(),
+            fee_estimates: None,
             base_node_selected,
             base_node_previous,
             base_node_list,
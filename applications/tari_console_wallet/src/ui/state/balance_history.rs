@@ -0,0 +1,199 @@
+// Copyright 2022 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+// Aggregates the wallet's completed transactions into daily or weekly balance buckets for the balance history chart.
+// The buckets are rebuilt whenever the cached transaction list changes, so drawing the chart never touches the
+// transaction list.
+
+use std::fmt::{Display, Error, Formatter};
+
+use chrono::{Datelike, Duration, NaiveDate};
+use tari_common_types::transaction::{TransactionDirection, TransactionStatus};
+use tari_core::transactions::tari_amount::MicroTari;
+
+use crate::ui::state::CompletedTransactionInfo;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BalanceHistoryPeriod {
+    Day,
+    Week,
+}
+
+impl BalanceHistoryPeriod {
+    pub fn toggle(self) -> Self {
+        match self {
+            BalanceHistoryPeriod::Day => BalanceHistoryPeriod::Week,
+            BalanceHistoryPeriod::Week => BalanceHistoryPeriod::Day,
+        }
+    }
+
+    /// The first day of the bucket that `date` falls into. Weekly buckets start on a Monday.
+    fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            BalanceHistoryPeriod::Day => date,
+            BalanceHistoryPeriod::Week => date - Duration::days(i64::from(date.weekday().num_days_from_monday())),
+        }
+    }
+
+    fn length(self) -> Duration {
+        match self {
+            BalanceHistoryPeriod::Day => Duration::days(1),
+            BalanceHistoryPeriod::Week => Duration::weeks(1),
+        }
+    }
+}
+
+impl Display for BalanceHistoryPeriod {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            BalanceHistoryPeriod::Day => write!(f, "Daily"),
+            BalanceHistoryPeriod::Week => write!(f, "Weekly"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BalanceHistoryBucket {
+    pub start: NaiveDate,
+    pub received: MicroTari,
+    pub spent: MicroTari,
+    /// The balance at the end of the bucket
+    pub balance: MicroTari,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BalanceHistory {
+    daily: Vec<BalanceHistoryBucket>,
+    weekly: Vec<BalanceHistoryBucket>,
+}
+
+impl BalanceHistory {
+    pub fn from_transactions<'a, I>(transactions: I) -> Self
+    where I: IntoIterator<Item = &'a CompletedTransactionInfo> {
+        let changes = transactions.into_iter().filter_map(balance_change).collect::<Vec<_>>();
+        Self {
+            daily: aggregate(&changes, BalanceHistoryPeriod::Day),
+            weekly: aggregate(&changes, BalanceHistoryPeriod::Week),
+        }
+    }
+
+    pub fn buckets(&self, period: BalanceHistoryPeriod) -> &[BalanceHistoryBucket] {
+        match period {
+            BalanceHistoryPeriod::Day => &self.daily,
+            BalanceHistoryPeriod::Week => &self.weekly,
+        }
+    }
+}
+
+/// The date and the (received, spent) amounts a transaction contributed to the balance, if it has affected the
+/// balance at all. Only mined or imported transactions count; a transaction sent to ourselves only costs its fee.
+fn balance_change(tx: &CompletedTransactionInfo) -> Option<(NaiveDate, MicroTari, MicroTari)> {
+    if tx.cancelled.is_some() {
+        return None;
+    }
+    if !matches!(
+        tx.status,
+        TransactionStatus::MinedUnconfirmed |
+            TransactionStatus::MinedConfirmed |
+            TransactionStatus::Imported |
+            TransactionStatus::FauxUnconfirmed |
+            TransactionStatus::FauxConfirmed
+    ) {
+        return None;
+    }
+    let date = tx.timestamp.date();
+    if tx.source_public_key == tx.destination_public_key {
+        return Some((date, MicroTari::from(0), tx.fee));
+    }
+    match tx.direction {
+        TransactionDirection::Inbound => Some((date, tx.amount, MicroTari::from(0))),
+        TransactionDirection::Outbound => Some((date, MicroTari::from(0), tx.amount + tx.fee)),
+        TransactionDirection::Unknown => None,
+    }
+}
+
+/// Sums the changes into consecutive buckets from the first to the last change. Buckets without any activity are
+/// included so that the chart's time axis stays linear.
+fn aggregate(changes: &[(NaiveDate, MicroTari, MicroTari)], period: BalanceHistoryPeriod) -> Vec<BalanceHistoryBucket> {
+    let first = match changes.iter().map(|(date, _, _)| *date).min() {
+        Some(date) => period.bucket_start(date),
+        None => return Vec::new(),
+    };
+    let last = changes
+        .iter()
+        .map(|(date, _, _)| period.bucket_start(*date))
+        .max()
+        .unwrap_or(first);
+
+    let num_buckets = ((last - first).num_days() / period.length().num_days()) as usize + 1;
+    let mut buckets = (0..num_buckets)
+        .map(|i| BalanceHistoryBucket {
+            start: first + period.length() * i as i32,
+            received: MicroTari::from(0),
+            spent: MicroTari::from(0),
+            balance: MicroTari::from(0),
+        })
+        .collect::<Vec<_>>();
+
+    for (date, received, spent) in changes {
+        let index = ((period.bucket_start(*date) - first).num_days() / period.length().num_days()) as usize;
+        buckets[index].received += *received;
+        buckets[index].spent += *spent;
+    }
+
+    let mut balance = MicroTari::from(0);
+    for bucket in &mut buckets {
+        balance = (balance + bucket.received).saturating_sub(bucket.spent);
+        bucket.balance = balance;
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd(y, m, d)
+    }
+
+    #[test]
+    fn it_fills_empty_days_and_tracks_the_running_balance() {
+        let changes = vec![
+            (date(2022, 3, 3), MicroTari::from(0), MicroTari::from(40)),
+            (date(2022, 3, 1), MicroTari::from(100), MicroTari::from(0)),
+            (date(2022, 3, 1), MicroTari::from(50), MicroTari::from(10)),
+        ];
+        let buckets = aggregate(&changes, BalanceHistoryPeriod::Day);
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(buckets[0].start, date(2022, 3, 1));
+        assert_eq!(buckets[0].received, MicroTari::from(150));
+        assert_eq!(buckets[0].balance, MicroTari::from(140));
+        assert_eq!(buckets[1].start, date(2022, 3, 2));
+        assert_eq!(buckets[1].balance, MicroTari::from(140));
+        assert_eq!(buckets[2].spent, MicroTari::from(40));
+        assert_eq!(buckets[2].balance, MicroTari::from(100));
+    }
+
+    #[test]
+    fn it_groups_weeks_starting_on_monday() {
+        // 2022-03-06 is a Sunday, 2022-03-07 a Monday
+        let changes = vec![
+            (date(2022, 3, 6), MicroTari::from(10), MicroTari::from(0)),
+            (date(2022, 3, 7), MicroTari::from(20), MicroTari::from(0)),
+            (date(2022, 3, 13), MicroTari::from(30), MicroTari::from(0)),
+        ];
+        let buckets = aggregate(&changes, BalanceHistoryPeriod::Week);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].start, date(2022, 2, 28));
+        assert_eq!(buckets[0].balance, MicroTari::from(10));
+        assert_eq!(buckets[1].start, date(2022, 3, 7));
+        assert_eq!(buckets[1].received, MicroTari::from(50));
+        assert_eq!(buckets[1].balance, MicroTari::from(60));
+    }
+
+    #[test]
+    fn it_returns_no_buckets_without_changes() {
+        assert!(aggregate(&[], BalanceHistoryPeriod::Week).is_empty());
+    }
+}
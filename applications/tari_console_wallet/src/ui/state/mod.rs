@@ -21,8 +21,9 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 mod app_state;
+mod balance_history;
 mod debouncer;
 mod tasks;
 mod wallet_event_monitor;
 
-pub use self::app_state::*;
+pub use self::{app_state::*, balance_history::*};
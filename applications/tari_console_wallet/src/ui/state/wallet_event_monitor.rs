@@ -20,7 +20,12 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{ops::Deref, sync::Arc};
+use std::{
+    fmt,
+    ops::Deref,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use log::*;
 use tari_common_types::transaction::TxId;
@@ -29,10 +34,14 @@ use tari_wallet::{
     base_node_service::{handle::BaseNodeEvent, service::BaseNodeState},
     connectivity_service::WalletConnectivityInterface,
     contacts_service::handle::ContactsLivenessEvent,
+    notifications::{Notification, NotificationChannel, NotificationConfig, NotificationCoordinator},
     output_manager_service::handle::OutputManagerEvent,
     transaction_service::handle::TransactionEvent,
 };
-use tokio::sync::{broadcast, RwLock};
+use tokio::{
+    sync::{broadcast, RwLock},
+    time,
+};
 
 use crate::{
     notifier::Notifier,
@@ -44,16 +53,39 @@ const LOG_TARGET: &str = "wallet::console_wallet::wallet_event_monitor";
 pub struct WalletEventMonitor {
     app_state_inner: Arc<RwLock<AppStateInner>>,
     balance_enquiry_debounce_tx: broadcast::Sender<()>,
+    notifications: NotificationCoordinator<FauxTransactionNotification>,
+}
+
+/// Faux transaction notifications are rate limited, as a wallet recovery can produce hundreds of them in a short period
+#[derive(Debug, Clone, Copy)]
+enum FauxTransactionNotification {
+    Confirmed(TxId),
+    Unconfirmed(TxId, u64),
+}
+
+impl fmt::Display for FauxTransactionNotification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FauxTransactionNotification::Confirmed(tx_id) => write!(f, "Transaction Confirmed - TxId: {}", tx_id),
+            FauxTransactionNotification::Unconfirmed(tx_id, num_confirmations) => write!(
+                f,
+                "Transaction Mined Unconfirmed with {} confirmations - TxId: {}",
+                num_confirmations, tx_id
+            ),
+        }
+    }
 }
 
 impl WalletEventMonitor {
     pub fn new(
         app_state_inner: Arc<RwLock<AppStateInner>>,
         balance_enquiry_debounce_tx: broadcast::Sender<()>,
+        notification_config: NotificationConfig,
     ) -> Self {
         Self {
             app_state_inner,
             balance_enquiry_debounce_tx,
+            notifications: NotificationCoordinator::new(notification_config),
         }
     }
 
@@ -83,6 +115,9 @@ impl WalletEventMonitor {
 
         let mut contacts_liveness_events = self.app_state_inner.read().await.get_contacts_liveness_event_stream();
 
+        let mut digest_interval = time::interval(self.notifications.digest_window().max(Duration::from_secs(1)));
+        digest_interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
         info!(target: LOG_TARGET, "Wallet Event Monitor starting");
         loop {
             tokio::select! {
@@ -107,8 +142,7 @@ impl WalletEventMonitor {
                                         format!("Finalized Transaction Received - TxId: {}", tx_id)
                                     ).await;
                                 },
-                                TransactionEvent::TransactionMinedUnconfirmed{tx_id, num_confirmations, is_valid: _}  |
-                                TransactionEvent::FauxTransactionUnconfirmed{tx_id, num_confirmations, is_valid: _}=> {
+                                TransactionEvent::TransactionMinedUnconfirmed{tx_id, num_confirmations, is_valid: _} => {
                                     self.trigger_confirmations_refresh(tx_id, num_confirmations).await;
                                     self.trigger_tx_state_refresh(tx_id).await;
                                     self.trigger_balance_refresh();
@@ -121,14 +155,31 @@ impl WalletEventMonitor {
                                         )
                                     ).await;
                                 },
-                                TransactionEvent::TransactionMined{tx_id, is_valid: _} |
-                                TransactionEvent::FauxTransactionConfirmed{tx_id, is_valid: _}=> {
+                                TransactionEvent::FauxTransactionUnconfirmed{tx_id, num_confirmations, is_valid: _} => {
+                                    self.trigger_confirmations_refresh(tx_id, num_confirmations).await;
+                                    self.trigger_tx_state_refresh(tx_id).await;
+                                    self.trigger_balance_refresh();
+                                    self.submit_faux_transaction_notification(
+                                        &notifier,
+                                        FauxTransactionNotification::Unconfirmed(tx_id, num_confirmations)
+                                    ).await;
+                                },
+                                TransactionEvent::TransactionMined{tx_id, is_valid: _} => {
                                     self.trigger_confirmations_cleanup(tx_id).await;
                                     self.trigger_tx_state_refresh(tx_id).await;
                                     self.trigger_balance_refresh();
                                     notifier.transaction_mined(tx_id);
                                     self.add_notification(format!("Transaction Confirmed - TxId: {}", tx_id)).await;
                                 },
+                                TransactionEvent::FauxTransactionConfirmed{tx_id, is_valid: _} => {
+                                    self.trigger_confirmations_cleanup(tx_id).await;
+                                    self.trigger_tx_state_refresh(tx_id).await;
+                                    self.trigger_balance_refresh();
+                                    self.submit_faux_transaction_notification(
+                                        &notifier,
+                                        FauxTransactionNotification::Confirmed(tx_id)
+                                    ).await;
+                                },
                                 TransactionEvent::TransactionCancelled(tx_id, _) => {
                                     self.trigger_tx_state_refresh(tx_id).await;
                                     self.trigger_balance_refresh();
@@ -182,6 +233,11 @@ impl WalletEventMonitor {
                         Err(broadcast::error::RecvError::Closed) => {}
                     }
                 },
+                _ = digest_interval.tick() => {
+                    for (_, notification) in self.notifications.poll_digests(Instant::now()) {
+                        self.deliver_faux_transaction_notification(&notifier, notification).await;
+                    }
+                },
                 Ok(_) = connectivity_status.changed() => {
                     trace!(target: LOG_TARGET, "Wallet Event Monitor received wallet connectivity status changed");
                     self.trigger_peer_state_refresh().await;
@@ -326,16 +382,47 @@ impl WalletEventMonitor {
     }
 
     async fn trigger_base_node_state_refresh(&mut self, state: BaseNodeState) {
-        let mut inner = self.app_state_inner.write().await;
+        let tip_height = state.chain_metadata.as_ref().map(|m| m.height_of_longest_chain());
+        {
+            let mut inner = self.app_state_inner.write().await;
 
-        if let Err(e) = inner.refresh_base_node_state(state).await {
-            warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            if let Err(e) = inner.refresh_base_node_state(state).await {
+                warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+            }
+
+            if inner.has_time_locked_balance() {
+                if let Err(e) = self.balance_enquiry_debounce_tx.send(()) {
+                    warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+                }
+            }
         }
 
-        if inner.has_time_locked_balance() {
-            if let Err(e) = self.balance_enquiry_debounce_tx.send(()) {
-                warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+        if let Some(tip_height) = tip_height {
+            self.trigger_fee_estimates_refresh(tip_height).await;
+        }
+    }
+
+    async fn trigger_fee_estimates_refresh(&mut self, tip_height: u64) {
+        let mut base_node_service = {
+            let inner = self.app_state_inner.read().await;
+            if inner.get_fee_estimates().map(|e| e.tip_height) == Some(tip_height) {
+                return;
             }
+            inner.get_base_node_service()
+        };
+
+        // The app state lock is not held while waiting on the base node
+        match base_node_service.get_fee_estimates().await {
+            Ok(estimates) => {
+                let mut inner = self.app_state_inner.write().await;
+                if let Err(e) = inner.refresh_fee_estimates(estimates).await {
+                    warn!(target: LOG_TARGET, "Error refresh app_state: {}", e);
+                }
+            },
+            Err(e) => warn!(
+                target: LOG_TARGET,
+                "Could not fetch fee estimates from base node: {}", e
+            ),
         }
     }
 
@@ -353,6 +440,40 @@ impl WalletEventMonitor {
         }
     }
 
+    async fn submit_faux_transaction_notification(&mut self, notifier: &Notifier, event: FauxTransactionNotification) {
+        if let Some(notification) = self
+            .notifications
+            .submit(NotificationChannel::Console, event, Instant::now())
+        {
+            self.deliver_faux_transaction_notification(notifier, notification).await;
+        }
+    }
+
+    /// A digest runs the notify script for its latest event only and adds a single summary notification
+    async fn deliver_faux_transaction_notification(
+        &mut self,
+        notifier: &Notifier,
+        notification: Notification<FauxTransactionNotification>,
+    ) {
+        let latest = match notification.latest() {
+            Some(event) => *event,
+            None => return,
+        };
+        match latest {
+            FauxTransactionNotification::Confirmed(tx_id) => notifier.transaction_mined(tx_id),
+            FauxTransactionNotification::Unconfirmed(tx_id, num_confirmations) => {
+                notifier.transaction_mined_unconfirmed(tx_id, num_confirmations)
+            },
+        }
+        let message = match notification {
+            Notification::Single(_) => latest.to_string(),
+            Notification::Digest(events) => {
+                format!("{} Imported Transactions Updated, latest: {}", events.len(), latest)
+            },
+        };
+        self.add_notification(message).await;
+    }
+
     async fn add_notification(&mut self, notification: String) {
         let mut inner = self.app_state_inner.write().await;
         inner.add_notification(notification);
@@ -4,6 +4,7 @@
 use tari_comms::connectivity::ConnectivityError;
 use tari_utilities::hex::HexError;
 use tari_wallet::{
+    base_node_service::error::BaseNodeServiceError,
     contacts_service::error::ContactsServiceError,
     error::{WalletError, WalletStorageError},
     output_manager_service::error::OutputManagerError,
@@ -18,6 +19,8 @@ pub enum UiError {
     #[error(transparent)]
     OutputManager(#[from] OutputManagerError),
     #[error(transparent)]
+    BaseNodeService(#[from] BaseNodeServiceError),
+    #[error(transparent)]
     ContactsService(#[from] ContactsServiceError),
     #[error(transparent)]
     Connectivity(#[from] ConnectivityError),
@@ -23,7 +23,11 @@ use std::{fs, io::Stdout, path::PathBuf};
 
 use log::*;
 use rand::{rngs::OsRng, seq::SliceRandom};
-use tari_common::exit_codes::{ExitCode, ExitError};
+use tari_app_grpc::{authentication::BearerTokenInterceptor, tls::server_tls_config};
+use tari_common::{
+    configuration::GrpcSecurityConfig,
+    exit_codes::{ExitCode, ExitError},
+};
 use tari_comms::{multiaddr::Multiaddr, peer_manager::Peer, utils::multiaddr::multiaddr_to_socketaddr};
 use tari_wallet::{WalletConfig, WalletSqlite};
 use tokio::runtime::Handle;
@@ -245,7 +249,7 @@ pub fn tui_mode(
 ) -> Result<(), ExitError> {
     if let Some(ref grpc_address) = config.grpc_address {
         let grpc = WalletGrpcServer::new(wallet.clone());
-        handle.spawn(run_grpc(grpc, grpc_address.clone()));
+        handle.spawn(run_grpc(grpc, grpc_address.clone(), config.grpc_security.clone()));
     }
 
     let notifier = Notifier::new(config.notify_file.clone(), handle.clone(), wallet.clone());
@@ -340,7 +344,7 @@ pub fn grpc_mode(handle: Handle, config: &WalletConfig, wallet: WalletSqlite) ->
     if let Some(grpc_address) = &config.grpc_address {
         let grpc = WalletGrpcServer::new(wallet);
         handle
-            .block_on(run_grpc(grpc, grpc_address.clone()))
+            .block_on(run_grpc(grpc, grpc_address.clone(), config.grpc_security.clone()))
             .map_err(|e| ExitError::new(ExitCode::GrpcError, &e))?;
     } else {
         println!("No grpc address specified");
@@ -349,15 +353,38 @@ pub fn grpc_mode(handle: Handle, config: &WalletConfig, wallet: WalletSqlite) ->
     Ok(())
 }
 
-async fn run_grpc(grpc: WalletGrpcServer, grpc_console_wallet_address: Multiaddr) -> Result<(), String> {
+async fn run_grpc(
+    grpc: WalletGrpcServer,
+    grpc_console_wallet_address: Multiaddr,
+    security: GrpcSecurityConfig,
+) -> Result<(), String> {
     // Do not remove this println!
     const CUCUMBER_TEST_MARKER_A: &str = "Tari Console Wallet running... (gRPC mode started)";
     println!("{}", CUCUMBER_TEST_MARKER_A);
 
     info!(target: LOG_TARGET, "Starting GRPC on {}", grpc_console_wallet_address);
     let address = multiaddr_to_socketaddr(&grpc_console_wallet_address).map_err(|e| e.to_string())?;
-    Server::builder()
-        .add_service(tari_app_grpc::tari_rpc::wallet_server::WalletServer::new(grpc))
+    if !address.ip().is_loopback() && security.api_token.is_none() {
+        warn!(
+            target: LOG_TARGET,
+            "GRPC is listening on {} without an API token. Set wallet.grpc_security.api_token to restrict access.",
+            address
+        );
+    }
+    let mut server = Server::builder();
+    if let Some(tls) = security.tls.as_ref() {
+        info!(target: LOG_TARGET, "GRPC TLS is enabled");
+        let tls_config = server_tls_config(tls).map_err(|e| format!("Could not load GRPC TLS config: {}", e))?;
+        server = server
+            .tls_config(tls_config)
+            .map_err(|e| format!("Invalid GRPC TLS config: {}", e))?;
+    }
+    let interceptor = BearerTokenInterceptor::new(security.api_token.as_deref());
+    server
+        .add_service(tari_app_grpc::tari_rpc::wallet_server::WalletServer::with_interceptor(
+            grpc,
+            interceptor,
+        ))
         .serve(address)
         .await
         .map_err(|e| format!("GRPC server returned error:{}", e))?;
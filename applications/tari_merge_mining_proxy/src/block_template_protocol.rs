@@ -127,6 +127,7 @@ impl BlockTemplateProtocol<'_> {
             miner_data,
             new_block_template: template,
             initial_sync_achieved,
+            ..
         } = self
             .base_node_client
             .get_new_block_template(grpc::NewBlockTemplateRequest {
@@ -134,6 +135,7 @@ impl BlockTemplateProtocol<'_> {
                     pow_algo: grpc::pow_algo::PowAlgos::Monero.into(),
                 }),
                 max_weight: 0,
+                changed_since_token: 0,
             })
             .await
             .map_err(|status| MmProxyError::GrpcRequestError {
@@ -39,6 +39,8 @@ pub struct MergeMiningProxyConfig {
     pub wait_for_initial_sync_at_startup: bool,
     pub check_tari_difficulty_before_submit: bool,
     pub max_randomx_vms: usize,
+    /// If set, Prometheus metrics are served on this address
+    pub metrics_server_address: Option<Multiaddr>,
 }
 
 impl Default for MergeMiningProxyConfig {
@@ -56,6 +58,7 @@ impl Default for MergeMiningProxyConfig {
             wait_for_initial_sync_at_startup: true,
             check_tari_difficulty_before_submit: true,
             max_randomx_vms: 5,
+            metrics_server_address: None,
         }
     }
 }
@@ -31,6 +31,8 @@ use tari_core::{proof_of_work::monero_rx::MergeMineError, transactions::Coinbase
 use thiserror::Error;
 use tonic::transport;
 
+use crate::monero_validation::MoneroValidationError;
+
 #[derive(Debug, Error)]
 pub enum MmProxyError {
     #[error("Configuration error: {0}")]
@@ -86,6 +88,8 @@ pub enum MmProxyError {
     ConversionError(String),
     #[error("No reachable servers in configuration")]
     ServersUnavailable,
+    #[error("Monero block validation failed: {0}")]
+    MoneroValidationError(#[from] MoneroValidationError),
 }
 
 impl From<tonic::Status> for MmProxyError {
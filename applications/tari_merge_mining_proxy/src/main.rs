@@ -26,6 +26,8 @@ mod cli;
 mod common;
 mod config;
 mod error;
+mod metrics;
+mod monero_validation;
 mod proxy;
 
 #[cfg(test)]
@@ -47,6 +49,7 @@ use tari_app_utilities::consts;
 use tari_common::{initialize_logging, load_configuration, DefaultConfigLoader};
 use tari_comms::utils::multiaddr::multiaddr_to_socketaddr;
 use tari_core::proof_of_work::randomx_factory::RandomXFactory;
+use tari_metrics::server::MetricsServerBuilder;
 use tokio::time::Duration;
 
 use crate::{
@@ -91,6 +94,15 @@ async fn main() -> Result<(), anyhow::Error> {
     info!(target: LOG_TARGET, "Connecting to wallet at {}", wallet);
     println!("Connecting to wallet at {}", wallet);
     let wallet_client = grpc::wallet_client::WalletClient::connect(format!("http://{}", wallet)).await?;
+    if let Some(addr) = config.metrics_server_address.as_ref() {
+        let addr = multiaddr_to_socketaddr(addr)?;
+        info!(target: LOG_TARGET, "Serving metrics on {}", addr);
+        tokio::spawn(
+            MetricsServerBuilder::new()
+                .with_scrape_server(addr)
+                .start(future::pending()),
+        );
+    }
     let listen_addr = multiaddr_to_socketaddr(&config.listener_address)?;
     let randomx_factory = RandomXFactory::new(config.max_randomx_vms);
     let xmrig_service = MergeMiningProxyService::new(
@@ -0,0 +1,308 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Structural validation of Monero block templates received from monerod and blocks submitted by miners.
+//!
+//! These checks catch problems early with a descriptive error, rather than forwarding the data and relying on monerod
+//! or the Tari base node to reject it with an opaque error.
+
+use monero::{
+    blockdata::transaction::{SubField, TxIn},
+    Hash,
+};
+use thiserror::Error;
+
+use crate::common::monero_rpc::CoreRpcErrorCode;
+
+/// The transaction count is stored as a u16 in Tari's Monero PoW data, so a block with more transactions cannot be
+/// merge mined.
+pub const MAX_TRANSACTIONS: usize = u16::MAX as usize;
+/// RandomX was introduced in Monero block major version 12
+pub const MIN_MAJOR_VERSION: u64 = 12;
+/// The number of blocks in a RandomX seed hash epoch
+const SEEDHASH_EPOCH_BLOCKS: u64 = 2048;
+/// The number of blocks after the start of an epoch before the new seed hash is used
+const SEEDHASH_EPOCH_LAG: u64 = 64;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MoneroValidationError {
+    #[error("Block contains {count} transactions which exceeds the maximum of {max}")]
+    TooManyTransactions { count: usize, max: usize },
+    #[error("Block major version {0} does not use RandomX")]
+    UnsupportedMajorVersion(u64),
+    #[error("Coinbase transaction must have exactly one generation input")]
+    InvalidCoinbaseInput,
+    #[error("Coinbase transaction is for height {actual} but the template is for height {expected}")]
+    CoinbaseHeightMismatch { expected: u64, actual: u64 },
+    #[error("Coinbase transaction has no outputs")]
+    CoinbaseHasNoOutputs,
+    #[error("Seed height {actual} is not the expected seed height {expected} for block height {height}")]
+    SeedHeightMismatch { height: u64, expected: u64, actual: u64 },
+    #[error("Block template already contains a merge mining tag")]
+    UnexpectedMergeMiningTag,
+    #[error("Block contains no merge mining tag")]
+    MissingMergeMiningTag,
+    #[error("Block contains {0} merge mining tags but exactly one is required")]
+    MultipleMergeMiningTags(usize),
+    #[error("Merge mining tag has depth {0} but only depth 0 is supported")]
+    InvalidMergeMiningTagDepth(u64),
+}
+
+impl MoneroValidationError {
+    /// The monerod RPC error code used when responding to the miner with this error
+    pub fn as_rpc_error_code(&self) -> CoreRpcErrorCode {
+        use MoneroValidationError::*;
+        match self {
+            TooManyTransactions { .. } => CoreRpcErrorCode::WrongBlockblobSize,
+            UnsupportedMajorVersion(_) |
+            InvalidCoinbaseInput |
+            CoinbaseHeightMismatch { .. } |
+            CoinbaseHasNoOutputs |
+            UnexpectedMergeMiningTag |
+            MissingMergeMiningTag |
+            MultipleMergeMiningTags(_) |
+            InvalidMergeMiningTagDepth(_) => CoreRpcErrorCode::WrongBlockblob,
+            SeedHeightMismatch { .. } => CoreRpcErrorCode::InternalError,
+        }
+    }
+
+    /// A short, fixed label for this error that is suitable for use in metrics
+    pub fn as_label(&self) -> &'static str {
+        use MoneroValidationError::*;
+        match self {
+            TooManyTransactions { .. } => "too_many_transactions",
+            UnsupportedMajorVersion(_) => "unsupported_major_version",
+            InvalidCoinbaseInput => "invalid_coinbase_input",
+            CoinbaseHeightMismatch { .. } => "coinbase_height_mismatch",
+            CoinbaseHasNoOutputs => "coinbase_has_no_outputs",
+            SeedHeightMismatch { .. } => "seed_height_mismatch",
+            UnexpectedMergeMiningTag => "unexpected_merge_mining_tag",
+            MissingMergeMiningTag => "missing_merge_mining_tag",
+            MultipleMergeMiningTags(_) => "multiple_merge_mining_tags",
+            InvalidMergeMiningTagDepth(_) => "invalid_merge_mining_tag_depth",
+        }
+    }
+}
+
+/// Returns the height of the block whose hash is used as the RandomX seed for a block at the given height
+pub fn rx_seed_height(height: u64) -> u64 {
+    if height <= SEEDHASH_EPOCH_BLOCKS + SEEDHASH_EPOCH_LAG {
+        return 0;
+    }
+    (height - SEEDHASH_EPOCH_LAG - 1) & !(SEEDHASH_EPOCH_BLOCKS - 1)
+}
+
+/// Validates a block template received from monerod, before the merge mining tag is added. `seed_height` is the
+/// `seed_height` field of the `get_block_template` response, if present.
+pub fn validate_template(
+    block: &monero::Block,
+    height: u64,
+    seed_height: Option<u64>,
+) -> Result<(), MoneroValidationError> {
+    validate_structure(block)?;
+    validate_coinbase_height(block, height)?;
+
+    if let Some(actual) = seed_height {
+        let expected = rx_seed_height(height);
+        if actual != expected {
+            return Err(MoneroValidationError::SeedHeightMismatch {
+                height,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    if !merge_mining_tags(block).is_empty() {
+        return Err(MoneroValidationError::UnexpectedMergeMiningTag);
+    }
+
+    Ok(())
+}
+
+/// Validates a block submitted by a miner. The block must contain exactly one merge mining tag with a depth of zero.
+/// Returns the merge mining hash.
+pub fn validate_submitted_block(block: &monero::Block) -> Result<Hash, MoneroValidationError> {
+    validate_structure(block)?;
+    let height = coinbase_height(block)?;
+    validate_coinbase_height(block, height)?;
+
+    let tags = merge_mining_tags(block);
+    match tags.as_slice() {
+        [] => Err(MoneroValidationError::MissingMergeMiningTag),
+        [(0, hash)] => Ok(*hash),
+        [(depth, _)] => Err(MoneroValidationError::InvalidMergeMiningTagDepth(*depth)),
+        tags => Err(MoneroValidationError::MultipleMergeMiningTags(tags.len())),
+    }
+}
+
+fn validate_structure(block: &monero::Block) -> Result<(), MoneroValidationError> {
+    // The coinbase transaction is included in the transaction count
+    let count = block.tx_hashes.len() + 1;
+    if count > MAX_TRANSACTIONS {
+        return Err(MoneroValidationError::TooManyTransactions {
+            count,
+            max: MAX_TRANSACTIONS,
+        });
+    }
+
+    let major_version = block.header.major_version.0;
+    if major_version < MIN_MAJOR_VERSION {
+        return Err(MoneroValidationError::UnsupportedMajorVersion(major_version));
+    }
+
+    if block.miner_tx.prefix.outputs.is_empty() {
+        return Err(MoneroValidationError::CoinbaseHasNoOutputs);
+    }
+
+    Ok(())
+}
+
+fn coinbase_height(block: &monero::Block) -> Result<u64, MoneroValidationError> {
+    match block.miner_tx.prefix.inputs.as_slice() {
+        [TxIn::Gen { height }] => Ok(height.0),
+        _ => Err(MoneroValidationError::InvalidCoinbaseInput),
+    }
+}
+
+fn validate_coinbase_height(block: &monero::Block, expected: u64) -> Result<(), MoneroValidationError> {
+    let actual = coinbase_height(block)?;
+    if actual != expected {
+        return Err(MoneroValidationError::CoinbaseHeightMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+fn merge_mining_tags(block: &monero::Block) -> Vec<(u64, Hash)> {
+    block
+        .miner_tx
+        .prefix
+        .extra
+        .0
+        .iter()
+        .filter_map(|field| match field {
+            SubField::MergeMining(depth, hash) => Some((depth.0, *hash)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use monero::VarInt;
+    use tari_core::proof_of_work::monero_rx::{append_merge_mining_tag, deserialize};
+
+    use super::*;
+
+    const TEMPLATE_BLOB: &str = "0c0c8cd6a0fa057fe21d764e7abf004e975396a2160773b93712bf6118c3b4959ddd8ee0f76aad0000000002e1ea2701ffa5ea2701d5a299e2abb002028eb3066ced1b2cc82ea046f3716a48e9ae37144057d5fb48a97f941225a1957b2b0106225b7ec0a6544d8da39abe68d8bd82619b4a7c5bdae89c3783b256a8fa47820208f63aa86d2e857f070000";
+
+    fn template_block() -> monero::Block {
+        deserialize::<monero::Block>(&hex::decode(TEMPLATE_BLOB).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn rx_seed_height_epochs() {
+        assert_eq!(rx_seed_height(0), 0);
+        assert_eq!(rx_seed_height(SEEDHASH_EPOCH_BLOCKS + SEEDHASH_EPOCH_LAG), 0);
+        assert_eq!(
+            rx_seed_height(SEEDHASH_EPOCH_BLOCKS + SEEDHASH_EPOCH_LAG + 1),
+            SEEDHASH_EPOCH_BLOCKS
+        );
+        assert_eq!(
+            rx_seed_height(2 * SEEDHASH_EPOCH_BLOCKS + SEEDHASH_EPOCH_LAG),
+            SEEDHASH_EPOCH_BLOCKS
+        );
+        assert_eq!(
+            rx_seed_height(2 * SEEDHASH_EPOCH_BLOCKS + SEEDHASH_EPOCH_LAG + 1),
+            2 * SEEDHASH_EPOCH_BLOCKS
+        );
+    }
+
+    #[test]
+    fn merge_mining_tag_checks() {
+        let mut block = template_block();
+        let height = coinbase_height(&block).unwrap();
+        assert!(validate_template(&block, height, None).is_ok());
+        assert_eq!(
+            validate_submitted_block(&block),
+            Err(MoneroValidationError::MissingMergeMiningTag)
+        );
+
+        append_merge_mining_tag(&mut block, [1u8; 32]).unwrap();
+        assert_eq!(
+            validate_template(&block, height, None),
+            Err(MoneroValidationError::UnexpectedMergeMiningTag)
+        );
+        assert_eq!(validate_submitted_block(&block).unwrap(), Hash::from_slice(&[1u8; 32]));
+
+        append_merge_mining_tag(&mut block, [2u8; 32]).unwrap();
+        assert_eq!(
+            validate_submitted_block(&block),
+            Err(MoneroValidationError::MultipleMergeMiningTags(2))
+        );
+    }
+
+    #[test]
+    fn template_structure_checks() {
+        let block = template_block();
+        let height = coinbase_height(&block).unwrap();
+        assert_eq!(
+            validate_template(&block, height + 1, None),
+            Err(MoneroValidationError::CoinbaseHeightMismatch {
+                expected: height + 1,
+                actual: height
+            })
+        );
+        assert_eq!(
+            validate_template(&block, height, Some(rx_seed_height(height) + 1)),
+            Err(MoneroValidationError::SeedHeightMismatch {
+                height,
+                expected: rx_seed_height(height),
+                actual: rx_seed_height(height) + 1
+            })
+        );
+
+        let mut block = template_block();
+        block.header.major_version = VarInt(11);
+        assert_eq!(
+            validate_template(&block, height, None),
+            Err(MoneroValidationError::UnsupportedMajorVersion(11))
+        );
+
+        let mut block = template_block();
+        block.miner_tx.prefix.outputs.clear();
+        assert_eq!(
+            validate_template(&block, height, None),
+            Err(MoneroValidationError::CoinbaseHasNoOutputs)
+        );
+
+        let mut block = template_block();
+        block.tx_hashes = vec![Hash::null_hash(); MAX_TRANSACTIONS];
+        assert_eq!(
+            validate_template(&block, height, None),
+            Err(MoneroValidationError::TooManyTransactions {
+                count: MAX_TRANSACTIONS + 1,
+                max: MAX_TRANSACTIONS
+            })
+        );
+    }
+}
@@ -56,6 +56,8 @@ use crate::{
     common::{json_rpc, monero_rpc::CoreRpcErrorCode, proxy, proxy::convert_json_to_hyper_json_response},
     config::MergeMiningProxyConfig,
     error::MmProxyError,
+    metrics,
+    monero_validation,
 };
 
 const LOG_TARGET: &str = "tari_mm_proxy::proxy";
@@ -239,9 +241,22 @@ impl InnerService {
         for param in params.iter().filter_map(|p| p.as_str()) {
             let monero_block = monero_rx::deserialize_monero_block_from_hex(param)?;
             debug!(target: LOG_TARGET, "Monero block: {}", monero_block);
-            let hash = monero_rx::extract_tari_hash(&monero_block)
-                .copied()
-                .ok_or_else(|| MmProxyError::MissingDataError("Could not find Tari header in coinbase".to_string()))?;
+            let hash = match monero_validation::validate_submitted_block(&monero_block) {
+                Ok(hash) => hash,
+                Err(err) => {
+                    warn!(target: LOG_TARGET, "Submitted block failed validation: {}", err);
+                    metrics::rejected_block_submissions(err.as_label()).inc();
+                    if !self.config.submit_to_origin {
+                        json_resp = json_rpc::error_response(
+                            request["id"].as_i64(),
+                            err.as_rpc_error_code().into(),
+                            &err.to_string(),
+                            None,
+                        );
+                    }
+                    continue;
+                },
+            };
 
             debug!(
                 target: LOG_TARGET,
@@ -383,6 +398,22 @@ impl InnerService {
             ));
         }
 
+        if let Err(err) = validate_monerod_template(&monerod_resp["result"]) {
+            warn!(
+                target: LOG_TARGET,
+                "Block template from monerod failed validation: {}", err
+            );
+            let (code, label) = match &err {
+                MmProxyError::MoneroValidationError(err) => (err.as_rpc_error_code(), err.as_label()),
+                _ => (CoreRpcErrorCode::InternalError, "invalid_monerod_response"),
+            };
+            metrics::rejected_block_templates(label).inc();
+            return proxy::json_response(
+                StatusCode::OK,
+                &json_rpc::error_response(monerod_resp["id"].as_i64(), code.into(), &err.to_string(), None),
+            );
+        }
+
         let mut grpc_client = self.base_node_client.clone();
         let mut grpc_wallet_client = self.wallet_client.clone();
 
@@ -940,6 +971,19 @@ fn try_into_json_block_header(header: grpc::BlockHeaderResponse) -> Result<json:
     }))
 }
 
+/// Structurally validates the `result` of a monerod `get_block_template` response
+fn validate_monerod_template(result: &json::Value) -> Result<(), MmProxyError> {
+    let height = result["height"].as_u64().ok_or_else(|| {
+        MmProxyError::InvalidMonerodResponse(
+            "Expected `get_block_template` to include a numeric `result.height`".to_string(),
+        )
+    })?;
+    let blob = result["blocktemplate_blob"].as_str().unwrap_or_default();
+    let block = monero_rx::deserialize_monero_block_from_hex(blob)?;
+    monero_validation::validate_template(&block, height, result["seed_height"].as_u64())?;
+    Ok(())
+}
+
 fn parse_method_name(request: &Request<Bytes>) -> String {
     match *request.method() {
         Method::GET => {
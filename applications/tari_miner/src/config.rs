@@ -33,6 +33,9 @@
 //! - mine_on_tip_only - will start mining only when node is reporting bootstrapped state
 //! - validate_tip_timeout_sec - will check tip with node every N seconds to validate that still
 //! mining on a tip
+//! - stratum_server_address - if set, serves SHA3 jobs to stratum mining software on this address instead of mining
+//! - stratum_share_difficulty - the minimum difficulty of shares accepted by the stratum server
+//! - stratum_template_refresh_sec - will issue a new stratum job every N seconds to include new transactions
 //! All miner options configured under `[miner]` section of
 //! Tari's `config.toml`.
 
@@ -54,6 +57,9 @@ pub struct MinerConfig {
     pub mining_pool_address: String,
     pub mining_wallet_address: String,
     pub mining_worker_name: String,
+    pub stratum_server_address: Option<Multiaddr>,
+    pub stratum_share_difficulty: u64,
+    pub stratum_template_refresh_sec: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -79,6 +85,9 @@ impl Default for MinerConfig {
             mining_pool_address: String::new(),
             mining_wallet_address: String::new(),
             mining_worker_name: String::new(),
+            stratum_server_address: None,
+            stratum_share_difficulty: 0,
+            stratum_template_refresh_sec: 60,
         }
     }
 }
@@ -90,7 +99,11 @@ impl MinerConfig {
                 pow_algo: PowAlgos::Sha3.into(),
             }),
         };
-        NewBlockTemplateRequest { algo, max_weight: 0 }
+        NewBlockTemplateRequest {
+            algo,
+            max_weight: 0,
+            changed_since_token: 0,
+        }
     }
 
     pub fn wait_timeout(&self) -> Duration {
@@ -101,6 +114,10 @@ impl MinerConfig {
     pub fn validate_tip_interval(&self) -> Duration {
         Duration::from_secs(self.validate_tip_timeout_sec)
     }
+
+    pub fn stratum_template_refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.stratum_template_refresh_sec)
+    }
 }
 
 #[cfg(test)]
@@ -34,6 +34,9 @@ pub struct BlockHeaderSha3 {
     header: BlockHeader,
     pow_bytes: Vec<u8>,
     hash_before_timestamp: Sha3_256,
+    /// Midstate of the header fields that do not change while mining, i.e. all fields up to (but excluding) the nonce.
+    /// This is only recalculated when the timestamp changes.
+    hash_before_nonce: Sha3_256,
     pub timestamp: u64,
     pub nonce: u64,
     pub hashes: u64,
@@ -54,29 +57,32 @@ impl BlockHeaderSha3 {
             .chain(header.height.to_le_bytes())
             .chain(header.prev_hash.as_bytes());
 
+        let timestamp = timestamp.seconds as u64;
+        let hash_before_nonce = Self::calc_hash_before_nonce(&hash_before_timestamp, &header, timestamp);
+
         Ok(Self {
             pow_bytes: pow.to_bytes(),
             hash_before_timestamp,
-            timestamp: timestamp.seconds as u64,
+            hash_before_nonce,
+            timestamp,
             nonce: header.nonce,
             header,
             hashes: 0,
         })
     }
 
-    #[inline]
-    fn get_hash_before_nonce(&self) -> Sha3_256 {
-        self.hash_before_timestamp
+    fn calc_hash_before_nonce(hash_before_timestamp: &Sha3_256, header: &BlockHeader, timestamp: u64) -> Sha3_256 {
+        hash_before_timestamp
             .clone()
-            .chain(self.timestamp.to_le_bytes())
-            .chain(self.header.input_mr.as_bytes())
-            .chain(self.header.output_mr.as_bytes())
-            .chain(self.header.output_mmr_size.to_le_bytes())
-            .chain(self.header.witness_mr.as_bytes())
-            .chain(self.header.kernel_mr.as_bytes())
-            .chain(self.header.kernel_mmr_size.to_le_bytes())
-            .chain(self.header.total_kernel_offset.as_bytes())
-            .chain(self.header.total_script_offset.as_bytes())
+            .chain(timestamp.to_le_bytes())
+            .chain(header.input_mr.as_bytes())
+            .chain(header.output_mr.as_bytes())
+            .chain(header.output_mmr_size.to_le_bytes())
+            .chain(header.witness_mr.as_bytes())
+            .chain(header.kernel_mr.as_bytes())
+            .chain(header.kernel_mmr_size.to_le_bytes())
+            .chain(header.total_kernel_offset.as_bytes())
+            .chain(header.total_script_offset.as_bytes())
     }
 
     /// This function will update the timestamp of the header, but only if the new timestamp is greater than the current
@@ -86,12 +92,12 @@ impl BlockHeaderSha3 {
         // should only change the timestamp if we move it forward.
         if timestamp > self.timestamp {
             self.timestamp = timestamp;
+            self.hash_before_nonce = Self::calc_hash_before_nonce(&self.hash_before_timestamp, &self.header, timestamp);
         }
     }
 
-    pub fn random_nonce(&mut self) {
-        use rand::{rngs::OsRng, RngCore};
-        self.nonce = OsRng.next_u64();
+    pub fn set_nonce(&mut self, nonce: u64) {
+        self.nonce = nonce;
     }
 
     #[inline]
@@ -103,7 +109,8 @@ impl BlockHeaderSha3 {
     pub fn difficulty(&mut self) -> Difficulty {
         self.hashes = self.hashes.saturating_add(1);
         let hash = self
-            .get_hash_before_nonce()
+            .hash_before_nonce
+            .clone()
             .chain(self.nonce.to_le_bytes())
             .chain(&self.pow_bytes)
             .finalize();
@@ -23,6 +23,7 @@
 use std::{
     convert::TryFrom,
     io::{stdout, Write},
+    sync::Arc,
     thread,
     time::Instant,
 };
@@ -129,6 +130,22 @@ async fn main_inner() -> Result<(), ExitError> {
             .map_err(|err| ExitError::new(ExitCode::UnknownError, &format!("Stratum error: {:?}", err)))?;
 
         Ok(())
+    } else if let Some(stratum_server_address) = config.stratum_server_address.clone() {
+        let address = multiaddr_to_socketaddr(&stratum_server_address).map_err(|err| {
+            ExitError::new(
+                ExitCode::ConfigError,
+                &format!("Invalid stratum server address: {}", err),
+            )
+        })?;
+        let (node_conn, wallet_conn) = connect(&config).await.map_err(|e| {
+            ExitError::new(
+                ExitCode::GrpcError,
+                &format!("Could not connect to wallet or base node: {}", e),
+            )
+        })?;
+        stratum::stratum_server::run(address, node_conn, wallet_conn, Arc::new(config))
+            .await
+            .map_err(|err| ExitError::new(ExitCode::UnknownError, &format!("Stratum server error: {}", err)))
     } else {
         let (mut node_conn, mut wallet_conn) = connect(&config).await.map_err(|e| {
             ExitError::new(
@@ -296,10 +313,10 @@ async fn mining_cycle(
                 block_submitted = true;
                 break;
             } else {
-                display_report(&report, config.num_mining_threads).await;
+                display_report(&report, reports.total_hashrate(), config.num_mining_threads).await;
             }
         } else {
-            display_report(&report, config.num_mining_threads).await;
+            display_report(&report, reports.total_hashrate(), config.num_mining_threads).await;
         }
         if config.mine_on_tip_only && reporting_timeout.elapsed() > config.validate_tip_interval() {
             validate_tip(node_conn, report.height, cli.mine_until_height).await?;
@@ -311,14 +328,13 @@ async fn mining_cycle(
     Ok(block_submitted)
 }
 
-async fn display_report(report: &MiningReport, num_mining_threads: usize) {
-    let hashrate = report.hashes as f64 / report.elapsed.as_micros() as f64;
+async fn display_report(report: &MiningReport, total_hashrate: f64, num_mining_threads: usize) {
     info!(
         target: LOG_TARGET,
         "Miner {} reported {:.2}MH/s with total {:.2}MH/s over {} threads. Height: {}. Target: {})",
         report.miner,
-        hashrate,
-        hashrate * num_mining_threads as f64,
+        report.hashrate(),
+        total_hashrate,
         num_mining_threads,
         report.height,
         report.target_difficulty,
@@ -22,6 +22,10 @@
 //
 use std::{
     pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     task::{Context, Poll, Waker},
     thread,
     time::{Duration, Instant},
@@ -30,6 +34,7 @@ use std::{
 use crossbeam::channel::{bounded, Select, Sender, TrySendError};
 use futures::Stream;
 use log::*;
+use rand::{rngs::OsRng, RngCore};
 use tari_app_grpc::{conversions::timestamp, tari_rpc::BlockHeader};
 use thread::JoinHandle;
 
@@ -41,6 +46,9 @@ pub const LOG_TARGET: &str = "tari_miner::miner::standalone";
 // ~400_000 hashes per second
 const REPORTING_FREQUENCY: u64 = 3_000_000;
 
+// How often (in hashes) mining threads check whether they have been cancelled
+const CANCELLATION_CHECK_FREQUENCY: u64 = 10_000;
+
 // Thread's stack size, ideally we would fit all thread's data in the CPU L1 cache
 const STACK_SIZE: usize = 32_000;
 
@@ -58,6 +66,13 @@ pub struct MiningReport {
     pub last_nonce: u64,
 }
 
+impl MiningReport {
+    /// The hashrate of the reporting thread in MH/s
+    pub fn hashrate(&self) -> f64 {
+        self.hashes as f64 / self.elapsed.as_micros() as f64
+    }
+}
+
 /// Miner is starting number of mining threads and implements Stream for async reports polling
 /// Communication with async world is performed via channel and waker so should be quite efficient
 pub struct Miner {
@@ -67,6 +82,10 @@ pub struct Miner {
     header: BlockHeader,
     target_difficulty: u64,
     share_mode: bool,
+    /// Set to signal all mining threads to stop, e.g. when a new block template arrives
+    stop_flag: Arc<AtomicBool>,
+    /// The last reported hashrate of each mining thread in MH/s
+    hashrates: Vec<f64>,
 }
 
 impl Miner {
@@ -78,16 +97,27 @@ impl Miner {
             num_threads,
             target_difficulty,
             share_mode,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            hashrates: vec![0.0; num_threads],
         }
     }
 
     // this will kill all mining threads currently active and attached to this miner
     pub fn kill_threads(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
         self.channels.clear();
     }
 
+    /// The sum of the last reported hashrates of all mining threads in MH/s
+    pub fn total_hashrate(&self) -> f64 {
+        self.hashrates.iter().sum()
+    }
+
     // Start mining threads with async context waker
     fn start_threads(&mut self, ctx: &Context<'_>) {
+        // Each thread mines its own range of the nonce space, starting at a random offset
+        let start_nonce = OsRng.next_u64();
+        let num_threads = self.num_threads;
         let miners = (0..self.num_threads)
             .map(|i| {
                 (
@@ -103,8 +133,10 @@ impl Miner {
                 let waker = ctx.waker().clone();
                 let difficulty = self.target_difficulty;
                 let share_mode = self.share_mode;
+                let stop_flag = self.stop_flag.clone();
+                let nonce = nonce_range_start(start_nonce, i, num_threads);
                 let handle = thread
-                    .spawn(move || mining_task(header, difficulty, tx, waker, i, share_mode))
+                    .spawn(move || mining_task(header, difficulty, nonce, tx, waker, stop_flag, i, share_mode))
                     .expect("Failed to create mining thread");
                 (handle, rx)
             });
@@ -163,27 +195,49 @@ impl Stream for Miner {
             // Dropping recipients would stop miners next time they try to report
             self.channels.clear();
         }
+        if let Some(hashrate) = self.hashrates.get_mut(report.miner) {
+            *hashrate = report.hashrate();
+        }
         Poll::Ready(Some(report))
     }
 }
 
-/// Miner starts with a random nonce and iterates until it finds a header hash that meets the desired
-/// target
+impl Drop for Miner {
+    fn drop(&mut self) {
+        self.kill_threads();
+    }
+}
+
+/// Returns the first nonce of the `miner`'s range when the nonce space is split evenly between `num_threads` threads
+fn nonce_range_start(start_nonce: u64, miner: usize, num_threads: usize) -> u64 {
+    let range_size = u64::MAX / num_threads.max(1) as u64;
+    start_nonce.wrapping_add(range_size.wrapping_mul(miner as u64))
+}
+
+/// Miner starts with the given nonce and iterates until it finds a header hash that meets the desired
+/// target, or until it is cancelled using the `stop_flag`
+#[allow(clippy::too_many_arguments)]
 pub fn mining_task(
     header: BlockHeader,
     target_difficulty: u64,
+    start_nonce: u64,
     sender: Sender<MiningReport>,
     waker: Waker,
+    stop_flag: Arc<AtomicBool>,
     miner: usize,
     share_mode: bool,
 ) {
     let start = Instant::now();
     let mut hasher = BlockHeaderSha3::new(header).unwrap();
-    hasher.random_nonce();
+    hasher.set_nonce(start_nonce);
     // We're mining over here!
     trace!(target: LOG_TARGET, "Mining thread {} started", miner);
     // Mining work
     loop {
+        if hasher.hashes % CANCELLATION_CHECK_FREQUENCY == 0 && stop_flag.load(Ordering::Relaxed) {
+            trace!(target: LOG_TARGET, "Mining thread {} cancelled", miner);
+            return;
+        }
         let difficulty = hasher.difficulty();
         if difficulty >= target_difficulty {
             debug!(
@@ -236,3 +290,23 @@ pub fn mining_task(
         hasher.inc_nonce();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nonce_ranges_do_not_overlap() {
+        let start_nonce = u64::MAX - 10;
+        let num_threads = 4;
+        let range_size = u64::MAX / num_threads as u64;
+        let starts = (0..num_threads)
+            .map(|i| nonce_range_start(start_nonce, i, num_threads))
+            .collect::<Vec<_>>();
+        assert_eq!(starts[0], start_nonce);
+        for pair in starts.windows(2) {
+            assert_eq!(pair[1].wrapping_sub(pair[0]), range_size);
+        }
+        assert_eq!(nonce_range_start(start_nonce, 0, 0), start_nonce);
+    }
+}
@@ -23,5 +23,6 @@
 pub mod controller;
 pub mod error;
 pub mod stratum_controller;
+pub mod stratum_server;
 pub mod stratum_types;
 pub mod stream;
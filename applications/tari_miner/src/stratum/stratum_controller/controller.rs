@@ -173,10 +173,10 @@ impl Controller {
                             self.keep_alive_time = SystemTime::now();
                             continue;
                         } else {
-                            display_report(&report, self.num_mining_threads).await;
+                            display_report(&report, reporter.total_hashrate(), self.num_mining_threads).await;
                         }
                     } else {
-                        display_report(&report, self.num_mining_threads).await;
+                        display_report(&report, reporter.total_hashrate(), self.num_mining_threads).await;
                     }
                 }
             }
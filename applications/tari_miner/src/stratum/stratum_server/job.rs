@@ -0,0 +1,207 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+};
+
+use tari_app_grpc::tari_rpc::Block;
+use tari_core::{blocks::BlockHeader, consensus::ToConsensusBytes, proof_of_work::sha3_difficulty};
+use tari_utilities::{hex::Hex, Hashable};
+use thiserror::Error;
+
+use crate::{
+    difficulty::Difficulty,
+    errors::{err_empty, MinerError},
+    stratum::stratum_types::job_params::JobParams,
+};
+
+/// Stratum error codes. These match the codes that the tari_miner stratum client acts on.
+pub const ERROR_CODE_UNAUTHORIZED: i32 = -1;
+pub const ERROR_CODE_INVALID_SHARE: i32 = 20;
+pub const ERROR_CODE_JOB_NOT_FOUND: i32 = 21;
+pub const ERROR_CODE_DUPLICATE_SHARE: i32 = 22;
+pub const ERROR_CODE_LOW_DIFFICULTY_SHARE: i32 = 23;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ShareError {
+    #[error("Job not found")]
+    JobNotFound,
+    #[error("Duplicate share")]
+    DuplicateShare,
+    #[error("Share hash does not match the header hash")]
+    InvalidHash,
+    #[error("Share difficulty {difficulty} is lower than the share target {share_difficulty}")]
+    LowDifficulty {
+        difficulty: Difficulty,
+        share_difficulty: Difficulty,
+    },
+}
+
+impl ShareError {
+    pub fn code(&self) -> i32 {
+        match self {
+            ShareError::JobNotFound => ERROR_CODE_JOB_NOT_FOUND,
+            ShareError::DuplicateShare => ERROR_CODE_DUPLICATE_SHARE,
+            ShareError::InvalidHash => ERROR_CODE_INVALID_SHARE,
+            ShareError::LowDifficulty { .. } => ERROR_CODE_LOW_DIFFICULTY_SHARE,
+        }
+    }
+}
+
+/// A SHA3 mining job served to stratum clients. The header is sent to clients as is, they only vary the nonce.
+#[derive(Debug)]
+pub struct StratumJob {
+    pub job_id: u64,
+    pub height: u64,
+    /// The difficulty a share needs to be a valid block
+    pub target_difficulty: Difficulty,
+    /// The difficulty a share needs to be accepted
+    pub share_difficulty: Difficulty,
+    header: BlockHeader,
+    block: Block,
+    submitted_nonces: Mutex<HashSet<u64>>,
+}
+
+impl StratumJob {
+    /// Creates a job for the given block. The share difficulty is capped at the block target difficulty.
+    pub fn new(
+        job_id: u64,
+        block: Block,
+        target_difficulty: Difficulty,
+        share_difficulty: Difficulty,
+    ) -> Result<Self, MinerError> {
+        let header = block.header.clone().ok_or_else(|| err_empty("block.header"))?;
+        let header = BlockHeader::try_from(header).map_err(MinerError::Conversion)?;
+        let share_difficulty = if share_difficulty == 0 {
+            target_difficulty
+        } else {
+            share_difficulty.min(target_difficulty)
+        };
+        Ok(Self {
+            job_id,
+            height: header.height,
+            target_difficulty,
+            share_difficulty,
+            header,
+            block,
+            submitted_nonces: Mutex::new(HashSet::new()),
+        })
+    }
+
+    pub fn job_params(&self) -> JobParams {
+        JobParams {
+            job_id: self.job_id.to_string(),
+            blob: base64::encode(self.header.to_consensus_bytes()),
+            target: self.share_difficulty.to_string(),
+            height: self.height,
+        }
+    }
+
+    /// Validates a share for this job, returning the achieved difficulty. The hash is optional, if given it must match
+    /// the hash of the header with the submitted nonce.
+    pub fn validate_share(&self, nonce: u64, hash: &str) -> Result<Difficulty, ShareError> {
+        let header = self.header_with_nonce(nonce);
+        if !hash.is_empty() && !hash.eq_ignore_ascii_case(&header.hash().to_hex()) {
+            return Err(ShareError::InvalidHash);
+        }
+        let difficulty = sha3_difficulty(&header).as_u64();
+        if difficulty < self.share_difficulty {
+            return Err(ShareError::LowDifficulty {
+                difficulty,
+                share_difficulty: self.share_difficulty,
+            });
+        }
+        let mut submitted_nonces = self.submitted_nonces.lock().expect("submitted_nonces lock poisoned");
+        if !submitted_nonces.insert(nonce) {
+            return Err(ShareError::DuplicateShare);
+        }
+        Ok(difficulty)
+    }
+
+    pub fn is_block(&self, difficulty: Difficulty) -> bool {
+        difficulty >= self.target_difficulty
+    }
+
+    /// Returns the block for this job with the given nonce
+    pub fn mined_block(&self, nonce: u64) -> Block {
+        let mut block = self.block.clone();
+        block.header = Some(self.header_with_nonce(nonce).into());
+        block
+    }
+
+    fn header_with_nonce(&self, nonce: u64) -> BlockHeader {
+        let mut header = self.header.clone();
+        header.nonce = nonce;
+        header
+    }
+}
+
+pub type SharedStratumJob = Arc<StratumJob>;
+
+#[cfg(test)]
+mod test {
+    use tari_core::proof_of_work::PowAlgorithm;
+
+    use super::*;
+
+    fn create_job(target_difficulty: Difficulty, share_difficulty: Difficulty) -> StratumJob {
+        let mut header = BlockHeader::new(0);
+        header.pow.pow_algo = PowAlgorithm::Sha3;
+        let block = Block {
+            header: Some(header.into()),
+            body: None,
+        };
+        StratumJob::new(1, block, target_difficulty, share_difficulty).unwrap()
+    }
+
+    #[test]
+    fn it_caps_the_share_difficulty_at_the_target() {
+        let job = create_job(100, 1000);
+        assert_eq!(job.share_difficulty, 100);
+        let job = create_job(100, 0);
+        assert_eq!(job.share_difficulty, 100);
+        let job = create_job(100, 10);
+        assert_eq!(job.share_difficulty, 10);
+        assert_eq!(job.job_params().target, "10");
+    }
+
+    #[test]
+    fn it_validates_shares() {
+        let job = create_job(u64::MAX, 1);
+        let hash = job.header_with_nonce(5).hash().to_hex();
+        let difficulty = job.validate_share(5, &hash).unwrap();
+        assert!(difficulty >= 1);
+        assert!(!job.is_block(difficulty));
+        assert_eq!(job.validate_share(5, ""), Err(ShareError::DuplicateShare));
+        assert_eq!(job.validate_share(6, &hash), Err(ShareError::InvalidHash));
+        assert_eq!(job.mined_block(5).header.unwrap().nonce, 5);
+
+        let job = create_job(u64::MAX, u64::MAX);
+        assert!(matches!(
+            job.validate_share(1, ""),
+            Err(ShareError::LowDifficulty { .. })
+        ));
+    }
+}
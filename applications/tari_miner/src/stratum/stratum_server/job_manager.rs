@@ -0,0 +1,218 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+use std::{
+    collections::VecDeque,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use log::*;
+use tari_app_grpc::tari_rpc::{base_node_client::BaseNodeClient, wallet_client::WalletClient, Block, Empty};
+use tari_utilities::hex::Hex;
+use tokio::{
+    sync::{mpsc, watch},
+    time,
+};
+use tonic::transport::Channel;
+
+use super::job::{SharedStratumJob, StratumJob};
+use crate::{
+    config::MinerConfig,
+    errors::{err_empty, MinerError},
+    utils::{coinbase_request, extract_outputs_and_kernels},
+};
+
+pub const LOG_TARGET: &str = "tari_miner::stratum_server::job_manager";
+
+/// The interval at which the base node is polled for a new tip
+const TIP_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// The number of recent jobs for which shares are accepted
+const MAX_RECENT_JOBS: usize = 5;
+
+/// The most recent jobs issued by the [JobManager], used to look up the job a share was submitted for
+#[derive(Clone, Default)]
+pub struct RecentJobs {
+    jobs: Arc<RwLock<VecDeque<SharedStratumJob>>>,
+}
+
+impl RecentJobs {
+    pub fn get(&self, job_id: u64) -> Option<SharedStratumJob> {
+        self.jobs
+            .read()
+            .expect("recent jobs lock poisoned")
+            .iter()
+            .find(|job| job.job_id == job_id)
+            .cloned()
+    }
+
+    fn insert(&self, job: SharedStratumJob) {
+        let mut jobs = self.jobs.write().expect("recent jobs lock poisoned");
+        jobs.push_back(job);
+        while jobs.len() > MAX_RECENT_JOBS {
+            jobs.pop_front();
+        }
+    }
+
+    fn clear(&self) {
+        self.jobs.write().expect("recent jobs lock poisoned").clear();
+    }
+}
+
+/// Builds SHA3 jobs from base node block templates and submits blocks found by stratum clients. A new job is issued
+/// when the chain tip changes, after a block is found and periodically so that new mempool transactions are included.
+pub struct JobManager {
+    node_conn: BaseNodeClient<Channel>,
+    wallet_conn: WalletClient<Channel>,
+    config: Arc<MinerConfig>,
+    recent_jobs: RecentJobs,
+    job_tx: watch::Sender<Option<SharedStratumJob>>,
+    block_rx: mpsc::Receiver<Block>,
+    next_job_id: u64,
+    current_tip: Option<Vec<u8>>,
+    last_job_time: Instant,
+}
+
+impl JobManager {
+    pub fn new(
+        node_conn: BaseNodeClient<Channel>,
+        wallet_conn: WalletClient<Channel>,
+        config: Arc<MinerConfig>,
+        recent_jobs: RecentJobs,
+        job_tx: watch::Sender<Option<SharedStratumJob>>,
+        block_rx: mpsc::Receiver<Block>,
+    ) -> Self {
+        Self {
+            node_conn,
+            wallet_conn,
+            config,
+            recent_jobs,
+            job_tx,
+            block_rx,
+            next_job_id: 0,
+            current_tip: None,
+            last_job_time: Instant::now(),
+        }
+    }
+
+    pub async fn run(mut self) {
+        let mut tip_poll = time::interval(TIP_POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                Some(block) = self.block_rx.recv() => {
+                    if let Err(err) = self.submit_block(block).await {
+                        error!(target: LOG_TARGET, "Failed to submit block: {}", err);
+                    }
+                    // The block template is stale whether or not the block was accepted
+                    self.current_tip = None;
+                },
+                _ = tip_poll.tick() => {
+                    if let Err(err) = self.check_tip().await {
+                        error!(target: LOG_TARGET, "Failed to update stratum job: {}", err);
+                    }
+                },
+            }
+        }
+    }
+
+    async fn check_tip(&mut self) -> Result<(), MinerError> {
+        let tip = self.node_conn.get_tip_info(Empty {}).await?.into_inner();
+        if !tip.initial_sync_achieved {
+            if self.current_tip.take().is_some() {
+                warn!(target: LOG_TARGET, "Base node is not synced, withdrawing stratum jobs");
+                self.recent_jobs.clear();
+                let _result = self.job_tx.send(None);
+            }
+            return Ok(());
+        }
+        let best_block = tip.metadata.ok_or_else(|| err_empty("metadata"))?.best_block;
+        let is_new_tip = self.current_tip.as_ref() != Some(&best_block);
+        if is_new_tip || self.last_job_time.elapsed() >= self.config.stratum_template_refresh_interval() {
+            self.issue_new_job().await?;
+            self.current_tip = Some(best_block);
+        }
+        Ok(())
+    }
+
+    async fn issue_new_job(&mut self) -> Result<(), MinerError> {
+        debug!(target: LOG_TARGET, "Getting new block template");
+        let template = self
+            .node_conn
+            .get_new_block_template(self.config.pow_algo_request())
+            .await?
+            .into_inner();
+        let mut block_template = template
+            .new_block_template
+            .clone()
+            .ok_or_else(|| err_empty("new_block_template"))?;
+
+        debug!(target: LOG_TARGET, "Getting coinbase");
+        let request = coinbase_request(&template)?;
+        let coinbase = self.wallet_conn.get_coinbase(request).await?.into_inner();
+        let (output, kernel) = extract_outputs_and_kernels(coinbase)?;
+        let body = block_template
+            .body
+            .as_mut()
+            .ok_or_else(|| err_empty("new_block_template.body"))?;
+        body.outputs.push(output);
+        body.kernels.push(kernel);
+        let target_difficulty = template
+            .miner_data
+            .ok_or_else(|| err_empty("miner_data"))?
+            .target_difficulty;
+
+        let block_result = self.node_conn.get_new_block(block_template).await?.into_inner();
+        let block = block_result.block.ok_or_else(|| err_empty("block"))?;
+
+        self.next_job_id += 1;
+        let job = Arc::new(StratumJob::new(
+            self.next_job_id,
+            block,
+            target_difficulty,
+            self.config.stratum_share_difficulty,
+        )?);
+        info!(
+            target: LOG_TARGET,
+            "New stratum job {} for height {} with target difficulty {} and share difficulty {}",
+            job.job_id,
+            job.height,
+            job.target_difficulty,
+            job.share_difficulty
+        );
+        self.recent_jobs.insert(job.clone());
+        self.last_job_time = Instant::now();
+        let _result = self.job_tx.send(Some(job));
+        Ok(())
+    }
+
+    async fn submit_block(&mut self, block: Block) -> Result<(), MinerError> {
+        let height = block.header.as_ref().map(|h| h.height).unwrap_or_default();
+        let response = self.node_conn.submit_block(block).await?.into_inner();
+        info!(
+            target: LOG_TARGET,
+            "Submitted block #{} ({}) found by stratum client",
+            height,
+            response.block_hash.to_hex()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+//! Stratum server for SHA3 solo mining
+//!
+//! Serves jobs built from base node block templates to standard stratum mining software over line delimited
+//! JSON-RPC, using the same protocol as the tari_miner stratum client. The coinbase is paid to the wallet connected
+//! over gRPC. Shares are validated against the job share difficulty and shares meeting the block target difficulty are
+//! submitted to the base node.
+
+mod job;
+mod job_manager;
+mod server;
+
+use std::{net::SocketAddr, sync::Arc};
+
+use tari_app_grpc::tari_rpc::{base_node_client::BaseNodeClient, wallet_client::WalletClient};
+use tokio::{
+    sync::{mpsc, watch},
+    task,
+};
+use tonic::transport::Channel;
+
+use self::{job_manager::JobManager, server::StratumServer};
+use crate::{config::MinerConfig, stratum::error::Error};
+
+/// Runs the stratum server until the listener fails
+pub async fn run(
+    address: SocketAddr,
+    node_conn: BaseNodeClient<Channel>,
+    wallet_conn: WalletClient<Channel>,
+    config: Arc<MinerConfig>,
+) -> Result<(), Error> {
+    let recent_jobs = job_manager::RecentJobs::default();
+    let (job_tx, job_rx) = watch::channel(None);
+    let (block_tx, block_rx) = mpsc::channel(10);
+    let server = StratumServer::bind(address, recent_jobs.clone(), job_rx, block_tx).await?;
+    let job_manager = JobManager::new(node_conn, wallet_conn, config, recent_jobs, job_tx, block_rx);
+    task::spawn(job_manager.run());
+    server.run().await
+}
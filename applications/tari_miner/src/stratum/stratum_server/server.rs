@@ -0,0 +1,315 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use futures::StreamExt;
+use log::*;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tari_app_grpc::tari_rpc::Block;
+use tokio::{
+    io::AsyncWriteExt,
+    net::{tcp::OwnedWriteHalf, TcpListener, TcpStream},
+    sync::{mpsc, watch},
+    task,
+};
+use tokio_util::codec::{FramedRead, LinesCodec};
+
+use super::{
+    job::{ShareError, SharedStratumJob, ERROR_CODE_UNAUTHORIZED},
+    job_manager::RecentJobs,
+};
+use crate::stratum::{
+    error::Error,
+    stratum_types::{
+        login_params::LoginParams,
+        login_response::LoginResponse,
+        rpc_error::RpcError,
+        rpc_request::RpcRequest,
+        rpc_response::RpcResponse,
+        submit_params::SubmitParams,
+        submit_response::SubmitResponse,
+    },
+};
+
+pub const LOG_TARGET: &str = "tari_miner::stratum_server::server";
+/// The maximum length of a message from a stratum client. Clients that send longer lines are disconnected.
+const MAX_MESSAGE_LENGTH: usize = 16 * 1024;
+
+const ERROR_CODE_PARSE_ERROR: i32 = -32700;
+const ERROR_CODE_METHOD_NOT_FOUND: i32 = -32601;
+const ERROR_CODE_INVALID_PARAMS: i32 = -32602;
+const ERROR_CODE_INTERNAL_ERROR: i32 = -32603;
+const ERROR_CODE_NO_JOB: i32 = -32000;
+
+/// Accepts stratum client connections and serves them the current job
+pub struct StratumServer {
+    listener: TcpListener,
+    recent_jobs: RecentJobs,
+    job_rx: watch::Receiver<Option<SharedStratumJob>>,
+    block_tx: mpsc::Sender<Block>,
+}
+
+impl StratumServer {
+    pub async fn bind(
+        address: SocketAddr,
+        recent_jobs: RecentJobs,
+        job_rx: watch::Receiver<Option<SharedStratumJob>>,
+        block_tx: mpsc::Sender<Block>,
+    ) -> Result<Self, Error> {
+        let listener = TcpListener::bind(address).await?;
+        Ok(Self {
+            listener,
+            recent_jobs,
+            job_rx,
+            block_tx,
+        })
+    }
+
+    pub async fn run(self) -> Result<(), Error> {
+        info!(
+            target: LOG_TARGET,
+            "Stratum server listening on {}",
+            self.listener.local_addr()?
+        );
+        let next_worker_id = Arc::new(AtomicU64::new(1));
+        loop {
+            let (socket, addr) = self.listener.accept().await?;
+            debug!(target: LOG_TARGET, "Stratum client connected from {}", addr);
+            let session = Session {
+                worker_id: next_worker_id.fetch_add(1, Ordering::Relaxed),
+                login: None,
+                recent_jobs: self.recent_jobs.clone(),
+                job_rx: self.job_rx.clone(),
+                block_tx: self.block_tx.clone(),
+            };
+            task::spawn(async move {
+                if let Err(err) = session.run(socket).await {
+                    debug!(target: LOG_TARGET, "Stratum client {} disconnected: {}", addr, err);
+                }
+            });
+        }
+    }
+}
+
+struct Session {
+    worker_id: u64,
+    login: Option<String>,
+    recent_jobs: RecentJobs,
+    job_rx: watch::Receiver<Option<SharedStratumJob>>,
+    block_tx: mpsc::Sender<Block>,
+}
+
+impl Session {
+    async fn run(mut self, socket: TcpStream) -> Result<(), Error> {
+        let (reader, mut writer) = socket.into_split();
+        let mut lines = FramedRead::new(reader, LinesCodec::new_with_max_length(MAX_MESSAGE_LENGTH));
+        loop {
+            tokio::select! {
+                line = lines.next() => {
+                    let line = match line {
+                        Some(Ok(line)) => line,
+                        Some(Err(err)) => return Err(Error::Connection(err.to_string())),
+                        None => return Ok(()),
+                    };
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let response = self.handle_message(&line).await;
+                    send_message(&mut writer, &response).await?;
+                },
+                changed = self.job_rx.changed() => {
+                    if changed.is_err() {
+                        return Err(Error::Connection("Job manager has stopped".to_string()));
+                    }
+                    if self.login.is_some() {
+                        self.notify_job(&mut writer).await?;
+                    }
+                },
+            }
+        }
+    }
+
+    async fn notify_job(&self, writer: &mut OwnedWriteHalf) -> Result<(), Error> {
+        let job = match self.job_rx.borrow().clone() {
+            Some(job) => job,
+            None => return Ok(()),
+        };
+        let request = RpcRequest {
+            id: None,
+            jsonrpc: "2.0".to_string(),
+            method: "job".to_string(),
+            params: Some(serde_json::to_value(job.job_params())?),
+        };
+        send_message(writer, &request).await
+    }
+
+    async fn handle_message(&mut self, message: &str) -> RpcResponse {
+        let request = match serde_json::from_str::<RpcRequest>(message) {
+            Ok(request) => request,
+            Err(err) => return error_response(String::new(), ERROR_CODE_PARSE_ERROR, err.to_string()),
+        };
+        let id = request.id.clone().unwrap_or_default();
+        let result = match request.method.as_str() {
+            "login" => self.handle_login(request.params),
+            "getjob" => self.handle_get_job(),
+            "submit" => self.handle_submit(request.params).await,
+            "keepalived" | "keepalive" => Ok(json!({ "status": "KEEPALIVED" })),
+            method => Err(RpcError {
+                code: ERROR_CODE_METHOD_NOT_FOUND,
+                message: format!("Unknown method '{}'", method),
+            }),
+        };
+        match result {
+            Ok(result) => RpcResponse {
+                id,
+                result: Some(result),
+                error: None,
+            },
+            Err(err) => RpcResponse {
+                id,
+                result: None,
+                error: Some(err),
+            },
+        }
+    }
+
+    fn handle_login(&mut self, params: Option<Value>) -> Result<Value, RpcError> {
+        let params = parse_params::<LoginParams>(params)?;
+        info!(
+            target: LOG_TARGET,
+            "Stratum worker {} logged in as '{}' ({})", self.worker_id, params.login, params.agent
+        );
+        self.login = Some(params.login);
+        let job = self.current_job()?;
+        to_value(&LoginResponse {
+            id: self.worker_id.to_string(),
+            job: job.job_params(),
+        })
+    }
+
+    fn handle_get_job(&self) -> Result<Value, RpcError> {
+        self.check_login()?;
+        to_value(&self.current_job()?.job_params())
+    }
+
+    async fn handle_submit(&self, params: Option<Value>) -> Result<Value, RpcError> {
+        self.check_login()?;
+        let params = parse_params::<SubmitParams>(params)?;
+        let response = match self.submit_share(&params).await {
+            Ok(()) => SubmitResponse {
+                status: Some("OK".to_string()),
+                error: None,
+            },
+            Err(err) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Rejected share from worker {} for job {}: {}", self.worker_id, params.job_id, err
+                );
+                SubmitResponse {
+                    status: None,
+                    error: Some(RpcError {
+                        code: err.code(),
+                        message: err.to_string(),
+                    }),
+                }
+            },
+        };
+        to_value(&response)
+    }
+
+    async fn submit_share(&self, params: &SubmitParams) -> Result<(), ShareError> {
+        let job = self.recent_jobs.get(params.job_id).ok_or(ShareError::JobNotFound)?;
+        let difficulty = job.validate_share(params.nonce, &params.hash)?;
+        debug!(
+            target: LOG_TARGET,
+            "Accepted share from worker {} for job {} with difficulty {}", self.worker_id, job.job_id, difficulty
+        );
+        if job.is_block(difficulty) {
+            info!(
+                target: LOG_TARGET,
+                "Worker {} found block #{} with difficulty {}", self.worker_id, job.height, difficulty
+            );
+            if self.block_tx.send(job.mined_block(params.nonce)).await.is_err() {
+                error!(target: LOG_TARGET, "Job manager has stopped, block was not submitted");
+            }
+        }
+        Ok(())
+    }
+
+    fn check_login(&self) -> Result<(), RpcError> {
+        if self.login.is_none() {
+            return Err(RpcError {
+                code: ERROR_CODE_UNAUTHORIZED,
+                message: "Unauthenticated".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn current_job(&self) -> Result<SharedStratumJob, RpcError> {
+        self.job_rx.borrow().clone().ok_or_else(|| RpcError {
+            code: ERROR_CODE_NO_JOB,
+            message: "No job available, the base node may not be synced".to_string(),
+        })
+    }
+}
+
+async fn send_message<T: Serialize>(writer: &mut OwnedWriteHalf, message: &T) -> Result<(), Error> {
+    let mut message = serde_json::to_vec(message)?;
+    message.push(b'\n');
+    writer.write_all(&message).await?;
+    Ok(())
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(params: Option<Value>) -> Result<T, RpcError> {
+    let params = params.ok_or_else(|| RpcError {
+        code: ERROR_CODE_INVALID_PARAMS,
+        message: "Missing params".to_string(),
+    })?;
+    serde_json::from_value(params).map_err(|err| RpcError {
+        code: ERROR_CODE_INVALID_PARAMS,
+        message: err.to_string(),
+    })
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|err| RpcError {
+        code: ERROR_CODE_INTERNAL_ERROR,
+        message: err.to_string(),
+    })
+}
+
+fn error_response(id: String, code: i32, message: String) -> RpcResponse {
+    RpcResponse {
+        id,
+        result: None,
+        error: Some(RpcError { code, message }),
+    }
+}
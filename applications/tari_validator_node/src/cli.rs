@@ -37,6 +37,9 @@ pub(crate) struct Cli {
     /// Supply a network (overrides existing configuration)
     #[clap(long, default_value = DEFAULT_NETWORK, env = "TARI_NETWORK")]
     pub network: String,
+    /// Only run the consensus signing service for this node's identity
+    #[clap(long)]
+    pub signing_server: bool,
 }
 
 impl Cli {
@@ -67,6 +67,15 @@ pub struct ValidatorNodeConfig {
     pub p2p: P2pConfig,
     pub committee_management_polling_interval: u64,
     pub committee_management_confirmation_time: u64,
+    /// Address of a signing service holding the consensus key. If not set, the node identity signs locally.
+    pub signing_service_address: Option<SocketAddr>,
+    /// Seconds to wait for the signing service before skipping the current view
+    pub signing_service_timeout: u64,
+    /// Address the signing service listens on when running with `--signing-server`
+    pub signing_service_listener_address: SocketAddr,
+    /// Hex public keys of the validator nodes that may request signatures when running with `--signing-server`. The
+    /// signing service will not start if this is empty.
+    pub signing_service_allowed_clients: Vec<String>,
 }
 
 impl ValidatorNodeConfig {
@@ -98,6 +107,10 @@ impl Default for ValidatorNodeConfig {
             committee_management_confirmation_time: 10,
             committee_management_polling_interval: 5,
             p2p: P2pConfig::default(),
+            signing_service_address: None,
+            signing_service_timeout: 5,
+            signing_service_listener_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 18145),
+            signing_service_allowed_clients: vec![],
         }
     }
 }
@@ -61,6 +61,7 @@ use crate::{
         inbound_connection_service::TariCommsInboundConnectionService,
         outbound_connection_service::TariCommsOutboundService,
     },
+    signing::{ConsensusSigningService, RemoteSigningService},
     TariCommsValidatorNodeClientFactory,
 };
 
@@ -199,7 +200,21 @@ impl DanNode {
         let payload_provider = TariDanPayloadProvider::new(mempool_service.clone());
 
         let events_publisher = LoggingEventsPublisher::default();
-        let signing_service = NodeIdentitySigningService::new(node_identity.clone());
+        let signing_service = match config.signing_service_address {
+            Some(address) => {
+                info!(
+                    target: LOG_TARGET,
+                    "Using signing service at {} for asset '{}'", address, asset_definition.public_key
+                );
+                ConsensusSigningService::Remote(RemoteSigningService::new(
+                    address,
+                    node_identity.public_key().clone(),
+                    Arc::new(node_identity.clone()),
+                    Duration::from_secs(config.signing_service_timeout),
+                ))
+            },
+            None => ConsensusSigningService::Local(NodeIdentitySigningService::new(node_identity.clone())),
+        };
 
         // let _backend = LmdbAssetStore::initialize(data_dir.join("asset_data"), Default::default())
         //     .map_err(|err| ExitCodes::DatabaseError(err.to_string()))?;
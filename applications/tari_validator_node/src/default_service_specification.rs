@@ -30,7 +30,6 @@ use tari_dan_core::{
         ConcreteCommitteeManager,
         LoggingEventsPublisher,
         MempoolServiceHandle,
-        NodeIdentitySigningService,
         ServiceSpecification,
         TariDanPayloadProcessor,
         TariDanPayloadProvider,
@@ -50,6 +49,7 @@ use crate::{
         outbound_connection_service::TariCommsOutboundService,
         rpc_client::TariCommsValidatorNodeClientFactory,
     },
+    signing::ConsensusSigningService,
 };
 
 #[derive(Default, Clone)]
@@ -72,7 +72,7 @@ impl ServiceSpecification for DefaultServiceSpecification {
     type Payload = TariDanPayload;
     type PayloadProcessor = TariDanPayloadProcessor<Self::AssetProcessor>;
     type PayloadProvider = TariDanPayloadProvider<Self::MempoolService>;
-    type SigningService = NodeIdentitySigningService;
+    type SigningService = ConsensusSigningService;
     type StateDbBackendAdapter = SqliteStateDbBackendAdapter;
     type ValidatorNodeClientFactory = TariCommsValidatorNodeClientFactory;
     type WalletClient = GrpcWalletClient;
@@ -21,7 +21,10 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 use tari_app_grpc::tari_rpc;
 use tari_crypto::tari_utilities::ByteArray;
-use tari_dan_core::models::SidechainMetadata;
+use tari_dan_core::{
+    models::SidechainMetadata,
+    storage::state::{StateEntry, StateEntryProof},
+};
 
 pub struct St(tari_rpc::SidechainMetadata);
 
@@ -34,3 +37,21 @@ impl From<SidechainMetadata> for St {
         })
     }
 }
+
+pub(crate) fn state_entry_to_grpc(entry: StateEntry) -> Result<tari_rpc::AssetStateEntry, serde_json::Error> {
+    Ok(tari_rpc::AssetStateEntry {
+        key: entry.key,
+        value: entry.value,
+        proof: entry.proof.map(state_entry_proof_to_grpc).transpose()?,
+    })
+}
+
+fn state_entry_proof_to_grpc(proof: StateEntryProof) -> Result<tari_rpc::AssetStateProof, serde_json::Error> {
+    Ok(tari_rpc::AssetStateProof {
+        schema_root: proof.schema_root,
+        leaf_index: proof.leaf_index as u64,
+        leaf_proof: serde_json::to_vec(&proof.leaf_proof)?,
+        schema_index: proof.schema_index as u64,
+        schema_proof: serde_json::to_vec(&proof.schema_proof)?,
+    })
+}
@@ -28,10 +28,18 @@ use tari_crypto::tari_utilities::ByteArray;
 use tari_dan_core::{
     models::Instruction,
     services::{AssetProcessor, AssetProxy, ServiceSpecification},
-    storage::DbFactory,
+    storage::{
+        state::{StateDbUnitOfWorkReader, StateQuery},
+        DbFactory,
+    },
 };
 use tonic::{Request, Response, Status};
 
+use crate::grpc::conversions::state_entry_to_grpc;
+
+const GET_ASSET_STATE_DEFAULT_LIMIT: usize = 100;
+const GET_ASSET_STATE_MAX_LIMIT: usize = 1_000;
+
 pub struct ValidatorNodeGrpcServer<TServiceSpecification: ServiceSpecification> {
     node_identity: NodeIdentity,
     db_factory: TServiceSpecification::DbFactory,
@@ -86,14 +94,22 @@ impl<TServiceSpecification: ServiceSpecification + 'static> rpc::validator_node_
         let asset_public_key = PublicKey::from_bytes(&request.asset_public_key)
             .map_err(|_err| Status::invalid_argument("asset_public_key was not a valid public key"))?;
 
+        let template_id = request
+            .template_id
+            .try_into()
+            .map_err(|_| Status::invalid_argument("invalid template_id"))?;
+        let template_version = self
+            .asset_processor
+            .template_registry()
+            .resolve_version(template_id, request.template_version)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+
         match self
             .asset_proxy
             .invoke_method(
                 &asset_public_key,
-                request
-                    .template_id
-                    .try_into()
-                    .map_err(|_| Status::invalid_argument("invalid template_id"))?,
+                template_id,
+                template_version,
                 request.method.clone(),
                 request.args.clone(),
             )
@@ -137,13 +153,18 @@ impl<TServiceSpecification: ServiceSpecification + 'static> rpc::validator_node_
             .template_id
             .try_into()
             .map_err(|_| Status::invalid_argument("Invalid template_id"))?;
+        let template_version = self
+            .asset_processor
+            .template_registry()
+            .resolve_version(template_id, request.template_version)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
         if let Some(state) = self
             .db_factory
             .get_state_db(&asset_public_key)
             .map_err(|e| Status::internal(format!("Could not create state db: {}", e)))?
         {
             let state_db_reader = state.reader();
-            let instruction = Instruction::new(template_id, request.method, request.args);
+            let instruction = Instruction::new(template_id, template_version, request.method, request.args);
             let response_bytes = self
                 .asset_processor
                 .invoke_read_method(&instruction, &state_db_reader)
@@ -160,7 +181,13 @@ impl<TServiceSpecification: ServiceSpecification + 'static> rpc::validator_node_
             // Forward to proxy
             let response_bytes = self
                 .asset_proxy
-                .invoke_read_method(&asset_public_key, template_id, request.method, request.args)
+                .invoke_read_method(
+                    &asset_public_key,
+                    template_id,
+                    template_version,
+                    request.method,
+                    request.args,
+                )
                 .await
                 .map_err(|err| Status::internal(format!("Error calling proxied method:{}", err)))?;
             // TODO: Populate authority
@@ -174,4 +201,50 @@ impl<TServiceSpecification: ServiceSpecification + 'static> rpc::validator_node_
             }))
         }
     }
+
+    async fn get_asset_state(
+        &self,
+        request: Request<rpc::GetAssetStateRequest>,
+    ) -> Result<Response<rpc::GetAssetStateResponse>, Status> {
+        let request = request.into_inner();
+        let asset_public_key = PublicKey::from_bytes(&request.asset_public_key)
+            .map_err(|err| Status::invalid_argument(format!("Asset public key was not a valid public key:{}", err)))?;
+        if request.schema.is_empty() {
+            return Err(Status::invalid_argument("schema must be provided"));
+        }
+        let limit = match request.limit as usize {
+            0 => GET_ASSET_STATE_DEFAULT_LIMIT,
+            limit => limit.min(GET_ASSET_STATE_MAX_LIMIT),
+        };
+
+        let state = self
+            .db_factory
+            .get_state_db(&asset_public_key)
+            .map_err(|e| Status::internal(format!("Could not create state db: {}", e)))?
+            .ok_or_else(|| Status::not_found("This node does not process this asset"))?;
+
+        let query = StateQuery {
+            schema: request.schema,
+            prefix: request.prefix,
+            cursor: Some(request.cursor).filter(|c| !c.is_empty()),
+            limit,
+            include_proofs: request.include_proofs,
+        };
+        let page = state
+            .reader()
+            .get_state_page(&query)
+            .map_err(|e| Status::internal(format!("Could not read asset state: {}", e)))?;
+
+        let entries = page
+            .entries
+            .into_iter()
+            .map(state_entry_to_grpc)
+            .collect::<Result<_, _>>()
+            .map_err(|e| Status::internal(format!("Could not encode state proof: {}", e)))?;
+        Ok(Response::new(rpc::GetAssetStateResponse {
+            entries,
+            next_cursor: page.next_cursor.unwrap_or_default(),
+            state_root: page.state_root.map(|r| r.as_bytes().to_vec()).unwrap_or_default(),
+        }))
+    }
 }
@@ -31,6 +31,7 @@ mod default_service_specification;
 mod grpc;
 mod monitoring;
 mod p2p;
+mod signing;
 
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -47,8 +48,9 @@ use tari_common::{
     exit_codes::{ExitCode, ExitError},
     load_configuration,
 };
-use tari_comms::{peer_manager::PeerFeatures, NodeIdentity};
+use tari_comms::{peer_manager::PeerFeatures, types::CommsPublicKey, NodeIdentity};
 use tari_comms_dht::Dht;
+use tari_crypto::tari_utilities::hex::Hex;
 use tari_dan_core::services::{ConcreteAssetProcessor, ConcreteAssetProxy, MempoolServiceHandle, ServiceSpecification};
 use tari_dan_storage_sqlite::SqliteDbFactory;
 use tari_p2p::comms_connector::SubscriptionFactory;
@@ -90,7 +92,11 @@ fn main_inner() -> Result<(), ExitError> {
 
     let config = ApplicationConfig::load_from(&cfg)?;
     let runtime = build_runtime()?;
-    runtime.block_on(run_node(&config))?;
+    if cli.signing_server {
+        runtime.block_on(run_signing_server(&config))?;
+    } else {
+        runtime.block_on(run_node(&config))?;
+    }
 
     Ok(())
 }
@@ -159,6 +165,31 @@ async fn run_node(config: &ApplicationConfig) -> Result<(), ExitError> {
     Ok(())
 }
 
+async fn run_signing_server(config: &ApplicationConfig) -> Result<(), ExitError> {
+    // The signing service holds the consensus key, so never create a new identity here
+    let node_identity = setup_node_identity(&config.validator_node.identity_file, None, false, PeerFeatures::NONE)?;
+    let allowed_clients = config
+        .validator_node
+        .signing_service_allowed_clients
+        .iter()
+        .map(|key| {
+            CommsPublicKey::from_hex(key).map_err(|err| {
+                ExitError::new(
+                    ExitCode::ConfigError,
+                    &format!("Invalid signing service client public key '{}': {}", key, err),
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    println!("🔑 Signing service started for {}", node_identity.public_key());
+    signing::run_signing_server(
+        config.validator_node.signing_service_listener_address,
+        node_identity,
+        allowed_clients,
+    )
+    .await
+}
+
 fn build_runtime() -> Result<Runtime, ExitError> {
     let mut builder = runtime::Builder::new_multi_thread();
     builder
@@ -116,6 +116,7 @@ impl From<&Instruction> for proto::common::Instruction {
     fn from(source: &Instruction) -> Self {
         Self {
             template_id: source.template_id() as u32,
+            template_version: source.template_version(),
             method: source.method().to_string(),
             args: Vec::from(source.args()),
         }
@@ -216,7 +217,7 @@ impl TryFrom<proto::common::Instruction> for Instruction {
 
     fn try_from(value: proto::common::Instruction) -> Result<Self, Self::Error> {
         let template_id = TemplateId::try_from(value.template_id).map_err(|err| err.to_string())?;
-        Ok(Self::new(template_id, value.method, value.args))
+        Ok(Self::new(template_id, value.template_version, value.method, value.args))
     }
 }
 
@@ -32,3 +32,7 @@ pub mod consensus {
 pub mod validator_node {
     include!(concat!(env!("OUT_DIR"), "/tari.dan.validator_node.rs"));
 }
+
+pub mod signing {
+    include!(concat!(env!("OUT_DIR"), "/tari.dan.signing.rs"));
+}
@@ -93,14 +93,16 @@ where
 
         let unit_of_work = state.reader();
 
-        let instruction = Instruction::new(
-            request
-                .template_id
-                .try_into()
-                .map_err(|_| RpcStatus::bad_request("Invalid template_id"))?,
-            request.method,
-            request.args,
-        );
+        let template_id = request
+            .template_id
+            .try_into()
+            .map_err(|_| RpcStatus::bad_request("Invalid template_id"))?;
+        let template_version = self
+            .asset_processor
+            .template_registry()
+            .resolve_version(template_id, request.template_version)
+            .map_err(|e| RpcStatus::bad_request(&e.to_string()))?;
+        let instruction = Instruction::new(template_id, template_version, request.method, request.args);
         let response_bytes = self
             .asset_processor
             .invoke_read_method(&instruction, &unit_of_work)
@@ -117,11 +119,18 @@ where
     ) -> Result<Response<proto::InvokeMethodResponse>, RpcStatus> {
         println!("{:?}", request);
         let request = request.into_message();
+        let template_id = request
+            .template_id
+            .try_into()
+            .map_err(|_| RpcStatus::bad_request("Invalid template_id"))?;
+        let template_version = self
+            .asset_processor
+            .template_registry()
+            .resolve_version(template_id, request.template_version)
+            .map_err(|e| RpcStatus::bad_request(&e.to_string()))?;
         let instruction = Instruction::new(
-            request
-                .template_id
-                .try_into()
-                .map_err(|_| RpcStatus::bad_request("Invalid template_id"))?,
+            template_id,
+            template_version,
             request.method.clone(),
             request.args.clone(),
             /* TokenId(request.token_id.clone()),
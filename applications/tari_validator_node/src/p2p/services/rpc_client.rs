@@ -56,6 +56,7 @@ impl ValidatorNodeRpcClient for TariCommsValidatorNodeRpcClient {
         &mut self,
         asset_public_key: &PublicKey,
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
     ) -> Result<Option<Vec<u8>>, ValidatorNodeClientError> {
@@ -68,6 +69,7 @@ impl ValidatorNodeRpcClient for TariCommsValidatorNodeRpcClient {
         let request = proto::InvokeReadMethodRequest {
             asset_public_key: asset_public_key.to_vec(),
             template_id: template_id as u32,
+            template_version,
             method,
             args,
         };
@@ -84,6 +86,7 @@ impl ValidatorNodeRpcClient for TariCommsValidatorNodeRpcClient {
         &mut self,
         asset_public_key: &PublicKey,
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
     ) -> Result<Option<Vec<u8>>, ValidatorNodeClientError> {
@@ -96,6 +99,7 @@ impl ValidatorNodeRpcClient for TariCommsValidatorNodeRpcClient {
         let request = proto::InvokeMethodRequest {
             asset_public_key: asset_public_key.to_vec(),
             template_id: template_id as u32,
+            template_version,
             method,
             args,
         };
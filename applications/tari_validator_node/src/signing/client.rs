@@ -0,0 +1,177 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{io, net::SocketAddr, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use log::*;
+use prost::Message;
+use tari_comms::{
+    connection_manager::ConnectionDirection,
+    types::CommsPublicKey,
+    NodeIdentity,
+    NoiseConfig,
+    NoiseSocket,
+};
+use tari_crypto::tari_utilities::ByteArray;
+use tari_dan_core::{
+    models::{ConsensusSigningRequest, Signature},
+    services::SigningService,
+    DigitalAssetError,
+};
+use tokio::{net::TcpStream, sync::Mutex, time};
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+use super::frame_codec;
+use crate::p2p::proto::signing as proto;
+
+const LOG_TARGET: &str = "tari::validator_node::signing::client";
+
+type SignerConnection = Framed<NoiseSocket<TcpStream>, LengthDelimitedCodec>;
+
+/// Requests consensus signatures from a signing service listening on `address`. A single connection is kept open
+/// and re-established on demand, so that a restarted signer is picked up without restarting the validator node.
+/// Failures to reach the signer are reported as `DigitalAssetError::SigningServiceUnavailable`.
+///
+/// Connections are noise sessions in which the validator node authenticates with `node_identity` and the signer must
+/// authenticate with the consensus `public_key`.
+pub struct RemoteSigningService {
+    address: SocketAddr,
+    public_key: CommsPublicKey,
+    noise_config: NoiseConfig,
+    timeout: Duration,
+    connection: Mutex<Option<SignerConnection>>,
+}
+
+impl RemoteSigningService {
+    pub fn new(
+        address: SocketAddr,
+        public_key: CommsPublicKey,
+        node_identity: Arc<NodeIdentity>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            address,
+            public_key,
+            noise_config: NoiseConfig::new(node_identity),
+            timeout,
+            connection: Mutex::new(None),
+        }
+    }
+
+    async fn connect(&self) -> io::Result<SignerConnection> {
+        let connect = async {
+            let stream = TcpStream::connect(self.address).await?;
+            self.noise_config
+                .upgrade_socket(stream, ConnectionDirection::Outbound)
+                .await
+                .map_err(|err| io::Error::new(io::ErrorKind::ConnectionRefused, err))
+        };
+        let socket = time::timeout(self.timeout, connect)
+            .await
+            .map_err(|_| timed_out("timed out connecting to the signing service"))??;
+        if socket.get_remote_public_key().as_ref() != Some(&self.public_key) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "signing service did not authenticate with the consensus public key",
+            ));
+        }
+        debug!(target: LOG_TARGET, "Connected to signing service at {}", self.address);
+        Ok(Framed::new(socket, frame_codec()))
+    }
+
+    async fn send_request(
+        &self,
+        connection: &mut Option<SignerConnection>,
+        request: &proto::SignRequest,
+    ) -> io::Result<proto::SignResponse> {
+        let mut framed = match connection.take() {
+            Some(framed) => framed,
+            None => self.connect().await?,
+        };
+        let exchange = async {
+            framed.send(Bytes::from(request.encode_to_vec())).await?;
+            let frame = framed.next().await.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::UnexpectedEof, "signing service closed the connection")
+            })??;
+            proto::SignResponse::decode(frame).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        };
+        let response = time::timeout(self.timeout, exchange)
+            .await
+            .map_err(|_| timed_out("timed out waiting for the signing service to respond"))??;
+        // Only a connection that completed an exchange is reused, a failed one may be left mid-frame
+        *connection = Some(framed);
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl SigningService<CommsPublicKey> for RemoteSigningService {
+    async fn sign(
+        &self,
+        identity: &CommsPublicKey,
+        request: &ConsensusSigningRequest,
+    ) -> Result<Signature, DigitalAssetError> {
+        if *identity != self.public_key {
+            return Err(DigitalAssetError::InvalidSignature);
+        }
+        let request = proto::SignRequest {
+            public_key: identity.to_vec(),
+            message_type: u32::from(request.message_type.as_u8()),
+            view_number: request.view_number.as_u64(),
+            asset_public_key: request.asset_public_key.to_vec(),
+            node_hash: request.node_hash.as_bytes().to_vec(),
+        };
+
+        let mut connection = self.connection.lock().await;
+        let response = match self.send_request(&mut connection, &request).await {
+            Ok(response) => response,
+            Err(err) => {
+                // The signer may have restarted since the connection was opened, so try once more on a fresh connection
+                debug!(
+                    target: LOG_TARGET,
+                    "Signing request to {} failed ({}). Retrying.", self.address, err
+                );
+                self.send_request(&mut connection, &request)
+                    .await
+                    .map_err(|err| DigitalAssetError::SigningServiceUnavailable(format!("{}: {}", self.address, err)))?
+            },
+        };
+
+        match response.result {
+            Some(proto::sign_response::Result::Signature(signature)) => Ok(Signature::from_bytes(&signature)),
+            Some(proto::sign_response::Result::Error(err)) => Err(DigitalAssetError::SigningServiceUnavailable(
+                format!("{} refused to sign: {}", self.address, err),
+            )),
+            None => Err(DigitalAssetError::SigningServiceUnavailable(format!(
+                "{} returned an empty response",
+                self.address
+            ))),
+        }
+    }
+}
+
+fn timed_out(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, msg)
+}
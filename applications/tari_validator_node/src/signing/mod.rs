@@ -0,0 +1,218 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Consensus signing for the validator node. Signatures are either produced locally from the node identity, or
+//! requested from a signing service running in a separate process (`--signing-server`) so that the consensus key can be
+//! kept on a hardened host. The signing service only accepts noise sessions from the validator nodes it is configured
+//! to trust, and only signs consensus votes, building the signature challenge itself.
+
+use async_trait::async_trait;
+use tari_comms::types::CommsPublicKey;
+use tari_dan_core::{
+    models::{ConsensusSigningRequest, Signature},
+    services::{NodeIdentitySigningService, SigningService},
+    DigitalAssetError,
+};
+use tokio_util::codec::LengthDelimitedCodec;
+
+mod client;
+pub use client::RemoteSigningService;
+
+mod server;
+pub use server::run_signing_server;
+
+/// Upper bound on the size of a single request or response frame
+const MAX_FRAME_LENGTH: usize = 64 * 1024;
+
+fn frame_codec() -> LengthDelimitedCodec {
+    LengthDelimitedCodec::builder()
+        .max_frame_length(MAX_FRAME_LENGTH)
+        .new_codec()
+}
+
+pub enum ConsensusSigningService {
+    Local(NodeIdentitySigningService),
+    Remote(RemoteSigningService),
+}
+
+#[async_trait]
+impl SigningService<CommsPublicKey> for ConsensusSigningService {
+    async fn sign(
+        &self,
+        identity: &CommsPublicKey,
+        request: &ConsensusSigningRequest,
+    ) -> Result<Signature, DigitalAssetError> {
+        match self {
+            ConsensusSigningService::Local(service) => service.sign(identity, request).await,
+            ConsensusSigningService::Remote(service) => service.sign(identity, request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+    use tari_common_types::types::PublicKey;
+    use tari_comms::{peer_manager::PeerFeatures, test_utils::node_identity::build_node_identity, NodeIdentity};
+    use tari_dan_core::models::{HotStuffMessageType, TreeNodeHash, ViewId};
+    use tokio::{net::TcpListener, task};
+
+    use super::*;
+
+    fn vote(message_type: HotStuffMessageType, view_number: u64, node_hash: u8) -> ConsensusSigningRequest {
+        ConsensusSigningRequest {
+            message_type,
+            view_number: ViewId(view_number),
+            asset_public_key: PublicKey::default(),
+            node_hash: TreeNodeHash::from([node_hash; 32]),
+        }
+    }
+
+    async fn spawn_server(signer_identity: Arc<NodeIdentity>, allowed_clients: Vec<CommsPublicKey>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        task::spawn(server::serve(listener, signer_identity, allowed_clients));
+        address
+    }
+
+    #[tokio::test]
+    async fn it_signs_using_the_remote_signing_service() {
+        let node_identity = build_node_identity(PeerFeatures::NONE);
+        let address = spawn_server(node_identity.clone(), vec![node_identity.public_key().clone()]).await;
+
+        let service = RemoteSigningService::new(
+            address,
+            node_identity.public_key().clone(),
+            node_identity.clone(),
+            Duration::from_secs(5),
+        );
+        service
+            .sign(node_identity.public_key(), &vote(HotStuffMessageType::Prepare, 1, 1))
+            .await
+            .unwrap();
+        // The connection is reused for subsequent requests
+        service
+            .sign(node_identity.public_key(), &vote(HotStuffMessageType::PreCommit, 1, 1))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_rejects_requests_for_another_key() {
+        let node_identity = build_node_identity(PeerFeatures::NONE);
+        let other_identity = build_node_identity(PeerFeatures::NONE);
+        let address = spawn_server(node_identity.clone(), vec![other_identity.public_key().clone()]).await;
+
+        let service = RemoteSigningService::new(
+            address,
+            other_identity.public_key().clone(),
+            other_identity.clone(),
+            Duration::from_secs(5),
+        );
+        let err = service
+            .sign(other_identity.public_key(), &vote(HotStuffMessageType::Prepare, 1, 1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DigitalAssetError::SigningServiceUnavailable(_)));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_clients_that_are_not_allowed() {
+        let node_identity = build_node_identity(PeerFeatures::NONE);
+        let client_identity = build_node_identity(PeerFeatures::NONE);
+        let address = spawn_server(node_identity.clone(), vec![node_identity.public_key().clone()]).await;
+
+        let service = RemoteSigningService::new(
+            address,
+            node_identity.public_key().clone(),
+            client_identity,
+            Duration::from_secs(5),
+        );
+        let err = service
+            .sign(node_identity.public_key(), &vote(HotStuffMessageType::Prepare, 1, 1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DigitalAssetError::SigningServiceUnavailable(_)));
+    }
+
+    #[tokio::test]
+    async fn it_only_signs_non_conflicting_votes() {
+        let node_identity = build_node_identity(PeerFeatures::NONE);
+        let address = spawn_server(node_identity.clone(), vec![node_identity.public_key().clone()]).await;
+        let service = RemoteSigningService::new(
+            address,
+            node_identity.public_key().clone(),
+            node_identity.clone(),
+            Duration::from_secs(5),
+        );
+        let public_key = node_identity.public_key();
+
+        let err = service
+            .sign(public_key, &vote(HotStuffMessageType::NewView, 1, 1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DigitalAssetError::SigningServiceUnavailable(_)));
+
+        service
+            .sign(public_key, &vote(HotStuffMessageType::Prepare, 2, 1))
+            .await
+            .unwrap();
+        // Signing the same vote again is allowed
+        service
+            .sign(public_key, &vote(HotStuffMessageType::Prepare, 2, 1))
+            .await
+            .unwrap();
+        // A different node in the same view, or a vote for an earlier view, is refused
+        service
+            .sign(public_key, &vote(HotStuffMessageType::Prepare, 2, 2))
+            .await
+            .unwrap_err();
+        service
+            .sign(public_key, &vote(HotStuffMessageType::Prepare, 1, 1))
+            .await
+            .unwrap_err();
+        service
+            .sign(public_key, &vote(HotStuffMessageType::Prepare, 3, 2))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_reports_an_unavailable_signing_service() {
+        let node_identity = build_node_identity(PeerFeatures::NONE);
+        // Bind and drop a listener to get an address that nothing is listening on
+        let address = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+
+        let service = RemoteSigningService::new(
+            address,
+            node_identity.public_key().clone(),
+            node_identity.clone(),
+            Duration::from_secs(1),
+        );
+        let err = service
+            .sign(node_identity.public_key(), &vote(HotStuffMessageType::Prepare, 1, 1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DigitalAssetError::SigningServiceUnavailable(_)));
+    }
+}
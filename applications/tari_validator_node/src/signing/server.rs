@@ -0,0 +1,234 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use digest::Digest;
+use futures::{SinkExt, StreamExt};
+use log::*;
+use prost::Message;
+use rand::rngs::OsRng;
+use tari_common::exit_codes::{ExitCode, ExitError};
+use tari_comms::{
+    connection_manager::ConnectionDirection,
+    types::{Challenge, CommsPublicKey},
+    utils::signature::sign_challenge,
+    NodeIdentity,
+    NoiseConfig,
+};
+use tari_crypto::tari_utilities::{message_format::MessageFormat, ByteArray};
+use tari_dan_core::models::{ConsensusSigningRequest, HotStuffMessageType, TreeNodeHash, ViewId};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    task,
+    time,
+};
+use tokio_util::codec::Framed;
+
+use super::frame_codec;
+use crate::p2p::proto::signing as proto;
+
+const LOG_TARGET: &str = "tari::validator_node::signing::server";
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub async fn run_signing_server(
+    address: SocketAddr,
+    node_identity: Arc<NodeIdentity>,
+    allowed_clients: Vec<CommsPublicKey>,
+) -> Result<(), ExitError> {
+    if allowed_clients.is_empty() {
+        return Err(ExitError::new(
+            ExitCode::ConfigError,
+            &"validator_node.signing_service_allowed_clients must contain the public key of at least one validator \
+              node",
+        ));
+    }
+    let listener = TcpListener::bind(address).await.map_err(|err| {
+        ExitError::new(
+            ExitCode::NetworkError,
+            &format!("Failed to bind to {}: {}", address, err),
+        )
+    })?;
+    info!(
+        target: LOG_TARGET,
+        "Signing service for {} listening on {}",
+        node_identity.public_key(),
+        address
+    );
+
+    serve(listener, node_identity, allowed_clients).await;
+    Ok(())
+}
+
+pub(super) async fn serve(
+    listener: TcpListener,
+    node_identity: Arc<NodeIdentity>,
+    allowed_clients: Vec<CommsPublicKey>,
+) {
+    let signer = Arc::new(Signer {
+        noise_config: NoiseConfig::new(node_identity.clone()),
+        node_identity,
+        allowed_clients,
+        last_votes: Mutex::new(HashMap::new()),
+    });
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer_addr)) => {
+                info!(target: LOG_TARGET, "Signing service connection from {}", peer_addr);
+                let signer = signer.clone();
+                task::spawn(async move {
+                    if let Err(err) = signer.handle_connection(stream).await {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Signing service connection from {} failed: {}", peer_addr, err
+                        );
+                    }
+                    debug!(
+                        target: LOG_TARGET,
+                        "Signing service connection from {} closed", peer_addr
+                    );
+                });
+            },
+            Err(err) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to accept signing service connection: {}", err
+                );
+            },
+        }
+    }
+}
+
+struct Signer {
+    node_identity: Arc<NodeIdentity>,
+    noise_config: NoiseConfig,
+    allowed_clients: Vec<CommsPublicKey>,
+    /// The last vote signed for each (asset, message type), used to refuse to sign conflicting votes
+    last_votes: Mutex<HashMap<(Vec<u8>, u8), (ViewId, TreeNodeHash)>>,
+}
+
+impl Signer {
+    async fn handle_connection(&self, stream: TcpStream) -> io::Result<()> {
+        let socket = time::timeout(
+            HANDSHAKE_TIMEOUT,
+            self.noise_config.upgrade_socket(stream, ConnectionDirection::Inbound),
+        )
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "noise handshake timed out"))?
+        .map_err(|err| io::Error::new(io::ErrorKind::ConnectionRefused, err))?;
+
+        match socket.get_remote_public_key() {
+            Some(client) if self.allowed_clients.contains(&client) => {
+                debug!(target: LOG_TARGET, "Signing service client authenticated as {}", client);
+            },
+            client => {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!(
+                        "client {} is not in the allowed client list",
+                        client
+                            .map(|pk| pk.to_string())
+                            .unwrap_or_else(|| "<unknown>".to_string())
+                    ),
+                ));
+            },
+        }
+
+        let mut framed = Framed::new(socket, frame_codec());
+        while let Some(frame) = framed.next().await {
+            let request =
+                proto::SignRequest::decode(frame?).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            let response = self.sign(&request);
+            framed.send(response.encode_to_vec().into()).await?;
+        }
+        Ok(())
+    }
+
+    fn sign(&self, request: &proto::SignRequest) -> proto::SignResponse {
+        use proto::sign_response::Result as SignResult;
+
+        let result = match self.check_request(request) {
+            Ok(request) => {
+                let challenge = Challenge::new().chain(request.challenge());
+                match sign_challenge(&mut OsRng, self.node_identity.secret_key().clone(), challenge)
+                    .map_err(|err| err.to_string())
+                    .and_then(|signature| signature.to_binary().map_err(|err| err.to_string()))
+                {
+                    Ok(signature) => SignResult::Signature(signature),
+                    Err(err) => {
+                        error!(target: LOG_TARGET, "Failed to sign challenge: {}", err);
+                        SignResult::Error(format!("Failed to sign challenge: {}", err))
+                    },
+                }
+            },
+            Err(err) => {
+                warn!(target: LOG_TARGET, "Refusing to sign request: {}", err);
+                SignResult::Error(err)
+            },
+        };
+        proto::SignResponse { result: Some(result) }
+    }
+
+    /// Converts the request into a consensus vote, refusing anything that is not a vote or that conflicts with a vote
+    /// that was already signed
+    fn check_request(&self, request: &proto::SignRequest) -> Result<ConsensusSigningRequest, String> {
+        if request.public_key != self.node_identity.public_key().as_bytes() {
+            return Err("Requested public key does not match the signing service key".to_string());
+        }
+        let message_type = u8::try_from(request.message_type)
+            .map_err(|_| "Invalid message type".to_string())
+            .and_then(HotStuffMessageType::try_from)?;
+        let request = ConsensusSigningRequest {
+            message_type,
+            view_number: ViewId(request.view_number),
+            asset_public_key: CommsPublicKey::from_bytes(&request.asset_public_key)
+                .map_err(|err| format!("Invalid asset public key: {}", err))?,
+            node_hash: TreeNodeHash::try_from(request.node_hash.clone())
+                .map_err(|err| format!("Invalid node hash: {}", err))?,
+        };
+        if !request.is_vote() {
+            return Err(format!("{:?} messages are not signed", request.message_type));
+        }
+
+        let mut last_votes = self.last_votes.lock().expect("signing service lock poisoned");
+        let key = (request.asset_public_key.to_vec(), request.message_type.as_u8());
+        if let Some((view_number, node_hash)) = last_votes.get(&key) {
+            if request.view_number.as_u64() < view_number.as_u64() ||
+                (request.view_number == *view_number && request.node_hash != *node_hash)
+            {
+                return Err(format!(
+                    "{:?} vote for view {} conflicts with the vote already signed for view {}",
+                    request.message_type, request.view_number, view_number
+                ));
+            }
+        }
+        last_votes.insert(key, (request.view_number, request.node_hash));
+        Ok(request)
+    }
+}
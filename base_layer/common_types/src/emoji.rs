@@ -132,6 +132,51 @@ impl EmojiId {
         PublicKey::from_bytes(&bytes).map_err(|_| EmojiIdError)
     }
 
+    /// Returns true if the string consists of 33 emoji from the emoji set, but the last emoji is not a valid checksum
+    pub fn is_checksum_error(s: &str) -> bool {
+        match EmojiId::indices(s) {
+            Some(indices) => indices.len() == 33 && !is_valid(&indices, 256),
+            None => false,
+        }
+    }
+
+    /// Suggests valid emoji IDs for a string that is not a valid emoji ID. Suggestions are only made for strings that
+    /// are missing the checksum emoji or contain a single transposition of adjacent emoji, since the checksum cannot
+    /// be used to correct other errors.
+    pub fn suggestions(s: &str) -> Vec<EmojiId> {
+        let indices = match EmojiId::indices(s) {
+            Some(indices) => indices,
+            None => return Vec::new(),
+        };
+        match indices.len() {
+            32 => {
+                let bytes = indices.iter().map(|i| *i as u8).collect::<Vec<_>>();
+                PublicKey::from_bytes(&bytes)
+                    .map(|key| vec![EmojiId::from_pubkey(&key)])
+                    .unwrap_or_default()
+            },
+            33 if !is_valid(&indices, 256) => {
+                let mut suggestions = Vec::new();
+                for i in 0..indices.len() - 1 {
+                    if indices[i] == indices[i + 1] {
+                        continue;
+                    }
+                    let mut candidate = indices.clone();
+                    candidate.swap(i, i + 1);
+                    if !is_valid(&candidate, 256) {
+                        continue;
+                    }
+                    let bytes = candidate.iter().take(32).map(|i| *i as u8).collect::<Vec<_>>();
+                    if let Ok(key) = PublicKey::from_bytes(&bytes) {
+                        suggestions.push(EmojiId::from_pubkey(&key));
+                    }
+                }
+                suggestions
+            },
+            _ => Vec::new(),
+        }
+    }
+
     /// Return the 33 character emoji string for this emoji ID
     pub fn as_str(&self) -> &str {
         &self.0
@@ -152,6 +197,11 @@ impl EmojiId {
         Self(id)
     }
 
+    /// Returns the emoji set index of each character in the string, or None if any character is not in the emoji set
+    fn indices(s: &str) -> Option<Vec<usize>> {
+        s.chars().map(|c| REVERSE_EMOJI.get(&c).copied()).collect()
+    }
+
     fn byte_vec(s: &str) -> Result<Vec<u8>, EmojiIdError> {
         let mut v = Vec::with_capacity(32);
         for c in s.chars().take(32) {
@@ -225,4 +275,29 @@ mod test {
             "Wrong checksum"
         );
     }
+
+    #[test]
+    fn suggestions() {
+        let eid = EmojiId::from_hex("70350e09c474809209824c6e6888707b7dd09959aa227343b5106382b856f73a").unwrap();
+        let chars = eid.as_str().chars().collect::<Vec<_>>();
+        assert!(EmojiId::suggestions(eid.as_str()).is_empty());
+        assert!(!EmojiId::is_checksum_error(eid.as_str()));
+
+        // Missing checksum
+        let no_checksum = chars.iter().take(32).collect::<String>();
+        assert!(!EmojiId::is_checksum_error(&no_checksum));
+        assert_eq!(EmojiId::suggestions(&no_checksum), vec![eid.clone()]);
+
+        // Transposed emoji
+        let mut transposed = chars.clone();
+        transposed.swap(3, 4);
+        let transposed = transposed.into_iter().collect::<String>();
+        assert!(EmojiId::is_checksum_error(&transposed));
+        assert!(EmojiId::suggestions(&transposed).contains(&eid));
+
+        assert!(EmojiId::suggestions("70350e09c474809209824c6e6888707b7dd09959aa227343b5106382b856f73a").is_empty());
+        assert!(!EmojiId::is_checksum_error(
+            "70350e09c474809209824c6e6888707b7dd09959aa227343b5106382b856f73a"
+        ));
+    }
 }
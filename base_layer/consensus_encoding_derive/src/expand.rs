@@ -0,0 +1,185 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, GenericArgument, Member, PathArguments, Type};
+
+use crate::options::{ContainerOptions, FieldOptions};
+
+struct FieldInfo {
+    member: Member,
+    binding: syn::Ident,
+    ty: Type,
+    options: FieldOptions,
+}
+
+fn struct_fields(input: &DeriveInput) -> syn::Result<(Vec<FieldInfo>, &Fields)> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(_) | Data::Union(_) => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "consensus encoding can only be derived for structs",
+            ))
+        },
+    };
+    let infos = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let (member, binding) = match &field.ident {
+                Some(ident) => (Member::Named(ident.clone()), format_ident!("__{}", ident)),
+                None => (Member::Unnamed(i.into()), format_ident!("__field{}", i)),
+            };
+            Ok(FieldInfo {
+                member,
+                binding,
+                ty: field.ty.clone(),
+                options: FieldOptions::from_attrs(&field.attrs)?,
+            })
+        })
+        .collect::<syn::Result<_>>()?;
+    Ok((infos, fields))
+}
+
+pub fn expand_encoding(input: DeriveInput) -> syn::Result<TokenStream> {
+    let (fields, _) = struct_fields(&input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let encode_fields = fields.iter().map(|field| {
+        let member = &field.member;
+        quote! {
+            written += ::tari_core::consensus::ConsensusEncoding::consensus_encode(&self.#member, writer)?;
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::tari_core::consensus::ConsensusEncoding for #name #ty_generics #where_clause {
+            fn consensus_encode<W: ::std::io::Write>(
+                &self,
+                writer: &mut W,
+            ) -> ::std::result::Result<usize, ::std::io::Error> {
+                #[allow(unused_mut)]
+                let mut written = 0;
+                #(#encode_fields)*
+                Ok(written)
+            }
+        }
+    })
+}
+
+pub fn expand_encoding_sized(input: DeriveInput) -> syn::Result<TokenStream> {
+    struct_fields(&input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::tari_core::consensus::ConsensusEncodingSized for #name #ty_generics #where_clause {}
+    })
+}
+
+pub fn expand_decoding(input: DeriveInput) -> syn::Result<TokenStream> {
+    let options = ContainerOptions::from_attrs(&input.attrs)?;
+    let (fields, shape) = struct_fields(&input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let decode_fields = fields.iter().map(decode_field).collect::<syn::Result<Vec<_>>>()?;
+    let members = fields.iter().map(|field| &field.member);
+    let bindings = fields.iter().map(|field| &field.binding);
+    let construct = match shape {
+        Fields::Named(_) => quote! { Self { #(#members: #bindings),* } },
+        Fields::Unnamed(_) => quote! { Self(#(#bindings),*) },
+        Fields::Unit => quote! { Self },
+    };
+    let validate = options.validate.map(|validate| {
+        quote! {
+            #validate(&__decoded).map_err(|err| {
+                ::std::io::Error::new(::std::io::ErrorKind::InvalidInput, err.to_string())
+            })?;
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::tari_core::consensus::ConsensusDecoding for #name #ty_generics #where_clause {
+            fn consensus_decode<R: ::std::io::Read>(reader: &mut R) -> ::std::result::Result<Self, ::std::io::Error> {
+                #(#decode_fields)*
+                let __decoded = #construct;
+                #validate
+                Ok(__decoded)
+            }
+        }
+    })
+}
+
+fn decode_field(field: &FieldInfo) -> syn::Result<TokenStream> {
+    let binding = &field.binding;
+    let ty = &field.ty;
+    let max_size = match &field.options.max_size {
+        Some(max_size) => max_size,
+        None => {
+            return Ok(quote! {
+                let #binding = <#ty as ::tari_core::consensus::ConsensusDecoding>::consensus_decode(reader)?;
+            })
+        },
+    };
+    let decode_ty = match vec_element_type(ty) {
+        Some(elem) if is_u8(elem) => quote! { ::tari_core::consensus::MaxSizeBytes<{ #max_size }> },
+        Some(elem) => quote! { ::tari_core::consensus::MaxSizeVec<#elem, { #max_size }> },
+        None => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`max_size` can only be used on `Vec` fields",
+            ))
+        },
+    };
+
+    Ok(quote! {
+        let #binding = <#decode_ty as ::tari_core::consensus::ConsensusDecoding>::consensus_decode(reader)?.into();
+    })
+}
+
+/// Returns `T` if the type is `Vec<T>`
+fn vec_element_type(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Vec" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match args.args.first()? {
+            GenericArgument::Type(elem) => Some(elem),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_u8(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none() && path.path.is_ident("u8"),
+        _ => false,
+    }
+}
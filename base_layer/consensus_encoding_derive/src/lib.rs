@@ -0,0 +1,76 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Derive macros for the consensus encoding traits in `tari_core::consensus`.
+//!
+//! ```rust,ignore
+//! #[derive(ConsensusEncoding, ConsensusEncodingSized, ConsensusDecoding)]
+//! #[consensus(validate = ProofOfWork::validate_pow_data_size)]
+//! pub struct ProofOfWork {
+//!     pub pow_algo: PowAlgorithm,
+//!     #[consensus(max_size = MAX_POW_DATA_SIZE)]
+//!     pub pow_data: Vec<u8>,
+//! }
+//! ```
+//!
+//! Fields are encoded in declaration order, so reordering the fields of a type changes its canonical encoding.
+//!
+//! Container attributes
+//! - `validate` is a function `fn(&Self) -> Result<(), E>` where `E: Display` that is called after decoding. Decoding
+//!   fails with `InvalidInput` if it returns an error.
+//!
+//! Field attributes
+//! - `max_size` is required to decode `Vec` fields, and limits the number of bytes (for `Vec<u8>`) or elements that are
+//!   decoded. It may be an integer literal or a constant.
+
+use proc_macro::TokenStream;
+
+mod expand;
+mod options;
+
+/// Derives `ConsensusEncoding` by encoding each field in declaration order
+#[proc_macro_derive(ConsensusEncoding, attributes(consensus))]
+pub fn derive_consensus_encoding(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    expand::expand_encoding(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Derives `ConsensusEncodingSized` using the default implementation, which counts the bytes written by
+/// `ConsensusEncoding`
+#[proc_macro_derive(ConsensusEncodingSized, attributes(consensus))]
+pub fn derive_consensus_encoding_sized(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    expand::expand_encoding_sized(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Derives `ConsensusDecoding` by decoding each field in declaration order
+#[proc_macro_derive(ConsensusDecoding, attributes(consensus))]
+pub fn derive_consensus_decoding(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    expand::expand_decoding(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
@@ -0,0 +1,92 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use syn::{parse::ParseStream, Attribute, Expr, Ident, Path, Token};
+
+const ATTR_NAME: &str = "consensus";
+
+/// Options set on the struct with `#[consensus(...)]`
+#[derive(Default)]
+pub struct ContainerOptions {
+    pub validate: Option<Path>,
+}
+
+impl ContainerOptions {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+        parse_options(attrs, |name, input| match name.to_string().as_str() {
+            "validate" => {
+                options.validate = Some(input.parse()?);
+                Ok(())
+            },
+            n => Err(syn::Error::new(
+                name.span(),
+                format!("expected `validate`, found `{}`", n),
+            )),
+        })?;
+        Ok(options)
+    }
+}
+
+/// Options set on a field with `#[consensus(...)]`
+#[derive(Default)]
+pub struct FieldOptions {
+    pub max_size: Option<Expr>,
+}
+
+impl FieldOptions {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+        parse_options(attrs, |name, input| match name.to_string().as_str() {
+            "max_size" => {
+                options.max_size = Some(input.parse()?);
+                Ok(())
+            },
+            n => Err(syn::Error::new(
+                name.span(),
+                format!("expected `max_size`, found `{}`", n),
+            )),
+        })?;
+        Ok(options)
+    }
+}
+
+/// Parses the comma separated `<name> = <value>` pairs of each `consensus` attribute, calling `parse_value` with the
+/// name and a stream positioned at the value
+fn parse_options<F>(attrs: &[Attribute], mut parse_value: F) -> syn::Result<()>
+where F: FnMut(&Ident, ParseStream<'_>) -> syn::Result<()> {
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(ATTR_NAME)) {
+        let parser = |input: ParseStream<'_>| {
+            while !input.is_empty() {
+                let name = input.parse::<Ident>()?;
+                input.parse::<Token![=]>()?;
+                parse_value(&name, input)?;
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+            }
+            Ok(())
+        };
+        attr.parse_args_with(parser)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Writes the seed corpus for each fuzz target.
+//!
+//! ```bash
+//! cargo run --example generate_corpus -- [corpus dir] [seeds per target]
+//! ```
+
+use std::{env, fs, io, path::Path};
+
+use tari_core::{consensus::ToConsensusBytes, transactions::CryptoFactories};
+use tari_core_fuzz::corpus::{
+    malformed_proofs_of_work,
+    mutations,
+    random_block_header,
+    random_proof_of_work,
+    random_transaction_output,
+};
+
+const DEFAULT_SEEDS_PER_TARGET: usize = 32;
+
+fn write_target<F>(corpus_dir: &Path, target: &str, num_seeds: usize, mut generate: F) -> io::Result<usize>
+where F: FnMut() -> Vec<u8> {
+    let dir = corpus_dir.join(target);
+    fs::create_dir_all(&dir)?;
+    let mut written = 0;
+    for i in 0..num_seeds {
+        let valid = generate();
+        fs::write(dir.join(format!("valid-{}", i)), &valid)?;
+        written += 1;
+        for (j, mutation) in mutations(&valid).into_iter().enumerate() {
+            fs::write(dir.join(format!("malformed-{}-{}", i, j)), mutation)?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+fn main() -> io::Result<()> {
+    let mut args = env::args().skip(1);
+    let corpus_dir = args.next().unwrap_or_else(|| "corpus".to_string());
+    let corpus_dir = Path::new(&corpus_dir);
+    let num_seeds = args
+        .next()
+        .map(|n| n.parse().expect("seeds per target must be a number"))
+        .unwrap_or(DEFAULT_SEEDS_PER_TARGET);
+
+    let mut written = write_target(corpus_dir, "proof_of_work", num_seeds, || {
+        random_proof_of_work().to_consensus_bytes()
+    })?;
+    for (i, malformed) in malformed_proofs_of_work().into_iter().enumerate() {
+        fs::write(
+            corpus_dir.join("proof_of_work").join(format!("invalid-{}", i)),
+            malformed,
+        )?;
+        written += 1;
+    }
+    written += write_target(corpus_dir, "block_header", num_seeds, || {
+        random_block_header().to_consensus_bytes()
+    })?;
+    let factories = CryptoFactories::default();
+    written += write_target(corpus_dir, "transaction_output", num_seeds, || {
+        random_transaction_output(&factories).to_consensus_bytes()
+    })?;
+
+    println!("Wrote {} seeds to {}", written, corpus_dir.display());
+    Ok(())
+}
@@ -0,0 +1,119 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Structured seed corpus generation. Seeds are valid encodings of randomly generated values, plus malformed variants
+//! of them that exercise the error paths of the decoders.
+
+use rand::{rngs::OsRng, Rng, RngCore};
+use tari_core::{
+    blocks::BlockHeader,
+    consensus::ToConsensusBytes,
+    proof_of_work::{PowAlgorithm, ProofOfWork},
+    transactions::{
+        tari_amount::MicroTari,
+        test_helpers::{TestParams, UtxoTestParams},
+        transaction_components::TransactionOutput,
+        CryptoFactories,
+    },
+};
+
+/// The maximum number of truncated and bit-flipped variants generated for each valid seed
+const MAX_MUTATIONS: usize = 16;
+
+/// The largest varint, used to claim an impossible length
+const MAX_VARINT: [u8; 10] = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+
+pub fn random_proof_of_work() -> ProofOfWork {
+    let pow_algo = [PowAlgorithm::Monero, PowAlgorithm::Sha3, PowAlgorithm::Sha3x][OsRng.gen_range(0..3)];
+    let mut pow_data = vec![0u8; OsRng.gen_range(0..=pow_algo.max_pow_data_size())];
+    OsRng.fill_bytes(&mut pow_data);
+    ProofOfWork { pow_algo, pow_data }
+}
+
+pub fn random_block_header() -> BlockHeader {
+    let mut header = BlockHeader::new(OsRng.gen_range(0..3));
+    header.height = OsRng.gen();
+    OsRng.fill_bytes(&mut header.prev_hash);
+    header.timestamp = OsRng.gen::<u64>().into();
+    OsRng.fill_bytes(&mut header.output_mr);
+    OsRng.fill_bytes(&mut header.witness_mr);
+    header.output_mmr_size = OsRng.gen();
+    OsRng.fill_bytes(&mut header.kernel_mr);
+    header.kernel_mmr_size = OsRng.gen();
+    OsRng.fill_bytes(&mut header.input_mr);
+    header.nonce = OsRng.gen();
+    header.pow = random_proof_of_work();
+    header
+}
+
+pub fn random_transaction_output(factories: &CryptoFactories) -> TransactionOutput {
+    let params = TestParams::new();
+    let output = params.create_unblinded_output(UtxoTestParams {
+        value: MicroTari(OsRng.gen_range(0..1_000_000_000)),
+        ..Default::default()
+    });
+    output
+        .as_transaction_output(factories)
+        .expect("test output is always valid")
+}
+
+/// Generates malformed variants of a valid encoding: truncations, single bit flips and trailing garbage
+pub fn mutations(valid: &[u8]) -> Vec<Vec<u8>> {
+    let mut mutations = Vec::new();
+    let step = (valid.len() / MAX_MUTATIONS).max(1);
+    for len in (0..valid.len()).step_by(step) {
+        mutations.push(valid[..len].to_vec());
+    }
+    for _ in 0..MAX_MUTATIONS.min(valid.len()) {
+        let mut flipped = valid.to_vec();
+        let pos = OsRng.gen_range(0..flipped.len());
+        flipped[pos] ^= 1 << OsRng.gen_range(0..8);
+        mutations.push(flipped);
+    }
+    let mut trailing = valid.to_vec();
+    trailing.extend_from_slice(&MAX_VARINT);
+    mutations.push(trailing);
+    mutations
+}
+
+/// Proof of work encodings with an unknown algorithm and with pow data length prefixes that exceed the maximum
+pub fn malformed_proofs_of_work() -> Vec<Vec<u8>> {
+    let mut unknown_algo = ProofOfWork::default().to_consensus_bytes();
+    unknown_algo[0] = 3;
+
+    let mut huge_len = vec![PowAlgorithm::Monero.as_u64() as u8];
+    huge_len.extend_from_slice(&MAX_VARINT);
+
+    let oversized = ProofOfWork {
+        pow_algo: PowAlgorithm::Monero,
+        pow_data: vec![0u8; PowAlgorithm::Monero.max_pow_data_size() + 1],
+    }
+    .to_consensus_bytes();
+
+    let sha3_with_data = ProofOfWork {
+        pow_algo: PowAlgorithm::Sha3,
+        pow_data: vec![1, 2, 3],
+    }
+    .to_consensus_bytes();
+
+    vec![unknown_algo, huge_len, oversized, sha3_with_data]
+}
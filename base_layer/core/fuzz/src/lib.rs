@@ -0,0 +1,57 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Fuzzing harness for the `ConsensusDecoding` implementations of types that are received from the network.
+//!
+//! Each fuzz target feeds arbitrary bytes to a decoder, which must either fail with an error or produce a value that
+//! survives an encode/decode round trip. Panics, aborts and excessive allocation are reported by libFuzzer as crashes.
+
+use std::fmt::Debug;
+
+use tari_core::consensus::{ConsensusDecoding, ConsensusEncodingSized, ToConsensusBytes};
+
+pub mod corpus;
+
+/// Decodes `data` as a `T` and, if it decodes, checks that the value re-encodes canonically
+pub fn check_consensus_decoding<T>(data: &[u8])
+where T: ConsensusDecoding + ConsensusEncodingSized + Debug {
+    let decoded = match T::consensus_decode(&mut &data[..]) {
+        Ok(decoded) => decoded,
+        Err(_) => return,
+    };
+
+    let encoded = decoded.to_consensus_bytes();
+    assert_eq!(
+        encoded.len(),
+        decoded.consensus_encode_exact_size(),
+        "exact size does not match the encoding of {:?}",
+        decoded
+    );
+    let redecoded = T::consensus_decode(&mut encoded.as_slice())
+        .unwrap_or_else(|err| panic!("re-encoded {:?} failed to decode: {}", decoded, err));
+    assert_eq!(
+        redecoded.to_consensus_bytes(),
+        encoded,
+        "encoding of {:?} is not stable across a round trip",
+        decoded
+    );
+}
@@ -0,0 +1,271 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    fmt::{Display, Formatter},
+    time::{Duration, Instant},
+};
+
+use log::*;
+use serde::{Deserialize, Serialize};
+use tari_common::configuration::serializers;
+use tari_common_types::chain_metadata::ChainMetadata;
+
+use super::{handle::PeerChainMetadata, LOG_TARGET};
+
+/// Configuration for the chain tip divergence monitor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TipDivergenceConfig {
+    /// Enable or disable the monitor
+    pub enabled: bool,
+    /// The fraction (0.0 - 1.0) of peers that must advertise a different tip before the node is considered divergent
+    pub divergence_threshold: f32,
+    /// The minimum number of peers with known chain metadata required before divergence is evaluated
+    pub min_peers: usize,
+    /// Peers within this many blocks of the local tip height are not considered divergent. This allows for normal
+    /// block propagation delays.
+    pub height_tolerance: u64,
+    /// How long the divergence must persist before a `TipDivergenceDetected` event is raised
+    #[serde(with = "serializers::seconds")]
+    pub max_divergence_period: Duration,
+    /// If true, the base node state machine will re-evaluate whether it should sync when divergence is detected
+    pub trigger_sync_on_divergence: bool,
+}
+
+impl Default for TipDivergenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            divergence_threshold: 0.5,
+            min_peers: 3,
+            height_tolerance: 2,
+            max_divergence_period: Duration::from_secs(10 * 60),
+            trigger_sync_on_divergence: false,
+        }
+    }
+}
+
+/// Details of a sustained divergence between the local chain tip and the tips advertised by connected peers.
+#[derive(Debug, Clone)]
+pub struct TipDivergence {
+    pub local_metadata: ChainMetadata,
+    pub divergent_peers: Vec<PeerChainMetadata>,
+    pub num_peers: usize,
+    pub divergent_for: Duration,
+    pub trigger_sync: bool,
+}
+
+impl Display for TipDivergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} peer(s) have advertised a chain tip that differs from the local tip (#{}) for {:.0?}",
+            self.divergent_peers.len(),
+            self.num_peers,
+            self.local_metadata.height_of_longest_chain(),
+            self.divergent_for
+        )
+    }
+}
+
+/// A change in divergence state reported by [TipDivergenceMonitor::update].
+#[derive(Debug, Clone)]
+pub enum TipDivergenceStatus {
+    Detected(TipDivergence),
+    Resolved,
+}
+
+/// Tracks how long a significant fraction of peers have been advertising a chain tip that differs from the local one.
+/// This is intended as an early warning for eclipse attacks or consensus bugs.
+pub struct TipDivergenceMonitor {
+    config: TipDivergenceConfig,
+    divergent_since: Option<Instant>,
+    is_alerted: bool,
+}
+
+impl TipDivergenceMonitor {
+    pub fn new(config: TipDivergenceConfig) -> Self {
+        Self {
+            config,
+            divergent_since: None,
+            is_alerted: false,
+        }
+    }
+
+    /// Returns true if a divergence has been detected and has not yet resolved
+    pub fn is_alerted(&self) -> bool {
+        self.is_alerted
+    }
+
+    /// Update the monitor with the latest local and peer chain metadata. A status is only returned when the divergence
+    /// state changes, i.e. once when divergence is first detected and once when it is resolved.
+    pub fn update(&mut self, local: &ChainMetadata, peers: &[PeerChainMetadata]) -> Option<TipDivergenceStatus> {
+        self.update_at(Instant::now(), local, peers)
+    }
+
+    fn update_at(
+        &mut self,
+        now: Instant,
+        local: &ChainMetadata,
+        peers: &[PeerChainMetadata],
+    ) -> Option<TipDivergenceStatus> {
+        if !self.config.enabled || peers.len() < self.config.min_peers {
+            return self.clear();
+        }
+
+        let divergent_peers = peers
+            .iter()
+            .filter(|p| self.is_divergent(local, p.claimed_chain_metadata()))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let fraction = divergent_peers.len() as f32 / peers.len() as f32;
+        if divergent_peers.is_empty() || fraction < self.config.divergence_threshold {
+            return self.clear();
+        }
+
+        let since = *self.divergent_since.get_or_insert(now);
+        let divergent_for = now.saturating_duration_since(since);
+        if self.is_alerted || divergent_for < self.config.max_divergence_period {
+            return None;
+        }
+
+        self.is_alerted = true;
+        Some(TipDivergenceStatus::Detected(TipDivergence {
+            local_metadata: local.clone(),
+            divergent_peers,
+            num_peers: peers.len(),
+            divergent_for,
+            trigger_sync: self.config.trigger_sync_on_divergence,
+        }))
+    }
+
+    fn is_divergent(&self, local: &ChainMetadata, peer: &ChainMetadata) -> bool {
+        if peer.best_block() == local.best_block() {
+            return false;
+        }
+        let local_height = local.height_of_longest_chain();
+        let peer_height = peer.height_of_longest_chain();
+        // A peer on a different tip at the same height is on a fork. Otherwise, we allow for the normal block
+        // propagation delay in either direction.
+        let height_diff = if peer_height > local_height {
+            peer_height - local_height
+        } else {
+            local_height - peer_height
+        };
+        height_diff == 0 || height_diff > self.config.height_tolerance
+    }
+
+    fn clear(&mut self) -> Option<TipDivergenceStatus> {
+        self.divergent_since = None;
+        if self.is_alerted {
+            debug!(target: LOG_TARGET, "Chain tip divergence resolved");
+            self.is_alerted = false;
+            return Some(TipDivergenceStatus::Resolved);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_comms::peer_manager::NodeId;
+
+    use super::*;
+
+    fn metadata(height: u64, hash: u8) -> ChainMetadata {
+        ChainMetadata::new(height, vec![hash; 32], 0, 0, u128::from(height))
+    }
+
+    fn peers(tips: &[(u64, u8)]) -> Vec<PeerChainMetadata> {
+        tips.iter()
+            .map(|(height, hash)| PeerChainMetadata::new(NodeId::new(), metadata(*height, *hash), None))
+            .collect()
+    }
+
+    fn config() -> TipDivergenceConfig {
+        TipDivergenceConfig {
+            min_peers: 2,
+            max_divergence_period: Duration::from_secs(60),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_detects_sustained_divergence() {
+        let mut monitor = TipDivergenceMonitor::new(config());
+        let local = metadata(100, 1);
+        let peers = peers(&[(100, 2), (100, 2), (100, 1)]);
+        let start = Instant::now();
+        assert!(monitor.update_at(start, &local, &peers).is_none());
+        assert!(monitor
+            .update_at(start + Duration::from_secs(30), &local, &peers)
+            .is_none());
+        let status = monitor
+            .update_at(start + Duration::from_secs(61), &local, &peers)
+            .unwrap();
+        match status {
+            TipDivergenceStatus::Detected(divergence) => {
+                assert_eq!(divergence.divergent_peers.len(), 2);
+                assert_eq!(divergence.num_peers, 3);
+            },
+            TipDivergenceStatus::Resolved => panic!("expected divergence to be detected"),
+        }
+        assert!(monitor.is_alerted());
+        // Only reported once
+        assert!(monitor
+            .update_at(start + Duration::from_secs(90), &local, &peers)
+            .is_none());
+
+        let peers = self::peers(&[(100, 1), (100, 1), (100, 1)]);
+        let status = monitor
+            .update_at(start + Duration::from_secs(100), &local, &peers)
+            .unwrap();
+        assert!(matches!(status, TipDivergenceStatus::Resolved));
+        assert!(!monitor.is_alerted());
+    }
+
+    #[test]
+    fn it_tolerates_propagation_delays() {
+        let mut monitor = TipDivergenceMonitor::new(config());
+        let local = metadata(100, 1);
+        let peers = peers(&[(101, 2), (99, 3), (102, 4)]);
+        let start = Instant::now();
+        assert!(monitor.update_at(start, &local, &peers).is_none());
+        assert!(monitor
+            .update_at(start + Duration::from_secs(120), &local, &peers)
+            .is_none());
+    }
+
+    #[test]
+    fn it_ignores_divergence_below_the_threshold() {
+        let mut monitor = TipDivergenceMonitor::new(config());
+        let local = metadata(100, 1);
+        let peers = peers(&[(100, 2), (100, 1), (100, 1)]);
+        let start = Instant::now();
+        assert!(monitor.update_at(start, &local, &peers).is_none());
+        assert!(monitor
+            .update_at(start + Duration::from_secs(120), &local, &peers)
+            .is_none());
+    }
+}
@@ -30,6 +30,8 @@ use tari_common_types::chain_metadata::ChainMetadata;
 use tari_comms::peer_manager::NodeId;
 use tokio::sync::broadcast;
 
+use super::TipDivergence;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PeerChainMetadata {
     node_id: NodeId,
@@ -82,6 +84,11 @@ impl Display for PeerChainMetadata {
 pub enum ChainMetadataEvent {
     PeerChainMetadataReceived(Vec<PeerChainMetadata>),
     NetworkSilence,
+    /// A significant fraction of peers have advertised a chain tip that differs from the local tip for longer than the
+    /// configured period
+    TipDivergenceDetected(TipDivergence),
+    /// A previously detected tip divergence has resolved
+    TipDivergenceResolved,
 }
 
 #[derive(Clone)]
@@ -26,12 +26,20 @@ use tari_p2p::services::liveness::LivenessHandle;
 use tari_service_framework::{async_trait, ServiceInitializationError, ServiceInitializer, ServiceInitializerContext};
 use tokio::sync::broadcast;
 
-use super::service::ChainMetadataService;
+use super::{service::ChainMetadataService, TipDivergenceConfig};
 use crate::base_node::{chain_metadata_service::handle::ChainMetadataHandle, comms_interface::LocalNodeCommsInterface};
 
 const LOG_TARGET: &str = "c::bn::chain_metadata_service::initializer";
 
-pub struct ChainMetadataServiceInitializer;
+pub struct ChainMetadataServiceInitializer {
+    tip_divergence_config: TipDivergenceConfig,
+}
+
+impl ChainMetadataServiceInitializer {
+    pub fn new(tip_divergence_config: TipDivergenceConfig) -> Self {
+        Self { tip_divergence_config }
+    }
+}
 
 #[async_trait]
 impl ServiceInitializer for ChainMetadataServiceInitializer {
@@ -43,12 +51,13 @@ impl ServiceInitializer for ChainMetadataServiceInitializer {
         let handle = ChainMetadataHandle::new(publisher.clone());
         context.register_handle(handle);
 
-        context.spawn_until_shutdown(|handles| {
+        let tip_divergence_config = self.tip_divergence_config.clone();
+        context.spawn_until_shutdown(move |handles| {
             let liveness = handles.expect_handle::<LivenessHandle>();
             let base_node = handles.expect_handle::<LocalNodeCommsInterface>();
             let connectivity = handles.expect_handle::<ConnectivityRequester>();
 
-            ChainMetadataService::new(liveness, base_node, connectivity, publisher).run()
+            ChainMetadataService::new(liveness, base_node, connectivity, publisher, tip_divergence_config).run()
         });
 
         debug!(target: LOG_TARGET, "Chain Metadata Service initialized");
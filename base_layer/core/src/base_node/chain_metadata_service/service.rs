@@ -37,8 +37,14 @@ use tokio::sync::broadcast;
 use super::{error::ChainMetadataSyncError, LOG_TARGET};
 use crate::{
     base_node::{
-        chain_metadata_service::handle::{ChainMetadataEvent, PeerChainMetadata},
+        chain_metadata_service::{
+            handle::{ChainMetadataEvent, PeerChainMetadata},
+            TipDivergenceConfig,
+            TipDivergenceMonitor,
+            TipDivergenceStatus,
+        },
         comms_interface::{BlockEvent, LocalNodeCommsInterface},
+        metrics,
     },
     chain_storage::BlockAddResult,
     proto::base_node as proto,
@@ -53,6 +59,8 @@ pub(super) struct ChainMetadataService {
     connectivity: ConnectivityRequester,
     event_publisher: broadcast::Sender<Arc<ChainMetadataEvent>>,
     number_of_rounds_no_pings: u16,
+    local_chain_metadata: Option<ChainMetadata>,
+    tip_divergence_monitor: TipDivergenceMonitor,
 }
 
 impl ChainMetadataService {
@@ -61,11 +69,13 @@ impl ChainMetadataService {
     /// ## Arguments
     /// `liveness` - the liveness service handle
     /// `base_node` - the base node service handle
+    /// `tip_divergence_config` - configuration for the chain tip divergence monitor
     pub fn new(
         liveness: LivenessHandle,
         base_node: LocalNodeCommsInterface,
         connectivity: ConnectivityRequester,
         event_publisher: broadcast::Sender<Arc<ChainMetadataEvent>>,
+        tip_divergence_config: TipDivergenceConfig,
     ) -> Self {
         Self {
             liveness,
@@ -74,6 +84,8 @@ impl ChainMetadataService {
             connectivity,
             event_publisher,
             number_of_rounds_no_pings: 0,
+            local_chain_metadata: None,
+            tip_divergence_monitor: TipDivergenceMonitor::new(tip_divergence_config),
         }
     }
 
@@ -148,6 +160,7 @@ impl ChainMetadataService {
     /// Tack this node's metadata on to ping/pongs sent by the liveness service
     async fn update_liveness_chain_metadata(&mut self) -> Result<(), ChainMetadataSyncError> {
         let chain_metadata = self.base_node.get_metadata().await?;
+        self.local_chain_metadata = Some(chain_metadata.clone());
         let bytes = proto::ChainMetadata::from(chain_metadata).to_encoded_bytes();
         self.liveness
             .set_metadata_entry(MetadataKey::ChainMetadata, bytes)
@@ -168,6 +181,7 @@ impl ChainMetadataService {
                 if event.metadata.has(MetadataKey::ChainMetadata) {
                     self.collect_chain_state_from_ping_pong(event)?;
                     self.send_chain_metadata_to_event_publisher().await?;
+                    self.check_tip_divergence();
                 }
             },
             // Received a pong, check if our neighbour sent it and it contains ChainMetadata
@@ -181,6 +195,7 @@ impl ChainMetadataService {
                 if event.metadata.has(MetadataKey::ChainMetadata) {
                     self.collect_chain_state_from_ping_pong(event)?;
                     self.send_chain_metadata_to_event_publisher().await?;
+                    self.check_tip_divergence();
                 }
             },
             // New ping round has begun
@@ -202,6 +217,7 @@ impl ChainMetadataService {
 
                 self.resize_chainstate_buffer(*num_peers);
             },
+            LivenessEvent::MonitoredPeerSlaBreached(_) | LivenessEvent::MonitoredPeerSlaRestored(_) => {},
         }
 
         Ok(())
@@ -223,6 +239,32 @@ impl ChainMetadataService {
         Ok(())
     }
 
+    fn check_tip_divergence(&mut self) {
+        let local = match self.local_chain_metadata.as_ref() {
+            Some(m) => m,
+            None => return,
+        };
+
+        let status = self.tip_divergence_monitor.update(local, &self.peer_chain_metadata);
+        let event = match status {
+            Some(TipDivergenceStatus::Detected(divergence)) => {
+                warn!(target: LOG_TARGET, "⚠️ Chain tip divergence detected: {}", divergence);
+                metrics::tip_divergence_detected().inc();
+                metrics::tip_divergent_peers().set(divergence.divergent_peers.len() as i64);
+                ChainMetadataEvent::TipDivergenceDetected(divergence)
+            },
+            Some(TipDivergenceStatus::Resolved) => {
+                info!(target: LOG_TARGET, "Chain tip divergence has resolved");
+                metrics::tip_divergent_peers().set(0);
+                ChainMetadataEvent::TipDivergenceResolved
+            },
+            None => return,
+        };
+
+        // send only fails if there are no subscribers.
+        let _size = self.event_publisher.send(Arc::new(event));
+    }
+
     fn resize_chainstate_buffer(&mut self, n: usize) {
         match self.peer_chain_metadata.capacity() {
             cap if n > cap => {
@@ -333,7 +375,13 @@ mod test {
         let connectivity_mock_state = mock.get_shared_state();
         task::spawn(mock.run());
 
-        let service = ChainMetadataService::new(liveness_handle, base_node, connectivity, publisher);
+        let service = ChainMetadataService::new(
+            liveness_handle,
+            base_node,
+            connectivity,
+            publisher,
+            TipDivergenceConfig::default(),
+        );
 
         (
             service,
@@ -0,0 +1,260 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tari_common_types::types::HashOutput;
+
+use crate::{blocks::NewBlockTemplate, mempool::StatsResponse, proof_of_work::PowAlgorithm};
+
+/// Templates older than this are always rebuilt so that newly arrived, higher fee transactions are eventually included
+const DEFAULT_MAX_TEMPLATE_AGE: Duration = Duration::from_secs(30);
+/// A template is rebuilt once the mempool weight has grown by more than 1/20th (5%) of the requested template weight
+const SIGNIFICANT_WEIGHT_CHANGE_DIVISOR: u64 = 20;
+/// Upper bound on the number of (algo, weight) combinations that are cached at any one time
+const MAX_CACHED_TEMPLATES: usize = 16;
+
+/// Caches new block templates so that frequent miner polls do not rebuild the template from the mempool each time.
+///
+/// A cached template is considered stale when the chain tip changes, when transactions have been removed from the
+/// mempool, when the mempool weight has changed significantly or when the template exceeds the maximum age. Each
+/// cached template is assigned a unique token that callers can pass back to find out whether the template changed.
+#[derive(Debug, Clone)]
+pub struct BlockTemplateCache {
+    inner: Arc<Mutex<CacheInner>>,
+    max_age: Duration,
+}
+
+#[derive(Debug, Default)]
+struct CacheInner {
+    entries: HashMap<(PowAlgorithm, u64), CachedTemplate>,
+    last_token: u64,
+}
+
+#[derive(Debug)]
+struct CachedTemplate {
+    template: NewBlockTemplate,
+    token: u64,
+    tip_hash: HashOutput,
+    unconfirmed_txs: usize,
+    mempool_weight: u64,
+    created_at: Instant,
+}
+
+impl CachedTemplate {
+    fn is_valid_for(&self, max_weight: u64, tip_hash: &[u8], stats: &StatsResponse, max_age: Duration) -> bool {
+        if self.tip_hash.as_slice() != tip_hash {
+            return false;
+        }
+        if self.created_at.elapsed() >= max_age {
+            return false;
+        }
+        // Transactions may have been removed because they were double spent or evicted, so the template could
+        // contain transactions that are no longer valid
+        if stats.unconfirmed_txs < self.unconfirmed_txs {
+            return false;
+        }
+        let weight_change = stats.total_weight.saturating_sub(self.mempool_weight);
+        weight_change <= max_weight / SIGNIFICANT_WEIGHT_CHANGE_DIVISOR
+    }
+}
+
+impl BlockTemplateCache {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(CacheInner::default())),
+            max_age,
+        }
+    }
+
+    /// Returns the cached template and its token for the given algorithm and weight if it is still valid for the
+    /// given chain tip and mempool state.
+    pub fn get(
+        &self,
+        algo: PowAlgorithm,
+        max_weight: u64,
+        tip_hash: &[u8],
+        stats: &StatsResponse,
+    ) -> Option<(NewBlockTemplate, u64)> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .entries
+            .get(&(algo, max_weight))
+            .filter(|entry| entry.is_valid_for(max_weight, tip_hash, stats, self.max_age))
+            .map(|entry| (entry.template.clone(), entry.token))
+    }
+
+    /// Caches a newly built template, replacing any previous template for the algorithm and weight. Templates built
+    /// on a different tip are discarded. Returns the token assigned to the template.
+    pub fn insert(
+        &self,
+        algo: PowAlgorithm,
+        max_weight: u64,
+        tip_hash: HashOutput,
+        stats: &StatsResponse,
+        template: NewBlockTemplate,
+    ) -> u64 {
+        let mut inner = self.inner.lock().unwrap();
+        inner.last_token += 1;
+        let token = inner.last_token;
+        inner.entries.retain(|_, entry| entry.tip_hash == tip_hash);
+        if inner.entries.len() >= MAX_CACHED_TEMPLATES {
+            let oldest = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.created_at)
+                .map(|(key, _)| *key);
+            if let Some(key) = oldest {
+                inner.entries.remove(&key);
+            }
+        }
+        inner.entries.insert((algo, max_weight), CachedTemplate {
+            template,
+            token,
+            tip_hash,
+            unconfirmed_txs: stats.unconfirmed_txs,
+            mempool_weight: stats.total_weight,
+            created_at: Instant::now(),
+        });
+        token
+    }
+}
+
+impl Default for BlockTemplateCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_TEMPLATE_AGE)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{blocks::BlockHeader, proof_of_work::Difficulty, transactions::tari_amount::MicroTari};
+
+    fn create_template(height: u64) -> NewBlockTemplate {
+        let mut header = BlockHeader::new(0);
+        header.height = height;
+        NewBlockTemplate::from_block(header.into_builder().build(), Difficulty::min(), MicroTari(0))
+    }
+
+    fn stats(unconfirmed_txs: usize, total_weight: u64) -> StatsResponse {
+        StatsResponse {
+            total_txs: unconfirmed_txs,
+            unconfirmed_txs,
+            reorg_txs: 0,
+            total_weight,
+            min_fee_per_gram: 0,
+        }
+    }
+
+    #[test]
+    fn it_returns_the_cached_template_for_the_same_tip_and_mempool() {
+        let cache = BlockTemplateCache::default();
+        let tip = vec![1u8; 32];
+        assert!(cache.get(PowAlgorithm::Sha3, 1000, &tip, &stats(1, 100)).is_none());
+        let token = cache.insert(
+            PowAlgorithm::Sha3,
+            1000,
+            tip.clone(),
+            &stats(1, 100),
+            create_template(1),
+        );
+        let (template, cached_token) = cache.get(PowAlgorithm::Sha3, 1000, &tip, &stats(1, 100)).unwrap();
+        assert_eq!(template.header.height, 1);
+        assert_eq!(cached_token, token);
+        assert!(cache.get(PowAlgorithm::Monero, 1000, &tip, &stats(1, 100)).is_none());
+        assert!(cache.get(PowAlgorithm::Sha3, 500, &tip, &stats(1, 100)).is_none());
+    }
+
+    #[test]
+    fn it_invalidates_on_new_tip() {
+        let cache = BlockTemplateCache::default();
+        cache.insert(
+            PowAlgorithm::Sha3,
+            1000,
+            vec![1u8; 32],
+            &stats(1, 100),
+            create_template(1),
+        );
+        assert!(cache
+            .get(PowAlgorithm::Sha3, 1000, &[2u8; 32], &stats(1, 100))
+            .is_none());
+    }
+
+    #[test]
+    fn it_invalidates_on_significant_mempool_change() {
+        let cache = BlockTemplateCache::default();
+        let tip = vec![1u8; 32];
+        cache.insert(
+            PowAlgorithm::Sha3,
+            1000,
+            tip.clone(),
+            &stats(2, 100),
+            create_template(1),
+        );
+        // Small increases in weight keep the template
+        assert!(cache.get(PowAlgorithm::Sha3, 1000, &tip, &stats(3, 150)).is_some());
+        // Growth of more than 5% of the template weight invalidates it
+        assert!(cache.get(PowAlgorithm::Sha3, 1000, &tip, &stats(4, 151)).is_none());
+        // Removed transactions always invalidate it
+        assert!(cache.get(PowAlgorithm::Sha3, 1000, &tip, &stats(1, 100)).is_none());
+    }
+
+    #[test]
+    fn it_invalidates_after_max_age() {
+        let cache = BlockTemplateCache::new(Duration::from_secs(0));
+        let tip = vec![1u8; 32];
+        cache.insert(
+            PowAlgorithm::Sha3,
+            1000,
+            tip.clone(),
+            &stats(1, 100),
+            create_template(1),
+        );
+        assert!(cache.get(PowAlgorithm::Sha3, 1000, &tip, &stats(1, 100)).is_none());
+    }
+
+    #[test]
+    fn it_assigns_new_tokens_and_discards_templates_for_old_tips() {
+        let cache = BlockTemplateCache::default();
+        let token1 = cache.insert(
+            PowAlgorithm::Sha3,
+            1000,
+            vec![1u8; 32],
+            &stats(1, 100),
+            create_template(1),
+        );
+        let token2 = cache.insert(
+            PowAlgorithm::Monero,
+            1000,
+            vec![2u8; 32],
+            &stats(1, 100),
+            create_template(2),
+        );
+        assert!(token2 > token1);
+        assert_eq!(cache.inner.lock().unwrap().entries.len(), 1);
+    }
+}
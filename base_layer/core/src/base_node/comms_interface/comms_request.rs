@@ -57,6 +57,7 @@ pub enum NodeCommsRequest {
     GetNewBlockTemplate(GetNewBlockTemplateRequest),
     GetNewBlock(NewBlockTemplate),
     FetchKernelByExcessSig(Signature),
+    FetchKernelLocationByExcessSig(Signature),
     FetchTokens {
         asset_public_key: PublicKey,
         unique_ids: Vec<Vec<u8>>,
@@ -70,12 +71,19 @@ pub enum NodeCommsRequest {
     FetchMempoolTransactionsByExcessSigs {
         excess_sigs: Vec<PrivateKey>,
     },
+    FetchFeeEstimates,
+    FetchTargetDifficultyEstimate {
+        pow_algo: PowAlgorithm,
+        height: Option<u64>,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetNewBlockTemplateRequest {
     pub algo: PowAlgorithm,
     pub max_weight: u64,
+    /// If set, the template is only returned if it has changed since the template identified by this token
+    pub changed_since_token: Option<u64>,
 }
 
 impl Display for NodeCommsRequest {
@@ -107,6 +115,12 @@ impl Display for NodeCommsRequest {
                 s.get_public_nonce().to_hex(),
                 s.get_signature().to_hex()
             ),
+            FetchKernelLocationByExcessSig(s) => write!(
+                f,
+                "FetchKernelLocationByExcessSig (signature=({}, {}))",
+                s.get_public_nonce().to_hex(),
+                s.get_signature().to_hex()
+            ),
             FetchTokens { .. } => {
                 write!(f, "FetchTokens")
             },
@@ -119,6 +133,10 @@ impl Display for NodeCommsRequest {
             FetchMempoolTransactionsByExcessSigs { .. } => {
                 write!(f, "FetchMempoolTransactionsByExcessSigs")
             },
+            FetchFeeEstimates => write!(f, "FetchFeeEstimates"),
+            FetchTargetDifficultyEstimate { pow_algo, height } => {
+                write!(f, "FetchTargetDifficultyEstimate ({}, height={:?})", pow_algo, height)
+            },
         }
     }
 }
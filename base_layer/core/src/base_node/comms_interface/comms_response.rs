@@ -31,9 +31,10 @@ use tari_common_types::{
 };
 
 use crate::{
+    base_node::proto::wallet_rpc::FeeEstimates,
     blocks::{Block, BlockHeader, ChainHeader, HistoricalBlock, NewBlockTemplate},
-    chain_storage::UtxoMinedInfo,
-    proof_of_work::Difficulty,
+    chain_storage::{KernelLocation, UtxoMinedInfo},
+    proof_of_work::{Difficulty, TargetDifficultyEstimate},
     transactions::transaction_components::{Transaction, TransactionKernel, TransactionOutput},
 };
 
@@ -42,12 +43,19 @@ use crate::{
 pub enum NodeCommsResponse {
     ChainMetadata(ChainMetadata),
     TransactionKernels(Vec<TransactionKernel>),
+    KernelLocation(Option<KernelLocation>),
     BlockHeaders(Vec<ChainHeader>),
     BlockHeader(Option<ChainHeader>),
     TransactionOutputs(Vec<TransactionOutput>),
     HistoricalBlocks(Vec<HistoricalBlock>),
     HistoricalBlock(Box<Option<HistoricalBlock>>),
-    NewBlockTemplate(NewBlockTemplate),
+    NewBlockTemplate {
+        template: NewBlockTemplate,
+        token: u64,
+    },
+    NewBlockTemplateUnchanged {
+        token: u64,
+    },
     NewBlock {
         success: bool,
         error: Option<String>,
@@ -66,6 +74,8 @@ pub enum NodeCommsResponse {
         output: Box<Option<UtxoMinedInfo>>,
     },
     FetchMempoolTransactionsByExcessSigsResponse(FetchMempoolTransactionsResponse),
+    TargetDifficultyEstimate(TargetDifficultyEstimate),
+    FeeEstimates(FeeEstimates),
 }
 
 impl Display for NodeCommsResponse {
@@ -75,12 +85,14 @@ impl Display for NodeCommsResponse {
         match self {
             ChainMetadata(_) => write!(f, "ChainMetadata"),
             TransactionKernels(_) => write!(f, "TransactionKernel"),
+            KernelLocation(_) => write!(f, "KernelLocation"),
             BlockHeaders(_) => write!(f, "BlockHeaders"),
             BlockHeader(_) => write!(f, "BlockHeader"),
             HistoricalBlock(_) => write!(f, "HistoricalBlock"),
             TransactionOutputs(_) => write!(f, "TransactionOutputs"),
             HistoricalBlocks(_) => write!(f, "HistoricalBlocks"),
-            NewBlockTemplate(_) => write!(f, "NewBlockTemplate"),
+            NewBlockTemplate { token, .. } => write!(f, "NewBlockTemplate(token={})", token),
+            NewBlockTemplateUnchanged { token } => write!(f, "NewBlockTemplateUnchanged(token={})", token),
             NewBlock {
                 success,
                 error,
@@ -103,6 +115,8 @@ impl Display for NodeCommsResponse {
                 resp.transactions.len(),
                 resp.not_found.len()
             ),
+            TargetDifficultyEstimate(estimate) => write!(f, "TargetDifficultyEstimate({})", estimate.pow_algo),
+            FeeEstimates(estimates) => write!(f, "FeeEstimates(tip_height={})", estimates.tip_height),
         }
     }
 }
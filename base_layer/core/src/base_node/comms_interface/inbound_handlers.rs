@@ -38,12 +38,16 @@ use crate::{
         comms_interface::{
             error::CommsInterfaceError,
             local_interface::BlockEventSender,
+            BlockTemplateCache,
             FetchMempoolTransactionsResponse,
             NodeCommsRequest,
             NodeCommsResponse,
             OutboundNodeCommsInterface,
         },
+        fee_estimate,
         metrics,
+        PeerOffense,
+        PeerOffenseTracker,
     },
     blocks::{Block, BlockBuilder, BlockHeader, ChainBlock, NewBlock, NewBlockTemplate},
     chain_storage::{async_db::AsyncBlockchainDb, BlockAddResult, BlockchainBackend, ChainStorageError, PrunedOutput},
@@ -58,6 +62,11 @@ const MAX_HEADERS_PER_RESPONSE: u32 = 100;
 const MAX_REQUEST_BY_BLOCK_HASHES: usize = 100;
 const MAX_REQUEST_BY_KERNEL_EXCESS_SIGS: usize = 100;
 const MAX_REQUEST_BY_UTXO_HASHES: usize = 100;
+/// The maximum number of missing ancestors that are fetched one after the other to connect an orphan block. Longer
+/// forks are left to block sync.
+const MAX_ORPHAN_PARENT_DEPTH: usize = 10;
+/// Missing parents are not requested for orphans that are more than this many blocks below the tip
+const MAX_ORPHAN_PARENT_HEIGHT_BELOW_TIP: u64 = 100;
 
 /// Events that can be published on the Validated Block Event Stream
 /// Broadcast is to notify subscribers if this is a valid propagated block event
@@ -78,6 +87,8 @@ pub struct InboundNodeCommsHandlers<B> {
     new_block_request_semaphore: Arc<Semaphore>,
     outbound_nci: OutboundNodeCommsInterface,
     connectivity: ConnectivityRequester,
+    offense_tracker: PeerOffenseTracker,
+    block_template_cache: BlockTemplateCache,
 }
 
 impl<B> InboundNodeCommsHandlers<B>
@@ -91,6 +102,7 @@ where B: BlockchainBackend + 'static
         consensus_manager: ConsensusManager,
         outbound_nci: OutboundNodeCommsInterface,
         connectivity: ConnectivityRequester,
+        offense_tracker: PeerOffenseTracker,
     ) -> Self {
         Self {
             block_event_sender,
@@ -100,6 +112,8 @@ where B: BlockchainBackend + 'static
             new_block_request_semaphore: Arc::new(Semaphore::new(1)),
             outbound_nci,
             connectivity,
+            offense_tracker,
+            block_template_cache: BlockTemplateCache::default(),
         }
     }
 
@@ -342,12 +356,10 @@ where B: BlockchainBackend + 'static
             },
             NodeCommsRequest::GetNewBlockTemplate(request) => {
                 let best_block_header = self.blockchain_db.fetch_tip_header().await?;
-
-                let mut header = BlockHeader::from_previous(best_block_header.header());
-                let constants = self.consensus_manager.consensus_constants(header.height);
-                header.version = constants.blockchain_version();
-                header.pow.pow_algo = request.algo;
-
+                let mempool_stats = self.mempool.stats().await?;
+                let constants = self
+                    .consensus_manager
+                    .consensus_constants(best_block_header.height() + 1);
                 let constants_weight = constants.get_max_block_weight_excluding_coinbase();
                 let asking_weight = if request.max_weight > constants_weight || request.max_weight == 0 {
                     constants_weight
@@ -355,43 +367,38 @@ where B: BlockchainBackend + 'static
                     request.max_weight
                 };
 
-                debug!(
-                    target: LOG_TARGET,
-                    "Fetching transactions with a maximum weight of {} for the template", asking_weight
-                );
-                let transactions = self
-                    .mempool
-                    .retrieve(asking_weight)
-                    .await?
-                    .into_iter()
-                    .map(|tx| Arc::try_unwrap(tx).unwrap_or_else(|tx| (*tx).clone()))
-                    .collect::<Vec<_>>();
-
-                debug!(
-                    target: LOG_TARGET,
-                    "Adding {} transaction(s) to new block template",
-                    transactions.len(),
-                );
-
-                let prev_hash = header.prev_hash.clone();
-                let height = header.height;
-
-                let block_template = NewBlockTemplate::from_block(
-                    header.into_builder().with_transactions(transactions).build(),
-                    self.get_target_difficulty_for_next_block(request.algo, constants, prev_hash)
-                        .await?,
-                    self.consensus_manager.get_block_reward_at(height),
-                );
+                if let Some((block_template, token)) =
+                    self.block_template_cache
+                        .get(request.algo, asking_weight, best_block_header.hash(), &mempool_stats)
+                {
+                    metrics::block_template_cache_hits().inc();
+                    if request.changed_since_token == Some(token) {
+                        debug!(target: LOG_TARGET, "Block template {} is unchanged", token);
+                        return Ok(NodeCommsResponse::NewBlockTemplateUnchanged { token });
+                    }
+                    debug!(target: LOG_TARGET, "Returning cached block template {}", token);
+                    return Ok(NodeCommsResponse::NewBlockTemplate {
+                        template: block_template,
+                        token,
+                    });
+                }
+                metrics::block_template_cache_misses().inc();
 
-                debug!(target: LOG_TARGET, "New template block: {}", block_template);
-                debug!(
-                    target: LOG_TARGET,
-                    "New block template requested at height {}, weight: {}",
-                    block_template.header.height,
-                    block_template.body.calculate_weight(constants.transaction_weight())
+                let tip_hash = best_block_header.hash().clone();
+                let block_template = self
+                    .build_new_block_template(best_block_header.header(), request.algo, asking_weight)
+                    .await?;
+                let token = self.block_template_cache.insert(
+                    request.algo,
+                    asking_weight,
+                    tip_hash,
+                    &mempool_stats,
+                    block_template.clone(),
                 );
-                trace!(target: LOG_TARGET, "{}", block_template);
-                Ok(NodeCommsResponse::NewBlockTemplate(block_template))
+                Ok(NodeCommsResponse::NewBlockTemplate {
+                    template: block_template,
+                    token,
+                })
             },
             NodeCommsRequest::GetNewBlock(block_template) => {
                 debug!(target: LOG_TARGET, "Prepared block: {}", block_template);
@@ -422,6 +429,13 @@ where B: BlockchainBackend + 'static
 
                 Ok(NodeCommsResponse::TransactionKernels(kernels))
             },
+            NodeCommsRequest::FetchKernelLocationByExcessSig(signature) => {
+                let location = self
+                    .blockchain_db
+                    .fetch_kernel_location_by_excess_sig(signature)
+                    .await?;
+                Ok(NodeCommsResponse::KernelLocation(location))
+            },
             NodeCommsRequest::FetchTokens {
                 asset_public_key,
                 unique_ids,
@@ -492,6 +506,25 @@ where B: BlockchainBackend + 'static
                     },
                 ))
             },
+            NodeCommsRequest::FetchTargetDifficultyEstimate { pow_algo, height } => {
+                let height = match height {
+                    Some(height) => height,
+                    None => self.blockchain_db.get_chain_metadata().await?.height_of_longest_chain(),
+                };
+                let estimate = self
+                    .blockchain_db
+                    .fetch_target_difficulty_estimate(pow_algo, height)
+                    .await?;
+                Ok(NodeCommsResponse::TargetDifficultyEstimate(estimate))
+            },
+            NodeCommsRequest::FetchFeeEstimates => {
+                let mempool_txs = self.mempool.unconfirmed_summaries().await?;
+                let mempool_stats = self.mempool.stats().await?;
+                let estimates =
+                    fee_estimate::estimate_fees(&self.blockchain_db, &mempool_txs, mempool_stats.min_fee_per_gram)
+                        .await?;
+                Ok(NodeCommsResponse::FeeEstimates(estimates))
+            },
         }
     }
 
@@ -697,6 +730,16 @@ where B: BlockchainBackend + 'static
         &mut self,
         block: Arc<Block>,
         source_peer: Option<NodeId>,
+    ) -> Result<BlockHash, CommsInterfaceError> {
+        self.handle_block_at_orphan_depth(block, source_peer, 0).await
+    }
+
+    /// Handles a block that was received as the `orphan_depth`th missing ancestor of an orphan block
+    async fn handle_block_at_orphan_depth(
+        &mut self,
+        block: Arc<Block>,
+        source_peer: Option<NodeId>,
+        orphan_depth: usize,
     ) -> Result<BlockHash, CommsInterfaceError> {
         let block_hash = block.hash();
         let block_height = block.header.height;
@@ -733,6 +776,16 @@ where B: BlockchainBackend + 'static
                     BlockAddResult::ChainReorg { .. } => true,
                 };
 
+                if let (BlockAddResult::OrphanBlock, Some(source_peer)) = (&block_add_result, &source_peer) {
+                    self.request_missing_orphan_parent(
+                        block_hash.clone(),
+                        block_height,
+                        source_peer.clone(),
+                        orphan_depth,
+                    )
+                    .await;
+                }
+
                 self.update_block_result_metrics(&block_add_result).await?;
                 self.publish_block_event(BlockEvent::ValidBlockAdded(block.clone(), block_add_result));
 
@@ -761,13 +814,14 @@ where B: BlockchainBackend + 'static
                     e
                 );
                 if let Some(source_peer) = source_peer {
-                    if let Err(e) = self
-                        .connectivity
-                        .ban_peer(source_peer, format!("Peer propagated invalid block: {}", e))
-                        .await
-                    {
-                        error!(target: LOG_TARGET, "Failed to ban peer: {}", e);
-                    }
+                    self.offense_tracker
+                        .report(
+                            &mut self.connectivity,
+                            source_peer,
+                            PeerOffense::InvalidBlock,
+                            format!("Peer propagated invalid block: {}", e),
+                        )
+                        .await;
                 }
                 self.publish_block_event(BlockEvent::AddBlockFailed(block));
                 Err(e.into())
@@ -781,6 +835,117 @@ where B: BlockchainBackend + 'static
         }
     }
 
+    /// Schedules a request for the block that is needed to connect the given orphan from the peer that sent it. Nothing
+    /// is requested if too many ancestors have already been fetched for the orphan or if the orphan is too far below
+    /// the tip, in which case the fork is left to block sync.
+    async fn request_missing_orphan_parent(
+        &self,
+        orphan_hash: BlockHash,
+        orphan_height: u64,
+        source_peer: NodeId,
+        orphan_depth: usize,
+    ) {
+        if orphan_depth >= MAX_ORPHAN_PARENT_DEPTH {
+            debug!(
+                target: LOG_TARGET,
+                "Not requesting the missing parent of orphan block {} because {} ancestors have already been fetched",
+                orphan_hash.to_hex(),
+                orphan_depth
+            );
+            return;
+        }
+        match self.blockchain_db.get_chain_metadata().await {
+            Ok(metadata)
+                if orphan_height.saturating_add(MAX_ORPHAN_PARENT_HEIGHT_BELOW_TIP) <
+                    metadata.height_of_longest_chain() =>
+            {
+                debug!(
+                    target: LOG_TARGET,
+                    "Not requesting the missing parent of orphan block {} at height {} because it is too far below \
+                     the tip at height {}",
+                    orphan_hash.to_hex(),
+                    orphan_height,
+                    metadata.height_of_longest_chain()
+                );
+                return;
+            },
+            Ok(_) => {},
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Failed to fetch chain metadata: {}", e);
+                return;
+            },
+        }
+
+        match self
+            .blockchain_db
+            .fetch_missing_orphan_parent(orphan_hash.clone())
+            .await
+        {
+            Ok(Some(parent_hash)) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Orphan block {} is missing ancestor {}. Requesting it from peer `{}`.",
+                    orphan_hash.to_hex(),
+                    parent_hash.to_hex(),
+                    source_peer
+                );
+                if let Err(e) = self
+                    .outbound_nci
+                    .request_orphan_parent(parent_hash, source_peer, orphan_depth + 1)
+                {
+                    warn!(target: LOG_TARGET, "Failed to schedule orphan parent request: {}", e);
+                }
+            },
+            Ok(None) => {},
+            Err(e) => warn!(
+                target: LOG_TARGET,
+                "Failed to find the missing parent of orphan block {}: {}",
+                orphan_hash.to_hex(),
+                e
+            ),
+        }
+    }
+
+    /// Requests the missing parent of an orphan block from the given peer and adds it to the chain. The parent is
+    /// skipped if it has been received since the request was scheduled. A peer that does not have the parent of an
+    /// orphan that it sent is reported, since it could otherwise make this node chase parents that do not exist.
+    pub async fn fetch_orphan_parent(
+        &mut self,
+        parent_hash: BlockHash,
+        source_peer: NodeId,
+        depth: usize,
+    ) -> Result<(), CommsInterfaceError> {
+        if self.blockchain_db.block_exists(parent_hash.clone()).await? {
+            return Ok(());
+        }
+        let mut blocks = self
+            .outbound_nci
+            .request_blocks_by_hashes_from_peer(vec![parent_hash.clone()], Some(source_peer.clone()))
+            .await?;
+        let block = match blocks.pop() {
+            Some(block) if block.hash() == &parent_hash => Arc::new(block.try_into_block()?),
+            _ => {
+                let details = format!(
+                    "Peer `{}` did not return the requested orphan parent {}",
+                    source_peer,
+                    parent_hash.to_hex()
+                );
+                self.offense_tracker
+                    .report(
+                        &mut self.connectivity,
+                        source_peer,
+                        PeerOffense::UnknownOrphanParent,
+                        details.clone(),
+                    )
+                    .await;
+                return Err(CommsInterfaceError::InvalidPeerResponse(details));
+            },
+        };
+        self.handle_block_at_orphan_depth(block, Some(source_peer), depth)
+            .await?;
+        Ok(())
+    }
+
     fn publish_block_event(&self, event: BlockEvent) {
         if let Err(event) = self.block_event_sender.send(Arc::new(event)) {
             debug!(target: LOG_TARGET, "No event subscribers. Event {} dropped.", event.0)
@@ -790,7 +955,7 @@ where B: BlockchainBackend + 'static
     async fn update_block_result_metrics(&self, block_add_result: &BlockAddResult) -> Result<(), CommsInterfaceError> {
         fn update_target_difficulty(block: &ChainBlock) {
             match block.header().pow_algo() {
-                PowAlgorithm::Sha3 => {
+                PowAlgorithm::Sha3 | PowAlgorithm::Sha3x => {
                     metrics::target_difficulty_sha(block.height())
                         .set(i64::try_from(block.accumulated_data().target_difficulty.as_u64()).unwrap_or(i64::MAX));
                 },
@@ -827,6 +992,56 @@ where B: BlockchainBackend + 'static
         Ok(())
     }
 
+    async fn build_new_block_template(
+        &self,
+        best_block_header: &BlockHeader,
+        algo: PowAlgorithm,
+        asking_weight: u64,
+    ) -> Result<NewBlockTemplate, CommsInterfaceError> {
+        let mut header = BlockHeader::from_previous(best_block_header);
+        let constants = self.consensus_manager.consensus_constants(header.height);
+        header.version = constants.blockchain_version();
+        header.pow.pow_algo = algo;
+
+        debug!(
+            target: LOG_TARGET,
+            "Fetching transactions with a maximum weight of {} for the template", asking_weight
+        );
+        let transactions = self
+            .mempool
+            .retrieve(asking_weight)
+            .await?
+            .into_iter()
+            .map(|tx| Arc::try_unwrap(tx).unwrap_or_else(|tx| (*tx).clone()))
+            .collect::<Vec<_>>();
+
+        debug!(
+            target: LOG_TARGET,
+            "Adding {} transaction(s) to new block template",
+            transactions.len(),
+        );
+
+        let prev_hash = header.prev_hash.clone();
+        let height = header.height;
+
+        let block_template = NewBlockTemplate::from_block(
+            header.into_builder().with_transactions(transactions).build(),
+            self.get_target_difficulty_for_next_block(algo, constants, prev_hash)
+                .await?,
+            self.consensus_manager.get_block_reward_at(height),
+        );
+
+        debug!(target: LOG_TARGET, "New template block: {}", block_template);
+        debug!(
+            target: LOG_TARGET,
+            "New block template requested at height {}, weight: {}",
+            block_template.header.height,
+            block_template.body.calculate_weight(constants.transaction_weight())
+        );
+        trace!(target: LOG_TARGET, "{}", block_template);
+        Ok(block_template)
+    }
+
     async fn get_target_difficulty_for_next_block(
         &self,
         pow_algo: PowAlgorithm,
@@ -857,6 +1072,8 @@ impl<B> Clone for InboundNodeCommsHandlers<B> {
             new_block_request_semaphore: self.new_block_request_semaphore.clone(),
             outbound_nci: self.outbound_nci.clone(),
             connectivity: self.connectivity.clone(),
+            offense_tracker: self.offense_tracker.clone(),
+            block_template_cache: self.block_template_cache.clone(),
         }
     }
 }
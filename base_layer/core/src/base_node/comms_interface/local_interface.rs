@@ -30,22 +30,30 @@ use tari_service_framework::{reply_channel::SenderService, Service};
 use tokio::sync::broadcast;
 
 use crate::{
-    base_node::comms_interface::{
-        comms_request::GetNewBlockTemplateRequest,
-        error::CommsInterfaceError,
-        BlockEvent,
-        NodeCommsRequest,
-        NodeCommsResponse,
+    base_node::{
+        comms_interface::{
+            comms_request::GetNewBlockTemplateRequest,
+            error::CommsInterfaceError,
+            BlockEvent,
+            NodeCommsRequest,
+            NodeCommsResponse,
+            ReorgEvent,
+            ReorgEventReceiver,
+            ReorgEventSender,
+        },
+        proto::wallet_rpc::FeeEstimates,
     },
     blocks::{Block, ChainHeader, HistoricalBlock, NewBlockTemplate},
-    chain_storage::UtxoMinedInfo,
-    proof_of_work::PowAlgorithm,
+    chain_storage::{KernelLocation, UtxoMinedInfo},
+    proof_of_work::{PowAlgorithm, TargetDifficultyEstimate},
     transactions::transaction_components::{TransactionKernel, TransactionOutput},
 };
 
 pub type BlockEventSender = broadcast::Sender<Arc<BlockEvent>>;
 pub type BlockEventReceiver = broadcast::Receiver<Arc<BlockEvent>>;
 
+const REORG_EVENT_CHANNEL_SIZE: usize = 50;
+
 /// The InboundNodeCommsInterface provides an interface to request information from the current local node by other
 /// internal services.
 #[derive(Clone)]
@@ -53,6 +61,7 @@ pub struct LocalNodeCommsInterface {
     request_sender: SenderService<NodeCommsRequest, Result<NodeCommsResponse, CommsInterfaceError>>,
     block_sender: SenderService<Block, Result<BlockHash, CommsInterfaceError>>,
     block_event_sender: BlockEventSender,
+    reorg_event_sender: ReorgEventSender,
 }
 
 impl LocalNodeCommsInterface {
@@ -62,10 +71,12 @@ impl LocalNodeCommsInterface {
         block_sender: SenderService<Block, Result<BlockHash, CommsInterfaceError>>,
         block_event_sender: BlockEventSender,
     ) -> Self {
+        let (reorg_event_sender, _) = broadcast::channel(REORG_EVENT_CHANNEL_SIZE);
         Self {
             request_sender,
             block_sender,
             block_event_sender,
+            reorg_event_sender,
         }
     }
 
@@ -139,16 +150,35 @@ impl LocalNodeCommsInterface {
         pow_algorithm: PowAlgorithm,
         max_weight: u64,
     ) -> Result<NewBlockTemplate, CommsInterfaceError> {
+        match self
+            .get_new_block_template_if_changed(pow_algorithm, max_weight, None)
+            .await?
+        {
+            Some((new_block_template, _)) => Ok(new_block_template),
+            None => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Request a new mineable block template along with its template token. If `changed_since_token` is given and the
+    /// template identified by that token is still current, `None` is returned instead of the template.
+    pub async fn get_new_block_template_if_changed(
+        &mut self,
+        pow_algorithm: PowAlgorithm,
+        max_weight: u64,
+        changed_since_token: Option<u64>,
+    ) -> Result<Option<(NewBlockTemplate, u64)>, CommsInterfaceError> {
         let request = GetNewBlockTemplateRequest {
             algo: pow_algorithm,
             max_weight,
+            changed_since_token,
         };
         match self
             .request_sender
             .call(NodeCommsRequest::GetNewBlockTemplate(request))
             .await??
         {
-            NodeCommsResponse::NewBlockTemplate(new_block_template) => Ok(new_block_template),
+            NodeCommsResponse::NewBlockTemplate { template, token } => Ok(Some((template, token))),
+            NodeCommsResponse::NewBlockTemplateUnchanged { .. } => Ok(None),
             _ => Err(CommsInterfaceError::UnexpectedApiResponse),
         }
     }
@@ -187,6 +217,17 @@ impl LocalNodeCommsInterface {
         self.block_event_sender.send(Arc::new(event)).unwrap_or(0)
     }
 
+    /// Returns a stream of the reorgs of the main chain, including rewinds. Each event is published once the mempool
+    /// has processed the reorg.
+    pub fn get_reorg_event_stream(&self) -> ReorgEventReceiver {
+        self.reorg_event_sender.subscribe()
+    }
+
+    pub fn publish_reorg_event(&self, event: ReorgEvent) -> usize {
+        // If event send fails, that means that there are no receivers (i.e. it was sent to zero receivers)
+        self.reorg_event_sender.send(Arc::new(event)).unwrap_or(0)
+    }
+
     pub async fn fetch_matching_utxos(
         &mut self,
         hashes: Vec<HashOutput>,
@@ -258,6 +299,21 @@ impl LocalNodeCommsInterface {
         }
     }
 
+    /// Return the blocks matching the given hashes. Blocks that cannot be found are omitted from the result.
+    pub async fn get_blocks_by_hashes(
+        &mut self,
+        hashes: Vec<HashOutput>,
+    ) -> Result<Vec<HistoricalBlock>, CommsInterfaceError> {
+        match self
+            .request_sender
+            .call(NodeCommsRequest::FetchBlocksByHash(hashes))
+            .await??
+        {
+            NodeCommsResponse::HistoricalBlocks(blocks) => Ok(blocks),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
+
     /// Searches for a kernel via the excess sig
     pub async fn get_kernel_by_excess_sig(
         &mut self,
@@ -273,6 +329,48 @@ impl LocalNodeCommsInterface {
         }
     }
 
+    /// Returns the block hash, height and MMR position of the kernel with the given excess signature, if it has been
+    /// mined
+    pub async fn get_kernel_location_by_excess_sig(
+        &mut self,
+        excess_sig: Signature,
+    ) -> Result<Option<KernelLocation>, CommsInterfaceError> {
+        match self
+            .request_sender
+            .call(NodeCommsRequest::FetchKernelLocationByExcessSig(excess_sig))
+            .await??
+        {
+            NodeCommsResponse::KernelLocation(location) => Ok(location),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns an estimate of the difficulty for the given proof of work algorithm for the block following the block at
+    /// the given height, or the chain tip if no height is given
+    pub async fn get_target_difficulty_estimate(
+        &mut self,
+        pow_algo: PowAlgorithm,
+        height: Option<u64>,
+    ) -> Result<TargetDifficultyEstimate, CommsInterfaceError> {
+        match self
+            .request_sender
+            .call(NodeCommsRequest::FetchTargetDifficultyEstimate { pow_algo, height })
+            .await??
+        {
+            NodeCommsResponse::TargetDifficultyEstimate(estimate) => Ok(estimate),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Returns suggested fees per gram for a transaction to be mined within the next block, ~6 blocks and ~24 blocks,
+    /// based on the fees paid in recent blocks and the current mempool congestion
+    pub async fn get_fee_estimates(&mut self) -> Result<FeeEstimates, CommsInterfaceError> {
+        match self.request_sender.call(NodeCommsRequest::FetchFeeEstimates).await?? {
+            NodeCommsResponse::FeeEstimates(estimates) => Ok(estimates),
+            _ => Err(CommsInterfaceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_tokens(
         &mut self,
         asset_public_key: PublicKey,
@@ -20,6 +20,9 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+mod block_template_cache;
+pub use block_template_cache::BlockTemplateCache;
+
 mod comms_request;
 pub use comms_request::{GetNewBlockTemplateRequest, MmrStateRequest, NodeCommsRequest};
 
@@ -35,6 +38,9 @@ pub use inbound_handlers::{BlockEvent, InboundNodeCommsHandlers};
 mod local_interface;
 pub use local_interface::{BlockEventReceiver, BlockEventSender, LocalNodeCommsInterface};
 
+mod reorg_event;
+pub use reorg_event::{ReorgEvent, ReorgEventReceiver, ReorgEventSender};
+
 // TODO: Remove this entirely when able
 mod outbound_interface;
 pub use outbound_interface::OutboundNodeCommsInterface;
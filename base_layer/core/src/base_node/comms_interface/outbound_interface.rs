@@ -40,6 +40,7 @@ use crate::{
 pub struct OutboundNodeCommsInterface {
     request_sender: SenderService<(NodeCommsRequest, Option<NodeId>), Result<NodeCommsResponse, CommsInterfaceError>>,
     block_sender: UnboundedSender<(NewBlock, Vec<NodeId>)>,
+    orphan_parent_sender: UnboundedSender<(BlockHash, NodeId, usize)>,
 }
 
 impl OutboundNodeCommsInterface {
@@ -50,10 +51,12 @@ impl OutboundNodeCommsInterface {
             Result<NodeCommsResponse, CommsInterfaceError>,
         >,
         block_sender: UnboundedSender<(NewBlock, Vec<NodeId>)>,
+        orphan_parent_sender: UnboundedSender<(BlockHash, NodeId, usize)>,
     ) -> Self {
         Self {
             request_sender,
             block_sender,
+            orphan_parent_sender,
         }
     }
 
@@ -105,4 +108,20 @@ impl OutboundNodeCommsInterface {
             CommsInterfaceError::InternalChannelError(format!("Failed to send on block_sender: {}", err))
         })
     }
+
+    /// Schedule a request for the missing parent of an orphan block from the peer that sent the orphan. If the request
+    /// fails, the parent is requested from other peers that sent orphans with the same missing parent. `depth` is the
+    /// number of orphan parents that have already been fetched to try to connect the orphan.
+    pub fn request_orphan_parent(
+        &self,
+        parent_hash: BlockHash,
+        node_id: NodeId,
+        depth: usize,
+    ) -> Result<(), CommsInterfaceError> {
+        self.orphan_parent_sender
+            .send((parent_hash, node_id, depth))
+            .map_err(|err| {
+                CommsInterfaceError::InternalChannelError(format!("Failed to send on orphan_parent_sender: {}", err))
+            })
+    }
 }
@@ -0,0 +1,128 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{fmt, fmt::Display, sync::Arc};
+
+use tari_common_types::types::BlockHash;
+use tari_utilities::{hex::Hex, Hashable};
+use tokio::sync::broadcast;
+
+use crate::{blocks::Block, transactions::transaction_components::Transaction};
+
+pub type ReorgEventSender = broadcast::Sender<Arc<ReorgEvent>>;
+pub type ReorgEventReceiver = broadcast::Receiver<Arc<ReorgEvent>>;
+
+/// A reorg of the main chain. It is published once the mempool has processed the reorg, so that subscribers can
+/// update their state from the changed blocks and transactions instead of re-scanning the chain.
+#[derive(Debug, Clone)]
+pub struct ReorgEvent {
+    /// The height of the last block that the old and new chains have in common
+    pub fork_height: u64,
+    /// The hashes of the blocks that were removed from the main chain, in ascending height order
+    pub removed_blocks: Vec<BlockHash>,
+    /// The hashes of the blocks that were added to the main chain, in ascending height order
+    pub added_blocks: Vec<BlockHash>,
+    /// The transactions from the removed blocks that were returned to the mempool
+    pub returned_transactions: Vec<Arc<Transaction>>,
+}
+
+impl ReorgEvent {
+    pub fn new(
+        removed_blocks: &[Arc<Block>],
+        added_blocks: &[Arc<Block>],
+        returned_transactions: Vec<Arc<Transaction>>,
+    ) -> Self {
+        let fork_height = removed_blocks
+            .iter()
+            .chain(added_blocks)
+            .map(|block| block.header.height)
+            .min()
+            .unwrap_or(0)
+            .saturating_sub(1);
+        Self {
+            fork_height,
+            removed_blocks: hashes_by_height(removed_blocks),
+            added_blocks: hashes_by_height(added_blocks),
+            returned_transactions,
+        }
+    }
+
+    /// The number of blocks that were removed from the main chain
+    pub fn depth(&self) -> usize {
+        self.removed_blocks.len()
+    }
+}
+
+fn hashes_by_height(blocks: &[Arc<Block>]) -> Vec<BlockHash> {
+    let mut blocks = blocks.iter().collect::<Vec<_>>();
+    blocks.sort_by_key(|block| block.header.height);
+    blocks.into_iter().map(|block| block.hash()).collect()
+}
+
+impl Display for ReorgEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Reorg at fork height {}: {} block(s) removed, {} block(s) added ({}), {} transaction(s) returned to the \
+             mempool",
+            self.fork_height,
+            self.depth(),
+            self.added_blocks.len(),
+            self.added_blocks.last().map(|hash| hash.to_hex()).unwrap_or_default(),
+            self.returned_transactions.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{blocks::BlockHeader, transactions::aggregated_body::AggregateBody};
+
+    fn block_at(height: u64, nonce: u64) -> Arc<Block> {
+        let mut header = BlockHeader::new(0);
+        header.height = height;
+        header.nonce = nonce;
+        Arc::new(Block::new(header, AggregateBody::empty()))
+    }
+
+    #[test]
+    fn it_orders_the_blocks_by_height() {
+        let removed = vec![block_at(6, 0), block_at(5, 0)];
+        let added = vec![block_at(5, 1), block_at(6, 1), block_at(7, 1)];
+        let event = ReorgEvent::new(&removed, &added, vec![]);
+        assert_eq!(event.fork_height, 4);
+        assert_eq!(event.depth(), 2);
+        assert_eq!(event.removed_blocks, vec![removed[1].hash(), removed[0].hash()]);
+        assert_eq!(
+            event.added_blocks,
+            added.iter().map(|block| block.hash()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_uses_the_removed_blocks_for_a_rewind() {
+        let event = ReorgEvent::new(&[block_at(3, 0), block_at(4, 0)], &[], vec![]);
+        assert_eq!(event.fork_height, 2);
+        assert!(event.added_blocks.is_empty());
+    }
+}
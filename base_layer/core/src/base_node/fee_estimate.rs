@@ -0,0 +1,233 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::cmp;
+
+use crate::{
+    base_node::proto::wallet_rpc::{FeeEstimate, FeeEstimates},
+    blocks::Block,
+    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, ChainStorageError},
+    mempool::UnconfirmedTxSummary,
+    transactions::{tari_amount::MicroTari, weight::TransactionWeight},
+};
+
+/// The confirmation windows, in blocks, for which fees are estimated: the next block, ~6 blocks and ~24 blocks
+pub const FEE_ESTIMATE_TARGETS: [u64; 3] = [1, 6, 24];
+/// The number of recent blocks that are analysed to determine how congested the chain is
+pub const FEE_ESTIMATE_BLOCK_WINDOW: u64 = 24;
+/// The lowest fee per gram that is suggested, even if the chain and mempool are empty
+pub const MIN_ESTIMATED_FEE_PER_GRAM: MicroTari = MicroTari(1);
+/// Blocks that are filled to at least this percentage of the maximum block weight are considered to be full
+const FULL_BLOCK_PERCENT: u64 = 90;
+
+/// The fees paid in a mined block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockFeeStats {
+    pub weight: u64,
+    pub total_fees: MicroTari,
+}
+
+impl BlockFeeStats {
+    pub fn new(block: &Block, weighting: &TransactionWeight) -> Self {
+        Self {
+            weight: block.body.calculate_weight(weighting),
+            total_fees: block.body.get_total_fee(),
+        }
+    }
+
+    /// The average fee per gram paid by the transactions in the block
+    pub fn fee_per_gram(&self) -> u64 {
+        self.total_fees.as_u64().checked_div(self.weight).unwrap_or(0)
+    }
+}
+
+/// Suggests fees per gram for transactions to be mined within a number of blocks. The suggestion is the highest of:
+/// * the fee needed to outbid the mempool transactions that would fill the blocks in the confirmation window,
+/// * a percentile of the fees paid in recent blocks, if most of them were full, and
+/// * the minimum fee that the mempool currently accepts.
+#[derive(Debug, Clone, Copy)]
+pub struct FeeEstimator {
+    max_block_weight: u64,
+}
+
+impl FeeEstimator {
+    pub fn new(max_block_weight: u64) -> Self {
+        Self { max_block_weight }
+    }
+
+    /// Estimates fees for each of the `FEE_ESTIMATE_TARGETS` from the recent blocks (oldest first), the transactions in
+    /// the unconfirmed pool and the minimum fee per gram that the mempool accepts
+    pub fn estimate(
+        &self,
+        tip_height: u64,
+        recent_blocks: &[BlockFeeStats],
+        mempool_txs: &[UnconfirmedTxSummary],
+        mempool_min_fee_per_gram: u64,
+    ) -> FeeEstimates {
+        let mut mempool_txs = mempool_txs
+            .iter()
+            .map(|tx| (tx.fee_per_gram(), tx.weight))
+            .collect::<Vec<_>>();
+        // Highest fee first, the order in which transactions are selected for a block
+        mempool_txs.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+
+        let estimates = FEE_ESTIMATE_TARGETS
+            .iter()
+            .map(|&target_blocks| {
+                let fee_per_gram = cmp::max(
+                    self.mempool_fee_per_gram(&mempool_txs, target_blocks),
+                    self.recent_blocks_fee_per_gram(recent_blocks, target_blocks),
+                );
+                let fee_per_gram = cmp::max(fee_per_gram, mempool_min_fee_per_gram);
+                FeeEstimate {
+                    target_blocks,
+                    fee_per_gram: cmp::max(MicroTari(fee_per_gram), MIN_ESTIMATED_FEE_PER_GRAM),
+                }
+            })
+            .collect();
+
+        FeeEstimates { tip_height, estimates }
+    }
+
+    /// Returns the fee per gram needed to outbid the mempool transactions that fill the next `target_blocks` blocks, or
+    /// zero if the mempool does not contain enough transactions to fill them
+    fn mempool_fee_per_gram(&self, sorted_txs: &[(u64, u64)], target_blocks: u64) -> u64 {
+        let capacity = self.max_block_weight.saturating_mul(target_blocks);
+        let mut total_weight = 0u64;
+        for (fee_per_gram, weight) in sorted_txs {
+            total_weight = total_weight.saturating_add(*weight);
+            if total_weight >= capacity {
+                return fee_per_gram + 1;
+            }
+        }
+        0
+    }
+
+    /// Returns a percentile of the fees paid in the recent full blocks if most of the recent blocks were full,
+    /// otherwise zero. The longer the confirmation window, the lower the percentile.
+    fn recent_blocks_fee_per_gram(&self, recent_blocks: &[BlockFeeStats], target_blocks: u64) -> u64 {
+        let full_block_weight = self.max_block_weight.saturating_mul(FULL_BLOCK_PERCENT) / 100;
+        let mut fees = recent_blocks
+            .iter()
+            .filter(|block| block.weight >= full_block_weight)
+            .map(|block| block.fee_per_gram())
+            .collect::<Vec<_>>();
+        if fees.is_empty() || fees.len() * 2 <= recent_blocks.len() {
+            return 0;
+        }
+        fees.sort_unstable();
+        let percentile = 50 / cmp::max(target_blocks, 1) as usize;
+        fees[(fees.len() - 1) * percentile / 100]
+    }
+}
+
+/// Estimates fees from the most recent `FEE_ESTIMATE_BLOCK_WINDOW` blocks and the given unconfirmed pool transactions
+pub async fn estimate_fees<B: BlockchainBackend + 'static>(
+    db: &AsyncBlockchainDb<B>,
+    mempool_txs: &[UnconfirmedTxSummary],
+    mempool_min_fee_per_gram: u64,
+) -> Result<FeeEstimates, ChainStorageError> {
+    let tip_height = db.get_chain_metadata().await?.height_of_longest_chain();
+    let start_height = tip_height.saturating_sub(FEE_ESTIMATE_BLOCK_WINDOW - 1);
+    let blocks = db.fetch_blocks(start_height..=tip_height).await?;
+    let constants = db.rules().consensus_constants(tip_height);
+    let recent_blocks = blocks
+        .iter()
+        .map(|block| BlockFeeStats::new(block.block(), constants.transaction_weight()))
+        .collect::<Vec<_>>();
+
+    let estimator = FeeEstimator::new(constants.get_max_block_transaction_weight());
+    Ok(estimator.estimate(tip_height, &recent_blocks, mempool_txs, mempool_min_fee_per_gram))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    const MAX_BLOCK_WEIGHT: u64 = 1000;
+
+    fn mempool_tx(fee_per_gram: u64, weight: u64) -> UnconfirmedTxSummary {
+        UnconfirmedTxSummary {
+            excess_sig: Default::default(),
+            fee: MicroTari(fee_per_gram * weight),
+            weight,
+            age: Duration::from_secs(0),
+            num_inputs: 1,
+            num_outputs: 2,
+            num_kernels: 1,
+        }
+    }
+
+    fn block(fee_per_gram: u64, weight: u64) -> BlockFeeStats {
+        BlockFeeStats {
+            weight,
+            total_fees: MicroTari(fee_per_gram * weight),
+        }
+    }
+
+    fn fees(estimates: &FeeEstimates) -> Vec<u64> {
+        estimates.estimates.iter().map(|e| e.fee_per_gram.as_u64()).collect()
+    }
+
+    #[test]
+    fn it_suggests_the_minimum_fee_when_there_is_no_congestion() {
+        let estimator = FeeEstimator::new(MAX_BLOCK_WEIGHT);
+        let estimates = estimator.estimate(10, &[block(50, 100)], &[mempool_tx(20, 100)], 0);
+        assert_eq!(estimates.tip_height, 10);
+        let targets = estimates.estimates.iter().map(|e| e.target_blocks).collect::<Vec<_>>();
+        assert_eq!(targets, FEE_ESTIMATE_TARGETS.to_vec());
+        assert_eq!(fees(&estimates), vec![MIN_ESTIMATED_FEE_PER_GRAM.as_u64(); 3]);
+
+        let estimates = estimator.estimate(10, &[], &[], 7);
+        assert_eq!(fees(&estimates), vec![7, 7, 7]);
+    }
+
+    #[test]
+    fn it_outbids_the_mempool_transactions_that_fill_the_target_blocks() {
+        let estimator = FeeEstimator::new(MAX_BLOCK_WEIGHT);
+        // 10 blocks worth of transactions, paying 100 down to 10 uT/g
+        let mempool_txs = (1..=10)
+            .map(|i| mempool_tx(i * 10, MAX_BLOCK_WEIGHT))
+            .collect::<Vec<_>>();
+        let estimates = estimator.estimate(10, &[], &mempool_txs, 0);
+        assert_eq!(fees(&estimates), vec![101, 51, 1]);
+        assert_eq!(estimates.for_target(1).unwrap().fee_per_gram, MicroTari(101));
+        assert_eq!(estimates.for_target(3).unwrap().target_blocks, 6);
+        assert_eq!(estimates.for_target(100).unwrap().target_blocks, 24);
+    }
+
+    #[test]
+    fn it_uses_recent_fees_when_most_blocks_are_full() {
+        let estimator = FeeEstimator::new(MAX_BLOCK_WEIGHT);
+        let full_blocks = (1..=10).map(|i| block(i * 10, MAX_BLOCK_WEIGHT)).collect::<Vec<_>>();
+        let estimates = estimator.estimate(10, &full_blocks, &[], 0);
+        assert_eq!(fees(&estimates), vec![50, 10, 10]);
+
+        // Only a minority of the blocks were full
+        let mut blocks = full_blocks[..4].to_vec();
+        blocks.extend((0..6).map(|_| block(100, 10)));
+        let estimates = estimator.estimate(10, &blocks, &[], 0);
+        assert_eq!(fees(&estimates), vec![1, 1, 1]);
+    }
+}
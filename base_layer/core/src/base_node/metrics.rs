@@ -24,6 +24,8 @@ use once_cell::sync::Lazy;
 use tari_metrics::{IntCounter, IntCounterVec, IntGauge, IntGaugeVec};
 use tari_utilities::hex::to_hex;
 
+use crate::base_node::PeerOffense;
+
 pub fn tip_height() -> IntGauge {
     static METER: Lazy<IntGauge> = Lazy::new(|| {
         tari_metrics::register_int_gauge("base_node::blockchain::tip_height", "The current tip height").unwrap()
@@ -162,3 +164,112 @@ pub fn utxo_set_size() -> IntGauge {
 
     METER.clone()
 }
+
+pub fn tip_divergent_peers() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        tari_metrics::register_int_gauge(
+            "base_node::chain_metadata::tip_divergent_peers",
+            "Number of peers advertising a chain tip that has diverged from the local tip",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
+pub fn tip_divergence_detected() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "base_node::chain_metadata::tip_divergence_detected",
+            "Number of times a sustained chain tip divergence from peers was detected",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
+pub fn suppressed_block_relays() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "base_node::relay::suppressed_blocks",
+            "Number of block propagations suppressed because block relay is disabled",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
+pub fn invalid_block_submissions() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "base_node::peer_offenses::invalid_blocks",
+            "Number of invalid blocks submitted by peers",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
+pub fn invalid_transaction_submissions() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "base_node::peer_offenses::invalid_transactions",
+            "Number of invalid transactions submitted by peers",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
+pub fn unknown_orphan_parent_submissions() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "base_node::peer_offenses::unknown_orphan_parents",
+            "Number of orphan blocks submitted by peers that could not provide the missing parent",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
+pub fn auto_banned_peers(offense: PeerOffense) -> IntCounter {
+    static METER: Lazy<IntCounterVec> = Lazy::new(|| {
+        tari_metrics::register_int_counter_vec(
+            "base_node::peer_offenses::auto_bans",
+            "Number of peers automatically banned for exceeding the permitted number of offenses",
+            &["offense"],
+        )
+        .unwrap()
+    });
+
+    METER.with_label_values(&[&offense.to_string()])
+}
+
+pub fn block_template_cache_hits() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "base_node::block_template_cache::hits",
+            "Number of new block template requests served from the template cache",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
+pub fn block_template_cache_misses() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "base_node::block_template_cache::misses",
+            "Number of new block template requests that required a new template to be built",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
@@ -39,12 +39,25 @@ pub mod chain_metadata_service;
 pub mod comms_interface;
 #[cfg(feature = "base_node")]
 pub use comms_interface::LocalNodeCommsInterface;
+#[cfg(feature = "base_node")]
+pub mod fee_estimate;
+
 #[cfg(feature = "base_node")]
 mod metrics;
 
+#[cfg(feature = "base_node")]
+mod peer_offense;
+#[cfg(feature = "base_node")]
+pub use peer_offense::{PeerAutoBanConfig, PeerOffense, PeerOffenseTracker};
+
 #[cfg(feature = "base_node")]
 pub mod service;
 
+#[cfg(feature = "base_node")]
+mod standby;
+#[cfg(feature = "base_node")]
+pub use standby::StandbyHandle;
+
 #[cfg(feature = "base_node")]
 pub mod state_machine_service;
 #[cfg(feature = "base_node")]
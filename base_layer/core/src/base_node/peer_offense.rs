@@ -0,0 +1,228 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{Display, Formatter},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use log::*;
+use serde::{Deserialize, Serialize};
+use tari_common::configuration::serializers;
+use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId};
+
+use crate::base_node::metrics;
+
+const LOG_TARGET: &str = "c::bn::peer_offense";
+
+/// Configuration for automatically banning peers that repeatedly submit invalid blocks or transactions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeerAutoBanConfig {
+    /// Enable or disable automatic bans. Offenses are still counted in the metrics when disabled.
+    pub enabled: bool,
+    /// The number of invalid blocks a peer may submit within the offense period before it is banned
+    pub max_invalid_blocks: usize,
+    /// The number of invalid transactions a peer may submit within the offense period before it is banned
+    pub max_invalid_transactions: usize,
+    /// The number of orphan blocks a peer may submit within the offense period without being able to provide the
+    /// missing parent before it is banned
+    pub max_unknown_orphan_parents: usize,
+    /// The sliding window over which offenses are counted
+    #[serde(with = "serializers::seconds")]
+    pub offense_period: Duration,
+    /// How long an offending peer is banned for
+    #[serde(with = "serializers::seconds")]
+    pub ban_duration: Duration,
+}
+
+impl Default for PeerAutoBanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_invalid_blocks: 0,
+            max_invalid_transactions: 10,
+            max_unknown_orphan_parents: 5,
+            offense_period: Duration::from_secs(10 * 60),
+            ban_duration: Duration::from_secs(2 * 60 * 60),
+        }
+    }
+}
+
+/// The kinds of peer misbehaviour that count towards an automatic ban
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PeerOffense {
+    InvalidBlock,
+    InvalidTransaction,
+    /// The peer sent an orphan block but could not provide its parent
+    UnknownOrphanParent,
+}
+
+impl Display for PeerOffense {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeerOffense::InvalidBlock => write!(f, "invalid block"),
+            PeerOffense::InvalidTransaction => write!(f, "invalid transaction"),
+            PeerOffense::UnknownOrphanParent => write!(f, "unknown orphan parent"),
+        }
+    }
+}
+
+/// Counts the offenses committed by each peer within the configured period and bans peers that exceed the permitted
+/// number of offenses. The tracker is cheap to clone and all clones share the same offense counts.
+#[derive(Debug, Clone)]
+pub struct PeerOffenseTracker {
+    config: PeerAutoBanConfig,
+    offenses: Arc<Mutex<HashMap<(NodeId, PeerOffense), VecDeque<Instant>>>>,
+}
+
+impl PeerOffenseTracker {
+    pub fn new(config: PeerAutoBanConfig) -> Self {
+        Self {
+            config,
+            offenses: Default::default(),
+        }
+    }
+
+    /// Records an offense committed by `peer` and, if the peer has exceeded the permitted number of offenses of this
+    /// kind, bans it for the configured duration.
+    pub async fn report(
+        &self,
+        connectivity: &mut ConnectivityRequester,
+        peer: NodeId,
+        offense: PeerOffense,
+        details: String,
+    ) {
+        if !self.record(&peer, offense) {
+            return;
+        }
+        metrics::auto_banned_peers(offense).inc();
+        let reason = format!(
+            "Peer exceeded the permitted number of {} submissions. Last offense: {}",
+            offense, details
+        );
+        debug!(target: LOG_TARGET, "Banning peer {}: {}", peer, reason);
+        if let Err(e) = connectivity
+            .ban_peer_until(peer, self.config.ban_duration, reason)
+            .await
+        {
+            error!(target: LOG_TARGET, "Failed to ban peer: {}", e);
+        }
+    }
+
+    /// Records an offense and returns true if the peer should be banned
+    fn record(&self, peer: &NodeId, offense: PeerOffense) -> bool {
+        match offense {
+            PeerOffense::InvalidBlock => metrics::invalid_block_submissions().inc(),
+            PeerOffense::InvalidTransaction => metrics::invalid_transaction_submissions().inc(),
+            PeerOffense::UnknownOrphanParent => metrics::unknown_orphan_parent_submissions().inc(),
+        }
+        self.record_at(Instant::now(), peer, offense)
+    }
+
+    fn record_at(&self, now: Instant, peer: &NodeId, offense: PeerOffense) -> bool {
+        let mut offenses = self.offenses.lock().expect("peer offense lock poisoned");
+        let period = self.config.offense_period;
+        offenses.retain(|_, timestamps| {
+            while timestamps
+                .front()
+                .map(|t| now.saturating_duration_since(*t) > period)
+                .unwrap_or(false)
+            {
+                timestamps.pop_front();
+            }
+            !timestamps.is_empty()
+        });
+
+        let timestamps = offenses.entry((peer.clone(), offense)).or_default();
+        timestamps.push_back(now);
+        if !self.config.enabled || timestamps.len() <= self.max_offenses(offense) {
+            return false;
+        }
+
+        // The ban resets the count so that the peer gets a clean slate once the ban expires
+        offenses.retain(|(node_id, _), _| node_id != peer);
+        true
+    }
+
+    fn max_offenses(&self, offense: PeerOffense) -> usize {
+        match offense {
+            PeerOffense::InvalidBlock => self.config.max_invalid_blocks,
+            PeerOffense::InvalidTransaction => self.config.max_invalid_transactions,
+            PeerOffense::UnknownOrphanParent => self.config.max_unknown_orphan_parents,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_utilities::ByteArray;
+
+    use super::*;
+
+    fn tracker() -> PeerOffenseTracker {
+        PeerOffenseTracker::new(PeerAutoBanConfig {
+            max_invalid_blocks: 0,
+            max_invalid_transactions: 2,
+            offense_period: Duration::from_secs(60),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn it_bans_after_the_threshold_is_exceeded() {
+        let tracker = tracker();
+        let peer = NodeId::new();
+        let start = Instant::now();
+        assert!(!tracker.record_at(start, &peer, PeerOffense::InvalidTransaction));
+        assert!(!tracker.record_at(start, &peer, PeerOffense::InvalidTransaction));
+        assert!(tracker.record_at(start, &peer, PeerOffense::InvalidTransaction));
+        // The count is reset after a ban
+        assert!(!tracker.record_at(start, &peer, PeerOffense::InvalidTransaction));
+
+        assert!(tracker.record_at(start, &peer, PeerOffense::InvalidBlock));
+    }
+
+    #[test]
+    fn it_only_counts_offenses_within_the_period() {
+        let tracker = tracker();
+        let peer = NodeId::new();
+        let other_peer = NodeId::from_bytes(&[1u8; 13]).unwrap();
+        let start = Instant::now();
+        assert!(!tracker.record_at(start, &peer, PeerOffense::InvalidTransaction));
+        assert!(!tracker.record_at(start, &peer, PeerOffense::InvalidTransaction));
+        assert!(!tracker.record_at(start, &other_peer, PeerOffense::InvalidTransaction));
+        assert!(!tracker.record_at(start + Duration::from_secs(61), &peer, PeerOffense::InvalidTransaction));
+    }
+
+    #[test]
+    fn it_does_not_ban_when_disabled() {
+        let tracker = PeerOffenseTracker::new(PeerAutoBanConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        let peer = NodeId::new();
+        assert!(!tracker.record_at(Instant::now(), &peer, PeerOffense::InvalidBlock));
+    }
+}
@@ -29,7 +29,10 @@ use serde::{Deserialize, Serialize};
 use tari_common_types::types::{BlockHash, Signature};
 use tari_utilities::ByteArrayError;
 
-use crate::proto::{base_node as proto, types};
+use crate::{
+    proto::{base_node as proto, types},
+    transactions::tari_amount::MicroTari,
+};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TxSubmissionResponse {
@@ -46,6 +49,7 @@ pub enum TxSubmissionRejectionReason {
     Orphan,
     TimeLocked,
     ValidationFailed,
+    FeeTooLow,
 }
 
 impl Display for TxSubmissionRejectionReason {
@@ -58,6 +62,7 @@ impl Display for TxSubmissionRejectionReason {
             Orphan => "Orphan",
             TimeLocked => "Time Locked",
             ValidationFailed => "Validation Failed",
+            FeeTooLow => "Fee Too Low",
             None => "None",
         };
         fmt.write_str(response)
@@ -77,6 +82,7 @@ impl TryFrom<proto::TxSubmissionRejectionReason> for TxSubmissionRejectionReason
             Orphan => TxSubmissionRejectionReason::Orphan,
             TimeLocked => TxSubmissionRejectionReason::TimeLocked,
             ValidationFailed => TxSubmissionRejectionReason::ValidationFailed,
+            FeeTooLow => TxSubmissionRejectionReason::FeeTooLow,
         })
     }
 }
@@ -92,6 +98,7 @@ impl From<TxSubmissionRejectionReason> for proto::TxSubmissionRejectionReason {
             Orphan => proto::TxSubmissionRejectionReason::Orphan,
             TimeLocked => proto::TxSubmissionRejectionReason::TimeLocked,
             ValidationFailed => proto::TxSubmissionRejectionReason::ValidationFailed,
+            FeeTooLow => proto::TxSubmissionRejectionReason::FeeTooLow,
         }
     }
 }
@@ -233,6 +240,64 @@ impl TryFrom<proto::TxQueryBatchResponse> for TxQueryBatchResponse {
     }
 }
 
+/// A suggested fee per gram for a transaction to be mined within the given number of blocks
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    pub target_blocks: u64,
+    pub fee_per_gram: MicroTari,
+}
+
+/// Fee estimates for a set of confirmation windows, from the shortest to the longest window
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeEstimates {
+    /// The height of the chain tip at which the estimates were made
+    pub tip_height: u64,
+    pub estimates: Vec<FeeEstimate>,
+}
+
+impl FeeEstimates {
+    /// Returns the estimate with the shortest confirmation window that is at least `target_blocks` long, or the
+    /// longest window if none are
+    pub fn for_target(&self, target_blocks: u64) -> Option<&FeeEstimate> {
+        self.estimates
+            .iter()
+            .find(|estimate| estimate.target_blocks >= target_blocks)
+            .or_else(|| self.estimates.last())
+    }
+}
+
+impl From<proto::FeeEstimatesResponse> for FeeEstimates {
+    fn from(response: proto::FeeEstimatesResponse) -> Self {
+        Self {
+            tip_height: response.tip_height,
+            estimates: response
+                .estimates
+                .into_iter()
+                .map(|estimate| FeeEstimate {
+                    target_blocks: estimate.target_blocks,
+                    fee_per_gram: estimate.fee_per_gram.into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<FeeEstimates> for proto::FeeEstimatesResponse {
+    fn from(estimates: FeeEstimates) -> Self {
+        Self {
+            tip_height: estimates.tip_height,
+            estimates: estimates
+                .estimates
+                .into_iter()
+                .map(|estimate| proto::FeeEstimate {
+                    target_blocks: estimate.target_blocks,
+                    fee_per_gram: estimate.fee_per_gram.as_u64(),
+                })
+                .collect(),
+        }
+    }
+}
+
 impl proto::SyncUtxosResponse {
     pub fn into_utxo(self) -> Option<proto::SyncUtxo> {
         use proto::sync_utxos_response::UtxoOrDeleted::{DeletedDiff, Utxo};
@@ -41,8 +41,12 @@ use crate::{
     proto,
     proto::{
         base_node::{
+            FeeEstimatesResponse,
             FetchMatchingUtxos,
+            FetchSpendingInputsRequest,
+            FetchSpendingInputsResponse,
             FetchUtxosResponse,
+            KernelLocationResponse,
             QueryDeletedRequest,
             QueryDeletedResponse,
             Signatures,
@@ -111,6 +115,21 @@ pub trait BaseNodeWalletService: Send + Sync + 'static {
         &self,
         request: Request<SyncUtxosByBlockRequest>,
     ) -> Result<Streaming<SyncUtxosByBlockResponse>, RpcStatus>;
+
+    #[rpc(method = 12)]
+    async fn get_kernel_location(
+        &self,
+        request: Request<Signature>,
+    ) -> Result<Response<KernelLocationResponse>, RpcStatus>;
+
+    #[rpc(method = 13)]
+    async fn get_fee_estimates(&self, request: Request<()>) -> Result<Response<FeeEstimatesResponse>, RpcStatus>;
+
+    #[rpc(method = 14)]
+    async fn fetch_spending_inputs(
+        &self,
+        request: Request<FetchSpendingInputsRequest>,
+    ) -> Result<Response<FetchSpendingInputsResponse>, RpcStatus>;
 }
 
 #[cfg(feature = "base_node")]
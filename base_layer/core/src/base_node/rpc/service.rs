@@ -31,6 +31,7 @@ use tokio::sync::mpsc;
 
 use crate::{
     base_node::{
+        fee_estimate,
         rpc::{sync_utxos_by_block_task::SyncUtxosByBlockTask, BaseNodeWalletService},
         state_machine_service::states::StateInfo,
         StateMachineHandle,
@@ -40,8 +41,12 @@ use crate::{
     proto,
     proto::{
         base_node::{
+            FeeEstimatesResponse,
             FetchMatchingUtxos,
+            FetchSpendingInputsRequest,
+            FetchSpendingInputsResponse,
             FetchUtxosResponse,
+            KernelLocationResponse,
             QueryDeletedRequest,
             QueryDeletedResponse,
             Signatures as SignaturesProto,
@@ -98,33 +103,22 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletRpcService<B> {
     async fn fetch_kernel(&self, signature: Signature) -> Result<TxQueryResponse, RpcStatus> {
         let db = self.db();
         let chain_metadata = db.get_chain_metadata().await.rpc_status_internal_error(LOG_TARGET)?;
-        match db
-            .fetch_kernel_by_excess_sig(signature.clone())
+        if let Some(location) = db
+            .fetch_kernel_location_by_excess_sig(signature.clone())
             .await
             .rpc_status_internal_error(LOG_TARGET)?
         {
-            None => (),
-            Some((_, block_hash)) => {
-                match db
-                    .fetch_header_by_block_hash(block_hash.clone())
-                    .await
-                    .rpc_status_internal_error(LOG_TARGET)?
-                {
-                    None => (),
-                    Some(header) => {
-                        let confirmations = chain_metadata.height_of_longest_chain().saturating_sub(header.height);
-                        let response = TxQueryResponse {
-                            location: TxLocation::Mined as i32,
-                            block_hash: Some(block_hash),
-                            confirmations,
-                            is_synced: true,
-                            height_of_longest_chain: chain_metadata.height_of_longest_chain(),
-                        };
-                        return Ok(response);
-                    },
-                }
-            },
-        };
+            let confirmations = chain_metadata
+                .height_of_longest_chain()
+                .saturating_sub(location.block_height);
+            return Ok(TxQueryResponse {
+                location: TxLocation::Mined as i32,
+                block_hash: Some(location.block_hash),
+                confirmations,
+                is_synced: true,
+                height_of_longest_chain: chain_metadata.height_of_longest_chain(),
+            });
+        }
 
         // If not in a block then check the mempool
         let mut mempool = self.mempool();
@@ -145,6 +139,7 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletRpcService<B> {
             TxStorageResponse::NotStoredTimeLocked |
             TxStorageResponse::NotStoredAlreadySpent |
             TxStorageResponse::NotStoredConsensus |
+            TxStorageResponse::NotStoredFeeTooLow |
             TxStorageResponse::NotStored => TxQueryResponse {
                 location: TxLocation::NotStored as i32,
                 block_hash: None,
@@ -197,6 +192,11 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
                 rejection_reason: TxSubmissionRejectionReason::TimeLocked.into(),
                 is_synced,
             },
+            TxStorageResponse::NotStoredFeeTooLow => TxSubmissionResponse {
+                accepted: false,
+                rejection_reason: TxSubmissionRejectionReason::FeeTooLow.into(),
+                is_synced,
+            },
             TxStorageResponse::NotStoredConsensus | TxStorageResponse::NotStored => TxSubmissionResponse {
                 accepted: false,
                 rejection_reason: TxSubmissionRejectionReason::ValidationFailed.into(),
@@ -597,4 +597,92 @@ impl<B: BlockchainBackend + 'static> BaseNodeWalletService for BaseNodeWalletRpc
 
         Ok(Streaming::new(rx))
     }
+
+    async fn get_kernel_location(
+        &self,
+        request: Request<SignatureProto>,
+    ) -> Result<Response<KernelLocationResponse>, RpcStatus> {
+        let state_machine = self.state_machine();
+        let status_watch = state_machine.get_status_info_watch();
+        let is_synced = match status_watch.borrow().state_info {
+            StateInfo::Listening(li) => li.is_synced(),
+            _ => false,
+        };
+
+        let message = request.into_message();
+        let signature = Signature::try_from(message).map_err(|_| RpcStatus::bad_request("Signature was invalid"))?;
+
+        let db = self.db();
+        let metadata = db.get_chain_metadata().await.rpc_status_internal_error(LOG_TARGET)?;
+        let location = db
+            .fetch_kernel_location_by_excess_sig(signature)
+            .await
+            .rpc_status_internal_error(LOG_TARGET)?;
+
+        let response = match location {
+            Some(location) => KernelLocationResponse {
+                confirmations: metadata.height_of_longest_chain().saturating_sub(location.block_height),
+                block_hash: Some(location.block_hash),
+                block_height: location.block_height,
+                mmr_position: u64::from(location.mmr_position),
+                kernel_hash: location.kernel_hash,
+                height_of_longest_chain: metadata.height_of_longest_chain(),
+                is_synced,
+            },
+            None => KernelLocationResponse {
+                height_of_longest_chain: metadata.height_of_longest_chain(),
+                is_synced,
+                ..Default::default()
+            },
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_fee_estimates(&self, _request: Request<()>) -> Result<Response<FeeEstimatesResponse>, RpcStatus> {
+        let mut mempool = self.mempool();
+        let mempool_txs = mempool
+            .get_unconfirmed_transactions()
+            .await
+            .rpc_status_internal_error(LOG_TARGET)?;
+        let mempool_stats = mempool.get_stats().await.rpc_status_internal_error(LOG_TARGET)?;
+        let estimates = fee_estimate::estimate_fees(&self.db, &mempool_txs, mempool_stats.min_fee_per_gram)
+            .await
+            .rpc_status_internal_error(LOG_TARGET)?;
+        Ok(Response::new(estimates.into()))
+    }
+
+    async fn fetch_spending_inputs(
+        &self,
+        request: Request<FetchSpendingInputsRequest>,
+    ) -> Result<Response<FetchSpendingInputsResponse>, RpcStatus> {
+        let message = request.into_message();
+        if message.output_hashes.is_empty() {
+            return Err(RpcStatus::bad_request("Empty output hashes"));
+        }
+
+        let block = self
+            .db
+            .fetch_block_by_hash(message.block_hash)
+            .await
+            .rpc_status_internal_error(LOG_TARGET)?
+            .ok_or_else(|| RpcStatus::not_found("Block not found. It might have been reorged out"))?;
+        let inputs = block
+            .block()
+            .body
+            .inputs()
+            .iter()
+            .filter(|input| message.output_hashes.contains(&input.output_hash()))
+            .cloned()
+            .map(proto::types::TransactionInput::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| RpcStatus::general(&err))?;
+        debug!(
+            target: LOG_TARGET,
+            "Found {} of {} spending input(s)",
+            inputs.len(),
+            message.output_hashes.len()
+        );
+
+        Ok(Response::new(FetchSpendingInputsResponse { inputs }))
+    }
 }
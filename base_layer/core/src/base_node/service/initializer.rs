@@ -45,6 +45,9 @@ use crate::{
     base_node::{
         comms_interface::{InboundNodeCommsHandlers, LocalNodeCommsInterface, OutboundNodeCommsInterface},
         service::service::{BaseNodeService, BaseNodeStreams},
+        PeerAutoBanConfig,
+        PeerOffenseTracker,
+        StandbyHandle,
         StateMachineHandle,
     },
     blocks::NewBlock,
@@ -65,18 +68,27 @@ pub struct BaseNodeServiceInitializer<T> {
     mempool: Mempool,
     consensus_manager: ConsensusManager,
     service_request_timeout: Duration,
+    block_relay_enabled: bool,
+    auto_ban_config: PeerAutoBanConfig,
+    standby: bool,
 }
 
 impl<T> BaseNodeServiceInitializer<T>
 where T: BlockchainBackend
 {
-    /// Create a new BaseNodeServiceInitializer from the inbound message subscriber.
+    /// Create a new BaseNodeServiceInitializer from the inbound message subscriber. If `block_relay_enabled` is false,
+    /// valid blocks are still processed but are not propagated to peers. Peers that submit too many invalid blocks or
+    /// transactions are banned according to `auto_ban_config`. If `standby` is true, the node starts in warm standby
+    /// and does not relay blocks or transactions until it is promoted using the registered `StandbyHandle`.
     pub fn new(
         inbound_message_subscription_factory: Arc<SubscriptionFactory>,
         blockchain_db: AsyncBlockchainDb<T>,
         mempool: Mempool,
         consensus_manager: ConsensusManager,
         service_request_timeout: Duration,
+        block_relay_enabled: bool,
+        auto_ban_config: PeerAutoBanConfig,
+        standby: bool,
     ) -> Self {
         Self {
             inbound_message_subscription_factory,
@@ -84,6 +96,9 @@ where T: BlockchainBackend
             mempool,
             consensus_manager,
             service_request_timeout,
+            block_relay_enabled,
+            auto_ban_config,
+            standby,
         }
     }
 
@@ -156,10 +171,14 @@ where T: BlockchainBackend + 'static
         // Connect InboundNodeCommsInterface and OutboundNodeCommsInterface to BaseNodeService
         let (outbound_request_sender_service, outbound_request_stream) = reply_channel::unbounded();
         let (outbound_block_sender_service, outbound_block_stream) = mpsc::unbounded_channel();
+        let (orphan_parent_sender, orphan_parent_stream) = mpsc::unbounded_channel();
         let (local_request_sender_service, local_request_stream) = reply_channel::unbounded();
         let (local_block_sender_service, local_block_stream) = reply_channel::unbounded();
-        let outbound_nci =
-            OutboundNodeCommsInterface::new(outbound_request_sender_service, outbound_block_sender_service);
+        let outbound_nci = OutboundNodeCommsInterface::new(
+            outbound_request_sender_service,
+            outbound_block_sender_service,
+            orphan_parent_sender,
+        );
         let (block_event_sender, _) = broadcast::channel(50);
         let local_nci = LocalNodeCommsInterface::new(
             local_request_sender_service,
@@ -170,8 +189,15 @@ where T: BlockchainBackend + 'static
         // Register handle to OutboundNodeCommsInterface before waiting for handles to be ready
         context.register_handle(outbound_nci.clone());
         context.register_handle(local_nci);
+        // The offense tracker is shared with the mempool service so that both count towards the same peer bans
+        let offense_tracker = PeerOffenseTracker::new(self.auto_ban_config.clone());
+        context.register_handle(offense_tracker.clone());
+        // The standby handle is shared with the mempool service and the base node CLI, which promotes the node
+        let standby = StandbyHandle::new(self.standby);
+        context.register_handle(standby.clone());
 
         let service_request_timeout = self.service_request_timeout;
+        let block_relay_enabled = self.block_relay_enabled;
         let blockchain_db = self.blockchain_db.clone();
         let mempool = self.mempool.clone();
         let consensus_manager = self.consensus_manager.clone();
@@ -190,11 +216,13 @@ where T: BlockchainBackend + 'static
                 consensus_manager,
                 outbound_nci.clone(),
                 connectivity,
+                offense_tracker,
             );
 
             let streams = BaseNodeStreams {
                 outbound_request_stream,
                 outbound_block_stream,
+                orphan_parent_stream,
                 inbound_request_stream,
                 inbound_response_stream,
                 inbound_block_stream,
@@ -206,6 +234,8 @@ where T: BlockchainBackend + 'static
                 inbound_nch,
                 service_request_timeout,
                 state_machine,
+                block_relay_enabled,
+                standby,
             )
             .start(streams);
             futures::pin_mut!(service);
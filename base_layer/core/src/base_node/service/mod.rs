@@ -25,6 +25,8 @@ mod error;
 mod initializer;
 pub use initializer::BaseNodeServiceInitializer;
 
+mod orphan_parent_scheduler;
+
 #[allow(clippy::module_inception)]
 mod service;
 pub use service_request::BaseNodeServiceRequest;
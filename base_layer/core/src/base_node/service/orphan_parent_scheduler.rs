@@ -0,0 +1,199 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use tari_common_types::types::BlockHash;
+use tari_comms::peer_manager::NodeId;
+
+/// Schedules requests for the missing parents of orphan blocks. Each missing parent is requested from one of the peers
+/// that sent an orphan with that parent at a time. A failed request is retried with the next peer once the retry
+/// interval has elapsed, until the maximum number of attempts is reached. The number of missing parents that are
+/// tracked is bounded. Each parent carries the number of orphan parents that were fetched before it was found to be
+/// missing so that long chains of orphans are not followed indefinitely.
+pub(super) struct OrphanParentScheduler {
+    pending: HashMap<BlockHash, PendingParent>,
+    max_pending: usize,
+    retry_interval: Duration,
+    max_attempts: usize,
+}
+
+struct PendingParent {
+    /// The peers that sent orphans with this missing parent, in the order that they were received
+    peers: Vec<NodeId>,
+    depth: usize,
+    attempts: usize,
+    last_request: Option<Instant>,
+    in_flight: bool,
+}
+
+impl OrphanParentScheduler {
+    pub fn new(max_pending: usize, retry_interval: Duration, max_attempts: usize) -> Self {
+        Self {
+            pending: HashMap::new(),
+            max_pending,
+            retry_interval,
+            max_attempts,
+        }
+    }
+
+    /// Adds a missing parent that can be requested from `peer`. Returns false if the parent was not added because the
+    /// maximum number of pending parents has been reached.
+    pub fn add(&mut self, parent_hash: BlockHash, peer: NodeId, depth: usize) -> bool {
+        if let Some(pending) = self.pending.get_mut(&parent_hash) {
+            if !pending.peers.contains(&peer) {
+                pending.peers.push(peer);
+            }
+            pending.depth = pending.depth.max(depth);
+            return true;
+        }
+        if self.pending.len() >= self.max_pending {
+            return false;
+        }
+        self.pending.insert(parent_hash, PendingParent {
+            peers: vec![peer],
+            depth,
+            attempts: 0,
+            last_request: None,
+            in_flight: false,
+        });
+        true
+    }
+
+    /// Returns the parents that should be requested now, the peer to request each of them from and their depth. The
+    /// returned requests are marked as in flight until `on_request_completed` is called for them.
+    pub fn next_requests(&mut self, now: Instant) -> Vec<(BlockHash, NodeId, usize)> {
+        let max_attempts = self.max_attempts;
+        let retry_interval = self.retry_interval;
+        // Give up on parents that none of the peers could provide
+        self.pending
+            .retain(|_, pending| pending.in_flight || pending.attempts < max_attempts);
+
+        self.pending
+            .iter_mut()
+            .filter(|(_, pending)| {
+                !pending.in_flight &&
+                    pending
+                        .last_request
+                        .map(|last| now.saturating_duration_since(last) >= retry_interval)
+                        .unwrap_or(true)
+            })
+            .map(|(parent_hash, pending)| {
+                let peer = pending.peers[pending.attempts % pending.peers.len()].clone();
+                pending.attempts += 1;
+                pending.last_request = Some(now);
+                pending.in_flight = true;
+                (parent_hash.clone(), peer, pending.depth)
+            })
+            .collect()
+    }
+
+    /// Records the result of a request. A parent that was received is no longer tracked, otherwise it is requested
+    /// again from the next peer.
+    pub fn on_request_completed(&mut self, parent_hash: &BlockHash, is_success: bool) {
+        if is_success {
+            self.pending.remove(parent_hash);
+        } else if let Some(pending) = self.pending.get_mut(parent_hash) {
+            pending.in_flight = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_utilities::ByteArray;
+
+    use super::*;
+
+    const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+    fn peer(n: u8) -> NodeId {
+        NodeId::from_bytes(&[n; 13]).unwrap()
+    }
+
+    #[test]
+    fn it_requests_the_parent_from_the_peer_that_sent_the_orphan() {
+        let mut scheduler = OrphanParentScheduler::new(10, RETRY_INTERVAL, 3);
+        let now = Instant::now();
+        assert!(scheduler.add(vec![1], peer(1), 0));
+        assert_eq!(scheduler.next_requests(now), vec![(vec![1], peer(1), 0)]);
+        // The request is in flight
+        assert!(scheduler.next_requests(now + RETRY_INTERVAL).is_empty());
+
+        scheduler.on_request_completed(&vec![1], true);
+        assert!(scheduler.next_requests(now + RETRY_INTERVAL).is_empty());
+    }
+
+    #[test]
+    fn it_retries_with_the_next_peer_after_the_retry_interval() {
+        let mut scheduler = OrphanParentScheduler::new(10, RETRY_INTERVAL, 3);
+        let now = Instant::now();
+        scheduler.add(vec![1], peer(1), 0);
+        scheduler.add(vec![1], peer(2), 0);
+        scheduler.add(vec![1], peer(1), 0);
+        assert_eq!(scheduler.next_requests(now), vec![(vec![1], peer(1), 0)]);
+
+        scheduler.on_request_completed(&vec![1], false);
+        assert!(scheduler.next_requests(now).is_empty());
+        assert_eq!(scheduler.next_requests(now + RETRY_INTERVAL), vec![(
+            vec![1],
+            peer(2),
+            0
+        )]);
+    }
+
+    #[test]
+    fn it_gives_up_after_the_maximum_attempts() {
+        let mut scheduler = OrphanParentScheduler::new(10, RETRY_INTERVAL, 2);
+        let mut now = Instant::now();
+        scheduler.add(vec![1], peer(1), 0);
+        for _ in 0..2 {
+            assert_eq!(scheduler.next_requests(now).len(), 1);
+            scheduler.on_request_completed(&vec![1], false);
+            now += RETRY_INTERVAL;
+        }
+        assert!(scheduler.next_requests(now).is_empty());
+        // The parent is no longer tracked, so it can be added again
+        assert!(scheduler.add(vec![1], peer(1), 0));
+    }
+
+    #[test]
+    fn it_bounds_the_number_of_pending_parents() {
+        let mut scheduler = OrphanParentScheduler::new(2, RETRY_INTERVAL, 3);
+        assert!(scheduler.add(vec![1], peer(1), 0));
+        assert!(scheduler.add(vec![2], peer(1), 0));
+        assert!(!scheduler.add(vec![3], peer(1), 0));
+        // Known parents can still gain peers
+        assert!(scheduler.add(vec![1], peer(2), 0));
+    }
+
+    #[test]
+    fn it_keeps_the_greatest_depth() {
+        let mut scheduler = OrphanParentScheduler::new(10, RETRY_INTERVAL, 3);
+        scheduler.add(vec![1], peer(1), 2);
+        scheduler.add(vec![1], peer(2), 1);
+        assert_eq!(scheduler.next_requests(Instant::now()), vec![(vec![1], peer(1), 2)]);
+    }
+}
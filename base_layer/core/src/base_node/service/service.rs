@@ -20,7 +20,11 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{convert::TryInto, sync::Arc, time::Duration};
+use std::{
+    convert::TryInto,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use futures::{pin_mut, stream::StreamExt, Stream};
 use log::*;
@@ -41,17 +45,21 @@ use tari_utilities::{hex::Hex, Hashable};
 use tokio::{
     sync::{
         mpsc,
-        mpsc::{Receiver, Sender, UnboundedReceiver},
+        mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender},
         oneshot::Sender as OneshotSender,
     },
     task,
+    time,
+    time::MissedTickBehavior,
 };
 
 use crate::{
     base_node::{
         comms_interface::{CommsInterfaceError, InboundNodeCommsHandlers, NodeCommsRequest, NodeCommsResponse},
-        service::error::BaseNodeServiceError,
+        metrics,
+        service::{error::BaseNodeServiceError, orphan_parent_scheduler::OrphanParentScheduler},
         state_machine_service::states::StateInfo,
+        StandbyHandle,
         StateMachineHandle,
     },
     blocks::{Block, NewBlock},
@@ -61,6 +69,12 @@ use crate::{
 };
 
 const LOG_TARGET: &str = "c::bn::base_node_service::service";
+/// The maximum number of missing orphan parents that are tracked at once
+const MAX_PENDING_ORPHAN_PARENTS: usize = 100;
+/// The interval between attempts to fetch a missing orphan parent
+const ORPHAN_PARENT_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+/// The number of attempts to fetch a missing orphan parent, rotating through the peers that sent its orphans
+const MAX_ORPHAN_PARENT_ATTEMPTS: usize = 3;
 
 /// A convenience struct to hold all the BaseNode streams
 pub(super) struct BaseNodeStreams<SOutReq, SInReq, SInRes, SBlockIn, SLocalReq, SLocalBlock> {
@@ -70,6 +84,8 @@ pub(super) struct BaseNodeStreams<SOutReq, SInReq, SInRes, SBlockIn, SLocalReq,
     /// Blocks to be propagated out to the network. The second element of the tuple is a list of peers to exclude from
     /// this round of propagation
     pub outbound_block_stream: UnboundedReceiver<(NewBlock, Vec<NodeId>)>,
+    /// Missing parents of orphan blocks to request from the peers that sent the orphans
+    pub orphan_parent_stream: UnboundedReceiver<(BlockHash, NodeId, usize)>,
     /// `BaseNodeRequest` messages received from external peers
     pub inbound_request_stream: SInReq,
     /// `BaseNodeResponse` messages received from external peers
@@ -93,6 +109,11 @@ pub(super) struct BaseNodeService<B> {
     timeout_receiver_stream: Option<Receiver<RequestKey>>,
     service_request_timeout: Duration,
     state_machine_handle: StateMachineHandle,
+    block_relay_enabled: bool,
+    standby: StandbyHandle,
+    orphan_parents: OrphanParentScheduler,
+    orphan_parent_result_sender: UnboundedSender<(BlockHash, bool)>,
+    orphan_parent_result_receiver: Option<UnboundedReceiver<(BlockHash, bool)>>,
 }
 
 impl<B> BaseNodeService<B>
@@ -103,8 +124,11 @@ where B: BlockchainBackend + 'static
         inbound_nch: InboundNodeCommsHandlers<B>,
         service_request_timeout: Duration,
         state_machine_handle: StateMachineHandle,
+        block_relay_enabled: bool,
+        standby: StandbyHandle,
     ) -> Self {
         let (timeout_sender, timeout_receiver) = mpsc::channel(100);
+        let (orphan_parent_result_sender, orphan_parent_result_receiver) = mpsc::unbounded_channel();
         Self {
             outbound_message_service,
             inbound_nch,
@@ -113,6 +137,15 @@ where B: BlockchainBackend + 'static
             timeout_receiver_stream: Some(timeout_receiver),
             service_request_timeout,
             state_machine_handle,
+            block_relay_enabled,
+            standby,
+            orphan_parents: OrphanParentScheduler::new(
+                MAX_PENDING_ORPHAN_PARENTS,
+                ORPHAN_PARENT_RETRY_INTERVAL,
+                MAX_ORPHAN_PARENT_ATTEMPTS,
+            ),
+            orphan_parent_result_sender,
+            orphan_parent_result_receiver: Some(orphan_parent_result_receiver),
         }
     }
 
@@ -149,6 +182,13 @@ where B: BlockchainBackend + 'static
             .take()
             .expect("Base Node Service initialized without timeout_receiver_stream");
         pin_mut!(timeout_receiver_stream);
+        let mut orphan_parent_stream = streams.orphan_parent_stream;
+        let mut orphan_parent_result_stream = self
+            .orphan_parent_result_receiver
+            .take()
+            .expect("Base Node Service initialized without orphan_parent_result_receiver");
+        let mut orphan_parent_retry = time::interval(ORPHAN_PARENT_RETRY_INTERVAL);
+        orphan_parent_retry.set_missed_tick_behavior(MissedTickBehavior::Delay);
         loop {
             tokio::select! {
                 // Outbound request messages from the OutboundNodeCommsInterface
@@ -191,6 +231,26 @@ where B: BlockchainBackend + 'static
                     self.spawn_handle_local_block(local_block_context);
                 },
 
+                // Missing orphan parents from the InboundNodeCommsHandlers
+                Some((parent_hash, peer, depth)) = orphan_parent_stream.recv() => {
+                    if !self.orphan_parents.add(parent_hash.clone(), peer, depth) {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Too many pending orphan parent requests. Not requesting `{}`",
+                            parent_hash.to_hex()
+                        );
+                    }
+                    self.spawn_orphan_parent_requests();
+                },
+
+                Some((parent_hash, is_success)) = orphan_parent_result_stream.recv() => {
+                    self.orphan_parents.on_request_completed(&parent_hash, is_success);
+                },
+
+                _ = orphan_parent_retry.tick() => {
+                    self.spawn_orphan_parent_requests();
+                },
+
                 else => {
                     info!(target: LOG_TARGET, "Base Node service shutting down because all streams ended");
                     break;
@@ -232,6 +292,24 @@ where B: BlockchainBackend + 'static
     }
 
     fn spawn_handle_outbound_block(&self, new_block: NewBlock, excluded_peers: Vec<NodeId>) {
+        if !self.block_relay_enabled {
+            debug!(
+                target: LOG_TARGET,
+                "Block relay is disabled. Not propagating block `{}`",
+                new_block.header.hash().to_hex()
+            );
+            metrics::suppressed_block_relays().inc();
+            return;
+        }
+        if self.standby.is_standby() {
+            debug!(
+                target: LOG_TARGET,
+                "Node is in standby. Not propagating block `{}`",
+                new_block.header.hash().to_hex()
+            );
+            metrics::suppressed_block_relays().inc();
+            return;
+        }
         let outbound_message_service = self.outbound_message_service.clone();
         task::spawn(async move {
             let result = handle_outbound_block(outbound_message_service, new_block, excluded_peers).await;
@@ -327,6 +405,25 @@ where B: BlockchainBackend + 'static
         });
     }
 
+    fn spawn_orphan_parent_requests(&mut self) {
+        for (parent_hash, peer, depth) in self.orphan_parents.next_requests(Instant::now()) {
+            let mut inbound_nch = self.inbound_nch.clone();
+            let result_sender = self.orphan_parent_result_sender.clone();
+            task::spawn(async move {
+                let result = inbound_nch.fetch_orphan_parent(parent_hash.clone(), peer, depth).await;
+                if let Err(ref e) = result {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Failed to fetch orphan parent `{}`: {}",
+                        parent_hash.to_hex(),
+                        e
+                    );
+                }
+                let _result = result_sender.send((parent_hash, result.is_ok()));
+            });
+        }
+    }
+
     fn spawn_handle_local_block(&self, block_context: RequestContext<Block, Result<BlockHash, CommsInterfaceError>>) {
         let mut inbound_nch = self.inbound_nch.clone();
         task::spawn(async move {
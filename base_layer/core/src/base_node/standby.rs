@@ -0,0 +1,68 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Tracks whether the base node is in warm standby. A node in standby stays synced with the network but does not
+/// relay blocks or transactions to its peers until it is promoted. The handle is cheap to clone and all clones share
+/// the same state.
+#[derive(Debug, Clone, Default)]
+pub struct StandbyHandle {
+    standby: Arc<AtomicBool>,
+}
+
+impl StandbyHandle {
+    pub fn new(standby: bool) -> Self {
+        Self {
+            standby: Arc::new(AtomicBool::new(standby)),
+        }
+    }
+
+    /// Returns true if the node is in standby
+    pub fn is_standby(&self) -> bool {
+        self.standby.load(Ordering::SeqCst)
+    }
+
+    /// Promotes the node to full service. Returns false if the node was not in standby.
+    pub fn promote(&self) -> bool {
+        self.standby.swap(false, Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_promotes_once() {
+        let handle = StandbyHandle::new(true);
+        let clone = handle.clone();
+        assert!(clone.is_standby());
+        assert!(handle.promote());
+        assert!(!clone.is_standby());
+        assert!(!clone.promote());
+        assert!(!StandbyHandle::default().is_standby());
+    }
+}
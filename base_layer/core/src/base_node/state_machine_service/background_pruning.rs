@@ -0,0 +1,108 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::Duration;
+
+use log::*;
+use tari_shutdown::ShutdownSignal;
+use tokio::{
+    sync::watch,
+    time::{self, MissedTickBehavior},
+};
+
+use crate::{
+    base_node::state_machine_service::states::{StateInfo, StatusInfo},
+    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend},
+};
+
+const LOG_TARGET: &str = "c::bn::state_machine_service::background_pruning";
+
+/// Prunes spent outputs beyond the pruning horizon in small steps while the node is synced and listening. Each step
+/// prunes at most `batch_size` blocks and is skipped if the database write lock is held, e.g. for block validation.
+pub(super) struct BackgroundPruningTask<B> {
+    db: AsyncBlockchainDb<B>,
+    status_event_receiver: watch::Receiver<StatusInfo>,
+    batch_size: u64,
+    interval: Duration,
+    shutdown_signal: ShutdownSignal,
+}
+
+impl<B: BlockchainBackend + 'static> BackgroundPruningTask<B> {
+    pub fn new(
+        db: AsyncBlockchainDb<B>,
+        status_event_receiver: watch::Receiver<StatusInfo>,
+        batch_size: u64,
+        interval: Duration,
+        shutdown_signal: ShutdownSignal,
+    ) -> Self {
+        Self {
+            db,
+            status_event_receiver,
+            batch_size,
+            interval,
+            shutdown_signal,
+        }
+    }
+
+    pub async fn run(mut self) {
+        let mut interval = time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {},
+                _ = self.shutdown_signal.wait() => break,
+            }
+
+            if !is_idle(&*self.status_event_receiver.borrow()) {
+                continue;
+            }
+            match self.db.try_prune_step(self.batch_size).await {
+                Ok(Some(0)) => {},
+                Ok(Some(num_pruned)) => debug!(target: LOG_TARGET, "Pruned {} block(s)", num_pruned),
+                Ok(None) => trace!(target: LOG_TARGET, "Database is busy, skipping pruning step"),
+                Err(err) => warn!(target: LOG_TARGET, "Background pruning step failed: {}", err),
+            }
+        }
+        debug!(target: LOG_TARGET, "Background pruning task has shut down");
+    }
+}
+
+/// The node is idle when it is synced and listening for new blocks
+fn is_idle(status: &StatusInfo) -> bool {
+    matches!(&status.state_info, StateInfo::Listening(info) if info.is_synced())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::base_node::state_machine_service::states::ListeningInfo;
+
+    #[test]
+    fn it_is_idle_when_synced_and_listening() {
+        let mut status = StatusInfo::new();
+        assert!(!is_idle(&status));
+        status.state_info = StateInfo::Listening(ListeningInfo::new(false));
+        assert!(!is_idle(&status));
+        status.state_info = StateInfo::Listening(ListeningInfo::new(true));
+        assert!(is_idle(&status));
+    }
+}
@@ -31,6 +31,7 @@ use crate::{
     base_node::{
         chain_metadata_service::ChainMetadataHandle,
         state_machine_service::{
+            background_pruning::BackgroundPruningTask,
             handle::StateMachineHandle,
             state_machine::{BaseNodeStateMachine, BaseNodeStateMachineConfig},
             states::StatusInfo,
@@ -51,6 +52,7 @@ pub struct BaseNodeStateMachineInitializer<B> {
     config: BaseNodeStateMachineConfig,
     rules: ConsensusManager,
     factories: CryptoFactories,
+    randomx_factory: RandomXFactory,
 }
 
 impl<B> BaseNodeStateMachineInitializer<B>
@@ -61,12 +63,14 @@ where B: BlockchainBackend + 'static
         config: BaseNodeStateMachineConfig,
         rules: ConsensusManager,
         factories: CryptoFactories,
+        randomx_factory: RandomXFactory,
     ) -> Self {
         Self {
             db,
             config,
             rules,
             factories,
+            randomx_factory,
         }
     }
 }
@@ -82,7 +86,7 @@ where B: BlockchainBackend + 'static
 
         let handle = StateMachineHandle::new(
             state_event_publisher.clone(),
-            status_event_receiver,
+            status_event_receiver.clone(),
             context.get_shutdown_signal(),
         );
         context.register_handle(handle);
@@ -91,6 +95,7 @@ where B: BlockchainBackend + 'static
         let rules = self.rules.clone();
         let db = self.db.clone();
         let config = self.config.clone();
+        let randomx_factory = self.randomx_factory.clone();
 
         let mut mdc = vec![];
         log_mdc::iter(|k, v| mdc.push((k.to_owned(), v.to_owned())));
@@ -108,7 +113,18 @@ where B: BlockchainBackend + 'static
                 config.bypass_range_proof_verification,
                 config.blockchain_sync_config.validation_concurrency,
             );
-            let max_randomx_vms = config.max_randomx_vms;
+
+            let storage_config = db.inner().config();
+            if storage_config.background_pruning {
+                let pruning_task = BackgroundPruningTask::new(
+                    db.clone(),
+                    status_event_receiver,
+                    storage_config.background_pruning_batch_size,
+                    storage_config.background_pruning_interval,
+                    handles.get_shutdown_signal(),
+                );
+                tokio::spawn(pruning_task.run());
+            }
 
             let node = BaseNodeStateMachine::new(
                 db,
@@ -120,7 +136,7 @@ where B: BlockchainBackend + 'static
                 sync_validators,
                 status_event_sender,
                 state_event_publisher,
-                RandomXFactory::new(max_randomx_vms),
+                randomx_factory,
                 rules,
                 handles.get_shutdown_signal(),
             );
@@ -24,6 +24,7 @@ use std::{cmp::Ordering, time::Instant};
 
 use log::*;
 use tari_common_types::chain_metadata::ChainMetadata;
+use tari_comms::{connectivity::PeerScore, PeerManager};
 
 use crate::{
     base_node::{
@@ -69,10 +70,26 @@ impl HeaderSyncState {
         self.sync_peers
     }
 
+    /// Moves sync peers with a better connection quality score ahead of those with a worse score. The existing latency
+    /// ordering is kept between peers with a similar score.
+    async fn sort_by_peer_score(&mut self, peer_manager: &PeerManager) {
+        let mut ranked = Vec::with_capacity(self.sync_peers.len());
+        for sync_peer in self.sync_peers.drain(..) {
+            let rank = match peer_manager.find_by_node_id(sync_peer.node_id()).await {
+                Ok(Some(peer)) => PeerScore::from_peer(&peer).unwrap_or_default().rank(),
+                _ => PeerScore::default().rank(),
+            };
+            ranked.push((rank, sync_peer));
+        }
+        ranked.sort_by(|(a, _), (b, _)| b.cmp(a));
+        self.sync_peers = ranked.into_iter().map(|(_, sync_peer)| sync_peer).collect();
+    }
+
     pub async fn next_event<B: BlockchainBackend + 'static>(
         &mut self,
         shared: &mut BaseNodeStateMachine<B>,
     ) -> StateEvent {
+        self.sort_by_peer_score(&shared.peer_manager).await;
         let mut synchronizer = HeaderSynchronizer::new(
             shared.config.blockchain_sync_config.clone(),
             shared.db.clone(),
@@ -245,6 +245,41 @@ impl Listening {
                         debug!(target: LOG_TARGET, "Initial sync achieved");
                     }
                 },
+                Ok(ChainMetadataEvent::TipDivergenceDetected(divergence)) => {
+                    warn!(target: LOG_TARGET, "Chain tip divergence detected: {}", divergence);
+                    if !divergence.trigger_sync {
+                        continue;
+                    }
+
+                    let mut divergent_peers = divergence.divergent_peers.iter().collect::<Vec<_>>();
+                    let configured_sync_peers = &shared.config.blockchain_sync_config.forced_sync_peers;
+                    if !configured_sync_peers.is_empty() {
+                        divergent_peers.retain(|p| configured_sync_peers.contains(p.node_id()));
+                    }
+
+                    let best_metadata = match best_claimed_metadata(&divergent_peers) {
+                        Some(m) => m,
+                        None => continue,
+                    };
+
+                    let local_metadata = match shared.db.get_chain_metadata().await {
+                        Ok(m) => m,
+                        Err(e) => {
+                            return FatalError(format!("Could not get local blockchain metadata. {}", e));
+                        },
+                    };
+                    log_mdc::extend(mdc.clone());
+
+                    // Re-evaluate immediately, without the usual allowance for block propagation
+                    let sync_peers = select_sync_peers(best_metadata, &divergent_peers);
+                    let sync_mode = determine_sync_mode(0, &local_metadata, best_metadata, sync_peers);
+                    if sync_mode.is_lagging() {
+                        return StateEvent::FallenBehind(sync_mode);
+                    }
+                },
+                Ok(ChainMetadataEvent::TipDivergenceResolved) => {
+                    debug!(target: LOG_TARGET, "Chain tip divergence resolved");
+                },
                 Err(broadcast::error::RecvError::Lagged(n)) => {
                     debug!(target: LOG_TARGET, "Metadata event subscriber lagged by {} item(s)", n);
                 },
@@ -58,4 +58,6 @@ pub enum BlockSyncError {
     },
     #[error("All sync peers exceeded max allowed latency")]
     AllSyncPeersExceedLatency,
+    #[error("Peer {peer} did not send a block within {timeout:.2?}")]
+    SyncPeerStalled { peer: NodeId, timeout: Duration },
 }
@@ -23,5 +23,7 @@
 mod error;
 pub use error::BlockSyncError;
 
+mod range_scheduler;
+
 mod synchronizer;
 pub use synchronizer::BlockSynchronizer;
@@ -0,0 +1,181 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+use std::{
+    cmp,
+    collections::{BTreeMap, HashMap},
+};
+
+use tari_comms::peer_manager::NodeId;
+
+/// A range of block heights, inclusive of both ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl BlockRange {
+    pub fn num_blocks(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// Splits the heights to be synchronized into ranges that are downloaded from several sync peers concurrently.
+/// Ranges are handed out in ascending order and only within a window ahead of the last validated height, so that the
+/// number of downloaded blocks waiting for validation is bounded. A range released by a stalled peer is handed out
+/// again before any new range.
+pub struct BlockRangeScheduler {
+    end_height: u64,
+    range_size: u64,
+    max_ranges_ahead: u64,
+    next_unscheduled: u64,
+    next_to_validate: u64,
+    released: BTreeMap<u64, BlockRange>,
+    assigned: HashMap<NodeId, BlockRange>,
+}
+
+impl BlockRangeScheduler {
+    pub fn new(start_height: u64, end_height: u64, range_size: u64, max_ranges_ahead: u64) -> Self {
+        Self {
+            end_height,
+            range_size: cmp::max(range_size, 1),
+            max_ranges_ahead: cmp::max(max_ranges_ahead, 1),
+            next_unscheduled: start_height,
+            next_to_validate: start_height,
+            released: BTreeMap::new(),
+            assigned: HashMap::new(),
+        }
+    }
+
+    /// The height of the next block that must be validated
+    pub fn next_to_validate(&self) -> u64 {
+        self.next_to_validate
+    }
+
+    /// Assigns the next range to the given peer. Returns None if the peer already has a range or if there is no range
+    /// available within the download window.
+    pub fn assign(&mut self, peer: &NodeId) -> Option<BlockRange> {
+        if self.assigned.contains_key(peer) {
+            return None;
+        }
+
+        let range = match self.released.keys().next().copied() {
+            Some(start) => self.released.remove(&start)?,
+            None => {
+                let window_end = self
+                    .next_to_validate
+                    .saturating_add(self.range_size.saturating_mul(self.max_ranges_ahead));
+                if self.next_unscheduled > self.end_height || self.next_unscheduled >= window_end {
+                    return None;
+                }
+                let start = self.next_unscheduled;
+                let end = cmp::min(start.saturating_add(self.range_size - 1), self.end_height);
+                self.next_unscheduled = end + 1;
+                BlockRange { start, end }
+            },
+        };
+        self.assigned.insert(peer.clone(), range);
+        Some(range)
+    }
+
+    /// Marks the range assigned to the peer as downloaded
+    pub fn complete(&mut self, peer: &NodeId) -> Option<BlockRange> {
+        self.assigned.remove(peer)
+    }
+
+    /// Releases the range assigned to the peer so that it is assigned to another peer
+    pub fn release(&mut self, peer: &NodeId) -> Option<BlockRange> {
+        let range = self.assigned.remove(peer)?;
+        self.released.insert(range.start, range);
+        Some(range)
+    }
+
+    /// Marks the blocks up to and including the given height as validated, advancing the download window
+    pub fn set_validated(&mut self, height: u64) {
+        self.next_to_validate = cmp::max(self.next_to_validate, height + 1);
+    }
+
+    /// Returns true once every block has been validated
+    pub fn is_complete(&self) -> bool {
+        self.next_to_validate > self.end_height
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_utilities::ByteArray;
+
+    use super::*;
+
+    fn node_id(n: u8) -> NodeId {
+        NodeId::from_bytes(&[n; 13]).unwrap()
+    }
+
+    #[test]
+    fn it_assigns_ranges_in_order() {
+        let mut scheduler = BlockRangeScheduler::new(1, 25, 10, 4);
+        assert_eq!(scheduler.assign(&node_id(1)), Some(BlockRange { start: 1, end: 10 }));
+        assert_eq!(scheduler.assign(&node_id(1)), None);
+        assert_eq!(scheduler.assign(&node_id(2)), Some(BlockRange { start: 11, end: 20 }));
+        let last = scheduler.assign(&node_id(3)).unwrap();
+        assert_eq!(last, BlockRange { start: 21, end: 25 });
+        assert_eq!(last.num_blocks(), 5);
+        assert_eq!(scheduler.assign(&node_id(4)), None);
+    }
+
+    #[test]
+    fn it_limits_ranges_to_the_download_window() {
+        let mut scheduler = BlockRangeScheduler::new(1, 100, 10, 2);
+        assert!(scheduler.assign(&node_id(1)).is_some());
+        assert!(scheduler.assign(&node_id(2)).is_some());
+        assert_eq!(scheduler.assign(&node_id(3)), None);
+
+        assert_eq!(scheduler.complete(&node_id(1)), Some(BlockRange { start: 1, end: 10 }));
+        assert_eq!(scheduler.assign(&node_id(3)), None);
+        scheduler.set_validated(10);
+        assert_eq!(scheduler.next_to_validate(), 11);
+        assert_eq!(scheduler.assign(&node_id(3)), Some(BlockRange { start: 21, end: 30 }));
+    }
+
+    #[test]
+    fn it_reassigns_released_ranges_first() {
+        let mut scheduler = BlockRangeScheduler::new(1, 100, 10, 4);
+        assert!(scheduler.assign(&node_id(1)).is_some());
+        assert!(scheduler.assign(&node_id(2)).is_some());
+        assert_eq!(scheduler.release(&node_id(1)), Some(BlockRange { start: 1, end: 10 }));
+        assert_eq!(scheduler.release(&node_id(1)), None);
+        assert_eq!(scheduler.assign(&node_id(3)), Some(BlockRange { start: 1, end: 10 }));
+        assert_eq!(scheduler.assign(&node_id(1)), Some(BlockRange { start: 21, end: 30 }));
+    }
+
+    #[test]
+    fn it_completes_once_all_blocks_are_validated() {
+        let mut scheduler = BlockRangeScheduler::new(5, 9, 10, 4);
+        assert!(!scheduler.is_complete());
+        assert_eq!(scheduler.assign(&node_id(1)), Some(BlockRange { start: 5, end: 9 }));
+        scheduler.complete(&node_id(1));
+        assert!(!scheduler.is_complete());
+        scheduler.set_validated(9);
+        assert!(scheduler.is_complete());
+        assert_eq!(scheduler.assign(&node_id(1)), None);
+    }
+}
@@ -21,19 +21,28 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    cmp,
+    collections::BTreeMap,
     convert::TryFrom,
+    mem,
     sync::Arc,
     time::{Duration, Instant},
 };
 
-use futures::StreamExt;
+use futures::{stream::FuturesUnordered, StreamExt};
 use log::*;
 use num_format::{Locale, ToFormattedString};
+use prost::Message;
+use tari_common_types::types::HashOutput;
 use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId, PeerConnection};
 use tari_utilities::{hex::Hex, Hashable};
+use tokio::time;
 use tracing;
 
-use super::error::BlockSyncError;
+use super::{
+    error::BlockSyncError,
+    range_scheduler::{BlockRange, BlockRangeScheduler},
+};
 use crate::{
     base_node::{
         sync::{hooks::Hooks, rpc, SyncPeer},
@@ -42,7 +51,7 @@ use crate::{
     blocks::{Block, BlockValidationError, ChainBlock},
     chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend},
     common::rolling_avg::RollingAverageTime,
-    proto::base_node::SyncBlocksRequest,
+    proto::base_node::{BlockBodyResponse, SyncBlocksRequest},
     transactions::aggregated_body::AggregateBody,
     validation::{BlockSyncBodyValidation, ValidationError},
 };
@@ -116,20 +125,19 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
     }
 
     async fn attempt_block_sync(&mut self, max_latency: Duration) -> Result<(), BlockSyncError> {
+        if self.config.max_parallel_block_sync_peers > 1 && self.sync_peers.len() > 1 {
+            return self.attempt_parallel_block_sync(max_latency).await;
+        }
+
         let sync_peer_node_ids = self.sync_peers.iter().map(|p| p.node_id()).cloned().collect::<Vec<_>>();
         for (i, node_id) in sync_peer_node_ids.iter().enumerate() {
-            let mut conn = self.connect_to_sync_peer(node_id.clone()).await?;
-            let mut client = conn
-                .connect_rpc_using_builder(rpc::BaseNodeSyncRpcClient::builder().with_deadline(Duration::from_secs(60)))
-                .await?;
-            let latency = client
-                .get_last_request_latency()
-                .expect("unreachable panic: last request latency must be set after connect");
-            self.sync_peers[i].set_latency(latency);
+            let client = self.connect_sync_client(node_id).await?;
             let sync_peer = self.sync_peers[i].clone();
             info!(
                 target: LOG_TARGET,
-                "Attempting to synchronize blocks with `{}` latency: {:.2?}", node_id, latency
+                "Attempting to synchronize blocks with `{}` latency: {:.2?}",
+                node_id,
+                sync_peer.latency().unwrap_or_default()
             );
             match self.synchronize_blocks(sync_peer, client, max_latency).await {
                 Ok(_) => {
@@ -138,25 +146,7 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
                 },
                 Err(err @ BlockSyncError::ValidationError(ValidationError::AsyncTaskFailed(_))) => return Err(err),
                 Err(BlockSyncError::ValidationError(err)) => {
-                    match &err {
-                        ValidationError::BlockHeaderError(_) => {},
-                        ValidationError::BlockError(BlockValidationError::MismatchedMmrRoots { .. }) |
-                        ValidationError::BadBlockFound { .. } |
-                        ValidationError::BlockError(BlockValidationError::MismatchedMmrSize { .. }) => {
-                            let num_cleared = self.db.clear_all_pending_headers().await?;
-                            warn!(
-                                target: LOG_TARGET,
-                                "Cleared {} incomplete headers from bad chain", num_cleared
-                            );
-                        },
-                        _ => {},
-                    }
-                    warn!(
-                        target: LOG_TARGET,
-                        "Banning peer because provided block failed validation: {}", err
-                    );
-                    self.ban_peer(node_id, &err).await?;
-                    return Err(err.into());
+                    return self.on_block_validation_failed(node_id, err).await;
                 },
                 Err(err @ BlockSyncError::MaxLatencyExceeded { .. }) => {
                     warn!(target: LOG_TARGET, "{}", err);
@@ -177,11 +167,217 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
         Err(BlockSyncError::NoSyncPeers)
     }
 
+    /// Downloads the block bodies from several sync peers concurrently. The heights to be synchronized are split into
+    /// ranges which are assigned to the sync peers as they become idle, and the downloaded blocks are validated and
+    /// stored in height order. If a peer stalls, its range is reassigned to another peer.
+    ///
+    /// Only block bodies are downloaded in parallel. Headers are still synchronized from a single peer by header sync,
+    /// because each header is validated against its predecessors and the peer must prove its claimed accumulated
+    /// difficulty. Once the headers are validated, the body for each height is known by hash, so bodies can be fetched
+    /// from any peer and checked against the header chain.
+    async fn attempt_parallel_block_sync(&mut self, max_latency: Duration) -> Result<(), BlockSyncError> {
+        let tip_header = self.db.fetch_last_header().await?;
+        let local_metadata = self.db.get_chain_metadata().await?;
+        let best_height = local_metadata.height_of_longest_chain();
+        if tip_header.height <= best_height {
+            debug!(
+                target: LOG_TARGET,
+                "Blocks already synchronized to height {}.", tip_header.height
+            );
+            return Ok(());
+        }
+        let tip_height = tip_header.height;
+        let stall_timeout = cmp::max(self.config.block_sync_stall_timeout, max_latency);
+
+        let sync_peer_node_ids = self
+            .sync_peers
+            .iter()
+            .take(self.config.max_parallel_block_sync_peers)
+            .map(|p| p.node_id())
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut idle_peers = Vec::with_capacity(sync_peer_node_ids.len());
+        for node_id in sync_peer_node_ids {
+            match self.connect_sync_client(&node_id).await {
+                Ok(client) => idle_peers.push((node_id, client)),
+                Err(err) => warn!(
+                    target: LOG_TARGET,
+                    "Failed to connect to sync peer `{}`: {}", node_id, err
+                ),
+            }
+        }
+        if idle_peers.is_empty() {
+            return Err(BlockSyncError::NoSyncPeers);
+        }
+
+        info!(
+            target: LOG_TARGET,
+            "Starting parallel block sync of #{} to #{} from {} sync peer(s)",
+            best_height + 1,
+            tip_height,
+            idle_peers.len()
+        );
+        self.hooks.call_on_starting_hook();
+
+        let mut scheduler = BlockRangeScheduler::new(
+            best_height + 1,
+            tip_height,
+            self.config.block_sync_range_size,
+            2 * idle_peers.len() as u64,
+        );
+        let mut downloads = FuturesUnordered::new();
+        let mut downloaded = BTreeMap::new();
+        let mut prev_hash = self.db.fetch_chain_header(best_height).await?.hash().clone();
+        let mut current_block = None;
+        let mut num_stalled = 0usize;
+
+        while !scheduler.is_complete() {
+            for (node_id, client) in mem::take(&mut idle_peers) {
+                match scheduler.assign(&node_id) {
+                    Some(range) => {
+                        let start_hash = self.db.fetch_chain_header(range.start - 1).await?.hash().clone();
+                        let end_hash = self.db.fetch_chain_header(range.end).await?.hash().clone();
+                        debug!(
+                            target: LOG_TARGET,
+                            "Requesting blocks #{} to #{} from `{}`", range.start, range.end, node_id
+                        );
+                        downloads.push(download_block_range(
+                            node_id,
+                            client,
+                            range,
+                            start_hash,
+                            end_hash,
+                            stall_timeout,
+                        ));
+                    },
+                    None => idle_peers.push((node_id, client)),
+                }
+            }
+
+            // Ranges are always assignable to an idle peer when nothing is downloading, so there are no peers left
+            let download = match downloads.next().await {
+                Some(download) => download,
+                None if num_stalled > 0 => return Err(BlockSyncError::AllSyncPeersExceedLatency),
+                None => return Err(BlockSyncError::NoSyncPeers),
+            };
+
+            let RangeDownload {
+                node_id,
+                client,
+                range,
+                result,
+                bytes,
+                elapsed,
+            } = download;
+            match result {
+                Ok(blocks) => {
+                    scheduler.complete(&node_id);
+                    if let Some(sync_peer) = self.sync_peer_mut(&node_id) {
+                        sync_peer.add_bytes_received(bytes, elapsed);
+                        for (_, latency) in &blocks {
+                            sync_peer.add_sample(*latency);
+                        }
+                        debug!(
+                            target: LOG_TARGET,
+                            "Downloaded blocks #{} to #{} ({} bytes) from `{}` in {:.2?} ({:.0} bytes/s)",
+                            range.start,
+                            range.end,
+                            bytes,
+                            node_id,
+                            elapsed,
+                            sync_peer.bytes_per_second().unwrap_or_default()
+                        );
+                    }
+                    downloaded.insert(range.start, (node_id.clone(), blocks));
+                    idle_peers.push((node_id, client));
+                },
+                Err(err @ BlockSyncError::ProtocolViolation(_)) => {
+                    scheduler.release(&node_id);
+                    warn!(target: LOG_TARGET, "Banning peer: {}", err);
+                    self.ban_peer(&node_id, &err).await?;
+                },
+                Err(err) => {
+                    scheduler.release(&node_id);
+                    if matches!(err, BlockSyncError::SyncPeerStalled { .. }) {
+                        num_stalled += 1;
+                    }
+                    warn!(
+                        target: LOG_TARGET,
+                        "Reassigning blocks #{} to #{} because sync peer `{}` failed: {}",
+                        range.start,
+                        range.end,
+                        node_id,
+                        err
+                    );
+                },
+            }
+
+            while let Some((node_id, blocks)) = downloaded.remove(&scheduler.next_to_validate()) {
+                for (block, latency) in blocks {
+                    let block = match self.process_block(block, &prev_hash, latency).await {
+                        Ok(block) => block,
+                        Err(err @ BlockSyncError::ValidationError(ValidationError::AsyncTaskFailed(_))) => {
+                            return Err(err)
+                        },
+                        Err(BlockSyncError::ValidationError(err)) => {
+                            return self.on_block_validation_failed(&node_id, err).await;
+                        },
+                        Err(err @ BlockSyncError::ProtocolViolation(_)) => {
+                            warn!(target: LOG_TARGET, "Banning peer: {}", err);
+                            self.ban_peer(&node_id, &err).await?;
+                            return Err(err);
+                        },
+                        Err(err) => return Err(err),
+                    };
+                    prev_hash = block.hash().clone();
+                    scheduler.set_validated(block.height());
+                    if let Some(sync_peer) = self.sync_peers.iter().find(|p| *p.node_id() == node_id) {
+                        self.hooks
+                            .call_on_progress_block_hooks(block.clone(), tip_height, sync_peer);
+                    }
+                    current_block = Some(block);
+                }
+            }
+        }
+
+        if let Some(block) = current_block {
+            self.hooks.call_on_complete_hooks(block);
+        }
+
+        for sync_peer in self.sync_peers.iter().filter(|p| p.bytes_received() > 0) {
+            debug!(
+                target: LOG_TARGET,
+                "Received {} bytes from `{}` ({:.0} bytes/s)",
+                sync_peer.bytes_received(),
+                sync_peer.node_id(),
+                sync_peer.bytes_per_second().unwrap_or_default()
+            );
+        }
+        debug!(target: LOG_TARGET, "Completed parallel block sync to #{}", tip_height);
+
+        self.db.cleanup_orphans().await?;
+        Ok(())
+    }
+
     async fn connect_to_sync_peer(&self, peer: NodeId) -> Result<PeerConnection, BlockSyncError> {
         let connection = self.connectivity.dial_peer(peer).await?;
         Ok(connection)
     }
 
+    async fn connect_sync_client(&mut self, node_id: &NodeId) -> Result<rpc::BaseNodeSyncRpcClient, BlockSyncError> {
+        let mut conn = self.connect_to_sync_peer(node_id.clone()).await?;
+        let client = conn
+            .connect_rpc_using_builder(rpc::BaseNodeSyncRpcClient::builder().with_deadline(Duration::from_secs(60)))
+            .await?;
+        let latency = client
+            .get_last_request_latency()
+            .expect("unreachable panic: last request latency must be set after connect");
+        if let Some(sync_peer) = self.sync_peer_mut(node_id) {
+            sync_peer.set_latency(latency);
+        }
+        Ok(client)
+    }
+
     async fn synchronize_blocks(
         &mut self,
         mut sync_peer: SyncPeer,
@@ -230,95 +426,8 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
         while let Some(block) = block_stream.next().await {
             let latency = last_sync_timer.elapsed();
             avg_latency.add_sample(latency);
-            let block = block?;
-
-            let header = self
-                .db
-                .fetch_chain_header_by_block_hash(block.hash.clone())
-                .await?
-                .ok_or_else(|| {
-                    BlockSyncError::ProtocolViolation(format!(
-                        "Peer sent hash ({}) for block header we do not have",
-                        block.hash.to_hex()
-                    ))
-                })?;
-
-            let current_height = header.height();
-            let header_hash = header.hash().clone();
-
-            if header.header().prev_hash != prev_hash {
-                return Err(BlockSyncError::PeerSentBlockThatDidNotFormAChain {
-                    expected: prev_hash.to_hex(),
-                    got: header.header().prev_hash.to_hex(),
-                });
-            }
-
-            prev_hash = header_hash.clone();
-
-            let body = block
-                .body
-                .map(AggregateBody::try_from)
-                .ok_or_else(|| BlockSyncError::ProtocolViolation("Block body was empty".to_string()))?
-                .map_err(BlockSyncError::ProtocolViolation)?;
-
-            debug!(
-                target: LOG_TARGET,
-                "Validating block body #{} (PoW = {}, {}, latency: {:.2?})",
-                current_height,
-                header.header().pow_algo(),
-                body.to_counts_string(),
-                latency
-            );
-
-            let timer = Instant::now();
-            let (header, header_accum_data) = header.into_parts();
-
-            let block = match self.block_validator.validate_body(Block::new(header, body)).await {
-                Ok(block) => block,
-                Err(err @ ValidationError::BadBlockFound { .. }) |
-                Err(err @ ValidationError::FatalStorageError(_)) |
-                Err(err @ ValidationError::AsyncTaskFailed(_)) |
-                Err(err @ ValidationError::CustomError(_)) => return Err(err.into()),
-                Err(err) => {
-                    // Add to bad blocks
-                    if let Err(err) = self
-                        .db
-                        .write_transaction()
-                        .insert_bad_block(header_hash, current_height)
-                        .commit()
-                        .await
-                    {
-                        error!(target: LOG_TARGET, "Failed to insert bad block: {}", err);
-                    }
-                    return Err(err.into());
-                },
-            };
-
-            let block = ChainBlock::try_construct(Arc::new(block), header_accum_data)
-                .map(Arc::new)
-                .ok_or(BlockSyncError::FailedToConstructChainBlock)?;
-
-            debug!(
-                target: LOG_TARGET,
-                "Validated in {:.0?}. Storing block body #{} (PoW = {}, {})",
-                timer.elapsed(),
-                block.header().height,
-                block.header().pow_algo(),
-                block.block().body.to_counts_string(),
-            );
-
-            let timer = Instant::now();
-            self.db
-                .write_transaction()
-                .insert_block_body(block.clone())
-                .set_best_block(
-                    block.height(),
-                    header_hash,
-                    block.accumulated_data().total_accumulated_difficulty,
-                    block.header().prev_hash.clone(),
-                )
-                .commit()
-                .await?;
+            let block = self.process_block(block?, &prev_hash, latency).await?;
+            prev_hash = block.hash().clone();
 
             // Average time between receiving blocks from the peer - used to detect a slow sync peer
             let last_avg_latency = avg_latency.calculate_average_with_min_samples(5);
@@ -330,19 +439,6 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
             self.hooks
                 .call_on_progress_block_hooks(block.clone(), tip_height, &sync_peer);
 
-            debug!(
-                target: LOG_TARGET,
-                "Block body #{} added in {:.0?}, Tot_acc_diff {}, Monero {}, SHA3 {}, latency: {:.2?}",
-                block.height(),
-                timer.elapsed(),
-                block
-                    .accumulated_data()
-                    .total_accumulated_difficulty
-                    .to_formatted_string(&Locale::en),
-                block.accumulated_data().accumulated_monero_difficulty,
-                block.accumulated_data().accumulated_sha_difficulty,
-                latency
-            );
             if let Some(avg_latency) = last_avg_latency {
                 if avg_latency > max_latency {
                     return Err(BlockSyncError::MaxLatencyExceeded {
@@ -366,6 +462,147 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
         Ok(())
     }
 
+    /// Validates the block body received from a sync peer against the stored header and stores it as the new best
+    /// block. The block must build on `prev_hash`.
+    async fn process_block(
+        &self,
+        block: BlockBodyResponse,
+        prev_hash: &HashOutput,
+        latency: Duration,
+    ) -> Result<Arc<ChainBlock>, BlockSyncError> {
+        let header = self
+            .db
+            .fetch_chain_header_by_block_hash(block.hash.clone())
+            .await?
+            .ok_or_else(|| {
+                BlockSyncError::ProtocolViolation(format!(
+                    "Peer sent hash ({}) for block header we do not have",
+                    block.hash.to_hex()
+                ))
+            })?;
+
+        let current_height = header.height();
+        let header_hash = header.hash().clone();
+
+        if header.header().prev_hash != *prev_hash {
+            return Err(BlockSyncError::PeerSentBlockThatDidNotFormAChain {
+                expected: prev_hash.to_hex(),
+                got: header.header().prev_hash.to_hex(),
+            });
+        }
+
+        let body = block
+            .body
+            .map(AggregateBody::try_from)
+            .ok_or_else(|| BlockSyncError::ProtocolViolation("Block body was empty".to_string()))?
+            .map_err(BlockSyncError::ProtocolViolation)?;
+
+        debug!(
+            target: LOG_TARGET,
+            "Validating block body #{} (PoW = {}, {}, latency: {:.2?})",
+            current_height,
+            header.header().pow_algo(),
+            body.to_counts_string(),
+            latency
+        );
+
+        let timer = Instant::now();
+        let (header, header_accum_data) = header.into_parts();
+
+        let block = match self.block_validator.validate_body(Block::new(header, body)).await {
+            Ok(block) => block,
+            Err(err @ ValidationError::BadBlockFound { .. }) |
+            Err(err @ ValidationError::FatalStorageError(_)) |
+            Err(err @ ValidationError::AsyncTaskFailed(_)) |
+            Err(err @ ValidationError::CustomError(_)) => return Err(err.into()),
+            Err(err) => {
+                // Add to bad blocks
+                if let Err(err) = self
+                    .db
+                    .write_transaction()
+                    .insert_bad_block(header_hash, current_height)
+                    .commit()
+                    .await
+                {
+                    error!(target: LOG_TARGET, "Failed to insert bad block: {}", err);
+                }
+                return Err(err.into());
+            },
+        };
+
+        let block = ChainBlock::try_construct(Arc::new(block), header_accum_data)
+            .map(Arc::new)
+            .ok_or(BlockSyncError::FailedToConstructChainBlock)?;
+
+        debug!(
+            target: LOG_TARGET,
+            "Validated in {:.0?}. Storing block body #{} (PoW = {}, {})",
+            timer.elapsed(),
+            block.header().height,
+            block.header().pow_algo(),
+            block.block().body.to_counts_string(),
+        );
+
+        let timer = Instant::now();
+        self.db
+            .write_transaction()
+            .insert_block_body(block.clone())
+            .set_best_block(
+                block.height(),
+                header_hash,
+                block.accumulated_data().total_accumulated_difficulty,
+                block.header().prev_hash.clone(),
+            )
+            .commit()
+            .await?;
+
+        debug!(
+            target: LOG_TARGET,
+            "Block body #{} added in {:.0?}, Tot_acc_diff {}, Monero {}, SHA3 {}, latency: {:.2?}",
+            block.height(),
+            timer.elapsed(),
+            block
+                .accumulated_data()
+                .total_accumulated_difficulty
+                .to_formatted_string(&Locale::en),
+            block.accumulated_data().accumulated_monero_difficulty,
+            block.accumulated_data().accumulated_sha_difficulty,
+            latency
+        );
+
+        Ok(block)
+    }
+
+    async fn on_block_validation_failed(
+        &mut self,
+        node_id: &NodeId,
+        err: ValidationError,
+    ) -> Result<(), BlockSyncError> {
+        match &err {
+            ValidationError::BlockHeaderError(_) => {},
+            ValidationError::BlockError(BlockValidationError::MismatchedMmrRoots { .. }) |
+            ValidationError::BadBlockFound { .. } |
+            ValidationError::BlockError(BlockValidationError::MismatchedMmrSize { .. }) => {
+                let num_cleared = self.db.clear_all_pending_headers().await?;
+                warn!(
+                    target: LOG_TARGET,
+                    "Cleared {} incomplete headers from bad chain", num_cleared
+                );
+            },
+            _ => {},
+        }
+        warn!(
+            target: LOG_TARGET,
+            "Banning peer because provided block failed validation: {}", err
+        );
+        self.ban_peer(node_id, &err).await?;
+        Err(err.into())
+    }
+
+    fn sync_peer_mut(&mut self, node_id: &NodeId) -> Option<&mut SyncPeer> {
+        self.sync_peers.iter_mut().find(|p| p.node_id() == node_id)
+    }
+
     async fn ban_peer<T: ToString>(&mut self, node_id: &NodeId, reason: T) -> Result<(), BlockSyncError> {
         let reason = reason.to_string();
         if self.config.forced_sync_peers.contains(node_id) {
@@ -392,3 +629,72 @@ impl<B: BlockchainBackend + 'static> BlockSynchronizer<B> {
         Ok(())
     }
 }
+
+/// The result of downloading a range of blocks from a sync peer. The RPC client is returned so that the peer can be
+/// assigned another range.
+struct RangeDownload {
+    node_id: NodeId,
+    client: rpc::BaseNodeSyncRpcClient,
+    range: BlockRange,
+    result: Result<Vec<(BlockBodyResponse, Duration)>, BlockSyncError>,
+    bytes: u64,
+    elapsed: Duration,
+}
+
+/// Downloads the blocks in the range from the sync peer, along with the time taken to receive each block. The
+/// download fails if the peer does not send a block within `stall_timeout`.
+async fn download_block_range(
+    node_id: NodeId,
+    mut client: rpc::BaseNodeSyncRpcClient,
+    range: BlockRange,
+    start_hash: HashOutput,
+    end_hash: HashOutput,
+    stall_timeout: Duration,
+) -> RangeDownload {
+    let timer = Instant::now();
+    let mut bytes = 0u64;
+    let result = async {
+        let request = SyncBlocksRequest { start_hash, end_hash };
+        let mut block_stream = client.sync_blocks(request).await?;
+        let mut blocks = Vec::with_capacity(range.num_blocks() as usize);
+        let mut last_block_timer = Instant::now();
+        loop {
+            let next = time::timeout(stall_timeout, block_stream.next()).await.map_err(|_| {
+                BlockSyncError::SyncPeerStalled {
+                    peer: node_id.clone(),
+                    timeout: stall_timeout,
+                }
+            })?;
+            match next {
+                Some(block) => {
+                    let block = block?;
+                    bytes += block.encoded_len() as u64;
+                    blocks.push((block, last_block_timer.elapsed()));
+                    last_block_timer = Instant::now();
+                },
+                None => break,
+            }
+        }
+
+        if blocks.len() as u64 != range.num_blocks() {
+            return Err(BlockSyncError::ProtocolViolation(format!(
+                "Peer sent {} block(s) for blocks #{} to #{} but {} were requested",
+                blocks.len(),
+                range.start,
+                range.end,
+                range.num_blocks()
+            )));
+        }
+        Ok(blocks)
+    }
+    .await;
+
+    RangeDownload {
+        node_id,
+        client,
+        range,
+        result,
+        bytes,
+        elapsed: timer.elapsed(),
+    }
+}
@@ -43,6 +43,14 @@ pub struct BlockchainSyncConfig {
     pub forced_sync_peers: Vec<NodeId>,
     /// Number of threads to use for validation
     pub validation_concurrency: usize,
+    /// The maximum number of sync peers from which block bodies are downloaded concurrently. A value of 1 downloads
+    /// all blocks from a single sync peer. Headers are always synchronized from a single sync peer.
+    pub max_parallel_block_sync_peers: usize,
+    /// The number of blocks in each range that is requested from a sync peer during parallel block sync
+    pub block_sync_range_size: u64,
+    /// If a sync peer does not send a block within this period during parallel block sync, its range is reassigned to
+    /// another sync peer
+    pub block_sync_stall_timeout: Duration,
 }
 
 impl Default for BlockchainSyncConfig {
@@ -54,6 +62,9 @@ impl Default for BlockchainSyncConfig {
             short_ban_period: Duration::from_secs(60),
             forced_sync_peers: Default::default(),
             validation_concurrency: 6,
+            max_parallel_block_sync_peers: 4,
+            block_sync_range_size: 100,
+            block_sync_stall_timeout: Duration::from_secs(30),
         }
     }
 }
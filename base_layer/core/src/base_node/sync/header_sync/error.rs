@@ -27,8 +27,13 @@ use tari_comms::{
     peer_manager::NodeId,
     protocol::rpc::{RpcError, RpcStatus},
 };
+use tokio::task;
 
-use crate::{blocks::BlockError, chain_storage::ChainStorageError, validation::ValidationError};
+use crate::{
+    blocks::BlockError,
+    chain_storage::ChainStorageError,
+    validation::{HeaderVerificationError, ValidationError},
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum BlockHeaderSyncError {
@@ -92,4 +97,27 @@ pub enum BlockHeaderSyncError {
     },
     #[error("All sync peers exceeded max allowed latency")]
     AllSyncPeersExceedLatency,
+    #[error("Join error: {0}")]
+    JoinError(#[from] task::JoinError),
+}
+
+impl From<HeaderVerificationError> for BlockHeaderSyncError {
+    fn from(err: HeaderVerificationError) -> Self {
+        match err {
+            HeaderVerificationError::InvalidHeight { expected, actual } => {
+                BlockHeaderSyncError::InvalidBlockHeight { expected, actual }
+            },
+            HeaderVerificationError::ChainLinkBroken {
+                height,
+                actual,
+                expected,
+            } => BlockHeaderSyncError::ChainLinkBroken {
+                height,
+                actual,
+                expected,
+            },
+            HeaderVerificationError::ValidationFailed(err) => BlockHeaderSyncError::ValidationFailed(err),
+            HeaderVerificationError::BlockError(err) => BlockHeaderSyncError::BlockError(err),
+        }
+    }
 }
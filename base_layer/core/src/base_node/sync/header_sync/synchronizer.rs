@@ -55,6 +55,8 @@ use crate::{
 const LOG_TARGET: &str = "c::bn::header_sync";
 
 const NUM_INITIAL_HEADERS_TO_REQUEST: u64 = 1000;
+/// The maximum number of streamed headers that are validated together
+const HEADER_VALIDATION_BATCH_SIZE: usize = 250;
 
 pub struct HeaderSynchronizer<'a, B> {
     config: BlockchainSyncConfig,
@@ -492,7 +494,7 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
         let chain_split_hash = block_hashes.get(fork_hash_index as usize).unwrap();
 
         self.header_validator.initialize_state(chain_split_hash).await?;
-        for header in headers {
+        for header in &headers {
             debug!(
                 target: LOG_TARGET,
                 "Validating header #{} (Pow: {}) with hash: ({})",
@@ -500,8 +502,8 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
                 header.pow_algo(),
                 header.hash().to_hex(),
             );
-            self.header_validator.validate(header)?;
         }
+        self.header_validator.validate_batch(headers).await?;
 
         debug!(
             target: LOG_TARGET,
@@ -618,7 +620,11 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
             count: 0,
         };
 
-        let mut header_stream = client.sync_headers(request).await?;
+        // Headers that have already been received are validated together so that their PoW can be checked in parallel
+        let mut header_stream = client
+            .sync_headers(request)
+            .await?
+            .ready_chunks(HEADER_VALIDATION_BATCH_SIZE);
         debug!(
             target: LOG_TARGET,
             "Reading headers from peer `{}`",
@@ -629,32 +635,39 @@ impl<'a, B: BlockchainBackend + 'static> HeaderSynchronizer<'a, B> {
 
         let mut last_total_accumulated_difficulty = 0;
         let mut avg_latency = RollingAverageTime::new(20);
-        while let Some(header) = header_stream.next().await {
+        while let Some(headers) = header_stream.next().await {
             let latency = last_sync_timer.elapsed();
             avg_latency.add_sample(latency);
-            let header = BlockHeader::try_from(header?).map_err(BlockHeaderSyncError::ReceivedInvalidHeader)?;
-            debug!(
-                target: LOG_TARGET,
-                "Validating header #{} (Pow: {}) with hash: ({}). Latency: {:.2?}",
-                header.height,
-                header.pow_algo(),
-                header.hash().to_hex(),
-                latency
-            );
-            let existing_header = self.db.fetch_header_by_block_hash(header.hash()).await?;
-            // TODO: Due to a bug in a previous version of base node sync RPC, the duplicate headers can be sent. We
-            //       should be a little more strict about this in future.
-            if let Some(h) = existing_header {
-                warn!(
+            let mut batch = Vec::with_capacity(headers.len());
+            for header in headers {
+                let header = BlockHeader::try_from(header?).map_err(BlockHeaderSyncError::ReceivedInvalidHeader)?;
+                debug!(
                     target: LOG_TARGET,
-                    "Received header #{} `{}` that we already have. Ignoring",
-                    h.height,
-                    h.hash().to_hex()
+                    "Validating header #{} (Pow: {}) with hash: ({}). Latency: {:.2?}",
+                    header.height,
+                    header.pow_algo(),
+                    header.hash().to_hex(),
+                    latency
                 );
-                continue;
+                let existing_header = self.db.fetch_header_by_block_hash(header.hash()).await?;
+                // TODO: Due to a bug in a previous version of base node sync RPC, the duplicate headers can be sent. We
+                //       should be a little more strict about this in future.
+                if let Some(h) = existing_header {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Received header #{} `{}` that we already have. Ignoring",
+                        h.height,
+                        h.hash().to_hex()
+                    );
+                    continue;
+                }
+                batch.push(header);
             }
-            let current_height = header.height;
-            last_total_accumulated_difficulty = self.header_validator.validate(header)?;
+            let current_height = match batch.last() {
+                Some(header) => header.height,
+                None => continue,
+            };
+            last_total_accumulated_difficulty = self.header_validator.validate_batch(batch).await?;
 
             if has_switched_to_new_chain {
                 // If we've switched to the new chain, we simply commit every COMMIT_EVERY_N_HEADERS headers
@@ -19,26 +19,22 @@
 //  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
-use std::cmp::Ordering;
+use std::{cmp::Ordering, iter};
 
 use log::*;
 use tari_common_types::types::HashOutput;
-use tari_utilities::{epoch_time::EpochTime, hash::Hashable, hex::Hex};
+use tari_utilities::{hash::Hashable, hex::Hex};
+use tokio::task;
 
 use crate::{
     base_node::sync::BlockHeaderSyncError,
-    blocks::{BlockHeader, BlockHeaderAccumulatedData, ChainHeader},
-    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, ChainStorageError, TargetDifficulties},
-    common::rolling_vec::RollingVec,
+    blocks::{BlockHeader, ChainHeader},
+    chain_storage::{async_db::AsyncBlockchainDb, BlockchainBackend, ChainStorageError},
     consensus::ConsensusManager,
-    proof_of_work::{randomx_factory::RandomXFactory, PowAlgorithm},
-    validation::helpers::{
-        check_blockchain_version,
-        check_header_timestamp_greater_than_median,
-        check_not_bad_block,
-        check_pow_data,
-        check_target_difficulty,
-        check_timestamp_ftl,
+    proof_of_work::{calculate_achieved_difficulties, randomx_factory::RandomXFactory, Difficulty, PowAlgorithm},
+    validation::{
+        helpers::{check_not_bad_block, check_pow_data},
+        HeaderChainState,
     },
 };
 
@@ -54,10 +50,7 @@ pub struct BlockHeaderSyncValidator<B> {
 
 #[derive(Debug, Clone)]
 struct State {
-    current_height: u64,
-    timestamps: RollingVec<EpochTime>,
-    target_difficulties: TargetDifficulties,
-    previous_accum: BlockHeaderAccumulatedData,
+    chain: HeaderChainState,
     valid_headers: Vec<ChainHeader>,
 }
 
@@ -100,10 +93,7 @@ impl<B: BlockchainBackend + 'static> BlockHeaderSyncValidator<B> {
             target_difficulties.get(PowAlgorithm::Monero).len(),
         );
         self.state = Some(State {
-            current_height: start_header.height,
-            timestamps,
-            target_difficulties,
-            previous_accum,
+            chain: HeaderChainState::new(start_header.height, previous_accum, timestamps, target_difficulties),
             // One large allocation is usually better even if it is not always used.
             valid_headers: Vec::with_capacity(1000),
         });
@@ -116,71 +106,83 @@ impl<B: BlockchainBackend + 'static> BlockHeaderSyncValidator<B> {
     }
 
     pub fn validate(&mut self, header: BlockHeader) -> Result<u128, BlockHeaderSyncError> {
-        let state = self.state();
-        let constants = self.consensus_rules.consensus_constants(header.height);
-        check_blockchain_version(constants, header.version)?;
-
-        let expected_height = state.current_height + 1;
-        if header.height != expected_height {
-            return Err(BlockHeaderSyncError::InvalidBlockHeight {
-                expected: expected_height,
-                actual: header.height,
-            });
-        }
-        if header.prev_hash != state.previous_accum.hash {
-            return Err(BlockHeaderSyncError::ChainLinkBroken {
-                height: header.height,
-                actual: header.prev_hash.to_hex(),
-                expected: state.previous_accum.hash.to_hex(),
-            });
-        }
-        check_timestamp_ftl(&header, &self.consensus_rules)?;
-
-        check_header_timestamp_greater_than_median(&header, &state.timestamps)?;
+        self.validate_with_achieved_difficulty(header, None)
+    }
 
-        let target_difficulty = state.target_difficulties.get(header.pow_algo()).calculate(
-            constants.min_pow_difficulty(header.pow_algo()),
-            constants.max_pow_difficulty(header.pow_algo()),
-        );
-        let achieved_target = check_target_difficulty(&header, target_difficulty, &self.randomx_factory)?;
+    /// Validates a contiguous batch of headers. Returns the total accumulated difficulty of the last header in the
+    /// batch.
+    ///
+    /// The cheap checks (height, previous hash, timestamps, PoW data and bad blocks) are performed on the batch first.
+    /// The PoW of the headers that pass them is then calculated in parallel on a blocking thread before the headers
+    /// are validated in order, so that a peer cannot cause RandomX hashing for headers that are invalid anyway.
+    pub async fn validate_batch(&mut self, headers: Vec<BlockHeader>) -> Result<u128, BlockHeaderSyncError> {
+        let num_to_hash = self.count_prevalidated(&headers)?;
+        let randomx_factory = self.randomx_factory.clone();
+        let (headers, achieved_difficulties) = task::spawn_blocking(move || {
+            let achieved_difficulties = calculate_achieved_difficulties(&headers[..num_to_hash], &randomx_factory);
+            (headers, achieved_difficulties)
+        })
+        .await?;
+
+        let mut total_accumulated_difficulty = self.state().chain.accumulated_data().total_accumulated_difficulty;
+        let achieved_difficulties = achieved_difficulties
+            .into_iter()
+            .map(|achieved| achieved.ok())
+            .chain(iter::repeat(None));
+        for (header, achieved) in headers.into_iter().zip(achieved_difficulties) {
+            // If the PoW was not calculated, the header is validated as usual so that the error is reported
+            // consistently. Headers that were not hashed fail the cheap checks before their PoW is calculated.
+            total_accumulated_difficulty = self.validate_with_achieved_difficulty(header, achieved)?;
+        }
+        Ok(total_accumulated_difficulty)
+    }
 
-        let block_hash = header.hash();
+    /// Returns the number of leading headers in the batch that pass the checks that do not require the PoW to be
+    /// calculated
+    fn count_prevalidated(&self, headers: &[BlockHeader]) -> Result<usize, BlockHeaderSyncError> {
+        let num_valid = self
+            .state()
+            .chain
+            .count_structurally_valid(headers, &self.consensus_rules);
+        let txn = self.db.inner().db_read_access()?;
+        for (index, header) in headers[..num_valid].iter().enumerate() {
+            if check_not_bad_block(&*txn, &header.hash()).is_err() ||
+                check_pow_data(header, &self.consensus_rules, &*txn).is_err()
+            {
+                return Ok(index);
+            }
+        }
+        Ok(num_valid)
+    }
 
+    fn validate_with_achieved_difficulty(
+        &mut self,
+        header: BlockHeader,
+        achieved: Option<Difficulty>,
+    ) -> Result<u128, BlockHeaderSyncError> {
         {
+            // The checks that are not covered by the header chain state, as they require the blockchain database
             let txn = self.db.inner().db_read_access()?;
-            check_not_bad_block(&*txn, &block_hash)?;
+            check_not_bad_block(&*txn, &header.hash())?;
             check_pow_data(&header, &self.consensus_rules, &*txn)?;
         }
 
-        // Header is valid, add this header onto the validation state for the next round
-        // Mutable borrow done later in the function to allow multiple immutable borrows before this line. This has
-        // nothing to do with locking or concurrency.
-        let state = self.state_mut();
-
-        // Ensure that timestamps are inserted in sorted order
-        let maybe_index = state.timestamps.iter().position(|ts| ts >= &header.timestamp());
-        match maybe_index {
-            Some(pos) => {
-                state.timestamps.insert(pos, header.timestamp());
+        // Borrow the fields directly so that the state can be mutated while the rules are borrowed
+        let state = self
+            .state
+            .as_mut()
+            .expect("validate() called before state was initialized (using the `begin` method)");
+        let chain_header = match achieved {
+            Some(achieved) => {
+                state
+                    .chain
+                    .verify_next_with_achieved_difficulty(header, &self.consensus_rules, achieved)?
             },
-            None => state.timestamps.push(header.timestamp()),
-        }
-
-        state.current_height = header.height;
-        // Add a "more recent" datapoint onto the target difficulty
-        state.target_difficulties.add_back(&header, target_difficulty);
-
-        let accumulated_data = BlockHeaderAccumulatedData::builder(&state.previous_accum)
-            .with_hash(block_hash)
-            .with_achieved_target_difficulty(achieved_target)
-            .with_total_kernel_offset(header.total_kernel_offset.clone())
-            .build()?;
-
-        let total_accumulated_difficulty = accumulated_data.total_accumulated_difficulty;
-        // NOTE: accumulated_data constructed from header so they are guaranteed to correspond
-        let chain_header = ChainHeader::try_construct(header, accumulated_data).unwrap();
-
-        state.previous_accum = chain_header.accumulated_data().clone();
+            None => state
+                .chain
+                .verify_next(header, &self.consensus_rules, &self.randomx_factory)?,
+        };
+        let total_accumulated_difficulty = chain_header.accumulated_data().total_accumulated_difficulty;
         state.valid_headers.push(chain_header);
 
         Ok(total_accumulated_difficulty)
@@ -245,7 +247,7 @@ mod test {
     };
 
     fn setup() -> (BlockHeaderSyncValidator<TempDatabase>, AsyncBlockchainDb<TempDatabase>) {
-        let rules = ConsensusManager::builder(Network::LocalNet).build();
+        let rules = ConsensusManager::builder(Network::LocalNet).build().unwrap();
         let randomx_factory = RandomXFactory::default();
         let db = create_new_blockchain();
         (
@@ -290,10 +292,10 @@ mod test {
             validator.initialize_state(&tip.header().hash()).await.unwrap();
             let state = validator.state();
             assert!(state.valid_headers.is_empty());
-            assert_eq!(state.target_difficulties.get(PowAlgorithm::Sha3).len(), 2);
-            assert!(state.target_difficulties.get(PowAlgorithm::Monero).is_empty());
-            assert_eq!(state.timestamps.len(), 2);
-            assert_eq!(state.current_height, 1);
+            assert_eq!(state.chain.target_difficulties().get(PowAlgorithm::Sha3).len(), 2);
+            assert!(state.chain.target_difficulties().get(PowAlgorithm::Monero).is_empty());
+            assert_eq!(state.chain.timestamps().len(), 2);
+            assert_eq!(state.chain.height(), 1);
         }
 
         #[tokio::test]
@@ -323,6 +325,30 @@ mod test {
             assert_eq!(validator.valid_headers().len(), 2);
         }
 
+        #[tokio::test]
+        async fn it_passes_a_valid_batch_of_headers() {
+            let (mut validator, _, tip) = setup_with_headers(1).await;
+            validator.initialize_state(tip.hash()).await.unwrap();
+            let mut prev = tip.header().clone();
+            let headers = (0..5)
+                .map(|_| {
+                    let header = BlockHeader::from_previous(&prev);
+                    prev = header.clone();
+                    header
+                })
+                .collect::<Vec<_>>();
+            let total_accumulated_difficulty = validator.validate_batch(headers).await.unwrap();
+            assert_eq!(validator.valid_headers().len(), 5);
+            assert_eq!(
+                validator
+                    .current_valid_chain_tip_header()
+                    .unwrap()
+                    .accumulated_data()
+                    .total_accumulated_difficulty,
+                total_accumulated_difficulty
+            );
+        }
+
         #[tokio::test]
         async fn it_fails_if_height_is_not_serial() {
             let (mut validator, _, tip) = setup_with_headers(2).await;
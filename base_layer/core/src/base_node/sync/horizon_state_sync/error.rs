@@ -59,6 +59,13 @@ pub enum HorizonSyncError {
         expected_hex: String,
         actual_hex: String,
     },
+    #[error("MMR size did not match for {mmr_tree} at height {at_height}. Expected {expected} but found {actual}")]
+    InconsistentMmrSize {
+        mmr_tree: MmrTree,
+        at_height: u64,
+        expected: u64,
+        actual: u64,
+    },
     #[error("Invalid range proof for output:{0} : {1}")]
     InvalidRangeProof(String, String),
     #[error("RPC error: {0}")]
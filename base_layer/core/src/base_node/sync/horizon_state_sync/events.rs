@@ -39,7 +39,7 @@ impl HorizonSyncInfo {
     }
 
     pub fn to_progress_string(&self) -> String {
-        use HorizonSyncStatus::{Finalizing, Kernels, Outputs, Starting};
+        use HorizonSyncStatus::{Finalizing, Kernels, Outputs, Starting, Verifying};
         match self.status {
             Starting => "Starting horizon sync".to_string(),
             Kernels {
@@ -74,6 +74,12 @@ impl HorizonSyncInfo {
                     .unwrap_or_default(),
                 sync_peer.latency().unwrap_or_default()
             ),
+            Verifying { current, total } => format!(
+                "Verifying horizon state: {}/{} ({:.0}%)",
+                current,
+                total,
+                current as f64 / total as f64 * 100.0
+            ),
             Finalizing => "Finalizing horizon sync".to_string(),
         }
     }
@@ -114,6 +120,9 @@ impl Display for HorizonSyncInfo {
                     sync_peer.latency().unwrap_or_default()
                 )
             },
+            HorizonSyncStatus::Verifying { current, total } => {
+                write!(f, "Verifying horizon state: {}/{} blocks", current, total)
+            },
             HorizonSyncStatus::Finalizing => write!(f, "Finalizing horizon state synchronization"),
         }
     }
@@ -131,5 +140,9 @@ pub enum HorizonSyncStatus {
         total: u64,
         sync_peer: SyncPeer,
     },
+    Verifying {
+        current: u64,
+        total: u64,
+    },
     Finalizing,
 }
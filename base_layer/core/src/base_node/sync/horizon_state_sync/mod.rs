@@ -28,3 +28,5 @@ pub use events::{HorizonSyncInfo, HorizonSyncStatus};
 
 mod synchronizer;
 pub use synchronizer::HorizonStateSynchronization;
+
+mod verification;
@@ -31,16 +31,13 @@ use std::{
 use croaring::Bitmap;
 use futures::{stream::FuturesUnordered, StreamExt};
 use log::*;
-use tari_common_types::types::{Commitment, HashDigest, RangeProofService};
+use tari_common_types::types::{HashDigest, RangeProofService};
 use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId};
-use tari_crypto::{
-    commitment::HomomorphicCommitment,
-    tari_utilities::{hex::Hex, Hashable},
-};
+use tari_crypto::tari_utilities::{hex::Hex, Hashable};
 use tari_mmr::{MerkleMountainRange, MutableMmr};
 use tokio::task;
 
-use super::error::HorizonSyncError;
+use super::{error::HorizonSyncError, verification::VerificationCheckpoint};
 use crate::{
     base_node::sync::{
         hooks::Hooks,
@@ -49,14 +46,13 @@ use crate::{
         BlockchainSyncConfig,
         SyncPeer,
     },
-    blocks::{BlockHeader, ChainHeader, UpdateBlockAccumulatedData},
+    blocks::{BlockHeader, UpdateBlockAccumulatedData},
     chain_storage::{
         async_db::AsyncBlockchainDb,
         BlockchainBackend,
         ChainStorageError,
-        DbTransaction,
+        HorizonSyncCheckpoint,
         MmrTree,
-        PrunedOutput,
     },
     common::rolling_avg::RollingAverageTime,
     consensus::ConsensusManager,
@@ -74,6 +70,9 @@ use crate::{
 
 const LOG_TARGET: &str = "c::bn::state_machine_service::states::horizon_state_sync";
 
+/// The number of times a chunk of the final horizon state verification is attempted before giving up
+const MAX_VERIFICATION_CHUNK_ATTEMPTS: usize = 3;
+
 pub struct HorizonStateSynchronization<'a, B> {
     config: BlockchainSyncConfig,
     db: AsyncBlockchainDb<B>,
@@ -84,6 +83,7 @@ pub struct HorizonStateSynchronization<'a, B> {
     num_kernels: u64,
     num_outputs: u64,
     full_bitmap: Option<Bitmap>,
+    checkpoint: Option<HorizonSyncCheckpoint>,
     hooks: Hooks,
     connectivity: ConnectivityRequester,
     final_state_validator: Arc<dyn FinalHorizonStateValidation<B>>,
@@ -114,6 +114,7 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
             num_kernels: 0,
             num_outputs: 0,
             full_bitmap: None,
+            checkpoint: None,
             hooks: Hooks::default(),
             final_state_validator,
         }
@@ -207,7 +208,7 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         to_header: &BlockHeader,
     ) -> Result<(), HorizonSyncError> {
         debug!(target: LOG_TARGET, "Initializing");
-        self.initialize().await?;
+        self.initialize(to_header).await?;
         self.hooks.call_on_starting_hook();
         debug!(target: LOG_TARGET, "Synchronizing kernels");
         self.synchronize_kernels(sync_peer.clone(), client, to_header).await?;
@@ -216,7 +217,7 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         Ok(())
     }
 
-    async fn initialize(&mut self) -> Result<(), HorizonSyncError> {
+    async fn initialize(&mut self, to_header: &BlockHeader) -> Result<(), HorizonSyncError> {
         let db = self.db();
         let local_metadata = db.get_chain_metadata().await?;
 
@@ -228,6 +229,39 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
 
         self.full_bitmap = Some(db.fetch_deleted_bitmap_at_tip().await?.into_bitmap());
 
+        // Kernels and outputs are committed together with the checkpoint, so the checkpoint is only resumed if it
+        // agrees with the locally stored MMR sizes and was made for the same horizon block.
+        let horizon_hash = to_header.hash();
+        let local_num_kernels = db.fetch_mmr_size(MmrTree::Kernel).await?;
+        let local_num_outputs = db.fetch_mmr_size(MmrTree::Utxo).await?;
+        let checkpoint = match db.fetch_horizon_sync_checkpoint().await? {
+            Some(checkpoint)
+                if *checkpoint.horizon_hash() == horizon_hash &&
+                    checkpoint.kernel_mmr_position() == local_num_kernels &&
+                    checkpoint.output_mmr_position() == local_num_outputs =>
+            {
+                info!(
+                    target: LOG_TARGET,
+                    "Resuming horizon sync to #{} from validated height {} ({} kernel(s), {} output(s))",
+                    checkpoint.horizon_height(),
+                    checkpoint.last_validated_height(),
+                    local_num_kernels,
+                    local_num_outputs
+                );
+                checkpoint
+            },
+            Some(checkpoint) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Discarding horizon sync checkpoint for #{} because it does not match the local state",
+                    checkpoint.horizon_height()
+                );
+                HorizonSyncCheckpoint::new(to_header.height, horizon_hash, local_num_kernels, local_num_outputs)
+            },
+            None => HorizonSyncCheckpoint::new(to_header.height, horizon_hash, local_num_kernels, local_num_outputs),
+        };
+        self.checkpoint = Some(checkpoint);
+
         Ok(())
     }
 
@@ -345,6 +379,11 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
                         ..Default::default()
                     },
                 );
+                let checkpoint = self
+                    .checkpoint_mut()
+                    .set_kernels_validated(mmr_position + 1, current_header.height())
+                    .clone();
+                txn.set_horizon_sync_checkpoint(checkpoint);
 
                 txn.commit().await?;
                 debug!(
@@ -618,6 +657,11 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
                             ..Default::default()
                         },
                     );
+                    let checkpoint = self
+                        .checkpoint_mut()
+                        .set_outputs_validated(mmr_position, current_header.height())
+                        .clone();
+                    txn.set_horizon_sync_checkpoint(checkpoint);
                     txn.commit().await?;
 
                     debug!(
@@ -721,19 +765,46 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
         Ok(())
     }
 
-    // Finalize the horizon state synchronization by setting the chain metadata to the local tip and committing
-    // the horizon state to the blockchain backend.
+    // Finalize the horizon state synchronization by verifying the horizon state, setting the chain metadata to the
+    // local tip and committing the horizon state to the blockchain backend.
     async fn finalize_horizon_sync(&mut self, sync_peer: &SyncPeer) -> Result<(), HorizonSyncError> {
         debug!(target: LOG_TARGET, "Validating horizon state");
 
+        let header = self.db().fetch_chain_header(self.horizon_sync_height).await?;
+        let deleted = self.take_final_bitmap();
+        let mut checkpoint = self.resume_verification(header.height()).await?;
+        while !checkpoint.is_complete() {
+            self.hooks.call_on_progress_horizon_hooks(HorizonSyncInfo::new(
+                vec![sync_peer.node_id().clone()],
+                HorizonSyncStatus::Verifying {
+                    current: checkpoint.num_verified(),
+                    total: checkpoint.num_blocks(),
+                },
+            ));
+            checkpoint = self.verify_next_chunk(&checkpoint, &deleted).await?;
+            debug!(
+                target: LOG_TARGET,
+                "Final Validation: verified {}/{} block(s)",
+                checkpoint.num_verified(),
+                checkpoint.num_blocks()
+            );
+            self.save_verification_progress(&checkpoint).await?;
+        }
+
         self.hooks.call_on_progress_horizon_hooks(HorizonSyncInfo::new(
             vec![sync_peer.node_id().clone()],
             HorizonSyncStatus::Finalizing,
         ));
 
-        let header = self.db().fetch_chain_header(self.horizon_sync_height).await?;
-        let (calc_utxo_sum, calc_kernel_sum) = self.calculate_commitment_sums(&header).await?;
+        let prune_positions = checkpoint.take_prune_positions();
+        if !prune_positions.is_empty() {
+            debug!(target: LOG_TARGET, "Pruning {} spent outputs", prune_positions.len());
+            let mut txn = self.db().write_transaction();
+            txn.prune_outputs_at_positions(prune_positions);
+            txn.commit().await?;
+        }
 
+        let (calc_utxo_sum, calc_kernel_sum) = checkpoint.commitment_sums();
         self.final_state_validator
             .validate(
                 &*self.db().inner().db_read_access()?,
@@ -758,127 +829,127 @@ impl<'a, B: BlockchainBackend + 'static> HorizonStateSynchronization<'a, B> {
             )
             .set_pruned_height(header.height())
             .set_horizon_data(calc_kernel_sum, calc_utxo_sum)
+            .clear_horizon_sync_checkpoint()
             .commit()
             .await?;
 
         Ok(())
     }
 
-    fn take_final_bitmap(&mut self) -> Arc<Bitmap> {
-        self.full_bitmap
-            .take()
-            .map(Arc::new)
-            .expect("take_full_bitmap called before initialize")
-    }
+    /// Resumes the final horizon state verification from the persisted checkpoint, if any of it has been completed.
+    async fn resume_verification(&self, horizon_height: u64) -> Result<VerificationCheckpoint, HorizonSyncError> {
+        let verified_height = self.checkpoint().verified_height();
+        if verified_height == 0 {
+            return Ok(VerificationCheckpoint::new(horizon_height));
+        }
 
-    fn full_bitmap_mut(&mut self) -> &mut Bitmap {
-        self.full_bitmap
-            .as_mut()
-            .expect("full_bitmap_mut called before initialize")
+        let header = self.db().fetch_chain_header(verified_height - 1).await?;
+        let checkpoint = self.checkpoint();
+        let verification = VerificationCheckpoint::resume(
+            horizon_height,
+            verified_height,
+            header.header().output_mmr_size,
+            header.header().kernel_mmr_size,
+            checkpoint.verified_commitment_sums(),
+            checkpoint.verified_prune_positions().to_vec(),
+        );
+        info!(
+            target: LOG_TARGET,
+            "Resuming horizon state verification at block #{}",
+            verification.num_verified()
+        );
+        Ok(verification)
     }
 
-    /// (UTXO sum, Kernel sum)
-    async fn calculate_commitment_sums(
+    /// Persists the progress of the final horizon state verification so that it can be resumed if interrupted.
+    async fn save_verification_progress(
         &mut self,
-        header: &ChainHeader,
-    ) -> Result<(Commitment, Commitment), HorizonSyncError> {
-        let mut utxo_sum = HomomorphicCommitment::default();
-        let mut kernel_sum = HomomorphicCommitment::default();
+        verification: &VerificationCheckpoint,
+    ) -> Result<(), HorizonSyncError> {
+        let checkpoint = self
+            .checkpoint_mut()
+            .set_verified(
+                verification.num_verified(),
+                verification.commitment_sums(),
+                verification.prune_positions().to_vec(),
+            )
+            .clone();
+        self.db()
+            .write_transaction()
+            .set_horizon_sync_checkpoint(checkpoint)
+            .commit()
+            .await?;
+        Ok(())
+    }
 
-        let mut prev_mmr = 0;
-        let mut prev_kernel_mmr = 0;
+    /// Verifies the next chunk of the horizon state, returning the advanced checkpoint. If the chunk is interrupted by
+    /// an error other than a verification failure, it is resumed from the given checkpoint.
+    async fn verify_next_chunk(
+        &self,
+        checkpoint: &VerificationCheckpoint,
+        deleted: &Arc<Bitmap>,
+    ) -> Result<VerificationCheckpoint, HorizonSyncError> {
+        let mut attempts = 1;
+        loop {
+            match self.verify_chunk(checkpoint.clone(), deleted.clone()).await {
+                Ok(checkpoint) => return Ok(checkpoint),
+                Err(err)
+                    if attempts < MAX_VERIFICATION_CHUNK_ATTEMPTS &&
+                        matches!(
+                            err,
+                            HorizonSyncError::ChainStorageError(_) | HorizonSyncError::JoinError(_)
+                        ) =>
+                {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Horizon state verification interrupted at block #{} (attempt {}): {}. Resuming.",
+                        checkpoint.num_verified(),
+                        attempts,
+                        err
+                    );
+                    attempts += 1;
+                },
+                Err(err) => return Err(err),
+            }
+        }
+    }
 
-        let height = header.height();
-        let bitmap = self.take_final_bitmap();
+    async fn verify_chunk(
+        &self,
+        mut checkpoint: VerificationCheckpoint,
+        deleted: Arc<Bitmap>,
+    ) -> Result<VerificationCheckpoint, HorizonSyncError> {
         let db = self.db().inner().clone();
-        task::spawn_blocking(move || {
-            let mut txn = DbTransaction::new();
-            let mut utxo_mmr_position = 0;
-            let mut prune_positions = vec![];
-
-            for h in 0..=height {
-                let curr_header = db.fetch_chain_header(h)?;
-
-                trace!(
-                    target: LOG_TARGET,
-                    "Fetching utxos from db: height:{}, header.output_mmr:{}, prev_mmr:{}, end:{}",
-                    curr_header.height(),
-                    curr_header.header().output_mmr_size,
-                    prev_mmr,
-                    curr_header.header().output_mmr_size - 1
-                );
-                let (utxos, _) = db.fetch_utxos_in_block(curr_header.hash().clone(), None)?;
-                debug!(
-                    target: LOG_TARGET,
-                    "{} output(s) loaded for height {}",
-                    utxos.len(),
-                    curr_header.height()
-                );
-                trace!(
-                    target: LOG_TARGET,
-                    "Fetching kernels from db: height:{}, header.kernel_mmr:{}, prev_mmr:{}, end:{}",
-                    curr_header.height(),
-                    curr_header.header().kernel_mmr_size,
-                    prev_kernel_mmr,
-                    curr_header.header().kernel_mmr_size - 1
-                );
-
-                trace!(target: LOG_TARGET, "Number of utxos returned: {}", utxos.len());
-                let mut pruned_counter = 0;
-                for u in utxos {
-                    match u {
-                        PrunedOutput::NotPruned { output } => {
-                            if bitmap.contains(utxo_mmr_position) {
-                                debug!(
-                                    target: LOG_TARGET,
-                                    "Found output that needs pruning at height: {} position: {}", h, utxo_mmr_position
-                                );
-                                prune_positions.push(utxo_mmr_position);
-                                pruned_counter += 1;
-                            } else {
-                                utxo_sum = &output.commitment + &utxo_sum;
-                            }
-                        },
-                        _ => {
-                            pruned_counter += 1;
-                        },
-                    }
-                    utxo_mmr_position += 1;
-                }
-                if pruned_counter > 0 {
-                    trace!(target: LOG_TARGET, "{} pruned output(s)", pruned_counter);
-                }
-                prev_mmr = curr_header.header().output_mmr_size;
+        let (checkpoint, outputs) = task::spawn_blocking(move || -> Result<_, HorizonSyncError> {
+            let outputs = checkpoint.verify_chunk(&db, &deleted)?;
+            Ok((checkpoint, outputs))
+        })
+        .await??;
+        self.validate_rangeproofs(outputs).await?;
+        Ok(checkpoint)
+    }
 
-                let kernels = db.fetch_kernels_in_block(curr_header.hash().clone())?;
-                trace!(target: LOG_TARGET, "Number of kernels returned: {}", kernels.len());
-                for k in kernels {
-                    kernel_sum = &k.excess + &kernel_sum;
-                }
-                prev_kernel_mmr = curr_header.header().kernel_mmr_size;
+    fn take_final_bitmap(&mut self) -> Arc<Bitmap> {
+        self.full_bitmap
+            .take()
+            .map(Arc::new)
+            .expect("take_full_bitmap called before initialize")
+    }
 
-                if h % 1000 == 0 {
-                    debug!(
-                        target: LOG_TARGET,
-                        "Final Validation: {:.2}% complete. Height: {}, mmr_position: {}, {} outputs to prune after \
-                         sync",
-                        (h as f32 / height as f32) * 100.0,
-                        h,
-                        utxo_mmr_position,
-                        prune_positions.len()
-                    );
-                }
-            }
+    fn checkpoint(&self) -> &HorizonSyncCheckpoint {
+        self.checkpoint.as_ref().expect("checkpoint called before initialize")
+    }
 
-            if !prune_positions.is_empty() {
-                debug!(target: LOG_TARGET, "Pruning {} spent outputs", prune_positions.len());
-                txn.prune_outputs_at_positions(prune_positions);
-                db.write(txn)?;
-            }
+    fn checkpoint_mut(&mut self) -> &mut HorizonSyncCheckpoint {
+        self.checkpoint
+            .as_mut()
+            .expect("checkpoint_mut called before initialize")
+    }
 
-            Ok((utxo_sum, kernel_sum))
-        })
-        .await?
+    fn full_bitmap_mut(&mut self) -> &mut Bitmap {
+        self.full_bitmap
+            .as_mut()
+            .expect("full_bitmap_mut called before initialize")
     }
 
     #[inline]
@@ -0,0 +1,253 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{cmp, convert::TryFrom};
+
+use croaring::Bitmap;
+use log::*;
+use tari_common_types::types::Commitment;
+
+use super::error::HorizonSyncError;
+use crate::{
+    chain_storage::{BlockchainBackend, BlockchainDatabase, MmrTree, PrunedOutput},
+    transactions::transaction_components::TransactionOutput,
+};
+
+const LOG_TARGET: &str = "c::bn::state_machine_service::states::horizon_state_sync::verification";
+
+/// The number of blocks that are verified in each chunk of the final horizon state verification
+const VERIFICATION_CHUNK_SIZE: u64 = 1000;
+
+/// Checkpoint of the final horizon state verification, which runs over the blocks up to the horizon height in chunks of
+/// [VERIFICATION_CHUNK_SIZE] blocks. Chunks are verified against a copy of the checkpoint, which only replaces the
+/// checkpoint once the chunk has been verified, so an interrupted chunk is resumed without repeating the chunks that
+/// have already completed.
+#[derive(Debug, Clone)]
+pub(super) struct VerificationCheckpoint {
+    horizon_height: u64,
+    next_height: u64,
+    output_mmr_position: u64,
+    kernel_mmr_position: u64,
+    utxo_sum: Commitment,
+    kernel_sum: Commitment,
+    prune_positions: Vec<u32>,
+}
+
+impl VerificationCheckpoint {
+    pub fn new(horizon_height: u64) -> Self {
+        Self {
+            horizon_height,
+            next_height: 0,
+            output_mmr_position: 0,
+            kernel_mmr_position: 0,
+            utxo_sum: Commitment::default(),
+            kernel_sum: Commitment::default(),
+            prune_positions: vec![],
+        }
+    }
+
+    /// Resumes the verification from `next_height` with the commitment sums and prune positions accumulated over the
+    /// blocks before it. The MMR positions are the output and kernel MMR sizes of the block before `next_height`.
+    pub fn resume(
+        horizon_height: u64,
+        next_height: u64,
+        output_mmr_position: u64,
+        kernel_mmr_position: u64,
+        (utxo_sum, kernel_sum): (Commitment, Commitment),
+        prune_positions: Vec<u32>,
+    ) -> Self {
+        Self {
+            horizon_height,
+            next_height,
+            output_mmr_position,
+            kernel_mmr_position,
+            utxo_sum,
+            kernel_sum,
+            prune_positions,
+        }
+    }
+
+    /// True once every block up to the horizon height has been verified
+    pub fn is_complete(&self) -> bool {
+        self.next_height > self.horizon_height
+    }
+
+    /// The number of blocks that have been verified
+    pub fn num_verified(&self) -> u64 {
+        self.next_height
+    }
+
+    /// The number of blocks that are verified
+    pub fn num_blocks(&self) -> u64 {
+        self.horizon_height + 1
+    }
+
+    /// (UTXO sum, Kernel sum)
+    pub fn commitment_sums(&self) -> (Commitment, Commitment) {
+        (self.utxo_sum.clone(), self.kernel_sum.clone())
+    }
+
+    /// The MMR positions of the outputs that have been found to be spent
+    pub fn prune_positions(&self) -> &[u32] {
+        &self.prune_positions
+    }
+
+    /// Takes the MMR positions of the outputs that were found to be spent
+    pub fn take_prune_positions(&mut self) -> Vec<u32> {
+        std::mem::take(&mut self.prune_positions)
+    }
+
+    /// Verifies the next chunk in a single pass over its blocks. The outputs and kernels stored for each block are
+    /// checked against the MMR sizes committed to in the block header and their unspent commitments are added to the
+    /// running commitment sums. Outputs that are spent at the horizon height but have not been pruned are recorded so
+    /// that they can be pruned once verification is complete. Returns the unspent outputs in the chunk, whose range
+    /// proofs need to be verified.
+    pub fn verify_chunk<B: BlockchainBackend>(
+        &mut self,
+        db: &BlockchainDatabase<B>,
+        deleted: &Bitmap,
+    ) -> Result<Vec<TransactionOutput>, HorizonSyncError> {
+        let end = self.chunk_end();
+        let mut unspent = vec![];
+        for h in self.next_height..=end {
+            let header = db.fetch_chain_header(h)?;
+            let (utxos, _) = db.fetch_utxos_in_block(header.hash().clone(), None)?;
+            for u in utxos {
+                if let PrunedOutput::NotPruned { output } = u {
+                    let position = u32::try_from(self.output_mmr_position)?;
+                    if deleted.contains(position) {
+                        trace!(
+                            target: LOG_TARGET,
+                            "Found output that needs pruning at height: {} position: {}",
+                            h,
+                            position
+                        );
+                        self.prune_positions.push(position);
+                    } else {
+                        self.utxo_sum = &output.commitment + &self.utxo_sum;
+                        unspent.push(output);
+                    }
+                }
+                self.output_mmr_position += 1;
+            }
+            if self.output_mmr_position != header.header().output_mmr_size {
+                return Err(HorizonSyncError::InconsistentMmrSize {
+                    mmr_tree: MmrTree::Utxo,
+                    at_height: h,
+                    expected: header.header().output_mmr_size,
+                    actual: self.output_mmr_position,
+                });
+            }
+
+            let kernels = db.fetch_kernels_in_block(header.hash().clone())?;
+            self.kernel_mmr_position += kernels.len() as u64;
+            if self.kernel_mmr_position != header.header().kernel_mmr_size {
+                return Err(HorizonSyncError::InconsistentMmrSize {
+                    mmr_tree: MmrTree::Kernel,
+                    at_height: h,
+                    expected: header.header().kernel_mmr_size,
+                    actual: self.kernel_mmr_position,
+                });
+            }
+            for k in kernels {
+                self.kernel_sum = &k.excess + &self.kernel_sum;
+            }
+        }
+        self.next_height = end + 1;
+        Ok(unspent)
+    }
+
+    fn chunk_end(&self) -> u64 {
+        cmp::min(
+            self.next_height.saturating_add(VERIFICATION_CHUNK_SIZE - 1),
+            self.horizon_height,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_helpers::blockchain::create_new_blockchain;
+
+    #[test]
+    fn it_advances_in_chunks() {
+        let mut checkpoint = VerificationCheckpoint::new(2 * VERIFICATION_CHUNK_SIZE + 10);
+        assert_eq!(checkpoint.num_blocks(), 2 * VERIFICATION_CHUNK_SIZE + 11);
+        for chunk in 0..3 {
+            assert!(!checkpoint.is_complete());
+            assert_eq!(checkpoint.num_verified(), chunk * VERIFICATION_CHUNK_SIZE);
+            checkpoint.next_height = checkpoint.chunk_end() + 1;
+        }
+        assert!(checkpoint.is_complete());
+        assert_eq!(checkpoint.num_verified(), checkpoint.num_blocks());
+    }
+
+    #[test]
+    fn it_resumes_with_the_accumulated_state() {
+        let db = create_new_blockchain();
+        let mut verified = VerificationCheckpoint::new(0);
+        verified.verify_chunk(&db, &Bitmap::create()).unwrap();
+
+        let resumed = VerificationCheckpoint::resume(
+            2 * VERIFICATION_CHUNK_SIZE + 10,
+            VERIFICATION_CHUNK_SIZE,
+            12,
+            34,
+            verified.commitment_sums(),
+            vec![5, 8],
+        );
+        assert!(!resumed.is_complete());
+        assert_eq!(resumed.num_verified(), VERIFICATION_CHUNK_SIZE);
+        assert_eq!(resumed.output_mmr_position, 12);
+        assert_eq!(resumed.kernel_mmr_position, 34);
+        assert_eq!(resumed.commitment_sums(), verified.commitment_sums());
+        assert_eq!(resumed.prune_positions(), &[5, 8]);
+    }
+
+    #[test]
+    fn it_verifies_the_genesis_block() {
+        let db = create_new_blockchain();
+        let deleted = Bitmap::create();
+        let mut checkpoint = VerificationCheckpoint::new(0);
+        let outputs = checkpoint.verify_chunk(&db, &deleted).unwrap();
+        assert!(!outputs.is_empty());
+        assert!(checkpoint.is_complete());
+        let (utxo_sum, kernel_sum) = checkpoint.commitment_sums();
+        assert_ne!(utxo_sum, Commitment::default());
+        assert_ne!(kernel_sum, Commitment::default());
+        assert!(checkpoint.take_prune_positions().is_empty());
+    }
+
+    #[test]
+    fn it_records_spent_outputs_for_pruning() {
+        let db = create_new_blockchain();
+        let mut deleted = Bitmap::create();
+        deleted.add(0);
+        let mut checkpoint = VerificationCheckpoint::new(0);
+        let outputs = checkpoint.verify_chunk(&db, &Bitmap::create()).unwrap();
+        let mut pruned = VerificationCheckpoint::new(0);
+        let unspent = pruned.verify_chunk(&db, &deleted).unwrap();
+        assert_eq!(unspent.len(), outputs.len() - 1);
+        assert_eq!(pruned.take_prune_positions(), vec![0]);
+    }
+}
@@ -34,6 +34,8 @@ use crate::{base_node::chain_metadata_service::PeerChainMetadata, common::rollin
 pub struct SyncPeer {
     peer_metadata: PeerChainMetadata,
     avg_latency: RollingAverageTime,
+    bytes_received: u64,
+    download_time: Duration,
 }
 
 impl SyncPeer {
@@ -66,6 +68,26 @@ impl SyncPeer {
     pub fn calc_avg_latency(&self) -> Option<Duration> {
         self.avg_latency.calculate_average()
     }
+
+    /// The total number of bytes received from this peer during sync
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+
+    /// The average download rate from this peer in bytes per second, or None if nothing has been downloaded
+    pub fn bytes_per_second(&self) -> Option<f64> {
+        let secs = self.download_time.as_secs_f64();
+        if self.bytes_received == 0 || secs == 0.0 {
+            return None;
+        }
+        Some(self.bytes_received as f64 / secs)
+    }
+
+    pub(super) fn add_bytes_received(&mut self, bytes: u64, elapsed: Duration) -> &mut Self {
+        self.bytes_received += bytes;
+        self.download_time += elapsed;
+        self
+    }
 }
 
 impl From<PeerChainMetadata> for SyncPeer {
@@ -73,6 +95,8 @@ impl From<PeerChainMetadata> for SyncPeer {
         Self {
             peer_metadata,
             avg_latency: RollingAverageTime::new(20),
+            bytes_received: 0,
+            download_time: Duration::from_secs(0),
         }
     }
 }
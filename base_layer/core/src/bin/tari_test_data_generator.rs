@@ -0,0 +1,191 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Writes random, internally consistent blocks, transactions and UTXO sets to files, so that benchmarks and downstream
+//! tests can use the same fixtures across runs.
+//!
+//! ```text
+//! cargo run -p tari_core --features test_data_generator --bin tari_test_data_generator -- \
+//!     --output-dir fixtures blocks --count 10 --txs-per-block 20 --features mixed
+//! ```
+
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Instant,
+};
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use tari_common::configuration::Network;
+use tari_core::{
+    consensus::ConsensusManager,
+    test_helpers::test_data::{generate_blocks, generate_transactions, generate_utxo_set, FeatureMix, TransactionSpec},
+    transactions::tari_amount::MicroTari,
+};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Cli {
+    /// The directory to write the generated data to
+    #[clap(long, short, default_value = ".")]
+    output_dir: PathBuf,
+    /// The file format to write: json or bincode
+    #[clap(long, default_value = "json")]
+    format: OutputFormat,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate a chain of blocks following the genesis block of the given network
+    Blocks {
+        #[clap(long, default_value = "10")]
+        count: usize,
+        #[clap(long, default_value = "10")]
+        txs_per_block: usize,
+        #[clap(long, default_value = "localnet")]
+        network: Network,
+        #[clap(flatten)]
+        tx: TransactionArgs,
+    },
+    /// Generate independent transactions
+    Transactions {
+        #[clap(long, default_value = "100")]
+        count: usize,
+        #[clap(flatten)]
+        tx: TransactionArgs,
+    },
+    /// Generate a set of unspent outputs
+    Utxos {
+        #[clap(long, default_value = "1000")]
+        count: usize,
+        /// The output features to use: standard, timelocked or mixed
+        #[clap(long, default_value = "standard")]
+        features: FeatureMix,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+struct TransactionArgs {
+    #[clap(long, default_value = "1")]
+    inputs: usize,
+    #[clap(long, default_value = "2")]
+    outputs: usize,
+    /// The fee per gram in µT
+    #[clap(long, default_value = "5")]
+    fee_per_gram: u64,
+    /// The output features to use: standard, timelocked or mixed
+    #[clap(long, default_value = "standard")]
+    features: FeatureMix,
+}
+
+impl From<TransactionArgs> for TransactionSpec {
+    fn from(args: TransactionArgs) -> Self {
+        Self {
+            num_inputs: args.inputs,
+            num_outputs: args.outputs,
+            fee_per_gram: MicroTari::from(args.fee_per_gram),
+            features: args.features,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum OutputFormat {
+    Json,
+    Bincode,
+}
+
+impl OutputFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Bincode => "bin",
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "bincode" | "bin" => Ok(OutputFormat::Bincode),
+            _ => Err(format!("Invalid format '{}'. Expected json or bincode", s)),
+        }
+    }
+}
+
+fn write_data<T: Serialize>(dir: &Path, name: &str, format: OutputFormat, data: &T) -> Result<PathBuf, Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.{}", name, format.extension()));
+    let writer = BufWriter::new(File::create(&path)?);
+    match format {
+        OutputFormat::Json => serde_json::to_writer(writer, data)?,
+        OutputFormat::Bincode => bincode::serialize_into(writer, data)?,
+    }
+    Ok(path)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let timer = Instant::now();
+    let (count, path) = match cli.command {
+        Command::Blocks {
+            count,
+            txs_per_block,
+            network,
+            tx,
+        } => {
+            let rules = ConsensusManager::builder(network).build()?;
+            let blocks = generate_blocks(&rules, count, txs_per_block, &tx.into());
+            (
+                blocks.len(),
+                write_data(&cli.output_dir, "blocks", cli.format, &blocks)?,
+            )
+        },
+        Command::Transactions { count, tx } => {
+            let txs = generate_transactions(count, &tx.into());
+            (
+                txs.len(),
+                write_data(&cli.output_dir, "transactions", cli.format, &txs)?,
+            )
+        },
+        Command::Utxos { count, features } => {
+            let utxos = generate_utxo_set(count, features);
+            (utxos.len(), write_data(&cli.output_dir, "utxos", cli.format, &utxos)?)
+        },
+    };
+    println!(
+        "Generated {} item(s) in {:.2?} and wrote them to {}",
+        count,
+        timer.elapsed(),
+        path.display()
+    );
+    Ok(())
+}
@@ -300,7 +300,7 @@ impl BlockHeaderAccumulatedDataBuilder<'_> {
                 previous_accum.accumulated_monero_difficulty + achieved_target.achieved(),
                 previous_accum.accumulated_sha_difficulty,
             ),
-            PowAlgorithm::Sha3 => (
+            PowAlgorithm::Sha3 | PowAlgorithm::Sha3x => (
                 previous_accum.accumulated_monero_difficulty,
                 previous_accum.accumulated_sha_difficulty + achieved_target.achieved(),
             ),
@@ -85,6 +85,8 @@ pub enum BlockHeaderValidationError {
     ProofOfWorkError(#[from] PowError),
     #[error("Monero seed hash too old")]
     OldSeedHash,
+    #[error("Proof of work algorithm {pow_algo} is not permitted at height {height}")]
+    PowAlgorithmNotPermitted { pow_algo: PowAlgorithm, height: u64 },
 }
 
 /// The BlockHeader contains all the metadata for the block, including proof of work, a link to the previous block
@@ -439,7 +441,11 @@ mod test {
 
     use tari_utilities::Hashable;
 
-    use crate::blocks::BlockHeader;
+    use crate::{
+        blocks::BlockHeader,
+        consensus::{ConsensusDecoding, ToConsensusBytes},
+        proof_of_work::{PowAlgorithm, ProofOfWork},
+    };
     #[test]
     fn from_previous() {
         let mut h1 = crate::proof_of_work::sha3_test::get_header();
@@ -536,4 +542,23 @@ mod test {
         let error_margin = f64::EPSILON; // Use machine epsilon for comparison of floats
         assert!((avg - 60f64).abs() < error_margin);
     }
+
+    #[test]
+    fn it_rejects_truncated_encodings() {
+        let header = BlockHeader {
+            height: 1234,
+            pow: ProofOfWork {
+                pow_algo: PowAlgorithm::Monero,
+                pow_data: vec![1; 128],
+            },
+            ..BlockHeader::new(2)
+        };
+        let bytes = header.to_consensus_bytes();
+        let decoded = BlockHeader::consensus_decode(&mut bytes.as_slice()).unwrap();
+        assert_eq!(decoded.to_consensus_bytes(), bytes);
+
+        for len in 0..bytes.len() {
+            assert!(BlockHeader::consensus_decode(&mut &bytes[..len]).is_err());
+        }
+    }
 }
@@ -375,8 +375,11 @@ mod test {
         let db = create_new_blockchain_with_network(Network::Dibbler);
 
         let lock = db.db_read_access().unwrap();
-        ChainBalanceValidator::new(ConsensusManager::builder(Network::Dibbler).build(), Default::default())
-            .validate(&*lock, 0, &utxo_sum, &kernel_sum)
-            .unwrap();
+        ChainBalanceValidator::new(
+            ConsensusManager::builder(Network::Dibbler).build().unwrap(),
+            Default::default(),
+        )
+        .validate(&*lock, 0, &utxo_sum, &kernel_sum)
+        .unwrap();
     }
 }
@@ -57,15 +57,18 @@ use crate::{
         BlockchainDatabase,
         ChainStorageError,
         DbBasicStats,
+        DbIntegrityReport,
         DbTotalSizeStats,
         DbTransaction,
         HorizonData,
+        HorizonSyncCheckpoint,
+        KernelLocation,
         MmrTree,
         PrunedOutput,
         TargetDifficulties,
     },
     common::rolling_vec::RollingVec,
-    proof_of_work::{PowAlgorithm, TargetDifficultyWindow},
+    proof_of_work::{PowAlgorithm, TargetDifficultyEstimate, TargetDifficultyWindow},
     transactions::transaction_components::{TransactionKernel, TransactionOutput},
 };
 
@@ -159,6 +162,8 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
 
     make_async_fn!(fetch_horizon_data() -> HorizonData, "fetch_horizon_data");
 
+    make_async_fn!(fetch_horizon_sync_checkpoint() -> Option<HorizonSyncCheckpoint>, "fetch_horizon_sync_checkpoint");
+
     //---------------------------------- TXO --------------------------------------------//
     make_async_fn!(fetch_utxo(hash: HashOutput) -> Option<PrunedOutput>, "fetch_utxo");
 
@@ -179,6 +184,8 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
     //---------------------------------- Kernel --------------------------------------------//
     make_async_fn!(fetch_kernel_by_excess_sig(excess_sig: Signature) -> Option<(TransactionKernel, HashOutput)>, "fetch_kernel_by_excess_sig");
 
+    make_async_fn!(fetch_kernel_location_by_excess_sig(excess_sig: Signature) -> Option<KernelLocation>, "fetch_kernel_location_by_excess_sig");
+
     make_async_fn!(fetch_kernels_in_block(hash: HashOutput) -> Vec<TransactionKernel>, "fetch_kernels_in_block");
 
     //---------------------------------- MMR --------------------------------------------//
@@ -234,6 +241,8 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
 
     make_async_fn!(bad_block_exists(block_hash: BlockHash) -> bool, "bad_block_exists");
 
+    make_async_fn!(fetch_missing_orphan_parent(hash: BlockHash) -> Option<BlockHash>, "fetch_missing_orphan_parent");
+
     make_async_fn!(fetch_block(height: u64) -> HistoricalBlock, "fetch_block");
 
     make_async_fn!(fetch_blocks<T: RangeBounds<u64>>(bounds: T) -> Vec<HistoricalBlock>, "fetch_blocks");
@@ -262,6 +271,8 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
 
     make_async_fn!(fetch_target_difficulty_for_next_block(pow_algo: PowAlgorithm, current_block_hash: HashOutput) -> TargetDifficultyWindow, "fetch_target_difficulty");
 
+    make_async_fn!(fetch_target_difficulty_estimate(pow_algo: PowAlgorithm, height: u64) -> TargetDifficultyEstimate, "fetch_target_difficulty_estimate");
+
     make_async_fn!(fetch_target_difficulties_for_next_block(current_block_hash: HashOutput) -> TargetDifficulties, "fetch_target_difficulties_for_next_block");
 
     make_async_fn!(fetch_block_hashes_from_header_tip(n: usize, offset: usize) -> Vec<HashOutput>, "fetch_block_hashes_from_header_tip");
@@ -274,6 +285,10 @@ impl<B: BlockchainBackend + 'static> AsyncBlockchainDb<B> {
 
     make_async_fn!(get_stats() -> DbBasicStats, "get_stats");
 
+    make_async_fn!(check_integrity() -> DbIntegrityReport, "check_integrity");
+
+    make_async_fn!(try_prune_step(max_blocks: u64) -> Option<u64>, "try_prune_step");
+
     make_async_fn!(fetch_total_size_stats() -> DbTotalSizeStats, "fetch_total_size_stats");
 }
 
@@ -324,6 +339,16 @@ impl<'a, B: BlockchainBackend + 'static> AsyncDbTransaction<'a, B> {
         self
     }
 
+    pub fn set_horizon_sync_checkpoint(&mut self, checkpoint: HorizonSyncCheckpoint) -> &mut Self {
+        self.transaction.set_horizon_sync_checkpoint(checkpoint);
+        self
+    }
+
+    pub fn clear_horizon_sync_checkpoint(&mut self) -> &mut Self {
+        self.transaction.clear_horizon_sync_checkpoint();
+        self
+    }
+
     pub fn insert_kernel_via_horizon_sync(
         &mut self,
         kernel: TransactionKernel,
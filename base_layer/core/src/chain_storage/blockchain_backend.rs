@@ -24,11 +24,14 @@ use crate::{
         pruned_output::PrunedOutput,
         ChainStorageError,
         DbBasicStats,
+        DbIntegrityReport,
         DbKey,
         DbTotalSizeStats,
         DbTransaction,
         DbValue,
         HorizonData,
+        HorizonSyncCheckpoint,
+        KernelLocation,
         MmrTree,
         Reorg,
         UtxoMinedInfo,
@@ -105,6 +108,13 @@ pub trait BlockchainBackend: Send + Sync {
         excess_sig: &Signature,
     ) -> Result<Option<(TransactionKernel, HashOutput)>, ChainStorageError>;
 
+    /// Fetch the block hash, height and MMR position of the kernel with this excess signature using the kernel excess
+    /// signature index, without loading the kernel or block header
+    fn fetch_kernel_location_by_excess_sig(
+        &self,
+        excess_sig: &Signature,
+    ) -> Result<Option<KernelLocation>, ChainStorageError>;
+
     /// Fetch all UTXOs and spends in the block
     fn fetch_utxos_in_block(
         &self,
@@ -180,18 +190,23 @@ pub trait BlockchainBackend: Send + Sync {
     /// Returns the full deleted bitmap at the current blockchain tip
     fn fetch_deleted_bitmap(&self) -> Result<DeletedBitmap, ChainStorageError>;
 
-    /// Delete orphans according to age. Used to keep the orphan pool at a certain capacity
-    fn delete_oldest_orphans(
-        &mut self,
-        horizon_height: u64,
-        orphan_storage_capacity: usize,
-    ) -> Result<(), ChainStorageError>;
+    /// Evicts the orphans at or below the horizon height and, if the orphan pool is still over capacity, the orphans
+    /// with the lowest accumulated difficulty. Orphans that do not connect to a known chain are evicted first.
+    fn evict_orphans(&mut self, horizon_height: u64, orphan_storage_capacity: usize) -> Result<(), ChainStorageError>;
 
     /// This gets the monero seed_height. This will return 0, if the seed is unkown
     fn fetch_monero_seed_first_seen_height(&self, seed: &[u8]) -> Result<u64, ChainStorageError>;
 
     fn fetch_horizon_data(&self) -> Result<Option<HorizonData>, ChainStorageError>;
 
+    /// Returns the checkpoint of an interrupted horizon state sync, if any.
+    fn fetch_horizon_sync_checkpoint(&self) -> Result<Option<HorizonSyncCheckpoint>, ChainStorageError>;
+
+    /// Verifies the MMR roots and sizes of every block on the main chain against its header, and checks the tables for
+    /// entries that do not belong to the main chain or indexes that refer to missing entries. This call may be very
+    /// slow and will obtain a read lock for the duration.
+    fn check_integrity(&self) -> Result<DbIntegrityReport, ChainStorageError>;
+
     /// Returns basic database stats for each internal database, such as number of entries and page sizes. This call may
     /// not apply to every database implementation.
     fn get_stats(&self) -> Result<DbBasicStats, ChainStorageError>;
@@ -27,13 +27,14 @@ use std::{
     convert::TryFrom,
     mem,
     ops::{Bound, Range, RangeBounds},
-    sync::{atomic, atomic::AtomicBool, Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
-    time::Instant,
+    sync::{atomic, atomic::AtomicBool, Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError},
+    time::{Duration, Instant},
 };
 
 use croaring::Bitmap;
 use log::*;
 use serde::{Deserialize, Serialize};
+use tari_common::configuration::serializers;
 use tari_common_types::{
     chain_metadata::ChainMetadata,
     types::{BlockHash, Commitment, HashDigest, HashOutput, PublicKey, Signature},
@@ -57,6 +58,8 @@ use crate::{
     },
     chain_storage::{
         consts::{
+            BLOCKCHAIN_DATABASE_BACKGROUND_PRUNING_BATCH_SIZE,
+            BLOCKCHAIN_DATABASE_BACKGROUND_PRUNING_INTERVAL,
             BLOCKCHAIN_DATABASE_ORPHAN_STORAGE_CAPACITY,
             BLOCKCHAIN_DATABASE_PRUNED_MODE_PRUNING_INTERVAL,
             BLOCKCHAIN_DATABASE_PRUNING_HORIZON,
@@ -68,8 +71,11 @@ use crate::{
         BlockAddResult,
         BlockchainBackend,
         DbBasicStats,
+        DbIntegrityReport,
         DbTotalSizeStats,
         HorizonData,
+        HorizonSyncCheckpoint,
+        KernelLocation,
         MmrTree,
         Optional,
         OrNotFound,
@@ -78,7 +84,7 @@ use crate::{
     },
     common::rolling_vec::RollingVec,
     consensus::{chain_strength_comparer::ChainStrengthComparer, ConsensusConstants, ConsensusManager},
-    proof_of_work::{monero_rx::MoneroPowData, PowAlgorithm, TargetDifficultyWindow},
+    proof_of_work::{PowAlgorithm, TargetDifficultyEstimate, TargetDifficultyWindow},
     transactions::transaction_components::{TransactionInput, TransactionKernel},
     validation::{
         helpers::calc_median_timestamp,
@@ -100,6 +106,15 @@ pub struct BlockchainDatabaseConfig {
     pub pruning_interval: u64,
     pub track_reorgs: bool,
     pub cleanup_orphans_at_startup: bool,
+    /// If true, a pruned node prunes spent outputs beyond the pruning horizon in small steps while it is idle, instead
+    /// of when blocks are added. Default: false
+    pub background_pruning: bool,
+    /// The maximum number of blocks pruned in each background pruning step. Default: 10
+    pub background_pruning_batch_size: u64,
+    /// The delay between background pruning steps. A step is skipped if block validation holds the database lock.
+    /// Default: 1s
+    #[serde(with = "serializers::seconds")]
+    pub background_pruning_interval: Duration,
 }
 
 impl Default for BlockchainDatabaseConfig {
@@ -110,6 +125,9 @@ impl Default for BlockchainDatabaseConfig {
             pruning_interval: BLOCKCHAIN_DATABASE_PRUNED_MODE_PRUNING_INTERVAL,
             track_reorgs: false,
             cleanup_orphans_at_startup: false,
+            background_pruning: false,
+            background_pruning_batch_size: BLOCKCHAIN_DATABASE_BACKGROUND_PRUNING_BATCH_SIZE,
+            background_pruning_interval: Duration::from_secs(BLOCKCHAIN_DATABASE_BACKGROUND_PRUNING_INTERVAL),
         }
     }
 }
@@ -289,6 +307,11 @@ where B: BlockchainBackend
         &self.consensus_manager
     }
 
+    /// Returns a reference to the database configuration
+    pub fn config(&self) -> &BlockchainDatabaseConfig {
+        &self.config
+    }
+
     // Be careful about making this method public. Rather use `db_and_metadata_read_access`
     // so that metadata and db are read in the correct order so that deadlocks don't occur
     pub fn db_read_access(&self) -> Result<RwLockReadGuard<B>, ChainStorageError> {
@@ -439,6 +462,14 @@ where B: BlockchainBackend
         db.fetch_kernel_by_excess_sig(&excess_sig)
     }
 
+    pub fn fetch_kernel_location_by_excess_sig(
+        &self,
+        excess_sig: Signature,
+    ) -> Result<Option<KernelLocation>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        db.fetch_kernel_location_by_excess_sig(&excess_sig)
+    }
+
     pub fn fetch_kernels_in_block(&self, hash: HashOutput) -> Result<Vec<TransactionKernel>, ChainStorageError> {
         let db = self.db_read_access()?;
         db.fetch_kernels_in_block(&hash)
@@ -726,6 +757,17 @@ where B: BlockchainBackend
         fetch_target_difficulty_for_next_block(&*db, &self.consensus_manager, pow_algo, &current_block_hash)
     }
 
+    /// Returns an estimate of the difficulty for the given proof of work algorithm for the block following the block at
+    /// the given height
+    pub fn fetch_target_difficulty_estimate(
+        &self,
+        pow_algo: PowAlgorithm,
+        height: u64,
+    ) -> Result<TargetDifficultyEstimate, ChainStorageError> {
+        let db = self.db_read_access()?;
+        fetch_target_difficulty_estimate(&*db, &self.consensus_manager, pow_algo, height)
+    }
+
     pub fn fetch_target_difficulties_for_next_block(
         &self,
         current_block_hash: HashOutput,
@@ -931,8 +973,11 @@ where B: BlockchainBackend
                 "Best chain is now at height: {}",
                 db.fetch_chain_metadata()?.height_of_longest_chain()
             );
-            // If blocks were added and the node is in pruned mode, perform pruning
-            prune_database_if_needed(&mut *db, self.config.pruning_horizon, self.config.pruning_interval)?;
+            // If blocks were added and the node is in pruned mode, perform pruning. With background pruning, the
+            // pruning task catches up once the node is idle.
+            if !self.config.background_pruning {
+                prune_database_if_needed(&mut *db, self.config.pruning_horizon, self.config.pruning_interval)?;
+            }
         }
 
         if let Err(e) = cleanup_orphans(&mut *db, self.config.orphan_storage_capacity) {
@@ -989,6 +1034,26 @@ where B: BlockchainBackend
         prune_to_height(&mut *db, height)
     }
 
+    /// Prunes at most `max_blocks` blocks beyond the pruning horizon. This never waits for the write lock, so that
+    /// pruning does not hold up block validation. Returns None if the database is busy, otherwise the number of blocks
+    /// that were pruned.
+    pub fn try_prune_step(&self, max_blocks: u64) -> Result<Option<u64>, ChainStorageError> {
+        let mut db = match self.db.try_write() {
+            Ok(db) => db,
+            Err(TryLockError::WouldBlock) => return Ok(None),
+            Err(TryLockError::Poisoned(e)) => {
+                error!(
+                    target: LOG_TARGET,
+                    "An attempt to get a write lock on the blockchain backend failed. {:?}", e
+                );
+                return Err(ChainStorageError::AccessError(
+                    "Write lock on blockchain backend failed".into(),
+                ));
+            },
+        };
+        prune_step(&mut *db, self.config.pruning_horizon, max_blocks).map(Some)
+    }
+
     /// Fetch a block from the blockchain database.
     ///
     /// # Returns
@@ -1066,6 +1131,26 @@ where B: BlockchainBackend
         Ok(db.contains(&DbKey::BlockHash(hash.clone()))? || db.contains(&DbKey::OrphanBlock(hash))?)
     }
 
+    /// Follows the parents of the given orphan block through the orphan pool and returns the hash of the first ancestor
+    /// that is neither an orphan nor in the main chain, i.e. the block that is needed to connect the orphan. Returns
+    /// None if the block is not an orphan or its ancestors connect to the main chain.
+    pub fn fetch_missing_orphan_parent(&self, hash: BlockHash) -> Result<Option<BlockHash>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        let mut parent_hash = match db.fetch(&DbKey::OrphanBlock(hash))? {
+            Some(DbValue::OrphanBlock(block)) => block.header.prev_hash,
+            _ => return Ok(None),
+        };
+        loop {
+            if db.contains(&DbKey::BlockHash(parent_hash.clone()))? {
+                return Ok(None);
+            }
+            match db.fetch(&DbKey::OrphanBlock(parent_hash.clone()))? {
+                Some(DbValue::OrphanBlock(block)) => parent_hash = block.header.prev_hash,
+                _ => return Ok(Some(parent_hash)),
+            }
+        }
+    }
+
     /// Returns true if this block exists in the chain, or is orphaned.
     pub fn bad_block_exists(&self, hash: BlockHash) -> Result<bool, ChainStorageError> {
         let db = self.db_read_access()?;
@@ -1103,6 +1188,11 @@ where B: BlockchainBackend
         Ok(db.fetch_horizon_data()?.unwrap_or_default())
     }
 
+    pub fn fetch_horizon_sync_checkpoint(&self) -> Result<Option<HorizonSyncCheckpoint>, ChainStorageError> {
+        let db = self.db_read_access()?;
+        db.fetch_horizon_sync_checkpoint()
+    }
+
     pub fn fetch_complete_deleted_bitmap_at(
         &self,
         hash: HashOutput,
@@ -1146,6 +1236,13 @@ where B: BlockchainBackend
         db.fetch_header_hash_by_deleted_mmr_positions(mmr_positions)
     }
 
+    /// Checks the database for inconsistencies between the headers, MMR data and indexes. See
+    /// [BlockchainBackend::check_integrity].
+    pub fn check_integrity(&self) -> Result<DbIntegrityReport, ChainStorageError> {
+        let db = self.db_read_access()?;
+        db.check_integrity()
+    }
+
     pub fn get_stats(&self) -> Result<DbBasicStats, ChainStorageError> {
         let lock = self.db_read_access()?;
         lock.get_stats()
@@ -1411,8 +1508,11 @@ fn insert_best_block(txn: &mut DbTransaction, block: Arc<ChainBlock>) -> Result<
         block_hash.to_hex()
     );
     if block.header().pow_algo() == PowAlgorithm::Monero {
-        let monero_header =
-            MoneroPowData::from_header(block.header()).map_err(|e| ChainStorageError::InvalidArguments {
+        let monero_header = block
+            .header()
+            .pow
+            .monero_data()
+            .map_err(|e| ChainStorageError::InvalidArguments {
                 func: "insert_best_block",
                 arg: "block",
                 message: format!("block contained invalid or malformed monero PoW data: {}", e),
@@ -1446,13 +1546,15 @@ pub fn fetch_target_difficulty_for_next_block<T: BlockchainBackend>(
     // The block may be in the chained orphan pool or in the main chain
     let mut header = db.fetch_chain_header_in_all_chains(current_block_hash)?;
     let mut target_difficulties = consensus_manager.new_target_difficulty(pow_algo, header.height() + 1);
-    if header.header().pow.pow_algo == pow_algo {
+    // Variants of an algorithm (e.g. Sha3x) share the target difficulty window of their base algorithm
+    let pow_algo = pow_algo.base_algorithm();
+    if header.header().pow.pow_algo.base_algorithm() == pow_algo {
         target_difficulties.add_front(header.header().timestamp(), header.accumulated_data().target_difficulty);
     }
     while header.height() > 0 && !target_difficulties.is_full() {
         header = db.fetch_chain_header_in_all_chains(&header.header().prev_hash)?;
 
-        if header.header().pow.pow_algo == pow_algo {
+        if header.header().pow.pow_algo.base_algorithm() == pow_algo {
             target_difficulties.add_front(header.header().timestamp(), header.accumulated_data().target_difficulty);
         }
     }
@@ -1460,6 +1562,44 @@ pub fn fetch_target_difficulty_for_next_block<T: BlockchainBackend>(
     Ok(target_difficulties)
 }
 
+fn fetch_target_difficulty_estimate<T: BlockchainBackend>(
+    db: &T,
+    consensus_manager: &ConsensusManager,
+    pow_algo: PowAlgorithm,
+    height: u64,
+) -> Result<TargetDifficultyEstimate, ChainStorageError> {
+    let header = db.fetch_chain_header_by_height(height)?;
+    let constants = consensus_manager.consensus_constants(height.saturating_add(1));
+    let target_difficulty = fetch_target_difficulty_for_next_block(db, consensus_manager, pow_algo, header.hash())?
+        .calculate(
+            constants.min_pow_difficulty(pow_algo),
+            constants.max_pow_difficulty(pow_algo),
+        );
+
+    // Find the most recent block mined with this algorithm. Blocks older than the difficulty window do not affect the
+    // target difficulty, so the search stops there and falls back to the minimum difficulty.
+    let base_algo = pow_algo.base_algorithm();
+    let min_height = height.saturating_sub(constants.get_difficulty_block_window());
+    let mut last_header = header;
+    while last_header.header().pow.pow_algo.base_algorithm() != base_algo && last_header.height() > min_height {
+        last_header = db.fetch_chain_header_by_height(last_header.height() - 1)?;
+    }
+    let current_difficulty = if last_header.header().pow.pow_algo.base_algorithm() == base_algo {
+        last_header.accumulated_data().target_difficulty
+    } else {
+        constants.min_pow_difficulty(pow_algo)
+    };
+
+    Ok(TargetDifficultyEstimate {
+        pow_algo,
+        height,
+        current_difficulty,
+        target_difficulty,
+        target_block_interval: constants.get_diff_target_block_interval(pow_algo),
+        last_block_timestamp: last_header.header().timestamp(),
+    })
+}
+
 fn fetch_block<T: BlockchainBackend>(db: &T, height: u64) -> Result<HistoricalBlock, ChainStorageError> {
     let mark = Instant::now();
     let (tip_height, is_pruned) = check_for_valid_height(&*db, height)?;
@@ -2222,14 +2362,14 @@ fn find_strongest_orphan_tip(
     best_block_header
 }
 
-// Perform a comprehensive search to remove all the minimum height orphans to maintain the configured orphan pool
-// storage limit. If the node is configured to run in pruned mode then orphan blocks with heights lower than the horizon
-// block height will also be discarded.
+// Evict the orphans with the lowest accumulated difficulty to maintain the configured orphan pool storage limit. If the
+// node is configured to run in pruned mode then orphan blocks with heights lower than the horizon block height will
+// also be discarded.
 fn cleanup_orphans<T: BlockchainBackend>(db: &mut T, orphan_storage_capacity: usize) -> Result<(), ChainStorageError> {
     let metadata = db.fetch_chain_metadata()?;
     let horizon_height = metadata.horizon_block(metadata.height_of_longest_chain());
 
-    db.delete_oldest_orphans(horizon_height, orphan_storage_capacity)
+    db.evict_orphans(horizon_height, orphan_storage_capacity)
 }
 
 fn prune_database_if_needed<T: BlockchainBackend>(
@@ -2259,6 +2399,26 @@ fn prune_database_if_needed<T: BlockchainBackend>(
     Ok(())
 }
 
+/// Prunes at most `max_blocks` blocks beyond the pruning horizon and returns the number of blocks that were pruned
+fn prune_step<T: BlockchainBackend>(
+    db: &mut T,
+    pruning_horizon: u64,
+    max_blocks: u64,
+) -> Result<u64, ChainStorageError> {
+    let metadata = db.fetch_chain_metadata()?;
+    if !metadata.is_pruned_node() {
+        return Ok(0);
+    }
+
+    let abs_pruning_horizon = metadata.height_of_longest_chain().saturating_sub(pruning_horizon);
+    let target_height = cmp::min(abs_pruning_horizon, metadata.pruned_height().saturating_add(max_blocks));
+    if target_height <= metadata.pruned_height() {
+        return Ok(0);
+    }
+    prune_to_height(db, target_height)?;
+    Ok(target_height - metadata.pruned_height())
+}
+
 fn prune_to_height<T: BlockchainBackend>(db: &mut T, target_horizon_height: u64) -> Result<(), ChainStorageError> {
     let metadata = db.fetch_chain_metadata()?;
     let last_pruned = metadata.pruned_height();
@@ -2383,6 +2543,7 @@ mod test {
             ConsensusConstantsBuilder,
             ConsensusManager,
         },
+        proof_of_work::DifficultyAdjustmentAlgorithm,
         test_helpers::{
             blockchain::{
                 create_chained_blocks,
@@ -2428,6 +2589,23 @@ mod test {
         }
     }
 
+    mod fetch_target_difficulty_estimate {
+        use super::*;
+
+        #[test]
+        fn it_falls_back_to_the_minimum_difficulty_if_the_algorithm_was_not_used() {
+            let db = create_new_blockchain();
+            create_main_chain(&db, &[("A->GB", 1, 120), ("B->A", 1, 120), ("C->B", 1, 120)]);
+            let estimate = db.fetch_target_difficulty_estimate(PowAlgorithm::Monero, 3).unwrap();
+            let constants = db.consensus_constants().unwrap();
+            assert_eq!(estimate.height, 3);
+            assert_eq!(
+                estimate.current_difficulty,
+                constants.min_pow_difficulty(PowAlgorithm::Monero)
+            );
+        }
+    }
+
     mod get_orphan_link_main_chain {
         use super::*;
 
@@ -3179,10 +3357,12 @@ mod test {
                         max_target_time: 1200,
                         min_difficulty: 1.into(),
                         max_difficulty: 100.into(),
-                        target_time: 120,
+                        block_share: 100,
+                        difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
                     })
                     .build(),
             )
             .build()
+            .unwrap()
     }
 }
@@ -26,3 +26,7 @@ pub const BLOCKCHAIN_DATABASE_ORPHAN_STORAGE_CAPACITY: usize = 720;
 pub const BLOCKCHAIN_DATABASE_PRUNING_HORIZON: u64 = 0;
 /// The chain height interval used to determine when a pruned node should perform pruning.
 pub const BLOCKCHAIN_DATABASE_PRUNED_MODE_PRUNING_INTERVAL: u64 = 50;
+/// The maximum number of blocks pruned in each step of background pruning.
+pub const BLOCKCHAIN_DATABASE_BACKGROUND_PRUNING_BATCH_SIZE: u64 = 10;
+/// The delay in seconds between steps of background pruning.
+pub const BLOCKCHAIN_DATABASE_BACKGROUND_PRUNING_INTERVAL: u64 = 1;
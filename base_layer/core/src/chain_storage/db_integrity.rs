@@ -0,0 +1,163 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+use std::{fmt, fmt::Display};
+
+use crate::chain_storage::MmrTree;
+
+/// An inconsistency found by a blockchain database integrity check
+#[derive(Debug, Clone, PartialEq)]
+pub enum DbIntegrityIssue {
+    /// There is no header stored at a height on the main chain
+    MissingHeader { height: u64 },
+    /// The block hash index does not map the hash of the header back to its height
+    InconsistentHashIndex { height: u64 },
+    /// The accumulated MMR data of a block is missing
+    MissingBlockAccumulatedData { height: u64 },
+    /// The MMR root calculated from the stored data does not match the root committed to in the header
+    MismatchedMmrRoot { height: u64, mmr_tree: MmrTree },
+    /// The number of kernels or outputs stored for a block does not match the MMR sizes in the headers
+    MismatchedMmrSize {
+        height: u64,
+        mmr_tree: MmrTree,
+        expected: u64,
+        actual: u64,
+    },
+    /// Entries that belong to a block that is not on the main chain
+    OrphanedEntries { table: &'static str, count: usize },
+    /// Index entries that refer to data that does not exist
+    DanglingIndexEntries { table: &'static str, count: usize },
+}
+
+impl DbIntegrityIssue {
+    /// The height of the block that the issue applies to, or None if the issue does not apply to a single block
+    pub fn height(&self) -> Option<u64> {
+        #[allow(clippy::enum_glob_use)]
+        use DbIntegrityIssue::*;
+        match self {
+            MissingHeader { height } |
+            InconsistentHashIndex { height } |
+            MissingBlockAccumulatedData { height } |
+            MismatchedMmrRoot { height, .. } |
+            MismatchedMmrSize { height, .. } => Some(*height),
+            OrphanedEntries { .. } | DanglingIndexEntries { .. } => None,
+        }
+    }
+}
+
+impl Display for DbIntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[allow(clippy::enum_glob_use)]
+        use DbIntegrityIssue::*;
+        match self {
+            MissingHeader { height } => write!(f, "Missing header at height {}", height),
+            InconsistentHashIndex { height } => write!(f, "Block hash index is inconsistent at height {}", height),
+            MissingBlockAccumulatedData { height } => {
+                write!(f, "Missing block accumulated data at height {}", height)
+            },
+            MismatchedMmrRoot { height, mmr_tree } => write!(f, "{} MMR root mismatch at height {}", mmr_tree, height),
+            MismatchedMmrSize {
+                height,
+                mmr_tree,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} MMR size mismatch at height {}: expected {} but found {}",
+                mmr_tree, height, expected, actual
+            ),
+            OrphanedEntries { table, count } => write!(f, "{} orphaned entries in {}", count, table),
+            DanglingIndexEntries { table, count } => write!(f, "{} dangling index entries in {}", count, table),
+        }
+    }
+}
+
+/// The result of a blockchain database integrity check
+#[derive(Debug, Clone, Default)]
+pub struct DbIntegrityReport {
+    pub tip_height: u64,
+    pub pruned_height: u64,
+    pub num_blocks_checked: u64,
+    pub issues: Vec<DbIntegrityIssue>,
+}
+
+impl DbIntegrityReport {
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The height of the block before the first block with an issue, or None if no block has an issue. Rewinding the
+    /// chain to this height removes every block-level inconsistency.
+    pub fn last_consistent_height(&self) -> Option<u64> {
+        self.issues
+            .iter()
+            .filter_map(DbIntegrityIssue::height)
+            .min()
+            .map(|height| height.saturating_sub(1))
+    }
+}
+
+impl Display for DbIntegrityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Checked {} block(s) (tip: #{}, pruned height: #{})",
+            self.num_blocks_checked, self.tip_height, self.pruned_height
+        )?;
+        if self.is_consistent() {
+            return write!(f, "No issues found");
+        }
+        writeln!(f, "{} issue(s) found:", self.issues.len())?;
+        for issue in &self.issues {
+            writeln!(f, "- {}", issue)?;
+        }
+        match self.last_consistent_height() {
+            Some(height) => write!(f, "Last consistent height: #{}", height),
+            None => write!(f, "All blocks are consistent"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_returns_the_height_before_the_first_block_issue() {
+        let mut report = DbIntegrityReport::default();
+        assert!(report.is_consistent());
+        assert_eq!(report.last_consistent_height(), None);
+
+        report.issues.push(DbIntegrityIssue::OrphanedEntries {
+            table: "kernels_db",
+            count: 2,
+        });
+        assert!(!report.is_consistent());
+        assert_eq!(report.last_consistent_height(), None);
+
+        report.issues.push(DbIntegrityIssue::MismatchedMmrRoot {
+            height: 10,
+            mmr_tree: MmrTree::Kernel,
+        });
+        report.issues.push(DbIntegrityIssue::MissingHeader { height: 7 });
+        assert_eq!(report.last_consistent_height(), Some(6));
+    }
+}
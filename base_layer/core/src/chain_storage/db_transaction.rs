@@ -35,7 +35,7 @@ use tari_utilities::{
 
 use crate::{
     blocks::{Block, BlockHeader, BlockHeaderAccumulatedData, ChainBlock, ChainHeader, UpdateBlockAccumulatedData},
-    chain_storage::{error::ChainStorageError, HorizonData, Reorg},
+    chain_storage::{error::ChainStorageError, HorizonData, HorizonSyncCheckpoint, Reorg},
     transactions::transaction_components::{TransactionKernel, TransactionOutput},
 };
 
@@ -260,6 +260,17 @@ impl DbTransaction {
         self
     }
 
+    pub fn set_horizon_sync_checkpoint(&mut self, checkpoint: HorizonSyncCheckpoint) -> &mut Self {
+        self.operations
+            .push(WriteOperation::SetHorizonSyncCheckpoint { checkpoint });
+        self
+    }
+
+    pub fn clear_horizon_sync_checkpoint(&mut self) -> &mut Self {
+        self.operations.push(WriteOperation::ClearHorizonSyncCheckpoint);
+        self
+    }
+
     pub(crate) fn operations(&self) -> &[WriteOperation] {
         &self.operations
     }
@@ -348,6 +359,10 @@ pub enum WriteOperation {
     SetHorizonData {
         horizon_data: HorizonData,
     },
+    SetHorizonSyncCheckpoint {
+        checkpoint: HorizonSyncCheckpoint,
+    },
+    ClearHorizonSyncCheckpoint,
     InsertReorg {
         reorg: Reorg,
     },
@@ -441,6 +456,13 @@ impl fmt::Display for WriteOperation {
             DeleteOrphan(hash) => write!(f, "Delete orphan with hash: {}", hash.to_hex()),
             InsertBadBlock { hash, height } => write!(f, "Insert bad block #{} {}", height, hash.to_hex()),
             SetHorizonData { .. } => write!(f, "Set horizon data"),
+            SetHorizonSyncCheckpoint { checkpoint } => write!(
+                f,
+                "Set horizon sync checkpoint for #{} at height {}",
+                checkpoint.horizon_height(),
+                checkpoint.last_validated_height()
+            ),
+            ClearHorizonSyncCheckpoint => write!(f, "Clear horizon sync checkpoint"),
             InsertReorg { .. } => write!(f, "Insert reorg"),
             ClearAllReorgs => write!(f, "Clear all reorgs"),
         }
@@ -0,0 +1,125 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{BlockHash, Commitment};
+
+/// The persisted progress of a horizon state sync. The checkpoint is written in the same transaction as each batch of
+/// synchronized data, so that an interrupted sync to the same horizon block can be resumed from the last validated
+/// batch.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HorizonSyncCheckpoint {
+    horizon_height: u64,
+    horizon_hash: BlockHash,
+    kernel_mmr_position: u64,
+    output_mmr_position: u64,
+    last_validated_height: u64,
+    verified_height: u64,
+    verified_utxo_sum: Commitment,
+    verified_kernel_sum: Commitment,
+    verified_prune_positions: Vec<u32>,
+}
+
+impl HorizonSyncCheckpoint {
+    pub fn new(
+        horizon_height: u64,
+        horizon_hash: BlockHash,
+        kernel_mmr_position: u64,
+        output_mmr_position: u64,
+    ) -> Self {
+        Self {
+            horizon_height,
+            horizon_hash,
+            kernel_mmr_position,
+            output_mmr_position,
+            last_validated_height: 0,
+            verified_height: 0,
+            verified_utxo_sum: Commitment::default(),
+            verified_kernel_sum: Commitment::default(),
+            verified_prune_positions: vec![],
+        }
+    }
+
+    /// The height of the horizon block that is being synchronized to
+    pub fn horizon_height(&self) -> u64 {
+        self.horizon_height
+    }
+
+    /// The hash of the horizon block that is being synchronized to
+    pub fn horizon_hash(&self) -> &BlockHash {
+        &self.horizon_hash
+    }
+
+    /// The number of kernels that have been synchronized and validated
+    pub fn kernel_mmr_position(&self) -> u64 {
+        self.kernel_mmr_position
+    }
+
+    /// The number of outputs that have been synchronized and validated
+    pub fn output_mmr_position(&self) -> u64 {
+        self.output_mmr_position
+    }
+
+    /// The height of the last block whose synchronized data was validated against the MMR roots in its header
+    pub fn last_validated_height(&self) -> u64 {
+        self.last_validated_height
+    }
+
+    /// The number of blocks that have been verified by the final horizon state verification
+    pub fn verified_height(&self) -> u64 {
+        self.verified_height
+    }
+
+    /// The (UTXO sum, Kernel sum) of the unspent outputs and kernels in the verified blocks
+    pub fn verified_commitment_sums(&self) -> (Commitment, Commitment) {
+        (self.verified_utxo_sum.clone(), self.verified_kernel_sum.clone())
+    }
+
+    /// The MMR positions of the spent outputs in the verified blocks that are to be pruned
+    pub fn verified_prune_positions(&self) -> &[u32] {
+        &self.verified_prune_positions
+    }
+
+    pub fn set_kernels_validated(&mut self, kernel_mmr_position: u64, height: u64) -> &mut Self {
+        self.kernel_mmr_position = kernel_mmr_position;
+        self.last_validated_height = height;
+        self
+    }
+
+    pub fn set_outputs_validated(&mut self, output_mmr_position: u64, height: u64) -> &mut Self {
+        self.output_mmr_position = output_mmr_position;
+        self.last_validated_height = height;
+        self
+    }
+
+    pub fn set_verified(
+        &mut self,
+        verified_height: u64,
+        (utxo_sum, kernel_sum): (Commitment, Commitment),
+        prune_positions: Vec<u32>,
+    ) -> &mut Self {
+        self.verified_height = verified_height;
+        self.verified_utxo_sum = utxo_sum;
+        self.verified_kernel_sum = kernel_sum;
+        self.verified_prune_positions = prune_positions;
+        self
+    }
+}
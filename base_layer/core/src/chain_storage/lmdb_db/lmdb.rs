@@ -382,6 +382,39 @@ where
     Ok(result)
 }
 
+/// Calls `f` with the key and value of each entry in key order and returns the results that are `Some`. For tables
+/// that allow duplicates, `f` is called for every value of a key.
+pub fn lmdb_filter_map_key_values<F, V, R>(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    f: F,
+) -> Result<Vec<R>, ChainStorageError>
+where
+    F: Fn(&[u8], V) -> Option<R>,
+    V: DeserializeOwned,
+{
+    let access = txn.access();
+    let mut cursor = txn.cursor(db).map_err(|e| {
+        error!(target: LOG_TARGET, "Could not get read cursor from lmdb: {:?}", e);
+        ChainStorageError::AccessError(e.to_string())
+    })?;
+    let iter = CursorIter::new(
+        MaybeOwned::Borrowed(&mut cursor),
+        &access,
+        |c, a| c.first(a),
+        Cursor::next::<[u8], [u8]>,
+    )?;
+
+    let mut result = vec![];
+    for row in iter {
+        let (key, val) = row?;
+        if let Some(r) = f(key, deserialize::<V>(val)?) {
+            result.push(r);
+        }
+    }
+    Ok(result)
+}
+
 /// Fetches the size of all key/values in the given DB. Returns the number of entries, the total size of all the
 /// keys and values in bytes.
 pub fn fetch_db_entry_sizes(txn: &ConstTransaction<'_>, db: &Database) -> Result<(u64, u64, u64), ChainStorageError> {
@@ -26,6 +26,7 @@
 #![allow(clippy::ptr_arg)]
 
 use std::{
+    convert::TryInto,
     fmt,
     fmt::Formatter,
     fs,
@@ -40,9 +41,9 @@ use std::{
 use blake2::Digest;
 use croaring::Bitmap;
 use fs2::FileExt;
-use lmdb_zero::{open, ConstTransaction, Database, Environment, ReadTransaction, WriteTransaction};
+use lmdb_zero::{copy, open, ConstTransaction, Database, Environment, ReadTransaction, WriteTransaction};
 use log::*;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tari_common_types::{
     chain_metadata::ChainMetadata,
     types::{BlockHash, Commitment, HashDigest, HashOutput, PublicKey, Signature, BLOCK_HASH_LENGTH},
@@ -50,6 +51,7 @@ use tari_common_types::{
 use tari_mmr::{Hash, MerkleMountainRange, MutableMmr};
 use tari_storage::lmdb_store::{db, LMDBBuilder, LMDBConfig, LMDBStore};
 use tari_utilities::{
+    epoch_time::EpochTime,
     hash::Hashable,
     hex::{to_hex, Hex},
     ByteArray,
@@ -79,6 +81,7 @@ use crate::{
                 lmdb_delete_keys_starting_with,
                 lmdb_exists,
                 lmdb_fetch_matching_after,
+                lmdb_filter_map_key_values,
                 lmdb_filter_map_values,
                 lmdb_first_after,
                 lmdb_get,
@@ -90,17 +93,29 @@ use crate::{
                 lmdb_len,
                 lmdb_replace,
             },
+            migrations::{
+                pending_migrations,
+                Migration,
+                MigrationProgress,
+                MigrationRecord,
+                MigrationReport,
+                MIGRATIONS,
+            },
             TransactionInputRowData,
             TransactionInputRowDataRef,
             TransactionKernelRowData,
             TransactionOutputRowData,
         },
-        stats::DbTotalSizeStats,
+        stats::{DbCompactionReport, DbTotalSizeStats},
         utxo_mined_info::UtxoMinedInfo,
         BlockchainBackend,
         DbBasicStats,
+        DbIntegrityIssue,
+        DbIntegrityReport,
         DbSize,
         HorizonData,
+        HorizonSyncCheckpoint,
+        KernelLocation,
         MmrTree,
         PrunedOutput,
         Reorg,
@@ -115,6 +130,9 @@ type DatabaseRef = Arc<Database<'static>>;
 
 pub const LOG_TARGET: &str = "c::cs::lmdb_db::lmdb_db";
 
+/// The name of the LMDB data file in the database directory
+const LMDB_DATA_FILE: &str = "data.mdb";
+
 const LMDB_DB_METADATA: &str = "metadata";
 const LMDB_DB_HEADERS: &str = "headers";
 const LMDB_DB_HEADER_ACCUMULATED_DATA: &str = "header_accumulated_data";
@@ -136,10 +154,87 @@ const LMDB_DB_MONERO_SEED_HEIGHT: &str = "monero_seed_height";
 const LMDB_DB_ORPHAN_HEADER_ACCUMULATED_DATA: &str = "orphan_accumulated_data";
 const LMDB_DB_ORPHAN_CHAIN_TIPS: &str = "orphan_chain_tips";
 const LMDB_DB_ORPHAN_PARENT_MAP_INDEX: &str = "orphan_parent_map_index";
+const LMDB_DB_ORPHAN_HEIGHT_INDEX: &str = "orphan_height_index";
 const LMDB_DB_BAD_BLOCK_LIST: &str = "bad_blocks";
 const LMDB_DB_REORGS: &str = "reorgs";
+const LMDB_DB_MIGRATIONS: &str = "migrations";
 
+/// Opens (or creates) the LMDB chain database at `path` and applies any pending schema migrations
 pub fn create_lmdb_database<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Result<LMDBDatabase, ChainStorageError> {
+    let db = open_lmdb_database(path, config)?;
+    let report = db.run_migrations(false, |progress| info!(target: LOG_TARGET, "{}", progress))?;
+    if !report.applied.is_empty() {
+        info!(target: LOG_TARGET, "{}", report);
+    }
+    Ok(db)
+}
+
+/// Opens the LMDB chain database at `path` and applies the pending schema migrations in a transaction that is
+/// discarded afterwards, leaving the database unchanged.
+pub fn dry_run_lmdb_migrations<P, F>(
+    path: P,
+    config: LMDBConfig,
+    on_progress: F,
+) -> Result<MigrationReport, ChainStorageError>
+where
+    P: AsRef<Path>,
+    F: FnMut(&MigrationProgress),
+{
+    open_lmdb_database(path, config)?.run_migrations(true, on_progress)
+}
+
+/// Copies the LMDB chain database at `path` into a fresh environment, leaving out the free pages, and replaces the
+/// data file with the copy. This reclaims the space freed by reorgs and pruning, which LMDB otherwise only reuses. The
+/// database must not be open in a running node.
+pub fn compact_lmdb_database<P: AsRef<Path>>(
+    path: P,
+    config: LMDBConfig,
+) -> Result<DbCompactionReport, ChainStorageError> {
+    let path = path.as_ref();
+    let data_file = path.join(LMDB_DATA_FILE);
+    let compact_path = path.join("compact");
+    let compact_path_str = compact_path
+        .to_str()
+        .ok_or_else(|| ChainStorageError::InvalidArguments {
+            func: "compact_lmdb_database",
+            arg: "path",
+            message: format!("{} is not a valid UTF-8 path", compact_path.display()),
+        })?
+        .to_string();
+
+    let db = open_lmdb_database(path, config)?;
+    let size_before = fs::metadata(&data_file)?.len();
+    if compact_path.exists() {
+        fs::remove_dir_all(&compact_path)?;
+    }
+    fs::create_dir_all(&compact_path)?;
+    info!(
+        target: LOG_TARGET,
+        "Compacting LMDB database at {} ({} bytes)",
+        path.display(),
+        size_before
+    );
+    let timer = Instant::now();
+    db.env.copy(&compact_path_str, copy::COMPACT)?;
+
+    // Keep the file lock while the data file is replaced, the environment must be closed first
+    let file_lock = db._file_lock.clone();
+    drop(db);
+    let compact_file = compact_path.join(LMDB_DATA_FILE);
+    let size_after = fs::metadata(&compact_file)?.len();
+    fs::rename(&compact_file, &data_file)?;
+    fs::remove_dir_all(&compact_path)?;
+    drop(file_lock);
+
+    let report = DbCompactionReport {
+        size_before,
+        size_after,
+    };
+    info!(target: LOG_TARGET, "{} in {:.2?}", report, timer.elapsed());
+    Ok(report)
+}
+
+fn open_lmdb_database<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Result<LMDBDatabase, ChainStorageError> {
     let flags = db::CREATE;
     debug!(target: LOG_TARGET, "Creating LMDB database at {:?}", path.as_ref());
     std::fs::create_dir_all(&path)?;
@@ -173,8 +268,10 @@ pub fn create_lmdb_database<P: AsRef<Path>>(path: P, config: LMDBConfig) -> Resu
         .add_database(LMDB_DB_MONERO_SEED_HEIGHT, flags)
         .add_database(LMDB_DB_ORPHAN_CHAIN_TIPS, flags)
         .add_database(LMDB_DB_ORPHAN_PARENT_MAP_INDEX, flags | db::DUPSORT)
+        .add_database(LMDB_DB_ORPHAN_HEIGHT_INDEX, flags | db::INTEGERKEY | db::DUPSORT)
         .add_database(LMDB_DB_BAD_BLOCK_LIST, flags)
         .add_database(LMDB_DB_REORGS, flags | db::INTEGERKEY)
+        .add_database(LMDB_DB_MIGRATIONS, flags | db::INTEGERKEY)
         .build()
         .map_err(|err| ChainStorageError::CriticalError(format!("Could not create LMDB store:{}", err)))?;
     debug!(target: LOG_TARGET, "LMDB database creation successful");
@@ -206,8 +303,10 @@ pub struct LMDBDatabase {
     orphan_header_accumulated_data_db: DatabaseRef,
     orphan_chain_tips_db: DatabaseRef,
     orphan_parent_map_index: DatabaseRef,
+    orphan_height_index: DatabaseRef,
     bad_blocks: DatabaseRef,
     reorgs: DatabaseRef,
+    migrations_db: DatabaseRef,
     _file_lock: Arc<File>,
 }
 
@@ -240,8 +339,10 @@ impl LMDBDatabase {
             monero_seed_height_db: get_database(&store, LMDB_DB_MONERO_SEED_HEIGHT)?,
             orphan_chain_tips_db: get_database(&store, LMDB_DB_ORPHAN_CHAIN_TIPS)?,
             orphan_parent_map_index: get_database(&store, LMDB_DB_ORPHAN_PARENT_MAP_INDEX)?,
+            orphan_height_index: get_database(&store, LMDB_DB_ORPHAN_HEIGHT_INDEX)?,
             bad_blocks: get_database(&store, LMDB_DB_BAD_BLOCK_LIST)?,
             reorgs: get_database(&store, LMDB_DB_REORGS)?,
+            migrations_db: get_database(&store, LMDB_DB_MIGRATIONS)?,
             env,
             env_config: store.env_config(),
             _file_lock: Arc::new(file_lock),
@@ -250,6 +351,92 @@ impl LMDBDatabase {
         Ok(db)
     }
 
+    /// Returns the schema version of the database, i.e. the version of the last migration that was applied
+    pub fn schema_version(&self) -> Result<u64, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let last: Option<MigrationRecord> = lmdb_last(&txn, &self.migrations_db)?;
+        Ok(last.map(|record| record.version).unwrap_or(0))
+    }
+
+    /// Applies the migrations newer than the database's schema version, calling `on_progress` before each step.
+    /// Each migration is committed separately. A dry run applies all pending migrations in a single transaction and
+    /// then discards it.
+    pub fn run_migrations<F>(&self, dry_run: bool, on_progress: F) -> Result<MigrationReport, ChainStorageError>
+    where F: FnMut(&MigrationProgress) {
+        self.apply_migrations(MIGRATIONS, dry_run, on_progress)
+    }
+
+    fn apply_migrations<F>(
+        &self,
+        migrations: &'static [Migration],
+        dry_run: bool,
+        mut on_progress: F,
+    ) -> Result<MigrationReport, ChainStorageError>
+    where
+        F: FnMut(&MigrationProgress),
+    {
+        let from_version = self.schema_version()?;
+        let pending = pending_migrations(migrations, from_version)?;
+        // A new database already has the latest layout, so the migrations are only recorded
+        let is_new_database = from_version == 0 && self.is_empty()?;
+
+        if dry_run {
+            let txn = self.write_transaction()?;
+            for migration in &pending {
+                self.apply_migration(&txn, migration, is_new_database, &mut on_progress)?;
+            }
+            // The transaction is aborted when dropped without being committed
+        } else {
+            for migration in &pending {
+                let txn = self.write_transaction()?;
+                self.apply_migration(&txn, migration, is_new_database, &mut on_progress)?;
+                txn.commit()?;
+            }
+        }
+
+        Ok(MigrationReport {
+            from_version,
+            to_version: pending.last().map(|m| m.version).unwrap_or(from_version),
+            applied: pending.iter().map(|m| (m.version, m.name)).collect(),
+            dry_run,
+        })
+    }
+
+    fn apply_migration<F>(
+        &self,
+        txn: &WriteTransaction<'_>,
+        migration: &Migration,
+        skip_steps: bool,
+        on_progress: &mut F,
+    ) -> Result<(), ChainStorageError>
+    where
+        F: FnMut(&MigrationProgress),
+    {
+        if !skip_steps {
+            for (i, step) in migration.steps.iter().enumerate() {
+                on_progress(&MigrationProgress {
+                    version: migration.version,
+                    name: migration.name,
+                    step: i + 1,
+                    num_steps: migration.steps.len(),
+                    description: step.description,
+                });
+                (step.run)(self, txn)?;
+            }
+        }
+        lmdb_insert(
+            txn,
+            &self.migrations_db,
+            &migration.version,
+            &MigrationRecord {
+                version: migration.version,
+                name: migration.name.to_string(),
+                applied_at: EpochTime::now().as_u64(),
+            },
+            "migrations_db",
+        )
+    }
+
     /// Try to establish a read lock on the LMDB database. If an exclusive write lock has been previously acquired, this
     /// method will block until that lock is released.
     fn read_transaction(&self) -> Result<ReadTransaction<'_>, ChainStorageError> {
@@ -416,6 +603,19 @@ impl LMDBDatabase {
                         MetadataValue::HorizonData(horizon_data.clone()),
                     )?;
                 },
+                SetHorizonSyncCheckpoint { checkpoint } => {
+                    self.set_metadata(
+                        &write_txn,
+                        MetadataKey::HorizonSyncCheckpoint,
+                        MetadataValue::HorizonSyncCheckpoint(checkpoint.clone()),
+                    )?;
+                },
+                ClearHorizonSyncCheckpoint => {
+                    let k = MetadataKey::HorizonSyncCheckpoint.as_u32();
+                    if lmdb_exists(&write_txn, &self.metadata_db, &k)? {
+                        lmdb_delete(&write_txn, &self.metadata_db, &k, LMDB_DB_METADATA)?;
+                    }
+                },
                 InsertBadBlock { hash, height } => {
                     self.insert_bad_block_and_cleanup(&write_txn, hash, *height)?;
                 },
@@ -432,8 +632,8 @@ impl LMDBDatabase {
         Ok(())
     }
 
-    fn all_dbs(&self) -> [(&'static str, &DatabaseRef); 23] {
-        [
+    fn all_dbs(&self) -> Vec<(&'static str, &DatabaseRef)> {
+        vec![
             ("metadata_db", &self.metadata_db),
             ("headers_db", &self.headers_db),
             ("header_accumulated_data_db", &self.header_accumulated_data_db),
@@ -461,8 +661,10 @@ impl LMDBDatabase {
             ("monero_seed_height_db", &self.monero_seed_height_db),
             ("orphan_chain_tips_db", &self.orphan_chain_tips_db),
             ("orphan_parent_map_index", &self.orphan_parent_map_index),
+            ("orphan_height_index", &self.orphan_height_index),
             ("bad_blocks", &self.bad_blocks),
             ("reorgs", &self.reorgs),
+            ("migrations_db", &self.migrations_db),
         ]
     }
 
@@ -741,11 +943,206 @@ impl LMDBDatabase {
     fn insert_orphan_block(&self, txn: &WriteTransaction<'_>, block: &Block) -> Result<(), ChainStorageError> {
         let k = block.hash();
         lmdb_insert_dup(txn, &self.orphan_parent_map_index, &block.header.prev_hash, &k)?;
+        lmdb_insert_dup(txn, &self.orphan_height_index, &block.header.height, &k)?;
         lmdb_insert(txn, &self.orphans_db, k.as_slice(), &block, "orphans_db")?;
 
         Ok(())
     }
 
+    /// Rebuilds the orphan height index from the orphan blocks. Used by the migration that introduced the index.
+    pub(super) fn index_orphans_by_height(&self, txn: &WriteTransaction<'_>) -> Result<(), ChainStorageError> {
+        lmdb_clear(txn, &self.orphan_height_index)?;
+        let orphans = lmdb_filter_map_values(txn, &self.orphans_db, |block: Block| {
+            Some((block.header.height, block.hash()))
+        })?;
+        for (height, hash) in orphans {
+            lmdb_insert_dup(txn, &self.orphan_height_index, &height, &hash)?;
+        }
+        Ok(())
+    }
+
+    /// Checks the header, block hash index, accumulated MMR data, MMR roots and MMR sizes of every block on the main
+    /// chain, from the tip down to the genesis block.
+    fn check_block_integrity(
+        &self,
+        txn: &ConstTransaction<'_>,
+        report: &mut DbIntegrityReport,
+    ) -> Result<(), ChainStorageError> {
+        // The deleted bitmap of each block is recovered by undoing the spends of the blocks above it, starting from
+        // the bitmap at the tip. It cannot be recovered below a block with missing data.
+        let mut deleted = Some(fetch_deleted_bitmap(txn, &self.metadata_db)?.into_bitmap());
+        for height in (0..=report.tip_height).rev() {
+            report.num_blocks_checked += 1;
+            let header: BlockHeader = match lmdb_get(txn, &self.headers_db, &height)? {
+                Some(header) => header,
+                None => {
+                    report.issues.push(DbIntegrityIssue::MissingHeader { height });
+                    deleted = None;
+                    continue;
+                },
+            };
+            let hash = header.hash();
+            if self.fetch_height_from_hash(txn, &hash)? != Some(height) {
+                report.issues.push(DbIntegrityIssue::InconsistentHashIndex { height });
+            }
+
+            let prev_mmr_sizes = if height == 0 {
+                Some((0, 0))
+            } else {
+                lmdb_get::<_, BlockHeader>(txn, &self.headers_db, &(height - 1))?
+                    .map(|prev| (prev.kernel_mmr_size, prev.output_mmr_size))
+            };
+            if let Some((prev_kernel_mmr_size, prev_output_mmr_size)) = prev_mmr_sizes {
+                let num_kernels =
+                    lmdb_fetch_matching_after::<TransactionKernelRowData>(txn, &self.kernels_db, &hash)?.len() as u64;
+                if prev_kernel_mmr_size + num_kernels != header.kernel_mmr_size {
+                    report.issues.push(DbIntegrityIssue::MismatchedMmrSize {
+                        height,
+                        mmr_tree: MmrTree::Kernel,
+                        expected: header.kernel_mmr_size,
+                        actual: prev_kernel_mmr_size + num_kernels,
+                    });
+                }
+                let num_outputs =
+                    lmdb_fetch_matching_after::<TransactionOutputRowData>(txn, &self.utxos_db, &hash)?.len() as u64;
+                if prev_output_mmr_size + num_outputs != header.output_mmr_size {
+                    report.issues.push(DbIntegrityIssue::MismatchedMmrSize {
+                        height,
+                        mmr_tree: MmrTree::Utxo,
+                        expected: header.output_mmr_size,
+                        actual: prev_output_mmr_size + num_outputs,
+                    });
+                }
+            }
+
+            let block_data = match self.fetch_block_accumulated_data(txn, height)? {
+                Some(block_data) => block_data,
+                None => {
+                    report
+                        .issues
+                        .push(DbIntegrityIssue::MissingBlockAccumulatedData { height });
+                    deleted = None;
+                    continue;
+                },
+            };
+            let (kernels, outputs, witness, deleted_diff) = block_data.dissolve();
+
+            if MerkleMountainRange::<HashDigest, _>::new(kernels).get_merkle_root()? != header.kernel_mr {
+                report.issues.push(DbIntegrityIssue::MismatchedMmrRoot {
+                    height,
+                    mmr_tree: MmrTree::Kernel,
+                });
+            }
+            if MerkleMountainRange::<HashDigest, _>::new(witness).get_merkle_root()? != header.witness_mr {
+                report.issues.push(DbIntegrityIssue::MismatchedMmrRoot {
+                    height,
+                    mmr_tree: MmrTree::Witness,
+                });
+            }
+            if let Some(deleted) = deleted.as_mut() {
+                let mut output_mmr = MutableMmr::<HashDigest, _>::new(outputs, deleted.clone())?;
+                output_mmr.compress();
+                if output_mmr.get_merkle_root()? != header.output_mr {
+                    report.issues.push(DbIntegrityIssue::MismatchedMmrRoot {
+                        height,
+                        mmr_tree: MmrTree::Utxo,
+                    });
+                }
+                deleted.xor_inplace(&deleted_diff);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks for entries that belong to blocks that are not on the main chain and for index entries that refer to
+    /// missing data.
+    fn check_index_integrity(
+        &self,
+        txn: &ConstTransaction<'_>,
+        report: &mut DbIntegrityReport,
+    ) -> Result<(), ChainStorageError> {
+        let tip_height = report.tip_height;
+        let is_orphaned = |header_hash: &HashOutput| -> Result<bool, ChainStorageError> {
+            Ok(!matches!(self.fetch_height_from_hash(txn, header_hash)?, Some(height) if height <= tip_height))
+        };
+        let orphaned = [
+            (
+                "kernels_db",
+                count_entries_where(txn, &self.kernels_db, |row: TransactionKernelRowData| {
+                    is_orphaned(&row.header_hash)
+                })?,
+            ),
+            (
+                "utxos_db",
+                count_entries_where(txn, &self.utxos_db, |row: TransactionOutputRowData| {
+                    is_orphaned(&row.header_hash)
+                })?,
+            ),
+            (
+                "inputs_db",
+                count_entries_where(txn, &self.inputs_db, |row: TransactionInputRowData| {
+                    is_orphaned(&row.header_hash)
+                })?,
+            ),
+        ];
+        for (table, count) in orphaned {
+            if count > 0 {
+                report.issues.push(DbIntegrityIssue::OrphanedEntries { table, count });
+            }
+        }
+
+        let kernel_exists = |(header_hash, mmr_position, hash): (HashOutput, u32, HashOutput)| {
+            let key = KernelKey::new(&header_hash, mmr_position, &hash);
+            lmdb_exists(txn, &self.kernels_db, key.as_bytes()).map(|exists| !exists)
+        };
+        let orphan_exists =
+            |hash: HashOutput| lmdb_exists(txn, &self.orphans_db, hash.as_slice()).map(|exists| !exists);
+        let dangling = [
+            (
+                "txos_hash_to_index_db",
+                count_entries_where(txn, &self.txos_hash_to_index_db, |(_, key): (u32, Vec<u8>)| {
+                    lmdb_exists(txn, &self.utxos_db, key.as_slice()).map(|exists| !exists)
+                })?,
+            ),
+            (
+                "kernel_excess_index",
+                count_entries_where(txn, &self.kernel_excess_index, kernel_exists)?,
+            ),
+            (
+                "kernel_excess_sig_index",
+                count_entries_where(txn, &self.kernel_excess_sig_index, kernel_exists)?,
+            ),
+            (
+                "orphan_parent_map_index",
+                count_entries_where(txn, &self.orphan_parent_map_index, orphan_exists)?,
+            ),
+            (
+                "orphan_height_index",
+                count_entries_where(txn, &self.orphan_height_index, orphan_exists)?,
+            ),
+            (
+                "orphan_chain_tips_db",
+                count_entries_where(txn, &self.orphan_chain_tips_db, orphan_exists)?,
+            ),
+            (
+                "orphan_header_accumulated_data_db",
+                count_entries_where(
+                    txn,
+                    &self.orphan_header_accumulated_data_db,
+                    |data: BlockHeaderAccumulatedData| orphan_exists(data.hash),
+                )?,
+            ),
+        ];
+        for (table, count) in dangling {
+            if count > 0 {
+                report
+                    .issues
+                    .push(DbIntegrityIssue::DanglingIndexEntries { table, count });
+            }
+        }
+        Ok(())
+    }
+
     fn set_accumulated_data_for_orphan(
         &self,
         txn: &WriteTransaction<'_>,
@@ -1105,6 +1502,7 @@ impl LMDBDatabase {
 
         let parent_hash = orphan.header.prev_hash;
         lmdb_delete_key_value(txn, &self.orphan_parent_map_index, parent_hash.as_slice(), &hash)?;
+        lmdb_delete_key_value(txn, &self.orphan_height_index, &orphan.header.height, &hash)?;
 
         // Orphan is a tip hash
         if lmdb_exists(txn, &self.orphan_chain_tips_db, hash.as_slice())? {
@@ -1536,9 +1934,9 @@ pub fn create_recovery_lmdb_database<P: AsRef<Path>>(path: P) -> Result<(), Chai
     let new_path = path.as_ref().join("temp_recovery");
     let _result = fs::create_dir_all(&new_path);
 
-    let data_file = path.as_ref().join("data.mdb");
+    let data_file = path.as_ref().join(LMDB_DATA_FILE);
 
-    let new_data_file = new_path.join("data.mdb");
+    let new_data_file = new_path.join(LMDB_DATA_FILE);
 
     fs::rename(data_file, new_data_file)
         .map_err(|err| ChainStorageError::CriticalError(format!("Could not copy LMDB store:{}", err)))?;
@@ -1884,6 +2282,30 @@ impl BlockchainBackend for LMDBDatabase {
         }
     }
 
+    fn fetch_kernel_location_by_excess_sig(
+        &self,
+        excess_sig: &Signature,
+    ) -> Result<Option<KernelLocation>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let mut key = Vec::<u8>::with_capacity(32 * 2);
+        key.extend(excess_sig.get_public_nonce().as_bytes());
+        key.extend(excess_sig.get_signature().as_bytes());
+        let (block_hash, mmr_position, kernel_hash) =
+            match lmdb_get::<_, (HashOutput, u32, HashOutput)>(&txn, &self.kernel_excess_sig_index, key.as_slice())? {
+                Some(v) => v,
+                None => return Ok(None),
+            };
+        let block_height =
+            self.fetch_height_from_hash(&txn, &block_hash)
+                .or_not_found("BlockHash", "hash", block_hash.to_hex())?;
+        Ok(Some(KernelLocation {
+            block_hash,
+            block_height,
+            mmr_position,
+            kernel_hash,
+        }))
+    }
+
     fn fetch_utxos_in_block(
         &self,
         header_hash: &HashOutput,
@@ -2217,46 +2639,58 @@ impl BlockchainBackend for LMDBDatabase {
             .collect()
     }
 
-    fn delete_oldest_orphans(
-        &mut self,
-        horizon_height: u64,
-        orphan_storage_capacity: usize,
-    ) -> Result<(), ChainStorageError> {
-        let orphan_count = self.orphan_count()?;
-        let num_over_limit = orphan_count.saturating_sub(orphan_storage_capacity);
-        if num_over_limit == 0 {
-            return Ok(());
-        }
-        debug!(
-            target: LOG_TARGET,
-            "Orphan block storage limit of {} reached, performing cleanup of {} entries.",
-            orphan_storage_capacity,
-            num_over_limit,
-        );
-
-        let mut orphans;
+    fn evict_orphans(&mut self, horizon_height: u64, orphan_storage_capacity: usize) -> Result<(), ChainStorageError> {
+        let to_evict = {
+            let txn = self.read_transaction()?;
+            // The height index is ordered by height
+            let orphans = lmdb_filter_map_key_values(&txn, &self.orphan_height_index, |k, hash: HashOutput| {
+                let height = u64::from_ne_bytes(k.try_into().ok()?);
+                Some((height, hash))
+            })?;
+            let (mut to_evict, retained): (Vec<_>, Vec<_>) =
+                orphans.into_iter().partition(|(height, _)| *height <= horizon_height);
 
-        {
-            let read_txn = self.read_transaction()?;
+            let num_over_limit = retained.len().saturating_sub(orphan_storage_capacity);
+            if num_over_limit > 0 {
+                debug!(
+                    target: LOG_TARGET,
+                    "Orphan block storage limit of {} reached, evicting {} entries.",
+                    orphan_storage_capacity,
+                    num_over_limit,
+                );
+                // Orphans that do not connect to a known chain have no accumulated difficulty and are evicted first
+                let mut ranked = retained
+                    .into_iter()
+                    .map(|(height, hash)| {
+                        let accum: Option<BlockHeaderAccumulatedData> =
+                            lmdb_get(&txn, &self.orphan_header_accumulated_data_db, hash.as_slice())?;
+                        let difficulty = accum.map(|a| a.total_accumulated_difficulty).unwrap_or(0);
+                        Ok((difficulty, height, hash))
+                    })
+                    .collect::<Result<Vec<_>, ChainStorageError>>()?;
+                ranked.sort_by_key(|(difficulty, height, _)| (*difficulty, *height));
+                to_evict.extend(
+                    ranked
+                        .into_iter()
+                        .take(num_over_limit)
+                        .map(|(_, height, hash)| (height, hash)),
+                );
+            }
+            to_evict
+        };
 
-            orphans = lmdb_filter_map_values(&read_txn, &self.orphans_db, |block: Block| {
-                Some((block.header.height, block.hash()))
-            })?;
+        if to_evict.is_empty() {
+            return Ok(());
         }
-
-        orphans.sort_by(|a, b| a.0.cmp(&b.0));
         let mut txn = DbTransaction::new();
-        for (removed_count, (height, block_hash)) in orphans.into_iter().enumerate() {
-            if height > horizon_height && removed_count >= num_over_limit {
-                break;
-            }
+        for (height, block_hash) in to_evict {
             debug!(
                 target: LOG_TARGET,
                 "Discarding orphan block #{} ({}).",
                 height,
                 block_hash.to_hex()
             );
-            txn.delete_orphan(block_hash.clone());
+            txn.delete_orphan(block_hash);
         }
         self.write(txn)?;
 
@@ -2273,6 +2707,32 @@ impl BlockchainBackend for LMDBDatabase {
         Ok(Some(fetch_horizon_data(&txn, &self.metadata_db)?))
     }
 
+    fn fetch_horizon_sync_checkpoint(&self) -> Result<Option<HorizonSyncCheckpoint>, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        fetch_horizon_sync_checkpoint(&txn, &self.metadata_db)
+    }
+
+    fn check_integrity(&self) -> Result<DbIntegrityReport, ChainStorageError> {
+        let txn = self.read_transaction()?;
+        let metadata = fetch_metadata(&txn, &self.metadata_db)?;
+        let mut report = DbIntegrityReport {
+            tip_height: metadata.height_of_longest_chain(),
+            pruned_height: metadata.pruned_height(),
+            ..Default::default()
+        };
+        let timer = Instant::now();
+        self.check_block_integrity(&txn, &mut report)?;
+        self.check_index_integrity(&txn, &mut report)?;
+        debug!(
+            target: LOG_TARGET,
+            "Integrity check of {} block(s) found {} issue(s) in {:.2?}",
+            report.num_blocks_checked,
+            report.issues.len(),
+            timer.elapsed()
+        );
+        Ok(report)
+    }
+
     fn get_stats(&self) -> Result<DbBasicStats, ChainStorageError> {
         let global = self.env.stat()?;
         let env_info = self.env.info()?;
@@ -2390,6 +2850,40 @@ fn fetch_horizon_data(txn: &ConstTransaction<'_>, db: &Database) -> Result<Horiz
         }),
     }
 }
+
+/// Counts the entries in the table for which `f` returns true
+fn count_entries_where<V, F>(txn: &ConstTransaction<'_>, db: &Database, f: F) -> Result<usize, ChainStorageError>
+where
+    V: DeserializeOwned,
+    F: Fn(V) -> Result<bool, ChainStorageError>,
+{
+    let results = lmdb_filter_map_values(txn, db, |val: V| match f(val) {
+        Ok(true) => Some(Ok(())),
+        Ok(false) => None,
+        Err(err) => Some(Err(err)),
+    })?;
+    results
+        .into_iter()
+        .try_fold(0, |count, result| result.map(|_| count + 1))
+}
+
+/// Fetches the horizon sync checkpoint from the provided metadata db.
+fn fetch_horizon_sync_checkpoint(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+) -> Result<Option<HorizonSyncCheckpoint>, ChainStorageError> {
+    let k = MetadataKey::HorizonSyncCheckpoint;
+    let val: Option<MetadataValue> = lmdb_get(txn, db, &k.as_u32())?;
+    match val {
+        Some(MetadataValue::HorizonSyncCheckpoint(checkpoint)) => Ok(Some(checkpoint)),
+        None => Ok(None),
+        Some(k) => Err(ChainStorageError::DataInconsistencyDetected {
+            function: "fetch_horizon_sync_checkpoint",
+            details: format!("Received incorrect value {:?} for key horizon sync checkpoint", k),
+        }),
+    }
+}
+
 // Fetches the best block hash from the provided metadata db.
 fn fetch_best_block(txn: &ConstTransaction<'_>, db: &Database) -> Result<BlockHash, ChainStorageError> {
     let k = MetadataKey::BestBlock;
@@ -2459,6 +2953,7 @@ enum MetadataKey {
     PrunedHeight,
     HorizonData,
     DeletedBitmap,
+    HorizonSyncCheckpoint,
 }
 
 impl MetadataKey {
@@ -2478,6 +2973,7 @@ impl fmt::Display for MetadataKey {
             MetadataKey::BestBlock => f.write_str("Chain tip block hash"),
             MetadataKey::HorizonData => f.write_str("Database info"),
             MetadataKey::DeletedBitmap => f.write_str("Deleted bitmap"),
+            MetadataKey::HorizonSyncCheckpoint => f.write_str("Horizon sync checkpoint"),
         }
     }
 }
@@ -2492,6 +2988,7 @@ enum MetadataValue {
     PrunedHeight(u64),
     HorizonData(HorizonData),
     DeletedBitmap(DeletedBitmap),
+    HorizonSyncCheckpoint(HorizonSyncCheckpoint),
 }
 
 impl fmt::Display for MetadataValue {
@@ -2506,6 +3003,12 @@ impl fmt::Display for MetadataValue {
             MetadataValue::DeletedBitmap(deleted) => {
                 write!(f, "Deleted Bitmap ({} indexes)", deleted.bitmap().cardinality())
             },
+            MetadataValue::HorizonSyncCheckpoint(checkpoint) => write!(
+                f,
+                "Horizon sync checkpoint for #{} at height {}",
+                checkpoint.horizon_height(),
+                checkpoint.last_validated_height()
+            ),
         }
     }
 }
@@ -2659,3 +3162,91 @@ impl CompositeKey {
 type InputKey = CompositeKey;
 type KernelKey = CompositeKey;
 type OutputKey = CompositeKey;
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::chain_storage::lmdb_db::migrations::{latest_schema_version, MigrationStep};
+
+    static STEP_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    fn count_step(_db: &LMDBDatabase, _txn: &WriteTransaction<'_>) -> Result<(), ChainStorageError> {
+        STEP_RUNS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: 2,
+            name: "two",
+            steps: &[],
+        },
+        Migration {
+            version: 3,
+            name: "three",
+            steps: &[MigrationStep {
+                description: "count",
+                run: count_step,
+            }],
+        },
+    ];
+
+    #[test]
+    fn it_applies_pending_migrations() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = create_lmdb_database(temp_dir.path(), LMDBConfig::default()).unwrap();
+        // A new database is stamped with the latest version without running any steps
+        assert_eq!(db.schema_version().unwrap(), latest_schema_version());
+
+        let txn = db.write_transaction().unwrap();
+        lmdb_insert(&txn, &db.headers_db, &0u64, &0u64, "headers_db").unwrap();
+        txn.commit().unwrap();
+
+        let mut progress = Vec::new();
+        let report = db
+            .apply_migrations(TEST_MIGRATIONS, true, |p| progress.push(p.clone()))
+            .unwrap();
+        assert_eq!(report.applied, vec![(3, "three")]);
+        assert_eq!(report.to_version, 3);
+        assert_eq!(progress.len(), 1);
+        assert_eq!(STEP_RUNS.load(Ordering::SeqCst), 1);
+        assert_eq!(db.schema_version().unwrap(), 2);
+
+        let report = db.apply_migrations(TEST_MIGRATIONS, false, |_| {}).unwrap();
+        assert_eq!(report.applied, vec![(3, "three")]);
+        assert_eq!(STEP_RUNS.load(Ordering::SeqCst), 2);
+        assert_eq!(db.schema_version().unwrap(), 3);
+
+        let report = db.apply_migrations(TEST_MIGRATIONS, false, |_| {}).unwrap();
+        assert!(report.applied.is_empty());
+        assert_eq!(STEP_RUNS.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn it_compacts_the_database() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        {
+            let db = create_lmdb_database(temp_dir.path(), LMDBConfig::default()).unwrap();
+            let txn = db.write_transaction().unwrap();
+            for height in 0..1000u64 {
+                lmdb_insert(&txn, &db.headers_db, &height, &vec![0u8; 1024], "headers_db").unwrap();
+            }
+            txn.commit().unwrap();
+            let txn = db.write_transaction().unwrap();
+            lmdb_clear(&txn, &db.headers_db).unwrap();
+            txn.commit().unwrap();
+            assert!(db.get_stats().unwrap().num_free_pages() > 0);
+            // The database cannot be compacted while it is open
+            assert!(compact_lmdb_database(temp_dir.path(), LMDBConfig::default()).is_err());
+        }
+
+        let report = compact_lmdb_database(temp_dir.path(), LMDBConfig::default()).unwrap();
+        assert!(report.size_after < report.size_before);
+        assert!(!temp_dir.path().join("compact").exists());
+
+        let db = create_lmdb_database(temp_dir.path(), LMDBConfig::default()).unwrap();
+        assert_eq!(db.schema_version().unwrap(), latest_schema_version());
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt;
+
+use lmdb_zero::WriteTransaction;
+use serde::{Deserialize, Serialize};
+
+use crate::chain_storage::{ChainStorageError, LMDBDatabase};
+
+/// The migrations that bring the LMDB chain database layout up to date, in the order they are applied. Each release
+/// that changes the layout appends a migration with the next version number. Migrations must never be reordered or
+/// removed once released.
+pub(super) const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "Add schema version table",
+        // Databases created before migrations were introduced already have this layout
+        steps: &[],
+    },
+    Migration {
+        version: 2,
+        name: "Add orphan height index",
+        steps: &[MigrationStep {
+            description: "Index the stored orphan blocks by height",
+            run: LMDBDatabase::index_orphans_by_height,
+        }],
+    },
+];
+
+/// Returns the schema version of a database that has had all known migrations applied
+pub fn latest_schema_version() -> u64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// A versioned change to the chain database layout, made up of ordered steps that are applied in a single write
+/// transaction.
+pub struct Migration {
+    pub version: u64,
+    pub name: &'static str,
+    pub steps: &'static [MigrationStep],
+}
+
+pub struct MigrationStep {
+    pub description: &'static str,
+    pub run: fn(&LMDBDatabase, &WriteTransaction<'_>) -> Result<(), ChainStorageError>,
+}
+
+/// Reported before each migration step is run
+#[derive(Debug, Clone)]
+pub struct MigrationProgress {
+    pub version: u64,
+    pub name: &'static str,
+    pub step: usize,
+    pub num_steps: usize,
+    pub description: &'static str,
+}
+
+impl fmt::Display for MigrationProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Migration v{} ({}): step {}/{} {}",
+            self.version, self.name, self.step, self.num_steps, self.description
+        )
+    }
+}
+
+/// The outcome of running the pending migrations
+#[derive(Debug, Clone)]
+pub struct MigrationReport {
+    /// The schema version before the migrations were run
+    pub from_version: u64,
+    /// The schema version after the migrations were run. For a dry run this is the version the database would have.
+    pub to_version: u64,
+    /// The version and name of each migration that was (or, for a dry run, would be) applied
+    pub applied: Vec<(u64, &'static str)>,
+    pub dry_run: bool,
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.applied.is_empty() {
+            return write!(f, "Database schema is up to date at v{}", self.from_version);
+        }
+        writeln!(
+            f,
+            "Database schema {} from v{} to v{}:",
+            if self.dry_run { "can be migrated" } else { "migrated" },
+            self.from_version,
+            self.to_version
+        )?;
+        for (version, name) in &self.applied {
+            writeln!(f, "  v{}: {}", version, name)?;
+        }
+        Ok(())
+    }
+}
+
+/// A row in the schema version table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct MigrationRecord {
+    pub version: u64,
+    pub name: String,
+    pub applied_at: u64,
+}
+
+/// Checks that the migration versions are strictly increasing and returns the ones newer than `version`
+pub(super) fn pending_migrations(
+    migrations: &'static [Migration],
+    version: u64,
+) -> Result<Vec<&'static Migration>, ChainStorageError> {
+    if migrations.windows(2).any(|w| w[0].version >= w[1].version) {
+        return Err(ChainStorageError::CriticalError(
+            "Chain database migrations are not in strictly increasing version order".to_string(),
+        ));
+    }
+    let latest = migrations.last().map(|m| m.version).unwrap_or(0);
+    if version > latest {
+        return Err(ChainStorageError::CriticalError(format!(
+            "The chain database schema version (v{}) is newer than this release supports (v{}). Please upgrade.",
+            version, latest
+        )));
+    }
+    Ok(migrations.iter().filter(|m| m.version > version).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const UNORDERED: &[Migration] = &[
+        Migration {
+            version: 2,
+            name: "b",
+            steps: &[],
+        },
+        Migration {
+            version: 1,
+            name: "a",
+            steps: &[],
+        },
+    ];
+
+    const ORDERED: &[Migration] = &[
+        Migration {
+            version: 1,
+            name: "a",
+            steps: &[],
+        },
+        Migration {
+            version: 3,
+            name: "b",
+            steps: &[],
+        },
+    ];
+
+    #[test]
+    fn it_returns_the_migrations_newer_than_the_version() {
+        let pending = pending_migrations(ORDERED, 0).unwrap();
+        assert_eq!(pending.iter().map(|m| m.version).collect::<Vec<_>>(), vec![1, 3]);
+        let pending = pending_migrations(ORDERED, 1).unwrap();
+        assert_eq!(pending.iter().map(|m| m.version).collect::<Vec<_>>(), vec![3]);
+        assert!(pending_migrations(ORDERED, 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_rejects_unordered_migrations() {
+        assert!(pending_migrations(UNORDERED, 0).is_err());
+    }
+
+    #[test]
+    fn it_rejects_a_database_from_a_newer_release() {
+        assert!(pending_migrations(ORDERED, 4).is_err());
+    }
+
+    #[test]
+    fn it_has_ordered_migrations() {
+        pending_migrations(MIGRATIONS, 0).unwrap();
+    }
+}
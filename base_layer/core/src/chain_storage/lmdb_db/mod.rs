@@ -20,7 +20,14 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-pub use lmdb_db::{create_lmdb_database, create_recovery_lmdb_database, LMDBDatabase};
+pub use lmdb_db::{
+    compact_lmdb_database,
+    create_lmdb_database,
+    create_recovery_lmdb_database,
+    dry_run_lmdb_migrations,
+    LMDBDatabase,
+};
+pub use migrations::{latest_schema_version, MigrationProgress, MigrationReport};
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::HashOutput;
 
@@ -31,6 +38,7 @@ pub(crate) mod key_prefix_cursor;
 mod lmdb;
 #[allow(clippy::module_inception)]
 mod lmdb_db;
+mod migrations;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub(crate) struct TransactionOutputRowData {
@@ -60,9 +60,15 @@ pub use mmr_tree::MmrTree;
 mod error;
 pub use error::{ChainStorageError, Optional, OrNotFound};
 
+mod db_integrity;
+pub use db_integrity::{DbIntegrityIssue, DbIntegrityReport};
+
 mod horizon_data;
 pub use horizon_data::HorizonData;
 
+mod horizon_sync_checkpoint;
+pub use horizon_sync_checkpoint::HorizonSyncCheckpoint;
+
 mod pruned_output;
 pub use pruned_output::PrunedOutput;
 
@@ -70,10 +76,22 @@ mod reorg;
 pub use reorg::Reorg;
 
 mod lmdb_db;
-pub use lmdb_db::{create_lmdb_database, create_recovery_lmdb_database, LMDBDatabase};
+pub use lmdb_db::{
+    compact_lmdb_database,
+    create_lmdb_database,
+    create_recovery_lmdb_database,
+    dry_run_lmdb_migrations,
+    latest_schema_version,
+    LMDBDatabase,
+    MigrationProgress,
+    MigrationReport,
+};
 
 mod stats;
-pub use stats::{DbBasicStats, DbSize, DbStat, DbTotalSizeStats};
+pub use stats::{DbBasicStats, DbCompactionReport, DbSize, DbStat, DbTotalSizeStats};
+
+mod kernel_location;
+pub use kernel_location::KernelLocation;
 
 mod target_difficulties;
 mod utxo_mined_info;
@@ -27,6 +27,9 @@ use std::{
 
 use lmdb_zero as lmdb;
 
+/// LMDB reserves the first two pages of the data file for the meta pages
+const NUM_META_PAGES: usize = 2;
+
 #[derive(Debug, Clone)]
 pub struct DbBasicStats {
     root: DbStat,
@@ -58,6 +61,31 @@ impl DbBasicStats {
     pub fn db_stats(&self) -> &[DbStat] {
         &self.db_stats
     }
+
+    /// Returns the number of pages that have been allocated in the data file, including free pages
+    pub fn num_allocated_pages(&self) -> usize {
+        self.env_info.last_pgno + 1
+    }
+
+    /// Returns the number of pages in use by the databases and the meta pages
+    pub fn num_used_pages(&self) -> usize {
+        NUM_META_PAGES + self.root.num_pages() + self.db_stats.iter().map(DbStat::num_pages).sum::<usize>()
+    }
+
+    /// Returns an estimate of the number of pages on the freelist. These pages are reused by later writes but are
+    /// only returned to the file system by compacting the database.
+    pub fn num_free_pages(&self) -> usize {
+        self.num_allocated_pages().saturating_sub(self.num_used_pages())
+    }
+
+    /// Returns the proportion of the memory map that has been allocated, between 0 and 1. LMDB fails to write once
+    /// the map is full.
+    pub fn map_size_utilization(&self) -> f64 {
+        if self.env_info.mapsize == 0 {
+            return 0.0;
+        }
+        (self.num_allocated_pages() * self.root.psize as usize) as f64 / self.env_info.mapsize as f64
+    }
 }
 
 impl Display for DbBasicStats {
@@ -66,7 +94,14 @@ impl Display for DbBasicStats {
         for stat in &self.db_stats {
             writeln!(f, "{}", stat)?;
         }
-        Ok(())
+        writeln!(
+            f,
+            "Map: {:.2}% allocated, pages: {} allocated, {} used, ~{} free",
+            self.map_size_utilization() * 100.0,
+            self.num_allocated_pages(),
+            self.num_used_pages(),
+            self.num_free_pages()
+        )
     }
 }
 
@@ -90,9 +125,14 @@ pub struct DbStat {
 }
 
 impl DbStat {
+    /// Returns the number of pages used by the database
+    pub fn num_pages(&self) -> usize {
+        self.leaf_pages + self.branch_pages + self.overflow_pages
+    }
+
     /// Returns the total size in bytes of all pages
     pub fn total_page_size(&self) -> usize {
-        self.psize as usize * (self.leaf_pages + self.branch_pages + self.overflow_pages)
+        self.psize as usize * self.num_pages()
     }
 }
 
@@ -213,3 +253,31 @@ impl Display for EnvInfo {
         )
     }
 }
+
+/// The result of compacting a database
+#[derive(Debug, Clone, Copy)]
+pub struct DbCompactionReport {
+    /// Size of the data file in bytes before compaction
+    pub size_before: u64,
+    /// Size of the data file in bytes after compaction
+    pub size_after: u64,
+}
+
+impl DbCompactionReport {
+    /// Returns the number of bytes returned to the file system
+    pub fn reclaimed(&self) -> u64 {
+        self.size_before.saturating_sub(self.size_after)
+    }
+}
+
+impl Display for DbCompactionReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Compacted database from {:.2} MiB to {:.2} MiB ({:.2} MiB reclaimed)",
+            self.size_before as f64 / 1024.0 / 1024.0,
+            self.size_after as f64 / 1024.0 / 1024.0,
+            self.reclaimed() as f64 / 1024.0 / 1024.0,
+        )
+    }
+}
@@ -59,18 +59,18 @@ impl TargetDifficulties {
     }
 
     pub fn get(&self, algo: PowAlgorithm) -> &TargetDifficultyWindow {
-        use PowAlgorithm::{Monero, Sha3};
+        use PowAlgorithm::{Monero, Sha3, Sha3x};
         match algo {
             Monero => &self.monero,
-            Sha3 => &self.sha3,
+            Sha3 | Sha3x => &self.sha3,
         }
     }
 
     fn get_mut(&mut self, algo: PowAlgorithm) -> &mut TargetDifficultyWindow {
-        use PowAlgorithm::{Monero, Sha3};
+        use PowAlgorithm::{Monero, Sha3, Sha3x};
         match algo {
             Monero => &mut self.monero,
-            Sha3 => &mut self.sha3,
+            Sha3 | Sha3x => &mut self.sha3,
         }
     }
 }
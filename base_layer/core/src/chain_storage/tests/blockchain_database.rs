@@ -658,6 +658,35 @@ mod fetch_header_containing_kernel_mmr {
     }
 }
 
+mod fetch_kernel_location_by_excess_sig {
+    use super::*;
+
+    #[test]
+    fn it_returns_the_block_containing_the_kernel() {
+        let db = setup();
+        let (blocks, outputs) = add_many_chained_blocks(1, &db);
+        let (txns, _) = schema_to_transaction(&[txn_schema!(from: vec![outputs[0].clone()], to: vec![50 * T])]);
+        let excess_sig = txns[0].body.kernels()[0].excess_sig.clone();
+        assert!(db
+            .fetch_kernel_location_by_excess_sig(excess_sig.clone())
+            .unwrap()
+            .is_none());
+
+        let (block, _) = create_next_block(&db, &blocks[0], txns);
+        db.add_block(block.clone()).unwrap().assert_added();
+        let _block_and_outputs = add_many_chained_blocks(2, &db);
+
+        let location = db
+            .fetch_kernel_location_by_excess_sig(excess_sig.clone())
+            .unwrap()
+            .unwrap();
+        assert_eq!(location.block_hash, block.hash());
+        assert_eq!(location.block_height, 2);
+        let (kernel, _) = db.fetch_kernel_by_excess_sig(excess_sig).unwrap().unwrap();
+        assert_eq!(location.kernel_hash, kernel.hash());
+    }
+}
+
 mod clear_all_pending_headers {
     use super::*;
 
@@ -850,3 +879,59 @@ mod fetch_utxo_by_unique_id {
         assert_utxo_found(asset_utxo2, Some(11));
     }
 }
+
+mod fetch_horizon_sync_checkpoint {
+    use super::*;
+    use crate::chain_storage::{DbTransaction, HorizonSyncCheckpoint};
+
+    #[test]
+    fn it_returns_none_if_not_set() {
+        let db = setup();
+        assert!(db.fetch_horizon_sync_checkpoint().unwrap().is_none());
+    }
+
+    #[test]
+    fn it_sets_and_clears_the_checkpoint() {
+        let db = setup();
+        let genesis = db.fetch_chain_header(0).unwrap();
+        let mut checkpoint = HorizonSyncCheckpoint::new(0, genesis.hash().clone(), 1, 2);
+        let kernel_sum = db.fetch_kernels_in_block(genesis.hash().clone()).unwrap()[0]
+            .excess
+            .clone();
+        let (utxos, _) = db.fetch_utxos_in_block(genesis.hash().clone(), None).unwrap();
+        let utxo_sum = utxos[0].as_transaction_output().unwrap().commitment.clone();
+        checkpoint
+            .set_kernels_validated(3, 1)
+            .set_verified(1000, (utxo_sum, kernel_sum), vec![3, 7]);
+
+        let mut txn = DbTransaction::new();
+        txn.set_horizon_sync_checkpoint(checkpoint.clone());
+        db.write(txn).unwrap();
+        assert_eq!(db.fetch_horizon_sync_checkpoint().unwrap(), Some(checkpoint));
+
+        let mut txn = DbTransaction::new();
+        txn.clear_horizon_sync_checkpoint();
+        db.write(txn).unwrap();
+        assert!(db.fetch_horizon_sync_checkpoint().unwrap().is_none());
+
+        // Clearing a checkpoint that does not exist is not an error
+        let mut txn = DbTransaction::new();
+        txn.clear_horizon_sync_checkpoint();
+        db.write(txn).unwrap();
+    }
+}
+
+mod check_integrity {
+    use super::*;
+
+    #[test]
+    fn it_reports_a_consistent_chain() {
+        let db = setup();
+        let _block_and_outputs = add_many_chained_blocks(3, &db);
+        let report = db.check_integrity().unwrap();
+        assert_eq!(report.tip_height, 3);
+        assert_eq!(report.num_blocks_checked, 4);
+        assert!(report.is_consistent(), "{}", report);
+        assert_eq!(report.last_consistent_height(), None);
+    }
+}
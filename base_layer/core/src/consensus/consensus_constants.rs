@@ -31,8 +31,9 @@ use tari_script::script;
 use tari_utilities::epoch_time::EpochTime;
 
 use crate::{
-    consensus::{network::NetworkConsensus, ConsensusEncodingSized},
-    proof_of_work::{Difficulty, PowAlgorithm},
+    consensus::{network::NetworkConsensus, ConsensusEncodingSized, ConsensusManagerError},
+    covenants::CovenantLimits,
+    proof_of_work::{Difficulty, DifficultyAdjustmentAlgorithm, PowAlgorithm},
     transactions::{
         tari_amount::{uT, MicroTari, T},
         transaction_components::{
@@ -60,6 +61,8 @@ pub struct ConsensusConstants {
     /// The Future Time Limit (FTL) of the blockchain in seconds. This is the max allowable timestamp that is excepted.
     /// We use T*N/20 where T = desired chain target time, and N = block_window
     future_time_limit: u64,
+    /// The target time between blocks of the chain in seconds, irrespective of the proof of work algorithm
+    target_block_interval: u64,
     /// When doing difficulty adjustments and FTL calculations this is the amount of blocks we look at
     /// <https://github.com/zawy12/difficulty-algorithms/issues/14>
     difficulty_block_window: u64,
@@ -75,15 +78,22 @@ pub struct ConsensusConstants {
     pub(in crate::consensus) emission_tail: MicroTari,
     /// This is the maximum age a monero merge mined seed can be reused
     max_randomx_seed_height: u64,
-    /// This keeps track of the block split targets and which algo is accepted
-    /// Ideally this should count up to 100. If this does not you will reduce your target time.
+    /// This keeps track of the block split targets and which algo is accepted. The block shares of all algorithms must
+    /// add up to 100.
     proof_of_work: HashMap<PowAlgorithm, PowAlgorithmConstants>,
+    /// The height from which Sha3x replaces Sha3 as the permitted sha3 proof of work, or None if Sha3x is not active
+    /// on this network. Sha3x uses the Sha3 proof of work constants.
+    sha3x_activation_height: Option<u64>,
     /// This is to keep track of the value inside of the genesis block
     faucet_value: MicroTari,
     /// Transaction Weight params
     transaction_weight: TransactionWeight,
     /// Maximum byte size of TariScript
     max_script_byte_size: usize,
+    /// Maximum number of covenant tokens that may be consumed while executing a single covenant
+    max_covenant_operations: usize,
+    /// Maximum nesting depth of covenant filters
+    max_covenant_depth: usize,
     /// Range of valid transaction input versions
     pub(crate) input_version_range: RangeInclusive<TransactionInputVersion>,
     /// Range of valid transaction output (and features) versions
@@ -122,9 +132,12 @@ pub struct PowAlgorithmConstants {
     pub max_target_time: u64,
     pub min_difficulty: Difficulty,
     pub max_difficulty: Difficulty,
-    /// target time is calculated as desired chain target time / block %.
+    /// The percentage of blocks that should be mined using this algorithm. The target time of the difficulty
+    /// adjustment is calculated as chain target block interval / block %.
     /// example 120/0.5 = 240 for a 50% of the blocks, chain target time of 120.
-    pub target_time: u64,
+    pub block_share: u8,
+    /// The difficulty adjustment algorithm used to calculate the target difficulty for this proof of work algorithm
+    pub difficulty_adjustment: DifficultyAdjustmentAlgorithm,
 }
 
 // The target time used by the difficulty adjustment algorithms, their target time is the target block interval * PoW
@@ -200,24 +213,67 @@ impl ConsensusConstants {
         self.proof_of_work.len() as u64
     }
 
+    /// The target time between blocks of the chain in seconds
+    pub fn get_target_block_interval(&self) -> u64 {
+        self.target_block_interval
+    }
+
+    /// The percentage of blocks that should be mined using the PoW algorithm
+    pub fn pow_block_share(&self, pow_algo: PowAlgorithm) -> u8 {
+        match self.proof_of_work.get(&pow_algo.base_algorithm()) {
+            Some(v) => v.block_share,
+            _ => 0,
+        }
+    }
+
     /// The target time used by the difficulty adjustment algorithms, their target time is the target block interval /
     /// algo block percentage
     pub fn get_diff_target_block_interval(&self, pow_algo: PowAlgorithm) -> u64 {
-        match self.proof_of_work.get(&pow_algo) {
-            Some(v) => v.target_time,
+        match self.proof_of_work.get(&pow_algo.base_algorithm()) {
+            Some(v) if v.block_share > 0 => self.target_block_interval * 100 / u64::from(v.block_share),
             _ => 0,
         }
     }
 
+    /// Checks that the block share of each PoW algorithm is between 1 and 100 percent and that the shares of all
+    /// algorithms add up to 100 percent
+    pub fn validate_pow_block_shares(&self) -> Result<(), ConsensusManagerError> {
+        if self.target_block_interval == 0 {
+            return Err(ConsensusManagerError::InvalidTargetBlockInterval);
+        }
+        let mut total = 0u64;
+        for (pow_algo, constants) in &self.proof_of_work {
+            if constants.block_share == 0 || constants.block_share > 100 {
+                return Err(ConsensusManagerError::InvalidPowBlockShare {
+                    pow_algo: *pow_algo,
+                    block_share: constants.block_share,
+                });
+            }
+            total += u64::from(constants.block_share);
+        }
+        if total != 100 {
+            return Err(ConsensusManagerError::PowBlockSharesNotTotal100 { total });
+        }
+        Ok(())
+    }
+
     /// The maximum time a block is considered to take. Used by the difficulty adjustment algorithms
     /// Multiplied by the PoW algorithm block percentage.
     pub fn get_difficulty_max_block_interval(&self, pow_algo: PowAlgorithm) -> u64 {
-        match self.proof_of_work.get(&pow_algo) {
+        match self.proof_of_work.get(&pow_algo.base_algorithm()) {
             Some(v) => v.max_target_time,
             _ => 0,
         }
     }
 
+    /// The difficulty adjustment algorithm used to calculate the target difficulty for the PoW algorithm
+    pub fn get_difficulty_adjustment_algorithm(&self, pow_algo: PowAlgorithm) -> DifficultyAdjustmentAlgorithm {
+        match self.proof_of_work.get(&pow_algo.base_algorithm()) {
+            Some(v) => v.difficulty_adjustment,
+            _ => DifficultyAdjustmentAlgorithm::Lwma,
+        }
+    }
+
     /// This is how many blocks we use to count towards the median timestamp to ensure the block chain moves forward.
     pub fn get_median_timestamp_count(&self) -> usize {
         self.median_timestamp_count
@@ -228,9 +284,17 @@ impl ConsensusConstants {
         self.max_script_byte_size
     }
 
+    /// The execution limits applied to covenants in blocks at this height
+    pub fn covenant_limits(&self) -> CovenantLimits {
+        CovenantLimits {
+            max_operations: self.max_covenant_operations,
+            max_depth: self.max_covenant_depth,
+        }
+    }
+
     /// This is the min initial difficulty that can be requested for the pow
     pub fn min_pow_difficulty(&self, pow_algo: PowAlgorithm) -> Difficulty {
-        match self.proof_of_work.get(&pow_algo) {
+        match self.proof_of_work.get(&pow_algo.base_algorithm()) {
             Some(v) => v.min_difficulty,
             _ => 0.into(),
         }
@@ -242,12 +306,26 @@ impl ConsensusConstants {
     }
 
     pub fn max_pow_difficulty(&self, pow_algo: PowAlgorithm) -> Difficulty {
-        match self.proof_of_work.get(&pow_algo) {
+        match self.proof_of_work.get(&pow_algo.base_algorithm()) {
             Some(v) => v.max_difficulty,
             _ => 0.into(),
         }
     }
 
+    /// The height from which Sha3x replaces Sha3, if Sha3x is active on this network
+    pub fn sha3x_activation_height(&self) -> Option<u64> {
+        self.sha3x_activation_height
+    }
+
+    /// Returns true if blocks at the given height may be mined using `pow_algo`
+    pub fn is_pow_algo_permitted(&self, pow_algo: PowAlgorithm, height: u64) -> bool {
+        match pow_algo {
+            PowAlgorithm::Monero => true,
+            PowAlgorithm::Sha3 => self.sha3x_activation_height.map_or(true, |h| height < h),
+            PowAlgorithm::Sha3x => self.sha3x_activation_height.map_or(false, |h| height >= h),
+        }
+    }
+
     /// The maximum age a monero merge mined seed can be reused
     pub fn max_randomx_seed_height(&self) -> u64 {
         self.max_randomx_seed_height
@@ -279,13 +357,15 @@ impl ConsensusConstants {
             max_target_time: 1800,
             min_difficulty: 1.into(),
             max_difficulty: 1.into(),
-            target_time: 300,
+            block_share: 40,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         });
         algos.insert(PowAlgorithm::Monero, PowAlgorithmConstants {
             max_target_time: 1200,
             min_difficulty: 1.into(),
             max_difficulty: 1.into(),
-            target_time: 200,
+            block_share: 60,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         });
         let (input_version_range, output_version_range, kernel_version_range) = version_zero();
         vec![ConsensusConstants {
@@ -294,6 +374,7 @@ impl ConsensusConstants {
             blockchain_version: 1,
             valid_blockchain_version_range: 0..=3,
             future_time_limit: 540,
+            target_block_interval: 120,
             difficulty_block_window,
             max_block_transaction_weight: 19500,
             median_timestamp_count: 11,
@@ -302,9 +383,12 @@ impl ConsensusConstants {
             emission_tail: 100.into(),
             max_randomx_seed_height: u64::MAX,
             proof_of_work: algos,
+            sha3x_activation_height: None,
             faucet_value: (5000 * 4000) * T,
             transaction_weight: TransactionWeight::latest(),
             max_script_byte_size: 2048,
+            max_covenant_operations: 128,
+            max_covenant_depth: 16,
             input_version_range,
             output_version_range,
             kernel_version_range,
@@ -318,13 +402,15 @@ impl ConsensusConstants {
             max_target_time: 1800,
             min_difficulty: 60_000_000.into(),
             max_difficulty: u64::MAX.into(),
-            target_time: 300,
+            block_share: 40,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         });
         algos.insert(PowAlgorithm::Monero, PowAlgorithmConstants {
             max_target_time: 1200,
             min_difficulty: 60_000.into(),
             max_difficulty: u64::MAX.into(),
-            target_time: 200,
+            block_share: 60,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         });
         let (input_version_range, output_version_range, kernel_version_range) = version_zero();
         vec![ConsensusConstants {
@@ -333,6 +419,7 @@ impl ConsensusConstants {
             blockchain_version: 1,
             valid_blockchain_version_range: 0..=3,
             future_time_limit: 540,
+            target_block_interval: 120,
             difficulty_block_window: 90,
             max_block_transaction_weight: 19500,
             median_timestamp_count: 11,
@@ -341,9 +428,12 @@ impl ConsensusConstants {
             emission_tail: 100.into(),
             max_randomx_seed_height: std::u64::MAX,
             proof_of_work: algos,
+            sha3x_activation_height: None,
             faucet_value: (5000 * 4000) * T,
             transaction_weight: TransactionWeight::v1(),
             max_script_byte_size: 2048,
+            max_covenant_operations: 128,
+            max_covenant_depth: 16,
             input_version_range,
             output_version_range,
             kernel_version_range,
@@ -357,13 +447,15 @@ impl ConsensusConstants {
             max_target_time: 1800,
             min_difficulty: 60_000_000.into(),
             max_difficulty: u64::MAX.into(),
-            target_time: 300,
+            block_share: 40,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         });
         algos.insert(PowAlgorithm::Monero, PowAlgorithmConstants {
             max_target_time: 1200,
             min_difficulty: 60_000.into(),
             max_difficulty: u64::MAX.into(),
-            target_time: 200,
+            block_share: 60,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         });
         let (input_version_range, output_version_range, kernel_version_range) = version_zero();
         vec![ConsensusConstants {
@@ -372,6 +464,7 @@ impl ConsensusConstants {
             blockchain_version: 2,
             valid_blockchain_version_range: 0..=3,
             future_time_limit: 540,
+            target_block_interval: 120,
             difficulty_block_window: 90,
             // 65536 =  target_block_size / bytes_per_gram =  (1024*1024) / 16
             // adj. + 95% = 127,795 - this effectively targets ~2Mb blocks closely matching the previous 19500
@@ -383,9 +476,12 @@ impl ConsensusConstants {
             emission_tail: 100.into(),
             max_randomx_seed_height: u64::MAX,
             proof_of_work: algos,
+            sha3x_activation_height: None,
             faucet_value: (5000 * 4000) * T,
             transaction_weight: TransactionWeight::v2(),
             max_script_byte_size: 2048,
+            max_covenant_operations: 128,
+            max_covenant_depth: 16,
             input_version_range,
             output_version_range,
             kernel_version_range,
@@ -405,13 +501,15 @@ impl ConsensusConstants {
             max_target_time: 1800,
             min_difficulty: 60_000_000.into(),
             max_difficulty: u64::MAX.into(),
-            target_time: 300,
+            block_share: 40,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         });
         algos.insert(PowAlgorithm::Monero, PowAlgorithmConstants {
             max_target_time: 1200,
             min_difficulty: 60_000.into(),
             max_difficulty: u64::MAX.into(),
-            target_time: 200,
+            block_share: 60,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         });
         let (input_version_range, output_version_range, kernel_version_range) = version_zero();
         vec![
@@ -421,6 +519,7 @@ impl ConsensusConstants {
                 blockchain_version: 2,
                 valid_blockchain_version_range: 0..=3,
                 future_time_limit: 540,
+                target_block_interval: 120,
                 difficulty_block_window: 90,
                 // 65536 =  target_block_size / bytes_per_gram =  (1024*1024) / 16
                 // adj. + 95% = 127,795 - this effectively targets ~2Mb blocks closely matching the previous 19500
@@ -432,9 +531,12 @@ impl ConsensusConstants {
                 emission_tail: 800 * T,
                 max_randomx_seed_height: u64::MAX,
                 proof_of_work: algos.clone(),
+                sha3x_activation_height: None,
                 faucet_value: (10 * 4000) * T,
                 transaction_weight: TransactionWeight::v2(),
                 max_script_byte_size: 2048,
+                max_covenant_operations: 128,
+                max_covenant_depth: 16,
                 input_version_range: input_version_range.clone(),
                 output_version_range: output_version_range.clone(),
                 kernel_version_range: kernel_version_range.clone(),
@@ -446,6 +548,7 @@ impl ConsensusConstants {
                 blockchain_version: 3,
                 valid_blockchain_version_range: 0..=3,
                 future_time_limit: 540,
+                target_block_interval: 120,
                 difficulty_block_window: 90,
                 max_block_transaction_weight: 127_795,
                 median_timestamp_count: 11,
@@ -454,9 +557,12 @@ impl ConsensusConstants {
                 emission_tail: 800 * T,
                 max_randomx_seed_height: u64::MAX,
                 proof_of_work: algos,
+                sha3x_activation_height: None,
                 faucet_value: (10 * 4000) * T,
                 transaction_weight: TransactionWeight::v2(),
                 max_script_byte_size: 2048,
+                max_covenant_operations: 128,
+                max_covenant_depth: 16,
                 input_version_range,
                 output_version_range,
                 kernel_version_range,
@@ -472,13 +578,15 @@ impl ConsensusConstants {
             max_target_time: 1800,
             min_difficulty: 40_000.into(),
             max_difficulty: u64::MAX.into(),
-            target_time: 300,
+            block_share: 40,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         });
         algos.insert(PowAlgorithm::Monero, PowAlgorithmConstants {
             max_target_time: 800,
             min_difficulty: 70_000_000.into(),
             max_difficulty: u64::MAX.into(),
-            target_time: 200,
+            block_share: 60,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         });
         let (input_version_range, output_version_range, kernel_version_range) = version_zero();
         vec![ConsensusConstants {
@@ -487,6 +595,7 @@ impl ConsensusConstants {
             blockchain_version: 1,
             valid_blockchain_version_range: 0..=0,
             future_time_limit: 540,
+            target_block_interval: 120,
             difficulty_block_window,
             max_block_transaction_weight: 19500,
             median_timestamp_count: 11,
@@ -495,9 +604,12 @@ impl ConsensusConstants {
             emission_tail: 100.into(),
             max_randomx_seed_height: u64::MAX,
             proof_of_work: algos,
+            sha3x_activation_height: None,
             faucet_value: MicroTari::from(0),
             transaction_weight: TransactionWeight::v2(),
             max_script_byte_size: 2048,
+            max_covenant_operations: 128,
+            max_covenant_depth: 16,
             input_version_range,
             output_version_range,
             kernel_version_range,
@@ -534,6 +646,37 @@ impl ConsensusConstantsBuilder {
         self
     }
 
+    pub fn with_difficulty_block_window(mut self, block_window: u64) -> Self {
+        self.consensus.difficulty_block_window = block_window;
+        self
+    }
+
+    /// Sets the difficulty adjustment algorithm for a proof of work algorithm that has already been added
+    pub fn with_difficulty_adjustment_algorithm(
+        mut self,
+        pow_algo: PowAlgorithm,
+        algorithm: DifficultyAdjustmentAlgorithm,
+    ) -> Self {
+        if let Some(constants) = self.consensus.proof_of_work.get_mut(&pow_algo.base_algorithm()) {
+            constants.difficulty_adjustment = algorithm;
+        }
+        self
+    }
+
+    /// Sets the target time between blocks of the chain
+    pub fn with_target_block_interval(mut self, interval: u64) -> Self {
+        self.consensus.target_block_interval = interval;
+        self
+    }
+
+    /// Sets the percentage of blocks that should be mined using a proof of work algorithm that has already been added
+    pub fn with_pow_block_share(mut self, pow_algo: PowAlgorithm, block_share: u8) -> Self {
+        if let Some(constants) = self.consensus.proof_of_work.get_mut(&pow_algo.base_algorithm()) {
+            constants.block_share = block_share;
+        }
+        self
+    }
+
     pub fn with_coinbase_lockheight(mut self, height: u64) -> Self {
         self.consensus.coinbase_lock_height = height;
         self
@@ -544,6 +687,12 @@ impl ConsensusConstantsBuilder {
         self
     }
 
+    pub fn with_covenant_limits(mut self, limits: CovenantLimits) -> Self {
+        self.consensus.max_covenant_operations = limits.max_operations;
+        self.consensus.max_covenant_depth = limits.max_depth;
+        self
+    }
+
     pub fn with_max_block_transaction_weight(mut self, weight: u64) -> Self {
         self.consensus.max_block_transaction_weight = weight;
         self
@@ -554,6 +703,11 @@ impl ConsensusConstantsBuilder {
         self
     }
 
+    pub fn with_sha3x_activation_height(mut self, height: u64) -> Self {
+        self.consensus.sha3x_activation_height = Some(height);
+        self
+    }
+
     pub fn with_max_randomx_seed_height(mut self, height: u64) -> Self {
         self.consensus.max_randomx_seed_height = height;
         self
@@ -583,11 +737,17 @@ impl ConsensusConstantsBuilder {
 
 #[cfg(test)]
 mod test {
+    use tari_common::configuration::Network;
+
     use crate::{
         consensus::{
             emission::{Emission, EmissionSchedule},
             ConsensusConstants,
+            ConsensusConstantsBuilder,
+            ConsensusManager,
+            ConsensusManagerError,
         },
+        proof_of_work::{DifficultyAdjustmentAlgorithm, PowAlgorithm},
         transactions::tari_amount::uT,
     };
 
@@ -613,4 +773,129 @@ mod test {
         let (_, reward, _) = rewards.next().unwrap();
         assert_eq!(reward, dibbler[0].emission_tail);
     }
+
+    #[test]
+    fn sha3x_replaces_sha3_at_activation_height() {
+        let constants = ConsensusConstantsBuilder::new(Network::LocalNet).build();
+        assert!(constants.is_pow_algo_permitted(PowAlgorithm::Sha3, 100));
+        assert!(!constants.is_pow_algo_permitted(PowAlgorithm::Sha3x, 100));
+
+        let constants = ConsensusConstantsBuilder::new(Network::LocalNet)
+            .with_sha3x_activation_height(10)
+            .build();
+        assert!(constants.is_pow_algo_permitted(PowAlgorithm::Sha3, 9));
+        assert!(!constants.is_pow_algo_permitted(PowAlgorithm::Sha3x, 9));
+        assert!(!constants.is_pow_algo_permitted(PowAlgorithm::Sha3, 10));
+        assert!(constants.is_pow_algo_permitted(PowAlgorithm::Sha3x, 10));
+        assert!(constants.is_pow_algo_permitted(PowAlgorithm::Monero, 10));
+        assert_eq!(
+            constants.min_pow_difficulty(PowAlgorithm::Sha3x),
+            constants.min_pow_difficulty(PowAlgorithm::Sha3)
+        );
+    }
+
+    #[test]
+    fn difficulty_adjustment_algorithm_is_selectable_per_pow_algo() {
+        let constants = ConsensusConstantsBuilder::new(Network::LocalNet).build();
+        assert_eq!(
+            constants.get_difficulty_adjustment_algorithm(PowAlgorithm::Sha3),
+            DifficultyAdjustmentAlgorithm::Lwma
+        );
+
+        let constants = ConsensusConstantsBuilder::new(Network::LocalNet)
+            .with_difficulty_block_window(60)
+            .with_difficulty_adjustment_algorithm(PowAlgorithm::Sha3, DifficultyAdjustmentAlgorithm::Ema)
+            .build();
+        assert_eq!(constants.get_difficulty_block_window(), 60);
+        assert_eq!(
+            constants.get_difficulty_adjustment_algorithm(PowAlgorithm::Sha3),
+            DifficultyAdjustmentAlgorithm::Ema
+        );
+        assert_eq!(
+            constants.get_difficulty_adjustment_algorithm(PowAlgorithm::Sha3x),
+            DifficultyAdjustmentAlgorithm::Ema
+        );
+        assert_eq!(
+            constants.get_difficulty_adjustment_algorithm(PowAlgorithm::Monero),
+            DifficultyAdjustmentAlgorithm::Lwma
+        );
+    }
+
+    #[test]
+    fn network_pow_block_shares_are_valid() {
+        let networks = [
+            ConsensusConstants::localnet(),
+            ConsensusConstants::weatherwax(),
+            ConsensusConstants::igor(),
+            ConsensusConstants::dibbler(),
+            ConsensusConstants::mainnet(),
+        ];
+        for constants in networks.iter().flatten() {
+            constants.validate_pow_block_shares().unwrap();
+            // sha3/monero to 40/60 split of 2 minute blocks
+            assert_eq!(constants.pow_block_share(PowAlgorithm::Sha3), 40);
+            assert_eq!(constants.pow_block_share(PowAlgorithm::Monero), 60);
+            assert_eq!(constants.get_diff_target_block_interval(PowAlgorithm::Sha3), 300);
+            assert_eq!(constants.get_diff_target_block_interval(PowAlgorithm::Sha3x), 300);
+            assert_eq!(constants.get_diff_target_block_interval(PowAlgorithm::Monero), 200);
+        }
+    }
+
+    #[test]
+    fn pow_block_shares_are_configurable() {
+        let constants = ConsensusConstantsBuilder::new(Network::LocalNet)
+            .with_target_block_interval(60)
+            .with_pow_block_share(PowAlgorithm::Sha3, 25)
+            .with_pow_block_share(PowAlgorithm::Monero, 75)
+            .build();
+        constants.validate_pow_block_shares().unwrap();
+        assert_eq!(constants.get_diff_target_block_interval(PowAlgorithm::Sha3), 240);
+        assert_eq!(constants.get_diff_target_block_interval(PowAlgorithm::Monero), 80);
+    }
+
+    #[test]
+    fn invalid_pow_block_shares_are_rejected() {
+        let constants = ConsensusConstantsBuilder::new(Network::LocalNet)
+            .with_pow_block_share(PowAlgorithm::Sha3, 50)
+            .build();
+        assert!(matches!(
+            constants.validate_pow_block_shares(),
+            Err(ConsensusManagerError::PowBlockSharesNotTotal100 { total: 110 })
+        ));
+
+        let constants = ConsensusConstantsBuilder::new(Network::LocalNet)
+            .with_pow_block_share(PowAlgorithm::Sha3, 0)
+            .with_pow_block_share(PowAlgorithm::Monero, 100)
+            .build();
+        assert!(matches!(
+            constants.validate_pow_block_shares(),
+            Err(ConsensusManagerError::InvalidPowBlockShare {
+                pow_algo: PowAlgorithm::Sha3,
+                block_share: 0
+            })
+        ));
+        assert_eq!(constants.get_diff_target_block_interval(PowAlgorithm::Sha3), 0);
+
+        let constants = ConsensusConstantsBuilder::new(Network::LocalNet)
+            .with_target_block_interval(0)
+            .build();
+        assert!(matches!(
+            constants.validate_pow_block_shares(),
+            Err(ConsensusManagerError::InvalidTargetBlockInterval)
+        ));
+    }
+
+    #[test]
+    fn consensus_manager_rejects_invalid_pow_block_shares() {
+        let constants = ConsensusConstantsBuilder::new(Network::LocalNet)
+            .with_pow_block_share(PowAlgorithm::Sha3, 50)
+            .build();
+        let result = ConsensusManager::builder(Network::LocalNet)
+            .add_consensus_constants(constants)
+            .build();
+        assert!(matches!(
+            result,
+            Err(ConsensusManagerError::PowBlockSharesNotTotal100 { total: 110 })
+        ));
+    }
 }
@@ -89,4 +89,87 @@ pub mod test {
         assert_eq!(decoded, subject);
         Ok(())
     }
+
+    mod derive {
+        use std::io::ErrorKind;
+
+        use super::*;
+        use crate::consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized};
+
+        const MAX_ITEMS: usize = 3;
+
+        #[derive(Debug, PartialEq, Eq, ConsensusEncoding, ConsensusEncodingSized, ConsensusDecoding)]
+        #[consensus(validate = Named::validate)]
+        struct Named {
+            reader: u64,
+            #[consensus(max_size = 4)]
+            bytes: Vec<u8>,
+            #[consensus(max_size = MAX_ITEMS)]
+            items: Vec<Option<u32>>,
+        }
+
+        impl Named {
+            fn validate(&self) -> Result<(), String> {
+                if self.reader == 0 {
+                    return Err("reader must not be zero".to_string());
+                }
+                Ok(())
+            }
+        }
+
+        #[derive(Debug, PartialEq, Eq, ConsensusEncoding, ConsensusEncodingSized, ConsensusDecoding)]
+        struct Tuple(u16, #[consensus(max_size = 2)] Vec<u8>, [u8; 2]);
+
+        #[derive(Debug, PartialEq, Eq, ConsensusEncoding, ConsensusEncodingSized, ConsensusDecoding)]
+        struct Unit;
+
+        #[test]
+        fn it_encodes_fields_in_declaration_order() {
+            let subject = Named {
+                reader: 300,
+                bytes: vec![1, 2],
+                items: vec![Some(1), None],
+            };
+            assert_eq!(subject.to_consensus_bytes(), vec![0xac, 0x02, 2, 1, 2, 2, 1, 1, 0]);
+            check_consensus_encoding_correctness(subject).unwrap();
+
+            let subject = Tuple(1, vec![2], [3, 4]);
+            assert_eq!(subject.to_consensus_bytes(), vec![1, 1, 2, 3, 4]);
+            check_consensus_encoding_correctness(subject).unwrap();
+
+            assert!(Unit.to_consensus_bytes().is_empty());
+            check_consensus_encoding_correctness(Unit).unwrap();
+        }
+
+        #[test]
+        fn it_rejects_fields_that_exceed_the_max_size() {
+            let subject = Named {
+                reader: 1,
+                bytes: vec![1; 5],
+                items: vec![],
+            };
+            let err = check_consensus_encoding_correctness(subject).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+            let subject = Named {
+                reader: 1,
+                bytes: vec![],
+                items: vec![None; MAX_ITEMS + 1],
+            };
+            let err = check_consensus_encoding_correctness(subject).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        }
+
+        #[test]
+        fn it_validates_the_decoded_value() {
+            let subject = Named {
+                reader: 0,
+                bytes: vec![],
+                items: vec![],
+            };
+            let err = check_consensus_encoding_correctness(subject).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+            assert_eq!(err.to_string(), "reader must not be zero");
+        }
+    }
 }
@@ -20,12 +20,16 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{convert::TryFrom, io, io::Read};
+use std::{cmp, convert::TryFrom, io, io::Read, mem};
 
 use integer_encoding::VarIntReader;
 
 use crate::consensus::ConsensusDecoding;
 
+/// The maximum number of bytes that are preallocated for the elements of a decoded `MaxSizeVec`. The length prefix is
+/// untrusted, so the vec only grows beyond this as elements are successfully decoded.
+const MAX_PREALLOCATED_BYTES: usize = 64 * 1024;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MaxSizeVec<T, const MAX: usize> {
     inner: Vec<T>,
@@ -64,7 +68,8 @@ impl<T: ConsensusDecoding, const MAX: usize> ConsensusDecoding for MaxSizeVec<T,
                 format!("Vec size ({}) exceeded maximum ({})", len, MAX),
             ));
         }
-        let mut elems = Vec::with_capacity(len);
+        let max_preallocated_elems = MAX_PREALLOCATED_BYTES / cmp::max(mem::size_of::<T>(), 1);
+        let mut elems = Vec::with_capacity(cmp::min(len, max_preallocated_elems));
         for _ in 0..len {
             let elem = T::consensus_decode(reader)?;
             elems.push(elem)
@@ -78,3 +83,31 @@ impl<T: PartialEq, const MAX: usize> PartialEq<Vec<T>> for MaxSizeVec<T, MAX> {
         self.inner.eq(other)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use integer_encoding::VarInt;
+
+    use super::*;
+
+    #[test]
+    fn it_decodes_up_to_the_max_size() {
+        let mut buf = 3usize.encode_var_vec();
+        buf.extend([1u8, 2, 3]);
+        let v = MaxSizeVec::<u8, 3>::consensus_decode(&mut buf.as_slice()).unwrap();
+        assert_eq!(v, vec![1, 2, 3]);
+
+        let mut buf = 4usize.encode_var_vec();
+        buf.extend([1u8, 2, 3, 4]);
+        let err = MaxSizeVec::<u8, 3>::consensus_decode(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn it_does_not_trust_the_length_prefix_for_allocation() {
+        // Preallocating the claimed length would require 1GiB
+        let buf = (1024 * 1024usize).encode_var_vec();
+        let err = MaxSizeVec::<[u8; 1024], { 1024 * 1024 }>::consensus_decode(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
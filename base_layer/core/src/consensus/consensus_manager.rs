@@ -38,7 +38,7 @@ use crate::{
         ConsensusConstants,
         NetworkConsensus,
     },
-    proof_of_work::DifficultyAdjustmentError,
+    proof_of_work::{DifficultyAdjustmentError, PowAlgorithm},
     transactions::{tari_amount::MicroTari, transaction_components::TransactionKernel},
 };
 
@@ -53,6 +53,12 @@ pub enum ConsensusManagerError {
     PoisonedAccess(String),
     #[error("No Difficulty adjustment manager present")]
     MissingDifficultyAdjustmentManager,
+    #[error("The block share of {pow_algo} must be between 1 and 100 percent, but was {block_share}")]
+    InvalidPowBlockShare { pow_algo: PowAlgorithm, block_share: u8 },
+    #[error("The block shares of all proof of work algorithms must add up to 100 percent, but add up to {total}")]
+    PowBlockSharesNotTotal100 { total: u64 },
+    #[error("The target block interval must be greater than zero")]
+    InvalidTargetBlockInterval,
 }
 
 /// Container struct for consensus rules. This can be cheaply cloned.
@@ -119,6 +125,7 @@ impl ConsensusManager {
         let block_window = constants.get_difficulty_block_window();
 
         TargetDifficultyWindow::new(
+            constants.get_difficulty_adjustment_algorithm(pow_algo),
             usize::try_from(block_window).expect("difficulty block window exceeds usize::MAX"),
             constants.get_diff_target_block_interval(pow_algo),
             constants.get_difficulty_max_block_interval(pow_algo),
@@ -201,12 +208,15 @@ impl ConsensusManagerBuilder {
         self
     }
 
-    /// Builds a consensus manager
-    pub fn build(mut self) -> ConsensusManager {
+    /// Builds a consensus manager. Returns an error if any of the consensus constants are invalid.
+    pub fn build(mut self) -> Result<ConsensusManager, ConsensusManagerError> {
         if self.consensus_constants.is_empty() {
             self.consensus_constants = self.network.create_consensus_constants();
         }
         // TODO: Check that constants is not empty
+        for constants in &self.consensus_constants {
+            constants.validate_pow_block_shares()?;
+        }
 
         let emission = EmissionSchedule::new(
             self.consensus_constants[0].emission_initial,
@@ -232,6 +242,6 @@ impl ConsensusManagerBuilder {
                     .build()
             }),
         };
-        ConsensusManager { inner: Arc::new(inner) }
+        Ok(ConsensusManager { inner: Arc::new(inner) })
     }
 }
@@ -41,6 +41,7 @@ pub use consensus_encoding::{
     MaxSizeVec,
     ToConsensusBytes,
 };
+pub use tari_consensus_encoding_derive::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized};
 
 mod network;
 pub use network::NetworkConsensus;
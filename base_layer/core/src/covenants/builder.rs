@@ -0,0 +1,494 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A typed builder for covenants.
+//!
+//! Covenants are token streams where each filter is followed by its arguments, so a stream assembled by hand can easily
+//! be malformed or compare a field to a value of the wrong type and only fail once the output is spent. The
+//! [CovenantBuilder] composes the same filters as typed values instead, and renders them using the syntax of the
+//! `covenant!` macro.
+//!
+//! ```rust,ignore
+//! // Before height 42, this may only be spent into an output with a maturity of 100
+//! let builder = CovenantBuilder::absolute_height(42)
+//!     .or(CovenantBuilder::field_eq(CovenantField::features_maturity(), 100));
+//! assert_eq!(
+//!     builder.to_string(),
+//!     "or(absolute_height(@uint(42)), field_eq(@field::features_maturity, @uint(100)))"
+//! );
+//! let covenant = builder.build()?;
+//! ```
+
+use std::{
+    fmt::{Display, Formatter},
+    marker::PhantomData,
+    ops::Not,
+};
+
+use digest::Digest;
+use tari_common_types::types::{Commitment, PublicKey};
+use tari_script::TariScript;
+use tari_utilities::hex::{to_hex, Hex};
+
+use crate::{
+    covenants::{
+        arguments::{CovenantArg, Hash},
+        covenant::MAX_COVENANT_BYTES,
+        error::CovenantError,
+        fields::{OutputField, OutputFields},
+        token::CovenantToken,
+        Covenant,
+    },
+    transactions::transaction_components::TransactionOutput,
+};
+
+/// A covenant filter expression. Each constructor takes exactly the arguments its filter requires, so any expression
+/// that compiles encodes to a well-formed covenant.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CovenantBuilder {
+    filter: BuilderFilter,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+enum BuilderFilter {
+    Identity,
+    And(Box<CovenantBuilder>, Box<CovenantBuilder>),
+    Or(Box<CovenantBuilder>, Box<CovenantBuilder>),
+    Xor(Box<CovenantBuilder>, Box<CovenantBuilder>),
+    Not(Box<CovenantBuilder>),
+    OutputHashEq(Hash),
+    FieldsPreserved(OutputFields),
+    FieldEq(OutputField, FieldValue),
+    FieldsHashedEq(OutputFields, Hash),
+    AbsoluteHeight(u64),
+}
+
+impl CovenantBuilder {
+    fn new(filter: BuilderFilter) -> Self {
+        Self { filter }
+    }
+
+    /// Matches all outputs
+    pub fn identity() -> Self {
+        Self::new(BuilderFilter::Identity)
+    }
+
+    /// Matches the outputs that match both this and `other`
+    pub fn and(self, other: CovenantBuilder) -> Self {
+        Self::new(BuilderFilter::And(Box::new(self), Box::new(other)))
+    }
+
+    /// Matches the outputs that match either this or `other`
+    pub fn or(self, other: CovenantBuilder) -> Self {
+        Self::new(BuilderFilter::Or(Box::new(self), Box::new(other)))
+    }
+
+    /// Matches the outputs that match exactly one of this and `other`
+    pub fn xor(self, other: CovenantBuilder) -> Self {
+        Self::new(BuilderFilter::Xor(Box::new(self), Box::new(other)))
+    }
+
+    /// Matches the output with the given hash
+    pub fn output_hash_eq(hash: Hash) -> Self {
+        Self::new(BuilderFilter::OutputHashEq(hash))
+    }
+
+    /// Matches the outputs whose fields are equal to those of the input being spent. Duplicate fields are ignored.
+    pub fn fields_preserved<I: IntoIterator<Item = OutputField>>(fields: I) -> Self {
+        Self::new(BuilderFilter::FieldsPreserved(unique_fields(fields)))
+    }
+
+    /// Matches the outputs where `field` is equal to `value`. The type of `value` is determined by the field.
+    pub fn field_eq<T: CovenantFieldValue>(field: CovenantField<T>, value: T) -> Self {
+        Self::new(BuilderFilter::FieldEq(field.field, value.into_field_value()))
+    }
+
+    /// Matches the outputs where the hash of `fields` is equal to `hash`. Duplicate fields are ignored.
+    pub fn fields_hashed_eq<I: IntoIterator<Item = OutputField>>(fields: I, hash: Hash) -> Self {
+        Self::new(BuilderFilter::FieldsHashedEq(unique_fields(fields), hash))
+    }
+
+    /// Matches the outputs where the hash of `fields` is equal to the hash of the same fields of `output`
+    pub fn fields_hashed_eq_to_output<I: IntoIterator<Item = OutputField>>(
+        fields: I,
+        output: &TransactionOutput,
+    ) -> Self {
+        let fields = unique_fields(fields);
+        let mut hash = Hash::default();
+        hash.copy_from_slice(&fields.construct_challenge_from(output).finalize());
+        Self::new(BuilderFilter::FieldsHashedEq(fields, hash))
+    }
+
+    /// Matches no outputs until the block height is at least `height`, and all outputs thereafter
+    pub fn absolute_height(height: u64) -> Self {
+        Self::new(BuilderFilter::AbsoluteHeight(height))
+    }
+
+    /// Encodes the expression as a covenant. This fails if the encoded covenant exceeds the maximum covenant size.
+    pub fn build(self) -> Result<Covenant, CovenantError> {
+        let mut tokens = Vec::new();
+        self.push_tokens(&mut tokens)?;
+        let covenant = tokens.into_iter().collect::<Covenant>();
+        let size = covenant.get_byte_length();
+        if size > MAX_COVENANT_BYTES {
+            return Err(CovenantError::ExceededMaxSize {
+                size,
+                max: MAX_COVENANT_BYTES,
+            });
+        }
+        Ok(covenant)
+    }
+
+    fn push_tokens(self, tokens: &mut Vec<CovenantToken>) -> Result<(), CovenantError> {
+        #[allow(clippy::enum_glob_use)]
+        use BuilderFilter::*;
+        match self.filter {
+            Identity => tokens.push(CovenantToken::identity()),
+            And(left, right) => {
+                tokens.push(CovenantToken::and());
+                left.push_tokens(tokens)?;
+                right.push_tokens(tokens)?;
+            },
+            Or(left, right) => {
+                tokens.push(CovenantToken::or());
+                left.push_tokens(tokens)?;
+                right.push_tokens(tokens)?;
+            },
+            Xor(left, right) => {
+                tokens.push(CovenantToken::xor());
+                left.push_tokens(tokens)?;
+                right.push_tokens(tokens)?;
+            },
+            Not(inner) => {
+                tokens.push(CovenantToken::not());
+                inner.push_tokens(tokens)?;
+            },
+            OutputHashEq(hash) => tokens.extend([CovenantToken::output_hash_eq(), CovenantToken::hash(hash)]),
+            FieldsPreserved(fields) => tokens.extend([
+                CovenantToken::fields_preserved(),
+                CovenantArg::OutputFields(fields).into(),
+            ]),
+            FieldEq(field, value) => {
+                let value = match value {
+                    FieldValue::Arg(arg) => arg,
+                    FieldValue::Covenant(covenant) => CovenantArg::Covenant(covenant.build()?),
+                };
+                tokens.extend([CovenantToken::field_eq(), CovenantToken::field(field), value.into()]);
+            },
+            FieldsHashedEq(fields, hash) => tokens.extend([
+                CovenantToken::fields_hashed_eq(),
+                CovenantArg::OutputFields(fields).into(),
+                CovenantToken::hash(hash),
+            ]),
+            AbsoluteHeight(height) => tokens.extend([CovenantToken::absolute_height(), CovenantToken::uint(height)]),
+        }
+        Ok(())
+    }
+}
+
+impl Not for CovenantBuilder {
+    type Output = CovenantBuilder;
+
+    /// Matches the outputs that do not match this expression
+    fn not(self) -> Self::Output {
+        Self::new(BuilderFilter::Not(Box::new(self)))
+    }
+}
+
+impl Display for CovenantBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        #[allow(clippy::enum_glob_use)]
+        use BuilderFilter::*;
+        match &self.filter {
+            Identity => write!(f, "identity()"),
+            And(left, right) => write!(f, "and({}, {})", left, right),
+            Or(left, right) => write!(f, "or({}, {})", left, right),
+            Xor(left, right) => write!(f, "xor({}, {})", left, right),
+            Not(inner) => write!(f, "not({})", inner),
+            OutputHashEq(hash) => write!(f, "output_hash_eq(@hash({}))", to_hex(&hash[..])),
+            FieldsPreserved(fields) => write!(f, "fields_preserved({})", DisplayFields(fields)),
+            FieldEq(field, value) => write!(f, "field_eq(@{}, {})", field, value),
+            FieldsHashedEq(fields, hash) => write!(
+                f,
+                "fields_hashed_eq({}, @hash({}))",
+                DisplayFields(fields),
+                to_hex(&hash[..])
+            ),
+            AbsoluteHeight(height) => write!(f, "absolute_height(@uint({}))", height),
+        }
+    }
+}
+
+impl std::fmt::Debug for CovenantBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "covenant!({})", self)
+    }
+}
+
+fn unique_fields<I: IntoIterator<Item = OutputField>>(fields: I) -> OutputFields {
+    let mut unique = OutputFields::new();
+    for field in fields {
+        if !unique.fields().contains(&field) {
+            unique.push(field);
+        }
+    }
+    unique
+}
+
+struct DisplayFields<'a>(&'a OutputFields);
+
+impl Display for DisplayFields<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let fields = self.0.iter().map(|field| format!("@{}", field)).collect::<Vec<_>>();
+        write!(f, "@fields({})", fields.join(", "))
+    }
+}
+
+/// An output field, typed by the value that `field_eq` compares it to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CovenantField<T> {
+    field: OutputField,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T> CovenantField<T> {
+    fn new(field: OutputField) -> Self {
+        Self {
+            field,
+            _value: PhantomData,
+        }
+    }
+
+    pub fn field(&self) -> OutputField {
+        self.field
+    }
+}
+
+impl<T> From<CovenantField<T>> for OutputField {
+    fn from(field: CovenantField<T>) -> Self {
+        field.field
+    }
+}
+
+impl CovenantField<Commitment> {
+    pub fn commitment() -> Self {
+        Self::new(OutputField::Commitment)
+    }
+}
+
+impl CovenantField<TariScript> {
+    pub fn script() -> Self {
+        Self::new(OutputField::Script)
+    }
+}
+
+impl CovenantField<PublicKey> {
+    pub fn sender_offset_public_key() -> Self {
+        Self::new(OutputField::SenderOffsetPublicKey)
+    }
+
+    /// Only matches outputs that have a parent public key
+    pub fn features_parent_public_key() -> Self {
+        Self::new(OutputField::FeaturesParentPublicKey)
+    }
+}
+
+impl CovenantField<CovenantBuilder> {
+    pub fn covenant() -> Self {
+        Self::new(OutputField::Covenant)
+    }
+}
+
+impl CovenantField<u64> {
+    pub fn features_maturity() -> Self {
+        Self::new(OutputField::FeaturesMaturity)
+    }
+}
+
+impl CovenantField<Vec<u8>> {
+    /// Only matches outputs that have a unique id
+    pub fn features_unique_id() -> Self {
+        Self::new(OutputField::FeaturesUniqueId)
+    }
+
+    pub fn features_metadata() -> Self {
+        Self::new(OutputField::FeaturesMetadata)
+    }
+}
+
+/// A value that an output field can be compared to. This is implemented for the value types of [CovenantField] only.
+pub trait CovenantFieldValue: sealed::Sealed {
+    #[doc(hidden)]
+    fn into_field_value(self) -> FieldValue;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+#[doc(hidden)]
+#[derive(Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    Arg(CovenantArg),
+    Covenant(Box<CovenantBuilder>),
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldValue::Arg(CovenantArg::PublicKey(public_key)) => write!(f, "@public_key({})", public_key.to_hex()),
+            FieldValue::Arg(CovenantArg::Commitment(commitment)) => write!(f, "@commitment({})", commitment.to_hex()),
+            FieldValue::Arg(CovenantArg::TariScript(script)) => write!(f, "@script({})", script),
+            FieldValue::Arg(CovenantArg::Uint(val)) => write!(f, "@uint({})", val),
+            FieldValue::Arg(CovenantArg::Bytes(bytes)) => write!(f, "@bytes({})", bytes.to_hex()),
+            FieldValue::Arg(arg) => write!(f, "{}", arg),
+            FieldValue::Covenant(covenant) => write!(f, "@covenant_lit({})", covenant),
+        }
+    }
+}
+
+macro_rules! field_value_impl {
+    ($ty:ty, $arg:ident) => {
+        impl sealed::Sealed for $ty {}
+
+        impl CovenantFieldValue for $ty {
+            fn into_field_value(self) -> FieldValue {
+                FieldValue::Arg(CovenantArg::$arg(self))
+            }
+        }
+    };
+}
+
+field_value_impl!(Commitment, Commitment);
+field_value_impl!(TariScript, TariScript);
+field_value_impl!(PublicKey, PublicKey);
+field_value_impl!(u64, Uint);
+field_value_impl!(Vec<u8>, Bytes);
+
+impl sealed::Sealed for CovenantBuilder {}
+
+impl CovenantFieldValue for CovenantBuilder {
+    fn into_field_value(self) -> FieldValue {
+        FieldValue::Covenant(Box::new(self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_utilities::hex::from_hex;
+
+    use super::*;
+    use crate::{
+        covenant,
+        covenants::test::{create_input, create_outputs},
+        transactions::test_helpers::UtxoTestParams,
+    };
+
+    fn test_hash() -> Hash {
+        let mut hash = Hash::default();
+        hash.copy_from_slice(&from_hex("53563b674ba8e5166adb57afa8355bcf2ee759941eef8f8959b802367c2558bd").unwrap());
+        hash
+    }
+
+    #[test]
+    fn it_encodes_the_same_as_the_macro() {
+        let covenant = CovenantBuilder::identity()
+            .or(CovenantBuilder::fields_hashed_eq(
+                vec![OutputField::Commitment, OutputField::FeaturesMetadata],
+                test_hash(),
+            ))
+            .and(CovenantBuilder::field_eq(CovenantField::features_maturity(), 42))
+            .build()
+            .unwrap();
+        let expected = covenant!(and(
+            or(
+                identity(),
+                fields_hashed_eq(@fields(@field::commitment, @field::features_metadata), @hash(test_hash())),
+            ),
+            field_eq(@field::features_maturity, @uint(42))
+        ));
+        assert_eq!(covenant, expected);
+
+        let bytes = vec![0xba, 0xda, 0x55];
+        let covenant = (!CovenantBuilder::identity())
+            .xor(CovenantBuilder::field_eq(
+                CovenantField::covenant(),
+                CovenantBuilder::field_eq(CovenantField::features_unique_id(), bytes.clone()),
+            ))
+            .build()
+            .unwrap();
+        let expected = covenant!(xor(
+            not(identity(),),
+            field_eq(@field::covenant, @covenant_lit(field_eq(@field::features_unique_id, @bytes(bytes))))
+        ));
+        assert_eq!(covenant, expected);
+        assert_eq!(Covenant::from_bytes(&covenant.to_bytes()).unwrap(), covenant);
+    }
+
+    #[test]
+    fn it_renders_in_macro_syntax() {
+        let builder = CovenantBuilder::absolute_height(42).or(!CovenantBuilder::fields_preserved(vec![
+            OutputField::Commitment,
+            OutputField::FeaturesMaturity,
+            OutputField::Commitment,
+        ]));
+        assert_eq!(
+            builder.to_string(),
+            "or(absolute_height(@uint(42)), not(fields_preserved(@fields(@field::commitment, \
+             @field::features_maturity))))"
+        );
+        assert_eq!(format!("{:?}", builder), format!("covenant!({})", builder));
+
+        let builder =
+            CovenantBuilder::field_eq(CovenantField::covenant(), CovenantBuilder::output_hash_eq(test_hash()));
+        assert_eq!(
+            builder.to_string(),
+            "field_eq(@field::covenant, \
+             @covenant_lit(output_hash_eq(@hash(53563b674ba8e5166adb57afa8355bcf2ee759941eef8f8959b802367c2558bd))))"
+        );
+    }
+
+    #[test]
+    fn it_matches_outputs_with_the_hashed_fields_of_an_output() {
+        let mut outputs = create_outputs(10, UtxoTestParams::default());
+        outputs[5].features.maturity = 42;
+        outputs[7].features.maturity = 42;
+        let covenant = CovenantBuilder::fields_hashed_eq_to_output(vec![OutputField::FeaturesMaturity], &outputs[5])
+            .build()
+            .unwrap();
+        let num_matching_outputs = covenant.execute(0, &create_input(), &outputs).unwrap();
+        assert_eq!(num_matching_outputs, 2);
+    }
+
+    #[test]
+    fn it_rejects_covenants_that_exceed_the_max_size() {
+        let err = CovenantBuilder::field_eq(CovenantField::features_metadata(), vec![0u8; MAX_COVENANT_BYTES])
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, CovenantError::ExceededMaxSize { .. }));
+        let big_field_eq = || CovenantBuilder::field_eq(CovenantField::features_metadata(), vec![0u8; 2048]);
+        CovenantBuilder::field_eq(CovenantField::covenant(), big_field_eq().and(big_field_eq()))
+            .build()
+            .unwrap_err();
+        big_field_eq().build().unwrap();
+    }
+}
@@ -30,18 +30,52 @@ use crate::{
     transactions::transaction_components::TransactionInput,
 };
 
+/// Default maximum number of tokens consumed by a single covenant execution. Matches the consensus value.
+pub const DEFAULT_MAX_COVENANT_OPERATIONS: usize = 128;
+/// Default maximum filter nesting depth. Matches the consensus value.
+pub const DEFAULT_MAX_COVENANT_DEPTH: usize = 16;
+
+/// Limits on the work a covenant may perform during execution. Covenants that exceed these limits fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CovenantLimits {
+    /// The maximum number of tokens (filters and arguments) that may be consumed
+    pub max_operations: usize,
+    /// The maximum nesting depth of filters
+    pub max_depth: usize,
+}
+
+impl Default for CovenantLimits {
+    fn default() -> Self {
+        Self {
+            max_operations: DEFAULT_MAX_COVENANT_OPERATIONS,
+            max_depth: DEFAULT_MAX_COVENANT_DEPTH,
+        }
+    }
+}
+
 pub struct CovenantContext<'a> {
     input: &'a TransactionInput,
     tokens: CovenantTokenCollection,
     block_height: u64,
+    limits: CovenantLimits,
+    operations: usize,
+    depth: usize,
 }
 
 impl<'a> CovenantContext<'a> {
-    pub fn new(tokens: CovenantTokenCollection, input: &'a TransactionInput, block_height: u64) -> Self {
+    pub fn new(
+        tokens: CovenantTokenCollection,
+        input: &'a TransactionInput,
+        block_height: u64,
+        limits: CovenantLimits,
+    ) -> Self {
         Self {
             input,
             tokens,
             block_height,
+            limits,
+            operations: 0,
+            depth: 0,
         }
     }
 
@@ -50,7 +84,7 @@ impl<'a> CovenantContext<'a> {
     }
 
     pub fn next_arg(&mut self) -> Result<CovenantArg, CovenantError> {
-        match self.tokens.next().ok_or(CovenantError::UnexpectedEndOfTokens)? {
+        match self.next_token()?.ok_or(CovenantError::UnexpectedEndOfTokens)? {
             CovenantToken::Arg(arg) => Ok(*arg),
             CovenantToken::Filter(_) => Err(CovenantError::ExpectedArgButGotFilter),
         }
@@ -59,19 +93,49 @@ impl<'a> CovenantContext<'a> {
     // Only happens to be used in tests for now
     #[cfg(test)]
     pub fn next_filter(&mut self) -> Option<CovenantFilter> {
-        match self.tokens.next()? {
+        match self.next_token().ok()?? {
             CovenantToken::Filter(filter) => Some(filter),
             CovenantToken::Arg(_) => None,
         }
     }
 
     pub fn require_next_filter(&mut self) -> Result<CovenantFilter, CovenantError> {
-        match self.tokens.next().ok_or(CovenantError::UnexpectedEndOfTokens)? {
+        match self.next_token()?.ok_or(CovenantError::UnexpectedEndOfTokens)? {
             CovenantToken::Filter(filter) => Ok(filter),
             CovenantToken::Arg(_) => Err(CovenantError::ExpectedFilterButGotArg),
         }
     }
 
+    /// Called before a filter is applied. Returns an error if the filter would exceed the maximum nesting depth.
+    pub fn enter_filter(&mut self) -> Result<(), CovenantError> {
+        if self.depth >= self.limits.max_depth {
+            return Err(CovenantError::ExceededMaxDepth {
+                max: self.limits.max_depth,
+            });
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Called once a filter has been applied
+    pub fn exit_filter(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    fn next_token(&mut self) -> Result<Option<CovenantToken>, CovenantError> {
+        let token = match self.tokens.next() {
+            Some(token) => token,
+            None => return Ok(None),
+        };
+        if self.operations >= self.limits.max_operations {
+            return Err(CovenantError::ExceededMaxOperations {
+                max: self.limits.max_operations,
+            });
+        }
+        self.operations += 1;
+        Ok(Some(token))
+    }
+
     pub fn block_height(&self) -> u64 {
         self.block_height
     }
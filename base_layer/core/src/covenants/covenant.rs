@@ -28,7 +28,7 @@ use crate::{
     common::{byte_counter::ByteCounter, limited_reader::LimitedBytesReader},
     consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized},
     covenants::{
-        context::CovenantContext,
+        context::{CovenantContext, CovenantLimits},
         decoder::{CovenantDecodeError, CovenantTokenDecoder},
         encoder::CovenantTokenEncoder,
         error::CovenantError,
@@ -39,7 +39,7 @@ use crate::{
     transactions::transaction_components::{TransactionInput, TransactionOutput},
 };
 
-const MAX_COVENANT_BYTES: usize = 4096;
+pub(super) const MAX_COVENANT_BYTES: usize = 4096;
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Covenant {
@@ -77,11 +77,23 @@ impl Covenant {
         counter.get()
     }
 
+    /// Executes the covenant against the given outputs using the default [CovenantLimits].
     pub fn execute<'a>(
         &self,
         block_height: u64,
         input: &TransactionInput,
         outputs: &'a [TransactionOutput],
+    ) -> Result<usize, CovenantError> {
+        self.execute_with_limits(block_height, input, outputs, CovenantLimits::default())
+    }
+
+    /// Executes the covenant against the given outputs, failing if execution exceeds the given limits.
+    pub fn execute_with_limits<'a>(
+        &self,
+        block_height: u64,
+        input: &TransactionInput,
+        outputs: &'a [TransactionOutput],
+        limits: CovenantLimits,
     ) -> Result<usize, CovenantError> {
         if self.tokens.is_empty() {
             // Empty covenants always pass
@@ -89,7 +101,7 @@ impl Covenant {
         }
 
         let tokens = CovenantTokenCollection::from_iter(self.tokens.clone());
-        let mut cx = CovenantContext::new(tokens, input, block_height);
+        let mut cx = CovenantContext::new(tokens, input, block_height, limits);
         let root = cx.require_next_filter()?;
         let mut output_set = OutputSet::new(outputs);
         root.filter(&mut cx, &mut output_set)?;
@@ -195,6 +207,69 @@ mod test {
         assert_eq!(num_matching_outputs, 3);
     }
 
+    mod limits {
+        use super::*;
+
+        fn nested_not(depth: usize) -> Covenant {
+            let mut covenant = Covenant::new();
+            for _ in 0..depth {
+                covenant.push_token(CovenantToken::not());
+            }
+            covenant.push_token(CovenantToken::absolute_height());
+            covenant.push_token(CovenantToken::uint(0));
+            covenant
+        }
+
+        #[test]
+        fn it_fails_if_the_max_depth_is_exceeded() {
+            let outputs = create_outputs(1, Default::default());
+            let input = create_input();
+            let limits = CovenantLimits {
+                max_operations: 1000,
+                max_depth: 3,
+            };
+            // not(not(absolute_height(...))) has a depth of 3
+            let num_matching_outputs = nested_not(2).execute_with_limits(0, &input, &outputs, limits).unwrap();
+            assert_eq!(num_matching_outputs, 1);
+            let err = nested_not(3)
+                .execute_with_limits(0, &input, &outputs, limits)
+                .unwrap_err();
+            assert!(matches!(err, CovenantError::ExceededMaxDepth { max: 3 }));
+        }
+
+        #[test]
+        fn it_fails_if_the_max_operations_are_exceeded() {
+            let outputs = create_outputs(10, Default::default());
+            let input = create_input();
+            let covenant = covenant!(and(
+                absolute_height(@uint(0),),
+                and(absolute_height(@uint(0),), absolute_height(@uint(0)))
+            ));
+            // 3 absolute_height + 3 args + 2 ands
+            let limits = CovenantLimits {
+                max_operations: 8,
+                max_depth: 16,
+            };
+            let num_matching_outputs = covenant.execute_with_limits(0, &input, &outputs, limits).unwrap();
+            assert_eq!(num_matching_outputs, 10);
+
+            let limits = CovenantLimits {
+                max_operations: 7,
+                ..limits
+            };
+            let err = covenant.execute_with_limits(0, &input, &outputs, limits).unwrap_err();
+            assert!(matches!(err, CovenantError::ExceededMaxOperations { max: 7 }));
+        }
+
+        #[test]
+        fn it_limits_deeply_nested_covenants_by_default() {
+            let outputs = create_outputs(1, Default::default());
+            let input = create_input();
+            let err = nested_not(1000).execute(0, &input, &outputs).unwrap_err();
+            assert!(matches!(err, CovenantError::ExceededMaxDepth { .. }));
+        }
+    }
+
     mod consensus_encoding {
         use super::*;
 
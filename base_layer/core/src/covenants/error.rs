@@ -40,4 +40,10 @@ pub enum CovenantError {
     RemainingTokens,
     #[error("Invalid argument for filter {filter}: {details}")]
     InvalidArgument { filter: &'static str, details: String },
+    #[error("Covenant is {size} bytes which exceeds the maximum of {max} bytes")]
+    ExceededMaxSize { size: usize, max: usize },
+    #[error("Covenant execution exceeded the maximum of {max} operations")]
+    ExceededMaxOperations { max: usize },
+    #[error("Covenant filters exceeded the maximum nesting depth of {max}")]
+    ExceededMaxDepth { max: usize },
 }
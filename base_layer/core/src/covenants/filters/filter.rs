@@ -151,7 +151,8 @@ impl Filter for CovenantFilter {
     fn filter(&self, context: &mut CovenantContext<'_>, output_set: &mut OutputSet<'_>) -> Result<(), CovenantError> {
         #[allow(clippy::enum_glob_use)]
         use CovenantFilter::*;
-        match self {
+        context.enter_filter()?;
+        let result = match self {
             Identity(identity) => identity.filter(context, output_set),
             And(and) => and.filter(context, output_set),
             Or(or) => or.filter(context, output_set),
@@ -162,7 +163,9 @@ impl Filter for CovenantFilter {
             FieldEq(fields_eq) => fields_eq.filter(context, output_set),
             FieldsHashedEq(fields_hashed_eq) => fields_hashed_eq.filter(context, output_set),
             AbsoluteHeight(abs_height) => abs_height.filter(context, output_set),
-        }
+        };
+        context.exit_filter();
+        result
     }
 }
 
@@ -27,6 +27,7 @@
 //! <https://rfc.tari.com/RFC-0250_Covenants.html>
 
 mod arguments;
+mod builder;
 mod byte_codes;
 mod context;
 mod covenant;
@@ -39,11 +40,12 @@ mod output_set;
 mod serde;
 mod token;
 
+pub use builder::{CovenantBuilder, CovenantField, CovenantFieldValue};
+pub use context::CovenantLimits;
 pub use covenant::Covenant;
 pub use error::CovenantError;
 // Used in macro
-#[allow(unused_imports)]
-pub(crate) use fields::OutputField;
+pub use fields::OutputField;
 pub use token::CovenantToken;
 
 #[macro_use]
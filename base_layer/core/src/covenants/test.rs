@@ -48,5 +48,5 @@ pub fn create_input() -> TransactionInput {
 
 pub fn create_context<'a>(covenant: &Covenant, input: &'a TransactionInput, block_height: u64) -> CovenantContext<'a> {
     let tokens = covenant.tokens().to_vec();
-    CovenantContext::new(tokens.into(), input, block_height)
+    CovenantContext::new(tokens.into(), input, block_height, Default::default())
 }
@@ -21,6 +21,8 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 #[macro_use]
 extern crate bitflags;
+// Allows the consensus encoding derive macros, which refer to `::tari_core`, to be used within this crate
+extern crate self as tari_core;
 
 pub mod blocks;
 #[cfg(feature = "base_node")]
@@ -30,6 +32,8 @@ pub mod consensus;
 pub mod covenants;
 #[cfg(feature = "base_node")]
 pub mod iterators;
+#[cfg(feature = "base_node")]
+pub mod privacy_analysis;
 pub mod proof_of_work;
 #[cfg(feature = "base_node")]
 pub mod validation;
@@ -49,6 +53,9 @@ pub mod mempool;
 #[cfg(feature = "transactions")]
 pub mod transactions;
 
+#[cfg(feature = "base_node")]
+pub mod test_vectors;
+
 mod common;
 
 #[allow(clippy::ptr_offset_with_cast)]
@@ -20,8 +20,10 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
-use tari_common::SubConfigPath;
+use tari_common::{configuration::serializers, SubConfigPath};
 
 use crate::mempool::{reorg_pool::ReorgPoolConfig, unconfirmed_pool::UnconfirmedPoolConfig};
 
@@ -50,6 +52,17 @@ pub struct MempoolServiceConfig {
     pub initial_sync_num_peers: usize,
     /// The maximum number of transactions to sync in a single sync session Default: 10_000
     pub initial_sync_max_transactions: usize,
+    /// If false, transactions received from peers are not added to the mempool and transactions are not propagated
+    /// to peers. Default: true
+    pub relay_transactions: bool,
+    /// The maximum number of incoming transactions that are validated concurrently. Transactions that spend the same
+    /// inputs are always validated in the order they are received. Transactions received while every validation slot
+    /// is busy are dropped. Default: 8
+    pub max_concurrent_tx_validations: usize,
+    /// A transaction is relayed to peers at most once within this window, no matter how many peers it is received
+    /// from. Zero disables suppression. Default: 30s
+    #[serde(with = "serializers::seconds")]
+    pub tx_relay_suppression_window: Duration,
 }
 
 impl Default for MempoolServiceConfig {
@@ -57,6 +70,9 @@ impl Default for MempoolServiceConfig {
         Self {
             initial_sync_num_peers: 2,
             initial_sync_max_transactions: 10_000,
+            relay_transactions: true,
+            max_concurrent_tx_validations: 8,
+            tx_relay_suppression_window: Duration::from_secs(30),
         }
     }
 }
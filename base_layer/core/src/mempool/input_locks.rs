@@ -0,0 +1,138 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tari_common_types::types::HashOutput;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Serialises the processing of transactions that spend the same inputs, so that conflicting transactions are
+/// validated and inserted into the mempool in the order in which they arrive. Transactions with disjoint inputs are
+/// processed concurrently.
+#[derive(Default)]
+pub(crate) struct InputLocks {
+    locks: Mutex<HashMap<HashOutput, Arc<AsyncMutex<()>>>>,
+}
+
+impl InputLocks {
+    /// Waits until no other transaction holds a lock on any of the given input hashes and then locks them until the
+    /// returned guard is dropped. Waiters for the same input are served in FIFO order.
+    pub async fn acquire(&self, mut input_hashes: Vec<HashOutput>) -> InputLocksGuard<'_> {
+        // Always lock in the same order to prevent deadlocks between transactions sharing more than one input
+        input_hashes.sort();
+        input_hashes.dedup();
+        let mutexes = {
+            let mut locks = self.locks.lock().expect("InputLocks mutex poisoned");
+            input_hashes
+                .iter()
+                .map(|hash| locks.entry(hash.clone()).or_default().clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut guards = Vec::with_capacity(mutexes.len());
+        for mutex in mutexes {
+            guards.push(mutex.lock_owned().await);
+        }
+
+        InputLocksGuard {
+            owner: self,
+            input_hashes,
+            guards,
+        }
+    }
+
+    #[cfg(test)]
+    fn num_locked(&self) -> usize {
+        self.locks.lock().unwrap().len()
+    }
+}
+
+pub(crate) struct InputLocksGuard<'a> {
+    owner: &'a InputLocks,
+    input_hashes: Vec<HashOutput>,
+    guards: Vec<OwnedMutexGuard<()>>,
+}
+
+impl Drop for InputLocksGuard<'_> {
+    fn drop(&mut self) {
+        self.guards.clear();
+        if let Ok(mut locks) = self.owner.locks.lock() {
+            // Remove the locks that no other transaction is waiting on
+            for hash in &self.input_hashes {
+                if locks
+                    .get(hash)
+                    .map(|mutex| Arc::strong_count(mutex) == 1)
+                    .unwrap_or(false)
+                {
+                    locks.remove(hash);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        time::Duration,
+    };
+
+    use tokio::time;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn it_does_not_block_disjoint_inputs() {
+        let locks = InputLocks::default();
+        let _guard1 = locks.acquire(vec![vec![1], vec![2]]).await;
+        let guard2 = time::timeout(Duration::from_secs(1), locks.acquire(vec![vec![3]])).await;
+        assert!(guard2.is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_blocks_conflicting_inputs_until_released() {
+        let locks = Arc::new(InputLocks::default());
+        let guard1 = locks.acquire(vec![vec![2], vec![1], vec![1]]).await;
+        assert_eq!(locks.num_locked(), 2);
+
+        let acquired = Arc::new(AtomicBool::new(false));
+        let waiter = {
+            let locks = locks.clone();
+            let acquired = acquired.clone();
+            tokio::spawn(async move {
+                let _guard = locks.acquire(vec![vec![1]]).await;
+                acquired.store(true, Ordering::SeqCst);
+            })
+        };
+        time::sleep(Duration::from_millis(50)).await;
+        assert!(!acquired.load(Ordering::SeqCst));
+
+        drop(guard1);
+        waiter.await.unwrap();
+        assert!(acquired.load(Ordering::SeqCst));
+        assert_eq!(locks.num_locked(), 0);
+    }
+}
@@ -23,18 +23,23 @@
 use std::sync::{Arc, RwLock};
 
 use tari_common_types::types::{PrivateKey, Signature};
-use tokio::task;
+use tokio::{sync::broadcast, task};
 
 use crate::{
     blocks::Block,
     consensus::ConsensusManager,
     mempool::{
         error::MempoolError,
+        input_locks::InputLocks,
         mempool_storage::MempoolStorage,
         MempoolConfig,
+        MempoolEvent,
+        MempoolEventReceiver,
+        MempoolEventSender,
         StateResponse,
         StatsResponse,
         TxStorageResponse,
+        UnconfirmedTxSummary,
     },
     transactions::transaction_components::Transaction,
     validation::MempoolTransactionValidation,
@@ -46,8 +51,13 @@ use crate::{
 #[derive(Clone)]
 pub struct Mempool {
     pool_storage: Arc<RwLock<MempoolStorage>>,
+    validator: Arc<dyn MempoolTransactionValidation>,
+    input_locks: Arc<InputLocks>,
+    event_sender: MempoolEventSender,
 }
 
+const MEMPOOL_EVENT_CHANNEL_SIZE: usize = 1000;
+
 impl Mempool {
     /// Create a new Mempool with an UnconfirmedPool and ReOrgPool.
     pub fn new(
@@ -55,21 +65,73 @@ impl Mempool {
         rules: ConsensusManager,
         validator: Box<dyn MempoolTransactionValidation>,
     ) -> Self {
+        let validator = Arc::from(validator);
+        let (event_sender, _) = broadcast::channel(MEMPOOL_EVENT_CHANNEL_SIZE);
         Self {
-            pool_storage: Arc::new(RwLock::new(MempoolStorage::new(config, rules, validator))),
+            pool_storage: Arc::new(RwLock::new(MempoolStorage::new(config, rules, Arc::clone(&validator)))),
+            validator,
+            input_locks: Arc::new(InputLocks::default()),
+            event_sender,
         }
     }
 
+    /// Returns a stream of the transactions received by the mempool and whether each was accepted
+    pub fn get_event_stream(&self) -> MempoolEventReceiver {
+        self.event_sender.subscribe()
+    }
+
+    /// Returns the sender used to publish mempool events
+    pub fn event_sender(&self) -> MempoolEventSender {
+        self.event_sender.clone()
+    }
+
+    fn publish_event(&self, transaction: Arc<Transaction>, response: &TxStorageResponse) {
+        // An error means that there are no subscribers
+        let _result = self.event_sender.send(Arc::new(MempoolEvent {
+            transaction,
+            response: response.clone(),
+        }));
+    }
+
     /// Insert an unconfirmed transaction into the Mempool.
+    ///
+    /// The transaction is validated without holding the storage lock, so independent transactions are validated
+    /// concurrently. Transactions that spend the same inputs are validated and inserted in the order they arrive.
     pub async fn insert(&self, tx: Arc<Transaction>) -> Result<TxStorageResponse, MempoolError> {
-        self.with_write_access(|storage| storage.insert(tx)).await
+        let input_hashes = tx.body.inputs().iter().map(|input| input.output_hash()).collect();
+        let _input_locks = self.input_locks.acquire(input_hashes).await;
+
+        let chain_generation = self.with_read_access(|storage| Ok(storage.chain_generation())).await?;
+        let validator = self.validator.clone();
+        let validation_tx = tx.clone();
+        let validation_result = task::spawn_blocking(move || validator.validate(&validation_tx)).await?;
+
+        let event_tx = tx.clone();
+        let response = self
+            .with_write_access(move |storage| {
+                if storage.chain_generation() == chain_generation {
+                    storage.insert_validated(tx, validation_result)
+                } else {
+                    // The chain tip changed during validation, so the result may be stale
+                    storage.insert(tx)
+                }
+            })
+            .await?;
+        self.publish_event(event_tx, &response);
+        Ok(response)
     }
 
     /// Inserts all transactions into the mempool.
     pub async fn insert_all(&self, transactions: Vec<Arc<Transaction>>) -> Result<(), MempoolError> {
-        self.with_write_access(|storage| {
+        let event_sender = self.event_sender.clone();
+        self.with_write_access(move |storage| {
             for tx in transactions {
-                storage.insert(tx)?;
+                let response = storage.insert(tx.clone())?;
+                // An error means that there are no subscribers
+                let _result = event_sender.send(Arc::new(MempoolEvent {
+                    transaction: tx,
+                    response,
+                }));
             }
 
             Ok(())
@@ -84,12 +146,13 @@ impl Mempool {
     }
 
     /// In the event of a ReOrg, resubmit all ReOrged transactions into the Mempool and process each newly introduced
-    /// block from the latest longest chain.
+    /// block from the latest longest chain. Returns the transactions from the removed blocks that were returned to the
+    /// unconfirmed pool.
     pub async fn process_reorg(
         &self,
         removed_blocks: Vec<Arc<Block>>,
         new_blocks: Vec<Arc<Block>>,
-    ) -> Result<(), MempoolError> {
+    ) -> Result<Vec<Arc<Transaction>>, MempoolError> {
         self.with_write_access(move |storage| storage.process_reorg(&removed_blocks, &new_blocks))
             .await
     }
@@ -99,6 +162,19 @@ impl Mempool {
         self.with_read_access(|storage| Ok(storage.snapshot())).await
     }
 
+    /// Returns a summary of each transaction in the unconfirmed pool
+    pub async fn unconfirmed_summaries(&self) -> Result<Vec<UnconfirmedTxSummary>, MempoolError> {
+        self.with_read_access(|storage| Ok(storage.unconfirmed_summaries()))
+            .await
+    }
+
+    /// Evicts the unconfirmed transaction with the given kernel excess signature. Returns true if a transaction was
+    /// removed.
+    pub async fn remove_unconfirmed_transaction(&self, excess_sig: PrivateKey) -> Result<bool, MempoolError> {
+        self.with_write_access(move |storage| Ok(storage.remove_unconfirmed_transaction(&excess_sig)))
+            .await
+    }
+
     /// Returns a list of transaction ranked by transaction priority up to a given weight.
     /// Only transactions that fit into a block will be returned
     pub async fn retrieve(&self, total_weight: u64) -> Result<Vec<Arc<Transaction>>, MempoolError> {
@@ -37,6 +37,7 @@ use crate::{
         StateResponse,
         StatsResponse,
         TxStorageResponse,
+        UnconfirmedTxSummary,
     },
     transactions::{transaction_components::Transaction, weight::TransactionWeight},
     validation::{MempoolTransactionValidation, ValidationError},
@@ -50,8 +51,9 @@ pub const LOG_TARGET: &str = "c::mp::mempool_storage";
 pub struct MempoolStorage {
     unconfirmed_pool: UnconfirmedPool,
     reorg_pool: ReorgPool,
-    validator: Box<dyn MempoolTransactionValidation>,
+    validator: Arc<dyn MempoolTransactionValidation>,
     rules: ConsensusManager,
+    chain_generation: u64,
 }
 
 impl MempoolStorage {
@@ -59,19 +61,37 @@ impl MempoolStorage {
     pub fn new(
         config: MempoolConfig,
         rules: ConsensusManager,
-        validator: Box<dyn MempoolTransactionValidation>,
+        validator: Arc<dyn MempoolTransactionValidation>,
     ) -> Self {
         Self {
             unconfirmed_pool: UnconfirmedPool::new(config.unconfirmed_pool),
             reorg_pool: ReorgPool::new(config.reorg_pool),
             validator,
             rules,
+            chain_generation: 0,
         }
     }
 
+    /// A counter that changes whenever the chain tip changes. A transaction validation result obtained outside of the
+    /// storage lock is only valid if this has not changed in the meantime.
+    pub fn chain_generation(&self) -> u64 {
+        self.chain_generation
+    }
+
     /// Insert an unconfirmed transaction into the Mempool. The transaction *MUST* have passed through the validation
     /// pipeline already and will thus always be internally consistent by this stage
     pub fn insert(&mut self, tx: Arc<Transaction>) -> Result<TxStorageResponse, MempoolError> {
+        let validation_result = self.validator.validate(&tx);
+        self.insert_validated(tx, validation_result)
+    }
+
+    /// Insert an unconfirmed transaction into the Mempool according to the result of validating it against the
+    /// current chain tip.
+    pub fn insert_validated(
+        &mut self,
+        tx: Arc<Transaction>,
+        validation_result: Result<(), ValidationError>,
+    ) -> Result<TxStorageResponse, MempoolError> {
         let tx_id = tx
             .body
             .kernels()
@@ -79,21 +99,21 @@ impl MempoolStorage {
             .map(|k| k.excess_sig.get_signature().to_hex())
             .unwrap_or_else(|| "None?!".into());
         debug!(target: LOG_TARGET, "Inserting tx into mempool: {}", tx_id);
-        match self.validator.validate(&tx) {
+        match validation_result {
             Ok(()) => {
                 debug!(
                     target: LOG_TARGET,
                     "Transaction {} is VALID, inserting in unconfirmed pool", tx_id
                 );
                 let weight = self.get_transaction_weighting(0);
-                self.unconfirmed_pool.insert(tx, None, &weight)?;
-                Ok(TxStorageResponse::UnconfirmedPool)
+                let response = self.unconfirmed_pool.insert(tx, None, &weight)?;
+                Ok(response)
             },
             Err(ValidationError::UnknownInputs(dependent_outputs)) => {
                 if self.unconfirmed_pool.contains_all_outputs(&dependent_outputs) {
                     let weight = self.get_transaction_weighting(0);
-                    self.unconfirmed_pool.insert(tx, Some(dependent_outputs), &weight)?;
-                    Ok(TxStorageResponse::UnconfirmedPool)
+                    let response = self.unconfirmed_pool.insert(tx, Some(dependent_outputs), &weight)?;
+                    Ok(response)
                 } else {
                     warn!(target: LOG_TARGET, "Validation failed due to unknown inputs");
                     Ok(TxStorageResponse::NotStoredOrphan)
@@ -139,6 +159,7 @@ impl MempoolStorage {
             published_block.header.hash().to_hex(),
             published_block.body.to_counts_string()
         );
+        self.chain_generation = self.chain_generation.wrapping_add(1);
         // Move published txs to ReOrgPool and discard double spends
         let removed_transactions = self
             .unconfirmed_pool
@@ -154,13 +175,15 @@ impl MempoolStorage {
     }
 
     /// In the event of a ReOrg, resubmit all ReOrged transactions into the Mempool and process each newly introduced
-    /// block from the latest longest chain.
+    /// block from the latest longest chain. Returns the transactions from the removed blocks that were returned to the
+    /// unconfirmed pool.
     pub fn process_reorg(
         &mut self,
         removed_blocks: &[Arc<Block>],
         new_blocks: &[Arc<Block>],
-    ) -> Result<(), MempoolError> {
+    ) -> Result<Vec<Arc<Transaction>>, MempoolError> {
         debug!(target: LOG_TARGET, "Mempool processing reorg");
+        self.chain_generation = self.chain_generation.wrapping_add(1);
         let previous_tip = removed_blocks.last().map(|block| block.header.height);
         let new_tip = new_blocks.last().map(|block| block.header.height);
 
@@ -170,10 +193,10 @@ impl MempoolStorage {
         let removed_txs = self.unconfirmed_pool.drain_all_mempool_transactions();
         self.insert_txs(removed_txs)?;
         // Remove re-orged transactions from reorg  pool and re-submit them to the unconfirmed mempool
-        let removed_txs = self
+        let reorged_txs = self
             .reorg_pool
             .remove_reorged_txs_and_discard_double_spends(removed_blocks, new_blocks);
-        self.insert_txs(removed_txs)?;
+        self.insert_txs(reorged_txs.clone())?;
         // Update the Mempool based on the received set of new blocks.
         for block in new_blocks {
             self.process_published_block(block)?;
@@ -200,7 +223,16 @@ impl MempoolStorage {
             }
         }
 
-        Ok(())
+        // The re-orged transactions that are still unconfirmed were returned to the mempool
+        let returned_txs = reorged_txs
+            .into_iter()
+            .filter(|tx| {
+                tx.first_kernel_excess_sig()
+                    .map(|sig| self.unconfirmed_pool.has_tx_with_excess_sig(sig))
+                    .unwrap_or(false)
+            })
+            .collect();
+        Ok(returned_txs)
     }
 
     /// Returns all unconfirmed transaction stored in the Mempool, except the transactions stored in the ReOrgPool.
@@ -209,6 +241,24 @@ impl MempoolStorage {
         self.unconfirmed_pool.snapshot()
     }
 
+    /// Returns a summary of each transaction in the unconfirmed pool
+    pub fn unconfirmed_summaries(&self) -> Vec<UnconfirmedTxSummary> {
+        self.unconfirmed_pool.summaries()
+    }
+
+    /// Evicts the unconfirmed transaction with the given kernel excess signature from the Mempool. Returns true if a
+    /// transaction was removed.
+    pub fn remove_unconfirmed_transaction(&mut self, excess_sig: &PrivateKey) -> bool {
+        let removed = self.unconfirmed_pool.remove_by_excess_sig(excess_sig);
+        debug!(
+            target: LOG_TARGET,
+            "Evicted {} transaction(s) with excess signature {} from unconfirmed pool",
+            removed.len(),
+            excess_sig.to_hex()
+        );
+        !removed.is_empty()
+    }
+
     /// Returns a list of transaction ranked by transaction priority up to a given weight.
     /// Will only return transactions that will fit into the given weight
     pub fn retrieve_and_revalidate(&mut self, total_weight: u64) -> Result<Vec<Arc<Transaction>>, MempoolError> {
@@ -283,6 +333,7 @@ impl MempoolStorage {
             unconfirmed_txs: self.unconfirmed_pool.len(),
             reorg_txs: self.reorg_pool.len(),
             total_weight: self.unconfirmed_pool.calculate_weight(&weighting),
+            min_fee_per_gram: self.unconfirmed_pool.min_fee_per_gram(),
         }
     }
 
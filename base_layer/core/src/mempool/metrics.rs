@@ -75,3 +75,51 @@ pub fn reorg_pool_size() -> IntGauge {
 
     METER.clone()
 }
+
+pub fn suppressed_transaction_relays() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "base_node::mempool::suppressed_relays",
+            "Number of inbound or outbound transactions dropped because transaction relay is disabled",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
+pub fn suppressed_duplicate_relays() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "base_node::mempool::suppressed_duplicate_relays",
+            "Number of transactions not relayed because they were already relayed within the suppression window",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
+pub fn dropped_inbound_transactions() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "base_node::mempool::dropped_inbound_transactions",
+            "Number of inbound transactions dropped because the maximum number of concurrent validations was reached",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
+pub fn relayed_transactions() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "base_node::mempool::relayed_transactions",
+            "Number of transactions relayed to peers",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
@@ -28,6 +28,8 @@ mod config;
 #[cfg(feature = "base_node")]
 mod error;
 #[cfg(feature = "base_node")]
+mod input_locks;
+#[cfg(feature = "base_node")]
 #[allow(clippy::module_inception)]
 mod mempool;
 #[cfg(feature = "base_node")]
@@ -67,14 +69,15 @@ pub use service::{MempoolServiceError, MempoolServiceInitializer, OutboundMempoo
 #[cfg(feature = "base_node")]
 mod sync_protocol;
 use core::fmt::{Display, Error, Formatter};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "base_node")]
 pub use sync_protocol::MempoolSyncInitializer;
 use tari_common_types::types::Signature;
+use tokio::sync::broadcast;
 
-use crate::transactions::transaction_components::Transaction;
+use crate::transactions::{tari_amount::MicroTari, transaction_components::Transaction};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct StatsResponse {
@@ -82,14 +85,18 @@ pub struct StatsResponse {
     pub unconfirmed_txs: usize,
     pub reorg_txs: usize,
     pub total_weight: u64,
+    /// The lowest fee per gram that a new transaction must pay to be accepted into the unconfirmed pool. This is zero
+    /// unless the pool is full.
+    pub min_fee_per_gram: u64,
 }
 
 impl Display for StatsResponse {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
         write!(
             fmt,
-            "Mempool stats: Total transactions: {}, Unconfirmed: {}, Published: {}, Total Weight: {}g",
-            self.total_txs, self.unconfirmed_txs, self.reorg_txs, self.total_weight
+            "Mempool stats: Total transactions: {}, Unconfirmed: {}, Published: {}, Total Weight: {}g, Min fee: {} \
+             uT/g",
+            self.total_txs, self.unconfirmed_txs, self.reorg_txs, self.total_weight, self.min_fee_per_gram
         )
     }
 }
@@ -100,6 +107,26 @@ pub struct StateResponse {
     pub reorg_pool: Vec<Signature>,
 }
 
+/// A summary of a transaction in the unconfirmed pool
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnconfirmedTxSummary {
+    /// The excess signature of the first kernel of the transaction
+    pub excess_sig: Signature,
+    pub fee: MicroTari,
+    pub weight: u64,
+    /// The time that the transaction has been in the unconfirmed pool
+    pub age: Duration,
+    pub num_inputs: usize,
+    pub num_outputs: usize,
+    pub num_kernels: usize,
+}
+
+impl UnconfirmedTxSummary {
+    pub fn fee_per_gram(&self) -> u64 {
+        self.fee.as_u64().checked_div(self.weight).unwrap_or(0)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TxStorageResponse {
     UnconfirmedPool,
@@ -108,6 +135,7 @@ pub enum TxStorageResponse {
     NotStoredTimeLocked,
     NotStoredAlreadySpent,
     NotStoredConsensus,
+    NotStoredFeeTooLow,
     NotStored,
 }
 
@@ -126,8 +154,27 @@ impl Display for TxStorageResponse {
             TxStorageResponse::NotStoredTimeLocked => "Not stored time locked transaction",
             TxStorageResponse::NotStoredAlreadySpent => "Not stored output already spent",
             TxStorageResponse::NotStoredConsensus => "Not stored due to consensus rule",
+            TxStorageResponse::NotStoredFeeTooLow => "Not stored fee too low",
             TxStorageResponse::NotStored => "Not stored",
         };
         fmt.write_str(storage)
     }
 }
+
+/// An event published by the mempool for each transaction that it receives
+#[derive(Clone, Debug)]
+pub struct MempoolEvent {
+    pub transaction: Arc<Transaction>,
+    /// Where the transaction was stored, or why it was not stored
+    pub response: TxStorageResponse,
+}
+
+impl MempoolEvent {
+    /// Returns true if the transaction was added to the mempool
+    pub fn is_accepted(&self) -> bool {
+        self.response.is_stored()
+    }
+}
+
+pub type MempoolEventSender = broadcast::Sender<Arc<MempoolEvent>>;
+pub type MempoolEventReceiver = broadcast::Receiver<Arc<MempoolEvent>>;
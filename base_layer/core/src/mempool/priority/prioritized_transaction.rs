@@ -23,6 +23,7 @@
 use std::{
     fmt::{Display, Formatter},
     sync::Arc,
+    time::Instant,
 };
 
 use tari_common_types::types::{HashOutput, PrivateKey, PublicKey};
@@ -73,7 +74,11 @@ pub struct PrioritizedTransaction {
     pub transaction: Arc<Transaction>,
     pub priority: FeePriority,
     pub weight: u64,
+    /// The serialized size of the transaction in bytes
+    pub size: u64,
     pub dependent_output_hashes: Vec<HashOutput>,
+    /// The time at which the transaction was inserted into the pool
+    pub inserted_at: Instant,
 }
 
 impl PrioritizedTransaction {
@@ -84,12 +89,15 @@ impl PrioritizedTransaction {
         dependent_outputs: Option<Vec<HashOutput>>,
     ) -> PrioritizedTransaction {
         let weight = transaction.calculate_weight(weighting);
+        let size = bincode::serialized_size(&*transaction).unwrap_or(0);
         Self {
             key,
             priority: FeePriority::new(&transaction, weight),
             weight,
+            size,
             transaction,
             dependent_output_hashes: dependent_outputs.unwrap_or_default(),
+            inserted_at: Instant::now(),
         }
     }
 }
@@ -101,6 +109,10 @@ impl Display for PrioritizedTransaction {
             .first_kernel_excess_sig()
             .map(|sig| sig.get_signature().to_hex())
             .unwrap_or_else(|| "No kernels!".to_string());
-        write!(f, "{} (weight: {}, internal key: {})", sig_hex, self.weight, self.key)
+        write!(
+            f,
+            "{} (weight: {}, size: {} bytes, internal key: {})",
+            sig_hex, self.weight, self.size, self.key
+        )
     }
 }
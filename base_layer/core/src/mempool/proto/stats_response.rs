@@ -33,6 +33,7 @@ impl TryFrom<ProtoStatsResponse> for StatsResponse {
             unconfirmed_txs: stats.unconfirmed_txs as usize,
             reorg_txs: stats.reorg_txs as usize,
             total_weight: stats.total_weight,
+            min_fee_per_gram: stats.min_fee_per_gram,
         })
     }
 }
@@ -44,6 +45,7 @@ impl From<StatsResponse> for ProtoStatsResponse {
             unconfirmed_txs: stats.unconfirmed_txs as u64,
             reorg_txs: stats.reorg_txs as u64,
             total_weight: stats.total_weight,
+            min_fee_per_gram: stats.min_fee_per_gram,
         }
     }
 }
@@ -388,7 +388,7 @@ mod test {
     #[test]
     fn remove_scan_for_and_remove_reorged_txs() {
         let network = Network::LocalNet;
-        let consensus = ConsensusManagerBuilder::new(network).build();
+        let consensus = ConsensusManagerBuilder::new(network).build().unwrap();
         let tx1 = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(10), lock: 4000, inputs: 2, outputs: 1).0);
         let tx2 = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(6), lock: 3000, inputs: 2, outputs: 1).0);
         let tx3 = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(4), lock: 2500, inputs: 2, outputs: 1).0);
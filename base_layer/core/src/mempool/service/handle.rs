@@ -30,6 +30,7 @@ use crate::{
         StateResponse,
         StatsResponse,
         TxStorageResponse,
+        UnconfirmedTxSummary,
     },
     transactions::transaction_components::Transaction,
 };
@@ -68,6 +69,13 @@ impl MempoolHandle {
         }
     }
 
+    pub async fn get_unconfirmed_transactions(&mut self) -> Result<Vec<UnconfirmedTxSummary>, MempoolServiceError> {
+        match self.inner.call(MempoolRequest::GetUnconfirmedTransactions).await?? {
+            MempoolResponse::UnconfirmedTransactions(txs) => Ok(txs),
+            _ => panic!("Incorrect response"),
+        }
+    }
+
     pub async fn submit_transaction(
         &mut self,
         transaction: Transaction,
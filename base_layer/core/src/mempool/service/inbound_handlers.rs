@@ -23,11 +23,16 @@
 use std::sync::Arc;
 
 use log::*;
-use tari_comms::peer_manager::NodeId;
+use tari_comms::{connectivity::ConnectivityRequester, peer_manager::NodeId};
 use tari_utilities::hex::Hex;
 
 use crate::{
-    base_node::comms_interface::BlockEvent,
+    base_node::{
+        comms_interface::{BlockEvent, LocalNodeCommsInterface, ReorgEvent},
+        PeerOffense,
+        PeerOffenseTracker,
+    },
+    blocks::Block,
     chain_storage::BlockAddResult,
     mempool::{
         metrics,
@@ -46,18 +51,40 @@ pub const LOG_TARGET: &str = "c::mp::service::inbound_handlers";
 pub struct MempoolInboundHandlers {
     mempool: Mempool,
     outbound_nmi: OutboundMempoolServiceInterface,
+    connectivity: ConnectivityRequester,
+    offense_tracker: PeerOffenseTracker,
+    base_node: LocalNodeCommsInterface,
 }
 
 impl MempoolInboundHandlers {
     /// Construct the MempoolInboundHandlers.
-    pub fn new(mempool: Mempool, outbound_nmi: OutboundMempoolServiceInterface) -> Self {
-        Self { mempool, outbound_nmi }
+    pub fn new(
+        mempool: Mempool,
+        outbound_nmi: OutboundMempoolServiceInterface,
+        connectivity: ConnectivityRequester,
+        offense_tracker: PeerOffenseTracker,
+        base_node: LocalNodeCommsInterface,
+    ) -> Self {
+        Self {
+            mempool,
+            outbound_nmi,
+            connectivity,
+            offense_tracker,
+            base_node,
+        }
     }
 
     /// Handle inbound Mempool service requests from remote nodes and local services.
     pub async fn handle_request(&mut self, request: MempoolRequest) -> Result<MempoolResponse, MempoolServiceError> {
         debug!(target: LOG_TARGET, "Handling remote request: {}", request);
-        use MempoolRequest::{GetState, GetStats, GetTxStateByExcessSig, SubmitTransaction};
+        use MempoolRequest::{
+            GetState,
+            GetStats,
+            GetTxStateByExcessSig,
+            GetUnconfirmedTransactions,
+            RemoveTransaction,
+            SubmitTransaction,
+        };
         match request {
             GetStats => Ok(MempoolResponse::Stats(self.mempool.stats().await?)),
             GetState => Ok(MempoolResponse::State(self.mempool.state().await?)),
@@ -72,6 +99,16 @@ impl MempoolInboundHandlers {
                 );
                 Ok(MempoolResponse::TxStorage(self.submit_transaction(tx, None).await?))
             },
+            GetUnconfirmedTransactions => Ok(MempoolResponse::UnconfirmedTransactions(
+                self.mempool.unconfirmed_summaries().await?,
+            )),
+            RemoveTransaction(excess_sig) => {
+                let removed = self.mempool.remove_unconfirmed_transaction(excess_sig).await?;
+                if removed {
+                    self.update_pool_size_metrics().await;
+                }
+                Ok(MempoolResponse::TransactionRemoved(removed))
+            },
         }
     }
 
@@ -125,6 +162,21 @@ impl MempoolInboundHandlers {
                 }
                 self.update_pool_size_metrics().await;
 
+                // Orphaned, time-locked and double-spending transactions may be valid from the sender's point of view,
+                // and `NotStored` is also returned for transient failures, so only consensus failures count as offenses
+                if let Some(ref source_peer) = source_peer {
+                    if matches!(tx_storage, TxStorageResponse::NotStoredConsensus) {
+                        self.offense_tracker
+                            .report(
+                                &mut self.connectivity,
+                                source_peer.clone(),
+                                PeerOffense::InvalidTransaction,
+                                format!("Transaction {} was rejected: {}", kernel_excess_sig, tx_storage),
+                            )
+                            .await;
+                    }
+                }
+
                 debug!(
                     target: LOG_TARGET,
                     "Transaction inserted into mempool: {}, pool: {}.", kernel_excess_sig, tx_storage
@@ -152,6 +204,23 @@ impl MempoolInboundHandlers {
         }
     }
 
+    /// Processes a reorg in the mempool and publishes the reorg with the transactions that were returned to the
+    /// mempool
+    async fn process_reorg(
+        &mut self,
+        removed_blocks: Vec<Arc<Block>>,
+        added_blocks: Vec<Arc<Block>>,
+    ) -> Result<(), MempoolServiceError> {
+        let returned_txs = self
+            .mempool
+            .process_reorg(removed_blocks.clone(), added_blocks.clone())
+            .await?;
+        let event = ReorgEvent::new(&removed_blocks, &added_blocks, returned_txs);
+        debug!(target: LOG_TARGET, "{}", event);
+        self.base_node.publish_reorg_event(event);
+        Ok(())
+    }
+
     /// Handle inbound block events from the local base node service.
     pub async fn handle_block_event(&mut self, block_event: &BlockEvent) -> Result<(), MempoolServiceError> {
         use BlockEvent::{AddBlockFailed, BlockSyncComplete, BlockSyncRewind, ValidBlockAdded};
@@ -160,17 +229,15 @@ impl MempoolInboundHandlers {
                 self.mempool.process_published_block(block.clone()).await?;
             },
             ValidBlockAdded(_, BlockAddResult::ChainReorg { added, removed }) => {
-                self.mempool
-                    .process_reorg(
-                        removed.iter().map(|b| b.to_arc_block()).collect(),
-                        added.iter().map(|b| b.to_arc_block()).collect(),
-                    )
-                    .await?;
+                self.process_reorg(
+                    removed.iter().map(|b| b.to_arc_block()).collect(),
+                    added.iter().map(|b| b.to_arc_block()).collect(),
+                )
+                .await?;
             },
             ValidBlockAdded(_, _) => {},
             BlockSyncRewind(removed_blocks) => {
-                self.mempool
-                    .process_reorg(removed_blocks.iter().map(|b| b.to_arc_block()).collect(), vec![])
+                self.process_reorg(removed_blocks.iter().map(|b| b.to_arc_block()).collect(), vec![])
                     .await?;
             },
             BlockSyncComplete(tip_block) => {
@@ -24,6 +24,7 @@ use std::{convert::TryFrom, sync::Arc};
 
 use futures::{Stream, StreamExt};
 use log::*;
+use tari_comms::{bounded_executor::BoundedExecutor, connectivity::ConnectivityRequester};
 use tari_comms_dht::Dht;
 use tari_p2p::{
     comms_connector::{PeerMessage, SubscriptionFactory},
@@ -40,7 +41,7 @@ use tari_service_framework::{
 use tokio::sync::mpsc;
 
 use crate::{
-    base_node::{comms_interface::LocalNodeCommsInterface, StateMachineHandle},
+    base_node::{comms_interface::LocalNodeCommsInterface, PeerOffenseTracker, StandbyHandle, StateMachineHandle},
     mempool::{
         mempool::Mempool,
         service::{
@@ -50,6 +51,7 @@ use crate::{
             service::{MempoolService, MempoolStreams},
             MempoolHandle,
         },
+        MempoolServiceConfig,
     },
     proto,
     transactions::transaction_components::Transaction,
@@ -60,14 +62,20 @@ const SUBSCRIPTION_LABEL: &str = "Mempool";
 
 /// Initializer for the Mempool service and service future.
 pub struct MempoolServiceInitializer {
+    config: MempoolServiceConfig,
     mempool: Mempool,
     inbound_message_subscription_factory: Arc<SubscriptionFactory>,
 }
 
 impl MempoolServiceInitializer {
     /// Create a new MempoolServiceInitializer from the inbound message subscriber.
-    pub fn new(mempool: Mempool, inbound_message_subscription_factory: Arc<SubscriptionFactory>) -> Self {
+    pub fn new(
+        config: MempoolServiceConfig,
+        mempool: Mempool,
+        inbound_message_subscription_factory: Arc<SubscriptionFactory>,
+    ) -> Self {
         Self {
+            config,
             mempool,
             inbound_message_subscription_factory,
         }
@@ -126,17 +134,27 @@ impl ServiceInitializer for MempoolServiceInitializer {
         let (outbound_tx_sender, outbound_tx_stream) = mpsc::unbounded_channel();
         let (local_request_sender_service, local_request_stream) = reply_channel::unbounded();
         let outbound_mp_interface = OutboundMempoolServiceInterface::new(outbound_tx_sender);
-        let local_mp_interface = LocalMempoolService::new(local_request_sender_service);
-        let inbound_handlers = MempoolInboundHandlers::new(self.mempool.clone(), outbound_mp_interface.clone());
+        let local_mp_interface = LocalMempoolService::new(local_request_sender_service, self.mempool.event_sender());
+        let mempool = self.mempool.clone();
+        let relay_transactions = self.config.relay_transactions;
+        let max_concurrent_tx_validations = self.config.max_concurrent_tx_validations;
+        let tx_relay_suppression_window = self.config.tx_relay_suppression_window;
 
         // Register handle to OutboundMempoolServiceInterface before waiting for handles to be ready
-        context.register_handle(outbound_mp_interface);
+        context.register_handle(outbound_mp_interface.clone());
         context.register_handle(local_mp_interface);
 
         context.spawn_until_shutdown(move |handles| {
             let outbound_message_service = handles.expect_handle::<Dht>().outbound_requester();
             let state_machine = handles.expect_handle::<StateMachineHandle>();
             let base_node = handles.expect_handle::<LocalNodeCommsInterface>();
+            let inbound_handlers = MempoolInboundHandlers::new(
+                mempool,
+                outbound_mp_interface,
+                handles.expect_handle::<ConnectivityRequester>(),
+                handles.expect_handle::<PeerOffenseTracker>(),
+                base_node.clone(),
+            );
 
             let streams = MempoolStreams {
                 outbound_tx_stream,
@@ -146,7 +164,16 @@ impl ServiceInitializer for MempoolServiceInitializer {
                 request_receiver,
             };
             debug!(target: LOG_TARGET, "Mempool service started");
-            MempoolService::new(outbound_message_service, inbound_handlers, state_machine).start(streams)
+            MempoolService::new(
+                outbound_message_service,
+                inbound_handlers,
+                state_machine,
+                relay_transactions,
+                handles.expect_handle::<StandbyHandle>(),
+                BoundedExecutor::from_current(max_concurrent_tx_validations),
+                tx_relay_suppression_window,
+            )
+            .start(streams)
         });
 
         Ok(())
@@ -20,15 +20,18 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use tari_common_types::types::Signature;
+use tari_common_types::types::{PrivateKey, Signature};
 use tari_service_framework::{reply_channel::SenderService, Service};
 
 use crate::{
     mempool::{
         service::{MempoolRequest, MempoolResponse, MempoolServiceError},
+        MempoolEventReceiver,
+        MempoolEventSender,
         StateResponse,
         StatsResponse,
         TxStorageResponse,
+        UnconfirmedTxSummary,
     },
     transactions::transaction_components::Transaction,
 };
@@ -45,6 +48,7 @@ pub type LocalMempoolRequester = SenderService<MempoolRequest, Result<MempoolRes
 #[derive(Clone)]
 pub struct LocalMempoolService {
     request_sender: LocalMempoolRequester,
+    event_sender: MempoolEventSender,
 }
 
 impl LocalMempoolService {
@@ -54,8 +58,16 @@ impl LocalMempoolService {
     ///
     /// To make things a little more ergonomic, the channel handling is done for you in the other member functions,
     /// such that the request behaves like a standard future.
-    pub fn new(request_sender: LocalMempoolRequester) -> Self {
-        LocalMempoolService { request_sender }
+    pub fn new(request_sender: LocalMempoolRequester, event_sender: MempoolEventSender) -> Self {
+        LocalMempoolService {
+            request_sender,
+            event_sender,
+        }
+    }
+
+    /// Returns a stream of the transactions received by the mempool and whether each was accepted
+    pub fn get_mempool_event_stream(&self) -> MempoolEventReceiver {
+        self.event_sender.subscribe()
     }
 
     /// Returns a future that resolves to the current mempool statistics
@@ -100,13 +112,38 @@ impl LocalMempoolService {
             _ => Err(MempoolServiceError::UnexpectedApiResponse),
         }
     }
+
+    /// Returns a summary of each transaction in the unconfirmed pool
+    pub async fn get_unconfirmed_transactions(&mut self) -> Result<Vec<UnconfirmedTxSummary>, MempoolServiceError> {
+        match self
+            .request_sender
+            .call(MempoolRequest::GetUnconfirmedTransactions)
+            .await??
+        {
+            MempoolResponse::UnconfirmedTransactions(txs) => Ok(txs),
+            _ => Err(MempoolServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Evicts the unconfirmed transaction with the given kernel excess signature from the mempool. Returns true if a
+    /// transaction was removed.
+    pub async fn remove_transaction(&mut self, excess_sig: PrivateKey) -> Result<bool, MempoolServiceError> {
+        match self
+            .request_sender
+            .call(MempoolRequest::RemoveTransaction(excess_sig))
+            .await??
+        {
+            MempoolResponse::TransactionRemoved(removed) => Ok(removed),
+            _ => Err(MempoolServiceError::UnexpectedApiResponse),
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use futures::StreamExt;
     use tari_service_framework::reply_channel::{unbounded, Receiver};
-    use tokio::task;
+    use tokio::{sync::broadcast, task};
 
     use crate::mempool::{
         service::{local_service::LocalMempoolService, MempoolRequest, MempoolResponse},
@@ -122,6 +159,7 @@ mod test {
             unconfirmed_txs: 3,
             reorg_txs: 4,
             total_weight: 1000,
+            min_fee_per_gram: 0,
         }
     }
 
@@ -139,7 +177,7 @@ mod test {
     #[tokio::test]
     async fn mempool_stats() {
         let (tx, rx) = unbounded();
-        let mut service = LocalMempoolService::new(tx);
+        let mut service = LocalMempoolService::new(tx, broadcast::channel(1).0);
         task::spawn(mock_handler(rx));
         let stats = service.get_mempool_stats().await;
         let stats = stats.expect("get_mempool_stats should have succeeded");
@@ -149,7 +187,7 @@ mod test {
     #[tokio::test]
     async fn mempool_stats_from_multiple() {
         let (tx, rx) = unbounded();
-        let mut service = LocalMempoolService::new(tx);
+        let mut service = LocalMempoolService::new(tx, broadcast::channel(1).0);
         let mut service2 = service.clone();
         task::spawn(mock_handler(rx));
         let stats = service.get_mempool_stats().await;
@@ -43,6 +43,9 @@ mod outbound_interface;
 #[cfg(feature = "base_node")]
 pub use outbound_interface::OutboundMempoolServiceInterface;
 
+#[cfg(feature = "base_node")]
+mod relay_suppression;
+
 #[allow(clippy::module_inception)]
 #[cfg(feature = "base_node")]
 mod service;
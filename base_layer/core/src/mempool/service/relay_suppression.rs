@@ -0,0 +1,113 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Tracks the transactions that have recently been relayed, so that a transaction that is received from many peers in
+/// quick succession is only relayed once per window.
+pub(super) struct RelaySuppressionWindow {
+    window: Duration,
+    last_relayed: HashMap<Vec<u8>, Instant>,
+    last_pruned: Instant,
+}
+
+impl RelaySuppressionWindow {
+    /// Creates a new suppression window. A zero window disables suppression.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            last_relayed: HashMap::new(),
+            last_pruned: Instant::now(),
+        }
+    }
+
+    /// Returns true if the transaction identified by `key` should be relayed at `now`, in which case it is recorded as
+    /// relayed. Returns false if it was already relayed within the window.
+    pub fn check_and_record(&mut self, key: &[u8], now: Instant) -> bool {
+        if self.window.as_millis() == 0 {
+            return true;
+        }
+        self.prune(now);
+
+        match self.last_relayed.get(key) {
+            Some(last) if now.saturating_duration_since(*last) < self.window => false,
+            _ => {
+                self.last_relayed.insert(key.to_vec(), now);
+                true
+            },
+        }
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.last_relayed.len()
+    }
+
+    fn prune(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.last_pruned) < self.window {
+            return;
+        }
+        let window = self.window;
+        self.last_relayed
+            .retain(|_, last| now.saturating_duration_since(*last) < window);
+        self.last_pruned = now;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_suppresses_duplicates_within_the_window() {
+        let mut suppression = RelaySuppressionWindow::new(Duration::from_secs(10));
+        let now = Instant::now();
+        assert!(suppression.check_and_record(b"tx1", now));
+        assert!(!suppression.check_and_record(b"tx1", now + Duration::from_secs(1)));
+        assert!(suppression.check_and_record(b"tx2", now + Duration::from_secs(1)));
+        assert!(!suppression.check_and_record(b"tx1", now + Duration::from_secs(9)));
+        assert!(suppression.check_and_record(b"tx1", now + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn it_prunes_expired_entries() {
+        let mut suppression = RelaySuppressionWindow::new(Duration::from_secs(10));
+        let now = Instant::now();
+        assert!(suppression.check_and_record(b"tx1", now));
+        assert!(suppression.check_and_record(b"tx2", now));
+        assert_eq!(suppression.len(), 2);
+        assert!(suppression.check_and_record(b"tx3", now + Duration::from_secs(20)));
+        assert_eq!(suppression.len(), 1);
+    }
+
+    #[test]
+    fn it_is_disabled_with_a_zero_window() {
+        let mut suppression = RelaySuppressionWindow::new(Duration::from_secs(0));
+        let now = Instant::now();
+        assert!(suppression.check_and_record(b"tx1", now));
+        assert!(suppression.check_and_record(b"tx1", now));
+        assert_eq!(suppression.len(), 0);
+    }
+}
@@ -23,7 +23,10 @@
 use core::fmt::{Display, Error, Formatter};
 
 use serde::{Deserialize, Serialize};
-use tari_common_types::{types::Signature, waiting_requests::RequestKey};
+use tari_common_types::{
+    types::{PrivateKey, Signature},
+    waiting_requests::RequestKey,
+};
 use tari_utilities::hex::Hex;
 
 use crate::transactions::transaction_components::Transaction;
@@ -36,6 +39,8 @@ pub enum MempoolRequest {
     GetState,
     GetTxStateByExcessSig(Signature),
     SubmitTransaction(Transaction),
+    GetUnconfirmedTransactions,
+    RemoveTransaction(PrivateKey),
 }
 
 impl Display for MempoolRequest {
@@ -50,6 +55,10 @@ impl Display for MempoolRequest {
                 "SubmitTransaction ({})",
                 tx.body.kernels()[0].excess_sig.get_signature().to_hex()
             )),
+            MempoolRequest::GetUnconfirmedTransactions => f.write_str("GetUnconfirmedTransactions"),
+            MempoolRequest::RemoveTransaction(excess_sig) => {
+                f.write_str(&format!("RemoveTransaction ({})", excess_sig.to_hex()))
+            },
         }
     }
 }
@@ -24,7 +24,7 @@ use std::{fmt, fmt::Formatter};
 
 use tari_common_types::waiting_requests::RequestKey;
 
-use crate::mempool::{StateResponse, StatsResponse, TxStorageResponse};
+use crate::mempool::{StateResponse, StatsResponse, TxStorageResponse, UnconfirmedTxSummary};
 
 /// API Response enum for Mempool responses.
 #[derive(Clone, Debug)]
@@ -32,15 +32,19 @@ pub enum MempoolResponse {
     Stats(StatsResponse),
     State(StateResponse),
     TxStorage(TxStorageResponse),
+    UnconfirmedTransactions(Vec<UnconfirmedTxSummary>),
+    TransactionRemoved(bool),
 }
 
 impl fmt::Display for MempoolResponse {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        use MempoolResponse::{State, Stats, TxStorage};
+        use MempoolResponse::{State, Stats, TransactionRemoved, TxStorage, UnconfirmedTransactions};
         match &self {
             Stats(_) => write!(f, "Stats"),
             State(_) => write!(f, "State"),
             TxStorage(_) => write!(f, "TxStorage"),
+            UnconfirmedTransactions(_) => write!(f, "UnconfirmedTransactions"),
+            TransactionRemoved(_) => write!(f, "TransactionRemoved"),
         }
     }
 }
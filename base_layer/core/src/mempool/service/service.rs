@@ -20,11 +20,15 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{convert::TryFrom, sync::Arc};
+use std::{
+    convert::TryFrom,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use futures::{pin_mut, stream::StreamExt, Stream};
 use log::*;
-use tari_comms::peer_manager::NodeId;
+use tari_comms::{bounded_executor::BoundedExecutor, peer_manager::NodeId};
 use tari_comms_dht::{
     domain_message::OutboundDomainMessage,
     envelope::NodeDestination,
@@ -32,19 +36,24 @@ use tari_comms_dht::{
 };
 use tari_p2p::{domain_message::DomainMessage, tari_message::TariMessageType};
 use tari_service_framework::{reply_channel, reply_channel::RequestContext};
-use tari_utilities::hex::Hex;
+use tari_utilities::{hex::Hex, ByteArray};
 use tokio::{sync::mpsc, task};
 
 use crate::{
     base_node::{
         comms_interface::{BlockEvent, BlockEventReceiver},
+        StandbyHandle,
         StateMachineHandle,
     },
-    mempool::service::{
-        error::MempoolServiceError,
-        inbound_handlers::MempoolInboundHandlers,
-        MempoolRequest,
-        MempoolResponse,
+    mempool::{
+        metrics,
+        service::{
+            error::MempoolServiceError,
+            inbound_handlers::MempoolInboundHandlers,
+            relay_suppression::RelaySuppressionWindow,
+            MempoolRequest,
+            MempoolResponse,
+        },
     },
     proto,
     transactions::transaction_components::Transaction,
@@ -67,6 +76,10 @@ pub struct MempoolService {
     outbound_message_service: OutboundMessageRequester,
     inbound_handlers: MempoolInboundHandlers,
     state_machine: StateMachineHandle,
+    relay_transactions: bool,
+    standby: StandbyHandle,
+    validation_executor: BoundedExecutor,
+    relay_suppression: RelaySuppressionWindow,
 }
 
 impl MempoolService {
@@ -74,11 +87,19 @@ impl MempoolService {
         outbound_message_service: OutboundMessageRequester,
         inbound_handlers: MempoolInboundHandlers,
         state_machine: StateMachineHandle,
+        relay_transactions: bool,
+        standby: StandbyHandle,
+        validation_executor: BoundedExecutor,
+        tx_relay_suppression_window: Duration,
     ) -> Self {
         Self {
             outbound_message_service,
             inbound_handlers,
             state_machine,
+            relay_transactions,
+            standby,
+            validation_executor,
+            relay_suppression: RelaySuppressionWindow::new(tx_relay_suppression_window),
         }
     }
 
@@ -144,7 +165,31 @@ impl MempoolService {
         self.inbound_handlers.handle_request(request).await
     }
 
-    fn spawn_handle_outbound_tx(&self, tx: Arc<Transaction>, excluded_peers: Vec<NodeId>) {
+    fn spawn_handle_outbound_tx(&mut self, tx: Arc<Transaction>, excluded_peers: Vec<NodeId>) {
+        if !self.relay_transactions {
+            metrics::suppressed_transaction_relays().inc();
+            return;
+        }
+        if self.standby.is_standby() {
+            trace!(target: LOG_TARGET, "Node is in standby. Not propagating transaction");
+            metrics::suppressed_transaction_relays().inc();
+            return;
+        }
+        if let Some(excess_sig) = tx.first_kernel_excess_sig() {
+            if !self
+                .relay_suppression
+                .check_and_record(excess_sig.get_signature().as_bytes(), Instant::now())
+            {
+                trace!(
+                    target: LOG_TARGET,
+                    "Transaction {} was relayed recently. Not propagating it again.",
+                    excess_sig.get_signature().to_hex()
+                );
+                metrics::suppressed_duplicate_relays().inc();
+                return;
+            }
+        }
+        metrics::relayed_transactions().inc();
         let outbound_message_service = self.outbound_message_service.clone();
         task::spawn(async move {
             let result = handle_outbound_tx(outbound_message_service, tx, excluded_peers).await;
@@ -154,7 +199,19 @@ impl MempoolService {
         });
     }
 
+    /// Spawns a task to validate and insert the incoming transaction. If the maximum number of concurrent validations
+    /// has been reached the transaction is dropped, so that a flood of transactions cannot stall the service loop.
     fn spawn_handle_incoming_tx(&self, tx_msg: DomainMessage<Transaction>) {
+        if !self.relay_transactions {
+            trace!(
+                target: LOG_TARGET,
+                "Transaction relay is disabled. Ignoring transaction from peer `{}`",
+                tx_msg.source_peer.node_id.short_str()
+            );
+            metrics::suppressed_transaction_relays().inc();
+            return;
+        }
+
         // Determine if we are bootstrapped
         let status_watch = self.state_machine.get_status_info_watch();
 
@@ -168,7 +225,8 @@ impl MempoolService {
             return;
         }
         let inbound_handlers = self.inbound_handlers.clone();
-        task::spawn(async move {
+        let source_peer = tx_msg.source_peer.node_id.clone();
+        let result = self.validation_executor.try_spawn(async move {
             let result = handle_incoming_tx(inbound_handlers, tx_msg).await;
             if let Err(e) = result {
                 error!(
@@ -177,6 +235,14 @@ impl MempoolService {
                 );
             }
         });
+        if result.is_err() {
+            debug!(
+                target: LOG_TARGET,
+                "Maximum concurrent transaction validations reached. Dropping transaction from peer `{}`",
+                source_peer.short_str()
+            );
+            metrics::dropped_inbound_transactions().inc();
+        }
     }
 
     fn spawn_handle_local_request(
@@ -79,34 +79,47 @@ impl ServiceInitializer for MempoolSyncInitializer {
 
         let mut mdc = vec![];
         log_mdc::iter(|k, v| mdc.push((k.to_owned(), v.to_owned())));
-        context.spawn_until_shutdown(move |handles| async move {
-            log_mdc::extend(mdc.clone());
-            let state_machine = handles.expect_handle::<StateMachineHandle>();
-            let connectivity = handles.expect_handle::<ConnectivityRequester>();
-            // Ensure that we get an subscription ASAP so that we don't miss any connectivity events
-            let connectivity_event_subscription = connectivity.get_event_subscription();
+        context.spawn_restartable("mempool-sync", notif_rx, move |handles, notif_rx, mut stop_signal| {
+            let config = config.clone();
+            let mempool = mempool.clone();
+            let mdc = mdc.clone();
+            async move {
+                log_mdc::extend(mdc.clone());
+                let state_machine = handles.expect_handle::<StateMachineHandle>();
+                let connectivity = handles.expect_handle::<ConnectivityRequester>();
+                // Ensure that we get an subscription ASAP so that we don't miss any connectivity events
+                let connectivity_event_subscription = connectivity.get_event_subscription();
 
-            let mut status_watch = state_machine.get_status_info_watch();
-            if !status_watch.borrow().bootstrapped {
-                debug!(target: LOG_TARGET, "Waiting for node to bootstrap...");
-                while status_watch.changed().await.is_ok() {
-                    log_mdc::extend(mdc.clone());
-                    if status_watch.borrow().bootstrapped {
-                        debug!(target: LOG_TARGET, "Node bootstrapped. Starting mempool sync protocol");
-                        break;
+                let mut status_watch = state_machine.get_status_info_watch();
+                if !status_watch.borrow().bootstrapped {
+                    debug!(target: LOG_TARGET, "Waiting for node to bootstrap...");
+                    loop {
+                        tokio::select! {
+                            changed = status_watch.changed() => {
+                                if changed.is_err() {
+                                    break;
+                                }
+                            },
+                            _ = stop_signal.wait() => return notif_rx,
+                        }
+                        log_mdc::extend(mdc.clone());
+                        if status_watch.borrow().bootstrapped {
+                            debug!(target: LOG_TARGET, "Node bootstrapped. Starting mempool sync protocol");
+                            break;
+                        }
+                        trace!(
+                            target: LOG_TARGET,
+                            "Mempool sync still on hold, waiting for bootstrap to finish",
+                        );
+                        sleep(Duration::from_secs(1)).await;
                     }
-                    trace!(
-                        target: LOG_TARGET,
-                        "Mempool sync still on hold, waiting for bootstrap to finish",
-                    );
-                    sleep(Duration::from_secs(1)).await;
+                    log_mdc::extend(mdc.clone());
                 }
-                log_mdc::extend(mdc.clone());
-            }
 
-            MempoolSyncProtocol::new(config, notif_rx, connectivity_event_subscription, mempool)
-                .run()
-                .await;
+                MempoolSyncProtocol::new(config, notif_rx, connectivity_event_subscription, mempool)
+                    .run(stop_signal)
+                    .await
+            }
         });
 
         debug!(target: LOG_TARGET, "Mempool sync service initialized");
@@ -88,6 +88,7 @@ use tari_comms::{
     Bytes,
     PeerConnection,
 };
+use tari_shutdown::ShutdownSignal;
 use tari_utilities::{hex::Hex, ByteArray};
 use tokio::{
     io::{AsyncRead, AsyncWrite},
@@ -141,7 +142,9 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + Sync + 'static
         }
     }
 
-    pub async fn run(mut self) {
+    /// Runs the protocol until the shutdown signal is triggered. The protocol notification receiver is returned so that
+    /// the protocol can be restarted.
+    pub async fn run(mut self, mut shutdown_signal: ShutdownSignal) -> ProtocolNotificationRx<TSubstream> {
         info!(target: LOG_TARGET, "Mempool protocol handler has started");
 
         loop {
@@ -153,8 +156,15 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + Sync + 'static
                 Some(notif) = self.protocol_notifier.recv() => {
                     self.handle_protocol_notification(notif);
                 }
+
+                _ = shutdown_signal.wait() => {
+                    info!(target: LOG_TARGET, "Mempool protocol handler is shutting down");
+                    break;
+                }
             }
         }
+
+        self.protocol_notifier
     }
 
     async fn handle_connectivity_event(&mut self, event: ConnectivityEvent) {
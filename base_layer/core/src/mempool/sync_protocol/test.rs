@@ -35,6 +35,7 @@ use tari_comms::{
     Bytes,
     BytesMut,
 };
+use tari_shutdown::Shutdown;
 use tari_utilities::ByteArray;
 use tokio::{
     sync::{broadcast, mpsc},
@@ -64,7 +65,7 @@ pub fn create_transactions(n: usize) -> Vec<Transaction> {
 async fn new_mempool_with_transactions(n: usize) -> (Mempool, Vec<Transaction>) {
     let mempool = Mempool::new(
         Default::default(),
-        ConsensusManager::builder(Network::LocalNet).build(),
+        ConsensusManager::builder(Network::LocalNet).build().unwrap(),
         Box::new(MockValidator::new(true)),
     );
 
@@ -94,7 +95,10 @@ async fn setup(
         mempool.clone(),
     );
 
-    task::spawn(protocol.run());
+    task::spawn(async move {
+        let shutdown = Shutdown::new();
+        protocol.run(shutdown.to_signal()).await
+    });
 
     (protocol_notif_tx, connectivity_events_tx, mempool, transactions)
 }
@@ -62,6 +62,7 @@ impl Default for MempoolMockState {
                 unconfirmed_txs: 0,
                 reorg_txs: 0,
                 total_weight: 0,
+                min_fee_per_gram: 0,
             })),
             get_state: Arc::new(Mutex::new(StateResponse {
                 unconfirmed_pool: vec![],
@@ -125,7 +126,14 @@ impl MempoolServiceMock {
     }
 
     async fn handle_request(&self, req: MempoolRequest) -> Result<MempoolResponse, MempoolServiceError> {
-        use MempoolRequest::{GetState, GetStats, GetTxStateByExcessSig, SubmitTransaction};
+        use MempoolRequest::{
+            GetState,
+            GetStats,
+            GetTxStateByExcessSig,
+            GetUnconfirmedTransactions,
+            RemoveTransaction,
+            SubmitTransaction,
+        };
 
         self.state.inc_call_count();
         match req {
@@ -137,6 +145,8 @@ impl MempoolServiceMock {
             SubmitTransaction(_) => Ok(MempoolResponse::TxStorage(
                 self.state.submit_transaction.lock().await.clone(),
             )),
+            GetUnconfirmedTransactions => Ok(MempoolResponse::UnconfirmedTransactions(vec![])),
+            RemoveTransaction(_) => Ok(MempoolResponse::TransactionRemoved(false)),
         }
     }
 }
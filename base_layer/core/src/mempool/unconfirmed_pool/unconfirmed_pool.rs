@@ -37,6 +37,8 @@ use crate::{
     mempool::{
         priority::{FeePriority, PrioritizedTransaction},
         unconfirmed_pool::UnconfirmedPoolError,
+        TxStorageResponse,
+        UnconfirmedTxSummary,
     },
     transactions::{
         transaction_components::{Transaction, TransactionOutput},
@@ -54,28 +56,39 @@ type TransactionKey = usize;
 pub struct UnconfirmedPoolConfig {
     /// The maximum number of transactions that can be stored in the Unconfirmed Transaction pool
     pub storage_capacity: usize,
+    /// The maximum total serialized size in bytes of the transactions stored in the Unconfirmed Transaction pool. The
+    /// transactions with the lowest fee per gram are evicted to make space for higher paying transactions.
+    pub storage_max_size_bytes: u64,
     /// The maximum number of transactions that can be skipped when compiling a set of highest priority transactions,
     /// skipping over large transactions are performed in an attempt to fit more transactions into the remaining space.
     pub weight_tx_skip_count: usize,
+    /// A transaction that spends the same inputs as transactions in the pool only replaces them if its fee per gram is
+    /// at least this many percent higher than theirs, otherwise it is rejected.
+    pub replacement_fee_increase_percent: u64,
 }
 
 impl Default for UnconfirmedPoolConfig {
     fn default() -> Self {
         Self {
             storage_capacity: 40_000,
+            storage_max_size_bytes: 100 * 1024 * 1024,
             weight_tx_skip_count: 20,
+            replacement_fee_increase_percent: 10,
         }
     }
 }
 
 /// The Unconfirmed Transaction Pool consists of all unconfirmed transactions that are ready to be included in a block
-/// and they are prioritised according to the priority metric.
-/// The txs_by_signature HashMap is used to find a transaction using its excess_sig, this functionality is used to match
-/// transactions included in blocks with transactions stored in the pool. The txs_by_priority BTreeMap prioritise the
-/// transactions in the pool according to TXPriority, it allows transactions to be inserted in sorted order by their
-/// priority. The txs_by_priority BTreeMap makes it easier to select the set of highest priority transactions that can
-/// be included in a block. The excess_sig of a transaction is used a key to uniquely identify a specific transaction in
-/// these containers.
+/// and they are prioritised according to the priority metric, which is primarily the fee per gram.
+/// The pool is bounded by the number of transactions and their total size in bytes. When it is full, the lowest
+/// priority transactions are evicted to make space for higher priority transactions. The txs_by_input HashMap is used
+/// to find transactions that spend the same inputs, a new transaction only replaces them if it pays a sufficiently
+/// higher fee. The txs_by_signature HashMap is used to find a transaction using its excess_sig, this functionality is
+/// used to match transactions included in blocks with transactions stored in the pool. The txs_by_priority BTreeMap
+/// prioritise the transactions in the pool according to TXPriority, it allows transactions to be inserted in sorted
+/// order by their priority. The txs_by_priority BTreeMap makes it easier to select the set of highest priority
+/// transactions that can be included in a block. The excess_sig of a transaction is used a key to uniquely identify a
+/// specific transaction in these containers.
 pub struct UnconfirmedPool {
     config: UnconfirmedPoolConfig,
     key_counter: usize,
@@ -83,7 +96,9 @@ pub struct UnconfirmedPool {
     txs_by_signature: HashMap<PrivateKey, Vec<TransactionKey>>,
     tx_by_priority: BTreeMap<FeePriority, TransactionKey>,
     txs_by_output: HashMap<HashOutput, Vec<TransactionKey>>,
+    txs_by_input: HashMap<HashOutput, Vec<TransactionKey>>,
     txs_by_unique_id: HashMap<[u8; 32], Vec<TransactionKey>>,
+    total_size: u64,
 }
 
 // helper class to reduce type complexity
@@ -102,38 +117,84 @@ impl UnconfirmedPool {
             txs_by_signature: HashMap::new(),
             tx_by_priority: BTreeMap::new(),
             txs_by_output: HashMap::new(),
+            txs_by_input: HashMap::new(),
             txs_by_unique_id: HashMap::new(),
+            total_size: 0,
         }
     }
 
     /// Insert a new transaction into the UnconfirmedPool. Low priority transactions will be removed to make space for
-    /// higher priority transactions. The lowest priority transactions will be removed when the maximum capacity is
-    /// reached and the new transaction has a higher priority than the currently stored lowest priority transaction.
+    /// higher priority transactions. The lowest priority transactions will be removed when the maximum capacity or size
+    /// is reached and the new transaction has a higher priority than the currently stored lowest priority transaction.
+    /// Transactions that spend the same inputs as the new transaction are replaced if the new transaction pays a
+    /// sufficiently higher fee per gram, otherwise the new transaction is rejected.
     pub fn insert(
         &mut self,
         tx: Arc<Transaction>,
         dependent_outputs: Option<Vec<HashOutput>>,
         transaction_weighting: &TransactionWeight,
-    ) -> Result<(), UnconfirmedPoolError> {
+    ) -> Result<TxStorageResponse, UnconfirmedPoolError> {
         if tx
             .body
             .kernels()
             .iter()
             .all(|k| self.txs_by_signature.contains_key(k.excess_sig.get_signature()))
         {
-            return Ok(());
+            return Ok(TxStorageResponse::UnconfirmedPool);
         }
 
         let new_key = self.get_next_key();
         let prioritized_tx = PrioritizedTransaction::new(new_key, transaction_weighting, tx, dependent_outputs);
-        if self.tx_by_key.len() >= self.config.storage_capacity {
-            if prioritized_tx.priority < *self.lowest_priority() {
-                return Ok(());
+
+        let conflicting_keys = self.find_conflicting_transactions(&prioritized_tx);
+        if let Some(conflicting_tx) = conflicting_keys
+            .iter()
+            .filter_map(|key| self.tx_by_key.get(key))
+            .find(|ptx| !self.is_sufficient_replacement(&prioritized_tx, ptx))
+        {
+            debug!(
+                target: LOG_TARGET,
+                "Rejecting transaction {} because it spends the same inputs as transaction {} without paying a \
+                 sufficiently higher fee",
+                prioritized_tx,
+                conflicting_tx
+            );
+            return Ok(TxStorageResponse::NotStoredFeeTooLow);
+        }
+
+        let keys_to_evict = match self.find_transactions_to_evict(&prioritized_tx, &conflicting_keys) {
+            Some(keys) => keys,
+            None => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Rejecting transaction {} because the unconfirmed pool is full and its fee is too low",
+                    prioritized_tx
+                );
+                return Ok(TxStorageResponse::NotStoredFeeTooLow);
+            },
+        };
+
+        for key in conflicting_keys {
+            if let Some(replaced_tx) = self.remove_transaction(key) {
+                debug!(
+                    target: LOG_TARGET,
+                    "Transaction {} replaced by {}",
+                    replaced_tx
+                        .first_kernel_excess_sig()
+                        .map(|sig| sig.get_signature().to_hex())
+                        .unwrap_or_else(|| "No kernels!".to_string()),
+                    prioritized_tx
+                );
             }
-            self.remove_lowest_priority_tx();
+        }
+        for key in keys_to_evict {
+            self.remove_transaction(key);
         }
 
         self.tx_by_priority.insert(prioritized_tx.priority.clone(), new_key);
+        for input in prioritized_tx.transaction.body.inputs() {
+            self.txs_by_input.entry(input.output_hash()).or_default().push(new_key);
+        }
         for output in prioritized_tx.transaction.body.outputs() {
             self.txs_by_output.entry(output.hash()).or_default().push(new_key);
 
@@ -150,9 +211,122 @@ impl UnconfirmedPool {
             target: LOG_TARGET,
             "Inserted transaction {} into unconfirmed pool:", prioritized_tx
         );
+        self.total_size += prioritized_tx.size;
         self.tx_by_key.insert(new_key, prioritized_tx);
 
-        Ok(())
+        Ok(TxStorageResponse::UnconfirmedPool)
+    }
+
+    /// Returns the keys of all transactions in the pool that spend at least one of the inputs of the given transaction
+    fn find_conflicting_transactions(&self, transaction: &PrioritizedTransaction) -> Vec<TransactionKey> {
+        let mut keys = transaction
+            .transaction
+            .body
+            .inputs()
+            .iter()
+            .filter_map(|input| self.txs_by_input.get(&input.output_hash()))
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    /// Returns true if the replacement transaction pays a fee per gram that is at least
+    /// `replacement_fee_increase_percent` higher than the existing transaction
+    fn is_sufficient_replacement(
+        &self,
+        replacement: &PrioritizedTransaction,
+        existing: &PrioritizedTransaction,
+    ) -> bool {
+        let replacement_fee = u128::from(replacement.transaction.body.get_total_fee().as_u64());
+        let existing_fee = u128::from(existing.transaction.body.get_total_fee().as_u64());
+        // Cross multiply to compare the fee per gram of both transactions without losing precision
+        replacement_fee * u128::from(existing.weight) * 100 >=
+            existing_fee *
+                u128::from(replacement.weight) *
+                (100 + u128::from(self.config.replacement_fee_increase_percent))
+    }
+
+    /// Returns the keys of the lowest priority transactions that have to be evicted so that the given transaction fits
+    /// into the pool, or None if the transaction does not have a high enough priority to be stored. Transactions that
+    /// are excluded are about to be removed and are therefore never evicted.
+    fn find_transactions_to_evict(
+        &self,
+        transaction: &PrioritizedTransaction,
+        excluded: &[TransactionKey],
+    ) -> Option<Vec<TransactionKey>> {
+        if transaction.size > self.config.storage_max_size_bytes {
+            return None;
+        }
+        let excluded_size = excluded
+            .iter()
+            .filter_map(|key| self.tx_by_key.get(key))
+            .map(|ptx| ptx.size)
+            .sum::<u64>();
+        let mut num_txs = self.tx_by_key.len() - excluded.len();
+        let mut total_size = self.total_size - excluded_size;
+        let fits = |num_txs: usize, total_size: u64| {
+            num_txs < self.config.storage_capacity &&
+                total_size + transaction.size <= self.config.storage_max_size_bytes
+        };
+
+        let mut keys_to_evict = Vec::new();
+        for (priority, key) in &self.tx_by_priority {
+            if fits(num_txs, total_size) {
+                break;
+            }
+            if excluded.contains(key) {
+                continue;
+            }
+            if *priority >= transaction.priority {
+                return None;
+            }
+            let ptx = self.tx_by_key.get(key)?;
+            num_txs -= 1;
+            total_size -= ptx.size;
+            keys_to_evict.push(*key);
+        }
+
+        if fits(num_txs, total_size) {
+            Some(keys_to_evict)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the lowest fee per gram that a new transaction must pay to be accepted into the pool. This is zero while
+    /// the pool has space, otherwise the fee per gram has to exceed that of the lowest priority transaction.
+    pub fn min_fee_per_gram(&self) -> u64 {
+        if !self.is_full() {
+            return 0;
+        }
+        self.tx_by_priority
+            .values()
+            .next()
+            .and_then(|key| self.tx_by_key.get(key))
+            .map(|ptx| {
+                ptx.transaction
+                    .body
+                    .get_total_fee()
+                    .as_u64()
+                    .checked_div(ptx.weight)
+                    .unwrap_or(0) +
+                    1
+            })
+            .unwrap_or(0)
+    }
+
+    /// Returns true if the pool has reached its maximum number of transactions or cannot fit another transaction of
+    /// average size
+    fn is_full(&self) -> bool {
+        let num_txs = self.tx_by_key.len();
+        if num_txs == 0 {
+            return false;
+        }
+        let average_size = self.total_size / num_txs as u64;
+        num_txs >= self.config.storage_capacity || self.total_size + average_size > self.config.storage_max_size_bytes
     }
 
     /// TThis will search the unconfirmed pool for the set of outputs and return true if all of them are found
@@ -371,24 +545,13 @@ impl UnconfirmedPool {
         false
     }
 
-    fn lowest_priority(&self) -> &FeePriority {
-        self.tx_by_priority
-            .keys()
-            .next()
-            .expect("lowest_priority called on empty mempool")
-    }
-
-    fn remove_lowest_priority_tx(&mut self) {
-        if let Some(tx_key) = self.tx_by_priority.values().next().copied() {
-            self.remove_transaction(tx_key);
-        }
-    }
-
     /// Remove all current mempool transactions from the UnconfirmedPoolStorage, returning that which have been removed
     pub fn drain_all_mempool_transactions(&mut self) -> Vec<Arc<Transaction>> {
         self.txs_by_signature.clear();
         self.tx_by_priority.clear();
         self.txs_by_output.clear();
+        self.txs_by_input.clear();
+        self.total_size = 0;
         self.tx_by_key.drain().map(|(_, val)| val.transaction).collect()
     }
 
@@ -467,6 +630,19 @@ impl UnconfirmedPool {
         let prioritized_transaction = self.tx_by_key.remove(&tx_key)?;
 
         self.tx_by_priority.remove(&prioritized_transaction.priority);
+        self.total_size -= prioritized_transaction.size;
+
+        for input in prioritized_transaction.transaction.body.inputs() {
+            let input_hash = input.output_hash();
+            if let Some(keys) = self.txs_by_input.get_mut(&input_hash) {
+                if let Some(pos) = keys.iter().position(|k| *k == tx_key) {
+                    keys.remove(pos);
+                }
+                if keys.is_empty() {
+                    self.txs_by_input.remove(&input_hash);
+                }
+            }
+        }
 
         for kernel in prioritized_transaction.transaction.body.kernels() {
             let sig = kernel.excess_sig.get_signature();
@@ -535,6 +711,33 @@ impl UnconfirmedPool {
         self.tx_by_key.iter().map(|(_, ptx)| ptx.transaction.clone()).collect()
     }
 
+    /// Returns a summary of each transaction stored in the UnconfirmedPool.
+    pub fn summaries(&self) -> Vec<UnconfirmedTxSummary> {
+        self.tx_by_key
+            .values()
+            .map(|ptx| UnconfirmedTxSummary {
+                excess_sig: ptx.transaction.first_kernel_excess_sig().cloned().unwrap_or_default(),
+                fee: ptx.transaction.body.get_total_fee(),
+                weight: ptx.weight,
+                age: ptx.inserted_at.elapsed(),
+                num_inputs: ptx.transaction.body.inputs().len(),
+                num_outputs: ptx.transaction.body.outputs().len(),
+                num_kernels: ptx.transaction.body.kernels().len(),
+            })
+            .collect()
+    }
+
+    /// Removes all transactions that contain a kernel with the given excess signature and returns the removed
+    /// transactions. Transactions that depend on the outputs of a removed transaction are re-validated the next time
+    /// transactions are retrieved from the pool.
+    pub fn remove_by_excess_sig(&mut self, excess_sig: &PrivateKey) -> Vec<Arc<Transaction>> {
+        let tx_keys = self.txs_by_signature.get(excess_sig).cloned().unwrap_or_default();
+        tx_keys
+            .into_iter()
+            .filter_map(|tx_key| self.remove_transaction(tx_key))
+            .collect()
+    }
+
     /// Returns the total weight of all transactions stored in the pool.
     pub fn calculate_weight(&self, transaction_weight: &TransactionWeight) -> u64 {
         self.tx_by_key.values().fold(0, |weight, ptx| {
@@ -555,6 +758,10 @@ impl UnconfirmedPool {
             self.txs_by_output
                 .values()
                 .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key))) &&
+            self.txs_by_input
+                .values()
+                .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key))) &&
+            self.tx_by_key.values().map(|ptx| ptx.size).sum::<u64>() == self.total_size &&
             self.txs_by_unique_id
                 .values()
                 .all(|tx_keys| tx_keys.iter().all(|tx_key| self.tx_by_key.contains_key(tx_key)))
@@ -580,6 +787,7 @@ impl UnconfirmedPool {
         let (old, new) = shrink_hashmap(&mut self.tx_by_key);
         shrink_hashmap(&mut self.txs_by_signature);
         shrink_hashmap(&mut self.txs_by_output);
+        shrink_hashmap(&mut self.txs_by_input);
         shrink_hashmap(&mut self.txs_by_unique_id);
 
         if old - new > 0 {
@@ -666,6 +874,7 @@ mod test {
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 4,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
 
         let tx_weight = TransactionWeight::latest();
@@ -695,6 +904,37 @@ mod test {
         assert!(unconfirmed_pool.check_data_consistency());
     }
 
+    #[test]
+    fn test_remove_by_excess_sig() {
+        let tx1 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(5), inputs: 2, outputs: 1).0);
+        let tx2 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(20), inputs: 2, outputs: 1).0);
+
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig::default());
+        let tx_weight = TransactionWeight::latest();
+        unconfirmed_pool
+            .insert_many([tx1.clone(), tx2.clone()], &tx_weight)
+            .unwrap();
+
+        let summaries = unconfirmed_pool.summaries();
+        assert_eq!(summaries.len(), 2);
+        let summary = summaries
+            .iter()
+            .find(|s| s.excess_sig == tx2.body.kernels()[0].excess_sig)
+            .unwrap();
+        assert_eq!(summary.fee, tx2.body.get_total_fee());
+        assert_eq!(summary.weight, tx2.calculate_weight(&tx_weight));
+        assert_eq!(summary.num_inputs, 2);
+
+        let removed = unconfirmed_pool.remove_by_excess_sig(tx2.body.kernels()[0].excess_sig.get_signature());
+        assert_eq!(removed, vec![tx2.clone()]);
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx1.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx2.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool
+            .remove_by_excess_sig(tx2.body.kernels()[0].excess_sig.get_signature())
+            .is_empty());
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
     #[test]
     fn test_double_spend_inputs() {
         let (tx1, _, _) = tx!(MicroTari(5_000), fee: MicroTari(10), inputs: 1, outputs: 1);
@@ -747,13 +987,19 @@ mod test {
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 4,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
 
         let tx_weight = TransactionWeight::latest();
         unconfirmed_pool
-            .insert_many(vec![tx1.clone(), tx2.clone(), tx3.clone()], &tx_weight)
+            .insert_many(vec![tx1.clone(), tx2.clone()], &tx_weight)
             .unwrap();
-        assert_eq!(unconfirmed_pool.len(), 3);
+        // tx3 pays the same fee per gram as tx2, so it cannot replace it
+        assert_eq!(
+            unconfirmed_pool.insert(tx3.clone(), None, &tx_weight).unwrap(),
+            TxStorageResponse::NotStoredFeeTooLow
+        );
+        assert_eq!(unconfirmed_pool.len(), 2);
 
         let desired_weight = tx1.calculate_weight(&tx_weight) +
             tx2.calculate_weight(&tx_weight) +
@@ -761,15 +1007,85 @@ mod test {
             1000;
         let results = unconfirmed_pool.fetch_highest_priority_txs(desired_weight).unwrap();
         assert!(results.retrieved_transactions.contains(&tx1));
-        // Whether tx2 or tx3 is selected is non-deterministic
-        assert!(results.retrieved_transactions.contains(&tx2) ^ results.retrieved_transactions.contains(&tx3));
+        assert!(results.retrieved_transactions.contains(&tx2));
+        assert!(!results.retrieved_transactions.contains(&tx3));
         assert_eq!(results.retrieved_transactions.len(), 2);
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[test]
+    fn test_replace_transaction_with_higher_fee() {
+        let tx1 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(10), inputs: 2, outputs: 1).0);
+        let mut tx2 = tx!(MicroTari(5_000), fee: MicroTari(10), inputs: 2, outputs: 1).0;
+        let mut tx3 = tx!(MicroTari(5_000), fee: MicroTari(20), inputs: 2, outputs: 1).0;
+        // tx2 and tx3 both spend an input of tx1
+        tx2.body.inputs_mut()[0] = tx1.body.inputs()[0].clone();
+        tx3.body.inputs_mut()[1] = tx1.body.inputs()[1].clone();
+        let tx2 = Arc::new(tx2);
+        let tx3 = Arc::new(tx3);
+
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig::default());
+        assert_eq!(
+            unconfirmed_pool.insert(tx1.clone(), None, &tx_weight).unwrap(),
+            TxStorageResponse::UnconfirmedPool
+        );
+        assert_eq!(
+            unconfirmed_pool.insert(tx2.clone(), None, &tx_weight).unwrap(),
+            TxStorageResponse::NotStoredFeeTooLow
+        );
+        assert_eq!(
+            unconfirmed_pool.insert(tx3.clone(), None, &tx_weight).unwrap(),
+            TxStorageResponse::UnconfirmedPool
+        );
+
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx1.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx2.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx3.body.kernels()[0].excess_sig));
+        assert_eq!(unconfirmed_pool.len(), 1);
+        assert!(unconfirmed_pool.check_data_consistency());
+    }
+
+    #[test]
+    fn test_evict_lowest_fee_txs_when_size_limit_reached() {
+        let tx1 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(5), inputs: 2, outputs: 1).0);
+        let tx2 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(10), inputs: 2, outputs: 1).0);
+        let tx3 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(20), inputs: 2, outputs: 1).0);
+        let tx4 = Arc::new(tx!(MicroTari(5_000), fee: MicroTari(40), inputs: 2, outputs: 1).0);
+
+        let tx_size = bincode::serialized_size(&*tx1).unwrap();
+        let tx_weight = TransactionWeight::latest();
+        let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
+            storage_max_size_bytes: tx_size * 2 + tx_size / 2,
+            ..Default::default()
+        });
+        assert_eq!(unconfirmed_pool.min_fee_per_gram(), 0);
+
+        unconfirmed_pool
+            .insert_many([tx2.clone(), tx3.clone()], &tx_weight)
+            .unwrap();
+        // The pool is full and tx1 pays less than every transaction in it
+        assert!(unconfirmed_pool.min_fee_per_gram() > 0);
+        assert_eq!(
+            unconfirmed_pool.insert(tx1.clone(), None, &tx_weight).unwrap(),
+            TxStorageResponse::NotStoredFeeTooLow
+        );
+        assert_eq!(
+            unconfirmed_pool.insert(tx4.clone(), None, &tx_weight).unwrap(),
+            TxStorageResponse::UnconfirmedPool
+        );
+
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx1.body.kernels()[0].excess_sig));
+        assert!(!unconfirmed_pool.has_tx_with_excess_sig(&tx2.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx3.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.has_tx_with_excess_sig(&tx4.body.kernels()[0].excess_sig));
+        assert!(unconfirmed_pool.check_data_consistency());
     }
 
     #[test]
     fn test_remove_reorg_txs() {
         let network = Network::LocalNet;
-        let consensus = ConsensusManagerBuilder::new(network).build();
+        let consensus = ConsensusManagerBuilder::new(network).build().unwrap();
         let tx1 = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(50), inputs:2, outputs: 1).0);
         let tx2 = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(20), inputs:3, outputs: 1).0);
         let tx3 = Arc::new(tx!(MicroTari(10_000), fee: MicroTari(100), inputs:2, outputs: 1).0);
@@ -781,6 +1097,7 @@ mod test {
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
         unconfirmed_pool
             .insert_many(
@@ -831,6 +1148,7 @@ mod test {
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
         unconfirmed_pool
             .insert_many(
@@ -866,13 +1184,16 @@ mod test {
         let (tx1, _, _) = tx!(MicroTari(150_000), fee: MicroTari(50), inputs:5, outputs:5);
         let (tx2, _, _) = tx!(MicroTari(250_000), fee: MicroTari(50), inputs:5, outputs:5);
 
-        // Create transactions with duplicate kernels (will not pass internal validation, but that is ok)
+        // Create transactions with duplicate outputs (will not pass internal validation, but that is ok)
         let mut tx3 = tx1.clone();
         let mut tx4 = tx2.clone();
         let (tx5, _, _) = tx!(MicroTari(350_000), fee: MicroTari(50), inputs:5, outputs:5);
         let (tx6, _, _) = tx!(MicroTari(450_000), fee: MicroTari(50), inputs:5, outputs:5);
         tx3.body.set_kernel(tx5.body.kernels()[0].clone());
         tx4.body.set_kernel(tx6.body.kernels()[0].clone());
+        // Use different inputs so that the transactions are not replacements of each other
+        tx3.body.inputs_mut().clone_from(tx5.body.inputs());
+        tx4.body.inputs_mut().clone_from(tx6.body.inputs());
 
         // Insert multiple transactions with the same outputs into the mempool
 
@@ -880,6 +1201,7 @@ mod test {
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
         let txns = vec![
             Arc::new(tx1.clone()),
@@ -967,6 +1289,7 @@ mod test {
         let mut unconfirmed_pool = UnconfirmedPool::new(UnconfirmedPoolConfig {
             storage_capacity: 10,
             weight_tx_skip_count: 3,
+            ..Default::default()
         });
 
         let tx1 = Arc::new(tx1);
@@ -0,0 +1,306 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Offline transaction graph privacy analysis.
+//!
+//! Computes linkability metrics over a range of blocks from the local chain database. The report only contains
+//! aggregate counts and histograms, no commitments, keys or other data that identifies an output or its owner, so that
+//! reports can be shared to measure the practical privacy of the protocol over time.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tari_script::{Opcode, TariScript};
+use tari_utilities::ByteArray;
+
+use crate::transactions::aggregated_body::AggregateBody;
+
+/// The (inclusive) upper bounds of the one-sided recipient cluster size histogram buckets. The last bucket has no upper
+/// bound.
+const CLUSTER_SIZE_BUCKETS: [u64; 5] = [1, 2, 5, 10, 50];
+/// The (inclusive) upper bounds, in blocks, of the spend age histogram buckets. The last bucket has no upper bound.
+const SPEND_AGE_BUCKETS: [u64; 5] = [10, 100, 1_000, 10_000, 100_000];
+
+/// Linkability metrics for a range of blocks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LinkabilityReport {
+    pub start_height: u64,
+    pub end_height: u64,
+    pub num_blocks: u64,
+    /// The number of non-coinbase kernels
+    pub num_transactions: u64,
+    pub num_inputs: u64,
+    pub num_outputs: u64,
+    /// Blocks containing exactly one transaction. The inputs and outputs of these blocks are trivially linked.
+    pub single_transaction_blocks: u64,
+    /// The mean number of transactions in blocks that contain at least one transaction
+    pub mean_transactions_per_block: f64,
+    pub one_sided: OneSidedStats,
+    /// Outputs with a script other than the default or a one-sided payment script
+    pub custom_script_outputs: u64,
+    /// Outputs that share a sender offset public key with another output
+    pub reused_sender_offset_outputs: u64,
+    pub spend_age: SpendAgeStats,
+}
+
+/// One-sided payment scripts reveal the recipient's public key. Outputs paid to the same key are clustered.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OneSidedStats {
+    pub num_outputs: u64,
+    /// The number of distinct recipient keys
+    pub num_recipients: u64,
+    /// The number of recipient keys that received more than one output
+    pub num_reused_recipients: u64,
+    /// The number of outputs paid to a recipient key that received more than one output
+    pub outputs_to_reused_recipients: u64,
+    pub largest_cluster: u64,
+    pub cluster_size_histogram: Vec<HistogramBucket>,
+}
+
+/// The number of blocks between the creation and the spending of outputs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpendAgeStats {
+    pub histogram: Vec<HistogramBucket>,
+    /// Inputs spending coinbase outputs created in the analysed range
+    pub coinbase_spends: u64,
+    /// Inputs spending outputs created before the analysed range
+    pub unknown: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistogramBucket {
+    /// The inclusive upper bound of this bucket, or None for the last bucket
+    pub upper_bound: Option<u64>,
+    pub count: u64,
+}
+
+/// Accumulates linkability metrics from blocks. Blocks should be added in ascending height order, so that the outputs
+/// spent by later blocks are known.
+#[derive(Debug, Default)]
+pub struct LinkabilityAnalyzer {
+    report: LinkabilityReport,
+    non_empty_blocks: u64,
+    /// Creation height and coinbase flag of each output, keyed by commitment
+    output_heights: HashMap<Vec<u8>, (u64, bool)>,
+    one_sided_recipients: HashMap<Vec<u8>, u64>,
+    sender_offset_keys: HashMap<Vec<u8>, u64>,
+    spend_ages: Vec<u64>,
+}
+
+impl LinkabilityAnalyzer {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add the body of the block at the given height to the analysis
+    pub fn add_block(&mut self, height: u64, body: &AggregateBody) {
+        let num_transactions = body.kernels().iter().filter(|k| !k.is_coinbase()).count() as u64;
+        self.record_block(height, num_transactions);
+        for output in body.outputs() {
+            self.record_output(
+                height,
+                output.commitment.as_bytes(),
+                &output.script,
+                output.sender_offset_public_key.as_bytes(),
+                output.is_coinbase(),
+            );
+        }
+        for input in body.inputs() {
+            match input.commitment() {
+                Ok(commitment) => self.record_input(height, commitment.as_bytes()),
+                Err(_) => self.report.spend_age.unknown += 1,
+            }
+            self.report.num_inputs += 1;
+        }
+    }
+
+    /// Consume the analyzer and return the report
+    pub fn finish(mut self) -> LinkabilityReport {
+        if self.non_empty_blocks > 0 {
+            self.report.mean_transactions_per_block =
+                self.report.num_transactions as f64 / self.non_empty_blocks as f64;
+        }
+
+        let one_sided = &mut self.report.one_sided;
+        one_sided.num_recipients = self.one_sided_recipients.len() as u64;
+        let reused = self.one_sided_recipients.values().filter(|n| **n > 1);
+        one_sided.num_reused_recipients = reused.clone().count() as u64;
+        one_sided.outputs_to_reused_recipients = reused.sum();
+        one_sided.largest_cluster = self.one_sided_recipients.values().copied().max().unwrap_or(0);
+        one_sided.cluster_size_histogram =
+            histogram(&CLUSTER_SIZE_BUCKETS, self.one_sided_recipients.values().copied());
+
+        self.report.reused_sender_offset_outputs = self.sender_offset_keys.values().filter(|n| **n > 1).sum();
+        self.report.spend_age.histogram = histogram(&SPEND_AGE_BUCKETS, self.spend_ages.iter().copied());
+
+        self.report
+    }
+
+    fn record_block(&mut self, height: u64, num_transactions: u64) {
+        if self.report.num_blocks == 0 {
+            self.report.start_height = height;
+        }
+        self.report.end_height = height;
+        self.report.num_blocks += 1;
+        self.report.num_transactions += num_transactions;
+        if num_transactions > 0 {
+            self.non_empty_blocks += 1;
+        }
+        if num_transactions == 1 {
+            self.report.single_transaction_blocks += 1;
+        }
+    }
+
+    fn record_output(
+        &mut self,
+        height: u64,
+        commitment: &[u8],
+        script: &TariScript,
+        sender_offset_public_key: &[u8],
+        is_coinbase: bool,
+    ) {
+        self.report.num_outputs += 1;
+        self.output_heights.insert(commitment.to_vec(), (height, is_coinbase));
+        *self
+            .sender_offset_keys
+            .entry(sender_offset_public_key.to_vec())
+            .or_insert(0) += 1;
+
+        match script.opcodes() {
+            [Opcode::Nop] | [] => {},
+            [Opcode::PushPubKey(recipient)] => {
+                self.report.one_sided.num_outputs += 1;
+                *self
+                    .one_sided_recipients
+                    .entry(recipient.as_bytes().to_vec())
+                    .or_insert(0) += 1;
+            },
+            _ => self.report.custom_script_outputs += 1,
+        }
+    }
+
+    fn record_input(&mut self, height: u64, commitment: &[u8]) {
+        match self.output_heights.remove(commitment) {
+            Some((created_height, is_coinbase)) => {
+                self.spend_ages.push(height.saturating_sub(created_height));
+                if is_coinbase {
+                    self.report.spend_age.coinbase_spends += 1;
+                }
+            },
+            None => self.report.spend_age.unknown += 1,
+        }
+    }
+}
+
+fn histogram<I: Iterator<Item = u64>>(upper_bounds: &[u64], values: I) -> Vec<HistogramBucket> {
+    let mut buckets = upper_bounds
+        .iter()
+        .map(|b| HistogramBucket {
+            upper_bound: Some(*b),
+            count: 0,
+        })
+        .chain(Some(HistogramBucket {
+            upper_bound: None,
+            count: 0,
+        }))
+        .collect::<Vec<_>>();
+
+    for value in values {
+        let pos = upper_bounds
+            .iter()
+            .position(|b| value <= *b)
+            .unwrap_or(upper_bounds.len());
+        buckets[pos].count += 1;
+    }
+
+    buckets
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::OsRng;
+    use tari_common_types::types::{PrivateKey, PublicKey};
+    use tari_crypto::keys::{PublicKey as PublicKeyTrait, SecretKey};
+    use tari_script::script;
+
+    use super::*;
+
+    fn random_public_key() -> PublicKey {
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng))
+    }
+
+    #[test]
+    fn it_clusters_one_sided_outputs_by_recipient() {
+        let mut analyzer = LinkabilityAnalyzer::new();
+        let recipient = random_public_key();
+        let one_sided = script!(PushPubKey(Box::new(recipient)));
+        analyzer.record_block(1, 3);
+        analyzer.record_output(1, &[1], &one_sided, random_public_key().as_bytes(), false);
+        analyzer.record_output(1, &[2], &one_sided, random_public_key().as_bytes(), false);
+        analyzer.record_output(
+            1,
+            &[3],
+            &script!(PushPubKey(Box::new(random_public_key()))),
+            random_public_key().as_bytes(),
+            false,
+        );
+        analyzer.record_output(1, &[4], &script!(Nop), random_public_key().as_bytes(), false);
+
+        let report = analyzer.finish();
+        assert_eq!(report.num_outputs, 4);
+        assert_eq!(report.one_sided.num_outputs, 3);
+        assert_eq!(report.one_sided.num_recipients, 2);
+        assert_eq!(report.one_sided.num_reused_recipients, 1);
+        assert_eq!(report.one_sided.outputs_to_reused_recipients, 2);
+        assert_eq!(report.one_sided.largest_cluster, 2);
+        assert_eq!(report.one_sided.cluster_size_histogram[0].count, 1);
+        assert_eq!(report.one_sided.cluster_size_histogram[1].count, 1);
+        assert_eq!(report.custom_script_outputs, 0);
+        assert_eq!(report.reused_sender_offset_outputs, 0);
+    }
+
+    #[test]
+    fn it_records_spend_ages() {
+        let mut analyzer = LinkabilityAnalyzer::new();
+        let sender_offset = random_public_key();
+        analyzer.record_block(10, 1);
+        analyzer.record_output(10, &[1], &script!(Nop), sender_offset.as_bytes(), true);
+        analyzer.record_output(10, &[2], &script!(Nop), sender_offset.as_bytes(), false);
+        analyzer.record_block(20, 2);
+        analyzer.record_input(20, &[1]);
+        analyzer.record_input(20, &[3]);
+        analyzer.record_block(500, 0);
+        analyzer.record_input(500, &[2]);
+
+        let report = analyzer.finish();
+        assert_eq!(report.start_height, 10);
+        assert_eq!(report.end_height, 500);
+        assert_eq!(report.num_blocks, 3);
+        assert_eq!(report.single_transaction_blocks, 1);
+        assert!((report.mean_transactions_per_block - 1.5).abs() < f64::EPSILON);
+        assert_eq!(report.reused_sender_offset_outputs, 2);
+        assert_eq!(report.spend_age.histogram[0].count, 1);
+        assert_eq!(report.spend_age.histogram[2].count, 1);
+        assert_eq!(report.spend_age.coinbase_spends, 1);
+        assert_eq!(report.spend_age.unknown, 1);
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::collections::HashMap;
+
+use log::*;
+use rayon::prelude::*;
+
+use crate::{
+    blocks::BlockHeader,
+    proof_of_work::{
+        monero_difficulty,
+        monero_rx::MoneroPowData,
+        randomx_factory::RandomXFactory,
+        sha3_difficulty,
+        sha3x_difficulty,
+        Difficulty,
+        PowAlgorithm,
+        PowError,
+    },
+};
+
+const LOG_TARGET: &str = "c::pow::batch_verification";
+
+/// Calculates the achieved difficulty of each of the given headers, returning the results in the same order as the
+/// headers.
+///
+/// Sha3 and Sha3x hashes are independent of one another and are calculated in parallel across all available CPU cores.
+/// RandomX VMs are expensive to initialize and are cached per seed by the `RandomXFactory`, so Monero headers are
+/// grouped by RandomX seed and each group is hashed serially (alongside the Sha3 headers) to avoid thrashing the VM
+/// cache.
+pub fn calculate_achieved_difficulties(
+    headers: &[BlockHeader],
+    randomx_factory: &RandomXFactory,
+) -> Vec<Result<Difficulty, PowError>> {
+    let (monero_headers, sha_headers) = headers
+        .iter()
+        .enumerate()
+        .partition::<Vec<_>, _>(|(_, header)| header.pow_algo() == PowAlgorithm::Monero);
+
+    let (sha_results, monero_results) = rayon::join(
+        || {
+            sha_headers
+                .into_par_iter()
+                .map(|(index, header)| (index, Ok(calculate_sha_difficulty(header))))
+                .collect::<Vec<_>>()
+        },
+        || calculate_monero_difficulties(monero_headers, randomx_factory),
+    );
+
+    let mut results = (0..headers.len()).map(|_| None).collect::<Vec<_>>();
+    for (index, result) in sha_results.into_iter().chain(monero_results) {
+        results[index] = Some(result);
+    }
+    results
+        .into_iter()
+        .map(|result| result.expect("every header index is assigned a result"))
+        .collect()
+}
+
+fn calculate_sha_difficulty(header: &BlockHeader) -> Difficulty {
+    match header.pow_algo() {
+        PowAlgorithm::Sha3x => sha3x_difficulty(header),
+        _ => sha3_difficulty(header),
+    }
+}
+
+fn calculate_monero_difficulties(
+    headers: Vec<(usize, &BlockHeader)>,
+    randomx_factory: &RandomXFactory,
+) -> Vec<(usize, Result<Difficulty, PowError>)> {
+    let mut results = Vec::with_capacity(headers.len());
+    // Group by seed, preserving the order in which each seed was first seen
+    let mut seed_order = Vec::new();
+    let mut groups = HashMap::<Vec<u8>, Vec<(usize, &BlockHeader)>>::new();
+    for (index, header) in headers {
+        match MoneroPowData::from_header(header) {
+            Ok(pow_data) => {
+                let seed = pow_data.randomx_key().to_vec();
+                if !groups.contains_key(&seed) {
+                    seed_order.push(seed.clone());
+                }
+                groups.entry(seed).or_insert_with(Vec::new).push((index, header));
+            },
+            Err(err) => results.push((index, Err(PowError::from(err)))),
+        }
+    }
+
+    for seed in seed_order {
+        let group = groups.remove(&seed).unwrap_or_default();
+        debug!(
+            target: LOG_TARGET,
+            "Verifying {} RandomX header(s) with the same seed",
+            group.len()
+        );
+        for (index, header) in group {
+            results.push((
+                index,
+                monero_difficulty(header, randomx_factory).map_err(PowError::from),
+            ));
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::proof_of_work::sha3_test::get_header;
+
+    #[test]
+    fn it_calculates_difficulties_in_order() {
+        let headers = (0..50u64)
+            .map(|nonce| {
+                let mut header = get_header();
+                header.nonce = nonce;
+                if nonce % 2 == 0 {
+                    header.pow.pow_algo = PowAlgorithm::Sha3x;
+                }
+                header
+            })
+            .collect::<Vec<_>>();
+
+        let results = calculate_achieved_difficulties(&headers, &RandomXFactory::default());
+        assert_eq!(results.len(), headers.len());
+        for (header, result) in headers.iter().zip(results) {
+            assert_eq!(result.unwrap(), calculate_sha_difficulty(header));
+        }
+    }
+
+    #[test]
+    fn it_returns_an_error_for_invalid_monero_data() {
+        let mut monero_header = get_header();
+        monero_header.pow.pow_algo = PowAlgorithm::Monero;
+        monero_header.pow.pow_data = vec![1, 2, 3];
+        let headers = vec![get_header(), monero_header, get_header()];
+
+        let results = calculate_achieved_difficulties(&headers, &RandomXFactory::default());
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(PowError::MergeMineError(_))));
+        assert!(results[2].is_ok());
+    }
+}
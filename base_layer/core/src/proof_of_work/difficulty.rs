@@ -95,6 +95,16 @@ impl From<Difficulty> for u64 {
     }
 }
 
+/// The difficulty adjustment algorithms that can be selected for each proof of work algorithm in the consensus
+/// constants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifficultyAdjustmentAlgorithm {
+    /// Linear weighted moving average, see [lwma_diff](crate::proof_of_work::lwma_diff)
+    Lwma,
+    /// Exponential moving average, see [ema_diff](crate::proof_of_work::ema_diff)
+    Ema,
+}
+
 /// General difficulty adjustment algorithm trait. The key method is `get_difficulty`, which returns the target
 /// difficulty given a set of historical achieved difficulties; supplied through the `add` method.
 pub trait DifficultyAdjustment {
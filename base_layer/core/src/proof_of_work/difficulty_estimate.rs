@@ -0,0 +1,95 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::cmp;
+
+use tari_utilities::epoch_time::EpochTime;
+
+use crate::proof_of_work::{Difficulty, PowAlgorithm};
+
+/// The difficulty of a proof of work algorithm at a given block height, used to estimate the hash rate on the network
+/// and when the difficulty will next adjust.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetDifficultyEstimate {
+    pub pow_algo: PowAlgorithm,
+    /// The height of the block from which the estimate was made
+    pub height: u64,
+    /// The target difficulty of the most recent block mined with this algorithm
+    pub current_difficulty: Difficulty,
+    /// The target difficulty for the next block mined with this algorithm
+    pub target_difficulty: Difficulty,
+    /// The target time between blocks mined with this algorithm in seconds
+    pub target_block_interval: u64,
+    /// The timestamp of the most recent block mined with this algorithm
+    pub last_block_timestamp: EpochTime,
+}
+
+impl TargetDifficultyEstimate {
+    /// Returns the hash rate (hashes per second) required to mine blocks at the target difficulty within the target
+    /// block interval
+    pub fn estimated_hash_rate(&self) -> u64 {
+        self.target_difficulty.as_u64() / cmp::max(self.target_block_interval, 1)
+    }
+
+    /// Returns the number of seconds until the next block for this algorithm is due. The difficulty is adjusted with
+    /// every block, so this is when the target difficulty is expected to change next.
+    pub fn time_to_next_adjustment(&self, now: EpochTime) -> u64 {
+        self.last_block_timestamp
+            .as_u64()
+            .saturating_add(self.target_block_interval)
+            .saturating_sub(now.as_u64())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn estimate() -> TargetDifficultyEstimate {
+        TargetDifficultyEstimate {
+            pow_algo: PowAlgorithm::Monero,
+            height: 100,
+            current_difficulty: 1000.into(),
+            target_difficulty: 1200.into(),
+            target_block_interval: 120,
+            last_block_timestamp: 1000.into(),
+        }
+    }
+
+    #[test]
+    fn it_estimates_the_hash_rate() {
+        assert_eq!(estimate().estimated_hash_rate(), 10);
+        let estimate = TargetDifficultyEstimate {
+            target_block_interval: 0,
+            ..estimate()
+        };
+        assert_eq!(estimate.estimated_hash_rate(), 1200);
+    }
+
+    #[test]
+    fn it_calculates_the_time_to_next_adjustment() {
+        let estimate = estimate();
+        assert_eq!(estimate.time_to_next_adjustment(1000.into()), 120);
+        assert_eq!(estimate.time_to_next_adjustment(1100.into()), 20);
+        assert_eq!(estimate.time_to_next_adjustment(1500.into()), 0);
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright 2019. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{cmp, collections::VecDeque, convert::TryFrom};
+
+use log::*;
+use tari_utilities::epoch_time::EpochTime;
+
+use crate::proof_of_work::{
+    difficulty::{Difficulty, DifficultyAdjustment},
+    error::DifficultyAdjustmentError,
+};
+
+pub const LOG_TARGET: &str = "c::pow::ema_diff";
+
+/// Solve times are tracked with this many fractional units per second to limit rounding errors in the moving average
+const SOLVE_TIME_SCALE: u128 = 1_000_000;
+
+/// An exponential moving average (EMA) difficulty adjustment. The target difficulty is the ratio of the EMAs of the
+/// block difficulties and solve times, scaled to the target time. The smoothing factor is `2 / (block_window + 1)`, so
+/// recent blocks carry more weight than with the linear weighted moving average, which lets the difficulty respond
+/// faster to changes in hash rate.
+#[derive(Debug, Clone)]
+pub struct ExponentialMovingAverage {
+    target_difficulties: VecDeque<(EpochTime, Difficulty)>,
+    block_window: usize,
+    target_time: u128,
+    max_block_time: u64,
+}
+
+impl ExponentialMovingAverage {
+    pub fn new(block_window: usize, target_time: u64, max_block_time: u64) -> Self {
+        Self {
+            target_difficulties: VecDeque::with_capacity(block_window + 1),
+            block_window,
+            target_time: u128::from(target_time),
+            max_block_time,
+        }
+    }
+
+    fn calculate(&self) -> Option<Difficulty> {
+        if self.target_difficulties.len() <= 1 {
+            return None;
+        }
+
+        let n = cmp::max(self.block_window, 1) as u128;
+        let mut ema_difficulty: Option<u128> = None;
+        let mut ema_solve_time: u128 = 0;
+
+        let (mut previous_timestamp, _) = self.target_difficulties[0];
+        for (timestamp, difficulty) in self.target_difficulties.iter().skip(1) {
+            // As with the LWMA, non-increasing timestamps are treated as a 1 second solve time
+            let this_timestamp = if *timestamp > previous_timestamp {
+                *timestamp
+            } else {
+                previous_timestamp.increase(1)
+            };
+            let solve_time = cmp::min((this_timestamp - previous_timestamp).as_u64(), self.max_block_time);
+            previous_timestamp = this_timestamp;
+
+            let difficulty = u128::from(difficulty.as_u64());
+            let solve_time = u128::from(solve_time) * SOLVE_TIME_SCALE;
+            match ema_difficulty {
+                Some(ema) => {
+                    ema_difficulty = Some((2 * difficulty + (n - 1) * ema) / (n + 1));
+                    ema_solve_time = (2 * solve_time + (n - 1) * ema_solve_time) / (n + 1);
+                },
+                None => {
+                    ema_difficulty = Some(difficulty);
+                    ema_solve_time = solve_time;
+                },
+            }
+        }
+
+        let ema_difficulty = ema_difficulty?;
+        let target = ema_difficulty * self.target_time * SOLVE_TIME_SCALE / cmp::max(ema_solve_time, 1);
+        let target = u64::try_from(target).unwrap_or(u64::MAX);
+        trace!(
+            target: LOG_TARGET,
+            "DiffCalc; t={}; bw={}; ema_difficulty={}; ema_solve_time={}; target={}",
+            self.target_time,
+            self.block_window,
+            ema_difficulty,
+            ema_solve_time / SOLVE_TIME_SCALE,
+            target
+        );
+        Some(target.into())
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.num_samples() == self.block_window + 1
+    }
+
+    #[inline]
+    pub fn num_samples(&self) -> usize {
+        self.target_difficulties.len()
+    }
+
+    pub fn add_front(&mut self, timestamp: EpochTime, target_difficulty: Difficulty) {
+        if self.is_full() {
+            self.target_difficulties.pop_back();
+        }
+        self.target_difficulties.push_front((timestamp, target_difficulty));
+    }
+
+    pub fn add_back(&mut self, timestamp: EpochTime, target_difficulty: Difficulty) {
+        if self.is_full() {
+            self.target_difficulties.pop_front();
+        }
+        self.target_difficulties.push_back((timestamp, target_difficulty));
+    }
+}
+
+impl DifficultyAdjustment for ExponentialMovingAverage {
+    fn add(&mut self, timestamp: EpochTime, target_difficulty: Difficulty) -> Result<(), DifficultyAdjustmentError> {
+        self.add_back(timestamp, target_difficulty);
+        Ok(())
+    }
+
+    fn get_difficulty(&self) -> Option<Difficulty> {
+        self.calculate()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ema_zero_len() {
+        let dif = ExponentialMovingAverage::new(90, 120, 120 * 6);
+        assert_eq!(dif.get_difficulty(), None);
+    }
+
+    #[test]
+    fn ema_is_full() {
+        let mut dif = ExponentialMovingAverage::new(1, 120, 120 * 6);
+        dif.add_front(60.into(), 100.into());
+        assert!(!dif.is_full());
+        dif.add_front(60.into(), 100.into());
+        assert!(dif.is_full());
+        dif.add_back(60.into(), 100.into());
+        assert_eq!(dif.num_samples(), 2);
+    }
+
+    #[test]
+    fn ema_is_stable_at_the_target_time() {
+        let mut dif = ExponentialMovingAverage::new(5, 60, 60 * 6);
+        for i in 1..20u64 {
+            dif.add(EpochTime::from(i * 60), 100.into()).unwrap();
+        }
+        assert_eq!(dif.get_difficulty().unwrap(), 100.into());
+    }
+
+    #[test]
+    fn ema_responds_to_solve_times() {
+        let mut fast = ExponentialMovingAverage::new(5, 60, 60 * 6);
+        let mut slow = ExponentialMovingAverage::new(5, 60, 60 * 6);
+        for i in 1..10u64 {
+            fast.add(EpochTime::from(i * 30), 100.into()).unwrap();
+            slow.add(EpochTime::from(i * 120), 100.into()).unwrap();
+        }
+        assert_eq!(fast.get_difficulty().unwrap(), 200.into());
+        assert_eq!(slow.get_difficulty().unwrap(), 50.into());
+    }
+
+    #[test]
+    fn ema_limits_solve_times() {
+        let mut dif = ExponentialMovingAverage::new(1, 60, 60 * 6);
+        dif.add(60.into(), 100.into()).unwrap();
+        dif.add(10_000_000.into(), 100.into()).unwrap();
+        // The solve time is clamped to 6 * 60 seconds
+        assert_eq!(dif.get_difficulty().unwrap(), 16.into());
+    }
+
+    #[test]
+    fn ema_does_not_overflow_with_large_difficulties() {
+        let mut dif = ExponentialMovingAverage::new(6000, 60, 60 * 6);
+        for _i in 0..6000 {
+            dif.add(60.into(), u64::MAX.into()).unwrap();
+        }
+        assert_eq!(dif.get_difficulty().unwrap(), u64::MAX.into());
+    }
+}
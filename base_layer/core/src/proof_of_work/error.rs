@@ -24,7 +24,7 @@ use thiserror::Error;
 
 #[cfg(feature = "base_node")]
 use crate::proof_of_work::monero_rx::MergeMineError;
-use crate::proof_of_work::Difficulty;
+use crate::proof_of_work::{Difficulty, PowAlgorithm};
 
 #[derive(Debug, Error)]
 pub enum PowError {
@@ -34,6 +34,17 @@ pub enum PowError {
     AchievedDifficultyTooLow { target: Difficulty, achieved: Difficulty },
     #[error("Invalid target difficulty (expected: {expected}, got: {got})")]
     InvalidTargetDifficulty { expected: Difficulty, got: Difficulty },
+    #[error("{pow_algo} proof of work data is {size} bytes which exceeds the maximum of {max_size} bytes")]
+    PowDataTooLarge {
+        pow_algo: PowAlgorithm,
+        size: usize,
+        max_size: usize,
+    },
+    #[error("Expected {expected} proof of work but got {actual}")]
+    UnexpectedPowAlgorithm {
+        expected: PowAlgorithm,
+        actual: PowAlgorithm,
+    },
     #[cfg(feature = "base_node")]
     #[error("Invalid merge mining data or operation: {0}")]
     MergeMineError(#[from] MergeMineError),
@@ -20,16 +20,26 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+#[cfg(feature = "base_node")]
+mod batch_verification;
+#[cfg(feature = "base_node")]
+pub use batch_verification::calculate_achieved_difficulties;
+
 #[cfg(any(feature = "base_node", feature = "transactions"))]
 pub(crate) mod difficulty;
 #[cfg(any(feature = "base_node", feature = "transactions"))]
-pub use difficulty::{Difficulty, DifficultyAdjustment};
+pub use difficulty::{Difficulty, DifficultyAdjustment, DifficultyAdjustmentAlgorithm};
 
 #[cfg(any(feature = "base_node", feature = "transactions"))]
 mod error;
 #[cfg(any(feature = "base_node", feature = "transactions"))]
 pub use error::{DifficultyAdjustmentError, PowError};
 
+#[cfg(feature = "base_node")]
+mod difficulty_estimate;
+#[cfg(feature = "base_node")]
+pub use difficulty_estimate::TargetDifficultyEstimate;
+
 #[cfg(feature = "base_node")]
 pub mod monero_rx;
 #[cfg(feature = "base_node")]
@@ -48,10 +58,10 @@ pub use proof_of_work_algorithm::PowAlgorithm;
 
 #[cfg(feature = "base_node")]
 mod sha3_pow;
-#[cfg(feature = "base_node")]
-pub use sha3_pow::sha3_difficulty;
 #[cfg(all(test, feature = "base_node"))]
 pub use sha3_pow::test as sha3_test;
+#[cfg(feature = "base_node")]
+pub use sha3_pow::{sha3_difficulty, sha3x_difficulty, sha3x_difficulty_with_midstate, sha3x_midstate};
 
 mod target_difficulty;
 pub use target_difficulty::AchievedTargetDifficulty;
@@ -63,5 +73,7 @@ pub use target_difficulty_window::TargetDifficultyWindow;
 
 pub mod lwma_diff;
 
+pub mod ema_diff;
+
 #[cfg(feature = "base_node")]
 pub mod randomx_factory;
@@ -117,6 +117,10 @@ mod test {
     use tari_utilities::hex::from_hex;
 
     use super::*;
+    use crate::{
+        consensus::{ConsensusDecoding, ToConsensusBytes},
+        proof_of_work::{PowAlgorithm, ProofOfWork},
+    };
 
     const POW_DATA_BLOB: &str = "0e0eff8a828606e62827cbb1c8f13eeddaae1d2c5dbb36c12a3d30d20d20b35a540bdba9d8e162604a0000202378cf4e85ef9a0629719e228c8c9807575469c3f45b3710c7960079a5dfdd661600b3cdc310a8f619ea2feadb178021ea0b853caa2f41749f7f039dcd4102d24f0504b4d72f22ca81245c538371a07331546cbd9935068637166d9cd627c521fb0e98d6161a7d971ee608b2b93719327d1cf5f95f9cc15beab7c6fb0894205c9218e4f9810873976eaf62d53ce631e8ad37bbaacc5da0267cd38342d66bdecce6541bb5c761b8ff66e7f6369cd3b0c2cb106a325c7342603516c77c9dcbb67388128a04000000000002fd873401ffc1873401c983eae58cd001026eb5be712030e2d49c9329f7f578325daa8ad7296a58985131544d8fe8a24c934d01ad27b94726423084ffc0f7eda31a8c9691836839c587664a036c3986b33f568f020861f4f1c2c37735680300916c27a920e462fbbfce5ac661ea9ef91fc78d620c61c43d5bb6a9644e3c17e000";
 
@@ -131,6 +135,19 @@ mod test {
         assert_eq!(ser, bytes);
     }
 
+    #[test]
+    fn it_decodes_from_proof_of_work() {
+        let pow = ProofOfWork {
+            pow_algo: PowAlgorithm::Monero,
+            pow_data: from_hex(POW_DATA_BLOB).unwrap(),
+        };
+        let decoded = ProofOfWork::consensus_decode(&mut pow.to_consensus_bytes().as_slice()).unwrap();
+        assert_eq!(decoded, pow);
+        let data = decoded.monero_data().unwrap();
+        assert_eq!(data.transaction_count, 22);
+        assert_eq!(consensus::serialize(&data), pow.pow_data);
+    }
+
     #[test]
     fn consensus_deserialize_reject_extra_bytes() {
         let mut bytes = from_hex(POW_DATA_BLOB).unwrap();
@@ -20,33 +20,37 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{
-    convert::TryFrom,
-    fmt::{Display, Error, Formatter},
-    io,
-    io::{ErrorKind, Read, Write},
-};
+use std::fmt::{Display, Error, Formatter};
 
 use bytes::BufMut;
 use serde::{Deserialize, Serialize};
 use tari_utilities::hex::Hex;
 
+#[cfg(feature = "base_node")]
+use crate::proof_of_work::monero_rx::{self, MergeMineError, MoneroPowData};
 use crate::{
-    consensus::{ConsensusDecoding, ConsensusEncoding, MaxSizeBytes},
-    proof_of_work::PowAlgorithm,
+    consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized},
+    proof_of_work::{PowAlgorithm, PowError},
 };
 
+/// The maximum size of the supplemental proof of work data for any algorithm
+const MAX_POW_DATA_SIZE: usize = 5120;
+
 pub trait AchievedDifficulty {}
 
 /// The proof of work data structure that is included in the block header. There's some non-Rustlike redundancy here
 /// to make serialization more straightforward
 #[allow(deprecated)]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(
+    Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ConsensusEncoding, ConsensusEncodingSized, ConsensusDecoding,
+)]
+#[consensus(validate = ProofOfWork::validate_pow_data_size)]
 pub struct ProofOfWork {
     /// The algorithm used to mine this block
     pub pow_algo: PowAlgorithm,
     /// Supplemental proof of work data. For example for Sha3, this would be empty (only the block header is
     /// required), but for Monero merge mining we need the Monero block header and RandomX seed hash.
+    #[consensus(max_size = MAX_POW_DATA_SIZE)]
     pub pow_data: Vec<u8>,
 }
 
@@ -77,39 +81,52 @@ impl ProofOfWork {
         buf.put_slice(&self.pow_data);
         buf
     }
-}
 
-impl Display for ProofOfWork {
-    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
-        writeln!(fmt, "Mining algorithm: {}", self.pow_algo)?;
-        writeln!(fmt, "Pow data: {}", self.pow_data.to_hex())?;
+    /// Checks that the supplemental proof of work data does not exceed the maximum size for the algorithm
+    pub fn validate_pow_data_size(&self) -> Result<(), PowError> {
+        let max_size = self.pow_algo.max_pow_data_size();
+        if self.pow_data.len() > max_size {
+            return Err(PowError::PowDataTooLarge {
+                pow_algo: self.pow_algo,
+                size: self.pow_data.len(),
+                max_size,
+            });
+        }
         Ok(())
     }
-}
 
-impl ConsensusEncoding for ProofOfWork {
-    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
-        let mut written = self.pow_algo.as_u64().consensus_encode(writer)?;
-        written += self.pow_data.consensus_encode(writer)?;
-        Ok(written)
+    /// Decodes the Monero merge mining data. An error is returned if this proof of work is not for Monero or if the
+    /// data is oversized or malformed.
+    #[cfg(feature = "base_node")]
+    pub fn monero_data(&self) -> Result<MoneroPowData, PowError> {
+        if !self.pow_algo.is_monero() {
+            return Err(PowError::UnexpectedPowAlgorithm {
+                expected: PowAlgorithm::Monero,
+                actual: self.pow_algo,
+            });
+        }
+        self.validate_pow_data_size()?;
+        let monero_data =
+            monero_rx::deserialize(&self.pow_data).map_err(|e| MergeMineError::DeserializeError(format!("{:?}", e)))?;
+        Ok(monero_data)
     }
 }
 
-impl ConsensusDecoding for ProofOfWork {
-    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, io::Error> {
-        let pow_algo = PowAlgorithm::try_from(u64::consensus_decode(reader)?)
-            .map_err(|e| io::Error::new(ErrorKind::InvalidInput, e))?;
-        let mut pow = ProofOfWork::new(pow_algo);
-        const MAX_POW_DATA_SIZE: usize = 5120;
-        let pow_data = <MaxSizeBytes<MAX_POW_DATA_SIZE> as ConsensusDecoding>::consensus_decode(reader)?;
-        pow.pow_data = pow_data.into();
-        Ok(pow)
+impl Display for ProofOfWork {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(fmt, "Mining algorithm: {}", self.pow_algo)?;
+        writeln!(fmt, "Pow data: {}", self.pow_data.to_hex())?;
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::proof_of_work::proof_of_work::{PowAlgorithm, ProofOfWork};
+    use integer_encoding::VarInt;
+    use rand::{Rng, RngCore};
+
+    use super::*;
+    use crate::consensus::{check_consensus_encoding_correctness, ToConsensusBytes};
 
     #[test]
     fn display() {
@@ -125,4 +142,77 @@ mod test {
         };
         assert_eq!(pow.to_bytes(), vec![1]);
     }
+
+    #[test]
+    fn consensus_encoding() {
+        check_consensus_encoding_correctness(ProofOfWork::new(PowAlgorithm::Sha3x)).unwrap();
+        let pow = ProofOfWork {
+            pow_algo: PowAlgorithm::Monero,
+            pow_data: vec![1; 374],
+        };
+        check_consensus_encoding_correctness(pow).unwrap();
+    }
+
+    #[test]
+    fn it_rejects_pow_data_that_exceeds_the_algorithm_maximum() {
+        let pow = ProofOfWork {
+            pow_algo: PowAlgorithm::Sha3,
+            pow_data: vec![1, 2, 3],
+        };
+        assert!(matches!(
+            pow.validate_pow_data_size(),
+            Err(PowError::PowDataTooLarge {
+                size: 3,
+                max_size: 0,
+                ..
+            })
+        ));
+        let bytes = pow.to_consensus_bytes();
+        assert!(ProofOfWork::consensus_decode(&mut bytes.as_slice()).is_err());
+
+        let pow = ProofOfWork {
+            pow_algo: PowAlgorithm::Monero,
+            pow_data: vec![1; PowAlgorithm::Monero.max_pow_data_size() + 1],
+        };
+        assert!(pow.validate_pow_data_size().is_err());
+        let bytes = pow.to_consensus_bytes();
+        assert!(ProofOfWork::consensus_decode(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn it_round_trips_random_proofs_of_work() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let pow_algo = [PowAlgorithm::Monero, PowAlgorithm::Sha3, PowAlgorithm::Sha3x][rng.gen_range(0..3)];
+            let mut pow_data = vec![0u8; rng.gen_range(0..=pow_algo.max_pow_data_size())];
+            rng.fill_bytes(&mut pow_data);
+            let pow = ProofOfWork { pow_algo, pow_data };
+
+            // The derived encoding is the algorithm followed by the length-prefixed data
+            let mut expected = pow.pow_algo.as_u64().encode_var_vec();
+            expected.extend(pow.pow_data.len().encode_var_vec());
+            expected.extend(&pow.pow_data);
+            assert_eq!(pow.to_consensus_bytes(), expected);
+            check_consensus_encoding_correctness(pow).unwrap();
+        }
+    }
+
+    #[cfg(feature = "base_node")]
+    #[test]
+    fn monero_data_requires_monero_pow() {
+        let pow = ProofOfWork::new(PowAlgorithm::Sha3);
+        assert!(matches!(
+            pow.monero_data(),
+            Err(PowError::UnexpectedPowAlgorithm {
+                expected: PowAlgorithm::Monero,
+                actual: PowAlgorithm::Sha3
+            })
+        ));
+
+        let pow = ProofOfWork {
+            pow_algo: PowAlgorithm::Monero,
+            pow_data: vec![1, 2, 3],
+        };
+        assert!(matches!(pow.monero_data(), Err(PowError::MergeMineError(_))));
+    }
 }
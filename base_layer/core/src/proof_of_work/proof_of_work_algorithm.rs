@@ -22,17 +22,28 @@
 use std::{
     convert::TryFrom,
     fmt::{Display, Formatter},
+    io,
+    io::{ErrorKind, Read, Write},
     str::FromStr,
 };
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized};
+
+/// The maximum size of the Monero merge mining data, which contains the Monero block header, coinbase transaction and
+/// coinbase merkle proof
+const MAX_MONERO_POW_DATA_SIZE: usize = 5120;
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Hash, Eq)]
 pub enum PowAlgorithm {
     Monero = 0,
     Sha3 = 1,
+    /// A tweaked Sha3 with domain separated hashing and a nonce-independent header midstate. Sha3x replaces Sha3 from
+    /// the activation height set in the consensus constants.
+    Sha3x = 2,
 }
 
 impl PowAlgorithm {
@@ -44,9 +55,31 @@ impl PowAlgorithm {
         matches!(self, Self::Sha3)
     }
 
+    pub fn is_sha3x(&self) -> bool {
+        matches!(self, Self::Sha3x)
+    }
+
+    /// Returns the algorithm whose consensus constants, target difficulty window and accumulated difficulty are used
+    /// for this algorithm. Sha3x is a variant of Sha3 and so shares these with Sha3.
+    pub fn base_algorithm(&self) -> PowAlgorithm {
+        match self {
+            PowAlgorithm::Sha3x => PowAlgorithm::Sha3,
+            algo => *algo,
+        }
+    }
+
     pub fn as_u64(&self) -> u64 {
         *self as u64
     }
+
+    /// Returns the maximum size in bytes of the supplemental proof of work data for this algorithm. Sha3 blocks do not
+    /// carry any supplemental data.
+    pub fn max_pow_data_size(&self) -> usize {
+        match self {
+            PowAlgorithm::Monero => MAX_MONERO_POW_DATA_SIZE,
+            PowAlgorithm::Sha3 | PowAlgorithm::Sha3x => 0,
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -63,6 +96,7 @@ impl TryFrom<u64> for PowAlgorithm {
         match v {
             0 => Ok(PowAlgorithm::Monero),
             1 => Ok(PowAlgorithm::Sha3),
+            2 => Ok(PowAlgorithm::Sha3x),
             _ => Err("Invalid PoWAlgorithm".into()),
         }
     }
@@ -75,6 +109,7 @@ impl FromStr for PowAlgorithm {
         match s {
             "monero" => Ok(Self::Monero),
             "sha" | "sha3" | "SHA3" => Ok(Self::Sha3),
+            "sha3x" | "SHA3X" => Ok(Self::Sha3x),
             other => Err(PowAlgorithmParseError::UnknownType(other.into())),
         }
     }
@@ -85,7 +120,22 @@ impl Display for PowAlgorithm {
         let algo = match self {
             PowAlgorithm::Monero => "Monero",
             PowAlgorithm::Sha3 => "Sha3",
+            PowAlgorithm::Sha3x => "Sha3x",
         };
         fmt.write_str(algo)
     }
 }
+
+impl ConsensusEncoding for PowAlgorithm {
+    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        self.as_u64().consensus_encode(writer)
+    }
+}
+
+impl ConsensusEncodingSized for PowAlgorithm {}
+
+impl ConsensusDecoding for PowAlgorithm {
+    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, io::Error> {
+        PowAlgorithm::try_from(u64::consensus_decode(reader)?).map_err(|e| io::Error::new(ErrorKind::InvalidInput, e))
+    }
+}
@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: BSD-3-Clause
 
 use std::{
+    cmp,
     collections::HashMap,
     fmt,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
     time::Instant,
 };
 
@@ -26,7 +27,9 @@ pub struct RandomXVMInstance {
     // Note: If a cache and dataset (if assigned) allocated to the VM drops, the VM will crash.
     // The cache and dataset for the VM need to be stored together with it since they are not
     // mix and match.
-    instance: Arc<RwLock<RandomXVMInstanceInner>>,
+    // Note: A RandomX VM is not thread safe, so hashes are calculated with exclusive access to the VM. This allows the
+    // VM to be shared between the header sync validator and the block sync pipeline.
+    instance: Arc<Mutex<RandomXVMInstanceInner>>,
 }
 
 impl RandomXVMInstance {
@@ -58,7 +61,7 @@ impl RandomXVMInstance {
         // light mode. These are not set by RandomX automatically even in fast mode.
 
         Ok(Self {
-            instance: Arc::new(RwLock::new(RandomXVMInstanceInner {
+            instance: Arc::new(Mutex::new(RandomXVMInstanceInner {
                 vm,
                 _cache: cache,
                 _dataset: None,
@@ -67,7 +70,7 @@ impl RandomXVMInstance {
     }
 
     pub fn calculate_hash(&self, input: &[u8]) -> Result<Vec<u8>, RandomXError> {
-        self.instance.read().unwrap().vm.calculate_hash(input)
+        self.instance.lock().unwrap().vm.calculate_hash(input)
     }
 }
 
@@ -78,7 +81,9 @@ impl RandomXVMInstance {
 unsafe impl Send for RandomXVMInstance {}
 unsafe impl Sync for RandomXVMInstance {}
 
-// Thread safe impl of the inner impl
+/// A thread safe pool of RandomX VMs keyed by RandomX seed hash. Initializing a VM is slow, so VMs are cached and
+/// reused for every header with the same seed hash. Once `max_vms` VMs have been created, the least recently used VM is
+/// evicted.
 #[derive(Clone, Debug)]
 pub struct RandomXFactory {
     inner: Arc<RwLock<RandomXFactoryInner>>,
@@ -124,6 +129,7 @@ struct RandomXFactoryInner {
 
 impl RandomXFactoryInner {
     pub fn new(max_vms: usize) -> Self {
+        let max_vms = cmp::max(max_vms, 1);
         let flags = RandomXFlag::get_recommended_flags();
         debug!(
             target: LOG_TARGET,
@@ -144,20 +150,18 @@ impl RandomXFactoryInner {
         }
 
         if self.vms.len() >= self.max_vms {
-            let mut oldest_value = Instant::now();
-            let mut oldest_key = None;
-            for (k, v) in &self.vms {
-                if v.0 < oldest_value {
-                    oldest_key = Some(k.clone());
-                    oldest_value = v.0;
-                }
-            }
-            if let Some(k) = oldest_key {
-                self.vms.remove(&k);
-            }
+            self.evict_least_recently_used();
         }
 
+        let timer = Instant::now();
         let vm = RandomXVMInstance::create(key, self.flags)?;
+        debug!(
+            target: LOG_TARGET,
+            "Initialized RandomX VM {}/{} in {:.2?}",
+            self.vms.len() + 1,
+            self.max_vms,
+            timer.elapsed()
+        );
 
         self.vms.insert(Vec::from(key), (Instant::now(), vm.clone()));
 
@@ -171,6 +175,18 @@ impl RandomXFactoryInner {
     pub fn get_flags(&self) -> RandomXFlag {
         self.flags
     }
+
+    fn evict_least_recently_used(&mut self) {
+        let oldest_key = self
+            .vms
+            .iter()
+            .min_by_key(|(_, (last_used, _))| *last_used)
+            .map(|(k, _)| k.clone());
+        if let Some(k) = oldest_key {
+            debug!(target: LOG_TARGET, "Evicting least recently used RandomX VM");
+            self.vms.remove(&k);
+        }
+    }
 }
 
 impl fmt::Debug for RandomXFactoryInner {
@@ -201,4 +217,28 @@ mod test {
         let vm = factory.create(&key[..]).unwrap();
         assert_ne!(vm.calculate_hash(&preimage[..]).unwrap(), hash1);
     }
+
+    #[test]
+    fn it_evicts_the_least_recently_used_vm() {
+        let factory = RandomXFactory::new(2);
+        factory.create(b"key-1").unwrap();
+        factory.create(b"key-2").unwrap();
+        // Use key-1 so that key-2 is the least recently used
+        factory.create(b"key-1").unwrap();
+        factory.create(b"key-3").unwrap();
+        assert_eq!(factory.get_count(), 2);
+
+        let inner = factory.inner.read().unwrap();
+        assert!(inner.vms.contains_key(&b"key-1"[..]));
+        assert!(!inner.vms.contains_key(&b"key-2"[..]));
+        assert!(inner.vms.contains_key(&b"key-3"[..]));
+    }
+
+    #[test]
+    fn it_caches_at_least_one_vm() {
+        let factory = RandomXFactory::new(0);
+        factory.create(b"key-1").unwrap();
+        factory.create(b"key-1").unwrap();
+        assert_eq!(factory.get_count(), 1);
+    }
 }
@@ -64,6 +64,51 @@ fn sha3_difficulty_with_hash(header: &BlockHeader) -> (Difficulty, Vec<u8>) {
     (difficulty, hash.to_vec())
 }
 
+const SHA3X_MIDSTATE_DOMAIN: &[u8] = b"com.tari.pow.sha3x.midstate";
+const SHA3X_POW_DOMAIN: &[u8] = b"com.tari.pow.sha3x.pow";
+
+/// The Sha3x proof of work. This is a tweak of the Sha3 proof of work that hashes the header in two domain separated
+/// stages:
+///
+/// `midstate = H256("com.tari.pow.sha3x.midstate" || header without nonce)`
+/// `pow = H256("com.tari.pow.sha3x.pow" || midstate || nonce)`
+///
+/// The midstate does not depend on the nonce, so a miner only needs to calculate it once per block template.
+pub fn sha3x_difficulty(header: &BlockHeader) -> Difficulty {
+    sha3x_difficulty_with_midstate(&sha3x_midstate(header), header.nonce)
+}
+
+/// Calculates the Sha3x difficulty for the given nonce from a midstate returned by [sha3x_midstate]
+pub fn sha3x_difficulty_with_midstate(midstate: &[u8], nonce: u64) -> Difficulty {
+    let hash = Sha3_256::new()
+        .chain(SHA3X_POW_DOMAIN)
+        .chain(midstate)
+        .chain(nonce.to_le_bytes())
+        .finalize();
+    big_endian_difficulty(&hash)
+}
+
+/// Returns the nonce-independent Sha3x midstate of the header
+pub fn sha3x_midstate(header: &BlockHeader) -> Vec<u8> {
+    Sha3_256::new()
+        .chain(SHA3X_MIDSTATE_DOMAIN)
+        .chain(header.version.to_le_bytes())
+        .chain(header.height.to_le_bytes())
+        .chain(header.prev_hash.as_bytes())
+        .chain(header.timestamp.as_u64().to_le_bytes())
+        .chain(header.input_mr.as_bytes())
+        .chain(header.output_mr.as_bytes())
+        .chain(header.output_mmr_size.to_le_bytes())
+        .chain(header.witness_mr.as_bytes())
+        .chain(header.kernel_mr.as_bytes())
+        .chain(header.kernel_mmr_size.to_le_bytes())
+        .chain(header.total_kernel_offset.as_bytes())
+        .chain(header.total_script_offset.as_bytes())
+        .chain(header.pow.to_bytes())
+        .finalize()
+        .to_vec()
+}
+
 #[cfg(test)]
 pub mod test {
     use chrono::{DateTime, NaiveDate, Utc};
@@ -71,7 +116,11 @@ pub mod test {
 
     use crate::{
         blocks::BlockHeader,
-        proof_of_work::{sha3_pow::sha3_difficulty, Difficulty, PowAlgorithm},
+        proof_of_work::{
+            sha3_pow::{sha3_difficulty, sha3x_difficulty, sha3x_difficulty_with_midstate, sha3x_midstate},
+            Difficulty,
+            PowAlgorithm,
+        },
     };
 
     /// A simple example miner. It starts at nonce = 0 and iterates until it finds a header hash that meets the desired
@@ -101,4 +150,22 @@ pub mod test {
         header.nonce = 1;
         assert_eq!(sha3_difficulty(&header), Difficulty::from(1));
     }
+
+    #[test]
+    fn sha3x_midstate_is_independent_of_the_nonce() {
+        let mut header = get_header();
+        header.pow.pow_algo = PowAlgorithm::Sha3x;
+        let midstate = sha3x_midstate(&header);
+        for nonce in 0..10 {
+            header.nonce = nonce;
+            assert_eq!(sha3x_midstate(&header), midstate);
+            assert_eq!(
+                sha3x_difficulty(&header),
+                sha3x_difficulty_with_midstate(&midstate, nonce)
+            );
+        }
+
+        header.height += 1;
+        assert_ne!(sha3x_midstate(&header), midstate);
+    }
 }
@@ -24,59 +24,100 @@ use std::cmp;
 
 use tari_utilities::epoch_time::EpochTime;
 
-use crate::proof_of_work::{difficulty::DifficultyAdjustment, lwma_diff::LinearWeightedMovingAverage, Difficulty};
+use crate::proof_of_work::{
+    difficulty::DifficultyAdjustment,
+    ema_diff::ExponentialMovingAverage,
+    lwma_diff::LinearWeightedMovingAverage,
+    Difficulty,
+    DifficultyAdjustmentAlgorithm,
+};
+
+#[derive(Debug, Clone)]
+enum DifficultyWindow {
+    Lwma(LinearWeightedMovingAverage),
+    Ema(ExponentialMovingAverage),
+}
 
 #[derive(Debug, Clone)]
 pub struct TargetDifficultyWindow {
-    lwma: LinearWeightedMovingAverage,
+    window: DifficultyWindow,
 }
 
 impl TargetDifficultyWindow {
-    /// Initialize a new `TargetDifficultyWindow`
+    /// Initialize a new `TargetDifficultyWindow` that uses the given difficulty adjustment algorithm
     ///
     /// # Panics
     ///
     /// Panics if block_window is 0
-    pub(crate) fn new(block_window: usize, target_time: u64, max_block_time: u64) -> Self {
+    pub(crate) fn new(
+        algorithm: DifficultyAdjustmentAlgorithm,
+        block_window: usize,
+        target_time: u64,
+        max_block_time: u64,
+    ) -> Self {
         assert!(
             block_window > 0,
             "TargetDifficulty::new expected block_window to be greater than 0, but 0 was given"
         );
-        Self {
-            lwma: LinearWeightedMovingAverage::new(block_window, target_time, max_block_time),
-        }
+        let window = match algorithm {
+            DifficultyAdjustmentAlgorithm::Lwma => DifficultyWindow::Lwma(LinearWeightedMovingAverage::new(
+                block_window,
+                target_time,
+                max_block_time,
+            )),
+            DifficultyAdjustmentAlgorithm::Ema => {
+                DifficultyWindow::Ema(ExponentialMovingAverage::new(block_window, target_time, max_block_time))
+            },
+        };
+        Self { window }
     }
 
     /// Appends a target difficulty. If the number of stored difficulties exceeds the block window, the oldest block
     /// window is removed keeping the size of the stored difficulties equal to the block window.
     #[inline]
     pub fn add_back(&mut self, time: EpochTime, difficulty: Difficulty) {
-        self.lwma.add_back(time, difficulty);
+        match &mut self.window {
+            DifficultyWindow::Lwma(lwma) => lwma.add_back(time, difficulty),
+            DifficultyWindow::Ema(ema) => ema.add_back(time, difficulty),
+        }
     }
 
     #[inline]
     pub fn add_front(&mut self, time: EpochTime, difficulty: Difficulty) {
-        self.lwma.add_front(time, difficulty);
+        match &mut self.window {
+            DifficultyWindow::Lwma(lwma) => lwma.add_front(time, difficulty),
+            DifficultyWindow::Ema(ema) => ema.add_front(time, difficulty),
+        }
     }
 
     /// Returns true of the TargetDifficulty has `block_window` data points, otherwise false
     #[inline]
     pub fn is_full(&self) -> bool {
-        self.lwma.is_full()
+        match &self.window {
+            DifficultyWindow::Lwma(lwma) => lwma.is_full(),
+            DifficultyWindow::Ema(ema) => ema.is_full(),
+        }
     }
 
     pub fn len(&self) -> usize {
-        self.lwma.num_samples()
+        match &self.window {
+            DifficultyWindow::Lwma(lwma) => lwma.num_samples(),
+            DifficultyWindow::Ema(ema) => ema.num_samples(),
+        }
     }
 
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.lwma.num_samples() == 0
+        self.len() == 0
     }
 
     /// Calculates the target difficulty for the current set of target difficulties.
     pub fn calculate(&self, min: Difficulty, max: Difficulty) -> Difficulty {
-        cmp::max(min, cmp::min(max, self.lwma.get_difficulty().unwrap_or(min)))
+        let difficulty = match &self.window {
+            DifficultyWindow::Lwma(lwma) => lwma.get_difficulty(),
+            DifficultyWindow::Ema(ema) => ema.get_difficulty(),
+        };
+        cmp::max(min, cmp::min(max, difficulty.unwrap_or(min)))
     }
 }
 
@@ -86,7 +127,7 @@ mod test {
 
     #[test]
     fn it_calculates_the_target_difficulty() {
-        let mut target_difficulties = TargetDifficultyWindow::new(5, 60, 60 * 6);
+        let mut target_difficulties = TargetDifficultyWindow::new(DifficultyAdjustmentAlgorithm::Lwma, 5, 60, 60 * 6);
         let mut time = 60.into();
         target_difficulties.add_back(time, 100.into());
         time += 60.into();
@@ -98,4 +139,17 @@ mod test {
 
         assert_eq!(target_difficulties.calculate(1.into(), 400.into()), 100.into());
     }
+
+    #[test]
+    fn it_calculates_the_target_difficulty_using_an_ema() {
+        let mut target_difficulties = TargetDifficultyWindow::new(DifficultyAdjustmentAlgorithm::Ema, 5, 60, 60 * 6);
+        let mut time = 60.into();
+        for _ in 0..10 {
+            target_difficulties.add_back(time, 100.into());
+            time += 30.into();
+        }
+        assert!(target_difficulties.is_full());
+        assert_eq!(target_difficulties.calculate(1.into(), 400.into()), 200.into());
+        assert_eq!(target_difficulties.calculate(1.into(), 150.into()), 150.into());
+    }
 }
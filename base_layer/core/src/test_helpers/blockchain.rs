@@ -57,11 +57,14 @@ use crate::{
         BlockchainDatabaseConfig,
         ChainStorageError,
         DbBasicStats,
+        DbIntegrityReport,
         DbKey,
         DbTotalSizeStats,
         DbTransaction,
         DbValue,
         HorizonData,
+        HorizonSyncCheckpoint,
+        KernelLocation,
         LMDBDatabase,
         MmrTree,
         PrunedOutput,
@@ -95,7 +98,8 @@ pub fn create_new_blockchain_with_network(network: Network) -> BlockchainDatabas
         .add_consensus_constants(consensus_constants)
         .with_block(genesis)
         .on_ties(ChainStrengthComparerBuilder::new().by_height().build())
-        .build();
+        .build()
+        .unwrap();
     create_custom_blockchain(consensus_manager)
 }
 
@@ -280,6 +284,16 @@ impl BlockchainBackend for TempDatabase {
         self.db.as_ref().unwrap().fetch_kernel_by_excess_sig(excess_sig)
     }
 
+    fn fetch_kernel_location_by_excess_sig(
+        &self,
+        excess_sig: &Signature,
+    ) -> Result<Option<KernelLocation>, ChainStorageError> {
+        self.db
+            .as_ref()
+            .unwrap()
+            .fetch_kernel_location_by_excess_sig(excess_sig)
+    }
+
     fn fetch_utxos_in_block(
         &self,
         header_hash: &HashOutput,
@@ -389,15 +403,11 @@ impl BlockchainBackend for TempDatabase {
         self.db.as_ref().unwrap().fetch_deleted_bitmap()
     }
 
-    fn delete_oldest_orphans(
-        &mut self,
-        horizon_height: u64,
-        orphan_storage_capacity: usize,
-    ) -> Result<(), ChainStorageError> {
+    fn evict_orphans(&mut self, horizon_height: u64, orphan_storage_capacity: usize) -> Result<(), ChainStorageError> {
         self.db
             .as_mut()
             .unwrap()
-            .delete_oldest_orphans(horizon_height, orphan_storage_capacity)
+            .evict_orphans(horizon_height, orphan_storage_capacity)
     }
 
     fn fetch_monero_seed_first_seen_height(&self, seed: &[u8]) -> Result<u64, ChainStorageError> {
@@ -408,6 +418,14 @@ impl BlockchainBackend for TempDatabase {
         self.db.as_ref().unwrap().fetch_horizon_data()
     }
 
+    fn fetch_horizon_sync_checkpoint(&self) -> Result<Option<HorizonSyncCheckpoint>, ChainStorageError> {
+        self.db.as_ref().unwrap().fetch_horizon_sync_checkpoint()
+    }
+
+    fn check_integrity(&self) -> Result<DbIntegrityReport, ChainStorageError> {
+        self.db.as_ref().unwrap().check_integrity()
+    }
+
     fn get_stats(&self) -> Result<DbBasicStats, ChainStorageError> {
         self.db.as_ref().unwrap().get_stats()
     }
@@ -441,7 +459,7 @@ pub fn create_chained_blocks<T: Into<BlockSpecs>>(
 ) -> (Vec<String>, HashMap<String, Arc<ChainBlock>>) {
     let mut block_hashes = HashMap::new();
     block_hashes.insert("GB".to_string(), genesis_block);
-    let rules = ConsensusManager::builder(Network::LocalNet).build();
+    let rules = ConsensusManager::builder(Network::LocalNet).build().unwrap();
     let blocks: BlockSpecs = blocks.into();
     let mut block_names = Vec::with_capacity(blocks.len());
     for block_spec in blocks {
@@ -46,9 +46,10 @@ use crate::{
 #[macro_use]
 mod block_spec;
 pub mod blockchain;
+pub mod test_data;
 
 pub fn create_consensus_rules() -> ConsensusManager {
-    ConsensusManager::builder(Network::LocalNet).build()
+    ConsensusManager::builder(Network::LocalNet).build().unwrap()
 }
 
 pub fn create_consensus_constants(height: u64) -> ConsensusConstants {
@@ -0,0 +1,235 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Generators for random, internally consistent blocks, transactions and UTXO sets of a configurable size. These are
+//! used by the `tari_test_data_generator` binary to write fixtures for benchmarks and downstream tests.
+
+use std::{fmt, str::FromStr};
+
+use tari_script::script;
+
+use crate::{
+    blocks::Block,
+    consensus::ConsensusManager,
+    covenants::Covenant,
+    test_helpers::{create_block, BlockSpec},
+    transactions::{
+        tari_amount::{uT, MicroTari, T},
+        test_helpers::{create_tx, create_utxo},
+        transaction_components::{OutputFeatures, Transaction, TransactionOutput},
+        CryptoFactories,
+    },
+};
+
+/// The maturity given to time-locked outputs
+const TIME_LOCKED_MATURITY: u64 = 1000;
+
+/// The output features used for generated outputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureMix {
+    /// All outputs use the default output features
+    Standard,
+    /// All outputs have a non-zero maturity
+    TimeLocked,
+    /// Alternates between standard and time-locked outputs
+    Mixed,
+}
+
+impl FeatureMix {
+    /// Returns the output features for the `n`th generated item
+    pub fn output_features(&self, n: usize) -> OutputFeatures {
+        let is_time_locked = match self {
+            FeatureMix::Standard => false,
+            FeatureMix::TimeLocked => true,
+            FeatureMix::Mixed => n % 2 == 1,
+        };
+        if is_time_locked {
+            OutputFeatures {
+                maturity: TIME_LOCKED_MATURITY,
+                ..Default::default()
+            }
+        } else {
+            OutputFeatures::default()
+        }
+    }
+}
+
+impl Default for FeatureMix {
+    fn default() -> Self {
+        FeatureMix::Standard
+    }
+}
+
+impl FromStr for FeatureMix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "standard" => Ok(FeatureMix::Standard),
+            "timelocked" | "time-locked" => Ok(FeatureMix::TimeLocked),
+            "mixed" => Ok(FeatureMix::Mixed),
+            _ => Err(format!(
+                "Invalid feature mix '{}'. Expected one of standard, timelocked or mixed",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for FeatureMix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeatureMix::Standard => write!(f, "standard"),
+            FeatureMix::TimeLocked => write!(f, "timelocked"),
+            FeatureMix::Mixed => write!(f, "mixed"),
+        }
+    }
+}
+
+/// The shape of generated transactions
+#[derive(Debug, Clone, Copy)]
+pub struct TransactionSpec {
+    pub num_inputs: usize,
+    pub num_outputs: usize,
+    pub fee_per_gram: MicroTari,
+    pub features: FeatureMix,
+}
+
+impl Default for TransactionSpec {
+    fn default() -> Self {
+        Self {
+            num_inputs: 1,
+            num_outputs: 2,
+            fee_per_gram: 5 * uT,
+            features: FeatureMix::Standard,
+        }
+    }
+}
+
+/// Generates `count` valid, independent transactions. The transaction inputs are not included in any generated UTXO
+/// set.
+pub fn generate_transactions(count: usize, spec: &TransactionSpec) -> Vec<Transaction> {
+    (0..count)
+        .map(|n| {
+            let (tx, _, _) = create_tx(
+                T,
+                spec.fee_per_gram,
+                0,
+                spec.num_inputs,
+                0,
+                spec.num_outputs,
+                spec.features.output_features(n),
+            );
+            tx
+        })
+        .collect()
+}
+
+/// Generates a chain of `num_blocks` blocks following the genesis block of the given consensus rules. Each block
+/// contains a coinbase and `txs_per_block` transactions generated according to `spec`. Blocks are internally
+/// consistent, but are not mined and their MMR roots are not set.
+pub fn generate_blocks(
+    rules: &ConsensusManager,
+    num_blocks: usize,
+    txs_per_block: usize,
+    spec: &TransactionSpec,
+) -> Vec<Block> {
+    let mut prev_block = rules.get_genesis_block().block().clone();
+    let mut blocks = Vec::with_capacity(num_blocks);
+    for _ in 0..num_blocks {
+        let transactions = generate_transactions(txs_per_block, spec);
+        let (block, _) = create_block(
+            rules,
+            &prev_block,
+            BlockSpec::new().with_transactions(transactions).finish(),
+        );
+        prev_block = block.clone();
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// Generates a set of `count` unspent outputs with random values
+pub fn generate_utxo_set(count: usize, features: FeatureMix) -> Vec<TransactionOutput> {
+    let factories = CryptoFactories::default();
+    let script = script![Nop];
+    let covenant = Covenant::default();
+    (0..count)
+        .map(|n| {
+            let value = MicroTari::from(rand::random::<u32>() as u64 + 1);
+            let (utxo, _, _) = create_utxo(value, &factories, &features.output_features(n), &script, &covenant);
+            utxo
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use tari_common::configuration::Network;
+
+    use super::*;
+
+    #[test]
+    fn it_generates_transactions_of_the_given_shape() {
+        let spec = TransactionSpec {
+            num_inputs: 2,
+            num_outputs: 3,
+            features: FeatureMix::Mixed,
+            ..Default::default()
+        };
+        let txs = generate_transactions(2, &spec);
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].body.inputs().len(), 2);
+        assert_eq!(txs[0].body.outputs().len(), 3);
+        assert!(txs[0].body.outputs().iter().all(|o| o.features.maturity == 0));
+        assert!(txs[1]
+            .body
+            .outputs()
+            .iter()
+            .all(|o| o.features.maturity == TIME_LOCKED_MATURITY));
+    }
+
+    #[test]
+    fn it_generates_a_chain_of_blocks() {
+        let rules = ConsensusManager::builder(Network::LocalNet).build().unwrap();
+        let blocks = generate_blocks(&rules, 2, 1, &TransactionSpec::default());
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].header.height, 1);
+        assert_eq!(blocks[1].header.height, 2);
+        // Coinbase plus one transaction
+        assert_eq!(blocks[1].body.kernels().len(), 2);
+    }
+
+    #[test]
+    fn it_parses_feature_mix() {
+        assert_eq!("Mixed".parse::<FeatureMix>().unwrap(), FeatureMix::Mixed);
+        assert_eq!("time-locked".parse::<FeatureMix>().unwrap(), FeatureMix::TimeLocked);
+        assert!("fancy".parse::<FeatureMix>().is_err());
+    }
+
+    #[test]
+    fn it_generates_a_utxo_set() {
+        let utxos = generate_utxo_set(3, FeatureMix::TimeLocked);
+        assert_eq!(utxos.len(), 3);
+        assert!(utxos.iter().all(|o| o.features.maturity == TIME_LOCKED_MATURITY));
+    }
+}
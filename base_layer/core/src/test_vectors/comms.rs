@@ -0,0 +1,109 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::convert::TryFrom;
+
+use prost::Message;
+use tari_comms::protocol::rpc::wire::{RpcRequest, RpcResponse};
+use tari_comms_dht::envelope::{Destination, DhtEnvelope, DhtHeader, DhtMessageType};
+use tari_utilities::ByteArray;
+
+use crate::test_vectors::consensus::base_point;
+
+/// Prefixes the message with its length as a 4 byte big-endian integer, as done by the canonical comms framing
+fn frame(message: &[u8]) -> Vec<u8> {
+    let len = u32::try_from(message.len()).expect("Test vector frames are small");
+    let mut frame = len.to_be_bytes().to_vec();
+    frame.extend_from_slice(message);
+    frame
+}
+
+fn unframe(frame: &[u8]) -> Result<&[u8], String> {
+    if frame.len() < 4 {
+        return Err("Frame is shorter than the length prefix".to_string());
+    }
+    let (prefix, message) = frame.split_at(4);
+    let len = u32::from_be_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+    if len != message.len() {
+        return Err(format!(
+            "Frame length prefix is {} but the message is {} bytes",
+            len,
+            message.len()
+        ));
+    }
+    Ok(message)
+}
+
+pub fn rpc_request_frame() -> Vec<u8> {
+    let request = RpcRequest {
+        request_id: 1,
+        method: 2,
+        flags: 0,
+        deadline: 10,
+        payload: b"ping".to_vec(),
+    };
+    frame(&request.encode_to_vec())
+}
+
+pub fn round_trip_rpc_request_frame(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let request = RpcRequest::decode(unframe(bytes)?).map_err(|err| err.to_string())?;
+    Ok(frame(&request.encode_to_vec()))
+}
+
+pub fn rpc_response_frame() -> Vec<u8> {
+    let response = RpcResponse {
+        request_id: 1,
+        status: 0,
+        flags: 1,
+        payload: b"pong".to_vec(),
+    };
+    frame(&response.encode_to_vec())
+}
+
+pub fn round_trip_rpc_response_frame(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let response = RpcResponse::decode(unframe(bytes)?).map_err(|err| err.to_string())?;
+    Ok(frame(&response.encode_to_vec()))
+}
+
+pub fn dht_envelope() -> Vec<u8> {
+    let header = DhtHeader {
+        major: 2,
+        minor: 0,
+        destination: Some(Destination::PublicKey(base_point().to_vec())),
+        origin_mac: vec![],
+        ephemeral_public_key: vec![],
+        message_type: DhtMessageType::Join as i32,
+        flags: 0,
+        message_tag: 123,
+        expires: None,
+    };
+    let envelope = DhtEnvelope {
+        header: Some(header),
+        body: b"hello".to_vec(),
+    };
+    envelope.encode_to_vec()
+}
+
+pub fn round_trip_dht_envelope(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let envelope = DhtEnvelope::decode(bytes).map_err(|err| err.to_string())?;
+    Ok(envelope.encode_to_vec())
+}
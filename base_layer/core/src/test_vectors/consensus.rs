@@ -0,0 +1,119 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use tari_common_types::types::{Commitment, PrivateKey, PublicKey, Signature};
+use tari_crypto::keys::PublicKey as PublicKeyTrait;
+use tari_utilities::{epoch_time::EpochTime, ByteArray};
+
+use crate::{
+    blocks::{Block, BlockHeader},
+    consensus::{ConsensusDecoding, ConsensusEncoding},
+    proof_of_work::{PowAlgorithm, ProofOfWork},
+    transactions::{
+        aggregated_body::AggregateBody,
+        tari_amount::MicroTari,
+        transaction_components::{KernelFeatures, TransactionKernel, TransactionKernelVersion},
+    },
+};
+
+/// The Ristretto base point, i.e. the public key of the secret key `1`
+pub(super) fn base_point() -> PublicKey {
+    PublicKey::from_secret_key(&PrivateKey::from(1u64))
+}
+
+fn header() -> BlockHeader {
+    BlockHeader {
+        version: 1,
+        height: 12345,
+        prev_hash: vec![0x11; 32],
+        timestamp: EpochTime::from(1_650_000_000),
+        output_mr: vec![0x22; 32],
+        witness_mr: vec![0x33; 32],
+        output_mmr_size: 1000,
+        kernel_mr: vec![0x44; 32],
+        kernel_mmr_size: 500,
+        input_mr: vec![0x55; 32],
+        total_kernel_offset: PrivateKey::from(1u64),
+        total_script_offset: PrivateKey::from(2u64),
+        nonce: 0xdead_beef,
+        pow: ProofOfWork {
+            pow_algo: PowAlgorithm::Sha3,
+            pow_data: vec![],
+        },
+    }
+}
+
+fn kernel() -> TransactionKernel {
+    let excess = Commitment::from_bytes(base_point().as_bytes()).expect("The base point is a valid commitment");
+    TransactionKernel::new(
+        TransactionKernelVersion::V0,
+        KernelFeatures::COINBASE_KERNEL,
+        MicroTari(25),
+        100,
+        excess,
+        Signature::new(base_point(), PrivateKey::from(2u64)),
+    )
+}
+
+fn encode<T: ConsensusEncoding>(value: &T) -> Vec<u8> {
+    let mut buf = Vec::new();
+    value
+        .consensus_encode(&mut buf)
+        .expect("Consensus encoding into a Vec cannot fail");
+    buf
+}
+
+fn round_trip<T: ConsensusEncoding + ConsensusDecoding>(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut reader = bytes;
+    let value = T::consensus_decode(&mut reader).map_err(|err| err.to_string())?;
+    if !reader.is_empty() {
+        return Err(format!("{} trailing bytes", reader.len()));
+    }
+    Ok(encode(&value))
+}
+
+pub fn block_header() -> Vec<u8> {
+    encode(&header())
+}
+
+pub fn round_trip_block_header(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    round_trip::<BlockHeader>(bytes)
+}
+
+pub fn transaction_kernel() -> Vec<u8> {
+    encode(&kernel())
+}
+
+pub fn round_trip_transaction_kernel(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    round_trip::<TransactionKernel>(bytes)
+}
+
+pub fn block() -> Vec<u8> {
+    encode(&Block::new(
+        header(),
+        AggregateBody::new(vec![], vec![], vec![kernel()]),
+    ))
+}
+
+pub fn round_trip_block(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    round_trip::<Block>(bytes)
+}
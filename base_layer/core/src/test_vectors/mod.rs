@@ -0,0 +1,275 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Golden test vectors for the Tari wire formats.
+//!
+//! The vectors in `wire_formats.json` are the canonical byte encodings of a fixed set of RPC frames, DHT envelopes
+//! and consensus encoded blocks and transaction components. They are checked against this implementation in CI so
+//! that any change to a wire format is caught, and can be consumed by other implementations to verify their
+//! compatibility with this one.
+//!
+//! To regenerate the golden file after an intentional wire format change, run the tests in this module with the
+//! `TARI_UPDATE_TEST_VECTORS` environment variable set.
+
+mod comms;
+mod consensus;
+
+use serde::{Deserialize, Serialize};
+use tari_utilities::hex::{from_hex, to_hex};
+use thiserror::Error;
+
+/// The version of the test vector file format
+pub const TEST_VECTORS_VERSION: u32 = 1;
+
+const GOLDEN_TEST_VECTORS: &str = include_str!("wire_formats.json");
+
+/// A named, hex encoded example of a wire format
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestVector {
+    pub name: String,
+    pub description: String,
+    pub hex: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestVectorSet {
+    pub version: u32,
+    pub vectors: Vec<TestVector>,
+}
+
+#[derive(Debug, Error)]
+pub enum TestVectorError {
+    #[error("Invalid test vector JSON: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+}
+
+/// A difference between an expected test vector and this implementation
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TestVectorMismatch {
+    #[error("Test vector '{name}' is not known to this implementation")]
+    Unknown { name: String },
+    #[error("Test vector '{name}' is missing from the expected test vectors")]
+    Missing { name: String },
+    #[error("Test vector '{name}' is not valid hex")]
+    InvalidHex { name: String },
+    #[error("Test vector '{name}' differs. Expected: {expected}, actual: {actual}")]
+    EncodingMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Test vector '{name}' could not be decoded: {error}")]
+    DecodingFailed { name: String, error: String },
+    #[error("Test vector '{name}' was not re-encoded to the same bytes after decoding")]
+    RoundTripMismatch { name: String },
+}
+
+/// A wire format with a fixed example value
+struct WireFormat {
+    name: &'static str,
+    description: &'static str,
+    encode: fn() -> Vec<u8>,
+    /// Decodes the given bytes and encodes the decoded value again
+    round_trip: fn(&[u8]) -> Result<Vec<u8>, String>,
+}
+
+fn wire_formats() -> Vec<WireFormat> {
+    vec![
+        WireFormat {
+            name: "block_header",
+            description: "Consensus encoding of a Sha3 block header",
+            encode: consensus::block_header,
+            round_trip: consensus::round_trip_block_header,
+        },
+        WireFormat {
+            name: "transaction_kernel",
+            description: "Consensus encoding of a coinbase transaction kernel",
+            encode: consensus::transaction_kernel,
+            round_trip: consensus::round_trip_transaction_kernel,
+        },
+        WireFormat {
+            name: "block",
+            description: "Consensus encoding of a block containing a single kernel",
+            encode: consensus::block,
+            round_trip: consensus::round_trip_block,
+        },
+        WireFormat {
+            name: "rpc_request_frame",
+            description: "Length delimited RPC request frame",
+            encode: comms::rpc_request_frame,
+            round_trip: comms::round_trip_rpc_request_frame,
+        },
+        WireFormat {
+            name: "rpc_response_frame",
+            description: "Length delimited RPC response frame with the FIN flag set",
+            encode: comms::rpc_response_frame,
+            round_trip: comms::round_trip_rpc_response_frame,
+        },
+        WireFormat {
+            name: "dht_envelope",
+            description: "Unencrypted DHT envelope destined for a public key",
+            encode: comms::dht_envelope,
+            round_trip: comms::round_trip_dht_envelope,
+        },
+    ]
+}
+
+impl TestVectorSet {
+    /// The golden test vectors stored in this repository
+    pub fn golden() -> Result<Self, TestVectorError> {
+        Self::from_json(GOLDEN_TEST_VECTORS)
+    }
+
+    /// Generates the test vectors using this implementation
+    pub fn generate() -> Self {
+        Self {
+            version: TEST_VECTORS_VERSION,
+            vectors: wire_formats()
+                .into_iter()
+                .map(|format| TestVector {
+                    name: format.name.to_string(),
+                    description: format.description.to_string(),
+                    hex: to_hex(&(format.encode)()),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, TestVectorError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, TestVectorError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Checks these test vectors against this implementation. Every vector must be produced byte for byte by this
+    /// implementation, and must decode and re-encode to the same bytes.
+    pub fn check(&self) -> Vec<TestVectorMismatch> {
+        let formats = wire_formats();
+        let mut mismatches = formats
+            .iter()
+            .filter(|format| self.vectors.iter().all(|v| v.name != format.name))
+            .map(|format| TestVectorMismatch::Missing {
+                name: format.name.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        for vector in &self.vectors {
+            let name = vector.name.clone();
+            let format = match formats.iter().find(|format| format.name == vector.name) {
+                Some(format) => format,
+                None => {
+                    mismatches.push(TestVectorMismatch::Unknown { name });
+                    continue;
+                },
+            };
+            let expected = match from_hex(&vector.hex) {
+                Ok(bytes) => bytes,
+                Err(_) => {
+                    mismatches.push(TestVectorMismatch::InvalidHex { name });
+                    continue;
+                },
+            };
+
+            let actual = (format.encode)();
+            if actual != expected {
+                mismatches.push(TestVectorMismatch::EncodingMismatch {
+                    name,
+                    expected: vector.hex.clone(),
+                    actual: to_hex(&actual),
+                });
+                continue;
+            }
+
+            match (format.round_trip)(&expected) {
+                Ok(bytes) if bytes == expected => {},
+                Ok(_) => mismatches.push(TestVectorMismatch::RoundTripMismatch { name }),
+                Err(error) => mismatches.push(TestVectorMismatch::DecodingFailed { name, error }),
+            }
+        }
+
+        mismatches
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use super::*;
+
+    #[test]
+    fn it_matches_the_golden_test_vectors() {
+        let generated = TestVectorSet::generate();
+        if env::var("TARI_UPDATE_TEST_VECTORS").is_ok() {
+            let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/test_vectors/wire_formats.json");
+            fs::write(path, generated.to_json().unwrap() + "\n").unwrap();
+            return;
+        }
+
+        let golden = TestVectorSet::golden().unwrap();
+        let mismatches = golden.check();
+        assert!(mismatches.is_empty(), "{:#?}", mismatches);
+        assert_eq!(generated, golden);
+    }
+
+    #[test]
+    fn it_reports_changed_encodings() {
+        let mut vectors = TestVectorSet::generate();
+        vectors.vectors[0].hex.replace_range(0..2, "ff");
+        let mismatches = vectors.check();
+        assert_eq!(mismatches.len(), 1);
+        assert!(matches!(
+            &mismatches[0],
+            TestVectorMismatch::EncodingMismatch { name, .. } if name == "block_header"
+        ));
+    }
+
+    #[test]
+    fn it_reports_missing_and_unknown_vectors() {
+        let mut vectors = TestVectorSet::generate();
+        let mut removed = vectors.vectors.remove(1);
+        removed.name = "not_a_wire_format".to_string();
+        vectors.vectors.push(removed);
+
+        let mismatches = vectors.check();
+        assert_eq!(mismatches, vec![
+            TestVectorMismatch::Missing {
+                name: "transaction_kernel".to_string()
+            },
+            TestVectorMismatch::Unknown {
+                name: "not_a_wire_format".to_string()
+            },
+        ]);
+    }
+
+    #[test]
+    fn it_round_trips_the_json_format() {
+        let vectors = TestVectorSet::generate();
+        let json = vectors.to_json().unwrap();
+        assert_eq!(TestVectorSet::from_json(&json).unwrap(), vectors);
+        assert!(TestVectorSet::from_json("{}").is_err());
+    }
+}
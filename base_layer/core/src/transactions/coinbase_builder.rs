@@ -282,7 +282,7 @@ mod test {
 
     fn get_builder() -> (CoinbaseBuilder, ConsensusManager, CryptoFactories) {
         let network = Network::LocalNet;
-        let rules = ConsensusManagerBuilder::new(network).build();
+        let rules = ConsensusManagerBuilder::new(network).build().unwrap();
         let factories = CryptoFactories::default();
         (CoinbaseBuilder::new(factories.clone()), rules, factories)
     }
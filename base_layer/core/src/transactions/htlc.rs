@@ -0,0 +1,194 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Script templates for SHA-256 hash time locked contracts (HTLCs), the building block of cross-chain atomic swaps.
+//!
+//! An HTLC output can be claimed by the holder of the claim key who reveals the pre-image of the hash lock, or
+//! refunded to the holder of the refund key once the chain has reached the lock height. A claim publishes the
+//! pre-image on chain, which lets the refunding party complete the other leg of the swap.
+
+use digest::Digest;
+use sha2::Sha256;
+use tari_common_types::types::PublicKey;
+use tari_crypto::tari_utilities::ByteArray;
+use tari_script::{inputs, script, ExecutionStack, HashValue, Opcode, StackItem, TariScript};
+
+/// The hash lock for `pre_image`, i.e. the value that `HashSha256` produces for it
+pub fn sha256_hash_lock(pre_image: &PublicKey) -> HashValue {
+    Sha256::digest(pre_image.as_bytes()).into()
+}
+
+/// The parameters of a SHA-256 HTLC script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaHtlcScript {
+    /// The SHA-256 hash of the pre-image that unlocks the claim path
+    pub hash: HashValue,
+    /// The script key of the party that can claim the output with the pre-image
+    pub claim_public_key: PublicKey,
+    /// The script key of the party that can reclaim the output after `lock_height`
+    pub refund_public_key: PublicKey,
+    /// The block height from which the output can be refunded
+    pub lock_height: u64,
+}
+
+impl ShaHtlcScript {
+    pub fn new(hash: HashValue, claim_public_key: PublicKey, refund_public_key: PublicKey, lock_height: u64) -> Self {
+        Self {
+            hash,
+            claim_public_key,
+            refund_public_key,
+            lock_height,
+        }
+    }
+
+    /// Builds the HTLC script:
+    /// `HashSha256 PushHash(hash) Equal IfThen PushPubKey(claim) Else CheckHeightVerify(lock_height) PushPubKey(refund)
+    /// EndIf`
+    pub fn to_script(&self) -> TariScript {
+        script!(
+            HashSha256 PushHash(Box::new(self.hash)) Equal IfThen
+                PushPubKey(Box::new(self.claim_public_key.clone()))
+            Else
+                CheckHeightVerify(self.lock_height) PushPubKey(Box::new(self.refund_public_key.clone()))
+            EndIf
+        )
+    }
+
+    /// Returns the HTLC parameters if the given script is a SHA-256 HTLC script, otherwise None
+    pub fn from_script(script: &TariScript) -> Option<Self> {
+        use Opcode::{CheckHeightVerify, PushHash, PushPubKey};
+        let htlc = match script.opcodes() {
+            [_, PushHash(hash), _, _, PushPubKey(claim), _, CheckHeightVerify(height), PushPubKey(refund), _] => {
+                Self::new(**hash, (**claim).clone(), (**refund).clone(), *height)
+            },
+            _ => return None,
+        };
+        // The remaining opcodes must match the template exactly
+        if htlc.to_script() == *script {
+            Some(htlc)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if `pre_image` unlocks the claim path
+    pub fn is_pre_image(&self, pre_image: &PublicKey) -> bool {
+        sha256_hash_lock(pre_image) == self.hash
+    }
+
+    /// The input data that spends the output through the claim path. The spend must be signed with the claim key.
+    pub fn claim_input_data(pre_image: &PublicKey) -> ExecutionStack {
+        inputs!(pre_image.clone())
+    }
+
+    /// The input data that spends the output through the refund path. The spend must be signed with the refund key
+    /// and cannot be mined before the lock height.
+    pub fn refund_input_data(&self) -> ExecutionStack {
+        inputs!(self.refund_public_key.clone())
+    }
+
+    /// Extract the pre-image from the input data of a spend of this HTLC. Returns `None` if the output was refunded.
+    pub fn pre_image_from_input_data(&self, input_data: &ExecutionStack) -> Option<PublicKey> {
+        match input_data.peek() {
+            Some(StackItem::PublicKey(pre_image)) if self.is_pre_image(pre_image) => Some(pre_image.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::OsRng;
+    use tari_common_types::types::Commitment;
+    use tari_crypto::keys::PublicKey as PublicKeyTrait;
+    use tari_script::{ScriptContext, ScriptError};
+
+    use super::*;
+
+    fn random_public_key() -> PublicKey {
+        PublicKey::random_keypair(&mut OsRng).1
+    }
+
+    fn context(height: u64) -> ScriptContext {
+        ScriptContext::new(height, &HashValue::default(), &Commitment::default())
+    }
+
+    #[test]
+    fn it_round_trips_the_script() {
+        let htlc = ShaHtlcScript::new(
+            sha256_hash_lock(&random_public_key()),
+            random_public_key(),
+            random_public_key(),
+            720,
+        );
+        assert_eq!(ShaHtlcScript::from_script(&htlc.to_script()), Some(htlc.clone()));
+        assert_eq!(ShaHtlcScript::from_script(&script!(Nop)), None);
+
+        let mut opcodes = htlc.to_script().opcodes().to_vec();
+        opcodes[0] = Opcode::HashSha3;
+        assert_eq!(ShaHtlcScript::from_script(&TariScript::new(opcodes)), None);
+    }
+
+    #[test]
+    fn it_unlocks_the_claim_and_refund_paths() {
+        let pre_image = random_public_key();
+        let htlc = ShaHtlcScript::new(
+            sha256_hash_lock(&pre_image),
+            random_public_key(),
+            random_public_key(),
+            720,
+        );
+        let script = htlc.to_script();
+
+        let claim_inputs = ShaHtlcScript::claim_input_data(&pre_image);
+        assert_eq!(
+            script.execute_with_context(&claim_inputs, &context(1)),
+            Ok(StackItem::PublicKey(htlc.claim_public_key.clone()))
+        );
+
+        let refund_inputs = htlc.refund_input_data();
+        assert_eq!(
+            script.execute_with_context(&refund_inputs, &context(719)),
+            Err(ScriptError::VerifyFailed)
+        );
+        assert_eq!(
+            script.execute_with_context(&refund_inputs, &context(720)),
+            Ok(StackItem::PublicKey(htlc.refund_public_key.clone()))
+        );
+    }
+
+    #[test]
+    fn it_extracts_the_pre_image_from_a_claim() {
+        let pre_image = random_public_key();
+        let htlc = ShaHtlcScript::new(
+            sha256_hash_lock(&pre_image),
+            random_public_key(),
+            random_public_key(),
+            720,
+        );
+        assert_eq!(
+            htlc.pre_image_from_input_data(&ShaHtlcScript::claim_input_data(&pre_image)),
+            Some(pre_image)
+        );
+        assert_eq!(htlc.pre_image_from_input_data(&htlc.refund_input_data()), None);
+    }
+}
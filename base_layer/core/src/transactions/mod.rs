@@ -10,6 +10,7 @@ mod coinbase_builder;
 pub use coinbase_builder::{CoinbaseBuildError, CoinbaseBuilder};
 
 pub mod fee;
+pub mod htlc;
 pub mod tari_amount;
 pub mod transaction_components;
 
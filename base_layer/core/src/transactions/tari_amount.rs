@@ -46,7 +46,10 @@ use super::format_currency;
 /// let b = MicroTari::from(50);
 /// assert_eq!(a + b, MicroTari::from(550));
 /// ```
+///
+/// `MicroTari` is `#[repr(transparent)]` and so has the same layout and ABI as a `u64`.
 #[derive(Copy, Default, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
+#[repr(transparent)]
 pub struct MicroTari(pub u64);
 
 #[derive(Debug, Clone, ThisError, PartialEq)]
@@ -55,6 +58,12 @@ pub enum MicroTariError {
     ParseError(String),
     #[error("Failed to convert value: {0}")]
     ConversionError(#[from] DecimalConvertError),
+    #[error("No unit given for amount '{0}'. Specify T, mT or µT (uT)")]
+    MissingUnit(String),
+    #[error("Arithmetic overflow: {1} {0} {2}")]
+    Overflow(&'static str, MicroTari, MicroTari),
+    #[error("Amount {0} µT is too large to pass across the FFI (max {} µT)", i64::MAX)]
+    FfiOutOfRange(u64),
 }
 /// A convenience constant that makes it easier to define Tari amounts.
 /// ```edition2018
@@ -68,19 +77,107 @@ pub const uT: MicroTari = MicroTari(1);
 pub const T: MicroTari = MicroTari(1_000_000);
 
 // You can only add or subtract µT from µT
-newtype_ops! { [MicroTari] {add sub mul div} {:=} Self Self }
-newtype_ops! { [MicroTari] {add sub mul div} {:=} &Self &Self }
-newtype_ops! { [MicroTari] {add sub mul div} {:=} Self &Self }
+#[cfg(not(feature = "checked_arithmetic"))]
+newtype_ops! { [MicroTari] {add sub mul} {:=} Self Self }
+#[cfg(not(feature = "checked_arithmetic"))]
+newtype_ops! { [MicroTari] {add sub mul} {:=} &Self &Self }
+#[cfg(not(feature = "checked_arithmetic"))]
+newtype_ops! { [MicroTari] {add sub mul} {:=} Self &Self }
+newtype_ops! { [MicroTari] {div} {:=} Self Self }
+newtype_ops! { [MicroTari] {div} {:=} &Self &Self }
+newtype_ops! { [MicroTari] {div} {:=} Self &Self }
 
 // Multiplication and division only makes sense when µT is multiplied/divided by a scalar
-newtype_ops! { [MicroTari] {mul div rem} {:=} Self u64 }
-newtype_ops! { [MicroTari] {mul div rem} {:=} &Self u64 }
+#[cfg(not(feature = "checked_arithmetic"))]
+newtype_ops! { [MicroTari] {mul} {:=} Self u64 }
+#[cfg(not(feature = "checked_arithmetic"))]
+newtype_ops! { [MicroTari] {mul} {:=} &Self u64 }
+newtype_ops! { [MicroTari] {div rem} {:=} Self u64 }
+newtype_ops! { [MicroTari] {div rem} {:=} &Self u64 }
 
 impl Mul<MicroTari> for u64 {
     type Output = MicroTari;
 
     fn mul(self, rhs: MicroTari) -> Self::Output {
-        MicroTari(self * rhs.0)
+        rhs * self
+    }
+}
+
+/// With the `checked_arithmetic` feature enabled, µT addition, subtraction and multiplication panic with a
+/// [MicroTariError::Overflow] in every build profile, instead of wrapping silently in release builds.
+#[cfg(feature = "checked_arithmetic")]
+mod checked_ops {
+    use std::ops::{AddAssign, SubAssign};
+
+    use super::*;
+
+    macro_rules! impl_checked_op {
+        ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $checked:ident, $op:expr) => {
+            impl $trait<MicroTari> for MicroTari {
+                type Output = MicroTari;
+
+                fn $method(self, rhs: MicroTari) -> MicroTari {
+                    match self.as_u64().$checked(rhs.as_u64()) {
+                        Some(v) => MicroTari(v),
+                        None => panic!("{}", MicroTariError::Overflow($op, self, rhs)),
+                    }
+                }
+            }
+
+            impl<'a> $trait<&'a MicroTari> for MicroTari {
+                type Output = MicroTari;
+
+                fn $method(self, rhs: &'a MicroTari) -> MicroTari {
+                    self.$method(*rhs)
+                }
+            }
+
+            impl<'a, 'b> $trait<&'a MicroTari> for &'b MicroTari {
+                type Output = MicroTari;
+
+                fn $method(self, rhs: &'a MicroTari) -> MicroTari {
+                    (*self).$method(*rhs)
+                }
+            }
+
+            impl $assign_trait<MicroTari> for MicroTari {
+                fn $assign_method(&mut self, rhs: MicroTari) {
+                    *self = (*self).$method(rhs);
+                }
+            }
+
+            impl<'a> $assign_trait<&'a MicroTari> for MicroTari {
+                fn $assign_method(&mut self, rhs: &'a MicroTari) {
+                    *self = (*self).$method(*rhs);
+                }
+            }
+        };
+    }
+
+    impl_checked_op!(Add, add, AddAssign, add_assign, checked_add, "+");
+    impl_checked_op!(Sub, sub, SubAssign, sub_assign, checked_sub, "-");
+    impl_checked_op!(Mul, mul, MulAssign, mul_assign, checked_mul, "*");
+
+    impl Mul<u64> for MicroTari {
+        type Output = MicroTari;
+
+        fn mul(self, rhs: u64) -> MicroTari {
+            self * MicroTari(rhs)
+        }
+    }
+
+    impl<'a> Mul<u64> for &'a MicroTari {
+        type Output = MicroTari;
+
+        fn mul(self, rhs: u64) -> MicroTari {
+            *self * MicroTari(rhs)
+        }
+    }
+
+    impl MulAssign<u64> for MicroTari {
+        fn mul_assign(&mut self, rhs: u64) {
+            *self = *self * MicroTari(rhs);
+        }
     }
 }
 
@@ -90,10 +187,7 @@ impl MicroTari {
     }
 
     pub fn checked_sub(self, v: MicroTari) -> Option<MicroTari> {
-        if self >= v {
-            return Some(self - v);
-        }
-        None
+        self.as_u64().checked_sub(v.as_u64()).map(Into::into)
     }
 
     pub fn checked_mul(self, v: MicroTari) -> Option<MicroTari> {
@@ -104,11 +198,49 @@ impl MicroTari {
         self.as_u64().checked_div(v.as_u64()).map(Into::into)
     }
 
+    /// Adds `v` and returns a typed error on overflow
+    pub fn try_add(self, v: MicroTari) -> Result<MicroTari, MicroTariError> {
+        self.checked_add(v).ok_or(MicroTariError::Overflow("+", self, v))
+    }
+
+    /// Subtracts `v` and returns a typed error on underflow
+    pub fn try_sub(self, v: MicroTari) -> Result<MicroTari, MicroTariError> {
+        self.checked_sub(v).ok_or(MicroTariError::Overflow("-", self, v))
+    }
+
+    /// Multiplies by `v` and returns a typed error on overflow
+    pub fn try_mul(self, v: MicroTari) -> Result<MicroTari, MicroTariError> {
+        self.checked_mul(v).ok_or(MicroTariError::Overflow("*", self, v))
+    }
+
+    /// Returns the wrapped sum and whether an overflow occurred
+    pub fn overflowing_add(self, v: MicroTari) -> (MicroTari, bool) {
+        let (v, overflowed) = self.as_u64().overflowing_add(v.as_u64());
+        (MicroTari(v), overflowed)
+    }
+
+    /// Returns the wrapped difference and whether an underflow occurred
+    pub fn overflowing_sub(self, v: MicroTari) -> (MicroTari, bool) {
+        let (v, overflowed) = self.as_u64().overflowing_sub(v.as_u64());
+        (MicroTari(v), overflowed)
+    }
+
+    /// Returns the wrapped product and whether an overflow occurred
+    pub fn overflowing_mul(self, v: MicroTari) -> (MicroTari, bool) {
+        let (v, overflowed) = self.as_u64().overflowing_mul(v.as_u64());
+        (MicroTari(v), overflowed)
+    }
+
+    pub fn saturating_add(self, v: MicroTari) -> MicroTari {
+        MicroTari(self.as_u64().saturating_add(v.as_u64()))
+    }
+
     pub fn saturating_sub(self, v: MicroTari) -> MicroTari {
-        if self >= v {
-            return self - v;
-        }
-        Self(0)
+        MicroTari(self.as_u64().saturating_sub(v.as_u64()))
+    }
+
+    pub fn saturating_mul(self, v: MicroTari) -> MicroTari {
+        MicroTari(self.as_u64().saturating_mul(v.as_u64()))
     }
 
     #[inline]
@@ -138,32 +270,185 @@ impl From<MicroTari> for u64 {
     }
 }
 
+impl MicroTari {
+    /// Parses an amount that must have an explicit unit suffix i.e. `T`, `mT` or `µT` (`uT`). Unlike `from_str`, a
+    /// bare number such as "5" is rejected because it is ambiguous.
+    ///
+    /// ```edition2018
+    /// use tari_core::transactions::tari_amount::{uT, MicroTari, T};
+    /// assert_eq!(MicroTari::from_str_strict("5 T").unwrap(), 5 * T);
+    /// assert_eq!(MicroTari::from_str_strict("5 mT").unwrap(), 5_000 * uT);
+    /// assert!(MicroTari::from_str_strict("5").is_err());
+    /// ```
+    pub fn from_str_strict(s: &str) -> Result<Self, MicroTariError> {
+        parse_amount(s, true)
+    }
+}
+
+/// Conversions for the wallet FFI layer
+impl MicroTari {
+    /// The number of decimal places in the fixed-point string representation i.e. 1 T = 1.000000
+    pub const FIXED_POINT_DECIMALS: usize = 6;
+    /// The largest amount that can be passed across the FFI. Amounts are limited to `i64::MAX` so that they can be
+    /// represented by the signed 64-bit integer types on mobile platforms (e.g. Java's `long`).
+    pub const MAX_FFI: MicroTari = MicroTari(i64::MAX as u64);
+
+    /// Returns the raw µT value of this amount for passing across the FFI
+    #[inline]
+    pub fn to_ffi_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Converts a raw µT value received over the FFI into `MicroTari`, rejecting values larger than
+    /// [MicroTari::MAX_FFI].
+    ///
+    /// ```edition2018
+    /// use tari_core::transactions::tari_amount::MicroTari;
+    /// assert_eq!(
+    ///     MicroTari::try_from_ffi_u64(1_500_000).unwrap(),
+    ///     MicroTari::from(1_500_000)
+    /// );
+    /// assert!(MicroTari::try_from_ffi_u64(u64::MAX).is_err());
+    /// ```
+    pub fn try_from_ffi_u64(v: u64) -> Result<Self, MicroTariError> {
+        if v > Self::MAX_FFI.0 {
+            return Err(MicroTariError::FfiOutOfRange(v));
+        }
+        Ok(MicroTari(v))
+    }
+
+    /// Returns the amount in Tari as a fixed-point string with exactly 6 decimal places and no unit or separators e.g.
+    /// 1,234,567 µT is "1.234567". Unlike `Display`, the format does not depend on the size of the amount, and it is
+    /// losslessly parsed by [MicroTari::from_fixed_point_str].
+    pub fn to_fixed_point_string(self) -> String {
+        let divisor = T.0;
+        format!(
+            "{}.{:0width$}",
+            self.0 / divisor,
+            self.0 % divisor,
+            width = Self::FIXED_POINT_DECIMALS
+        )
+    }
+
+    /// Parses a fixed-point Tari string as produced by [MicroTari::to_fixed_point_string]. The fraction is optional
+    /// and may have at most 6 digits. Signs, units, exponents, separators and whitespace are rejected.
+    ///
+    /// ```edition2018
+    /// use tari_core::transactions::tari_amount::{uT, MicroTari, T};
+    /// assert_eq!(MicroTari::from_fixed_point_str("1.5").unwrap(), 1_500_000 * uT);
+    /// assert_eq!(MicroTari::from_fixed_point_str("2").unwrap(), 2 * T);
+    /// assert!(MicroTari::from_fixed_point_str("1.0000001").is_err());
+    /// ```
+    pub fn from_fixed_point_str(s: &str) -> Result<Self, MicroTariError> {
+        let invalid = || MicroTariError::ParseError(format!("Invalid fixed-point amount '{}'", s));
+        let (integer, fraction) = match s.find('.') {
+            Some(pos) => (&s[..pos], Some(&s[pos + 1..])),
+            None => (s, None),
+        };
+        let is_digits = |v: &str| !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit());
+        if !is_digits(integer) {
+            return Err(invalid());
+        }
+        let fraction = match fraction {
+            Some(f) if is_digits(f) && f.len() <= Self::FIXED_POINT_DECIMALS => {
+                format!("{:0<width$}", f, width = Self::FIXED_POINT_DECIMALS)
+            },
+            Some(_) => return Err(invalid()),
+            None => "0".to_string(),
+        };
+
+        let integer = integer.parse::<u64>().map_err(|_| DecimalConvertError::Overflow)?;
+        let fraction = fraction.parse::<u64>().map_err(|_| invalid())?;
+        integer
+            .checked_mul(T.0)
+            .and_then(|v| v.checked_add(fraction))
+            .map(MicroTari)
+            .ok_or_else(|| DecimalConvertError::Overflow.into())
+    }
+}
+
 impl std::str::FromStr for MicroTari {
     type Err = MicroTariError;
 
+    /// Parses an amount with an optional unit suffix: `T` (Tari), `mT` (milliTari) or `µT`/`uT` (microTari). Units are
+    /// case-sensitive. Amounts without a unit are interpreted as µT. Scientific notation (e.g. `1.5e3 uT`) and `,`
+    /// separators are accepted.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let processed = s.replace(",", "").replace(" ", "").to_ascii_lowercase();
-        // Is this Tari or MicroTari
-        let is_micro_tari = if processed.ends_with("ut") || processed.ends_with("µt") {
-            true
-        } else {
-            !processed.ends_with('t')
-        };
+        parse_amount(s, false)
+    }
+}
 
-        let processed = processed.replace("ut", "").replace("µt", "").replace("t", "");
-        if is_micro_tari {
-            processed
-                .parse::<u64>()
-                .map(MicroTari::from)
-                .map_err(|e| MicroTariError::ParseError(e.to_string()))
-        } else {
-            processed
-                .parse::<Decimal>()
-                .map_err(|e| MicroTariError::ParseError(e.to_string()))
-                .and_then(Tari::try_from)
-                .map(MicroTari::from)
+/// The number of decimal places each unit is shifted by relative to µT. Units are matched case-sensitively, so that
+/// e.g. `MT` is not mistaken for `mT`. `T` must come last since it is a suffix of the other units.
+const TARI_UNITS: &[(&str, u32)] = &[("µT", 0), ("uT", 0), ("mT", 3), ("T", 6)];
+
+fn parse_amount(s: &str, strict: bool) -> Result<MicroTari, MicroTariError> {
+    let processed = s.replace(",", "").replace(" ", "");
+    let unit = TARI_UNITS.iter().find(|(suffix, _)| processed.ends_with(suffix));
+    let (number, unit_exponent) = match unit {
+        Some((suffix, exp)) => (&processed[..processed.len() - suffix.len()], *exp),
+        None if strict => return Err(MicroTariError::MissingUnit(s.to_string())),
+        None => (processed.as_str(), 0),
+    };
+
+    let (mantissa, exponent) = parse_decimal(number)?;
+    let exponent = exponent.saturating_add(i64::from(unit_exponent));
+    let micro_tari = if exponent >= 0 {
+        u32::try_from(exponent)
+            .ok()
+            .and_then(|e| 10u128.checked_pow(e))
+            .and_then(|m| mantissa.checked_mul(m))
+            .ok_or(DecimalConvertError::Overflow)?
+    } else {
+        let divisor = u32::try_from(exponent.saturating_neg())
+            .ok()
+            .and_then(|e| 10u128.checked_pow(e))
+            .unwrap_or(u128::MAX);
+        if mantissa % divisor != 0 {
+            return Err(MicroTariError::ParseError(format!(
+                "'{}' has more precision than the smallest unit (1 µT)",
+                s
+            )));
         }
-    }
+        mantissa / divisor
+    };
+
+    let micro_tari = u64::try_from(micro_tari).map_err(|_| DecimalConvertError::Overflow)?;
+    Ok(MicroTari(micro_tari))
+}
+
+/// Parses a non-negative decimal number, optionally in scientific notation, into its mantissa and base 10 exponent
+/// e.g. "1.25e3" is parsed as (125, 1).
+fn parse_decimal(s: &str) -> Result<(u128, i64), MicroTariError> {
+    let invalid = || MicroTariError::ParseError(format!("Invalid amount '{}'", s));
+    let (significand, exponent) = match s.find(|c| c == 'e' || c == 'E') {
+        Some(pos) => (&s[..pos], s[pos + 1..].parse::<i64>().map_err(|_| invalid())?),
+        None => (s, 0),
+    };
+
+    let (integer, fraction) = match significand.find('.') {
+        Some(pos) => (&significand[..pos], &significand[pos + 1..]),
+        None => (significand, ""),
+    };
+    if integer.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    if !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    let digits = format!("{}{}", integer, fraction);
+    let digits = digits.trim_start_matches('0');
+    let mantissa = if digits.is_empty() {
+        0
+    } else {
+        digits
+            .parse::<u128>()
+            .map_err(|e| MicroTariError::ParseError(e.to_string()))?
+    };
+    let fraction_len = i64::try_from(fraction.len()).map_err(|_| invalid())?;
+    Ok((mantissa, exponent.saturating_sub(fraction_len)))
 }
 
 impl From<u64> for MicroTari {
@@ -371,6 +656,41 @@ mod test {
         assert_eq!(a % 50, MicroTari::from(5));
     }
 
+    #[test]
+    fn micro_tari_overflow_handling() {
+        let max = MicroTari(u64::MAX);
+        let one = MicroTari(1);
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(MicroTari(0).checked_sub(one), None);
+        assert_eq!(max.try_add(one), Err(MicroTariError::Overflow("+", max, one)));
+        assert_eq!(
+            MicroTari(0).try_sub(one),
+            Err(MicroTariError::Overflow("-", MicroTari(0), one))
+        );
+        assert_eq!(
+            max.try_mul(MicroTari(2)),
+            Err(MicroTariError::Overflow("*", max, MicroTari(2)))
+        );
+        assert_eq!(MicroTari(2).try_mul(MicroTari(3)), Ok(MicroTari(6)));
+
+        assert_eq!(max.overflowing_add(one), (MicroTari(0), true));
+        assert_eq!(MicroTari(0).overflowing_sub(one), (max, true));
+        assert_eq!(max.overflowing_mul(MicroTari(2)), (MicroTari(u64::MAX - 1), true));
+        assert_eq!(one.overflowing_add(one), (MicroTari(2), false));
+
+        assert_eq!(max.saturating_add(one), max);
+        assert_eq!(MicroTari(0).saturating_sub(one), MicroTari(0));
+        assert_eq!(max.saturating_mul(MicroTari(2)), max);
+        assert_eq!(MicroTari(3).saturating_mul(MicroTari(3)), MicroTari(9));
+    }
+
+    #[cfg(feature = "checked_arithmetic")]
+    #[test]
+    #[should_panic(expected = "Arithmetic overflow")]
+    fn checked_arithmetic_panics_on_overflow() {
+        let _ = MicroTari(u64::MAX) * 2;
+    }
+
     #[test]
     fn micro_tari_display() {
         let s = format!("{}", MicroTari::from(1234));
@@ -424,6 +744,38 @@ mod test {
         assert!(MicroTari::from_str("5garbage T").is_err());
     }
 
+    #[test]
+    fn micro_tari_from_string_with_units() {
+        assert_eq!(MicroTari::from_str("5 mT").unwrap(), MicroTari::from(5_000));
+        assert_eq!(MicroTari::from_str("1.5mT").unwrap(), MicroTari::from(1_500));
+        assert_eq!(MicroTari::from_str("5 µT").unwrap(), MicroTari::from(5));
+        assert_eq!(MicroTari::from_str("0.000001 T").unwrap(), MicroTari::from(1));
+        assert_eq!(MicroTari::from_str("1e3").unwrap(), MicroTari::from(1_000));
+        assert_eq!(MicroTari::from_str("1.5e3 uT").unwrap(), MicroTari::from(1_500));
+        assert_eq!(MicroTari::from_str("2.5e-3 T").unwrap(), MicroTari::from(2_500));
+        assert_eq!(MicroTari::from_str("1E2 mT").unwrap(), MicroTari::from(100_000));
+        assert!(MicroTari::from_str("1E2 MT").is_err());
+        assert!(MicroTari::from_str("5 t").is_err());
+        assert!(MicroTari::from_str("5 UT").is_err());
+        assert!(MicroTari::from_str("1.5 uT").is_err());
+        assert!(MicroTari::from_str("0.0000001 T").is_err());
+        assert!(MicroTari::from_str("1e30 T").is_err());
+        assert!(MicroTari::from_str("T").is_err());
+        assert!(MicroTari::from_str("1e T").is_err());
+    }
+
+    #[test]
+    fn micro_tari_from_string_strict() {
+        assert_eq!(MicroTari::from_str_strict("5 T").unwrap(), MicroTari::from(5_000_000));
+        assert_eq!(MicroTari::from_str_strict("5mT").unwrap(), MicroTari::from(5_000));
+        assert_eq!(MicroTari::from_str_strict("5 uT").unwrap(), MicroTari::from(5));
+        assert_eq!(
+            MicroTari::from_str_strict("5").unwrap_err(),
+            MicroTariError::MissingUnit("5".to_string())
+        );
+        assert!(MicroTari::from_str_strict("5,000").is_err());
+    }
+
     #[test]
     fn add_tari_and_microtari() {
         let a = MicroTari::from(100_000);
@@ -463,4 +815,58 @@ mod test {
         );
         assert_eq!(s, "99.100000 T");
     }
+
+    #[test]
+    fn ffi_u64_conversion() {
+        assert_eq!(std::mem::size_of::<MicroTari>(), std::mem::size_of::<u64>());
+        let v = MicroTari::from(123_456);
+        assert_eq!(MicroTari::try_from_ffi_u64(v.to_ffi_u64()).unwrap(), v);
+        assert_eq!(
+            MicroTari::try_from_ffi_u64(MicroTari::MAX_FFI.to_ffi_u64()).unwrap(),
+            MicroTari::MAX_FFI
+        );
+        assert_eq!(
+            MicroTari::try_from_ffi_u64(u64::MAX),
+            Err(MicroTariError::FfiOutOfRange(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn fixed_point_string_round_trip() {
+        let cases = [
+            (0, "0.000000"),
+            (1, "0.000001"),
+            (1_000_000, "1.000000"),
+            (1_234_567, "1.234567"),
+            (u64::MAX, "18446744073709.551615"),
+        ];
+        for (v, s) in cases {
+            let v = MicroTari::from(v);
+            assert_eq!(v.to_fixed_point_string(), s);
+            assert_eq!(MicroTari::from_fixed_point_str(s).unwrap(), v);
+        }
+
+        assert_eq!(MicroTari::from_fixed_point_str("12").unwrap(), 12 * T);
+        assert_eq!(MicroTari::from_fixed_point_str("0.5").unwrap(), 500_000 * uT);
+        for invalid in [
+            "",
+            ".",
+            ".5",
+            "1.",
+            "-1",
+            "+1",
+            "1.0000001",
+            "1,000",
+            " 1",
+            "1 T",
+            "1e3",
+            "18446744073709.551616",
+        ] {
+            assert!(
+                MicroTari::from_fixed_point_str(invalid).is_err(),
+                "'{}' should not parse",
+                invalid
+            );
+        }
+    }
 }
@@ -585,6 +585,7 @@ pub fn create_sender_transaction_protocol_with(
     let test_params = TestParams::new();
     let constants = ConsensusManager::builder(Network::LocalNet)
         .build()
+        .unwrap()
         .consensus_constants(0)
         .clone();
     let mut stx_builder = SenderTransactionProtocol::builder(0, constants);
@@ -631,6 +632,7 @@ pub fn create_stx_protocol(schema: TransactionSchema) -> (SenderTransactionProto
     let output_version = TransactionOutputVersion::get_current_version();
     let constants = ConsensusManager::builder(Network::LocalNet)
         .build()
+        .unwrap()
         .consensus_constants(0)
         .clone();
     let mut stx_builder = SenderTransactionProtocol::builder(0, constants);
@@ -20,51 +20,36 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{
-    io,
-    io::{Read, Write},
-};
-
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::PublicKey;
 
 use crate::{
-    consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized, MaxSizeVec},
+    consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized},
     transactions::transaction_components::TemplateParameter,
 };
 
-#[derive(Debug, Clone, Hash, PartialEq, Deserialize, Serialize, Eq)]
+/// The maximum number of template ids an asset can implement
+pub(super) const MAX_TEMPLATES: usize = 50;
+/// The maximum number of template parameters of an asset
+pub(super) const MAX_TEMPLATE_PARAMS: usize = 50;
+
+#[derive(
+    Debug,
+    Clone,
+    Hash,
+    PartialEq,
+    Deserialize,
+    Serialize,
+    Eq,
+    ConsensusEncoding,
+    ConsensusEncodingSized,
+    ConsensusDecoding,
+)]
 pub struct AssetOutputFeatures {
     pub public_key: PublicKey,
     // TODO: remove in favour of template args
+    #[consensus(max_size = MAX_TEMPLATES)]
     pub template_ids_implemented: Vec<u32>,
+    #[consensus(max_size = MAX_TEMPLATE_PARAMS)]
     pub template_parameters: Vec<TemplateParameter>,
 }
-
-impl ConsensusEncoding for AssetOutputFeatures {
-    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
-        let mut written = self.public_key.consensus_encode(writer)?;
-        written += self.template_ids_implemented.consensus_encode(writer)?;
-        written += self.template_parameters.consensus_encode(writer)?;
-        Ok(written)
-    }
-}
-
-impl ConsensusEncodingSized for AssetOutputFeatures {}
-
-impl ConsensusDecoding for AssetOutputFeatures {
-    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, io::Error> {
-        let public_key = PublicKey::consensus_decode(reader)?;
-        const MAX_TEMPLATES: usize = 50;
-        let template_ids_implemented = MaxSizeVec::<u32, MAX_TEMPLATES>::consensus_decode(reader)?;
-
-        const MAX_TEMPLATE_PARAMS: usize = 50;
-        let template_parameters = MaxSizeVec::<TemplateParameter, MAX_TEMPLATE_PARAMS>::consensus_decode(reader)?;
-
-        Ok(Self {
-            public_key,
-            template_ids_implemented: template_ids_implemented.into(),
-            template_parameters: template_parameters.into(),
-        })
-    }
-}
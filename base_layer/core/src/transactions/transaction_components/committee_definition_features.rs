@@ -20,29 +20,23 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::io::{Error, Read, Write};
-
 use integer_encoding::VarInt;
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::PublicKey;
 use tari_crypto::keys::PublicKey as PublicKeyTrait;
 
-use crate::consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized, MaxSizeVec};
+use crate::consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized};
+
+/// The maximum number of committee members
+const MAX_COMMITTEE_KEYS: usize = 50;
 
-#[derive(Debug, Clone, Hash, PartialEq, Deserialize, Serialize, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Deserialize, Serialize, Eq, ConsensusEncoding, ConsensusDecoding)]
 pub struct CommitteeDefinitionFeatures {
+    #[consensus(max_size = MAX_COMMITTEE_KEYS)]
     pub committee: Vec<PublicKey>,
     pub effective_sidechain_height: u64,
 }
 
-impl ConsensusEncoding for CommitteeDefinitionFeatures {
-    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-        let mut written = self.committee.consensus_encode(writer)?;
-        written += self.effective_sidechain_height.consensus_encode(writer)?;
-        Ok(written)
-    }
-}
-
 impl ConsensusEncodingSized for CommitteeDefinitionFeatures {
     fn consensus_encode_exact_size(&self) -> usize {
         self.committee.len().required_space() +
@@ -51,19 +45,6 @@ impl ConsensusEncodingSized for CommitteeDefinitionFeatures {
     }
 }
 
-impl ConsensusDecoding for CommitteeDefinitionFeatures {
-    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, Error> {
-        const MAX_COMMITTEE_KEYS: usize = 50;
-        let committee = MaxSizeVec::<PublicKey, MAX_COMMITTEE_KEYS>::consensus_decode(reader)?;
-        let effective_sidechain_height = u64::consensus_decode(reader)?;
-
-        Ok(Self {
-            committee: committee.into(),
-            effective_sidechain_height,
-        })
-    }
-}
-
 #[cfg(test)]
 mod test {
     use std::{io::ErrorKind, iter};
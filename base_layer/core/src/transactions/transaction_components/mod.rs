@@ -32,6 +32,7 @@ pub use kernel_features::KernelFeatures;
 pub use kernel_sum::KernelSum;
 pub use mint_non_fungible_features::MintNonFungibleFeatures;
 pub use output_features::OutputFeatures;
+pub use output_features_builder::{OutputFeaturesBuilder, OutputFeaturesBuilderError};
 pub use output_features_version::OutputFeaturesVersion;
 pub use output_flags::OutputFlags;
 pub use rewind_result::RewindResult;
@@ -59,6 +60,7 @@ mod kernel_features;
 mod kernel_sum;
 mod mint_non_fungible_features;
 mod output_features;
+mod output_features_builder;
 mod output_features_version;
 mod output_flags;
 mod rewind_result;
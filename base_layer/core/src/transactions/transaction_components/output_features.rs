@@ -53,6 +53,11 @@ use crate::{
     },
 };
 
+/// The maximum size of the unique id in bytes
+pub(super) const MAX_UNIQUE_ID_SIZE: usize = 256;
+/// The maximum size of the output metadata in bytes
+pub(super) const MAX_METADATA_SIZE: usize = 1024;
+
 /// Options for UTXO's
 #[derive(Debug, Clone, Hash, PartialEq, Deserialize, Serialize, Eq)]
 pub struct OutputFeatures {
@@ -367,13 +372,11 @@ impl ConsensusDecoding for OutputFeatures {
             OutputFeaturesVersion::V1 => OutputFeatures::consensus_decode_recovery_byte(reader)?,
         };
         let parent_public_key = <Option<PublicKey> as ConsensusDecoding>::consensus_decode(reader)?;
-        const MAX_UNIQUE_ID_SIZE: usize = 256;
         let unique_id = <Option<MaxSizeBytes<MAX_UNIQUE_ID_SIZE>> as ConsensusDecoding>::consensus_decode(reader)?;
         let asset = <Option<AssetOutputFeatures> as ConsensusDecoding>::consensus_decode(reader)?;
         let mint_non_fungible = <Option<MintNonFungibleFeatures> as ConsensusDecoding>::consensus_decode(reader)?;
         let sidechain_checkpoint =
             <Option<SideChainCheckpointFeatures> as ConsensusDecoding>::consensus_decode(reader)?;
-        const MAX_METADATA_SIZE: usize = 1024;
         let metadata = <MaxSizeBytes<MAX_METADATA_SIZE> as ConsensusDecoding>::consensus_decode(reader)?;
         let committee_definition = match version {
             OutputFeaturesVersion::V0 => None,
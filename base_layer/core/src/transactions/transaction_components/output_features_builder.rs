@@ -0,0 +1,410 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use tari_common_types::types::{FixedHash, PublicKey};
+use thiserror::Error;
+
+use super::{
+    asset_output_features::{MAX_TEMPLATES, MAX_TEMPLATE_PARAMS},
+    output_features::{MAX_METADATA_SIZE, MAX_UNIQUE_ID_SIZE},
+    side_chain_checkpoint_features::MAX_COMMITTEE_KEYS,
+    template_parameter::MAX_TEMPLATE_DATA_LEN,
+};
+use crate::transactions::transaction_components::{OutputFeatures, OutputFlags, TemplateParameter};
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum OutputFeaturesBuilderError {
+    #[error("Metadata is {size} bytes which exceeds the maximum of {max} bytes")]
+    MetadataTooLarge { size: usize, max: usize },
+    #[error("Unique id is {size} bytes which exceeds the maximum of {max} bytes")]
+    UniqueIdTooLarge { size: usize, max: usize },
+    #[error("Unique id must not be empty")]
+    EmptyUniqueId,
+    #[error("{count} template ids exceeds the maximum of {max}")]
+    TooManyTemplateIds { count: usize, max: usize },
+    #[error("{count} template parameters exceeds the maximum of {max}")]
+    TooManyTemplateParameters { count: usize, max: usize },
+    #[error("Data for template {template_id} is {size} bytes which exceeds the maximum of {max} bytes")]
+    TemplateDataTooLarge { template_id: u32, size: usize, max: usize },
+    #[error("Committee of {count} members exceeds the maximum of {max}")]
+    CommitteeTooLarge { count: usize, max: usize },
+    #[error("`{setter}` does not apply to {kind} features")]
+    SetterNotApplicable { setter: &'static str, kind: &'static str },
+}
+
+#[derive(Debug, Clone)]
+enum FeaturesKind {
+    AssetRegistration {
+        public_key: PublicKey,
+        template_ids_implemented: Vec<u32>,
+        template_parameters: Vec<TemplateParameter>,
+    },
+    Checkpoint {
+        parent_public_key: PublicKey,
+        unique_id: Vec<u8>,
+        merkle_root: FixedHash,
+        committee: Vec<PublicKey>,
+        is_initial: bool,
+    },
+    Burn {
+        parent_public_key: PublicKey,
+        unique_id: Vec<u8>,
+    },
+}
+
+impl FeaturesKind {
+    fn name(&self) -> &'static str {
+        match self {
+            FeaturesKind::AssetRegistration { .. } => "asset registration",
+            FeaturesKind::Checkpoint { .. } => "checkpoint",
+            FeaturesKind::Burn { .. } => "burn",
+        }
+    }
+}
+
+/// Builds asset registration, sidechain checkpoint and non-fungible burn `OutputFeatures`, enforcing the consensus size
+/// limits at construction time. Features produced by this builder always round-trip through consensus encoding.
+/// Setters that do not apply to the kind of features being built cause `build` to fail, so that data is never silently
+/// dropped.
+#[derive(Debug, Clone)]
+pub struct OutputFeaturesBuilder {
+    kind: FeaturesKind,
+    maturity: u64,
+    metadata: Vec<u8>,
+    not_applicable_setter: Option<&'static str>,
+}
+
+impl OutputFeaturesBuilder {
+    /// Features that register a new asset with the given public key
+    pub fn asset_registration(public_key: PublicKey) -> Self {
+        Self::new(FeaturesKind::AssetRegistration {
+            public_key,
+            template_ids_implemented: Vec::new(),
+            template_parameters: Vec::new(),
+        })
+    }
+
+    /// Features for a sidechain checkpoint of the asset with the given public key. The committee is empty and the
+    /// checkpoint is not the initial checkpoint unless specified.
+    pub fn checkpoint(parent_public_key: PublicKey, unique_id: Vec<u8>, merkle_root: FixedHash) -> Self {
+        Self::new(FeaturesKind::Checkpoint {
+            parent_public_key,
+            unique_id,
+            merkle_root,
+            committee: Vec::new(),
+            is_initial: false,
+        })
+    }
+
+    /// Features that burn the non-fungible token with the given unique id of the asset with the given public key
+    pub fn burn(parent_public_key: PublicKey, unique_id: Vec<u8>) -> Self {
+        Self::new(FeaturesKind::Burn {
+            parent_public_key,
+            unique_id,
+        })
+    }
+
+    fn new(kind: FeaturesKind) -> Self {
+        Self {
+            kind,
+            maturity: 0,
+            metadata: Vec::new(),
+            not_applicable_setter: None,
+        }
+    }
+
+    fn not_applicable(mut self, setter: &'static str) -> Self {
+        self.not_applicable_setter.get_or_insert(setter);
+        self
+    }
+
+    pub fn with_maturity(mut self, maturity: u64) -> Self {
+        self.maturity = maturity;
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: Vec<u8>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Sets the template ids implemented by the asset. Only applies to asset registrations.
+    pub fn with_template_ids(mut self, template_ids: Vec<u32>) -> Self {
+        match self.kind {
+            FeaturesKind::AssetRegistration {
+                ref mut template_ids_implemented,
+                ..
+            } => {
+                *template_ids_implemented = template_ids;
+                self
+            },
+            _ => self.not_applicable("with_template_ids"),
+        }
+    }
+
+    /// Sets the template parameters of the asset. Only applies to asset registrations.
+    pub fn with_template_parameters(mut self, parameters: Vec<TemplateParameter>) -> Self {
+        match self.kind {
+            FeaturesKind::AssetRegistration {
+                ref mut template_parameters,
+                ..
+            } => {
+                *template_parameters = parameters;
+                self
+            },
+            _ => self.not_applicable("with_template_parameters"),
+        }
+    }
+
+    /// Sets the committee of the checkpoint. Only applies to checkpoints.
+    pub fn with_committee(mut self, members: Vec<PublicKey>) -> Self {
+        match self.kind {
+            FeaturesKind::Checkpoint { ref mut committee, .. } => {
+                *committee = members;
+                self
+            },
+            _ => self.not_applicable("with_committee"),
+        }
+    }
+
+    /// Marks the checkpoint as the initial checkpoint, which also mints the checkpoint unique id. Only applies to
+    /// checkpoints.
+    pub fn initial_checkpoint(mut self) -> Self {
+        match self.kind {
+            FeaturesKind::Checkpoint { ref mut is_initial, .. } => {
+                *is_initial = true;
+                self
+            },
+            _ => self.not_applicable("initial_checkpoint"),
+        }
+    }
+
+    pub fn build(self) -> Result<OutputFeatures, OutputFeaturesBuilderError> {
+        if let Some(setter) = self.not_applicable_setter {
+            return Err(OutputFeaturesBuilderError::SetterNotApplicable {
+                setter,
+                kind: self.kind.name(),
+            });
+        }
+        check_max_size(self.metadata.len(), MAX_METADATA_SIZE, |size, max| {
+            OutputFeaturesBuilderError::MetadataTooLarge { size, max }
+        })?;
+
+        let mut features = match self.kind {
+            FeaturesKind::AssetRegistration {
+                public_key,
+                template_ids_implemented,
+                template_parameters,
+            } => {
+                check_max_size(template_ids_implemented.len(), MAX_TEMPLATES, |count, max| {
+                    OutputFeaturesBuilderError::TooManyTemplateIds { count, max }
+                })?;
+                check_max_size(template_parameters.len(), MAX_TEMPLATE_PARAMS, |count, max| {
+                    OutputFeaturesBuilderError::TooManyTemplateParameters { count, max }
+                })?;
+                for parameter in &template_parameters {
+                    check_max_size(parameter.template_data.len(), MAX_TEMPLATE_DATA_LEN, |size, max| {
+                        OutputFeaturesBuilderError::TemplateDataTooLarge {
+                            template_id: parameter.template_id,
+                            size,
+                            max,
+                        }
+                    })?;
+                }
+                OutputFeatures::for_asset_registration(
+                    self.metadata,
+                    public_key,
+                    template_ids_implemented,
+                    template_parameters,
+                )
+            },
+            FeaturesKind::Checkpoint {
+                parent_public_key,
+                unique_id,
+                merkle_root,
+                committee,
+                is_initial,
+            } => {
+                check_unique_id(&unique_id)?;
+                check_max_size(committee.len(), MAX_COMMITTEE_KEYS, |count, max| {
+                    OutputFeaturesBuilderError::CommitteeTooLarge { count, max }
+                })?;
+                OutputFeatures {
+                    metadata: self.metadata,
+                    ..OutputFeatures::for_checkpoint(parent_public_key, unique_id, merkle_root, committee, is_initial)
+                }
+            },
+            FeaturesKind::Burn {
+                parent_public_key,
+                unique_id,
+            } => {
+                check_unique_id(&unique_id)?;
+                OutputFeatures {
+                    flags: OutputFlags::BURN_NON_FUNGIBLE,
+                    parent_public_key: Some(parent_public_key),
+                    unique_id: Some(unique_id),
+                    metadata: self.metadata,
+                    ..Default::default()
+                }
+            },
+        };
+        features.maturity = self.maturity;
+        Ok(features)
+    }
+}
+
+fn check_unique_id(unique_id: &[u8]) -> Result<(), OutputFeaturesBuilderError> {
+    if unique_id.is_empty() {
+        return Err(OutputFeaturesBuilderError::EmptyUniqueId);
+    }
+    check_max_size(unique_id.len(), MAX_UNIQUE_ID_SIZE, |size, max| {
+        OutputFeaturesBuilderError::UniqueIdTooLarge { size, max }
+    })
+}
+
+fn check_max_size<F>(size: usize, max: usize, err: F) -> Result<(), OutputFeaturesBuilderError>
+where F: FnOnce(usize, usize) -> OutputFeaturesBuilderError {
+    if size > max {
+        return Err(err(size, max));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use tari_utilities::ByteArray;
+
+    use super::*;
+    use crate::consensus::check_consensus_encoding_correctness;
+
+    #[test]
+    fn it_builds_an_asset_registration() {
+        let public_key = PublicKey::default();
+        let features = OutputFeaturesBuilder::asset_registration(public_key.clone())
+            .with_metadata(vec![1, 2, 3])
+            .with_template_ids(vec![1, 2])
+            .with_template_parameters(vec![TemplateParameter {
+                template_id: 1,
+                template_data_version: 0,
+                template_data: vec![0u8; 10],
+            }])
+            .build()
+            .unwrap();
+        assert_eq!(features.flags, OutputFlags::ASSET_REGISTRATION);
+        assert_eq!(features.unique_id, Some(public_key.as_bytes().to_vec()));
+        assert_eq!(features.asset.as_ref().unwrap().template_ids_implemented, vec![1, 2]);
+        check_consensus_encoding_correctness(features).unwrap();
+    }
+
+    #[test]
+    fn it_builds_a_checkpoint() {
+        let features = OutputFeaturesBuilder::checkpoint(PublicKey::default(), vec![1u8; 32], FixedHash::default())
+            .with_committee(vec![PublicKey::default(); 3])
+            .initial_checkpoint()
+            .with_maturity(10)
+            .build()
+            .unwrap();
+        assert!(features.flags.contains(OutputFlags::SIDECHAIN_CHECKPOINT));
+        assert!(features.is_non_fungible_mint());
+        assert_eq!(features.maturity, 10);
+        assert_eq!(features.sidechain_checkpoint.as_ref().unwrap().committee.len(), 3);
+        check_consensus_encoding_correctness(features).unwrap();
+    }
+
+    #[test]
+    fn it_builds_a_burn() {
+        let features = OutputFeaturesBuilder::burn(PublicKey::default(), vec![1u8; 32])
+            .build()
+            .unwrap();
+        assert!(features.is_non_fungible_burn());
+        check_consensus_encoding_correctness(features).unwrap();
+    }
+
+    #[test]
+    fn it_rejects_features_that_exceed_consensus_limits() {
+        let err = OutputFeaturesBuilder::burn(PublicKey::default(), vec![1u8; MAX_UNIQUE_ID_SIZE + 1])
+            .build()
+            .unwrap_err();
+        assert_eq!(err, OutputFeaturesBuilderError::UniqueIdTooLarge {
+            size: MAX_UNIQUE_ID_SIZE + 1,
+            max: MAX_UNIQUE_ID_SIZE
+        });
+
+        let err = OutputFeaturesBuilder::burn(PublicKey::default(), vec![])
+            .build()
+            .unwrap_err();
+        assert_eq!(err, OutputFeaturesBuilderError::EmptyUniqueId);
+
+        let err = OutputFeaturesBuilder::asset_registration(PublicKey::default())
+            .with_metadata(vec![0u8; MAX_METADATA_SIZE + 1])
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, OutputFeaturesBuilderError::MetadataTooLarge { .. }));
+
+        let err = OutputFeaturesBuilder::asset_registration(PublicKey::default())
+            .with_template_parameters(vec![TemplateParameter {
+                template_id: 5,
+                template_data_version: 0,
+                template_data: vec![0u8; MAX_TEMPLATE_DATA_LEN + 1],
+            }])
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, OutputFeaturesBuilderError::TemplateDataTooLarge {
+            template_id: 5,
+            ..
+        }));
+
+        let err = OutputFeaturesBuilder::checkpoint(PublicKey::default(), vec![1u8; 32], FixedHash::default())
+            .with_committee(vec![PublicKey::default(); MAX_COMMITTEE_KEYS + 1])
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, OutputFeaturesBuilderError::CommitteeTooLarge { .. }));
+    }
+
+    #[test]
+    fn it_rejects_setters_that_do_not_apply() {
+        let err = OutputFeaturesBuilder::burn(PublicKey::default(), vec![1u8; 32])
+            .with_committee(vec![PublicKey::default()])
+            .build()
+            .unwrap_err();
+        assert_eq!(err, OutputFeaturesBuilderError::SetterNotApplicable {
+            setter: "with_committee",
+            kind: "burn"
+        });
+
+        let err = OutputFeaturesBuilder::checkpoint(PublicKey::default(), vec![1u8; 32], FixedHash::default())
+            .with_template_ids(vec![1])
+            .initial_checkpoint()
+            .build()
+            .unwrap_err();
+        assert_eq!(err, OutputFeaturesBuilderError::SetterNotApplicable {
+            setter: "with_template_ids",
+            kind: "checkpoint"
+        });
+
+        let err = OutputFeaturesBuilder::asset_registration(PublicKey::default())
+            .initial_checkpoint()
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, OutputFeaturesBuilderError::SetterNotApplicable { .. }));
+    }
+}
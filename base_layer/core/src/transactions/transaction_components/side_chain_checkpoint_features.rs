@@ -20,50 +20,29 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::io::{Error, Read, Write};
-
 use integer_encoding::VarInt;
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::{FixedHash, PublicKey};
 use tari_crypto::keys::PublicKey as PublicKeyTrait;
 
-use crate::consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized, MaxSizeVec};
+use crate::consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized};
+
+/// The maximum number of committee members
+pub(super) const MAX_COMMITTEE_KEYS: usize = 50;
 
-#[derive(Debug, Clone, Hash, PartialEq, Deserialize, Serialize, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Deserialize, Serialize, Eq, ConsensusEncoding, ConsensusDecoding)]
 pub struct SideChainCheckpointFeatures {
     pub merkle_root: FixedHash,
+    #[consensus(max_size = MAX_COMMITTEE_KEYS)]
     pub committee: Vec<PublicKey>,
 }
 
-impl ConsensusEncoding for SideChainCheckpointFeatures {
-    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
-        self.merkle_root.consensus_encode(writer)?;
-        let mut written = 32;
-        written += self.committee.consensus_encode(writer)?;
-        Ok(written)
-    }
-}
-
 impl ConsensusEncodingSized for SideChainCheckpointFeatures {
     fn consensus_encode_exact_size(&self) -> usize {
         32 + self.committee.len().required_space() + self.committee.len() * PublicKey::key_length()
     }
 }
 
-impl ConsensusDecoding for SideChainCheckpointFeatures {
-    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, Error> {
-        let merkle_root = FixedHash::consensus_decode(reader)?;
-
-        const MAX_COMMITTEE_KEYS: usize = 50;
-        let committee = MaxSizeVec::<PublicKey, MAX_COMMITTEE_KEYS>::consensus_decode(reader)?;
-
-        Ok(Self {
-            merkle_root,
-            committee: committee.into(),
-        })
-    }
-}
-
 #[cfg(test)]
 mod test {
     use std::{io::ErrorKind, iter};
@@ -20,55 +20,41 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{
-    io,
-    io::{Read, Write},
-};
-
-use integer_encoding::{VarIntReader, VarIntWriter};
 use serde::{Deserialize, Serialize};
 
-use crate::consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized, MaxSizeBytes};
+use crate::consensus::{ConsensusDecoding, ConsensusEncoding, ConsensusEncodingSized};
+
+/// The maximum size of the template data of a template parameter in bytes
+pub(super) const MAX_TEMPLATE_DATA_LEN: usize = 1024;
 
-#[derive(Debug, Clone, Hash, PartialEq, Deserialize, Serialize, Eq)]
+#[derive(
+    Debug,
+    Clone,
+    Hash,
+    PartialEq,
+    Deserialize,
+    Serialize,
+    Eq,
+    ConsensusEncoding,
+    ConsensusEncodingSized,
+    ConsensusDecoding,
+)]
 pub struct TemplateParameter {
     pub template_id: u32,
     pub template_data_version: u32,
+    #[consensus(max_size = MAX_TEMPLATE_DATA_LEN)]
     pub template_data: Vec<u8>,
 }
 
-impl ConsensusEncoding for TemplateParameter {
-    fn consensus_encode<W: Write>(&self, writer: &mut W) -> Result<usize, io::Error> {
-        let mut written = writer.write_varint(self.template_id)?;
-        written += writer.write_varint(self.template_data_version)?;
-        written += self.template_data.consensus_encode(writer)?;
-        Ok(written)
-    }
-}
-
-impl ConsensusEncodingSized for TemplateParameter {}
-
-impl ConsensusDecoding for TemplateParameter {
-    fn consensus_decode<R: Read>(reader: &mut R) -> Result<Self, io::Error> {
-        let template_id = reader.read_varint()?;
-        let template_data_version = reader.read_varint()?;
-        const MAX_TEMPLATE_DATA_LEN: usize = 1024;
-        let template_data = MaxSizeBytes::<MAX_TEMPLATE_DATA_LEN>::consensus_decode(reader)?;
-
-        Ok(Self {
-            template_id,
-            template_data_version,
-            template_data: template_data.into(),
-        })
-    }
-}
-
 #[cfg(test)]
 mod test {
     use std::io::ErrorKind;
 
+    use integer_encoding::VarInt;
+    use rand::{Rng, RngCore};
+
     use super::*;
-    use crate::consensus::check_consensus_encoding_correctness;
+    use crate::consensus::{check_consensus_encoding_correctness, ToConsensusBytes};
 
     #[test]
     fn it_encodes_and_decodes_correctly() {
@@ -81,6 +67,27 @@ mod test {
         check_consensus_encoding_correctness(params).unwrap();
     }
 
+    #[test]
+    fn it_round_trips_random_template_parameters() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let mut template_data = vec![0u8; rng.gen_range(0..=MAX_TEMPLATE_DATA_LEN)];
+            rng.fill_bytes(&mut template_data);
+            let params = TemplateParameter {
+                template_id: rng.gen(),
+                template_data_version: rng.gen(),
+                template_data,
+            };
+
+            let mut expected = params.template_id.encode_var_vec();
+            expected.extend(params.template_data_version.encode_var_vec());
+            expected.extend(params.template_data.len().encode_var_vec());
+            expected.extend(&params.template_data);
+            assert_eq!(params.to_consensus_bytes(), expected);
+            check_consensus_encoding_correctness(params).unwrap();
+        }
+    }
+
     #[test]
     fn it_fails_for_large_template_data_vec() {
         let params = TemplateParameter {
@@ -678,3 +678,32 @@ mod validate_internal_consistency {
         .unwrap();
     }
 }
+
+mod transaction_output_encoding {
+    use super::*;
+    use crate::consensus::{check_consensus_encoding_correctness, ConsensusDecoding, ToConsensusBytes};
+
+    #[test]
+    fn it_rejects_malformed_encodings() {
+        let output = TestParams::new()
+            .create_unblinded_output(UtxoTestParams {
+                script: script!(Nop),
+                features: OutputFeatures {
+                    metadata: vec![1; 64],
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .as_transaction_output(&CryptoFactories::default())
+            .unwrap();
+        let bytes = output.to_consensus_bytes();
+        check_consensus_encoding_correctness(output).unwrap();
+
+        for len in 0..bytes.len() {
+            assert!(TransactionOutput::consensus_decode(&mut &bytes[..len]).is_err());
+        }
+        let mut invalid_version = bytes;
+        invalid_version[0] = 0xff;
+        assert!(TransactionOutput::consensus_decode(&mut invalid_version.as_slice()).is_err());
+    }
+}
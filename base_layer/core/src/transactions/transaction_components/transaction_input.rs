@@ -151,7 +151,7 @@ impl TransactionInput {
         };
     }
 
-    pub(super) fn build_script_challenge(
+    pub(crate) fn build_script_challenge(
         version: TransactionInputVersion,
         nonce_commitment: &Commitment,
         script: &TariScript,
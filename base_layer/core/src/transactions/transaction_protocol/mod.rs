@@ -99,6 +99,7 @@ use thiserror::Error;
 
 use crate::transactions::{tari_amount::*, transaction_components::TransactionError};
 
+pub mod multisig;
 pub mod proto;
 pub mod recipient;
 pub mod sender;
@@ -133,6 +134,8 @@ pub enum TransactionProtocolError {
     ConversionError(String),
     #[error("The script offset private key could not be found")]
     ScriptOffsetPrivateKeyNotFound,
+    #[error("Multisig error: `{0}`")]
+    MultisigError(String),
 }
 
 /// Transaction metadata, including the fee and lock height
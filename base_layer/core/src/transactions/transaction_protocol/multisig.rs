@@ -0,0 +1,609 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Threshold (m-of-n) signing for transaction kernels and script signatures.
+//!
+//! A group secret is split into `n` key shares with Shamir secret sharing, so that any `m` of the shares are enough to
+//! sign for the group. The dealer also publishes Feldman commitments to the sharing polynomial, which lets every
+//! participant verify its own share and derive the public share of every other participant.
+//!
+//! The kernel and script signatures are linear in the signing key. A signer holding share `x_i` signs with
+//! `λ_i·x_i`, where `λ_i` is its Lagrange coefficient for the chosen set of signers, and the partial signatures of the
+//! set add up to a signature for the group key. The group secret is never reconstructed.
+//!
+//! Spending a group output takes two rounds. The proposer collects a kernel nonce and a script nonce from each of the
+//! cosigners that approve the spend, sends them the nonce sums, and then completes the transaction with their partial
+//! signatures using [build_multisig_spend_transaction].
+
+use derivative::Derivative;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{ComSignature, Commitment, PrivateKey, PublicKey, Signature};
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    keys::{PublicKey as PK, SecretKey as SK},
+};
+use tari_script::{inputs, script, ExecutionStack, TariScript};
+
+use crate::transactions::{
+    crypto_factories::CryptoFactories,
+    tari_amount::MicroTari,
+    transaction_components::{
+        KernelBuilder,
+        SpentOutput,
+        Transaction,
+        TransactionInput,
+        TransactionInputVersion,
+        TransactionOutput,
+    },
+    transaction_protocol::{build_challenge, TransactionMetadata, TransactionProtocolError as TPE},
+};
+
+/// The largest group that can be set up. Indices are kept small so that they fit comfortably in the protocol messages.
+pub const MAX_MULTISIG_PARTICIPANTS: usize = 255;
+
+/// The group order minus two, little endian. Raising a scalar to this power gives its multiplicative inverse.
+const GROUP_ORDER_MINUS_TWO: [u8; 32] = [
+    0xeb, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+];
+
+/// A participant's share of a multisig group secret. Indices start at 1.
+#[derive(Derivative, Clone, PartialEq, Deserialize, Serialize)]
+#[derivative(Debug)]
+pub struct MultisigKeyShare {
+    pub index: u32,
+    #[derivative(Debug = "ignore")]
+    pub secret: PrivateKey,
+}
+
+/// Split `secret` into `num_shares` key shares, any `threshold` of which can sign for the group. Returns the shares
+/// together with the Feldman commitments to the sharing polynomial. The first commitment is the group public key.
+pub fn split_secret(
+    secret: &PrivateKey,
+    threshold: usize,
+    num_shares: usize,
+) -> Result<(Vec<MultisigKeyShare>, Vec<PublicKey>), TPE> {
+    if threshold == 0 || threshold > num_shares {
+        return Err(TPE::MultisigError(format!(
+            "Threshold {} is not valid for {} participants",
+            threshold, num_shares
+        )));
+    }
+    if num_shares > MAX_MULTISIG_PARTICIPANTS {
+        return Err(TPE::MultisigError(format!(
+            "At most {} participants are supported",
+            MAX_MULTISIG_PARTICIPANTS
+        )));
+    }
+
+    let mut coefficients = Vec::with_capacity(threshold);
+    coefficients.push(secret.clone());
+    for _ in 1..threshold {
+        coefficients.push(PrivateKey::random(&mut OsRng));
+    }
+    let commitments = coefficients.iter().map(PublicKey::from_secret_key).collect();
+
+    let shares = (1..=num_shares as u32)
+        .map(|index| {
+            // Horner's method, starting from the highest order coefficient
+            let x = PrivateKey::from(u64::from(index));
+            let secret = coefficients
+                .iter()
+                .rev()
+                .skip(1)
+                .fold(coefficients[threshold - 1].clone(), |acc, a| &(&acc * &x) + a);
+            MultisigKeyShare { index, secret }
+        })
+        .collect();
+
+    Ok((shares, commitments))
+}
+
+/// The public key of the share with the given index, derived from the polynomial commitments of the group
+pub fn share_public_key(index: u32, commitments: &[PublicKey]) -> Result<PublicKey, TPE> {
+    let (highest, rest) = commitments
+        .split_last()
+        .ok_or_else(|| TPE::MultisigError("No share commitments provided".to_string()))?;
+    let x = PrivateKey::from(u64::from(index));
+    Ok(rest.iter().rev().fold(highest.clone(), |acc, c| &(&acc * &x) + c))
+}
+
+/// Check that a key share is consistent with the polynomial commitments published by the dealer
+pub fn verify_key_share(share: &MultisigKeyShare, commitments: &[PublicKey]) -> bool {
+    share.index != 0 &&
+        share_public_key(share.index, commitments)
+            .map(|public_key| public_key == PublicKey::from_secret_key(&share.secret))
+            .unwrap_or(false)
+}
+
+/// The Lagrange coefficient at zero for the participant with `index`, when signing together with `signers`
+pub fn lagrange_coefficient(index: u32, signers: &[u32]) -> Result<PrivateKey, TPE> {
+    if !signers.contains(&index) {
+        return Err(TPE::MultisigError(format!("Participant {} is not a signer", index)));
+    }
+    let mut numerator = PrivateKey::from(1u64);
+    let mut denominator = PrivateKey::from(1u64);
+    for (i, &j) in signers.iter().enumerate() {
+        if j == 0 || signers[..i].contains(&j) {
+            return Err(TPE::MultisigError(format!("Invalid signer set {:?}", signers)));
+        }
+        if j == index {
+            continue;
+        }
+        let j_key = PrivateKey::from(u64::from(j));
+        denominator = &denominator * &(&j_key - &PrivateKey::from(u64::from(index)));
+        numerator = &numerator * &j_key;
+    }
+    Ok(&numerator * &invert(&denominator))
+}
+
+/// Create this signer's partial kernel signature. Spending the group output removes the group key from the kernel
+/// excess, so the share is signed with a negative weight.
+pub fn partial_kernel_signature(
+    share: &MultisigKeyShare,
+    signers: &[u32],
+    nonce: PrivateKey,
+    challenge: &[u8],
+) -> Result<Signature, TPE> {
+    let key = &PrivateKey::default() - &weighted_share(share, signers)?;
+    Ok(Signature::sign(key, nonce, challenge)?)
+}
+
+/// Create this signer's partial script signature. This is the part of the input's script signature that proves
+/// knowledge of the group key; the signature nonce only has a blinding factor component.
+pub fn partial_script_signature(
+    share: &MultisigKeyShare,
+    signers: &[u32],
+    nonce: PrivateKey,
+    challenge: &[u8],
+) -> Result<Signature, TPE> {
+    Ok(Signature::sign(weighted_share(share, signers)?, nonce, challenge)?)
+}
+
+/// Check a partial kernel signature received from the signer with `index`
+pub fn verify_partial_kernel_signature(
+    signature: &Signature,
+    index: u32,
+    signers: &[u32],
+    commitments: &[PublicKey],
+    challenge: &[u8],
+) -> Result<bool, TPE> {
+    let weight = &PrivateKey::default() - &lagrange_coefficient(index, signers)?;
+    let public_key = &share_public_key(index, commitments)? * &weight;
+    Ok(signature.verify_challenge(&public_key, challenge))
+}
+
+/// Check a partial script signature received from the signer with `index`
+pub fn verify_partial_script_signature(
+    signature: &Signature,
+    index: u32,
+    signers: &[u32],
+    commitments: &[PublicKey],
+    challenge: &[u8],
+) -> Result<bool, TPE> {
+    let public_key = &share_public_key(index, commitments)? * &lagrange_coefficient(index, signers)?;
+    Ok(signature.verify_challenge(&public_key, challenge))
+}
+
+/// Add partial signatures made over the same challenge into a single signature
+pub fn aggregate_signatures(signatures: &[Signature]) -> Result<Signature, TPE> {
+    let (first, rest) = signatures
+        .split_first()
+        .ok_or_else(|| TPE::MultisigError("No partial signatures to aggregate".to_string()))?;
+    Ok(rest.iter().fold(first.clone(), |acc, sig| {
+        Signature::new(
+            acc.get_public_nonce() + sig.get_public_nonce(),
+            acc.get_signature() + sig.get_signature(),
+        )
+    }))
+}
+
+/// The private nonces a signer uses for one spend. Only the proposer uses `script_value_nonce`, since it is the only
+/// signer that contributes the value part of the script signature.
+#[derive(Derivative, Clone, PartialEq)]
+#[derivative(Debug)]
+pub struct MultisigPrivateNonces {
+    #[derivative(Debug = "ignore")]
+    pub kernel_nonce: PrivateKey,
+    #[derivative(Debug = "ignore")]
+    pub script_nonce: PrivateKey,
+    #[derivative(Debug = "ignore")]
+    pub script_value_nonce: PrivateKey,
+}
+
+impl MultisigPrivateNonces {
+    pub fn random() -> Self {
+        Self {
+            kernel_nonce: PrivateKey::random(&mut OsRng),
+            script_nonce: PrivateKey::random(&mut OsRng),
+            script_value_nonce: PrivateKey::random(&mut OsRng),
+        }
+    }
+
+    pub fn public_kernel_nonce(&self) -> PublicKey {
+        PublicKey::from_secret_key(&self.kernel_nonce)
+    }
+
+    pub fn public_script_nonce(&self) -> PublicKey {
+        PublicKey::from_secret_key(&self.script_nonce)
+    }
+}
+
+/// Group outputs are locked with a `Nop` script and spent by pushing the public key of the group script key, which is
+/// known to every participant.
+pub fn multisig_script() -> TariScript {
+    script!(Nop)
+}
+
+/// The input data used to spend a group output
+pub fn multisig_input_data(script_private_key: &PrivateKey) -> ExecutionStack {
+    inputs!(PublicKey::from_secret_key(script_private_key))
+}
+
+/// The public nonce of the input's script signature: the proposer's nonce commitment plus the public script nonces of
+/// the cosigners
+pub fn script_nonce_sum(
+    proposer_nonces: &MultisigPrivateNonces,
+    cosigner_script_nonces: &[PublicKey],
+    factories: &CryptoFactories,
+) -> Commitment {
+    cosigner_script_nonces.iter().fold(
+        factories
+            .commitment
+            .commit(&proposer_nonces.script_nonce, &proposer_nonces.script_value_nonce),
+        |acc, nonce| &acc + &Commitment::from_public_key(nonce),
+    )
+}
+
+/// The challenge of the script signature of the input that spends `group_output`
+pub fn script_signature_challenge(
+    script_nonce_sum: &Commitment,
+    group_output: &TransactionOutput,
+    script_private_key: &PrivateKey,
+) -> [u8; 32] {
+    TransactionInput::build_script_challenge(
+        TransactionInputVersion::get_current_version(),
+        script_nonce_sum,
+        &group_output.script,
+        &multisig_input_data(script_private_key),
+        &PublicKey::from_secret_key(script_private_key),
+        &group_output.commitment,
+    )
+}
+
+/// Assemble the transaction that spends `group_output` into `output`. The cosigners' partial signatures are completed
+/// with the proposer's contributions: its own key share, the spending key of the new output less a random offset for
+/// the kernel, and the script key and value for the script signature.
+#[allow(clippy::too_many_arguments)]
+pub fn build_multisig_spend_transaction(
+    group_output: &TransactionOutput,
+    value: MicroTari,
+    group_public_key: &PublicKey,
+    script_private_key: &PrivateKey,
+    share: &MultisigKeyShare,
+    signers: &[u32],
+    output: TransactionOutput,
+    output_spending_key: &PrivateKey,
+    sender_offset_private_key: &PrivateKey,
+    metadata: &TransactionMetadata,
+    nonces: &MultisigPrivateNonces,
+    kernel_signatures: &[Signature],
+    script_signatures: &[Signature],
+    factories: &CryptoFactories,
+) -> Result<Transaction, TPE> {
+    let own_share = weighted_share(share, signers)?;
+    let offset = PrivateKey::random(&mut OsRng);
+    let kernel_key = output_spending_key - &offset;
+    let kernel_nonce_sum = kernel_signatures
+        .iter()
+        .fold(nonces.public_kernel_nonce(), |acc, s| &acc + s.get_public_nonce());
+    let e = build_challenge(&kernel_nonce_sum, metadata);
+    let mut partials = kernel_signatures.to_vec();
+    partials.push(Signature::sign(
+        &kernel_key - &own_share,
+        nonces.kernel_nonce.clone(),
+        &e,
+    )?);
+    let excess_sig = aggregate_signatures(&partials)?;
+    let minus_one = &PrivateKey::default() - &PrivateKey::from(1u64);
+    let excess = &PublicKey::from_secret_key(&kernel_key) + &(group_public_key * &minus_one);
+    let kernel = KernelBuilder::new()
+        .with_fee(metadata.fee)
+        .with_lock_height(metadata.lock_height)
+        .with_excess(&Commitment::from_public_key(&excess))
+        .with_signature(&excess_sig)
+        .build()?;
+    kernel.verify_signature()?;
+
+    let cosigner_script_nonces = script_signatures
+        .iter()
+        .map(|s| s.get_public_nonce().clone())
+        .collect::<Vec<_>>();
+    let script_nonce_sum = script_nonce_sum(nonces, &cosigner_script_nonces, factories);
+    let e = script_signature_challenge(&script_nonce_sum, group_output, script_private_key);
+    let u = script_signatures.iter().fold(
+        Signature::sign(script_private_key + &own_share, nonces.script_nonce.clone(), &e)?
+            .get_signature()
+            .clone(),
+        |acc, s| &acc + s.get_signature(),
+    );
+    let v = Signature::sign(PrivateKey::from(value.as_u64()), nonces.script_value_nonce.clone(), &e)?;
+    let input = TransactionInput::new_current_version(
+        SpentOutput::OutputData {
+            features: group_output.features.clone(),
+            commitment: group_output.commitment.clone(),
+            script: group_output.script.clone(),
+            sender_offset_public_key: group_output.sender_offset_public_key.clone(),
+            covenant: group_output.covenant.clone(),
+            version: group_output.version,
+        },
+        multisig_input_data(script_private_key),
+        ComSignature::new(script_nonce_sum, u, v.get_signature().clone()),
+    );
+    input.validate_script_signature(&PublicKey::from_secret_key(script_private_key), &factories.commitment)?;
+
+    let script_offset = script_private_key - sender_offset_private_key;
+    Ok(Transaction::new(
+        vec![input],
+        vec![output],
+        vec![kernel],
+        offset,
+        script_offset,
+    ))
+}
+
+fn weighted_share(share: &MultisigKeyShare, signers: &[u32]) -> Result<PrivateKey, TPE> {
+    Ok(&lagrange_coefficient(share.index, signers)? * &share.secret)
+}
+
+/// The multiplicative inverse of a non-zero scalar, by Fermat's little theorem
+fn invert(k: &PrivateKey) -> PrivateKey {
+    let mut result = PrivateKey::from(1u64);
+    for byte in GROUP_ORDER_MINUS_TWO.iter().rev() {
+        for bit in (0..8).rev() {
+            result = &result * &result;
+            if (byte >> bit) & 1 == 1 {
+                result = &result * k;
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        covenants::Covenant,
+        transactions::transaction_components::{OutputFeatures, TransactionOutputVersion, UnblindedOutput},
+    };
+
+    fn combine(shares: &[&MultisigKeyShare]) -> PrivateKey {
+        let signers = shares.iter().map(|s| s.index).collect::<Vec<_>>();
+        shares.iter().fold(PrivateKey::default(), |acc, share| {
+            &acc + &weighted_share(share, &signers).unwrap()
+        })
+    }
+
+    #[test]
+    fn it_inverts_scalars() {
+        let k = PrivateKey::random(&mut OsRng);
+        assert_eq!(&k * &invert(&k), PrivateKey::from(1u64));
+        assert_eq!(invert(&PrivateKey::from(1u64)), PrivateKey::from(1u64));
+    }
+
+    #[test]
+    fn any_threshold_of_shares_recovers_the_secret() {
+        let secret = PrivateKey::random(&mut OsRng);
+        let (shares, commitments) = split_secret(&secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert_eq!(commitments.len(), 3);
+        assert_eq!(commitments[0], PublicKey::from_secret_key(&secret));
+
+        assert_eq!(combine(&[&shares[0], &shares[1], &shares[2]]), secret);
+        assert_eq!(combine(&[&shares[4], &shares[1], &shares[3]]), secret);
+        assert_eq!(combine(&[&shares[0], &shares[2], &shares[3], &shares[4]]), secret);
+        assert_ne!(combine(&[&shares[0], &shares[2]]), secret);
+    }
+
+    #[test]
+    fn it_verifies_key_shares() {
+        let secret = PrivateKey::random(&mut OsRng);
+        let (shares, commitments) = split_secret(&secret, 2, 3).unwrap();
+        for share in &shares {
+            assert!(verify_key_share(share, &commitments));
+        }
+
+        let mut bad_share = shares[1].clone();
+        bad_share.secret = PrivateKey::random(&mut OsRng);
+        assert!(!verify_key_share(&bad_share, &commitments));
+        bad_share = shares[1].clone();
+        bad_share.index = 3;
+        assert!(!verify_key_share(&bad_share, &commitments));
+    }
+
+    #[test]
+    fn it_rejects_invalid_parameters() {
+        let secret = PrivateKey::random(&mut OsRng);
+        assert!(split_secret(&secret, 0, 3).is_err());
+        assert!(split_secret(&secret, 4, 3).is_err());
+        assert!(split_secret(&secret, 2, MAX_MULTISIG_PARTICIPANTS + 1).is_err());
+        assert!(split_secret(&secret, 1, 1).is_ok());
+
+        assert!(lagrange_coefficient(2, &[1, 3]).is_err());
+        assert!(lagrange_coefficient(1, &[1, 3, 3]).is_err());
+        assert!(lagrange_coefficient(1, &[0, 1]).is_err());
+        assert!(aggregate_signatures(&[]).is_err());
+    }
+
+    #[test]
+    fn threshold_signatures_verify_against_the_group_key() {
+        let secret = PrivateKey::random(&mut OsRng);
+        let (shares, commitments) = split_secret(&secret, 2, 3).unwrap();
+        let signers = [1, 3];
+        let nonces = [PrivateKey::random(&mut OsRng), PrivateKey::random(&mut OsRng)];
+        let public_nonce = &PublicKey::from_secret_key(&nonces[0]) + &PublicKey::from_secret_key(&nonces[1]);
+        let metadata = TransactionMetadata {
+            fee: MicroTari::from(100),
+            lock_height: 0,
+        };
+        let e = build_challenge(&public_nonce, &metadata);
+
+        let kernel_partials = vec![
+            partial_kernel_signature(&shares[0], &signers, nonces[0].clone(), &e).unwrap(),
+            partial_kernel_signature(&shares[2], &signers, nonces[1].clone(), &e).unwrap(),
+        ];
+        assert!(verify_partial_kernel_signature(&kernel_partials[0], 1, &signers, &commitments, &e).unwrap());
+        assert!(verify_partial_kernel_signature(&kernel_partials[1], 3, &signers, &commitments, &e).unwrap());
+        assert!(!verify_partial_kernel_signature(&kernel_partials[1], 1, &signers, &commitments, &e).unwrap());
+        let kernel_signature = aggregate_signatures(&kernel_partials).unwrap();
+        let excess = PublicKey::from_secret_key(&(&PrivateKey::default() - &secret));
+        assert!(kernel_signature.verify_challenge(&excess, &e));
+
+        let script_partials = vec![
+            partial_script_signature(&shares[0], &signers, nonces[0].clone(), &e).unwrap(),
+            partial_script_signature(&shares[2], &signers, nonces[1].clone(), &e).unwrap(),
+        ];
+        assert!(verify_partial_script_signature(&script_partials[0], 1, &signers, &commitments, &e).unwrap());
+        assert!(verify_partial_script_signature(&script_partials[1], 3, &signers, &commitments, &e).unwrap());
+        let script_signature = aggregate_signatures(&script_partials).unwrap();
+        assert!(script_signature.verify_challenge(&commitments[0], &e));
+    }
+
+    fn output_with_key(
+        value: MicroTari,
+        spending_key: &PrivateKey,
+        script_private_key: &PrivateKey,
+        sender_offset_private_key: &PrivateKey,
+        factories: &CryptoFactories,
+    ) -> TransactionOutput {
+        let script = multisig_script();
+        let features = OutputFeatures::default();
+        let covenant = Covenant::default();
+        let metadata_signature = TransactionOutput::create_final_metadata_signature(
+            TransactionOutputVersion::get_current_version(),
+            value,
+            spending_key,
+            &script,
+            &features,
+            sender_offset_private_key,
+            &covenant,
+        )
+        .unwrap();
+        UnblindedOutput::new_current_version(
+            value,
+            spending_key.clone(),
+            features,
+            script,
+            multisig_input_data(script_private_key),
+            script_private_key.clone(),
+            PublicKey::from_secret_key(sender_offset_private_key),
+            metadata_signature,
+            0,
+            covenant,
+        )
+        .as_transaction_output(factories)
+        .unwrap()
+    }
+
+    #[test]
+    fn cosigners_spend_a_group_output() {
+        let factories = CryptoFactories::default();
+        let secret = PrivateKey::random(&mut OsRng);
+        let script_private_key = PrivateKey::random(&mut OsRng);
+        let (shares, commitments) = split_secret(&secret, 2, 3).unwrap();
+        let value = MicroTari::from(10_000);
+        let group_output = output_with_key(
+            value,
+            &secret,
+            &script_private_key,
+            &PrivateKey::random(&mut OsRng),
+            &factories,
+        );
+        let metadata = TransactionMetadata {
+            fee: MicroTari::from(500),
+            lock_height: 0,
+        };
+
+        let spend = |signers: &[u32], cosigners: &[&MultisigKeyShare]| {
+            let proposer = MultisigPrivateNonces::random();
+            let cosigner_nonces = cosigners
+                .iter()
+                .map(|_| MultisigPrivateNonces::random())
+                .collect::<Vec<_>>();
+            let kernel_nonce_sum = cosigner_nonces
+                .iter()
+                .fold(proposer.public_kernel_nonce(), |acc, n| &acc + &n.public_kernel_nonce());
+            let script_nonces = cosigner_nonces
+                .iter()
+                .map(MultisigPrivateNonces::public_script_nonce)
+                .collect::<Vec<_>>();
+            let script_nonce_sum = script_nonce_sum(&proposer, &script_nonces, &factories);
+            let kernel_e = build_challenge(&kernel_nonce_sum, &metadata);
+            let script_e = script_signature_challenge(&script_nonce_sum, &group_output, &script_private_key);
+            let mut kernel_signatures = Vec::new();
+            let mut script_signatures = Vec::new();
+            for (share, nonces) in cosigners.iter().zip(cosigner_nonces) {
+                kernel_signatures
+                    .push(partial_kernel_signature(share, signers, nonces.kernel_nonce, &kernel_e).unwrap());
+                script_signatures
+                    .push(partial_script_signature(share, signers, nonces.script_nonce, &script_e).unwrap());
+            }
+
+            let output_spending_key = PrivateKey::random(&mut OsRng);
+            let sender_offset_private_key = PrivateKey::random(&mut OsRng);
+            let output = output_with_key(
+                value - metadata.fee,
+                &output_spending_key,
+                &PrivateKey::random(&mut OsRng),
+                &sender_offset_private_key,
+                &factories,
+            );
+            build_multisig_spend_transaction(
+                &group_output,
+                value,
+                &commitments[0],
+                &script_private_key,
+                &shares[1],
+                signers,
+                output,
+                &output_spending_key,
+                &sender_offset_private_key,
+                &metadata,
+                &proposer,
+                &kernel_signatures,
+                &script_signatures,
+                &factories,
+            )
+        };
+
+        let tx = spend(&[2, 3], &[&shares[2]]).unwrap();
+        tx.validate_internal_consistency(false, &factories, None, None, u64::MAX)
+            .unwrap();
+        let tx = spend(&[1, 2, 3], &[&shares[0], &shares[2]]).unwrap();
+        tx.validate_internal_consistency(false, &factories, None, None, u64::MAX)
+            .unwrap();
+        // A single share is below the threshold
+        assert!(spend(&[2], &[]).is_err());
+    }
+}
@@ -85,6 +85,9 @@ pub struct SenderTransactionInitializer {
     inputs: Vec<TransactionInput>,
     unblinded_inputs: Vec<UnblindedOutput>,
     sender_custom_outputs: Vec<UnblindedOutput>,
+    /// Rewind data for custom outputs that are not rewindable with `rewind_data`, by index into
+    /// `sender_custom_outputs`
+    sender_custom_output_rewind_data: HashMap<usize, RewindData>,
     sender_offset_private_keys: Vec<PrivateKey>,
     change_secret: Option<BlindingFactor>,
     change_script: Option<TariScript>,
@@ -129,6 +132,7 @@ impl SenderTransactionInitializer {
             inputs: Vec::new(),
             unblinded_inputs: Vec::new(),
             sender_custom_outputs: Vec::new(),
+            sender_custom_output_rewind_data: HashMap::new(),
             sender_offset_private_keys: vec![],
             change_secret: None,
             change_script: None,
@@ -213,10 +217,33 @@ impl SenderTransactionInitializer {
         &mut self,
         output: UnblindedOutput,
         sender_offset_private_key: PrivateKey,
+    ) -> Result<&mut Self, BuildError> {
+        self.add_output(output, sender_offset_private_key, None)
+    }
+
+    /// Adds an output built by the sender on behalf of someone else, such as a one-sided payment. Its range proof and
+    /// recovery byte use `rewind_data` instead of the rewind data set with `with_rewindable_outputs`.
+    pub fn with_rewindable_output(
+        &mut self,
+        output: UnblindedOutput,
+        sender_offset_private_key: PrivateKey,
+        rewind_data: RewindData,
+    ) -> Result<&mut Self, BuildError> {
+        self.add_output(output, sender_offset_private_key, Some(rewind_data))
+    }
+
+    fn add_output(
+        &mut self,
+        output: UnblindedOutput,
+        sender_offset_private_key: PrivateKey,
+        rewind_data: Option<RewindData>,
     ) -> Result<&mut Self, BuildError> {
         let commitment_factory = PedersenCommitmentFactory::default();
         let commitment = commitment_factory.commit(&output.spending_key, &PrivateKey::from(output.value));
-        let recovery_byte = OutputFeatures::create_unique_recovery_byte(&commitment, self.rewind_data.as_ref());
+        let recovery_byte = OutputFeatures::create_unique_recovery_byte(
+            &commitment,
+            rewind_data.as_ref().or(self.rewind_data.as_ref()),
+        );
         if recovery_byte != output.features.recovery_byte {
             // This is not a hard error by choice; we allow inconsistent recovery byte data into the wallet database
             error!(
@@ -247,6 +274,10 @@ impl SenderTransactionInitializer {
             ))?;
         }
         self.excess_blinding_factor = &self.excess_blinding_factor + &output.spending_key;
+        if let Some(rewind_data) = rewind_data {
+            self.sender_custom_output_rewind_data
+                .insert(self.sender_custom_outputs.len(), rewind_data);
+        }
         self.sender_custom_outputs.push(output);
         self.sender_offset_private_keys.push(sender_offset_private_key);
         Ok(self)
@@ -518,12 +549,17 @@ impl SenderTransactionInitializer {
         let mut outputs = match self
             .sender_custom_outputs
             .iter()
-            .map(|o| {
+            .enumerate()
+            .map(|(i, o)| {
                 let commitment = factories.commitment.commit_value(&o.spending_key, o.value.as_u64());
                 let mut uo = o.clone();
-                uo.features.update_recovery_byte(&commitment, self.rewind_data.as_ref());
+                let rewind_data = self
+                    .sender_custom_output_rewind_data
+                    .get(&i)
+                    .or_else(|| self.rewind_data.as_ref());
+                uo.features.update_recovery_byte(&commitment, rewind_data);
 
-                if let Some(rewind_data) = self.rewind_data.as_ref() {
+                if let Some(rewind_data) = rewind_data {
                     uo.as_rewindable_transaction_output(factories, rewind_data, None)
                 } else {
                     uo.as_transaction_output(factories)
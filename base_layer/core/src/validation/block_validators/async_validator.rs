@@ -156,7 +156,7 @@ impl<B: BlockchainBackend + 'static> BlockValidator<B> {
             AggregateBody::new_sorted_unchecked(inputs_result.inputs, outputs_result.outputs, kernels_result.kernels),
         );
 
-        helpers::validate_covenants(&block)?;
+        helpers::validate_covenants(&block, self.rules.consensus_constants(block.header.height))?;
 
         Ok(block)
     }
@@ -64,7 +64,8 @@ fn setup() -> (TestBlockchain, BlockValidator<TempDatabase>) {
                 .with_coinbase_lockheight(0)
                 .build(),
         )
-        .build();
+        .build()
+        .unwrap();
     setup_with_rules(rules)
 }
 
@@ -194,7 +195,8 @@ async fn it_limits_the_script_byte_size() {
                 .with_max_script_byte_size(0)
                 .build(),
         )
-        .build();
+        .build()
+        .unwrap();
     let (mut blockchain, validator) = setup_with_rules(rules);
 
     let (_, coinbase_a) = blockchain.add_next_tip("A", Default::default());
@@ -217,7 +219,8 @@ async fn it_rejects_invalid_input_metadata() {
                 .with_coinbase_lockheight(0)
                 .build(),
         )
-        .build();
+        .build()
+        .unwrap();
     let (mut blockchain, validator) = setup_with_rules(rules);
 
     let (_, coinbase_a) = blockchain.add_next_tip("A", Default::default());
@@ -421,7 +424,8 @@ mod body_only {
                     .with_coinbase_lockheight(0)
                     .build(),
             )
-            .build();
+            .build()
+            .unwrap();
         let mut blockchain = TestBlockchain::create(rules.clone());
         let validator = BodyOnlyValidator::new(rules);
 
@@ -455,7 +459,8 @@ mod orphan_validator {
                     .with_coinbase_lockheight(0)
                     .build(),
             )
-            .build();
+            .build()
+            .unwrap();
         let mut blockchain = TestBlockchain::create(rules.clone());
         let validator = OrphanBlockValidator::new(rules, false, CryptoFactories::default());
         let (_, coinbase) = blockchain.append(block_spec!("1", parent: "GB"));
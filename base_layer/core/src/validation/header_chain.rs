@@ -0,0 +1,389 @@
+// Copyright 2022 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Batch verification of contiguous block header chains that does not require a blockchain database. This is used by
+//! header sync and can be used by light clients that only keep track of headers.
+
+use std::iter;
+
+use tari_common_types::types::HashOutput;
+use tari_utilities::{epoch_time::EpochTime, hash::Hashable, hex::Hex};
+
+use crate::{
+    blocks::{BlockError, BlockHeader, BlockHeaderAccumulatedData, ChainHeader},
+    chain_storage::TargetDifficulties,
+    common::rolling_vec::RollingVec,
+    consensus::ConsensusManager,
+    proof_of_work::{
+        calculate_achieved_difficulties,
+        randomx_factory::RandomXFactory,
+        AchievedTargetDifficulty,
+        Difficulty,
+    },
+    validation::{
+        helpers::{
+            check_achieved_difficulty,
+            check_blockchain_version,
+            check_header_timestamp_greater_than_median,
+            check_pow_algo_and_data_size,
+            check_target_difficulty,
+            check_timestamp_ftl,
+        },
+        ValidationError,
+    },
+};
+
+/// The reason a single header failed verification against a [HeaderChainState]
+#[derive(Debug, thiserror::Error)]
+pub enum HeaderVerificationError {
+    #[error("Expected header height {expected} got {actual}")]
+    InvalidHeight { expected: u64, actual: u64 },
+    #[error("Header at height {height} did not form a chain. Expected {actual} to equal the previous hash {expected}")]
+    ChainLinkBroken {
+        height: u64,
+        actual: String,
+        expected: String,
+    },
+    #[error("Validation failed: {0}")]
+    ValidationFailed(#[from] ValidationError),
+    #[error("Block error: {0}")]
+    BlockError(#[from] BlockError),
+}
+
+/// A header in a chain failed verification
+#[derive(Debug, thiserror::Error)]
+#[error("Header at index {index} (height {height}) failed verification: {source}")]
+pub struct HeaderChainError {
+    /// The index of the invalid header in the given slice of headers
+    pub index: usize,
+    /// The height claimed by the invalid header
+    pub height: u64,
+    pub source: HeaderVerificationError,
+}
+
+/// The state of the chain that the next header is verified against. This contains the accumulated data of the current
+/// tip header and the timestamp and target difficulty windows needed to check the next header.
+#[derive(Debug, Clone)]
+pub struct HeaderChainState {
+    height: u64,
+    previous_accum: BlockHeaderAccumulatedData,
+    timestamps: RollingVec<EpochTime>,
+    target_difficulties: TargetDifficulties,
+}
+
+impl HeaderChainState {
+    /// Creates the state from a tip header. `timestamps` and `target_difficulties` must contain the windows up to and
+    /// including the tip header, e.g. as returned by `fetch_block_timestamps` and
+    /// `fetch_target_difficulties_for_next_block`.
+    pub fn new(
+        height: u64,
+        previous_accum: BlockHeaderAccumulatedData,
+        timestamps: RollingVec<EpochTime>,
+        target_difficulties: TargetDifficulties,
+    ) -> Self {
+        Self {
+            height,
+            previous_accum,
+            timestamps,
+            target_difficulties,
+        }
+    }
+
+    /// Creates the state for verifying a chain starting at the genesis block of the network
+    pub fn from_genesis(rules: &ConsensusManager) -> Self {
+        let genesis = rules.get_genesis_block();
+        let header = genesis.header();
+        let mut timestamps = RollingVec::new(rules.consensus_constants(0).get_median_timestamp_count());
+        timestamps.push(header.timestamp);
+        let mut target_difficulties = TargetDifficulties::new(rules, 1);
+        target_difficulties.add_back(header, genesis.accumulated_data().target_difficulty);
+        Self::new(
+            header.height,
+            genesis.accumulated_data().clone(),
+            timestamps,
+            target_difficulties,
+        )
+    }
+
+    /// The height of the current tip header
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    /// The accumulated data of the current tip header
+    pub fn accumulated_data(&self) -> &BlockHeaderAccumulatedData {
+        &self.previous_accum
+    }
+
+    /// The timestamps of the most recent headers in ascending order, used to calculate the median timestamp
+    pub fn timestamps(&self) -> &[EpochTime] {
+        &self.timestamps
+    }
+
+    pub fn target_difficulties(&self) -> &TargetDifficulties {
+        &self.target_difficulties
+    }
+
+    /// Verifies that the header extends the current tip and, if it does, makes it the new tip. The state is not
+    /// changed if the header is invalid.
+    ///
+    /// The checks are the database-free header consensus checks: version, height, linkage to the previous header,
+    /// future time limit, median timestamp, PoW algorithm and data size, and achieved vs target difficulty. Checks that
+    /// require a blockchain database (bad blocks and the RandomX seed age) are not performed.
+    pub fn verify_next(
+        &mut self,
+        header: BlockHeader,
+        rules: &ConsensusManager,
+        randomx_factory: &RandomXFactory,
+    ) -> Result<ChainHeader, HeaderVerificationError> {
+        self.verify_next_with(header, rules, |header, target| {
+            check_target_difficulty(header, target, randomx_factory)
+        })
+    }
+
+    /// Performs the same checks as `verify_next`, using an achieved difficulty that was previously calculated for the
+    /// header instead of hashing it again.
+    pub fn verify_next_with_achieved_difficulty(
+        &mut self,
+        header: BlockHeader,
+        rules: &ConsensusManager,
+        achieved: Difficulty,
+    ) -> Result<ChainHeader, HeaderVerificationError> {
+        self.verify_next_with(header, rules, |header, target| {
+            check_achieved_difficulty(header, target, achieved)
+        })
+    }
+
+    fn verify_next_with<F>(
+        &mut self,
+        header: BlockHeader,
+        rules: &ConsensusManager,
+        check_difficulty: F,
+    ) -> Result<ChainHeader, HeaderVerificationError>
+    where
+        F: FnOnce(&BlockHeader, Difficulty) -> Result<AchievedTargetDifficulty, ValidationError>,
+    {
+        check_header_structure(&header, rules, self.height, &self.previous_accum.hash, &self.timestamps)?;
+
+        let constants = rules.consensus_constants(header.height);
+        let target_difficulty = self.target_difficulties.get(header.pow_algo()).calculate(
+            constants.min_pow_difficulty(header.pow_algo()),
+            constants.max_pow_difficulty(header.pow_algo()),
+        );
+        let achieved_target = check_difficulty(&header, target_difficulty)?;
+
+        let accumulated_data = BlockHeaderAccumulatedData::builder(&self.previous_accum)
+            .with_hash(header.hash())
+            .with_achieved_target_difficulty(achieved_target)
+            .with_total_kernel_offset(header.total_kernel_offset.clone())
+            .build()?;
+
+        // Header is valid, add it onto the state for the next header
+        insert_timestamp_sorted(&mut self.timestamps, header.timestamp());
+        self.height = header.height;
+        // Add a "more recent" datapoint onto the target difficulty
+        self.target_difficulties.add_back(&header, target_difficulty);
+        self.previous_accum = accumulated_data.clone();
+
+        // NOTE: accumulated_data constructed from header so they are guaranteed to correspond
+        Ok(ChainHeader::try_construct(header, accumulated_data).unwrap())
+    }
+
+    /// Returns the number of leading headers in `headers` that pass the checks of `verify_next` that do not require
+    /// the PoW to be calculated. The state is not changed.
+    ///
+    /// Used to avoid spending time hashing headers that would be rejected by these cheaper checks anyway.
+    pub fn count_structurally_valid(&self, headers: &[BlockHeader], rules: &ConsensusManager) -> usize {
+        let mut height = self.height;
+        let mut prev_hash = self.previous_accum.hash.clone();
+        let mut timestamps = self.timestamps.clone();
+        for (index, header) in headers.iter().enumerate() {
+            if check_header_structure(header, rules, height, &prev_hash, &timestamps).is_err() {
+                return index;
+            }
+            insert_timestamp_sorted(&mut timestamps, header.timestamp());
+            height = header.height;
+            prev_hash = header.hash();
+        }
+        headers.len()
+    }
+}
+
+/// The checks of `verify_next` that do not depend on the achieved difficulty of the header
+fn check_header_structure(
+    header: &BlockHeader,
+    rules: &ConsensusManager,
+    tip_height: u64,
+    tip_hash: &HashOutput,
+    timestamps: &[EpochTime],
+) -> Result<(), HeaderVerificationError> {
+    let constants = rules.consensus_constants(header.height);
+    check_blockchain_version(constants, header.version)?;
+
+    let expected_height = tip_height + 1;
+    if header.height != expected_height {
+        return Err(HeaderVerificationError::InvalidHeight {
+            expected: expected_height,
+            actual: header.height,
+        });
+    }
+    if header.prev_hash != *tip_hash {
+        return Err(HeaderVerificationError::ChainLinkBroken {
+            height: header.height,
+            actual: header.prev_hash.to_hex(),
+            expected: tip_hash.to_hex(),
+        });
+    }
+    check_timestamp_ftl(header, rules)?;
+    check_header_timestamp_greater_than_median(header, timestamps)?;
+    check_pow_algo_and_data_size(header, rules)?;
+    Ok(())
+}
+
+/// Ensure that timestamps are inserted in sorted order
+fn insert_timestamp_sorted(timestamps: &mut RollingVec<EpochTime>, timestamp: EpochTime) {
+    match timestamps.iter().position(|ts| ts >= &timestamp) {
+        Some(pos) => {
+            timestamps.insert(pos, timestamp);
+        },
+        None => timestamps.push(timestamp),
+    }
+}
+
+/// Verifies a contiguous chain of headers that extends the tip of `state`, returning the verified headers with their
+/// accumulated data. The total accumulated difficulty of the chain is that of the last returned header.
+///
+/// Verification stops at the first invalid header, the returned error contains its position in `headers`. In that
+/// case `state` is left at the last valid header so that the valid prefix of the chain can still be used.
+/// Verifies a contiguous chain of headers on top of the given state. The PoW of the headers that pass the cheaper
+/// structural checks is calculated up front in parallel (see `calculate_achieved_difficulties`) before the headers are
+/// verified in order.
+pub fn verify_header_chain(
+    rules: &ConsensusManager,
+    randomx_factory: &RandomXFactory,
+    state: &mut HeaderChainState,
+    headers: &[BlockHeader],
+) -> Result<Vec<ChainHeader>, HeaderChainError> {
+    let num_to_hash = state.count_structurally_valid(headers, rules);
+    let achieved_difficulties = calculate_achieved_difficulties(&headers[..num_to_hash], randomx_factory);
+    headers
+        .iter()
+        .zip(
+            achieved_difficulties
+                .into_iter()
+                .map(Some)
+                .chain(iter::repeat_with(|| None)),
+        )
+        .enumerate()
+        .map(|(index, (header, achieved))| {
+            let result = match achieved {
+                Some(Ok(achieved)) => state.verify_next_with_achieved_difficulty(header.clone(), rules, achieved),
+                // Verify the header as usual so that the error is reported consistently. Headers that were not hashed
+                // fail the structural checks before their PoW is calculated.
+                _ => state.verify_next(header.clone(), rules, randomx_factory),
+            };
+            result.map_err(|source| HeaderChainError {
+                index,
+                height: header.height,
+                source,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use tari_common::configuration::Network;
+    use tari_test_utils::unpack_enum;
+
+    use super::*;
+
+    fn create_headers(rules: &ConsensusManager, n: usize) -> Vec<BlockHeader> {
+        let mut prev = rules.get_genesis_block().header().clone();
+        let mut headers = Vec::with_capacity(n);
+        for _ in 0..n {
+            let header = BlockHeader::from_previous(&prev);
+            prev = header.clone();
+            headers.push(header);
+        }
+        headers
+    }
+
+    #[test]
+    fn it_verifies_a_valid_chain() {
+        let rules = ConsensusManager::builder(Network::LocalNet).build().unwrap();
+        let randomx_factory = RandomXFactory::default();
+        let headers = create_headers(&rules, 5);
+        let mut state = HeaderChainState::from_genesis(&rules);
+        let start_difficulty = state.accumulated_data().total_accumulated_difficulty;
+
+        let chain = verify_header_chain(&rules, &randomx_factory, &mut state, &headers).unwrap();
+        assert_eq!(chain.len(), 5);
+        assert_eq!(state.height(), 5);
+        assert_eq!(state.accumulated_data().hash, headers[4].hash());
+        assert_eq!(
+            chain.last().unwrap().accumulated_data().total_accumulated_difficulty,
+            state.accumulated_data().total_accumulated_difficulty
+        );
+        assert!(state.accumulated_data().total_accumulated_difficulty > start_difficulty);
+    }
+
+    #[test]
+    fn it_reports_the_position_of_a_broken_link() {
+        let rules = ConsensusManager::builder(Network::LocalNet).build().unwrap();
+        let randomx_factory = RandomXFactory::default();
+        let mut headers = create_headers(&rules, 5);
+        headers[3].prev_hash = vec![1; 32];
+
+        let mut state = HeaderChainState::from_genesis(&rules);
+        let err = verify_header_chain(&rules, &randomx_factory, &mut state, &headers).unwrap_err();
+        assert_eq!(err.index, 3);
+        assert_eq!(err.height, 4);
+        unpack_enum!(HeaderVerificationError::ChainLinkBroken { height, .. } = err.source);
+        assert_eq!(height, 4);
+        // The state is left at the last valid header
+        assert_eq!(state.height(), 3);
+        assert_eq!(state.accumulated_data().hash, headers[2].hash());
+    }
+
+    #[test]
+    fn it_rejects_non_contiguous_heights() {
+        let rules = ConsensusManager::builder(Network::LocalNet).build().unwrap();
+        let randomx_factory = RandomXFactory::default();
+        let headers = create_headers(&rules, 3);
+
+        let mut state = HeaderChainState::from_genesis(&rules);
+        let err = verify_header_chain(&rules, &randomx_factory, &mut state, &headers[1..]).unwrap_err();
+        assert_eq!(err.index, 0);
+        unpack_enum!(HeaderVerificationError::InvalidHeight { expected, actual } = err.source);
+        assert_eq!(expected, 1);
+        assert_eq!(actual, 2);
+    }
+
+    #[test]
+    fn it_rejects_a_timestamp_below_the_median() {
+        let rules = ConsensusManager::builder(Network::LocalNet).build().unwrap();
+        let randomx_factory = RandomXFactory::default();
+        let mut headers = create_headers(&rules, 2);
+        headers[1].timestamp = EpochTime::from(0);
+
+        let mut state = HeaderChainState::from_genesis(&rules);
+        let err = verify_header_chain(&rules, &randomx_factory, &mut state, &headers).unwrap_err();
+        assert_eq!(err.index, 1);
+        unpack_enum!(HeaderVerificationError::ValidationFailed(_err) = err.source);
+    }
+
+    #[test]
+    fn it_counts_the_structurally_valid_prefix_without_changing_state() {
+        let rules = ConsensusManager::builder(Network::LocalNet).build().unwrap();
+        let mut headers = create_headers(&rules, 5);
+        let state = HeaderChainState::from_genesis(&rules);
+        assert_eq!(state.count_structurally_valid(&headers, &rules), 5);
+
+        headers[3].prev_hash = vec![1; 32];
+        assert_eq!(state.count_structurally_valid(&headers, &rules), 3);
+        headers[1].height = 10;
+        assert_eq!(state.count_structurally_valid(&headers, &rules), 1);
+        assert_eq!(state.height(), 0);
+    }
+}
@@ -39,9 +39,9 @@ use crate::{
     consensus::{emission::Emission, ConsensusConstants, ConsensusEncodingSized, ConsensusManager},
     proof_of_work::{
         monero_difficulty,
-        monero_rx::MoneroPowData,
         randomx_factory::RandomXFactory,
         sha3_difficulty,
+        sha3x_difficulty,
         AchievedTargetDifficulty,
         Difficulty,
         PowAlgorithm,
@@ -145,39 +145,53 @@ pub fn check_header_timestamp_greater_than_median(
     Ok(())
 }
 
-/// Check the PoW data in the BlockHeader. This currently only applies to blocks merged mined with Monero.
+/// Check that the PoW algorithm is permitted at the height of the BlockHeader and that the PoW data does not exceed the
+/// size limit for the algorithm. Unlike [check_pow_data], this does not require access to the blockchain database.
+pub fn check_pow_algo_and_data_size(
+    block_header: &BlockHeader,
+    rules: &ConsensusManager,
+) -> Result<(), ValidationError> {
+    let pow_algo = block_header.pow.pow_algo;
+    if !rules
+        .consensus_constants(block_header.height)
+        .is_pow_algo_permitted(pow_algo, block_header.height)
+    {
+        return Err(ValidationError::BlockHeaderError(
+            BlockHeaderValidationError::PowAlgorithmNotPermitted {
+                pow_algo,
+                height: block_header.height,
+            },
+        ));
+    }
+
+    block_header.pow.validate_pow_data_size()?;
+    Ok(())
+}
+
+/// Check that the PoW algorithm is permitted at the height of the BlockHeader and check the PoW data. The PoW data
+/// currently only applies to blocks merged mined with Monero.
 pub fn check_pow_data<B: BlockchainBackend>(
     block_header: &BlockHeader,
     rules: &ConsensusManager,
     db: &B,
 ) -> Result<(), ValidationError> {
-    use PowAlgorithm::{Monero, Sha3};
-    match block_header.pow.pow_algo {
-        Monero => {
-            let monero_data =
-                MoneroPowData::from_header(block_header).map_err(|e| ValidationError::CustomError(e.to_string()))?;
-            let seed_height = db.fetch_monero_seed_first_seen_height(&monero_data.randomx_key)?;
-            if seed_height != 0 {
-                // Saturating sub: subtraction can underflow in reorgs / rewind-blockchain command
-                let seed_used_height = block_header.height.saturating_sub(seed_height);
-                if seed_used_height > rules.consensus_constants(block_header.height).max_randomx_seed_height() {
-                    return Err(ValidationError::BlockHeaderError(
-                        BlockHeaderValidationError::OldSeedHash,
-                    ));
-                }
-            }
-
-            Ok(())
-        },
-        Sha3 => {
-            if !block_header.pow.pow_data.is_empty() {
-                return Err(ValidationError::CustomError(
-                    "Proof of work data must be empty for Sha3 blocks".to_string(),
+    check_pow_algo_and_data_size(block_header, rules)?;
+
+    if block_header.pow.pow_algo.is_monero() {
+        let monero_data = block_header.pow.monero_data()?;
+        let seed_height = db.fetch_monero_seed_first_seen_height(&monero_data.randomx_key)?;
+        if seed_height != 0 {
+            // Saturating sub: subtraction can underflow in reorgs / rewind-blockchain command
+            let seed_used_height = block_header.height.saturating_sub(seed_height);
+            if seed_used_height > rules.consensus_constants(block_header.height).max_randomx_seed_height() {
+                return Err(ValidationError::BlockHeaderError(
+                    BlockHeaderValidationError::OldSeedHash,
                 ));
             }
-            Ok(())
-        },
+        }
     }
+
+    Ok(())
 }
 
 pub fn check_target_difficulty(
@@ -188,8 +202,19 @@ pub fn check_target_difficulty(
     let achieved = match block_header.pow_algo() {
         PowAlgorithm::Monero => monero_difficulty(block_header, randomx_factory)?,
         PowAlgorithm::Sha3 => sha3_difficulty(block_header),
+        PowAlgorithm::Sha3x => sha3x_difficulty(block_header),
     };
 
+    check_achieved_difficulty(block_header, target, achieved)
+}
+
+/// Checks that an achieved difficulty that was already calculated for the header (e.g. by
+/// `calculate_achieved_difficulties`) meets the target difficulty.
+pub fn check_achieved_difficulty(
+    block_header: &BlockHeader,
+    target: Difficulty,
+    achieved: Difficulty,
+) -> Result<AchievedTargetDifficulty, ValidationError> {
     match AchievedTargetDifficulty::try_construct(block_header.pow_algo(), target, achieved) {
         Some(achieved_target) => Ok(achieved_target),
         None => {
@@ -670,11 +695,13 @@ pub fn check_not_bad_block<B: BlockchainBackend>(db: &B, hash: &[u8]) -> Result<
     Ok(())
 }
 
-pub fn validate_covenants(block: &Block) -> Result<(), ValidationError> {
+pub fn validate_covenants(block: &Block, constants: &ConsensusConstants) -> Result<(), ValidationError> {
+    let limits = constants.covenant_limits();
     for input in block.body.inputs() {
-        let output_set_size = input
-            .covenant()?
-            .execute(block.header.height, input, block.body.outputs())?;
+        let output_set_size =
+            input
+                .covenant()?
+                .execute_with_limits(block.header.height, input, block.body.outputs(), limits)?;
         trace!(target: LOG_TARGET, "{} output(s) passed covenant", output_set_size);
     }
     Ok(())
@@ -841,6 +868,61 @@ mod test {
         }
     }
 
+    mod check_pow_data {
+        use tari_common::configuration::Network;
+
+        use super::*;
+        use crate::{consensus::ConsensusConstantsBuilder, test_helpers::blockchain::create_test_db};
+
+        fn rules_with_sha3x_activation_height(height: u64) -> ConsensusManager {
+            ConsensusManager::builder(Network::LocalNet)
+                .add_consensus_constants(
+                    ConsensusConstantsBuilder::new(Network::LocalNet)
+                        .with_sha3x_activation_height(height)
+                        .build(),
+                )
+                .build()
+                .unwrap()
+        }
+
+        fn header(pow_algo: PowAlgorithm, height: u64) -> BlockHeader {
+            let mut header = BlockHeader::new(0);
+            header.height = height;
+            header.pow.pow_algo = pow_algo;
+            header
+        }
+
+        #[test]
+        fn it_only_permits_sha3x_from_the_activation_height() {
+            let db = create_test_db();
+            let rules = rules_with_sha3x_activation_height(10);
+
+            check_pow_data(&header(PowAlgorithm::Sha3, 9), &rules, &db).unwrap();
+            check_pow_data(&header(PowAlgorithm::Sha3x, 10), &rules, &db).unwrap();
+            assert!(matches!(
+                check_pow_data(&header(PowAlgorithm::Sha3x, 9), &rules, &db),
+                Err(ValidationError::BlockHeaderError(
+                    BlockHeaderValidationError::PowAlgorithmNotPermitted { .. }
+                ))
+            ));
+            assert!(matches!(
+                check_pow_data(&header(PowAlgorithm::Sha3, 10), &rules, &db),
+                Err(ValidationError::BlockHeaderError(
+                    BlockHeaderValidationError::PowAlgorithmNotPermitted { .. }
+                ))
+            ));
+        }
+
+        #[test]
+        fn it_rejects_sha3x_pow_data() {
+            let db = create_test_db();
+            let rules = rules_with_sha3x_activation_height(0);
+            let mut header = header(PowAlgorithm::Sha3x, 1);
+            header.pow.pow_data = vec![1, 2, 3];
+            assert!(check_pow_data(&header, &rules, &db).is_err());
+        }
+    }
+
     mod check_lock_height {
         use super::*;
         use crate::transactions::test_helpers;
@@ -45,7 +45,9 @@ pub use traits::{
 pub mod block_validators;
 mod difficulty_calculator;
 pub use difficulty_calculator::*;
+mod header_chain;
 pub mod header_validator;
+pub use header_chain::{verify_header_chain, HeaderChainError, HeaderChainState, HeaderVerificationError};
 pub mod mocks;
 pub mod transaction_validators;
 // pub mod header_validator;
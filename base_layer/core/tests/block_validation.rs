@@ -33,6 +33,7 @@ use tari_core::{
         monero_rx,
         monero_rx::{FixedByteArray, MoneroPowData},
         randomx_factory::RandomXFactory,
+        DifficultyAdjustmentAlgorithm,
         PowAlgorithm,
     },
     test_helpers::blockchain::{create_store_with_consensus_and_validators, create_test_db},
@@ -77,7 +78,7 @@ mod helpers;
 fn test_genesis_block() {
     let factories = CryptoFactories::default();
     let network = Network::Dibbler;
-    let rules = ConsensusManager::builder(network).build();
+    let rules = ConsensusManager::builder(network).build().unwrap();
     let backend = create_test_db();
     let validators = Validators::new(
         BodyOnlyValidator::new(rules.clone()),
@@ -110,23 +111,30 @@ fn test_monero_blocks() {
 
     let factories = CryptoFactories::default();
     let network = Network::Dibbler;
+    // Block shares of 40/60 over a 120s interval give target times of 300s (Sha3) and 200s (Monero)
     let cc = ConsensusConstantsBuilder::new(network)
         .with_max_randomx_seed_height(1)
+        .with_target_block_interval(120)
         .clear_proof_of_work()
         .add_proof_of_work(PowAlgorithm::Sha3, PowAlgorithmConstants {
             max_target_time: 1800,
             min_difficulty: 1.into(),
             max_difficulty: 1.into(),
-            target_time: 300,
+            block_share: 40,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         })
         .add_proof_of_work(PowAlgorithm::Monero, PowAlgorithmConstants {
             max_target_time: 1200,
             min_difficulty: 1.into(),
             max_difficulty: 1.into(),
-            target_time: 200,
+            block_share: 60,
+            difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
         })
         .build();
-    let cm = ConsensusManager::builder(network).add_consensus_constants(cc).build();
+    let cm = ConsensusManager::builder(network)
+        .add_consensus_constants(cc)
+        .build()
+        .unwrap();
     let header_validator = HeaderValidator::new(cm.clone());
     let db = create_store_with_consensus_and_validators(
         cm.clone(),
@@ -263,7 +271,8 @@ fn test_orphan_validator() {
     let rules = ConsensusManager::builder(network)
         .add_consensus_constants(consensus_constants)
         .with_block(genesis.clone())
-        .build();
+        .build()
+        .unwrap();
     let backend = create_test_db();
     let orphan_validator = OrphanBlockValidator::new(rules.clone(), false, factories.clone());
     let validators = Validators::new(
@@ -370,9 +379,11 @@ fn test_orphan_body_validation() {
         max_target_time: 1800,
         min_difficulty: 10.into(),
         max_difficulty: u64::MAX.into(),
-        target_time: 300,
+        block_share: 100,
+        difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
     };
     let consensus_constants = ConsensusConstantsBuilder::new(network)
+        .with_target_block_interval(300)
         .clear_proof_of_work()
         .add_proof_of_work(PowAlgorithm::Sha3, sha3_constants)
         .build();
@@ -381,7 +392,8 @@ fn test_orphan_body_validation() {
     let rules = ConsensusManager::builder(network)
         .add_consensus_constants(consensus_constants)
         .with_block(genesis.clone())
-        .build();
+        .build()
+        .unwrap();
     let backend = create_test_db();
     let body_only_validator = BodyOnlyValidator::new(rules.clone());
     let header_validator = HeaderValidator::new(rules.clone());
@@ -566,9 +578,11 @@ fn test_header_validation() {
         max_target_time: 1800,
         min_difficulty: 20.into(),
         max_difficulty: u64::MAX.into(),
-        target_time: 300,
+        block_share: 100,
+        difficulty_adjustment: DifficultyAdjustmentAlgorithm::Lwma,
     };
     let consensus_constants = ConsensusConstantsBuilder::new(network)
+        .with_target_block_interval(300)
         .clear_proof_of_work()
         .add_proof_of_work(PowAlgorithm::Sha3, sha3_constants)
         .build();
@@ -577,7 +591,8 @@ fn test_header_validation() {
     let rules = ConsensusManager::builder(network)
         .add_consensus_constants(consensus_constants)
         .with_block(genesis.clone())
-        .build();
+        .build()
+        .unwrap();
     let backend = create_test_db();
     let header_validator = HeaderValidator::new(rules.clone());
     let validators = Validators::new(
@@ -685,7 +700,8 @@ async fn test_block_sync_body_validator() {
     let rules = ConsensusManager::builder(network)
         .add_consensus_constants(consensus_constants)
         .with_block(genesis.clone())
-        .build();
+        .build()
+        .unwrap();
     let backend = create_test_db();
 
     let validators = Validators::new(
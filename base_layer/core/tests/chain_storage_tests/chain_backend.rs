@@ -36,7 +36,7 @@ use crate::helpers::database::create_orphan_block;
 #[test]
 fn lmdb_insert_contains_delete_and_fetch_orphan() {
     let network = Network::LocalNet;
-    let consensus = ConsensusManagerBuilder::new(network).build();
+    let consensus = ConsensusManagerBuilder::new(network).build().unwrap();
     let mut db = create_test_db();
     let txs = vec![
         (tx!(1000.into(), fee: 4.into(), inputs: 2, outputs: 1)).0,
@@ -79,7 +79,7 @@ use crate::helpers::{
 #[test]
 fn fetch_nonexistent_header() {
     let network = Network::LocalNet;
-    let _consensus_manager = ConsensusManagerBuilder::new(network).build();
+    let _consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
     let store = create_test_blockchain_db();
 
     assert_eq!(store.fetch_header(1).unwrap(), None);
@@ -88,7 +88,7 @@ fn fetch_nonexistent_header() {
 #[test]
 fn insert_and_fetch_header() {
     let network = Network::LocalNet;
-    let _consensus_manager = ConsensusManagerBuilder::new(network).build();
+    let _consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
     let store = create_test_blockchain_db();
     let genesis_block = store.fetch_tip_header().unwrap();
     let mut header1 = BlockHeader::from_previous(genesis_block.header());
@@ -114,7 +114,7 @@ fn insert_and_fetch_header() {
 #[test]
 fn insert_and_fetch_orphan() {
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManagerBuilder::new(network).build();
+    let consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
     let store = create_test_blockchain_db();
     let txs = vec![
         (tx!(1000.into(), fee: 4.into(), inputs: 2, outputs: 1)).0,
@@ -149,7 +149,7 @@ fn store_and_retrieve_block() {
 fn add_multiple_blocks() {
     // Create new database with genesis block
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManagerBuilder::new(network).build();
+    let consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
     let store = create_store_with_consensus(consensus_manager.clone());
     let metadata = store.get_chain_metadata().unwrap();
     assert_eq!(metadata.height_of_longest_chain(), 0);
@@ -244,7 +244,10 @@ fn rewind_to_height() {
 fn rewind_past_horizon_height() {
     let network = Network::LocalNet;
     let block0 = genesis_block::get_dibbler_genesis_block();
-    let consensus_manager = ConsensusManagerBuilder::new(network).with_block(block0.clone()).build();
+    let consensus_manager = ConsensusManagerBuilder::new(network)
+        .with_block(block0.clone())
+        .build()
+        .unwrap();
     let validators = Validators::new(
         MockValidator::new(true),
         MockValidator::new(true),
@@ -283,6 +286,50 @@ fn rewind_past_horizon_height() {
     assert_eq!(metadata.height_of_longest_chain(), 0);
 }
 
+#[test]
+fn background_pruning_steps() {
+    let network = Network::LocalNet;
+    let block0 = genesis_block::get_dibbler_genesis_block();
+    let consensus_manager = ConsensusManagerBuilder::new(network)
+        .with_block(block0.clone())
+        .build()
+        .unwrap();
+    let validators = Validators::new(
+        MockValidator::new(true),
+        MockValidator::new(true),
+        MockValidator::new(true),
+    );
+    let db = create_test_db();
+    let config = BlockchainDatabaseConfig {
+        pruning_horizon: 2,
+        pruning_interval: 1,
+        background_pruning: true,
+        ..Default::default()
+    };
+    let store = BlockchainDatabase::new(
+        db,
+        consensus_manager.clone(),
+        validators,
+        config,
+        DifficultyCalculator::new(consensus_manager.clone(), Default::default()),
+    )
+    .unwrap();
+
+    let block1 = append_block(&store, &block0, vec![], &consensus_manager, 1.into()).unwrap();
+    let block2 = append_block(&store, &block1, vec![], &consensus_manager, 1.into()).unwrap();
+    let block3 = append_block(&store, &block2, vec![], &consensus_manager, 1.into()).unwrap();
+    let _block4 = append_block(&store, &block3, vec![], &consensus_manager, 1.into()).unwrap();
+    // Blocks are not pruned when they are added
+    assert_eq!(store.get_chain_metadata().unwrap().pruned_height(), 0);
+
+    assert_eq!(store.try_prune_step(1).unwrap(), Some(1));
+    assert_eq!(store.get_chain_metadata().unwrap().pruned_height(), 1);
+    // Pruning stops at the pruning horizon
+    assert_eq!(store.try_prune_step(10).unwrap(), Some(1));
+    assert_eq!(store.get_chain_metadata().unwrap().pruned_height(), 2);
+    assert_eq!(store.try_prune_step(10).unwrap(), Some(0));
+}
+
 #[test]
 fn handle_tip_reorg() {
     // GB --> A1 --> A2(Low PoW)      [Main Chain]
@@ -971,7 +1018,7 @@ fn store_and_retrieve_blocks() {
         MockValidator::new(true),
     );
     let network = Network::LocalNet;
-    let rules = ConsensusManagerBuilder::new(network).build();
+    let rules = ConsensusManagerBuilder::new(network).build().unwrap();
     let db = create_test_db();
     let store = BlockchainDatabase::new(
         db,
@@ -1207,7 +1254,10 @@ fn restore_metadata_and_pruning_horizon_update() {
     );
     let network = Network::LocalNet;
     let block0 = genesis_block::get_dibbler_genesis_block();
-    let rules = ConsensusManagerBuilder::new(network).with_block(block0.clone()).build();
+    let rules = ConsensusManagerBuilder::new(network)
+        .with_block(block0.clone())
+        .build()
+        .unwrap();
     let mut config = BlockchainDatabaseConfig::default();
     let block_hash: BlockHash;
     let temp_path = create_temporary_data_path();
@@ -1283,7 +1333,8 @@ fn invalid_block() {
     let consensus_manager = ConsensusManagerBuilder::new(network)
         .add_consensus_constants(consensus_constants)
         .with_block(block0.clone())
-        .build();
+        .build()
+        .unwrap();
     let validator = MockValidator::new(true);
     let is_valid = validator.shared_flag();
     let validators = Validators::new(MockValidator::new(true), MockValidator::new(true), validator);
@@ -1376,7 +1427,7 @@ fn invalid_block() {
 #[test]
 fn orphan_cleanup_on_block_add() {
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManagerBuilder::new(network).build();
+    let consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
     let validators = Validators::new(
         MockValidator::new(true),
         MockValidator::new(true),
@@ -1444,7 +1495,10 @@ fn orphan_cleanup_on_block_add() {
 fn horizon_height_orphan_cleanup() {
     let network = Network::LocalNet;
     let block0 = genesis_block::get_dibbler_genesis_block();
-    let consensus_manager = ConsensusManagerBuilder::new(network).with_block(block0.clone()).build();
+    let consensus_manager = ConsensusManagerBuilder::new(network)
+        .with_block(block0.clone())
+        .build()
+        .unwrap();
     let validators = Validators::new(
         MockValidator::new(true),
         MockValidator::new(true),
@@ -1509,7 +1563,8 @@ fn orphan_cleanup_on_reorg() {
     let consensus_manager = ConsensusManagerBuilder::new(network)
         .add_consensus_constants(consensus_constants)
         .with_block(block0.clone())
-        .build();
+        .build()
+        .unwrap();
     let validators = Validators::new(
         MockValidator::new(true),
         MockValidator::new(true),
@@ -1644,11 +1699,65 @@ fn orphan_cleanup_on_reorg() {
     );
 }
 
+#[test]
+fn fetch_missing_orphan_parent() {
+    let network = Network::LocalNet;
+    let factories = CryptoFactories::default();
+    let consensus_constants = ConsensusConstantsBuilder::new(network).build();
+    let (block0, output) = create_genesis_block(&factories, &consensus_constants);
+    let consensus_manager = ConsensusManagerBuilder::new(network)
+        .add_consensus_constants(consensus_constants)
+        .with_block(block0.clone())
+        .build()
+        .unwrap();
+    let store = create_store_with_consensus(consensus_manager.clone());
+
+    // Create a chain B1->B2->B3 that the store does not know about
+    let mut orphan_store = create_store_with_consensus(consensus_manager.clone());
+    let mut orphan_blocks = vec![block0];
+    let mut orphan_outputs = vec![vec![output]];
+    for _ in 0..3 {
+        generate_new_block_with_achieved_difficulty(
+            &mut orphan_store,
+            &mut orphan_blocks,
+            &mut orphan_outputs,
+            vec![],
+            Difficulty::from(2),
+            &consensus_manager,
+        )
+        .unwrap();
+    }
+
+    assert_eq!(
+        store.add_block(orphan_blocks[3].to_arc_block()).unwrap(),
+        BlockAddResult::OrphanBlock
+    );
+    assert_eq!(
+        store.add_block(orphan_blocks[2].to_arc_block()).unwrap(),
+        BlockAddResult::OrphanBlock
+    );
+    // B3 is connected through B2, so B1 is missing
+    assert_eq!(
+        store
+            .fetch_missing_orphan_parent(orphan_blocks[3].hash().clone())
+            .unwrap(),
+        Some(orphan_blocks[1].hash().clone())
+    );
+
+    store.add_block(orphan_blocks[1].to_arc_block()).unwrap();
+    assert_eq!(
+        store
+            .fetch_missing_orphan_parent(orphan_blocks[3].hash().clone())
+            .unwrap(),
+        None
+    );
+}
+
 #[test]
 fn orphan_cleanup_delete_all_orphans() {
     let path = create_temporary_data_path();
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManagerBuilder::new(network).build();
+    let consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
     let validators = Validators::new(
         MockValidator::new(true),
         MockValidator::new(true),
@@ -1756,7 +1865,8 @@ fn fails_validation() {
     let consensus_manager = ConsensusManagerBuilder::new(network)
         .add_consensus_constants(consensus_constants)
         .with_block(block0.clone())
-        .build();
+        .build()
+        .unwrap();
     let validators = Validators::new(
         MockValidator::new(false),
         MockValidator::new(true),
@@ -1801,7 +1911,10 @@ fn fails_validation() {
 fn pruned_mode_cleanup_and_fetch_block() {
     let network = Network::LocalNet;
     let block0 = genesis_block::get_dibbler_genesis_block();
-    let consensus_manager = ConsensusManagerBuilder::new(network).with_block(block0.clone()).build();
+    let consensus_manager = ConsensusManagerBuilder::new(network)
+        .with_block(block0.clone())
+        .build()
+        .unwrap();
     let validators = Validators::new(
         MockValidator::new(true),
         MockValidator::new(true),
@@ -115,7 +115,7 @@ fn genesis_template(
 fn print_new_genesis_block() {
     let network = Network::Dibbler;
 
-    let consensus_manager: ConsensusManager = ConsensusManagerBuilder::new(network).build();
+    let consensus_manager: ConsensusManager = ConsensusManagerBuilder::new(network).build().unwrap();
     let factories = CryptoFactories::default();
     let mut header = BlockHeader::new(consensus_manager.consensus_constants(0).blockchain_version());
     let value = consensus_manager.emission_schedule().block_reward(0);
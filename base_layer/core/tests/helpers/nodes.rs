@@ -185,7 +185,7 @@ impl BaseNodeBuilder {
         let network = self.network.as_network();
         let consensus_manager = self
             .consensus_manager
-            .unwrap_or_else(|| ConsensusManagerBuilder::new(network).build());
+            .unwrap_or_else(|| ConsensusManagerBuilder::new(network).build().unwrap());
         let blockchain_db = create_store_with_consensus_and_validators(consensus_manager.clone(), validators);
         let mempool_validator = TxInputAndMaturityValidator::new(blockchain_db.clone());
         let mempool = Mempool::new(
@@ -282,7 +282,7 @@ pub async fn create_network_with_3_base_nodes(
     data_path: &str,
 ) -> (NodeInterfaces, NodeInterfaces, NodeInterfaces, ConsensusManager) {
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManagerBuilder::new(network).build();
+    let consensus_manager = ConsensusManagerBuilder::new(network).build().unwrap();
     create_network_with_3_base_nodes_with_config(
         MempoolServiceConfig::default(),
         LivenessConfig::default(),
@@ -405,10 +405,17 @@ async fn setup_base_node_services(
             mempool.clone(),
             consensus_manager,
             Duration::from_secs(60),
+            true,
+            Default::default(),
+            false,
+        ))
+        .add_initializer(MempoolServiceInitializer::new(
+            Default::default(),
+            mempool.clone(),
+            subscription_factory,
         ))
-        .add_initializer(MempoolServiceInitializer::new(mempool.clone(), subscription_factory))
         .add_initializer(mock_state_machine.get_initializer())
-        .add_initializer(ChainMetadataServiceInitializer)
+        .add_initializer(ChainMetadataServiceInitializer::new(Default::default()))
         .build()
         .await
         .unwrap();
@@ -72,6 +72,41 @@ use tempfile::tempdir;
 #[allow(dead_code)]
 mod helpers;
 
+#[tokio::test]
+#[allow(clippy::identity_op)]
+async fn test_mempool_publishes_insert_events() {
+    let network = Network::LocalNet;
+    let (mut store, mut blocks, mut outputs, consensus_manager) = create_new_blockchain(network);
+    let mempool_validator = TxInputAndMaturityValidator::new(store.clone());
+    let mempool = Mempool::new(
+        MempoolConfig::default(),
+        consensus_manager.clone(),
+        Box::new(mempool_validator),
+    );
+    let mut events = mempool.get_event_stream();
+    let txs = vec![txn_schema!(
+        from: vec![outputs[0][0].clone()],
+        to: vec![2 * T, 2 * T],fee: 5.into(), lock: 0, features: OutputFeatures::default()
+    )];
+    generate_new_block(&mut store, &mut blocks, &mut outputs, txs, &consensus_manager).unwrap();
+
+    let tx = txn_schema!(from: vec![outputs[1][0].clone()], to: vec![1*T], fee: 20*uT, lock: 0, features: OutputFeatures::default());
+    let tx = Arc::new(spend_utxos(tx).0);
+    let (orphan, _, _) = tx!(1*T, fee: 100*uT);
+    let orphan = Arc::new(orphan);
+    mempool.insert(tx.clone()).await.unwrap();
+    mempool.insert(orphan.clone()).await.unwrap();
+
+    let event = events.recv().await.unwrap();
+    assert!(event.is_accepted());
+    assert_eq!(event.transaction, tx);
+    assert_eq!(event.response, TxStorageResponse::UnconfirmedPool);
+    let event = events.recv().await.unwrap();
+    assert!(!event.is_accepted());
+    assert_eq!(event.transaction, orphan);
+    assert_eq!(event.response, TxStorageResponse::NotStoredOrphan);
+}
+
 #[tokio::test]
 #[allow(clippy::identity_op)]
 async fn test_insert_and_process_published_block() {
@@ -762,10 +797,12 @@ async fn test_reorg() {
     let template = chain_block(blocks[2].block(), vec![], &consensus_manager);
     let reorg_block3 = db.prepare_new_block(template).unwrap();
 
-    mempool
+    let returned_txs = mempool
         .process_reorg(vec![blocks[3].to_arc_block()], vec![reorg_block3.into()])
         .await
         .unwrap();
+    // The transactions mined in the removed block are returned to the mempool
+    assert_eq!(returned_txs.len(), 2);
     let stats = mempool.stats().await.unwrap();
     assert_eq!(stats.unconfirmed_txs, 2);
     // assert_eq!(stats.timelocked_txs, 1);
@@ -795,7 +832,8 @@ async fn receive_and_propagate_transaction() {
     let consensus_manager = ConsensusManager::builder(network)
         .add_consensus_constants(consensus_constants)
         .with_block(block0)
-        .build();
+        .build()
+        .unwrap();
     let (mut alice_node, mut bob_node, mut carol_node, _consensus_manager) =
         create_network_with_3_base_nodes_with_config(
             MempoolServiceConfig::default(),
@@ -1319,7 +1357,8 @@ async fn block_event_and_reorg_event_handling() {
     let consensus_manager = ConsensusManager::builder(network)
         .add_consensus_constants(consensus_constants[0].clone())
         .with_block(block0.clone())
-        .build();
+        .build()
+        .unwrap();
     let (mut alice, mut bob, consensus_manager) = create_network_with_2_base_nodes_with_config(
         MempoolServiceConfig::default(),
         LivenessConfig::default(),
@@ -1386,12 +1425,19 @@ async fn block_event_and_reorg_event_handling() {
     );
     alice.mempool.insert(Arc::new(tx2a.clone())).await.unwrap();
     alice.mempool.insert(Arc::new(tx3a.clone())).await.unwrap();
-    alice.mempool.insert(Arc::new(tx2b.clone())).await.unwrap();
-    alice.mempool.insert(Arc::new(tx3b.clone())).await.unwrap();
     bob.mempool.insert(Arc::new(tx2a.clone())).await.unwrap();
     bob.mempool.insert(Arc::new(tx3a.clone())).await.unwrap();
-    bob.mempool.insert(Arc::new(tx2b.clone())).await.unwrap();
-    bob.mempool.insert(Arc::new(tx3b.clone())).await.unwrap();
+    // tx2b and tx3b spend the same inputs as tx2a and tx3a without paying a higher fee, so they are rejected
+    for tx in [&tx2b, &tx3b] {
+        assert_eq!(
+            alice.mempool.insert(Arc::new(tx.clone())).await.unwrap(),
+            TxStorageResponse::NotStoredFeeTooLow
+        );
+        assert_eq!(
+            bob.mempool.insert(Arc::new(tx.clone())).await.unwrap(),
+            TxStorageResponse::NotStoredFeeTooLow
+        );
+    }
 
     let mut block2a = bob
         .blockchain_db
@@ -1405,7 +1451,7 @@ async fn block_event_and_reorg_event_handling() {
         .unwrap();
     find_header_with_achieved_difficulty(&mut block2b.header, Difficulty::from(10));
 
-    // Add Block2a - tx2b and tx3b will be discarded as double spends.
+    // Add Block2a - tx2a and tx3a will be moved to the ReorgPool.
     assert!(bob.local_nci.submit_block(block2a.clone(),).await.is_ok());
 
     async_assert_eventually!(
@@ -1441,7 +1487,7 @@ async fn block_event_and_reorg_event_handling() {
             .has_tx_with_excess_sig(tx2b_excess_sig.clone())
             .await
             .unwrap(),
-        TxStorageResponse::ReorgPool
+        TxStorageResponse::NotStored
     );
     assert_eq!(
         alice
@@ -1449,6 +1495,6 @@ async fn block_event_and_reorg_event_handling() {
             .has_tx_with_excess_sig(tx3b_excess_sig.clone())
             .await
             .unwrap(),
-        TxStorageResponse::ReorgPool
+        TxStorageResponse::NotStored
     );
 }
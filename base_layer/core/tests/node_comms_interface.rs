@@ -25,11 +25,9 @@ use tari_common::configuration::Network;
 use tari_common_types::types::PublicKey;
 use tari_comms::test_utils::mocks::create_connectivity_mock;
 use tari_core::{
-    base_node::comms_interface::{
-        InboundNodeCommsHandlers,
-        NodeCommsRequest,
-        NodeCommsResponse,
-        OutboundNodeCommsInterface,
+    base_node::{
+        comms_interface::{InboundNodeCommsHandlers, NodeCommsRequest, NodeCommsResponse, OutboundNodeCommsInterface},
+        PeerOffenseTracker,
     },
     chain_storage::{BlockchainDatabaseConfig, DbTransaction, Validators},
     consensus::ConsensusManager,
@@ -68,11 +66,12 @@ async fn inbound_get_metadata() {
     let mempool = new_mempool();
 
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManager::builder(network).build();
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
     let (block_event_sender, _) = broadcast::channel(50);
     let (request_sender, _) = reply_channel::unbounded();
     let (block_sender, _) = mpsc::unbounded_channel();
-    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender.clone());
+    let (orphan_parent_sender, _) = mpsc::unbounded_channel();
+    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender.clone(), orphan_parent_sender);
 
     let (connectivity, _) = create_connectivity_mock();
     let inbound_nch = InboundNodeCommsHandlers::new(
@@ -82,6 +81,7 @@ async fn inbound_get_metadata() {
         consensus_manager,
         outbound_nci,
         connectivity,
+        PeerOffenseTracker::new(Default::default()),
     );
     let block = store.fetch_block(0).unwrap().block().clone();
 
@@ -102,11 +102,12 @@ async fn inbound_fetch_kernel_by_excess_sig() {
     let mempool = new_mempool();
 
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManager::builder(network).build();
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
     let (block_event_sender, _) = broadcast::channel(50);
     let (request_sender, _) = reply_channel::unbounded();
     let (block_sender, _) = mpsc::unbounded_channel();
-    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender.clone());
+    let (orphan_parent_sender, _) = mpsc::unbounded_channel();
+    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender.clone(), orphan_parent_sender);
     let (connectivity, _) = create_connectivity_mock();
     let inbound_nch = InboundNodeCommsHandlers::new(
         block_event_sender,
@@ -115,6 +116,7 @@ async fn inbound_fetch_kernel_by_excess_sig() {
         consensus_manager,
         outbound_nci,
         connectivity,
+        PeerOffenseTracker::new(Default::default()),
     );
     let block = store.fetch_block(0).unwrap().block().clone();
     let sig = block.body.kernels()[0].excess_sig.clone();
@@ -135,11 +137,12 @@ async fn inbound_fetch_headers() {
     let store = create_test_blockchain_db();
     let mempool = new_mempool();
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManager::builder(network).build();
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
     let (block_event_sender, _) = broadcast::channel(50);
     let (request_sender, _) = reply_channel::unbounded();
     let (block_sender, _) = mpsc::unbounded_channel();
-    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender);
+    let (orphan_parent_sender, _) = mpsc::unbounded_channel();
+    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender, orphan_parent_sender);
     let (connectivity, _) = create_connectivity_mock();
     let inbound_nch = InboundNodeCommsHandlers::new(
         block_event_sender,
@@ -148,6 +151,7 @@ async fn inbound_fetch_headers() {
         consensus_manager,
         outbound_nci,
         connectivity,
+        PeerOffenseTracker::new(Default::default()),
     );
     let header = store.fetch_block(0).unwrap().header().clone();
 
@@ -168,11 +172,12 @@ async fn inbound_fetch_utxos() {
     let store = create_test_blockchain_db();
     let mempool = new_mempool();
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManager::builder(network).build();
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
     let (block_event_sender, _) = broadcast::channel(50);
     let (request_sender, _) = reply_channel::unbounded();
     let (block_sender, _) = mpsc::unbounded_channel();
-    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender);
+    let (orphan_parent_sender, _) = mpsc::unbounded_channel();
+    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender, orphan_parent_sender);
     let (connectivity, _) = create_connectivity_mock();
     let inbound_nch = InboundNodeCommsHandlers::new(
         block_event_sender,
@@ -181,6 +186,7 @@ async fn inbound_fetch_utxos() {
         consensus_manager,
         outbound_nci,
         connectivity,
+        PeerOffenseTracker::new(Default::default()),
     );
     let block = store.fetch_block(0).unwrap().block().clone();
     let utxo_1 = block.body.outputs()[0].clone();
@@ -214,10 +220,11 @@ async fn inbound_fetch_txos() {
     let mempool = new_mempool();
     let (block_event_sender, _) = broadcast::channel(50);
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManager::builder(network).build();
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
     let (request_sender, _) = reply_channel::unbounded();
     let (block_sender, _) = mpsc::unbounded_channel();
-    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender);
+    let (orphan_parent_sender, _) = mpsc::unbounded_channel();
+    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender, orphan_parent_sender);
     let (connectivity, _) = create_connectivity_mock();
     let inbound_nch = InboundNodeCommsHandlers::new(
         block_event_sender,
@@ -226,6 +233,7 @@ async fn inbound_fetch_txos() {
         consensus_manager,
         outbound_nci,
         connectivity,
+        PeerOffenseTracker::new(Default::default()),
     );
 
     let (utxo, _, _) = create_utxo(
@@ -288,10 +296,11 @@ async fn inbound_fetch_blocks() {
     let mempool = new_mempool();
     let (block_event_sender, _) = broadcast::channel(50);
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManager::builder(network).build();
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
     let (request_sender, _) = reply_channel::unbounded();
     let (block_sender, _) = mpsc::unbounded_channel();
-    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender);
+    let (orphan_parent_sender, _) = mpsc::unbounded_channel();
+    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender, orphan_parent_sender);
     let (connectivity, _) = create_connectivity_mock();
     let inbound_nch = InboundNodeCommsHandlers::new(
         block_event_sender,
@@ -300,6 +309,7 @@ async fn inbound_fetch_blocks() {
         consensus_manager,
         outbound_nci,
         connectivity,
+        PeerOffenseTracker::new(Default::default()),
     );
     let block = store.fetch_block(0).unwrap().block().clone();
 
@@ -318,7 +328,7 @@ async fn inbound_fetch_blocks() {
 async fn inbound_fetch_blocks_before_horizon_height() {
     let factories = CryptoFactories::default();
     let network = Network::LocalNet;
-    let consensus_manager = ConsensusManager::builder(network).build();
+    let consensus_manager = ConsensusManager::builder(network).build().unwrap();
     let block0 = consensus_manager.get_genesis_block();
     let validators = Validators::new(
         MockValidator::new(true),
@@ -340,7 +350,8 @@ async fn inbound_fetch_blocks_before_horizon_height() {
     let (block_event_sender, _) = broadcast::channel(50);
     let (request_sender, _) = reply_channel::unbounded();
     let (block_sender, _) = mpsc::unbounded_channel();
-    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender);
+    let (orphan_parent_sender, _) = mpsc::unbounded_channel();
+    let outbound_nci = OutboundNodeCommsInterface::new(request_sender, block_sender, orphan_parent_sender);
     let (connectivity, _) = create_connectivity_mock();
     let inbound_nch = InboundNodeCommsHandlers::new(
         block_event_sender,
@@ -349,6 +360,7 @@ async fn inbound_fetch_blocks_before_horizon_height() {
         consensus_manager.clone(),
         outbound_nci,
         connectivity,
+        PeerOffenseTracker::new(Default::default()),
     );
     let script = script!(Nop);
     let (utxo, key, offset) = create_utxo(
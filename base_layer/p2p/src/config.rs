@@ -20,11 +20,15 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use serde::{Deserialize, Serialize};
 use tari_common::{
     configuration::{
+        serializers,
         utils::{deserialize_string_or_struct, serialize_string},
         StringList,
     },
@@ -115,9 +119,48 @@ pub struct P2pConfig {
     /// for direct comms between a wallet and base node. If this is set to None, no listener will be bound.
     /// Default: None
     pub auxiliary_tcp_listener_address: Option<Multiaddr>,
+    /// The address to bind a WebSocket listener on _in addition to_ the primary transport, e.g.
+    /// `/ip4/0.0.0.0/tcp/18190/ws`. This allows light clients and browser-based wallets to connect directly. If this
+    /// is set to None, no listener will be bound.
+    /// Default: None
+    pub websocket_listener_address: Option<Multiaddr>,
     /// The global maximum allowed RPC sessions.
     /// Default: 100
     pub rpc_max_simultaneous_sessions: usize,
+    /// Hex public keys of locally authorized peers (e.g. a co-located wallet) for which RPC sessions are reserved.
+    /// These peers are able to establish RPC sessions even if `rpc_max_simultaneous_sessions` has been reached.
+    /// Default: empty
+    pub rpc_reserved_session_public_keys: StringList,
+    /// The maximum number of RPC sessions reserved for the peers in `rpc_reserved_session_public_keys`.
+    /// Default: 10
+    pub rpc_max_reserved_sessions: usize,
+    /// The size of the chunks into which RPC response messages are split.
+    /// Default: 256 KiB
+    pub rpc_chunk_size: usize,
+    /// Reduce the RPC chunk size of sessions for which sending responses is slow, e.g. over Tor circuits.
+    /// Default: false
+    pub rpc_adaptive_chunking: bool,
+    /// The maximum number of inbound connection attempts that are handled at the same time. Once this limit is
+    /// reached, peers attempting to connect will have to wait for another connection attempt to complete.
+    /// Default: 100
+    pub max_simultaneous_inbound_connects: usize,
+    /// The minimum age of an inactive connection before it is disconnected.
+    /// Default: 20 minutes
+    #[serde(with = "serializers::seconds")]
+    pub connection_reaper_min_inactive_age: Duration,
+    /// Interval to check the connection pool, including reaping inactive connections.
+    /// Default: 60 seconds
+    #[serde(with = "serializers::seconds")]
+    pub connection_pool_refresh_interval: Duration,
+    /// Rotate the session keys of a connection once this many bytes have been sent with the current key. Keys are only
+    /// rotated on connections to peers that support it.
+    /// Default: 1 GiB
+    pub noise_rekey_after_bytes: Option<u64>,
+    /// Rotate the session keys of a connection once the current key has been in use for this long. Keys are only
+    /// rotated on connections to peers that support it.
+    /// Default: 1 hour
+    #[serde(with = "serializers::optional_seconds")]
+    pub noise_rekey_interval: Option<Duration>,
 }
 
 impl Default for P2pConfig {
@@ -140,7 +183,17 @@ impl Default for P2pConfig {
             listener_liveness_allowlist_cidrs: StringList::default(),
             user_agent: "".to_string(),
             auxiliary_tcp_listener_address: None,
+            websocket_listener_address: None,
             rpc_max_simultaneous_sessions: 100,
+            rpc_reserved_session_public_keys: StringList::default(),
+            rpc_max_reserved_sessions: 10,
+            rpc_chunk_size: 256 * 1024,
+            rpc_adaptive_chunking: false,
+            max_simultaneous_inbound_connects: 100,
+            connection_reaper_min_inactive_age: Duration::from_secs(20 * 60),
+            connection_pool_refresh_interval: Duration::from_secs(60),
+            noise_rekey_after_bytes: Some(1024 * 1024 * 1024),
+            noise_rekey_interval: Some(Duration::from_secs(60 * 60)),
         }
     }
 }
@@ -41,6 +41,7 @@ use tari_comms::{
     backoff::ConstantBackoff,
     peer_manager::{NodeIdentity, Peer, PeerFeatures, PeerManagerError},
     pipeline,
+    port_mapping::PortMappingConfig,
     protocol::{
         messaging::{MessagingEventSender, MessagingProtocolExtension},
         rpc::RpcServer,
@@ -48,12 +49,20 @@ use tari_comms::{
     },
     tor,
     tor::HiddenServiceControllerError,
-    transports::{predicate::FalsePredicate, MemoryTransport, SocksConfig, SocksTransport, TcpWithTorTransport},
+    transports::{
+        predicate::FalsePredicate,
+        MemoryTransport,
+        QuicTransport,
+        SocksConfig,
+        SocksTransport,
+        TcpWithTorTransport,
+    },
     utils::cidr::parse_cidrs,
     CommsBuilder,
     CommsBuilderError,
     CommsNode,
     PeerManager,
+    RekeyPolicy,
     UnspawnedCommsNode,
 };
 use tari_comms_dht::{Dht, DhtInitializationError};
@@ -218,10 +227,11 @@ pub async fn spawn_comms_using_transport(
                     proxy_bypass_predicate: Arc::new(FalsePredicate::new()),
                 });
             }
-            comms
-                .with_listener_address(config.listener_address)
-                .spawn_with_transport(transport)
-                .await?
+            let mut comms = comms.with_listener_address(config.listener_address);
+            if config.enable_port_mapping {
+                comms = comms.with_port_mapping(PortMappingConfig::default());
+            }
+            comms.spawn_with_transport(transport).await?
         },
         TransportType::Tor => {
             let tor_config = transport_config.tor;
@@ -244,6 +254,16 @@ pub async fn spawn_comms_using_transport(
                 .spawn_with_transport(transport)
                 .await?
         },
+        TransportType::Quic => {
+            debug!(target: LOG_TARGET, "Building QUIC comms stack");
+            let config = transport_config.quic;
+            let listener_address = config.listener_address.clone();
+            let transport = QuicTransport::new(config.into())?;
+            comms
+                .with_listener_address(listener_address)
+                .spawn_with_transport(transport)
+                .await?
+        },
     };
 
     Ok(comms)
@@ -294,16 +314,26 @@ async fn configure_comms_and_dht(
     let listener_liveness_allowlist_cidrs = parse_cidrs(&config.listener_liveness_allowlist_cidrs)
         .map_err(CommsInitializationError::InvalidLivenessCidrs)?;
 
-    let builder = builder
+    let mut builder = builder
         .with_listener_liveness_max_sessions(config.listener_liveness_max_sessions)
         .with_listener_liveness_allowlist_cidrs(listener_liveness_allowlist_cidrs)
         .with_dial_backoff(ConstantBackoff::new(Duration::from_millis(500)))
+        .with_max_simultaneous_inbound_connects(config.max_simultaneous_inbound_connects)
+        .with_reaper_min_inactive_age(config.connection_reaper_min_inactive_age)
+        .with_connection_pool_refresh_interval(config.connection_pool_refresh_interval)
+        .with_noise_rekey_policy(RekeyPolicy {
+            max_bytes: config.noise_rekey_after_bytes,
+            max_age: config.noise_rekey_interval,
+        })
         .with_peer_storage(peer_database, Some(file_lock));
 
-    let mut comms = match config.auxiliary_tcp_listener_address {
-        Some(ref addr) => builder.with_auxiliary_tcp_listener_address(addr.clone()).build()?,
-        None => builder.build()?,
-    };
+    if let Some(ref addr) = config.auxiliary_tcp_listener_address {
+        builder = builder.with_auxiliary_tcp_listener_address(addr.clone());
+    }
+    if let Some(ref addr) = config.websocket_listener_address {
+        builder = builder.with_websocket_listener_address(addr.clone());
+    }
+    let mut comms = builder.build()?;
 
     let peer_manager = comms.peer_manager();
     let connectivity = comms.connectivity();
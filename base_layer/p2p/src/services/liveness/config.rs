@@ -35,6 +35,12 @@ pub struct LivenessConfig {
     pub monitored_peers: Vec<NodeId>,
     /// Number of ping failures to tolerate before disconnecting the peer. A value of zero disables this feature.
     pub max_allowed_ping_failures: usize,
+    /// A `MonitoredPeerSlaBreached` event is published when the 90th percentile round-trip time of a monitored peer
+    /// exceeds this value, or None to disable the latency check (default: None (disabled))
+    pub monitored_peer_max_latency: Option<Duration>,
+    /// A `MonitoredPeerSlaBreached` event is published when a monitored peer fails to respond to more than this
+    /// percentage of recent pings, or None to disable the ping loss check (default: None (disabled))
+    pub monitored_peer_max_ping_loss_percent: Option<u8>,
 }
 
 impl Default for LivenessConfig {
@@ -44,6 +50,8 @@ impl Default for LivenessConfig {
             num_peers_per_round: 8,
             monitored_peers: Default::default(),
             max_allowed_ping_failures: 2,
+            monitored_peer_max_latency: None,
+            monitored_peer_max_ping_loss_percent: None,
         }
     }
 }
@@ -27,7 +27,10 @@ use tari_service_framework::reply_channel::SenderService;
 use tokio::sync::broadcast;
 use tower::Service;
 
-use super::{error::LivenessError, state::Metadata};
+use super::{
+    error::LivenessError,
+    state::{Metadata, PeerLivenessStats, SlaViolation},
+};
 use crate::proto::liveness::MetadataKey;
 
 /// Request types made through the `LivenessHandle` and are handled by the `LivenessService`
@@ -43,6 +46,8 @@ pub enum LivenessRequest {
     GetAvgLatency(NodeId),
     /// Get average latency for all connected nodes
     GetNetworkAvgLatency,
+    /// Get the round-trip-time histogram and ping loss for node ID
+    GetPeerStats(NodeId),
     /// Set the metadata attached to each ping/pong message
     SetMetadataEntry(MetadataKey, Vec<u8>),
     /// Add a monitored peer to the basic config
@@ -60,6 +65,8 @@ pub enum LivenessResponse {
     Count(usize),
     /// Response for GetAvgLatency and GetNetworkAvgLatency
     AvgLatency(Option<Duration>),
+    /// Response for GetPeerStats
+    PeerStats(Option<Box<PeerLivenessStats>>),
     /// The number of active neighbouring peers
     NumActiveNeighbours(usize),
 }
@@ -73,6 +80,17 @@ pub enum LivenessEvent {
     ReceivedPong(Box<PingPongEvent>),
     /// A round of pings was broadcast to random and monitored peers
     PingRoundBroadcast(usize),
+    /// A monitored peer exceeded the configured latency or ping loss threshold
+    MonitoredPeerSlaBreached(Box<SlaBreachEvent>),
+    /// A monitored peer that previously exceeded a threshold is within the configured thresholds again
+    MonitoredPeerSlaRestored(NodeId),
+}
+
+/// Represents a monitored peer breaching the configured liveness thresholds
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlaBreachEvent {
+    pub node_id: NodeId,
+    pub violation: SlaViolation,
 }
 
 /// Represents a ping or pong event
@@ -185,6 +203,15 @@ impl LivenessHandle {
         }
     }
 
+    /// Retrieve the round-trip-time histogram and ping loss for a given node. None is returned if no pings have been
+    /// sent to the node.
+    pub async fn get_peer_stats(&mut self, node_id: NodeId) -> Result<Option<PeerLivenessStats>, LivenessError> {
+        match self.handle.call(LivenessRequest::GetPeerStats(node_id)).await?? {
+            LivenessResponse::PeerStats(v) => Ok(v.map(|stats| *stats)),
+            _ => Err(LivenessError::UnexpectedApiResponse),
+        }
+    }
+
     /// Retrieve the mean average latency for all connected nodes
     pub async fn get_network_avg_latency(&mut self) -> Result<Option<Duration>, LivenessError> {
         match self.handle.call(LivenessRequest::GetNetworkAvgLatency).await?? {
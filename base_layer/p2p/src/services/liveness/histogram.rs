@@ -0,0 +1,151 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{collections::VecDeque, time::Duration};
+
+/// Upper bounds of the latency histogram buckets. Samples greater than the last bound are counted in an overflow
+/// bucket.
+const BUCKET_UPPER_BOUNDS: [Duration; 8] = [
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_millis(2500),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+];
+
+/// A bucket in a latency histogram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyBucket {
+    /// The inclusive upper bound of this bucket, or None for the overflow bucket
+    pub upper_bound: Option<Duration>,
+    /// The number of samples in this bucket
+    pub count: usize,
+}
+
+/// A rolling round-trip-time histogram. Only the most recent `capacity` samples are retained.
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    samples: VecDeque<Duration>,
+    capacity: usize,
+}
+
+impl LatencyHistogram {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Add a sample, discarding the oldest sample if the histogram is at capacity
+    pub fn add_sample(&mut self, sample: Duration) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the sample counts for each bucket, including the overflow bucket
+    pub fn buckets(&self) -> Vec<LatencyBucket> {
+        let mut counts = [0usize; BUCKET_UPPER_BOUNDS.len() + 1];
+        for sample in &self.samples {
+            let idx = BUCKET_UPPER_BOUNDS
+                .iter()
+                .position(|bound| sample <= bound)
+                .unwrap_or(BUCKET_UPPER_BOUNDS.len());
+            counts[idx] += 1;
+        }
+        counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| LatencyBucket {
+                upper_bound: BUCKET_UPPER_BOUNDS.get(i).copied(),
+                count: *count,
+            })
+            .collect()
+    }
+
+    /// Returns the sample at the given percentile (0-100) using the nearest-rank method, or None if there are no
+    /// samples
+    pub fn percentile(&self, percentile: u8) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut sorted = self.samples.iter().copied().collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let rank = (usize::from(percentile.min(100)) * sorted.len() + 99) / 100;
+        Some(sorted[rank.saturating_sub(1)])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_discards_the_oldest_sample() {
+        let mut histogram = LatencyHistogram::new(3);
+        for ms in [1000, 10, 20, 30] {
+            histogram.add_sample(Duration::from_millis(ms));
+        }
+        assert_eq!(histogram.len(), 3);
+        assert_eq!(histogram.percentile(100), Some(Duration::from_millis(30)));
+    }
+
+    #[test]
+    fn it_calculates_percentiles() {
+        let mut histogram = LatencyHistogram::new(100);
+        assert!(histogram.percentile(50).is_none());
+        for ms in 1..=100 {
+            histogram.add_sample(Duration::from_millis(ms));
+        }
+        assert_eq!(histogram.percentile(50), Some(Duration::from_millis(50)));
+        assert_eq!(histogram.percentile(90), Some(Duration::from_millis(90)));
+        assert_eq!(histogram.percentile(0), Some(Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn it_buckets_samples() {
+        let mut histogram = LatencyHistogram::new(10);
+        for ms in [10, 50, 51, 400, 20_000] {
+            histogram.add_sample(Duration::from_millis(ms));
+        }
+        let buckets = histogram.buckets();
+        assert_eq!(buckets.len(), BUCKET_UPPER_BOUNDS.len() + 1);
+        assert_eq!(buckets[0].count, 2);
+        assert_eq!(buckets[1].count, 1);
+        assert_eq!(buckets[3].count, 1);
+        assert_eq!(buckets.last().unwrap().count, 1);
+        assert!(buckets.last().unwrap().upper_bound.is_none());
+    }
+}
@@ -137,6 +137,9 @@ impl LivenessMock {
             GetAvgLatency(_) => {
                 reply.send(Ok(LivenessResponse::AvgLatency(None))).unwrap();
             },
+            GetPeerStats(_) => {
+                reply.send(Ok(LivenessResponse::PeerStats(None))).unwrap();
+            },
             GetNetworkAvgLatency => {
                 reply.send(Ok(LivenessResponse::AvgLatency(None))).unwrap();
             },
@@ -27,10 +27,9 @@
 //!
 //! It is responsible for:
 //! - handling requests to the Liveness backend. Types of requests can be found in the [LivenessRequest] enum, and
-//! - reading incoming [PingPong] messages and processing them.
-//!
-//! In future, this service may be expanded to included periodic pings to maintain
-//! latency and availability statistics for peers.
+//! - reading incoming [PingPong] messages and processing them, and
+//! - maintaining rolling round-trip-time histograms and ping loss for peers, publishing an event when a monitored
+//! peer exceeds the configured latency or ping loss thresholds.
 //!
 //! [LivenessRequest]: ./messages/enum.LivenessRequets.html
 //! [PingPong]: ./messages/enum.PingPong.html
@@ -48,13 +47,17 @@ pub use handle::{
     LivenessRequest,
     LivenessResponse,
     PingPongEvent,
+    SlaBreachEvent,
 };
 
+mod histogram;
+pub use histogram::LatencyBucket;
+
 mod message;
 mod service;
 
 mod state;
-pub use state::Metadata;
+pub use state::{Metadata, PeerLivenessStats, SlaViolation};
 
 #[cfg(feature = "test-mocks")]
 pub mod mock;
@@ -107,8 +110,10 @@ impl LivenessInitializer {
     }
 
     /// Get a stream of inbound PingPong messages
-    fn ping_stream(&self) -> impl Stream<Item = DomainMessage<PingPongMessage>> {
-        self.inbound_message_subscription_factory
+    fn ping_stream(
+        subscription_factory: &TopicSubscriptionFactory<TariMessageType, Arc<PeerMessage>>,
+    ) -> impl Stream<Item = DomainMessage<PingPongMessage>> {
+        subscription_factory
             .get_subscription(TariMessageType::PingPong, "Liveness")
             .map(map_decode::<PingPongMessage>)
             .filter_map(ok_or_skip_result)
@@ -132,27 +137,34 @@ impl ServiceInitializer for LivenessInitializer {
             .take()
             .expect("Liveness service initialized more than once.");
 
-        // Create a stream which receives PingPong messages from comms
-        let ping_stream = self.ping_stream();
-
-        // Spawn the Liveness service on the executor
-        context.spawn_when_ready(|handles| async move {
-            let dht = handles.expect_handle::<Dht>();
-            let connectivity = handles.expect_handle::<ConnectivityRequester>();
-            let outbound_messages = dht.outbound_requester();
-
-            let service = LivenessService::new(
-                config,
-                receiver,
-                ping_stream,
-                LivenessState::new(),
-                connectivity,
-                outbound_messages,
-                publisher,
-                handles.get_shutdown_signal(),
-            );
-            service.run().await;
-            debug!(target: LOG_TARGET, "Liveness service has shut down");
+        let subscription_factory = self.inbound_message_subscription_factory.clone();
+
+        // Spawn the Liveness service on the executor. The service can be restarted at runtime, in which case it starts
+        // with a fresh state and a new subscription to PingPong messages.
+        context.spawn_restartable("liveness", receiver, move |handles, receiver, stop_signal| {
+            // Create a stream which receives PingPong messages from comms
+            let ping_stream = Self::ping_stream(&subscription_factory);
+            let config = config.clone();
+            let publisher = publisher.clone();
+            async move {
+                let dht = handles.expect_handle::<Dht>();
+                let connectivity = handles.expect_handle::<ConnectivityRequester>();
+                let outbound_messages = dht.outbound_requester();
+
+                let service = LivenessService::new(
+                    config,
+                    receiver,
+                    ping_stream,
+                    LivenessState::new(),
+                    connectivity,
+                    outbound_messages,
+                    publisher,
+                    stop_signal,
+                );
+                let receiver = service.run().await;
+                debug!(target: LOG_TARGET, "Liveness service has shut down");
+                receiver
+            }
         });
 
         debug!(target: LOG_TARGET, "Liveness service initialized");
@@ -42,14 +42,14 @@ use super::{
     config::LivenessConfig,
     error::LivenessError,
     message::{PingPong, PingPongMessage},
-    state::LivenessState,
+    state::{LivenessState, SlaTransition},
     LivenessRequest,
     LivenessResponse,
     LOG_TARGET,
 };
 use crate::{
     domain_message::DomainMessage,
-    services::liveness::{handle::LivenessEventSender, LivenessEvent, PingPongEvent},
+    services::liveness::{handle::LivenessEventSender, LivenessEvent, PingPongEvent, SlaBreachEvent},
     tari_message::TariMessageType,
 };
 
@@ -69,7 +69,7 @@ pub struct LivenessService<THandleStream, TPingStream> {
 impl<TRequestStream, TPingStream> LivenessService<TRequestStream, TPingStream>
 where
     TPingStream: Stream<Item = DomainMessage<PingPongMessage>>,
-    TRequestStream: Stream<Item = RequestContext<LivenessRequest, Result<LivenessResponse, LivenessError>>>,
+    TRequestStream: Stream<Item = RequestContext<LivenessRequest, Result<LivenessResponse, LivenessError>>> + Unpin,
 {
     pub fn new(
         config: LivenessConfig,
@@ -94,14 +94,15 @@ where
         }
     }
 
-    pub async fn run(mut self) {
+    /// Runs the service until the shutdown signal is triggered. The request stream is returned so that the service can
+    /// be restarted.
+    pub async fn run(mut self) -> TRequestStream {
         debug!(target: LOG_TARGET, "Liveness service started");
         debug!(target: LOG_TARGET, "Config = {:?}", self.config);
         let ping_stream = self.ping_stream.take().expect("ping_stream cannot be None").fuse();
         pin_mut!(ping_stream);
 
-        let request_stream = self.request_rx.take().expect("ping_stream cannot be None").fuse();
-        pin_mut!(request_stream);
+        let mut request_stream = self.request_rx.take().expect("request_rx cannot be None").fuse();
 
         let mut ping_tick = match self.config.auto_ping_interval {
             Some(interval) => {
@@ -145,6 +146,8 @@ where
                 }
             }
         }
+
+        request_stream.into_inner()
     }
 
     async fn handle_incoming_message(&mut self, msg: DomainMessage<PingPongMessage>) -> Result<(), LivenessError> {
@@ -199,8 +202,13 @@ where
                     message_tag,
                 );
 
-                let pong_event = PingPongEvent::new(node_id, maybe_latency, ping_pong_msg.metadata.into());
+                let pong_event = PingPongEvent::new(node_id.clone(), maybe_latency, ping_pong_msg.metadata.into());
                 self.publish_event(LivenessEvent::ReceivedPong(Box::new(pong_event)));
+
+                let is_monitored = { self.monitored_peers.read().await.contains(&node_id) };
+                if is_monitored {
+                    self.check_sla(&node_id);
+                }
             },
         }
         Ok(())
@@ -253,6 +261,10 @@ where
                 let latency = self.state.get_network_avg_latency();
                 Ok(LivenessResponse::AvgLatency(latency))
             },
+            GetPeerStats(node_id) => {
+                let stats = self.state.get_peer_stats(&node_id);
+                Ok(LivenessResponse::PeerStats(stats.map(Box::new)))
+            },
             SetMetadataEntry(key, value) => {
                 self.state.set_metadata_entry(key, value);
                 Ok(LivenessResponse::Ok)
@@ -276,6 +288,10 @@ where
 
     async fn start_ping_round(&mut self) -> Result<(), LivenessError> {
         let monitored_peers = { self.monitored_peers.read().await.clone() };
+        // Ping loss is recorded when inflight pings expire, so evaluate the monitored peers before the next round
+        for node_id in &monitored_peers {
+            self.check_sla(node_id);
+        }
         let selected_peers = self
             .connectivity
             .select_connections(ConnectivitySelection::random_nodes(
@@ -331,6 +347,38 @@ where
         Ok(())
     }
 
+    /// Publishes an event if the monitored peer has started or stopped breaching the configured thresholds
+    fn check_sla(&mut self, node_id: &NodeId) {
+        let transition = self.state.evaluate_sla(
+            node_id,
+            self.config.monitored_peer_max_latency,
+            self.config.monitored_peer_max_ping_loss_percent,
+        );
+        match transition {
+            Some(SlaTransition::Breached(violation)) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Monitored peer '{}' is breaching liveness thresholds: {:?}",
+                    node_id.short_str(),
+                    violation
+                );
+                self.publish_event(LivenessEvent::MonitoredPeerSlaBreached(Box::new(SlaBreachEvent {
+                    node_id: node_id.clone(),
+                    violation,
+                })));
+            },
+            Some(SlaTransition::Restored) => {
+                info!(
+                    target: LOG_TARGET,
+                    "Monitored peer '{}' is within liveness thresholds again",
+                    node_id.short_str()
+                );
+                self.publish_event(LivenessEvent::MonitoredPeerSlaRestored(node_id.clone()));
+            },
+            None => {},
+        }
+    }
+
     fn publish_event(&mut self, event: LivenessEvent) {
         let _ = self.event_publisher.send(Arc::new(event)).map_err(|_| {
             trace!(
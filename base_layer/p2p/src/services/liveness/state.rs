@@ -21,7 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     convert::TryFrom,
     time::{Duration, Instant},
 };
@@ -29,11 +29,20 @@ use std::{
 use log::*;
 use tari_comms::peer_manager::NodeId;
 
-use super::LOG_TARGET;
+use super::{
+    histogram::{LatencyBucket, LatencyHistogram},
+    LOG_TARGET,
+};
 use crate::proto::liveness::MetadataKey;
 
 const LATENCY_SAMPLE_WINDOW_SIZE: usize = 25;
 const MAX_INFLIGHT_TTL: Duration = Duration::from_secs(40);
+/// The number of round-trip-time samples retained in each peer's histogram
+const LATENCY_HISTOGRAM_SIZE: usize = 100;
+/// The number of most recent ping outcomes used to calculate ping loss
+const PING_OUTCOME_WINDOW_SIZE: usize = 20;
+/// The minimum number of samples required before a peer's SLA is evaluated
+const MIN_SLA_SAMPLES: usize = 5;
 
 /// Represents metadata in a ping/pong message.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -77,6 +86,7 @@ pub struct LivenessState {
     inflight_pings: HashMap<u64, (NodeId, Instant)>,
     peer_latency: HashMap<NodeId, AverageLatency>,
     failed_pings: HashMap<NodeId, usize>,
+    peer_stats: HashMap<NodeId, PeerPingStats>,
 
     pings_received: usize,
     pongs_received: usize,
@@ -151,6 +161,7 @@ impl LivenessState {
         self.inflight_pings = inflight;
 
         for (_, (node_id, _)) in expired {
+            self.peer_stats_mut(node_id.clone()).add_outcome(false);
             self.failed_pings
                 .entry(node_id)
                 .and_modify(|v| {
@@ -173,9 +184,13 @@ impl LivenessState {
 
         let (node_id, _) = self.inflight_pings.get(&nonce)?;
         if node_id == sent_by {
-            self.inflight_pings
-                .remove(&nonce)
-                .map(|(node_id, sent_time)| self.add_latency_sample(node_id, sent_time.elapsed()).calc_average())
+            self.inflight_pings.remove(&nonce).map(|(node_id, sent_time)| {
+                let latency = sent_time.elapsed();
+                let stats = self.peer_stats_mut(node_id.clone());
+                stats.histogram.add_sample(latency);
+                stats.add_outcome(true);
+                self.add_latency_sample(node_id, latency).calc_average()
+            })
         } else {
             warn!(
                 target: LOG_TARGET,
@@ -215,6 +230,45 @@ impl LivenessState {
             .map(|latency| Duration::from_millis(u64::try_from(latency.as_millis()).unwrap() / num_peers as u64))
     }
 
+    fn peer_stats_mut(&mut self, node_id: NodeId) -> &mut PeerPingStats {
+        self.peer_stats.entry(node_id).or_insert_with(PeerPingStats::new)
+    }
+
+    /// Returns the round-trip-time histogram and ping loss for the given peer, if any pings have been sent to it
+    pub fn get_peer_stats(&self, node_id: &NodeId) -> Option<PeerLivenessStats> {
+        self.peer_stats.get(node_id).map(|stats| PeerLivenessStats {
+            num_samples: stats.histogram.len(),
+            p50: stats.histogram.percentile(50),
+            p90: stats.histogram.percentile(90),
+            p99: stats.histogram.percentile(99),
+            buckets: stats.histogram.buckets(),
+            ping_loss_percent: stats.ping_loss_percent(),
+        })
+    }
+
+    /// Evaluates the peer's latency and ping loss against the given thresholds. A transition is returned if the peer
+    /// has started or stopped breaching the thresholds since the last evaluation.
+    pub fn evaluate_sla(
+        &mut self,
+        node_id: &NodeId,
+        max_latency: Option<Duration>,
+        max_ping_loss_percent: Option<u8>,
+    ) -> Option<SlaTransition> {
+        let stats = self.peer_stats.get_mut(node_id)?;
+        let violation = stats.find_violation(max_latency, max_ping_loss_percent);
+        match (violation, stats.sla_breached) {
+            (Some(violation), false) => {
+                stats.sla_breached = true;
+                Some(SlaTransition::Breached(violation))
+            },
+            (None, true) => {
+                stats.sla_breached = false;
+                Some(SlaTransition::Restored)
+            },
+            _ => None,
+        }
+    }
+
     pub fn failed_pings_iter(&self) -> impl Iterator<Item = (&NodeId, &usize)> {
         self.failed_pings.iter()
     }
@@ -224,6 +278,90 @@ impl LivenessState {
     }
 }
 
+/// The reason a peer is in breach of its liveness SLA
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SlaViolation {
+    /// The 90th percentile round-trip time exceeded the threshold
+    Latency { p90: Duration, threshold: Duration },
+    /// The percentage of recent pings that did not receive a pong exceeded the threshold
+    PingLoss { loss_percent: u8, threshold: u8 },
+}
+
+/// A change in a peer's SLA status
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SlaTransition {
+    Breached(SlaViolation),
+    Restored,
+}
+
+/// Round-trip-time and ping loss statistics for a peer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeerLivenessStats {
+    /// The number of round-trip-time samples in the histogram
+    pub num_samples: usize,
+    pub p50: Option<Duration>,
+    pub p90: Option<Duration>,
+    pub p99: Option<Duration>,
+    pub buckets: Vec<LatencyBucket>,
+    /// The percentage of the most recent pings that did not receive a pong
+    pub ping_loss_percent: u8,
+}
+
+#[derive(Debug)]
+struct PeerPingStats {
+    histogram: LatencyHistogram,
+    outcomes: VecDeque<bool>,
+    sla_breached: bool,
+}
+
+impl PeerPingStats {
+    fn new() -> Self {
+        Self {
+            histogram: LatencyHistogram::new(LATENCY_HISTOGRAM_SIZE),
+            outcomes: VecDeque::with_capacity(PING_OUTCOME_WINDOW_SIZE),
+            sla_breached: false,
+        }
+    }
+
+    fn add_outcome(&mut self, received_pong: bool) {
+        if self.outcomes.len() == PING_OUTCOME_WINDOW_SIZE {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back(received_pong);
+    }
+
+    fn ping_loss_percent(&self) -> u8 {
+        if self.outcomes.is_empty() {
+            return 0;
+        }
+        let num_lost = self.outcomes.iter().filter(|received| !**received).count();
+        // num_lost <= outcomes.len() so this is at most 100
+        u8::try_from(num_lost * 100 / self.outcomes.len()).unwrap_or(100)
+    }
+
+    fn find_violation(&self, max_latency: Option<Duration>, max_ping_loss_percent: Option<u8>) -> Option<SlaViolation> {
+        if let Some(threshold) = max_latency {
+            if self.histogram.len() >= MIN_SLA_SAMPLES {
+                if let Some(p90) = self.histogram.percentile(90).filter(|p90| *p90 > threshold) {
+                    return Some(SlaViolation::Latency { p90, threshold });
+                }
+            }
+        }
+
+        if let Some(threshold) = max_ping_loss_percent {
+            let loss_percent = self.ping_loss_percent();
+            if self.outcomes.len() >= MIN_SLA_SAMPLES && loss_percent > threshold {
+                return Some(SlaViolation::PingLoss {
+                    loss_percent,
+                    threshold,
+                });
+            }
+        }
+
+        None
+    }
+}
+
 /// A very simple implementation for calculating average latency. Samples are added in milliseconds and the mean average
 /// is calculated for those samples. If more than [LATENCY_SAMPLE_WINDOW_SIZE](self::LATENCY_SAMPLE_WINDOW_SIZE) samples
 /// are added the oldest sample is discarded.
@@ -363,4 +501,70 @@ mod test {
         assert_eq!(*n, 1);
         assert!(state.failed_pings.get(&peer2).is_none());
     }
+
+    #[test]
+    fn peer_stats() {
+        let mut state = LivenessState::new();
+        let node_id = NodeId::default();
+        assert!(state.get_peer_stats(&node_id).is_none());
+
+        for nonce in 0..4 {
+            state.add_inflight_ping(nonce, node_id.clone());
+        }
+        state.record_pong(0, &node_id).unwrap();
+        state.record_pong(1, &node_id).unwrap();
+        for nonce in [2, 3] {
+            let (_, time) = state.inflight_pings.get_mut(&nonce).unwrap();
+            *time = Instant::now() - (MAX_INFLIGHT_TTL + Duration::from_secs(1));
+        }
+        state.clear_stale_inflight_pings();
+
+        let stats = state.get_peer_stats(&node_id).unwrap();
+        assert_eq!(stats.num_samples, 2);
+        assert_eq!(stats.ping_loss_percent, 50);
+        assert!(stats.p90.unwrap() < Duration::from_millis(50));
+        assert_eq!(stats.buckets.iter().map(|b| b.count).sum::<usize>(), 2);
+    }
+
+    #[test]
+    fn evaluate_sla() {
+        let mut state = LivenessState::new();
+        let node_id = NodeId::default();
+        assert!(state.evaluate_sla(&node_id, None, Some(10)).is_none());
+
+        for _ in 0..MIN_SLA_SAMPLES {
+            state.peer_stats_mut(node_id.clone()).add_outcome(false);
+        }
+        assert_eq!(
+            state.evaluate_sla(&node_id, None, Some(10)),
+            Some(SlaTransition::Breached(SlaViolation::PingLoss {
+                loss_percent: 100,
+                threshold: 10
+            }))
+        );
+        // Only transitions are reported
+        assert!(state.evaluate_sla(&node_id, None, Some(10)).is_none());
+
+        for _ in 0..PING_OUTCOME_WINDOW_SIZE {
+            state.peer_stats_mut(node_id.clone()).add_outcome(true);
+        }
+        assert_eq!(
+            state.evaluate_sla(&node_id, None, Some(10)),
+            Some(SlaTransition::Restored)
+        );
+
+        for _ in 0..MIN_SLA_SAMPLES {
+            state
+                .peer_stats_mut(node_id.clone())
+                .histogram
+                .add_sample(Duration::from_secs(2));
+        }
+        assert_eq!(
+            state.evaluate_sla(&node_id, Some(Duration::from_secs(1)), Some(10)),
+            Some(SlaTransition::Breached(SlaViolation::Latency {
+                p90: Duration::from_secs(2),
+                threshold: Duration::from_secs(1)
+            }))
+        );
+    }
 }
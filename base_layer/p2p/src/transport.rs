@@ -19,7 +19,7 @@
 //  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
-use std::{num::NonZeroU16, sync::Arc};
+use std::{num::NonZeroU16, sync::Arc, time::Duration};
 
 use serde::{Deserialize, Serialize};
 use tari_comms::{
@@ -27,7 +27,7 @@ use tari_comms::{
     socks,
     tor,
     tor::TorIdentity,
-    transports::{predicate::FalsePredicate, SocksConfig},
+    transports::{predicate::FalsePredicate, QuicConfig, SocksConfig},
     utils::multiaddr::multiaddr_to_socketaddr,
 };
 
@@ -42,6 +42,7 @@ pub struct TransportConfig {
     pub tor: TorTransportConfig,
     pub socks: Socks5TransportConfig,
     pub memory: MemoryTransportConfig,
+    pub quic: QuicTransportConfig,
 }
 
 impl TransportConfig {
@@ -61,6 +62,14 @@ impl TransportConfig {
         }
     }
 
+    pub fn new_quic(config: QuicTransportConfig) -> Self {
+        Self {
+            transport_type: TransportType::Quic,
+            quic: config,
+            ..Default::default()
+        }
+    }
+
     pub fn new_tor(config: TorTransportConfig) -> Self {
         Self {
             transport_type: TransportType::Tor,
@@ -99,6 +108,9 @@ pub enum TransportType {
     Tor,
     /// Use a SOCKS5 proxy transport. This transport allows any addresses supported by the proxy.
     Socks5,
+    /// Use QUIC to join the Tari network. This transport can only contact peers that advertise a QUIC address in the
+    /// form '/ip4/x.x.x.x/udp/x/quic'.
+    Quic,
 }
 
 impl Default for TransportType {
@@ -118,6 +130,9 @@ pub struct TcpTransportConfig {
     pub tor_socks_address: Option<Multiaddr>,
     /// Optional tor SOCKS proxy authentication
     pub tor_socks_auth: SocksAuthentication,
+    /// Forward the listener port on the local gateway using UPnP or NAT-PMP, and use the mapped external address as
+    /// the node's public address
+    pub enable_port_mapping: bool,
 }
 
 impl Default for TcpTransportConfig {
@@ -126,6 +141,7 @@ impl Default for TcpTransportConfig {
             listener_address: "/ip4/0.0.0.0/tcp/18189".parse().unwrap(),
             tor_socks_address: None,
             tor_socks_auth: SocksAuthentication::None,
+            enable_port_mapping: false,
         }
     }
 }
@@ -223,6 +239,43 @@ impl Default for Socks5TransportConfig {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuicTransportConfig {
+    /// Socket to bind the QUIC listener
+    pub listener_address: Multiaddr,
+    /// Send data on resumed sessions before the QUIC handshake completes. This only ever carries the first noise
+    /// handshake message.
+    pub enable_0rtt: bool,
+    /// The interval at which keep alive packets are sent, keeping NAT mappings open
+    pub keep_alive_interval: Duration,
+    /// Close a connection if no packets are received for this length of time
+    pub max_idle_timeout: Duration,
+}
+
+impl From<QuicTransportConfig> for QuicConfig {
+    fn from(config: QuicTransportConfig) -> Self {
+        Self {
+            enable_0rtt: config.enable_0rtt,
+            keep_alive_interval: config.keep_alive_interval,
+            max_idle_timeout: config.max_idle_timeout,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for QuicTransportConfig {
+    fn default() -> Self {
+        let quic = QuicConfig::default();
+        Self {
+            listener_address: "/ip4/0.0.0.0/udp/18189/quic".parse().unwrap(),
+            enable_0rtt: quic.enable_0rtt,
+            keep_alive_interval: quic.keep_alive_interval,
+            max_idle_timeout: quic.max_idle_timeout,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct MemoryTransportConfig {
@@ -31,7 +31,10 @@ use futures::{future, future::Either, Future, FutureExt};
 use tari_shutdown::{Shutdown, ShutdownSignal};
 use tokio::task;
 
-use crate::context::LazyService;
+use crate::{
+    context::LazyService,
+    control::{self, ServiceControlHandle},
+};
 
 /// Create a Notifier, ServiceInitializerContext pair.
 ///
@@ -61,10 +64,9 @@ impl ServiceInitializerContext {
     /// `ready_signal` - indicates that all services are ready. This should be triggered by the `StackBuilder` once all
     ///                  initializers have run.
     pub(crate) fn new(shutdown_signal: ShutdownSignal, ready_signal: ShutdownSignal) -> Self {
-        Self {
-            inner: ServiceHandles::new(shutdown_signal),
-            ready_signal,
-        }
+        let inner = ServiceHandles::new(shutdown_signal);
+        inner.register(ServiceControlHandle::default());
+        Self { inner, ready_signal }
     }
 
     /// Insert a service handle with the given name
@@ -110,6 +112,30 @@ impl ServiceInitializerContext {
         })
     }
 
+    /// Spawn a service that can be stopped, started and restarted at runtime using the `ServiceControlHandle`.
+    ///
+    /// `state` holds the resources that must survive a restart, such as channel receivers. Each time the service is
+    /// started, the closure is called with the resolved handles, the state and a signal that is triggered when the
+    /// service should stop. The returned future must resolve to the state once that signal has been triggered.
+    pub fn spawn_restartable<S, F, Fut>(self, name: &str, state: S, f: F) -> task::JoinHandle<()>
+    where
+        S: Send + 'static,
+        F: FnMut(ServiceHandles, S, ShutdownSignal) -> Fut + Send + 'static,
+        Fut: Future<Output = S> + Send + 'static,
+    {
+        let control = self.inner.expect_handle::<ServiceControlHandle>();
+        let commands = control.register(name);
+        task::spawn(control::supervise(
+            name.to_string(),
+            control,
+            commands,
+            self.ready_signal,
+            self.inner,
+            state,
+            f,
+        ))
+    }
+
     /// Wait until the service handle are ready and return them when they are.
     pub async fn wait_ready(self) -> ServiceHandles {
         self.ready_signal.await;
@@ -0,0 +1,305 @@
+// Copyright 2022 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Runtime control of individual services. Services that are spawned using
+//! [ServiceInitializerContext::spawn_restartable](crate::ServiceInitializerContext::spawn_restartable) can be stopped,
+//! started and restarted through the [ServiceControlHandle] without restarting the rest of the stack.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use futures::{future::FutureExt, Future};
+use log::*;
+use tari_shutdown::{Shutdown, ShutdownSignal};
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::ServiceHandles;
+
+const LOG_TARGET: &str = "service_framework::control";
+
+#[derive(Debug, Error)]
+pub enum ServiceControlError {
+    #[error("Service `{0}` is not registered for runtime control")]
+    ServiceNotFound(String),
+    #[error("Service `{0}` has shut down")]
+    ServiceShutdown(String),
+}
+
+/// The runtime status of a controllable service
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceStatus {
+    /// The service has been registered but the stack is not ready yet
+    Pending,
+    Running,
+    Stopped,
+}
+
+impl fmt::Display for ServiceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceStatus::Pending => write!(f, "Pending"),
+            ServiceStatus::Running => write!(f, "Running"),
+            ServiceStatus::Stopped => write!(f, "Stopped"),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum ServiceCommand {
+    Start(oneshot::Sender<()>),
+    Stop(oneshot::Sender<()>),
+    Restart(oneshot::Sender<()>),
+}
+
+/// Receives the commands for a single controllable service
+pub(crate) struct ServiceCommandReceiver(mpsc::UnboundedReceiver<ServiceCommand>);
+
+struct ControlledService {
+    status: ServiceStatus,
+    sender: mpsc::UnboundedSender<ServiceCommand>,
+}
+
+/// Handle used to stop, start and restart individual services at runtime. The handle is registered with every service
+/// stack and is cheap to clone.
+#[derive(Clone, Default)]
+pub struct ServiceControlHandle {
+    services: Arc<Mutex<BTreeMap<String, ControlledService>>>,
+}
+
+impl ServiceControlHandle {
+    /// Returns the name and status of each controllable service, ordered by name
+    pub fn services(&self) -> Vec<(String, ServiceStatus)> {
+        self.lock()
+            .iter()
+            .map(|(name, service)| (name.clone(), service.status))
+            .collect()
+    }
+
+    /// Returns the status of the named service
+    pub fn status(&self, name: &str) -> Option<ServiceStatus> {
+        self.lock().get(name).map(|service| service.status)
+    }
+
+    /// Stops the named service. Resolves once the service has stopped.
+    pub async fn stop(&self, name: &str) -> Result<(), ServiceControlError> {
+        self.send(name, ServiceCommand::Stop).await
+    }
+
+    /// Starts the named service if it is stopped. Resolves once the service has started.
+    pub async fn start(&self, name: &str) -> Result<(), ServiceControlError> {
+        self.send(name, ServiceCommand::Start).await
+    }
+
+    /// Stops the named service, if it is running, and starts it again. Resolves once the service has started.
+    pub async fn restart(&self, name: &str) -> Result<(), ServiceControlError> {
+        self.send(name, ServiceCommand::Restart).await
+    }
+
+    async fn send<F>(&self, name: &str, command: F) -> Result<(), ServiceControlError>
+    where F: FnOnce(oneshot::Sender<()>) -> ServiceCommand {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.lock()
+            .get(name)
+            .ok_or_else(|| ServiceControlError::ServiceNotFound(name.to_string()))?
+            .sender
+            .send(command(reply_tx))
+            .map_err(|_| ServiceControlError::ServiceShutdown(name.to_string()))?;
+        reply_rx
+            .await
+            .map_err(|_| ServiceControlError::ServiceShutdown(name.to_string()))
+    }
+
+    pub(crate) fn register(&self, name: &str) -> ServiceCommandReceiver {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let existing = self.lock().insert(name.to_string(), ControlledService {
+            status: ServiceStatus::Pending,
+            sender,
+        });
+        if existing.is_some() {
+            warn!(
+                target: LOG_TARGET,
+                "Service `{}` was registered for runtime control more than once. Only the last registration can be \
+                 controlled.",
+                name
+            );
+        }
+        ServiceCommandReceiver(receiver)
+    }
+
+    fn set_status(&self, name: &str, status: ServiceStatus) {
+        if let Some(service) = self.lock().get_mut(name) {
+            service.status = status;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, ControlledService>> {
+        match self.services.lock() {
+            Ok(lock) => lock,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Runs a restartable service until the stack shuts down. See `ServiceInitializerContext::spawn_restartable`.
+pub(crate) async fn supervise<S, F, Fut>(
+    name: String,
+    control: ServiceControlHandle,
+    commands: ServiceCommandReceiver,
+    ready_signal: ShutdownSignal,
+    handles: ServiceHandles,
+    state: S,
+    mut service_fn: F,
+) where
+    F: FnMut(ServiceHandles, S, ShutdownSignal) -> Fut,
+    Fut: Future<Output = S>,
+{
+    let ServiceCommandReceiver(mut commands) = commands;
+    let mut shutdown_signal = handles.get_shutdown_signal();
+    ready_signal.await;
+
+    let mut state = Some(state);
+    let mut should_run = true;
+    // Replies to the command that caused the service to start, sent once it has started
+    let mut start_reply = None;
+    while !shutdown_signal.is_triggered() {
+        if !should_run {
+            control.set_status(&name, ServiceStatus::Stopped);
+            tokio::select! {
+                Some(command) = commands.recv() => match command {
+                    ServiceCommand::Start(reply) | ServiceCommand::Restart(reply) => {
+                        should_run = true;
+                        start_reply = Some(reply);
+                    },
+                    ServiceCommand::Stop(reply) => {
+                        let _ = reply.send(());
+                    },
+                },
+                _ = shutdown_signal.wait() => break,
+                else => break,
+            }
+            continue;
+        }
+
+        let mut stop = Shutdown::new();
+        let run_state = match state.take() {
+            Some(s) => s,
+            None => break,
+        };
+        debug!(target: LOG_TARGET, "Starting service `{}`", name);
+        let service = service_fn(handles.clone(), run_state, stop.to_signal()).fuse();
+        futures::pin_mut!(service);
+        control.set_status(&name, ServiceStatus::Running);
+        if let Some(reply) = start_reply.take() {
+            let _ = reply.send(());
+        }
+
+        let mut stop_replies = Vec::new();
+        let mut restart = false;
+        let mut is_stopping = false;
+        state = loop {
+            tokio::select! {
+                s = &mut service => break Some(s),
+                Some(command) = commands.recv(), if !is_stopping => match command {
+                    ServiceCommand::Start(reply) => {
+                        let _ = reply.send(());
+                    },
+                    ServiceCommand::Stop(reply) => {
+                        stop_replies.push(reply);
+                        is_stopping = true;
+                        stop.trigger();
+                    },
+                    ServiceCommand::Restart(reply) => {
+                        start_reply = Some(reply);
+                        restart = true;
+                        is_stopping = true;
+                        stop.trigger();
+                    },
+                },
+                _ = shutdown_signal.wait(), if !is_stopping => {
+                    is_stopping = true;
+                    stop.trigger();
+                },
+            }
+        };
+        debug!(target: LOG_TARGET, "Service `{}` has stopped", name);
+        for reply in stop_replies {
+            let _ = reply.send(());
+        }
+        // A service that exits without being asked to stop remains stopped until it is started again
+        should_run = restart;
+    }
+
+    control.set_status(&name, ServiceStatus::Stopped);
+    debug!(target: LOG_TARGET, "Service `{}` has shut down", name);
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn setup() -> (Shutdown, Shutdown, ServiceHandles, ServiceControlHandle) {
+        let shutdown = Shutdown::new();
+        let ready = Shutdown::new();
+        let handles = ServiceHandles::new(shutdown.to_signal());
+        (shutdown, ready, handles, ServiceControlHandle::default())
+    }
+
+    #[tokio::test]
+    async fn it_stops_and_restarts_a_service_with_its_state() {
+        let (mut shutdown, mut ready, handles, control) = setup();
+        let num_starts = Arc::new(AtomicUsize::new(0));
+        let task = tokio::spawn(supervise(
+            "test".to_string(),
+            control.clone(),
+            control.register("test"),
+            ready.to_signal(),
+            handles,
+            0usize,
+            {
+                let num_starts = num_starts.clone();
+                move |_, state: usize, stop: ShutdownSignal| {
+                    num_starts.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        stop.await;
+                        state + 1
+                    }
+                }
+            },
+        ));
+        assert_eq!(control.status("test"), Some(ServiceStatus::Pending));
+        ready.trigger();
+
+        control.restart("test").await.unwrap();
+        assert_eq!(control.status("test"), Some(ServiceStatus::Running));
+        control.stop("test").await.unwrap();
+        assert_eq!(control.status("test"), Some(ServiceStatus::Stopped));
+        assert_eq!(num_starts.load(Ordering::SeqCst), 2);
+        control.start("test").await.unwrap();
+        assert_eq!(control.status("test"), Some(ServiceStatus::Running));
+        assert_eq!(num_starts.load(Ordering::SeqCst), 3);
+
+        shutdown.trigger();
+        task.await.unwrap();
+        assert_eq!(control.status("test"), Some(ServiceStatus::Stopped));
+        assert!(matches!(
+            control.start("test").await,
+            Err(ServiceControlError::ServiceShutdown(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_errors_for_unknown_services() {
+        let control = ServiceControlHandle::default();
+        assert!(matches!(
+            control.stop("unknown").await,
+            Err(ServiceControlError::ServiceNotFound(_))
+        ));
+        assert!(control.services().is_empty());
+    }
+}
@@ -75,6 +75,7 @@ pub use initializer::{ServiceInitializationError, ServiceInitializer};
 mod stack;
 pub use stack::StackBuilder;
 
+pub mod control;
 pub mod reply_channel;
 pub mod tower;
 
@@ -0,0 +1 @@
+DROP TABLE send_intents;
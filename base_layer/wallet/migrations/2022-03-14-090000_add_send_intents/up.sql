@@ -0,0 +1,7 @@
+CREATE TABLE send_intents (
+    tx_id                  BIGINT PRIMARY KEY NOT NULL,
+    destination_public_key BLOB               NOT NULL,
+    amount                 BIGINT             NOT NULL,
+    fee_per_gram           BIGINT             NOT NULL,
+    timestamp              DATETIME           NOT NULL
+);
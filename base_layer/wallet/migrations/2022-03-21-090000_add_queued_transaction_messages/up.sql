@@ -0,0 +1,10 @@
+CREATE TABLE queued_transaction_messages (
+    tx_id                  BIGINT   NOT NULL,
+    message_type           INTEGER  NOT NULL,
+    destination_public_key BLOB     NOT NULL,
+    message                BLOB     NOT NULL,
+    attempts               INTEGER  NOT NULL DEFAULT 0,
+    next_attempt           DATETIME NOT NULL,
+    expires_at             DATETIME NOT NULL,
+    PRIMARY KEY (tx_id, message_type)
+);
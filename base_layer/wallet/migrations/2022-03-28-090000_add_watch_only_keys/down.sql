@@ -0,0 +1,2 @@
+DROP TABLE watch_only_outputs;
+DROP TABLE watch_only_keys;
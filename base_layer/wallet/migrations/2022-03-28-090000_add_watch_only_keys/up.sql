@@ -0,0 +1,17 @@
+CREATE TABLE watch_only_keys (
+    spend_public_key           BLOB PRIMARY KEY NOT NULL,
+    rewind_public_key          BLOB             NOT NULL,
+    rewind_blinding_public_key BLOB             NOT NULL,
+    label                      TEXT             NOT NULL
+);
+
+-- Outputs of watched wallets are kept out of the `outputs` table so that they can never be selected for spending
+CREATE TABLE watch_only_outputs (
+    commitment       BLOB PRIMARY KEY NOT NULL,
+    spend_public_key BLOB             NOT NULL,
+    value            BIGINT           NULL,
+    mined_height     BIGINT           NOT NULL,
+    mined_in_block   BLOB             NOT NULL
+);
+
+CREATE INDEX watch_only_outputs_spend_public_key_index ON watch_only_outputs (spend_public_key);
@@ -0,0 +1,4 @@
+ALTER TABLE contacts
+    ADD COLUMN last_transaction_id BIGINT NULL;
+ALTER TABLE contacts
+    ADD COLUMN last_transaction_at DATETIME NULL;
@@ -0,0 +1,16 @@
+CREATE TABLE scheduled_payments (
+    id                     BIGINT PRIMARY KEY NOT NULL,
+    destination_public_key BLOB               NOT NULL,
+    amount                 BIGINT             NOT NULL,
+    fee_per_gram           BIGINT             NOT NULL,
+    message                TEXT               NOT NULL,
+    payment_type           INTEGER            NOT NULL,
+    next_payment_at        DATETIME           NOT NULL,
+    interval_seconds       BIGINT             NULL,
+    remaining_payments     INTEGER            NULL,
+    num_payments_made      INTEGER            NOT NULL DEFAULT 0,
+    status                 INTEGER            NOT NULL,
+    last_tx_id             BIGINT             NULL,
+    last_error             TEXT               NULL,
+    created_at             DATETIME           NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP TABLE multisig_sessions;
+DROP TABLE multisig_groups;
@@ -0,0 +1,30 @@
+CREATE TABLE multisig_groups (
+    group_id          BIGINT PRIMARY KEY NOT NULL,
+    threshold         INTEGER            NOT NULL,
+    participants      BLOB               NOT NULL,
+    share_index       INTEGER            NOT NULL,
+    share_commitments BLOB               NOT NULL,
+    output            TEXT               NOT NULL,
+    value             BIGINT             NOT NULL,
+    secrets           BLOB               NOT NULL,
+    status            INTEGER            NOT NULL,
+    created_at        DATETIME           NOT NULL
+);
+
+CREATE TABLE multisig_sessions (
+    session_id             BIGINT PRIMARY KEY NOT NULL,
+    group_id               BIGINT             NOT NULL,
+    role                   INTEGER            NOT NULL,
+    proposer_public_key    BLOB               NOT NULL,
+    destination_public_key BLOB               NOT NULL,
+    amount                 BIGINT             NOT NULL,
+    fee                    BIGINT             NOT NULL,
+    lock_height            BIGINT             NOT NULL,
+    message                TEXT               NOT NULL,
+    state                  INTEGER            NOT NULL,
+    private_nonces         BLOB               NULL,
+    negotiation            TEXT               NOT NULL,
+    tx_id                  BIGINT             NULL,
+    failure_reason         TEXT               NULL,
+    created_at             DATETIME           NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE send_intents
+    ADD COLUMN one_sided INTEGER NOT NULL DEFAULT 0;
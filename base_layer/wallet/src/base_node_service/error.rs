@@ -35,6 +35,8 @@ pub enum BaseNodeServiceError {
     BaseNodeConnectivityError(#[from] ConnectivityError),
     #[error("RPC Error: `{0}`")]
     RpcError(#[from] RpcError),
+    #[error("Timed out waiting for a connection to the base node")]
+    BaseNodeConnectionTimeout,
     #[error("No chain metadata from peer")]
     NoChainMetadata,
     #[error("Unexpected API Response")]
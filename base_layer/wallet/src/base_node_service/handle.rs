@@ -23,6 +23,7 @@
 use std::{fmt, fmt::Formatter, sync::Arc, time::Duration};
 
 use tari_common_types::chain_metadata::ChainMetadata;
+use tari_core::base_node::proto::wallet_rpc::FeeEstimates;
 use tari_service_framework::reply_channel::SenderService;
 use tokio::sync::broadcast;
 use tower::Service;
@@ -36,12 +37,14 @@ pub type BaseNodeEventReceiver = broadcast::Receiver<Arc<BaseNodeEvent>>;
 pub enum BaseNodeServiceRequest {
     GetChainMetadata,
     GetBaseNodeLatency,
+    GetFeeEstimates,
 }
 /// API Response enum
 #[derive(Debug)]
 pub enum BaseNodeServiceResponse {
     ChainMetadata(Option<ChainMetadata>),
     Latency(Option<Duration>),
+    FeeEstimates(FeeEstimates),
 }
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub enum BaseNodeEvent {
@@ -98,4 +101,13 @@ impl BaseNodeServiceHandle {
             _ => Err(BaseNodeServiceError::UnexpectedApiResponse),
         }
     }
+
+    /// Requests suggested fees per gram for a transaction to be mined within the next block, ~6 blocks and ~24 blocks
+    /// from the connected base node
+    pub async fn get_fee_estimates(&mut self) -> Result<FeeEstimates, BaseNodeServiceError> {
+        match self.handle.call(BaseNodeServiceRequest::GetFeeEstimates).await?? {
+            BaseNodeServiceResponse::FeeEstimates(estimates) => Ok(estimates),
+            _ => Err(BaseNodeServiceError::UnexpectedApiResponse),
+        }
+    }
 }
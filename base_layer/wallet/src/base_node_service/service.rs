@@ -28,7 +28,7 @@ use log::*;
 use tari_common_types::chain_metadata::ChainMetadata;
 use tari_service_framework::reply_channel::Receiver;
 use tari_shutdown::ShutdownSignal;
-use tokio::sync::RwLock;
+use tokio::{sync::RwLock, time};
 
 use super::{
     config::BaseNodeServiceConfig,
@@ -42,6 +42,7 @@ use crate::{
 };
 
 const LOG_TARGET: &str = "wallet::base_node_service::service";
+const BASE_NODE_RPC_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// State determined from Base Node Service Requests
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
@@ -161,6 +162,18 @@ where T: WalletBackend + 'static
             BaseNodeServiceRequest::GetBaseNodeLatency => {
                 Ok(BaseNodeServiceResponse::Latency(self.state.read().await.latency))
             },
+            BaseNodeServiceRequest::GetFeeEstimates => {
+                // Do not hold up other requests while the base node is not connected
+                let mut client = time::timeout(
+                    BASE_NODE_RPC_CONNECT_TIMEOUT,
+                    self.wallet_connectivity.obtain_base_node_wallet_rpc_client(),
+                )
+                .await
+                .map_err(|_| BaseNodeServiceError::BaseNodeConnectionTimeout)?
+                .ok_or(BaseNodeServiceError::NoBaseNodePeer)?;
+                let estimates = client.get_fee_estimates().await?;
+                Ok(BaseNodeServiceResponse::FeeEstimates(estimates.into()))
+            },
         }
     }
 }
@@ -28,7 +28,7 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 use tari_common::{
-    configuration::{serializers, Network, StringList},
+    configuration::{serializers, GrpcSecurityConfig, Network, StringList},
     SubConfigPath,
 };
 use tari_comms::multiaddr::Multiaddr;
@@ -36,6 +36,7 @@ use tari_p2p::{auto_update::AutoUpdateConfig, P2pConfig};
 
 use crate::{
     base_node_service::config::BaseNodeServiceConfig,
+    notifications::NotificationConfig,
     output_manager_service::config::OutputManagerServiceConfig,
     transaction_service::config::TransactionServiceConfig,
 };
@@ -66,6 +67,8 @@ pub struct WalletConfig {
     pub command_send_wait_stage: String,
     pub notify_file: Option<PathBuf>,
     pub grpc_address: Option<Multiaddr>,
+    /// TLS and API token authentication for the gRPC server
+    pub grpc_security: GrpcSecurityConfig,
     pub custom_base_node: Option<String>,
     pub base_node_service_peers: StringList,
     pub recovery_retry_limit: usize,
@@ -73,6 +76,7 @@ pub struct WalletConfig {
     pub num_required_confirmations: u64,
     pub use_libtor: bool,
     pub identity_file: Option<PathBuf>,
+    pub notifications: NotificationConfig,
 }
 
 impl Default for WalletConfig {
@@ -97,6 +101,7 @@ impl Default for WalletConfig {
             command_send_wait_timeout: Duration::from_secs(300),
             notify_file: None,
             grpc_address: None,
+            grpc_security: GrpcSecurityConfig::default(),
             custom_base_node: None,
             base_node_service_peers: StringList::default(),
             recovery_retry_limit: 3,
@@ -104,6 +109,7 @@ impl Default for WalletConfig {
             num_required_confirmations: 3,
             use_libtor: false,
             identity_file: None,
+            notifications: Default::default(),
         }
     }
 }
@@ -122,6 +128,7 @@ impl WalletConfig {
         if !self.data_dir.is_absolute() {
             self.data_dir = base_path.as_ref().join(self.data_dir.as_path());
         }
+        self.grpc_security.set_base_path(base_path.as_ref());
         self.p2p.set_base_path(self.data_dir.as_path());
     }
 }
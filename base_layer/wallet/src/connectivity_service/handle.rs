@@ -34,6 +34,7 @@ use crate::{connectivity_service::WalletConnectivityInterface, util::watch::Watc
 pub enum WalletConnectivityRequest {
     ObtainBaseNodeWalletRpcClient(oneshot::Sender<RpcClientLease<BaseNodeWalletRpcClient>>),
     ObtainBaseNodeSyncRpcClient(oneshot::Sender<RpcClientLease<BaseNodeSyncRpcClient>>),
+    ObtainIndependentBaseNodeWalletRpcClients(usize, oneshot::Sender<Vec<RpcClientLease<BaseNodeWalletRpcClient>>>),
 }
 
 #[derive(Clone)]
@@ -111,6 +112,24 @@ impl WalletConnectivityInterface for WalletConnectivityHandle {
         reply_rx.await.ok()
     }
 
+    async fn obtain_independent_base_node_wallet_rpc_clients(
+        &mut self,
+        n: usize,
+    ) -> Vec<RpcClientLease<BaseNodeWalletRpcClient>> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let sent = self
+            .sender
+            .send(WalletConnectivityRequest::ObtainIndependentBaseNodeWalletRpcClients(
+                n, reply_tx,
+            ))
+            .await;
+        if sent.is_err() {
+            return Vec::new();
+        }
+
+        reply_rx.await.unwrap_or_default()
+    }
+
     fn get_connectivity_status(&mut self) -> OnlineStatus {
         *self.online_status_rx.borrow()
     }
@@ -52,6 +52,15 @@ pub trait WalletConnectivityInterface: Clone + Send + Sync + 'static {
     /// BaseNodeSyncRpcClient RPC session.
     async fn obtain_base_node_sync_rpc_client(&mut self) -> Option<RpcClientLease<BaseNodeSyncRpcClient>>;
 
+    /// Obtain BaseNodeWalletRpcClients for up to `n` connected base nodes other than the currently selected base node.
+    ///
+    /// These are used to cross-check information received from the selected base node. Fewer than `n` clients are
+    /// returned if there are not enough connected base nodes.
+    async fn obtain_independent_base_node_wallet_rpc_clients(
+        &mut self,
+        n: usize,
+    ) -> Vec<RpcClientLease<BaseNodeWalletRpcClient>>;
+
     fn get_connectivity_status(&mut self) -> OnlineStatus;
 
     fn get_connectivity_status_watch(&self) -> watch::Receiver<OnlineStatus>;
@@ -43,6 +43,7 @@ pub struct WalletConnectivityMock {
     base_node_watch: Watch<Option<Peer>>,
     base_node_wallet_rpc_client: Watch<Option<RpcClientLease<BaseNodeWalletRpcClient>>>,
     base_node_sync_rpc_client: Watch<Option<RpcClientLease<BaseNodeSyncRpcClient>>>,
+    independent_base_node_wallet_rpc_clients: Watch<Vec<RpcClientLease<BaseNodeWalletRpcClient>>>,
 }
 
 impl WalletConnectivityMock {
@@ -52,6 +53,7 @@ impl WalletConnectivityMock {
             base_node_watch: Watch::new(None),
             base_node_wallet_rpc_client: Watch::new(None),
             base_node_sync_rpc_client: Watch::new(None),
+            independent_base_node_wallet_rpc_clients: Watch::new(Vec::new()),
         }
     }
 }
@@ -65,6 +67,15 @@ impl WalletConnectivityMock {
         self.base_node_sync_rpc_client.send(Some(RpcClientLease::new(client)));
     }
 
+    pub fn set_independent_base_node_wallet_rpc_clients(&self, clients: Vec<BaseNodeWalletRpcClient>) {
+        self.independent_base_node_wallet_rpc_clients
+            .send(clients.into_iter().map(RpcClientLease::new).collect());
+    }
+
+    pub fn set_connectivity_status(&self, status: OnlineStatus) {
+        self.online_status_watch.send(status);
+    }
+
     pub fn notify_base_node_set(&self, base_node_peer: Peer) {
         self.base_node_watch.send(Some(base_node_peer));
     }
@@ -107,6 +118,18 @@ impl WalletConnectivityInterface for WalletConnectivityMock {
         borrow.as_ref().cloned()
     }
 
+    async fn obtain_independent_base_node_wallet_rpc_clients(
+        &mut self,
+        n: usize,
+    ) -> Vec<RpcClientLease<BaseNodeWalletRpcClient>> {
+        self.independent_base_node_wallet_rpc_clients
+            .borrow()
+            .iter()
+            .take(n)
+            .cloned()
+            .collect()
+    }
+
     fn get_connectivity_status(&mut self) -> OnlineStatus {
         *self.online_status_watch.borrow()
     }
@@ -24,7 +24,7 @@ use std::{mem, time::Duration};
 
 use log::*;
 use tari_comms::{
-    connectivity::{ConnectivityError, ConnectivityRequester},
+    connectivity::{ConnectivityError, ConnectivityRequester, ConnectivitySelection},
     peer_manager::{NodeId, Peer},
     protocol::rpc::{RpcClientLease, RpcClientPool},
     PeerConnection,
@@ -32,6 +32,7 @@ use tari_comms::{
 use tari_core::base_node::{rpc::BaseNodeWalletRpcClient, sync::rpc::BaseNodeSyncRpcClient};
 use tokio::{
     sync::{mpsc, oneshot, watch},
+    task,
     time,
     time::MissedTickBehavior,
 };
@@ -133,7 +134,11 @@ impl WalletConnectivityService {
     }
 
     async fn handle_request(&mut self, request: WalletConnectivityRequest) {
-        use WalletConnectivityRequest::{ObtainBaseNodeSyncRpcClient, ObtainBaseNodeWalletRpcClient};
+        use WalletConnectivityRequest::{
+            ObtainBaseNodeSyncRpcClient,
+            ObtainBaseNodeWalletRpcClient,
+            ObtainIndependentBaseNodeWalletRpcClients,
+        };
         match request {
             ObtainBaseNodeWalletRpcClient(reply) => {
                 self.handle_pool_request(reply.into()).await;
@@ -141,9 +146,51 @@ impl WalletConnectivityService {
             ObtainBaseNodeSyncRpcClient(reply) => {
                 self.handle_pool_request(reply.into()).await;
             },
+            ObtainIndependentBaseNodeWalletRpcClients(n, reply) => {
+                self.handle_get_independent_base_node_wallet_rpc_clients(n, reply).await;
+            },
         }
     }
 
+    async fn handle_get_independent_base_node_wallet_rpc_clients(
+        &mut self,
+        n: usize,
+        reply: oneshot::Sender<Vec<RpcClientLease<BaseNodeWalletRpcClient>>>,
+    ) {
+        let exclude = self.current_base_node().into_iter().collect();
+        let connections = match self
+            .connectivity
+            .select_connections(ConnectivitySelection::random_nodes(n, exclude))
+            .await
+        {
+            Ok(connections) => connections,
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Failed to select independent base nodes: {}", e);
+                let _result = reply.send(Vec::new());
+                return;
+            },
+        };
+
+        // Establish the RPC sessions in a separate task so that other requests are not held up
+        task::spawn(async move {
+            let mut clients = Vec::with_capacity(connections.len());
+            for mut conn in connections {
+                match conn.connect_rpc::<BaseNodeWalletRpcClient>().await {
+                    Ok(client) => clients.push(RpcClientLease::new(client)),
+                    Err(e) => {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Failed to establish RPC session with independent base node {}: {}",
+                            conn.peer_node_id(),
+                            e
+                        );
+                    },
+                }
+            }
+            let _result = reply.send(clients);
+        });
+    }
+
     async fn handle_pool_request(&mut self, reply: ReplyOneshot) {
         use ReplyOneshot::{SyncRpc, WalletRpc};
         match reply {
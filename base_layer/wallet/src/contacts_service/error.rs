@@ -25,7 +25,11 @@ use tari_p2p::services::liveness::error::LivenessError;
 use tari_service_framework::reply_channel::TransportChannelError;
 use thiserror::Error;
 
-use crate::{contacts_service::storage::database::DbKey, error::WalletStorageError};
+use crate::{
+    contacts_service::storage::database::DbKey,
+    error::WalletStorageError,
+    transaction_service::error::TransactionServiceError,
+};
 
 #[derive(Debug, Error)]
 #[allow(clippy::large_enum_variant)]
@@ -34,12 +38,18 @@ pub enum ContactsServiceError {
     ContactNotFound,
     #[error("Received incorrect response from service request")]
     UnexpectedApiResponse,
+    #[error("Invalid contact alias: `{0}`")]
+    InvalidAlias(String),
+    #[error("The alias `{0}` is already used by another contact")]
+    DuplicateAlias(String),
     #[error("Contacts service storage error: `{0}`")]
     ContactsServiceStorageError(#[from] ContactsServiceStorageError),
     #[error("Transport channel error: `{0}`")]
     TransportChannelError(#[from] TransportChannelError),
     #[error("Livenessl error: `{0}`")]
     LivenessError(#[from] LivenessError),
+    #[error("Transaction service error: `{0}`")]
+    TransactionServiceError(#[from] TransactionServiceError),
 }
 
 #[derive(Debug, Error)]
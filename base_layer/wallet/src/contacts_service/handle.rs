@@ -125,6 +125,7 @@ pub enum ContactsLivenessEvent {
 #[derive(Debug)]
 pub enum ContactsServiceRequest {
     GetContact(CommsPublicKey),
+    GetContactByAlias(String),
     UpsertContact(Contact),
     RemoveContact(CommsPublicKey),
     GetContacts,
@@ -172,6 +173,17 @@ impl ContactsServiceHandle {
         }
     }
 
+    pub async fn get_contact_by_alias(&mut self, alias: String) -> Result<Contact, ContactsServiceError> {
+        match self
+            .request_response_service
+            .call(ContactsServiceRequest::GetContactByAlias(alias))
+            .await??
+        {
+            ContactsServiceResponse::Contact(c) => Ok(c),
+            _ => Err(ContactsServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_contacts(&mut self) -> Result<Vec<Contact>, ContactsServiceError> {
         match self
             .request_response_service
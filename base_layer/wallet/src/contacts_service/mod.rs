@@ -40,10 +40,13 @@ use tari_service_framework::{
 };
 use tokio::sync::broadcast;
 
-use crate::contacts_service::{
-    handle::ContactsServiceHandle,
-    service::ContactsService,
-    storage::database::{ContactsBackend, ContactsDatabase},
+use crate::{
+    contacts_service::{
+        handle::ContactsServiceHandle,
+        service::ContactsService,
+        storage::database::{ContactsBackend, ContactsDatabase},
+    },
+    transaction_service::handle::TransactionServiceHandle,
 };
 
 const LOG_TARGET: &str = "wallet::contacts_service::initializer";
@@ -93,6 +96,7 @@ where T: ContactsBackend + 'static
         context.spawn_when_ready(move |handles| async move {
             let liveness = handles.expect_handle::<LivenessHandle>();
             let connectivity = handles.expect_handle::<ConnectivityRequester>();
+            let transaction_service = handles.get_handle::<TransactionServiceHandle>();
 
             let service = ContactsService::new(
                 ContactsDatabase::new(backend),
@@ -100,6 +104,7 @@ where T: ContactsBackend + 'static
                 handles.get_shutdown_signal(),
                 liveness,
                 connectivity,
+                transaction_service,
                 publisher,
                 contacts_auto_ping_interval,
                 contacts_online_ping_window,
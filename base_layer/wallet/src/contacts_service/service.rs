@@ -30,20 +30,28 @@ use std::{
 use chrono::{NaiveDateTime, Utc};
 use futures::{pin_mut, StreamExt};
 use log::*;
+use tari_common_types::transaction::TransactionDirection;
 use tari_comms::connectivity::{ConnectivityEvent, ConnectivityRequester};
 use tari_p2p::services::liveness::{LivenessEvent, LivenessHandle, MetadataKey, PingPongEvent};
 use tari_service_framework::reply_channel;
 use tari_shutdown::ShutdownSignal;
 use tokio::sync::broadcast;
 
-use crate::contacts_service::{
-    error::ContactsServiceError,
-    handle::{ContactsLivenessData, ContactsLivenessEvent, ContactsServiceRequest, ContactsServiceResponse},
-    storage::database::{Contact, ContactsBackend, ContactsDatabase},
+use crate::{
+    contacts_service::{
+        error::{ContactsServiceError, ContactsServiceStorageError},
+        handle::{ContactsLivenessData, ContactsLivenessEvent, ContactsServiceRequest, ContactsServiceResponse},
+        storage::database::{Contact, ContactsBackend, ContactsDatabase},
+    },
+    transaction_service::{
+        handle::{TransactionEvent, TransactionServiceHandle},
+        storage::models::WalletTransaction,
+    },
 };
 
 const LOG_TARGET: &str = "wallet:contacts_service";
 const NUM_ROUNDS_NETWORK_SILENCE: u16 = 3;
+const MAX_ALIAS_LENGTH: usize = 64;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ContactMessageType {
@@ -89,6 +97,7 @@ where T: ContactsBackend + 'static
     liveness: LivenessHandle,
     liveness_data: Vec<ContactsLivenessData>,
     connectivity: ConnectivityRequester,
+    transaction_service: Option<TransactionServiceHandle>,
     event_publisher: broadcast::Sender<Arc<ContactsLivenessEvent>>,
     number_of_rounds_no_pings: u16,
     contacts_auto_ping_interval: Duration,
@@ -107,6 +116,7 @@ where T: ContactsBackend + 'static
         shutdown_signal: ShutdownSignal,
         liveness: LivenessHandle,
         connectivity: ConnectivityRequester,
+        transaction_service: Option<TransactionServiceHandle>,
         event_publisher: broadcast::Sender<Arc<ContactsLivenessEvent>>,
         contacts_auto_ping_interval: Duration,
         contacts_online_ping_window: usize,
@@ -118,6 +128,7 @@ where T: ContactsBackend + 'static
             liveness,
             liveness_data: Vec::new(),
             connectivity,
+            transaction_service,
             event_publisher,
             number_of_rounds_no_pings: 0,
             contacts_auto_ping_interval,
@@ -139,6 +150,9 @@ where T: ContactsBackend + 'static
         let connectivity_events = self.connectivity.get_event_subscription();
         pin_mut!(connectivity_events);
 
+        // The transaction service is not part of every service stack the contacts service runs in
+        let mut transaction_events = self.transaction_service.as_ref().map(|ts| ts.get_event_stream());
+
         let shutdown = self
             .shutdown_signal
             .take()
@@ -177,6 +191,18 @@ where T: ContactsBackend + 'static
                     self.handle_connectivity_event(event);
                 }
 
+                Some(Ok(event)) = async {
+                    match transaction_events.as_mut() {
+                        Some(events) => Some(events.recv().await),
+                        None => None,
+                    }
+                } => {
+                    let _result = self.handle_transaction_event(&*event).await.map_err(|e| {
+                        warn!(target: LOG_TARGET, "Failed to record transaction with contact: {:?}", e);
+                        e
+                    });
+                },
+
                 _ = shutdown.wait() => {
                     info!(target: LOG_TARGET, "Contacts service shutting down because it received the shutdown signal");
                     break;
@@ -199,7 +225,22 @@ where T: ContactsBackend + 'static
                 };
                 Ok(result.map(ContactsServiceResponse::Contact)?)
             },
+            ContactsServiceRequest::GetContactByAlias(alias) => {
+                let result = self.db.get_contact_by_alias(alias).await;
+                if let Ok(ref contact) = result {
+                    self.liveness.check_add_monitored_peer(contact.node_id.clone()).await?;
+                };
+                Ok(result.map(ContactsServiceResponse::Contact)?)
+            },
             ContactsServiceRequest::UpsertContact(c) => {
+                validate_alias(&c.alias)?;
+                match self.db.get_contact_by_alias(c.alias.clone()).await {
+                    Ok(existing) if existing.public_key != c.public_key => {
+                        return Err(ContactsServiceError::DuplicateAlias(c.alias));
+                    },
+                    Ok(_) | Err(ContactsServiceStorageError::ValueNotFound(_)) => {},
+                    Err(e) => return Err(e.into()),
+                }
                 self.db.upsert_contact(c.clone()).await?;
                 self.liveness.check_add_monitored_peer(c.node_id).await?;
                 info!(
@@ -297,11 +338,49 @@ where T: ContactsBackend + 'static
                     }
                 };
             },
+            LivenessEvent::MonitoredPeerSlaBreached(_) | LivenessEvent::MonitoredPeerSlaRestored(_) => {},
         }
 
         Ok(())
     }
 
+    /// Keep track of the most recent transaction with each contact
+    async fn handle_transaction_event(&mut self, event: &TransactionEvent) -> Result<(), ContactsServiceError> {
+        let tx_id = match event {
+            TransactionEvent::ReceivedTransaction(tx_id) |
+            TransactionEvent::ReceivedFinalizedTransaction(tx_id) |
+            TransactionEvent::TransactionSendResult(tx_id, _) |
+            TransactionEvent::TransactionCompletedImmediately(tx_id) |
+            TransactionEvent::TransactionImported(tx_id) => *tx_id,
+            _ => return Ok(()),
+        };
+        let transaction_service = match self.transaction_service.as_mut() {
+            Some(ts) => ts,
+            None => return Ok(()),
+        };
+        let (counterparty, timestamp) = match transaction_service.get_any_transaction(tx_id).await? {
+            Some(WalletTransaction::PendingInbound(tx)) => (tx.source_public_key, tx.timestamp),
+            Some(WalletTransaction::PendingOutbound(tx)) => (tx.destination_public_key, tx.timestamp),
+            Some(WalletTransaction::Completed(tx)) => match tx.direction {
+                TransactionDirection::Inbound => (tx.source_public_key, tx.timestamp),
+                TransactionDirection::Outbound => (tx.destination_public_key, tx.timestamp),
+                TransactionDirection::Unknown => return Ok(()),
+            },
+            None => return Ok(()),
+        };
+        if self
+            .db
+            .update_contact_last_transaction(counterparty.clone(), tx_id, timestamp)
+            .await?
+        {
+            debug!(
+                target: LOG_TARGET,
+                "Recorded transaction {} with contact {}", tx_id, counterparty
+            );
+        }
+        Ok(())
+    }
+
     fn get_online_status(&self, last_seen: Option<NaiveDateTime>) -> Result<ContactOnlineStatus, ContactsServiceError> {
         let mut online_status = ContactOnlineStatus::NeverSeen;
         if let Some(time) = last_seen {
@@ -411,3 +490,28 @@ where T: ContactsBackend + 'static
         }
     }
 }
+
+/// Aliases are used to address contacts from the command line (`@alias`), so they may not be blank or start with `@`
+fn validate_alias(alias: &str) -> Result<(), ContactsServiceError> {
+    if alias.trim().is_empty() {
+        return Err(ContactsServiceError::InvalidAlias("alias may not be empty".to_string()));
+    }
+    if alias.starts_with('@') {
+        return Err(ContactsServiceError::InvalidAlias(format!(
+            "`{}` may not start with '@'",
+            alias
+        )));
+    }
+    if alias.chars().count() > MAX_ALIAS_LENGTH {
+        return Err(ContactsServiceError::InvalidAlias(format!(
+            "alias may not be longer than {} characters",
+            MAX_ALIAS_LENGTH
+        )));
+    }
+    if alias.chars().any(char::is_control) {
+        return Err(ContactsServiceError::InvalidAlias(
+            "alias may not contain control characters".to_string(),
+        ));
+    }
+    Ok(())
+}
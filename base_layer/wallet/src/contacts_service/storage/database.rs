@@ -27,6 +27,7 @@ use std::{
 
 use chrono::NaiveDateTime;
 use log::*;
+use tari_common_types::transaction::TxId;
 use tari_comms::{peer_manager::NodeId, types::CommsPublicKey};
 
 use crate::contacts_service::error::ContactsServiceStorageError;
@@ -40,6 +41,8 @@ pub struct Contact {
     pub node_id: NodeId,
     pub last_seen: Option<NaiveDateTime>,
     pub latency: Option<u32>,
+    pub last_transaction_id: Option<TxId>,
+    pub last_transaction_at: Option<NaiveDateTime>,
 }
 
 impl Contact {
@@ -55,6 +58,8 @@ impl Contact {
             node_id: NodeId::from_key(&public_key),
             last_seen,
             latency,
+            last_transaction_id: None,
+            last_transaction_at: None,
         }
     }
 }
@@ -71,6 +76,7 @@ pub trait ContactsBackend: Send + Sync + Clone {
 pub enum DbKey {
     Contact(CommsPublicKey),
     ContactId(NodeId),
+    ContactAlias(String),
     Contacts,
 }
 
@@ -84,11 +90,13 @@ pub enum DbValue {
 pub enum DbKeyValuePair {
     Contact(CommsPublicKey, Contact),
     LastSeen(NodeId, NaiveDateTime, Option<i32>),
+    LastTransaction(CommsPublicKey, TxId, NaiveDateTime),
 }
 
 pub enum WriteOperation {
     Upsert(Box<DbKeyValuePair>),
     UpdateLastSeen(Box<DbKeyValuePair>),
+    UpdateLastTransaction(Box<DbKeyValuePair>),
     Remove(DbKey),
 }
 
@@ -126,6 +134,22 @@ where T: ContactsBackend + 'static
             .and_then(|inner_result| inner_result)
     }
 
+    pub async fn get_contact_by_alias(&self, alias: String) -> Result<Contact, ContactsServiceStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            let key = DbKey::ContactAlias(alias);
+            match db_clone.fetch(&key) {
+                Ok(None) => Err(ContactsServiceStorageError::ValueNotFound(key)),
+                Ok(Some(DbValue::Contact(c))) => Ok(*c),
+                Ok(Some(other)) => unexpected_result(key, other),
+                Err(e) => log_error(key, e),
+            }
+        })
+        .await
+        .map_err(|err| ContactsServiceStorageError::BlockingTaskSpawnError(err.to_string()))
+        .and_then(|inner_result| inner_result)
+    }
+
     pub async fn get_contacts(&self) -> Result<Vec<Contact>, ContactsServiceStorageError> {
         let db_clone = self.db.clone();
 
@@ -185,6 +209,25 @@ where T: ContactsBackend + 'static
         }
     }
 
+    /// Record the most recent transaction with a contact. Returns false if the public key is not a contact.
+    pub async fn update_contact_last_transaction(
+        &self,
+        pub_key: CommsPublicKey,
+        tx_id: TxId,
+        timestamp: NaiveDateTime,
+    ) -> Result<bool, ContactsServiceStorageError> {
+        let db_clone = self.db.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            db_clone.write(WriteOperation::UpdateLastTransaction(Box::new(
+                DbKeyValuePair::LastTransaction(pub_key, tx_id, timestamp),
+            )))
+        })
+        .await
+        .map_err(|err| ContactsServiceStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(result.is_some())
+    }
+
     pub async fn remove_contact(&self, pub_key: CommsPublicKey) -> Result<Contact, ContactsServiceStorageError> {
         let db_clone = self.db.clone();
         let pub_key_clone = pub_key.clone();
@@ -215,6 +258,7 @@ impl Display for DbKey {
         match self {
             DbKey::Contact(c) => f.write_str(&format!("Contact: {:?}", c)),
             DbKey::ContactId(id) => f.write_str(&format!("Contact: {:?}", id)),
+            DbKey::ContactAlias(alias) => f.write_str(&format!("Contact: {}", alias)),
             DbKey::Contacts => f.write_str(&"Contacts".to_string()),
         }
     }
@@ -24,7 +24,7 @@ use std::convert::TryFrom;
 
 use chrono::NaiveDateTime;
 use diesel::{prelude::*, result::Error as DieselError, SqliteConnection};
-use tari_common_types::types::PublicKey;
+use tari_common_types::{transaction::TxId, types::PublicKey};
 use tari_comms::peer_manager::NodeId;
 use tari_utilities::ByteArray;
 
@@ -64,6 +64,11 @@ impl ContactsBackend for ContactsServiceSqliteDatabase {
                 Err(ContactsServiceStorageError::DieselError(DieselError::NotFound)) => None,
                 Err(e) => return Err(e),
             },
+            DbKey::ContactAlias(alias) => match ContactSql::find_by_alias(alias, &conn) {
+                Ok(c) => Some(DbValue::Contact(Box::new(Contact::try_from(c)?))),
+                Err(ContactsServiceStorageError::DieselError(DieselError::NotFound)) => None,
+                Err(e) => return Err(e),
+            },
             DbKey::Contacts => Some(DbValue::Contacts(
                 ContactSql::index(&conn)?
                     .iter()
@@ -85,8 +90,7 @@ impl ContactsBackend for ContactsServiceSqliteDatabase {
                         let _contact_sql = found_c.update(
                             UpdateContact {
                                 alias: Some(c.alias),
-                                last_seen: None,
-                                latency: None,
+                                ..Default::default()
                             },
                             &conn,
                         )?;
@@ -95,7 +99,9 @@ impl ContactsBackend for ContactsServiceSqliteDatabase {
                         ContactSql::from(c).commit(&conn)?;
                     },
                 },
-                DbKeyValuePair::LastSeen(..) => return Err(ContactsServiceStorageError::OperationNotSupported),
+                DbKeyValuePair::LastSeen(..) | DbKeyValuePair::LastTransaction(..) => {
+                    return Err(ContactsServiceStorageError::OperationNotSupported)
+                },
             },
             WriteOperation::UpdateLastSeen(kvp) => match *kvp {
                 DbKeyValuePair::LastSeen(node_id, date_time, latency) => {
@@ -103,9 +109,9 @@ impl ContactsBackend for ContactsServiceSqliteDatabase {
                         Ok(found_c) => {
                             let contact = found_c.update(
                                 UpdateContact {
-                                    alias: None,
                                     last_seen: Some(Some(date_time)),
                                     latency: Some(latency),
+                                    ..Default::default()
                                 },
                                 &conn,
                             )?;
@@ -117,7 +123,31 @@ impl ContactsBackend for ContactsServiceSqliteDatabase {
                         Err(e) => return Err(e),
                     }
                 },
-                DbKeyValuePair::Contact(..) => return Err(ContactsServiceStorageError::OperationNotSupported),
+                DbKeyValuePair::Contact(..) | DbKeyValuePair::LastTransaction(..) => {
+                    return Err(ContactsServiceStorageError::OperationNotSupported)
+                },
+            },
+            WriteOperation::UpdateLastTransaction(kvp) => match *kvp {
+                DbKeyValuePair::LastTransaction(pk, tx_id, timestamp) => {
+                    match ContactSql::find_by_public_key(&pk.to_vec(), &conn) {
+                        Ok(found_c) => {
+                            let contact = found_c.update(
+                                UpdateContact {
+                                    last_transaction_id: Some(Some(tx_id.as_u64() as i64)),
+                                    last_transaction_at: Some(Some(timestamp)),
+                                    ..Default::default()
+                                },
+                                &conn,
+                            )?;
+                            return Ok(Some(DbValue::Contact(Box::new(Contact::try_from(contact)?))));
+                        },
+                        Err(ContactsServiceStorageError::DieselError(DieselError::NotFound)) => (),
+                        Err(e) => return Err(e),
+                    }
+                },
+                DbKeyValuePair::Contact(..) | DbKeyValuePair::LastSeen(..) => {
+                    return Err(ContactsServiceStorageError::OperationNotSupported)
+                },
             },
             WriteOperation::Remove(k) => match k {
                 DbKey::Contact(k) => match ContactSql::find_by_public_key(&k.to_vec(), &conn) {
@@ -136,7 +166,9 @@ impl ContactsBackend for ContactsServiceSqliteDatabase {
                     Err(ContactsServiceStorageError::DieselError(DieselError::NotFound)) => (),
                     Err(e) => return Err(e),
                 },
-                DbKey::Contacts => return Err(ContactsServiceStorageError::OperationNotSupported),
+                DbKey::ContactAlias(_) | DbKey::Contacts => {
+                    return Err(ContactsServiceStorageError::OperationNotSupported)
+                },
             },
         }
 
@@ -153,6 +185,8 @@ struct ContactSql {
     alias: String,
     last_seen: Option<NaiveDateTime>,
     latency: Option<i32>,
+    last_transaction_id: Option<i64>,
+    last_transaction_at: Option<NaiveDateTime>,
 }
 
 impl ContactSql {
@@ -179,6 +213,13 @@ impl ContactSql {
             .first::<ContactSql>(conn)?)
     }
 
+    /// Find a particular Contact by their alias, if it exists
+    pub fn find_by_alias(alias: &str, conn: &SqliteConnection) -> Result<ContactSql, ContactsServiceStorageError> {
+        Ok(contacts::table
+            .filter(contacts::alias.eq(alias))
+            .first::<ContactSql>(conn)?)
+    }
+
     /// Find a particular Contact by their node ID, if it exists
     pub fn find_by_node_id(node_id: &[u8], conn: &SqliteConnection) -> Result<ContactSql, ContactsServiceStorageError> {
         Ok(contacts::table
@@ -225,6 +266,8 @@ impl TryFrom<ContactSql> for Contact {
             alias: o.alias,
             last_seen: o.last_seen,
             latency: o.latency.map(|val| val as u32),
+            last_transaction_id: o.last_transaction_id.map(|val| TxId::from(val as u64)),
+            last_transaction_at: o.last_transaction_at,
         })
     }
 }
@@ -239,25 +282,33 @@ impl From<Contact> for ContactSql {
             alias: o.alias,
             last_seen: o.last_seen,
             latency: o.latency.map(|val| val as i32),
+            last_transaction_id: o.last_transaction_id.map(|val| val.as_u64() as i64),
+            last_transaction_at: o.last_transaction_at,
         }
     }
 }
 
-#[derive(AsChangeset)]
+#[derive(AsChangeset, Default)]
 #[table_name = "contacts"]
 pub struct UpdateContact {
     alias: Option<String>,
     last_seen: Option<Option<NaiveDateTime>>,
     latency: Option<Option<i32>>,
+    last_transaction_id: Option<Option<i64>>,
+    last_transaction_at: Option<Option<NaiveDateTime>>,
 }
 
 #[cfg(test)]
 mod test {
     use std::convert::TryFrom;
 
+    use chrono::Utc;
     use diesel::{Connection, SqliteConnection};
     use rand::rngs::OsRng;
-    use tari_common_types::types::{PrivateKey, PublicKey};
+    use tari_common_types::{
+        transaction::TxId,
+        types::{PrivateKey, PublicKey},
+    };
     use tari_crypto::{
         keys::{PublicKey as PublicKeyTrait, SecretKey as SecretKeyTrait},
         tari_utilities::ByteArray,
@@ -317,8 +368,7 @@ mod test {
             c.update(
                 UpdateContact {
                     alias: Some("Fred".to_string()),
-                    last_seen: None,
-                    latency: None,
+                    ..Default::default()
                 },
                 &conn,
             )
@@ -326,6 +376,26 @@ mod test {
 
             let c_updated = ContactSql::find_by_public_key(&contacts[1].public_key.to_vec(), &conn).unwrap();
             assert_eq!(c_updated.alias, "Fred".to_string());
+            assert_eq!(ContactSql::find_by_alias("Fred", &conn).unwrap(), c_updated);
+            assert!(ContactSql::find_by_alias("Alice", &conn).is_err());
+
+            let timestamp = Utc::now().naive_utc();
+            c_updated
+                .update(
+                    UpdateContact {
+                        last_transaction_id: Some(Some(42)),
+                        last_transaction_at: Some(Some(timestamp)),
+                        ..Default::default()
+                    },
+                    &conn,
+                )
+                .unwrap();
+            let contact =
+                Contact::try_from(ContactSql::find_by_public_key(&contacts[1].public_key.to_vec(), &conn).unwrap())
+                    .unwrap();
+            assert_eq!(contact.alias, "Fred".to_string());
+            assert_eq!(contact.last_transaction_id, Some(TxId::from(42u64)));
+            assert_eq!(contact.last_transaction_at, Some(timestamp));
         });
     }
 }
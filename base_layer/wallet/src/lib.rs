@@ -14,6 +14,7 @@ pub mod base_node_service;
 pub mod connectivity_service;
 pub mod contacts_service;
 pub mod error;
+pub mod notifications;
 mod operation_id;
 pub mod output_manager_service;
 pub mod storage;
@@ -0,0 +1,98 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{fmt, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tari_common::configuration::serializers;
+
+/// A destination that wallet notifications are delivered to. Each channel is rate limited independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationChannel {
+    /// Callbacks invoked through the wallet FFI, typically by mobile apps
+    FfiCallback,
+    /// Applications that forward wallet events to an HTTP endpoint
+    Webhook,
+    /// Console wallet UI notifications and the notify script
+    Console,
+}
+
+impl fmt::Display for NotificationChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotificationChannel::FfiCallback => write!(f, "FFI callback"),
+            NotificationChannel::Webhook => write!(f, "Webhook"),
+            NotificationChannel::Console => write!(f, "Console"),
+        }
+    }
+}
+
+/// The maximum number of notifications that may be delivered on a channel within a sliding time period
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationRateLimit {
+    pub max_notifications: usize,
+    #[serde(with = "serializers::seconds")]
+    pub period: Duration,
+}
+
+impl NotificationRateLimit {
+    pub fn new(max_notifications: usize, period: Duration) -> Self {
+        Self {
+            max_notifications,
+            period,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationConfig {
+    /// Events that arrive while a channel is rate limited are held for at least this long and then delivered as a
+    /// single digest notification
+    #[serde(with = "serializers::seconds")]
+    pub digest_window: Duration,
+    pub ffi_callback: NotificationRateLimit,
+    pub webhook: NotificationRateLimit,
+    pub console: NotificationRateLimit,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            digest_window: Duration::from_secs(5),
+            ffi_callback: NotificationRateLimit::new(10, Duration::from_secs(10)),
+            webhook: NotificationRateLimit::new(10, Duration::from_secs(60)),
+            console: NotificationRateLimit::new(10, Duration::from_secs(10)),
+        }
+    }
+}
+
+impl NotificationConfig {
+    pub fn rate_limit(&self, channel: NotificationChannel) -> &NotificationRateLimit {
+        match channel {
+            NotificationChannel::FfiCallback => &self.ffi_callback,
+            NotificationChannel::Webhook => &self.webhook,
+            NotificationChannel::Console => &self.console,
+        }
+    }
+}
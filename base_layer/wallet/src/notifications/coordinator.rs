@@ -0,0 +1,266 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crate::notifications::{NotificationChannel, NotificationConfig, NotificationRateLimit};
+
+/// A notification produced by the [NotificationCoordinator] for delivery on a channel
+#[derive(Debug, Clone, PartialEq)]
+pub enum Notification<T> {
+    /// A single event delivered as it occurred
+    Single(T),
+    /// A burst of events that were held back by the rate limit, in the order they occurred
+    Digest(Vec<T>),
+}
+
+impl<T> Notification<T> {
+    /// The number of events represented by this notification
+    pub fn len(&self) -> usize {
+        match self {
+            Notification::Single(_) => 1,
+            Notification::Digest(events) => events.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The most recent event represented by this notification
+    pub fn latest(&self) -> Option<&T> {
+        match self {
+            Notification::Single(event) => Some(event),
+            Notification::Digest(events) => events.last(),
+        }
+    }
+}
+
+struct ChannelState<T> {
+    delivered: VecDeque<Instant>,
+    pending: Vec<T>,
+    pending_since: Option<Instant>,
+}
+
+impl<T> Default for ChannelState<T> {
+    fn default() -> Self {
+        Self {
+            delivered: VecDeque::new(),
+            pending: Vec::new(),
+            pending_since: None,
+        }
+    }
+}
+
+impl<T> ChannelState<T> {
+    fn prune(&mut self, now: Instant, period: Duration) {
+        while let Some(delivered_at) = self.delivered.front() {
+            if now.saturating_duration_since(*delivered_at) < period {
+                break;
+            }
+            self.delivered.pop_front();
+        }
+    }
+
+    fn has_budget(&self, max_notifications: usize) -> bool {
+        self.delivered.len() < max_notifications
+    }
+}
+
+/// Groups bursts of wallet events into digest notifications and enforces a rate limit on each notification channel.
+///
+/// Events are delivered immediately while a channel is within its rate limit. Once the limit is reached, further
+/// events are held back and delivered together as a single [Notification::Digest] by
+/// [poll_digests](NotificationCoordinator::poll_digests) once the digest window has elapsed and the channel has
+/// budget again. This prevents clients from being flooded, for example when hundreds of outputs are found during a
+/// wallet recovery.
+pub struct NotificationCoordinator<T> {
+    config: NotificationConfig,
+    channels: HashMap<NotificationChannel, ChannelState<T>>,
+}
+
+impl<T> NotificationCoordinator<T> {
+    pub fn new(config: NotificationConfig) -> Self {
+        Self {
+            config,
+            channels: HashMap::new(),
+        }
+    }
+
+    /// The interval at which [poll_digests](NotificationCoordinator::poll_digests) should be called
+    pub fn digest_window(&self) -> Duration {
+        self.config.digest_window
+    }
+
+    /// Submit an event for delivery on the given channel. Returns the notification to deliver now, or `None` if the
+    /// event has been held back for a later digest.
+    pub fn submit(&mut self, channel: NotificationChannel, event: T, now: Instant) -> Option<Notification<T>> {
+        let NotificationRateLimit {
+            max_notifications,
+            period,
+        } = *self.config.rate_limit(channel);
+        let state = self.channels.entry(channel).or_default();
+        state.prune(now, period);
+
+        if state.pending.is_empty() && state.has_budget(max_notifications) {
+            state.delivered.push_back(now);
+            return Some(Notification::Single(event));
+        }
+
+        if state.pending.is_empty() {
+            state.pending_since = Some(now);
+        }
+        state.pending.push(event);
+        None
+    }
+
+    /// Returns the digests that are ready for delivery. A digest is released once the digest window has elapsed since
+    /// its first event was held back and the channel's rate limit allows another notification.
+    pub fn poll_digests(&mut self, now: Instant) -> Vec<(NotificationChannel, Notification<T>)> {
+        let digest_window = self.config.digest_window;
+        let mut ready = Vec::new();
+        for (channel, state) in &mut self.channels {
+            let pending_since = match state.pending_since {
+                Some(pending_since) => pending_since,
+                None => continue,
+            };
+            let NotificationRateLimit {
+                max_notifications,
+                period,
+            } = *self.config.rate_limit(*channel);
+            state.prune(now, period);
+            // A digest must always eventually be delivered, even if the channel is configured to allow nothing else
+            if now.saturating_duration_since(pending_since) < digest_window ||
+                !state.has_budget(max_notifications.max(1))
+            {
+                continue;
+            }
+
+            state.delivered.push_back(now);
+            state.pending_since = None;
+            let mut events = std::mem::take(&mut state.pending);
+            let notification = if events.len() == 1 {
+                Notification::Single(events.remove(0))
+            } else {
+                Notification::Digest(events)
+            };
+            ready.push((*channel, notification));
+        }
+        ready
+    }
+
+    /// The number of events currently held back on the given channel
+    pub fn num_pending(&self, channel: NotificationChannel) -> usize {
+        self.channels.get(&channel).map(|s| s.pending.len()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config() -> NotificationConfig {
+        NotificationConfig {
+            digest_window: Duration::from_secs(5),
+            ffi_callback: NotificationRateLimit::new(3, Duration::from_secs(10)),
+            webhook: NotificationRateLimit::new(1, Duration::from_secs(60)),
+            console: NotificationRateLimit::new(3, Duration::from_secs(10)),
+        }
+    }
+
+    #[test]
+    fn it_groups_a_burst_into_a_digest() {
+        let mut coordinator = NotificationCoordinator::new(config());
+        let start = Instant::now();
+
+        let delivered = (0..500u32)
+            .filter_map(|i| coordinator.submit(NotificationChannel::FfiCallback, i, start))
+            .collect::<Vec<_>>();
+        assert_eq!(delivered, vec![
+            Notification::Single(0),
+            Notification::Single(1),
+            Notification::Single(2)
+        ]);
+        assert_eq!(coordinator.num_pending(NotificationChannel::FfiCallback), 497);
+
+        // The digest window has not elapsed yet
+        assert!(coordinator.poll_digests(start + Duration::from_secs(4)).is_empty());
+        // The digest window has elapsed, but the rate limit has not reset
+        assert!(coordinator.poll_digests(start + Duration::from_secs(6)).is_empty());
+
+        let digests = coordinator.poll_digests(start + Duration::from_secs(10));
+        assert_eq!(digests.len(), 1);
+        let (channel, digest) = &digests[0];
+        assert_eq!(*channel, NotificationChannel::FfiCallback);
+        assert_eq!(digest.len(), 497);
+        assert_eq!(digest.latest(), Some(&499));
+        assert_eq!(coordinator.num_pending(NotificationChannel::FfiCallback), 0);
+    }
+
+    #[test]
+    fn it_holds_events_behind_a_pending_digest() {
+        let mut coordinator = NotificationCoordinator::new(config());
+        let start = Instant::now();
+        for i in 0..4u32 {
+            coordinator.submit(NotificationChannel::Console, i, start);
+        }
+        // Budget has freed up, but event 4 must not overtake the pending digest
+        let later = start + Duration::from_secs(11);
+        assert!(coordinator.submit(NotificationChannel::Console, 4, later).is_none());
+        let digests = coordinator.poll_digests(later);
+        assert_eq!(digests, vec![(
+            NotificationChannel::Console,
+            Notification::Digest(vec![3, 4])
+        )]);
+    }
+
+    #[test]
+    fn it_rate_limits_channels_independently() {
+        let mut coordinator = NotificationCoordinator::new(config());
+        let now = Instant::now();
+        assert!(coordinator.submit(NotificationChannel::Webhook, 1u32, now).is_some());
+        assert!(coordinator.submit(NotificationChannel::Webhook, 2, now).is_none());
+        assert!(coordinator.submit(NotificationChannel::Console, 3, now).is_some());
+        assert!(coordinator.submit(NotificationChannel::FfiCallback, 4, now).is_some());
+
+        let digests = coordinator.poll_digests(now + Duration::from_secs(60));
+        assert_eq!(digests, vec![(NotificationChannel::Webhook, Notification::Single(2))]);
+    }
+
+    #[test]
+    fn it_always_delivers_digests_for_zero_limits() {
+        let mut cfg = config();
+        cfg.console = NotificationRateLimit::new(0, Duration::from_secs(10));
+        let mut coordinator = NotificationCoordinator::new(cfg);
+        let now = Instant::now();
+        assert!(coordinator.submit(NotificationChannel::Console, 1u32, now).is_none());
+        assert!(coordinator.submit(NotificationChannel::Console, 2, now).is_none());
+        let digests = coordinator.poll_digests(now + Duration::from_secs(5));
+        assert_eq!(digests, vec![(
+            NotificationChannel::Console,
+            Notification::Digest(vec![1, 2])
+        )]);
+    }
+}
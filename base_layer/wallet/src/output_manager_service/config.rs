@@ -37,6 +37,9 @@ pub struct OutputManagerServiceConfig {
     pub seed_word_language: MnemonicLanguage,
     pub event_channel_size: usize,
     pub num_confirmations_required: u64,
+    /// The number of base nodes, other than the connected base node, that must agree on the block containing a
+    /// received output before it is marked as spendable. Zero trusts the connected base node alone.
+    pub num_independent_header_confirmations: usize,
     pub tx_validator_batch_size: usize,
 }
 
@@ -50,6 +53,7 @@ impl Default for OutputManagerServiceConfig {
             seed_word_language: MnemonicLanguage::English,
             event_channel_size: 250,
             num_confirmations_required: 3,
+            num_independent_header_confirmations: 0,
             tx_validator_batch_size: 100,
         }
     }
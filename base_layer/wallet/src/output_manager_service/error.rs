@@ -126,6 +126,24 @@ pub enum OutputManagerError {
     InvalidMessageError(String),
     #[error("Key manager service error : {0}")]
     KeyManagerServiceError(#[from] KeyManagerServiceError),
+    #[error("Output is not locked by a vault script")]
+    NotAVaultOutput,
+    #[error("Recovery key does not match the vault recovery public key")]
+    InvalidVaultRecoveryKey,
+    #[error("Invalid coin split: {0}")]
+    InvalidCoinSplit(String),
+    #[error("Not enough outputs to join: found {0}, need at least 2")]
+    NotEnoughOutputsToJoin(usize),
+    #[error("Cannot bump the transaction fee: {0}")]
+    FeeBumpNotPossible(String),
+    #[error("Cannot build the offline transaction: {0}")]
+    OfflineTransactionNotPossible(String),
+    #[error("Invalid watch-only key: {0}")]
+    InvalidWatchOnlyKey(String),
+    #[error("Output is not locked by an HTLC script")]
+    NotAnHtlcOutput,
+    #[error("Invalid HTLC spend: {0}")]
+    InvalidHtlcSpend(String),
 }
 
 #[derive(Debug, Error)]
@@ -25,7 +25,7 @@ use std::{fmt, fmt::Formatter, sync::Arc};
 use aes_gcm::Aes256Gcm;
 use tari_common_types::{
     transaction::TxId,
-    types::{HashOutput, PrivateKey, PublicKey},
+    types::{BlockHash, Commitment, HashOutput, PrivateKey, PublicKey},
 };
 use tari_core::{
     covenants::Covenant,
@@ -52,10 +52,21 @@ use tower::Service;
 
 use crate::output_manager_service::{
     error::OutputManagerError,
-    service::{Balance, OutputStatusesByTxId},
-    storage::models::{KnownOneSidedPaymentScript, SpendingPriority},
+    service::{Balance, OutputStatusesByTxId, WatchOnlyBalance},
+    storage::models::{KnownOneSidedPaymentScript, SpendingPriority, WatchOnlyKey, WatchOnlyOutput},
+    vault::VaultOutput,
 };
 
+/// A recipient of a one-sided payment made with `create_pay_to_many_transaction`
+#[derive(Debug, Clone)]
+pub struct PaymentRecipient {
+    pub address: PublicKey,
+    pub amount: MicroTari,
+    /// Embedded in the range proof of the recipient's output, so only the recipient can read it. At most
+    /// `REWIND_USER_MESSAGE_LENGTH` bytes.
+    pub payment_id: Vec<u8>,
+}
+
 /// API Request enum
 #[allow(clippy::large_enum_variant)]
 pub enum OutputManagerRequest {
@@ -132,7 +143,80 @@ pub enum OutputManagerRequest {
     SetCoinbaseAbandoned(TxId, bool),
     CreateClaimShaAtomicSwapTransaction(HashOutput, PublicKey, MicroTari),
     CreateHtlcRefundTransaction(HashOutput, MicroTari),
+    FetchHtlcPreImage(HashOutput),
     GetOutputStatusesByTxId(TxId),
+    CreateVaultTransaction {
+        tx_id: TxId,
+        amount: MicroTari,
+        unlock_height: u64,
+        recovery_public_key: PublicKey,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
+    CreateVaultRecoveryTransaction {
+        output_hash: HashOutput,
+        recovery_private_key: PrivateKey,
+        fee_per_gram: MicroTari,
+    },
+    GetVaultOutputs,
+    CreatePayToManyTransaction {
+        tx_id: TxId,
+        recipients: Vec<PaymentRecipient>,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
+    CreateMultisigFundingTransaction {
+        tx_id: TxId,
+        amount: MicroTari,
+        spending_key: PrivateKey,
+        script_private_key: PrivateKey,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
+    CreateEvenCoinSplit {
+        split_count: usize,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+    },
+    CreateCoinJoin {
+        dust_threshold: MicroTari,
+        max_inputs: usize,
+        fee_per_gram: MicroTari,
+    },
+    PrepareFeeBumpTransaction {
+        previous_tx_id: TxId,
+        tx_id: TxId,
+        amount: MicroTari,
+        previous_fee: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
+    ReserveOfflineTransactionInputs {
+        tx_id: TxId,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        script: TariScript,
+    },
+    BuildOfflineTransaction {
+        tx_id: TxId,
+        inputs: Vec<Commitment>,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        lock_height: u64,
+        message: String,
+        script: TariScript,
+        sender_offset_private_key: Box<PrivateKey>,
+    },
+    AddWatchOnlyKey(Box<WatchOnlyKey>),
+    RemoveWatchOnlyKey(PublicKey),
+    GetWatchOnlyKeys,
+    ScanOutputsForWatchOnlyKeys {
+        outputs: Vec<TransactionOutput>,
+        mined_height: u64,
+        mined_in_block: BlockHash,
+    },
+    GetWatchOnlyOutputs,
+    GetWatchOnlyBalances,
 }
 
 impl fmt::Display for OutputManagerRequest {
@@ -211,8 +295,60 @@ impl fmt::Display for OutputManagerRequest {
                 output.to_hex(),
                 fee_per_gram,
             ),
+            FetchHtlcPreImage(output) => write!(f, "FetchHtlcPreImage(output hash: {})", output.to_hex()),
 
             GetOutputStatusesByTxId(t) => write!(f, "GetOutputStatusesByTxId: {}", t),
+            CreateVaultTransaction {
+                tx_id,
+                amount,
+                unlock_height,
+                ..
+            } => write!(
+                f,
+                "CreateVaultTransaction ({}: {}, unlock height: {})",
+                tx_id, amount, unlock_height
+            ),
+            CreateVaultRecoveryTransaction { output_hash, .. } => write!(
+                f,
+                "CreateVaultRecoveryTransaction (output hash: {})",
+                output_hash.to_hex()
+            ),
+            GetVaultOutputs => write!(f, "GetVaultOutputs"),
+            CreatePayToManyTransaction { tx_id, recipients, .. } => write!(
+                f,
+                "CreatePayToManyTransaction ({}: {} recipient(s))",
+                tx_id,
+                recipients.len()
+            ),
+            CreateMultisigFundingTransaction { tx_id, amount, .. } => {
+                write!(f, "CreateMultisigFundingTransaction ({}: {})", tx_id, amount)
+            },
+            CreateEvenCoinSplit { split_count, .. } => write!(f, "CreateEvenCoinSplit ({})", split_count),
+            CreateCoinJoin { dust_threshold, .. } => write!(f, "CreateCoinJoin (below {})", dust_threshold),
+            PrepareFeeBumpTransaction {
+                previous_tx_id,
+                tx_id,
+                fee_per_gram,
+                ..
+            } => write!(
+                f,
+                "PrepareFeeBumpTransaction ({} -> {}, fee per gram: {})",
+                previous_tx_id, tx_id, fee_per_gram
+            ),
+            ReserveOfflineTransactionInputs { tx_id, amount, .. } => {
+                write!(f, "ReserveOfflineTransactionInputs ({}: {})", tx_id, amount)
+            },
+            BuildOfflineTransaction { tx_id, inputs, .. } => {
+                write!(f, "BuildOfflineTransaction ({}, {} input(s))", tx_id, inputs.len())
+            },
+            AddWatchOnlyKey(key) => write!(f, "AddWatchOnlyKey ({})", key.spend_public_key),
+            RemoveWatchOnlyKey(spend_public_key) => write!(f, "RemoveWatchOnlyKey ({})", spend_public_key),
+            GetWatchOnlyKeys => write!(f, "GetWatchOnlyKeys"),
+            ScanOutputsForWatchOnlyKeys { mined_height, .. } => {
+                write!(f, "ScanOutputsForWatchOnlyKeys (height: {})", mined_height)
+            },
+            GetWatchOnlyOutputs => write!(f, "GetWatchOnlyOutputs"),
+            GetWatchOnlyBalances => write!(f, "GetWatchOnlyBalances"),
         }
     }
 }
@@ -230,6 +366,7 @@ pub enum OutputManagerResponse {
     PendingTransactionConfirmed,
     PayToSelfTransaction((MicroTari, Transaction)),
     TransactionToSend(SenderTransactionProtocol),
+    OfflineTransactionInputsReserved(Vec<Commitment>),
     TransactionCancelled,
     SpentOutputs(Vec<UnblindedOutput>),
     UnspentOutputs(Vec<UnblindedOutput>),
@@ -250,7 +387,18 @@ pub enum OutputManagerResponse {
     ReinstatedCancelledInboundTx,
     CoinbaseAbandonedSet,
     ClaimHtlcTransaction((TxId, MicroTari, MicroTari, Transaction)),
+    HtlcPreImage(Option<PublicKey>),
     OutputStatusesByTxId(OutputStatusesByTxId),
+    VaultTransaction((MicroTari, Transaction)),
+    VaultRecoveryTransaction((TxId, MicroTari, MicroTari, Transaction)),
+    VaultOutputs(Vec<VaultOutput>),
+    PayToManyTransaction((MicroTari, Transaction)),
+    MultisigFundingTransaction(Box<(MicroTari, Transaction, TransactionOutput)>),
+    WatchOnlyKeyAdded,
+    WatchOnlyKeyRemoved,
+    WatchOnlyKeys(Vec<WatchOnlyKey>),
+    WatchOnlyOutputs(Vec<WatchOnlyOutput>),
+    WatchOnlyBalances(Vec<WatchOnlyBalance>),
 }
 
 pub type OutputManagerEventSender = broadcast::Sender<Arc<OutputManagerEvent>>;
@@ -261,6 +409,11 @@ pub type OutputManagerEventReceiver = broadcast::Receiver<Arc<OutputManagerEvent
 pub enum OutputManagerEvent {
     TxoValidationSuccess(u64),
     TxoValidationFailure(u64),
+    /// A counterparty claimed an HTLC output funded by this wallet, revealing the pre-image of its hash lock
+    HtlcClaimed {
+        output_hash: HashOutput,
+        pre_image: PublicKey,
+    },
     Error(String),
 }
 
@@ -273,6 +426,12 @@ impl fmt::Display for OutputManagerEvent {
             OutputManagerEvent::TxoValidationFailure(tx) => {
                 write!(f, "TxoValidationFailure for {}", tx)
             },
+            OutputManagerEvent::HtlcClaimed { output_hash, pre_image } => write!(
+                f,
+                "HtlcClaimed for output {} with pre-image {}",
+                output_hash.to_hex(),
+                pre_image.to_hex()
+            ),
             OutputManagerEvent::Error(error) => {
                 write!(f, "Error {}", error)
             },
@@ -669,6 +828,148 @@ impl OutputManagerHandle {
         }
     }
 
+    /// Create a transaction that splits the largest spendable UTXO into `split_count` outputs of equal value.
+    /// Returns (tx_id, tx, utxos_total_value).
+    pub async fn create_even_coin_split(
+        &mut self,
+        split_count: usize,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+    ) -> Result<(TxId, Transaction, MicroTari), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreateEvenCoinSplit {
+                split_count,
+                fee_per_gram,
+                lock_height,
+            })
+            .await??
+        {
+            OutputManagerResponse::Transaction(ct) => Ok(ct),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Create a transaction that consolidates up to `max_inputs` spendable UTXOs worth less than `dust_threshold`
+    /// into a single output.
+    /// Returns (tx_id, tx, utxos_total_value).
+    pub async fn create_coin_join(
+        &mut self,
+        dust_threshold: MicroTari,
+        max_inputs: usize,
+        fee_per_gram: MicroTari,
+    ) -> Result<(TxId, Transaction, MicroTari), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreateCoinJoin {
+                dust_threshold,
+                max_inputs,
+                fee_per_gram,
+            })
+            .await??
+        {
+            OutputManagerResponse::Transaction(ct) => Ok(ct),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Rebuild the pending outbound transaction `previous_tx_id` as `tx_id` from the same inputs with a higher fee.
+    /// The inputs are moved from the previous transaction to the new one, and the previous change output is discarded.
+    pub async fn prepare_fee_bump_transaction(
+        &mut self,
+        previous_tx_id: TxId,
+        tx_id: TxId,
+        amount: MicroTari,
+        previous_fee: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::PrepareFeeBumpTransaction {
+                previous_tx_id,
+                tx_id,
+                amount,
+                previous_fee,
+                fee_per_gram,
+                message,
+            })
+            .await??
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Select and encumber the inputs of a payment that will be built and signed by an offline wallet. Returns the
+    /// commitments of the selected outputs.
+    pub async fn reserve_offline_transaction_inputs(
+        &mut self,
+        tx_id: TxId,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        script: TariScript,
+    ) -> Result<Vec<Commitment>, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::ReserveOfflineTransactionInputs {
+                tx_id,
+                amount,
+                fee_per_gram,
+                script,
+            })
+            .await??
+        {
+            OutputManagerResponse::OfflineTransactionInputsReserved(inputs) => Ok(inputs),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Build a payment that spends exactly the outputs with the given commitments, for signing on an offline wallet.
+    /// The wallet database is not changed.
+    pub async fn build_offline_transaction(
+        &mut self,
+        tx_id: TxId,
+        inputs: Vec<Commitment>,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        lock_height: u64,
+        message: String,
+        script: TariScript,
+        sender_offset_private_key: PrivateKey,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::BuildOfflineTransaction {
+                tx_id,
+                inputs,
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+                script,
+                sender_offset_private_key: Box::new(sender_offset_private_key),
+            })
+            .await??
+        {
+            OutputManagerResponse::TransactionToSend(stp) => Ok(stp),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Fetch the pre-image revealed by the counterparty claiming an HTLC output funded by this wallet. Returns `None`
+    /// if the output has not been spent, or was refunded.
+    pub async fn fetch_htlc_pre_image(&mut self, output: HashOutput) -> Result<Option<PublicKey>, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::FetchHtlcPreImage(output))
+            .await??
+        {
+            OutputManagerResponse::HtlcPreImage(pre_image) => Ok(pre_image),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn create_htlc_refund_transaction(
         &mut self,
         output: HashOutput,
@@ -684,6 +985,115 @@ impl OutputManagerHandle {
         }
     }
 
+    /// Creates a transaction that locks `amount` in a vault output owned by this wallet. The owner can only spend the
+    /// output from `unlock_height`, while the holder of the recovery key can spend it at any time.
+    pub async fn create_vault_transaction(
+        &mut self,
+        tx_id: TxId,
+        amount: MicroTari,
+        unlock_height: u64,
+        recovery_public_key: PublicKey,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(MicroTari, Transaction), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreateVaultTransaction {
+                tx_id,
+                amount,
+                unlock_height,
+                recovery_public_key,
+                fee_per_gram,
+                message,
+            })
+            .await??
+        {
+            OutputManagerResponse::VaultTransaction(ct) => Ok(ct),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Creates a transaction that sweeps a vault output back into this wallet using the recovery key path
+    pub async fn create_vault_recovery_transaction(
+        &mut self,
+        output_hash: HashOutput,
+        recovery_private_key: PrivateKey,
+        fee_per_gram: MicroTari,
+    ) -> Result<(TxId, MicroTari, MicroTari, Transaction), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreateVaultRecoveryTransaction {
+                output_hash,
+                recovery_private_key,
+                fee_per_gram,
+            })
+            .await??
+        {
+            OutputManagerResponse::VaultRecoveryTransaction(ct) => Ok(ct),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_vault_outputs(&mut self) -> Result<Vec<VaultOutput>, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::GetVaultOutputs).await?? {
+            OutputManagerResponse::VaultOutputs(v) => Ok(v),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Creates a single transaction with a one-sided payment output for each recipient. Returns the fee and the
+    /// finalized transaction.
+    pub async fn create_pay_to_many_transaction(
+        &mut self,
+        tx_id: TxId,
+        recipients: Vec<PaymentRecipient>,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(MicroTari, Transaction), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreatePayToManyTransaction {
+                tx_id,
+                recipients,
+                fee_per_gram,
+                message,
+            })
+            .await??
+        {
+            OutputManagerResponse::PayToManyTransaction(ct) => Ok(ct),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Creates a transaction that funds a multisig group output with `amount`. The output is not added to this
+    /// wallet's outputs, since it can only be spent by the group. Returns the fee, the finalized transaction and the
+    /// group output.
+    pub async fn create_multisig_funding_transaction(
+        &mut self,
+        tx_id: TxId,
+        amount: MicroTari,
+        spending_key: PrivateKey,
+        script_private_key: PrivateKey,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(MicroTari, Transaction, TransactionOutput), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::CreateMultisigFundingTransaction {
+                tx_id,
+                amount,
+                spending_key,
+                script_private_key,
+                fee_per_gram,
+                message,
+            })
+            .await??
+        {
+            OutputManagerResponse::MultisigFundingTransaction(ct) => Ok(*ct),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn create_claim_sha_atomic_swap_transaction(
         &mut self,
         output: HashOutput,
@@ -746,6 +1156,71 @@ impl OutputManagerHandle {
         }
     }
 
+    /// Start tracking the outputs of a wallet for which only the public view data is known
+    pub async fn add_watch_only_key(&mut self, key: WatchOnlyKey) -> Result<(), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::AddWatchOnlyKey(Box::new(key)))
+            .await??
+        {
+            OutputManagerResponse::WatchOnlyKeyAdded => Ok(()),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn remove_watch_only_key(&mut self, spend_public_key: PublicKey) -> Result<(), OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::RemoveWatchOnlyKey(spend_public_key))
+            .await??
+        {
+            OutputManagerResponse::WatchOnlyKeyRemoved => Ok(()),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_watch_only_keys(&mut self) -> Result<Vec<WatchOnlyKey>, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::GetWatchOnlyKeys).await?? {
+            OutputManagerResponse::WatchOnlyKeys(keys) => Ok(keys),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Record the outputs of a block that belong to any of the watched wallets, returning those not seen before
+    pub async fn scan_outputs_for_watch_only_keys(
+        &mut self,
+        outputs: Vec<TransactionOutput>,
+        mined_height: u64,
+        mined_in_block: BlockHash,
+    ) -> Result<Vec<WatchOnlyOutput>, OutputManagerError> {
+        match self
+            .handle
+            .call(OutputManagerRequest::ScanOutputsForWatchOnlyKeys {
+                outputs,
+                mined_height,
+                mined_in_block,
+            })
+            .await??
+        {
+            OutputManagerResponse::WatchOnlyOutputs(outputs) => Ok(outputs),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_watch_only_outputs(&mut self) -> Result<Vec<WatchOnlyOutput>, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::GetWatchOnlyOutputs).await?? {
+            OutputManagerResponse::WatchOnlyOutputs(outputs) => Ok(outputs),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_watch_only_balances(&mut self) -> Result<Vec<WatchOnlyBalance>, OutputManagerError> {
+        match self.handle.call(OutputManagerRequest::GetWatchOnlyBalances).await?? {
+            OutputManagerResponse::WatchOnlyBalances(balances) => Ok(balances),
+            _ => Err(OutputManagerError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn add_known_script(&mut self, script: KnownOneSidedPaymentScript) -> Result<(), OutputManagerError> {
         match self
             .handle
@@ -0,0 +1,72 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Monitoring of the HTLC outputs this wallet has funded. When the counterparty of an atomic swap claims an HTLC output
+//! the spending input reveals the pre-image, which this wallet needs to complete its own side of the swap.
+
+use std::convert::TryFrom;
+
+use tari_common_types::types::{BlockHash, HashOutput, PublicKey};
+use tari_core::{
+    base_node::rpc::BaseNodeWalletRpcClient,
+    proto::base_node::FetchSpendingInputsRequest,
+    transactions::{htlc::ShaHtlcScript, transaction_components::TransactionInput},
+};
+
+use crate::output_manager_service::{error::OutputManagerError, storage::models::DbUnblindedOutput};
+
+/// Fetches the pre-images revealed by the spends of `outputs`, which must all have been spent in the block with hash
+/// `block_hash`. Outputs that are not HTLCs, or that were refunded rather than claimed, are skipped.
+pub(crate) async fn fetch_revealed_pre_images(
+    client: &mut BaseNodeWalletRpcClient,
+    block_hash: BlockHash,
+    outputs: &[DbUnblindedOutput],
+) -> Result<Vec<(HashOutput, PublicKey)>, OutputManagerError> {
+    let htlcs = outputs
+        .iter()
+        .filter_map(|o| ShaHtlcScript::from_script(&o.unblinded_output.script).map(|htlc| (o.hash.clone(), htlc)))
+        .collect::<Vec<_>>();
+    if htlcs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let response = client
+        .fetch_spending_inputs(FetchSpendingInputsRequest {
+            block_hash,
+            output_hashes: htlcs.iter().map(|(hash, _)| hash.clone()).collect(),
+        })
+        .await?;
+
+    let mut pre_images = Vec::new();
+    for input in response.inputs {
+        let input = TransactionInput::try_from(input).map_err(OutputManagerError::ConversionError)?;
+        let output_hash = input.output_hash();
+        let pre_image = htlcs
+            .iter()
+            .find(|(hash, _)| *hash == output_hash)
+            .and_then(|(_, htlc)| htlc.pre_image_from_input_data(&input.input_data));
+        if let Some(pre_image) = pre_image {
+            pre_images.push((output_hash, pre_image));
+        }
+    }
+    Ok(pre_images)
+}
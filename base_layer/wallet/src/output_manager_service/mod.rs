@@ -50,11 +50,13 @@ use crate::{
 pub mod config;
 pub mod error;
 pub mod handle;
+mod htlc;
 mod recovery;
 pub mod resources;
 pub mod service;
 pub mod storage;
 mod tasks;
+pub mod vault;
 use std::marker::PhantomData;
 
 const LOG_TARGET: &str = "wallet::output_manager_service::initializer";
@@ -29,7 +29,7 @@ use log::*;
 use rand::{rngs::OsRng, RngCore};
 use tari_common_types::{
     transaction::TxId,
-    types::{BlockHash, HashOutput, PrivateKey, PublicKey},
+    types::{BlockHash, Commitment, HashOutput, PrivateKey, PublicKey},
 };
 use tari_comms::{types::CommsPublicKey, NodeIdentity};
 use tari_core::{
@@ -38,6 +38,7 @@ use tari_core::{
     proto::base_node::FetchMatchingUtxos,
     transactions::{
         fee::Fee,
+        htlc::ShaHtlcScript,
         tari_amount::MicroTari,
         transaction_components::{
             KernelFeatures,
@@ -48,7 +49,11 @@ use tari_core::{
             UnblindedOutput,
             UnblindedOutputBuilder,
         },
-        transaction_protocol::{sender::TransactionSenderMessage, RewindData},
+        transaction_protocol::{
+            multisig::{multisig_input_data, multisig_script},
+            sender::TransactionSenderMessage,
+            RewindData,
+        },
         CoinbaseBuilder,
         CryptoFactories,
         ReceiverTransactionProtocol,
@@ -77,17 +82,20 @@ use crate::{
             OutputManagerEventSender,
             OutputManagerRequest,
             OutputManagerResponse,
+            PaymentRecipient,
             PublicRewindKeys,
             RecoveredOutput,
         },
+        htlc::fetch_revealed_pre_images,
         recovery::StandardUtxoRecoverer,
         resources::{OutputManagerKeyManagerBranch, OutputManagerResources},
         storage::{
             database::{OutputManagerBackend, OutputManagerDatabase},
-            models::{DbUnblindedOutput, KnownOneSidedPaymentScript, SpendingPriority},
+            models::{DbUnblindedOutput, KnownOneSidedPaymentScript, SpendingPriority, WatchOnlyKey, WatchOnlyOutput},
             OutputStatus,
         },
         tasks::TxoValidationTask,
+        vault::{VaultOutput, VaultScript},
     },
     types::HashDigest,
 };
@@ -454,10 +462,146 @@ where
                 .create_htlc_refund_transaction(output, fee_per_gram)
                 .await
                 .map(OutputManagerResponse::ClaimHtlcTransaction),
+            OutputManagerRequest::FetchHtlcPreImage(output) => self
+                .fetch_htlc_pre_image(output)
+                .await
+                .map(OutputManagerResponse::HtlcPreImage),
             OutputManagerRequest::GetOutputStatusesByTxId(tx_id) => {
                 let output_statuses_by_tx_id = self.get_output_status_by_tx_id(tx_id)?;
                 Ok(OutputManagerResponse::OutputStatusesByTxId(output_statuses_by_tx_id))
             },
+            OutputManagerRequest::CreateVaultTransaction {
+                tx_id,
+                amount,
+                unlock_height,
+                recovery_public_key,
+                fee_per_gram,
+                message,
+            } => self
+                .create_vault_transaction(tx_id, amount, unlock_height, recovery_public_key, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::VaultTransaction),
+            OutputManagerRequest::CreateVaultRecoveryTransaction {
+                output_hash,
+                recovery_private_key,
+                fee_per_gram,
+            } => self
+                .create_vault_recovery_transaction(output_hash, recovery_private_key, fee_per_gram)
+                .await
+                .map(OutputManagerResponse::VaultRecoveryTransaction),
+            OutputManagerRequest::GetVaultOutputs => {
+                self.fetch_vault_outputs().map(OutputManagerResponse::VaultOutputs)
+            },
+            OutputManagerRequest::CreatePayToManyTransaction {
+                tx_id,
+                recipients,
+                fee_per_gram,
+                message,
+            } => self
+                .create_pay_to_many_transaction(tx_id, recipients, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::PayToManyTransaction),
+            OutputManagerRequest::CreateMultisigFundingTransaction {
+                tx_id,
+                amount,
+                spending_key,
+                script_private_key,
+                fee_per_gram,
+                message,
+            } => self
+                .create_multisig_funding_transaction(
+                    tx_id,
+                    amount,
+                    spending_key,
+                    script_private_key,
+                    fee_per_gram,
+                    message,
+                )
+                .await
+                .map(|ct| OutputManagerResponse::MultisigFundingTransaction(Box::new(ct))),
+            OutputManagerRequest::CreateEvenCoinSplit {
+                split_count,
+                fee_per_gram,
+                lock_height,
+            } => self
+                .create_even_coin_split(split_count, fee_per_gram, lock_height)
+                .await
+                .map(OutputManagerResponse::Transaction),
+            OutputManagerRequest::CreateCoinJoin {
+                dust_threshold,
+                max_inputs,
+                fee_per_gram,
+            } => self
+                .create_coin_join(dust_threshold, max_inputs, fee_per_gram)
+                .await
+                .map(OutputManagerResponse::Transaction),
+            OutputManagerRequest::PrepareFeeBumpTransaction {
+                previous_tx_id,
+                tx_id,
+                amount,
+                previous_fee,
+                fee_per_gram,
+                message,
+            } => self
+                .prepare_fee_bump_transaction(previous_tx_id, tx_id, amount, previous_fee, fee_per_gram, message)
+                .await
+                .map(OutputManagerResponse::TransactionToSend),
+            OutputManagerRequest::ReserveOfflineTransactionInputs {
+                tx_id,
+                amount,
+                fee_per_gram,
+                script,
+            } => self
+                .reserve_offline_transaction_inputs(tx_id, amount, fee_per_gram, script)
+                .await
+                .map(OutputManagerResponse::OfflineTransactionInputsReserved),
+            OutputManagerRequest::BuildOfflineTransaction {
+                tx_id,
+                inputs,
+                amount,
+                fee_per_gram,
+                lock_height,
+                message,
+                script,
+                sender_offset_private_key,
+            } => self
+                .build_offline_transaction(
+                    tx_id,
+                    inputs,
+                    amount,
+                    fee_per_gram,
+                    lock_height,
+                    message,
+                    script,
+                    *sender_offset_private_key,
+                )
+                .await
+                .map(OutputManagerResponse::TransactionToSend),
+            OutputManagerRequest::AddWatchOnlyKey(key) => self
+                .add_watch_only_key(*key)
+                .map(|_| OutputManagerResponse::WatchOnlyKeyAdded),
+            OutputManagerRequest::RemoveWatchOnlyKey(spend_public_key) => self
+                .resources
+                .db
+                .remove_watch_only_key(&spend_public_key)
+                .map(|_| OutputManagerResponse::WatchOnlyKeyRemoved)
+                .map_err(OutputManagerError::from),
+            OutputManagerRequest::GetWatchOnlyKeys => Ok(OutputManagerResponse::WatchOnlyKeys(
+                self.resources.db.fetch_watch_only_keys()?,
+            )),
+            OutputManagerRequest::ScanOutputsForWatchOnlyKeys {
+                outputs,
+                mined_height,
+                mined_in_block,
+            } => self
+                .scan_outputs_for_watch_only_keys(outputs, mined_height, mined_in_block)
+                .map(OutputManagerResponse::WatchOnlyOutputs),
+            OutputManagerRequest::GetWatchOnlyOutputs => Ok(OutputManagerResponse::WatchOnlyOutputs(
+                self.resources.db.fetch_watch_only_outputs()?,
+            )),
+            OutputManagerRequest::GetWatchOnlyBalances => self
+                .get_watch_only_balances()
+                .map(OutputManagerResponse::WatchOnlyBalances),
         }
     }
 
@@ -989,6 +1133,267 @@ where
         Ok(stp)
     }
 
+    /// Rebuilds the pending outbound transaction `previous_tx_id` as a new transaction `tx_id` that spends exactly the
+    /// same inputs with a higher fee. Nothing is changed unless the new transaction can be built, in which case the
+    /// previous transaction's outputs are cancelled and its inputs are encumbered to the new transaction in one step.
+    async fn prepare_fee_bump_transaction(
+        &mut self,
+        previous_tx_id: TxId,
+        tx_id: TxId,
+        amount: MicroTari,
+        previous_fee: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError> {
+        let inputs = self
+            .resources
+            .db
+            .fetch_outputs_by_tx_id(previous_tx_id)?
+            .into_iter()
+            .filter(|o| {
+                matches!(
+                    o.status,
+                    OutputStatus::EncumberedToBeSpent | OutputStatus::ShortTermEncumberedToBeSpent
+                )
+            })
+            .collect::<Vec<_>>();
+        if inputs.is_empty() {
+            return Err(OutputManagerError::FeeBumpNotPossible(format!(
+                "transaction {} has no pending inputs",
+                previous_tx_id
+            )));
+        }
+        if inputs.iter().any(|o| o.unblinded_output.features.unique_id.is_some()) {
+            return Err(OutputManagerError::FeeBumpNotPossible(
+                "token transactions cannot be rebuilt".to_string(),
+            ));
+        }
+
+        let recipient_script = script!(Nop);
+        let recipient_covenant = Covenant::default();
+        let fee_calc = self.get_fee_calc();
+        let metadata_byte_size = fee_calc.weighting().round_up_metadata_size(
+            OutputFeatures::default().consensus_encode_exact_size() +
+                recipient_script.consensus_encode_exact_size() +
+                recipient_covenant.consensus_encode_exact_size(),
+        );
+        let total_value = inputs.iter().map(|o| o.unblinded_output.value).sum::<MicroTari>();
+        let fee_without_change = fee_calc.calculate(fee_per_gram, 1, inputs.len(), 1, metadata_byte_size);
+        let fee_with_change = fee_calc.calculate(fee_per_gram, 1, inputs.len(), 2, 2 * metadata_byte_size);
+        if fee_without_change <= previous_fee {
+            return Err(OutputManagerError::FeeBumpNotPossible(format!(
+                "the new fee ({}) must be higher than the previous fee ({})",
+                fee_without_change, previous_fee
+            )));
+        }
+        if total_value < amount + fee_without_change {
+            return Err(OutputManagerError::NotEnoughFunds);
+        }
+        let requires_change_output = total_value > amount + fee_with_change;
+
+        let mut builder = SenderTransactionProtocol::builder(1, self.resources.consensus_constants.clone());
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(PrivateKey::random(&mut OsRng))
+            .with_private_nonce(PrivateKey::random(&mut OsRng))
+            .with_amount(0, amount)
+            .with_recipient_data(
+                0,
+                recipient_script,
+                PrivateKey::random(&mut OsRng),
+                OutputFeatures::default(),
+                PrivateKey::random(&mut OsRng),
+                recipient_covenant,
+            )
+            .with_message(message)
+            .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount)
+            .with_tx_id(tx_id);
+        for uo in &inputs {
+            builder.with_input(
+                uo.unblinded_output
+                    .as_transaction_input(&self.resources.factories.commitment)?,
+                uo.unblinded_output.clone(),
+            );
+        }
+        if requires_change_output {
+            let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
+            builder.with_change_secret(spending_key);
+            builder.with_rewindable_outputs(self.resources.rewind_data.clone());
+            builder.with_change_script(
+                script!(Nop),
+                inputs!(PublicKey::from_secret_key(&script_private_key)),
+                script_private_key,
+            );
+        }
+
+        let stp = builder
+            .build::<HashDigest>(
+                &self.resources.factories,
+                None,
+                self.last_seen_tip_height.unwrap_or(u64::MAX),
+            )
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        let mut change_output = Vec::<DbUnblindedOutput>::new();
+        if let Some(unblinded_output) = stp.get_change_unblinded_output()? {
+            change_output.push(DbUnblindedOutput::rewindable_from_unblinded_output(
+                unblinded_output,
+                &self.resources.factories,
+                &self.resources.rewind_data.clone(),
+                None,
+                None,
+            )?);
+        }
+
+        self.resources.db.cancel_pending_transaction_outputs(previous_tx_id)?;
+        self.resources.db.encumber_outputs(tx_id, inputs, change_output)?;
+
+        debug!(
+            target: LOG_TARGET,
+            "Prepared fee bump transaction (TxId: {}) to replace TxId: {}", tx_id, previous_tx_id
+        );
+
+        Ok(stp)
+    }
+
+    /// Selects and encumbers the inputs of a payment that is built and signed by an offline wallet, and returns their
+    /// commitments. The change output is created by the offline wallet, so none is recorded here. The encumbrance is
+    /// short term, so the inputs are released on restart if the signed transaction is never broadcast.
+    async fn reserve_offline_transaction_inputs(
+        &mut self,
+        tx_id: TxId,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        recipient_script: TariScript,
+    ) -> Result<Vec<Commitment>, OutputManagerError> {
+        let metadata_byte_size = self
+            .resources
+            .consensus_constants
+            .transaction_weight()
+            .round_up_metadata_size(
+                OutputFeatures::default().consensus_encode_exact_size() +
+                    recipient_script.consensus_encode_exact_size() +
+                    Covenant::default().consensus_encode_exact_size(),
+            );
+        let input_selection = self
+            .select_utxos(amount, fee_per_gram, 1, metadata_byte_size, None, None, None)
+            .await?;
+        let commitments = input_selection.iter().map(|o| o.commitment.clone()).collect();
+        self.resources
+            .db
+            .encumber_outputs(tx_id, input_selection.into_selected(), Vec::new())?;
+
+        debug!(
+            target: LOG_TARGET,
+            "Reserved inputs of offline transaction (TxId: {})", tx_id
+        );
+        Ok(commitments)
+    }
+
+    /// Builds the payment of an offline transaction from exactly the outputs with the given commitments. This runs on
+    /// the offline wallet, which never broadcasts the transaction, so nothing is written to the database.
+    async fn build_offline_transaction(
+        &mut self,
+        tx_id: TxId,
+        inputs: Vec<Commitment>,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        lock_height: u64,
+        message: String,
+        recipient_script: TariScript,
+        sender_offset_private_key: PrivateKey,
+    ) -> Result<SenderTransactionProtocol, OutputManagerError> {
+        if inputs.is_empty() {
+            return Err(OutputManagerError::OfflineTransactionNotPossible(
+                "the transaction has no inputs".to_string(),
+            ));
+        }
+        if inputs.iter().enumerate().any(|(i, c)| inputs[..i].contains(c)) {
+            return Err(OutputManagerError::OfflineTransactionNotPossible(
+                "the transaction spends an output more than once".to_string(),
+            ));
+        }
+        let unspent = self.resources.db.fetch_all_unspent_outputs()?;
+        let inputs = inputs
+            .iter()
+            .map(|commitment| {
+                unspent
+                    .iter()
+                    .find(|o| o.commitment == *commitment)
+                    .cloned()
+                    .ok_or_else(|| {
+                        OutputManagerError::OfflineTransactionNotPossible(format!(
+                            "{} is not an unspent output of this wallet",
+                            commitment.to_hex()
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let recipient_covenant = Covenant::default();
+        let fee_calc = self.get_fee_calc();
+        let metadata_byte_size = fee_calc.weighting().round_up_metadata_size(
+            OutputFeatures::default().consensus_encode_exact_size() +
+                recipient_script.consensus_encode_exact_size() +
+                recipient_covenant.consensus_encode_exact_size(),
+        );
+        let total_value = inputs.iter().map(|o| o.unblinded_output.value).sum::<MicroTari>();
+        let fee_without_change = fee_calc.calculate(fee_per_gram, 1, inputs.len(), 1, metadata_byte_size);
+        let fee_with_change = fee_calc.calculate(fee_per_gram, 1, inputs.len(), 2, 2 * metadata_byte_size);
+        if total_value < amount + fee_without_change {
+            return Err(OutputManagerError::NotEnoughFunds);
+        }
+        let requires_change_output = total_value > amount + fee_with_change;
+
+        let mut builder = SenderTransactionProtocol::builder(1, self.resources.consensus_constants.clone());
+        builder
+            .with_lock_height(lock_height)
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(PrivateKey::random(&mut OsRng))
+            .with_private_nonce(PrivateKey::random(&mut OsRng))
+            .with_amount(0, amount)
+            .with_recipient_data(
+                0,
+                recipient_script,
+                sender_offset_private_key,
+                OutputFeatures::default(),
+                PrivateKey::random(&mut OsRng),
+                recipient_covenant,
+            )
+            .with_message(message)
+            .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount)
+            .with_tx_id(tx_id);
+        for uo in &inputs {
+            builder.with_input(
+                uo.unblinded_output
+                    .as_transaction_input(&self.resources.factories.commitment)?,
+                uo.unblinded_output.clone(),
+            );
+        }
+        if requires_change_output {
+            let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
+            builder.with_change_secret(spending_key);
+            builder.with_rewindable_outputs(self.resources.rewind_data.clone());
+            builder.with_change_script(
+                script!(Nop),
+                inputs!(PublicKey::from_secret_key(&script_private_key)),
+                script_private_key,
+            );
+        }
+
+        let stp = builder
+            .build::<HashDigest>(
+                &self.resources.factories,
+                None,
+                self.last_seen_tip_height.unwrap_or(u64::MAX),
+            )
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+        debug!(target: LOG_TARGET, "Built offline transaction (TxId: {})", tx_id);
+
+        Ok(stp)
+    }
+
     /// Request a Coinbase transaction for a specific block height. All existing pending transactions with
     /// this blockheight will be cancelled.
     /// The key will be derived from the coinbase specific keychain using the blockheight as an index. The coinbase
@@ -1586,18 +1991,7 @@ where
             "Select UTXOs and estimate coin split transaction fee."
         );
         let output_count = split_count;
-        let script = script!(Nop);
-        let covenant = Covenant::default();
-        let output_features_estimate = OutputFeatures::default();
-        let metadata_byte_size = self
-            .resources
-            .consensus_constants
-            .transaction_weight()
-            .round_up_metadata_size(
-                output_features_estimate.consensus_encode_exact_size() +
-                    script.consensus_encode_exact_size() +
-                    covenant.consensus_encode_exact_size(),
-            );
+        let metadata_byte_size = self.self_output_metadata_size();
 
         let total_split_amount = amount_per_split * split_count as u64;
         let input_selection = self
@@ -1637,50 +2031,649 @@ where
         trace!(target: LOG_TARGET, "Add outputs to coin split transaction.");
         let mut outputs: Vec<DbUnblindedOutput> = Vec::with_capacity(output_count);
         for _ in 0..output_count {
-            let output_amount = amount_per_split;
+            let (utxo, sender_offset_private_key) = self.create_self_output(amount_per_split).await?;
+            builder
+                .with_output(utxo.unblinded_output.clone(), sender_offset_private_key)
+                .map_err(|e| OutputManagerError::BuildError(e.message))?;
+            outputs.push(utxo);
+        }
 
+        if input_selection.requires_change_output() {
             let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
-            let recovery_byte = self.calculate_recovery_byte(spending_key.clone(), output_amount.as_u64(), true)?;
-            let output_features = OutputFeatures {
-                recovery_byte,
-                ..Default::default()
-            };
-
-            let sender_offset_private_key = PrivateKey::random(&mut OsRng);
-            let sender_offset_public_key = PublicKey::from_secret_key(&sender_offset_private_key);
-            let metadata_signature = TransactionOutput::create_final_metadata_signature(
-                TransactionOutputVersion::get_current_version(),
-                output_amount,
-                &spending_key,
-                &script,
-                &output_features,
-                &sender_offset_private_key,
-                &covenant,
-            )?;
-            let utxo = DbUnblindedOutput::rewindable_from_unblinded_output(
-                UnblindedOutput::new_current_version(
-                    output_amount,
-                    spending_key,
-                    output_features,
-                    script.clone(),
-                    inputs!(PublicKey::from_secret_key(&script_private_key)),
-                    script_private_key,
-                    sender_offset_public_key,
-                    metadata_signature,
-                    0,
-                    covenant.clone(),
-                ),
+            builder.with_change_secret(spending_key);
+            builder.with_rewindable_outputs(self.resources.rewind_data.clone());
+            builder.with_change_script(
+                script!(Nop),
+                inputs!(PublicKey::from_secret_key(&script_private_key)),
+                script_private_key,
+            );
+        }
+
+        let factories = CryptoFactories::default();
+        let mut stp = builder
+            .build::<HashDigest>(
+                &self.resources.factories,
+                None,
+                self.last_seen_tip_height.unwrap_or(u64::MAX),
+            )
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+        // The Transaction Protocol built successfully so we will pull the unspent outputs out of the unspent list and
+        // store them until the transaction times out OR is confirmed
+        let tx_id = stp.get_tx_id()?;
+        trace!(
+            target: LOG_TARGET,
+            "Encumber coin split transaction ({}) outputs.",
+            tx_id
+        );
+
+        if input_selection.requires_change_output() {
+            let unblinded_output = stp.get_change_unblinded_output()?.ok_or_else(|| {
+                OutputManagerError::BuildError(
+                    "There should be a change output metadata signature available".to_string(),
+                )
+            })?;
+            outputs.push(DbUnblindedOutput::rewindable_from_unblinded_output(
+                unblinded_output,
+                &self.resources.factories,
+                &self.resources.rewind_data.clone(),
+                None,
+                None,
+            )?);
+        }
+
+        self.resources
+            .db
+            .encumber_outputs(tx_id, input_selection.into_selected(), outputs)?;
+        self.confirm_encumberance(tx_id)?;
+        trace!(target: LOG_TARGET, "Finalize coin split transaction ({}).", tx_id);
+        stp.finalize(
+            KernelFeatures::empty(),
+            &factories,
+            None,
+            self.last_seen_tip_height.unwrap_or(u64::MAX),
+        )?;
+        let tx = stp.take_transaction()?;
+        Ok((tx_id, tx, utxos_total_value))
+    }
+
+    /// Splits the largest spendable UTXO into `split_count` outputs of equal value, after paying the fee. Any
+    /// remainder from the division is added to the first output so that no change output is needed.
+    async fn create_even_coin_split(
+        &mut self,
+        split_count: usize,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+    ) -> Result<(TxId, Transaction, MicroTari), OutputManagerError> {
+        if split_count < 2 {
+            return Err(OutputManagerError::InvalidCoinSplit(
+                "The split count must be at least 2".to_string(),
+            ));
+        }
+        let tip_height = self
+            .base_node_service
+            .get_chain_metadata()
+            .await?
+            .map(|metadata| metadata.height_of_longest_chain());
+        let largest = self
+            .resources
+            .db
+            .fetch_unspent_outputs_for_spending(UTXOSelectionStrategy::Largest, MicroTari::from(0), tip_height)?
+            .into_iter()
+            .max_by_key(|o| o.unblinded_output.value)
+            .ok_or(OutputManagerError::NotEnoughFunds)?;
+
+        let fee = self.get_fee_calc().calculate(
+            fee_per_gram,
+            1,
+            1,
+            split_count,
+            split_count * self.self_output_metadata_size(),
+        );
+        let spendable = largest
+            .unblinded_output
+            .value
+            .checked_sub(fee)
+            .ok_or(OutputManagerError::NotEnoughFunds)?
+            .as_u64();
+        let amount_per_split = spendable / split_count as u64;
+        if amount_per_split == 0 {
+            return Err(OutputManagerError::InvalidCoinSplit(format!(
+                "The largest UTXO ({}) is too small to split into {} outputs",
+                largest.unblinded_output.value, split_count
+            )));
+        }
+        let mut amounts = vec![MicroTari::from(amount_per_split); split_count];
+        amounts[0] += MicroTari::from(spendable % split_count as u64);
+
+        debug!(
+            target: LOG_TARGET,
+            "Splitting UTXO of {} into {} outputs of {} (fee: {})",
+            largest.unblinded_output.value,
+            split_count,
+            MicroTari::from(amount_per_split),
+            fee
+        );
+        self.create_self_spend_transaction(vec![largest], amounts, fee_per_gram, lock_height)
+            .await
+    }
+
+    /// Consolidates up to `max_inputs` spendable UTXOs that are each worth less than `dust_threshold` into a single
+    /// output, smallest first.
+    async fn create_coin_join(
+        &mut self,
+        dust_threshold: MicroTari,
+        max_inputs: usize,
+        fee_per_gram: MicroTari,
+    ) -> Result<(TxId, Transaction, MicroTari), OutputManagerError> {
+        let tip_height = self
+            .base_node_service
+            .get_chain_metadata()
+            .await?
+            .map(|metadata| metadata.height_of_longest_chain());
+        let mut dust = self
+            .resources
+            .db
+            .fetch_unspent_outputs_for_spending(UTXOSelectionStrategy::Smallest, MicroTari::from(0), tip_height)?
+            .into_iter()
+            .filter(|o| o.unblinded_output.value < dust_threshold)
+            .collect::<Vec<_>>();
+        dust.sort_by_key(|o| o.unblinded_output.value);
+        dust.truncate(max_inputs);
+        if dust.len() < 2 {
+            return Err(OutputManagerError::NotEnoughOutputsToJoin(dust.len()));
+        }
+
+        let fee = self
+            .get_fee_calc()
+            .calculate(fee_per_gram, 1, dust.len(), 1, self.self_output_metadata_size());
+        let total = dust.iter().map(|o| o.unblinded_output.value).sum::<MicroTari>();
+        let amount = match total.checked_sub(fee) {
+            Some(v) if v > MicroTari::from(0) => v,
+            _ => return Err(OutputManagerError::NotEnoughFunds),
+        };
+
+        debug!(
+            target: LOG_TARGET,
+            "Joining {} UTXOs below {} into one output of {} (fee: {})",
+            dust.len(),
+            dust_threshold,
+            amount,
+            fee
+        );
+        self.create_self_spend_transaction(dust, vec![amount], fee_per_gram, None)
+            .await
+    }
+
+    /// Builds, encumbers and finalizes a transaction that spends `inputs` into new outputs of `output_amounts` owned
+    /// by this wallet. The amounts must account for the fee exactly, as no change output is added.
+    async fn create_self_spend_transaction(
+        &mut self,
+        inputs: Vec<DbUnblindedOutput>,
+        output_amounts: Vec<MicroTari>,
+        fee_per_gram: MicroTari,
+        lock_height: Option<u64>,
+    ) -> Result<(TxId, Transaction, MicroTari), OutputManagerError> {
+        let mut builder = SenderTransactionProtocol::builder(0, self.resources.consensus_constants.clone());
+        builder
+            .with_lock_height(lock_height.unwrap_or(0))
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(PrivateKey::random(&mut OsRng))
+            .with_private_nonce(PrivateKey::random(&mut OsRng))
+            .with_rewindable_outputs(self.resources.rewind_data.clone());
+
+        for uo in &inputs {
+            builder.with_input(
+                uo.unblinded_output
+                    .as_transaction_input(&self.resources.factories.commitment)?,
+                uo.unblinded_output.clone(),
+            );
+        }
+        let utxos_total_value = inputs.iter().map(|uo| uo.unblinded_output.value).sum::<MicroTari>();
+
+        let mut outputs = Vec::with_capacity(output_amounts.len());
+        for amount in output_amounts {
+            let (utxo, sender_offset_private_key) = self.create_self_output(amount).await?;
+            builder
+                .with_output(utxo.unblinded_output.clone(), sender_offset_private_key)
+                .map_err(|e| OutputManagerError::BuildError(e.message))?;
+            outputs.push(utxo);
+        }
+
+        let mut stp = builder
+            .build::<HashDigest>(
+                &self.resources.factories,
+                None,
+                self.last_seen_tip_height.unwrap_or(u64::MAX),
+            )
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+        let tx_id = stp.get_tx_id()?;
+        self.resources.db.encumber_outputs(tx_id, inputs, outputs)?;
+        self.confirm_encumberance(tx_id)?;
+        stp.finalize(
+            KernelFeatures::empty(),
+            &self.resources.factories,
+            None,
+            self.last_seen_tip_height.unwrap_or(u64::MAX),
+        )?;
+        let tx = stp.take_transaction()?;
+        Ok((tx_id, tx, utxos_total_value))
+    }
+
+    /// Creates a rewindable `Nop` script output of `amount` that belongs to this wallet, returning it with the sender
+    /// offset private key needed to add it to a transaction.
+    async fn create_self_output(
+        &mut self,
+        amount: MicroTari,
+    ) -> Result<(DbUnblindedOutput, PrivateKey), OutputManagerError> {
+        let script = script!(Nop);
+        let covenant = Covenant::default();
+        let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
+        let recovery_byte = self.calculate_recovery_byte(spending_key.clone(), amount.as_u64(), true)?;
+        let output_features = OutputFeatures {
+            recovery_byte,
+            ..Default::default()
+        };
+
+        let sender_offset_private_key = PrivateKey::random(&mut OsRng);
+        let sender_offset_public_key = PublicKey::from_secret_key(&sender_offset_private_key);
+        let metadata_signature = TransactionOutput::create_final_metadata_signature(
+            TransactionOutputVersion::get_current_version(),
+            amount,
+            &spending_key,
+            &script,
+            &output_features,
+            &sender_offset_private_key,
+            &covenant,
+        )?;
+        let utxo = DbUnblindedOutput::rewindable_from_unblinded_output(
+            UnblindedOutput::new_current_version(
+                amount,
+                spending_key,
+                output_features,
+                script,
+                inputs!(PublicKey::from_secret_key(&script_private_key)),
+                script_private_key,
+                sender_offset_public_key,
+                metadata_signature,
+                0,
+                covenant,
+            ),
+            &self.resources.factories,
+            &self.resources.rewind_data.clone(),
+            None,
+            None,
+        )?;
+        Ok((utxo, sender_offset_private_key))
+    }
+
+    /// The rounded up metadata size of an output created by `create_self_output`
+    fn self_output_metadata_size(&self) -> usize {
+        self.resources
+            .consensus_constants
+            .transaction_weight()
+            .round_up_metadata_size(
+                OutputFeatures::default().consensus_encode_exact_size() +
+                    script!(Nop).consensus_encode_exact_size() +
+                    Covenant::default().consensus_encode_exact_size(),
+            )
+    }
+
+    async fn fetch_htlc_pre_image(&mut self, output_hash: HashOutput) -> Result<Option<PublicKey>, OutputManagerError> {
+        let spent_output = self
+            .resources
+            .db
+            .fetch_spent_outputs()?
+            .into_iter()
+            .chain(self.resources.db.fetch_mined_unspent_outputs()?)
+            .find(|o| o.hash == output_hash);
+        let output = match spent_output {
+            Some(output) => output,
+            None => return Ok(None),
+        };
+        if ShaHtlcScript::from_script(&output.unblinded_output.script).is_none() {
+            return Err(OutputManagerError::NotAnHtlcOutput);
+        }
+        let block_hash = match output.marked_deleted_in_block.clone() {
+            Some(block_hash) => block_hash,
+            None => return Ok(None),
+        };
+
+        let mut client = self
+            .resources
+            .connectivity
+            .obtain_base_node_wallet_rpc_client()
+            .await
+            .ok_or_else(|| {
+                OutputManagerError::InvalidResponseError("Could not connect to base node rpc client".to_string())
+            })?;
+        let mut pre_images = fetch_revealed_pre_images(&mut client, block_hash, &[output]).await?;
+        Ok(pre_images.pop().map(|(_, pre_image)| pre_image))
+    }
+
+    async fn fetch_outputs_from_node(
+        &mut self,
+        hashes: Vec<HashOutput>,
+    ) -> Result<Vec<TransactionOutput>, OutputManagerError> {
+        // lets get the output from the blockchain
+        let req = FetchMatchingUtxos { output_hashes: hashes };
+        let results: Vec<TransactionOutput> = self
+            .resources
+            .connectivity
+            .obtain_base_node_wallet_rpc_client()
+            .await
+            .ok_or_else(|| {
+                OutputManagerError::InvalidResponseError("Could not connect to base node rpc client".to_string())
+            })?
+            .fetch_matching_utxos(req)
+            .await?
+            .outputs
+            .into_iter()
+            .filter_map(|o| match o.try_into() {
+                Ok(output) => Some(output),
+                _ => None,
+            })
+            .collect();
+        Ok(results)
+    }
+
+    pub async fn create_claim_sha_atomic_swap_transaction(
+        &mut self,
+        output: TransactionOutput,
+        pre_image: PublicKey,
+        fee_per_gram: MicroTari,
+    ) -> Result<(TxId, MicroTari, MicroTari, Transaction), OutputManagerError> {
+        let htlc = ShaHtlcScript::from_script(&output.script).ok_or(OutputManagerError::NotAnHtlcOutput)?;
+        if &htlc.claim_public_key != self.node_identity.public_key() {
+            return Err(OutputManagerError::InvalidHtlcSpend(
+                "The HTLC cannot be claimed by this wallet".to_string(),
+            ));
+        }
+        if !htlc.is_pre_image(&pre_image) {
+            return Err(OutputManagerError::InvalidHtlcSpend(
+                "The pre-image does not match the HTLC hash lock".to_string(),
+            ));
+        }
+        let spending_key = PrivateKey::from_bytes(
+            CommsPublicKey::shared_secret(
+                self.node_identity.as_ref().secret_key(),
+                &output.sender_offset_public_key,
+            )
+            .as_bytes(),
+        )?;
+        let blinding_key = PrivateKey::from_bytes(&hash_secret_key(&spending_key))?;
+        let rewind_key = PrivateKey::from_bytes(&hash_secret_key(&blinding_key))?;
+        let rewound =
+            output.full_rewind_range_proof(&self.resources.factories.range_proof, &rewind_key, &blinding_key)?;
+
+        let rewound_output = UnblindedOutput::new(
+            output.version,
+            rewound.committed_value,
+            rewound.blinding_factor.clone(),
+            output.features,
+            output.script,
+            ShaHtlcScript::claim_input_data(&pre_image),
+            self.node_identity.as_ref().secret_key().clone(),
+            output.sender_offset_public_key,
+            output.metadata_signature,
+            // Although the technically the script does have a script lock higher than 0, this does not apply to to us
+            // as we are claiming the Hashed part which has a 0 time lock
+            0,
+            output.covenant,
+        );
+        let amount = rewound.committed_value;
+
+        let offset = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
+        let message = "SHA-XTR atomic swap".to_string();
+
+        // Create builder with no recipients (other than ourselves)
+        let mut builder = SenderTransactionProtocol::builder(0, self.resources.consensus_constants.clone());
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(offset.clone())
+            .with_private_nonce(nonce.clone())
+            .with_message(message)
+            .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount)
+            .with_input(
+                rewound_output.as_transaction_input(&self.resources.factories.commitment)?,
+                rewound_output,
+            );
+
+        let mut outputs = Vec::new();
+
+        let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
+        builder.with_change_secret(spending_key);
+        builder.with_rewindable_outputs(self.resources.rewind_data.clone());
+        builder.with_change_script(
+            script!(Nop),
+            inputs!(PublicKey::from_secret_key(&script_private_key)),
+            script_private_key,
+        );
+
+        let factories = CryptoFactories::default();
+        let mut stp = builder
+            .build::<HashDigest>(
+                &self.resources.factories,
+                None,
+                self.last_seen_tip_height.unwrap_or(u64::MAX),
+            )
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        let tx_id = stp.get_tx_id()?;
+
+        let unblinded_output = stp.get_change_unblinded_output()?.ok_or_else(|| {
+            OutputManagerError::BuildError("There should be a change output metadata signature available".to_string())
+        })?;
+        let change_output = DbUnblindedOutput::rewindable_from_unblinded_output(
+            unblinded_output,
+            &self.resources.factories,
+            &self.resources.rewind_data,
+            None,
+            None,
+        )?;
+        outputs.push(change_output);
+
+        trace!(target: LOG_TARGET, "Claiming HTLC with transaction ({}).", tx_id);
+        self.resources.db.encumber_outputs(tx_id, Vec::new(), outputs)?;
+        self.confirm_encumberance(tx_id)?;
+        let fee = stp.get_fee_amount()?;
+        trace!(target: LOG_TARGET, "Finalize send-to-self transaction ({}).", tx_id);
+        stp.finalize(
+            KernelFeatures::empty(),
+            &factories,
+            None,
+            self.last_seen_tip_height.unwrap_or(u64::MAX),
+        )?;
+        let tx = stp.take_transaction()?;
+
+        Ok((tx_id, fee, amount - fee, tx))
+    }
+
+    pub async fn create_htlc_refund_transaction(
+        &mut self,
+        output_hash: HashOutput,
+        fee_per_gram: MicroTari,
+    ) -> Result<(TxId, MicroTari, MicroTari, Transaction), OutputManagerError> {
+        let output = self.resources.db.get_unspent_output(output_hash)?.unblinded_output;
+        let htlc = ShaHtlcScript::from_script(&output.script).ok_or(OutputManagerError::NotAnHtlcOutput)?;
+        let tip_height = self.last_seen_tip_height.unwrap_or(0);
+        if tip_height < htlc.lock_height {
+            return Err(OutputManagerError::InvalidHtlcSpend(format!(
+                "The HTLC cannot be refunded before block {} (tip height {})",
+                htlc.lock_height, tip_height
+            )));
+        }
+
+        let amount = output.value;
+
+        let offset = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
+        let message = "SHA-XTR atomic refund".to_string();
+
+        // Create builder with no recipients (other than ourselves)
+        let mut builder = SenderTransactionProtocol::builder(0, self.resources.consensus_constants.clone());
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(offset.clone())
+            .with_private_nonce(nonce.clone())
+            .with_message(message)
+            .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount)
+            .with_input(
+                output.as_transaction_input(&self.resources.factories.commitment)?,
+                output,
+            );
+
+        let mut outputs = Vec::new();
+
+        let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
+        builder.with_change_secret(spending_key);
+        builder.with_rewindable_outputs(self.resources.rewind_data.clone());
+        builder.with_change_script(
+            script!(Nop),
+            inputs!(PublicKey::from_secret_key(&script_private_key)),
+            script_private_key,
+        );
+
+        let factories = CryptoFactories::default();
+        let mut stp = builder
+            .build::<HashDigest>(
                 &self.resources.factories,
-                &self.resources.rewind_data.clone(),
-                None,
                 None,
-            )?;
-            builder
-                .with_output(utxo.unblinded_output.clone(), sender_offset_private_key)
-                .map_err(|e| OutputManagerError::BuildError(e.message))?;
-            outputs.push(utxo);
+                self.last_seen_tip_height.unwrap_or(u64::MAX),
+            )
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        let tx_id = stp.get_tx_id()?;
+
+        let unblinded_output = stp.get_change_unblinded_output()?.ok_or_else(|| {
+            OutputManagerError::BuildError("There should be a change output metadata signature available".to_string())
+        })?;
+
+        let change_output = DbUnblindedOutput::rewindable_from_unblinded_output(
+            unblinded_output,
+            &self.resources.factories,
+            &self.resources.rewind_data,
+            None,
+            None,
+        )?;
+        outputs.push(change_output);
+
+        trace!(target: LOG_TARGET, "Claiming HTLC refund with transaction ({}).", tx_id);
+
+        let fee = stp.get_fee_amount()?;
+
+        stp.finalize(
+            KernelFeatures::empty(),
+            &factories,
+            None,
+            self.last_seen_tip_height.unwrap_or(u64::MAX),
+        )?;
+
+        let tx = stp.take_transaction()?;
+
+        self.resources.db.encumber_outputs(tx_id, Vec::new(), outputs)?;
+        self.confirm_encumberance(tx_id)?;
+        Ok((tx_id, fee, amount - fee, tx))
+    }
+
+    /// Create a transaction that locks `amount` in a vault output belonging to this wallet. The output can be spent by
+    /// this wallet from `unlock_height` onwards, or at any time with the private key of `recovery_public_key`.
+    async fn create_vault_transaction(
+        &mut self,
+        tx_id: TxId,
+        amount: MicroTari,
+        unlock_height: u64,
+        recovery_public_key: PublicKey,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(MicroTari, Transaction), OutputManagerError> {
+        let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
+        let vault = VaultScript::new(
+            unlock_height,
+            PublicKey::from_secret_key(&script_private_key),
+            recovery_public_key,
+        );
+        let script = vault.to_script();
+        let covenant = Covenant::default();
+        let metadata_byte_size = self
+            .resources
+            .consensus_constants
+            .transaction_weight()
+            .round_up_metadata_size(
+                OutputFeatures::default().consensus_encode_exact_size() +
+                    script.consensus_encode_exact_size() +
+                    covenant.consensus_encode_exact_size(),
+            );
+
+        let input_selection = self
+            .select_utxos(amount, fee_per_gram, 1, metadata_byte_size, None, None, None)
+            .await?;
+
+        let offset = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
+        let sender_offset_private_key = PrivateKey::random(&mut OsRng);
+
+        // Create builder with no recipients (other than ourselves)
+        let mut builder = SenderTransactionProtocol::builder(0, self.resources.consensus_constants.clone());
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(offset.clone())
+            .with_private_nonce(nonce.clone())
+            .with_message(message)
+            .with_rewindable_outputs(self.resources.rewind_data.clone())
+            .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount)
+            .with_tx_id(tx_id);
+
+        for uo in input_selection.iter() {
+            builder.with_input(
+                uo.unblinded_output
+                    .as_transaction_input(&self.resources.factories.commitment)?,
+                uo.unblinded_output.clone(),
+            );
         }
 
+        let recovery_byte = self.calculate_recovery_byte(spending_key.clone(), amount.as_u64(), true)?;
+        let output_features = OutputFeatures {
+            recovery_byte,
+            ..Default::default()
+        };
+        let metadata_signature = TransactionOutput::create_final_metadata_signature(
+            TransactionOutputVersion::get_current_version(),
+            amount,
+            &spending_key,
+            &script,
+            &output_features,
+            &sender_offset_private_key,
+            &covenant,
+        )?;
+        // The script lock height stops the owner path from being selected for spending before the vault matures
+        let utxo = DbUnblindedOutput::rewindable_from_unblinded_output(
+            UnblindedOutput::new_current_version(
+                amount,
+                spending_key,
+                output_features,
+                script,
+                VaultScript::owner_input_data(),
+                script_private_key,
+                PublicKey::from_secret_key(&sender_offset_private_key),
+                metadata_signature,
+                unlock_height,
+                covenant,
+            ),
+            &self.resources.factories,
+            &self.resources.rewind_data,
+            None,
+            None,
+        )?;
+        builder
+            .with_output(utxo.unblinded_output.clone(), sender_offset_private_key)
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        let mut outputs = vec![utxo];
+
         if input_selection.requires_change_output() {
             let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
             builder.with_change_secret(spending_key);
@@ -1700,14 +2693,189 @@ where
                 self.last_seen_tip_height.unwrap_or(u64::MAX),
             )
             .map_err(|e| OutputManagerError::BuildError(e.message))?;
-        // The Transaction Protocol built successfully so we will pull the unspent outputs out of the unspent list and
-        // store them until the transaction times out OR is confirmed
-        let tx_id = stp.get_tx_id()?;
+
+        if input_selection.requires_change_output() {
+            let unblinded_output = stp.get_change_unblinded_output()?.ok_or_else(|| {
+                OutputManagerError::BuildError(
+                    "There should be a change output metadata signature available".to_string(),
+                )
+            })?;
+            let change_output = DbUnblindedOutput::rewindable_from_unblinded_output(
+                unblinded_output,
+                &self.resources.factories,
+                &self.resources.rewind_data,
+                None,
+                None,
+            )?;
+            outputs.push(change_output);
+        }
+
         trace!(
             target: LOG_TARGET,
-            "Encumber coin split transaction ({}) outputs.",
-            tx_id
+            "Encumber vault transaction ({}) outputs, unlock height {}.",
+            tx_id,
+            unlock_height
         );
+        self.resources
+            .db
+            .encumber_outputs(tx_id, input_selection.into_selected(), outputs)?;
+        self.confirm_encumberance(tx_id)?;
+        let fee = stp.get_fee_amount()?;
+        stp.finalize(
+            KernelFeatures::empty(),
+            &factories,
+            None,
+            self.last_seen_tip_height.unwrap_or(u64::MAX),
+        )?;
+        let tx = stp.take_transaction()?;
+
+        Ok((fee, tx))
+    }
+
+    /// Create a transaction with a one-sided payment output for each recipient, so that a batch of payments shares
+    /// one kernel and one change output.
+    async fn create_pay_to_many_transaction(
+        &mut self,
+        tx_id: TxId,
+        recipients: Vec<PaymentRecipient>,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(MicroTari, Transaction), OutputManagerError> {
+        if recipients
+            .iter()
+            .any(|r| r.payment_id.len() > REWIND_USER_MESSAGE_LENGTH)
+        {
+            return Err(OutputManagerError::BuildError(format!(
+                "Payment ids can be at most {} bytes",
+                REWIND_USER_MESSAGE_LENGTH
+            )));
+        }
+        let covenant = Covenant::default();
+        let weighting = self.resources.consensus_constants.transaction_weight();
+        let scripts = recipients
+            .iter()
+            .map(|r| script!(PushPubKey(Box::new(r.address.clone()))))
+            .collect::<Vec<_>>();
+        let metadata_byte_size = scripts
+            .iter()
+            .map(|script| {
+                weighting.round_up_metadata_size(
+                    OutputFeatures::default().consensus_encode_exact_size() +
+                        script.consensus_encode_exact_size() +
+                        covenant.consensus_encode_exact_size(),
+                )
+            })
+            .sum();
+        let total_amount = recipients.iter().map(|r| r.amount).sum::<MicroTari>();
+
+        let input_selection = self
+            .select_utxos(
+                total_amount,
+                fee_per_gram,
+                recipients.len(),
+                metadata_byte_size,
+                None,
+                None,
+                None,
+            )
+            .await?;
+
+        let offset = PrivateKey::random(&mut OsRng);
+        let nonce = PrivateKey::random(&mut OsRng);
+
+        // The recipient outputs are built here in full, so the protocol has no interactive recipients
+        let mut builder = SenderTransactionProtocol::builder(0, self.resources.consensus_constants.clone());
+        builder
+            .with_lock_height(0)
+            .with_fee_per_gram(fee_per_gram)
+            .with_offset(offset)
+            .with_private_nonce(nonce)
+            .with_message(message)
+            .with_rewindable_outputs(self.resources.rewind_data.clone())
+            .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount)
+            .with_tx_id(tx_id);
+
+        for uo in input_selection.iter() {
+            builder.with_input(
+                uo.unblinded_output
+                    .as_transaction_input(&self.resources.factories.commitment)?,
+                uo.unblinded_output.clone(),
+            );
+        }
+
+        for (recipient, script) in recipients.into_iter().zip(scripts) {
+            let sender_offset_private_key = PrivateKey::random(&mut OsRng);
+            // The recipient derives the same spending and rewind keys from the Diffie-Hellman shared secret when it
+            // scans for one-sided payments to its public key
+            let spending_key = PrivateKey::from_bytes(
+                CommsPublicKey::shared_secret(&sender_offset_private_key, &recipient.address).as_bytes(),
+            )?;
+            let rewind_blinding_key = PrivateKey::from_bytes(&hash_secret_key(&spending_key))?;
+            let rewind_key = PrivateKey::from_bytes(&hash_secret_key(&rewind_blinding_key))?;
+            let recovery_byte_key = PrivateKey::from_bytes(&hash_secret_key(&rewind_key))?;
+            let mut proof_message = [0u8; REWIND_USER_MESSAGE_LENGTH];
+            proof_message[..recipient.payment_id.len()].copy_from_slice(&recipient.payment_id);
+            let rewind_data = RewindData {
+                rewind_key,
+                rewind_blinding_key,
+                recovery_byte_key,
+                proof_message,
+            };
+
+            let commitment = self
+                .resources
+                .factories
+                .commitment
+                .commit_value(&spending_key, recipient.amount.as_u64());
+            let output_features = OutputFeatures {
+                recovery_byte: OutputFeatures::create_unique_recovery_byte(&commitment, Some(&rewind_data)),
+                ..Default::default()
+            };
+            let metadata_signature = TransactionOutput::create_final_metadata_signature(
+                TransactionOutputVersion::get_current_version(),
+                recipient.amount,
+                &spending_key,
+                &script,
+                &output_features,
+                &sender_offset_private_key,
+                &covenant,
+            )?;
+            // Only the recipient knows its script key, which is not needed to build the output
+            let output = UnblindedOutput::new_current_version(
+                recipient.amount,
+                spending_key,
+                output_features,
+                script,
+                inputs!(PublicKey::default()),
+                PrivateKey::default(),
+                PublicKey::from_secret_key(&sender_offset_private_key),
+                metadata_signature,
+                0,
+                covenant.clone(),
+            );
+            builder
+                .with_rewindable_output(output, sender_offset_private_key, rewind_data)
+                .map_err(|e| OutputManagerError::BuildError(e.message))?;
+        }
+
+        let mut outputs = Vec::new();
+        if input_selection.requires_change_output() {
+            let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
+            builder.with_change_secret(spending_key);
+            builder.with_change_script(
+                script!(Nop),
+                inputs!(PublicKey::from_secret_key(&script_private_key)),
+                script_private_key,
+            );
+        }
+
+        let mut stp = builder
+            .build::<HashDigest>(
+                &self.resources.factories,
+                None,
+                self.last_seen_tip_height.unwrap_or(u64::MAX),
+            )
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
 
         if input_selection.requires_change_output() {
             let unblinded_output = stp.get_change_unblinded_output()?.ok_or_else(|| {
@@ -1718,7 +2886,7 @@ where
             outputs.push(DbUnblindedOutput::rewindable_from_unblinded_output(
                 unblinded_output,
                 &self.resources.factories,
-                &self.resources.rewind_data.clone(),
+                &self.resources.rewind_data,
                 None,
                 None,
             )?);
@@ -1728,108 +2896,122 @@ where
             .db
             .encumber_outputs(tx_id, input_selection.into_selected(), outputs)?;
         self.confirm_encumberance(tx_id)?;
-        trace!(target: LOG_TARGET, "Finalize coin split transaction ({}).", tx_id);
+        let fee = stp.get_fee_amount()?;
         stp.finalize(
             KernelFeatures::empty(),
-            &factories,
+            &self.resources.factories,
             None,
             self.last_seen_tip_height.unwrap_or(u64::MAX),
         )?;
         let tx = stp.take_transaction()?;
-        Ok((tx_id, tx, utxos_total_value))
-    }
 
-    async fn fetch_outputs_from_node(
-        &mut self,
-        hashes: Vec<HashOutput>,
-    ) -> Result<Vec<TransactionOutput>, OutputManagerError> {
-        // lets get the output from the blockchain
-        let req = FetchMatchingUtxos { output_hashes: hashes };
-        let results: Vec<TransactionOutput> = self
-            .resources
-            .connectivity
-            .obtain_base_node_wallet_rpc_client()
-            .await
-            .ok_or_else(|| {
-                OutputManagerError::InvalidResponseError("Could not connect to base node rpc client".to_string())
-            })?
-            .fetch_matching_utxos(req)
-            .await?
-            .outputs
-            .into_iter()
-            .filter_map(|o| match o.try_into() {
-                Ok(output) => Some(output),
-                _ => None,
-            })
-            .collect();
-        Ok(results)
+        Ok((fee, tx))
     }
 
-    pub async fn create_claim_sha_atomic_swap_transaction(
+    /// Create a transaction that funds a multisig group output. The group output is rewindable with keys derived from
+    /// its spending key, but it is not added to this wallet's outputs since only the group can spend it.
+    async fn create_multisig_funding_transaction(
         &mut self,
-        output: TransactionOutput,
-        pre_image: PublicKey,
+        tx_id: TxId,
+        amount: MicroTari,
+        spending_key: PrivateKey,
+        script_private_key: PrivateKey,
         fee_per_gram: MicroTari,
-    ) -> Result<(TxId, MicroTari, MicroTari, Transaction), OutputManagerError> {
-        let spending_key = PrivateKey::from_bytes(
-            CommsPublicKey::shared_secret(
-                self.node_identity.as_ref().secret_key(),
-                &output.sender_offset_public_key,
-            )
-            .as_bytes(),
-        )?;
-        let blinding_key = PrivateKey::from_bytes(&hash_secret_key(&spending_key))?;
-        let rewind_key = PrivateKey::from_bytes(&hash_secret_key(&blinding_key))?;
-        let rewound =
-            output.full_rewind_range_proof(&self.resources.factories.range_proof, &rewind_key, &blinding_key)?;
+        message: String,
+    ) -> Result<(MicroTari, Transaction, TransactionOutput), OutputManagerError> {
+        let script = multisig_script();
+        let covenant = Covenant::default();
+        let metadata_byte_size = self
+            .resources
+            .consensus_constants
+            .transaction_weight()
+            .round_up_metadata_size(
+                OutputFeatures::default().consensus_encode_exact_size() +
+                    script.consensus_encode_exact_size() +
+                    covenant.consensus_encode_exact_size(),
+            );
 
-        let rewound_output = UnblindedOutput::new(
-            output.version,
-            rewound.committed_value,
-            rewound.blinding_factor.clone(),
-            output.features,
-            output.script,
-            inputs!(pre_image),
-            self.node_identity.as_ref().secret_key().clone(),
-            output.sender_offset_public_key,
-            output.metadata_signature,
-            // Although the technically the script does have a script lock higher than 0, this does not apply to to us
-            // as we are claiming the Hashed part which has a 0 time lock
-            0,
-            output.covenant,
-        );
-        let amount = rewound.committed_value;
+        let input_selection = self
+            .select_utxos(amount, fee_per_gram, 1, metadata_byte_size, None, None, None)
+            .await?;
 
         let offset = PrivateKey::random(&mut OsRng);
         let nonce = PrivateKey::random(&mut OsRng);
-        let message = "SHA-XTR atomic swap".to_string();
+        let sender_offset_private_key = PrivateKey::random(&mut OsRng);
 
-        // Create builder with no recipients (other than ourselves)
         let mut builder = SenderTransactionProtocol::builder(0, self.resources.consensus_constants.clone());
         builder
             .with_lock_height(0)
             .with_fee_per_gram(fee_per_gram)
-            .with_offset(offset.clone())
-            .with_private_nonce(nonce.clone())
+            .with_offset(offset)
+            .with_private_nonce(nonce)
             .with_message(message)
+            .with_rewindable_outputs(self.resources.rewind_data.clone())
             .with_prevent_fee_gt_amount(self.resources.config.prevent_fee_gt_amount)
-            .with_input(
-                rewound_output.as_transaction_input(&self.resources.factories.commitment)?,
-                rewound_output,
-            );
+            .with_tx_id(tx_id);
 
-        let mut outputs = Vec::new();
+        for uo in input_selection.iter() {
+            builder.with_input(
+                uo.unblinded_output
+                    .as_transaction_input(&self.resources.factories.commitment)?,
+                uo.unblinded_output.clone(),
+            );
+        }
 
-        let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
-        builder.with_change_secret(spending_key);
-        builder.with_rewindable_outputs(self.resources.rewind_data.clone());
-        builder.with_change_script(
-            script!(Nop),
-            inputs!(PublicKey::from_secret_key(&script_private_key)),
+        let rewind_blinding_key = PrivateKey::from_bytes(&hash_secret_key(&spending_key))?;
+        let rewind_key = PrivateKey::from_bytes(&hash_secret_key(&rewind_blinding_key))?;
+        let recovery_byte_key = PrivateKey::from_bytes(&hash_secret_key(&rewind_key))?;
+        let rewind_data = RewindData {
+            rewind_key,
+            rewind_blinding_key,
+            recovery_byte_key,
+            proof_message: [0u8; REWIND_USER_MESSAGE_LENGTH],
+        };
+        let commitment = self
+            .resources
+            .factories
+            .commitment
+            .commit_value(&spending_key, amount.as_u64());
+        let output_features = OutputFeatures {
+            recovery_byte: OutputFeatures::create_unique_recovery_byte(&commitment, Some(&rewind_data)),
+            ..Default::default()
+        };
+        let metadata_signature = TransactionOutput::create_final_metadata_signature(
+            TransactionOutputVersion::get_current_version(),
+            amount,
+            &spending_key,
+            &script,
+            &output_features,
+            &sender_offset_private_key,
+            &covenant,
+        )?;
+        let output = UnblindedOutput::new_current_version(
+            amount,
+            spending_key,
+            output_features,
+            script,
+            multisig_input_data(&script_private_key),
             script_private_key,
+            PublicKey::from_secret_key(&sender_offset_private_key),
+            metadata_signature,
+            0,
+            covenant,
         );
+        builder
+            .with_rewindable_output(output, sender_offset_private_key, rewind_data)
+            .map_err(|e| OutputManagerError::BuildError(e.message))?;
+
+        let mut outputs = Vec::new();
+        if input_selection.requires_change_output() {
+            let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
+            builder.with_change_secret(spending_key);
+            builder.with_change_script(
+                script!(Nop),
+                inputs!(PublicKey::from_secret_key(&script_private_key)),
+                script_private_key,
+            );
+        }
 
-        let factories = CryptoFactories::default();
         let mut stp = builder
             .build::<HashDigest>(
                 &self.resources.factories,
@@ -1838,48 +3020,67 @@ where
             )
             .map_err(|e| OutputManagerError::BuildError(e.message))?;
 
-        let tx_id = stp.get_tx_id()?;
-
-        let unblinded_output = stp.get_change_unblinded_output()?.ok_or_else(|| {
-            OutputManagerError::BuildError("There should be a change output metadata signature available".to_string())
-        })?;
-        let change_output = DbUnblindedOutput::rewindable_from_unblinded_output(
-            unblinded_output,
-            &self.resources.factories,
-            &self.resources.rewind_data,
-            None,
-            None,
-        )?;
-        outputs.push(change_output);
+        if input_selection.requires_change_output() {
+            let unblinded_output = stp.get_change_unblinded_output()?.ok_or_else(|| {
+                OutputManagerError::BuildError(
+                    "There should be a change output metadata signature available".to_string(),
+                )
+            })?;
+            outputs.push(DbUnblindedOutput::rewindable_from_unblinded_output(
+                unblinded_output,
+                &self.resources.factories,
+                &self.resources.rewind_data,
+                None,
+                None,
+            )?);
+        }
 
-        trace!(target: LOG_TARGET, "Claiming HTLC with transaction ({}).", tx_id);
-        self.resources.db.encumber_outputs(tx_id, Vec::new(), outputs)?;
+        self.resources
+            .db
+            .encumber_outputs(tx_id, input_selection.into_selected(), outputs)?;
         self.confirm_encumberance(tx_id)?;
         let fee = stp.get_fee_amount()?;
-        trace!(target: LOG_TARGET, "Finalize send-to-self transaction ({}).", tx_id);
         stp.finalize(
             KernelFeatures::empty(),
-            &factories,
+            &self.resources.factories,
             None,
             self.last_seen_tip_height.unwrap_or(u64::MAX),
         )?;
         let tx = stp.take_transaction()?;
-
-        Ok((tx_id, fee, amount - fee, tx))
+        let group_output = tx
+            .body
+            .outputs()
+            .iter()
+            .find(|o| o.commitment == commitment)
+            .cloned()
+            .ok_or_else(|| OutputManagerError::BuildError("The multisig group output is missing".to_string()))?;
+
+        Ok((fee, tx, group_output))
     }
 
-    pub async fn create_htlc_refund_transaction(
+    /// Create a transaction that spends a vault output via the recovery path, sending the funds to a new output in
+    /// this wallet. This does not need to wait for the vault unlock height.
+    async fn create_vault_recovery_transaction(
         &mut self,
         output_hash: HashOutput,
+        recovery_private_key: PrivateKey,
         fee_per_gram: MicroTari,
     ) -> Result<(TxId, MicroTari, MicroTari, Transaction), OutputManagerError> {
-        let output = self.resources.db.get_unspent_output(output_hash)?.unblinded_output;
+        let db_output = self.resources.db.get_unspent_output(output_hash)?;
+        let vault =
+            VaultScript::from_script(&db_output.unblinded_output.script).ok_or(OutputManagerError::NotAVaultOutput)?;
+        if PublicKey::from_secret_key(&recovery_private_key) != vault.recovery_public_key {
+            return Err(OutputManagerError::InvalidVaultRecoveryKey);
+        }
 
+        let mut output = db_output.unblinded_output.clone();
+        output.input_data = VaultScript::recovery_input_data();
+        output.script_private_key = recovery_private_key;
         let amount = output.value;
 
         let offset = PrivateKey::random(&mut OsRng);
         let nonce = PrivateKey::random(&mut OsRng);
-        let message = "SHA-XTR atomic refund".to_string();
+        let message = "Vault recovery".to_string();
 
         // Create builder with no recipients (other than ourselves)
         let mut builder = SenderTransactionProtocol::builder(0, self.resources.consensus_constants.clone());
@@ -1895,8 +3096,6 @@ where
                 output,
             );
 
-        let mut outputs = Vec::new();
-
         let (spending_key, script_private_key) = self.get_spend_and_script_keys().await?;
         builder.with_change_secret(spending_key);
         builder.with_rewindable_outputs(self.resources.rewind_data.clone());
@@ -1907,7 +3106,6 @@ where
         );
 
         let factories = CryptoFactories::default();
-        println!("he`");
         let mut stp = builder
             .build::<HashDigest>(
                 &self.resources.factories,
@@ -1921,7 +3119,6 @@ where
         let unblinded_output = stp.get_change_unblinded_output()?.ok_or_else(|| {
             OutputManagerError::BuildError("There should be a change output metadata signature available".to_string())
         })?;
-
         let change_output = DbUnblindedOutput::rewindable_from_unblinded_output(
             unblinded_output,
             &self.resources.factories,
@@ -1929,9 +3126,12 @@ where
             None,
             None,
         )?;
-        outputs.push(change_output);
 
-        trace!(target: LOG_TARGET, "Claiming HTLC refund with transaction ({}).", tx_id);
+        trace!(
+            target: LOG_TARGET,
+            "Recovering vault output with transaction ({}).",
+            tx_id
+        );
 
         let fee = stp.get_fee_amount()?;
 
@@ -1944,13 +3144,144 @@ where
 
         let tx = stp.take_transaction()?;
 
-        self.resources.db.encumber_outputs(tx_id, Vec::new(), outputs)?;
+        self.resources
+            .db
+            .encumber_outputs(tx_id, vec![db_output], vec![change_output])?;
         self.confirm_encumberance(tx_id)?;
         Ok((tx_id, fee, amount - fee, tx))
     }
 
+    /// Returns all unspent outputs that are locked by a vault script
+    fn fetch_vault_outputs(&self) -> Result<Vec<VaultOutput>, OutputManagerError> {
+        Ok(self
+            .resources
+            .db
+            .fetch_all_unspent_outputs()?
+            .iter()
+            .filter_map(VaultOutput::from_db_output)
+            .collect())
+    }
+
     /// Persist a one-sided payment script for a Comms Public/Private key. These are the scripts that this wallet knows
     /// to look for when scanning for one-sided payments
+    fn add_watch_only_key(&mut self, key: WatchOnlyKey) -> Result<(), OutputManagerError> {
+        // Watching our own wallet would report our outputs twice
+        if key.rewind_public_key == PublicKey::from_secret_key(&self.resources.rewind_data.rewind_key) {
+            return Err(OutputManagerError::InvalidWatchOnlyKey(
+                "The view data belongs to this wallet".to_string(),
+            ));
+        }
+        debug!(
+            target: LOG_TARGET,
+            "Watching outputs of spend public key {} ({})", key.spend_public_key, key.label
+        );
+        self.resources.db.add_watch_only_key(key)?;
+        Ok(())
+    }
+
+    /// Find the outputs of a block that belong to a watched wallet. One-sided payments are recognised by their script
+    /// alone, while other outputs are recognised by rewinding the value from their range proof with the public rewind
+    /// keys of the watched wallet. Outputs seen before are not returned.
+    fn scan_outputs_for_watch_only_keys(
+        &mut self,
+        outputs: Vec<TransactionOutput>,
+        mined_height: u64,
+        mined_in_block: BlockHash,
+    ) -> Result<Vec<WatchOnlyOutput>, OutputManagerError> {
+        let watch_only_keys = self.resources.db.fetch_watch_only_keys()?;
+        if watch_only_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let one_sided_scripts = watch_only_keys
+            .iter()
+            .map(|key| script!(PushPubKey(Box::new(key.spend_public_key.clone()))))
+            .collect::<Vec<_>>();
+
+        let mut found_outputs = Vec::new();
+        for output in outputs {
+            let found = watch_only_keys
+                .iter()
+                .zip(one_sided_scripts.iter())
+                .find_map(|(key, one_sided_script)| {
+                    if output.script == *one_sided_script {
+                        return Some((key, None));
+                    }
+                    output
+                        .rewind_range_proof_value_only(
+                            &self.resources.factories.range_proof,
+                            &key.rewind_public_key,
+                            &key.rewind_blinding_public_key,
+                        )
+                        .ok()
+                        .map(|rewound| (key, Some(rewound.committed_value)))
+                });
+            let (key, value) = match found {
+                Some(found) => found,
+                None => continue,
+            };
+
+            let watch_only_output = WatchOnlyOutput {
+                commitment: output.commitment.clone(),
+                spend_public_key: key.spend_public_key.clone(),
+                value,
+                mined_height,
+                mined_in_block: mined_in_block.clone(),
+            };
+            match self.resources.db.add_watch_only_output(watch_only_output.clone()) {
+                Ok(_) => {
+                    trace!(
+                        target: LOG_TARGET,
+                        "Found output {} for watched wallet {}",
+                        output.commitment.to_hex(),
+                        key.label
+                    );
+                    found_outputs.push(watch_only_output);
+                },
+                Err(OutputManagerStorageError::DuplicateOutput) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Watch-only output {} has already been recorded. Ignoring the output.",
+                        output.commitment.to_hex()
+                    );
+                },
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(found_outputs)
+    }
+
+    fn get_watch_only_balances(&self) -> Result<Vec<WatchOnlyBalance>, OutputManagerError> {
+        let outputs = self.resources.db.fetch_watch_only_outputs()?;
+        let balances = self
+            .resources
+            .db
+            .fetch_watch_only_keys()?
+            .into_iter()
+            .map(|key| {
+                let mut balance = WatchOnlyBalance {
+                    spend_public_key: key.spend_public_key,
+                    label: key.label,
+                    received: MicroTari::from(0),
+                    num_outputs: 0,
+                    num_outputs_with_unknown_value: 0,
+                };
+                for output in outputs
+                    .iter()
+                    .filter(|o| o.spend_public_key == balance.spend_public_key)
+                {
+                    balance.num_outputs += 1;
+                    match output.value {
+                        Some(value) => balance.received += value,
+                        None => balance.num_outputs_with_unknown_value += 1,
+                    }
+                }
+                balance
+            })
+            .collect();
+        Ok(balances)
+    }
+
     fn add_known_script(&mut self, known_script: KnownOneSidedPaymentScript) -> Result<(), OutputManagerError> {
         debug!(target: LOG_TARGET, "Adding new script to output manager service");
         // It is not a problem if the script has already been persisted
@@ -2023,7 +3354,8 @@ where
                             rewind_key,
                             rewind_blinding_key,
                             recovery_byte_key,
-                            proof_message: [0u8; 21],
+                            // Keeps the payment id of payments made with `create_pay_to_many_transaction`
+                            proof_message: rewound_result.proof_message,
                         },
                         None,
                         Some(&output.proof),
@@ -2129,6 +3461,33 @@ impl fmt::Display for Balance {
     }
 }
 
+/// The outputs received by a watched wallet. Spends by the watched wallet are not visible to us, so this is the total
+/// received rather than what is still available.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchOnlyBalance {
+    pub spend_public_key: PublicKey,
+    pub label: String,
+    /// The total value of the outputs whose value could be rewound
+    pub received: MicroTari,
+    pub num_outputs: usize,
+    /// One-sided payments to the watched wallet, whose value can only be read with its spend key
+    pub num_outputs_with_unknown_value: usize,
+}
+
+impl fmt::Display for WatchOnlyBalance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}): received {} in {} output(s)",
+            self.label, self.spend_public_key, self.received, self.num_outputs
+        )?;
+        if self.num_outputs_with_unknown_value > 0 {
+            write!(f, ", {} of unknown value", self.num_outputs_with_unknown_value)?;
+        }
+        Ok(())
+    }
+}
+
 fn hash_secret_key(key: &PrivateKey) -> Vec<u8> {
     HashDigest::new().chain(key.as_bytes()).finalize().to_vec()
 }
@@ -13,7 +13,7 @@ use crate::output_manager_service::{
     service::{Balance, UTXOSelectionStrategy},
     storage::{
         database::{DbKey, DbValue, WriteOperation},
-        models::DbUnblindedOutput,
+        models::{DbUnblindedOutput, WatchOnlyKey, WatchOnlyOutput},
     },
 };
 
@@ -114,4 +114,12 @@ pub trait OutputManagerBackend: Send + Sync + Clone {
         current_tip_height: Option<u64>,
     ) -> Result<Vec<DbUnblindedOutput>, OutputManagerStorageError>;
     fn fetch_outputs_by_tx_id(&self, tx_id: TxId) -> Result<Vec<DbUnblindedOutput>, OutputManagerStorageError>;
+    /// Add the view data of a watched wallet, replacing any previously stored for the same spend public key
+    fn add_watch_only_key(&self, key: WatchOnlyKey) -> Result<(), OutputManagerStorageError>;
+    fn fetch_watch_only_keys(&self) -> Result<Vec<WatchOnlyKey>, OutputManagerStorageError>;
+    /// Remove the view data of a watched wallet along with the outputs found for it
+    fn remove_watch_only_key(&self, spend_public_key: &PublicKey) -> Result<(), OutputManagerStorageError>;
+    /// Record an output found for a watched wallet. These are kept apart from the wallet's own outputs.
+    fn add_watch_only_output(&self, output: WatchOnlyOutput) -> Result<(), OutputManagerStorageError>;
+    fn fetch_watch_only_outputs(&self) -> Result<Vec<WatchOnlyOutput>, OutputManagerStorageError>;
 }
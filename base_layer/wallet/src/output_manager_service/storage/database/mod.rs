@@ -43,7 +43,7 @@ use crate::output_manager_service::{
     error::OutputManagerStorageError,
     service::{Balance, UTXOSelectionStrategy},
     storage::{
-        models::{DbUnblindedOutput, KnownOneSidedPaymentScript},
+        models::{DbUnblindedOutput, KnownOneSidedPaymentScript, WatchOnlyKey, WatchOnlyOutput},
         OutputStatus,
     },
 };
@@ -419,6 +419,26 @@ where T: OutputManagerBackend + 'static
         let outputs = self.db.fetch_outputs_by_tx_id(tx_id)?;
         Ok(outputs)
     }
+
+    pub fn add_watch_only_key(&self, key: WatchOnlyKey) -> Result<(), OutputManagerStorageError> {
+        self.db.add_watch_only_key(key)
+    }
+
+    pub fn fetch_watch_only_keys(&self) -> Result<Vec<WatchOnlyKey>, OutputManagerStorageError> {
+        self.db.fetch_watch_only_keys()
+    }
+
+    pub fn remove_watch_only_key(&self, spend_public_key: &PublicKey) -> Result<(), OutputManagerStorageError> {
+        self.db.remove_watch_only_key(spend_public_key)
+    }
+
+    pub fn add_watch_only_output(&self, output: WatchOnlyOutput) -> Result<(), OutputManagerStorageError> {
+        self.db.add_watch_only_output(output)
+    }
+
+    pub fn fetch_watch_only_outputs(&self) -> Result<Vec<WatchOnlyOutput>, OutputManagerStorageError> {
+        self.db.fetch_watch_only_outputs()
+    }
 }
 
 fn unexpected_result<T>(req: DbKey, res: DbValue) -> Result<T, OutputManagerStorageError> {
@@ -23,8 +23,9 @@
 use std::cmp::Ordering;
 
 use derivative::Derivative;
-use tari_common_types::types::{BlockHash, BulletRangeProof, Commitment, HashOutput, PrivateKey};
+use tari_common_types::types::{BlockHash, BulletRangeProof, Commitment, HashOutput, PrivateKey, PublicKey};
 use tari_core::transactions::{
+    tari_amount::MicroTari,
     transaction_components::UnblindedOutput,
     transaction_protocol::RewindData,
     CryptoFactories,
@@ -160,3 +161,25 @@ impl PartialEq for KnownOneSidedPaymentScript {
         self.script_hash == other.script_hash
     }
 }
+
+/// The view data of a wallet that is watched without holding any of its keys. All of it is public: the spend public key
+/// identifies one-sided payments to the watched wallet and the public rewind keys reveal the value of its other
+/// outputs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchOnlyKey {
+    pub spend_public_key: PublicKey,
+    pub rewind_public_key: PublicKey,
+    pub rewind_blinding_public_key: PublicKey,
+    pub label: String,
+}
+
+/// An output belonging to a watched wallet. This wallet does not hold the keys to sign for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchOnlyOutput {
+    pub commitment: Commitment,
+    pub spend_public_key: PublicKey,
+    /// Unknown for one-sided payments, as their range proof can only be rewound with the spend key
+    pub value: Option<MicroTari>,
+    pub mined_height: u64,
+    pub mined_in_block: BlockHash,
+}
@@ -39,6 +39,7 @@ use tari_core::transactions::transaction_components::{OutputFlags, TransactionOu
 use tari_crypto::tari_utilities::{hex::Hex, ByteArray};
 use tari_script::{ExecutionStack, TariScript};
 use tokio::time::Instant;
+pub use watch_only_sql::{WatchOnlyKeySql, WatchOnlyOutputSql};
 
 use crate::{
     output_manager_service::{
@@ -46,7 +47,7 @@ use crate::{
         service::{Balance, UTXOSelectionStrategy},
         storage::{
             database::{DbKey, DbKeyValuePair, DbValue, OutputManagerBackend, WriteOperation},
-            models::{DbUnblindedOutput, KnownOneSidedPaymentScript},
+            models::{DbUnblindedOutput, KnownOneSidedPaymentScript, WatchOnlyKey, WatchOnlyOutput},
             OutputStatus,
         },
     },
@@ -60,6 +61,7 @@ use crate::{
 
 mod new_output_sql;
 mod output_sql;
+mod watch_only_sql;
 
 const LOG_TARGET: &str = "wallet::output_manager_service::database::wallet";
 
@@ -1226,6 +1228,40 @@ impl OutputManagerBackend for OutputManagerSqliteDatabase {
             .map(|o| DbUnblindedOutput::try_from(o.clone()))
             .collect::<Result<Vec<_>, _>>()
     }
+
+    fn add_watch_only_key(&self, key: WatchOnlyKey) -> Result<(), OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        WatchOnlyKeySql::from(key).commit(&conn)
+    }
+
+    fn fetch_watch_only_keys(&self) -> Result<Vec<WatchOnlyKey>, OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        WatchOnlyKeySql::index(&conn)?
+            .into_iter()
+            .map(WatchOnlyKey::try_from)
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    fn remove_watch_only_key(&self, spend_public_key: &PublicKey) -> Result<(), OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        WatchOnlyKeySql::delete(spend_public_key.as_bytes(), &conn)
+    }
+
+    fn add_watch_only_output(&self, output: WatchOnlyOutput) -> Result<(), OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        if WatchOnlyOutputSql::find(output.commitment.as_bytes(), &conn).is_ok() {
+            return Err(OutputManagerStorageError::DuplicateOutput);
+        }
+        WatchOnlyOutputSql::from(output).commit(&conn)
+    }
+
+    fn fetch_watch_only_outputs(&self) -> Result<Vec<WatchOnlyOutput>, OutputManagerStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        WatchOnlyOutputSql::index(&conn)?
+            .into_iter()
+            .map(WatchOnlyOutput::try_from)
+            .collect::<Result<Vec<_>, _>>()
+    }
 }
 
 /// These are the fields that can be updated for an Output
@@ -0,0 +1,170 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::convert::TryFrom;
+
+use diesel::{prelude::*, SqliteConnection};
+use tari_common_types::types::{Commitment, PublicKey};
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_utilities::ByteArray;
+
+use crate::{
+    output_manager_service::{
+        error::OutputManagerStorageError,
+        storage::models::{WatchOnlyKey, WatchOnlyOutput},
+    },
+    schema::{watch_only_keys, watch_only_outputs},
+};
+
+/// The view data of a watched wallet as stored in the database. None of it is secret, so unlike the known one-sided
+/// payment scripts it is not encrypted.
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "watch_only_keys"]
+pub struct WatchOnlyKeySql {
+    pub spend_public_key: Vec<u8>,
+    pub rewind_public_key: Vec<u8>,
+    pub rewind_blinding_public_key: Vec<u8>,
+    pub label: String,
+}
+
+impl WatchOnlyKeySql {
+    /// Write this struct to the database, replacing the view data previously stored for the same spend public key
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        diesel::replace_into(watch_only_keys::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    /// Return all watch-only keys
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<WatchOnlyKeySql>, OutputManagerStorageError> {
+        Ok(watch_only_keys::table.load::<WatchOnlyKeySql>(conn)?)
+    }
+
+    /// Delete the view data for a spend public key along with the outputs found for it
+    pub fn delete(spend_public_key: &[u8], conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        conn.transaction::<_, OutputManagerStorageError, _>(|| {
+            let num_deleted =
+                diesel::delete(watch_only_keys::table.filter(watch_only_keys::spend_public_key.eq(spend_public_key)))
+                    .execute(conn)?;
+            if num_deleted == 0 {
+                return Err(OutputManagerStorageError::ValuesNotFound);
+            }
+            diesel::delete(watch_only_outputs::table.filter(watch_only_outputs::spend_public_key.eq(spend_public_key)))
+                .execute(conn)?;
+            Ok(())
+        })
+    }
+}
+
+impl TryFrom<WatchOnlyKeySql> for WatchOnlyKey {
+    type Error = OutputManagerStorageError;
+
+    fn try_from(o: WatchOnlyKeySql) -> Result<Self, Self::Error> {
+        Ok(WatchOnlyKey {
+            spend_public_key: public_key_from_bytes(&o.spend_public_key)?,
+            rewind_public_key: public_key_from_bytes(&o.rewind_public_key)?,
+            rewind_blinding_public_key: public_key_from_bytes(&o.rewind_blinding_public_key)?,
+            label: o.label,
+        })
+    }
+}
+
+impl From<WatchOnlyKey> for WatchOnlyKeySql {
+    fn from(key: WatchOnlyKey) -> Self {
+        WatchOnlyKeySql {
+            spend_public_key: key.spend_public_key.as_bytes().to_vec(),
+            rewind_public_key: key.rewind_public_key.as_bytes().to_vec(),
+            rewind_blinding_public_key: key.rewind_blinding_public_key.as_bytes().to_vec(),
+            label: key.label,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "watch_only_outputs"]
+pub struct WatchOnlyOutputSql {
+    pub commitment: Vec<u8>,
+    pub spend_public_key: Vec<u8>,
+    pub value: Option<i64>,
+    pub mined_height: i64,
+    pub mined_in_block: Vec<u8>,
+}
+
+impl WatchOnlyOutputSql {
+    /// Write this struct to the database
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), OutputManagerStorageError> {
+        diesel::insert_into(watch_only_outputs::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn find(commitment: &[u8], conn: &SqliteConnection) -> Result<WatchOnlyOutputSql, OutputManagerStorageError> {
+        Ok(watch_only_outputs::table
+            .filter(watch_only_outputs::commitment.eq(commitment))
+            .first::<WatchOnlyOutputSql>(conn)?)
+    }
+
+    /// Return all watch-only outputs, ordered by the height they were mined at
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<WatchOnlyOutputSql>, OutputManagerStorageError> {
+        Ok(watch_only_outputs::table
+            .order_by(watch_only_outputs::mined_height.asc())
+            .load::<WatchOnlyOutputSql>(conn)?)
+    }
+}
+
+impl TryFrom<WatchOnlyOutputSql> for WatchOnlyOutput {
+    type Error = OutputManagerStorageError;
+
+    fn try_from(o: WatchOnlyOutputSql) -> Result<Self, Self::Error> {
+        let commitment =
+            Commitment::from_bytes(&o.commitment).map_err(|_| OutputManagerStorageError::ConversionError {
+                reason: "Commitment could not be converted from bytes".to_string(),
+            })?;
+        Ok(WatchOnlyOutput {
+            commitment,
+            spend_public_key: public_key_from_bytes(&o.spend_public_key)?,
+            value: o.value.map(|v| MicroTari::from(v as u64)),
+            mined_height: o.mined_height as u64,
+            mined_in_block: o.mined_in_block,
+        })
+    }
+}
+
+impl From<WatchOnlyOutput> for WatchOnlyOutputSql {
+    fn from(output: WatchOnlyOutput) -> Self {
+        WatchOnlyOutputSql {
+            commitment: output.commitment.as_bytes().to_vec(),
+            spend_public_key: output.spend_public_key.as_bytes().to_vec(),
+            value: output.value.map(|v| u64::from(v) as i64),
+            mined_height: output.mined_height as i64,
+            mined_in_block: output.mined_in_block,
+        }
+    }
+}
+
+fn public_key_from_bytes(bytes: &[u8]) -> Result<PublicKey, OutputManagerStorageError> {
+    PublicKey::from_bytes(bytes).map_err(|_| OutputManagerStorageError::ConversionError {
+        reason: "PublicKey could not be converted from bytes".to_string(),
+    })
+}
@@ -23,11 +23,12 @@ use std::{collections::HashMap, convert::TryInto, sync::Arc};
 
 use log::*;
 use tari_common_types::types::BlockHash;
-use tari_comms::protocol::rpc::RpcError::RequestFailed;
+use tari_comms::protocol::rpc::{RpcClientLease, RpcError::RequestFailed};
 use tari_core::{
     base_node::rpc::BaseNodeWalletRpcClient,
     blocks::BlockHeader,
     proto::base_node::{QueryDeletedRequest, UtxoQueryRequest},
+    transactions::htlc::ShaHtlcScript,
 };
 use tari_shutdown::ShutdownSignal;
 use tari_utilities::{hex::Hex, Hashable};
@@ -38,6 +39,7 @@ use crate::{
         config::OutputManagerServiceConfig,
         error::{OutputManagerError, OutputManagerProtocolError, OutputManagerProtocolErrorExt},
         handle::{OutputManagerEvent, OutputManagerEventSender},
+        htlc::fetch_revealed_pre_images,
         storage::{
             database::{OutputManagerBackend, OutputManagerDatabase},
             models::DbUnblindedOutput,
@@ -53,6 +55,8 @@ pub struct TxoValidationTask<TBackend, TWalletConnectivity> {
     connectivity: TWalletConnectivity,
     event_publisher: OutputManagerEventSender,
     config: OutputManagerServiceConfig,
+    independent_clients: Option<Vec<RpcClientLease<BaseNodeWalletRpcClient>>>,
+    independent_header_checks: HashMap<(u64, BlockHash), bool>,
 }
 
 impl<TBackend, TWalletConnectivity> TxoValidationTask<TBackend, TWalletConnectivity>
@@ -73,6 +77,8 @@ where
             connectivity,
             event_publisher,
             config,
+            independent_clients: None,
+            independent_header_checks: HashMap::new(),
         }
     }
 
@@ -114,6 +120,8 @@ where
             return Ok(());
         }
 
+        let mut spent_htlcs: HashMap<BlockHash, Vec<DbUnblindedOutput>> = HashMap::new();
+
         for batch in mined_outputs.chunks(self.config.tx_validator_batch_size) {
             debug!(
                 target: LOG_TARGET,
@@ -171,6 +179,15 @@ where
                     let confirmed = (deleted_bitmap_response.height_of_longest_chain - deleted_height) >=
                         self.config.num_confirmations_required;
 
+                    if output.marked_deleted_in_block.as_ref() != Some(&deleted_block) &&
+                        ShaHtlcScript::from_script(&output.unblinded_output.script).is_some()
+                    {
+                        spent_htlcs
+                            .entry(deleted_block.clone())
+                            .or_insert_with(Vec::new)
+                            .push(output.clone());
+                    }
+
                     self.db
                         .mark_output_as_spent(output.hash.clone(), deleted_height, deleted_block, confirmed)
                         .for_protocol(self.operation_id)?;
@@ -207,12 +224,47 @@ where
                 }
             }
         }
+
+        for (block_hash, outputs) in spent_htlcs {
+            self.publish_htlc_claims(wallet_client, block_hash, &outputs).await;
+        }
         Ok(())
     }
 
-    async fn update_unconfirmed_outputs(
+    /// Publish the pre-images revealed by counterparties claiming HTLC outputs that were spent in `block_hash`. Failing
+    /// to fetch the claims does not fail the validation, as the pre-images can still be requested later.
+    async fn publish_htlc_claims(
         &self,
         wallet_client: &mut BaseNodeWalletRpcClient,
+        block_hash: BlockHash,
+        outputs: &[DbUnblindedOutput],
+    ) {
+        match fetch_revealed_pre_images(wallet_client, block_hash, outputs).await {
+            Ok(pre_images) => {
+                for (output_hash, pre_image) in pre_images {
+                    info!(
+                        target: LOG_TARGET,
+                        "HTLC output {} was claimed with pre-image {} (Operation ID: {})",
+                        output_hash.to_hex(),
+                        pre_image.to_hex(),
+                        self.operation_id
+                    );
+                    self.publish_event(OutputManagerEvent::HtlcClaimed { output_hash, pre_image });
+                }
+            },
+            Err(e) => warn!(
+                target: LOG_TARGET,
+                "Could not fetch HTLC claims for block {}: {} (Operation ID: {})",
+                block_hash.to_hex(),
+                e,
+                self.operation_id
+            ),
+        }
+    }
+
+    async fn update_unconfirmed_outputs(
+        &mut self,
+        wallet_client: &mut BaseNodeWalletRpcClient,
     ) -> Result<(), OutputManagerProtocolError> {
         let unconfirmed_outputs = self.db.fetch_unconfirmed_outputs().for_protocol(self.operation_id)?;
 
@@ -421,16 +473,86 @@ where
         Ok((mined, unmined, batch_response.height_of_longest_chain))
     }
 
+    /// Returns true if `num_independent_header_confirmations` base nodes other than the connected base node report
+    /// the same block hash at the given height. This guards against a malicious connected base node claiming that an
+    /// output was mined. Base nodes that cannot be reached or return an error do not count towards the confirmations.
+    async fn is_header_independently_confirmed(&mut self, height: u64, block_hash: &BlockHash) -> bool {
+        let required = self.config.num_independent_header_confirmations;
+        if required == 0 {
+            return true;
+        }
+        if let Some(confirmed) = self.independent_header_checks.get(&(height, block_hash.clone())) {
+            return *confirmed;
+        }
+
+        let mut clients = match self.independent_clients.take() {
+            Some(clients) => clients,
+            None => {
+                self.connectivity
+                    .obtain_independent_base_node_wallet_rpc_clients(required)
+                    .await
+            },
+        };
+
+        let mut num_confirmations = 0;
+        let mut num_disagreements = 0;
+        for client in &mut clients {
+            match self.get_base_node_block_at_height(height, client).await {
+                Ok(Some(hash)) if hash == *block_hash => num_confirmations += 1,
+                Ok(_) => num_disagreements += 1,
+                Err(e) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Independent base node could not be queried for header at height {}: {} (Operation ID: {})",
+                        height,
+                        e,
+                        self.operation_id
+                    );
+                },
+            }
+        }
+        self.independent_clients = Some(clients);
+
+        if num_disagreements > 0 {
+            warn!(
+                target: LOG_TARGET,
+                "{} independent base node(s) disagree with the connected base node about block {} at height {}. \
+                 Outputs in this block will not be marked as spendable (Operation ID: {})",
+                num_disagreements,
+                block_hash.to_hex(),
+                height,
+                self.operation_id
+            );
+        }
+        let confirmed = num_disagreements == 0 && num_confirmations >= required;
+        if !confirmed && num_disagreements == 0 {
+            debug!(
+                target: LOG_TARGET,
+                "Block {} at height {} confirmed by {}/{} independent base node(s) (Operation ID: {})",
+                block_hash.to_hex(),
+                height,
+                num_confirmations,
+                required,
+                self.operation_id
+            );
+        }
+        self.independent_header_checks
+            .insert((height, block_hash.clone()), confirmed);
+        confirmed
+    }
+
     #[allow(clippy::ptr_arg)]
     async fn update_output_as_mined(
-        &self,
+        &mut self,
         tx: &DbUnblindedOutput,
         mined_in_block: &BlockHash,
         mined_height: u64,
         mmr_position: u64,
         tip_height: u64,
     ) -> Result<(), OutputManagerProtocolError> {
-        let confirmed = (tip_height - mined_height) >= self.config.num_confirmations_required;
+        let confirmed = (tip_height - mined_height) >= self.config.num_confirmations_required &&
+            self.is_header_independently_confirmed(mined_height, mined_in_block)
+                .await;
 
         self.db
             .set_received_output_mined_height(
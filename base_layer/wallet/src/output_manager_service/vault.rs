@@ -0,0 +1,167 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Time-vaults are outputs locked behind a script with two spending paths: the owner path, which only becomes valid
+//! once the chain has reached the unlock height, and a recovery path that can be taken at any time by the holder of
+//! the recovery key. Typically the recovery key is kept in cold storage so that funds can be swept to safety if the
+//! owner key is compromised before the vault matures.
+
+use tari_common_types::types::{Commitment, HashOutput, PublicKey};
+use tari_core::transactions::tari_amount::MicroTari;
+use tari_script::{inputs, ExecutionStack, Opcode, TariScript};
+
+use crate::output_manager_service::storage::{models::DbUnblindedOutput, OutputStatus};
+
+/// The parameters of a vault script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultScript {
+    pub unlock_height: u64,
+    pub owner_public_key: PublicKey,
+    pub recovery_public_key: PublicKey,
+}
+
+impl VaultScript {
+    pub fn new(unlock_height: u64, owner_public_key: PublicKey, recovery_public_key: PublicKey) -> Self {
+        Self {
+            unlock_height,
+            owner_public_key,
+            recovery_public_key,
+        }
+    }
+
+    /// Builds the vault script:
+    /// `IfThen CheckHeightVerify(unlock_height) PushPubKey(owner) Else PushPubKey(recovery) EndIf`
+    pub fn to_script(&self) -> TariScript {
+        TariScript::new(vec![
+            Opcode::IfThen,
+            Opcode::CheckHeightVerify(self.unlock_height),
+            Opcode::PushPubKey(Box::new(self.owner_public_key.clone())),
+            Opcode::Else,
+            Opcode::PushPubKey(Box::new(self.recovery_public_key.clone())),
+            Opcode::EndIf,
+        ])
+    }
+
+    /// Returns the vault parameters if the given script is a vault script, otherwise None
+    pub fn from_script(script: &TariScript) -> Option<Self> {
+        use Opcode::{CheckHeightVerify, Else, EndIf, IfThen, PushPubKey};
+        match script.opcodes() {
+            [IfThen, CheckHeightVerify(height), PushPubKey(owner), Else, PushPubKey(recovery), EndIf] => {
+                Some(Self::new(*height, (**owner).clone(), (**recovery).clone()))
+            },
+            _ => None,
+        }
+    }
+
+    /// The input data that selects the owner (time-locked) spending path
+    pub fn owner_input_data() -> ExecutionStack {
+        inputs!(1i64)
+    }
+
+    /// The input data that selects the recovery spending path
+    pub fn recovery_input_data() -> ExecutionStack {
+        inputs!(0i64)
+    }
+}
+
+/// A vault output tracked by the output manager
+#[derive(Debug, Clone)]
+pub struct VaultOutput {
+    pub hash: HashOutput,
+    pub commitment: Commitment,
+    pub value: MicroTari,
+    pub vault: VaultScript,
+    pub status: OutputStatus,
+}
+
+impl VaultOutput {
+    /// Returns a VaultOutput if the output is locked by a vault script
+    pub fn from_db_output(output: &DbUnblindedOutput) -> Option<Self> {
+        VaultScript::from_script(&output.unblinded_output.script).map(|vault| Self {
+            hash: output.hash.clone(),
+            commitment: output.commitment.clone(),
+            value: output.unblinded_output.value,
+            vault,
+            status: output.status,
+        })
+    }
+
+    /// Returns true if the owner path can be spent in a block at the given height
+    pub fn is_unlocked_at(&self, height: u64) -> bool {
+        height >= self.vault.unlock_height
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::OsRng;
+    use tari_common_types::types::{Commitment, PublicKey};
+    use tari_crypto::keys::PublicKey as PublicKeyTrait;
+    use tari_script::{script, HashValue, Opcode, ScriptContext, StackItem, TariScript};
+
+    use super::VaultScript;
+
+    fn context(height: u64) -> ScriptContext {
+        ScriptContext::new(height, &HashValue::default(), &Commitment::default())
+    }
+
+    fn vault() -> VaultScript {
+        let (_, owner) = PublicKey::random_keypair(&mut OsRng);
+        let (_, recovery) = PublicKey::random_keypair(&mut OsRng);
+        VaultScript::new(100, owner, recovery)
+    }
+
+    #[test]
+    fn owner_path_requires_unlock_height() {
+        let vault = vault();
+        let script = vault.to_script();
+        let input = VaultScript::owner_input_data();
+
+        assert!(script.execute_with_context(&input, &context(99)).is_err());
+        let result = script.execute_with_context(&input, &context(100)).unwrap();
+        assert_eq!(result, StackItem::PublicKey(vault.owner_public_key.clone()));
+        let result = script.execute_with_context(&input, &context(150)).unwrap();
+        assert_eq!(result, StackItem::PublicKey(vault.owner_public_key));
+    }
+
+    #[test]
+    fn recovery_path_is_always_spendable() {
+        let vault = vault();
+        let script = vault.to_script();
+        let input = VaultScript::recovery_input_data();
+
+        let result = script.execute_with_context(&input, &context(0)).unwrap();
+        assert_eq!(result, StackItem::PublicKey(vault.recovery_public_key.clone()));
+        let result = script.execute_with_context(&input, &context(1000)).unwrap();
+        assert_eq!(result, StackItem::PublicKey(vault.recovery_public_key));
+    }
+
+    #[test]
+    fn it_parses_vault_scripts() {
+        let vault = vault();
+        assert_eq!(VaultScript::from_script(&vault.to_script()), Some(vault));
+
+        let (_, key) = PublicKey::random_keypair(&mut OsRng);
+        assert!(VaultScript::from_script(&script!(Nop)).is_none());
+        assert!(VaultScript::from_script(&TariScript::new(vec![Opcode::PushPubKey(Box::new(key))])).is_none());
+    }
+}
@@ -39,6 +39,8 @@ table! {
         alias -> Text,
         last_seen -> Nullable<Timestamp>,
         latency -> Nullable<Integer>,
+        last_transaction_id -> Nullable<BigInt>,
+        last_transaction_at -> Nullable<Timestamp>,
     }
 }
 
@@ -86,6 +88,41 @@ table! {
     }
 }
 
+table! {
+    multisig_groups (group_id) {
+        group_id -> BigInt,
+        threshold -> Integer,
+        participants -> Binary,
+        share_index -> Integer,
+        share_commitments -> Binary,
+        output -> Text,
+        value -> BigInt,
+        secrets -> Binary,
+        status -> Integer,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    multisig_sessions (session_id) {
+        session_id -> BigInt,
+        group_id -> BigInt,
+        role -> Integer,
+        proposer_public_key -> Binary,
+        destination_public_key -> Binary,
+        amount -> BigInt,
+        fee -> BigInt,
+        lock_height -> BigInt,
+        message -> Text,
+        state -> Integer,
+        private_nonces -> Nullable<Binary>,
+        negotiation -> Text,
+        tx_id -> Nullable<BigInt>,
+        failure_reason -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
 table! {
     outbound_transactions (tx_id) {
         tx_id -> BigInt,
@@ -138,6 +175,18 @@ table! {
     }
 }
 
+table! {
+    queued_transaction_messages (tx_id, message_type) {
+        tx_id -> BigInt,
+        message_type -> Integer,
+        destination_public_key -> Binary,
+        message -> Binary,
+        attempts -> Integer,
+        next_attempt -> Timestamp,
+        expires_at -> Timestamp,
+    }
+}
+
 table! {
     scanned_blocks (header_hash) {
         header_hash -> Binary,
@@ -148,6 +197,36 @@ table! {
     }
 }
 
+table! {
+    scheduled_payments (id) {
+        id -> BigInt,
+        destination_public_key -> Binary,
+        amount -> BigInt,
+        fee_per_gram -> BigInt,
+        message -> Text,
+        payment_type -> Integer,
+        next_payment_at -> Timestamp,
+        interval_seconds -> Nullable<BigInt>,
+        remaining_payments -> Nullable<Integer>,
+        num_payments_made -> Integer,
+        status -> Integer,
+        last_tx_id -> Nullable<BigInt>,
+        last_error -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    send_intents (tx_id) {
+        tx_id -> BigInt,
+        destination_public_key -> Binary,
+        amount -> BigInt,
+        fee_per_gram -> BigInt,
+        timestamp -> Timestamp,
+        one_sided -> Integer,
+    }
+}
+
 table! {
     wallet_settings (key) {
         key -> Text,
@@ -155,6 +234,25 @@ table! {
     }
 }
 
+table! {
+    watch_only_keys (spend_public_key) {
+        spend_public_key -> Binary,
+        rewind_public_key -> Binary,
+        rewind_blinding_public_key -> Binary,
+        label -> Text,
+    }
+}
+
+table! {
+    watch_only_outputs (commitment) {
+        commitment -> Binary,
+        spend_public_key -> Binary,
+        value -> Nullable<BigInt>,
+        mined_height -> BigInt,
+        mined_in_block -> Binary,
+    }
+}
+
 allow_tables_to_appear_in_same_query!(
     client_key_values,
     completed_transactions,
@@ -163,8 +261,15 @@ allow_tables_to_appear_in_same_query!(
     key_manager_states,
     key_manager_states_old,
     known_one_sided_payment_scripts,
+    multisig_groups,
+    multisig_sessions,
     outbound_transactions,
     outputs,
+    queued_transaction_messages,
     scanned_blocks,
+    scheduled_payments,
+    send_intents,
     wallet_settings,
+    watch_only_keys,
+    watch_only_outputs,
 );
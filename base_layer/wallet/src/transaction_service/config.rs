@@ -50,9 +50,22 @@ pub struct TransactionServiceConfig {
     pub num_confirmations_required: u64,
     pub max_tx_query_batch_size: usize,
     pub transaction_routing_mechanism: TransactionRoutingMechanism,
+    /// How to send an interactive transaction to a contact whose liveness data shows them to be offline
+    pub offline_recipient_policy: OfflineRecipientPolicy,
     pub transaction_event_channel_size: usize,
     #[serde(with = "serializers::seconds")]
     pub transaction_mempool_resubmission_window: Duration,
+    #[serde(with = "serializers::seconds")]
+    pub message_queue_check_interval: Duration,
+    #[serde(with = "serializers::seconds")]
+    pub message_queue_initial_retry_delay: Duration,
+    #[serde(with = "serializers::seconds")]
+    pub message_queue_max_retry_delay: Duration,
+    #[serde(with = "serializers::seconds")]
+    pub message_queue_expiry: Duration,
+    /// How often to check for scheduled payments that have fallen due
+    #[serde(with = "serializers::seconds")]
+    pub scheduled_payment_check_interval: Duration,
 }
 
 impl Default for TransactionServiceConfig {
@@ -69,8 +82,14 @@ impl Default for TransactionServiceConfig {
             num_confirmations_required: 3,
             max_tx_query_batch_size: 20,
             transaction_routing_mechanism: TransactionRoutingMechanism::default(),
+            offline_recipient_policy: OfflineRecipientPolicy::default(),
             transaction_event_channel_size: 1000,
             transaction_mempool_resubmission_window: Duration::from_secs(600),
+            message_queue_check_interval: Duration::from_secs(60),
+            message_queue_initial_retry_delay: Duration::from_secs(300),
+            message_queue_max_retry_delay: Duration::from_secs(7200),
+            message_queue_expiry: Duration::from_secs(259200), // 3 Days
+            scheduled_payment_check_interval: Duration::from_secs(30),
         }
     }
 }
@@ -115,3 +134,31 @@ impl Default for TransactionRoutingMechanism {
         Self::DirectAndStoreAndForward
     }
 }
+
+/// The action taken when an interactive transaction is sent to a contact that is known to be offline. Recipients that
+/// are not contacts, or that have never been seen, are always sent an interactive transaction.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum OfflineRecipientPolicy {
+    /// Send the interactive transaction using the configured routing mechanism
+    Interactive,
+    /// Send a one-sided transaction instead, which does not require the recipient to be online
+    OneSided,
+    /// Skip the direct send attempt and queue the transaction with store and forward
+    StoreAndForward,
+}
+
+impl fmt::Display for OfflineRecipientPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Interactive => f.write_str("'Interactive'"),
+            Self::OneSided => f.write_str("'OneSided'"),
+            Self::StoreAndForward => f.write_str("'StoreAndForward'"),
+        }
+    }
+}
+
+impl Default for OfflineRecipientPolicy {
+    fn default() -> Self {
+        Self::Interactive
+    }
+}
@@ -40,6 +40,7 @@ use crate::{
     error::WalletStorageError,
     output_manager_service::error::OutputManagerError,
     transaction_service::{
+        offline_signing::OfflineTransactionError,
         storage::{database::DbKey, sqlite_db::CompletedTransactionConversionError},
         utc::NegativeDurationError,
     },
@@ -115,6 +116,8 @@ pub enum TransactionServiceError {
     WalletStorageError(#[from] WalletStorageError),
     #[error("Invalid message error: `{0}`")]
     InvalidMessageError(String),
+    #[error("Invalid HTLC: `{0}`")]
+    InvalidHtlc(String),
     #[error("Transaction error: `{0}`")]
     TransactionError(#[from] TransactionError),
     #[error("Conversion error: `{0}`")]
@@ -164,6 +167,16 @@ pub enum TransactionServiceError {
     },
     #[error("Base Node is not synced")]
     BaseNodeNotSynced,
+    #[error("Scheduled payment `{0}` not found")]
+    ScheduledPaymentNotFound(u64),
+    #[error("Invalid payment schedule: {0}")]
+    InvalidPaymentSchedule(String),
+    #[error("Multisig group `{0}` not found")]
+    MultisigGroupNotFound(u64),
+    #[error("Multisig session `{0}` not found")]
+    MultisigSessionNotFound(u64),
+    #[error("Multisig error: {0}")]
+    MultisigError(String),
 }
 
 #[derive(Debug, Error)]
@@ -224,6 +237,8 @@ pub enum TransactionStorageError {
     ByteArrayError(#[from] ByteArrayError),
     #[error("Not a coinbase transaction so cannot be abandoned")]
     NotCoinbase,
+    #[error("Offline transaction error: `{0}`")]
+    OfflineTransactionError(#[from] OfflineTransactionError),
 }
 
 /// This error type is used to return TransactionServiceErrors from inside a Transaction Service protocol but also
@@ -37,18 +37,24 @@ use tari_core::transactions::{
     tari_amount::MicroTari,
     transaction_components::{Transaction, TransactionOutput},
 };
+use tari_script::HashValue;
 use tari_service_framework::reply_channel::SenderService;
 use tari_utilities::hex::Hex;
 use tokio::sync::broadcast;
 use tower::Service;
 
 use crate::{
+    output_manager_service::handle::PaymentRecipient,
     transaction_service::{
         error::TransactionServiceError,
+        multisig::{MultisigGroup, MultisigSession},
+        offline_signing::{SignedTransaction, UnsignedTransaction},
         storage::models::{
             CompletedTransaction,
             InboundTransaction,
             OutboundTransaction,
+            QueuedTransactionMessage,
+            ScheduledPayment,
             TxCancellationReason,
             WalletTransaction,
         },
@@ -68,6 +74,27 @@ pub enum TransactionServiceRequest {
     GetCancelledCompletedTransactions,
     GetCompletedTransaction(TxId),
     GetAnyTransaction(TxId),
+    GetQueuedTransactionMessages(TxId),
+    SchedulePayment(Box<ScheduledPayment>),
+    GetScheduledPayments,
+    CancelScheduledPayment(u64),
+    CreateMultisigGroup {
+        threshold: u32,
+        cosigners: Vec<CommsPublicKey>,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
+    GetMultisigGroups,
+    ProposeMultisigSpend {
+        group_id: u64,
+        destination: CommsPublicKey,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
+    ApproveMultisigSpend(u64),
+    CancelMultisigSession(u64),
+    GetMultisigSessions,
     SendTransaction {
         dest_pubkey: CommsPublicKey,
         amount: MicroTari,
@@ -84,8 +111,25 @@ pub enum TransactionServiceRequest {
         fee_per_gram: MicroTari,
         message: String,
     },
+    SendOneSidedToManyTransaction {
+        recipients: Vec<PaymentRecipient>,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
     SendShaAtomicSwapTransaction(CommsPublicKey, MicroTari, MicroTari, String),
+    SendHtlcTransaction {
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        hash: HashValue,
+        lock_height: u64,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
     CancelTransaction(TxId),
+    BumpTransactionFee {
+        tx_id: TxId,
+        fee_per_gram: MicroTari,
+    },
     ImportUtxoWithStatus {
         amount: MicroTari,
         source_public_key: CommsPublicKey,
@@ -96,6 +140,17 @@ pub enum TransactionServiceRequest {
         current_height: Option<u64>,
     },
     SubmitTransactionToSelf(TxId, Transaction, MicroTari, MicroTari, String),
+    PrepareOfflineTransaction {
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    },
+    SignOfflineTransaction(Box<UnsignedTransaction>),
+    SubmitOfflineTransaction {
+        unsigned: Box<UnsignedTransaction>,
+        signed: Box<SignedTransaction>,
+    },
     SetLowPowerMode,
     SetNormalPowerMode,
     ApplyEncryption(Box<Aes256Gcm>),
@@ -141,10 +196,36 @@ impl fmt::Display for TransactionServiceRequest {
                 amount,
                 message
             )),
+            Self::SendOneSidedToManyTransaction {
+                recipients, message, ..
+            } => f.write_str(&format!(
+                "SendOneSidedToManyTransaction (to {} recipient(s), {})",
+                recipients.len(),
+                message
+            )),
             Self::SendShaAtomicSwapTransaction(k, v, _, msg) => {
                 f.write_str(&format!("SendShaAtomicSwapTransaction (to {}, {}, {})", k, v, msg))
             },
+            Self::SendHtlcTransaction {
+                dest_pubkey,
+                amount,
+                hash,
+                lock_height,
+                message,
+                ..
+            } => f.write_str(&format!(
+                "SendHtlcTransaction (to {}, {}, hash: {}, lock height: {}, {})",
+                dest_pubkey,
+                amount,
+                hash.to_vec().to_hex(),
+                lock_height,
+                message
+            )),
             Self::CancelTransaction(t) => f.write_str(&format!("CancelTransaction ({})", t)),
+            Self::BumpTransactionFee { tx_id, fee_per_gram } => f.write_str(&format!(
+                "BumpTransactionFee ({}, fee per gram: {})",
+                tx_id, fee_per_gram
+            )),
             Self::ImportUtxoWithStatus {
                 amount,
                 source_public_key,
@@ -164,6 +245,19 @@ impl fmt::Display for TransactionServiceRequest {
                 current_height,
             )),
             Self::SubmitTransactionToSelf(tx_id, _, _, _, _) => f.write_str(&format!("SubmitTransaction ({})", tx_id)),
+            Self::PrepareOfflineTransaction {
+                dest_pubkey, amount, ..
+            } => f.write_str(&format!(
+                "PrepareOfflineTransaction (to {}, {})",
+                dest_pubkey.to_hex(),
+                amount
+            )),
+            Self::SignOfflineTransaction(unsigned) => {
+                f.write_str(&format!("SignOfflineTransaction ({})", unsigned.tx_id))
+            },
+            Self::SubmitOfflineTransaction { signed, .. } => {
+                f.write_str(&format!("SubmitOfflineTransaction ({})", signed.tx_id))
+            },
             Self::SetLowPowerMode => f.write_str("SetLowPowerMode "),
             Self::SetNormalPowerMode => f.write_str("SetNormalPowerMode"),
             Self::ApplyEncryption(_) => f.write_str("ApplyEncryption"),
@@ -176,6 +270,37 @@ impl fmt::Display for TransactionServiceRequest {
             Self::GetNumConfirmationsRequired => f.write_str("GetNumConfirmationsRequired"),
             Self::SetNumConfirmationsRequired(_) => f.write_str("SetNumConfirmationsRequired"),
             Self::GetAnyTransaction(t) => f.write_str(&format!("GetAnyTransaction({})", t)),
+            Self::GetQueuedTransactionMessages(t) => f.write_str(&format!("GetQueuedTransactionMessages({})", t)),
+            Self::SchedulePayment(payment) => f.write_str(&format!(
+                "SchedulePayment (to {}, {}, {})",
+                payment.destination_public_key.to_hex(),
+                payment.amount,
+                payment.message
+            )),
+            Self::GetScheduledPayments => f.write_str("GetScheduledPayments"),
+            Self::CancelScheduledPayment(id) => f.write_str(&format!("CancelScheduledPayment({})", id)),
+            Self::CreateMultisigGroup {
+                threshold,
+                cosigners,
+                amount,
+                ..
+            } => f.write_str(&format!(
+                "CreateMultisigGroup ({}-of-{}, {})",
+                threshold,
+                cosigners.len() + 1,
+                amount
+            )),
+            Self::GetMultisigGroups => f.write_str("GetMultisigGroups"),
+            Self::ProposeMultisigSpend {
+                group_id, destination, ..
+            } => f.write_str(&format!(
+                "ProposeMultisigSpend (group {} to {})",
+                group_id,
+                destination.to_hex()
+            )),
+            Self::ApproveMultisigSpend(id) => f.write_str(&format!("ApproveMultisigSpend({})", id)),
+            Self::CancelMultisigSession(id) => f.write_str(&format!("CancelMultisigSession({})", id)),
+            Self::GetMultisigSessions => f.write_str("GetMultisigSessions"),
             TransactionServiceRequest::ValidateTransactions => f.write_str("ValidateTransactions"),
             TransactionServiceRequest::ReValidateTransactions => f.write_str("ReValidateTransactions"),
         }
@@ -194,6 +319,8 @@ pub enum TransactionServiceResponse {
     BaseNodePublicKeySet,
     UtxoImported(TxId),
     TransactionSubmitted,
+    OfflineTransactionPrepared(Box<UnsignedTransaction>),
+    OfflineTransactionSigned(Box<SignedTransaction>),
     LowPowerModeSet,
     NormalPowerModeSet,
     EncryptionApplied,
@@ -201,11 +328,22 @@ pub enum TransactionServiceResponse {
     CoinbaseTransactionGenerated(Box<Transaction>),
     ProtocolsRestarted,
     AnyTransaction(Box<Option<WalletTransaction>>),
+    QueuedTransactionMessages(Vec<QueuedTransactionMessage>),
+    PaymentScheduled(u64),
+    ScheduledPayments(Vec<ScheduledPayment>),
+    ScheduledPaymentCancelled,
+    MultisigGroupCreated { group_id: u64, tx_id: TxId },
+    MultisigGroups(Vec<MultisigGroup>),
+    MultisigSpendProposed(u64),
+    MultisigSpendApproved,
+    MultisigSessionCancelled,
+    MultisigSessions(Vec<MultisigSession>),
     NumConfirmationsRequired(u64),
     NumConfirmationsSet,
     ValidationStarted(OperationId),
     CompletedTransactionValidityChanged,
     ShaAtomicSwapTransactionSent(Box<(TxId, PublicKey, TransactionOutput)>),
+    HtlcTransactionSent(Box<(TxId, TransactionOutput)>),
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Default)]
@@ -260,6 +398,31 @@ pub enum TransactionEvent {
     TransactionValidationStateChanged(OperationId),
     TransactionValidationCompleted(OperationId),
     TransactionValidationFailed(OperationId),
+    ScheduledPaymentExecuted {
+        payment_id: u64,
+        tx_id: TxId,
+    },
+    ScheduledPaymentDeferred {
+        payment_id: u64,
+        reason: String,
+    },
+    ScheduledPaymentFailed {
+        payment_id: u64,
+        reason: String,
+    },
+    MultisigGroupJoined(u64),
+    MultisigSpendProposalReceived {
+        session_id: u64,
+        group_id: u64,
+    },
+    MultisigSpendCompleted {
+        session_id: u64,
+        tx_id: TxId,
+    },
+    MultisigSessionCancelled {
+        session_id: u64,
+        reason: String,
+    },
     Error(String),
 }
 
@@ -339,6 +502,27 @@ impl fmt::Display for TransactionEvent {
             TransactionEvent::TransactionValidationFailed(operation_id) => {
                 write!(f, "Transaction validation failed: {}", operation_id)
             },
+            TransactionEvent::ScheduledPaymentExecuted { payment_id, tx_id } => {
+                write!(f, "Scheduled payment {} executed as {}", payment_id, tx_id)
+            },
+            TransactionEvent::ScheduledPaymentDeferred { payment_id, reason } => {
+                write!(f, "Scheduled payment {} deferred: {}", payment_id, reason)
+            },
+            TransactionEvent::ScheduledPaymentFailed { payment_id, reason } => {
+                write!(f, "Scheduled payment {} failed: {}", payment_id, reason)
+            },
+            TransactionEvent::MultisigGroupJoined(group_id) => write!(f, "Joined multisig group {}", group_id),
+            TransactionEvent::MultisigSpendProposalReceived { session_id, group_id } => write!(
+                f,
+                "Received multisig spend proposal {} for group {}",
+                session_id, group_id
+            ),
+            TransactionEvent::MultisigSpendCompleted { session_id, tx_id } => {
+                write!(f, "Multisig spend {} completed as {}", session_id, tx_id)
+            },
+            TransactionEvent::MultisigSessionCancelled { session_id, reason } => {
+                write!(f, "Multisig spend {} cancelled: {}", session_id, reason)
+            },
         }
     }
 }
@@ -442,6 +626,27 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Sends a one-sided payment to each recipient in a single transaction
+    pub async fn send_one_sided_to_many_transaction(
+        &mut self,
+        recipients: Vec<PaymentRecipient>,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SendOneSidedToManyTransaction {
+                recipients,
+                fee_per_gram,
+                message,
+            })
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn send_one_sided_transaction_or_token(
         &mut self,
         dest_pubkey: CommsPublicKey,
@@ -479,6 +684,23 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Replace a pending outbound transaction with one that spends the same inputs at a higher fee per gram. The
+    /// original transaction is cancelled and the id of the replacement transaction is returned.
+    pub async fn bump_transaction_fee(
+        &mut self,
+        tx_id: TxId,
+        fee_per_gram: MicroTari,
+    ) -> Result<TxId, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::BumpTransactionFee { tx_id, fee_per_gram })
+            .await??
+        {
+            TransactionServiceResponse::TransactionSent(tx_id) => Ok(tx_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn get_pending_inbound_transactions(
         &mut self,
     ) -> Result<HashMap<TxId, InboundTransaction>, TransactionServiceError> {
@@ -585,6 +807,148 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Returns the messages for the given transaction that are queued for redelivery to the counterparty
+    pub async fn get_queued_transaction_messages(
+        &mut self,
+        tx_id: TxId,
+    ) -> Result<Vec<QueuedTransactionMessage>, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::GetQueuedTransactionMessages(tx_id))
+            .await??
+        {
+            TransactionServiceResponse::QueuedTransactionMessages(messages) => Ok(messages),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Schedule a payment to be sent by the transaction service when it falls due. Returns the id of the scheduled
+    /// payment.
+    pub async fn schedule_payment(&mut self, payment: ScheduledPayment) -> Result<u64, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SchedulePayment(Box::new(payment)))
+            .await??
+        {
+            TransactionServiceResponse::PaymentScheduled(id) => Ok(id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_scheduled_payments(&mut self) -> Result<Vec<ScheduledPayment>, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::GetScheduledPayments)
+            .await??
+        {
+            TransactionServiceResponse::ScheduledPayments(payments) => Ok(payments),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn cancel_scheduled_payment(&mut self, payment_id: u64) -> Result<(), TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::CancelScheduledPayment(payment_id))
+            .await??
+        {
+            TransactionServiceResponse::ScheduledPaymentCancelled => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Fund a new `threshold`-of-n multisig group with `amount` and send a key share to each cosigner. This wallet
+    /// holds the first share. Returns the id of the group and of the funding transaction.
+    pub async fn create_multisig_group(
+        &mut self,
+        threshold: u32,
+        cosigners: Vec<CommsPublicKey>,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(u64, TxId), TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::CreateMultisigGroup {
+                threshold,
+                cosigners,
+                amount,
+                fee_per_gram,
+                message,
+            })
+            .await??
+        {
+            TransactionServiceResponse::MultisigGroupCreated { group_id, tx_id } => Ok((group_id, tx_id)),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_multisig_groups(&mut self) -> Result<Vec<MultisigGroup>, TransactionServiceError> {
+        match self.handle.call(TransactionServiceRequest::GetMultisigGroups).await?? {
+            TransactionServiceResponse::MultisigGroups(groups) => Ok(groups),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Propose spending the funds of a multisig group to a one-sided payment to `destination`. The cosigners have to
+    /// approve the proposal before the payment is made. Returns the id of the signing session.
+    pub async fn propose_multisig_spend(
+        &mut self,
+        group_id: u64,
+        destination: CommsPublicKey,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<u64, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::ProposeMultisigSpend {
+                group_id,
+                destination,
+                fee_per_gram,
+                message,
+            })
+            .await??
+        {
+            TransactionServiceResponse::MultisigSpendProposed(session_id) => Ok(session_id),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Approve a spend proposed by another participant of a multisig group
+    pub async fn approve_multisig_spend(&mut self, session_id: u64) -> Result<(), TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::ApproveMultisigSpend(session_id))
+            .await??
+        {
+            TransactionServiceResponse::MultisigSpendApproved => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Decline a proposal received from another participant, or cancel a spend proposed by this wallet
+    pub async fn cancel_multisig_session(&mut self, session_id: u64) -> Result<(), TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::CancelMultisigSession(session_id))
+            .await??
+        {
+            TransactionServiceResponse::MultisigSessionCancelled => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    pub async fn get_multisig_sessions(&mut self) -> Result<Vec<MultisigSession>, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::GetMultisigSessions)
+            .await??
+        {
+            TransactionServiceResponse::MultisigSessions(sessions) => Ok(sessions),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn import_utxo_with_status(
         &mut self,
         amount: MicroTari,
@@ -633,6 +997,66 @@ impl TransactionServiceHandle {
         }
     }
 
+    /// Select the inputs of a one-sided payment to be signed by an offline wallet. The inputs are reserved until the
+    /// wallet restarts.
+    pub async fn prepare_offline_transaction(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<UnsignedTransaction, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::PrepareOfflineTransaction {
+                dest_pubkey,
+                amount,
+                fee_per_gram,
+                message,
+            })
+            .await??
+        {
+            TransactionServiceResponse::OfflineTransactionPrepared(unsigned) => Ok(*unsigned),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Build and sign a transaction exported by an online wallet. The signed transaction is neither stored nor
+    /// broadcast.
+    pub async fn sign_offline_transaction(
+        &mut self,
+        unsigned: UnsignedTransaction,
+    ) -> Result<SignedTransaction, TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SignOfflineTransaction(Box::new(unsigned)))
+            .await??
+        {
+            TransactionServiceResponse::OfflineTransactionSigned(signed) => Ok(*signed),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
+    /// Broadcast a transaction signed by an offline wallet, after checking that it is the payment `unsigned` that was
+    /// exported by this wallet
+    pub async fn submit_offline_transaction(
+        &mut self,
+        unsigned: UnsignedTransaction,
+        signed: SignedTransaction,
+    ) -> Result<(), TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SubmitOfflineTransaction {
+                unsigned: Box::new(unsigned),
+                signed: Box::new(signed),
+            })
+            .await??
+        {
+            TransactionServiceResponse::TransactionSubmitted => Ok(()),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
+
     pub async fn set_low_power_mode(&mut self) -> Result<(), TransactionServiceError> {
         match self.handle.call(TransactionServiceRequest::SetLowPowerMode).await?? {
             TransactionServiceResponse::LowPowerModeSet => Ok(()),
@@ -779,4 +1203,32 @@ impl TransactionServiceHandle {
             _ => Err(TransactionServiceError::UnexpectedApiResponse),
         }
     }
+
+    /// Lock `amount` in an HTLC output claimable by `dest_pubkey` with the pre-image of `hash`, and refundable to this
+    /// wallet from `lock_height`
+    pub async fn send_htlc_transaction(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        hash: HashValue,
+        lock_height: u64,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<(TxId, TransactionOutput), TransactionServiceError> {
+        match self
+            .handle
+            .call(TransactionServiceRequest::SendHtlcTransaction {
+                dest_pubkey,
+                amount,
+                hash,
+                lock_height,
+                fee_per_gram,
+                message,
+            })
+            .await??
+        {
+            TransactionServiceResponse::HtlcTransactionSent(boxed) => Ok(*boxed),
+            _ => Err(TransactionServiceError::UnexpectedApiResponse),
+        }
+    }
 }
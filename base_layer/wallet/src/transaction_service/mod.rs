@@ -48,6 +48,7 @@ use tokio::sync::broadcast;
 use crate::{
     base_node_service::handle::BaseNodeServiceHandle,
     connectivity_service::WalletConnectivityHandle,
+    contacts_service::handle::ContactsServiceHandle,
     output_manager_service::handle::OutputManagerHandle,
     storage::database::{WalletBackend, WalletDatabase},
     transaction_service::{
@@ -61,6 +62,8 @@ use crate::{
 pub mod config;
 pub mod error;
 pub mod handle;
+pub mod multisig;
+pub mod offline_signing;
 pub mod protocols;
 pub mod service;
 pub mod storage;
@@ -171,6 +174,19 @@ where
             .map(map_decode::<proto::TransactionCancelledMessage>)
             .filter_map(ok_or_skip_result)
     }
+
+    fn multisig_stream(&self) -> impl Stream<Item = DomainMessage<proto::MultisigMessage>> {
+        trace!(
+            target: LOG_TARGET,
+            "Subscription '{}' for topic '{:?}' created.",
+            SUBSCRIPTION_LABEL,
+            TariMessageType::MultisigNegotiation
+        );
+        self.subscription_factory
+            .get_subscription(TariMessageType::MultisigNegotiation, SUBSCRIPTION_LABEL)
+            .map(map_decode::<proto::MultisigMessage>)
+            .filter_map(ok_or_skip_result)
+    }
 }
 
 #[async_trait]
@@ -186,6 +202,7 @@ where
         let transaction_finalized_stream = self.transaction_finalized_stream();
         let base_node_response_stream = self.base_node_response_stream();
         let transaction_cancelled_stream = self.transaction_cancelled_stream();
+        let multisig_stream = self.multisig_stream();
 
         let (publisher, _) = broadcast::channel(self.config.transaction_event_channel_size);
 
@@ -213,6 +230,7 @@ where
             let output_manager_service = handles.expect_handle::<OutputManagerHandle>();
             let connectivity = handles.expect_handle::<WalletConnectivityHandle>();
             let base_node_service_handle = handles.expect_handle::<BaseNodeServiceHandle>();
+            let contacts_service_handle = handles.get_handle::<ContactsServiceHandle>();
 
             let result = TransactionService::new(
                 config,
@@ -224,6 +242,7 @@ where
                 transaction_finalized_stream,
                 base_node_response_stream,
                 transaction_cancelled_stream,
+                multisig_stream,
                 output_manager_service,
                 outbound_message_service,
                 connectivity,
@@ -232,6 +251,7 @@ where
                 factories,
                 handles.get_shutdown_signal(),
                 base_node_service_handle,
+                contacts_service_handle,
             )
             .start()
             .await;
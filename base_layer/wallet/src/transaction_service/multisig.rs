@@ -0,0 +1,1143 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//! Multisig groups and the negotiation used to spend their funds.
+//!
+//! A group is set up by a dealer, which funds a group output and sends every cosigner a key share for it (see
+//! [tari_core::transactions::transaction_protocol::multisig]). The dealer holds share 1 and the cosigners hold the
+//! following shares, in the order they were listed when the group was created.
+//!
+//! Any participant can propose to spend the whole group output to a one-sided payment. A [MultisigSession] tracks the
+//! proposal on every participant: the proposer collects nonces from the first cosigners to approve, sends them a
+//! signing request with the nonce sums once it has enough signers, and completes the transaction with their partial
+//! signatures.
+
+use std::{
+    convert::TryFrom,
+    fmt::{Display, Error, Formatter},
+};
+
+use chrono::{NaiveDateTime, Utc};
+use derivative::Derivative;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use tari_common_types::{
+    transaction::{TransactionConversionError, TxId},
+    types::{Commitment, PrivateKey, PublicKey, Signature},
+};
+use tari_comms::types::CommsPublicKey;
+use tari_core::{
+    consensus::ConsensusEncodingSized,
+    covenants::Covenant,
+    transactions::{
+        fee::Fee,
+        tari_amount::MicroTari,
+        transaction_components::{
+            OutputFeatures,
+            Transaction,
+            TransactionOutput,
+            TransactionOutputVersion,
+            UnblindedOutput,
+        },
+        transaction_protocol::{
+            build_challenge,
+            multisig::{
+                build_multisig_spend_transaction,
+                multisig_script,
+                partial_kernel_signature,
+                partial_script_signature,
+                script_nonce_sum,
+                script_signature_challenge,
+                verify_key_share,
+                verify_partial_kernel_signature,
+                verify_partial_script_signature,
+                MultisigKeyShare,
+                MultisigPrivateNonces,
+            },
+            proto::protocol as proto,
+            RewindData,
+            TransactionMetadata,
+        },
+        weight::TransactionWeight,
+        CryptoFactories,
+    },
+};
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    keys::{DiffieHellmanSharedSecret, PublicKey as PublicKeyTrait, SecretKey},
+    range_proof::REWIND_USER_MESSAGE_LENGTH,
+};
+use tari_script::{inputs, script};
+use tari_utilities::ByteArray;
+
+use crate::transaction_service::{error::TransactionServiceError, service::hash_secret_key};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MultisigGroupStatus {
+    Active, // 0
+    /// The group output has been spent
+    Spent, // 1
+}
+
+impl TryFrom<i32> for MultisigGroupStatus {
+    type Error = TransactionConversionError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(MultisigGroupStatus::Active),
+            1 => Ok(MultisigGroupStatus::Spent),
+            code => Err(TransactionConversionError { code }),
+        }
+    }
+}
+
+impl Display for MultisigGroupStatus {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            MultisigGroupStatus::Active => fmt.write_str("Active"),
+            MultisigGroupStatus::Spent => fmt.write_str("Spent"),
+        }
+    }
+}
+
+/// A multisig group this wallet is a participant in, together with this wallet's key share
+#[derive(Derivative, Clone, PartialEq)]
+#[derivative(Debug)]
+pub struct MultisigGroup {
+    pub group_id: u64,
+    pub threshold: u32,
+    /// The comms public keys of the participants. The participant at position `i` holds the share with index `i + 1`.
+    pub participants: Vec<CommsPublicKey>,
+    pub key_share: MultisigKeyShare,
+    /// The dealer's commitments to the sharing polynomial. The first commitment is the group public key.
+    pub share_commitments: Vec<PublicKey>,
+    pub output: TransactionOutput,
+    pub value: MicroTari,
+    /// The script key of the group output, which every participant knows
+    #[derivative(Debug = "ignore")]
+    pub script_private_key: PrivateKey,
+    pub status: MultisigGroupStatus,
+    pub created_at: NaiveDateTime,
+}
+
+impl MultisigGroup {
+    pub fn new(
+        threshold: u32,
+        participants: Vec<CommsPublicKey>,
+        key_share: MultisigKeyShare,
+        share_commitments: Vec<PublicKey>,
+        output: TransactionOutput,
+        value: MicroTari,
+        script_private_key: PrivateKey,
+    ) -> Self {
+        Self {
+            group_id: OsRng.next_u64(),
+            threshold,
+            participants,
+            key_share,
+            share_commitments,
+            output,
+            value,
+            script_private_key,
+            status: MultisigGroupStatus::Active,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    pub fn group_public_key(&self) -> &PublicKey {
+        &self.share_commitments[0]
+    }
+
+    /// The share index of the participant with the given public key
+    pub fn participant_index(&self, public_key: &CommsPublicKey) -> Option<u32> {
+        self.participants
+            .iter()
+            .position(|p| p == public_key)
+            .map(|pos| pos as u32 + 1)
+    }
+
+    pub fn participant(&self, index: u32) -> Option<&CommsPublicKey> {
+        index.checked_sub(1).and_then(|pos| self.participants.get(pos as usize))
+    }
+
+    /// Check that the key share and the group output are consistent with the published share commitments
+    pub fn validate(&self, factories: &CryptoFactories) -> Result<(), TransactionServiceError> {
+        if self.threshold == 0 ||
+            self.threshold as usize > self.participants.len() ||
+            self.share_commitments.len() != self.threshold as usize
+        {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "A threshold of {} with {} participants and {} share commitments is not valid",
+                self.threshold,
+                self.participants.len(),
+                self.share_commitments.len()
+            )));
+        }
+        if !verify_key_share(&self.key_share, &self.share_commitments) {
+            return Err(TransactionServiceError::MultisigError(
+                "The key share does not match the share commitments".to_string(),
+            ));
+        }
+        let expected_commitment = &Commitment::from_public_key(self.group_public_key()) +
+            &factories
+                .commitment
+                .commit_value(&PrivateKey::default(), self.value.as_u64());
+        if self.output.commitment != expected_commitment || self.output.script != multisig_script() {
+            return Err(TransactionServiceError::MultisigError(
+                "The group output is not locked to the group key".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The message that sends `share` to its participant
+    pub fn key_share_message(&self, share: &MultisigKeyShare) -> proto::MultisigKeyShare {
+        proto::MultisigKeyShare {
+            group_id: self.group_id,
+            threshold: self.threshold,
+            participants: self.participants.iter().map(|p| p.to_vec()).collect(),
+            index: share.index,
+            share: share.secret.to_vec(),
+            share_commitments: self.share_commitments.iter().map(|c| c.to_vec()).collect(),
+            output: Some(self.output.clone().into()),
+            value: self.value.as_u64(),
+            script_private_key: self.script_private_key.to_vec(),
+        }
+    }
+}
+
+impl TryFrom<proto::MultisigKeyShare> for MultisigGroup {
+    type Error = String;
+
+    fn try_from(message: proto::MultisigKeyShare) -> Result<Self, Self::Error> {
+        let participants = message
+            .participants
+            .iter()
+            .map(|p| CommsPublicKey::from_bytes(p))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid participant public key: {}", e))?;
+        let share_commitments = message
+            .share_commitments
+            .iter()
+            .map(|c| PublicKey::from_bytes(c))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid share commitment: {}", e))?;
+        let output = message
+            .output
+            .map(TransactionOutput::try_from)
+            .ok_or_else(|| "Group output not provided".to_string())??;
+        Ok(Self {
+            group_id: message.group_id,
+            threshold: message.threshold,
+            participants,
+            key_share: MultisigKeyShare {
+                index: message.index,
+                secret: PrivateKey::from_bytes(&message.share).map_err(|e| format!("Invalid key share: {}", e))?,
+            },
+            share_commitments,
+            output,
+            value: MicroTari::from(message.value),
+            script_private_key: PrivateKey::from_bytes(&message.script_private_key)
+                .map_err(|e| format!("Invalid script private key: {}", e))?,
+            status: MultisigGroupStatus::Active,
+            created_at: Utc::now().naive_utc(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MultisigRole {
+    Proposer, // 0
+    Cosigner, // 1
+}
+
+impl TryFrom<i32> for MultisigRole {
+    type Error = TransactionConversionError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(MultisigRole::Proposer),
+            1 => Ok(MultisigRole::Cosigner),
+            code => Err(TransactionConversionError { code }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MultisigSessionState {
+    /// A cosigner has received a proposal that the user has not approved yet
+    AwaitingApproval, // 0
+    /// The proposer is waiting for enough cosigners to send their nonces
+    CollectingNonces, // 1
+    /// A cosigner has approved the proposal and is waiting for a signing request
+    NoncesSent, // 2
+    /// The proposer is waiting for the partial signatures of the signers
+    CollectingSignatures, // 3
+    /// A cosigner has sent its partial signatures
+    Signed, // 4
+    Completed, // 5
+    Declined,  // 6
+    Cancelled, // 7
+}
+
+impl TryFrom<i32> for MultisigSessionState {
+    type Error = TransactionConversionError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(MultisigSessionState::AwaitingApproval),
+            1 => Ok(MultisigSessionState::CollectingNonces),
+            2 => Ok(MultisigSessionState::NoncesSent),
+            3 => Ok(MultisigSessionState::CollectingSignatures),
+            4 => Ok(MultisigSessionState::Signed),
+            5 => Ok(MultisigSessionState::Completed),
+            6 => Ok(MultisigSessionState::Declined),
+            7 => Ok(MultisigSessionState::Cancelled),
+            code => Err(TransactionConversionError { code }),
+        }
+    }
+}
+
+impl Display for MultisigSessionState {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            MultisigSessionState::AwaitingApproval => fmt.write_str("Awaiting approval"),
+            MultisigSessionState::CollectingNonces => fmt.write_str("Collecting nonces"),
+            MultisigSessionState::NoncesSent => fmt.write_str("Nonces sent"),
+            MultisigSessionState::CollectingSignatures => fmt.write_str("Collecting signatures"),
+            MultisigSessionState::Signed => fmt.write_str("Signed"),
+            MultisigSessionState::Completed => fmt.write_str("Completed"),
+            MultisigSessionState::Declined => fmt.write_str("Declined"),
+            MultisigSessionState::Cancelled => fmt.write_str("Cancelled"),
+        }
+    }
+}
+
+/// The public nonces a signer sends to the proposer in the first round of a spend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultisigPublicNonces {
+    pub index: u32,
+    pub kernel_nonce: PublicKey,
+    pub script_nonce: PublicKey,
+}
+
+impl MultisigPublicNonces {
+    pub fn to_proto(&self, session_id: u64) -> proto::MultisigNonces {
+        proto::MultisigNonces {
+            session_id,
+            index: self.index,
+            kernel_nonce: self.kernel_nonce.to_vec(),
+            script_nonce: self.script_nonce.to_vec(),
+        }
+    }
+}
+
+impl TryFrom<proto::MultisigNonces> for MultisigPublicNonces {
+    type Error = String;
+
+    fn try_from(message: proto::MultisigNonces) -> Result<Self, Self::Error> {
+        Ok(Self {
+            index: message.index,
+            kernel_nonce: PublicKey::from_bytes(&message.kernel_nonce).map_err(|e| e.to_string())?,
+            script_nonce: PublicKey::from_bytes(&message.script_nonce).map_err(|e| e.to_string())?,
+        })
+    }
+}
+
+/// The signers chosen by the proposer and the sums of their nonces
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultisigSigningRequest {
+    pub signers: Vec<u32>,
+    pub kernel_nonce_sum: PublicKey,
+    pub script_nonce_sum: Commitment,
+}
+
+impl MultisigSigningRequest {
+    pub fn to_proto(&self, session_id: u64) -> proto::MultisigSigningRequest {
+        proto::MultisigSigningRequest {
+            session_id,
+            signers: self.signers.clone(),
+            kernel_nonce_sum: self.kernel_nonce_sum.to_vec(),
+            script_nonce_sum: Some(self.script_nonce_sum.clone().into()),
+        }
+    }
+}
+
+impl TryFrom<proto::MultisigSigningRequest> for MultisigSigningRequest {
+    type Error = String;
+
+    fn try_from(message: proto::MultisigSigningRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            signers: message.signers,
+            kernel_nonce_sum: PublicKey::from_bytes(&message.kernel_nonce_sum).map_err(|e| e.to_string())?,
+            script_nonce_sum: message
+                .script_nonce_sum
+                .map(Commitment::try_from)
+                .ok_or_else(|| "Script nonce sum not provided".to_string())?
+                .map_err(|e| e.to_string())?,
+        })
+    }
+}
+
+/// A signer's partial kernel and script signatures
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultisigPartialSignatures {
+    pub index: u32,
+    pub kernel_signature: Signature,
+    pub script_signature: Signature,
+}
+
+impl MultisigPartialSignatures {
+    pub fn to_proto(&self, session_id: u64) -> proto::MultisigPartialSignatures {
+        proto::MultisigPartialSignatures {
+            session_id,
+            index: self.index,
+            kernel_signature: Some(self.kernel_signature.clone().into()),
+            script_signature: Some(self.script_signature.clone().into()),
+        }
+    }
+}
+
+impl TryFrom<proto::MultisigPartialSignatures> for MultisigPartialSignatures {
+    type Error = String;
+
+    fn try_from(message: proto::MultisigPartialSignatures) -> Result<Self, Self::Error> {
+        Ok(Self {
+            index: message.index,
+            kernel_signature: message
+                .kernel_signature
+                .map(Signature::try_from)
+                .ok_or_else(|| "Kernel signature not provided".to_string())?
+                .map_err(|e| e.to_string())?,
+            script_signature: message
+                .script_signature
+                .map(Signature::try_from)
+                .ok_or_else(|| "Script signature not provided".to_string())?
+                .map_err(|e| e.to_string())?,
+        })
+    }
+}
+
+/// The public state of a spend negotiation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MultisigNegotiation {
+    /// The nonces received from cosigners, in the order they arrived
+    pub nonces: Vec<MultisigPublicNonces>,
+    pub signing_request: Option<MultisigSigningRequest>,
+    /// The verified partial signatures received from cosigners
+    pub partial_signatures: Vec<MultisigPartialSignatures>,
+}
+
+/// A proposal to spend a group output, as seen by one participant
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultisigSession {
+    pub session_id: u64,
+    pub group_id: u64,
+    pub role: MultisigRole,
+    pub proposer: CommsPublicKey,
+    pub destination_public_key: CommsPublicKey,
+    /// The amount paid to the destination, which is the group value less the fee
+    pub amount: MicroTari,
+    pub fee: MicroTari,
+    pub lock_height: u64,
+    pub message: String,
+    pub state: MultisigSessionState,
+    /// This participant's nonces. They are discarded as soon as they have been used to sign, so that they can never be
+    /// used for a second signature.
+    pub private_nonces: Option<MultisigPrivateNonces>,
+    pub negotiation: MultisigNegotiation,
+    pub tx_id: Option<TxId>,
+    pub failure_reason: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl MultisigSession {
+    /// Propose spending the group output, less `fee`, to a one-sided payment to `destination_public_key`
+    pub fn propose(
+        group: &MultisigGroup,
+        proposer: CommsPublicKey,
+        destination_public_key: CommsPublicKey,
+        fee: MicroTari,
+        lock_height: u64,
+        message: String,
+    ) -> Result<Self, TransactionServiceError> {
+        if group.status != MultisigGroupStatus::Active {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Multisig group {} has already been spent",
+                group.group_id
+            )));
+        }
+        if fee >= group.value {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "The fee of {} is more than the group balance of {}",
+                fee, group.value
+            )));
+        }
+        Ok(Self {
+            session_id: OsRng.next_u64(),
+            group_id: group.group_id,
+            role: MultisigRole::Proposer,
+            proposer,
+            destination_public_key,
+            amount: group.value - fee,
+            fee,
+            lock_height,
+            message,
+            state: MultisigSessionState::CollectingNonces,
+            private_nonces: Some(MultisigPrivateNonces::random()),
+            negotiation: MultisigNegotiation::default(),
+            tx_id: None,
+            failure_reason: None,
+            created_at: Utc::now().naive_utc(),
+        })
+    }
+
+    /// Create a cosigner's session for a proposal received from `proposer`
+    pub fn from_proposal(
+        group: &MultisigGroup,
+        proposer: CommsPublicKey,
+        proposal: proto::MultisigSpendProposal,
+    ) -> Result<Self, TransactionServiceError> {
+        match group.participant_index(&proposer) {
+            Some(index) if index != group.key_share.index => {},
+            _ => {
+                return Err(TransactionServiceError::MultisigError(format!(
+                    "The proposer is not a cosigner of group {}",
+                    group.group_id
+                )))
+            },
+        }
+        if group.status != MultisigGroupStatus::Active {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Multisig group {} has already been spent",
+                group.group_id
+            )));
+        }
+        let amount = MicroTari::from(proposal.amount);
+        let fee = MicroTari::from(proposal.fee);
+        if proposal.amount.checked_add(proposal.fee) != Some(group.value.as_u64()) {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "The proposal spends {} with a fee of {}, but the group holds {}",
+                amount, fee, group.value
+            )));
+        }
+        Ok(Self {
+            session_id: proposal.session_id,
+            group_id: group.group_id,
+            role: MultisigRole::Cosigner,
+            proposer,
+            destination_public_key: CommsPublicKey::from_bytes(&proposal.destination_public_key)?,
+            amount,
+            fee,
+            lock_height: proposal.lock_height,
+            message: proposal.message,
+            state: MultisigSessionState::AwaitingApproval,
+            private_nonces: None,
+            negotiation: MultisigNegotiation::default(),
+            tx_id: None,
+            failure_reason: None,
+            created_at: Utc::now().naive_utc(),
+        })
+    }
+
+    pub fn to_proposal(&self) -> proto::MultisigSpendProposal {
+        proto::MultisigSpendProposal {
+            session_id: self.session_id,
+            group_id: self.group_id,
+            destination_public_key: self.destination_public_key.to_vec(),
+            amount: self.amount.as_u64(),
+            fee: self.fee.as_u64(),
+            lock_height: self.lock_height,
+            message: self.message.clone(),
+        }
+    }
+
+    /// Approve a proposal, returning the nonces to send to the proposer
+    pub fn approve(&mut self, group: &MultisigGroup) -> Result<MultisigPublicNonces, TransactionServiceError> {
+        if self.role != MultisigRole::Cosigner || self.state != MultisigSessionState::AwaitingApproval {
+            return Err(TransactionServiceError::InvalidStateError);
+        }
+        let nonces = MultisigPrivateNonces::random();
+        let public_nonces = MultisigPublicNonces {
+            index: group.key_share.index,
+            kernel_nonce: nonces.public_kernel_nonce(),
+            script_nonce: nonces.public_script_nonce(),
+        };
+        self.private_nonces = Some(nonces);
+        self.state = MultisigSessionState::NoncesSent;
+        Ok(public_nonces)
+    }
+
+    pub fn decline(&mut self) -> Result<(), TransactionServiceError> {
+        if self.role != MultisigRole::Cosigner || self.state != MultisigSessionState::AwaitingApproval {
+            return Err(TransactionServiceError::InvalidStateError);
+        }
+        self.state = MultisigSessionState::Declined;
+        Ok(())
+    }
+
+    /// Add the nonces of a cosigner that approved the proposal. Returns the signing request to send to the signers once
+    /// enough cosigners have approved.
+    pub fn add_nonces(
+        &mut self,
+        group: &MultisigGroup,
+        nonces: MultisigPublicNonces,
+        factories: &CryptoFactories,
+    ) -> Result<Option<MultisigSigningRequest>, TransactionServiceError> {
+        if self.role != MultisigRole::Proposer || self.state != MultisigSessionState::CollectingNonces {
+            return Err(TransactionServiceError::InvalidStateError);
+        }
+        if nonces.index == group.key_share.index ||
+            group.participant(nonces.index).is_none() ||
+            self.negotiation.nonces.iter().any(|n| n.index == nonces.index)
+        {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Unexpected nonces from participant {}",
+                nonces.index
+            )));
+        }
+        self.negotiation.nonces.push(nonces);
+        Ok(self.start_signing(group, factories))
+    }
+
+    /// Choose the signers and move on to the signing round, if enough cosigners have sent their nonces
+    pub fn start_signing(
+        &mut self,
+        group: &MultisigGroup,
+        factories: &CryptoFactories,
+    ) -> Option<MultisigSigningRequest> {
+        let num_cosigners = (group.threshold as usize).checked_sub(1)?;
+        if self.state != MultisigSessionState::CollectingNonces || self.negotiation.nonces.len() < num_cosigners {
+            return None;
+        }
+        let own_nonces = self.private_nonces.as_ref()?;
+        let cosigners = &self.negotiation.nonces[..num_cosigners];
+        let mut signers = cosigners.iter().map(|n| n.index).collect::<Vec<_>>();
+        signers.push(group.key_share.index);
+        signers.sort_unstable();
+        let kernel_nonce_sum = cosigners
+            .iter()
+            .fold(own_nonces.public_kernel_nonce(), |acc, n| &acc + &n.kernel_nonce);
+        let script_nonces = cosigners.iter().map(|n| n.script_nonce.clone()).collect::<Vec<_>>();
+        let request = MultisigSigningRequest {
+            signers,
+            kernel_nonce_sum,
+            script_nonce_sum: script_nonce_sum(own_nonces, &script_nonces, factories),
+        };
+        self.negotiation.signing_request = Some(request.clone());
+        self.state = MultisigSessionState::CollectingSignatures;
+        Some(request)
+    }
+
+    /// Create this cosigner's partial signatures for a signing request from the proposer
+    pub fn sign(
+        &mut self,
+        group: &MultisigGroup,
+        request: MultisigSigningRequest,
+    ) -> Result<MultisigPartialSignatures, TransactionServiceError> {
+        if self.role != MultisigRole::Cosigner || self.state != MultisigSessionState::NoncesSent {
+            return Err(TransactionServiceError::InvalidStateError);
+        }
+        if request.signers.len() != group.threshold as usize || !request.signers.contains(&group.key_share.index) {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Invalid signer set {:?}",
+                request.signers
+            )));
+        }
+        let nonces = self
+            .private_nonces
+            .take()
+            .ok_or(TransactionServiceError::InvalidStateError)?;
+        let kernel_challenge = build_challenge(&request.kernel_nonce_sum, &self.metadata());
+        let script_challenge =
+            script_signature_challenge(&request.script_nonce_sum, &group.output, &group.script_private_key);
+        let signatures = MultisigPartialSignatures {
+            index: group.key_share.index,
+            kernel_signature: partial_kernel_signature(
+                &group.key_share,
+                &request.signers,
+                nonces.kernel_nonce,
+                &kernel_challenge,
+            )?,
+            script_signature: partial_script_signature(
+                &group.key_share,
+                &request.signers,
+                nonces.script_nonce,
+                &script_challenge,
+            )?,
+        };
+        self.negotiation.signing_request = Some(request);
+        self.state = MultisigSessionState::Signed;
+        Ok(signatures)
+    }
+
+    /// Verify and add the partial signatures of a signer
+    pub fn add_partial_signatures(
+        &mut self,
+        group: &MultisigGroup,
+        signatures: MultisigPartialSignatures,
+    ) -> Result<(), TransactionServiceError> {
+        if self.role != MultisigRole::Proposer || self.state != MultisigSessionState::CollectingSignatures {
+            return Err(TransactionServiceError::InvalidStateError);
+        }
+        let request = self
+            .negotiation
+            .signing_request
+            .as_ref()
+            .ok_or(TransactionServiceError::InvalidStateError)?;
+        let nonces = self.negotiation.nonces.iter().find(|n| n.index == signatures.index);
+        let is_expected = signatures.index != group.key_share.index &&
+            request.signers.contains(&signatures.index) &&
+            !self
+                .negotiation
+                .partial_signatures
+                .iter()
+                .any(|s| s.index == signatures.index);
+        let nonces = match nonces {
+            Some(nonces) if is_expected => nonces,
+            _ => {
+                return Err(TransactionServiceError::MultisigError(format!(
+                    "Unexpected partial signatures from participant {}",
+                    signatures.index
+                )))
+            },
+        };
+
+        let kernel_challenge = build_challenge(&request.kernel_nonce_sum, &self.metadata());
+        let script_challenge =
+            script_signature_challenge(&request.script_nonce_sum, &group.output, &group.script_private_key);
+        let is_valid = signatures.kernel_signature.get_public_nonce() == &nonces.kernel_nonce &&
+            signatures.script_signature.get_public_nonce() == &nonces.script_nonce &&
+            verify_partial_kernel_signature(
+                &signatures.kernel_signature,
+                signatures.index,
+                &request.signers,
+                &group.share_commitments,
+                &kernel_challenge,
+            )? &&
+            verify_partial_script_signature(
+                &signatures.script_signature,
+                signatures.index,
+                &request.signers,
+                &group.share_commitments,
+                &script_challenge,
+            )?;
+        if !is_valid {
+            return Err(TransactionServiceError::MultisigError(format!(
+                "Invalid partial signatures from participant {}",
+                signatures.index
+            )));
+        }
+        self.negotiation.partial_signatures.push(signatures);
+        Ok(())
+    }
+
+    /// Whether the proposer has the partial signatures of every signer
+    pub fn has_all_signatures(&self, group: &MultisigGroup) -> bool {
+        self.role == MultisigRole::Proposer &&
+            self.state == MultisigSessionState::CollectingSignatures &&
+            self.negotiation.partial_signatures.len() + 1 == group.threshold as usize
+    }
+
+    /// Complete the spend with the collected partial signatures. `output` is the payment to the destination, built
+    /// with `output_spending_key` and `sender_offset_private_key`.
+    pub fn build_transaction(
+        &self,
+        group: &MultisigGroup,
+        output: TransactionOutput,
+        output_spending_key: &PrivateKey,
+        sender_offset_private_key: &PrivateKey,
+        factories: &CryptoFactories,
+    ) -> Result<Transaction, TransactionServiceError> {
+        if !self.has_all_signatures(group) {
+            return Err(TransactionServiceError::InvalidStateError);
+        }
+        let nonces = self
+            .private_nonces
+            .as_ref()
+            .ok_or(TransactionServiceError::InvalidStateError)?;
+        let request = self
+            .negotiation
+            .signing_request
+            .as_ref()
+            .ok_or(TransactionServiceError::InvalidStateError)?;
+        let (kernel_signatures, script_signatures): (Vec<_>, Vec<_>) = self
+            .negotiation
+            .partial_signatures
+            .iter()
+            .map(|s| (s.kernel_signature.clone(), s.script_signature.clone()))
+            .unzip();
+        Ok(build_multisig_spend_transaction(
+            &group.output,
+            group.value,
+            group.group_public_key(),
+            &group.script_private_key,
+            &group.key_share,
+            &request.signers,
+            output,
+            output_spending_key,
+            sender_offset_private_key,
+            &self.metadata(),
+            nonces,
+            &kernel_signatures,
+            &script_signatures,
+            factories,
+        )?)
+    }
+
+    pub fn complete(&mut self, tx_id: Option<TxId>) {
+        self.state = MultisigSessionState::Completed;
+        self.tx_id = tx_id;
+        self.private_nonces = None;
+    }
+
+    pub fn cancel(&mut self, reason: String) {
+        self.state = MultisigSessionState::Cancelled;
+        self.failure_reason = Some(reason);
+        self.private_nonces = None;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.state,
+            MultisigSessionState::Completed | MultisigSessionState::Declined | MultisigSessionState::Cancelled
+        )
+    }
+
+    fn metadata(&self) -> TransactionMetadata {
+        TransactionMetadata {
+            fee: self.fee,
+            lock_height: self.lock_height,
+        }
+    }
+}
+
+/// The fee for spending a group output to a one-sided payment to `destination_public_key`
+pub fn spend_fee(fee_per_gram: MicroTari, destination_public_key: &CommsPublicKey) -> MicroTari {
+    let fee = Fee::new(TransactionWeight::latest());
+    let metadata_byte_size = fee.weighting().round_up_metadata_size(
+        OutputFeatures::default().consensus_encode_exact_size() +
+            script!(PushPubKey(Box::new(destination_public_key.clone()))).consensus_encode_exact_size() +
+            Covenant::default().consensus_encode_exact_size(),
+    );
+    fee.calculate(fee_per_gram, 1, 1, 1, metadata_byte_size)
+}
+
+/// Build a one-sided payment output of `amount` to `destination_public_key`. Returns the output together with its
+/// spending key and sender offset private key, which are needed to complete the spend.
+pub fn one_sided_output(
+    destination_public_key: &CommsPublicKey,
+    amount: MicroTari,
+    factories: &CryptoFactories,
+) -> Result<(TransactionOutput, PrivateKey, PrivateKey), TransactionServiceError> {
+    let sender_offset_private_key = PrivateKey::random(&mut OsRng);
+    let spending_key = PrivateKey::from_bytes(
+        CommsPublicKey::shared_secret(&sender_offset_private_key, destination_public_key).as_bytes(),
+    )?;
+    let rewind_blinding_key = PrivateKey::from_bytes(&hash_secret_key(&spending_key))?;
+    let rewind_key = PrivateKey::from_bytes(&hash_secret_key(&rewind_blinding_key))?;
+    let recovery_byte_key = PrivateKey::from_bytes(&hash_secret_key(&rewind_key))?;
+    let rewind_data = RewindData {
+        rewind_key,
+        rewind_blinding_key,
+        recovery_byte_key,
+        proof_message: [0u8; REWIND_USER_MESSAGE_LENGTH],
+    };
+
+    let script = script!(PushPubKey(Box::new(destination_public_key.clone())));
+    let covenant = Covenant::default();
+    let commitment = factories.commitment.commit_value(&spending_key, amount.as_u64());
+    let features = OutputFeatures {
+        recovery_byte: OutputFeatures::create_unique_recovery_byte(&commitment, Some(&rewind_data)),
+        ..Default::default()
+    };
+    let metadata_signature = TransactionOutput::create_final_metadata_signature(
+        TransactionOutputVersion::get_current_version(),
+        amount,
+        &spending_key,
+        &script,
+        &features,
+        &sender_offset_private_key,
+        &covenant,
+    )?;
+    let output = UnblindedOutput::new_current_version(
+        amount,
+        spending_key.clone(),
+        features,
+        script,
+        inputs!(PublicKey::default()),
+        PrivateKey::default(),
+        PublicKey::from_secret_key(&sender_offset_private_key),
+        metadata_signature,
+        0,
+        covenant,
+    )
+    .as_rewindable_transaction_output(factories, &rewind_data, None)?;
+    Ok((output, spending_key, sender_offset_private_key))
+}
+
+#[cfg(test)]
+mod test {
+    use tari_core::transactions::{
+        tari_amount::uT,
+        transaction_protocol::multisig::{multisig_input_data, split_secret},
+    };
+
+    use super::*;
+
+    const VALUE: MicroTari = MicroTari(1_000_000);
+
+    /// Deal a group and return the view of it held by each participant, in share index order
+    fn setup_group(threshold: usize, num_participants: usize, factories: &CryptoFactories) -> Vec<MultisigGroup> {
+        let participants = (0..num_participants)
+            .map(|_| PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)))
+            .collect::<Vec<_>>();
+        let spending_key = PrivateKey::random(&mut OsRng);
+        let script_private_key = PrivateKey::random(&mut OsRng);
+        let sender_offset_private_key = PrivateKey::random(&mut OsRng);
+        let (mut shares, commitments) = split_secret(&spending_key, threshold, num_participants).unwrap();
+
+        let features = OutputFeatures::default();
+        let metadata_signature = TransactionOutput::create_final_metadata_signature(
+            TransactionOutputVersion::get_current_version(),
+            VALUE,
+            &spending_key,
+            &multisig_script(),
+            &features,
+            &sender_offset_private_key,
+            &Covenant::default(),
+        )
+        .unwrap();
+        let output = UnblindedOutput::new_current_version(
+            VALUE,
+            spending_key,
+            features,
+            multisig_script(),
+            multisig_input_data(&script_private_key),
+            script_private_key.clone(),
+            PublicKey::from_secret_key(&sender_offset_private_key),
+            metadata_signature,
+            0,
+            Covenant::default(),
+        )
+        .as_transaction_output(factories)
+        .unwrap();
+
+        let dealer = MultisigGroup::new(
+            threshold as u32,
+            participants,
+            shares.remove(0),
+            commitments,
+            output,
+            VALUE,
+            script_private_key,
+        );
+        dealer.validate(factories).unwrap();
+        let mut groups = vec![dealer.clone()];
+        for share in shares {
+            let mut group = MultisigGroup::try_from(dealer.key_share_message(&share)).unwrap();
+            group.validate(factories).unwrap();
+            group.created_at = dealer.created_at;
+            groups.push(group);
+        }
+        groups
+    }
+
+    /// Run the two signing rounds between a proposer and the cosigners that approve, in the order they approve
+    fn negotiate(
+        groups: &[MultisigGroup],
+        proposer: usize,
+        approvers: &[usize],
+        factories: &CryptoFactories,
+    ) -> (MultisigSession, PublicKey) {
+        let destination = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        let proposer_key = groups[proposer].participants[proposer].clone();
+        let fee = spend_fee(MicroTari::from(5), &destination);
+        let mut session = MultisigSession::propose(
+            &groups[proposer],
+            proposer_key.clone(),
+            destination.clone(),
+            fee,
+            0,
+            "Group payment".to_string(),
+        )
+        .unwrap();
+        assert_eq!(session.amount + session.fee, VALUE);
+
+        let mut cosigners = Vec::new();
+        let mut request = session.start_signing(&groups[proposer], factories);
+        for &i in approvers {
+            let mut cosigner =
+                MultisigSession::from_proposal(&groups[i], proposer_key.clone(), session.to_proposal()).unwrap();
+            assert_eq!(cosigner.state, MultisigSessionState::AwaitingApproval);
+            let nonces = cosigner.approve(&groups[i]).unwrap();
+            let nonces = MultisigPublicNonces::try_from(nonces.to_proto(session.session_id)).unwrap();
+            match session.add_nonces(&groups[proposer], nonces, factories) {
+                Ok(Some(r)) => request = Some(r),
+                Ok(None) => {},
+                Err(TransactionServiceError::InvalidStateError) => assert!(request.is_some()),
+                Err(e) => panic!("Unexpected error {}", e),
+            }
+            cosigners.push((i, cosigner));
+        }
+        let request = request.expect("Not enough cosigners approved");
+        let request = MultisigSigningRequest::try_from(request.to_proto(session.session_id)).unwrap();
+
+        for (i, cosigner) in &mut cosigners {
+            let index = *i as u32 + 1;
+            if !request.signers.contains(&index) {
+                continue;
+            }
+            let signatures = cosigner.sign(&groups[*i], request.clone()).unwrap();
+            assert_eq!(cosigner.state, MultisigSessionState::Signed);
+            assert!(cosigner.private_nonces.is_none());
+            let signatures = MultisigPartialSignatures::try_from(signatures.to_proto(session.session_id)).unwrap();
+            session.add_partial_signatures(&groups[proposer], signatures).unwrap();
+        }
+        (session, destination)
+    }
+
+    #[test]
+    fn a_threshold_of_participants_spend_the_group_output() {
+        let factories = CryptoFactories::default();
+        let groups = setup_group(2, 3, &factories);
+        assert_eq!(groups[2].participant_index(&groups[0].participants[2]), Some(3));
+        assert_eq!(groups[2].participant(1), Some(&groups[0].participants[0]));
+        assert_eq!(groups[2].participant(0), None);
+
+        // Participant 3 approves first, so participant 1 is not needed
+        let (session, destination) = negotiate(&groups, 1, &[2, 0], &factories);
+        assert_eq!(session.negotiation.signing_request.as_ref().unwrap().signers, vec![
+            2, 3
+        ]);
+        assert!(session.has_all_signatures(&groups[1]));
+
+        let (output, spending_key, sender_offset_private_key) =
+            one_sided_output(&destination, session.amount, &factories).unwrap();
+        let tx = session
+            .build_transaction(
+                &groups[1],
+                output,
+                &spending_key,
+                &sender_offset_private_key,
+                &factories,
+            )
+            .unwrap();
+        tx.validate_internal_consistency(false, &factories, None, None, u64::MAX)
+            .unwrap();
+        assert_eq!(tx.body.get_total_fee(), session.fee);
+    }
+
+    #[test]
+    fn a_single_signer_group_does_not_need_cosigners() {
+        let factories = CryptoFactories::default();
+        let groups = setup_group(1, 2, &factories);
+        let (session, destination) = negotiate(&groups, 0, &[], &factories);
+        assert!(session.has_all_signatures(&groups[0]));
+        let (output, spending_key, sender_offset_private_key) =
+            one_sided_output(&destination, session.amount, &factories).unwrap();
+        let tx = session
+            .build_transaction(
+                &groups[0],
+                output,
+                &spending_key,
+                &sender_offset_private_key,
+                &factories,
+            )
+            .unwrap();
+        tx.validate_internal_consistency(false, &factories, None, None, u64::MAX)
+            .unwrap();
+    }
+
+    #[test]
+    fn it_rejects_invalid_partial_signatures() {
+        let factories = CryptoFactories::default();
+        let groups = setup_group(2, 3, &factories);
+        let proposer_key = groups[0].participants[0].clone();
+        let destination = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        let mut session = MultisigSession::propose(
+            &groups[0],
+            proposer_key.clone(),
+            destination,
+            100 * uT,
+            0,
+            String::new(),
+        )
+        .unwrap();
+        let mut cosigner = MultisigSession::from_proposal(&groups[1], proposer_key, session.to_proposal()).unwrap();
+        let nonces = cosigner.approve(&groups[1]).unwrap();
+        let request = session.add_nonces(&groups[0], nonces, &factories).unwrap().unwrap();
+        let mut signatures = cosigner.sign(&groups[1], request.clone()).unwrap();
+        assert!(matches!(
+            cosigner.sign(&groups[1], request),
+            Err(TransactionServiceError::InvalidStateError)
+        ));
+
+        std::mem::swap(&mut signatures.kernel_signature, &mut signatures.script_signature);
+        assert!(session.add_partial_signatures(&groups[0], signatures.clone()).is_err());
+        std::mem::swap(&mut signatures.kernel_signature, &mut signatures.script_signature);
+        signatures.index = 3;
+        assert!(session.add_partial_signatures(&groups[0], signatures.clone()).is_err());
+        signatures.index = 2;
+        session.add_partial_signatures(&groups[0], signatures.clone()).unwrap();
+        assert!(session.add_partial_signatures(&groups[0], signatures).is_err());
+        assert!(session.has_all_signatures(&groups[0]));
+    }
+
+    #[test]
+    fn it_rejects_invalid_proposals_and_key_shares() {
+        let factories = CryptoFactories::default();
+        let mut groups = setup_group(2, 3, &factories);
+        let proposer_key = groups[0].participants[0].clone();
+        let destination = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        assert!(MultisigSession::propose(
+            &groups[0],
+            proposer_key.clone(),
+            destination.clone(),
+            VALUE,
+            0,
+            String::new()
+        )
+        .is_err());
+
+        let session = MultisigSession::propose(
+            &groups[0],
+            proposer_key.clone(),
+            destination,
+            100 * uT,
+            0,
+            String::new(),
+        )
+        .unwrap();
+        let mut proposal = session.to_proposal();
+        proposal.amount += 1;
+        assert!(MultisigSession::from_proposal(&groups[1], proposer_key.clone(), proposal).is_err());
+        let outsider = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        assert!(MultisigSession::from_proposal(&groups[1], outsider, session.to_proposal()).is_err());
+        groups[1].status = MultisigGroupStatus::Spent;
+        assert!(MultisigSession::from_proposal(&groups[1], proposer_key, session.to_proposal()).is_err());
+
+        groups[2].key_share.secret = PrivateKey::random(&mut OsRng);
+        assert!(groups[2].validate(&factories).is_err());
+        groups[1].value = VALUE + 1 * uT;
+        assert!(groups[1].validate(&factories).is_err());
+    }
+}
@@ -0,0 +1,367 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Serialization of transactions that are moved between an online wallet and an offline (air-gapped) wallet.
+//!
+//! The online wallet selects the inputs of a one-sided payment and exports it as an [UnsignedTransaction]. The offline
+//! wallet, which holds the spending keys, builds and signs exactly that transaction without broadcasting it, and
+//! exports it as a [SignedTransaction]. The online wallet checks that the signed transaction is the payment it
+//! exported before broadcasting it.
+//!
+//! The unsigned transaction contains the sender offset private key of the payment output, so both files must be kept
+//! private.
+//!
+//! Both are encoded as one or more text chunks of the form `TARITX1:<checksum>:<index>/<total>:<data>`. Chunks only
+//! contain upper case hex digits and separators, so each chunk can be written to a file as a line or displayed as a
+//! QR code in alphanumeric mode. Chunks may be decoded in any order.
+
+use std::collections::BTreeMap;
+
+use blake2::Digest;
+use serde::{Deserialize, Serialize};
+use tari_common_types::{
+    transaction::TxId,
+    types::{Commitment, CommitmentFactory, PrivateKey, PublicKey},
+};
+use tari_core::transactions::{tari_amount::MicroTari, transaction_components::Transaction};
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    common::Blake256,
+    keys::{DiffieHellmanSharedSecret, SecretKey},
+};
+use tari_utilities::{
+    hex::{from_hex, to_hex},
+    ByteArray,
+};
+use thiserror::Error;
+
+const CHUNK_PREFIX: &str = "TARITX1";
+const CHECKSUM_LEN: usize = 4;
+
+/// The default maximum length of a chunk, which fits in a QR code
+pub const DEFAULT_MAX_CHUNK_LEN: usize = 1024;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum OfflineTransactionError {
+    #[error("Could not serialize the transaction: {0}")]
+    Serialization(String),
+    #[error("Could not deserialize the transaction: {0}")]
+    Deserialization(String),
+    #[error("Invalid chunk: {0}")]
+    InvalidChunk(String),
+    #[error("The chunks belong to different transactions")]
+    MixedChunks,
+    #[error("Missing {missing} of {total} chunks")]
+    MissingChunks { missing: usize, total: usize },
+    #[error("No chunks were provided")]
+    NoChunks,
+    #[error("The checksum of the transaction does not match")]
+    ChecksumMismatch,
+    #[error("The maximum chunk length is too small")]
+    ChunkLengthTooSmall,
+    #[error("The signed transaction does not match the unsigned transaction: {0}")]
+    TransactionMismatch(String),
+}
+
+/// A one-sided payment prepared by an online wallet that has to be signed by an offline wallet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    pub tx_id: TxId,
+    pub destination: PublicKey,
+    pub amount: MicroTari,
+    pub fee_per_gram: MicroTari,
+    pub lock_height: u64,
+    /// Commitments of the outputs selected by the online wallet. The signed transaction spends exactly these.
+    pub inputs: Vec<Commitment>,
+    /// Sender offset of the payment output, from which the spending key of the one-sided output is derived
+    pub sender_offset_private_key: PrivateKey,
+    pub message: String,
+}
+
+impl UnsignedTransaction {
+    /// The commitment of the output paying `amount` to the destination
+    pub fn payment_commitment(&self, factory: &CommitmentFactory) -> Result<Commitment, OfflineTransactionError> {
+        let spend_key = PrivateKey::from_bytes(
+            PublicKey::shared_secret(&self.sender_offset_private_key, &self.destination).as_bytes(),
+        )
+        .map_err(|e| OfflineTransactionError::Deserialization(e.to_string()))?;
+        Ok(factory.commit_value(&spend_key, self.amount.as_u64()))
+    }
+}
+
+/// A transaction signed by an offline wallet, ready to be broadcast by an online wallet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedTransaction {
+    pub tx_id: TxId,
+    pub transaction: Transaction,
+    pub amount: MicroTari,
+    pub message: String,
+}
+
+impl SignedTransaction {
+    /// Checks that this transaction is the payment described by `unsigned`: it spends exactly its inputs, has its lock
+    /// height and pays its amount to its destination.
+    pub fn verify_matches(
+        &self,
+        unsigned: &UnsignedTransaction,
+        factory: &CommitmentFactory,
+    ) -> Result<(), OfflineTransactionError> {
+        let mismatch = |reason: &str| Err(OfflineTransactionError::TransactionMismatch(reason.to_string()));
+        if self.tx_id != unsigned.tx_id {
+            return mismatch("the transaction ids differ");
+        }
+        if self.amount != unsigned.amount {
+            return mismatch("the amounts differ");
+        }
+        let body = &self.transaction.body;
+        if body.inputs().len() != unsigned.inputs.len() ||
+            body.inputs()
+                .iter()
+                .any(|input| !matches!(input.commitment(), Ok(c) if unsigned.inputs.contains(c)))
+        {
+            return mismatch("the inputs differ");
+        }
+        if body
+            .kernels()
+            .iter()
+            .any(|kernel| kernel.lock_height != unsigned.lock_height)
+        {
+            return mismatch("the lock heights differ");
+        }
+        let payment = unsigned.payment_commitment(factory)?;
+        if !body.outputs().iter().any(|output| output.commitment == payment) {
+            return mismatch("the payment output is missing");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OfflineTransaction {
+    Unsigned(UnsignedTransaction),
+    Signed(SignedTransaction),
+}
+
+impl OfflineTransaction {
+    /// Encodes the transaction as chunks that are at most `max_chunk_len` characters long
+    pub fn to_chunks(&self, max_chunk_len: usize) -> Result<Vec<String>, OfflineTransactionError> {
+        let payload = bincode::serialize(self).map_err(|e| OfflineTransactionError::Serialization(e.to_string()))?;
+        let checksum = to_hex(&checksum(&payload)).to_uppercase();
+        let data = to_hex(&payload).to_uppercase();
+
+        // The header is at most `TARITX1:<checksum>:<index>/<total>:`, assume the worst case for index and total
+        let header_len = CHUNK_PREFIX.len() + checksum.len() + 2 * 10 + 3;
+        // Each chunk must carry whole bytes
+        let data_per_chunk = max_chunk_len.saturating_sub(header_len) & !1;
+        if data_per_chunk == 0 {
+            return Err(OfflineTransactionError::ChunkLengthTooSmall);
+        }
+        let parts = data.as_bytes().chunks(data_per_chunk).collect::<Vec<_>>();
+        let total = parts.len();
+        Ok(parts
+            .into_iter()
+            .enumerate()
+            .map(|(i, part)| {
+                format!(
+                    "{}:{}:{}/{}:{}",
+                    CHUNK_PREFIX,
+                    checksum,
+                    i + 1,
+                    total,
+                    String::from_utf8_lossy(part)
+                )
+            })
+            .collect())
+    }
+
+    /// Decodes a transaction from its chunks, which may be given in any order. Duplicate chunks are ignored.
+    pub fn from_chunks<I, S>(chunks: I) -> Result<Self, OfflineTransactionError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut checksum = None;
+        let mut total = None;
+        let mut parts = BTreeMap::new();
+        for chunk in chunks {
+            let chunk = chunk.as_ref().trim();
+            if chunk.is_empty() {
+                continue;
+            }
+            let (chunk_checksum, index, chunk_total, data) = parse_chunk(chunk)?;
+            if *checksum.get_or_insert(chunk_checksum) != chunk_checksum ||
+                *total.get_or_insert(chunk_total) != chunk_total
+            {
+                return Err(OfflineTransactionError::MixedChunks);
+            }
+            parts.insert(index, data.to_string());
+        }
+
+        let (checksum, total) = checksum.zip(total).ok_or(OfflineTransactionError::NoChunks)?;
+        if parts.len() != total {
+            return Err(OfflineTransactionError::MissingChunks {
+                missing: total - parts.len(),
+                total,
+            });
+        }
+        let data = parts.into_values().collect::<String>();
+        let payload = from_hex(&data).map_err(|e| OfflineTransactionError::InvalidChunk(e.to_string()))?;
+        if checksum != self::checksum(&payload) {
+            return Err(OfflineTransactionError::ChecksumMismatch);
+        }
+        bincode::deserialize(&payload).map_err(|e| OfflineTransactionError::Deserialization(e.to_string()))
+    }
+}
+
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let hash = Blake256::digest(payload);
+    let mut checksum = [0u8; CHECKSUM_LEN];
+    checksum.copy_from_slice(&hash[..CHECKSUM_LEN]);
+    checksum
+}
+
+fn parse_chunk(chunk: &str) -> Result<([u8; CHECKSUM_LEN], usize, usize, &str), OfflineTransactionError> {
+    let invalid = || OfflineTransactionError::InvalidChunk(chunk.chars().take(32).collect());
+    let mut fields = chunk.splitn(4, ':');
+    if fields.next() != Some(CHUNK_PREFIX) {
+        return Err(invalid());
+    }
+    let checksum = fields
+        .next()
+        .and_then(|c| from_hex(c).ok())
+        .filter(|c| c.len() == CHECKSUM_LEN)
+        .ok_or_else(invalid)?;
+    let (index, total) = fields
+        .next()
+        .and_then(|position| position.split_once('/'))
+        .and_then(|(index, total)| Some((index.parse::<usize>().ok()?, total.parse::<usize>().ok()?)))
+        .filter(|(index, total)| *index >= 1 && index <= total)
+        .ok_or_else(invalid)?;
+    let data = fields.next().filter(|data| data.len() % 2 == 0).ok_or_else(invalid)?;
+
+    let mut chunk_checksum = [0u8; CHECKSUM_LEN];
+    chunk_checksum.copy_from_slice(&checksum);
+    Ok((chunk_checksum, index, total, data))
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::OsRng;
+    use tari_crypto::keys::PublicKey as PublicKeyTrait;
+
+    use super::*;
+
+    fn unsigned_transaction() -> OfflineTransaction {
+        let (_, destination) = PublicKey::random_keypair(&mut OsRng);
+        let factory = CommitmentFactory::default();
+        OfflineTransaction::Unsigned(UnsignedTransaction {
+            tx_id: TxId::new_random(),
+            destination,
+            amount: MicroTari(1_000_000),
+            fee_per_gram: MicroTari(5),
+            lock_height: 0,
+            inputs: vec![factory.commit_value(&PrivateKey::random(&mut OsRng), 2_000_000)],
+            sender_offset_private_key: PrivateKey::random(&mut OsRng),
+            message: "cold storage payment".to_string(),
+        })
+    }
+
+    #[test]
+    fn it_round_trips_through_chunks_in_any_order() {
+        let tx = unsigned_transaction();
+        let mut chunks = tx.to_chunks(64).unwrap();
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.len() <= 64));
+        assert!(chunks.iter().all(|c| c
+            .chars()
+            .all(|ch| ch.is_ascii_uppercase() || ch.is_ascii_digit() || ":/".contains(ch))));
+
+        chunks.reverse();
+        chunks.push(chunks[0].clone());
+        assert_eq!(OfflineTransaction::from_chunks(&chunks).unwrap(), tx);
+    }
+
+    #[test]
+    fn it_rejects_incomplete_or_mixed_chunks() {
+        let chunks = unsigned_transaction().to_chunks(64).unwrap();
+        let total = chunks.len();
+        assert_eq!(
+            OfflineTransaction::from_chunks(&chunks[1..]).unwrap_err(),
+            OfflineTransactionError::MissingChunks { missing: 1, total }
+        );
+
+        let other = unsigned_transaction().to_chunks(64).unwrap();
+        let mixed = chunks.iter().take(1).chain(other.iter().skip(1));
+        assert_eq!(
+            OfflineTransaction::from_chunks(mixed).unwrap_err(),
+            OfflineTransactionError::MixedChunks
+        );
+
+        assert_eq!(
+            OfflineTransaction::from_chunks(Vec::<String>::new()).unwrap_err(),
+            OfflineTransactionError::NoChunks
+        );
+    }
+
+    #[test]
+    fn it_detects_corrupted_data() {
+        let mut chunks = unsigned_transaction().to_chunks(DEFAULT_MAX_CHUNK_LEN).unwrap();
+        assert_eq!(chunks.len(), 1);
+        // Flip the first hex digit of the data
+        let data_start = chunks[0].rfind(':').unwrap() + 1;
+        let flipped = if chunks[0][data_start..].starts_with('0') {
+            "1"
+        } else {
+            "0"
+        };
+        chunks[0].replace_range(data_start..=data_start, flipped);
+        assert_eq!(
+            OfflineTransaction::from_chunks(&chunks).unwrap_err(),
+            OfflineTransactionError::ChecksumMismatch
+        );
+    }
+
+    #[test]
+    fn it_rejects_signed_transactions_that_do_not_match() {
+        let unsigned = match unsigned_transaction() {
+            OfflineTransaction::Unsigned(tx) => tx,
+            OfflineTransaction::Signed(_) => unreachable!(),
+        };
+        let factory = CommitmentFactory::default();
+        let mut signed = SignedTransaction {
+            tx_id: unsigned.tx_id,
+            transaction: Transaction::new(vec![], vec![], vec![], Default::default(), Default::default()),
+            amount: unsigned.amount,
+            message: unsigned.message.clone(),
+        };
+        assert_eq!(
+            signed.verify_matches(&unsigned, &factory).unwrap_err(),
+            OfflineTransactionError::TransactionMismatch("the inputs differ".to_string())
+        );
+
+        signed.tx_id = TxId::new_random();
+        assert_eq!(
+            signed.verify_matches(&unsigned, &factory).unwrap_err(),
+            OfflineTransactionError::TransactionMismatch("the transaction ids differ".to_string())
+        );
+    }
+}
@@ -214,6 +214,16 @@ where
             return Ok(false);
         }
 
+        if !response.accepted && response.rejection_reason == TxSubmissionRejectionReason::FeeTooLow {
+            // The mempool is full or a conflicting transaction pays more, which may change as blocks are mined
+            warn!(
+                target: LOG_TARGET,
+                "Transaction (TxId: {}) fee is too low for the Base Node mempool, submission will be retried.",
+                self.tx_id
+            );
+            return Ok(false);
+        }
+
         if !response.accepted && response.rejection_reason != TxSubmissionRejectionReason::AlreadyMined {
             error!(
                 target: LOG_TARGET,
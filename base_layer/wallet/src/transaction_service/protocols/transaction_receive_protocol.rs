@@ -32,8 +32,9 @@ use tari_common_types::{
 use tari_comms::types::CommsPublicKey;
 use tari_core::transactions::{
     transaction_components::Transaction,
-    transaction_protocol::{recipient::RecipientState, sender::TransactionSenderMessage},
+    transaction_protocol::{proto::protocol as proto, recipient::RecipientState, sender::TransactionSenderMessage},
 };
+use tari_p2p::tari_message::TariMessageType;
 use tari_utilities::Hashable;
 use tokio::{
     sync::{mpsc, oneshot},
@@ -50,7 +51,10 @@ use crate::{
             database::TransactionBackend,
             models::{CompletedTransaction, InboundTransaction, TxCancellationReason},
         },
-        tasks::send_transaction_reply::send_transaction_reply,
+        tasks::{
+            send_queued_transaction_message::{queue_transaction_message, remove_queued_transaction_messages},
+            send_transaction_reply::send_transaction_reply,
+        },
         utc::utc_duration_since,
     },
 };
@@ -171,6 +175,12 @@ where
                 .await
                 .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
 
+            let proto_message: proto::RecipientSignedMessage = inbound_transaction
+                .receiver_protocol
+                .get_signed_data()
+                .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?
+                .clone()
+                .into();
             let send_result = send_transaction_reply(
                 inbound_transaction,
                 self.resources.outbound_message_service.clone(),
@@ -186,7 +196,19 @@ where
                 .await
                 .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
 
-            if send_result {
+            if !send_result.direct_send_result {
+                queue_transaction_message(
+                    &self.resources.db,
+                    &self.resources.config,
+                    data.tx_id,
+                    TariMessageType::ReceiverPartialTransactionReply,
+                    self.source_pubkey.clone(),
+                    &proto_message,
+                )
+                .await;
+            }
+
+            if send_result.is_sent() {
                 info!(
                     target: LOG_TARGET,
                     "Transaction with TX_ID = {} received from {}. Reply Sent", data.tx_id, self.source_pubkey,
@@ -357,6 +379,14 @@ where
                 }
             }
 
+            // The sender has finalized the transaction, so the reply no longer needs to be redelivered
+            remove_queued_transaction_messages(
+                &self.resources.db,
+                self.id,
+                Some(TariMessageType::ReceiverPartialTransactionReply),
+            )
+            .await;
+
             let finalized_transaction: Transaction = incoming_finalized_transaction.ok_or_else(|| {
                 TransactionServiceProtocolError::new(self.id, TransactionServiceError::TransactionCancelled)
             })?;
@@ -485,6 +515,8 @@ where
             "Cancelling Transaction Receive Protocol (TxId: {}) due to timeout after no counterparty response", self.id
         );
 
+        remove_queued_transaction_messages(&self.resources.db, self.id, None).await;
+
         self.resources
             .db
             .cancel_pending_transaction(self.id)
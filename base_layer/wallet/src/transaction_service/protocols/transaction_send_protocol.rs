@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::sync::Arc;
+use std::{convert::TryInto, sync::Arc};
 
 use chrono::Utc;
 use futures::FutureExt;
@@ -67,6 +67,7 @@ use crate::{
         },
         tasks::{
             send_finalized_transaction::send_finalized_transaction_message,
+            send_queued_transaction_message::{queue_transaction_message, remove_queued_transaction_messages},
             send_transaction_cancelled::send_transaction_cancelled_message,
             wait_on_dial::wait_on_dial,
         },
@@ -155,7 +156,14 @@ where
 
         let transaction_status = match self.stage {
             TransactionSendProtocolStage::Initial => {
-                let sender_protocol = self.prepare_transaction().await?;
+                let sender_protocol = match self.prepare_transaction().await {
+                    Ok(sp) => sp,
+                    Err(e) => {
+                        // Nothing was encumbered so the send intent can be discarded
+                        self.resolve_send_intent().await;
+                        return Err(e);
+                    },
+                };
                 let status = self.initial_send_transaction(sender_protocol).await?;
                 if status == TransactionStatus::Pending {
                     self.wait_for_reply().await?;
@@ -282,6 +290,8 @@ where
             ));
         }
 
+        let proto_message = proto::TransactionSenderMessage::single(msg.clone().into());
+
         // Attempt to send the initial transaction
         let SendResult {
             direct_send_result,
@@ -339,6 +349,19 @@ where
                 .await
                 .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
         }
+        // The pending transaction is now persisted, so the send can be resumed without the intent
+        self.resolve_send_intent().await;
+        if !direct_send_result {
+            queue_transaction_message(
+                &self.resources.db,
+                &self.resources.config,
+                tx_id,
+                TariMessageType::SenderPartialTransaction,
+                self.dest_pubkey.clone(),
+                &proto_message,
+            )
+            .await;
+        }
         if transaction_status == TransactionStatus::Pending {
             self.resources
                 .db
@@ -374,6 +397,15 @@ where
         Ok(transaction_status)
     }
 
+    async fn resolve_send_intent(&self) {
+        if let Err(e) = self.resources.db.resolve_send_intent(self.id).await {
+            warn!(
+                target: LOG_TARGET,
+                "Failed to resolve send intent for TxId: {}: {}", self.id, e
+            );
+        }
+    }
+
     async fn wait_for_reply(&mut self) -> Result<(), TransactionServiceProtocolError<TxId>> {
         // Waiting  for Transaction Reply
         let tx_id = self.id;
@@ -599,7 +631,15 @@ where
             "Transaction Recipient Reply for TX_ID = {} received", tx_id,
         );
 
-        send_finalized_transaction_message(
+        // The recipient has replied, so the initial message no longer needs to be redelivered
+        remove_queued_transaction_messages(
+            &self.resources.db,
+            tx_id,
+            Some(TariMessageType::SenderPartialTransaction),
+        )
+        .await;
+
+        let direct_send_result = match send_finalized_transaction_message(
             tx_id,
             tx.clone(),
             self.dest_pubkey.clone(),
@@ -608,7 +648,39 @@ where
             self.resources.config.transaction_routing_mechanism,
         )
         .await
-        .map_err(|e| TransactionServiceProtocolError::new(self.id, e))?;
+        {
+            Ok(result) => result,
+            Err(e)
+                if self.resources.config.transaction_routing_mechanism !=
+                    TransactionRoutingMechanism::StoreAndForwardOnly =>
+            {
+                warn!(
+                    target: LOG_TARGET,
+                    "Finalized Transaction (TxId: {}) could not be sent, it will be queued for redelivery: {:?}",
+                    tx_id,
+                    e
+                );
+                false
+            },
+            Err(e) => return Err(TransactionServiceProtocolError::new(self.id, e)),
+        };
+        if !direct_send_result {
+            let finalized_transaction_message = proto::TransactionFinalizedMessage {
+                tx_id: tx_id.into(),
+                transaction: Some(tx.clone().try_into().map_err(|e| {
+                    TransactionServiceProtocolError::new(self.id, TransactionServiceError::InvalidMessageError(e))
+                })?),
+            };
+            queue_transaction_message(
+                &self.resources.db,
+                &self.resources.config,
+                tx_id,
+                TariMessageType::TransactionFinalized,
+                self.dest_pubkey.clone(),
+                &finalized_transaction_message,
+            )
+            .await;
+        }
 
         self.resources
             .db
@@ -905,6 +977,8 @@ where
             .await
             .map_err(|e| TransactionServiceProtocolError::new(self.id, TransactionServiceError::from(e)))?;
 
+        remove_queued_transaction_messages(&self.resources.db, self.id, None).await;
+
         self.resources
             .db
             .cancel_pending_transaction(self.id)
@@ -22,7 +22,7 @@
 
 use std::{
     collections::{HashMap, HashSet},
-    convert::TryInto,
+    convert::{TryFrom, TryInto},
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -32,7 +32,6 @@ use digest::Digest;
 use futures::{pin_mut, stream::FuturesUnordered, Stream, StreamExt};
 use log::*;
 use rand::rngs::OsRng;
-use sha2::Sha256;
 use tari_common_types::{
     transaction::{ImportStatus, TransactionDirection, TransactionStatus, TxId},
     types::{PrivateKey, PublicKey},
@@ -43,12 +42,14 @@ use tari_core::{
     covenants::Covenant,
     proto::base_node as base_node_proto,
     transactions::{
+        htlc::{sha256_hash_lock, ShaHtlcScript},
         tari_amount::MicroTari,
         transaction_components::{KernelFeatures, Transaction, TransactionOutput, UnblindedOutput},
         transaction_protocol::{
-            proto::protocol as proto,
+            multisig::split_secret,
+            proto::{protocol as proto, protocol::multisig_message::Message as MultisigMessageType},
             recipient::RecipientSignedMessage,
-            sender::TransactionSenderMessage,
+            sender::{SenderTransactionProtocol, TransactionSenderMessage},
             RewindData,
         },
         CryptoFactories,
@@ -57,29 +58,50 @@ use tari_core::{
 };
 use tari_crypto::{
     keys::{DiffieHellmanSharedSecret, PublicKey as PKtrait, SecretKey},
+    range_proof::REWIND_USER_MESSAGE_LENGTH,
     tari_utilities::ByteArray,
 };
 use tari_p2p::domain_message::DomainMessage;
-use tari_script::{inputs, script};
+use tari_script::{script, HashValue};
 use tari_service_framework::{reply_channel, reply_channel::Receiver};
 use tari_shutdown::ShutdownSignal;
 use tokio::{
     sync::{mpsc, mpsc::Sender, oneshot},
     task::JoinHandle,
+    time,
+    time::MissedTickBehavior,
 };
 
 use crate::{
     base_node_service::handle::{BaseNodeEvent, BaseNodeServiceHandle},
-    connectivity_service::WalletConnectivityInterface,
+    connectivity_service::{OnlineStatus, WalletConnectivityInterface},
+    contacts_service::{
+        error::{ContactsServiceError, ContactsServiceStorageError},
+        handle::ContactsServiceHandle,
+        service::ContactOnlineStatus,
+    },
     output_manager_service::{
-        handle::{OutputManagerEvent, OutputManagerHandle},
+        handle::{OutputManagerEvent, OutputManagerHandle, PaymentRecipient},
         storage::models::SpendingPriority,
     },
     storage::database::{WalletBackend, WalletDatabase},
     transaction_service::{
-        config::TransactionServiceConfig,
+        config::{OfflineRecipientPolicy, TransactionRoutingMechanism, TransactionServiceConfig},
         error::{TransactionServiceError, TransactionServiceProtocolError},
         handle::{TransactionEvent, TransactionEventSender, TransactionServiceRequest, TransactionServiceResponse},
+        multisig::{
+            one_sided_output,
+            spend_fee,
+            MultisigGroup,
+            MultisigGroupStatus,
+            MultisigPartialSignatures,
+            MultisigPublicNonces,
+            MultisigRole,
+            MultisigSession,
+            MultisigSessionState,
+            MultisigSigningRequest,
+        },
+        offline_signing::{SignedTransaction, UnsignedTransaction},
         protocols::{
             transaction_broadcast_protocol::TransactionBroadcastProtocol,
             transaction_receive_protocol::{TransactionReceiveProtocol, TransactionReceiveProtocolStage},
@@ -88,11 +110,20 @@ use crate::{
         },
         storage::{
             database::{TransactionBackend, TransactionDatabase},
-            models::{CompletedTransaction, TxCancellationReason},
+            models::{
+                CompletedTransaction,
+                ScheduledPayment,
+                ScheduledPaymentStatus,
+                ScheduledPaymentType,
+                SendIntent,
+                TxCancellationReason,
+            },
         },
         tasks::{
             check_faux_transaction_status::check_faux_transactions,
             send_finalized_transaction::send_finalized_transaction_message,
+            send_multisig_message::send_multisig_message,
+            send_queued_transaction_message::{remove_queued_transaction_messages, send_queued_transaction_message},
             send_transaction_cancelled::send_transaction_cancelled_message,
             send_transaction_reply::send_transaction_reply,
         },
@@ -126,6 +157,7 @@ pub struct TransactionService<
     BNResponseStream,
     TBackend,
     TTxCancelledStream,
+    TMultisigStream,
     TWalletBackend,
     TWalletConnectivity,
 > {
@@ -137,6 +169,7 @@ pub struct TransactionService<
     transaction_finalized_stream: Option<TTxFinalizedStream>,
     base_node_response_stream: Option<BNResponseStream>,
     transaction_cancelled_stream: Option<TTxCancelledStream>,
+    multisig_stream: Option<TMultisigStream>,
     request_stream: Option<
         reply_channel::Receiver<TransactionServiceRequest, Result<TransactionServiceResponse, TransactionServiceError>>,
     >,
@@ -152,6 +185,7 @@ pub struct TransactionService<
     timeout_update_watch: Watch<Duration>,
     wallet_db: WalletDatabase<TWalletBackend>,
     base_node_service: BaseNodeServiceHandle,
+    contacts_service: Option<ContactsServiceHandle>,
     last_seen_tip_height: Option<u64>,
 }
 
@@ -162,6 +196,7 @@ impl<
         BNResponseStream,
         TBackend,
         TTxCancelledStream,
+        TMultisigStream,
         TWalletBackend,
         TWalletConnectivity,
     >
@@ -172,6 +207,7 @@ impl<
         BNResponseStream,
         TBackend,
         TTxCancelledStream,
+        TMultisigStream,
         TWalletBackend,
         TWalletConnectivity,
     >
@@ -181,6 +217,7 @@ where
     TTxFinalizedStream: Stream<Item = DomainMessage<proto::TransactionFinalizedMessage>>,
     BNResponseStream: Stream<Item = DomainMessage<base_node_proto::BaseNodeServiceResponse>>,
     TTxCancelledStream: Stream<Item = DomainMessage<proto::TransactionCancelledMessage>>,
+    TMultisigStream: Stream<Item = DomainMessage<proto::MultisigMessage>>,
     TBackend: TransactionBackend + 'static,
     TWalletBackend: WalletBackend + 'static,
     TWalletConnectivity: WalletConnectivityInterface,
@@ -198,6 +235,7 @@ where
         transaction_finalized_stream: TTxFinalizedStream,
         base_node_response_stream: BNResponseStream,
         transaction_cancelled_stream: TTxCancelledStream,
+        multisig_stream: TMultisigStream,
         output_manager_service: OutputManagerHandle,
         outbound_message_service: OutboundMessageRequester,
         connectivity: TWalletConnectivity,
@@ -206,6 +244,7 @@ where
         factories: CryptoFactories,
         shutdown_signal: ShutdownSignal,
         base_node_service: BaseNodeServiceHandle,
+        contacts_service: Option<ContactsServiceHandle>,
     ) -> Self {
         // Collect the resources that all protocols will need so that they can be neatly cloned as the protocols are
         // spawned.
@@ -237,6 +276,7 @@ where
             transaction_finalized_stream: Some(transaction_finalized_stream),
             base_node_response_stream: Some(base_node_response_stream),
             transaction_cancelled_stream: Some(transaction_cancelled_stream),
+            multisig_stream: Some(multisig_stream),
             request_stream: Some(request_stream),
             event_publisher,
             node_identity,
@@ -249,6 +289,7 @@ where
             active_transaction_broadcast_protocols: HashSet::new(),
             timeout_update_watch,
             base_node_service,
+            contacts_service,
             wallet_db,
             last_seen_tip_height: None,
         }
@@ -292,6 +333,12 @@ where
             .expect("Transaction Service initialized without transaction_cancelled_stream")
             .fuse();
         pin_mut!(transaction_cancelled_stream);
+        let multisig_stream = self
+            .multisig_stream
+            .take()
+            .expect("Transaction Service initialized without multisig_stream")
+            .fuse();
+        pin_mut!(multisig_stream);
 
         let mut shutdown = self.resources.shutdown_signal.clone();
 
@@ -314,6 +361,18 @@ where
         let mut base_node_service_event_stream = self.base_node_service.get_event_stream();
         let mut output_manager_event_stream = self.output_manager_service.get_event_stream();
 
+        if let Err(e) = self.reconcile_send_intents().await {
+            error!(
+                target: LOG_TARGET,
+                "Failed to reconcile outstanding send intents: {}", e
+            );
+        }
+
+        let mut message_queue_interval = time::interval(self.resources.config.message_queue_check_interval);
+        message_queue_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut scheduled_payment_interval = time::interval(self.resources.config.scheduled_payment_check_interval);
+        scheduled_payment_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
         debug!(target: LOG_TARGET, "Transaction Service started");
         loop {
             tokio::select! {
@@ -482,6 +541,26 @@ where
                         start.elapsed().as_millis(),
                     );
                 }
+                // Incoming multisig negotiation messages from the Comms layer
+                Some(msg) = multisig_stream.next() => {
+                    let (origin_public_key, inner_msg) = msg.clone().into_origin_and_inner();
+                    trace!(target: LOG_TARGET, "Handling Multisig message, Trace: {}", msg.dht_header.message_tag);
+                    match self.handle_multisig_message(
+                        origin_public_key,
+                        inner_msg,
+                        &mut transaction_broadcast_protocol_handles,
+                    ).await {
+                        Err(TransactionServiceError::RepeatedMessageError) => {
+                            trace!(target: LOG_TARGET, "A repeated Multisig message was received, Trace: {}",
+                            msg.dht_header.message_tag);
+                        },
+                        Err(e) => {
+                            warn!(target: LOG_TARGET, "Error handling Multisig message: {}, Trace: {}", e,
+                            msg.dht_header.message_tag);
+                        },
+                        Ok(_) => (),
+                    }
+                }
                 Some(join_result) = send_transaction_protocol_handles.next() => {
                     trace!(target: LOG_TARGET, "Send Protocol for Transaction has ended with result {:?}", join_result);
                     match join_result {
@@ -518,6 +597,19 @@ where
                         ).await,
                         Err(e) => error!(target: LOG_TARGET, "Error resolving Transaction Validation protocol: {:?}", e),
                     };
+                }
+                _ = message_queue_interval.tick() => {
+                    if let Err(e) = self.process_transaction_message_queue().await {
+                        warn!(target: LOG_TARGET, "Error processing the transaction message queue: {}", e);
+                    }
+                }
+                _ = scheduled_payment_interval.tick() => {
+                    if let Err(e) = self.process_scheduled_payments(
+                        &mut send_transaction_protocol_handles,
+                        &mut transaction_broadcast_protocol_handles,
+                    ).await {
+                        warn!(target: LOG_TARGET, "Error processing scheduled payments: {}", e);
+                    }
                 }
                  _ = shutdown.wait() => {
                     info!(target: LOG_TARGET, "Transaction service shutting down because it received the shutdown signal");
@@ -593,6 +685,19 @@ where
                 )
                 .await
                 .map(TransactionServiceResponse::TransactionSent),
+            TransactionServiceRequest::SendOneSidedToManyTransaction {
+                recipients,
+                fee_per_gram,
+                message,
+            } => self
+                .send_one_sided_to_many_transaction(
+                    recipients,
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
             TransactionServiceRequest::SendShaAtomicSwapTransaction(dest_pubkey, amount, fee_per_gram, message) => {
                 Ok(TransactionServiceResponse::ShaAtomicSwapTransactionSent(
                     self.send_sha_atomic_swap_transaction(
@@ -605,10 +710,33 @@ where
                     .await?,
                 ))
             },
+            TransactionServiceRequest::SendHtlcTransaction {
+                dest_pubkey,
+                amount,
+                hash,
+                lock_height,
+                fee_per_gram,
+                message,
+            } => self
+                .send_htlc_transaction(
+                    dest_pubkey,
+                    amount,
+                    hash,
+                    lock_height,
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(|sent| TransactionServiceResponse::HtlcTransactionSent(Box::new(sent))),
             TransactionServiceRequest::CancelTransaction(tx_id) => self
                 .cancel_pending_transaction(tx_id)
                 .await
                 .map(|_| TransactionServiceResponse::TransactionCancelled),
+            TransactionServiceRequest::BumpTransactionFee { tx_id, fee_per_gram } => self
+                .bump_transaction_fee(tx_id, fee_per_gram, send_transaction_join_handles)
+                .await
+                .map(TransactionServiceResponse::TransactionSent),
             TransactionServiceRequest::GetPendingInboundTransactions => {
                 Ok(TransactionServiceResponse::PendingInboundTransactions(
                     self.db.get_pending_inbound_transactions().await?,
@@ -646,6 +774,68 @@ where
             TransactionServiceRequest::GetAnyTransaction(tx_id) => Ok(TransactionServiceResponse::AnyTransaction(
                 Box::new(self.db.get_any_transaction(tx_id).await?),
             )),
+            TransactionServiceRequest::GetQueuedTransactionMessages(tx_id) => {
+                Ok(TransactionServiceResponse::QueuedTransactionMessages(
+                    self.db.get_queued_transaction_messages(Some(tx_id)).await?,
+                ))
+            },
+            TransactionServiceRequest::SchedulePayment(payment) => self
+                .schedule_payment(*payment)
+                .await
+                .map(TransactionServiceResponse::PaymentScheduled),
+            TransactionServiceRequest::GetScheduledPayments => Ok(TransactionServiceResponse::ScheduledPayments(
+                self.db.get_scheduled_payments().await?,
+            )),
+            TransactionServiceRequest::CancelScheduledPayment(payment_id) => self
+                .cancel_scheduled_payment(payment_id)
+                .await
+                .map(|_| TransactionServiceResponse::ScheduledPaymentCancelled),
+            TransactionServiceRequest::CreateMultisigGroup {
+                threshold,
+                cosigners,
+                amount,
+                fee_per_gram,
+                message,
+            } => self
+                .create_multisig_group(
+                    threshold,
+                    cosigners,
+                    amount,
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(|(group_id, tx_id)| TransactionServiceResponse::MultisigGroupCreated { group_id, tx_id }),
+            TransactionServiceRequest::GetMultisigGroups => Ok(TransactionServiceResponse::MultisigGroups(
+                self.db.get_multisig_groups().await?,
+            )),
+            TransactionServiceRequest::ProposeMultisigSpend {
+                group_id,
+                destination,
+                fee_per_gram,
+                message,
+            } => self
+                .propose_multisig_spend(
+                    group_id,
+                    destination,
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::MultisigSpendProposed),
+            TransactionServiceRequest::ApproveMultisigSpend(session_id) => self
+                .approve_multisig_spend(session_id)
+                .await
+                .map(|_| TransactionServiceResponse::MultisigSpendApproved),
+            TransactionServiceRequest::CancelMultisigSession(session_id) => self
+                .cancel_multisig_session(session_id)
+                .await
+                .map(|_| TransactionServiceResponse::MultisigSessionCancelled),
+            TransactionServiceRequest::GetMultisigSessions => Ok(TransactionServiceResponse::MultisigSessions(
+                self.db.get_multisig_sessions().await?,
+            )),
             TransactionServiceRequest::ImportUtxoWithStatus {
                 amount,
                 source_public_key,
@@ -670,6 +860,23 @@ where
                 .submit_transaction_to_self(transaction_broadcast_join_handles, tx_id, tx, fee, amount, message)
                 .await
                 .map(|_| TransactionServiceResponse::TransactionSubmitted),
+            TransactionServiceRequest::PrepareOfflineTransaction {
+                dest_pubkey,
+                amount,
+                fee_per_gram,
+                message,
+            } => self
+                .prepare_offline_transaction(dest_pubkey, amount, fee_per_gram, message)
+                .await
+                .map(|unsigned| TransactionServiceResponse::OfflineTransactionPrepared(Box::new(unsigned))),
+            TransactionServiceRequest::SignOfflineTransaction(unsigned) => self
+                .sign_offline_transaction(*unsigned)
+                .await
+                .map(|signed| TransactionServiceResponse::OfflineTransactionSigned(Box::new(signed))),
+            TransactionServiceRequest::SubmitOfflineTransaction { unsigned, signed } => self
+                .submit_offline_transaction(transaction_broadcast_join_handles, *unsigned, *signed)
+                .await
+                .map(|_| TransactionServiceResponse::TransactionSubmitted),
             TransactionServiceRequest::GenerateCoinbaseTransaction(reward, fees, block_height) => self
                 .generate_coinbase_transaction(reward, fees, block_height)
                 .await
@@ -805,7 +1012,35 @@ where
         >,
         reply_channel: oneshot::Sender<Result<TransactionServiceResponse, TransactionServiceError>>,
     ) -> Result<(), TransactionServiceError> {
+        let send_policy = if self.node_identity.public_key() == &dest_pubkey {
+            OfflineRecipientPolicy::Interactive
+        } else {
+            self.get_send_policy_for_recipient(&dest_pubkey).await
+        };
+
+        if send_policy == OfflineRecipientPolicy::OneSided {
+            let result = self
+                .send_one_sided_transaction(
+                    dest_pubkey,
+                    amount,
+                    unique_id,
+                    parent_public_key,
+                    fee_per_gram,
+                    message,
+                    transaction_broadcast_join_handles,
+                )
+                .await
+                .map(TransactionServiceResponse::TransactionSent);
+            let _result = reply_channel.send(result).map_err(|e| {
+                warn!(target: LOG_TARGET, "Failed to send service reply");
+                e
+            });
+            return Ok(());
+        }
+
         let tx_id = TxId::new_random();
+        self.record_send_intent(tx_id, &dest_pubkey, amount, fee_per_gram, false)
+            .await?;
 
         // If we're paying ourselves, let's complete and submit the transaction immediately
         if self.node_identity.public_key() == &dest_pubkey {
@@ -814,7 +1049,7 @@ where
                 "Received transaction with spend-to-self transaction"
             );
 
-            let (fee, transaction) = self
+            let (fee, transaction) = match self
                 .output_manager_service
                 .create_pay_to_self_transaction(
                     tx_id,
@@ -825,7 +1060,14 @@ where
                     None,
                     message.clone(),
                 )
-                .await?;
+                .await
+            {
+                Ok(v) => v,
+                Err(e) => {
+                    self.resolve_send_intent(tx_id).await;
+                    return Err(e.into());
+                },
+            };
 
             // Notify that the transaction was successfully resolved.
             let _size = self
@@ -850,6 +1092,7 @@ where
                 ),
             )
             .await?;
+            self.resolve_send_intent(tx_id).await;
 
             let _result = reply_channel
                 .send(Ok(TransactionServiceResponse::TransactionSent(tx_id)))
@@ -867,9 +1110,14 @@ where
         self.send_transaction_cancellation_senders
             .insert(tx_id, cancellation_sender);
 
+        let mut resources = self.resources.clone();
+        if send_policy == OfflineRecipientPolicy::StoreAndForward {
+            resources.config.transaction_routing_mechanism = TransactionRoutingMechanism::StoreAndForwardOnly;
+        }
+
         let protocol = TransactionSendProtocol::new(
             tx_id,
-            self.resources.clone(),
+            resources,
             tx_reply_receiver,
             cancellation_receiver,
             dest_pubkey,
@@ -890,6 +1138,59 @@ where
         Ok(())
     }
 
+    /// Determines how an interactive transaction to `dest_pubkey` should be sent. The configured offline recipient
+    /// policy only applies to contacts whose liveness data shows them to be offline, otherwise the transaction is sent
+    /// interactively as usual.
+    async fn get_send_policy_for_recipient(&mut self, dest_pubkey: &CommsPublicKey) -> OfflineRecipientPolicy {
+        let policy = self.config.offline_recipient_policy;
+        if policy == OfflineRecipientPolicy::Interactive {
+            return policy;
+        }
+        let contacts_service = match self.contacts_service.as_mut() {
+            Some(contacts_service) => contacts_service,
+            None => return OfflineRecipientPolicy::Interactive,
+        };
+
+        let contact = match contacts_service.get_contact(dest_pubkey.clone()).await {
+            Ok(contact) => contact,
+            Err(ContactsServiceError::ContactsServiceStorageError(ContactsServiceStorageError::ValueNotFound(_))) => {
+                return OfflineRecipientPolicy::Interactive;
+            },
+            Err(e) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Unable to look up contact {} to check liveness: {}", dest_pubkey, e
+                );
+                return OfflineRecipientPolicy::Interactive;
+            },
+        };
+
+        match contacts_service.get_contact_online_status(contact.last_seen).await {
+            Ok(ContactOnlineStatus::Offline) => {
+                info!(
+                    target: LOG_TARGET,
+                    "Recipient '{}' ({}) has been offline since {}, sending transaction using the {} policy",
+                    contact.alias,
+                    dest_pubkey,
+                    contact
+                        .last_seen
+                        .map(|dt| dt.to_string())
+                        .unwrap_or_else(|| "never".to_string()),
+                    policy
+                );
+                policy
+            },
+            Ok(_) => OfflineRecipientPolicy::Interactive,
+            Err(e) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Unable to determine online status of contact {}: {}", dest_pubkey, e
+                );
+                OfflineRecipientPolicy::Interactive
+            },
+        }
+    }
+
     /// broadcasts a SHA-XTR atomic swap transaction
     /// # Arguments
     /// 'dest_pubkey': The Comms pubkey of the recipient node
@@ -905,22 +1206,63 @@ where
             JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
         >,
     ) -> Result<Box<(TxId, PublicKey, TransactionOutput)>, TransactionServiceError> {
-        let tx_id = TxId::new_random();
         // this can be anything, so lets generate a random private key
         let pre_image = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
-        let hash: [u8; 32] = Sha256::digest(pre_image.as_bytes()).into();
+        let hash = sha256_hash_lock(&pre_image);
 
         // lets make the unlock height a day from now, 2 min blocks which gives us 30 blocks per hour * 24 hours
         let height = self.last_seen_tip_height.unwrap_or(0) + (24 * 30);
 
-        // lets create the HTLC script
-        let script = script!(
-            HashSha256 PushHash(Box::new(hash)) Equal IfThen
-                PushPubKey(Box::new(dest_pubkey.clone()))
-            Else
-                CheckHeightVerify(height) PushPubKey(Box::new(self.node_identity.public_key().clone()))
-            EndIf
+        let (tx_id, output) = self
+            .send_htlc_transaction(
+                dest_pubkey,
+                amount,
+                hash,
+                height,
+                fee_per_gram,
+                message,
+                transaction_broadcast_join_handles,
+            )
+            .await?;
+        Ok(Box::new((tx_id, pre_image, output)))
+    }
+
+    /// Broadcasts a SHA-256 HTLC transaction that locks `amount` to the hash of a pre-image chosen by the other party
+    /// of an atomic swap. The recipient can claim the funds by revealing the pre-image, otherwise this wallet can
+    /// reclaim them from `lock_height`.
+    /// # Arguments
+    /// 'dest_pubkey': The Comms pubkey of the recipient node
+    /// 'amount': The amount of Tari to send to the recipient
+    /// 'hash': The SHA-256 hash lock
+    /// 'lock_height': The block height from which the funds can be refunded
+    /// 'fee_per_gram': The amount of fee per transaction gram to be included in transaction
+    pub async fn send_htlc_transaction(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        hash: HashValue,
+        lock_height: u64,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<(TxId, TransactionOutput), TransactionServiceError> {
+        let tip_height = self.last_seen_tip_height.unwrap_or(0);
+        if lock_height <= tip_height {
+            return Err(TransactionServiceError::InvalidHtlc(format!(
+                "Lock height {} must be above the current tip height {}",
+                lock_height, tip_height
+            )));
+        }
+        let tx_id = TxId::new_random();
+        let htlc = ShaHtlcScript::new(
+            hash,
+            dest_pubkey.clone(),
+            self.node_identity.public_key().clone(),
+            lock_height,
         );
+        let script = htlc.to_script();
 
         // Empty covenant
         let covenant = Covenant::default();
@@ -991,11 +1333,11 @@ where
             spend_key,
             output.features.clone(),
             script,
-            inputs!(PublicKey::from_secret_key(self.node_identity.secret_key())),
+            htlc.refund_input_data(),
             self.node_identity.secret_key().clone(),
             output.sender_offset_public_key.clone(),
             output.metadata_signature.clone(),
-            height,
+            lock_height,
             covenant,
         );
 
@@ -1062,7 +1404,7 @@ where
         )
         .await?;
 
-        Ok(Box::new((tx_id, pre_image, output)))
+        Ok((tx_id, output))
     }
 
     /// Sends a one side payment transaction to a recipient
@@ -1090,9 +1432,11 @@ where
         }
 
         let tx_id = TxId::new_random();
+        self.record_send_intent(tx_id, &dest_pubkey, amount, fee_per_gram, true)
+            .await?;
 
         // Prepare sender part of the transaction
-        let mut stp = self
+        let mut stp = match self
             .output_manager_service
             .prepare_transaction_to_send(
                 tx_id,
@@ -1105,7 +1449,14 @@ where
                 script!(PushPubKey(Box::new(dest_pubkey.clone()))),
                 Covenant::default(),
             )
-            .await?;
+            .await
+        {
+            Ok(stp) => stp,
+            Err(e) => {
+                self.resolve_send_intent(tx_id).await;
+                return Err(e.into());
+            },
+        };
 
         // This call is needed to advance the state from `SingleRoundMessageReady` to `SingleRoundMessageReady`,
         // but the returned value is not used
@@ -1118,6 +1469,53 @@ where
             .await
             .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
 
+        self.finalize_one_sided_transaction(tx_id, &mut stp, &dest_pubkey)?;
+
+        // This event being sent is important, but not critical to the protocol being successful. Send only fails if
+        // there are no subscribers.
+        let _result = self
+            .event_publisher
+            .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
+
+        // Broadcast one-sided transaction
+
+        let tx = stp
+            .get_transaction()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        let fee = stp
+            .get_fee_amount()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        self.submit_transaction(
+            transaction_broadcast_join_handles,
+            CompletedTransaction::new(
+                tx_id,
+                self.resources.node_identity.public_key().clone(),
+                dest_pubkey.clone(),
+                amount,
+                fee,
+                tx.clone(),
+                TransactionStatus::Completed,
+                message.clone(),
+                Utc::now().naive_utc(),
+                TransactionDirection::Outbound,
+                None,
+                None,
+            ),
+        )
+        .await?;
+        self.resolve_send_intent(tx_id).await;
+
+        Ok(tx_id)
+    }
+
+    /// Creates the one-sided output paying `dest_pubkey` in a transaction prepared by the output manager service, and
+    /// finalizes the transaction
+    fn finalize_one_sided_transaction(
+        &self,
+        tx_id: TxId,
+        stp: &mut SenderTransactionProtocol,
+        dest_pubkey: &CommsPublicKey,
+    ) -> Result<(), TransactionServiceError> {
         // Prepare receiver part of the transaction
 
         // Diffie-Hellman shared secret `k_Ob * K_Sb = K_Ob * k_Sb` results in a public key, which is converted to
@@ -1126,7 +1524,7 @@ where
             .get_recipient_sender_offset_private_key(0)
             .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
         let spend_key = PrivateKey::from_bytes(
-            CommsPublicKey::shared_secret(&sender_offset_private_key.clone(), &dest_pubkey.clone()).as_bytes(),
+            CommsPublicKey::shared_secret(&sender_offset_private_key.clone(), dest_pubkey).as_bytes(),
         )
         .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
 
@@ -1173,31 +1571,73 @@ where
         })?;
         info!(target: LOG_TARGET, "Finalized one-side transaction TxId: {}", tx_id);
 
+        Ok(())
+    }
+
+    /// Sends a one-sided payment to each recipient in a single transaction. The transaction is recorded against the
+    /// first recipient, with the total amount sent.
+    pub async fn send_one_sided_to_many_transaction(
+        &mut self,
+        recipients: Vec<PaymentRecipient>,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        let destination = match recipients.first() {
+            Some(recipient) => recipient.address.clone(),
+            None => {
+                return Err(TransactionServiceError::OneSidedTransactionError(
+                    "At least one recipient is required".to_string(),
+                ))
+            },
+        };
+        if recipients.iter().any(|r| &r.address == self.node_identity.public_key()) {
+            warn!(target: LOG_TARGET, "One-sided spend-to-self transactions not supported");
+            return Err(TransactionServiceError::OneSidedTransactionError(
+                "One-sided spend-to-self transactions not supported".to_string(),
+            ));
+        }
+        if recipients
+            .iter()
+            .any(|r| r.payment_id.len() > REWIND_USER_MESSAGE_LENGTH)
+        {
+            return Err(TransactionServiceError::OneSidedTransactionError(format!(
+                "Payment ids can be at most {} bytes",
+                REWIND_USER_MESSAGE_LENGTH
+            )));
+        }
+
+        let tx_id = TxId::new_random();
+        let num_recipients = recipients.len();
+        let amount = recipients.iter().map(|r| r.amount).sum::<MicroTari>();
+        let (fee, tx) = self
+            .output_manager_service
+            .create_pay_to_many_transaction(tx_id, recipients, fee_per_gram, message.clone())
+            .await?;
+        info!(
+            target: LOG_TARGET,
+            "Finalized one-sided transaction TxId: {} to {} recipients", tx_id, num_recipients
+        );
+
         // This event being sent is important, but not critical to the protocol being successful. Send only fails if
         // there are no subscribers.
         let _result = self
             .event_publisher
             .send(Arc::new(TransactionEvent::TransactionCompletedImmediately(tx_id)));
 
-        // Broadcast one-sided transaction
-
-        let tx = stp
-            .get_transaction()
-            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
-        let fee = stp
-            .get_fee_amount()
-            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
         self.submit_transaction(
             transaction_broadcast_join_handles,
             CompletedTransaction::new(
                 tx_id,
                 self.resources.node_identity.public_key().clone(),
-                dest_pubkey.clone(),
+                destination,
                 amount,
                 fee,
-                tx.clone(),
+                tx,
                 TransactionStatus::Completed,
-                message.clone(),
+                message,
                 Utc::now().naive_utc(),
                 TransactionDirection::Outbound,
                 None,
@@ -1412,10 +1852,99 @@ where
         })?;
 
         self.output_manager_service.cancel_transaction(tx_id).await?;
+        self.stop_pending_transaction(tx_id, TxCancellationReason::UserCancelled)
+            .await;
 
-        if let Some(cancellation_sender) = self.send_transaction_cancellation_senders.remove(&tx_id) {
-            let _result = cancellation_sender.send(());
-        }
+        info!(target: LOG_TARGET, "Pending Transaction (TxId: {}) cancelled", tx_id);
+
+        Ok(())
+    }
+
+    /// Replaces the pending outbound transaction `tx_id` with a new transaction to the same recipient that spends the
+    /// same inputs at `fee_per_gram`. The inputs are moved over to the replacement by the output manager before the
+    /// original is cancelled, so they are never released for another transaction to spend in between.
+    async fn bump_transaction_fee(
+        &mut self,
+        tx_id: TxId,
+        fee_per_gram: MicroTari,
+        join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TransactionSendResult, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        let outbound_tx = self.db.get_pending_outbound_transaction(tx_id).await?;
+
+        let new_tx_id = TxId::new_random();
+        self.record_send_intent(
+            new_tx_id,
+            &outbound_tx.destination_public_key,
+            outbound_tx.amount,
+            fee_per_gram,
+            false,
+        )
+        .await?;
+        let sender_protocol = match self
+            .output_manager_service
+            .prepare_fee_bump_transaction(
+                tx_id,
+                new_tx_id,
+                outbound_tx.amount,
+                outbound_tx.fee,
+                fee_per_gram,
+                outbound_tx.message.clone(),
+            )
+            .await
+        {
+            Ok(sp) => sp,
+            Err(e) => {
+                self.resolve_send_intent(new_tx_id).await;
+                return Err(e.into());
+            },
+        };
+
+        self.db.cancel_pending_transaction(tx_id).await?;
+        self.stop_pending_transaction(tx_id, TxCancellationReason::FeeBumped)
+            .await;
+        info!(
+            target: LOG_TARGET,
+            "Pending Transaction (TxId: {}) replaced by TxId: {} at {} per gram", tx_id, new_tx_id, fee_per_gram
+        );
+
+        let (tx_reply_sender, tx_reply_receiver) = mpsc::channel(100);
+        let (cancellation_sender, cancellation_receiver) = oneshot::channel();
+        self.pending_transaction_reply_senders
+            .insert(new_tx_id, tx_reply_sender);
+        self.send_transaction_cancellation_senders
+            .insert(new_tx_id, cancellation_sender);
+        let protocol = TransactionSendProtocol::new(
+            new_tx_id,
+            self.resources.clone(),
+            tx_reply_receiver,
+            cancellation_receiver,
+            outbound_tx.destination_public_key,
+            outbound_tx.amount,
+            None,
+            None,
+            fee_per_gram,
+            outbound_tx.message,
+            None,
+            TransactionSendProtocolStage::Queued,
+            None,
+            self.last_seen_tip_height,
+            Some(sender_protocol),
+        );
+        join_handles.push(tokio::spawn(protocol.execute()));
+
+        Ok(new_tx_id)
+    }
+
+    /// Stops the protocols and discards the queued messages of a pending transaction that has been cancelled in the
+    /// database, and notifies subscribers of the cancellation
+    async fn stop_pending_transaction(&mut self, tx_id: TxId, reason: TxCancellationReason) {
+        remove_queued_transaction_messages(&self.db, tx_id, None).await;
+
+        if let Some(cancellation_sender) = self.send_transaction_cancellation_senders.remove(&tx_id) {
+            let _result = cancellation_sender.send(());
+        }
         let _public_key = self.pending_transaction_reply_senders.remove(&tx_id);
 
         if let Some(cancellation_sender) = self.receiver_transaction_cancellation_senders.remove(&tx_id) {
@@ -1425,10 +1954,7 @@ where
 
         let _size = self
             .event_publisher
-            .send(Arc::new(TransactionEvent::TransactionCancelled(
-                tx_id,
-                TxCancellationReason::UserCancelled,
-            )))
+            .send(Arc::new(TransactionEvent::TransactionCancelled(tx_id, reason)))
             .map_err(|e| {
                 trace!(
                     target: LOG_TARGET,
@@ -1437,10 +1963,6 @@ where
                 );
                 e
             });
-
-        info!(target: LOG_TARGET, "Pending Transaction (TxId: {}) cancelled", tx_id);
-
-        Ok(())
     }
 
     /// Handle a Transaction Cancelled message received from the Comms layer
@@ -2149,6 +2671,745 @@ where
         Ok(tx_id)
     }
 
+    /// Durably record the intent to send a transaction before any outputs are encumbered for it
+    async fn record_send_intent(
+        &self,
+        tx_id: TxId,
+        dest_pubkey: &CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        one_sided: bool,
+    ) -> Result<(), TransactionServiceError> {
+        self.db
+            .add_send_intent(SendIntent::new(
+                tx_id,
+                dest_pubkey.clone(),
+                amount,
+                fee_per_gram,
+                Utc::now().naive_utc(),
+                one_sided,
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Remove the send intent once the transaction has been persisted or abandoned
+    async fn resolve_send_intent(&self, tx_id: TxId) {
+        if let Err(e) = self.db.resolve_send_intent(tx_id).await {
+            warn!(
+                target: LOG_TARGET,
+                "Failed to resolve send intent for TxId: {}: {}", tx_id, e
+            );
+        }
+    }
+
+    /// Retry delivery of queued transaction messages that are due and drop the ones that have expired. Each delivery
+    /// attempt is rescheduled before it is made so that a failed or interrupted attempt backs off.
+    async fn process_transaction_message_queue(&mut self) -> Result<(), TransactionServiceError> {
+        let now = Utc::now().naive_utc();
+        for mut message in self.db.get_queued_transaction_messages(None).await? {
+            if message.is_expired(now) {
+                info!(
+                    target: LOG_TARGET,
+                    "Queued {:?} message (TxId: {}) expired after {} attempt(s)",
+                    message.message_type,
+                    message.tx_id,
+                    message.attempts
+                );
+                self.db
+                    .remove_queued_transaction_messages(message.tx_id, Some(message.message_type))
+                    .await?;
+                continue;
+            }
+            if !message.is_due(now) {
+                continue;
+            }
+
+            message.schedule_next_attempt(
+                now,
+                self.resources.config.message_queue_initial_retry_delay,
+                self.resources.config.message_queue_max_retry_delay,
+            );
+            self.db.queue_transaction_message(message.clone()).await?;
+
+            debug!(
+                target: LOG_TARGET,
+                "Retrying queued {:?} message (TxId: {}), attempt {}",
+                message.message_type,
+                message.tx_id,
+                message.attempts
+            );
+            let db = self.db.clone();
+            let outbound_message_service = self.resources.outbound_message_service.clone();
+            let direct_send_timeout = self.resources.config.direct_send_timeout;
+            tokio::spawn(async move {
+                let tx_id = message.tx_id;
+                if let Err(e) =
+                    send_queued_transaction_message(message, db, outbound_message_service, direct_send_timeout).await
+                {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Error sending queued message (TxId: {}): {}", tx_id, e
+                    );
+                }
+            });
+        }
+        Ok(())
+    }
+
+    async fn schedule_payment(&mut self, payment: ScheduledPayment) -> Result<u64, TransactionServiceError> {
+        payment
+            .schedule
+            .validate()
+            .map_err(TransactionServiceError::InvalidPaymentSchedule)?;
+        if payment.payment_type == ScheduledPaymentType::OneSided &&
+            self.node_identity.public_key() == &payment.destination_public_key
+        {
+            return Err(TransactionServiceError::OneSidedTransactionError(
+                "One-sided spend-to-self transactions not supported".to_string(),
+            ));
+        }
+        let payment_id = payment.id;
+        info!(
+            target: LOG_TARGET,
+            "Scheduled payment {} of {} to {}, first payment at {}",
+            payment_id,
+            payment.amount,
+            payment.destination_public_key,
+            payment.schedule.next_payment_at
+        );
+        self.db.upsert_scheduled_payment(payment).await?;
+        Ok(payment_id)
+    }
+
+    async fn cancel_scheduled_payment(&mut self, payment_id: u64) -> Result<(), TransactionServiceError> {
+        let mut payment = self
+            .db
+            .get_scheduled_payments()
+            .await?
+            .into_iter()
+            .find(|p| p.id == payment_id)
+            .ok_or(TransactionServiceError::ScheduledPaymentNotFound(payment_id))?;
+        if payment.status == ScheduledPaymentStatus::Active {
+            payment.status = ScheduledPaymentStatus::Cancelled;
+            self.db.upsert_scheduled_payment(payment).await?;
+        }
+        Ok(())
+    }
+
+    /// Send the scheduled payments that have fallen due. A payment is deferred, rather than failed, while the wallet is
+    /// offline or its available balance does not cover the payment.
+    async fn process_scheduled_payments(
+        &mut self,
+        send_transaction_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TransactionSendResult, TransactionServiceProtocolError<TxId>>>,
+        >,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let now = Utc::now().naive_utc();
+        let due_payments = self
+            .db
+            .get_scheduled_payments()
+            .await?
+            .into_iter()
+            .filter(|p| p.is_due(now))
+            .collect::<Vec<_>>();
+        if due_payments.is_empty() {
+            return Ok(());
+        }
+
+        let online_status = self.resources.connectivity.get_connectivity_status();
+        let mut available_balance = self.output_manager_service.get_balance().await?.available_balance;
+        for mut payment in due_payments {
+            let deferral_reason = if online_status != OnlineStatus::Online {
+                Some(format!("Wallet is not connected to a base node ({:?})", online_status))
+            } else if available_balance < payment.amount {
+                Some(format!(
+                    "Available balance of {} does not cover the payment of {}",
+                    available_balance, payment.amount
+                ))
+            } else {
+                None
+            };
+            if let Some(reason) = deferral_reason {
+                if payment.defer(reason.clone()) {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Scheduled payment {} deferred: {}", payment.id, reason
+                    );
+                    self.db.upsert_scheduled_payment(payment.clone()).await?;
+                    let _size = self
+                        .event_publisher
+                        .send(Arc::new(TransactionEvent::ScheduledPaymentDeferred {
+                            payment_id: payment.id,
+                            reason,
+                        }))
+                        .map_err(|e| {
+                            trace!(
+                                target: LOG_TARGET,
+                                "Error sending event, usually because there are no subscribers: {:?}",
+                                e
+                            );
+                            e
+                        });
+                }
+                continue;
+            }
+
+            // The schedule is advanced before sending so that a payment interrupted by a restart is not made twice
+            payment.start_payment(now);
+            self.db.upsert_scheduled_payment(payment.clone()).await?;
+
+            let result = match payment.payment_type {
+                ScheduledPaymentType::OneSided => {
+                    self.send_one_sided_transaction(
+                        payment.destination_public_key.clone(),
+                        payment.amount,
+                        None,
+                        None,
+                        payment.fee_per_gram,
+                        payment.message.clone(),
+                        transaction_broadcast_join_handles,
+                    )
+                    .await
+                },
+                ScheduledPaymentType::Standard => {
+                    let (reply_tx, reply_rx) = oneshot::channel();
+                    match self
+                        .send_transaction(
+                            payment.destination_public_key.clone(),
+                            payment.amount,
+                            None,
+                            None,
+                            payment.fee_per_gram,
+                            payment.message.clone(),
+                            send_transaction_join_handles,
+                            transaction_broadcast_join_handles,
+                            reply_tx,
+                        )
+                        .await
+                    {
+                        Ok(_) => match reply_rx.await {
+                            Ok(Ok(TransactionServiceResponse::TransactionSent(tx_id))) => Ok(tx_id),
+                            Ok(Ok(_)) => Err(TransactionServiceError::UnexpectedApiResponse),
+                            Ok(Err(e)) => Err(e),
+                            Err(_) => Err(TransactionServiceError::ApiReceiveFailed),
+                        },
+                        Err(e) => Err(e),
+                    }
+                },
+            };
+
+            let event = match result {
+                Ok(tx_id) => {
+                    info!(
+                        target: LOG_TARGET,
+                        "Scheduled payment {} of {} sent (TxId: {})", payment.id, payment.amount, tx_id
+                    );
+                    available_balance = available_balance.saturating_sub(payment.amount);
+                    payment.record_payment(tx_id);
+                    TransactionEvent::ScheduledPaymentExecuted {
+                        payment_id: payment.id,
+                        tx_id,
+                    }
+                },
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Scheduled payment {} failed: {}", payment.id, e);
+                    payment.record_failure(e.to_string());
+                    TransactionEvent::ScheduledPaymentFailed {
+                        payment_id: payment.id,
+                        reason: e.to_string(),
+                    }
+                },
+            };
+            self.db.upsert_scheduled_payment(payment).await?;
+            let _size = self.event_publisher.send(Arc::new(event)).map_err(|e| {
+                trace!(
+                    target: LOG_TARGET,
+                    "Error sending event, usually because there are no subscribers: {:?}",
+                    e
+                );
+                e
+            });
+        }
+        Ok(())
+    }
+
+    async fn get_multisig_group(&self, group_id: u64) -> Result<MultisigGroup, TransactionServiceError> {
+        self.db
+            .get_multisig_group(group_id)
+            .await?
+            .ok_or(TransactionServiceError::MultisigGroupNotFound(group_id))
+    }
+
+    async fn get_multisig_session(&self, session_id: u64) -> Result<MultisigSession, TransactionServiceError> {
+        self.db
+            .get_multisig_session(session_id)
+            .await?
+            .ok_or(TransactionServiceError::MultisigSessionNotFound(session_id))
+    }
+
+    fn send_multisig_message(&self, message: MultisigMessageType, destination_public_key: CommsPublicKey) {
+        tokio::spawn(send_multisig_message(
+            message,
+            destination_public_key,
+            self.resources.outbound_message_service.clone(),
+        ));
+    }
+
+    fn publish_multisig_event(&self, event: TransactionEvent) {
+        let _size = self.event_publisher.send(Arc::new(event)).map_err(|e| {
+            trace!(
+                target: LOG_TARGET,
+                "Error sending event, usually because there are no subscribers: {:?}",
+                e
+            );
+            e
+        });
+    }
+
+    /// Fund a new m-of-n multisig group with this wallet and the `cosigners` as participants. This wallet acts as the
+    /// dealer: it splits the group spending key and sends every cosigner its key share.
+    async fn create_multisig_group(
+        &mut self,
+        threshold: u32,
+        cosigners: Vec<CommsPublicKey>,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<(u64, TxId), TransactionServiceError> {
+        let own_public_key = self.node_identity.public_key().clone();
+        let unique_cosigners = cosigners.iter().collect::<HashSet<_>>();
+        if unique_cosigners.len() != cosigners.len() || unique_cosigners.contains(&own_public_key) {
+            return Err(TransactionServiceError::MultisigError(
+                "The cosigners must be distinct and must not include this wallet".to_string(),
+            ));
+        }
+        let mut participants = vec![own_public_key.clone()];
+        participants.extend(cosigners);
+
+        let spending_key = PrivateKey::random(&mut OsRng);
+        let script_private_key = PrivateKey::random(&mut OsRng);
+        let (mut shares, share_commitments) = split_secret(&spending_key, threshold as usize, participants.len())?;
+
+        let tx_id = TxId::new_random();
+        let (fee, transaction, output) = self
+            .output_manager_service
+            .create_multisig_funding_transaction(
+                tx_id,
+                amount,
+                spending_key,
+                script_private_key.clone(),
+                fee_per_gram,
+                message.clone(),
+            )
+            .await?;
+        let group = MultisigGroup::new(
+            threshold,
+            participants,
+            shares.remove(0),
+            share_commitments,
+            output,
+            amount,
+            script_private_key,
+        );
+        self.db.upsert_multisig_group(group.clone()).await?;
+
+        self.submit_transaction(
+            transaction_broadcast_join_handles,
+            CompletedTransaction::new(
+                tx_id,
+                own_public_key.clone(),
+                own_public_key,
+                amount,
+                fee,
+                transaction,
+                TransactionStatus::Completed,
+                message,
+                Utc::now().naive_utc(),
+                TransactionDirection::Outbound,
+                None,
+                None,
+            ),
+        )
+        .await?;
+
+        for share in shares {
+            if let Some(participant) = group.participant(share.index) {
+                self.send_multisig_message(
+                    MultisigMessageType::KeyShare(group.key_share_message(&share)),
+                    participant.clone(),
+                );
+            }
+        }
+        info!(
+            target: LOG_TARGET,
+            "Created {}-of-{} multisig group {} (TxId: {})",
+            group.threshold,
+            group.participants.len(),
+            group.group_id,
+            tx_id
+        );
+        Ok((group.group_id, tx_id))
+    }
+
+    /// Propose spending a multisig group output to `destination`. The spend is completed once enough cosigners have
+    /// approved the proposal and returned their partial signatures.
+    async fn propose_multisig_spend(
+        &mut self,
+        group_id: u64,
+        destination: CommsPublicKey,
+        fee_per_gram: MicroTari,
+        message: String,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<u64, TransactionServiceError> {
+        let group = self.get_multisig_group(group_id).await?;
+        let fee = spend_fee(fee_per_gram, &destination);
+        let mut session = MultisigSession::propose(
+            &group,
+            self.node_identity.public_key().clone(),
+            destination,
+            fee,
+            0,
+            message,
+        )?;
+        let session_id = session.session_id;
+        // A 1-of-n group does not need any cosigners
+        if session.start_signing(&group, &self.resources.factories).is_some() && session.has_all_signatures(&group) {
+            self.finalize_multisig_spend(group, session, transaction_broadcast_join_handles)
+                .await?;
+            return Ok(session_id);
+        }
+
+        self.db.upsert_multisig_session(session.clone()).await?;
+        let proposal = session.to_proposal();
+        for participant in group
+            .participants
+            .iter()
+            .filter(|p| *p != self.node_identity.public_key())
+        {
+            self.send_multisig_message(
+                MultisigMessageType::SpendProposal(proposal.clone()),
+                participant.clone(),
+            );
+        }
+        Ok(session_id)
+    }
+
+    async fn approve_multisig_spend(&mut self, session_id: u64) -> Result<(), TransactionServiceError> {
+        let mut session = self.get_multisig_session(session_id).await?;
+        let group = self.get_multisig_group(session.group_id).await?;
+        let nonces = session.approve(&group)?;
+        self.db.upsert_multisig_session(session.clone()).await?;
+        self.send_multisig_message(
+            MultisigMessageType::Nonces(nonces.to_proto(session_id)),
+            session.proposer,
+        );
+        Ok(())
+    }
+
+    /// Decline or cancel a multisig session. Declining only informs the proposer, while a proposer cancelling a session
+    /// informs every other participant.
+    async fn cancel_multisig_session(&mut self, session_id: u64) -> Result<(), TransactionServiceError> {
+        let mut session = self.get_multisig_session(session_id).await?;
+        if session.is_finished() {
+            return Err(TransactionServiceError::InvalidStateError);
+        }
+        let group = self.get_multisig_group(session.group_id).await?;
+        let (reason, recipients) = match session.role {
+            MultisigRole::Cosigner => {
+                let reason = if session.state == MultisigSessionState::AwaitingApproval {
+                    session.decline()?;
+                    "Declined by cosigner"
+                } else {
+                    session.cancel("Cancelled by cosigner".to_string());
+                    "Cancelled by cosigner"
+                };
+                (reason, vec![session.proposer.clone()])
+            },
+            MultisigRole::Proposer => {
+                session.cancel("Cancelled by proposer".to_string());
+                let recipients = group
+                    .participants
+                    .iter()
+                    .filter(|p| *p != self.node_identity.public_key())
+                    .cloned()
+                    .collect();
+                ("Cancelled by proposer", recipients)
+            },
+        };
+        self.db.upsert_multisig_session(session).await?;
+        for recipient in recipients {
+            self.send_multisig_message(
+                MultisigMessageType::SessionClosed(proto::MultisigSessionClosed {
+                    session_id,
+                    completed: false,
+                    reason: reason.to_string(),
+                }),
+                recipient,
+            );
+        }
+        Ok(())
+    }
+
+    /// Handle an incoming multisig negotiation message from `source_public_key`
+    async fn handle_multisig_message(
+        &mut self,
+        source_public_key: CommsPublicKey,
+        message: proto::MultisigMessage,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<(), TransactionServiceError> {
+        let message = message
+            .message
+            .ok_or_else(|| TransactionServiceError::InvalidMessageError("Empty multisig message".to_string()))?;
+        match message {
+            MultisigMessageType::KeyShare(key_share) => {
+                if self.db.get_multisig_group(key_share.group_id).await?.is_some() {
+                    return Err(TransactionServiceError::RepeatedMessageError);
+                }
+                let group = MultisigGroup::try_from(key_share).map_err(TransactionServiceError::InvalidMessageError)?;
+                if group.participant(1) != Some(&source_public_key) {
+                    return Err(TransactionServiceError::MultisigError(
+                        "Key share was not sent by the group dealer".to_string(),
+                    ));
+                }
+                if group.participant(group.key_share.index) != Some(self.node_identity.public_key()) {
+                    return Err(TransactionServiceError::MultisigError(
+                        "Key share is not for this wallet".to_string(),
+                    ));
+                }
+                group.validate(&self.resources.factories)?;
+                let group_id = group.group_id;
+                self.db.upsert_multisig_group(group).await?;
+                self.publish_multisig_event(TransactionEvent::MultisigGroupJoined(group_id));
+            },
+            MultisigMessageType::SpendProposal(proposal) => {
+                if self.db.get_multisig_session(proposal.session_id).await?.is_some() {
+                    return Err(TransactionServiceError::RepeatedMessageError);
+                }
+                let group = self.get_multisig_group(proposal.group_id).await?;
+                let session = MultisigSession::from_proposal(&group, source_public_key, proposal)?;
+                let (session_id, group_id) = (session.session_id, session.group_id);
+                self.db.upsert_multisig_session(session).await?;
+                self.publish_multisig_event(TransactionEvent::MultisigSpendProposalReceived { session_id, group_id });
+            },
+            MultisigMessageType::Nonces(nonces) => {
+                let session_id = nonces.session_id;
+                let mut session = self.get_multisig_session(session_id).await?;
+                let group = self.get_multisig_group(session.group_id).await?;
+                let nonces =
+                    MultisigPublicNonces::try_from(nonces).map_err(TransactionServiceError::InvalidMessageError)?;
+                if group.participant(nonces.index) != Some(&source_public_key) {
+                    return Err(TransactionServiceError::MultisigError(format!(
+                        "Nonces were not sent by participant {}",
+                        nonces.index
+                    )));
+                }
+                let request = session.add_nonces(&group, nonces, &self.resources.factories)?;
+                self.db.upsert_multisig_session(session).await?;
+                if let Some(request) = request {
+                    for index in request.signers.iter().filter(|i| **i != group.key_share.index) {
+                        if let Some(signer) = group.participant(*index) {
+                            self.send_multisig_message(
+                                MultisigMessageType::SigningRequest(request.to_proto(session_id)),
+                                signer.clone(),
+                            );
+                        }
+                    }
+                }
+            },
+            MultisigMessageType::SigningRequest(request) => {
+                let session_id = request.session_id;
+                let mut session = self.get_multisig_session(session_id).await?;
+                if session.proposer != source_public_key {
+                    return Err(TransactionServiceError::MultisigError(
+                        "Signing request was not sent by the proposer".to_string(),
+                    ));
+                }
+                let group = self.get_multisig_group(session.group_id).await?;
+                let request =
+                    MultisigSigningRequest::try_from(request).map_err(TransactionServiceError::InvalidMessageError)?;
+                let signatures = session.sign(&group, request)?;
+                // The nonces are single use, so they must be discarded before the signatures leave this wallet
+                self.db.upsert_multisig_session(session).await?;
+                self.send_multisig_message(
+                    MultisigMessageType::PartialSignatures(signatures.to_proto(session_id)),
+                    source_public_key,
+                );
+            },
+            MultisigMessageType::PartialSignatures(signatures) => {
+                let mut session = self.get_multisig_session(signatures.session_id).await?;
+                let group = self.get_multisig_group(session.group_id).await?;
+                let signatures = MultisigPartialSignatures::try_from(signatures)
+                    .map_err(TransactionServiceError::InvalidMessageError)?;
+                if group.participant(signatures.index) != Some(&source_public_key) {
+                    return Err(TransactionServiceError::MultisigError(format!(
+                        "Partial signatures were not sent by participant {}",
+                        signatures.index
+                    )));
+                }
+                session.add_partial_signatures(&group, signatures)?;
+                if session.has_all_signatures(&group) {
+                    self.finalize_multisig_spend(group, session, transaction_broadcast_join_handles)
+                        .await?;
+                } else {
+                    self.db.upsert_multisig_session(session).await?;
+                }
+            },
+            MultisigMessageType::SessionClosed(closed) => {
+                let mut session = self.get_multisig_session(closed.session_id).await?;
+                if session.is_finished() {
+                    return Ok(());
+                }
+                match session.role {
+                    MultisigRole::Cosigner => {
+                        if session.proposer != source_public_key {
+                            return Err(TransactionServiceError::MultisigError(
+                                "Session was not closed by the proposer".to_string(),
+                            ));
+                        }
+                        if closed.completed {
+                            session.complete(None);
+                            let mut group = self.get_multisig_group(session.group_id).await?;
+                            group.status = MultisigGroupStatus::Spent;
+                            self.db.upsert_multisig_group(group).await?;
+                            self.db.upsert_multisig_session(session).await?;
+                        } else {
+                            session.cancel(closed.reason.clone());
+                            self.db.upsert_multisig_session(session).await?;
+                            self.publish_multisig_event(TransactionEvent::MultisigSessionCancelled {
+                                session_id: closed.session_id,
+                                reason: closed.reason,
+                            });
+                        }
+                    },
+                    // The spend can still go ahead with the approval of the other cosigners
+                    MultisigRole::Proposer => info!(
+                        target: LOG_TARGET,
+                        "Cosigner {} closed multisig session {}: {}",
+                        source_public_key,
+                        closed.session_id,
+                        closed.reason
+                    ),
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Complete a multisig spend that has all its partial signatures and submit it to the network
+    async fn finalize_multisig_spend(
+        &mut self,
+        mut group: MultisigGroup,
+        mut session: MultisigSession,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+    ) -> Result<TxId, TransactionServiceError> {
+        let (output, spending_key, sender_offset_private_key) = one_sided_output(
+            &session.destination_public_key,
+            session.amount,
+            &self.resources.factories,
+        )?;
+        let transaction = session.build_transaction(
+            &group,
+            output,
+            &spending_key,
+            &sender_offset_private_key,
+            &self.resources.factories,
+        )?;
+        let tx_id = TxId::new_random();
+        self.submit_transaction(
+            transaction_broadcast_join_handles,
+            CompletedTransaction::new(
+                tx_id,
+                self.node_identity.public_key().clone(),
+                session.destination_public_key.clone(),
+                session.amount,
+                session.fee,
+                transaction,
+                TransactionStatus::Completed,
+                session.message.clone(),
+                Utc::now().naive_utc(),
+                TransactionDirection::Outbound,
+                None,
+                None,
+            ),
+        )
+        .await?;
+
+        session.complete(Some(tx_id));
+        let session_id = session.session_id;
+        self.db.upsert_multisig_session(session).await?;
+        group.status = MultisigGroupStatus::Spent;
+        self.db.upsert_multisig_group(group.clone()).await?;
+
+        for participant in group
+            .participants
+            .iter()
+            .filter(|p| *p != self.node_identity.public_key())
+        {
+            self.send_multisig_message(
+                MultisigMessageType::SessionClosed(proto::MultisigSessionClosed {
+                    session_id,
+                    completed: true,
+                    reason: String::new(),
+                }),
+                participant.clone(),
+            );
+        }
+        self.publish_multisig_event(TransactionEvent::MultisigSpendCompleted { session_id, tx_id });
+        info!(
+            target: LOG_TARGET,
+            "Multisig spend of group {} completed (TxId: {})", group.group_id, tx_id
+        );
+        Ok(tx_id)
+    }
+
+    /// Resolve any send intents left behind by a crash or shutdown between encumbering outputs and persisting the
+    /// transaction. Sends that were persisted will be resumed by the usual protocol restart, the rest are rolled back.
+    async fn reconcile_send_intents(&mut self) -> Result<(), TransactionServiceError> {
+        let intents = self.db.get_send_intents().await?;
+        for intent in intents {
+            let tx_id = intent.tx_id;
+            if !self.db.transaction_exists(tx_id).await? {
+                info!(
+                    target: LOG_TARGET,
+                    "Rolling back interrupted send of {} to {} (TxId: {})",
+                    intent.amount,
+                    intent.destination_public_key,
+                    tx_id
+                );
+                if let Err(e) = self.output_manager_service.cancel_transaction(tx_id).await {
+                    debug!(
+                        target: LOG_TARGET,
+                        "No encumbered outputs released for interrupted send (TxId: {}): {}", tx_id, e
+                    );
+                }
+                // The recipient may have received the initial message, so let them know it will not be finalized.
+                // One-sided sends and sends to ourselves have no counterparty protocol to cancel.
+                if !intent.one_sided && self.node_identity.public_key() != &intent.destination_public_key {
+                    tokio::spawn(send_transaction_cancelled_message(
+                        tx_id,
+                        intent.destination_public_key.clone(),
+                        self.resources.outbound_message_service.clone(),
+                    ));
+                }
+            }
+            self.db.resolve_send_intent(tx_id).await?;
+        }
+        Ok(())
+    }
+
     /// Submit a completed transaction to the Transaction Manager
     async fn submit_transaction(
         &mut self,
@@ -2212,6 +3473,127 @@ where
         Ok(())
     }
 
+    /// Selects the inputs of a one-sided payment to be built and signed by an offline wallet, and returns the
+    /// transaction to export to it
+    async fn prepare_offline_transaction(
+        &mut self,
+        dest_pubkey: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<UnsignedTransaction, TransactionServiceError> {
+        let tx_id = TxId::new_random();
+        let inputs = self
+            .output_manager_service
+            .reserve_offline_transaction_inputs(
+                tx_id,
+                amount,
+                fee_per_gram,
+                script!(PushPubKey(Box::new(dest_pubkey.clone()))),
+            )
+            .await?;
+        info!(
+            target: LOG_TARGET,
+            "Prepared offline transaction TxId: {} spending {} input(s)",
+            tx_id,
+            inputs.len()
+        );
+
+        Ok(UnsignedTransaction {
+            tx_id,
+            destination: dest_pubkey,
+            amount,
+            fee_per_gram,
+            lock_height: 0,
+            inputs,
+            sender_offset_private_key: PrivateKey::random(&mut OsRng),
+            message,
+        })
+    }
+
+    /// Builds and signs exactly the transaction exported by an online wallet. This runs on the offline wallet, so the
+    /// signed transaction is only returned, it is neither stored nor broadcast.
+    async fn sign_offline_transaction(
+        &mut self,
+        unsigned: UnsignedTransaction,
+    ) -> Result<SignedTransaction, TransactionServiceError> {
+        let tx_id = unsigned.tx_id;
+        let mut stp = self
+            .output_manager_service
+            .build_offline_transaction(
+                tx_id,
+                unsigned.inputs.clone(),
+                unsigned.amount,
+                unsigned.fee_per_gram,
+                unsigned.lock_height,
+                unsigned.message.clone(),
+                script!(PushPubKey(Box::new(unsigned.destination.clone()))),
+                unsigned.sender_offset_private_key.clone(),
+            )
+            .await?;
+
+        // This call is needed to advance the state from `SingleRoundMessageReady` to `SingleRoundMessageReady`,
+        // but the returned value is not used
+        let _single_round_sender_data = stp
+            .build_single_round_message()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        self.finalize_one_sided_transaction(tx_id, &mut stp, &unsigned.destination)?;
+
+        let transaction = stp
+            .take_transaction()
+            .map_err(|e| TransactionServiceProtocolError::new(tx_id, e.into()))?;
+        let signed = SignedTransaction {
+            tx_id,
+            transaction,
+            amount: unsigned.amount,
+            message: unsigned.message.clone(),
+        };
+        signed.verify_matches(&unsigned, &self.resources.factories.commitment)?;
+        Ok(signed)
+    }
+
+    /// Broadcasts a transaction signed by an offline wallet once it has been checked to be the payment that this wallet
+    /// exported
+    async fn submit_offline_transaction(
+        &mut self,
+        transaction_broadcast_join_handles: &mut FuturesUnordered<
+            JoinHandle<Result<TxId, TransactionServiceProtocolError<TxId>>>,
+        >,
+        unsigned: UnsignedTransaction,
+        signed: SignedTransaction,
+    ) -> Result<(), TransactionServiceError> {
+        signed.verify_matches(&unsigned, &self.resources.factories.commitment)?;
+        let tx_id = signed.tx_id;
+        // The inputs are released when the wallet restarts, in which case there is nothing to confirm and they are
+        // marked as spent once the transaction is mined
+        if let Err(e) = self.output_manager_service.confirm_pending_transaction(tx_id).await {
+            warn!(
+                target: LOG_TARGET,
+                "Inputs of offline transaction TxId: {} are no longer reserved: {}", tx_id, e
+            );
+        }
+
+        let fee = signed.transaction.body.get_total_fee();
+        self.submit_transaction(
+            transaction_broadcast_join_handles,
+            CompletedTransaction::new(
+                tx_id,
+                self.resources.node_identity.public_key().clone(),
+                unsigned.destination,
+                unsigned.amount,
+                fee,
+                signed.transaction,
+                TransactionStatus::Completed,
+                unsigned.message,
+                Utc::now().naive_utc(),
+                TransactionDirection::Outbound,
+                None,
+                None,
+            ),
+        )
+        .await
+    }
+
     async fn generate_coinbase_transaction(
         &mut self,
         reward: MicroTari,
@@ -2343,7 +3725,7 @@ pub struct PendingCoinbaseSpendingKey {
     pub spending_key: PrivateKey,
 }
 
-fn hash_secret_key(key: &PrivateKey) -> Vec<u8> {
+pub(crate) fn hash_secret_key(key: &PrivateKey) -> Vec<u8> {
     HashDigest::new().chain(key.as_bytes()).finalize().to_vec()
 }
 
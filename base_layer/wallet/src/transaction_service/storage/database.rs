@@ -37,14 +37,19 @@ use tari_common_types::{
 };
 use tari_comms::types::CommsPublicKey;
 use tari_core::transactions::{tari_amount::MicroTari, transaction_components::Transaction};
+use tari_p2p::tari_message::TariMessageType;
 
 use crate::transaction_service::{
     error::TransactionStorageError,
+    multisig::{MultisigGroup, MultisigSession},
     storage::{
         models::{
             CompletedTransaction,
             InboundTransaction,
             OutboundTransaction,
+            QueuedTransactionMessage,
+            ScheduledPayment,
+            SendIntent,
             TxCancellationReason,
             WalletTransaction,
         },
@@ -159,6 +164,41 @@ pub trait TransactionBackend: Send + Sync + Clone {
         height: u64,
     ) -> Result<Vec<CompletedTransaction>, TransactionStorageError>;
     fn abandon_coinbase_transaction(&self, tx_id: TxId) -> Result<(), TransactionStorageError>;
+    /// Durably record the intent to send a transaction before it is constructed
+    fn insert_send_intent(&self, intent: SendIntent) -> Result<(), TransactionStorageError>;
+    /// Remove the send intent for the given `TxId`, if it exists
+    fn remove_send_intent(&self, tx_id: TxId) -> Result<(), TransactionStorageError>;
+    /// Fetch all send intents that have not been resolved
+    fn fetch_send_intents(&self) -> Result<Vec<SendIntent>, TransactionStorageError>;
+    /// Add a message to the outbound transaction message queue, replacing any queued message of the same type for the
+    /// same transaction
+    fn upsert_queued_message(&self, message: QueuedTransactionMessage) -> Result<(), TransactionStorageError>;
+    /// Remove the queued message of the given type for a transaction, or all of its queued messages if no type is
+    /// given
+    fn remove_queued_messages(
+        &self,
+        tx_id: TxId,
+        message_type: Option<TariMessageType>,
+    ) -> Result<(), TransactionStorageError>;
+    /// Fetch the queued messages for a transaction, or for all transactions if no `TxId` is given
+    fn fetch_queued_messages(
+        &self,
+        tx_id: Option<TxId>,
+    ) -> Result<Vec<QueuedTransactionMessage>, TransactionStorageError>;
+    /// Insert a scheduled payment or replace the stored state of an existing one
+    fn upsert_scheduled_payment(&self, payment: ScheduledPayment) -> Result<(), TransactionStorageError>;
+    /// Fetch all scheduled payments, ordered by when their next payment falls due
+    fn fetch_scheduled_payments(&self) -> Result<Vec<ScheduledPayment>, TransactionStorageError>;
+    /// Insert a multisig group or replace the stored state of an existing one
+    fn upsert_multisig_group(&self, group: MultisigGroup) -> Result<(), TransactionStorageError>;
+    fn fetch_multisig_group(&self, group_id: u64) -> Result<Option<MultisigGroup>, TransactionStorageError>;
+    /// Fetch all multisig groups, oldest first
+    fn fetch_multisig_groups(&self) -> Result<Vec<MultisigGroup>, TransactionStorageError>;
+    /// Insert a multisig session or replace the stored state of an existing one
+    fn upsert_multisig_session(&self, session: MultisigSession) -> Result<(), TransactionStorageError>;
+    fn fetch_multisig_session(&self, session_id: u64) -> Result<Option<MultisigSession>, TransactionStorageError>;
+    /// Fetch all multisig sessions, oldest first
+    fn fetch_multisig_sessions(&self) -> Result<Vec<MultisigSession>, TransactionStorageError>;
 }
 
 #[derive(Clone, PartialEq)]
@@ -900,6 +940,131 @@ where T: TransactionBackend + 'static
             .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
         Ok(())
     }
+
+    pub async fn add_send_intent(&self, intent: SendIntent) -> Result<(), TransactionStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.insert_send_intent(intent))
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn resolve_send_intent(&self, tx_id: TxId) -> Result<(), TransactionStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.remove_send_intent(tx_id))
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn get_send_intents(&self) -> Result<Vec<SendIntent>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let intents = tokio::task::spawn_blocking(move || db_clone.fetch_send_intents())
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(intents)
+    }
+
+    pub async fn queue_transaction_message(
+        &self,
+        message: QueuedTransactionMessage,
+    ) -> Result<(), TransactionStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.upsert_queued_message(message))
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn remove_queued_transaction_messages(
+        &self,
+        tx_id: TxId,
+        message_type: Option<TariMessageType>,
+    ) -> Result<(), TransactionStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.remove_queued_messages(tx_id, message_type))
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn get_queued_transaction_messages(
+        &self,
+        tx_id: Option<TxId>,
+    ) -> Result<Vec<QueuedTransactionMessage>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let messages = tokio::task::spawn_blocking(move || db_clone.fetch_queued_messages(tx_id))
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(messages)
+    }
+
+    pub async fn upsert_scheduled_payment(&self, payment: ScheduledPayment) -> Result<(), TransactionStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.upsert_scheduled_payment(payment))
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn get_scheduled_payments(&self) -> Result<Vec<ScheduledPayment>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let payments = tokio::task::spawn_blocking(move || db_clone.fetch_scheduled_payments())
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(payments)
+    }
+
+    pub async fn upsert_multisig_group(&self, group: MultisigGroup) -> Result<(), TransactionStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.upsert_multisig_group(group))
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn get_multisig_group(&self, group_id: u64) -> Result<Option<MultisigGroup>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let group = tokio::task::spawn_blocking(move || db_clone.fetch_multisig_group(group_id))
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(group)
+    }
+
+    pub async fn get_multisig_groups(&self) -> Result<Vec<MultisigGroup>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let groups = tokio::task::spawn_blocking(move || db_clone.fetch_multisig_groups())
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(groups)
+    }
+
+    pub async fn upsert_multisig_session(&self, session: MultisigSession) -> Result<(), TransactionStorageError> {
+        let db_clone = self.db.clone();
+        tokio::task::spawn_blocking(move || db_clone.upsert_multisig_session(session))
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(())
+    }
+
+    pub async fn get_multisig_session(
+        &self,
+        session_id: u64,
+    ) -> Result<Option<MultisigSession>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let session = tokio::task::spawn_blocking(move || db_clone.fetch_multisig_session(session_id))
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(session)
+    }
+
+    pub async fn get_multisig_sessions(&self) -> Result<Vec<MultisigSession>, TransactionStorageError> {
+        let db_clone = self.db.clone();
+        let sessions = tokio::task::spawn_blocking(move || db_clone.fetch_multisig_sessions())
+            .await
+            .map_err(|err| TransactionStorageError::BlockingTaskSpawnError(err.to_string()))??;
+        Ok(sessions)
+    }
 }
 
 impl Display for DbKey {
@@ -21,11 +21,14 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    cmp,
     convert::TryFrom,
     fmt::{Display, Error, Formatter},
+    time::Duration,
 };
 
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, Utc};
+use rand::{rngs::OsRng, RngCore};
 use serde::{Deserialize, Serialize};
 use tari_common_types::{
     transaction::{TransactionConversionError, TransactionDirection, TransactionStatus, TxId},
@@ -38,6 +41,7 @@ use tari_core::transactions::{
     ReceiverTransactionProtocol,
     SenderTransactionProtocol,
 };
+use tari_p2p::tari_message::TariMessageType;
 use tari_utilities::hex::Hex;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -324,6 +328,301 @@ impl From<InboundTransaction> for CompletedTransaction {
     }
 }
 
+/// A durable record of the intent to send a transaction. It is written before the transaction is constructed and is
+/// removed once the resulting transaction has been persisted. Any intents that remain on startup belong to sends that
+/// were interrupted and must be reconciled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SendIntent {
+    pub tx_id: TxId,
+    pub destination_public_key: CommsPublicKey,
+    pub amount: MicroTari,
+    pub fee_per_gram: MicroTari,
+    pub timestamp: NaiveDateTime,
+    pub one_sided: bool,
+}
+
+impl SendIntent {
+    pub fn new(
+        tx_id: TxId,
+        destination_public_key: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        timestamp: NaiveDateTime,
+        one_sided: bool,
+    ) -> Self {
+        Self {
+            tx_id,
+            destination_public_key,
+            amount,
+            fee_per_gram,
+            timestamp,
+            one_sided,
+        }
+    }
+}
+
+/// A transaction negotiation message that could not be delivered directly to its destination, usually because the
+/// counterparty is offline. Queued messages are persisted in the wallet database and delivery is retried on a backoff
+/// schedule, in addition to store and forward, until the message is delivered, the counterparty responds or the message
+/// expires.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedTransactionMessage {
+    pub tx_id: TxId,
+    pub message_type: TariMessageType,
+    pub destination_public_key: CommsPublicKey,
+    /// The encoded protobuf message
+    pub message: Vec<u8>,
+    pub attempts: u32,
+    pub next_attempt: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+}
+
+impl QueuedTransactionMessage {
+    pub fn new<T: prost::Message>(
+        tx_id: TxId,
+        message_type: TariMessageType,
+        destination_public_key: CommsPublicKey,
+        message: &T,
+        now: NaiveDateTime,
+        retry_delay: Duration,
+        expiry: Duration,
+    ) -> Self {
+        Self {
+            tx_id,
+            message_type,
+            destination_public_key,
+            message: message.encode_to_vec(),
+            attempts: 0,
+            next_attempt: add_duration(now, retry_delay),
+            expires_at: add_duration(now, expiry),
+        }
+    }
+
+    pub fn is_expired(&self, now: NaiveDateTime) -> bool {
+        self.expires_at <= now
+    }
+
+    pub fn is_due(&self, now: NaiveDateTime) -> bool {
+        self.next_attempt <= now
+    }
+
+    /// Record a delivery attempt and schedule the next one. The delay starts at `initial_delay` and doubles after each
+    /// attempt, up to `max_delay`.
+    pub fn schedule_next_attempt(&mut self, now: NaiveDateTime, initial_delay: Duration, max_delay: Duration) {
+        self.attempts = self.attempts.saturating_add(1);
+        let delay = 1u32
+            .checked_shl(self.attempts - 1)
+            .and_then(|factor| initial_delay.checked_mul(factor))
+            .map_or(max_delay, |delay| cmp::min(delay, max_delay));
+        self.next_attempt = add_duration(now, delay);
+    }
+}
+
+fn add_duration(time: NaiveDateTime, duration: Duration) -> NaiveDateTime {
+    chrono::Duration::from_std(duration)
+        .ok()
+        .and_then(|d| time.checked_add_signed(d))
+        .unwrap_or(chrono::naive::MAX_DATETIME)
+}
+
+/// How a scheduled payment is sent when it falls due
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduledPaymentType {
+    /// An interactive transaction, completed when the recipient responds
+    Standard, // 0
+    OneSided, // 1
+}
+
+impl TryFrom<i32> for ScheduledPaymentType {
+    type Error = TransactionConversionError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ScheduledPaymentType::Standard),
+            1 => Ok(ScheduledPaymentType::OneSided),
+            code => Err(TransactionConversionError { code }),
+        }
+    }
+}
+
+impl Display for ScheduledPaymentType {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            ScheduledPaymentType::Standard => fmt.write_str("Standard"),
+            ScheduledPaymentType::OneSided => fmt.write_str("One-sided"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduledPaymentStatus {
+    Active, // 0
+    /// Every payment in the schedule has been attempted
+    Completed, // 1
+    Cancelled, // 2
+    /// The only payment in the schedule could not be made
+    Failed, // 3
+}
+
+impl TryFrom<i32> for ScheduledPaymentStatus {
+    type Error = TransactionConversionError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ScheduledPaymentStatus::Active),
+            1 => Ok(ScheduledPaymentStatus::Completed),
+            2 => Ok(ScheduledPaymentStatus::Cancelled),
+            3 => Ok(ScheduledPaymentStatus::Failed),
+            code => Err(TransactionConversionError { code }),
+        }
+    }
+}
+
+impl Display for ScheduledPaymentStatus {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            ScheduledPaymentStatus::Active => fmt.write_str("Active"),
+            ScheduledPaymentStatus::Completed => fmt.write_str("Completed"),
+            ScheduledPaymentStatus::Cancelled => fmt.write_str("Cancelled"),
+            ScheduledPaymentStatus::Failed => fmt.write_str("Failed"),
+        }
+    }
+}
+
+/// When the payments of a scheduled payment fall due
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentSchedule {
+    pub next_payment_at: NaiveDateTime,
+    /// The time between payments, or `None` for a single payment
+    pub interval: Option<Duration>,
+    /// The number of payments still to be made, or `None` to repeat until cancelled
+    pub remaining_payments: Option<u32>,
+}
+
+impl PaymentSchedule {
+    pub fn once(payment_at: NaiveDateTime) -> Self {
+        Self {
+            next_payment_at: payment_at,
+            interval: None,
+            remaining_payments: Some(1),
+        }
+    }
+
+    pub fn recurring(first_payment_at: NaiveDateTime, interval: Duration, num_payments: Option<u32>) -> Self {
+        Self {
+            next_payment_at: first_payment_at,
+            interval: Some(interval),
+            remaining_payments: num_payments,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match (self.interval, self.remaining_payments) {
+            (_, Some(0)) => Err("The schedule must include at least one payment".to_string()),
+            (None, Some(1)) => Ok(()),
+            (None, _) => Err("A schedule without an interval can only make a single payment".to_string()),
+            (Some(interval), _) if interval.as_secs() == 0 => {
+                Err("The interval between payments must be at least one second".to_string())
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A payment instruction that the transaction service executes when it falls due, for instance a monthly salary.
+/// The outcome of every payment is published on the transaction event stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledPayment {
+    pub id: u64,
+    pub destination_public_key: CommsPublicKey,
+    pub amount: MicroTari,
+    pub fee_per_gram: MicroTari,
+    pub message: String,
+    pub payment_type: ScheduledPaymentType,
+    pub schedule: PaymentSchedule,
+    pub num_payments_made: u32,
+    pub status: ScheduledPaymentStatus,
+    pub last_tx_id: Option<TxId>,
+    /// Why the last payment failed or is being deferred
+    pub last_error: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl ScheduledPayment {
+    pub fn new(
+        destination_public_key: CommsPublicKey,
+        amount: MicroTari,
+        fee_per_gram: MicroTari,
+        message: String,
+        payment_type: ScheduledPaymentType,
+        schedule: PaymentSchedule,
+    ) -> Self {
+        Self {
+            id: OsRng.next_u64(),
+            destination_public_key,
+            amount,
+            fee_per_gram,
+            message,
+            payment_type,
+            schedule,
+            num_payments_made: 0,
+            status: ScheduledPaymentStatus::Active,
+            last_tx_id: None,
+            last_error: None,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    pub fn is_due(&self, now: NaiveDateTime) -> bool {
+        self.status == ScheduledPaymentStatus::Active && self.schedule.next_payment_at <= now
+    }
+
+    /// Advance the schedule past the payment that is due. This is persisted before the payment is sent, so a payment
+    /// interrupted by a restart is skipped rather than made twice. Payments missed while the wallet was not running are
+    /// not made up: the next payment falls on the first occurrence after `now`.
+    pub fn start_payment(&mut self, now: NaiveDateTime) {
+        if let Some(remaining) = self.schedule.remaining_payments.as_mut() {
+            *remaining = remaining.saturating_sub(1);
+        }
+        match self.schedule.interval {
+            Some(interval) if self.schedule.remaining_payments != Some(0) => {
+                let interval_secs = cmp::max(interval.as_secs(), 1) as i64;
+                let elapsed_secs = cmp::max((now - self.schedule.next_payment_at).num_seconds(), 0);
+                let periods = elapsed_secs / interval_secs + 1;
+                self.schedule.next_payment_at = self
+                    .schedule
+                    .next_payment_at
+                    .checked_add_signed(chrono::Duration::seconds(periods.saturating_mul(interval_secs)))
+                    .unwrap_or(chrono::naive::MAX_DATETIME);
+            },
+            _ => self.status = ScheduledPaymentStatus::Completed,
+        }
+    }
+
+    pub fn record_payment(&mut self, tx_id: TxId) {
+        self.num_payments_made = self.num_payments_made.saturating_add(1);
+        self.last_tx_id = Some(tx_id);
+        self.last_error = None;
+    }
+
+    pub fn record_failure(&mut self, reason: String) {
+        if self.schedule.interval.is_none() {
+            self.status = ScheduledPaymentStatus::Failed;
+        }
+        self.last_error = Some(reason);
+    }
+
+    /// Record why a payment that is due cannot be attempted yet. Returns false if it was already deferred for the same
+    /// reason.
+    pub fn defer(&mut self, reason: String) -> bool {
+        if self.last_error.as_ref() == Some(&reason) {
+            return false;
+        }
+        self.last_error = Some(reason);
+        true
+    }
+}
+
 #[derive(Debug)]
 #[allow(clippy::large_enum_variant)]
 pub enum WalletTransaction {
@@ -352,6 +651,7 @@ pub enum TxCancellationReason {
     TimeLocked,         // 5
     InvalidTransaction, // 6
     AbandonedCoinbase,  // 7
+    FeeBumped,          // 8
 }
 
 impl TryFrom<u32> for TxCancellationReason {
@@ -367,6 +667,7 @@ impl TryFrom<u32> for TxCancellationReason {
             5 => Ok(TxCancellationReason::TimeLocked),
             6 => Ok(TxCancellationReason::InvalidTransaction),
             7 => Ok(TxCancellationReason::AbandonedCoinbase),
+            8 => Ok(TxCancellationReason::FeeBumped),
             code => Err(TransactionConversionError { code: code as i32 }),
         }
     }
@@ -385,7 +686,122 @@ impl Display for TxCancellationReason {
             TimeLocked => "TimeLocked",
             InvalidTransaction => "Invalid Transaction",
             AbandonedCoinbase => "Abandoned Coinbase",
+            FeeBumped => "Fee Bumped",
         };
         fmt.write_str(response)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+    use tari_core::transactions::transaction_protocol::proto::protocol as proto;
+
+    use super::*;
+
+    #[test]
+    fn queued_message_retry_delay_doubles_up_to_the_maximum() {
+        let now = Utc::now().naive_utc();
+        let mut message = QueuedTransactionMessage::new(
+            1u64.into(),
+            TariMessageType::TransactionCancelled,
+            CommsPublicKey::default(),
+            &proto::TransactionCancelledMessage { tx_id: 1 },
+            now,
+            Duration::from_secs(60),
+            Duration::from_secs(3600),
+        );
+        assert!(!message.is_due(now));
+        assert!(message.is_due(now + chrono::Duration::seconds(60)));
+        assert!(!message.is_expired(now));
+        assert!(message.is_expired(now + chrono::Duration::seconds(3600)));
+
+        let expected = [60, 120, 240, 300, 300];
+        for (attempt, delay) in expected.iter().enumerate() {
+            message.schedule_next_attempt(now, Duration::from_secs(60), Duration::from_secs(300));
+            assert_eq!(message.attempts as usize, attempt + 1);
+            assert_eq!(message.next_attempt, now + chrono::Duration::seconds(*delay));
+        }
+
+        message.attempts = 40;
+        message.schedule_next_attempt(now, Duration::from_secs(60), Duration::from_secs(300));
+        assert_eq!(message.next_attempt, now + chrono::Duration::seconds(300));
+    }
+
+    #[test]
+    fn single_scheduled_payment_completes_or_fails() {
+        let now = Utc::now().naive_utc();
+        let new_payment = |schedule| {
+            ScheduledPayment::new(
+                CommsPublicKey::default(),
+                MicroTari::from(1000),
+                MicroTari::from(5),
+                "rent".to_string(),
+                ScheduledPaymentType::Standard,
+                schedule,
+            )
+        };
+        let mut payment = new_payment(PaymentSchedule::once(now + chrono::Duration::seconds(10)));
+        assert!(payment.schedule.validate().is_ok());
+        assert!(!payment.is_due(now));
+        assert!(payment.is_due(now + chrono::Duration::seconds(10)));
+
+        payment.start_payment(now);
+        payment.record_payment(1u64.into());
+        assert_eq!(payment.status, ScheduledPaymentStatus::Completed);
+        assert_eq!(payment.num_payments_made, 1);
+        assert!(!payment.is_due(now + chrono::Duration::days(1)));
+
+        let mut payment = new_payment(PaymentSchedule::once(now));
+        assert!(payment.defer("Offline".to_string()));
+        assert!(!payment.defer("Offline".to_string()));
+        assert_eq!(payment.status, ScheduledPaymentStatus::Active);
+        payment.start_payment(now);
+        payment.record_failure("Not enough funds".to_string());
+        assert_eq!(payment.status, ScheduledPaymentStatus::Failed);
+        assert_eq!(payment.last_error, Some("Not enough funds".to_string()));
+
+        let invalid = PaymentSchedule {
+            next_payment_at: now,
+            interval: None,
+            remaining_payments: None,
+        };
+        assert!(invalid.validate().is_err());
+        assert!(PaymentSchedule::recurring(now, Duration::from_secs(60), Some(0))
+            .validate()
+            .is_err());
+        assert!(PaymentSchedule::recurring(now, Duration::from_millis(10), None)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn recurring_payment_skips_missed_periods() {
+        let start = Utc::now().naive_utc();
+        let day = Duration::from_secs(86400);
+        let mut payment = ScheduledPayment::new(
+            CommsPublicKey::default(),
+            MicroTari::from(1000),
+            MicroTari::from(5),
+            "salary".to_string(),
+            ScheduledPaymentType::OneSided,
+            PaymentSchedule::recurring(start, day, Some(3)),
+        );
+
+        payment.start_payment(start);
+        assert_eq!(payment.schedule.next_payment_at, start + chrono::Duration::days(1));
+        assert_eq!(payment.schedule.remaining_payments, Some(2));
+
+        // The wallet was not running for three and a half days
+        let now = start + chrono::Duration::days(1) + chrono::Duration::hours(84);
+        payment.start_payment(now);
+        assert_eq!(payment.schedule.next_payment_at, start + chrono::Duration::days(5));
+        assert_eq!(payment.status, ScheduledPaymentStatus::Active);
+        payment.record_failure("Not enough funds".to_string());
+        assert_eq!(payment.status, ScheduledPaymentStatus::Active);
+
+        payment.start_payment(start + chrono::Duration::days(5));
+        assert_eq!(payment.schedule.remaining_payments, Some(0));
+        assert_eq!(payment.status, ScheduledPaymentStatus::Completed);
+    }
+}
@@ -25,6 +25,7 @@ use std::{
     convert::{TryFrom, TryInto},
     str::from_utf8,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
 use aes_gcm::{self, Aes256Gcm};
@@ -42,7 +43,11 @@ use tari_common_types::{
     types::{BlockHash, PrivateKey, PublicKey, Signature},
 };
 use tari_comms::types::CommsPublicKey;
-use tari_core::transactions::tari_amount::MicroTari;
+use tari_core::transactions::{
+    tari_amount::MicroTari,
+    transaction_protocol::multisig::{MultisigKeyShare, MultisigPrivateNonces},
+};
+use tari_p2p::tari_message::TariMessageType;
 use tari_utilities::{
     hex::{from_hex, Hex},
     ByteArray,
@@ -51,16 +56,32 @@ use thiserror::Error;
 use tokio::time::Instant;
 
 use crate::{
-    schema::{completed_transactions, inbound_transactions, outbound_transactions},
+    schema::{
+        completed_transactions,
+        inbound_transactions,
+        multisig_groups,
+        multisig_sessions,
+        outbound_transactions,
+        queued_transaction_messages,
+        scheduled_payments,
+        send_intents,
+    },
     storage::sqlite_utilities::wallet_db_connection::WalletDbConnection,
     transaction_service::{
         error::{TransactionKeyError, TransactionStorageError},
+        multisig::{MultisigGroup, MultisigGroupStatus, MultisigRole, MultisigSession, MultisigSessionState},
         storage::{
             database::{DbKey, DbKeyValuePair, DbValue, TransactionBackend, WriteOperation},
             models::{
                 CompletedTransaction,
                 InboundTransaction,
                 OutboundTransaction,
+                PaymentSchedule,
+                QueuedTransactionMessage,
+                ScheduledPayment,
+                ScheduledPaymentStatus,
+                ScheduledPaymentType,
+                SendIntent,
                 TxCancellationReason,
                 WalletTransaction,
             },
@@ -849,6 +870,30 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
             tx.update_encryption(&conn)?;
         }
 
+        let mut queued_messages = QueuedTransactionMessageSql::index(None, &conn)?;
+        for message in &mut queued_messages {
+            message
+                .encrypt(&cipher)
+                .map_err(|_| TransactionStorageError::AeadError("Encryption Error".to_string()))?;
+            message.update_encryption(&conn)?;
+        }
+
+        let mut multisig_groups = MultisigGroupSql::index(&conn)?;
+        for group in &mut multisig_groups {
+            group
+                .encrypt(&cipher)
+                .map_err(|_| TransactionStorageError::AeadError("Encryption Error".to_string()))?;
+            group.update_encryption(&conn)?;
+        }
+
+        let mut multisig_sessions = MultisigSessionSql::index(&conn)?;
+        for session in &mut multisig_sessions {
+            session
+                .encrypt(&cipher)
+                .map_err(|_| TransactionStorageError::AeadError("Encryption Error".to_string()))?;
+            session.update_encryption(&conn)?;
+        }
+
         (*current_cipher) = Some(cipher);
         if start.elapsed().as_millis() > 0 {
             trace!(
@@ -898,6 +943,30 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
             tx.update_encryption(&conn)?;
         }
 
+        let mut queued_messages = QueuedTransactionMessageSql::index(None, &conn)?;
+        for message in &mut queued_messages {
+            message
+                .decrypt(&cipher)
+                .map_err(|_| TransactionStorageError::AeadError("Decryption Error".to_string()))?;
+            message.update_encryption(&conn)?;
+        }
+
+        let mut multisig_groups = MultisigGroupSql::index(&conn)?;
+        for group in &mut multisig_groups {
+            group
+                .decrypt(&cipher)
+                .map_err(|_| TransactionStorageError::AeadError("Decryption Error".to_string()))?;
+            group.update_encryption(&conn)?;
+        }
+
+        let mut multisig_sessions = MultisigSessionSql::index(&conn)?;
+        for session in &mut multisig_sessions {
+            session
+                .decrypt(&cipher)
+                .map_err(|_| TransactionStorageError::AeadError("Decryption Error".to_string()))?;
+            session.update_encryption(&conn)?;
+        }
+
         // Now that all the decryption has been completed we can safely remove the cipher fully
         let _ = (*current_cipher).take();
         if start.elapsed().as_millis() > 0 {
@@ -1290,6 +1359,127 @@ impl TransactionBackend for TransactionServiceSqliteDatabase {
 
         Ok(())
     }
+
+    fn insert_send_intent(&self, intent: SendIntent) -> Result<(), TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        SendIntentSql::from(intent).commit(&conn)?;
+        Ok(())
+    }
+
+    fn remove_send_intent(&self, tx_id: TxId) -> Result<(), TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        SendIntentSql::delete(tx_id, &conn)?;
+        Ok(())
+    }
+
+    fn fetch_send_intents(&self) -> Result<Vec<SendIntent>, TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        SendIntentSql::index(&conn)?
+            .into_iter()
+            .map(SendIntent::try_from)
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    fn upsert_queued_message(&self, message: QueuedTransactionMessage) -> Result<(), TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        let mut message_sql = QueuedTransactionMessageSql::from(message);
+        self.encrypt_if_necessary(&mut message_sql)?;
+        message_sql.commit(&conn)?;
+        Ok(())
+    }
+
+    fn remove_queued_messages(
+        &self,
+        tx_id: TxId,
+        message_type: Option<TariMessageType>,
+    ) -> Result<(), TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        QueuedTransactionMessageSql::delete(tx_id, message_type, &conn)?;
+        Ok(())
+    }
+
+    fn fetch_queued_messages(
+        &self,
+        tx_id: Option<TxId>,
+    ) -> Result<Vec<QueuedTransactionMessage>, TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        QueuedTransactionMessageSql::index(tx_id, &conn)?
+            .into_iter()
+            .map(|mut message_sql| {
+                self.decrypt_if_necessary(&mut message_sql)?;
+                QueuedTransactionMessage::try_from(message_sql)
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    fn upsert_scheduled_payment(&self, payment: ScheduledPayment) -> Result<(), TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        ScheduledPaymentSql::from(payment).commit(&conn)
+    }
+
+    fn fetch_scheduled_payments(&self) -> Result<Vec<ScheduledPayment>, TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        ScheduledPaymentSql::index(&conn)?
+            .into_iter()
+            .map(ScheduledPayment::try_from)
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    fn upsert_multisig_group(&self, group: MultisigGroup) -> Result<(), TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        let mut group_sql = MultisigGroupSql::try_from(group)?;
+        self.encrypt_if_necessary(&mut group_sql)?;
+        group_sql.commit(&conn)
+    }
+
+    fn fetch_multisig_group(&self, group_id: u64) -> Result<Option<MultisigGroup>, TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        MultisigGroupSql::find(group_id, &conn)?
+            .map(|mut group_sql| {
+                self.decrypt_if_necessary(&mut group_sql)?;
+                MultisigGroup::try_from(group_sql)
+            })
+            .transpose()
+    }
+
+    fn fetch_multisig_groups(&self) -> Result<Vec<MultisigGroup>, TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        MultisigGroupSql::index(&conn)?
+            .into_iter()
+            .map(|mut group_sql| {
+                self.decrypt_if_necessary(&mut group_sql)?;
+                MultisigGroup::try_from(group_sql)
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    fn upsert_multisig_session(&self, session: MultisigSession) -> Result<(), TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        let mut session_sql = MultisigSessionSql::try_from(session)?;
+        self.encrypt_if_necessary(&mut session_sql)?;
+        session_sql.commit(&conn)
+    }
+
+    fn fetch_multisig_session(&self, session_id: u64) -> Result<Option<MultisigSession>, TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        MultisigSessionSql::find(session_id, &conn)?
+            .map(|mut session_sql| {
+                self.decrypt_if_necessary(&mut session_sql)?;
+                MultisigSession::try_from(session_sql)
+            })
+            .transpose()
+    }
+
+    fn fetch_multisig_sessions(&self) -> Result<Vec<MultisigSession>, TransactionStorageError> {
+        let conn = self.database_connection.get_pooled_connection()?;
+        MultisigSessionSql::index(&conn)?
+            .into_iter()
+            .map(|mut session_sql| {
+                self.decrypt_if_necessary(&mut session_sql)?;
+                MultisigSession::try_from(session_sql)
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -2122,6 +2312,526 @@ impl UnconfirmedTransactionInfoSql {
     }
 }
 
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "send_intents"]
+struct SendIntentSql {
+    tx_id: i64,
+    destination_public_key: Vec<u8>,
+    amount: i64,
+    fee_per_gram: i64,
+    timestamp: NaiveDateTime,
+    one_sided: i32,
+}
+
+impl SendIntentSql {
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::insert_into(send_intents::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<SendIntentSql>, TransactionStorageError> {
+        Ok(send_intents::table
+            .order_by((send_intents::timestamp.asc(), send_intents::tx_id.asc()))
+            .load::<SendIntentSql>(conn)?)
+    }
+
+    pub fn delete(tx_id: TxId, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::delete(send_intents::table.filter(send_intents::tx_id.eq(tx_id.as_u64() as i64))).execute(conn)?;
+        Ok(())
+    }
+}
+
+impl From<SendIntent> for SendIntentSql {
+    fn from(i: SendIntent) -> Self {
+        Self {
+            tx_id: i.tx_id.as_u64() as i64,
+            destination_public_key: i.destination_public_key.to_vec(),
+            amount: u64::from(i.amount) as i64,
+            fee_per_gram: u64::from(i.fee_per_gram) as i64,
+            timestamp: i.timestamp,
+            one_sided: i.one_sided as i32,
+        }
+    }
+}
+
+impl TryFrom<SendIntentSql> for SendIntent {
+    type Error = TransactionStorageError;
+
+    fn try_from(i: SendIntentSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            tx_id: (i.tx_id as u64).into(),
+            destination_public_key: PublicKey::from_vec(&i.destination_public_key)
+                .map_err(TransactionKeyError::Destination)?,
+            amount: MicroTari::from(i.amount as u64),
+            fee_per_gram: MicroTari::from(i.fee_per_gram as u64),
+            timestamp: i.timestamp,
+            one_sided: i.one_sided != 0,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "queued_transaction_messages"]
+struct QueuedTransactionMessageSql {
+    tx_id: i64,
+    message_type: i32,
+    destination_public_key: Vec<u8>,
+    message: Vec<u8>,
+    attempts: i32,
+    next_attempt: NaiveDateTime,
+    expires_at: NaiveDateTime,
+}
+
+impl QueuedTransactionMessageSql {
+    /// Insert the message, replacing any queued message of the same type for the same transaction
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::replace_into(queued_transaction_messages::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn index(
+        tx_id: Option<TxId>,
+        conn: &SqliteConnection,
+    ) -> Result<Vec<QueuedTransactionMessageSql>, TransactionStorageError> {
+        let mut query = queued_transaction_messages::table.into_boxed();
+        if let Some(tx_id) = tx_id {
+            query = query.filter(queued_transaction_messages::tx_id.eq(tx_id.as_u64() as i64));
+        }
+        Ok(query
+            .order_by((
+                queued_transaction_messages::next_attempt.asc(),
+                queued_transaction_messages::tx_id.asc(),
+                queued_transaction_messages::message_type.asc(),
+            ))
+            .load::<QueuedTransactionMessageSql>(conn)?)
+    }
+
+    pub fn delete(
+        tx_id: TxId,
+        message_type: Option<TariMessageType>,
+        conn: &SqliteConnection,
+    ) -> Result<(), TransactionStorageError> {
+        let messages =
+            queued_transaction_messages::table.filter(queued_transaction_messages::tx_id.eq(tx_id.as_u64() as i64));
+        match message_type {
+            Some(message_type) => {
+                diesel::delete(messages.filter(queued_transaction_messages::message_type.eq(message_type as i32)))
+                    .execute(conn)?
+            },
+            None => diesel::delete(messages).execute(conn)?,
+        };
+        Ok(())
+    }
+
+    pub fn update_encryption(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::update(
+            queued_transaction_messages::table
+                .filter(queued_transaction_messages::tx_id.eq(self.tx_id))
+                .filter(queued_transaction_messages::message_type.eq(self.message_type)),
+        )
+        .set(queued_transaction_messages::message.eq(self.message.clone()))
+        .execute(conn)
+        .num_rows_affected_or_not_found(1)?;
+        Ok(())
+    }
+}
+
+impl Encryptable<Aes256Gcm> for QueuedTransactionMessageSql {
+    fn encrypt(&mut self, cipher: &Aes256Gcm) -> Result<(), String> {
+        self.message = encrypt_bytes_integral_nonce(cipher, self.message.clone())?;
+        Ok(())
+    }
+
+    fn decrypt(&mut self, cipher: &Aes256Gcm) -> Result<(), String> {
+        self.message = decrypt_bytes_integral_nonce(cipher, self.message.clone())?;
+        Ok(())
+    }
+}
+
+impl From<QueuedTransactionMessage> for QueuedTransactionMessageSql {
+    fn from(m: QueuedTransactionMessage) -> Self {
+        Self {
+            tx_id: m.tx_id.as_u64() as i64,
+            message_type: m.message_type as i32,
+            destination_public_key: m.destination_public_key.to_vec(),
+            message: m.message,
+            attempts: m.attempts as i32,
+            next_attempt: m.next_attempt,
+            expires_at: m.expires_at,
+        }
+    }
+}
+
+impl TryFrom<QueuedTransactionMessageSql> for QueuedTransactionMessage {
+    type Error = TransactionStorageError;
+
+    fn try_from(m: QueuedTransactionMessageSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            tx_id: (m.tx_id as u64).into(),
+            message_type: TariMessageType::from_i32(m.message_type).ok_or_else(|| {
+                TransactionStorageError::UnexpectedResult(format!("Invalid queued message type {}", m.message_type))
+            })?,
+            destination_public_key: PublicKey::from_vec(&m.destination_public_key)
+                .map_err(TransactionKeyError::Destination)?,
+            message: m.message,
+            attempts: m.attempts as u32,
+            next_attempt: m.next_attempt,
+            expires_at: m.expires_at,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "scheduled_payments"]
+struct ScheduledPaymentSql {
+    id: i64,
+    destination_public_key: Vec<u8>,
+    amount: i64,
+    fee_per_gram: i64,
+    message: String,
+    payment_type: i32,
+    next_payment_at: NaiveDateTime,
+    interval_seconds: Option<i64>,
+    remaining_payments: Option<i32>,
+    num_payments_made: i32,
+    status: i32,
+    last_tx_id: Option<i64>,
+    last_error: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl ScheduledPaymentSql {
+    /// Insert the payment, replacing the stored state of a payment with the same id
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::replace_into(scheduled_payments::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<ScheduledPaymentSql>, TransactionStorageError> {
+        Ok(scheduled_payments::table
+            .order_by((scheduled_payments::next_payment_at.asc(), scheduled_payments::id.asc()))
+            .load::<ScheduledPaymentSql>(conn)?)
+    }
+}
+
+impl From<ScheduledPayment> for ScheduledPaymentSql {
+    fn from(p: ScheduledPayment) -> Self {
+        Self {
+            id: p.id as i64,
+            destination_public_key: p.destination_public_key.to_vec(),
+            amount: u64::from(p.amount) as i64,
+            fee_per_gram: u64::from(p.fee_per_gram) as i64,
+            message: p.message,
+            payment_type: p.payment_type as i32,
+            next_payment_at: p.schedule.next_payment_at,
+            interval_seconds: p.schedule.interval.map(|i| i.as_secs() as i64),
+            remaining_payments: p.schedule.remaining_payments.map(|r| r as i32),
+            num_payments_made: p.num_payments_made as i32,
+            status: p.status as i32,
+            last_tx_id: p.last_tx_id.map(|tx_id| tx_id.as_u64() as i64),
+            last_error: p.last_error,
+            created_at: p.created_at,
+        }
+    }
+}
+
+impl TryFrom<ScheduledPaymentSql> for ScheduledPayment {
+    type Error = TransactionStorageError;
+
+    fn try_from(p: ScheduledPaymentSql) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: p.id as u64,
+            destination_public_key: PublicKey::from_vec(&p.destination_public_key)
+                .map_err(TransactionKeyError::Destination)?,
+            amount: MicroTari::from(p.amount as u64),
+            fee_per_gram: MicroTari::from(p.fee_per_gram as u64),
+            message: p.message,
+            payment_type: ScheduledPaymentType::try_from(p.payment_type)?,
+            schedule: PaymentSchedule {
+                next_payment_at: p.next_payment_at,
+                interval: p.interval_seconds.map(|secs| Duration::from_secs(secs as u64)),
+                remaining_payments: p.remaining_payments.map(|r| r as u32),
+            },
+            num_payments_made: p.num_payments_made as u32,
+            status: ScheduledPaymentStatus::try_from(p.status)?,
+            last_tx_id: p.last_tx_id.map(|tx_id| (tx_id as u64).into()),
+            last_error: p.last_error,
+            created_at: p.created_at,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "multisig_groups"]
+struct MultisigGroupSql {
+    group_id: i64,
+    threshold: i32,
+    participants: Vec<u8>,
+    share_index: i32,
+    share_commitments: Vec<u8>,
+    output: String,
+    value: i64,
+    /// The key share followed by the script private key
+    secrets: Vec<u8>,
+    status: i32,
+    created_at: NaiveDateTime,
+}
+
+impl MultisigGroupSql {
+    /// Insert the group, replacing the stored state of a group with the same id
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::replace_into(multisig_groups::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn find(group_id: u64, conn: &SqliteConnection) -> Result<Option<MultisigGroupSql>, TransactionStorageError> {
+        Ok(multisig_groups::table
+            .filter(multisig_groups::group_id.eq(group_id as i64))
+            .first::<MultisigGroupSql>(conn)
+            .optional()?)
+    }
+
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<MultisigGroupSql>, TransactionStorageError> {
+        Ok(multisig_groups::table
+            .order_by((multisig_groups::created_at.asc(), multisig_groups::group_id.asc()))
+            .load::<MultisigGroupSql>(conn)?)
+    }
+
+    pub fn update_encryption(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::update(multisig_groups::table.filter(multisig_groups::group_id.eq(self.group_id)))
+            .set(multisig_groups::secrets.eq(self.secrets.clone()))
+            .execute(conn)
+            .num_rows_affected_or_not_found(1)?;
+        Ok(())
+    }
+}
+
+impl Encryptable<Aes256Gcm> for MultisigGroupSql {
+    fn encrypt(&mut self, cipher: &Aes256Gcm) -> Result<(), String> {
+        self.secrets = encrypt_bytes_integral_nonce(cipher, self.secrets.clone())?;
+        Ok(())
+    }
+
+    fn decrypt(&mut self, cipher: &Aes256Gcm) -> Result<(), String> {
+        self.secrets = decrypt_bytes_integral_nonce(cipher, self.secrets.clone())?;
+        Ok(())
+    }
+}
+
+impl TryFrom<MultisigGroup> for MultisigGroupSql {
+    type Error = TransactionStorageError;
+
+    fn try_from(g: MultisigGroup) -> Result<Self, Self::Error> {
+        let mut secrets = g.key_share.secret.to_vec();
+        secrets.extend_from_slice(g.script_private_key.as_bytes());
+        Ok(Self {
+            group_id: g.group_id as i64,
+            threshold: g.threshold as i32,
+            participants: join_keys(&g.participants),
+            share_index: g.key_share.index as i32,
+            share_commitments: join_keys(&g.share_commitments),
+            output: serde_json::to_string(&g.output)?,
+            value: u64::from(g.value) as i64,
+            secrets,
+            status: g.status as i32,
+            created_at: g.created_at,
+        })
+    }
+}
+
+impl TryFrom<MultisigGroupSql> for MultisigGroup {
+    type Error = TransactionStorageError;
+
+    fn try_from(g: MultisigGroupSql) -> Result<Self, Self::Error> {
+        let secrets = split_keys::<PrivateKey>(&g.secrets)?;
+        if secrets.len() != 2 {
+            return Err(TransactionStorageError::UnexpectedResult(format!(
+                "Invalid secrets for multisig group {}",
+                g.group_id
+            )));
+        }
+        Ok(Self {
+            group_id: g.group_id as u64,
+            threshold: g.threshold as u32,
+            participants: split_keys(&g.participants)?,
+            key_share: MultisigKeyShare {
+                index: g.share_index as u32,
+                secret: secrets[0].clone(),
+            },
+            share_commitments: split_keys(&g.share_commitments)?,
+            output: serde_json::from_str(&g.output)?,
+            value: MicroTari::from(g.value as u64),
+            script_private_key: secrets[1].clone(),
+            status: MultisigGroupStatus::try_from(g.status)?,
+            created_at: g.created_at,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Insertable, PartialEq)]
+#[table_name = "multisig_sessions"]
+struct MultisigSessionSql {
+    session_id: i64,
+    group_id: i64,
+    role: i32,
+    proposer_public_key: Vec<u8>,
+    destination_public_key: Vec<u8>,
+    amount: i64,
+    fee: i64,
+    lock_height: i64,
+    message: String,
+    state: i32,
+    /// The kernel, script and script value nonces
+    private_nonces: Option<Vec<u8>>,
+    negotiation: String,
+    tx_id: Option<i64>,
+    failure_reason: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl MultisigSessionSql {
+    /// Insert the session, replacing the stored state of a session with the same id
+    pub fn commit(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::replace_into(multisig_sessions::table)
+            .values(self.clone())
+            .execute(conn)?;
+        Ok(())
+    }
+
+    pub fn find(
+        session_id: u64,
+        conn: &SqliteConnection,
+    ) -> Result<Option<MultisigSessionSql>, TransactionStorageError> {
+        Ok(multisig_sessions::table
+            .filter(multisig_sessions::session_id.eq(session_id as i64))
+            .first::<MultisigSessionSql>(conn)
+            .optional()?)
+    }
+
+    pub fn index(conn: &SqliteConnection) -> Result<Vec<MultisigSessionSql>, TransactionStorageError> {
+        Ok(multisig_sessions::table
+            .order_by((multisig_sessions::created_at.asc(), multisig_sessions::session_id.asc()))
+            .load::<MultisigSessionSql>(conn)?)
+    }
+
+    pub fn update_encryption(&self, conn: &SqliteConnection) -> Result<(), TransactionStorageError> {
+        diesel::update(multisig_sessions::table.filter(multisig_sessions::session_id.eq(self.session_id)))
+            .set(multisig_sessions::private_nonces.eq(self.private_nonces.clone()))
+            .execute(conn)
+            .num_rows_affected_or_not_found(1)?;
+        Ok(())
+    }
+}
+
+impl Encryptable<Aes256Gcm> for MultisigSessionSql {
+    fn encrypt(&mut self, cipher: &Aes256Gcm) -> Result<(), String> {
+        if let Some(nonces) = self.private_nonces.take() {
+            self.private_nonces = Some(encrypt_bytes_integral_nonce(cipher, nonces)?);
+        }
+        Ok(())
+    }
+
+    fn decrypt(&mut self, cipher: &Aes256Gcm) -> Result<(), String> {
+        if let Some(nonces) = self.private_nonces.take() {
+            self.private_nonces = Some(decrypt_bytes_integral_nonce(cipher, nonces)?);
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<MultisigSession> for MultisigSessionSql {
+    type Error = TransactionStorageError;
+
+    fn try_from(s: MultisigSession) -> Result<Self, Self::Error> {
+        Ok(Self {
+            session_id: s.session_id as i64,
+            group_id: s.group_id as i64,
+            role: s.role as i32,
+            proposer_public_key: s.proposer.to_vec(),
+            destination_public_key: s.destination_public_key.to_vec(),
+            amount: u64::from(s.amount) as i64,
+            fee: u64::from(s.fee) as i64,
+            lock_height: s.lock_height as i64,
+            message: s.message,
+            state: s.state as i32,
+            private_nonces: s.private_nonces.map(|n| {
+                join_keys(&[
+                    n.kernel_nonce.clone(),
+                    n.script_nonce.clone(),
+                    n.script_value_nonce.clone(),
+                ])
+            }),
+            negotiation: serde_json::to_string(&s.negotiation)?,
+            tx_id: s.tx_id.map(|tx_id| tx_id.as_u64() as i64),
+            failure_reason: s.failure_reason,
+            created_at: s.created_at,
+        })
+    }
+}
+
+impl TryFrom<MultisigSessionSql> for MultisigSession {
+    type Error = TransactionStorageError;
+
+    fn try_from(s: MultisigSessionSql) -> Result<Self, Self::Error> {
+        let private_nonces = match s.private_nonces {
+            Some(bytes) => match split_keys::<PrivateKey>(&bytes)?.as_slice() {
+                [kernel_nonce, script_nonce, script_value_nonce] => Some(MultisigPrivateNonces {
+                    kernel_nonce: kernel_nonce.clone(),
+                    script_nonce: script_nonce.clone(),
+                    script_value_nonce: script_value_nonce.clone(),
+                }),
+                _ => {
+                    return Err(TransactionStorageError::UnexpectedResult(format!(
+                        "Invalid nonces for multisig session {}",
+                        s.session_id
+                    )))
+                },
+            },
+            None => None,
+        };
+        Ok(Self {
+            session_id: s.session_id as u64,
+            group_id: s.group_id as u64,
+            role: MultisigRole::try_from(s.role)?,
+            proposer: PublicKey::from_vec(&s.proposer_public_key).map_err(TransactionKeyError::Source)?,
+            destination_public_key: PublicKey::from_vec(&s.destination_public_key)
+                .map_err(TransactionKeyError::Destination)?,
+            amount: MicroTari::from(s.amount as u64),
+            fee: MicroTari::from(s.fee as u64),
+            lock_height: s.lock_height as u64,
+            message: s.message,
+            state: MultisigSessionState::try_from(s.state)?,
+            private_nonces,
+            negotiation: serde_json::from_str(&s.negotiation)?,
+            tx_id: s.tx_id.map(|tx_id| (tx_id as u64).into()),
+            failure_reason: s.failure_reason,
+            created_at: s.created_at,
+        })
+    }
+}
+
+/// Concatenate 32 byte keys into a single column value
+fn join_keys<K: ByteArray>(keys: &[K]) -> Vec<u8> {
+    keys.iter().flat_map(|k| k.as_bytes().to_vec()).collect()
+}
+
+fn split_keys<K: ByteArray>(bytes: &[u8]) -> Result<Vec<K>, TransactionStorageError> {
+    if bytes.len() % 32 != 0 {
+        return Err(TransactionStorageError::UnexpectedResult(
+            "Key column length is not a multiple of 32".to_string(),
+        ));
+    }
+    Ok(bytes.chunks(32).map(K::from_bytes).collect::<Result<Vec<_>, _>>()?)
+}
+
 #[cfg(test)]
 mod test {
     use std::{convert::TryFrom, time::Duration};
@@ -2143,14 +2853,19 @@ mod test {
         transactions::{
             tari_amount::MicroTari,
             test_helpers::{create_unblinded_output, TestParams},
-            transaction_components::{OutputFeatures, Transaction},
-            transaction_protocol::sender::TransactionSenderMessage,
+            transaction_components::{OutputFeatures, Transaction, TransactionOutput},
+            transaction_protocol::{
+                multisig::split_secret,
+                proto::protocol as proto,
+                sender::TransactionSenderMessage,
+            },
             CryptoFactories,
             ReceiverTransactionProtocol,
             SenderTransactionProtocol,
         },
     };
     use tari_crypto::keys::{PublicKey as PublicKeyTrait, SecretKey as SecretKeyTrait};
+    use tari_p2p::tari_message::TariMessageType;
     use tari_script::{script, ExecutionStack, TariScript};
     use tari_test_utils::random::string;
     use tempfile::tempdir;
@@ -2158,15 +2873,28 @@ mod test {
     use crate::{
         storage::sqlite_utilities::wallet_db_connection::WalletDbConnection,
         test_utils::create_consensus_constants,
-        transaction_service::storage::{
-            database::{DbKey, TransactionBackend},
-            models::{CompletedTransaction, InboundTransaction, OutboundTransaction, TxCancellationReason},
-            sqlite_db::{
-                CompletedTransactionSql,
-                InboundTransactionSenderInfo,
-                InboundTransactionSql,
-                OutboundTransactionSql,
-                TransactionServiceSqliteDatabase,
+        transaction_service::{
+            multisig::{MultisigGroup, MultisigGroupStatus, MultisigSession},
+            storage::{
+                database::{DbKey, TransactionBackend},
+                models::{
+                    CompletedTransaction,
+                    InboundTransaction,
+                    OutboundTransaction,
+                    PaymentSchedule,
+                    QueuedTransactionMessage,
+                    ScheduledPayment,
+                    ScheduledPaymentType,
+                    SendIntent,
+                    TxCancellationReason,
+                },
+                sqlite_db::{
+                    CompletedTransactionSql,
+                    InboundTransactionSenderInfo,
+                    InboundTransactionSql,
+                    OutboundTransactionSql,
+                    TransactionServiceSqliteDatabase,
+                },
             },
         },
         util::encryption::Encryptable,
@@ -2899,4 +3627,242 @@ mod test {
         assert_eq!(info_list.len(), 941);
         assert_eq!(info_list, info_list_reference);
     }
+
+    #[test]
+    fn test_send_intents() {
+        let db_name = format!("{}.sqlite3", string(8).as_str());
+        let temp_dir = tempdir().unwrap();
+        let db_folder = temp_dir.path().to_str().unwrap().to_string();
+        let db_path = format!("{}{}", db_folder, db_name);
+
+        embed_migrations!("./migrations");
+        let mut pool = SqliteConnectionPool::new(db_path.clone(), 1, true, true, Duration::from_secs(60));
+        pool.create_pool()
+            .unwrap_or_else(|_| panic!("Error connecting to {}", db_path));
+        {
+            let conn = pool
+                .get_pooled_connection()
+                .unwrap_or_else(|_| panic!("Error connecting to {}", db_path));
+            embedded_migrations::run_with_output(&conn, &mut std::io::stdout()).expect("Migration failed");
+        }
+
+        let db = TransactionServiceSqliteDatabase::new(WalletDbConnection::new(pool, None), None);
+        assert!(db.fetch_send_intents().unwrap().is_empty());
+
+        let intents = (1u64..=3)
+            .map(|i| {
+                SendIntent::new(
+                    i.into(),
+                    PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+                    MicroTari::from(1000 * i),
+                    MicroTari::from(5),
+                    Utc::now().naive_utc(),
+                    i == 2,
+                )
+            })
+            .collect::<Vec<_>>();
+        for intent in &intents {
+            db.insert_send_intent(intent.clone()).unwrap();
+        }
+        assert!(db.insert_send_intent(intents[0].clone()).is_err());
+        assert_eq!(db.fetch_send_intents().unwrap(), intents);
+
+        db.remove_send_intent(intents[1].tx_id).unwrap();
+        // Removing an intent that does not exist is not an error
+        db.remove_send_intent(intents[1].tx_id).unwrap();
+        let remaining = db.fetch_send_intents().unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.iter().all(|i| i.tx_id != intents[1].tx_id));
+    }
+
+    #[test]
+    fn test_queued_transaction_messages() {
+        let db_name = format!("{}.sqlite3", string(8).as_str());
+        let temp_dir = tempdir().unwrap();
+        let db_folder = temp_dir.path().to_str().unwrap().to_string();
+        let db_path = format!("{}{}", db_folder, db_name);
+
+        embed_migrations!("./migrations");
+        let mut pool = SqliteConnectionPool::new(db_path.clone(), 1, true, true, Duration::from_secs(60));
+        pool.create_pool()
+            .unwrap_or_else(|_| panic!("Error connecting to {}", db_path));
+        {
+            let conn = pool
+                .get_pooled_connection()
+                .unwrap_or_else(|_| panic!("Error connecting to {}", db_path));
+            embedded_migrations::run_with_output(&conn, &mut std::io::stdout()).expect("Migration failed");
+        }
+
+        let key = GenericArray::from_slice(b"an example very very secret key.");
+        let cipher = Aes256Gcm::new(key);
+        let db = TransactionServiceSqliteDatabase::new(WalletDbConnection::new(pool, None), None);
+        assert!(db.fetch_queued_messages(None).unwrap().is_empty());
+
+        let now = Utc::now().naive_utc();
+        let destination_public_key = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        let message = |tx_id: u64, message_type| {
+            QueuedTransactionMessage::new(
+                tx_id.into(),
+                message_type,
+                destination_public_key.clone(),
+                &proto::TransactionCancelledMessage { tx_id },
+                now,
+                Duration::from_secs(tx_id),
+                Duration::from_secs(3600),
+            )
+        };
+        let mut sender_message = message(1, TariMessageType::SenderPartialTransaction);
+        let finalized_message = message(1, TariMessageType::TransactionFinalized);
+        let reply_message = message(2, TariMessageType::ReceiverPartialTransactionReply);
+        db.upsert_queued_message(reply_message.clone()).unwrap();
+        db.upsert_queued_message(sender_message.clone()).unwrap();
+        db.upsert_queued_message(finalized_message.clone()).unwrap();
+        assert_eq!(db.fetch_queued_messages(None).unwrap(), vec![
+            sender_message.clone(),
+            finalized_message.clone(),
+            reply_message.clone()
+        ]);
+
+        // Queueing the same message type for the same transaction replaces the previous entry
+        sender_message.schedule_next_attempt(now, Duration::from_secs(10), Duration::from_secs(60));
+        db.upsert_queued_message(sender_message.clone()).unwrap();
+        assert_eq!(db.fetch_queued_messages(Some(1u64.into())).unwrap(), vec![
+            finalized_message.clone(),
+            sender_message.clone()
+        ]);
+
+        db.apply_encryption(cipher.clone()).unwrap();
+        assert_eq!(db.fetch_queued_messages(Some(2u64.into())).unwrap(), vec![
+            reply_message.clone()
+        ]);
+        db.remove_encryption().unwrap();
+
+        db.remove_queued_messages(1u64.into(), Some(TariMessageType::TransactionFinalized))
+            .unwrap();
+        assert_eq!(db.fetch_queued_messages(Some(1u64.into())).unwrap(), vec![
+            sender_message
+        ]);
+        db.remove_queued_messages(1u64.into(), None).unwrap();
+        assert_eq!(db.fetch_queued_messages(None).unwrap(), vec![reply_message]);
+    }
+
+    #[test]
+    fn test_scheduled_payments() {
+        let db_name = format!("{}.sqlite3", string(8).as_str());
+        let temp_dir = tempdir().unwrap();
+        let db_folder = temp_dir.path().to_str().unwrap().to_string();
+        let db_path = format!("{}{}", db_folder, db_name);
+
+        embed_migrations!("./migrations");
+        let mut pool = SqliteConnectionPool::new(db_path.clone(), 1, true, true, Duration::from_secs(60));
+        pool.create_pool()
+            .unwrap_or_else(|_| panic!("Error connecting to {}", db_path));
+        {
+            let conn = pool
+                .get_pooled_connection()
+                .unwrap_or_else(|_| panic!("Error connecting to {}", db_path));
+            embedded_migrations::run_with_output(&conn, &mut std::io::stdout()).expect("Migration failed");
+        }
+
+        let db = TransactionServiceSqliteDatabase::new(WalletDbConnection::new(pool, None), None);
+        assert!(db.fetch_scheduled_payments().unwrap().is_empty());
+
+        let now = Utc::now().naive_utc();
+        let destination_public_key = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+        let mut salary = ScheduledPayment::new(
+            destination_public_key.clone(),
+            MicroTari::from(50_000),
+            MicroTari::from(5),
+            "Salary".to_string(),
+            ScheduledPaymentType::OneSided,
+            PaymentSchedule::recurring(now, Duration::from_secs(86400), None),
+        );
+        let rent = ScheduledPayment::new(
+            destination_public_key,
+            MicroTari::from(10_000),
+            MicroTari::from(5),
+            "Rent".to_string(),
+            ScheduledPaymentType::Standard,
+            PaymentSchedule::once(now + chrono::Duration::hours(1)),
+        );
+        db.upsert_scheduled_payment(rent.clone()).unwrap();
+        db.upsert_scheduled_payment(salary.clone()).unwrap();
+        assert_eq!(db.fetch_scheduled_payments().unwrap(), vec![
+            salary.clone(),
+            rent.clone()
+        ]);
+
+        salary.start_payment(now);
+        salary.record_payment(7u64.into());
+        db.upsert_scheduled_payment(salary.clone()).unwrap();
+        assert_eq!(db.fetch_scheduled_payments().unwrap(), vec![rent, salary]);
+    }
+
+    #[test]
+    fn test_multisig_groups_and_sessions() {
+        let db_name = format!("{}.sqlite3", string(8).as_str());
+        let temp_dir = tempdir().unwrap();
+        let db_folder = temp_dir.path().to_str().unwrap().to_string();
+        let db_path = format!("{}{}", db_folder, db_name);
+
+        embed_migrations!("./migrations");
+        let mut pool = SqliteConnectionPool::new(db_path.clone(), 1, true, true, Duration::from_secs(60));
+        pool.create_pool()
+            .unwrap_or_else(|_| panic!("Error connecting to {}", db_path));
+        {
+            let conn = pool
+                .get_pooled_connection()
+                .unwrap_or_else(|_| panic!("Error connecting to {}", db_path));
+            embedded_migrations::run_with_output(&conn, &mut std::io::stdout()).expect("Migration failed");
+        }
+
+        let db = TransactionServiceSqliteDatabase::new(WalletDbConnection::new(pool, None), None);
+        assert!(db.fetch_multisig_groups().unwrap().is_empty());
+        assert!(db.fetch_multisig_session(1).unwrap().is_none());
+
+        let participants = (0..3)
+            .map(|_| PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)))
+            .collect::<Vec<_>>();
+        let (mut shares, commitments) = split_secret(&PrivateKey::random(&mut OsRng), 2, 3).unwrap();
+        let mut group = MultisigGroup::new(
+            2,
+            participants.clone(),
+            shares.remove(1),
+            commitments,
+            TransactionOutput::default(),
+            MicroTari::from(100_000),
+            PrivateKey::random(&mut OsRng),
+        );
+        db.upsert_multisig_group(group.clone()).unwrap();
+        assert_eq!(db.fetch_multisig_group(group.group_id).unwrap(), Some(group.clone()));
+
+        let mut session = MultisigSession::propose(
+            &group,
+            participants[1].clone(),
+            participants[0].clone(),
+            MicroTari::from(100),
+            0,
+            "Group payment".to_string(),
+        )
+        .unwrap();
+        db.upsert_multisig_session(session.clone()).unwrap();
+        assert_eq!(db.fetch_multisig_sessions().unwrap(), vec![session.clone()]);
+
+        let key = GenericArray::from_slice(b"an example very very secret key.");
+        let cipher = Aes256Gcm::new(key);
+        db.apply_encryption(cipher).unwrap();
+        assert_eq!(db.fetch_multisig_groups().unwrap(), vec![group.clone()]);
+        assert_eq!(
+            db.fetch_multisig_session(session.session_id).unwrap(),
+            Some(session.clone())
+        );
+
+        session.complete(Some(7u64.into()));
+        db.upsert_multisig_session(session.clone()).unwrap();
+        group.status = MultisigGroupStatus::Spent;
+        db.upsert_multisig_group(group.clone()).unwrap();
+        db.remove_encryption().unwrap();
+        assert_eq!(db.fetch_multisig_groups().unwrap(), vec![group]);
+        assert_eq!(db.fetch_multisig_sessions().unwrap(), vec![session]);
+    }
 }
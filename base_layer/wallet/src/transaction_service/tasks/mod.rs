@@ -22,6 +22,8 @@
 
 pub mod check_faux_transaction_status;
 pub mod send_finalized_transaction;
+pub mod send_multisig_message;
+pub mod send_queued_transaction_message;
 pub mod send_transaction_cancelled;
 pub mod send_transaction_reply;
 pub mod wait_on_dial;
@@ -41,6 +41,8 @@ use crate::transaction_service::{
 
 const LOG_TARGET: &str = "wallet::transaction_service::tasks::send_finalized_transaction";
 
+/// Send the finalized transaction to the recipient as per the routing mechanism. Returns true if the message was
+/// delivered directly to the recipient.
 pub async fn send_finalized_transaction_message(
     tx_id: TxId,
     transaction: Transaction,
@@ -48,7 +50,7 @@ pub async fn send_finalized_transaction_message(
     mut outbound_message_service: OutboundMessageRequester,
     direct_send_timeout: Duration,
     transaction_routing_mechanism: TransactionRoutingMechanism,
-) -> Result<(), TransactionServiceError> {
+) -> Result<bool, TransactionServiceError> {
     match transaction_routing_mechanism {
         TransactionRoutingMechanism::DirectOnly | TransactionRoutingMechanism::DirectAndStoreAndForward => {
             send_finalized_transaction_message_direct(
@@ -59,7 +61,7 @@ pub async fn send_finalized_transaction_message(
                 direct_send_timeout,
                 transaction_routing_mechanism,
             )
-            .await?;
+            .await
         },
         TransactionRoutingMechanism::StoreAndForwardOnly => {
             let finalized_transaction_message = proto::TransactionFinalizedMessage {
@@ -81,10 +83,9 @@ pub async fn send_finalized_transaction_message(
             if !store_and_forward_send_result {
                 return Err(TransactionServiceError::OutboundSendFailure);
             }
+            Ok(false)
         },
-    };
-
-    Ok(())
+    }
 }
 
 pub async fn send_finalized_transaction_message_direct(
@@ -94,7 +95,7 @@ pub async fn send_finalized_transaction_message_direct(
     mut outbound_message_service: OutboundMessageRequester,
     direct_send_timeout: Duration,
     transaction_routing_mechanism: TransactionRoutingMechanism,
-) -> Result<(), TransactionServiceError> {
+) -> Result<bool, TransactionServiceError> {
     let finalized_transaction_message = proto::TransactionFinalizedMessage {
         tx_id: tx_id.into(),
         transaction: Some(
@@ -210,7 +211,7 @@ pub async fn send_finalized_transaction_message_direct(
     if !direct_send_result && !store_and_forward_send_result {
         return Err(TransactionServiceError::OutboundSendFailure);
     }
-    Ok(())
+    Ok(direct_send_result)
 }
 
 async fn send_transaction_finalized_message_store_and_forward(
@@ -0,0 +1,195 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use log::*;
+use tari_common_types::transaction::TxId;
+use tari_comms::types::CommsPublicKey;
+use tari_comms_dht::{
+    domain_message::OutboundDomainMessage,
+    outbound::{OutboundMessageRequester, SendMessageResponse},
+};
+use tari_core::transactions::transaction_protocol::proto::protocol as proto;
+use tari_p2p::tari_message::TariMessageType;
+
+use crate::transaction_service::{
+    config::{TransactionRoutingMechanism, TransactionServiceConfig},
+    error::TransactionServiceError,
+    storage::{
+        database::{TransactionBackend, TransactionDatabase},
+        models::QueuedTransactionMessage,
+    },
+    tasks::wait_on_dial::wait_on_dial,
+};
+
+const LOG_TARGET: &str = "wallet::transaction_service::tasks::send_queued_transaction_message";
+
+/// Persist a transaction message that could not be delivered directly so that it is retried until the counterparty
+/// is reachable. Retries are always sent directly, so nothing is queued when routing is store-and-forward only.
+pub async fn queue_transaction_message<TBackend, T>(
+    db: &TransactionDatabase<TBackend>,
+    config: &TransactionServiceConfig,
+    tx_id: TxId,
+    message_type: TariMessageType,
+    destination_public_key: CommsPublicKey,
+    message: &T,
+) where
+    TBackend: TransactionBackend + 'static,
+    T: prost::Message,
+{
+    if config.transaction_routing_mechanism == TransactionRoutingMechanism::StoreAndForwardOnly {
+        return;
+    }
+    let queued_message = QueuedTransactionMessage::new(
+        tx_id,
+        message_type,
+        destination_public_key,
+        message,
+        Utc::now().naive_utc(),
+        config.message_queue_initial_retry_delay,
+        config.message_queue_expiry,
+    );
+    match db.queue_transaction_message(queued_message).await {
+        Ok(_) => debug!(
+            target: LOG_TARGET,
+            "Queued {:?} message (TxId: {}) for redelivery", message_type, tx_id
+        ),
+        Err(e) => warn!(
+            target: LOG_TARGET,
+            "Could not queue {:?} message (TxId: {}) for redelivery: {}", message_type, tx_id, e
+        ),
+    }
+}
+
+/// Remove queued messages for a transaction that no longer need to be delivered. If `message_type` is `None` all
+/// queued messages for the transaction are removed.
+pub async fn remove_queued_transaction_messages<TBackend>(
+    db: &TransactionDatabase<TBackend>,
+    tx_id: TxId,
+    message_type: Option<TariMessageType>,
+) where
+    TBackend: TransactionBackend + 'static,
+{
+    if let Err(e) = db.remove_queued_transaction_messages(tx_id, message_type).await {
+        warn!(
+            target: LOG_TARGET,
+            "Could not remove queued messages (TxId: {}): {}", tx_id, e
+        );
+    }
+}
+
+/// Attempt to deliver a queued message directly to the counterparty. The message is removed from the queue once it has
+/// been delivered. Returns true if the message was delivered.
+pub async fn send_queued_transaction_message<TBackend>(
+    message: QueuedTransactionMessage,
+    db: TransactionDatabase<TBackend>,
+    outbound_message_service: OutboundMessageRequester,
+    direct_send_timeout: Duration,
+) -> Result<bool, TransactionServiceError>
+where
+    TBackend: TransactionBackend + 'static,
+{
+    let delivered = match message.message_type {
+        TariMessageType::SenderPartialTransaction => {
+            send_direct::<proto::TransactionSenderMessage>(&message, outbound_message_service, direct_send_timeout)
+                .await?
+        },
+        TariMessageType::ReceiverPartialTransactionReply => {
+            send_direct::<proto::RecipientSignedMessage>(&message, outbound_message_service, direct_send_timeout)
+                .await?
+        },
+        TariMessageType::TransactionFinalized => {
+            send_direct::<proto::TransactionFinalizedMessage>(&message, outbound_message_service, direct_send_timeout)
+                .await?
+        },
+        message_type => {
+            return Err(TransactionServiceError::InvalidMessageError(format!(
+                "Message type {:?} cannot be queued",
+                message_type
+            )))
+        },
+    };
+
+    if delivered {
+        info!(
+            target: LOG_TARGET,
+            "Queued {:?} message (TxId: {}) delivered after {} attempt(s)",
+            message.message_type,
+            message.tx_id,
+            message.attempts
+        );
+        db.remove_queued_transaction_messages(message.tx_id, Some(message.message_type))
+            .await?;
+    }
+    Ok(delivered)
+}
+
+async fn send_direct<T>(
+    message: &QueuedTransactionMessage,
+    mut outbound_message_service: OutboundMessageRequester,
+    direct_send_timeout: Duration,
+) -> Result<bool, TransactionServiceError>
+where
+    T: prost::Message + Default,
+{
+    let body = T::decode(message.message.as_slice())
+        .map_err(|e| TransactionServiceError::InvalidMessageError(e.to_string()))?;
+    let send_states = match outbound_message_service
+        .send_direct(
+            message.destination_public_key.clone(),
+            OutboundDomainMessage::new(&message.message_type, body),
+        )
+        .await?
+    {
+        SendMessageResponse::Queued(send_states) => send_states,
+        SendMessageResponse::Failed(err) => {
+            debug!(
+                target: LOG_TARGET,
+                "Queued message (TxId: {}) send direct failed: {}", message.tx_id, err
+            );
+            return Ok(false);
+        },
+        SendMessageResponse::PendingDiscovery(rx) => match rx.await {
+            Ok(SendMessageResponse::Queued(send_states)) => send_states,
+            _ => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Discovery of {} failed for queued message (TxId: {})",
+                    message.destination_public_key,
+                    message.tx_id
+                );
+                return Ok(false);
+            },
+        },
+    };
+
+    Ok(wait_on_dial(
+        send_states,
+        message.tx_id,
+        message.destination_public_key.clone(),
+        "Queued Transaction Message",
+        direct_send_timeout,
+    )
+    .await)
+}
@@ -41,6 +41,20 @@ use crate::transaction_service::{
 
 const LOG_TARGET: &str = "wallet::transaction_service::tasks::send_transaction_reply";
 
+/// The outcome of sending a transaction reply
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TransactionReplySendResult {
+    pub direct_send_result: bool,
+    pub store_and_forward_send_result: bool,
+}
+
+impl TransactionReplySendResult {
+    /// Returns true if the reply was sent by any mechanism
+    pub fn is_sent(&self) -> bool {
+        self.direct_send_result || self.store_and_forward_send_result
+    }
+}
+
 /// A task to resend a transaction reply message if a repeated Send Transaction is received from a Sender
 /// either directly, via Store-and-forward or both as per config setting.
 pub async fn send_transaction_reply(
@@ -48,7 +62,7 @@ pub async fn send_transaction_reply(
     mut outbound_message_service: OutboundMessageRequester,
     direct_send_timeout: Duration,
     transaction_routing_mechanism: TransactionRoutingMechanism,
-) -> Result<bool, TransactionServiceError> {
+) -> Result<TransactionReplySendResult, TransactionServiceError> {
     let send_result;
     let recipient_reply = inbound_transaction.receiver_protocol.get_signed_data()?.clone();
     let proto_message: proto::RecipientSignedMessage = recipient_reply.into();
@@ -64,13 +78,16 @@ pub async fn send_transaction_reply(
             .await?;
         },
         TransactionRoutingMechanism::StoreAndForwardOnly => {
-            send_result = send_transaction_reply_store_and_forward(
-                inbound_transaction.tx_id,
-                inbound_transaction.source_public_key,
-                proto_message.clone(),
-                &mut outbound_message_service,
-            )
-            .await?;
+            send_result = TransactionReplySendResult {
+                direct_send_result: false,
+                store_and_forward_send_result: send_transaction_reply_store_and_forward(
+                    inbound_transaction.tx_id,
+                    inbound_transaction.source_public_key,
+                    proto_message.clone(),
+                    &mut outbound_message_service,
+                )
+                .await?,
+            };
         },
     };
 
@@ -83,7 +100,7 @@ pub async fn send_transaction_reply_direct(
     mut outbound_message_service: OutboundMessageRequester,
     direct_send_timeout: Duration,
     transaction_routing_mechanism: TransactionRoutingMechanism,
-) -> Result<bool, TransactionServiceError> {
+) -> Result<TransactionReplySendResult, TransactionServiceError> {
     let recipient_reply = inbound_transaction.receiver_protocol.get_signed_data()?.clone();
 
     let mut store_and_forward_send_result = false;
@@ -189,7 +206,10 @@ pub async fn send_transaction_reply_direct(
             warn!(target: LOG_TARGET, "Direct Transaction Reply Send failed: {:?}", e);
         },
     }
-    Ok(direct_send_result || store_and_forward_send_result)
+    Ok(TransactionReplySendResult {
+        direct_send_result,
+        store_and_forward_send_result,
+    })
 }
 
 async fn send_transaction_reply_store_and_forward(
@@ -43,10 +43,24 @@ pub enum UtxoScannerEvent {
         retry_limit: usize,
         error: String,
     },
-    /// Progress of the recovery process (current_block, current_chain_height)
+    /// Scanning is resuming from a previously saved checkpoint rather than from the wallet birthday
+    ResumedFromCheckpoint {
+        height: u64,
+        num_outputs_found: u64,
+    },
+    /// The saved checkpoint is not on the chain of the connected base node, so scanning restarts from the wallet
+    /// birthday
+    RestartedFromBirthday {
+        checkpoint_height: u64,
+    },
+    /// Progress of the recovery process (current_block, current_chain_height, blocks scanned by this scanning task,
+    /// outputs found since the wallet birthday and an estimate of the time until the tip is reached)
     Progress {
         current_height: u64,
         tip_height: u64,
+        blocks_scanned: u64,
+        num_outputs_found: u64,
+        estimated_time_remaining: Option<Duration>,
     },
     /// Completed Recovery (Number scanned, Num of Recovered outputs, Value of recovered outputs, Time taken)
     Completed {
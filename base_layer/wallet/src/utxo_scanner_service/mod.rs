@@ -36,3 +36,4 @@ mod utxo_scanner_task;
 pub mod uxto_scanner_service_builder;
 
 pub const RECOVERY_KEY: &str = "recovery_data";
+pub const SCANNING_CHECKPOINT_KEY: &str = "scanning_checkpoint";
@@ -25,6 +25,7 @@ use std::sync::Arc;
 use chrono::NaiveDateTime;
 use futures::FutureExt;
 use log::*;
+use serde::{Deserialize, Serialize};
 use tari_common_types::types::HashOutput;
 use tari_comms::{connectivity::ConnectivityRequester, peer_manager::Peer, types::CommsPublicKey, NodeIdentity};
 use tari_core::transactions::{tari_amount::MicroTari, CryptoFactories};
@@ -104,6 +105,7 @@ where TBackend: WalletBackend + 'static
             retry_limit: self.retry_limit,
             peer_index: 0,
             num_retries: 1,
+            num_blocks_scanned: 0,
             mode: self.mode.clone(),
             shutdown_signal,
         }
@@ -210,3 +212,15 @@ pub struct ScannedBlock {
     pub amount: Option<MicroTari>,
     pub timestamp: NaiveDateTime,
 }
+
+/// The furthest block scanned for a given wallet birthday, along with the totals found up to and including that block.
+/// Unlike the scanned block cache this survives switching to a base node that does not share our recent headers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanningCheckpoint {
+    pub birthday: u16,
+    pub height: u64,
+    pub header_hash: HashOutput,
+    pub num_outputs: u64,
+    pub amount: MicroTari,
+    pub timestamp: NaiveDateTime,
+}
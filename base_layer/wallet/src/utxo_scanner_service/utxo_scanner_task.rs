@@ -53,9 +53,10 @@ use crate::{
     utxo_scanner_service::{
         error::UtxoScannerError,
         handle::UtxoScannerEvent,
-        service::{ScannedBlock, UtxoScannerResources, SCANNED_BLOCK_CACHE_SIZE},
+        service::{ScannedBlock, ScanningCheckpoint, UtxoScannerResources, SCANNED_BLOCK_CACHE_SIZE},
         uxto_scanner_service_builder::UtxoScannerMode,
         RECOVERY_KEY,
+        SCANNING_CHECKPOINT_KEY,
     },
 };
 
@@ -68,6 +69,7 @@ where TBackend: WalletBackend + 'static
     pub(crate) event_sender: broadcast::Sender<UtxoScannerEvent>,
     pub(crate) retry_limit: usize,
     pub(crate) num_retries: usize,
+    pub(crate) num_blocks_scanned: u64,
     pub(crate) peer_seeds: Vec<CommsPublicKey>,
     pub(crate) peer_index: usize,
     pub(crate) mode: UtxoScannerMode,
@@ -149,6 +151,9 @@ where TBackend: WalletBackend + 'static
         self.publish_event(UtxoScannerEvent::Progress {
             current_height: final_height,
             tip_height: final_height,
+            blocks_scanned: self.num_blocks_scanned,
+            num_outputs_found: num_outputs_recovered,
+            estimated_time_remaining: Some(Duration::from_secs(0)),
         });
         self.publish_event(UtxoScannerEvent::Completed {
             final_height,
@@ -209,9 +214,18 @@ where TBackend: WalletBackend + 'static
         loop {
             let tip_header = self.get_chain_tip_header(&mut client).await?;
             let tip_header_hash = tip_header.hash();
-            let last_scanned_block = self.get_last_scanned_block(tip_header.height, &mut client).await?;
+            let last_scanned_block = match self.get_last_scanned_block(tip_header.height, &mut client).await? {
+                Some(block) => Some(block),
+                None => {
+                    // The node does not know of any of our cached headers, so fall back to the last scanning
+                    // checkpoint if it is on the node's chain
+                    self.resources.db.clear_scanned_blocks().await?;
+                    self.get_checkpoint_block(tip_header.height, &mut client).await?
+                },
+            };
 
             let next_block_to_scan = if let Some(last_scanned_block) = last_scanned_block {
+                let (num_outputs, amount) = self.get_outputs_found_before(last_scanned_block.height + 1).await?;
                 // If we have scanned to the tip and are told to start beyond the tip we are done
                 if last_scanned_block.height >= tip_header.height {
                     debug!(
@@ -220,12 +234,7 @@ where TBackend: WalletBackend + 'static
                         last_scanned_block.height,
                         timer.elapsed()
                     );
-                    return Ok((
-                        last_scanned_block.num_outputs.unwrap_or(0),
-                        last_scanned_block.height,
-                        last_scanned_block.amount.unwrap_or_else(|| MicroTari::from(0)),
-                        timer.elapsed(),
-                    ));
+                    return Ok((num_outputs, last_scanned_block.height, amount, timer.elapsed()));
                 }
 
                 let next_header =
@@ -235,15 +244,18 @@ where TBackend: WalletBackend + 'static
 
                 ScannedBlock {
                     height: last_scanned_block.height + 1,
-                    num_outputs: last_scanned_block.num_outputs,
-                    amount: last_scanned_block.amount,
+                    num_outputs: Some(num_outputs),
+                    amount: Some(amount),
                     header_hash: next_header_hash,
                     timestamp: Utc::now().naive_utc(),
                 }
             } else {
-                // The node does not know of any of our cached headers so we will start the scan anew from the
+                // Neither our cached headers nor the checkpoint are usable so we will start the scan anew from the
                 // wallet birthday
-                self.resources.db.clear_scanned_blocks().await?;
+                self.resources
+                    .db
+                    .clear_client_value(SCANNING_CHECKPOINT_KEY.to_owned())
+                    .await?;
                 let birthday_height_hash = self.get_birthday_header_height_hash(&mut client).await?;
 
                 ScannedBlock {
@@ -273,12 +285,7 @@ where TBackend: WalletBackend + 'static
             );
 
             let (num_recovered, num_scanned, amount) = self
-                .scan_utxos(
-                    &mut client,
-                    next_block_to_scan.header_hash,
-                    tip_header_hash,
-                    tip_header.height,
-                )
+                .scan_utxos(&mut client, &next_block_to_scan, tip_header_hash, tip_header.height)
                 .await?;
             if num_scanned == 0 {
                 return Err(UtxoScannerError::UtxoScanningError(
@@ -387,25 +394,157 @@ where TBackend: WalletBackend + 'static
         }
     }
 
+    /// Returns the last scanned block recorded by the scanning checkpoint if the checkpoint belongs to the current
+    /// wallet birthday and its header is found in the chain of the connected base node.
+    async fn get_checkpoint_block(
+        &self,
+        current_tip_height: u64,
+        client: &mut BaseNodeWalletRpcClient,
+    ) -> Result<Option<ScannedBlock>, UtxoScannerError> {
+        let checkpoint = match self.load_checkpoint().await? {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(None),
+        };
+
+        // Rather than throwing away the progress made, let the next peer have a go
+        if checkpoint.height > current_tip_height {
+            return Err(UtxoScannerError::UtxoScanningError(format!(
+                "Base node tip height {} is behind the scanning checkpoint at height {}",
+                current_tip_height, checkpoint.height
+            )));
+        }
+
+        let header = BlockHeader::try_from(client.get_header_by_height(checkpoint.height).await?)
+            .map_err(UtxoScannerError::ConversionError)?;
+        if header.hash() != checkpoint.header_hash {
+            warn!(
+                target: LOG_TARGET,
+                "Scanning checkpoint at height {} (Header Hash: {}) not found on base node, resuming scan from wallet \
+                 birthday",
+                checkpoint.height,
+                checkpoint.header_hash.to_hex()
+            );
+            self.publish_event(UtxoScannerEvent::RestartedFromBirthday {
+                checkpoint_height: checkpoint.height,
+            });
+            return Ok(None);
+        }
+
+        info!(
+            target: LOG_TARGET,
+            "Resuming scan from checkpoint at height {} ({} outputs found so far)",
+            checkpoint.height,
+            checkpoint.num_outputs
+        );
+        self.publish_event(UtxoScannerEvent::ResumedFromCheckpoint {
+            height: checkpoint.height,
+            num_outputs_found: checkpoint.num_outputs,
+        });
+
+        Ok(Some(ScannedBlock {
+            height: checkpoint.height,
+            num_outputs: Some(checkpoint.num_outputs),
+            amount: Some(checkpoint.amount),
+            header_hash: checkpoint.header_hash,
+            timestamp: Utc::now().naive_utc(),
+        }))
+    }
+
+    /// Totals of the outputs found in the blocks below `height`, made up of the checkpoint totals and the cached
+    /// blocks scanned after the checkpoint.
+    async fn get_outputs_found_before(&self, height: u64) -> Result<(u64, MicroTari), UtxoScannerError> {
+        let (mut num_outputs, mut amount, from_height) = match self.load_checkpoint().await? {
+            Some(checkpoint) if checkpoint.height < height => {
+                (checkpoint.num_outputs, checkpoint.amount, checkpoint.height + 1)
+            },
+            _ => (0, MicroTari::from(0), 0),
+        };
+        for sb in self.resources.db.get_scanned_blocks().await? {
+            if sb.height >= from_height && sb.height < height {
+                num_outputs = num_outputs.saturating_add(sb.num_outputs.unwrap_or(0));
+                amount = amount
+                    .checked_add(sb.amount.unwrap_or_else(|| MicroTari::from(0)))
+                    .ok_or(UtxoScannerError::OverflowError)?;
+            }
+        }
+        Ok((num_outputs, amount))
+    }
+
+    async fn load_checkpoint(&self) -> Result<Option<ScanningCheckpoint>, UtxoScannerError> {
+        let value = match self
+            .resources
+            .db
+            .get_client_key_value(SCANNING_CHECKPOINT_KEY.to_owned())
+            .await?
+        {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let checkpoint = match serde_json::from_str::<ScanningCheckpoint>(&value) {
+            Ok(checkpoint) => checkpoint,
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Ignoring unreadable scanning checkpoint: {}", e);
+                return Ok(None);
+            },
+        };
+        let birthday = self.resources.db.get_wallet_birthday().await?;
+        if checkpoint.birthday != birthday {
+            debug!(
+                target: LOG_TARGET,
+                "Ignoring scanning checkpoint for birthday {} (wallet birthday is {})", checkpoint.birthday, birthday
+            );
+            return Ok(None);
+        }
+        Ok(Some(checkpoint))
+    }
+
+    async fn save_checkpoint(
+        &self,
+        height: u64,
+        header_hash: HashOutput,
+        num_outputs: u64,
+        amount: MicroTari,
+    ) -> Result<(), UtxoScannerError> {
+        let checkpoint = ScanningCheckpoint {
+            birthday: self.resources.db.get_wallet_birthday().await?,
+            height,
+            header_hash,
+            num_outputs,
+            amount,
+            timestamp: Utc::now().naive_utc(),
+        };
+        self.resources
+            .db
+            .set_client_key_value(SCANNING_CHECKPOINT_KEY.to_owned(), serde_json::to_string(&checkpoint)?)
+            .await?;
+        Ok(())
+    }
+
     async fn scan_utxos(
         &mut self,
         client: &mut BaseNodeWalletRpcClient,
-        start_header_hash: HashOutput,
+        start_block: &ScannedBlock,
         end_header_hash: HashOutput,
         tip_height: u64,
     ) -> Result<(u64, u64, MicroTari), UtxoScannerError> {
-        // Setting how often the progress event and log should occur during scanning. Defined in blocks
+        // Setting how often the progress event, log and checkpoint should occur during scanning. Defined in blocks
         const PROGRESS_REPORT_INTERVAL: u64 = 100;
 
         let mut num_recovered = 0u64;
         let mut total_amount = MicroTari::from(0);
         let mut total_scanned = 0;
+        // Totals since the wallet birthday, which are what the checkpoint and progress events report
+        let mut num_outputs_found = start_block.num_outputs.unwrap_or(0);
+        let mut amount_found = start_block.amount.unwrap_or_else(|| MicroTari::from(0));
+        let mut num_blocks_this_round = 0u64;
+        let mut last_block = None;
 
         let request = SyncUtxosByBlockRequest {
-            start_header_hash: start_header_hash.clone(),
+            start_header_hash: start_block.header_hash.clone(),
             end_header_hash: end_header_hash.clone(),
         };
 
+        let round_start = Instant::now();
         let start = Instant::now();
         let mut utxo_stream = client.sync_utxos_by_block(request).await?;
         trace!(
@@ -424,6 +563,10 @@ where TBackend: WalletBackend + 'static
         } {
             if self.shutdown_signal.is_triggered() {
                 // if running is set to false, we know its been canceled upstream so lets exit the loop
+                if let Some((height, header_hash)) = last_block {
+                    self.save_checkpoint(height, header_hash, num_outputs_found, amount_found)
+                        .await?;
+                }
                 return Ok((num_recovered, total_scanned as u64, total_amount));
             }
 
@@ -439,8 +582,12 @@ where TBackend: WalletBackend + 'static
             total_scanned += outputs.len();
 
             let start = Instant::now();
-            let found_outputs = self.scan_for_outputs(outputs).await?;
+            let found_outputs = self.scan_for_outputs(outputs.clone()).await?;
             scan_for_outputs_profiling.push(start.elapsed());
+            self.resources
+                .output_manager_service
+                .scan_outputs_for_watch_only_keys(outputs, current_height, current_header_hash.clone())
+                .await?;
 
             let (count, amount) = self
                 .import_utxos_to_transaction_service(found_outputs, current_height)
@@ -449,7 +596,7 @@ where TBackend: WalletBackend + 'static
             self.resources
                 .db
                 .save_scanned_block(ScannedBlock {
-                    header_hash: current_header_hash,
+                    header_hash: current_header_hash.clone(),
                     height: current_height,
                     num_outputs: Some(count),
                     amount: Some(amount),
@@ -462,19 +609,45 @@ where TBackend: WalletBackend + 'static
                 .clear_scanned_blocks_before_height(current_height.saturating_sub(SCANNED_BLOCK_CACHE_SIZE), true)
                 .await?;
 
+            num_recovered = num_recovered.saturating_add(count);
+            total_amount += amount;
+            num_outputs_found = num_outputs_found.saturating_add(count);
+            amount_found += amount;
+            num_blocks_this_round += 1;
+            self.num_blocks_scanned = self.num_blocks_scanned.saturating_add(1);
+
             if current_height % PROGRESS_REPORT_INTERVAL == 0 {
                 debug!(
                     target: LOG_TARGET,
                     "Scanned up to block {} with a current tip_height of {}", current_height, tip_height
                 );
+                self.save_checkpoint(
+                    current_height,
+                    current_header_hash.clone(),
+                    num_outputs_found,
+                    amount_found,
+                )
+                .await?;
+                let remaining_blocks = tip_height.saturating_sub(current_height);
+                let estimated_time_remaining = round_start
+                    .elapsed()
+                    .checked_div(u32::try_from(num_blocks_this_round).unwrap_or(u32::MAX))
+                    .and_then(|per_block| per_block.checked_mul(u32::try_from(remaining_blocks).unwrap_or(u32::MAX)));
                 self.publish_event(UtxoScannerEvent::Progress {
                     current_height,
                     tip_height,
+                    blocks_scanned: self.num_blocks_scanned,
+                    num_outputs_found,
+                    estimated_time_remaining,
                 });
             }
 
-            num_recovered = num_recovered.saturating_add(count);
-            total_amount += amount;
+            last_block = Some((current_height, current_header_hash));
+        }
+
+        if let Some((height, header_hash)) = last_block {
+            self.save_checkpoint(height, header_hash, num_outputs_found, amount_found)
+                .await?;
         }
         trace!(
             target: LOG_TARGET,
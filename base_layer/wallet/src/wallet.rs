@@ -86,7 +86,10 @@ use crate::{
     output_manager_service::{
         error::OutputManagerError,
         handle::OutputManagerHandle,
-        storage::{database::OutputManagerBackend, models::KnownOneSidedPaymentScript},
+        storage::{
+            database::OutputManagerBackend,
+            models::{KnownOneSidedPaymentScript, WatchOnlyKey},
+        },
         OutputManagerServiceInitializer,
     },
     storage::database::{WalletBackend, WalletDatabase},
@@ -97,7 +100,12 @@ use crate::{
         TransactionServiceInitializer,
     },
     types::KeyDigest,
-    utxo_scanner_service::{handle::UtxoScannerHandle, initializer::UtxoScannerServiceInitializer, RECOVERY_KEY},
+    utxo_scanner_service::{
+        handle::UtxoScannerHandle,
+        initializer::UtxoScannerServiceInitializer,
+        RECOVERY_KEY,
+        SCANNING_CHECKPOINT_KEY,
+    },
 };
 
 const LOG_TARGET: &str = "wallet";
@@ -557,6 +565,63 @@ where
         }
     }
 
+    /// Split the largest spendable UTXO into `split_count` outputs of equal value
+    pub async fn coin_split_even(
+        &mut self,
+        split_count: usize,
+        fee_per_gram: MicroTari,
+        message: String,
+        lock_height: Option<u64>,
+    ) -> Result<TxId, WalletError> {
+        let (tx_id, split_tx, amount) = self
+            .output_manager_service
+            .create_even_coin_split(split_count, fee_per_gram, lock_height)
+            .await?;
+        self.transaction_service
+            .submit_transaction(tx_id, split_tx, amount, message)
+            .await?;
+        Ok(tx_id)
+    }
+
+    /// Consolidate up to `max_inputs` spendable UTXOs worth less than `dust_threshold` into a single output
+    pub async fn coin_join(
+        &mut self,
+        dust_threshold: MicroTari,
+        max_inputs: usize,
+        fee_per_gram: MicroTari,
+        message: String,
+    ) -> Result<TxId, WalletError> {
+        let (tx_id, join_tx, amount) = self
+            .output_manager_service
+            .create_coin_join(dust_threshold, max_inputs, fee_per_gram)
+            .await?;
+        self.transaction_service
+            .submit_transaction(tx_id, join_tx, amount, message)
+            .await?;
+        Ok(tx_id)
+    }
+
+    /// The public view data of this wallet, which another wallet can import to watch this wallet's outputs without
+    /// being able to spend them
+    pub async fn export_watch_only_key(&mut self, label: String) -> Result<WatchOnlyKey, WalletError> {
+        let rewind_keys = self.output_manager_service.get_rewind_public_keys().await?;
+        Ok(WatchOnlyKey {
+            spend_public_key: self.comms.node_identity().public_key().clone(),
+            rewind_public_key: rewind_keys.rewind_public_key,
+            rewind_blinding_public_key: rewind_keys.rewind_blinding_public_key,
+            label,
+        })
+    }
+
+    /// Start watching the outputs of another wallet from its public view data. The scanning progress is reset so that
+    /// the outputs the watched wallet received before the import are found on the next scanning round.
+    pub async fn add_watch_only_key(&mut self, key: WatchOnlyKey) -> Result<(), WalletError> {
+        self.output_manager_service.add_watch_only_key(key).await?;
+        self.db.clear_scanned_blocks().await?;
+        self.db.clear_client_value(SCANNING_CHECKPOINT_KEY.to_owned()).await?;
+        Ok(())
+    }
+
     /// Apply encryption to all the Wallet db backends. The Wallet backend will test if the db's are already encrypted
     /// in which case this will fail.
     pub async fn apply_encryption(&mut self, passphrase: String) -> Result<(), WalletError> {
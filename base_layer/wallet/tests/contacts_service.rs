@@ -98,6 +98,7 @@ pub fn setup_contacts_service<T: ContactsBackend + 'static>(
         listener_liveness_max_sessions: 0,
         user_agent: "tari/test-wallet".to_string(),
         rpc_max_simultaneous_sessions: 0,
+        ..Default::default()
     };
     let peer_message_subscription_factory = Arc::new(subscription_factory);
     let shutdown = Shutdown::new();
@@ -202,3 +203,56 @@ pub fn test_contacts_service() {
         Err(_) => panic!("Should not receive any other type of error here"),
     };
 }
+
+#[test]
+pub fn test_contact_aliases() {
+    let mut runtime = Runtime::new().unwrap();
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = ContactsServiceSqliteDatabase::new(connection);
+
+    let (mut contacts_service, _node_identity, _shutdown) = setup_contacts_service(&mut runtime, backend);
+
+    let (_secret_key, alice_public_key) = PublicKey::random_keypair(&mut OsRng);
+    let alice = Contact::new("alice".to_string(), alice_public_key, None, None);
+    runtime
+        .block_on(contacts_service.upsert_contact(alice.clone()))
+        .unwrap();
+
+    let contact = runtime
+        .block_on(contacts_service.get_contact_by_alias("alice".to_string()))
+        .unwrap();
+    assert_eq!(contact, alice);
+    assert_eq!(contact.last_transaction_id, None);
+
+    let result = runtime.block_on(contacts_service.get_contact_by_alias("bob".to_string()));
+    match result {
+        Err(ContactsServiceError::ContactsServiceStorageError(ContactsServiceStorageError::ValueNotFound(val))) => {
+            assert_eq!(val, DbKey::ContactAlias("bob".to_string()))
+        },
+        _ => panic!("There should be a specific error here"),
+    }
+
+    // Another contact may not take an alias that is already in use
+    let (_secret_key, bob_public_key) = PublicKey::random_keypair(&mut OsRng);
+    let result = runtime.block_on(contacts_service.upsert_contact(Contact::new(
+        "alice".to_string(),
+        bob_public_key.clone(),
+        None,
+        None,
+    )));
+    assert!(matches!(result, Err(ContactsServiceError::DuplicateAlias(_))));
+
+    for alias in ["", "   ", "@bob"] {
+        let result = runtime.block_on(contacts_service.upsert_contact(Contact::new(
+            alias.to_string(),
+            bob_public_key.clone(),
+            None,
+            None,
+        )));
+        assert!(matches!(result, Err(ContactsServiceError::InvalidAlias(_))));
+    }
+
+    // Saving a contact under its own alias again is an update, not a duplicate
+    runtime.block_on(contacts_service.upsert_contact(alice)).unwrap();
+    assert_eq!(runtime.block_on(contacts_service.get_contacts()).unwrap().len(), 1);
+}
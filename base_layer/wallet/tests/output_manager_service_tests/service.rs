@@ -83,7 +83,7 @@ use tari_wallet::{
         service::OutputManagerService,
         storage::{
             database::{OutputManagerBackend, OutputManagerDatabase},
-            models::SpendingPriority,
+            models::{SpendingPriority, WatchOnlyKey},
             sqlite_db::OutputManagerSqliteDatabase,
             OutputStatus,
         },
@@ -1211,6 +1211,94 @@ async fn coin_split_no_change() {
     assert_eq!(amount, val1 + val2 + val3);
 }
 
+#[tokio::test]
+async fn even_coin_split_uses_largest_output() {
+    let factories = CryptoFactories::default();
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection.clone(), None);
+    let ks_backend = KeyManagerSqliteDatabase::new(connection, None).unwrap();
+    let mut oms = setup_output_manager_service(backend, ks_backend, true).await;
+
+    let val1 = 3_000 * uT;
+    let val2 = 20_003 * uT;
+    let (_ti, uo1) = make_input(&mut OsRng, val1, &factories.commitment, None).await;
+    let (_ti, uo2) = make_input(&mut OsRng, val2, &factories.commitment, None).await;
+    assert!(oms.output_manager_handle.add_output(uo1, None).await.is_ok());
+    assert!(oms.output_manager_handle.add_output(uo2, None).await.is_ok());
+
+    let fee_per_gram = MicroTari::from(5);
+    let split_count = 4;
+    assert!(oms
+        .output_manager_handle
+        .create_even_coin_split(1, fee_per_gram, None)
+        .await
+        .is_err());
+
+    let (_tx_id, coin_split_tx, amount) = oms
+        .output_manager_handle
+        .create_even_coin_split(split_count, fee_per_gram, None)
+        .await
+        .unwrap();
+    let fee_calc = Fee::new(*create_consensus_constants(0).transaction_weight());
+    let expected_fee = fee_calc.calculate(
+        fee_per_gram,
+        1,
+        1,
+        split_count,
+        split_count * default_metadata_byte_size(),
+    );
+    assert_eq!(coin_split_tx.body.inputs().len(), 1);
+    assert_eq!(coin_split_tx.body.outputs().len(), split_count);
+    assert_eq!(coin_split_tx.body.get_total_fee(), expected_fee);
+    assert_eq!(amount, val2);
+
+    let balance = oms.output_manager_handle.get_balance().await.unwrap();
+    assert_eq!(balance.available_balance, val1);
+    assert_eq!(balance.pending_incoming_balance, val2 - expected_fee);
+}
+
+#[tokio::test]
+async fn coin_join_consolidates_dust() {
+    let factories = CryptoFactories::default();
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection.clone(), None);
+    let ks_backend = KeyManagerSqliteDatabase::new(connection, None).unwrap();
+    let mut oms = setup_output_manager_service(backend, ks_backend, true).await;
+
+    let fee_per_gram = MicroTari::from(1);
+    let dust_threshold = 2_000 * uT;
+    let dust_values = [1_000 * uT, 1_200 * uT, 1_500 * uT, 1_999 * uT];
+    for value in dust_values {
+        let (_ti, uo) = make_input(&mut OsRng, value, &factories.commitment, None).await;
+        assert!(oms.output_manager_handle.add_output(uo, None).await.is_ok());
+    }
+    let large_value = 50_000 * uT;
+    let (_ti, uo) = make_input(&mut OsRng, large_value, &factories.commitment, None).await;
+    assert!(oms.output_manager_handle.add_output(uo, None).await.is_ok());
+
+    let err = oms
+        .output_manager_handle
+        .create_coin_join(dust_threshold, 1, fee_per_gram)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::NotEnoughOutputsToJoin(1)));
+
+    let (_tx_id, coin_join_tx, amount) = oms
+        .output_manager_handle
+        .create_coin_join(dust_threshold, 3, fee_per_gram)
+        .await
+        .unwrap();
+    let fee_calc = Fee::new(*create_consensus_constants(0).transaction_weight());
+    let expected_fee = fee_calc.calculate(fee_per_gram, 1, 3, 1, default_metadata_byte_size());
+    assert_eq!(coin_join_tx.body.inputs().len(), 3);
+    assert_eq!(coin_join_tx.body.outputs().len(), 1);
+    assert_eq!(coin_join_tx.body.get_total_fee(), expected_fee);
+    assert_eq!(amount, dust_values[0] + dust_values[1] + dust_values[2]);
+
+    let balance = oms.output_manager_handle.get_balance().await.unwrap();
+    assert_eq!(balance.available_balance, dust_values[3] + large_value);
+}
+
 #[tokio::test]
 async fn handle_coinbase() {
     let factories = CryptoFactories::default();
@@ -1285,6 +1373,111 @@ async fn handle_coinbase() {
     assert_eq!(rewind_result.committed_value, value3);
 }
 
+#[tokio::test]
+async fn watch_only_outputs_are_tracked_apart_from_own_outputs() {
+    let factories = CryptoFactories::default();
+    let (connection, _tempdir) = get_temp_sqlite_database_connection();
+    let backend = OutputManagerSqliteDatabase::new(connection.clone(), None);
+    let ks_backend = KeyManagerSqliteDatabase::new(connection, None).unwrap();
+    let mut oms = setup_output_manager_service(backend, ks_backend, true).await;
+
+    let watched_rewind_data = RewindData {
+        rewind_key: PrivateKey::random(&mut OsRng),
+        rewind_blinding_key: PrivateKey::random(&mut OsRng),
+        recovery_byte_key: PrivateKey::random(&mut OsRng),
+        proof_message: [0u8; REWIND_USER_MESSAGE_LENGTH],
+    };
+    let watched_key = WatchOnlyKey {
+        spend_public_key: PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+        rewind_public_key: PublicKey::from_secret_key(&watched_rewind_data.rewind_key),
+        rewind_blinding_public_key: PublicKey::from_secret_key(&watched_rewind_data.rewind_blinding_key),
+        label: "cold storage".to_string(),
+    };
+    oms.output_manager_handle
+        .add_watch_only_key(watched_key.clone())
+        .await
+        .unwrap();
+
+    let mut outputs = Vec::new();
+    for value in [1000u64, 2000] {
+        let (_ti, uo) = make_input(&mut OsRng, MicroTari::from(value), &factories.commitment, None).await;
+        outputs.push(
+            uo.as_rewindable_transaction_output(&factories, &watched_rewind_data, None)
+                .unwrap(),
+        );
+    }
+    let (_ti, mut one_sided) = make_input(&mut OsRng, MicroTari::from(3000), &factories.commitment, None).await;
+    one_sided.script = script!(PushPubKey(Box::new(watched_key.spend_public_key.clone())));
+    outputs.push(one_sided.as_transaction_output(&factories).unwrap());
+    let (_ti, unrelated) = make_input(&mut OsRng, MicroTari::from(4000), &factories.commitment, None).await;
+    outputs.push(unrelated.as_transaction_output(&factories).unwrap());
+
+    let found = oms
+        .output_manager_handle
+        .scan_outputs_for_watch_only_keys(outputs.clone(), 5, vec![1u8; 32])
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 3);
+    assert_eq!(found[0].value, Some(MicroTari::from(1000)));
+    assert_eq!(found[1].value, Some(MicroTari::from(2000)));
+    assert_eq!(found[2].value, None);
+    assert!(found.iter().all(|o| o.spend_public_key == watched_key.spend_public_key));
+
+    // Rescanning the same block must not record the outputs twice
+    let found = oms
+        .output_manager_handle
+        .scan_outputs_for_watch_only_keys(outputs, 5, vec![1u8; 32])
+        .await
+        .unwrap();
+    assert!(found.is_empty());
+    assert_eq!(
+        oms.output_manager_handle.get_watch_only_outputs().await.unwrap().len(),
+        3
+    );
+
+    let balances = oms.output_manager_handle.get_watch_only_balances().await.unwrap();
+    assert_eq!(balances.len(), 1);
+    assert_eq!(balances[0].label, "cold storage");
+    assert_eq!(balances[0].received, MicroTari::from(3000));
+    assert_eq!(balances[0].num_outputs, 3);
+    assert_eq!(balances[0].num_outputs_with_unknown_value, 1);
+
+    // The watched outputs are not spendable by this wallet
+    let balance = oms.output_manager_handle.get_balance().await.unwrap();
+    assert_eq!(balance.available_balance, MicroTari::from(0));
+    assert_eq!(oms.output_manager_handle.get_unspent_outputs().await.unwrap().len(), 0);
+
+    let own_keys = oms.output_manager_handle.get_rewind_public_keys().await.unwrap();
+    let err = oms
+        .output_manager_handle
+        .add_watch_only_key(WatchOnlyKey {
+            spend_public_key: PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
+            rewind_public_key: own_keys.rewind_public_key,
+            rewind_blinding_public_key: own_keys.rewind_blinding_public_key,
+            label: "self".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OutputManagerError::InvalidWatchOnlyKey(_)));
+
+    oms.output_manager_handle
+        .remove_watch_only_key(watched_key.spend_public_key)
+        .await
+        .unwrap();
+    assert!(oms
+        .output_manager_handle
+        .get_watch_only_keys()
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(oms
+        .output_manager_handle
+        .get_watch_only_outputs()
+        .await
+        .unwrap()
+        .is_empty());
+}
+
 #[tokio::test]
 async fn test_txo_validation() {
     let factories = CryptoFactories::default();
@@ -113,6 +113,7 @@ impl MockBaseNodeService {
                 self.state.chain_metadata.clone(),
             )),
             BaseNodeServiceRequest::GetBaseNodeLatency => Ok(BaseNodeServiceResponse::Latency(None)),
+            BaseNodeServiceRequest::GetFeeEstimates => Ok(BaseNodeServiceResponse::FeeEstimates(Default::default())),
         }
     }
 }
@@ -43,8 +43,12 @@ use tari_core::{
     proto::{
         base_node::{
             ChainMetadata as ChainMetadataProto,
+            FeeEstimatesResponse,
             FetchMatchingUtxos,
+            FetchSpendingInputsRequest,
+            FetchSpendingInputsResponse,
             FetchUtxosResponse,
+            KernelLocationResponse,
             QueryDeletedRequest,
             QueryDeletedResponse,
             Signatures as SignaturesProto,
@@ -98,6 +102,7 @@ pub struct BaseNodeWalletRpcMockState {
     tip_info_response: Arc<Mutex<TipInfoResponse>>,
     utxo_query_response: Arc<Mutex<UtxoQueryResponses>>,
     query_deleted_response: Arc<Mutex<QueryDeletedResponse>>,
+    kernel_location_response: Arc<Mutex<KernelLocationResponse>>,
     fetch_utxos_calls: Arc<Mutex<Vec<Vec<Vec<u8>>>>>,
     response_delay: Arc<Mutex<Option<Duration>>>,
     rpc_status_error: Arc<Mutex<Option<RpcStatus>>>,
@@ -161,6 +166,7 @@ impl BaseNodeWalletRpcMockState {
                 heights_deleted_at: vec![],
                 blocks_deleted_in: vec![],
             })),
+            kernel_location_response: Arc::new(Mutex::new(KernelLocationResponse::default())),
             fetch_utxos_calls: Arc::new(Mutex::new(Vec::new())),
             response_delay: Arc::new(Mutex::new(None)),
             rpc_status_error: Arc::new(Mutex::new(None)),
@@ -203,6 +209,11 @@ impl BaseNodeWalletRpcMockState {
         *lock = response;
     }
 
+    pub fn set_kernel_location_response(&self, response: KernelLocationResponse) {
+        let mut lock = acquire_lock!(self.kernel_location_response);
+        *lock = response;
+    }
+
     pub fn set_response_delay(&self, delay: Option<Duration>) {
         let mut lock = acquire_lock!(self.response_delay);
         *lock = delay;
@@ -823,6 +834,40 @@ impl BaseNodeWalletService for BaseNodeWalletRpcMockService {
             Err(RpcStatus::not_found("Headers not found"))
         }
     }
+
+    async fn get_kernel_location(
+        &self,
+        _request: Request<SignatureProto>,
+    ) -> Result<Response<KernelLocationResponse>, RpcStatus> {
+        let status_lock = acquire_lock!(self.state.rpc_status_error);
+        if let Some(status) = (*status_lock).clone() {
+            return Err(status);
+        }
+
+        let kernel_location_response_lock = acquire_lock!(self.state.kernel_location_response);
+        Ok(Response::new(kernel_location_response_lock.clone()))
+    }
+
+    async fn get_fee_estimates(&self, _request: Request<()>) -> Result<Response<FeeEstimatesResponse>, RpcStatus> {
+        let status_lock = acquire_lock!(self.state.rpc_status_error);
+        if let Some(status) = (*status_lock).clone() {
+            return Err(status);
+        }
+
+        Ok(Response::new(FeeEstimatesResponse::default()))
+    }
+
+    async fn fetch_spending_inputs(
+        &self,
+        _request: Request<FetchSpendingInputsRequest>,
+    ) -> Result<Response<FetchSpendingInputsResponse>, RpcStatus> {
+        let status_lock = acquire_lock!(self.state.rpc_status_error);
+        if let Some(status) = (*status_lock).clone() {
+            return Err(status);
+        }
+
+        Ok(Response::new(FetchSpendingInputsResponse::default()))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -144,6 +144,14 @@ impl OutputManagerServiceMock {
                         e
                     });
             },
+            OutputManagerRequest::ScanOutputsForWatchOnlyKeys { .. } => {
+                let _result = reply_tx
+                    .send(Ok(OutputManagerResponse::WatchOnlyOutputs(Vec::new())))
+                    .map_err(|e| {
+                        warn!(target: LOG_TARGET, "Failed to send reply");
+                        e
+                    });
+            },
             _ => panic!("Output Manager Service Mock does not support this call"),
         }
     }
@@ -103,6 +103,7 @@ use tari_wallet::{
     base_node_service::{config::BaseNodeServiceConfig, handle::BaseNodeServiceHandle, BaseNodeServiceInitializer},
     connectivity_service::{
         create_wallet_connectivity_mock,
+        OnlineStatus,
         WalletConnectivityHandle,
         WalletConnectivityInitializer,
         WalletConnectivityInterface,
@@ -111,7 +112,7 @@ use tari_wallet::{
     key_manager_service::{storage::sqlite_db::KeyManagerSqliteDatabase, KeyManagerInitializer, KeyManagerMock},
     output_manager_service::{
         config::OutputManagerServiceConfig,
-        handle::{OutputManagerEvent, OutputManagerHandle},
+        handle::{OutputManagerEvent, OutputManagerHandle, PaymentRecipient},
         service::{Balance, OutputManagerService},
         storage::{
             database::OutputManagerDatabase,
@@ -133,7 +134,17 @@ use tari_wallet::{
         service::TransactionService,
         storage::{
             database::{DbKeyValuePair, TransactionBackend, TransactionDatabase, WriteOperation},
-            models::{CompletedTransaction, InboundTransaction, OutboundTransaction, WalletTransaction},
+            models::{
+                CompletedTransaction,
+                InboundTransaction,
+                OutboundTransaction,
+                PaymentSchedule,
+                ScheduledPayment,
+                ScheduledPaymentStatus,
+                ScheduledPaymentType,
+                TxCancellationReason,
+                WalletTransaction,
+            },
             sqlite_db::TransactionServiceSqliteDatabase,
         },
         TransactionServiceInitializer,
@@ -257,6 +268,7 @@ pub struct TransactionServiceNoCommsInterface {
     transaction_finalize_message_channel: Sender<DomainMessage<proto::TransactionFinalizedMessage>>,
     _base_node_response_message_channel: Sender<DomainMessage<base_node_proto::BaseNodeServiceResponse>>,
     transaction_cancelled_message_channel: Sender<DomainMessage<proto::TransactionCancelledMessage>>,
+    _multisig_message_channel: Sender<DomainMessage<proto::MultisigMessage>>,
     _shutdown: Shutdown,
     _mock_rpc_server: MockRpcServer<BaseNodeWalletRpcServer<BaseNodeWalletRpcMockService>>,
     base_node_identity: Arc<NodeIdentity>,
@@ -288,6 +300,7 @@ pub fn setup_transaction_service_no_comms(
     let (transaction_finalize_message_channel, tx_finalized_receiver) = mpsc::channel(20);
     let (base_node_response_message_channel, base_node_response_receiver) = mpsc::channel(20);
     let (transaction_cancelled_message_channel, tx_cancelled_receiver) = mpsc::channel(20);
+    let (multisig_message_channel, multisig_receiver) = mpsc::channel(20);
 
     let outbound_service_mock_state = mock_outbound_service.get_state();
     runtime.spawn(mock_outbound_service.run());
@@ -385,6 +398,7 @@ pub fn setup_transaction_service_no_comms(
         tx_finalized_receiver,
         base_node_response_receiver,
         tx_cancelled_receiver,
+        multisig_receiver,
         output_manager_service_handle.clone(),
         outbound_message_requester,
         wallet_connectivity_service_mock.clone(),
@@ -393,6 +407,7 @@ pub fn setup_transaction_service_no_comms(
         factories,
         shutdown.to_signal(),
         base_node_service_handle,
+        None,
     );
     runtime.spawn(async move { output_manager_service.start().await.unwrap() });
     runtime.spawn(async move { ts_service.start().await.unwrap() });
@@ -405,6 +420,7 @@ pub fn setup_transaction_service_no_comms(
         transaction_finalize_message_channel,
         _base_node_response_message_channel: base_node_response_message_channel,
         transaction_cancelled_message_channel,
+        _multisig_message_channel: multisig_message_channel,
         _shutdown: shutdown,
         _mock_rpc_server: mock_rpc_server,
         base_node_identity,
@@ -937,6 +953,137 @@ fn recover_one_sided_transaction() {
     });
 }
 
+#[test]
+fn recover_one_sided_transaction_to_many() {
+    let mut runtime = create_runtime();
+
+    let factories = CryptoFactories::default();
+    // Alice's parameters
+    let alice_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    // Bob's parameters
+    let bob_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    let base_node_identity = Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        get_next_memory_address(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+
+    log::info!(
+        "manage_single_transaction: Alice: '{}', Bob: '{}', Base: '{}'",
+        alice_node_identity.node_id().short_str(),
+        bob_node_identity.node_id().short_str(),
+        base_node_identity.node_id().short_str()
+    );
+
+    let temp_dir = tempdir().unwrap();
+    let temp_dir2 = tempdir().unwrap();
+    let database_path = temp_dir.path().to_str().unwrap().to_string();
+    let database_path2 = temp_dir2.path().to_str().unwrap().to_string();
+
+    let (alice_connection, _tempdir) = make_wallet_database_connection(Some(database_path.clone()));
+    let (bob_connection, _tempdir) = make_wallet_database_connection(Some(database_path2.clone()));
+
+    let shutdown = Shutdown::new();
+    let (mut alice_ts, alice_oms, _alice_comms, mut alice_connectivity) = setup_transaction_service(
+        &mut runtime,
+        alice_node_identity,
+        vec![],
+        factories.clone(),
+        alice_connection,
+        database_path,
+        Duration::from_secs(0),
+        shutdown.to_signal(),
+    );
+
+    let (_bob_ts, mut bob_oms, _bob_comms, _bob_connectivity) = setup_transaction_service(
+        &mut runtime,
+        bob_node_identity.clone(),
+        vec![],
+        factories.clone(),
+        bob_connection,
+        database_path2,
+        Duration::from_secs(0),
+        shutdown.to_signal(),
+    );
+    let script = script!(PushPubKey(Box::new(bob_node_identity.public_key().clone())));
+    let known_script = KnownOneSidedPaymentScript {
+        script_hash: script.as_hash::<Blake256>().unwrap().to_vec(),
+        private_key: bob_node_identity.secret_key().clone(),
+        script,
+        input: ExecutionStack::default(),
+        script_lock_height: 0,
+    };
+    let mut cloned_bob_oms = bob_oms.clone();
+    runtime.block_on(async move {
+        cloned_bob_oms.add_known_script(known_script).await.unwrap();
+    });
+
+    alice_connectivity.set_base_node(base_node_identity.to_peer());
+
+    let initial_wallet_value = 2500.into();
+    let (_utxo, uo1) = runtime.block_on(make_input(
+        &mut OsRng,
+        initial_wallet_value,
+        &factories.commitment,
+        Some(alice_oms.clone()),
+    ));
+    let mut alice_oms_clone = alice_oms;
+    runtime.block_on(async move { alice_oms_clone.add_rewindable_output(uo1, None, None).await.unwrap() });
+
+    let carol_public_key = PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng));
+    let message = "Batched withdrawal".to_string();
+    let bob_value = 1000.into();
+    let carol_value = 700.into();
+    let recipients = vec![
+        PaymentRecipient {
+            address: bob_node_identity.public_key().clone(),
+            amount: bob_value,
+            payment_id: b"withdrawal-42".to_vec(),
+        },
+        PaymentRecipient {
+            address: carol_public_key,
+            amount: carol_value,
+            payment_id: vec![],
+        },
+    ];
+    let mut alice_ts_clone = alice_ts.clone();
+    let tx_id = runtime.block_on(async move {
+        alice_ts_clone
+            .send_one_sided_to_many_transaction(recipients, 20.into(), message)
+            .await
+            .expect("Alice sending one-sided tx to Bob and Carol")
+    });
+
+    runtime.block_on(async move {
+        let completed_tx = alice_ts
+            .get_completed_transaction(tx_id)
+            .await
+            .expect("Could not find completed one-sided tx");
+        assert_eq!(completed_tx.amount, bob_value + carol_value);
+        let outputs = completed_tx.transaction.body.outputs().clone();
+        // One output for each recipient and one for change
+        assert_eq!(outputs.len(), 3);
+
+        let unblinded = bob_oms
+            .scan_outputs_for_one_sided_payments(outputs.clone())
+            .await
+            .unwrap();
+        // Bob should only be able to claim his own output
+        assert_eq!(1, unblinded.len());
+        assert_eq!(bob_value, unblinded[0].output.value);
+    });
+}
+
 #[test]
 fn test_htlc_send_and_claim() {
     let mut runtime = create_runtime();
@@ -2464,6 +2611,272 @@ fn test_transaction_cancellation() {
         .remove(&tx_id3)
         .is_none());
 }
+
+#[test]
+fn test_transaction_fee_bump() {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+
+    let bob_node_identity =
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE);
+
+    let (connection, _temp_dir) = make_wallet_database_connection(None);
+
+    let mut alice_ts_interface = setup_transaction_service_no_comms(&mut runtime, factories.clone(), connection, None);
+    let mut alice_event_stream = alice_ts_interface.transaction_service_handle.get_event_stream();
+
+    let alice_total_available = 250000 * uT;
+    let (_utxo, uo) = runtime.block_on(make_input(
+        &mut OsRng,
+        alice_total_available,
+        &factories.commitment,
+        None,
+    ));
+    runtime
+        .block_on(alice_ts_interface.output_manager_service_handle.add_output(uo, None))
+        .unwrap();
+
+    let amount_sent = 10000 * uT;
+    let tx_id = runtime
+        .block_on(alice_ts_interface.transaction_service_handle.send_transaction(
+            bob_node_identity.public_key().clone(),
+            amount_sent,
+            5 * uT,
+            "Testing Message".to_string(),
+        ))
+        .unwrap();
+
+    for i in 0..=12 {
+        let pending = runtime
+            .block_on(
+                alice_ts_interface
+                    .transaction_service_handle
+                    .get_pending_outbound_transactions(),
+            )
+            .unwrap();
+        if pending.contains_key(&tx_id) {
+            break;
+        }
+        runtime.block_on(async { sleep(Duration::from_secs(5)).await });
+        if i >= 12 {
+            panic!("Pending outbound transaction should have been added by now");
+        }
+    }
+    let original_tx = runtime
+        .block_on(
+            alice_ts_interface
+                .transaction_service_handle
+                .get_pending_outbound_transactions(),
+        )
+        .unwrap()
+        .remove(&tx_id)
+        .unwrap();
+
+    // The same fee per gram does not increase the fee
+    assert!(runtime
+        .block_on(
+            alice_ts_interface
+                .transaction_service_handle
+                .bump_transaction_fee(tx_id, 5 * uT)
+        )
+        .is_err());
+
+    let new_tx_id = runtime
+        .block_on(
+            alice_ts_interface
+                .transaction_service_handle
+                .bump_transaction_fee(tx_id, 50 * uT),
+        )
+        .unwrap();
+    assert_ne!(new_tx_id, tx_id);
+
+    runtime.block_on(async {
+        let delay = sleep(Duration::from_secs(60));
+        tokio::pin!(delay);
+        let mut cancelled = false;
+        loop {
+            tokio::select! {
+                event = alice_event_stream.recv() => {
+                    if let TransactionEvent::TransactionCancelled(id, reason) = &*event.unwrap() {
+                        assert_eq!(*id, tx_id);
+                        assert_eq!(*reason, TxCancellationReason::FeeBumped);
+                        cancelled = true;
+                        break;
+                    }
+                },
+                () = &mut delay => {
+                    break;
+                },
+            }
+        }
+        assert!(cancelled, "Cancelled event should have occurred");
+    });
+
+    let mut replacement_tx = None;
+    for _ in 0..=12 {
+        let mut pending = runtime
+            .block_on(
+                alice_ts_interface
+                    .transaction_service_handle
+                    .get_pending_outbound_transactions(),
+            )
+            .unwrap();
+        assert!(!pending.contains_key(&tx_id));
+        replacement_tx = pending.remove(&new_tx_id);
+        if replacement_tx.is_some() {
+            break;
+        }
+        runtime.block_on(async { sleep(Duration::from_secs(5)).await });
+    }
+    let replacement_tx = replacement_tx.expect("Replacement transaction should have been added by now");
+    assert_eq!(replacement_tx.amount, amount_sent);
+    assert_eq!(
+        replacement_tx.destination_public_key,
+        original_tx.destination_public_key
+    );
+    assert!(replacement_tx.fee > original_tx.fee);
+
+    // The same inputs are spent, so nothing was released back to the available balance
+    let balance = runtime
+        .block_on(alice_ts_interface.output_manager_service_handle.get_balance())
+        .unwrap();
+    assert_eq!(balance.available_balance, MicroTari::from(0));
+    assert_eq!(balance.pending_outgoing_balance, alice_total_available);
+}
+
+#[test]
+fn test_scheduled_payments() {
+    let factories = CryptoFactories::default();
+    let mut runtime = Runtime::new().unwrap();
+
+    let bob_node_identity =
+        NodeIdentity::random(&mut OsRng, get_next_memory_address(), PeerFeatures::COMMUNICATION_NODE);
+
+    let (connection, _temp_dir) = make_wallet_database_connection(None);
+
+    let mut alice_ts_interface = setup_transaction_service_no_comms(
+        &mut runtime,
+        factories.clone(),
+        connection,
+        Some(TransactionServiceConfig {
+            scheduled_payment_check_interval: Duration::from_secs(1),
+            ..Default::default()
+        }),
+    );
+    let mut alice_event_stream = alice_ts_interface.transaction_service_handle.get_event_stream();
+
+    let (_utxo, uo) = runtime.block_on(make_input(&mut OsRng, 250000 * uT, &factories.commitment, None));
+    runtime
+        .block_on(alice_ts_interface.output_manager_service_handle.add_output(uo, None))
+        .unwrap();
+
+    let now = Utc::now().naive_utc();
+    let new_payment = |schedule| {
+        ScheduledPayment::new(
+            bob_node_identity.public_key().clone(),
+            10000 * uT,
+            5 * uT,
+            "Salary".to_string(),
+            ScheduledPaymentType::OneSided,
+            schedule,
+        )
+    };
+    let invalid_schedule = PaymentSchedule::recurring(now, Duration::from_secs(3600), Some(0));
+    assert!(matches!(
+        runtime.block_on(
+            alice_ts_interface
+                .transaction_service_handle
+                .schedule_payment(new_payment(invalid_schedule))
+        ),
+        Err(TransactionServiceError::InvalidPaymentSchedule(_))
+    ));
+
+    let payment_id = runtime
+        .block_on(
+            alice_ts_interface
+                .transaction_service_handle
+                .schedule_payment(new_payment(PaymentSchedule::recurring(
+                    now,
+                    Duration::from_secs(3600),
+                    Some(2),
+                ))),
+        )
+        .unwrap();
+
+    // The payment is deferred while the wallet is offline, and sent once it comes online
+    let tx_id = runtime.block_on(async {
+        let delay = sleep(Duration::from_secs(60));
+        tokio::pin!(delay);
+        let mut deferred = false;
+        loop {
+            tokio::select! {
+                event = alice_event_stream.recv() => {
+                    match &*event.unwrap() {
+                        TransactionEvent::ScheduledPaymentDeferred { payment_id: id, .. } => {
+                            assert_eq!(*id, payment_id);
+                            deferred = true;
+                            alice_ts_interface
+                                .wallet_connectivity_service_mock
+                                .set_connectivity_status(OnlineStatus::Online);
+                        },
+                        TransactionEvent::ScheduledPaymentExecuted { payment_id: id, tx_id } => {
+                            assert_eq!(*id, payment_id);
+                            assert!(deferred, "Payment should have been deferred while offline");
+                            break *tx_id;
+                        },
+                        TransactionEvent::ScheduledPaymentFailed { reason, .. } => {
+                            panic!("Scheduled payment failed: {}", reason);
+                        },
+                        _ => (),
+                    }
+                },
+                () = &mut delay => {
+                    panic!("Scheduled payment should have been sent by now");
+                },
+            }
+        }
+    });
+
+    let completed_tx = runtime
+        .block_on(
+            alice_ts_interface
+                .transaction_service_handle
+                .get_completed_transaction(tx_id),
+        )
+        .unwrap();
+    assert_eq!(completed_tx.amount, 10000 * uT);
+
+    let payments = runtime
+        .block_on(alice_ts_interface.transaction_service_handle.get_scheduled_payments())
+        .unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].num_payments_made, 1);
+    assert_eq!(payments[0].last_tx_id, Some(tx_id));
+    assert_eq!(payments[0].schedule.remaining_payments, Some(1));
+    assert_eq!(payments[0].status, ScheduledPaymentStatus::Active);
+    assert!(payments[0].schedule.next_payment_at > Utc::now().naive_utc());
+
+    runtime
+        .block_on(
+            alice_ts_interface
+                .transaction_service_handle
+                .cancel_scheduled_payment(payment_id),
+        )
+        .unwrap();
+    let payments = runtime
+        .block_on(alice_ts_interface.transaction_service_handle.get_scheduled_payments())
+        .unwrap();
+    assert_eq!(payments[0].status, ScheduledPaymentStatus::Cancelled);
+    assert!(matches!(
+        runtime.block_on(
+            alice_ts_interface
+                .transaction_service_handle
+                .cancel_scheduled_payment(payment_id.wrapping_add(1))
+        ),
+        Err(TransactionServiceError::ScheduledPaymentNotFound(_))
+    ));
+}
+
 #[test]
 fn test_direct_vs_saf_send_of_tx_reply_and_finalize() {
     let factories = CryptoFactories::default();
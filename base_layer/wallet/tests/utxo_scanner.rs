@@ -520,6 +520,135 @@ async fn test_utxo_scanner_recovery_with_restart() {
     }
 }
 
+#[tokio::test]
+async fn test_utxo_scanner_recovery_resumes_from_checkpoint() {
+    let factories = CryptoFactories::default();
+    let mut test_interface = setup(UtxoScannerMode::Recovery, None, None, None).await;
+
+    let cipher_seed = CipherSeed::new();
+    let birthday_epoch_time = u64::from(cipher_seed.birthday() - 2) * 60 * 60 * 24;
+    test_interface.wallet_db.set_master_seed(cipher_seed).await.unwrap();
+
+    const NUM_BLOCKS: u64 = 11;
+    const BIRTHDAY_OFFSET: u64 = 5;
+
+    let TestBlockData {
+        block_headers,
+        unblinded_outputs,
+        utxos_by_block,
+    } = generate_block_headers_and_utxos(0, NUM_BLOCKS, birthday_epoch_time, BIRTHDAY_OFFSET, false).await;
+
+    test_interface
+        .rpc_service_state
+        .set_utxos_by_block(utxos_by_block.clone());
+    test_interface.rpc_service_state.set_blocks(block_headers.clone());
+
+    let chain_metadata = ChainMetadata {
+        height_of_longest_chain: Some(NUM_BLOCKS - 1),
+        best_block: Some(block_headers.get(&(NUM_BLOCKS - 1)).unwrap().clone().hash()),
+        accumulated_difficulty: Vec::new(),
+        pruned_height: 0,
+    };
+    test_interface.rpc_service_state.set_tip_info_response(TipInfoResponse {
+        metadata: Some(chain_metadata.clone()),
+        is_synced: true,
+    });
+
+    let mut db_unblinded_outputs = Vec::new();
+    let mut total_outputs_to_recover = 0;
+    let mut total_amount_to_recover = MicroTari::from(0);
+    for (h, outputs) in &unblinded_outputs {
+        for output in outputs.iter().skip(outputs.len() / 2) {
+            let dbo = DbUnblindedOutput::from_unblinded_output(output.clone(), &factories, None).unwrap();
+            if *h >= NUM_BLOCKS.saturating_sub(BIRTHDAY_OFFSET).saturating_sub(2) {
+                total_outputs_to_recover += 1;
+                total_amount_to_recover += dbo.unblinded_output.value;
+            }
+            db_unblinded_outputs.push(dbo);
+        }
+    }
+    test_interface
+        .oms_mock_state
+        .set_recoverable_outputs(db_unblinded_outputs.clone());
+
+    let mut scanner_event_stream = test_interface.scanner_handle.get_event_receiver();
+    tokio::spawn(test_interface.scanner_service.take().unwrap().run());
+
+    let delay = time::sleep(Duration::from_secs(60));
+    tokio::pin!(delay);
+    loop {
+        tokio::select! {
+            _ = &mut delay => {
+                panic!("Completed event should have arrived by now.");
+            }
+            event = scanner_event_stream.recv() => {
+                if let UtxoScannerEvent::Completed { .. } = event.unwrap() {
+                    break;
+                }
+            }
+        }
+    }
+    test_interface.shutdown_signal.trigger();
+
+    // Losing the scanned block cache, as happens when switching to a base node that does not share our recent
+    // headers, must not throw away the progress made
+    test_interface.wallet_db.clear_scanned_blocks().await.unwrap();
+
+    let mut test_interface2 = setup(UtxoScannerMode::Recovery, Some(test_interface.wallet_db), None, None).await;
+    test_interface2
+        .rpc_service_state
+        .set_utxos_by_block(utxos_by_block.clone());
+    test_interface2.rpc_service_state.set_blocks(block_headers.clone());
+    test_interface2
+        .rpc_service_state
+        .set_tip_info_response(TipInfoResponse {
+            metadata: Some(chain_metadata),
+            is_synced: true,
+        });
+    test_interface2
+        .oms_mock_state
+        .set_recoverable_outputs(db_unblinded_outputs);
+    let mut scanner_event_stream = test_interface2.scanner_handle.get_event_receiver();
+    tokio::spawn(test_interface2.scanner_service.take().unwrap().run());
+
+    let mut resumed = false;
+    let delay = time::sleep(Duration::from_secs(60));
+    tokio::pin!(delay);
+    loop {
+        tokio::select! {
+            _ = &mut delay => {
+                panic!("Completed event should have arrived by now.");
+            }
+            event = scanner_event_stream.recv() => {
+                match event.unwrap() {
+                    UtxoScannerEvent::ResumedFromCheckpoint { height, num_outputs_found } => {
+                        assert_eq!(height, NUM_BLOCKS - 1);
+                        assert_eq!(num_outputs_found, total_outputs_to_recover);
+                        resumed = true;
+                    },
+                    UtxoScannerEvent::Completed {
+                        final_height,
+                        num_recovered,
+                        value_recovered,
+                        time_taken: _,
+                    } => {
+                        assert_eq!(final_height, NUM_BLOCKS - 1);
+                        assert_eq!(num_recovered, total_outputs_to_recover);
+                        assert_eq!(value_recovered, total_amount_to_recover);
+                        break;
+                    },
+                    _ => {},
+                }
+            }
+        }
+    }
+    assert!(resumed);
+
+    // Nothing was rescanned
+    let requests = test_interface2.transaction_service_mock_state.drain_requests();
+    assert!(requests.is_empty());
+}
+
 #[tokio::test]
 async fn test_utxo_scanner_recovery_with_restart_and_reorg() {
     let factories = CryptoFactories::default();
@@ -166,6 +166,7 @@ async fn create_wallet(
         user_agent: "tari/test-wallet".to_string(),
         auxiliary_tcp_listener_address: None,
         rpc_max_simultaneous_sessions: 0,
+        ..Default::default()
     };
 
     let sql_database_path = comms_config
@@ -682,6 +683,7 @@ async fn test_import_utxo() {
             listener_address: "/ip4/127.0.0.1/tcp/0".parse().unwrap(),
             tor_socks_address: None,
             tor_socks_auth: Default::default(),
+            enable_port_mapping: false,
         }),
         datastore_path: temp_dir.path().to_path_buf(),
         peer_database_name: random::string(8),
@@ -695,6 +697,7 @@ async fn test_import_utxo() {
         user_agent: "tari/test-wallet".to_string(),
         auxiliary_tcp_listener_address: None,
         rpc_max_simultaneous_sessions: 0,
+        ..Default::default()
     };
     let config = WalletConfig {
         p2p: comms_config,
@@ -46,6 +46,9 @@
 //! `callback_faux_transaction_unconfirmed` - This will be called when a recovered output or one-sided transaction is
 //! freshly imported or when an imported transaction transitions from Imported to FauxUnconfirmed
 //!
+//! The faux transaction callbacks are rate limited according to the wallet's notification config. Bursts of events,
+//! e.g. during a wallet recovery, are delivered as a digest that only invokes the callback for the latest event.
+//!
 //! `callback_discovery_process_complete` - This will be called when a `send_transacion(..)` call is made to a peer
 //! whose address is not known and a discovery process must be conducted. The outcome of the discovery process is
 //! relayed via this callback
@@ -54,7 +57,11 @@
 //! request_key is used to identify which request this callback references and a result of true means it was successful
 //! and false that the process timed out and new one will be started
 
-use std::{ops::Deref, sync::Arc};
+use std::{
+    ops::Deref,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use log::*;
 use tari_common_types::transaction::TxId;
@@ -64,6 +71,7 @@ use tari_shutdown::ShutdownSignal;
 use tari_wallet::{
     connectivity_service::OnlineStatus,
     contacts_service::handle::{ContactsLivenessData, ContactsLivenessEvent},
+    notifications::{Notification, NotificationChannel, NotificationConfig, NotificationCoordinator},
     output_manager_service::{
         handle::{OutputManagerEvent, OutputManagerEventReceiver, OutputManagerHandle},
         service::Balance,
@@ -76,7 +84,10 @@ use tari_wallet::{
         },
     },
 };
-use tokio::sync::{broadcast, watch};
+use tokio::{
+    sync::{broadcast, watch},
+    time,
+};
 
 const LOG_TARGET: &str = "wallet::transaction_service::callback_handler";
 
@@ -109,6 +120,14 @@ where TBackend: TransactionBackend + 'static
     balance_cache: Balance,
     connectivity_status_watch: watch::Receiver<OnlineStatus>,
     contacts_liveness_events: broadcast::Receiver<Arc<ContactsLivenessEvent>>,
+    notifications: NotificationCoordinator<FauxTransactionNotification>,
+}
+
+/// Faux transaction events are rate limited, as a wallet recovery can produce hundreds of them in a short period
+#[derive(Debug, Clone, Copy)]
+enum FauxTransactionNotification {
+    Confirmed(TxId),
+    Unconfirmed(TxId, u64),
 }
 
 impl<TBackend> CallbackHandler<TBackend>
@@ -125,6 +144,7 @@ where TBackend: TransactionBackend + 'static
         comms_public_key: CommsPublicKey,
         connectivity_status_watch: watch::Receiver<OnlineStatus>,
         contacts_liveness_events: broadcast::Receiver<Arc<ContactsLivenessEvent>>,
+        notification_config: NotificationConfig,
         callback_received_transaction: unsafe extern "C" fn(*mut InboundTransaction),
         callback_received_transaction_reply: unsafe extern "C" fn(*mut CompletedTransaction),
         callback_received_finalized_transaction: unsafe extern "C" fn(*mut CompletedTransaction),
@@ -234,6 +254,7 @@ where TBackend: TransactionBackend + 'static
             balance_cache: Balance::zero(),
             connectivity_status_watch,
             contacts_liveness_events,
+            notifications: NotificationCoordinator::new(notification_config),
         }
     }
 
@@ -245,6 +266,9 @@ where TBackend: TransactionBackend + 'static
 
         info!(target: LOG_TARGET, "Transaction Service Callback Handler starting");
 
+        let mut digest_interval = time::interval(self.notifications.digest_window().max(Duration::from_secs(1)));
+        digest_interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
         loop {
             tokio::select! {
                 result = self.transaction_service_event_stream.recv() => {
@@ -285,12 +309,14 @@ where TBackend: TransactionBackend + 'static
                                     self.trigger_balance_refresh().await;
                                 },
                                 TransactionEvent::FauxTransactionConfirmed{tx_id, is_valid: _} => {
-                                    self.receive_faux_transaction_confirmed_event(tx_id).await;
-                                    self.trigger_balance_refresh().await;
+                                    self.submit_faux_transaction_notification(
+                                        FauxTransactionNotification::Confirmed(tx_id)
+                                    ).await;
                                 },
                                 TransactionEvent::FauxTransactionUnconfirmed{tx_id, num_confirmations, is_valid: _} => {
-                                    self.receive_faux_transaction_unconfirmed_event(tx_id, num_confirmations).await;
-                                    self.trigger_balance_refresh().await;
+                                    self.submit_faux_transaction_notification(
+                                        FauxTransactionNotification::Unconfirmed(tx_id, num_confirmations)
+                                    ).await;
                                 },
                                 TransactionEvent::TransactionValidationStateChanged(_request_key)  => {
                                     self.trigger_balance_refresh().await;
@@ -368,6 +394,9 @@ where TBackend: TransactionBackend + 'static
                         Err(broadcast::error::RecvError::Closed) => {}
                     }
                 }
+                _ = digest_interval.tick() => {
+                    self.deliver_faux_transaction_digests().await;
+                },
                  _ = shutdown_signal.wait() => {
                     info!(target: LOG_TARGET, "Transaction Callback Handler shutting down because the shutdown signal was received");
                     break;
@@ -557,6 +586,52 @@ where TBackend: TransactionBackend + 'static
         }
     }
 
+    async fn submit_faux_transaction_notification(&mut self, event: FauxTransactionNotification) {
+        match self
+            .notifications
+            .submit(NotificationChannel::FfiCallback, event, Instant::now())
+        {
+            Some(notification) => self.deliver_faux_transaction_notification(notification).await,
+            None => trace!(
+                target: LOG_TARGET,
+                "Faux transaction notification rate limited, holding {:?} for the next digest",
+                event
+            ),
+        }
+    }
+
+    async fn deliver_faux_transaction_digests(&mut self) {
+        for (_, notification) in self.notifications.poll_digests(Instant::now()) {
+            self.deliver_faux_transaction_notification(notification).await;
+        }
+    }
+
+    /// A digest only invokes the callback for its latest event, followed by a single balance update
+    async fn deliver_faux_transaction_notification(&mut self, notification: Notification<FauxTransactionNotification>) {
+        let latest = match notification.latest() {
+            Some(event) => *event,
+            None => return,
+        };
+        if let Notification::Digest(ref events) = notification {
+            debug!(
+                target: LOG_TARGET,
+                "Delivering digest of {} faux transaction events, latest: {:?}",
+                events.len(),
+                latest
+            );
+        }
+        match latest {
+            FauxTransactionNotification::Confirmed(tx_id) => {
+                self.receive_faux_transaction_confirmed_event(tx_id).await;
+            },
+            FauxTransactionNotification::Unconfirmed(tx_id, num_confirmations) => {
+                self.receive_faux_transaction_unconfirmed_event(tx_id, num_confirmations)
+                    .await;
+            },
+        }
+        self.trigger_balance_refresh().await;
+    }
+
     async fn receive_faux_transaction_confirmed_event(&mut self, tx_id: TxId) {
         match self.db.get_completed_transaction(tx_id).await {
             Ok(tx) => {
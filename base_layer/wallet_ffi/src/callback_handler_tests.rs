@@ -51,6 +51,7 @@ mod test {
             service::{ContactMessageType, ContactOnlineStatus},
             storage::database::Contact,
         },
+        notifications::NotificationConfig,
         output_manager_service::{
             handle::{OutputManagerEvent, OutputManagerHandle},
             service::Balance,
@@ -415,6 +416,7 @@ mod test {
             PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng)),
             connectivity_rx,
             contacts_liveness_events,
+            NotificationConfig::default(),
             received_tx_callback,
             received_tx_reply_callback,
             received_tx_finalized_callback,
@@ -259,6 +259,14 @@ impl From<WalletError> for LibWalletError {
                 code: 404,
                 message: format!("{:?}", w),
             },
+            WalletError::ContactsServiceError(ContactsServiceError::InvalidAlias(_)) => Self {
+                code: 405,
+                message: format!("{:?}", w),
+            },
+            WalletError::ContactsServiceError(ContactsServiceError::DuplicateAlias(_)) => Self {
+                code: 406,
+                message: format!("{:?}", w),
+            },
             // Wallet Encryption Errors
             WalletError::WalletStorageError(WalletStorageError::InvalidEncryptionCipher) => Self {
                 code: 420,
@@ -726,6 +726,81 @@ pub unsafe extern "C" fn emoji_id_to_public_key(emoji: *const c_char, error_out:
 
 /// -------------------------------------------------------------------------------------------- ///
 
+/// -------------------------------- MicroTari ------------------------------------------------- ///
+
+/// Converts an amount in MicroTari to a fixed-point Tari string with exactly 6 decimal places e.g. 1234567 is
+/// converted to "1.234567"
+///
+/// ## Arguments
+/// `amount` - The amount in MicroTari
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `*mut c_char` - Returns a pointer to a char array. Note that it returns empty if the amount is too large to be
+/// passed across the FFI
+///
+/// # Safety
+/// The ```string_destroy``` method must be called when finished with a string from rust to prevent a memory leak
+#[no_mangle]
+pub unsafe extern "C" fn micro_tari_to_fixed_point_string(amount: c_ulonglong, error_out: *mut c_int) -> *mut c_char {
+    let mut error = 0;
+    let mut result = CString::new("").expect("Blank CString will not fail.");
+    ptr::swap(error_out, &mut error as *mut c_int);
+    match MicroTari::try_from_ffi_u64(amount) {
+        Ok(amount) => {
+            result = CString::new(amount.to_fixed_point_string()).expect("Fixed-point string will not fail.");
+        },
+        Err(e) => {
+            error = LibWalletError::from(InterfaceError::InvalidArgument(e.to_string())).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+        },
+    }
+    CString::into_raw(result)
+}
+
+/// Parses a fixed-point Tari string with at most 6 decimal places (e.g. "1.234567") into an amount in MicroTari
+///
+/// ## Arguments
+/// `amount` - The pointer to a char array containing the fixed-point amount
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may not be null. Functions
+/// as an out parameter.
+///
+/// ## Returns
+/// `c_ulonglong` - Returns the amount in MicroTari. Note that it returns 0 on error.
+///
+/// # Safety
+/// None
+#[no_mangle]
+pub unsafe extern "C" fn micro_tari_from_fixed_point_string(
+    amount: *const c_char,
+    error_out: *mut c_int,
+) -> c_ulonglong {
+    let mut error = 0;
+    ptr::swap(error_out, &mut error as *mut c_int);
+    if amount.is_null() {
+        error = LibWalletError::from(InterfaceError::NullError("amount".to_string())).code;
+        ptr::swap(error_out, &mut error as *mut c_int);
+        return 0;
+    }
+
+    let result = CStr::from_ptr(amount)
+        .to_str()
+        .map_err(|e| e.to_string())
+        .and_then(|s| MicroTari::from_fixed_point_str(s).map_err(|e| e.to_string()))
+        .and_then(|v| MicroTari::try_from_ffi_u64(v.to_ffi_u64()).map_err(|e| e.to_string()));
+    match result {
+        Ok(v) => v.to_ffi_u64(),
+        Err(e) => {
+            error = LibWalletError::from(InterfaceError::InvalidArgument(e)).code;
+            ptr::swap(error_out, &mut error as *mut c_int);
+            0
+        },
+    }
+}
+
+/// -------------------------------------------------------------------------------------------- ///
+
 /// -------------------------------- Private Key ----------------------------------------------- ///
 
 /// Creates a TariPrivateKey from a ByteVector
@@ -2351,6 +2426,7 @@ pub unsafe extern "C" fn completed_transaction_get_confirmations(
 /// |   5 | TimeLocked          |
 /// |   6 | InvalidTransaction  |
 /// |   7 | AbandonedCoinbase   |
+/// |   8 | FeeBumped           |
 /// # Safety
 /// None
 #[no_mangle]
@@ -3332,6 +3408,7 @@ pub unsafe extern "C" fn comms_config_create(
                 listener_liveness_max_sessions: 0,
                 user_agent: format!("tari/mobile_wallet/{}", env!("CARGO_PKG_VERSION")),
                 rpc_max_simultaneous_sessions: 0,
+                ..Default::default()
             };
 
             Box::into_raw(Box::new(config))
@@ -3783,6 +3860,7 @@ pub unsafe extern "C" fn wallet_create(
         ..Default::default()
     };
 
+    let notification_config = wallet_config.notifications.clone();
     let w = runtime.block_on(Wallet::start(
         wallet_config,
         peer_seeds,
@@ -3816,6 +3894,7 @@ pub unsafe extern "C" fn wallet_create(
                 w.comms.node_identity().public_key().clone(),
                 w.wallet_connectivity.get_connectivity_status_watch(),
                 w.contacts_service.get_contacts_liveness_event_stream(),
+                notification_config,
                 callback_received_transaction,
                 callback_received_transaction_reply,
                 callback_received_finalized_transaction,
@@ -6716,6 +6795,30 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_micro_tari_fixed_point_string() {
+        unsafe {
+            let mut error = 0;
+            let error_ptr = &mut error as *mut c_int;
+            let s = micro_tari_to_fixed_point_string(1_234_567, error_ptr);
+            assert_eq!(error, 0);
+            assert_eq!(CStr::from_ptr(s).to_str().unwrap(), "1.234567");
+            let amount = micro_tari_from_fixed_point_string(s, error_ptr);
+            assert_eq!(error, 0);
+            assert_eq!(amount, 1_234_567);
+            string_destroy(s);
+
+            let s = micro_tari_to_fixed_point_string(u64::MAX, error_ptr);
+            assert_eq!(error, 7);
+            string_destroy(s);
+
+            let invalid = CString::new("1.2 T").unwrap();
+            let amount = micro_tari_from_fixed_point_string(invalid.as_ptr(), error_ptr);
+            assert_eq!(error, 7);
+            assert_eq!(amount, 0);
+        }
+    }
+
     #[test]
     fn test_emoji_set() {
         unsafe {
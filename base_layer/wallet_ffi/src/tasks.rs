@@ -86,9 +86,28 @@ pub async fn recovery_event_monitoring(
                     error
                 );
             },
+            Ok(UtxoScannerEvent::ResumedFromCheckpoint {
+                height,
+                num_outputs_found,
+            }) => {
+                info!(
+                    target: LOG_TARGET,
+                    "Recovery resuming from checkpoint at height {} ({} outputs found so far)",
+                    height,
+                    num_outputs_found
+                );
+            },
+            Ok(UtxoScannerEvent::RestartedFromBirthday { checkpoint_height }) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Recovery checkpoint at height {} not found on base node, restarting from wallet birthday",
+                    checkpoint_height
+                );
+            },
             Ok(UtxoScannerEvent::Progress {
                 current_height: current,
                 tip_height: total,
+                ..
             }) => {
                 unsafe {
                     (recovery_progress_callback)(RecoveryEvent::Progress as u8, current, total);
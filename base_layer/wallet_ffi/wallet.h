@@ -158,6 +158,14 @@ char *public_key_to_emoji_id(struct TariPublicKey *pk, int *error_out);
 // Converts a char array in emoji format to a public key
 struct TariPublicKey *emoji_id_to_public_key(const char *emoji, int *error_out);
 
+/// -------------------------------- MicroTari ----------------------------------------------- ///
+
+// Converts an amount in MicroTari to a fixed-point Tari string with 6 decimal places e.g. "1.234567"
+char *micro_tari_to_fixed_point_string(unsigned long long amount, int *error_out);
+
+// Parses a fixed-point Tari string with at most 6 decimal places into an amount in MicroTari
+unsigned long long micro_tari_from_fixed_point_string(const char *amount, int *error_out);
+
 
 /// -------------------------------- TariPrivateKey ----------------------------------------------- ///
 
@@ -318,6 +326,7 @@ struct TariTransactionKernel *completed_transaction_get_transaction_kernel(struc
 /// |   5 | TimeLocked          |
 /// |   6 | InvalidTransaction  |
 /// |   7 | AbandonedCoinbase   |
+/// |   8 | FeeBumped           |
 /// # Safety
 /// None
 int completed_transaction_get_cancellation_reason(struct TariCompletedTransaction *transaction, int *error_out);
@@ -0,0 +1,117 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Optional transport security and authentication for an application's gRPC server. Both are off by default, which
+/// is only safe while the server listens on localhost.
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GrpcSecurityConfig {
+    /// Serve gRPC over TLS
+    pub tls: Option<GrpcTlsConfig>,
+    /// When set, every request must carry an `authorization: Bearer <api_token>` header
+    pub api_token: Option<String>,
+}
+
+impl GrpcSecurityConfig {
+    pub fn set_base_path<P: AsRef<Path>>(&mut self, base_path: P) {
+        if let Some(tls) = self.tls.as_mut() {
+            tls.set_base_path(base_path);
+        }
+    }
+}
+
+impl fmt::Debug for GrpcSecurityConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GrpcSecurityConfig")
+            .field("tls", &self.tls)
+            .field("api_token", &self.api_token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GrpcTlsConfig {
+    /// PEM encoded server certificate chain
+    pub cert_file: PathBuf,
+    /// PEM encoded private key for the server certificate
+    pub key_file: PathBuf,
+    /// PEM encoded CA certificate. When set, clients must present a certificate signed by this CA.
+    pub client_ca_file: Option<PathBuf>,
+}
+
+impl GrpcTlsConfig {
+    pub fn set_base_path<P: AsRef<Path>>(&mut self, base_path: P) {
+        let base_path = base_path.as_ref();
+        if !self.cert_file.is_absolute() {
+            self.cert_file = base_path.join(self.cert_file.as_path());
+        }
+        if !self.key_file.is_absolute() {
+            self.key_file = base_path.join(self.key_file.as_path());
+        }
+        if let Some(client_ca_file) = self.client_ca_file.as_mut() {
+            if !client_ca_file.is_absolute() {
+                *client_ca_file = base_path.join(client_ca_file.as_path());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_redacts_the_api_token() {
+        let config = GrpcSecurityConfig {
+            tls: None,
+            api_token: Some("secret".to_string()),
+        };
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("secret"));
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[test]
+    fn it_resolves_relative_tls_paths() {
+        let mut config = GrpcSecurityConfig {
+            tls: Some(GrpcTlsConfig {
+                cert_file: PathBuf::from("config/grpc.crt"),
+                key_file: PathBuf::from("/etc/tari/grpc.key"),
+                client_ca_file: Some(PathBuf::from("config/ca.crt")),
+            }),
+            api_token: None,
+        };
+        config.set_base_path("/home/tari");
+        let tls = config.tls.unwrap();
+        assert_eq!(tls.cert_file, PathBuf::from("/home/tari/config/grpc.crt"));
+        assert_eq!(tls.key_file, PathBuf::from("/etc/tari/grpc.key"));
+        assert_eq!(tls.client_ca_file, Some(PathBuf::from("/home/tari/config/ca.crt")));
+    }
+}
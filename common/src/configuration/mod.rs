@@ -39,6 +39,8 @@
 
 pub mod bootstrap;
 pub mod error;
+mod grpc_security;
+pub use grpc_security::{GrpcSecurityConfig, GrpcTlsConfig};
 pub mod loader;
 mod network;
 pub use network::Network;
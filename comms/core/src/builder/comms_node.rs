@@ -30,6 +30,8 @@ use tokio::{
 };
 
 use super::{CommsBuilderError, CommsShutdown};
+#[cfg(feature = "port-mapping")]
+use crate::port_mapping::{PortMappingConfig, PortMappingService};
 use crate::{
     connection_manager::{
         ConnectionManager,
@@ -125,6 +127,14 @@ impl UnspawnedCommsNode {
         self
     }
 
+    /// Forward the listener port on the local gateway using UPnP or NAT-PMP. This is an alias to
+    /// `CommsBuilder::with_port_mapping`.
+    #[cfg(feature = "port-mapping")]
+    pub fn with_port_mapping(mut self, config: PortMappingConfig) -> Self {
+        self.builder = self.builder.with_port_mapping(config);
+        self
+    }
+
     /// Spawn a new node using the specified [Transport](crate::transports::Transport).
     pub async fn spawn_with_transport<TTransport>(self, transport: TTransport) -> Result<CommsNode, CommsBuilderError>
     where
@@ -149,6 +159,8 @@ impl UnspawnedCommsNode {
             hidden_service_ctl,
             connection_manager_config,
             connectivity_config,
+            #[cfg(feature = "port-mapping")]
+            port_mapping_config,
             ..
         } = builder;
 
@@ -178,7 +190,8 @@ impl UnspawnedCommsNode {
 
         //---------------------------------- Connection Manager --------------------------------------------//
 
-        let noise_config = NoiseConfig::new(node_identity.clone());
+        let noise_config =
+            NoiseConfig::new(node_identity.clone()).with_rekey_policy(connection_manager_config.noise_rekey_policy);
 
         let mut connection_manager = ConnectionManager::new(
             connection_manager_config,
@@ -213,6 +226,9 @@ impl UnspawnedCommsNode {
         );
 
         let listening_info = connection_manager_requester.wait_until_listening().await?;
+        if !listening_info.advertised_addresses().is_empty() {
+            node_identity.set_additional_addresses(listening_info.advertised_addresses().to_vec());
+        }
         let mut hidden_service = None;
         if let Some(mut ctl) = hidden_service_ctl {
             ctl.set_proxied_addr(listening_info.bind_address());
@@ -223,11 +239,37 @@ impl UnspawnedCommsNode {
             }
             hidden_service = Some(hs);
         }
+
+        #[cfg(feature = "port-mapping")]
+        if let Some(config) = port_mapping_config {
+            match PortMappingService::new(
+                config,
+                listening_info.bind_address(),
+                node_identity.clone(),
+                shutdown_signal.clone(),
+            ) {
+                Ok(mut port_mapping) => {
+                    if let Err(err) = port_mapping.map_port().await {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Automatic port mapping failed: {}. Peers will not be able to connect to this node if it \
+                             is behind a NAT and the port is not forwarded manually.",
+                            err
+                        );
+                    }
+                    port_mapping.spawn();
+                },
+                Err(err) => warn!(target: LOG_TARGET, "Automatic port mapping is unavailable: {}", err),
+            }
+        }
         info!(
             target: LOG_TARGET,
             "Your node's public address is '{}'",
             node_identity.public_address()
         );
+        for address in node_identity.additional_addresses() {
+            info!(target: LOG_TARGET, "Your node is also reachable at '{}'", address);
+        }
 
         Ok(CommsNode {
             shutdown_signal,
@@ -40,14 +40,18 @@ use std::{fs::File, sync::Arc, time::Duration};
 use tari_shutdown::ShutdownSignal;
 use tokio::sync::{broadcast, mpsc};
 
+#[cfg(feature = "port-mapping")]
+use crate::port_mapping::PortMappingConfig;
 use crate::{
     backoff::{Backoff, BoxedBackoff, ConstantBackoff},
-    connection_manager::{ConnectionManagerConfig, ConnectionManagerRequester},
+    connection_manager::{ConnectionManagerConfig, ConnectionManagerRequester, ListenerConfig},
     connectivity::{ConnectivityConfig, ConnectivityRequester},
     multiaddr::Multiaddr,
+    noise::RekeyPolicy,
     peer_manager::{NodeIdentity, PeerManager},
     protocol::{NodeNetworkInfo, ProtocolExtensions},
     tor,
+    transports::TransportKind,
     types::CommsDatabase,
 };
 
@@ -121,6 +125,8 @@ pub struct CommsBuilder {
     node_identity: Option<Arc<NodeIdentity>>,
     dial_backoff: BoxedBackoff,
     hidden_service_ctl: Option<tor::HiddenServiceController>,
+    #[cfg(feature = "port-mapping")]
+    port_mapping_config: Option<PortMappingConfig>,
     connection_manager_config: ConnectionManagerConfig,
     connectivity_config: ConnectivityConfig,
 
@@ -135,6 +141,8 @@ impl Default for CommsBuilder {
             node_identity: None,
             dial_backoff: Box::new(ConstantBackoff::new(Duration::from_millis(500))),
             hidden_service_ctl: None,
+            #[cfg(feature = "port-mapping")]
+            port_mapping_config: None,
             connection_manager_config: ConnectionManagerConfig::default(),
             connectivity_config: ConnectivityConfig::default(),
             shutdown_signal: None,
@@ -210,6 +218,44 @@ impl CommsBuilder {
         self
     }
 
+    /// Sets a WebSocket listener address (e.g. `/ip4/0.0.0.0/tcp/18190/ws`) that can accept peer connections from
+    /// clients that cannot use TCP directly, such as browser-based wallets. This is optional.
+    #[cfg(feature = "websocket")]
+    pub fn with_websocket_listener_address(mut self, listener_address: Multiaddr) -> Self {
+        self.connection_manager_config.websocket_listener_address = Some(listener_address);
+        self
+    }
+
+    /// Adds a listener with its own transport configuration, in addition to the main listener. Listeners that are
+    /// marked as advertised are included in the node's public addresses. This can be called more than once.
+    pub fn with_additional_listener(mut self, listener: ListenerConfig) -> Self {
+        self.connection_manager_config.additional_listeners.push(listener);
+        self
+    }
+
+    /// Sets the order of transports to use when dialing a peer with more than one address, e.g. to prefer a direct
+    /// TCP connection over an onion address. Addresses using a transport that is not listed are tried last.
+    pub fn with_preferred_transports(mut self, preferred_transports: Vec<TransportKind>) -> Self {
+        self.connection_manager_config.preferred_transports = preferred_transports;
+        self
+    }
+
+    /// Sets when the noise session keys of established connections are rotated, bounding the amount of traffic that is
+    /// exposed if a session key is compromised. Keys are only rotated on connections to peers that support it.
+    pub fn with_noise_rekey_policy(mut self, policy: RekeyPolicy) -> Self {
+        self.connection_manager_config.noise_rekey_policy = policy;
+        self
+    }
+
+    /// Attempt to forward the listener port on the local gateway using UPnP or NAT-PMP when the node is spawned. On
+    /// success, the node's public address is set to the mapped external address. Only `/ip4/<address>/tcp/<port>`
+    /// listener addresses are supported.
+    #[cfg(feature = "port-mapping")]
+    pub fn with_port_mapping(mut self, config: PortMappingConfig) -> Self {
+        self.port_mapping_config = Some(config);
+        self
+    }
+
     /// Sets the maximum allowed liveness sessions. Liveness is typically used by tools like docker or kubernetes to
     /// detect that the node is live. Defaults to 0 (disabled)
     pub fn with_listener_liveness_max_sessions(mut self, max_sessions: usize) -> Self {
@@ -242,6 +288,19 @@ impl CommsBuilder {
         self
     }
 
+    /// The minimum age of an inactive connection before it can be reaped. Lower values rotate idle peers out of the
+    /// connection pool more aggressively.
+    pub fn with_reaper_min_inactive_age(mut self, reaper_min_inactive_age: Duration) -> Self {
+        self.connectivity_config.reaper_min_inactive_age = reaper_min_inactive_age;
+        self
+    }
+
+    /// Sets the interval at which the connection pool is refreshed, including reaping inactive connections.
+    pub fn with_connection_pool_refresh_interval(mut self, interval: Duration) -> Self {
+        self.connectivity_config.connection_pool_refresh_interval = interval;
+        self
+    }
+
     /// Call to disable connection reaping. Usually you would want to have this enabled, however there are some test
     /// cases where disabling this is desirable.
     pub fn disable_connection_reaping(mut self) -> Self {
@@ -222,12 +222,7 @@ fn validate_address(addr: &Multiaddr, allow_test_addrs: bool) -> Result<(), Conn
 
     match proto {
         Protocol::Dns4(_) | Protocol::Dns6(_) | Protocol::Dnsaddr(_) => {
-            let tcp = addr_iter.next().ok_or_else(|| {
-                ConnectionManagerError::InvalidMultiaddr("Address does not include a TCP port".to_string())
-            })?;
-
-            validate_tcp_port(tcp)?;
-
+            validate_transport_port(&mut addr_iter)?;
             expect_end_of_address(addr_iter)
         },
 
@@ -246,11 +241,7 @@ fn validate_address(addr: &Multiaddr, allow_test_addrs: bool) -> Result<(), Conn
             ))
         },
         Protocol::Ip4(_) | Protocol::Ip6(_) => {
-            let tcp = addr_iter.next().ok_or_else(|| {
-                ConnectionManagerError::InvalidMultiaddr("Address does not include a TCP port".to_string())
-            })?;
-
-            validate_tcp_port(tcp)?;
+            validate_transport_port(&mut addr_iter)?;
             expect_end_of_address(addr_iter)
         },
         Protocol::Memory(0) => Err(ConnectionManagerError::InvalidMultiaddr(
@@ -275,6 +266,36 @@ fn validate_address(addr: &Multiaddr, allow_test_addrs: bool) -> Result<(), Conn
     }
 }
 
+/// Validates the transport component following the network address, one of `/tcp/<port>`, `/tcp/<port>/ws` or
+/// `/udp/<port>/quic`
+fn validate_transport_port(addr_iter: &mut multiaddr::Iter<'_>) -> Result<(), ConnectionManagerError> {
+    let transport = addr_iter.next().ok_or_else(|| {
+        ConnectionManagerError::InvalidMultiaddr("Address does not include a TCP or UDP port".to_string())
+    })?;
+
+    match transport {
+        Protocol::Udp(0) => Err(ConnectionManagerError::InvalidMultiaddr(
+            "Cannot connect to a zero UDP port".to_string(),
+        )),
+        Protocol::Udp(_) => match addr_iter.next() {
+            Some(Protocol::Quic) => Ok(()),
+            _ => Err(ConnectionManagerError::InvalidMultiaddr(
+                "UDP addresses must use the QUIC protocol".to_string(),
+            )),
+        },
+        p => {
+            validate_tcp_port(p)?;
+            match addr_iter.next() {
+                None | Some(Protocol::Ws(_)) => Ok(()),
+                Some(p) => Err(ConnectionManagerError::InvalidMultiaddr(format!(
+                    "Unexpected multiaddress component '{}'",
+                    p
+                ))),
+            }
+        },
+    }
+}
+
 fn validate_tcp_port(expected_tcp: Protocol) -> Result<(), ConnectionManagerError> {
     match expected_tcp {
         Protocol::Tcp(0) => Err(ConnectionManagerError::InvalidMultiaddr(
@@ -304,10 +325,19 @@ mod test {
                 .parse()
                 .unwrap(),
             multiaddr!(Dnsaddr("mike-magic-nodes.com"), Tcp(1u16)),
+            multiaddr!(Ip4([172, 0, 0, 1]), Udp(1u16), Quic),
+            multiaddr!(Dnsaddr("mike-magic-nodes.com"), Udp(1u16), Quic),
+            "/ip4/172.0.0.1/tcp/1/ws".parse().unwrap(),
+            "/dns4/mike-magic-nodes.com/tcp/1/ws".parse().unwrap(),
         ];
 
         let invalid = &[
             multiaddr!(Ip4([127, 0, 0, 1]), Tcp(1u16)),
+            multiaddr!(Ip4([127, 0, 0, 1]), Udp(1u16), Quic),
+            multiaddr!(Ip4([172, 0, 0, 1]), Udp(1u16)),
+            multiaddr!(Ip4([172, 0, 0, 1]), Udp(0u16), Quic),
+            "/ip4/172.0.0.1/tcp/0/ws".parse().unwrap(),
+            "/ip4/172.0.0.1/tcp/1/ws/tcp/2".parse().unwrap(),
             multiaddr!(Ip4([169, 254, 0, 1]), Tcp(1u16)),
             multiaddr!(Ip4([172, 0, 0, 1])),
             "/onion/aaimaq4ygg2iegci:1234/http".parse().unwrap(),
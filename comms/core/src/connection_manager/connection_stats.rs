@@ -0,0 +1,173 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    collections::HashSet,
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+        Mutex,
+    },
+    task::{Context, Poll},
+};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::protocol::ProtocolId;
+
+/// Traffic and protocol statistics for a single peer connection. This is cheap to clone and all clones share the same
+/// statistics.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionStats {
+    inner: Arc<ConnectionStatsInner>,
+}
+
+#[derive(Debug, Default)]
+struct ConnectionStatsInner {
+    bytes_read: AtomicU64,
+    bytes_written: AtomicU64,
+    protocols: Mutex<HashSet<ProtocolId>>,
+}
+
+impl ConnectionStats {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The number of bytes read from the underlying socket
+    pub fn bytes_read(&self) -> u64 {
+        self.inner.bytes_read.load(Ordering::Relaxed)
+    }
+
+    /// The number of bytes written to the underlying socket
+    pub fn bytes_written(&self) -> u64 {
+        self.inner.bytes_written.load(Ordering::Relaxed)
+    }
+
+    /// The protocols that have been negotiated on this connection
+    pub fn protocols(&self) -> Vec<ProtocolId> {
+        let mut protocols = match self.inner.protocols.lock() {
+            Ok(protocols) => protocols.iter().cloned().collect::<Vec<_>>(),
+            Err(_) => return Vec::new(),
+        };
+        protocols.sort();
+        protocols
+    }
+
+    pub(crate) fn add_protocol(&self, protocol: &ProtocolId) {
+        if let Ok(mut protocols) = self.inner.protocols.lock() {
+            if !protocols.contains(protocol) {
+                protocols.insert(protocol.clone());
+            }
+        }
+    }
+
+    fn add_bytes_read(&self, n: usize) {
+        self.inner.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    fn add_bytes_written(&self, n: usize) {
+        self.inner.bytes_written.fetch_add(n as u64, Ordering::Relaxed);
+    }
+}
+
+/// A socket wrapper that counts the bytes read from and written to the inner socket
+pub(crate) struct CountedSocket<TSocket> {
+    socket: TSocket,
+    stats: ConnectionStats,
+}
+
+impl<TSocket> CountedSocket<TSocket> {
+    pub fn new(socket: TSocket, stats: ConnectionStats) -> Self {
+        Self { socket, stats }
+    }
+}
+
+impl<TSocket: AsyncRead + Unpin> AsyncRead for CountedSocket<TSocket> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled_before = buf.filled().len();
+        let result = Pin::new(&mut this.socket).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            this.stats.add_bytes_read(buf.filled().len() - filled_before);
+        }
+        result
+    }
+}
+
+impl<TSocket: AsyncWrite + Unpin> AsyncWrite for CountedSocket<TSocket> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let result = Pin::new(&mut this.socket).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = result {
+            this.stats.add_bytes_written(n);
+        }
+        result
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().socket).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().socket).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::memsocket::MemorySocket;
+
+    #[tokio::test]
+    async fn it_counts_bytes_read_and_written() {
+        let (a, mut b) = MemorySocket::new_pair();
+        let stats = ConnectionStats::new();
+        let mut socket = CountedSocket::new(a, stats.clone());
+        socket.write_all(b"hello").await.unwrap();
+        socket.flush().await.unwrap();
+        let mut buf = [0u8; 5];
+        b.read_exact(&mut buf).await.unwrap();
+        b.write_all(b"tari").await.unwrap();
+        b.flush().await.unwrap();
+        let mut buf = [0u8; 4];
+        socket.read_exact(&mut buf).await.unwrap();
+        assert_eq!(stats.bytes_written(), 5);
+        assert_eq!(stats.bytes_read(), 4);
+    }
+
+    #[test]
+    fn it_records_protocols() {
+        let stats = ConnectionStats::new();
+        stats.add_protocol(&ProtocolId::from_static(b"t/rpc/1"));
+        stats.add_protocol(&ProtocolId::from_static(b"t/msg/1"));
+        stats.add_protocol(&ProtocolId::from_static(b"t/rpc/1"));
+        assert_eq!(stats.protocols(), vec![
+            ProtocolId::from_static(b"t/msg/1"),
+            ProtocolId::from_static(b"t/rpc/1")
+        ]);
+    }
+}
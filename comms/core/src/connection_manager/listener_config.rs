@@ -0,0 +1,89 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt;
+
+use multiaddr::Multiaddr;
+
+#[cfg(feature = "websocket")]
+use crate::transports::WebSocketTransport;
+use crate::transports::{MemoryTransport, TcpTransport};
+
+/// Configuration for an additional listener. Each listener binds with its own transport, so that a node can, for
+/// example, accept TCP connections on a public address while also accepting connections from local processes.
+#[derive(Debug, Clone)]
+pub struct ListenerConfig {
+    /// The address to bind on. This address must be supported by the transport.
+    pub bind_address: Multiaddr,
+    /// The transport (and its configuration) used to accept connections on this listener
+    pub transport: ListenerTransport,
+    /// If true, this listener's address is advertised to peers as one of the node's public addresses
+    pub advertise: bool,
+    /// The address to advertise for this listener if it differs from the bind address, e.g. an onion address for a
+    /// hidden service that proxies to `bind_address`, or an externally forwarded address.
+    /// Default: None (the bound address is advertised)
+    pub public_address: Option<Multiaddr>,
+}
+
+impl ListenerConfig {
+    pub fn new(bind_address: Multiaddr, transport: ListenerTransport) -> Self {
+        Self {
+            bind_address,
+            transport,
+            advertise: false,
+            public_address: None,
+        }
+    }
+
+    /// Advertise this listener's bound address (or `public_address` if set) to peers
+    pub fn advertised(mut self) -> Self {
+        self.advertise = true;
+        self
+    }
+
+    pub fn with_public_address(mut self, public_address: Multiaddr) -> Self {
+        self.public_address = Some(public_address);
+        self.advertise = true;
+        self
+    }
+}
+
+/// The transport used by an additional listener
+#[derive(Clone)]
+pub enum ListenerTransport {
+    Tcp(TcpTransport),
+    #[cfg(feature = "websocket")]
+    WebSocket(WebSocketTransport),
+    /// In-process connections, e.g. from an embedded wallet
+    Memory(MemoryTransport),
+}
+
+impl fmt::Debug for ListenerTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListenerTransport::Tcp(_) => write!(f, "Tcp"),
+            #[cfg(feature = "websocket")]
+            ListenerTransport::WebSocket(_) => write!(f, "WebSocket"),
+            ListenerTransport::Memory(_) => write!(f, "Memory"),
+        }
+    }
+}
@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{fmt, sync::Arc};
+use std::{fmt, mem, sync::Arc};
 
 use log::*;
 use multiaddr::Multiaddr;
@@ -38,17 +38,20 @@ use super::{
     dialer::{Dialer, DialerRequest},
     error::ConnectionManagerError,
     listener::PeerListener,
+    listener_config::{ListenerConfig, ListenerTransport},
     peer_connection::PeerConnection,
     requester::ConnectionManagerRequest,
 };
+#[cfg(feature = "websocket")]
+use crate::transports::WebSocketTransport;
 use crate::{
     backoff::Backoff,
     connection_manager::{metrics, ConnectionDirection, ConnectionId},
     multiplexing::Substream,
-    noise::NoiseConfig,
+    noise::{NoiseConfig, RekeyPolicy},
     peer_manager::{NodeId, NodeIdentity, PeerManagerError},
     protocol::{NodeNetworkInfo, ProtocolEvent, ProtocolId, Protocols},
-    transports::{TcpTransport, Transport},
+    transports::{MemoryTransport, TcpTransport, Transport, TransportKind},
     PeerManager,
 };
 
@@ -114,6 +117,22 @@ pub struct ConnectionManagerConfig {
     /// If set, an additional TCP-only p2p listener will be started. This is useful for local wallet connections.
     /// Default: None (disabled)
     pub auxiliary_tcp_listener_address: Option<Multiaddr>,
+    /// If set, an additional WebSocket p2p listener will be started (e.g. `/ip4/0.0.0.0/tcp/18190/ws`). This allows
+    /// browser-based clients to connect.
+    /// Default: None (disabled)
+    #[cfg(feature = "websocket")]
+    pub websocket_listener_address: Option<Multiaddr>,
+    /// Additional listeners to start, each with its own transport configuration.
+    /// Default: empty
+    pub additional_listeners: Vec<ListenerConfig>,
+    /// When dialing a peer, its addresses are tried in this transport order. Addresses using a transport that is not
+    /// listed are tried last.
+    /// Default: empty (addresses are tried in the order of the peer's address stats)
+    pub preferred_transports: Vec<TransportKind>,
+    /// Determines when the session keys of long-lived connections are rotated. Keys are only rotated on connections to
+    /// peers that support it.
+    /// Default: disabled
+    pub noise_rekey_policy: RekeyPolicy,
 }
 
 impl Default for ConnectionManagerConfig {
@@ -137,6 +156,11 @@ impl Default for ConnectionManagerConfig {
             time_to_first_byte: Duration::from_secs(45),
             liveness_cidr_allowlist: vec![cidr::AnyIpCidr::V4("127.0.0.1/32".parse().unwrap())],
             auxiliary_tcp_listener_address: None,
+            #[cfg(feature = "websocket")]
+            websocket_listener_address: None,
+            additional_listeners: Vec::new(),
+            preferred_transports: Vec::new(),
+            noise_rekey_policy: RekeyPolicy::disabled(),
         }
     }
 }
@@ -146,6 +170,10 @@ impl Default for ConnectionManagerConfig {
 pub struct ListenerInfo {
     bind_address: Multiaddr,
     aux_bind_address: Option<Multiaddr>,
+    #[cfg(feature = "websocket")]
+    websocket_bind_address: Option<Multiaddr>,
+    additional_bind_addresses: Vec<Multiaddr>,
+    advertised_addresses: Vec<Multiaddr>,
 }
 
 impl ListenerInfo {
@@ -159,6 +187,56 @@ impl ListenerInfo {
     pub fn auxiliary_bind_address(&self) -> Option<&Multiaddr> {
         self.aux_bind_address.as_ref()
     }
+
+    /// The WebSocket address that was bound on if enabled.
+    #[cfg(feature = "websocket")]
+    pub fn websocket_bind_address(&self) -> Option<&Multiaddr> {
+        self.websocket_bind_address.as_ref()
+    }
+
+    /// The addresses that the additional listeners were bound on, in the order they were configured.
+    pub fn additional_bind_addresses(&self) -> &[Multiaddr] {
+        &self.additional_bind_addresses
+    }
+
+    /// The addresses of additional listeners that should be advertised to peers.
+    pub fn advertised_addresses(&self) -> &[Multiaddr] {
+        &self.advertised_addresses
+    }
+}
+
+/// An additional listener that has not yet been bound
+struct AdditionalListener {
+    listener: AdditionalPeerListener,
+    advertise: bool,
+    public_address: Option<Multiaddr>,
+}
+
+enum AdditionalPeerListener {
+    Tcp(PeerListener<TcpTransport>),
+    #[cfg(feature = "websocket")]
+    WebSocket(PeerListener<WebSocketTransport>),
+    Memory(PeerListener<MemoryTransport>),
+}
+
+impl AdditionalPeerListener {
+    async fn listen(self, supported_protocols: Vec<ProtocolId>) -> Result<Multiaddr, ConnectionManagerError> {
+        match self {
+            AdditionalPeerListener::Tcp(mut listener) => {
+                listener.set_supported_protocols(supported_protocols);
+                listener.listen().await
+            },
+            #[cfg(feature = "websocket")]
+            AdditionalPeerListener::WebSocket(mut listener) => {
+                listener.set_supported_protocols(supported_protocols);
+                listener.listen().await
+            },
+            AdditionalPeerListener::Memory(mut listener) => {
+                listener.set_supported_protocols(supported_protocols);
+                listener.listen().await
+            },
+        }
+    }
 }
 
 /// The actor responsible for connection management.
@@ -169,6 +247,9 @@ pub(crate) struct ConnectionManager<TTransport, TBackoff> {
     dialer: Option<Dialer<TTransport, TBackoff>>,
     listener: Option<PeerListener<TTransport>>,
     aux_listener: Option<PeerListener<TcpTransport>>,
+    #[cfg(feature = "websocket")]
+    websocket_listener: Option<PeerListener<WebSocketTransport>>,
+    additional_listeners: Vec<AdditionalListener>,
     peer_manager: Arc<PeerManager>,
     shutdown_signal: Option<ShutdownSignal>,
     protocols: Protocols<Substream>,
@@ -197,6 +278,7 @@ where
     ) -> Self {
         let (internal_event_tx, internal_event_rx) = mpsc::channel(EVENT_CHANNEL_SIZE);
         let (dialer_tx, dialer_rx) = mpsc::channel(DIALER_REQUEST_CHANNEL_SIZE);
+        let additional_listener_configs = mem::take(&mut config.additional_listeners);
 
         let listener = PeerListener::new(
             config.clone(),
@@ -223,6 +305,64 @@ where
             )
         });
 
+        #[cfg(feature = "websocket")]
+        let websocket_listener = config.websocket_listener_address.take().map(|addr| {
+            info!(target: LOG_TARGET, "Starting WebSocket listener on {}", addr);
+            PeerListener::new(
+                config.clone(),
+                addr,
+                WebSocketTransport::default(),
+                noise_config.clone(),
+                internal_event_tx.clone(),
+                peer_manager.clone(),
+                node_identity.clone(),
+                shutdown_signal.clone(),
+            )
+        });
+
+        let additional_listeners = additional_listener_configs
+            .into_iter()
+            .map(|listener_config| {
+                let ListenerConfig {
+                    bind_address,
+                    transport: listener_transport,
+                    advertise,
+                    public_address,
+                } = listener_config;
+                info!(
+                    target: LOG_TARGET,
+                    "Starting additional {:?} listener on {}", listener_transport, bind_address
+                );
+                macro_rules! peer_listener {
+                    ($transport:expr) => {
+                        PeerListener::new(
+                            config.clone(),
+                            bind_address,
+                            $transport,
+                            noise_config.clone(),
+                            internal_event_tx.clone(),
+                            peer_manager.clone(),
+                            node_identity.clone(),
+                            shutdown_signal.clone(),
+                        )
+                    };
+                }
+                let listener = match listener_transport {
+                    ListenerTransport::Tcp(transport) => AdditionalPeerListener::Tcp(peer_listener!(transport)),
+                    #[cfg(feature = "websocket")]
+                    ListenerTransport::WebSocket(transport) => {
+                        AdditionalPeerListener::WebSocket(peer_listener!(transport))
+                    },
+                    ListenerTransport::Memory(transport) => AdditionalPeerListener::Memory(peer_listener!(transport)),
+                };
+                AdditionalListener {
+                    listener,
+                    advertise,
+                    public_address,
+                }
+            })
+            .collect();
+
         let dialer = Dialer::new(
             config,
             node_identity,
@@ -246,6 +386,9 @@ where
             listener: Some(listener),
             listener_info: None,
             aux_listener,
+            #[cfg(feature = "websocket")]
+            websocket_listener,
+            additional_listeners,
             listening_notifiers: Vec::new(),
             connection_manager_events_tx,
             complete_trigger: Shutdown::new(),
@@ -328,6 +471,10 @@ where
         let mut listener_info = ListenerInfo {
             bind_address: Multiaddr::empty(),
             aux_bind_address: None,
+            #[cfg(feature = "websocket")]
+            websocket_bind_address: None,
+            additional_bind_addresses: Vec::new(),
+            advertised_addresses: Vec::new(),
         };
         match listener.listen().await {
             Ok(addr) => {
@@ -343,6 +490,28 @@ where
             listener_info.aux_bind_address = Some(addr);
         }
 
+        #[cfg(feature = "websocket")]
+        if let Some(mut listener) = self.websocket_listener.take() {
+            listener.set_supported_protocols(self.protocols.get_supported_protocols());
+            let addr = listener.listen().await?;
+            debug!(target: LOG_TARGET, "WebSocket listener bound to address {}", addr);
+            listener_info.websocket_bind_address = Some(addr);
+        }
+
+        for additional in mem::take(&mut self.additional_listeners) {
+            let addr = additional
+                .listener
+                .listen(self.protocols.get_supported_protocols())
+                .await?;
+            debug!(target: LOG_TARGET, "Additional listener bound to address {}", addr);
+            if additional.advertise {
+                listener_info
+                    .advertised_addresses
+                    .push(additional.public_address.unwrap_or_else(|| addr.clone()));
+            }
+            listener_info.additional_bind_addresses.push(addr);
+        }
+
         Ok(listener_info)
     }
 
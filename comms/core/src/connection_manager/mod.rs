@@ -30,6 +30,9 @@
 mod dial_state;
 mod dialer;
 mod listener;
+
+mod listener_config;
+pub use listener_config::{ListenerConfig, ListenerTransport};
 mod metrics;
 
 mod common;
@@ -38,6 +41,10 @@ pub use common::validate_peer_addresses;
 mod direction;
 pub use direction::ConnectionDirection;
 
+mod connection_stats;
+pub use connection_stats::ConnectionStats;
+pub(crate) use connection_stats::CountedSocket;
+
 mod requester;
 pub use requester::{ConnectionManagerRequest, ConnectionManagerRequester};
 
@@ -40,6 +40,7 @@ use tokio_stream::StreamExt;
 use tracing::{self, span, Instrument, Level};
 
 use super::{
+    connection_stats::ConnectionStats,
     direction::ConnectionDirection,
     error::{ConnectionManagerError, PeerConnectionError},
     manager::ConnectionManagerEvent,
@@ -95,6 +96,7 @@ pub fn create(
         peer_addr,
         direction,
         substream_counter,
+        connection.connection_stats(),
     );
     let peer_actor = PeerConnectionActor::new(
         id,
@@ -138,6 +140,7 @@ pub struct PeerConnection {
     started_at: Instant,
     substream_counter: AtomicRefCounter,
     handle_counter: Arc<()>,
+    connection_stats: ConnectionStats,
 }
 
 impl PeerConnection {
@@ -149,6 +152,7 @@ impl PeerConnection {
         address: Multiaddr,
         direction: ConnectionDirection,
         substream_counter: AtomicRefCounter,
+        connection_stats: ConnectionStats,
     ) -> Self {
         Self {
             id,
@@ -160,6 +164,7 @@ impl PeerConnection {
             started_at: Instant::now(),
             substream_counter,
             handle_counter: Arc::new(()),
+            connection_stats,
         }
     }
 
@@ -205,6 +210,11 @@ impl PeerConnection {
         Arc::strong_count(&self.handle_counter)
     }
 
+    /// Returns the traffic and protocol statistics for this connection
+    pub fn connection_stats(&self) -> &ConnectionStats {
+        &self.connection_stats
+    }
+
     #[tracing::instrument(level = "trace", "peer_connection::open_substream", skip(self))]
     pub async fn open_substream(
         &mut self,
@@ -328,6 +338,7 @@ struct PeerConnectionActor {
     event_notifier: mpsc::Sender<ConnectionManagerEvent>,
     our_supported_protocols: Vec<ProtocolId>,
     their_supported_protocols: Vec<ProtocolId>,
+    connection_stats: ConnectionStats,
 }
 
 impl PeerConnectionActor {
@@ -345,6 +356,7 @@ impl PeerConnectionActor {
             id,
             peer_node_id,
             direction,
+            connection_stats: connection.connection_stats(),
             control: connection.get_yamux_control(),
             incoming_substreams: connection.into_incoming(),
             request_rx,
@@ -433,6 +445,7 @@ impl PeerConnectionActor {
         let selected_protocol = ProtocolNegotiation::new(&mut stream)
             .negotiate_protocol_inbound(&self.our_supported_protocols)
             .await?;
+        self.connection_stats.add_protocol(&selected_protocol);
 
         self.notify_event(ConnectionManagerEvent::NewInboundSubstream(
             self.peer_node_id.clone(),
@@ -469,6 +482,7 @@ impl PeerConnectionActor {
             let fut = negotiation.negotiate_protocol_outbound(&selected_protocols);
             time::timeout(PROTOCOL_NEGOTIATION_TIMEOUT, fut).await??
         };
+        self.connection_stats.add_protocol(&selected_protocol);
 
         Ok(NegotiatedSubstream::new(selected_protocol, stream))
     }
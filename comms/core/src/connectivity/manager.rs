@@ -20,7 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt,
     sync::Arc,
     time::{Duration, Instant},
@@ -42,6 +42,7 @@ use super::{
     connection_pool::{ConnectionPool, ConnectionStatus},
     connection_stats::PeerConnectionStats,
     error::ConnectivityError,
+    peer_score::{PeerScore, PEER_SCORE_METADATA_KEY},
     requester::{ConnectivityEvent, ConnectivityRequest},
     selection::ConnectivitySelection,
     ConnectivityEventTx,
@@ -53,7 +54,7 @@ use crate::{
         ConnectionManagerEvent,
         ConnectionManagerRequester,
     },
-    peer_manager::NodeId,
+    peer_manager::{NodeId, PeerManagerError},
     runtime::task,
     utils::datetime::format_duration,
     NodeIdentity,
@@ -90,6 +91,8 @@ impl ConnectivityManager {
             peer_manager: self.peer_manager.clone(),
             event_tx: self.event_tx,
             connection_stats: HashMap::new(),
+            peer_scores: HashMap::new(),
+            dirty_peer_scores: HashSet::new(),
             node_identity: self.node_identity,
             pool: ConnectionPool::new(),
             shutdown_signal: self.shutdown_signal,
@@ -153,6 +156,8 @@ struct ConnectivityManagerActor {
     peer_manager: Arc<PeerManager>,
     event_tx: ConnectivityEventTx,
     connection_stats: HashMap<NodeId, PeerConnectionStats>,
+    peer_scores: HashMap<NodeId, PeerScore>,
+    dirty_peer_scores: HashSet<NodeId>,
     pool: ConnectionPool,
     shutdown_signal: ShutdownSignal,
     #[cfg(feature = "metrics")]
@@ -204,6 +209,7 @@ impl ConnectivityManagerActor {
 
                 _ = ticker.tick() => {
                     self.cleanup_connection_stats();
+                    self.update_peer_scores().await;
                     if let Err(err) = self.refresh_connection_pool().await {
                         error!(target: LOG_TARGET, "Error when refreshing connection pools: {:?}", err);
                     }
@@ -395,6 +401,8 @@ impl ConnectivityManagerActor {
                 "Disconnecting '{}' because connection was inactive",
                 conn.peer_node_id().short_str()
             );
+            #[cfg(feature = "metrics")]
+            super::metrics::reaped_connections().inc();
             if let Err(err) = conn.disconnect().await {
                 // Already disconnected
                 debug!(
@@ -558,6 +566,8 @@ impl ConnectivityManagerActor {
                     "Connection to peer '{}' failed because '{:?}'", node_id, err
                 );
                 self.handle_peer_connection_failure(node_id).await?;
+                self.with_peer_score(node_id, PeerScore::record_connection_failure)
+                    .await;
                 (&*node_id, ConnectionStatus::Failed, None)
             },
             _ => return Ok(()),
@@ -580,6 +590,8 @@ impl ConnectivityManagerActor {
         match (old_status, new_status) {
             (_, Connected) => {
                 self.mark_peer_succeeded(node_id.clone());
+                self.with_peer_score(&node_id, PeerScore::record_connection_success)
+                    .await;
                 match self.pool.get_connection(&node_id).cloned() {
                     Some(conn) => {
                         self.publish_event(ConnectivityEvent::PeerConnected(conn));
@@ -844,6 +856,65 @@ impl ConnectivityManagerActor {
         Ok(())
     }
 
+    /// Applies `f` to the score for the peer, loading the persisted score from the peer database if it is not yet
+    /// held in memory
+    async fn with_peer_score<F: FnOnce(&mut PeerScore)>(&mut self, node_id: &NodeId, f: F) {
+        if !self.peer_scores.contains_key(node_id) {
+            let score = match self.peer_manager.find_by_node_id(node_id).await {
+                Ok(Some(peer)) => PeerScore::from_peer(&peer).unwrap_or_default(),
+                Ok(None) => PeerScore::new(),
+                Err(err) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Failed to load peer score for '{}': {}", node_id, err
+                    );
+                    PeerScore::new()
+                },
+            };
+            self.peer_scores.insert(node_id.clone(), score);
+        }
+        if let Some(score) = self.peer_scores.get_mut(node_id) {
+            f(score);
+        }
+        self.dirty_peer_scores.insert(node_id.clone());
+    }
+
+    /// Feeds the RPC stats collected since the last refresh into the peer scores and persists any changed scores
+    async fn update_peer_scores(&mut self) {
+        #[cfg(feature = "rpc")]
+        for (node_id, stats) in crate::protocol::rpc::take_peer_rpc_stats() {
+            self.with_peer_score(&node_id, |score| {
+                score.record_rpc_requests(stats.num_requests, stats.num_errors, stats.total_latency)
+            })
+            .await;
+        }
+
+        for node_id in self.dirty_peer_scores.drain().collect::<Vec<_>>() {
+            let bytes = match self.peer_scores.get(&node_id) {
+                Some(score) => score.to_bytes(),
+                None => continue,
+            };
+            match self
+                .peer_manager
+                .set_peer_metadata(&node_id, PEER_SCORE_METADATA_KEY, bytes)
+                .await
+            {
+                Ok(_) | Err(PeerManagerError::PeerNotFoundError) => {},
+                Err(err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Failed to persist peer score for '{}': {}", node_id, err
+                    );
+                },
+            }
+        }
+
+        // Scores are persisted, so only keep scores for connected peers in memory
+        let pool = &self.pool;
+        self.peer_scores
+            .retain(|node_id, _| pool.get_connection_status(node_id) == ConnectionStatus::Connected);
+    }
+
     fn cleanup_connection_stats(&mut self) {
         let mut to_remove = Vec::new();
         for node_id in self.connection_stats.keys() {
@@ -57,3 +57,15 @@ pub fn banned_peers_counter(peer: &NodeId) -> IntCounter {
 
     METER.with_label_values(&[peer.to_string().as_str()])
 }
+
+pub fn reaped_connections() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "comms::connectivity::reaped_connections",
+            "The number of inactive connections that have been reaped",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
@@ -44,6 +44,9 @@ pub use manager::ConnectivityStatus;
 #[cfg(feature = "metrics")]
 mod metrics;
 
+mod peer_score;
+pub use peer_score::{cmp_scores_desc, sort_peers_by_score, PeerScore, PEER_SCORE_METADATA_KEY};
+
 mod requester;
 pub(crate) use requester::ConnectivityRequest;
 pub use requester::{ConnectivityEvent, ConnectivityEventRx, ConnectivityEventTx, ConnectivityRequester};
@@ -0,0 +1,240 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    cmp::Ordering,
+    convert::{TryFrom, TryInto},
+    time::Duration,
+};
+
+use crate::peer_manager::Peer;
+
+/// The peer metadata key under which peer scores are persisted in the peer database
+pub const PEER_SCORE_METADATA_KEY: u8 = 0xf0;
+
+const PEER_SCORE_VERSION: u8 = 1;
+const PEER_SCORE_ENCODED_LEN: usize = 21;
+/// Counters are halved once the number of samples reaches this value, so that recent behaviour dominates the score
+const MAX_SAMPLES: u32 = 256;
+/// Weight given to a new latency sample in the moving average, in percent
+const LATENCY_SAMPLE_WEIGHT: u64 = 20;
+/// Score given to a component for which there are no samples yet
+const NEUTRAL_SCORE: f64 = 0.5;
+
+/// Connection quality score for a peer, built from connection attempts and RPC request outcomes. Scores range from 0.0
+/// (worst) to 1.0 (best). A peer with no recorded history scores 0.5.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeerScore {
+    avg_latency_ms: Option<u32>,
+    connection_attempts: u32,
+    connection_failures: u32,
+    rpc_requests: u32,
+    rpc_errors: u32,
+}
+
+impl PeerScore {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the persisted score for the peer, if any
+    pub fn from_peer(peer: &Peer) -> Option<Self> {
+        peer.get_metadata(PEER_SCORE_METADATA_KEY)
+            .and_then(|bytes| Self::from_bytes(bytes))
+    }
+
+    pub fn record_connection_success(&mut self) {
+        self.connection_attempts = self.connection_attempts.saturating_add(1);
+        self.decay_connection_counters();
+    }
+
+    pub fn record_connection_failure(&mut self) {
+        self.connection_attempts = self.connection_attempts.saturating_add(1);
+        self.connection_failures = self.connection_failures.saturating_add(1);
+        self.decay_connection_counters();
+    }
+
+    /// Records the outcome of RPC requests made to the peer. `total_latency` is the sum of the latencies of all
+    /// `num_requests` successful requests.
+    pub fn record_rpc_requests(&mut self, num_requests: u32, num_errors: u32, total_latency: Duration) {
+        if num_requests > 0 {
+            let avg = total_latency.as_millis() / u128::from(num_requests);
+            let sample = u64::try_from(avg).unwrap_or(u64::MAX);
+            let new_avg = match self.avg_latency_ms {
+                Some(prev) => (u64::from(prev) * (100 - LATENCY_SAMPLE_WEIGHT) + sample * LATENCY_SAMPLE_WEIGHT) / 100,
+                None => sample,
+            };
+            self.avg_latency_ms = Some(u32::try_from(new_avg).unwrap_or(u32::MAX));
+        }
+        self.rpc_requests = self.rpc_requests.saturating_add(num_requests);
+        self.rpc_errors = self.rpc_errors.saturating_add(num_errors);
+        while self.rpc_requests.saturating_add(self.rpc_errors) >= MAX_SAMPLES {
+            self.rpc_requests /= 2;
+            self.rpc_errors /= 2;
+        }
+    }
+
+    /// The moving average of RPC request latency, if any requests have completed
+    pub fn avg_latency(&self) -> Option<Duration> {
+        self.avg_latency_ms.map(|ms| Duration::from_millis(u64::from(ms)))
+    }
+
+    pub fn connection_failure_rate(&self) -> Option<f64> {
+        ratio(self.connection_failures, self.connection_attempts)
+    }
+
+    pub fn rpc_error_rate(&self) -> Option<f64> {
+        ratio(self.rpc_errors, self.rpc_requests.saturating_add(self.rpc_errors))
+    }
+
+    /// Returns the score in the range 0.0 to 1.0. Connection reliability, RPC reliability and latency are weighted
+    /// 40%, 30% and 30% respectively.
+    pub fn score(&self) -> f64 {
+        let connection = self.connection_failure_rate().map(|r| 1.0 - r).unwrap_or(NEUTRAL_SCORE);
+        let rpc = self.rpc_error_rate().map(|r| 1.0 - r).unwrap_or(NEUTRAL_SCORE);
+        // 1.0 at 0ms, 0.5 at 1s
+        let latency = self
+            .avg_latency_ms
+            .map(|ms| 1000.0 / (1000.0 + f64::from(ms)))
+            .unwrap_or(NEUTRAL_SCORE);
+        0.4 * connection + 0.3 * rpc + 0.3 * latency
+    }
+
+    /// A coarse-grained score from 0 to 10, for use when combining the score with another ordering e.g. latency
+    pub fn rank(&self) -> u8 {
+        (self.score() * 10.0).round() as u8
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(PEER_SCORE_ENCODED_LEN);
+        buf.push(PEER_SCORE_VERSION);
+        buf.extend_from_slice(&self.avg_latency_ms.unwrap_or(u32::MAX).to_le_bytes());
+        buf.extend_from_slice(&self.connection_attempts.to_le_bytes());
+        buf.extend_from_slice(&self.connection_failures.to_le_bytes());
+        buf.extend_from_slice(&self.rpc_requests.to_le_bytes());
+        buf.extend_from_slice(&self.rpc_errors.to_le_bytes());
+        buf
+    }
+
+    /// Decodes a score previously encoded with `to_bytes`. Returns None if the bytes are not a valid encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != PEER_SCORE_ENCODED_LEN || bytes[0] != PEER_SCORE_VERSION {
+            return None;
+        }
+        let read_u32 = |i: usize| u32::from_le_bytes(bytes[1 + i * 4..5 + i * 4].try_into().expect("length checked"));
+        let avg_latency_ms = read_u32(0);
+        Some(Self {
+            avg_latency_ms: if avg_latency_ms == u32::MAX {
+                None
+            } else {
+                Some(avg_latency_ms)
+            },
+            connection_attempts: read_u32(1),
+            connection_failures: read_u32(2),
+            rpc_requests: read_u32(3),
+            rpc_errors: read_u32(4),
+        })
+    }
+
+    fn decay_connection_counters(&mut self) {
+        if self.connection_attempts >= MAX_SAMPLES {
+            self.connection_attempts /= 2;
+            self.connection_failures /= 2;
+        }
+    }
+}
+
+fn ratio(numerator: u32, denominator: u32) -> Option<f64> {
+    if denominator == 0 {
+        return None;
+    }
+    Some(f64::from(numerator) / f64::from(denominator))
+}
+
+/// Compares two optional scores so that higher scores sort first. Peers without a score are treated as neutral.
+pub fn cmp_scores_desc(a: Option<&PeerScore>, b: Option<&PeerScore>) -> Ordering {
+    let a = a.map(PeerScore::score).unwrap_or(NEUTRAL_SCORE);
+    let b = b.map(PeerScore::score).unwrap_or(NEUTRAL_SCORE);
+    b.partial_cmp(&a).unwrap_or(Ordering::Equal)
+}
+
+/// Stable sorts peers so that higher scoring peers come first
+pub fn sort_peers_by_score(peers: &mut [Peer]) {
+    peers.sort_by(|a, b| cmp_scores_desc(PeerScore::from_peer(a).as_ref(), PeerScore::from_peer(b).as_ref()));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_scores_unknown_peers_as_neutral() {
+        let score = PeerScore::new();
+        assert!((score.score() - NEUTRAL_SCORE).abs() < f64::EPSILON);
+        assert_eq!(score.rank(), 5);
+        assert!(score.avg_latency().is_none());
+        assert!(score.connection_failure_rate().is_none());
+    }
+
+    #[test]
+    fn it_scores_reliable_fast_peers_higher() {
+        let mut good = PeerScore::new();
+        let mut bad = PeerScore::new();
+        for _ in 0..10 {
+            good.record_connection_success();
+            bad.record_connection_failure();
+        }
+        good.record_rpc_requests(10, 0, Duration::from_millis(500));
+        bad.record_rpc_requests(5, 5, Duration::from_secs(10));
+
+        assert!(good.score() > NEUTRAL_SCORE);
+        assert!(bad.score() < NEUTRAL_SCORE);
+        assert_eq!(good.avg_latency(), Some(Duration::from_millis(50)));
+        assert_eq!(bad.rpc_error_rate(), Some(0.5));
+        assert_eq!(cmp_scores_desc(Some(&good), Some(&bad)), Ordering::Less);
+        assert_eq!(cmp_scores_desc(None, Some(&bad)), Ordering::Less);
+    }
+
+    #[test]
+    fn it_decays_old_samples() {
+        let mut score = PeerScore::new();
+        for _ in 0..MAX_SAMPLES {
+            score.record_connection_failure();
+        }
+        assert!(score.connection_attempts < MAX_SAMPLES);
+        score.record_rpc_requests(MAX_SAMPLES, MAX_SAMPLES, Duration::from_secs(1));
+        assert!(score.rpc_requests + score.rpc_errors < MAX_SAMPLES);
+    }
+
+    #[test]
+    fn it_encodes_and_decodes() {
+        let mut score = PeerScore::new();
+        assert_eq!(PeerScore::from_bytes(&score.to_bytes()), Some(score.clone()));
+        score.record_connection_failure();
+        score.record_rpc_requests(3, 1, Duration::from_millis(300));
+        let bytes = score.to_bytes();
+        assert_eq!(bytes.len(), PEER_SCORE_ENCODED_LEN);
+        assert_eq!(PeerScore::from_bytes(&bytes), Some(score));
+        assert!(PeerScore::from_bytes(&bytes[1..]).is_none());
+        assert!(PeerScore::from_bytes(&[0u8; PEER_SCORE_ENCODED_LEN]).is_none());
+    }
+}
@@ -33,6 +33,8 @@ mod multiplexing;
 pub use multiplexing::Substream;
 
 mod noise;
+pub use noise::{NoiseConfig, NoiseError, NoiseSocket, RekeyPolicy};
+
 mod proto;
 mod stream_id;
 
@@ -45,6 +47,8 @@ pub mod runtime;
 pub mod message;
 pub mod net_address;
 pub mod pipeline;
+#[cfg(feature = "port-mapping")]
+pub mod port_mapping;
 pub mod socks;
 pub mod tor;
 pub mod transports;
@@ -35,7 +35,7 @@ pub use yamux::ConnectionError;
 use yamux::Mode;
 
 use crate::{
-    connection_manager::ConnectionDirection,
+    connection_manager::{ConnectionDirection, ConnectionStats, CountedSocket},
     runtime,
     stream_id,
     stream_id::StreamId,
@@ -48,6 +48,7 @@ pub struct Yamux {
     control: Control,
     incoming: IncomingSubstreams,
     substream_counter: AtomicRefCounter,
+    connection_stats: ConnectionStats,
 }
 
 const MAX_BUFFER_SIZE: u32 = 8 * 1024 * 1024; // 8MiB
@@ -71,6 +72,8 @@ impl Yamux {
         config.set_receive_window(RECEIVE_WINDOW);
 
         let substream_counter = AtomicRefCounter::new();
+        let connection_stats = ConnectionStats::new();
+        let socket = CountedSocket::new(socket, connection_stats.clone());
         let connection = yamux::Connection::new(socket.compat(), config, mode);
         let control = Control::new(connection.control(), substream_counter.clone());
         let incoming = Self::spawn_incoming_stream_worker(connection, substream_counter.clone());
@@ -79,6 +82,7 @@ impl Yamux {
             control,
             incoming,
             substream_counter,
+            connection_stats,
         })
     }
 
@@ -122,6 +126,11 @@ impl Yamux {
     pub(crate) fn substream_counter(&self) -> AtomicRefCounter {
         self.substream_counter.clone()
     }
+
+    /// Return the traffic and protocol statistics for this connection
+    pub fn connection_stats(&self) -> ConnectionStats {
+        self.connection_stats.clone()
+    }
 }
 
 #[derive(Clone)]
@@ -34,6 +34,7 @@ use crate::{
     noise::{
         crypto_resolver::TariCryptoResolver,
         error::NoiseError,
+        rekey::RekeyPolicy,
         socket::{Handshake, NoiseSocket},
     },
     peer_manager::NodeIdentity,
@@ -48,6 +49,7 @@ pub(super) const NOISE_IX_PARAMETER: &str = "Noise_IX_25519_ChaChaPoly_BLAKE2b";
 pub struct NoiseConfig {
     node_identity: Arc<NodeIdentity>,
     parameters: NoiseParams,
+    rekey_policy: RekeyPolicy,
 }
 
 impl NoiseConfig {
@@ -57,9 +59,16 @@ impl NoiseConfig {
         Self {
             node_identity,
             parameters,
+            rekey_policy: RekeyPolicy::disabled(),
         }
     }
 
+    /// Sets the policy used to rotate the session keys of established sessions with peers that support it
+    pub fn with_rekey_policy(mut self, rekey_policy: RekeyPolicy) -> Self {
+        self.rekey_policy = rekey_policy;
+        self
+    }
+
     /// Upgrades the given socket to using the noise protocol. The upgraded socket and the peer's static key
     /// is returned.
     #[tracing::instrument(name = "noise::upgrade_socket", skip(self, socket))]
@@ -88,7 +97,7 @@ impl NoiseConfig {
             }
         };
 
-        let handshake = Handshake::new(socket, handshake_state);
+        let handshake = Handshake::new(socket, handshake_state).with_rekey_policy(self.rekey_policy);
         let socket = handshake.handshake_1rt().await.map_err(NoiseError::HandshakeFailed)?;
 
         Ok(socket)
@@ -31,5 +31,8 @@ mod crypto_resolver;
 mod error;
 pub use error::NoiseError;
 
+mod rekey;
+pub use rekey::RekeyPolicy;
+
 mod socket;
 pub use socket::NoiseSocket;
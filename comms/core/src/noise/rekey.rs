@@ -0,0 +1,139 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::{Duration, Instant};
+
+/// Determines when the session keys of an established noise session are rotated. Keys are only rotated if the remote
+/// peer has announced that it supports rekeying, so this can be enabled without breaking connections to older peers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RekeyPolicy {
+    /// Rotate the outgoing key once this many bytes have been encrypted with it
+    pub max_bytes: Option<u64>,
+    /// Rotate the outgoing key once it has been in use for this long. The key is rotated before the next frame is
+    /// sent, so an idle session is not rekeyed.
+    pub max_age: Option<Duration>,
+}
+
+impl RekeyPolicy {
+    /// A policy that never rotates session keys
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_bytes.is_some() || self.max_age.is_some()
+    }
+}
+
+/// Tracks the use of the current outgoing key against a [RekeyPolicy]
+#[derive(Debug)]
+pub(super) struct RekeyState {
+    policy: RekeyPolicy,
+    remote_supports_rekey: bool,
+    bytes_since_rekey: u64,
+    last_rekey: Instant,
+}
+
+impl RekeyState {
+    pub fn new(policy: RekeyPolicy) -> Self {
+        Self {
+            policy,
+            remote_supports_rekey: false,
+            bytes_since_rekey: 0,
+            last_rekey: Instant::now(),
+        }
+    }
+
+    pub fn policy(&self) -> &RekeyPolicy {
+        &self.policy
+    }
+
+    pub fn set_remote_supports_rekey(&mut self) {
+        self.remote_supports_rekey = true;
+    }
+
+    pub fn record_bytes(&mut self, num_bytes: usize) {
+        self.bytes_since_rekey = self.bytes_since_rekey.saturating_add(num_bytes as u64);
+    }
+
+    /// Returns true if the outgoing key should be rotated before the next frame is encrypted
+    pub fn is_due(&self) -> bool {
+        if !self.remote_supports_rekey {
+            return false;
+        }
+        let bytes_exceeded = self
+            .policy
+            .max_bytes
+            .map(|max| self.bytes_since_rekey >= max)
+            .unwrap_or(false);
+        let age_exceeded = self
+            .policy
+            .max_age
+            .map(|max| self.last_rekey.elapsed() >= max)
+            .unwrap_or(false);
+        bytes_exceeded || age_exceeded
+    }
+
+    pub fn reset(&mut self) {
+        self.bytes_since_rekey = 0;
+        self.last_rekey = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_is_due_after_max_bytes() {
+        let mut state = RekeyState::new(RekeyPolicy {
+            max_bytes: Some(100),
+            max_age: None,
+        });
+        state.record_bytes(150);
+        // Not due until the remote has announced support
+        assert!(!state.is_due());
+        state.set_remote_supports_rekey();
+        assert!(state.is_due());
+        state.reset();
+        assert!(!state.is_due());
+        state.record_bytes(99);
+        assert!(!state.is_due());
+        state.record_bytes(1);
+        assert!(state.is_due());
+    }
+
+    #[test]
+    fn it_is_due_after_max_age() {
+        let mut state = RekeyState::new(RekeyPolicy {
+            max_bytes: None,
+            max_age: Some(Duration::from_secs(0)),
+        });
+        state.set_remote_supports_rekey();
+        assert!(state.is_due());
+
+        let mut state = RekeyState::new(RekeyPolicy::disabled());
+        state.set_remote_supports_rekey();
+        state.record_bytes(usize::MAX);
+        assert!(!state.is_due());
+    }
+}
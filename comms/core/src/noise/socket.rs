@@ -40,7 +40,10 @@ use snow::{error::StateProblem, HandshakeState, TransportState};
 use tari_utilities::ByteArray;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
 
-use crate::types::CommsPublicKey;
+use crate::{
+    noise::rekey::{RekeyPolicy, RekeyState},
+    types::CommsPublicKey,
+};
 
 const LOG_TARGET: &str = "comms::noise::socket";
 
@@ -50,6 +53,13 @@ const MAX_PAYLOAD_LENGTH: usize = u16::MAX as usize; // 65535
 // encrypted messages include a tag along with the payload.
 const MAX_WRITE_BUFFER_LENGTH: usize = u16::MAX as usize - 16; // 65519
 
+// Encrypted frames are never shorter than the 16 byte tag, so frame lengths below 16 are used as control frames. Peers
+// that do not support rekeying ignore empty frames, and are never sent a rekey frame.
+/// Sent once after the handshake to announce that this side supports rekeying
+const REKEY_SUPPORTED_FRAME_LEN: u16 = 0;
+/// Sent immediately before the first frame encrypted with a rotated key
+const REKEY_FRAME_LEN: u16 = 1;
+
 /// Collection of buffers used for buffering data during the various read/write states of a
 /// NoiseSocket
 struct NoiseBuffers {
@@ -107,6 +117,12 @@ enum WriteState {
     Init,
     /// Buffer provided data
     BufferData { offset: usize },
+    /// Write a rekey frame to the wire, followed by the frame encrypted with the new key
+    WriteRekeyFrame {
+        frame_len: u16,
+        buf: [u8; 2],
+        offset: usize,
+    },
     /// Write frame length to the wire
     WriteFrameLen {
         frame_len: u16,
@@ -135,6 +151,7 @@ pub struct NoiseSocket<TSocket> {
     buffers: Box<NoiseBuffers>,
     read_state: ReadState,
     write_state: WriteState,
+    rekey: RekeyState,
 }
 
 impl<TSocket> NoiseSocket<TSocket> {
@@ -145,6 +162,7 @@ impl<TSocket> NoiseSocket<TSocket> {
             buffers: Box::new(NoiseBuffers::new()),
             read_state: ReadState::Init,
             write_state: WriteState::Init,
+            rekey: RekeyState::new(RekeyPolicy::disabled()),
         }
     }
 
@@ -264,13 +282,23 @@ where TSocket: AsyncRead + Unpin
                     ref mut offset,
                 } => {
                     match ready!(poll_read_u16frame_len(context, Pin::new(&mut self.socket), buf, offset)) {
-                        Ok(Some(frame_len)) => {
+                        Ok(Some(REKEY_SUPPORTED_FRAME_LEN)) => {
                             // Empty Frame
-                            if frame_len == 0 {
+                            self.rekey.set_remote_supports_rekey();
+                            self.read_state = ReadState::Init;
+                        },
+                        Ok(Some(REKEY_FRAME_LEN)) => match self.state.rekey_incoming() {
+                            Ok(()) => {
+                                debug!(target: LOG_TARGET, "Remote rotated its session key");
                                 self.read_state = ReadState::Init;
-                            } else {
-                                self.read_state = ReadState::ReadFrame { frame_len, offset: 0 };
-                            }
+                            },
+                            Err(e) => {
+                                warn!(target: LOG_TARGET, "Unexpected rekey frame: {}", e);
+                                self.read_state = ReadState::DecryptionError(e);
+                            },
+                        },
+                        Ok(Some(frame_len)) => {
+                            self.read_state = ReadState::ReadFrame { frame_len, offset: 0 };
                         },
                         Ok(None) => {
                             self.read_state = ReadState::Eof(Ok(()));
@@ -399,16 +427,36 @@ where TSocket: AsyncWrite + Unpin
                     };
 
                     if buf.is_none() || *offset == MAX_WRITE_BUFFER_LENGTH {
+                        let is_rekeying = self.rekey.is_due();
+                        if is_rekeying {
+                            if let Err(e) = self.state.rekey_outgoing() {
+                                warn!(target: LOG_TARGET, "Rekey Error: {}", e);
+                                let err = io::Error::new(io::ErrorKind::InvalidData, format!("EncryptionError: {}", e));
+                                self.write_state = WriteState::EncryptionError(e);
+                                return Poll::Ready(Err(err));
+                            }
+                            debug!(target: LOG_TARGET, "Rotated outgoing session key");
+                            self.rekey.reset();
+                        }
                         match self.state.write_message(
                             &self.buffers.write_decrypted[..*offset],
                             &mut self.buffers.write_encrypted,
                         ) {
                             Ok(encrypted_len) => {
+                                self.rekey.record_bytes(encrypted_len);
                                 let frame_len = encrypted_len.try_into().expect("offset should be able to fit in u16");
-                                self.write_state = WriteState::WriteFrameLen {
-                                    frame_len,
-                                    buf: u16::to_be_bytes(frame_len),
-                                    offset: 0,
+                                self.write_state = if is_rekeying {
+                                    WriteState::WriteRekeyFrame {
+                                        frame_len,
+                                        buf: u16::to_be_bytes(REKEY_FRAME_LEN),
+                                        offset: 0,
+                                    }
+                                } else {
+                                    WriteState::WriteFrameLen {
+                                        frame_len,
+                                        buf: u16::to_be_bytes(frame_len),
+                                        offset: 0,
+                                    }
                                 };
                             },
                             Err(e) => {
@@ -424,6 +472,25 @@ where TSocket: AsyncWrite + Unpin
                         return Poll::Ready(Ok(Some(bytes_buffered)));
                     }
                 },
+                WriteState::WriteRekeyFrame {
+                    frame_len,
+                    ref buf,
+                    ref mut offset,
+                } => match ready!(poll_write_all(context, Pin::new(&mut self.socket), buf, offset)) {
+                    Ok(()) => {
+                        self.write_state = WriteState::WriteFrameLen {
+                            frame_len,
+                            buf: u16::to_be_bytes(frame_len),
+                            offset: 0,
+                        };
+                    },
+                    Err(e) => {
+                        if e.kind() == io::ErrorKind::WriteZero {
+                            self.write_state = WriteState::Eof;
+                        }
+                        return Poll::Ready(Err(e));
+                    },
+                },
                 WriteState::WriteFrameLen {
                     frame_len,
                     ref buf,
@@ -518,6 +585,12 @@ impl<TSocket> Handshake<TSocket> {
             socket: NoiseSocket::new(socket, state.into()),
         }
     }
+
+    /// Sets the policy used to rotate the session keys once the handshake has completed
+    pub fn with_rekey_policy(mut self, policy: RekeyPolicy) -> Self {
+        self.socket.rekey = RekeyState::new(policy);
+        self
+    }
 }
 
 impl<TSocket> Handshake<TSocket>
@@ -527,7 +600,18 @@ where TSocket: AsyncRead + AsyncWrite + Unpin
     /// (switched to transport mode) upon success.
     pub async fn handshake_1rt(mut self) -> io::Result<NoiseSocket<TSocket>> {
         match self.perform_handshake().await {
-            Ok(_) => self.build(),
+            Ok(_) => {
+                let mut socket = self.build()?;
+                if socket.rekey.policy().is_enabled() {
+                    // Older peers ignore this frame
+                    socket
+                        .socket
+                        .write_all(&u16::to_be_bytes(REKEY_SUPPORTED_FRAME_LEN))
+                        .await?;
+                    socket.socket.flush().await?;
+                }
+                Ok(socket)
+            },
             Err(err) => {
                 warn!(
                     target: LOG_TARGET,
@@ -619,6 +703,26 @@ impl NoiseState {
 
     proxy_state_method!(pub fn get_remote_static(&self) -> Option<&[u8]>);
 
+    pub fn rekey_outgoing(&mut self) -> Result<(), snow::Error> {
+        match self {
+            NoiseState::TransportState(state) => {
+                state.rekey_outgoing();
+                Ok(())
+            },
+            _ => Err(snow::Error::State(StateProblem::HandshakeNotFinished)),
+        }
+    }
+
+    pub fn rekey_incoming(&mut self) -> Result<(), snow::Error> {
+        match self {
+            NoiseState::TransportState(state) => {
+                state.rekey_incoming();
+                Ok(())
+            },
+            _ => Err(snow::Error::State(StateProblem::HandshakeNotFinished)),
+        }
+    }
+
     pub fn into_transport_mode(self) -> Result<Self, snow::Error> {
         match self {
             NoiseState::HandshakeState(state) => Ok(NoiseState::TransportState(Box::new(state.into_transport_mode()?))),
@@ -782,6 +886,71 @@ mod test {
         Ok(())
     }
 
+    #[runtime::test]
+    async fn rekeying() -> io::Result<()> {
+        let ((_dialer_keypair, dialer), (_listener_keypair, listener)) = build_test_connection().await.unwrap();
+        let policy = RekeyPolicy {
+            max_bytes: Some(1),
+            max_age: None,
+        };
+
+        let (mut a, mut b) =
+            perform_handshake(dialer.with_rekey_policy(policy), listener.with_rekey_policy(policy)).await?;
+
+        // Reading processes the remote's rekey announcement
+        b.write_all(b"Mistborn").await?;
+        b.flush().await?;
+        let mut buf = [0; 8];
+        a.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"Mistborn");
+
+        for _ in 0..3 {
+            a.write_all(b"The Well of Ascension").await?;
+            a.flush().await?;
+            // Each frame exceeds the byte limit, so the next frame is sent with a new key
+            assert!(a.rekey.is_due());
+        }
+        let mut buf = [0; 21];
+        for _ in 0..3 {
+            b.read_exact(&mut buf).await?;
+            assert_eq!(&buf, b"The Well of Ascension");
+        }
+
+        b.write_all(b"The Hero of Ages").await?;
+        b.flush().await?;
+        let mut buf = [0; 16];
+        a.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"The Hero of Ages");
+
+        Ok(())
+    }
+
+    #[runtime::test]
+    async fn rekeying_not_supported_by_remote() -> io::Result<()> {
+        let ((_dialer_keypair, dialer), (_listener_keypair, listener)) = build_test_connection().await.unwrap();
+        let policy = RekeyPolicy {
+            max_bytes: Some(1),
+            max_age: None,
+        };
+
+        let (mut a, mut b) = perform_handshake(dialer.with_rekey_policy(policy), listener).await?;
+
+        b.write_all(b"Elantris").await?;
+        b.flush().await?;
+        let mut buf = [0; 8];
+        a.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"Elantris");
+
+        a.write_all(b"Warbreaker").await?;
+        a.flush().await?;
+        assert!(!a.rekey.is_due());
+        let mut buf = [0; 10];
+        b.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"Warbreaker");
+
+        Ok(())
+    }
+
     #[runtime::test]
     async fn unexpected_eof() -> io::Result<()> {
         let ((_dialer_keypair, dialer), (_listener_keypair, listener)) = build_test_connection().await.unwrap();
@@ -180,7 +180,7 @@ mod test {
     use tari_crypto::keys::PublicKey;
 
     use super::*;
-    use crate::peer_manager::{NodeId, PeerFlags};
+    use crate::peer_manager::{NodeId, NodeIdentity, PeerFlags};
 
     mod is_valid_for_peer {
         use super::*;
@@ -207,6 +207,24 @@ mod test {
             assert!(identity.is_valid_for_peer(&peer));
         }
 
+        #[test]
+        fn it_returns_true_for_node_identity_with_additional_addresses() {
+            let node_identity = NodeIdentity::random(
+                &mut OsRng,
+                Multiaddr::from_str("/ip4/127.0.0.1/tcp/1234").unwrap(),
+                PeerFeatures::COMMUNICATION_NODE,
+            );
+            node_identity.set_additional_addresses(vec![
+                Multiaddr::from_str("/ip4/127.0.0.1/tcp/1235/ws").unwrap(),
+                Multiaddr::from_str("/ip4/10.0.0.1/tcp/1234").unwrap(),
+            ]);
+
+            let peer = node_identity.to_peer();
+            assert_eq!(peer.addresses.len(), 3);
+            let identity = node_identity.identity_signature_read().as_ref().cloned().unwrap();
+            assert!(identity.is_valid_for_peer(&peer));
+        }
+
         #[test]
         fn it_returns_false_for_tampered_address() {
             let secret = CommsSecretKey::random(&mut OsRng);
@@ -100,7 +100,7 @@ mod manager;
 pub use manager::PeerManager;
 
 mod peer_query;
-pub use peer_query::{PeerQuery, PeerQuerySortBy};
+pub use peer_query::{PeerBanState, PeerQuery, PeerQuerySortBy};
 
 mod peer_storage;
 pub use peer_storage::PeerStorage;
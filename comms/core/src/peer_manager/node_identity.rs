@@ -50,6 +50,10 @@ pub struct NodeIdentity {
     features: PeerFeatures,
     secret_key: CommsSecretKey,
     public_address: RwLock<Multiaddr>,
+    /// Addresses of additional listeners that are advertised along with the public address. These are set when the
+    /// listeners are bound, so are not persisted.
+    #[serde(skip)]
+    additional_addresses: RwLock<Vec<Multiaddr>>,
     #[serde(default = "rwlock_none")]
     identity_signature: RwLock<Option<IdentitySignature>>,
 }
@@ -70,6 +74,7 @@ impl NodeIdentity {
             features,
             secret_key,
             public_address: RwLock::new(public_address),
+            additional_addresses: RwLock::new(Vec::new()),
             identity_signature: RwLock::new(None),
         };
         node_identity.sign();
@@ -96,6 +101,7 @@ impl NodeIdentity {
             features,
             secret_key,
             public_address: RwLock::new(public_address),
+            additional_addresses: RwLock::new(Vec::new()),
             identity_signature: RwLock::new(identity_signature),
         }
     }
@@ -127,6 +133,39 @@ impl NodeIdentity {
         }
     }
 
+    /// The addresses of additional listeners that are advertised along with the public address
+    pub fn additional_addresses(&self) -> Vec<Multiaddr> {
+        acquire_read_lock!(self.additional_addresses).clone()
+    }
+
+    /// Modify the additional addresses. The identity is re-signed if the addresses have changed.
+    pub fn set_additional_addresses(&self, addresses: Vec<Multiaddr>) {
+        let mut must_sign = false;
+        {
+            let mut lock = acquire_write_lock!(self.additional_addresses);
+            if *lock != addresses {
+                *lock = addresses;
+                must_sign = true;
+            }
+        }
+        if must_sign {
+            self.sign()
+        }
+    }
+
+    /// All addresses that peers can use to connect to this node. The public address is always first.
+    pub fn public_addresses(&self) -> Vec<Multiaddr> {
+        let public_address = self.public_address();
+        let mut addresses = vec![public_address.clone()];
+        addresses.extend(
+            acquire_read_lock!(self.additional_addresses)
+                .iter()
+                .filter(|addr| **addr != public_address)
+                .cloned(),
+        );
+        addresses
+    }
+
     /// This returns a random NodeIdentity for testing purposes. This function can panic. If public_address
     /// is None, 127.0.0.1:9000 will be used (i.e. the caller doesn't care what the control_service_address is).
     #[cfg(test)]
@@ -170,23 +209,21 @@ impl NodeIdentity {
 
     /// Signs the peer using the peer secret key and replaces the peer account signature.
     pub fn sign(&self) {
-        let identity_sig = IdentitySignature::sign_new(
-            self.secret_key(),
-            self.features,
-            Some(&*acquire_read_lock!(self.public_address)),
-            Utc::now(),
-        );
+        // Peers verify the signature against the lexicographically sorted addresses
+        let mut addresses = self.public_addresses();
+        addresses.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        let identity_sig = IdentitySignature::sign_new(self.secret_key(), self.features, &addresses, Utc::now());
 
         *acquire_write_lock!(self.identity_signature) = Some(identity_sig);
     }
 
-    /// Returns a Peer with the same public key, node id, public address and features as represented in this
+    /// Returns a Peer with the same public key, node id, public addresses and features as represented in this
     /// NodeIdentity. _NOTE: PeerFlags, supported_protocols and user agent are empty._
     pub fn to_peer(&self) -> Peer {
         let mut peer = Peer::new(
             self.public_key().clone(),
             self.node_id().clone(),
-            self.public_address().into(),
+            self.public_addresses().into(),
             PeerFlags::empty(),
             self.features(),
             Default::default(),
@@ -206,6 +243,7 @@ impl Clone for NodeIdentity {
             features: self.features,
             secret_key: self.secret_key.clone(),
             public_address: RwLock::new(self.public_address()),
+            additional_addresses: RwLock::new(self.additional_addresses()),
             identity_signature: RwLock::new(self.identity_signature_read().as_ref().cloned()),
         }
     }
@@ -228,6 +266,7 @@ impl fmt::Debug for NodeIdentity {
             .field("public_key", &self.public_key)
             .field("node_id", &self.node_id)
             .field("public_address", &self.public_address)
+            .field("additional_addresses", &self.additional_addresses)
             .field("features", &self.features)
             .field("secret_key", &"<secret>")
             .field("identity_signature", &*acquire_read_lock!(self.identity_signature))
@@ -20,11 +20,15 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::cmp::{min, Ordering};
+use std::cmp::Ordering;
 
+use chrono::NaiveDateTime;
 use tari_storage::{IterationResult, KeyValueStore};
 
-use crate::peer_manager::{peer_id::PeerId, NodeId, Peer, PeerManagerError};
+use crate::{
+    peer_manager::{peer_id::PeerId, NodeId, Peer, PeerFeatures, PeerManagerError},
+    transports::TransportKind,
+};
 
 type Predicate<'a, A> = Box<dyn FnMut(&A) -> bool + Send + 'a>;
 
@@ -39,6 +43,19 @@ pub enum PeerQuerySortBy<'a> {
     LastConnected,
     /// Sort by distance from a given node followed by last connected
     DistanceFromLastConnected(&'a NodeId),
+    /// Sort by node id, ascending. This order is stable between queries and is suitable for pagination.
+    NodeId,
+    /// Sort by the time the peer was added to the peer database, most recent first
+    RecentlyAdded,
+}
+
+/// Selects peers by their ban state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerBanState {
+    /// Only select peers that are currently banned
+    Banned,
+    /// Only select peers that are not currently banned
+    NotBanned,
 }
 
 impl Default for PeerQuerySortBy<'_> {
@@ -51,6 +68,12 @@ impl Default for PeerQuerySortBy<'_> {
 #[derive(Default)]
 pub struct PeerQuery<'a> {
     select_predicate: Option<Predicate<'a, Peer>>,
+    features: Option<PeerFeatures>,
+    last_seen_after: Option<NaiveDateTime>,
+    last_seen_before: Option<NaiveDateTime>,
+    ban_state: Option<PeerBanState>,
+    transport: Option<TransportKind>,
+    offset: usize,
     limit: Option<usize>,
     sort_by: PeerQuerySortBy<'a>,
 }
@@ -69,6 +92,42 @@ impl<'a> PeerQuery<'a> {
         self
     }
 
+    /// Only select peers that have all of the given features
+    pub fn with_features(mut self, features: PeerFeatures) -> Self {
+        self.features = Some(features);
+        self
+    }
+
+    /// Only select peers that were last seen at or after the given time. Peers that have never been seen are excluded.
+    pub fn last_seen_after(mut self, datetime: NaiveDateTime) -> Self {
+        self.last_seen_after = Some(datetime);
+        self
+    }
+
+    /// Only select peers that were last seen before the given time. Peers that have never been seen are included.
+    pub fn last_seen_before(mut self, datetime: NaiveDateTime) -> Self {
+        self.last_seen_before = Some(datetime);
+        self
+    }
+
+    /// Only select peers with the given ban state
+    pub fn ban_state(mut self, ban_state: PeerBanState) -> Self {
+        self.ban_state = Some(ban_state);
+        self
+    }
+
+    /// Only select peers that have at least one address using the given transport
+    pub fn with_transport(mut self, transport: TransportKind) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Skip the first `offset` matching peers
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
     /// Set a limit on the number of results returned
     pub fn limit(mut self, limit: usize) -> Self {
         self.limit = Some(limit);
@@ -93,13 +152,52 @@ impl<'a> PeerQuery<'a> {
         self.limit.map(|inner_limit| inner_limit > limit).unwrap_or(true)
     }
 
-    /// Returns true if the specified select predicate returns true. If the
-    /// select predicate was not specified, this always returns true.
+    /// Returns true if the peer matches all of the filters and the select predicate. The select predicate is only
+    /// called for peers that pass the filters.
     fn is_selected(&mut self, peer: &Peer) -> bool {
-        self.select_predicate
-            .as_mut()
-            .map(|predicate| (predicate)(peer))
-            .unwrap_or(true)
+        self.matches_filters(peer) &&
+            self.select_predicate
+                .as_mut()
+                .map(|predicate| (predicate)(peer))
+                .unwrap_or(true)
+    }
+
+    fn matches_filters(&self, peer: &Peer) -> bool {
+        if let Some(features) = self.features {
+            if !peer.features.contains(features) {
+                return false;
+            }
+        }
+
+        match self.ban_state {
+            Some(PeerBanState::Banned) if !peer.is_banned() => return false,
+            Some(PeerBanState::NotBanned) if peer.is_banned() => return false,
+            _ => {},
+        }
+
+        if let Some(after) = self.last_seen_after {
+            if peer.last_seen().map(|dt| dt < after).unwrap_or(true) {
+                return false;
+            }
+        }
+
+        if let Some(before) = self.last_seen_before {
+            if peer.last_seen().map(|dt| dt >= before).unwrap_or(false) {
+                return false;
+            }
+        }
+
+        if let Some(transport) = self.transport {
+            if !peer
+                .addresses
+                .iter()
+                .any(|addr| TransportKind::from_address(addr) == Some(transport))
+            {
+                return false;
+            }
+        }
+
+        true
     }
 }
 
@@ -124,6 +222,8 @@ where DS: KeyValueStore<PeerId, Peer>
             PeerQuerySortBy::DistanceFromLastConnected(node_id) => {
                 self.get_distance_then_last_connected_results(node_id)
             },
+            PeerQuerySortBy::NodeId => self.get_sorted_results(|a, b| a.node_id.cmp(&b.node_id)),
+            PeerQuerySortBy::RecentlyAdded => self.get_sorted_results(|a, b| b.added_at.cmp(&a.added_at)),
         }
     }
 
@@ -159,30 +259,33 @@ where DS: KeyValueStore<PeerId, Peer>
             })
             .map_err(PeerManagerError::DatabaseError)?;
 
-        // Use all available peers up to a maximum of N
-        let max_available = self
-            .query
-            .limit
-            .map(|limit| min(selected_peers.len(), limit))
-            .unwrap_or_else(|| selected_peers.len());
-        if max_available == 0 {
+        let offset = self.query.offset;
+        if offset >= selected_peers.len() || self.query.limit == Some(0) {
             return Ok(Vec::new());
         }
 
         selected_peers.sort_by(compare);
-        selected_peers.truncate(max_available);
+        selected_peers.drain(..offset);
+        if let Some(limit) = self.query.limit {
+            selected_peers.truncate(limit);
+        }
 
         Ok(selected_peers)
     }
 
     pub fn get_unsorted_results(&mut self) -> Result<Vec<Peer>, PeerManagerError> {
         let mut selected_peers = self.query.limit.map(Vec::with_capacity).unwrap_or_default();
+        let mut num_skipped = 0;
 
         self.store
             .for_each_ok(|(_, peer)| {
                 if self.query.within_limit(selected_peers.len()) {
                     if self.query.is_selected(&peer) {
-                        selected_peers.push(peer);
+                        if num_skipped < self.query.offset {
+                            num_skipped += 1;
+                        } else {
+                            selected_peers.push(peer);
+                        }
                     }
                 } else {
                     return IterationResult::Break;
@@ -375,4 +478,103 @@ mod test {
         })
         .unwrap();
     }
+
+    #[test]
+    fn offset_pagination_query() {
+        let db = HashmapDatabase::new();
+        repeat_with(|| create_test_peer(false))
+            .take(7)
+            .enumerate()
+            .for_each(|(i, peer)| {
+                db.insert(i as PeerId, peer).unwrap();
+            });
+
+        let mut pages = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page = PeerQuery::new()
+                .sort_by(PeerQuerySortBy::NodeId)
+                .offset(offset)
+                .limit(3)
+                .executor(&db)
+                .get_results()
+                .unwrap();
+            if page.is_empty() {
+                break;
+            }
+            offset += page.len();
+            pages.push(page);
+        }
+
+        assert_eq!(pages.iter().map(Vec::len).collect::<Vec<_>>(), vec![3, 3, 1]);
+        let node_ids = pages.into_iter().flatten().map(|p| p.node_id).collect::<Vec<_>>();
+        let mut sorted = node_ids.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(node_ids, sorted);
+
+        let peers = PeerQuery::new().offset(5).executor(&db).get_results().unwrap();
+        assert_eq!(peers.len(), 2);
+    }
+
+    #[test]
+    fn filter_query() {
+        let db = HashmapDatabase::new();
+        let now = chrono::Utc::now().naive_utc();
+
+        let mut seen_recently = create_test_peer(false);
+        seen_recently.last_seen = Some(now);
+        db.insert(0, seen_recently.clone()).unwrap();
+
+        let mut seen_long_ago = create_test_peer(false);
+        seen_long_ago.last_seen = Some(now - chrono::Duration::days(10));
+        seen_long_ago.features = PeerFeatures::COMMUNICATION_CLIENT;
+        db.insert(1, seen_long_ago.clone()).unwrap();
+
+        let mut onion_peer = create_test_peer(true);
+        onion_peer.addresses = MultiaddressesWithStats::from(
+            "/onion3/vww6ybal4bd7szmgncyruucpgfkqahzddi37ktceo3ah7ngmcopnpyyd:1234"
+                .parse::<Multiaddr>()
+                .unwrap(),
+        );
+        db.insert(2, onion_peer.clone()).unwrap();
+
+        let one_day_ago = now - chrono::Duration::days(1);
+        let peers = PeerQuery::new()
+            .last_seen_after(one_day_ago)
+            .executor(&db)
+            .get_results()
+            .unwrap();
+        assert_eq!(peers, vec![seen_recently.clone()]);
+
+        let peers = PeerQuery::new()
+            .last_seen_before(one_day_ago)
+            .ban_state(PeerBanState::NotBanned)
+            .executor(&db)
+            .get_results()
+            .unwrap();
+        assert_eq!(peers, vec![seen_long_ago]);
+
+        let peers = PeerQuery::new()
+            .with_features(PeerFeatures::MESSAGE_PROPAGATION)
+            .ban_state(PeerBanState::NotBanned)
+            .executor(&db)
+            .get_results()
+            .unwrap();
+        assert_eq!(peers, vec![seen_recently]);
+
+        let peers = PeerQuery::new()
+            .with_transport(TransportKind::Onion)
+            .executor(&db)
+            .get_results()
+            .unwrap();
+        assert_eq!(peers, vec![onion_peer.clone()]);
+
+        let peers = PeerQuery::new()
+            .ban_state(PeerBanState::Banned)
+            .executor(&db)
+            .get_results()
+            .unwrap();
+        assert_eq!(peers, vec![onion_peer]);
+    }
 }
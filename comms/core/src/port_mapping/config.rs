@@ -0,0 +1,59 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::Duration;
+
+/// Configuration for automatic port mapping
+#[derive(Debug, Clone)]
+pub struct PortMappingConfig {
+    /// Attempt to map the port using UPnP IGD.
+    /// Default: true
+    pub enable_upnp: bool,
+    /// Attempt to map the port using NAT-PMP if UPnP is disabled or fails.
+    /// Default: true
+    pub enable_nat_pmp: bool,
+    /// The lease duration requested from the gateway. Leases are renewed at half of the granted duration.
+    /// Default: 1 hour
+    pub lease_duration: Duration,
+    /// The maximum time to wait for the gateway to respond, per protocol.
+    /// Default: 5 seconds
+    pub gateway_timeout: Duration,
+    /// The time to wait before retrying after all protocols have failed.
+    /// Default: 10 minutes
+    pub retry_interval: Duration,
+    /// Set the node's public address to the mapped external address.
+    /// Default: true
+    pub update_public_address: bool,
+}
+
+impl Default for PortMappingConfig {
+    fn default() -> Self {
+        Self {
+            enable_upnp: true,
+            enable_nat_pmp: true,
+            lease_duration: Duration::from_secs(60 * 60),
+            gateway_timeout: Duration::from_secs(5),
+            retry_interval: Duration::from_secs(10 * 60),
+            update_public_address: true,
+        }
+    }
+}
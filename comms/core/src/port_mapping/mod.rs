@@ -0,0 +1,67 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! # Port mapping
+//!
+//! Automatic port forwarding for nodes behind a NAT gateway, using UPnP IGD or NAT-PMP. When enabled, the comms node
+//! asks the gateway to forward the public port to the TCP listener at startup, renews the lease before it expires and
+//! sets the node's public address to the mapped external address. Available with the `port-mapping` crate feature.
+
+mod config;
+pub use config::PortMappingConfig;
+
+mod error;
+pub use error::PortMappingError;
+
+mod nat_pmp;
+
+mod service;
+pub(crate) use service::PortMappingService;
+
+mod upnp;
+
+use std::{fmt, net::SocketAddrV4, time::Duration};
+
+/// The protocol used to create a port mapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortMappingMethod {
+    Upnp,
+    NatPmp,
+}
+
+impl fmt::Display for PortMappingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortMappingMethod::Upnp => write!(f, "UPnP"),
+            PortMappingMethod::NatPmp => write!(f, "NAT-PMP"),
+        }
+    }
+}
+
+/// A port mapping created on the gateway
+#[derive(Debug, Clone)]
+pub struct PortMapping {
+    pub method: PortMappingMethod,
+    pub external_address: SocketAddrV4,
+    /// The lease duration granted by the gateway
+    pub lifetime: Duration,
+}
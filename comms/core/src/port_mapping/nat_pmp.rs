@@ -0,0 +1,218 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A minimal NAT-PMP client ([RFC 6886](https://datatracker.ietf.org/doc/html/rfc6886)).
+
+use std::{
+    convert::{TryFrom, TryInto},
+    net::{Ipv4Addr, SocketAddrV4},
+    time::Duration,
+};
+
+use tokio::{net::UdpSocket, time};
+
+use super::{PortMapping, PortMappingError, PortMappingMethod};
+
+const NAT_PMP_PORT: u16 = 5351;
+const NAT_PMP_VERSION: u8 = 0;
+const OP_PUBLIC_ADDRESS: u8 = 0;
+const OP_MAP_TCP: u8 = 2;
+const OP_RESPONSE: u8 = 128;
+/// The initial retransmission interval, doubled after each attempt
+const INITIAL_RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Requests a TCP port mapping from the default gateway
+pub async fn add_mapping(
+    internal_port: u16,
+    external_port: u16,
+    lease_duration: Duration,
+    timeout: Duration,
+) -> Result<PortMapping, PortMappingError> {
+    let gateway = default_gateway().ok_or(PortMappingError::NoGateway)?;
+    let socket = connect(gateway).await?;
+
+    let response = request(&socket, &[NAT_PMP_VERSION, OP_PUBLIC_ADDRESS], 12, timeout).await?;
+    let external_ip = decode_public_address_response(&response)?;
+
+    let lifetime = u32::try_from(lease_duration.as_secs()).unwrap_or(u32::MAX);
+    let request_bytes = encode_map_request(internal_port, external_port, lifetime);
+    let response = request(&socket, &request_bytes, 16, timeout).await?;
+    let (mapped_port, lifetime) = decode_map_response(&response, OP_MAP_TCP)?;
+
+    Ok(PortMapping {
+        method: PortMappingMethod::NatPmp,
+        external_address: SocketAddrV4::new(external_ip, mapped_port),
+        lifetime: Duration::from_secs(lifetime.into()),
+    })
+}
+
+/// Removes the TCP port mapping for the internal port
+pub async fn remove_mapping(internal_port: u16, timeout: Duration) -> Result<(), PortMappingError> {
+    let gateway = default_gateway().ok_or(PortMappingError::NoGateway)?;
+    let socket = connect(gateway).await?;
+    // A mapping is deleted by requesting a lifetime of zero with an external port of zero
+    let response = request(&socket, &encode_map_request(internal_port, 0, 0), 16, timeout).await?;
+    decode_map_response(&response, OP_MAP_TCP)?;
+    Ok(())
+}
+
+async fn connect(gateway: Ipv4Addr) -> Result<UdpSocket, PortMappingError> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.connect((gateway, NAT_PMP_PORT)).await?;
+    Ok(socket)
+}
+
+/// Sends the request, retransmitting with exponential backoff until a response of the expected length is received or
+/// the timeout elapses
+async fn request(
+    socket: &UdpSocket,
+    request: &[u8],
+    response_len: usize,
+    timeout: Duration,
+) -> Result<Vec<u8>, PortMappingError> {
+    let mut buf = [0u8; 16];
+    let mut retry_interval = INITIAL_RETRY_INTERVAL;
+    let deadline = time::Instant::now() + timeout;
+    while time::Instant::now() < deadline {
+        socket.send(request).await?;
+        let wait = retry_interval.min(deadline.saturating_duration_since(time::Instant::now()));
+        if let Ok(result) = time::timeout(wait, socket.recv(&mut buf)).await {
+            let n = result?;
+            if n >= response_len {
+                return Ok(buf[..response_len].to_vec());
+            }
+        }
+        retry_interval *= 2;
+    }
+    Err(PortMappingError::GatewayTimeout)
+}
+
+fn encode_map_request(internal_port: u16, external_port: u16, lifetime: u32) -> [u8; 12] {
+    let mut buf = [0u8; 12];
+    buf[0] = NAT_PMP_VERSION;
+    buf[1] = OP_MAP_TCP;
+    // bytes 2..4 are reserved
+    buf[4..6].copy_from_slice(&internal_port.to_be_bytes());
+    buf[6..8].copy_from_slice(&external_port.to_be_bytes());
+    buf[8..12].copy_from_slice(&lifetime.to_be_bytes());
+    buf
+}
+
+/// Checks the version, opcode and result code of a response
+fn check_response_header(buf: &[u8], op: u8) -> Result<(), PortMappingError> {
+    if buf.len() < 8 || buf[0] != NAT_PMP_VERSION || buf[1] != OP_RESPONSE + op {
+        return Err(PortMappingError::NatPmp("Malformed response".to_string()));
+    }
+    let result_code = u16::from_be_bytes([buf[2], buf[3]]);
+    if result_code != 0 {
+        return Err(PortMappingError::NatPmp(format!(
+            "Gateway returned result code {}",
+            result_code
+        )));
+    }
+    Ok(())
+}
+
+fn decode_public_address_response(buf: &[u8]) -> Result<Ipv4Addr, PortMappingError> {
+    check_response_header(buf, OP_PUBLIC_ADDRESS)?;
+    let octets: [u8; 4] = buf
+        .get(8..12)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| PortMappingError::NatPmp("Malformed public address response".to_string()))?;
+    Ok(Ipv4Addr::from(octets))
+}
+
+/// Returns the mapped external port and the granted lifetime in seconds
+fn decode_map_response(buf: &[u8], op: u8) -> Result<(u16, u32), PortMappingError> {
+    check_response_header(buf, op)?;
+    if buf.len() < 16 {
+        return Err(PortMappingError::NatPmp("Malformed mapping response".to_string()));
+    }
+    let external_port = u16::from_be_bytes([buf[10], buf[11]]);
+    let lifetime = u32::from_be_bytes([buf[12], buf[13], buf[14], buf[15]]);
+    Ok((external_port, lifetime))
+}
+
+#[cfg(target_os = "linux")]
+fn default_gateway() -> Option<Ipv4Addr> {
+    let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+    parse_route_table(&routes)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn default_gateway() -> Option<Ipv4Addr> {
+    None
+}
+
+/// Returns the gateway of the default route in the format of `/proc/net/route`
+#[cfg(any(target_os = "linux", test))]
+fn parse_route_table(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace().skip(1);
+        let destination = fields.next()?;
+        let gateway = fields.next()?;
+        if destination != "00000000" {
+            return None;
+        }
+        // Addresses are written as hex in host (little endian) byte order
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        Some(Ipv4Addr::from(gateway.to_le_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_encodes_a_map_request() {
+        let buf = encode_map_request(18189, 18190, 3600);
+        assert_eq!(buf, [0, 2, 0, 0, 0x47, 0x0d, 0x47, 0x0e, 0, 0, 0x0e, 0x10]);
+    }
+
+    #[test]
+    fn it_decodes_responses() {
+        let buf = [0, 128, 0, 0, 0, 0, 0, 1, 203, 0, 113, 7];
+        assert_eq!(
+            decode_public_address_response(&buf).unwrap(),
+            Ipv4Addr::new(203, 0, 113, 7)
+        );
+
+        let buf = [0, 130, 0, 0, 0, 0, 0, 1, 0x47, 0x0d, 0x47, 0x0e, 0, 0, 0x0e, 0x10];
+        assert_eq!(decode_map_response(&buf, OP_MAP_TCP).unwrap(), (18190, 3600));
+
+        // Non-zero result code
+        let buf = [0, 130, 0, 2, 0, 0, 0, 1, 0x47, 0x0d, 0x47, 0x0e, 0, 0, 0x0e, 0x10];
+        assert!(decode_map_response(&buf, OP_MAP_TCP).is_err());
+        // Wrong opcode
+        assert!(decode_public_address_response(&buf).is_err());
+    }
+
+    #[test]
+    fn it_parses_the_default_gateway() {
+        let routes = "Iface\tDestination\tGateway \
+                      \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\neth0\t0001A8C0\t00000000\t0001\t0\t0\\
+                      t0\t00FFFFFF\t0\t0\t0\neth0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0\n";
+        assert_eq!(parse_route_table(routes), Some(Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(parse_route_table("Iface\tDestination\tGateway\n"), None);
+    }
+}
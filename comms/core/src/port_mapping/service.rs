@@ -0,0 +1,225 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{cmp, sync::Arc, time::Duration};
+
+use log::*;
+use multiaddr::{Multiaddr, Protocol};
+use tari_shutdown::ShutdownSignal;
+use tokio::{task, time};
+
+use super::{nat_pmp, upnp, PortMapping, PortMappingConfig, PortMappingError, PortMappingMethod};
+use crate::peer_manager::NodeIdentity;
+
+const LOG_TARGET: &str = "comms::port_mapping";
+/// Lower bound on the renewal interval, in case the gateway grants a very short lease
+const MIN_RENEWAL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maps the listener port on the gateway and keeps the mapping alive for as long as the node is running
+pub(crate) struct PortMappingService {
+    config: PortMappingConfig,
+    node_identity: Arc<NodeIdentity>,
+    internal_port: u16,
+    external_port: u16,
+    mapping: Option<PortMapping>,
+    shutdown_signal: ShutdownSignal,
+}
+
+impl PortMappingService {
+    pub fn new(
+        config: PortMappingConfig,
+        listener_address: &Multiaddr,
+        node_identity: Arc<NodeIdentity>,
+        shutdown_signal: ShutdownSignal,
+    ) -> Result<Self, PortMappingError> {
+        let internal_port = ip4_tcp_port(listener_address)
+            .ok_or_else(|| PortMappingError::UnsupportedAddress(listener_address.clone()))?;
+        // Request the port of the configured public address, so that the public address is unchanged if it is correct
+        let external_port = ip4_tcp_port(&node_identity.public_address()).unwrap_or(internal_port);
+        Ok(Self {
+            config,
+            node_identity,
+            internal_port,
+            external_port,
+            mapping: None,
+            shutdown_signal,
+        })
+    }
+
+    /// Creates or renews the port mapping, trying each enabled protocol in turn. This is called once before the service
+    /// is spawned so that the node's public address is up to date before it is announced to the network.
+    pub async fn map_port(&mut self) -> Result<(), PortMappingError> {
+        let mut last_error = PortMappingError::AllMethodsDisabled;
+        for method in self.methods() {
+            let result = match method {
+                PortMappingMethod::Upnp => {
+                    upnp::add_mapping(
+                        self.internal_port,
+                        self.external_port,
+                        self.config.lease_duration,
+                        self.config.gateway_timeout,
+                    )
+                    .await
+                },
+                PortMappingMethod::NatPmp => {
+                    nat_pmp::add_mapping(
+                        self.internal_port,
+                        self.external_port,
+                        self.config.lease_duration,
+                        self.config.gateway_timeout,
+                    )
+                    .await
+                },
+            };
+
+            match result {
+                Ok(mapping) => {
+                    self.on_mapped(mapping);
+                    return Ok(());
+                },
+                Err(err) => {
+                    debug!(target: LOG_TARGET, "{} port mapping failed: {}", method, err);
+                    last_error = err;
+                },
+            }
+        }
+
+        self.mapping = None;
+        Err(last_error)
+    }
+
+    pub fn spawn(self) -> task::JoinHandle<()> {
+        task::spawn(self.run())
+    }
+
+    async fn run(mut self) {
+        let mut shutdown_signal = self.shutdown_signal.clone();
+        loop {
+            let delay = match self.mapping {
+                // Renew at half of the lease duration, as recommended by RFC 6886
+                Some(ref mapping) => cmp::max(mapping.lifetime / 2, MIN_RENEWAL_INTERVAL),
+                None => self.config.retry_interval,
+            };
+
+            tokio::select! {
+                biased;
+
+                _ = &mut shutdown_signal => {
+                    self.remove_mapping().await;
+                    break;
+                },
+
+                _ = time::sleep(delay) => {
+                    if let Err(err) = self.map_port().await {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Failed to renew port mapping: {}. Retrying in {:.0?}", err, self.config.retry_interval
+                        );
+                    }
+                },
+            }
+        }
+    }
+
+    fn methods(&self) -> Vec<PortMappingMethod> {
+        let mut methods = Vec::with_capacity(2);
+        // Prefer the method that succeeded previously
+        if let Some(ref mapping) = self.mapping {
+            methods.push(mapping.method);
+        }
+        if self.config.enable_upnp && !methods.contains(&PortMappingMethod::Upnp) {
+            methods.push(PortMappingMethod::Upnp);
+        }
+        if self.config.enable_nat_pmp && !methods.contains(&PortMappingMethod::NatPmp) {
+            methods.push(PortMappingMethod::NatPmp);
+        }
+        methods
+    }
+
+    fn on_mapped(&mut self, mapping: PortMapping) {
+        debug!(
+            target: LOG_TARGET,
+            "{} port mapping {} -> {} granted for {:.0?}",
+            mapping.method,
+            mapping.external_address,
+            self.internal_port,
+            mapping.lifetime
+        );
+        // Some NAT-PMP gateways grant a different port to the one requested, request the same one on renewal
+        self.external_port = mapping.external_address.port();
+
+        if self.config.update_public_address {
+            let mut address = Multiaddr::from(Protocol::Ip4(*mapping.external_address.ip()));
+            address.push(Protocol::Tcp(mapping.external_address.port()));
+            if self.node_identity.public_address() != address {
+                info!(
+                    target: LOG_TARGET,
+                    "Setting public address to {} mapped using {}", address, mapping.method
+                );
+                self.node_identity.set_public_address(address);
+            }
+        }
+        self.mapping = Some(mapping);
+    }
+
+    async fn remove_mapping(&mut self) {
+        let mapping = match self.mapping.take() {
+            Some(mapping) => mapping,
+            None => return,
+        };
+        let result = match mapping.method {
+            PortMappingMethod::Upnp => upnp::remove_mapping(self.external_port, self.config.gateway_timeout).await,
+            PortMappingMethod::NatPmp => nat_pmp::remove_mapping(self.internal_port, self.config.gateway_timeout).await,
+        };
+        match result {
+            Ok(_) => debug!(target: LOG_TARGET, "Removed {} port mapping", mapping.method),
+            Err(err) => debug!(
+                target: LOG_TARGET,
+                "Failed to remove {} port mapping: {}", mapping.method, err
+            ),
+        }
+    }
+}
+
+/// Returns the port of a `/ip4/<address>/tcp/<port>` address
+fn ip4_tcp_port(addr: &Multiaddr) -> Option<u16> {
+    let mut iter = addr.iter();
+    match (iter.next(), iter.next(), iter.next()) {
+        (Some(Protocol::Ip4(_)), Some(Protocol::Tcp(port)), None) if port != 0 => Some(port),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_only_maps_ip4_tcp_addresses() {
+        assert_eq!(ip4_tcp_port(&"/ip4/0.0.0.0/tcp/18189".parse().unwrap()), Some(18189));
+        assert_eq!(ip4_tcp_port(&"/ip4/0.0.0.0/tcp/0".parse().unwrap()), None);
+        assert_eq!(ip4_tcp_port(&"/ip6/::/tcp/18189".parse().unwrap()), None);
+        assert_eq!(ip4_tcp_port(&"/ip4/0.0.0.0/udp/18189/quic".parse().unwrap()), None);
+        assert_eq!(ip4_tcp_port(&"/ip4/0.0.0.0/tcp/18189/ws".parse().unwrap()), None);
+        assert_eq!(ip4_tcp_port(&"/memory/1".parse().unwrap()), None);
+    }
+}
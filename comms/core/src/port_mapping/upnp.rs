@@ -0,0 +1,96 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    convert::TryFrom,
+    net::{IpAddr, Ipv4Addr, SocketAddrV4, UdpSocket},
+    time::Duration,
+};
+
+use igd::{aio, PortMappingProtocol, SearchOptions};
+
+use super::{PortMapping, PortMappingError, PortMappingMethod};
+
+const MAPPING_DESCRIPTION: &str = "Tari";
+
+/// Requests a TCP port mapping from the first UPnP internet gateway device that responds
+pub async fn add_mapping(
+    internal_port: u16,
+    external_port: u16,
+    lease_duration: Duration,
+    timeout: Duration,
+) -> Result<PortMapping, PortMappingError> {
+    let gateway = search_gateway(timeout).await?;
+    let local_ip = local_ip_for_gateway(*gateway.addr.ip())?;
+    let external_ip = gateway.get_external_ip().await.map_err(to_upnp_error)?;
+    let lease_secs = u32::try_from(lease_duration.as_secs()).unwrap_or(u32::MAX);
+    gateway
+        .add_port(
+            PortMappingProtocol::TCP,
+            external_port,
+            SocketAddrV4::new(local_ip, internal_port),
+            lease_secs,
+            MAPPING_DESCRIPTION,
+        )
+        .await
+        .map_err(to_upnp_error)?;
+
+    Ok(PortMapping {
+        method: PortMappingMethod::Upnp,
+        external_address: SocketAddrV4::new(external_ip, external_port),
+        lifetime: lease_duration,
+    })
+}
+
+/// Removes the TCP port mapping for the external port
+pub async fn remove_mapping(external_port: u16, timeout: Duration) -> Result<(), PortMappingError> {
+    let gateway = search_gateway(timeout).await?;
+    gateway
+        .remove_port(PortMappingProtocol::TCP, external_port)
+        .await
+        .map_err(to_upnp_error)
+}
+
+async fn search_gateway(timeout: Duration) -> Result<aio::Gateway, PortMappingError> {
+    aio::search_gateway(SearchOptions {
+        timeout: Some(timeout),
+        ..Default::default()
+    })
+    .await
+    .map_err(to_upnp_error)
+}
+
+/// Returns the address of the local interface that routes to the gateway. The listener is usually bound to the
+/// unspecified address, so this is the address that the gateway must forward to.
+fn local_ip_for_gateway(gateway: Ipv4Addr) -> Result<Ipv4Addr, PortMappingError> {
+    // Connecting a UDP socket does not send any packets
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.connect((gateway, 1900))?;
+    match socket.local_addr()?.ip() {
+        IpAddr::V4(ip) => Ok(ip),
+        IpAddr::V6(_) => Err(PortMappingError::Upnp("Gateway is not reachable over IPv4".to_string())),
+    }
+}
+
+fn to_upnp_error<E: std::error::Error>(err: E) -> PortMappingError {
+    PortMappingError::Upnp(err.to_string())
+}
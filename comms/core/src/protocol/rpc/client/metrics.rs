@@ -20,6 +20,8 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
 use once_cell::sync::Lazy;
 use tari_metrics::{Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec};
 
@@ -128,3 +130,34 @@ pub fn inbound_response_bytes(peer: &NodeId, protocol: &ProtocolId) -> Histogram
 
     METER.with_label_values(&[peer.to_string().as_str(), String::from_utf8_lossy(protocol).as_ref()])
 }
+
+/// RPC request outcomes for a peer, aggregated across protocols
+#[derive(Debug, Clone, Default)]
+pub struct PeerRpcStats {
+    pub num_requests: u32,
+    pub num_errors: u32,
+    pub total_latency: Duration,
+}
+
+static PEER_RPC_STATS: Lazy<Mutex<HashMap<NodeId, PeerRpcStats>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Records the request to first response latency of a successful request to the peer
+pub fn record_peer_request(peer: &NodeId, latency: Duration) {
+    let mut stats = PEER_RPC_STATS.lock().unwrap();
+    let entry = stats.entry(peer.clone()).or_default();
+    entry.num_requests = entry.num_requests.saturating_add(1);
+    entry.total_latency += latency;
+}
+
+/// Records a failed or timed out request to the peer
+pub fn record_peer_error(peer: &NodeId) {
+    let mut stats = PEER_RPC_STATS.lock().unwrap();
+    let entry = stats.entry(peer.clone()).or_default();
+    entry.num_errors = entry.num_errors.saturating_add(1);
+}
+
+/// Returns the per-peer RPC stats recorded since the last call
+pub fn take_peer_rpc_stats() -> HashMap<NodeId, PeerRpcStats> {
+    let mut stats = PEER_RPC_STATS.lock().unwrap();
+    std::mem::take(&mut *stats)
+}
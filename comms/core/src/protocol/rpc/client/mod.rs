@@ -26,7 +26,6 @@ pub mod pool;
 mod tests;
 
 mod metrics;
-
 use std::{
     borrow::Cow,
     convert::TryFrom,
@@ -46,6 +45,7 @@ use futures::{
     StreamExt,
 };
 use log::*;
+pub(crate) use metrics::take_peer_rpc_stats;
 use prost::Message;
 use tari_shutdown::{Shutdown, ShutdownSignal};
 use tokio::{
@@ -487,6 +487,7 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
                         Some(req) => {
                             if let Err(err) = self.handle_request(req).await {
                                 metrics::client_errors(&self.node_id, &self.protocol_id).inc();
+                                metrics::record_peer_error(&self.node_id);
                                 error!(target: LOG_TARGET, "(stream={}) Unexpected error: {}. Worker is terminating.", self.stream_id(), err);
                                 break;
                             }
@@ -558,6 +559,7 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
                     start.elapsed()
                 );
                 metrics::client_timeouts(&self.node_id, &self.protocol_id).inc();
+                metrics::record_peer_error(&self.node_id);
                 let _result = reply.send(Err(RpcStatus::timed_out("Response timed out")));
                 return Ok(());
             },
@@ -636,6 +638,7 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
         if let Err(err) = self.send_request(req).await {
             warn!(target: LOG_TARGET, "{}", err);
             metrics::client_errors(&self.node_id, &self.protocol_id).inc();
+            metrics::record_peer_error(&self.node_id);
             let _result = response_tx.send(Err(err.into()));
             return Ok(());
         }
@@ -657,7 +660,9 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
             let resp = match self.read_response(request_id).await {
                 Ok(resp) => {
                     if let Some(t) = timer.take() {
-                        let _ = self.last_request_latency_tx.send(Some(t.elapsed()));
+                        let latency = t.elapsed();
+                        let _ = self.last_request_latency_tx.send(Some(latency));
+                        metrics::record_peer_request(&self.node_id, latency);
                     }
                     event!(Level::TRACE, "Message received");
                     trace!(
@@ -681,6 +686,7 @@ where TSubstream: AsyncRead + AsyncWrite + Unpin + Send + StreamId
                     );
                     event!(Level::ERROR, "Response timed out");
                     metrics::client_timeouts(&self.node_id, &self.protocol_id).inc();
+                    metrics::record_peer_error(&self.node_id);
                     if response_tx.is_closed() {
                         let req = proto::rpc::RpcRequest {
                             request_id: u32::try_from(request_id).unwrap(),
@@ -33,8 +33,8 @@ mod test;
 pub const RPC_MAX_FRAME_SIZE: usize = 2 * 1024 * 1024; // 2 MiB
 /// Maximum number of chunks into which a message can be broken up.
 const RPC_CHUNKING_MAX_CHUNKS: usize = 16; // 16 x 256 Kib = 4 MiB max combined message size
+/// The default chunk size
 const RPC_CHUNKING_THRESHOLD: usize = 256 * 1024;
-const RPC_CHUNKING_SIZE_LIMIT: usize = 384 * 1024;
 
 /// The maximum request payload size
 const fn max_request_size() -> usize {
@@ -63,9 +63,19 @@ pub use body::{Body, ClientStreaming, IntoBody, Streaming};
 mod context;
 
 mod server;
-pub use server::{mock, NamedProtocolService, RpcServer, RpcServerError, RpcServerHandle};
+pub use server::{
+    mock,
+    AdaptiveChunkingConfig,
+    NamedProtocolService,
+    RpcChunkingConfig,
+    RpcServer,
+    RpcServerError,
+    RpcServerHandle,
+    RpcSessionInfo,
+};
 
 mod client;
+pub(crate) use client::take_peer_rpc_stats;
 pub use client::{
     pool,
     pool::{RpcClientLease, RpcClientPool, RpcClientPoolError, RpcPoolClient},
@@ -88,6 +98,11 @@ pub use handshake::{Handshake, RpcHandshakeError};
 mod status;
 pub use status::{RpcStatus, RpcStatusCode, RpcStatusResultExt};
 
+/// The protobuf messages exchanged by the RPC protocol, exposed for protocol conformance testing
+pub mod wire {
+    pub use crate::proto::rpc::{RpcRequest, RpcResponse, RpcSession, RpcSessionReply};
+}
+
 mod not_found;
 
 // Re-exports used to keep things orderly in the #[tari_rpc] proc macro
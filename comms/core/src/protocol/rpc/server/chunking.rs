@@ -20,7 +20,14 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::cmp;
+use std::{
+    cmp,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use bytes::Bytes;
 use log::*;
@@ -33,26 +40,151 @@ use crate::{
         rpc::{
             message::{RpcMessageFlags, RpcResponse},
             RpcStatusCode,
-            RPC_CHUNKING_SIZE_LIMIT,
+            RPC_CHUNKING_MAX_CHUNKS,
             RPC_CHUNKING_THRESHOLD,
+            RPC_MAX_FRAME_SIZE,
         },
     },
 };
 
+/// The smallest chunk size that may be configured
+const MIN_CHUNK_SIZE: usize = 4 * 1024;
+/// The largest chunk size that may be configured. A chunk may grow to 1.5x this size, which must fit in a frame.
+const MAX_CHUNK_SIZE: usize = RPC_MAX_FRAME_SIZE / 2;
+
+/// Configures how RPC response messages are split into chunks
+#[derive(Debug, Clone, Copy)]
+pub struct RpcChunkingConfig {
+    /// The size of each chunk. The last chunk may be up to 1.5x this size to avoid emitting a small trailing chunk. A
+    /// response is never split into more than the maximum number of chunks supported by clients, so larger responses
+    /// are split into larger chunks if necessary. Clamped to between 4 KiB and 1 MiB.
+    /// Default: 256 KiB
+    pub chunk_size: usize,
+    /// If set, the chunk size of a session is reduced when sending chunks to the peer is slow (e.g. over a Tor circuit
+    /// or when the peer applies backpressure) and gradually increased back up to `chunk_size` when sending is fast.
+    /// Default: None (disabled)
+    pub adaptive: Option<AdaptiveChunkingConfig>,
+}
+
+impl RpcChunkingConfig {
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        Self {
+            chunk_size,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_adaptive(mut self, adaptive: AdaptiveChunkingConfig) -> Self {
+        self.adaptive = Some(adaptive);
+        self
+    }
+}
+
+impl Default for RpcChunkingConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: RPC_CHUNKING_THRESHOLD,
+            adaptive: None,
+        }
+    }
+}
+
+/// Adaptive chunking parameters
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveChunkingConfig {
+    /// The chunk size will not be reduced below this size.
+    /// Default: 16 KiB
+    pub min_chunk_size: usize,
+    /// The chunk size is halved when sending a chunk takes longer than this.
+    /// Default: 2 seconds
+    pub high_latency_threshold: Duration,
+    /// The chunk size is increased by `min_chunk_size` when sending a chunk takes less than this.
+    /// Default: 500 milliseconds
+    pub low_latency_threshold: Duration,
+}
+
+impl Default for AdaptiveChunkingConfig {
+    fn default() -> Self {
+        Self {
+            min_chunk_size: 16 * 1024,
+            high_latency_threshold: Duration::from_secs(2),
+            low_latency_threshold: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Tracks the chunk size of a session. The current chunk size is shared with the response stream of the session and
+/// applies to the next response message that is chunked.
+#[derive(Debug, Clone)]
+pub(super) struct ChunkSizeController {
+    max_chunk_size: usize,
+    adaptive: Option<AdaptiveChunkingConfig>,
+    current: Arc<AtomicUsize>,
+}
+
+impl ChunkSizeController {
+    pub fn new(config: RpcChunkingConfig) -> Self {
+        let max_chunk_size = clamp_chunk_size(config.chunk_size);
+        let adaptive = config.adaptive.map(|mut adaptive| {
+            adaptive.min_chunk_size = cmp::min(clamp_chunk_size(adaptive.min_chunk_size), max_chunk_size);
+            adaptive
+        });
+        Self {
+            max_chunk_size,
+            adaptive,
+            current: Arc::new(AtomicUsize::new(max_chunk_size)),
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    /// Adjusts the chunk size given the time taken to send a chunk to the peer. Has no effect if adaptive chunking is
+    /// disabled.
+    pub fn observe_send(&self, elapsed: Duration) {
+        let adaptive = match self.adaptive {
+            Some(ref adaptive) => adaptive,
+            None => return,
+        };
+        let current = self.current();
+        let next = if elapsed >= adaptive.high_latency_threshold {
+            cmp::max(current / 2, adaptive.min_chunk_size)
+        } else if elapsed <= adaptive.low_latency_threshold {
+            cmp::min(current + adaptive.min_chunk_size, self.max_chunk_size)
+        } else {
+            current
+        };
+        if next != current {
+            debug!(
+                target: LOG_TARGET,
+                "Adjusting RPC chunk size from {} to {} bytes (last send took {:.2?})", current, next, elapsed
+            );
+            self.current.store(next, Ordering::Relaxed);
+        }
+    }
+}
+
+fn clamp_chunk_size(chunk_size: usize) -> usize {
+    cmp::min(cmp::max(chunk_size, MIN_CHUNK_SIZE), MAX_CHUNK_SIZE)
+}
+
 pub(super) struct ChunkedResponseIter {
     message: RpcResponse,
     initial_payload_size: usize,
     has_emitted_once: bool,
     num_chunks: usize,
     total_chunks: usize,
+    threshold: usize,
+    size_limit: usize,
 }
 
-fn calculate_total_chunk_count(payload_len: usize) -> usize {
-    let mut total_chunks = payload_len / RPC_CHUNKING_THRESHOLD;
-    let excess = (payload_len % RPC_CHUNKING_THRESHOLD) + RPC_CHUNKING_THRESHOLD;
-    if total_chunks == 0 || excess > RPC_CHUNKING_SIZE_LIMIT {
-        // If the chunk (threshold size) + excess cannot fit in the RPC_CHUNKING_SIZE_LIMIT, then we'll emit another
-        // frame smaller than threshold size
+fn calculate_total_chunk_count(payload_len: usize, threshold: usize, size_limit: usize) -> usize {
+    let mut total_chunks = payload_len / threshold;
+    let excess = (payload_len % threshold) + threshold;
+    if total_chunks == 0 || excess > size_limit {
+        // If the chunk (threshold size) + excess cannot fit in the size limit, then we'll emit another frame smaller
+        // than threshold size
         total_chunks += 1;
     }
 
@@ -60,14 +192,27 @@ fn calculate_total_chunk_count(payload_len: usize) -> usize {
 }
 
 impl ChunkedResponseIter {
+    #[cfg(test)]
     pub fn new(message: RpcResponse) -> Self {
+        Self::with_chunk_size(message, RPC_CHUNKING_THRESHOLD)
+    }
+
+    pub fn with_chunk_size(message: RpcResponse, chunk_size: usize) -> Self {
         let len = message.payload.len();
+        // Use larger chunks if the payload would otherwise be split into more chunks than clients accept
+        let threshold = cmp::max(
+            chunk_size,
+            (len + RPC_CHUNKING_MAX_CHUNKS - 1) / RPC_CHUNKING_MAX_CHUNKS,
+        );
+        let size_limit = threshold + threshold / 2;
         Self {
             initial_payload_size: message.payload.len(),
             message,
             has_emitted_once: false,
             num_chunks: 0,
-            total_chunks: calculate_total_chunk_count(len),
+            total_chunks: calculate_total_chunk_count(len, threshold, size_limit),
+            threshold,
+            size_limit,
         }
     }
 
@@ -99,7 +244,7 @@ impl ChunkedResponseIter {
         }
 
         // If the payload is within the maximum chunk size, simply return the rest of it
-        if len <= RPC_CHUNKING_SIZE_LIMIT {
+        if len <= self.size_limit {
             let chunk = self.payload_mut().split_to(len);
             self.num_chunks += 1;
             trace!(
@@ -112,7 +257,7 @@ impl ChunkedResponseIter {
             return Some(chunk);
         }
 
-        let chunk_size = cmp::min(len, RPC_CHUNKING_THRESHOLD);
+        let chunk_size = cmp::min(len, self.threshold);
         let chunk = self.payload_mut().split_to(chunk_size);
 
         self.num_chunks += 1;
@@ -201,6 +346,8 @@ mod test {
 
     use super::*;
 
+    const RPC_CHUNKING_SIZE_LIMIT: usize = 384 * 1024;
+
     fn create(size: usize) -> ChunkedResponseIter {
         let msg = RpcResponse {
             payload: iter::repeat(0).take(size).collect(),
@@ -258,4 +405,56 @@ mod test {
         assert!(RpcMessageFlags::from_bits_truncate(u8::try_from(msgs[1].flags).unwrap()).is_more());
         assert!(!RpcMessageFlags::from_bits_truncate(u8::try_from(msgs[2].flags).unwrap()).is_more());
     }
+
+    #[test]
+    fn it_emits_chunks_of_the_configured_size() {
+        let msg = RpcResponse {
+            payload: iter::repeat(0).take(100 * 1024).collect(),
+            ..Default::default()
+        };
+        let msgs = ChunkedResponseIter::with_chunk_size(msg, 32 * 1024).collect::<Vec<_>>();
+        assert_eq!(msgs.len(), 3);
+        assert_eq!(msgs[0].payload.len(), 32 * 1024);
+        assert_eq!(msgs[2].payload.len(), 36 * 1024);
+    }
+
+    #[test]
+    fn it_does_not_exceed_the_maximum_number_of_chunks() {
+        let msg = RpcResponse {
+            payload: iter::repeat(0).take(rpc::max_response_payload_size()).collect(),
+            ..Default::default()
+        };
+        let iter = ChunkedResponseIter::with_chunk_size(msg, MIN_CHUNK_SIZE);
+        assert!(iter.total_chunks <= RPC_CHUNKING_MAX_CHUNKS);
+        assert_eq!(iter.count(), RPC_CHUNKING_MAX_CHUNKS);
+    }
+
+    #[test]
+    fn it_adapts_the_chunk_size_to_send_latency() {
+        let adaptive = AdaptiveChunkingConfig::default();
+        let controller = ChunkSizeController::new(RpcChunkingConfig::default().with_adaptive(adaptive));
+        assert_eq!(controller.current(), RPC_CHUNKING_THRESHOLD);
+
+        controller.observe_send(adaptive.high_latency_threshold);
+        assert_eq!(controller.current(), RPC_CHUNKING_THRESHOLD / 2);
+        for _ in 0..10 {
+            controller.observe_send(Duration::from_secs(10));
+        }
+        assert_eq!(controller.current(), adaptive.min_chunk_size);
+
+        controller.observe_send(adaptive.low_latency_threshold);
+        assert_eq!(controller.current(), adaptive.min_chunk_size * 2);
+        for _ in 0..100 {
+            controller.observe_send(Duration::from_millis(1));
+        }
+        assert_eq!(controller.current(), RPC_CHUNKING_THRESHOLD);
+    }
+
+    #[test]
+    fn it_does_not_adapt_if_disabled() {
+        let controller = ChunkSizeController::new(RpcChunkingConfig::with_chunk_size(1));
+        assert_eq!(controller.current(), MIN_CHUNK_SIZE);
+        controller.observe_send(Duration::from_secs(10));
+        assert_eq!(controller.current(), MIN_CHUNK_SIZE);
+    }
 }
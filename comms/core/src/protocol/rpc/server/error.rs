@@ -35,6 +35,8 @@ pub enum RpcServerError {
     Io(#[from] io::Error),
     #[error("Maximum number of RPC sessions reached")]
     MaximumSessionsReached,
+    #[error("RPC server is not accepting sessions")]
+    NotAcceptingSessions,
     #[error("Internal service request canceled")]
     RequestCanceled,
     #[error("Stream was closed by remote")]
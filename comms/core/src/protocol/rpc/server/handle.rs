@@ -20,13 +20,26 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::time::Instant;
+
 use tokio::sync::{mpsc, oneshot};
 
 use super::RpcServerError;
+use crate::{peer_manager::NodeId, protocol::ProtocolId};
 
 #[derive(Debug)]
 pub enum RpcServerRequest {
     GetNumActiveSessions(oneshot::Sender<usize>),
+    ListSessions(oneshot::Sender<Vec<RpcSessionInfo>>),
+    SetAcceptingSessions(bool),
+}
+
+/// Information about an active RPC session
+#[derive(Debug, Clone)]
+pub struct RpcSessionInfo {
+    pub node_id: NodeId,
+    pub protocol: ProtocolId,
+    pub started_at: Instant,
 }
 
 #[derive(Debug, Clone)]
@@ -47,4 +60,23 @@ impl RpcServerHandle {
             .map_err(|_| RpcServerError::RequestCanceled)?;
         resp.await.map_err(Into::into)
     }
+
+    /// Returns the active RPC sessions
+    pub async fn list_sessions(&mut self) -> Result<Vec<RpcSessionInfo>, RpcServerError> {
+        let (req, resp) = oneshot::channel();
+        self.sender
+            .send(RpcServerRequest::ListSessions(req))
+            .await
+            .map_err(|_| RpcServerError::RequestCanceled)?;
+        resp.await.map_err(Into::into)
+    }
+
+    /// Sets whether the server accepts new sessions. While not accepting sessions, only reserved session peers are
+    /// able to establish sessions. Existing sessions are not affected.
+    pub async fn set_accepting_sessions(&mut self, accepting: bool) -> Result<(), RpcServerError> {
+        self.sender
+            .send(RpcServerRequest::SetAcceptingSessions(accepting))
+            .await
+            .map_err(|_| RpcServerError::RequestCanceled)
+    }
 }
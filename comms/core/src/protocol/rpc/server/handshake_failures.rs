@@ -0,0 +1,165 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::peer_manager::NodeId;
+
+/// Tracks peers that repeatedly fail the RPC handshake (e.g. by sending malformed frames or disconnecting mid
+/// handshake) and refuses new substreams from them for a period of time.
+pub(super) struct HandshakeFailureTracker {
+    max_failures: usize,
+    failure_window: Duration,
+    refuse_duration: Duration,
+    peers: HashMap<NodeId, PeerFailures>,
+}
+
+#[derive(Default)]
+struct PeerFailures {
+    window_start: Option<Instant>,
+    num_failures: usize,
+    refused_until: Option<Instant>,
+}
+
+impl HandshakeFailureTracker {
+    /// Peers that fail the handshake `max_failures` times within `failure_window` are refused for
+    /// `refuse_duration`. A `max_failures` of zero disables refusals.
+    pub fn new(max_failures: usize, failure_window: Duration, refuse_duration: Duration) -> Self {
+        Self {
+            max_failures,
+            failure_window,
+            refuse_duration,
+            peers: HashMap::new(),
+        }
+    }
+
+    /// Returns true if new substreams from the peer should be refused
+    pub fn is_refused(&mut self, node_id: &NodeId, now: Instant) -> bool {
+        match self.peers.get(node_id).and_then(|p| p.refused_until) {
+            Some(until) if until > now => true,
+            Some(_) => {
+                self.peers.remove(node_id);
+                false
+            },
+            None => false,
+        }
+    }
+
+    /// Records a failed handshake, returning true if the peer is now refused
+    pub fn record_failure(&mut self, node_id: &NodeId, now: Instant) -> bool {
+        if self.max_failures == 0 {
+            return false;
+        }
+        self.prune(now);
+
+        let failure_window = self.failure_window;
+        let peer = self.peers.entry(node_id.clone()).or_default();
+        match peer.window_start {
+            Some(start) if now.saturating_duration_since(start) <= failure_window => {
+                peer.num_failures += 1;
+            },
+            _ => {
+                peer.window_start = Some(now);
+                peer.num_failures = 1;
+            },
+        }
+
+        if peer.num_failures >= self.max_failures {
+            peer.refused_until = Some(now + self.refuse_duration);
+            peer.window_start = None;
+            peer.num_failures = 0;
+            return true;
+        }
+        false
+    }
+
+    /// Clears the failures of a peer that completed the handshake
+    pub fn record_success(&mut self, node_id: &NodeId) {
+        self.peers.remove(node_id);
+    }
+
+    pub fn num_refused(&self, now: Instant) -> usize {
+        self.peers
+            .values()
+            .filter(|p| p.refused_until.map(|until| until > now).unwrap_or(false))
+            .count()
+    }
+
+    /// Removes peers whose failure window and refusal have both expired
+    fn prune(&mut self, now: Instant) {
+        let failure_window = self.failure_window;
+        self.peers.retain(|_, peer| {
+            let is_refused = peer.refused_until.map(|until| until > now).unwrap_or(false);
+            let in_window = peer
+                .window_start
+                .map(|start| now.saturating_duration_since(start) <= failure_window)
+                .unwrap_or(false);
+            is_refused || in_window
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::node_id;
+
+    #[test]
+    fn it_refuses_peers_that_repeatedly_fail() {
+        let mut tracker = HandshakeFailureTracker::new(3, Duration::from_secs(60), Duration::from_secs(600));
+        let peer = node_id::random();
+        let now = Instant::now();
+
+        assert!(!tracker.record_failure(&peer, now));
+        assert!(!tracker.record_failure(&peer, now + Duration::from_secs(1)));
+        assert!(!tracker.is_refused(&peer, now + Duration::from_secs(1)));
+        assert!(tracker.record_failure(&peer, now + Duration::from_secs(2)));
+        assert!(tracker.is_refused(&peer, now + Duration::from_secs(3)));
+        assert_eq!(tracker.num_refused(now + Duration::from_secs(3)), 1);
+
+        // The refusal expires
+        assert!(!tracker.is_refused(&peer, now + Duration::from_secs(603)));
+        assert_eq!(tracker.num_refused(now + Duration::from_secs(603)), 0);
+    }
+
+    #[test]
+    fn it_forgets_failures_outside_the_window_or_after_success() {
+        let mut tracker = HandshakeFailureTracker::new(2, Duration::from_secs(60), Duration::from_secs(600));
+        let peer = node_id::random();
+        let now = Instant::now();
+
+        assert!(!tracker.record_failure(&peer, now));
+        assert!(!tracker.record_failure(&peer, now + Duration::from_secs(61)));
+        tracker.record_success(&peer);
+        assert!(!tracker.record_failure(&peer, now + Duration::from_secs(62)));
+        assert!(!tracker.is_refused(&peer, now + Duration::from_secs(62)));
+
+        let mut disabled = HandshakeFailureTracker::new(0, Duration::from_secs(60), Duration::from_secs(600));
+        for _ in 0..10 {
+            assert!(!disabled.record_failure(&peer, now));
+        }
+        assert!(!disabled.is_refused(&peer, now));
+    }
+}
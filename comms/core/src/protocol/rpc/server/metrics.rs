@@ -57,6 +57,31 @@ pub fn handshake_error_counter(node_id: &NodeId, protocol: &ProtocolId) -> IntCo
     METER.with_label_values(&[node_id.to_string().as_str(), String::from_utf8_lossy(protocol).as_ref()])
 }
 
+pub fn handshake_refused_counter(node_id: &NodeId, protocol: &ProtocolId) -> IntCounter {
+    static METER: Lazy<IntCounterVec> = Lazy::new(|| {
+        tari_metrics::register_int_counter_vec(
+            "comms::rpc::server::handshake_refused_count",
+            "The number of substreams refused because the peer repeatedly failed the handshake per peer per protocol",
+            &["peer_id", "protocol"],
+        )
+        .unwrap()
+    });
+
+    METER.with_label_values(&[node_id.to_string().as_str(), String::from_utf8_lossy(protocol).as_ref()])
+}
+
+pub fn num_refused_peers() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        tari_metrics::register_int_gauge(
+            "comms::rpc::server::num_refused_peers",
+            "The number of peers currently refused because they repeatedly failed the handshake",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
 pub fn error_counter(node_id: &NodeId, protocol: &ProtocolId, err: &RpcServerError) -> IntCounter {
     static METER: Lazy<IntCounterVec> = Lazy::new(|| {
         tari_metrics::register_int_counter_vec(
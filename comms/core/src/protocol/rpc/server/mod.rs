@@ -21,14 +21,18 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 mod chunking;
-use chunking::ChunkedResponseIter;
+pub use chunking::{AdaptiveChunkingConfig, RpcChunkingConfig};
+use chunking::{ChunkSizeController, ChunkedResponseIter};
 
 mod error;
 pub use error::RpcServerError;
 
 mod handle;
-pub use handle::RpcServerHandle;
 use handle::RpcServerRequest;
+pub use handle::{RpcServerHandle, RpcSessionInfo};
+
+mod handshake_failures;
+use handshake_failures::HandshakeFailureTracker;
 
 mod metrics;
 
@@ -37,11 +41,12 @@ pub mod mock;
 mod router;
 use std::{
     borrow::Cow,
+    collections::{HashMap, HashSet},
     convert::TryFrom,
     future::Future,
     io,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex},
     task::Poll,
     time::{Duration, Instant},
 };
@@ -165,8 +170,16 @@ impl Default for RpcServer {
 #[derive(Clone)]
 pub struct RpcServerBuilder {
     maximum_simultaneous_sessions: Option<usize>,
+    reserved_session_peers: HashSet<NodeId>,
+    maximum_reserved_sessions: usize,
     minimum_client_deadline: Duration,
     handshake_timeout: Duration,
+    accepting_sessions: bool,
+    max_handshake_failures: usize,
+    handshake_failure_window: Duration,
+    handshake_refuse_duration: Duration,
+    chunking: RpcChunkingConfig,
+    protocol_chunking: HashMap<ProtocolId, RpcChunkingConfig>,
 }
 
 impl RpcServerBuilder {
@@ -184,11 +197,58 @@ impl RpcServerBuilder {
         self
     }
 
+    /// Reserve up to `limit` sessions for the given (locally authorized) peers. These peers are able to establish
+    /// sessions even if the maximum number of simultaneous sessions has been reached.
+    pub fn with_reserved_sessions<I: IntoIterator<Item = NodeId>>(mut self, peers: I, limit: usize) -> Self {
+        self.reserved_session_peers = peers.into_iter().collect();
+        self.maximum_reserved_sessions = limit;
+        self
+    }
+
+    /// Sets whether the server initially accepts new sessions from peers that are not reserved session peers. This
+    /// can be changed at runtime using `RpcServerHandle::set_accepting_sessions`. Default: true
+    pub fn with_accepting_sessions(mut self, accepting: bool) -> Self {
+        self.accepting_sessions = accepting;
+        self
+    }
+
+    /// Refuse new substreams for `refuse_duration` from peers that fail the handshake `max_failures` times within
+    /// `failure_window`. A `max_failures` of zero disables refusals. Default: 5 failures within 1 minute refuses the
+    /// peer for 10 minutes
+    pub fn with_handshake_failure_limit(
+        mut self,
+        max_failures: usize,
+        failure_window: Duration,
+        refuse_duration: Duration,
+    ) -> Self {
+        self.max_handshake_failures = max_failures;
+        self.handshake_failure_window = failure_window;
+        self.handshake_refuse_duration = refuse_duration;
+        self
+    }
+
     pub fn with_minimum_client_deadline(mut self, deadline: Duration) -> Self {
         self.minimum_client_deadline = deadline;
         self
     }
 
+    /// Sets how response messages are split into chunks for all protocols that do not have a protocol specific
+    /// chunking config. Default: 256 KiB chunks, adaptive chunking disabled
+    pub fn with_chunking(mut self, config: RpcChunkingConfig) -> Self {
+        self.chunking = config;
+        self
+    }
+
+    /// Sets how response messages are split into chunks for the given protocol
+    pub fn with_protocol_chunking(mut self, protocol: ProtocolId, config: RpcChunkingConfig) -> Self {
+        self.protocol_chunking.insert(protocol, config);
+        self
+    }
+
+    fn chunking_config(&self, protocol: &ProtocolId) -> RpcChunkingConfig {
+        self.protocol_chunking.get(protocol).copied().unwrap_or(self.chunking)
+    }
+
     pub fn finish(self) -> RpcServer {
         let (request_tx, request_rx) = mpsc::channel(10);
         RpcServer {
@@ -203,19 +263,32 @@ impl Default for RpcServerBuilder {
     fn default() -> Self {
         Self {
             maximum_simultaneous_sessions: Some(1000),
+            reserved_session_peers: HashSet::new(),
+            maximum_reserved_sessions: 0,
             minimum_client_deadline: Duration::from_secs(1),
             handshake_timeout: Duration::from_secs(15),
+            accepting_sessions: true,
+            max_handshake_failures: 5,
+            handshake_failure_window: Duration::from_secs(60),
+            handshake_refuse_duration: Duration::from_secs(10 * 60),
+            chunking: RpcChunkingConfig::default(),
+            protocol_chunking: HashMap::new(),
         }
     }
 }
 
 pub(super) struct PeerRpcServer<TSvc, TCommsProvider> {
     executor: BoundedExecutor,
+    reserved_executor: BoundedExecutor,
     config: RpcServerBuilder,
     service: TSvc,
     protocol_notifications: Option<ProtocolNotificationRx<Substream>>,
     comms_provider: TCommsProvider,
     request_rx: mpsc::Receiver<RpcServerRequest>,
+    accepting_sessions: bool,
+    sessions: Arc<Mutex<HashMap<u64, RpcSessionInfo>>>,
+    next_session_id: u64,
+    handshake_failures: HandshakeFailureTracker,
 }
 
 impl<TSvc, TCommsProvider> PeerRpcServer<TSvc, TCommsProvider>
@@ -245,11 +318,20 @@ where
                 Some(num) => BoundedExecutor::from_current(num),
                 None => BoundedExecutor::allow_maximum(),
             },
+            reserved_executor: BoundedExecutor::from_current(config.maximum_reserved_sessions),
+            accepting_sessions: config.accepting_sessions,
+            handshake_failures: HandshakeFailureTracker::new(
+                config.max_handshake_failures,
+                config.handshake_failure_window,
+                config.handshake_refuse_duration,
+            ),
             config,
             service,
             protocol_notifications: Some(protocol_notifications),
             comms_provider,
             request_rx,
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            next_session_id: 0,
         }
     }
 
@@ -283,17 +365,38 @@ where
         Ok(())
     }
 
-    async fn handle_request(&self, req: RpcServerRequest) {
-        use RpcServerRequest::GetNumActiveSessions;
+    async fn handle_request(&mut self, req: RpcServerRequest) {
+        use RpcServerRequest::{GetNumActiveSessions, ListSessions, SetAcceptingSessions};
         match req {
             GetNumActiveSessions(reply) => {
                 let max_sessions = self
                     .config
                     .maximum_simultaneous_sessions
                     .unwrap_or_else(BoundedExecutor::max_theoretical_tasks);
-                let num_active = max_sessions.saturating_sub(self.executor.num_available());
+                let num_active = max_sessions.saturating_sub(self.executor.num_available()) +
+                    self.config
+                        .maximum_reserved_sessions
+                        .saturating_sub(self.reserved_executor.num_available());
                 let _ = reply.send(num_active);
             },
+            ListSessions(reply) => {
+                let sessions = self
+                    .sessions
+                    .lock()
+                    .expect("RPC session list lock poisoned")
+                    .values()
+                    .cloned()
+                    .collect();
+                let _ = reply.send(sessions);
+            },
+            SetAcceptingSessions(accepting) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "RPC server is {} accepting new sessions",
+                    if accepting { "now" } else { "no longer" }
+                );
+                self.accepting_sessions = accepting;
+            },
         }
     }
 
@@ -311,15 +414,37 @@ where
                     node_id
                 );
 
+                // Checked before the handshake so that misbehaving peers do not use up any session permits
+                if self.handshake_failures.is_refused(&node_id, Instant::now()) {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Refusing RPC substream from peer `{}` because it repeatedly failed the handshake", node_id
+                    );
+                    metrics::handshake_refused_counter(&node_id, &notification.protocol).inc();
+                    return Ok(());
+                }
+
                 let framed = framing::canonical(substream, RPC_MAX_FRAME_SIZE);
                 match self
                     .try_initiate_service(notification.protocol.clone(), &node_id, framed)
                     .await
                 {
-                    Ok(_) => {},
+                    Ok(_) => {
+                        self.handshake_failures.record_success(&node_id);
+                    },
                     Err(err @ RpcServerError::HandshakeError(_)) => {
                         debug!(target: LOG_TARGET, "{}", err);
                         metrics::handshake_error_counter(&node_id, &notification.protocol).inc();
+                        let now = Instant::now();
+                        if self.handshake_failures.record_failure(&node_id, now) {
+                            warn!(
+                                target: LOG_TARGET,
+                                "Peer `{}` repeatedly failed the RPC handshake and will be refused for {:.0?}",
+                                node_id,
+                                self.config.handshake_refuse_duration
+                            );
+                        }
+                        metrics::num_refused_peers().set(self.handshake_failures.num_refused(now) as i64);
                     },
                     Err(err) => {
                         debug!(target: LOG_TARGET, "Unable to spawn RPC service: {}", err);
@@ -340,7 +465,21 @@ where
     ) -> Result<(), RpcServerError> {
         let mut handshake = Handshake::new(&mut framed).with_timeout(self.config.handshake_timeout);
 
-        if !self.executor.can_spawn() {
+        if !self.accepting_sessions && !self.config.reserved_session_peers.contains(node_id) {
+            debug!(
+                target: LOG_TARGET,
+                "Rejecting RPC session request for peer `{}` because the server is not accepting sessions", node_id
+            );
+            handshake
+                .reject_with_reason(HandshakeRejectReason::NoSessionsAvailable)
+                .await?;
+            return Err(RpcServerError::NotAcceptingSessions);
+        }
+
+        // Locally authorized peers use the reserved sessions first, so that they do not take sessions from other peers
+        let use_reserved_session =
+            self.config.reserved_session_peers.contains(node_id) && self.reserved_executor.can_spawn();
+        if !use_reserved_session && !self.executor.can_spawn() {
             debug!(
                 target: LOG_TARGET,
                 "Rejecting RPC session request for peer `{}` because {}",
@@ -385,11 +524,35 @@ where
         );
 
         let node_id = node_id.clone();
-        self.executor
+        let executor = if use_reserved_session {
+            debug!(
+                target: LOG_TARGET,
+                "Using a reserved RPC session for peer `{}`", node_id
+            );
+            &self.reserved_executor
+        } else {
+            &self.executor
+        };
+        let session_id = self.next_session_id;
+        self.next_session_id = self.next_session_id.wrapping_add(1);
+        let sessions = self.sessions.clone();
+        executor
             .try_spawn(async move {
                 let num_sessions = metrics::num_sessions(&node_id, &service.protocol);
                 num_sessions.inc();
+                sessions
+                    .lock()
+                    .expect("RPC session list lock poisoned")
+                    .insert(session_id, RpcSessionInfo {
+                        node_id: node_id.clone(),
+                        protocol: service.protocol.clone(),
+                        started_at: Instant::now(),
+                    });
                 service.start().await;
+                sessions
+                    .lock()
+                    .expect("RPC session list lock poisoned")
+                    .remove(&session_id);
                 num_sessions.dec();
             })
             .map_err(|_| RpcServerError::MaximumSessionsReached)?;
@@ -406,6 +569,7 @@ struct ActivePeerRpcService<TSvc, TCommsProvider> {
     framed: CanonicalFraming<Substream>,
     comms_provider: TCommsProvider,
     logging_context_string: Arc<String>,
+    chunk_size: ChunkSizeController,
 }
 
 impl<TSvc, TCommsProvider> ActivePeerRpcService<TSvc, TCommsProvider>
@@ -421,6 +585,7 @@ where
         framed: CanonicalFraming<Substream>,
         comms_provider: TCommsProvider,
     ) -> Self {
+        let chunk_size = ChunkSizeController::new(config.chunking_config(&protocol));
         Self {
             logging_context_string: Arc::new(format!(
                 "stream_id: {}, peer: {}, protocol: {}",
@@ -435,6 +600,7 @@ where
             service,
             framed,
             comms_provider,
+            chunk_size,
         }
     }
 
@@ -630,6 +796,7 @@ where
 
         let node_id = self.node_id.clone();
         let protocol = self.protocol.clone();
+        let chunk_size = self.chunk_size.clone();
         let mut stream = body
             .into_message()
             .map(|result| into_response(request_id, result))
@@ -637,7 +804,7 @@ where
                 if !message.status.is_ok() {
                     metrics::status_error_counter(&node_id, &protocol, message.status).inc();
                 }
-                stream::iter(ChunkedResponseIter::new(message))
+                stream::iter(ChunkedResponseIter::with_chunk_size(message, chunk_size.current()))
             })
             .map(|resp| Bytes::from(resp.to_encoded_bytes()));
 
@@ -672,7 +839,9 @@ where
                         msg.len()
                     );
 
+                    let send_start = Instant::now();
                     self.framed.send(msg).await?;
+                    self.chunk_size.observe_send(send_start.elapsed());
                 },
                 Ok(None) => {
                     debug!(target: LOG_TARGET, "{} Request complete", self.logging_context_string,);
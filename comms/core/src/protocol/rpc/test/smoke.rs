@@ -41,6 +41,7 @@ use crate::{
             context::RpcCommsBackend,
             error::HandshakeRejectReason,
             handshake::RpcHandshakeError,
+            server::RpcServerBuilder,
             test::{
                 greeting_service::{
                     GreetingClient,
@@ -75,6 +76,19 @@ pub(super) async fn setup_service<T: GreetingRpc>(
     task::JoinHandle<()>,
     RpcCommsBackend,
     Shutdown,
+) {
+    let builder = RpcServer::builder().with_maximum_simultaneous_sessions(num_concurrent_sessions);
+    setup_service_with_builder(service_impl, builder).await
+}
+
+async fn setup_service_with_builder<T: GreetingRpc>(
+    service_impl: T,
+    builder: RpcServerBuilder,
+) -> (
+    mpsc::Sender<ProtocolNotification<Substream>>,
+    task::JoinHandle<()>,
+    RpcCommsBackend,
+    Shutdown,
 ) {
     let (notif_tx, notif_rx) = mpsc::channel(1);
     let shutdown = Shutdown::new();
@@ -83,8 +97,7 @@ pub(super) async fn setup_service<T: GreetingRpc>(
         let context = context.clone();
         let shutdown_signal = shutdown.to_signal();
         async move {
-            let fut = RpcServer::builder()
-                .with_maximum_simultaneous_sessions(num_concurrent_sessions)
+            let fut = builder
                 .with_minimum_client_deadline(Duration::from_secs(0))
                 .finish()
                 .add_service(GreetingServer::new(service_impl))
@@ -367,6 +380,181 @@ async fn rejected_no_sessions_available() {
     ));
 }
 
+#[runtime::test]
+async fn reserved_session_peers_bypass_the_session_limit() {
+    let node_identity = build_node_identity(Default::default());
+    let builder = RpcServer::builder()
+        .with_maximum_simultaneous_sessions(0)
+        .with_reserved_sessions(vec![node_identity.node_id().clone()], 1);
+    let (notif_tx, _, context, _shutdown) = setup_service_with_builder(GreetingService::default(), builder).await;
+    context.peer_manager().add_peer(node_identity.to_peer()).await.unwrap();
+
+    let (_, mut inbound, outbound) = build_multiplexed_connections().await;
+    let substream = outbound.get_yamux_control().open_stream().await.unwrap();
+    notif_tx
+        .send(ProtocolNotification::new(
+            ProtocolId::from_static(b"/test/greeting/1.0"),
+            ProtocolEvent::NewInboundSubstream(node_identity.node_id().clone(), substream),
+        ))
+        .await
+        .unwrap();
+    let socket = inbound.incoming_mut().next().await.unwrap();
+    let framed = framing::canonical(socket, 1024);
+    let mut client = GreetingClient::builder().connect(framed).await.unwrap();
+    let resp = client
+        .say_hello(SayHelloRequest {
+            name: "Yathvan".to_string(),
+            language: 1,
+        })
+        .await
+        .unwrap();
+    assert_eq!(resp.greeting, "Jambo Yathvan");
+
+    // Other peers are still subject to the session limit
+    let other_peer = build_node_identity(Default::default());
+    context.peer_manager().add_peer(other_peer.to_peer()).await.unwrap();
+    let substream = outbound.get_yamux_control().open_stream().await.unwrap();
+    notif_tx
+        .send(ProtocolNotification::new(
+            ProtocolId::from_static(b"/test/greeting/1.0"),
+            ProtocolEvent::NewInboundSubstream(other_peer.node_id().clone(), substream),
+        ))
+        .await
+        .unwrap();
+    let socket = inbound.incoming_mut().next().await.unwrap();
+    let framed = framing::canonical(socket, 1024);
+    let err = GreetingClient::builder().connect(framed).await.unwrap_err();
+    assert!(matches!(
+        err,
+        RpcError::HandshakeError(RpcHandshakeError::Rejected(HandshakeRejectReason::NoSessionsAvailable))
+    ));
+}
+
+#[runtime::test]
+async fn sessions_are_rejected_until_the_server_accepts_sessions() {
+    let (notif_tx, notif_rx) = mpsc::channel(1);
+    let (context, _) = create_mocked_rpc_context();
+    let server = RpcServer::builder()
+        .with_accepting_sessions(false)
+        .with_minimum_client_deadline(Duration::from_secs(0))
+        .finish()
+        .add_service(GreetingServer::new(GreetingService::default()));
+    let mut handle = server.get_handle();
+    let shutdown = Shutdown::new();
+    task::spawn({
+        let context = context.clone();
+        let shutdown_signal = shutdown.to_signal();
+        async move {
+            tokio::select! {
+                biased;
+                _ = shutdown_signal => {},
+                r = server.serve(notif_rx, context) => r.unwrap(),
+            }
+        }
+    });
+
+    let node_identity = build_node_identity(Default::default());
+    context.peer_manager().add_peer(node_identity.to_peer()).await.unwrap();
+    let (_, mut inbound, outbound) = build_multiplexed_connections().await;
+
+    let substream = outbound.get_yamux_control().open_stream().await.unwrap();
+    notif_tx
+        .send(ProtocolNotification::new(
+            ProtocolId::from_static(b"/test/greeting/1.0"),
+            ProtocolEvent::NewInboundSubstream(node_identity.node_id().clone(), substream),
+        ))
+        .await
+        .unwrap();
+    let socket = inbound.incoming_mut().next().await.unwrap();
+    let err = GreetingClient::builder()
+        .connect(framing::canonical(socket, 1024))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        RpcError::HandshakeError(RpcHandshakeError::Rejected(HandshakeRejectReason::NoSessionsAvailable))
+    ));
+
+    handle.set_accepting_sessions(true).await.unwrap();
+    // Requests are handled in order, so once this returns the server is accepting sessions
+    assert_eq!(handle.get_num_active_sessions().await.unwrap(), 0);
+    let substream = outbound.get_yamux_control().open_stream().await.unwrap();
+    notif_tx
+        .send(ProtocolNotification::new(
+            ProtocolId::from_static(b"/test/greeting/1.0"),
+            ProtocolEvent::NewInboundSubstream(node_identity.node_id().clone(), substream),
+        ))
+        .await
+        .unwrap();
+    let socket = inbound.incoming_mut().next().await.unwrap();
+    let mut client = GreetingClient::builder()
+        .connect(framing::canonical(socket, 1024))
+        .await
+        .unwrap();
+    let resp = client
+        .say_hello(SayHelloRequest {
+            name: "Yathvan".to_string(),
+            language: 1,
+        })
+        .await
+        .unwrap();
+    assert_eq!(resp.greeting, "Jambo Yathvan");
+}
+
+#[runtime::test]
+async fn active_sessions_are_listed() {
+    let (notif_tx, notif_rx) = mpsc::channel(1);
+    let (context, _) = create_mocked_rpc_context();
+    let server = RpcServer::builder()
+        .with_minimum_client_deadline(Duration::from_secs(0))
+        .finish()
+        .add_service(GreetingServer::new(GreetingService::default()));
+    let mut handle = server.get_handle();
+    let shutdown = Shutdown::new();
+    task::spawn({
+        let context = context.clone();
+        let shutdown_signal = shutdown.to_signal();
+        async move {
+            tokio::select! {
+                biased;
+                _ = shutdown_signal => {},
+                r = server.serve(notif_rx, context) => r.unwrap(),
+            }
+        }
+    });
+
+    assert!(handle.list_sessions().await.unwrap().is_empty());
+
+    let node_identity = build_node_identity(Default::default());
+    context.peer_manager().add_peer(node_identity.to_peer()).await.unwrap();
+    let (_, mut inbound, outbound) = build_multiplexed_connections().await;
+    let substream = outbound.get_yamux_control().open_stream().await.unwrap();
+    notif_tx
+        .send(ProtocolNotification::new(
+            ProtocolId::from_static(b"/test/greeting/1.0"),
+            ProtocolEvent::NewInboundSubstream(node_identity.node_id().clone(), substream),
+        ))
+        .await
+        .unwrap();
+    let socket = inbound.incoming_mut().next().await.unwrap();
+    let mut client = GreetingClient::builder()
+        .connect(framing::canonical(socket, 1024))
+        .await
+        .unwrap();
+    client
+        .say_hello(SayHelloRequest {
+            name: "Yathvan".to_string(),
+            language: 1,
+        })
+        .await
+        .unwrap();
+
+    let sessions = handle.list_sessions().await.unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].node_id, *node_identity.node_id());
+    assert_eq!(sessions[0].protocol, ProtocolId::from_static(b"/test/greeting/1.0"));
+}
+
 #[runtime::test]
 async fn stream_still_works_after_cancel() {
     let service_impl = GreetingService::default();
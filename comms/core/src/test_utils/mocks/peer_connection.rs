@@ -34,6 +34,7 @@ use tokio_stream::StreamExt;
 use crate::{
     connection_manager::{
         ConnectionDirection,
+        ConnectionStats,
         NegotiatedSubstream,
         PeerConnection,
         PeerConnectionError,
@@ -60,6 +61,7 @@ pub fn create_dummy_peer_connection(node_id: NodeId) -> (PeerConnection, mpsc::R
             Multiaddr::empty(),
             ConnectionDirection::Inbound,
             AtomicRefCounter::new(),
+            ConnectionStats::new(),
         ),
         rx,
     )
@@ -97,6 +99,7 @@ pub async fn create_peer_connection_mock_pair(
             listen_addr.clone(),
             ConnectionDirection::Inbound,
             mock_state_in.substream_counter(),
+            ConnectionStats::new(),
         ),
         mock_state_in,
         PeerConnection::new(
@@ -107,6 +110,7 @@ pub async fn create_peer_connection_mock_pair(
             listen_addr,
             ConnectionDirection::Outbound,
             mock_state_out.substream_counter(),
+            ConnectionStats::new(),
         ),
         mock_state_out,
     )
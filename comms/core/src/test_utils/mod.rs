@@ -33,6 +33,8 @@ pub mod node_identity;
 pub mod peer_manager;
 pub use peer_manager::build_peer_manager;
 
+pub mod simulation;
+
 pub mod transport;
 
 pub fn count_string_occurrences<T, U>(items: T, expected: &[&str]) -> usize
@@ -0,0 +1,332 @@
+//  Copyright 2022, The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! # Simulated network
+//!
+//! An in-process network built on the memsocket transport in which link latencies and network partitions can be
+//! scripted. All delays use `tokio::time`, so when the tokio clock is paused (e.g. `#[tokio::test(start_paused =
+//! true)]`) a simulation of many nodes runs in virtual time and its timing is deterministic.
+//!
+//! Each node is given its own [SimulatedTransport] via [SimulatedNetwork::transport]. Outbound connections are relayed
+//! through a task that delays each chunk of data by the current link latency and severs the connection if the two
+//! nodes are partitioned from each other.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use multiaddr::{Multiaddr, Protocol};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    sync::{mpsc, watch},
+    task,
+    time,
+    time::Instant,
+};
+
+use crate::{
+    memsocket::MemorySocket,
+    transports::{MemoryTransport, Transport},
+};
+
+const RELAY_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Shared, scriptable network conditions for a set of in-process nodes
+#[derive(Debug, Clone)]
+pub struct SimulatedNetwork {
+    conditions: Arc<RwLock<NetworkConditions>>,
+    changed_tx: Arc<watch::Sender<()>>,
+    changed_rx: watch::Receiver<()>,
+}
+
+#[derive(Debug, Default)]
+struct NetworkConditions {
+    default_latency: Duration,
+    link_latencies: HashMap<(u16, u16), Duration>,
+    partitions: Vec<HashSet<u16>>,
+}
+
+impl NetworkConditions {
+    fn latency(&self, a: u16, b: u16) -> Duration {
+        self.link_latencies
+            .get(&link_key(a, b))
+            .copied()
+            .unwrap_or(self.default_latency)
+    }
+
+    fn is_reachable(&self, a: u16, b: u16) -> bool {
+        let partition_of = |port: u16| self.partitions.iter().position(|p| p.contains(&port));
+        match (partition_of(a), partition_of(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        }
+    }
+}
+
+impl SimulatedNetwork {
+    pub fn new() -> Self {
+        let (changed_tx, changed_rx) = watch::channel(());
+        Self {
+            conditions: Default::default(),
+            changed_tx: Arc::new(changed_tx),
+            changed_rx,
+        }
+    }
+
+    /// Returns a transport for the node listening on `listener_address`. The address must be a `/memory/<port>`
+    /// address.
+    pub fn transport(&self, listener_address: &Multiaddr) -> SimulatedTransport {
+        let local_port = parse_port(listener_address).expect("listener_address must be a /memory address");
+        SimulatedTransport {
+            network: self.clone(),
+            local_port,
+        }
+    }
+
+    /// Sets the one-way latency for all links that do not have a specific latency set
+    pub fn set_default_latency(&self, latency: Duration) {
+        self.update(|c| c.default_latency = latency);
+    }
+
+    /// Sets the one-way latency between the nodes at the given addresses
+    pub fn set_link_latency(&self, a: &Multiaddr, b: &Multiaddr, latency: Duration) {
+        let key = link_key(port_of(a), port_of(b));
+        self.update(|c| {
+            c.link_latencies.insert(key, latency);
+        });
+    }
+
+    /// Splits the network into the given groups. Nodes in different groups cannot dial each other and existing
+    /// connections between them are severed. Nodes that are not in any group can reach every node.
+    pub fn partition(&self, groups: &[&[Multiaddr]]) {
+        let partitions = groups.iter().map(|group| group.iter().map(port_of).collect()).collect();
+        self.update(|c| c.partitions = partitions);
+    }
+
+    /// Removes all partitions
+    pub fn heal(&self) {
+        self.update(|c| c.partitions.clear());
+    }
+
+    /// Returns true if the nodes at the given addresses are able to communicate
+    pub fn is_reachable(&self, a: &Multiaddr, b: &Multiaddr) -> bool {
+        self.read().is_reachable(port_of(a), port_of(b))
+    }
+
+    fn latency(&self, a: u16, b: u16) -> Duration {
+        self.read().latency(a, b)
+    }
+
+    fn is_reachable_port(&self, a: u16, b: u16) -> bool {
+        self.read().is_reachable(a, b)
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, NetworkConditions> {
+        self.conditions.read().expect("simulated network lock poisoned")
+    }
+
+    fn update<F: FnOnce(&mut NetworkConditions)>(&self, f: F) {
+        f(&mut *self.conditions.write().expect("simulated network lock poisoned"));
+        let _result = self.changed_tx.send(());
+    }
+
+    /// Resolves once the given nodes are partitioned from each other
+    async fn wait_for_partition(&self, a: u16, b: u16) {
+        let mut changed_rx = self.changed_rx.clone();
+        while self.is_reachable_port(a, b) {
+            if changed_rx.changed().await.is_err() {
+                // The network has been dropped, so it will never be partitioned
+                futures::future::pending::<()>().await;
+            }
+        }
+    }
+}
+
+impl Default for SimulatedNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A memsocket transport for a single node in a [SimulatedNetwork]
+#[derive(Debug, Clone)]
+pub struct SimulatedTransport {
+    network: SimulatedNetwork,
+    local_port: u16,
+}
+
+#[crate::async_trait]
+impl Transport for SimulatedTransport {
+    type Error = io::Error;
+    type Listener = <MemoryTransport as Transport>::Listener;
+    type Output = MemorySocket;
+
+    async fn listen(&self, addr: Multiaddr) -> Result<(Self::Listener, Multiaddr), Self::Error> {
+        MemoryTransport.listen(addr).await
+    }
+
+    async fn dial(&self, addr: Multiaddr) -> Result<Self::Output, Self::Error> {
+        let remote_port = parse_port(&addr)?;
+        let local_port = self.local_port;
+        if !self.network.is_reachable_port(local_port, remote_port) {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("{} is partitioned from this node", addr),
+            ));
+        }
+        // Connection setup costs a round trip
+        time::sleep(self.network.latency(local_port, remote_port) * 2).await;
+        let upstream = MemoryTransport.dial(addr).await?;
+        let (socket, relay_socket) = MemorySocket::new_pair();
+        let network = self.network.clone();
+        task::spawn(async move {
+            tokio::select! {
+                _ = relay_connection(network.clone(), local_port, remote_port, relay_socket, upstream) => {},
+                _ = network.wait_for_partition(local_port, remote_port) => {},
+            }
+        });
+        Ok(socket)
+    }
+}
+
+async fn relay_connection(network: SimulatedNetwork, local: u16, remote: u16, a: MemorySocket, b: MemorySocket) {
+    let (a_read, a_write) = tokio::io::split(a);
+    let (b_read, b_write) = tokio::io::split(b);
+    // If either direction closes, the whole connection is closed
+    tokio::select! {
+        _ = relay(network.clone(), local, remote, a_read, b_write) => {},
+        _ = relay(network, local, remote, b_read, a_write) => {},
+    }
+}
+
+async fn relay<R, W>(network: SimulatedNetwork, local: u16, remote: u16, mut reader: R, mut writer: W)
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let (tx, mut rx) = mpsc::unbounded_channel::<(Instant, Vec<u8>)>();
+    let read = async move {
+        let mut buf = vec![0u8; RELAY_BUFFER_SIZE];
+        loop {
+            match reader.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let deliver_at = Instant::now() + network.latency(local, remote);
+                    if tx.send((deliver_at, buf[..n].to_vec())).is_err() {
+                        break;
+                    }
+                },
+            }
+        }
+    };
+    let write = async move {
+        while let Some((deliver_at, bytes)) = rx.recv().await {
+            time::sleep_until(deliver_at).await;
+            if writer.write_all(&bytes).await.is_err() {
+                return;
+            }
+        }
+        let _result = writer.shutdown().await;
+    };
+    // Data already in flight is still delivered once the reader has closed
+    futures::future::join(read, write).await;
+}
+
+fn link_key(a: u16, b: u16) -> (u16, u16) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn port_of(addr: &Multiaddr) -> u16 {
+    parse_port(addr).expect("simulated network addresses must be /memory addresses")
+}
+
+fn parse_port(addr: &Multiaddr) -> io::Result<u16> {
+    match addr.iter().next() {
+        Some(Protocol::Memory(port)) if port <= u64::from(u16::MAX) => Ok(port as u16),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid memory address '{}'", addr),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::StreamExt;
+
+    use super::*;
+
+    async fn listen(network: &SimulatedNetwork) -> (<MemoryTransport as Transport>::Listener, Multiaddr) {
+        let port = MemoryTransport::acquire_next_memsocket_port();
+        let addr: Multiaddr = format!("/memory/{}", port).parse().unwrap();
+        network.transport(&addr).listen(addr).await.unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_delays_data_by_the_link_latency() {
+        let network = SimulatedNetwork::new();
+        let (_, addr_a) = listen(&network).await;
+        let (mut listener_b, addr_b) = listen(&network).await;
+        network.set_link_latency(&addr_a, &addr_b, Duration::from_millis(100));
+
+        let start = Instant::now();
+        let mut socket_a = network.transport(&addr_a).dial(addr_b).await.unwrap();
+        let (mut socket_b, _) = listener_b.next().await.unwrap().unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(200));
+
+        let start = Instant::now();
+        socket_a.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        socket_b.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+        assert_eq!(start.elapsed(), Duration::from_millis(100));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_enforces_partitions() {
+        let network = SimulatedNetwork::new();
+        let (_, addr_a) = listen(&network).await;
+        let (mut listener_b, addr_b) = listen(&network).await;
+
+        let mut socket_a = network.transport(&addr_a).dial(addr_b.clone()).await.unwrap();
+        let (_socket_b, _) = listener_b.next().await.unwrap().unwrap();
+
+        network.partition(&[&[addr_a.clone()], &[addr_b.clone()]]);
+        assert!(!network.is_reachable(&addr_a, &addr_b));
+        // The existing connection is severed
+        let mut buf = [0u8; 1];
+        assert_eq!(socket_a.read(&mut buf).await.unwrap(), 0);
+        // New connections are refused
+        let err = network.transport(&addr_a).dial(addr_b.clone()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+
+        network.heal();
+        network.transport(&addr_a).dial(addr_b).await.unwrap();
+    }
+}
@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use bitflags::bitflags;
 use log::*;
@@ -40,6 +40,7 @@ use crate::{
 };
 
 const LOG_TARGET: &str = "comms::tor::hidden_service";
+const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Error)]
 pub enum HiddenServiceBuilderError {
@@ -72,6 +73,7 @@ pub struct HiddenServiceBuilder {
     control_server_auth: Authentication,
     socks_auth: socks::Authentication,
     hs_flags: HsFlags,
+    health_check_interval: Option<Duration>,
     shutdown_signal: OptionalShutdownSignal,
 }
 
@@ -125,6 +127,14 @@ impl HiddenServiceBuilder {
         HsFlags
     );
 
+    setter!(
+        /// How often to check that the hidden service is still registered with the Tor daemon. A service that has been
+        /// dropped is re-published with the same key. Default: 60 seconds
+        with_health_check_interval,
+        health_check_interval,
+        Option<Duration>
+    );
+
     /// Use a direct TCP/IP connection if a TCP address is given instead of the tor proxy. This is worse for privacy
     /// but can use the full available connection bandwidth
     pub fn bypass_tor_for_tcp_addresses(mut self) -> Self {
@@ -180,6 +190,7 @@ impl HiddenServiceBuilder {
             self.identity,
             self.hs_flags,
             self.proxy_opts,
+            self.health_check_interval.unwrap_or(DEFAULT_HEALTH_CHECK_INTERVAL),
             self.shutdown_signal,
         );
 
@@ -26,7 +26,7 @@ use futures::{future, future::Either, pin_mut, StreamExt};
 use log::*;
 use tari_shutdown::OptionalShutdownSignal;
 use thiserror::Error;
-use tokio::{sync::broadcast, time};
+use tokio::{sync::broadcast, time, time::MissedTickBehavior};
 
 use crate::{
     multiaddr::Multiaddr,
@@ -37,7 +37,7 @@ use crate::{
             commands::{AddOnionFlag, AddOnionResponse},
             TorControlEvent,
         },
-        hidden_service::TorProxyOpts,
+        hidden_service::{HiddenServiceEvent, TorProxyOpts},
         Authentication,
         HiddenService,
         HsFlags,
@@ -79,6 +79,8 @@ pub struct HiddenServiceController {
     hs_flags: HsFlags,
     is_authenticated: bool,
     proxy_opts: TorProxyOpts,
+    health_check_interval: Duration,
+    events: broadcast::Sender<HiddenServiceEvent>,
     shutdown_signal: OptionalShutdownSignal,
 }
 
@@ -92,8 +94,10 @@ impl HiddenServiceController {
         identity: Option<TorIdentity>,
         hs_flags: HsFlags,
         proxy_opts: TorProxyOpts,
+        health_check_interval: Duration,
         shutdown_signal: OptionalShutdownSignal,
     ) -> Self {
+        let (events, _) = broadcast::channel(20);
         Self {
             client: None,
             control_server_addr,
@@ -105,6 +109,8 @@ impl HiddenServiceController {
             identity,
             is_authenticated: false,
             proxy_opts,
+            health_check_interval,
+            events,
             shutdown_signal,
         }
     }
@@ -128,6 +134,10 @@ impl HiddenServiceController {
     /// Connects, authenticates to the Tor control port and creates a hidden service using the tor identity if provided,
     /// otherwise a new tor identity will be created. The creation of a hidden service is idempotent i.e. if the
     /// hidden service exists, the
+    ///
+    /// A watchdog task keeps the hidden service available: the control port connection is re-established if it drops
+    /// and the hidden service is re-published with the same key if it is no longer registered with the Tor daemon.
+    /// Use [HiddenService::subscribe_events] to be notified of these changes.
     pub async fn create_hidden_service(mut self) -> Result<HiddenService, HiddenServiceControllerError> {
         self.connect_and_auth().await?;
         self.set_events().await?;
@@ -138,38 +148,58 @@ impl HiddenServiceController {
 
         task::spawn({
             async move {
+                let mut health_check = time::interval(self.health_check_interval);
+                health_check.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                // The first tick completes immediately and the service has just been created
+                health_check.tick().await;
+
                 loop {
-                    let either = future::select(&mut shutdown_signal, event_stream.next()).await;
-                    match either {
-                        Either::Left(_) => {
+                    tokio::select! {
+                        _ = &mut shutdown_signal => {
                             debug!(
                                 target: LOG_TARGET,
                                 "Tor controller shut down because the shutdown signal was received"
                             );
                             break;
                         },
-                        Either::Right((Some(Ok(TorControlEvent::TorControlDisconnected)), shutdown_signal)) => {
-                            let event_tx = self
-                                .client
-                                .as_ref()
-                                .map(|c| c.event_sender().clone())
-                                .expect("HiddenServiceController::client was None");
-                            warn!(
-                                target: LOG_TARGET,
-                                "Tor control server disconnected. Attempting to reestablish connection..."
-                            );
-                            if let Err(err) = self.reestablish_hidden_service(event_tx, shutdown_signal).await {
-                                error!(
+                        Some(event) = event_stream.next() => match event {
+                            Ok(TorControlEvent::TorControlDisconnected) => {
+                                let event_tx = self
+                                    .client
+                                    .as_ref()
+                                    .map(|c| c.event_sender().clone())
+                                    .expect("HiddenServiceController::client was None");
+                                warn!(
                                     target: LOG_TARGET,
-                                    "Failed to reestablish connection to tor control server because '{:?}'", err
+                                    "Tor control server disconnected. Attempting to reestablish connection..."
                                 );
-                                break;
-                            }
+                                self.publish_event(HiddenServiceEvent::TorControlDisconnected);
+                                let result = self.reestablish_hidden_service(event_tx, &mut shutdown_signal).await;
+                                if let Err(err) = result {
+                                    error!(
+                                        target: LOG_TARGET,
+                                        "Failed to reestablish connection to tor control server because '{:?}'", err
+                                    );
+                                    break;
+                                }
+                            },
+                            Ok(TorControlEvent::NetworkLivenessUp) => {
+                                self.publish_event(HiddenServiceEvent::NetworkLivenessUp);
+                            },
+                            Ok(TorControlEvent::NetworkLivenessDown) => {
+                                warn!(target: LOG_TARGET, "Tor reports that the network is unreachable");
+                                self.publish_event(HiddenServiceEvent::NetworkLivenessDown);
+                            },
+                            Ok(evt) => {
+                                trace!(target: LOG_TARGET, "Tor control event: {:?}", evt);
+                            },
+                            Err(err) => {
+                                warn!(target: LOG_TARGET, "Tor control event stream error: {}", err);
+                            },
                         },
-                        Either::Right((Some(Ok(evt)), _)) => {
-                            trace!(target: LOG_TARGET, "Tor control event: {:?}", evt);
+                        _ = health_check.tick() => {
+                            self.check_hidden_service().await;
                         },
-                        _ => {},
                     }
                 }
             }
@@ -202,12 +232,25 @@ impl HiddenServiceController {
             pin_mut!(connect_fut);
             let either = future::select(connect_fut, signal.take().expect("signal was None")).await;
             match either {
-                Either::Left((Ok(client), _)) => {
+                Either::Left((Ok(client), shutdown_signal)) => {
                     self.client = Some(client);
-                    self.authenticate().await?;
-                    self.set_events().await?;
-                    let _result = self.create_hidden_service_from_identity().await;
-                    break Ok(());
+                    match self.republish_hidden_service().await {
+                        Ok(()) => {
+                            self.publish_event(HiddenServiceEvent::TorControlReconnected);
+                            break Ok(());
+                        },
+                        Err(err) => {
+                            signal = Some(shutdown_signal);
+                            warn!(
+                                target: LOG_TARGET,
+                                "Failed to re-publish hidden service after reconnecting because '{:?}'", err
+                            );
+                            self.publish_event(HiddenServiceEvent::RepublishFailed(err.to_string()));
+                            self.client = None;
+                            warn!(target: LOG_TARGET, "Will attempt again in 5 seconds...");
+                            time::sleep(Duration::from_secs(5)).await;
+                        },
+                    }
                 },
                 Either::Left((Err(err), shutdown_signal)) => {
                     signal = Some(shutdown_signal);
@@ -226,6 +269,73 @@ impl HiddenServiceController {
         }
     }
 
+    async fn republish_hidden_service(&mut self) -> Result<(), HiddenServiceControllerError> {
+        self.authenticate().await?;
+        self.set_events().await?;
+        self.create_hidden_service_from_identity().await?;
+        Ok(())
+    }
+
+    /// Checks that the hidden service is still registered with the Tor daemon and re-publishes it if it is not
+    async fn check_hidden_service(&mut self) {
+        let service_id = match self.identity.as_ref() {
+            Some(identity) => identity.service_id.clone(),
+            None => return,
+        };
+        // Detached services are listed separately from services owned by this control connection
+        let key = if self.hs_flags.contains(HsFlags::DETACH) {
+            "onions/detached"
+        } else {
+            "onions/current"
+        };
+        let client = match self.client_mut() {
+            Ok(client) => client,
+            // A disconnect is handled by the TorControlDisconnected event
+            Err(_) => return,
+        };
+        match client.get_info(key).await {
+            Ok(service_ids) if service_ids.iter().any(|id| **id == service_id) => {
+                trace!(target: LOG_TARGET, "Hidden service '{}' is registered", service_id);
+                return;
+            },
+            Ok(_) => {},
+            Err(err) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to check that hidden service '{}' is registered because '{:?}'", service_id, err
+                );
+                return;
+            },
+        }
+
+        warn!(
+            target: LOG_TARGET,
+            "Hidden service '{}' is no longer registered with the Tor daemon. Re-publishing it...", service_id
+        );
+        self.publish_event(HiddenServiceEvent::HiddenServiceLost);
+        match self.create_hidden_service_from_identity().await {
+            Ok(_) => {
+                info!(target: LOG_TARGET, "Re-published hidden service '{}'", service_id);
+                self.publish_event(HiddenServiceEvent::HiddenServiceRepublished);
+            },
+            Err(err) => {
+                error!(
+                    target: LOG_TARGET,
+                    "Failed to re-publish hidden service '{}' because '{:?}'. Will try again in {:.0?}",
+                    service_id,
+                    err,
+                    self.health_check_interval
+                );
+                self.publish_event(HiddenServiceEvent::RepublishFailed(err.to_string()));
+            },
+        }
+    }
+
+    fn publish_event(&self, event: HiddenServiceEvent) {
+        // An error only means that there are no subscribers
+        let _result = self.events.send(event);
+    }
+
     fn client_mut(&mut self) -> Result<&mut TorControlPortClient, HiddenServiceControllerError> {
         self.client
             .as_mut()
@@ -327,6 +437,7 @@ impl HiddenServiceController {
         Ok(HiddenService {
             identity,
             proxied_addr,
+            events: self.events.clone(),
             shutdown_signal: self.shutdown_signal.clone(),
         })
     }
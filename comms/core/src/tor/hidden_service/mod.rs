@@ -33,6 +33,7 @@ use derivative::Derivative;
 pub use proxy_opts::TorProxyOpts;
 use serde_derive::{Deserialize, Serialize};
 use tari_shutdown::OptionalShutdownSignal;
+use tokio::sync::broadcast;
 
 use crate::{
     multiaddr::Multiaddr,
@@ -47,6 +48,8 @@ pub struct HiddenService {
     pub(super) identity: TorIdentity,
     /// The address where incoming traffic to the `onion_addr` will be forwarded to.
     pub(super) proxied_addr: Multiaddr,
+    /// Publishes changes to the availability of the hidden service
+    pub(super) events: broadcast::Sender<HiddenServiceEvent>,
     /// Shutdown signal for hidden service
     pub(super) shutdown_signal: OptionalShutdownSignal,
 }
@@ -69,6 +72,31 @@ impl HiddenService {
     pub fn tor_identity(&self) -> &TorIdentity {
         &self.identity
     }
+
+    /// Subscribe to changes in the availability of the hidden service
+    pub fn subscribe_events(&self) -> broadcast::Receiver<HiddenServiceEvent> {
+        self.events.subscribe()
+    }
+}
+
+/// Changes to the availability of a [HiddenService]
+#[derive(Debug, Clone)]
+pub enum HiddenServiceEvent {
+    /// The connection to the Tor control port was lost. Inbound connections are unavailable until it is re-established
+    /// unless the service is detached.
+    TorControlDisconnected,
+    /// The connection to the Tor control port was re-established and the hidden service was re-published
+    TorControlReconnected,
+    /// The hidden service is no longer registered with the Tor daemon
+    HiddenServiceLost,
+    /// The hidden service was re-published using the same key
+    HiddenServiceRepublished,
+    /// Re-publishing the hidden service failed. It will be retried.
+    RepublishFailed(String),
+    /// Tor reports that the network is reachable
+    NetworkLivenessUp,
+    /// Tor reports that the network is unreachable
+    NetworkLivenessDown,
 }
 
 fn multiaddr_from_service_id_and_port(service_id: &str, onion_port: u16) -> Result<Multiaddr, TorClientError> {
@@ -49,6 +49,7 @@ pub use hidden_service::{
     HiddenServiceBuilderError,
     HiddenServiceController,
     HiddenServiceControllerError,
+    HiddenServiceEvent,
     HsFlags,
     TorIdentity,
 };
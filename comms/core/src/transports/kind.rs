@@ -0,0 +1,148 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{fmt, str::FromStr};
+
+use multiaddr::{Multiaddr, Protocol};
+
+/// The kind of transport that an address is reached over. This is used to order a peer's addresses when dialing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransportKind {
+    Tcp,
+    Onion,
+    WebSocket,
+    Quic,
+    Memory,
+}
+
+impl TransportKind {
+    /// Returns the kind of transport used to reach the given address, or None if the address is not supported by any
+    /// transport.
+    pub fn from_address(address: &Multiaddr) -> Option<Self> {
+        let mut kind = None;
+        for protocol in address.iter() {
+            match protocol {
+                Protocol::Onion(_, _) | Protocol::Onion3(_) => return Some(TransportKind::Onion),
+                Protocol::Ws(_) | Protocol::Wss(_) => return Some(TransportKind::WebSocket),
+                Protocol::Quic => return Some(TransportKind::Quic),
+                Protocol::Memory(_) => return Some(TransportKind::Memory),
+                Protocol::Tcp(_) => kind = Some(TransportKind::Tcp),
+                _ => {},
+            }
+        }
+        kind
+    }
+}
+
+impl fmt::Display for TransportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TransportKind::Tcp => "tcp",
+            TransportKind::Onion => "onion",
+            TransportKind::WebSocket => "ws",
+            TransportKind::Quic => "quic",
+            TransportKind::Memory => "memory",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for TransportKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "tcp" => Ok(TransportKind::Tcp),
+            "onion" | "tor" => Ok(TransportKind::Onion),
+            "ws" | "websocket" => Ok(TransportKind::WebSocket),
+            "quic" => Ok(TransportKind::Quic),
+            "memory" => Ok(TransportKind::Memory),
+            s => Err(format!("Invalid transport kind '{}'", s)),
+        }
+    }
+}
+
+/// Orders addresses by the position of their transport in `preferred`. Addresses with a transport that is not in
+/// `preferred` are placed last. The sort is stable, so addresses with the same preference keep their given order.
+pub fn order_by_transport_preference<'a, I: IntoIterator<Item = &'a Multiaddr>>(
+    addresses: I,
+    preferred: &[TransportKind],
+) -> Vec<Multiaddr> {
+    let mut addresses = addresses.into_iter().cloned().collect::<Vec<_>>();
+    if preferred.is_empty() {
+        return addresses;
+    }
+    addresses.sort_by_key(|addr| {
+        TransportKind::from_address(addr)
+            .and_then(|kind| preferred.iter().position(|p| *p == kind))
+            .unwrap_or(preferred.len())
+    });
+    addresses
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_classifies_addresses() {
+        let kind = |s: &str| TransportKind::from_address(&s.parse().unwrap());
+        assert_eq!(kind("/ip4/1.2.3.4/tcp/18189"), Some(TransportKind::Tcp));
+        assert_eq!(kind("/dns4/example.com/tcp/18189"), Some(TransportKind::Tcp));
+        assert_eq!(kind("/ip4/1.2.3.4/tcp/18190/ws"), Some(TransportKind::WebSocket));
+        assert_eq!(kind("/ip4/1.2.3.4/udp/18189/quic"), Some(TransportKind::Quic));
+        assert_eq!(kind("/memory/1"), Some(TransportKind::Memory));
+        assert_eq!(
+            kind("/onion3/vww6ybal4bd7szmgncyruucpgfkqahzddi37ktceo3ah7ngmcopnpyyd:1234"),
+            Some(TransportKind::Onion)
+        );
+        assert_eq!(kind("/ip4/1.2.3.4"), None);
+    }
+
+    #[test]
+    fn it_orders_by_preference() {
+        let tcp = "/ip4/1.2.3.4/tcp/18189".parse::<Multiaddr>().unwrap();
+        let tcp2 = "/ip4/5.6.7.8/tcp/18189".parse::<Multiaddr>().unwrap();
+        let ws = "/ip4/1.2.3.4/tcp/18190/ws".parse::<Multiaddr>().unwrap();
+        let onion = "/onion3/vww6ybal4bd7szmgncyruucpgfkqahzddi37ktceo3ah7ngmcopnpyyd:1234"
+            .parse::<Multiaddr>()
+            .unwrap();
+        let addresses = vec![tcp.clone(), ws.clone(), onion.clone(), tcp2.clone()];
+
+        let ordered = order_by_transport_preference(&addresses, &[TransportKind::Onion, TransportKind::Tcp]);
+        assert_eq!(ordered, vec![onion, tcp.clone(), tcp2.clone(), ws.clone()]);
+
+        let ordered = order_by_transport_preference(&addresses, &[]);
+        assert_eq!(ordered, addresses);
+    }
+
+    #[test]
+    fn it_parses_transport_kinds() {
+        assert_eq!("TCP".parse::<TransportKind>().unwrap(), TransportKind::Tcp);
+        assert_eq!("tor".parse::<TransportKind>().unwrap(), TransportKind::Onion);
+        assert!("udp".parse::<TransportKind>().is_err());
+        assert_eq!(
+            TransportKind::WebSocket.to_string().parse::<TransportKind>().unwrap(),
+            TransportKind::WebSocket
+        );
+    }
+}
@@ -29,6 +29,9 @@
 //! Provides an abstraction for [Transport](self::Transport)s and several implemenations:
 //! - [TCP](self::TcpTransport) - communication over TCP and IP4/IP6 and DNS
 //! - [SOCKS](self::SocksTransport) - communication over a SOCKS5 proxy.
+//! - [QUIC](self::QuicTransport) - communication over QUIC (requires the `quic` feature).
+//! - [WebSocket](self::WebSocketTransport) - communication over WebSockets, e.g. with browser clients (requires the
+//!   `websocket` feature).
 //! - [Memory](self::MemoryTransport) - in-process communication (mpsc channel), typically for testing.
 
 use multiaddr::Multiaddr;
@@ -36,11 +39,19 @@ use tokio_stream::Stream;
 
 mod dns;
 
+mod kind;
+pub use kind::{order_by_transport_preference, TransportKind};
+
 pub mod predicate;
 
 mod memory;
 pub use memory::MemoryTransport;
 
+#[cfg(feature = "quic")]
+mod quic;
+#[cfg(feature = "quic")]
+pub use quic::{QuicConfig, QuicInbound, QuicStream, QuicTransport};
+
 mod socks;
 pub use socks::{SocksConfig, SocksTransport};
 
@@ -50,6 +61,11 @@ pub use tcp::TcpTransport;
 mod tcp_with_tor;
 pub use tcp_with_tor::TcpWithTorTransport;
 
+#[cfg(feature = "websocket")]
+mod websocket;
+#[cfg(feature = "websocket")]
+pub use websocket::{WebSocketConfig, WebSocketInbound, WebSocketStream, WebSocketTransport};
+
 /// Defines an abstraction for implementations that can dial and listen for connections over a provided address.
 #[crate::async_trait]
 pub trait Transport {
@@ -0,0 +1,401 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! QUIC transport.
+//!
+//! Each connection carries a single bidirectional QUIC stream, over which the usual noise upgrade and yamux
+//! multiplexing take place. Peers are therefore authenticated by the noise handshake exactly as they are for TCP. The
+//! TLS layer that QUIC requires uses an ephemeral self-signed certificate that is not verified by the dialer.
+//!
+//! 0-RTT session resumption is safe to use because the only data sent before the QUIC handshake completes is the first
+//! message of the noise handshake. Replaying it does not allow an attacker to complete the handshake or send data to
+//! the node.
+
+use std::{
+    convert::TryInto,
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+
+use futures::StreamExt;
+use log::*;
+use multiaddr::Multiaddr;
+use quinn::{
+    ClientConfig,
+    Connecting,
+    Connection,
+    Endpoint,
+    Incoming,
+    NewConnection,
+    RecvStream,
+    SendStream,
+    ServerConfig,
+    TransportConfig,
+};
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier},
+    Certificate,
+    PrivateKey,
+    ServerName,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::{mpsc, Semaphore},
+    task,
+    time,
+};
+use tokio_stream::Stream;
+
+use super::Transport;
+use crate::utils::multiaddr::{quic_multiaddr_to_socketaddr, socketaddr_to_quic_multiaddr};
+
+const LOG_TARGET: &str = "comms::transports::quic";
+
+/// The server name used for the TLS handshake. The certificate is not verified, so this only has to be consistent.
+const SERVER_NAME: &str = "tari";
+/// ALPN protocol identifier for tari comms over QUIC
+const ALPN_PROTOCOL: &[u8] = b"tari-comms";
+const INBOUND_BUFFER_SIZE: usize = 32;
+
+/// Configuration for the [QuicTransport]
+#[derive(Debug, Clone)]
+pub struct QuicConfig {
+    /// Send data on resumed sessions before the QUIC handshake completes.
+    /// Default: true
+    pub enable_0rtt: bool,
+    /// The interval at which keep alive packets are sent, keeping NAT mappings open.
+    /// Default: 10 seconds
+    pub keep_alive_interval: Duration,
+    /// Close the connection if no packets are received for this length of time.
+    /// Default: 60 seconds
+    pub max_idle_timeout: Duration,
+    /// The maximum time allowed for an inbound QUIC handshake to complete.
+    /// Default: 30 seconds
+    pub handshake_timeout: Duration,
+    /// The maximum number of inbound QUIC handshakes that may be in progress at once. Inbound connections are refused
+    /// while this many handshakes are in progress.
+    /// Default: 64
+    pub max_concurrent_handshakes: usize,
+}
+
+impl Default for QuicConfig {
+    fn default() -> Self {
+        Self {
+            enable_0rtt: true,
+            keep_alive_interval: Duration::from_secs(10),
+            max_idle_timeout: Duration::from_secs(60),
+            handshake_timeout: Duration::from_secs(30),
+            max_concurrent_handshakes: 64,
+        }
+    }
+}
+
+/// Transport implementation for QUIC. Addresses take the form `/ip4/1.2.3.4/udp/18189/quic`.
+///
+/// Outbound connections are made from the listening socket if there is one, so that peers behind a NAT can be reached
+/// on the same mapping that they use to dial out.
+#[derive(Clone)]
+pub struct QuicTransport {
+    config: QuicConfig,
+    client_config: ClientConfig,
+    server_config: ServerConfig,
+    endpoints: Arc<Mutex<Vec<Endpoint>>>,
+}
+
+impl QuicTransport {
+    /// Create a new QuicTransport. This generates the ephemeral TLS certificate used by the listener.
+    pub fn new(config: QuicConfig) -> io::Result<Self> {
+        let mut transport_config = TransportConfig::default();
+        transport_config
+            .keep_alive_interval(Some(config.keep_alive_interval))
+            .max_idle_timeout(Some(config.max_idle_timeout.try_into().map_err(to_io_error)?))
+            // Only a single stream is used, yamux multiplexes substreams over it
+            .max_concurrent_bidi_streams(1u32.into())
+            .max_concurrent_uni_streams(0u32.into());
+        let transport_config = Arc::new(transport_config);
+
+        let mut client_crypto = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(SkipServerVerification))
+            .with_no_client_auth();
+        client_crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+        client_crypto.enable_early_data = config.enable_0rtt;
+        let mut client_config = ClientConfig::new(Arc::new(client_crypto));
+        client_config.transport = transport_config.clone();
+
+        let cert = rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()]).map_err(to_io_error)?;
+        let cert_chain = vec![Certificate(cert.serialize_der().map_err(to_io_error)?)];
+        let key = PrivateKey(cert.serialize_private_key_der());
+        let mut server_crypto = rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(cert_chain, key)
+            .map_err(to_io_error)?;
+        server_crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+        if config.enable_0rtt {
+            server_crypto.max_early_data_size = u32::MAX;
+        }
+        let mut server_config = ServerConfig::with_crypto(Arc::new(server_crypto));
+        server_config.transport = transport_config;
+
+        Ok(Self {
+            config,
+            client_config,
+            server_config,
+            endpoints: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+
+    /// Returns an endpoint that is able to dial the given address, creating one if necessary
+    fn get_or_create_endpoint(&self, addr: &SocketAddr) -> io::Result<Endpoint> {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        for endpoint in endpoints.iter() {
+            if endpoint.local_addr()?.is_ipv4() == addr.is_ipv4() {
+                return Ok(endpoint.clone());
+            }
+        }
+
+        let bind_addr: SocketAddr = if addr.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+        let mut endpoint = Endpoint::client(bind_addr)?;
+        endpoint.set_default_client_config(self.client_config.clone());
+        endpoints.push(endpoint.clone());
+        Ok(endpoint)
+    }
+}
+
+#[crate::async_trait]
+impl Transport for QuicTransport {
+    type Error = io::Error;
+    type Listener = QuicInbound;
+    type Output = QuicStream;
+
+    async fn listen(&self, addr: Multiaddr) -> Result<(Self::Listener, Multiaddr), Self::Error> {
+        let socket_addr = quic_multiaddr_to_socketaddr(&addr)?;
+        let (mut endpoint, incoming) = Endpoint::server(self.server_config.clone(), socket_addr)?;
+        endpoint.set_default_client_config(self.client_config.clone());
+        let local_addr = socketaddr_to_quic_multiaddr(&endpoint.local_addr()?);
+        // Dial from the listening socket in preference to an ephemeral one
+        self.endpoints.lock().unwrap().insert(0, endpoint);
+        let inbound = QuicInbound::spawn(
+            incoming,
+            self.config.handshake_timeout,
+            self.config.max_concurrent_handshakes,
+        );
+        Ok((inbound, local_addr))
+    }
+
+    async fn dial(&self, addr: Multiaddr) -> Result<Self::Output, Self::Error> {
+        let socket_addr = quic_multiaddr_to_socketaddr(&addr)?;
+        let endpoint = self.get_or_create_endpoint(&socket_addr)?;
+        let connecting = endpoint.connect(socket_addr, SERVER_NAME).map_err(to_io_error)?;
+        let new_conn = if self.config.enable_0rtt {
+            match connecting.into_0rtt() {
+                Ok((new_conn, _)) => {
+                    trace!(target: LOG_TARGET, "Resumed QUIC session with {}", socket_addr);
+                    new_conn
+                },
+                Err(connecting) => connecting.await.map_err(to_io_error)?,
+            }
+        } else {
+            connecting.await.map_err(to_io_error)?
+        };
+
+        let (send, recv) = new_conn.connection.open_bi().await.map_err(to_io_error)?;
+        Ok(QuicStream::new(new_conn.connection, send, recv))
+    }
+}
+
+/// Inbound QUIC connections. The QUIC handshake for each connection completes in its own task so that a slow peer does
+/// not hold up other inbound connections. The number of handshakes in progress is bounded and each handshake is timed
+/// out.
+pub struct QuicInbound {
+    inbound_rx: mpsc::Receiver<io::Result<(QuicStream, Multiaddr)>>,
+}
+
+impl QuicInbound {
+    fn spawn(mut incoming: Incoming, handshake_timeout: Duration, max_concurrent_handshakes: usize) -> Self {
+        let (inbound_tx, inbound_rx) = mpsc::channel(INBOUND_BUFFER_SIZE);
+        let handshake_permits = Arc::new(Semaphore::new(max_concurrent_handshakes));
+        task::spawn(async move {
+            while let Some(connecting) = incoming.next().await {
+                if inbound_tx.is_closed() {
+                    break;
+                }
+                let permit = match handshake_permits.clone().try_acquire_owned() {
+                    Ok(permit) => permit,
+                    Err(_) => {
+                        // Dropping the connection refuses it
+                        debug!(
+                            target: LOG_TARGET,
+                            "Refusing inbound QUIC connection from {} because {} handshakes are in progress",
+                            connecting.remote_address(),
+                            max_concurrent_handshakes
+                        );
+                        continue;
+                    },
+                };
+                let inbound_tx = inbound_tx.clone();
+                task::spawn(async move {
+                    let _permit = permit;
+                    let result = time::timeout(handshake_timeout, accept_stream(connecting))
+                        .await
+                        .unwrap_or_else(|_| {
+                            Err(io::Error::new(
+                                io::ErrorKind::TimedOut,
+                                "Timed out waiting for QUIC handshake",
+                            ))
+                        });
+                    let _result = inbound_tx.send(result).await;
+                });
+            }
+            debug!(target: LOG_TARGET, "QUIC listener has closed");
+        });
+
+        Self { inbound_rx }
+    }
+}
+
+impl Stream for QuicInbound {
+    type Item = io::Result<(QuicStream, Multiaddr)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inbound_rx.poll_recv(cx)
+    }
+}
+
+async fn accept_stream(connecting: Connecting) -> io::Result<(QuicStream, Multiaddr)> {
+    let NewConnection {
+        connection,
+        mut bi_streams,
+        ..
+    } = connecting.await.map_err(to_io_error)?;
+    let peer_addr = socketaddr_to_quic_multiaddr(&connection.remote_address());
+    let (send, recv) = bi_streams
+        .next()
+        .await
+        .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "QUIC connection closed"))?
+        .map_err(to_io_error)?;
+    Ok((QuicStream::new(connection, send, recv), peer_addr))
+}
+
+/// A bidirectional QUIC stream
+pub struct QuicStream {
+    // Closing the connection closes the stream, the connection is held for as long as the stream is in use
+    _connection: Connection,
+    send: SendStream,
+    recv: RecvStream,
+}
+
+impl QuicStream {
+    fn new(connection: Connection, send: SendStream, recv: RecvStream) -> Self {
+        Self {
+            _connection: connection,
+            send,
+            recv,
+        }
+    }
+}
+
+impl AsyncRead for QuicStream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.recv).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for QuicStream {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.send).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_shutdown(cx)
+    }
+}
+
+/// Peers are authenticated by the noise handshake, so the TLS certificate presented by the listener is not verified
+struct SkipServerVerification;
+
+impl ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+fn to_io_error<E: std::error::Error + Send + Sync + 'static>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+#[cfg(test)]
+mod test {
+    use futures::future::join;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::runtime;
+
+    #[runtime::test]
+    async fn simple_listen_and_dial() -> Result<(), io::Error> {
+        let transport = QuicTransport::new(Default::default())?;
+
+        let (mut listener, addr) = transport.listen("/ip4/127.0.0.1/udp/0/quic".parse().unwrap()).await?;
+
+        let listener = async move {
+            let (mut socket, _addr) = listener.next().await.unwrap().unwrap();
+            let mut buf = [0u8; 11];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"hello world");
+        };
+
+        let dialer = QuicTransport::new(Default::default())?;
+        let mut outbound = dialer.dial(addr).await?;
+
+        let dialer = async move {
+            outbound.write_all(b"hello world").await.unwrap();
+            outbound.flush().await.unwrap();
+            // Keep the stream open until the listener has read the data
+            outbound
+        };
+
+        let (_outbound, _) = join(dialer, listener).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,286 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! WebSocket transport.
+//!
+//! Allows peers that cannot open raw TCP connections, such as light clients and wallets running in a browser, to
+//! connect to a node. The WebSocket connection carries the same byte stream as a TCP connection, so the noise upgrade
+//! and yamux multiplexing are unchanged. Every write is sent as a single binary WebSocket message. Message boundaries
+//! carry no meaning, so browser clients are free to split or coalesce the byte stream into messages as they see fit.
+//! Text messages are a protocol violation and close the stream with an error.
+
+use std::{
+    cmp,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::{Buf, Bytes};
+use futures::{ready, Sink, StreamExt};
+use log::*;
+use multiaddr::Multiaddr;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::TcpStream,
+    sync::mpsc,
+    task,
+    time,
+};
+use tokio_stream::Stream;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::{TcpTransport, Transport};
+use crate::utils::multiaddr::{multiaddr_to_socketaddr, tcp_multiaddr_to_ws_multiaddr, ws_multiaddr_to_tcp_multiaddr};
+
+const LOG_TARGET: &str = "comms::transports::websocket";
+
+const INBOUND_BUFFER_SIZE: usize = 32;
+
+/// Configuration for the [WebSocketTransport]
+#[derive(Debug, Clone)]
+pub struct WebSocketConfig {
+    /// The maximum time allowed for the WebSocket upgrade of an inbound connection to complete.
+    /// Default: 10 seconds
+    pub handshake_timeout: Duration,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            handshake_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Transport implementation for WebSockets over TCP. Addresses take the form `/ip4/1.2.3.4/tcp/18190/ws`.
+#[derive(Clone)]
+pub struct WebSocketTransport {
+    config: WebSocketConfig,
+    tcp: TcpTransport,
+}
+
+impl WebSocketTransport {
+    pub fn new(config: WebSocketConfig) -> Self {
+        let mut tcp = TcpTransport::new();
+        tcp.set_nodelay(true);
+        Self { config, tcp }
+    }
+}
+
+impl Default for WebSocketTransport {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+#[crate::async_trait]
+impl Transport for WebSocketTransport {
+    type Error = io::Error;
+    type Listener = WebSocketInbound;
+    type Output = WebSocketStream;
+
+    async fn listen(&self, addr: Multiaddr) -> Result<(Self::Listener, Multiaddr), Self::Error> {
+        let tcp_addr = ws_multiaddr_to_tcp_multiaddr(&addr)?;
+        let (inbound, local_addr) = self.tcp.listen(tcp_addr).await?;
+        Ok((
+            WebSocketInbound::spawn(inbound, self.config.handshake_timeout),
+            tcp_multiaddr_to_ws_multiaddr(local_addr),
+        ))
+    }
+
+    async fn dial(&self, addr: Multiaddr) -> Result<Self::Output, Self::Error> {
+        let tcp_addr = ws_multiaddr_to_tcp_multiaddr(&addr)?;
+        let socket_addr = multiaddr_to_socketaddr(&tcp_addr)?;
+        let socket = self.tcp.dial(tcp_addr).await?;
+        let (stream, _) = tokio_tungstenite::client_async(format!("ws://{}/", socket_addr), socket)
+            .await
+            .map_err(to_io_error)?;
+        Ok(WebSocketStream::new(stream))
+    }
+}
+
+/// Inbound WebSocket connections. The WebSocket upgrade for each connection completes in its own task so that a slow
+/// peer does not hold up other inbound connections.
+pub struct WebSocketInbound {
+    inbound_rx: mpsc::Receiver<io::Result<(WebSocketStream, Multiaddr)>>,
+}
+
+impl WebSocketInbound {
+    fn spawn<S>(mut incoming: S, handshake_timeout: Duration) -> Self
+    where S: Stream<Item = io::Result<(TcpStream, Multiaddr)>> + Send + Unpin + 'static {
+        let (inbound_tx, inbound_rx) = mpsc::channel(INBOUND_BUFFER_SIZE);
+        task::spawn(async move {
+            while let Some(result) = incoming.next().await {
+                if inbound_tx.is_closed() {
+                    break;
+                }
+                let (socket, peer_addr) = match result {
+                    Ok(v) => v,
+                    Err(err) => {
+                        let _result = inbound_tx.send(Err(err)).await;
+                        continue;
+                    },
+                };
+                let inbound_tx = inbound_tx.clone();
+                task::spawn(async move {
+                    let result = time::timeout(handshake_timeout, tokio_tungstenite::accept_async(socket))
+                        .await
+                        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "Timed out waiting for WebSocket upgrade"))
+                        .and_then(|result| result.map_err(to_io_error))
+                        .map(|stream| (WebSocketStream::new(stream), tcp_multiaddr_to_ws_multiaddr(peer_addr)));
+                    let _result = inbound_tx.send(result).await;
+                });
+            }
+            debug!(target: LOG_TARGET, "WebSocket listener has closed");
+        });
+
+        Self { inbound_rx }
+    }
+}
+
+impl Stream for WebSocketInbound {
+    type Item = io::Result<(WebSocketStream, Multiaddr)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inbound_rx.poll_recv(cx)
+    }
+}
+
+/// Adapts the binary messages of a WebSocket connection to a byte stream
+pub struct WebSocketStream {
+    inner: tokio_tungstenite::WebSocketStream<TcpStream>,
+    read_buf: Bytes,
+}
+
+impl WebSocketStream {
+    fn new(inner: tokio_tungstenite::WebSocketStream<TcpStream>) -> Self {
+        Self {
+            inner,
+            read_buf: Bytes::new(),
+        }
+    }
+}
+
+impl AsyncRead for WebSocketStream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        loop {
+            if !self.read_buf.is_empty() {
+                let n = cmp::min(buf.remaining(), self.read_buf.len());
+                buf.put_slice(&self.read_buf[..n]);
+                self.read_buf.advance(n);
+                return Poll::Ready(Ok(()));
+            }
+
+            match ready!(self.inner.poll_next_unpin(cx)) {
+                Some(Ok(Message::Binary(data))) => {
+                    self.read_buf = data.into();
+                },
+                // Ping and pong messages are answered by the websocket implementation
+                Some(Ok(Message::Ping(_))) | Some(Ok(Message::Pong(_))) => {},
+                Some(Ok(Message::Text(_))) => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Received a text WebSocket message",
+                    )));
+                },
+                // EOF
+                Some(Ok(Message::Close(_))) | Some(Ok(Message::Frame(_))) | None => return Poll::Ready(Ok(())),
+                Some(Err(err)) => return Poll::Ready(Err(to_io_error(err))),
+            }
+        }
+    }
+}
+
+impl AsyncWrite for WebSocketStream {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        ready!(Pin::new(&mut self.inner).poll_ready(cx)).map_err(to_io_error)?;
+        Pin::new(&mut self.inner)
+            .start_send(Message::Binary(buf.to_vec()))
+            .map_err(to_io_error)?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx).map_err(to_io_error)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx).map_err(to_io_error)
+    }
+}
+
+fn to_io_error<E: std::error::Error + Send + Sync + 'static>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+#[cfg(test)]
+mod test {
+    use futures::{future::join, SinkExt};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::{framing, runtime};
+
+    #[runtime::test]
+    async fn simple_listen_and_dial() -> Result<(), io::Error> {
+        let transport = WebSocketTransport::default();
+
+        let (mut listener, addr) = transport.listen("/ip4/127.0.0.1/tcp/0/ws".parse().unwrap()).await?;
+        assert!(addr.to_string().ends_with("/ws"));
+
+        let listener = async move {
+            let (mut socket, _addr) = listener.next().await.unwrap().unwrap();
+            let mut buf = [0u8; 11];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"hello world");
+        };
+
+        let mut outbound = transport.dial(addr).await?;
+
+        let dialer = async move {
+            outbound.write_all(b"hello world").await.unwrap();
+            outbound.flush().await.unwrap();
+            outbound
+        };
+
+        let (_outbound, _) = join(dialer, listener).await;
+        Ok(())
+    }
+
+    #[runtime::test]
+    async fn it_supports_canonical_framing() -> Result<(), io::Error> {
+        let transport = WebSocketTransport::default();
+        let (mut listener, addr) = transport.listen("/ip4/127.0.0.1/tcp/0/ws".parse().unwrap()).await?;
+        let outbound = transport.dial(addr).await?;
+        let (inbound, _) = listener.next().await.unwrap()?;
+
+        let mut outbound = framing::canonical(outbound, 1024);
+        let mut inbound = framing::canonical(inbound, 1024);
+        outbound.send(Bytes::from_static(b"first frame")).await?;
+        outbound.send(Bytes::from_static(b"second frame")).await?;
+        assert_eq!(&inbound.next().await.unwrap()?[..], b"first frame");
+        assert_eq!(&inbound.next().await.unwrap()?[..], b"second frame");
+        Ok(())
+    }
+}
@@ -45,21 +45,7 @@ pub fn multiaddr_to_socketaddr(addr: &Multiaddr) -> io::Result<SocketAddr> {
     }
 
     match (network_proto, transport_proto) {
-        (Protocol::Dns4(domain), Protocol::Tcp(port)) => {
-            let addr = format!("{}:{}", domain, port);
-            addr.to_socket_addrs()
-                .map_err(|_e| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid domain '{}'", domain)))?
-                .next()
-                .map_or_else(
-                    || {
-                        Err(io::Error::new(
-                            io::ErrorKind::InvalidInput,
-                            format!("Invalid domain '{}'", domain),
-                        ))
-                    },
-                    Ok,
-                )
-        },
+        (Protocol::Dns4(domain), Protocol::Tcp(port)) => resolve_domain(&domain, port),
         (Protocol::Ip4(host), Protocol::Tcp(port)) => Ok((host, port).into()),
         (Protocol::Ip6(host), Protocol::Tcp(port)) => Ok((host, port).into()),
         _ => Err(io::Error::new(
@@ -69,6 +55,44 @@ pub fn multiaddr_to_socketaddr(addr: &Multiaddr) -> io::Result<SocketAddr> {
     }
 }
 
+/// Convert a QUIC multiaddr (e.g. `/ip4/127.0.0.1/udp/18189/quic`) to a socket address.
+/// This function resolves DNS4 addresses to an ip address.
+pub fn quic_multiaddr_to_socketaddr(addr: &Multiaddr) -> io::Result<SocketAddr> {
+    let invalid_address = || io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid QUIC address '{}'", addr));
+    let mut addr_iter = addr.iter();
+    let network_proto = addr_iter.next().ok_or_else(invalid_address)?;
+    let port = match addr_iter.next() {
+        Some(Protocol::Udp(port)) => port,
+        _ => return Err(invalid_address()),
+    };
+    if !matches!(addr_iter.next(), Some(Protocol::Quic)) || addr_iter.next().is_some() {
+        return Err(invalid_address());
+    }
+
+    match network_proto {
+        Protocol::Dns4(domain) => resolve_domain(&domain, port),
+        Protocol::Ip4(host) => Ok((host, port).into()),
+        Protocol::Ip6(host) => Ok((host, port).into()),
+        _ => Err(invalid_address()),
+    }
+}
+
+fn resolve_domain(domain: &str, port: u16) -> io::Result<SocketAddr> {
+    let addr = format!("{}:{}", domain, port);
+    addr.to_socket_addrs()
+        .map_err(|_e| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid domain '{}'", domain)))?
+        .next()
+        .map_or_else(
+            || {
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Invalid domain '{}'", domain),
+                ))
+            },
+            Ok,
+        )
+}
+
 /// Convert a socket address to a multiaddress. Assumes the protocol is Tcp
 pub fn socketaddr_to_multiaddr(socket_addr: &SocketAddr) -> Multiaddr {
     let mut addr: Multiaddr = match socket_addr.ip() {
@@ -79,6 +103,36 @@ pub fn socketaddr_to_multiaddr(socket_addr: &SocketAddr) -> Multiaddr {
     addr
 }
 
+/// Convert a socket address to a QUIC multiaddress
+pub fn socketaddr_to_quic_multiaddr(socket_addr: &SocketAddr) -> Multiaddr {
+    let mut addr: Multiaddr = match socket_addr.ip() {
+        IpAddr::V4(addr) => Protocol::Ip4(addr).into(),
+        IpAddr::V6(addr) => Protocol::Ip6(addr).into(),
+    };
+    addr.push(Protocol::Udp(socket_addr.port()));
+    addr.push(Protocol::Quic);
+    addr
+}
+
+/// Convert a WebSocket multiaddr (e.g. `/ip4/127.0.0.1/tcp/18190/ws`) to the multiaddr of the underlying TCP
+/// connection
+pub fn ws_multiaddr_to_tcp_multiaddr(addr: &Multiaddr) -> io::Result<Multiaddr> {
+    let mut tcp_addr = addr.clone();
+    match tcp_addr.pop() {
+        Some(Protocol::Ws(_)) => Ok(tcp_addr),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid WebSocket address '{}'", addr),
+        )),
+    }
+}
+
+/// Convert a TCP multiaddr to a WebSocket multiaddr
+pub fn tcp_multiaddr_to_ws_multiaddr(mut addr: Multiaddr) -> Multiaddr {
+    addr.push(Protocol::Ws("/".into()));
+    addr
+}
+
 #[cfg(test)]
 mod test {
     use std::{net::Ipv4Addr, str::FromStr};
@@ -121,6 +175,40 @@ mod test {
         expect_fail("/dns4/doesntexist.theresnotldlikethis/tcp/1234")
     }
 
+    #[test]
+    fn quic_multiaddr_to_socketaddr_ok() {
+        let addr = Multiaddr::from_str("/ip4/254.0.1.2/udp/1234/quic").unwrap();
+        let sock_addr = super::quic_multiaddr_to_socketaddr(&addr).unwrap();
+        assert_eq!(sock_addr, "254.0.1.2:1234".parse().unwrap());
+        assert_eq!(super::socketaddr_to_quic_multiaddr(&sock_addr), addr);
+
+        let addr = Multiaddr::from_str("/dns4/localhost/udp/1234/quic").unwrap();
+        let sock_addr = super::quic_multiaddr_to_socketaddr(&addr).unwrap();
+        assert!(sock_addr.ip().is_loopback());
+
+        for addr in [
+            "/ip4/254.0.1.2/tcp/1234",
+            "/ip4/254.0.1.2/udp/1234",
+            "/ip4/254.0.1.2/udp/1234/quic/tcp/1",
+        ] {
+            let addr = Multiaddr::from_str(addr).unwrap();
+            let err = super::quic_multiaddr_to_socketaddr(&addr).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn ws_multiaddr_to_tcp_multiaddr() {
+        let addr = Multiaddr::from_str("/ip4/254.0.1.2/tcp/1234/ws").unwrap();
+        let tcp_addr = super::ws_multiaddr_to_tcp_multiaddr(&addr).unwrap();
+        assert_eq!(tcp_addr, Multiaddr::from_str("/ip4/254.0.1.2/tcp/1234").unwrap());
+        assert_eq!(super::tcp_multiaddr_to_ws_multiaddr(tcp_addr), addr);
+
+        let addr = Multiaddr::from_str("/ip4/254.0.1.2/tcp/1234").unwrap();
+        let err = super::ws_multiaddr_to_tcp_multiaddr(&addr).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
     #[test]
     fn multiaddr_from_components() {
         let ip: Ipv4Addr = "127.0.0.1".parse().unwrap();
@@ -0,0 +1,4 @@
+DROP INDEX idx_stored_messages_source_peer;
+
+ALTER TABLE stored_messages
+    DROP COLUMN source_peer;
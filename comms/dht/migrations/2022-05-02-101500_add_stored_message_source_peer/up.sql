@@ -0,0 +1,4 @@
+ALTER TABLE stored_messages
+    ADD source_peer TEXT;
+
+CREATE INDEX idx_stored_messages_source_peer ON stored_messages (source_peer);
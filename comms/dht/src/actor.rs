@@ -35,7 +35,16 @@ use log::*;
 use tari_comms::{
     connection_manager::ConnectionManagerError,
     connectivity::{ConnectivityError, ConnectivityRequester, ConnectivitySelection},
-    peer_manager::{NodeId, NodeIdentity, PeerFeatures, PeerManager, PeerManagerError, PeerQuery, PeerQuerySortBy},
+    peer_manager::{
+        NodeId,
+        NodeIdentity,
+        PeerBanState,
+        PeerFeatures,
+        PeerManager,
+        PeerManagerError,
+        PeerQuery,
+        PeerQuerySortBy,
+    },
     types::CommsPublicKey,
     PeerConnection,
 };
@@ -56,7 +65,10 @@ use crate::{
     broadcast_strategy::{BroadcastClosestRequest, BroadcastStrategy},
     dedup::DedupCacheDatabase,
     discovery::DhtDiscoveryError,
+    neighbourhood,
+    neighbourhood::{NeighbourhoodInfo, PeerSelectionLog},
     outbound::{DhtOutboundError, OutboundMessageRequester, SendMessageParams},
+    propagation::{ClosestNeighbourPropagation, PeerSelectionContext, PropagationStrategy},
     proto::{dht::JoinMessage, envelope::DhtMessageType},
     storage::{DbConnection, DhtDatabase, DhtMetadataKey, StorageError},
     DhtConfig,
@@ -118,6 +130,8 @@ pub enum DhtRequest {
         public_key: CommsPublicKey,
         reply: oneshot::Sender<Result<PeerConnection, DhtActorError>>,
     },
+    /// Fetch a snapshot of this node's neighbourhood and recent peer selections
+    GetNeighbourhoodInfo(oneshot::Sender<Result<NeighbourhoodInfo, DhtActorError>>),
 }
 
 impl Display for DhtRequest {
@@ -143,6 +157,7 @@ impl Display for DhtRequest {
                 write!(f, "SetMetadata (key={}, value={} bytes)", key, value.len())
             },
             DialDiscoverPeer { public_key, .. } => write!(f, "DialDiscoverPeer(public_key={})", public_key),
+            GetNeighbourhoodInfo(_) => write!(f, "GetNeighbourhoodInfo"),
         }
     }
 }
@@ -232,6 +247,14 @@ impl DhtRequester {
             .await?;
         reply_rx.await.map_err(|_| DhtActorError::ReplyCanceled)?
     }
+
+    /// Returns this node's view of its neighbourhood: known peers by distance, the closest peers and their connection
+    /// state, and the peers recently selected for messages sent to more than one peer.
+    pub async fn get_neighbourhood_info(&mut self) -> Result<NeighbourhoodInfo, DhtActorError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.sender.send(DhtRequest::GetNeighbourhoodInfo(reply_tx)).await?;
+        reply_rx.await.map_err(|_| DhtActorError::ReplyCanceled)?
+    }
 }
 
 /// DHT actor. Responsible for executing DHT-related tasks.
@@ -246,6 +269,8 @@ pub struct DhtActor {
     shutdown_signal: ShutdownSignal,
     request_rx: mpsc::Receiver<DhtRequest>,
     msg_hash_dedup_cache: DedupCacheDatabase,
+    selection_log: PeerSelectionLog,
+    propagation_strategy: Arc<dyn PropagationStrategy>,
 }
 
 impl DhtActor {
@@ -278,9 +303,17 @@ impl DhtActor {
             discovery,
             shutdown_signal,
             request_rx,
+            selection_log: PeerSelectionLog::default(),
+            propagation_strategy: Arc::new(ClosestNeighbourPropagation),
         }
     }
 
+    /// Use the given strategy to select peers for messages that are broadcast or propagated.
+    pub(crate) fn with_propagation_strategy(mut self, propagation_strategy: Arc<dyn PropagationStrategy>) -> Self {
+        self.propagation_strategy = propagation_strategy;
+        self
+    }
+
     /// Spawns the DHT actor on a new task.
     pub fn spawn(self) {
         task::spawn(async move {
@@ -390,11 +423,24 @@ impl DhtActor {
                 let node_identity = Arc::clone(&self.node_identity);
                 let connectivity = self.connectivity.clone();
                 let config = self.config.clone();
+                let propagation_strategy = self.propagation_strategy.clone();
+                let selection_log = self.selection_log.clone();
                 Box::pin(async move {
-                    match Self::select_peers(&config, node_identity, peer_manager, connectivity, broadcast_strategy)
-                        .await
+                    let strategy = broadcast_strategy.clone();
+                    match Self::select_peers(
+                        config,
+                        node_identity,
+                        peer_manager,
+                        connectivity,
+                        propagation_strategy,
+                        broadcast_strategy,
+                    )
+                    .await
                     {
-                        Ok(peers) => reply_tx.send(peers).map_err(|_| DhtActorError::ReplyCanceled),
+                        Ok(peers) => {
+                            selection_log.record(&strategy, &peers);
+                            reply_tx.send(peers).map_err(|_| DhtActorError::ReplyCanceled)
+                        },
                         Err(err) => {
                             warn!(target: LOG_TARGET, "Peer selection failed: {:?}", err);
                             reply_tx.send(Vec::new()).map_err(|_| DhtActorError::ReplyCanceled)
@@ -436,6 +482,25 @@ impl DhtActor {
                     Ok(())
                 })
             },
+            GetNeighbourhoodInfo(reply) => {
+                let node_identity = Arc::clone(&self.node_identity);
+                let peer_manager = Arc::clone(&self.peer_manager);
+                let mut connectivity = self.connectivity.clone();
+                let num_neighbouring_nodes = self.config.num_neighbouring_nodes;
+                let selection_log = self.selection_log.clone();
+                Box::pin(async move {
+                    let result = neighbourhood::get_neighbourhood_info(
+                        node_identity.node_id(),
+                        num_neighbouring_nodes,
+                        &peer_manager,
+                        &mut connectivity,
+                        &selection_log,
+                    )
+                    .await;
+                    let _result = reply.send(result);
+                    Ok(())
+                })
+            },
         }
     }
 
@@ -464,10 +529,11 @@ impl DhtActor {
     }
 
     async fn select_peers(
-        config: &DhtConfig,
+        config: Arc<DhtConfig>,
         node_identity: Arc<NodeIdentity>,
         peer_manager: Arc<PeerManager>,
         mut connectivity: ConnectivityRequester,
+        propagation_strategy: Arc<dyn PropagationStrategy>,
         broadcast_strategy: BroadcastStrategy,
     ) -> Result<Vec<NodeId>, DhtActorError> {
         #[allow(clippy::enum_glob_use)]
@@ -496,7 +562,7 @@ impl DhtActor {
                 Ok(peers.into_iter().map(|p| p.peer_node_id().clone()).collect())
             },
             ClosestNodes(closest_request) => {
-                Self::select_closest_node_connected(closest_request, config, connectivity, peer_manager).await
+                Self::select_closest_node_connected(closest_request, &config, connectivity, peer_manager).await
             },
             DirectOrClosestNodes(closest_request) => {
                 // First check if a direct connection exists
@@ -507,7 +573,7 @@ impl DhtActor {
                 {
                     return Ok(vec![closest_request.node_id.clone()]);
                 }
-                Self::select_closest_node_connected(closest_request, config, connectivity, peer_manager).await
+                Self::select_closest_node_connected(closest_request, &config, connectivity, peer_manager).await
             },
             Random(n, excluded) => {
                 // Send to a random set of peers of size n that are Communication Nodes
@@ -520,125 +586,16 @@ impl DhtActor {
             },
             SelectedPeers(peers) => Ok(peers),
             Broadcast(exclude) => {
-                let connections = connectivity
-                    .select_connections(ConnectivitySelection::random_nodes(
-                        config.broadcast_factor,
-                        exclude.clone(),
-                    ))
-                    .await?;
-
-                let candidates = connections
-                    .iter()
-                    .map(|c| c.peer_node_id())
-                    .cloned()
-                    .collect::<Vec<_>>();
-
-                if candidates.is_empty() {
-                    warn!(
-                        target: LOG_TARGET,
-                        "Broadcast requested but there are no node peer connections available"
-                    );
-                }
-                debug!(
-                    target: LOG_TARGET,
-                    "{} candidate(s) selected for broadcast",
-                    candidates.len()
-                );
-
-                Ok(candidates)
+                let mut context = PeerSelectionContext::new(config, node_identity, peer_manager, connectivity);
+                propagation_strategy
+                    .select_broadcast_peers(&mut context, &exclude)
+                    .await
             },
             Propagate(destination, exclude) => {
-                let dest_node_id = destination
-                    .node_id()
-                    .cloned()
-                    .or_else(|| destination.public_key().map(NodeId::from_public_key));
-
-                let connections = match dest_node_id {
-                    Some(node_id) => {
-                        let dest_connection = connectivity.get_connection(node_id.clone()).await?;
-                        // If the peer was added to the exclude list, we don't want to send directly to the peer.
-                        // This ensures that we don't just send a message back to the peer that sent it.
-                        let dest_connection = dest_connection.filter(|c| !exclude.contains(c.peer_node_id()));
-                        match dest_connection {
-                            Some(conn) => {
-                                // We're connected to the destination, so send the message directly
-                                vec![conn]
-                            },
-                            None => {
-                                // Select connections closer to the destination
-                                let mut connections = connectivity
-                                    .select_connections(ConnectivitySelection::closest_to(
-                                        node_id.clone(),
-                                        config.num_neighbouring_nodes,
-                                        exclude.clone(),
-                                    ))
-                                    .await?;
-
-                                // Exclude candidates that are further away from the destination than this node
-                                // unless this node has not selected a big enough sample i.e. this node is not well
-                                // connected
-                                if connections.len() >= config.propagation_factor {
-                                    let dist_from_dest = node_identity.node_id().distance(&node_id);
-                                    let before_len = connections.len();
-                                    connections = connections
-                                        .into_iter()
-                                        .filter(|conn| conn.peer_node_id().distance(&node_id) <= dist_from_dest)
-                                        .collect::<Vec<_>>();
-
-                                    debug!(
-                                        target: LOG_TARGET,
-                                        "Filtered out {} node(s) that are further away than this node.",
-                                        before_len - connections.len()
-                                    );
-                                }
-
-                                connections.truncate(config.propagation_factor);
-                                connections
-                            },
-                        }
-                    },
-                    None => {
-                        debug!(
-                            target: LOG_TARGET,
-                            "No destination for propagation, sending to {} random peers", config.propagation_factor
-                        );
-                        connectivity
-                            .select_connections(ConnectivitySelection::random_nodes(
-                                config.propagation_factor,
-                                exclude.clone(),
-                            ))
-                            .await?
-                    },
-                };
-
-                if connections.is_empty() {
-                    info!(
-                        target: LOG_TARGET,
-                        "Propagation requested but there are no node peer connections available"
-                    );
-                }
-
-                let candidates = connections
-                    .iter()
-                    .map(|c| c.peer_node_id())
-                    .cloned()
-                    .collect::<Vec<_>>();
-
-                debug!(
-                    target: LOG_TARGET,
-                    "{} candidate(s) selected for propagation to {}",
-                    candidates.len(),
-                    destination
-                );
-
-                trace!(
-                    target: LOG_TARGET,
-                    "(ThisNode = {}) Candidates are {}",
-                    node_identity.node_id().short_str(),
-                    candidates.iter().map(|n| n.short_str()).collect::<Vec<_>>().join(", ")
-                );
-
-                Ok(candidates)
+                let mut context = PeerSelectionContext::new(config, node_identity, peer_manager, connectivity);
+                propagation_strategy
+                    .select_propagation_peers(&mut context, &destination, &exclude)
+                    .await
             },
         }
     }
@@ -665,21 +622,11 @@ impl DhtActor {
         // - it didn't recently fail to connect, and
         // - it is not in the exclusion list in closest_request
         let mut connect_ineligable_count = 0;
-        let mut banned_count = 0;
         let mut excluded_count = 0;
-        let mut filtered_out_node_count = 0;
         let query = PeerQuery::new()
+            .ban_state(PeerBanState::NotBanned)
+            .with_features(features)
             .select_where(|peer| {
-                if peer.is_banned() {
-                    banned_count += 1;
-                    return false;
-                }
-
-                if !peer.features.contains(features) {
-                    filtered_out_node_count += 1;
-                    return false;
-                }
-
                 if peer.is_offline() {
                     connect_ineligable_count += 1;
                     return false;
@@ -697,17 +644,15 @@ impl DhtActor {
             .limit(n);
 
         let peers = peer_manager.perform_query(query).await?;
-        let total_excluded = banned_count + connect_ineligable_count + excluded_count + filtered_out_node_count;
+        let total_excluded = connect_ineligable_count + excluded_count;
         if total_excluded > 0 {
             debug!(
                 target: LOG_TARGET,
-                "👨‍👧‍👦 Closest Peer Selection: {num_peers} peer(s) selected, {total} peer(s) not selected, {banned} \
-                 banned, {filtered_out} not communication node, {not_connectable} are not connectable, {excluded} \
-                 explicitly excluded",
+                "👨‍👧‍👦 Closest Peer Selection: {num_peers} non-banned peer(s) with features {features:?} selected, \
+                 {total} peer(s) not selected, {not_connectable} are not connectable, {excluded} explicitly excluded",
                 num_peers = peers.len(),
+                features = features,
                 total = total_excluded,
-                banned = banned_count,
-                filtered_out = filtered_out_node_count,
                 not_connectable = connect_ineligable_count,
                 excluded = excluded_count
             );
@@ -1220,6 +1165,66 @@ mod test {
         assert_eq!(peers.len(), 1);
     }
 
+    #[runtime::test]
+    async fn select_peers_with_propagation_strategy() {
+        struct SelectSelf;
+
+        #[tari_comms::async_trait]
+        impl PropagationStrategy for SelectSelf {
+            async fn select_broadcast_peers(
+                &self,
+                context: &mut PeerSelectionContext,
+                _: &[NodeId],
+            ) -> Result<Vec<NodeId>, DhtActorError> {
+                Ok(vec![context.node_identity().node_id().clone()])
+            }
+
+            async fn select_propagation_peers(
+                &self,
+                _: &mut PeerSelectionContext,
+                destination: &NodeDestination,
+                _: &[NodeId],
+            ) -> Result<Vec<NodeId>, DhtActorError> {
+                Ok(destination.node_id().cloned().into_iter().collect())
+            }
+        }
+
+        let node_identity = make_node_identity();
+        let (connectivity_manager, mock) = create_connectivity_mock();
+        mock.spawn();
+        let (discovery, _) = create_dht_discovery_mock(Duration::from_secs(10));
+        let (out_tx, _) = mpsc::channel(1);
+        let (actor_tx, actor_rx) = mpsc::channel(1);
+        let mut requester = DhtRequester::new(actor_tx);
+        let shutdown = Shutdown::new();
+        DhtActor::new(
+            Default::default(),
+            db_connection().await,
+            Arc::clone(&node_identity),
+            build_peer_manager(),
+            connectivity_manager,
+            OutboundMessageRequester::new(out_tx),
+            actor_rx,
+            discovery,
+            shutdown.to_signal(),
+        )
+        .with_propagation_strategy(Arc::new(SelectSelf))
+        .spawn();
+
+        let peers = requester
+            .select_peers(BroadcastStrategy::Broadcast(Vec::new()))
+            .await
+            .unwrap();
+        assert_eq!(peers, vec![node_identity.node_id().clone()]);
+
+        let dest = make_node_identity().node_id().clone();
+        let peers = requester
+            .select_peers(BroadcastStrategy::Propagate(dest.clone().into(), Vec::new()))
+            .await
+            .unwrap();
+        assert_eq!(peers, vec![dest]);
+    }
+
     #[runtime::test]
     async fn get_and_set_metadata() {
         let node_identity = make_node_identity();
@@ -133,6 +133,23 @@ impl BroadcastStrategy {
         }
     }
 
+    /// Returns the `NodeId` of the peer that this strategy is trying to reach, otherwise None if the strategy has no
+    /// particular destination.
+    pub fn target_node_id(&self) -> Option<NodeId> {
+        #[allow(clippy::enum_glob_use)]
+        use BroadcastStrategy::*;
+        match self {
+            DirectNodeId(node_id) => Some((**node_id).clone()),
+            DirectPublicKey(pk) => Some(NodeId::from_public_key(pk)),
+            ClosestNodes(request) | DirectOrClosestNodes(request) => Some(request.node_id.clone()),
+            Propagate(destination, _) => destination
+                .node_id()
+                .cloned()
+                .or_else(|| destination.public_key().map(NodeId::from_public_key)),
+            Flood(_) | Random(_, _) | Broadcast(_) | SelectedPeers(_) => None,
+        }
+    }
+
     /// Returns the `CommsPublicKey` used in the `DirectPublicKey` strategy, otherwise None if the strategy is not
     /// `DirectPublicKey`.
     pub fn into_direct_public_key(self) -> Option<Box<CommsPublicKey>> {
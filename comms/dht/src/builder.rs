@@ -31,6 +31,7 @@ use tokio::sync::mpsc;
 use crate::{
     dht::DhtInitializationError,
     outbound::DhtOutboundRequest,
+    propagation::{ClosestNeighbourPropagation, PropagationStrategy},
     version::DhtProtocolVersion,
     DbConnectionUrl,
     Dht,
@@ -48,6 +49,7 @@ use crate::{
 pub struct DhtBuilder {
     config: DhtConfig,
     outbound_tx: Option<mpsc::Sender<DhtOutboundRequest>>,
+    propagation_strategy: Option<Arc<dyn PropagationStrategy>>,
 }
 
 impl DhtBuilder {
@@ -58,6 +60,7 @@ impl DhtBuilder {
             #[cfg(not(test))]
             config: Default::default(),
             outbound_tx: None,
+            propagation_strategy: None,
         }
     }
 
@@ -91,6 +94,13 @@ impl DhtBuilder {
         self
     }
 
+    /// Sets the strategy used to select peers for broadcast and propagated messages. If not set,
+    /// [ClosestNeighbourPropagation](crate::ClosestNeighbourPropagation) is used.
+    pub fn with_propagation_strategy<T: PropagationStrategy>(&mut self, propagation_strategy: T) -> &mut Self {
+        self.propagation_strategy = Some(Arc::new(propagation_strategy));
+        self
+    }
+
     /// Use the default testnet configuration.
     pub fn testnet(&mut self) -> &mut Self {
         self.config = DhtConfig::default_testnet();
@@ -164,12 +174,18 @@ impl DhtBuilder {
             .take()
             .ok_or(DhtInitializationError::BuilderNoOutboundMessageSender)?;
 
+        let propagation_strategy = self
+            .propagation_strategy
+            .clone()
+            .unwrap_or_else(|| Arc::new(ClosestNeighbourPropagation));
+
         Dht::initialize(
             self.config.clone(),
             node_identity,
             peer_manager,
             outbound_tx,
             connectivity,
+            propagation_strategy,
             shutdown_signal,
         )
         .await
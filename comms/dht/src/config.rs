@@ -25,7 +25,11 @@ use std::{path::Path, time::Duration};
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    inbound::InboundRateLimitConfig,
+    logging_middleware::MessageLoggingConfig,
+    misbehaviour::MisbehaviourReportConfig,
     network_discovery::NetworkDiscoveryConfig,
+    padding::MessagePaddingConfig,
     storage::DbConnectionUrl,
     store_forward::SafConfig,
     version::DhtProtocolVersion,
@@ -98,6 +102,19 @@ pub struct DhtConfig {
     /// peers that were previously tried.
     /// Default: 2 hours
     pub offline_peer_cooldown: Duration,
+    /// If set, the full peer list served by the `get_peers` RPC method is cached for this length of time instead of
+    /// being read from the peer database on every request. This is useful for seed nodes that serve many peer list
+    /// requests.
+    /// Default: None (disabled)
+    pub peer_list_cache_ttl: Option<Duration>,
+    /// Filtering and sampling of the DHT message trace log
+    pub message_logging: MessageLoggingConfig,
+    /// Per-peer inbound message rate limits
+    pub inbound_rate_limit: InboundRateLimitConfig,
+    /// Padding of encrypted message bodies
+    pub message_padding: MessagePaddingConfig,
+    /// Exchange of misbehaviour reports with trusted (operator-linked) peers
+    pub misbehaviour_reports: MisbehaviourReportConfig,
 }
 
 impl DhtConfig {
@@ -162,6 +179,11 @@ impl Default for DhtConfig {
             flood_ban_max_msg_count: 100_000,
             flood_ban_timespan: Duration::from_secs(100),
             offline_peer_cooldown: Duration::from_secs(2 * 60 * 60),
+            peer_list_cache_ttl: None,
+            message_logging: Default::default(),
+            inbound_rate_limit: Default::default(),
+            message_padding: Default::default(),
+            misbehaviour_reports: Default::default(),
         }
     }
 }
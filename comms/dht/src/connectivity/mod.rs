@@ -40,6 +40,7 @@ use log::*;
 pub use metrics::{MetricsCollector, MetricsCollectorHandle};
 use tari_comms::{
     connectivity::{
+        sort_peers_by_score,
         ConnectivityError,
         ConnectivityEvent,
         ConnectivityEventRx,
@@ -833,7 +834,9 @@ impl DhtConnectivity {
             // Fetch double here so that there is a bigger closest peer set that can be ordered by last seen
             .limit(n * 2);
 
-        let peers = peer_manager.perform_query(query).await?;
+        let mut peers = peer_manager.perform_query(query).await?;
+        // Prefer the better connected peers out of the closest peer set
+        sort_peers_by_score(&mut peers);
         let total_excluded = banned_count + connect_ineligable_count + excluded_count + filtered_out_node_count;
         if total_excluded > 0 {
             debug!(
@@ -857,8 +860,10 @@ impl DhtConnectivity {
     }
 
     async fn fetch_random_peers(&self, n: usize, excluded: &[NodeId]) -> Result<Vec<NodeId>, DhtConnectivityError> {
-        let peers = self.peer_manager.random_peers(n, excluded).await?;
-        Ok(peers.into_iter().map(|p| p.node_id).collect())
+        // Fetch double so that the better connected peers out of the random set can be preferred
+        let mut peers = self.peer_manager.random_peers(n * 2, excluded).await?;
+        sort_peers_by_score(&mut peers);
+        Ok(peers.into_iter().map(|p| p.node_id).take(n).collect())
     }
 
     fn should_send_join(&self) -> bool {
@@ -45,9 +45,11 @@ use crate::{
     inbound,
     inbound::{DecryptedDhtMessage, DhtInboundMessage, ForwardLayer, MetricsLayer},
     logging_middleware::MessageLoggingLayer,
+    misbehaviour::{MisbehaviourReportRequest, MisbehaviourReportRequester, MisbehaviourReportService},
     network_discovery::DhtNetworkDiscovery,
     outbound,
     outbound::DhtOutboundRequest,
+    propagation::PropagationStrategy,
     proto::envelope::DhtMessageType,
     rpc,
     storage::{DbConnection, StorageError},
@@ -64,6 +66,7 @@ const LOG_TARGET: &str = "comms::dht";
 const DHT_ACTOR_CHANNEL_SIZE: usize = 100;
 const DHT_DISCOVERY_CHANNEL_SIZE: usize = 100;
 const DHT_SAF_SERVICE_CHANNEL_SIZE: usize = 100;
+const DHT_MISBEHAVIOUR_REPORT_CHANNEL_SIZE: usize = 100;
 const DHT_EVENT_BROADCAST_CHANNEL_SIZE: usize = 100;
 
 #[derive(Debug, Error)]
@@ -98,12 +101,16 @@ pub struct Dht {
     saf_response_signal_sender: mpsc::Sender<()>,
     /// Sender for DHT discovery requests
     discovery_sender: mpsc::Sender<DhtDiscoveryRequest>,
+    /// Sender for misbehaviour report requests
+    misbehaviour_report_sender: mpsc::Sender<MisbehaviourReportRequest>,
     /// Connectivity actor requester
     connectivity: ConnectivityRequester,
     /// Event stream sender
     event_publisher: DhtEventSender,
     /// Used by MetricsLayer to collect metrics and to inform heuristics for peer banning
     metrics_collector: MetricsCollectorHandle,
+    /// Selects peers for broadcast and propagated messages
+    propagation_strategy: Arc<dyn PropagationStrategy>,
 }
 
 impl Dht {
@@ -113,12 +120,15 @@ impl Dht {
         peer_manager: Arc<PeerManager>,
         outbound_tx: mpsc::Sender<DhtOutboundRequest>,
         connectivity: ConnectivityRequester,
+        propagation_strategy: Arc<dyn PropagationStrategy>,
         shutdown_signal: ShutdownSignal,
     ) -> Result<Self, DhtInitializationError> {
         let (dht_sender, dht_receiver) = mpsc::channel(DHT_ACTOR_CHANNEL_SIZE);
         let (discovery_sender, discovery_receiver) = mpsc::channel(DHT_DISCOVERY_CHANNEL_SIZE);
         let (saf_sender, saf_receiver) = mpsc::channel(DHT_SAF_SERVICE_CHANNEL_SIZE);
         let (saf_response_signal_sender, saf_response_signal_receiver) = mpsc::channel(DHT_SAF_SERVICE_CHANNEL_SIZE);
+        let (misbehaviour_report_sender, misbehaviour_report_receiver) =
+            mpsc::channel(DHT_MISBEHAVIOUR_REPORT_CHANNEL_SIZE);
         let (event_publisher, _) = broadcast::channel(DHT_EVENT_BROADCAST_CHANNEL_SIZE);
 
         let metrics_collector = MetricsCollector::spawn();
@@ -134,7 +144,9 @@ impl Dht {
             saf_response_signal_sender,
             connectivity,
             discovery_sender,
+            misbehaviour_report_sender,
             event_publisher,
+            propagation_strategy,
         };
 
         let conn = DbConnection::connect_and_migrate(&dht.config.database_url.clone())
@@ -150,6 +162,10 @@ impl Dht {
         )
         .spawn();
         dht.actor(conn, dht_receiver, shutdown_signal.clone()).spawn();
+        if dht.config.misbehaviour_reports.enabled {
+            dht.misbehaviour_report_service(misbehaviour_report_receiver, shutdown_signal.clone())
+                .spawn();
+        }
         dht.discovery_service(discovery_receiver, shutdown_signal).spawn();
 
         debug!(target: LOG_TARGET, "Dht initialization complete.");
@@ -163,7 +179,10 @@ impl Dht {
 
     /// Create a DHT RPC service
     pub fn rpc_service(&self) -> rpc::DhtService<rpc::DhtRpcServiceImpl> {
-        rpc::DhtService::new(rpc::DhtRpcServiceImpl::new(self.peer_manager.clone()))
+        rpc::DhtService::new(rpc::DhtRpcServiceImpl::new(
+            self.peer_manager.clone(),
+            self.config.peer_list_cache_ttl,
+        ))
     }
 
     /// Create a DHT actor
@@ -184,6 +203,7 @@ impl Dht {
             self.discovery_service_requester(),
             shutdown_signal,
         )
+        .with_propagation_strategy(self.propagation_strategy.clone())
     }
 
     /// Create the discovery service
@@ -202,6 +222,23 @@ impl Dht {
         )
     }
 
+    /// Create the misbehaviour report service
+    fn misbehaviour_report_service(
+        &self,
+        request_receiver: mpsc::Receiver<MisbehaviourReportRequest>,
+        shutdown_signal: ShutdownSignal,
+    ) -> MisbehaviourReportService {
+        MisbehaviourReportService::new(
+            self.config.clone(),
+            Arc::clone(&self.node_identity),
+            Arc::clone(&self.peer_manager),
+            self.connectivity.clone(),
+            self.outbound_requester(),
+            request_receiver,
+            shutdown_signal,
+        )
+    }
+
     fn connectivity_service(&self, shutdown_signal: ShutdownSignal) -> DhtConnectivity {
         DhtConnectivity::new(
             self.config.clone(),
@@ -263,6 +300,11 @@ impl Dht {
         DhtDiscoveryRequester::new(self.discovery_sender.clone(), self.config.discovery_request_timeout)
     }
 
+    /// Returns a requester for the MisbehaviourReportService associated with this instance
+    pub fn misbehaviour_report_requester(&self) -> MisbehaviourReportRequester {
+        MisbehaviourReportRequester::new(self.misbehaviour_report_sender.clone())
+    }
+
     /// Returns a requester for the StoreAndForwardService associated with this instance
     pub fn store_and_forward_requester(&self) -> StoreAndForwardRequester {
         StoreAndForwardRequester::new(self.saf_sender.clone())
@@ -296,6 +338,7 @@ impl Dht {
     {
         ServiceBuilder::new()
             .layer(MetricsLayer::new(self.metrics_collector.clone()))
+            .layer(inbound::RateLimitLayer::new(&self.config, self.connectivity.clone()))
             .layer(inbound::DeserializeLayer::new(self.peer_manager.clone()))
             .layer(filter::FilterLayer::new(self.unsupported_saf_messages_filter()))
             .layer(inbound::DecryptionLayer::new(
@@ -308,10 +351,10 @@ impl Dht {
                 self.config.dedup_allowed_message_occurrences,
             ))
             .layer(filter::FilterLayer::new(filter_messages_to_rebroadcast))
-            .layer(MessageLoggingLayer::new(format!(
-                "Inbound [{}]",
-                self.node_identity.node_id().short_str()
-            )))
+            .layer(MessageLoggingLayer::new(
+                format!("Inbound [{}]", self.node_identity.node_id().short_str()),
+                &self.config.message_logging,
+            ))
             .layer(store_forward::StoreLayer::new(
                 self.config.saf.clone(),
                 Arc::clone(&self.peer_manager),
@@ -336,6 +379,7 @@ impl Dht {
                 self.node_identity.clone(),
                 self.peer_manager.clone(),
                 self.discovery_service_requester(),
+                self.misbehaviour_report_requester(),
                 self.outbound_requester(),
             ))
             .into_inner()
@@ -365,10 +409,10 @@ impl Dht {
                 self.discovery_service_requester(),
                 &self.config,
             ))
-            .layer(MessageLoggingLayer::new(format!(
-                "Outbound [{}]",
-                self.node_identity.node_id().short_str()
-            )))
+            .layer(MessageLoggingLayer::new(
+                format!("Outbound [{}]", self.node_identity.node_id().short_str()),
+                &self.config.message_logging,
+            ))
             .layer(outbound::SerializeLayer)
             .into_inner()
     }
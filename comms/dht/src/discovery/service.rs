@@ -318,7 +318,12 @@ impl DhtDiscoveryService {
     ) -> Result<(), DhtDiscoveryError> {
         let discover_msg = DiscoveryMessage {
             node_id: self.node_identity.node_id().to_vec(),
-            addresses: vec![self.node_identity.public_address().to_string()],
+            addresses: self
+                .node_identity
+                .public_addresses()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
             peer_features: self.node_identity.features().bits(),
             nonce,
             identity_signature: self.node_identity.identity_signature_read().as_ref().map(Into::into),
@@ -102,6 +102,8 @@ bitflags! {
         const NONE = 0x00;
         /// Set if the message is encrypted
         const ENCRYPTED = 0x01;
+        /// Set if the encrypted message body is padded to a fixed size bucket
+        const PADDED = 0x02;
     }
 }
 
@@ -109,6 +111,10 @@ impl DhtMessageFlags {
     pub fn is_encrypted(self) -> bool {
         self.contains(Self::ENCRYPTED)
     }
+
+    pub fn is_padded(self) -> bool {
+        self.contains(Self::PADDED)
+    }
 }
 
 impl DhtMessageType {
@@ -117,7 +123,10 @@ impl DhtMessageType {
     }
 
     pub fn is_dht_message(self) -> bool {
-        self.is_dht_discovery() || matches!(self, DhtMessageType::DiscoveryResponse) || self.is_dht_join()
+        self.is_dht_discovery() ||
+            matches!(self, DhtMessageType::DiscoveryResponse) ||
+            self.is_dht_join() ||
+            self.is_misbehaviour_report()
     }
 
     pub fn is_dht_discovery(self) -> bool {
@@ -128,6 +137,10 @@ impl DhtMessageType {
         matches!(self, DhtMessageType::Join)
     }
 
+    pub fn is_misbehaviour_report(self) -> bool {
+        matches!(self, DhtMessageType::MisbehaviourReport)
+    }
+
     pub fn is_saf_message(self) -> bool {
         use DhtMessageType::{SafRequestMessages, SafStoredMessages};
         matches!(self, SafRequestMessages | SafStoredMessages)
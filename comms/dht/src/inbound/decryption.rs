@@ -42,6 +42,7 @@ use crate::{
     crypt::CipherKey,
     envelope::DhtMessageHeader,
     inbound::message::{DecryptedDhtMessage, DhtInboundMessage},
+    padding,
     proto::envelope::OriginMac,
     DhtConfig,
 };
@@ -266,7 +267,7 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
             message.tag,
             message.dht_header.message_tag
         );
-        match Self::attempt_decrypt_message_body(&shared_secret, &message.body) {
+        match Self::attempt_decrypt_message_body(&shared_secret, &message.body, message.dht_header.flags.is_padded()) {
             Ok(message_body) => {
                 debug!(
                     target: LOG_TARGET,
@@ -336,12 +337,18 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
     fn attempt_decrypt_message_body(
         shared_secret: &CipherKey,
         message_body: &[u8],
+        is_padded: bool,
     ) -> Result<EnvelopeBody, DecryptionError> {
         let decrypted =
             crypt::decrypt(shared_secret, message_body).map_err(|_| DecryptionError::MessageBodyDecryptionFailed)?;
+        let decrypted = if is_padded {
+            padding::unpad_message(&decrypted).ok_or(DecryptionError::MessageBodyDecryptionFailed)?
+        } else {
+            decrypted.as_slice()
+        };
         // Deserialization into an EnvelopeBody is done here to determine if the
         // decryption produced valid bytes or not.
-        EnvelopeBody::decode(decrypted.as_slice())
+        EnvelopeBody::decode(decrypted)
             .and_then(|body| {
                 // Check if we received a body length of zero
                 //
@@ -472,6 +479,37 @@ mod test {
         assert_eq!(decrypted.decryption_result.unwrap(), plain_text_msg);
     }
 
+    #[test]
+    fn decrypt_inbound_padded_success() {
+        let result = Arc::new(Mutex::new(None));
+        let service = service_fn({
+            let result = result.clone();
+            move |msg: DecryptedDhtMessage| {
+                *result.lock().unwrap() = Some(msg);
+                future::ready(Result::<(), PipelineError>::Ok(()))
+            }
+        });
+        let node_identity = make_node_identity();
+        let (connectivity, _) = create_connectivity_mock();
+        let mut service = DecryptionService::new(Default::default(), node_identity.clone(), connectivity, service);
+
+        let plain_text_msg = wrap_in_envelope_body!(b"Secret plans".to_vec());
+        let padded = padding::pad_message(&plain_text_msg.to_encoded_bytes(), &[1024]);
+        assert_eq!(padded.len(), 1024);
+        let inbound_msg = make_dht_inbound_message(
+            &node_identity,
+            padded,
+            DhtMessageFlags::ENCRYPTED | DhtMessageFlags::PADDED,
+            true,
+            true,
+        );
+
+        block_on(service.call(inbound_msg)).unwrap();
+        let decrypted = result.lock().unwrap().take().unwrap();
+        assert!(decrypted.decryption_succeeded());
+        assert_eq!(decrypted.decryption_result.unwrap(), plain_text_msg);
+    }
+
     #[test]
     fn decrypt_inbound_fail() {
         let result = Arc::new(Mutex::new(None));
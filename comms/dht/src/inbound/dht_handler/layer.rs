@@ -26,7 +26,12 @@ use tari_comms::peer_manager::{NodeIdentity, PeerManager};
 use tower::layer::Layer;
 
 use super::middleware::DhtHandlerMiddleware;
-use crate::{discovery::DhtDiscoveryRequester, outbound::OutboundMessageRequester, DhtConfig};
+use crate::{
+    discovery::DhtDiscoveryRequester,
+    misbehaviour::MisbehaviourReportRequester,
+    outbound::OutboundMessageRequester,
+    DhtConfig,
+};
 
 pub struct DhtHandlerLayer {
     config: Arc<DhtConfig>,
@@ -34,6 +39,7 @@ pub struct DhtHandlerLayer {
     node_identity: Arc<NodeIdentity>,
     outbound_service: OutboundMessageRequester,
     discovery_requester: DhtDiscoveryRequester,
+    misbehaviour_report_requester: MisbehaviourReportRequester,
 }
 
 impl DhtHandlerLayer {
@@ -42,6 +48,7 @@ impl DhtHandlerLayer {
         node_identity: Arc<NodeIdentity>,
         peer_manager: Arc<PeerManager>,
         discovery_requester: DhtDiscoveryRequester,
+        misbehaviour_report_requester: MisbehaviourReportRequester,
         outbound_service: OutboundMessageRequester,
     ) -> Self {
         Self {
@@ -50,6 +57,7 @@ impl DhtHandlerLayer {
             node_identity,
             outbound_service,
             discovery_requester,
+            misbehaviour_report_requester,
         }
     }
 }
@@ -64,6 +72,7 @@ impl<S> Layer<S> for DhtHandlerLayer {
             Arc::clone(&self.peer_manager),
             self.outbound_service.clone(),
             self.discovery_requester.clone(),
+            self.misbehaviour_report_requester.clone(),
             self.config.clone(),
         )
     }
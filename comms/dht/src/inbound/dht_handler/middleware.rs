@@ -33,6 +33,7 @@ use super::task::ProcessDhtMessage;
 use crate::{
     discovery::DhtDiscoveryRequester,
     inbound::DecryptedDhtMessage,
+    misbehaviour::MisbehaviourReportRequester,
     outbound::OutboundMessageRequester,
     DhtConfig,
 };
@@ -44,6 +45,7 @@ pub struct DhtHandlerMiddleware<S> {
     node_identity: Arc<NodeIdentity>,
     outbound_service: OutboundMessageRequester,
     discovery_requester: DhtDiscoveryRequester,
+    misbehaviour_report_requester: MisbehaviourReportRequester,
     config: Arc<DhtConfig>,
 }
 
@@ -54,6 +56,7 @@ impl<S> DhtHandlerMiddleware<S> {
         peer_manager: Arc<PeerManager>,
         outbound_service: OutboundMessageRequester,
         discovery_requester: DhtDiscoveryRequester,
+        misbehaviour_report_requester: MisbehaviourReportRequester,
         config: Arc<DhtConfig>,
     ) -> Self {
         Self {
@@ -62,6 +65,7 @@ impl<S> DhtHandlerMiddleware<S> {
             node_identity,
             outbound_service,
             discovery_requester,
+            misbehaviour_report_requester,
             config,
         }
     }
@@ -88,6 +92,7 @@ where
                 self.outbound_service.clone(),
                 Arc::clone(&self.node_identity),
                 self.discovery_requester.clone(),
+                self.misbehaviour_report_requester.clone(),
                 message,
                 self.config.clone(),
             )
@@ -38,10 +38,11 @@ use crate::{
     discovery::DhtDiscoveryRequester,
     envelope::NodeDestination,
     inbound::{error::DhtInboundError, message::DecryptedDhtMessage},
+    misbehaviour::MisbehaviourReportRequester,
     outbound::{OutboundMessageRequester, SendMessageParams},
     peer_validator::PeerValidator,
     proto::{
-        dht::{DiscoveryMessage, DiscoveryResponseMessage, JoinMessage},
+        dht::{DiscoveryMessage, DiscoveryResponseMessage, JoinMessage, MisbehaviourReportMessage},
         envelope::DhtMessageType,
     },
     DhtConfig,
@@ -56,6 +57,7 @@ pub struct ProcessDhtMessage<S> {
     node_identity: Arc<NodeIdentity>,
     message: Option<DecryptedDhtMessage>,
     discovery_requester: DhtDiscoveryRequester,
+    misbehaviour_report_requester: MisbehaviourReportRequester,
     config: Arc<DhtConfig>,
 }
 
@@ -68,6 +70,7 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
         outbound_service: OutboundMessageRequester,
         node_identity: Arc<NodeIdentity>,
         discovery_requester: DhtDiscoveryRequester,
+        misbehaviour_report_requester: MisbehaviourReportRequester,
         message: DecryptedDhtMessage,
         config: Arc<DhtConfig>,
     ) -> Self {
@@ -77,6 +80,7 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
             outbound_service,
             node_identity,
             discovery_requester,
+            misbehaviour_report_requester,
             message: Some(message),
             config,
         }
@@ -123,6 +127,7 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
             DhtMessageType::Join => self.handle_join(message).await?,
             DhtMessageType::Discovery => self.handle_discover(message).await?,
             DhtMessageType::DiscoveryResponse => self.handle_discover_response(message).await?,
+            DhtMessageType::MisbehaviourReport => self.handle_misbehaviour_report(message).await?,
             // Not a DHT message, call downstream middleware
             _ => {
                 trace!(
@@ -269,6 +274,35 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
         Ok(())
     }
 
+    async fn handle_misbehaviour_report(&mut self, message: DecryptedDhtMessage) -> Result<(), DhtInboundError> {
+        if !self.config.misbehaviour_reports.enabled {
+            debug!(
+                target: LOG_TARGET,
+                "Discarding misbehaviour report from peer '{}' because misbehaviour reports are disabled",
+                message.source_peer.node_id.short_str()
+            );
+            return Ok(());
+        }
+
+        let authenticated_pk = message.authenticated_origin.clone().ok_or_else(|| {
+            DhtInboundError::OriginRequired("Origin header required for misbehaviour report".to_string())
+        })?;
+
+        let msg = message
+            .success()
+            .expect("already checked that this message decrypted successfully");
+
+        let report = msg
+            .decode_part::<MisbehaviourReportMessage>(0)?
+            .ok_or(DhtInboundError::InvalidMessageBody)?;
+
+        self.misbehaviour_report_requester
+            .notify_report_received(authenticated_pk, report)
+            .await?;
+
+        Ok(())
+    }
+
     async fn handle_discover(&mut self, message: DecryptedDhtMessage) -> Result<(), DhtInboundError> {
         let msg = message
             .success()
@@ -343,7 +377,12 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
     ) -> Result<(), DhtInboundError> {
         let response = DiscoveryResponseMessage {
             node_id: self.node_identity.node_id().to_vec(),
-            addresses: vec![self.node_identity.public_address().to_string()],
+            addresses: self
+                .node_identity
+                .public_addresses()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
             peer_features: self.node_identity.features().bits(),
             nonce,
             identity_signature: self.node_identity.identity_signature_read().as_ref().map(Into::into),
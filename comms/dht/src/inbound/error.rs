@@ -23,7 +23,12 @@
 use tari_comms::{message::MessageError, peer_manager::PeerManagerError};
 use thiserror::Error;
 
-use crate::{discovery::DhtDiscoveryError, outbound::DhtOutboundError, peer_validator::PeerValidatorError};
+use crate::{
+    discovery::DhtDiscoveryError,
+    misbehaviour::MisbehaviourReportError,
+    outbound::DhtOutboundError,
+    peer_validator::PeerValidatorError,
+};
 
 #[derive(Debug, Error)]
 pub enum DhtInboundError {
@@ -45,4 +50,6 @@ pub enum DhtInboundError {
     InvalidPeerIdentitySignature(String),
     #[error("Invalid peer: {0}")]
     PeerValidatorError(#[from] PeerValidatorError),
+    #[error("MisbehaviourReportError: {0}")]
+    MisbehaviourReportError(#[from] MisbehaviourReportError),
 }
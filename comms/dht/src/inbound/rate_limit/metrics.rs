@@ -0,0 +1,75 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use once_cell::sync::Lazy;
+use tari_comms::peer_manager::NodeId;
+use tari_metrics::{IntCounter, IntCounterVec, IntGauge};
+
+pub fn dropped_message_count(node_id: &NodeId) -> IntCounter {
+    static METER: Lazy<IntCounterVec> = Lazy::new(|| {
+        tari_metrics::register_int_counter_vec(
+            "comms::dht::inbound::rate_limit::dropped_message_count",
+            "The number of inbound messages dropped because the peer exceeded the rate limit per peer",
+            &["peer_id"],
+        )
+        .unwrap()
+    });
+
+    METER.with_label_values(&[node_id.to_string().as_str()])
+}
+
+pub fn dropped_bytes_count(node_id: &NodeId) -> IntCounter {
+    static METER: Lazy<IntCounterVec> = Lazy::new(|| {
+        tari_metrics::register_int_counter_vec(
+            "comms::dht::inbound::rate_limit::dropped_bytes_count",
+            "The number of inbound message bytes dropped because the peer exceeded the rate limit per peer",
+            &["peer_id"],
+        )
+        .unwrap()
+    });
+
+    METER.with_label_values(&[node_id.to_string().as_str()])
+}
+
+pub fn ban_count() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        tari_metrics::register_int_counter(
+            "comms::dht::inbound::rate_limit::ban_count",
+            "The number of peers banned for exceeding the inbound message rate limit",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
+
+pub fn num_tracked_peers() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        tari_metrics::register_int_gauge(
+            "comms::dht::inbound::rate_limit::num_tracked_peers",
+            "The number of peers whose inbound message rate is being tracked",
+        )
+        .unwrap()
+    });
+
+    METER.clone()
+}
@@ -0,0 +1,190 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Per-peer inbound message rate limiting. Messages from a peer that exceed the configured message or byte rate are
+//! discarded before they are deserialized, and peers that keep flooding are banned.
+
+mod metrics;
+mod tracker;
+
+use std::{
+    sync::{Arc, Mutex},
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+use futures::{future, future::BoxFuture, task::Context, FutureExt};
+use log::*;
+use serde::{Deserialize, Serialize};
+use tari_comms::{connectivity::ConnectivityRequester, message::InboundMessage, pipeline::PipelineError};
+use tower::{layer::Layer, Service};
+use tracker::{RateDecision, RateTracker};
+
+use crate::DhtConfig;
+
+const LOG_TARGET: &str = "comms::dht::inbound::rate_limit";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InboundRateLimitConfig {
+    /// Set to false to disable inbound message rate limiting
+    /// Default: true
+    pub enabled: bool,
+    /// The window over which messages and bytes are counted for each peer
+    /// Default: 10 seconds
+    pub window: Duration,
+    /// The maximum number of messages accepted from a peer within the window
+    /// Default: 1,000
+    pub max_messages: usize,
+    /// The maximum number of message bytes accepted from a peer within the window
+    /// Default: 50 MiB
+    pub max_bytes: usize,
+    /// Ban the peer (for `ban_duration_short`) once this many of its messages have been dropped within the window. If
+    /// None, excess messages are dropped but the peer is never banned.
+    /// Default: 1,000
+    pub ban_after_dropped: Option<usize>,
+}
+
+impl Default for InboundRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window: Duration::from_secs(10),
+            max_messages: 1_000,
+            max_bytes: 50 * 1024 * 1024,
+            ban_after_dropped: Some(1_000),
+        }
+    }
+}
+
+/// Layer that drops inbound messages from peers that exceed the configured rate limits
+pub struct RateLimitLayer {
+    tracker: Option<Arc<Mutex<RateTracker>>>,
+    connectivity: ConnectivityRequester,
+    ban_duration: Duration,
+}
+
+impl RateLimitLayer {
+    pub fn new(config: &DhtConfig, connectivity: ConnectivityRequester) -> Self {
+        let rate_limit = &config.inbound_rate_limit;
+        Self {
+            tracker: if rate_limit.enabled {
+                Some(Arc::new(Mutex::new(RateTracker::new(
+                    rate_limit.clone(),
+                    Instant::now(),
+                ))))
+            } else {
+                None
+            },
+            connectivity,
+            ban_duration: config.ban_duration_short,
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RateLimitService {
+            inner: service,
+            tracker: self.tracker.clone(),
+            connectivity: self.connectivity.clone(),
+            ban_duration: self.ban_duration,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    tracker: Option<Arc<Mutex<RateTracker>>>,
+    connectivity: ConnectivityRequester,
+    ban_duration: Duration,
+}
+
+impl<S> Service<InboundMessage> for RateLimitService<S>
+where
+    S: Service<InboundMessage, Response = (), Error = PipelineError>,
+    S::Future: Send + 'static,
+{
+    type Error = PipelineError;
+    type Future = BoxFuture<'static, Result<(), PipelineError>>;
+    type Response = ();
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, message: InboundMessage) -> Self::Future {
+        let tracker = match self.tracker {
+            Some(ref tracker) => tracker,
+            None => return self.inner.call(message).boxed(),
+        };
+
+        let decision = {
+            let mut tracker = tracker.lock().unwrap();
+            let decision = tracker.check(&message.source_peer, message.body.len(), Instant::now());
+            metrics::num_tracked_peers().set(tracker.num_tracked_peers() as i64);
+            decision
+        };
+
+        match decision {
+            RateDecision::Allow => self.inner.call(message).boxed(),
+            RateDecision::Drop => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Peer '{}' exceeded the inbound message rate limit. Discarding message ({})",
+                    message.source_peer,
+                    message.tag
+                );
+                metrics::dropped_message_count(&message.source_peer).inc();
+                metrics::dropped_bytes_count(&message.source_peer).inc_by(message.body.len() as u64);
+                future::ready(Ok(())).boxed()
+            },
+            RateDecision::Ban => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Peer '{}' repeatedly exceeded the inbound message rate limit. Banning peer for {:.0?}",
+                    message.source_peer,
+                    self.ban_duration
+                );
+                metrics::dropped_message_count(&message.source_peer).inc();
+                metrics::dropped_bytes_count(&message.source_peer).inc_by(message.body.len() as u64);
+                metrics::ban_count().inc();
+                let mut connectivity = self.connectivity.clone();
+                let ban_duration = self.ban_duration;
+                async move {
+                    connectivity
+                        .ban_peer_until(
+                            message.source_peer,
+                            ban_duration,
+                            "Exceeded the inbound message rate limit".to_string(),
+                        )
+                        .await?;
+                    Ok(())
+                }
+                .boxed()
+            },
+        }
+    }
+}
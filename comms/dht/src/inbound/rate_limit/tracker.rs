@@ -0,0 +1,170 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{collections::HashMap, time::Instant};
+
+use tari_comms::peer_manager::NodeId;
+
+use super::InboundRateLimitConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RateDecision {
+    /// The message is within the peer's rate limits
+    Allow,
+    /// The message exceeds the peer's rate limits and should be discarded
+    Drop,
+    /// The message should be discarded and the peer has dropped enough messages to be banned
+    Ban,
+}
+
+#[derive(Debug)]
+struct PeerRate {
+    window_start: Instant,
+    num_messages: usize,
+    num_bytes: usize,
+    num_dropped: usize,
+}
+
+impl PeerRate {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            num_messages: 0,
+            num_bytes: 0,
+            num_dropped: 0,
+        }
+    }
+}
+
+/// Counts the messages and bytes received from each peer over a fixed window.
+#[derive(Debug)]
+pub(super) struct RateTracker {
+    config: InboundRateLimitConfig,
+    peers: HashMap<NodeId, PeerRate>,
+    last_pruned: Instant,
+}
+
+impl RateTracker {
+    pub fn new(config: InboundRateLimitConfig, now: Instant) -> Self {
+        Self {
+            config,
+            peers: HashMap::new(),
+            last_pruned: now,
+        }
+    }
+
+    pub fn check(&mut self, node_id: &NodeId, num_bytes: usize, now: Instant) -> RateDecision {
+        self.prune(now);
+
+        let window = self.config.window;
+        let rate = self.peers.entry(node_id.clone()).or_insert_with(|| PeerRate::new(now));
+        if now.duration_since(rate.window_start) >= window {
+            *rate = PeerRate::new(now);
+        }
+
+        if rate.num_messages < self.config.max_messages &&
+            rate.num_bytes.saturating_add(num_bytes) <= self.config.max_bytes
+        {
+            rate.num_messages += 1;
+            rate.num_bytes += num_bytes;
+            return RateDecision::Allow;
+        }
+
+        rate.num_dropped += 1;
+        // Only ban once per window, the peer is disconnected by the ban
+        if self.config.ban_after_dropped == Some(rate.num_dropped) {
+            RateDecision::Ban
+        } else {
+            RateDecision::Drop
+        }
+    }
+
+    pub fn num_tracked_peers(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Removes the peers whose window has expired, at most once per window
+    fn prune(&mut self, now: Instant) {
+        let window = self.config.window;
+        if now.duration_since(self.last_pruned) < window {
+            return;
+        }
+        self.peers
+            .retain(|_, rate| now.duration_since(rate.window_start) < window);
+        self.last_pruned = now;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn config() -> InboundRateLimitConfig {
+        InboundRateLimitConfig {
+            enabled: true,
+            window: Duration::from_secs(10),
+            max_messages: 2,
+            max_bytes: 100,
+            ban_after_dropped: Some(2),
+        }
+    }
+
+    #[test]
+    fn it_drops_messages_over_the_message_limit_and_bans() {
+        let now = Instant::now();
+        let node_id = NodeId::default();
+        let mut tracker = RateTracker::new(config(), now);
+        assert_eq!(tracker.check(&node_id, 1, now), RateDecision::Allow);
+        assert_eq!(tracker.check(&node_id, 1, now), RateDecision::Allow);
+        assert_eq!(tracker.check(&node_id, 1, now), RateDecision::Drop);
+        assert_eq!(tracker.check(&node_id, 1, now), RateDecision::Ban);
+        assert_eq!(tracker.check(&node_id, 1, now), RateDecision::Drop);
+    }
+
+    #[test]
+    fn it_drops_messages_over_the_byte_limit() {
+        let now = Instant::now();
+        let node_id = NodeId::default();
+        let mut tracker = RateTracker::new(config(), now);
+        assert_eq!(tracker.check(&node_id, 80, now), RateDecision::Allow);
+        assert_eq!(tracker.check(&node_id, 21, now), RateDecision::Drop);
+        assert_eq!(tracker.check(&node_id, 20, now), RateDecision::Allow);
+    }
+
+    #[test]
+    fn it_resets_and_prunes_after_the_window() {
+        let now = Instant::now();
+        let node_id = NodeId::default();
+        let mut tracker = RateTracker::new(config(), now);
+        assert_eq!(tracker.check(&node_id, 1, now), RateDecision::Allow);
+        assert_eq!(tracker.check(&node_id, 1, now), RateDecision::Allow);
+        assert_eq!(tracker.check(&node_id, 1, now), RateDecision::Drop);
+
+        let later = now + Duration::from_secs(10);
+        assert_eq!(tracker.check(&node_id, 1, later), RateDecision::Allow);
+        assert_eq!(tracker.num_tracked_peers(), 1);
+        tracker.prune(later + Duration::from_secs(20));
+        assert_eq!(tracker.num_tracked_peers(), 0);
+    }
+}
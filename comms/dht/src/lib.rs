@@ -91,9 +91,23 @@ pub use dht::{Dht, DhtInitializationError};
 mod discovery;
 pub use discovery::{DhtDiscoveryError, DhtDiscoveryRequester};
 
+mod misbehaviour;
+pub use misbehaviour::{
+    MisbehaviourReportConfig,
+    MisbehaviourReportError,
+    MisbehaviourReportPolicy,
+    MisbehaviourReportRequester,
+};
+
 mod network_discovery;
 pub use network_discovery::NetworkDiscoveryConfig;
 
+mod neighbourhood;
+pub use neighbourhood::{NeighbourPeer, NeighbourhoodInfo, PeerSelectionRecord, RegionBucket};
+
+mod propagation;
+pub use propagation::{ClosestNeighbourPropagation, PeerSelectionContext, PropagationStrategy};
+
 mod storage;
 pub use storage::DbConnectionUrl;
 
@@ -102,6 +116,11 @@ pub use dedup::DedupLayer;
 
 mod filter;
 mod logging_middleware;
+pub use logging_middleware::MessageLoggingConfig;
+
+mod padding;
+pub use padding::MessagePaddingConfig;
+
 mod peer_validator;
 mod proto;
 mod rpc;
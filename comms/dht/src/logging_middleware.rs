@@ -20,25 +20,192 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{borrow::Cow, fmt::Display, marker::PhantomData, task::Poll};
+use std::{borrow::Cow, fmt::Display, marker::PhantomData, sync::Arc, task::Poll};
 
 use futures::task::Context;
 use log::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tari_comms::{message::MessageTag, peer_manager::NodeId, types::CommsPublicKey};
+use tari_utilities::hex::Hex;
 use tower::{layer::Layer, Service};
 
+use crate::{
+    envelope::{DhtMessageType, NodeDestination},
+    inbound::DecryptedDhtMessage,
+    outbound::DhtOutboundMessage,
+};
+
 const LOG_TARGET: &str = "comms::middleware::message_logging";
 
+/// Configuration for the DHT message logging middleware. Messages are only logged when the
+/// `comms::middleware::message_logging` target is enabled at trace level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MessageLoggingConfig {
+    /// Only log messages with one of these DHT message types (e.g. `Join`, `Discovery`, `None` for domain messages).
+    /// Default: empty (all message types)
+    pub message_types: Vec<String>,
+    /// Only log messages with one of these DHT destinations. A destination is either `unknown`, a hex public key or a
+    /// hex node id.
+    /// Default: empty (all destinations)
+    pub destinations: Vec<String>,
+    /// Only log messages received from or sent to one of these peers, given as hex node ids.
+    /// Default: empty (all peers)
+    pub peers: Vec<String>,
+    /// The probability that a message which passes the filters is logged, between 0.0 and 1.0.
+    /// Default: 1.0
+    pub sample_rate: f64,
+    /// Emit structured `tracing` events with the message properties as fields instead of formatted log lines.
+    /// Default: false
+    pub structured: bool,
+}
+
+impl Default for MessageLoggingConfig {
+    fn default() -> Self {
+        Self {
+            message_types: Vec::new(),
+            destinations: Vec::new(),
+            peers: Vec::new(),
+            sample_rate: 1.0,
+            structured: false,
+        }
+    }
+}
+
+/// The message properties the logging middleware filters on.
+pub trait LoggableMessage: Display {
+    fn message_type(&self) -> DhtMessageType;
+    fn destination(&self) -> &NodeDestination;
+    /// The peer that the message was received from or is being sent to
+    fn peer_node_id(&self) -> &NodeId;
+    fn message_tag(&self) -> MessageTag;
+}
+
+impl LoggableMessage for DecryptedDhtMessage {
+    fn message_type(&self) -> DhtMessageType {
+        self.dht_header.message_type
+    }
+
+    fn destination(&self) -> &NodeDestination {
+        &self.dht_header.destination
+    }
+
+    fn peer_node_id(&self) -> &NodeId {
+        &self.source_peer.node_id
+    }
+
+    fn message_tag(&self) -> MessageTag {
+        self.tag
+    }
+}
+
+impl LoggableMessage for DhtOutboundMessage {
+    fn message_type(&self) -> DhtMessageType {
+        self.dht_message_type
+    }
+
+    fn destination(&self) -> &NodeDestination {
+        &self.destination
+    }
+
+    fn peer_node_id(&self) -> &NodeId {
+        &self.destination_node_id
+    }
+
+    fn message_tag(&self) -> MessageTag {
+        self.tag
+    }
+}
+
+/// The parsed form of [MessageLoggingConfig](self::MessageLoggingConfig) that is shared between service clones.
+#[derive(Debug)]
+struct MessageLogFilter {
+    message_types: Vec<DhtMessageType>,
+    destinations: Vec<NodeDestination>,
+    peers: Vec<NodeId>,
+    sample_rate: f64,
+    structured: bool,
+}
+
+impl MessageLogFilter {
+    fn from_config(config: &MessageLoggingConfig) -> Self {
+        Self {
+            message_types: parse_all(&config.message_types, "DHT message type", parse_message_type),
+            destinations: parse_all(&config.destinations, "destination", parse_destination),
+            peers: parse_all(&config.peers, "peer node id", |s| NodeId::from_hex(s).ok()),
+            sample_rate: config.sample_rate.max(0.0).min(1.0),
+            structured: config.structured,
+        }
+    }
+
+    fn is_match(&self, message_type: DhtMessageType, destination: &NodeDestination, peer: &NodeId) -> bool {
+        (self.message_types.is_empty() || self.message_types.contains(&message_type)) &&
+            (self.destinations.is_empty() || self.destinations.contains(destination)) &&
+            (self.peers.is_empty() || self.peers.contains(peer))
+    }
+
+    fn is_sampled(&self) -> bool {
+        self.sample_rate >= 1.0 || rand::thread_rng().gen_bool(self.sample_rate)
+    }
+}
+
+/// Parses each configured value, logging and skipping the ones that are invalid.
+fn parse_all<T, F>(values: &[String], kind: &str, parse: F) -> Vec<T>
+where F: Fn(&str) -> Option<T> {
+    values
+        .iter()
+        .filter_map(|value| {
+            let parsed = parse(value);
+            if parsed.is_none() {
+                warn!(
+                    target: LOG_TARGET,
+                    "Ignoring invalid {} '{}' in message logging config", kind, value
+                );
+            }
+            parsed
+        })
+        .collect()
+}
+
+fn parse_destination(value: &str) -> Option<NodeDestination> {
+    if value.eq_ignore_ascii_case("unknown") {
+        return Some(NodeDestination::Unknown);
+    }
+    CommsPublicKey::from_hex(value)
+        .map(NodeDestination::from)
+        .or_else(|_| NodeId::from_hex(value).map(NodeDestination::from))
+        .ok()
+}
+
+fn parse_message_type(name: &str) -> Option<DhtMessageType> {
+    [
+        DhtMessageType::None,
+        DhtMessageType::Join,
+        DhtMessageType::Discovery,
+        DhtMessageType::DiscoveryResponse,
+        DhtMessageType::MisbehaviourReport,
+        DhtMessageType::SafRequestMessages,
+        DhtMessageType::SafStoredMessages,
+    ]
+    .iter()
+    .copied()
+    .find(|t| t.to_string().eq_ignore_ascii_case(name))
+}
+
 /// This layer is responsible for logging messages for debugging.
 pub struct MessageLoggingLayer<'a, R> {
     prefix_msg: Cow<'a, str>,
+    filter: Arc<MessageLogFilter>,
     _r: PhantomData<R>,
 }
 
 impl<'a, R> MessageLoggingLayer<'a, R> {
     /// Creates a new logging middleware layer
-    pub fn new<T: Into<Cow<'a, str>>>(prefix_msg: T) -> Self {
+    pub fn new<T: Into<Cow<'a, str>>>(prefix_msg: T, config: &MessageLoggingConfig) -> Self {
         Self {
             prefix_msg: prefix_msg.into(),
+            filter: Arc::new(MessageLogFilter::from_config(config)),
             _r: PhantomData,
         }
     }
@@ -47,12 +214,12 @@ impl<'a, R> MessageLoggingLayer<'a, R> {
 impl<'a, S, R> Layer<S> for MessageLoggingLayer<'a, R>
 where
     S: Service<R>,
-    R: Display,
+    R: LoggableMessage,
 {
     type Service = MessageLoggingService<'a, S>;
 
     fn layer(&self, service: S) -> Self::Service {
-        MessageLoggingService::new(self.prefix_msg.clone(), service)
+        MessageLoggingService::new(self.prefix_msg.clone(), self.filter.clone(), service)
     }
 }
 
@@ -60,14 +227,43 @@ where
 #[derive(Clone)]
 pub struct MessageLoggingService<'a, S> {
     prefix_msg: Cow<'a, str>,
+    filter: Arc<MessageLogFilter>,
     inner: S,
 }
 
 impl<'a, S> MessageLoggingService<'a, S> {
-    pub fn new(prefix_msg: Cow<'a, str>, service: S) -> Self {
+    fn new(prefix_msg: Cow<'a, str>, filter: Arc<MessageLogFilter>, service: S) -> Self {
         Self {
             inner: service,
             prefix_msg,
+            filter,
+        }
+    }
+
+    fn log_message<R: LoggableMessage>(&self, msg: &R) {
+        if !self
+            .filter
+            .is_match(msg.message_type(), msg.destination(), msg.peer_node_id())
+        {
+            return;
+        }
+        if !self.filter.is_sampled() {
+            return;
+        }
+
+        if self.filter.structured {
+            tracing::event!(
+                target: LOG_TARGET,
+                tracing::Level::TRACE,
+                prefix = %self.prefix_msg,
+                message_type = %msg.message_type(),
+                destination = %msg.destination(),
+                peer = %msg.peer_node_id(),
+                tag = %msg.message_tag(),
+                "DHT message"
+            );
+        } else {
+            trace!(target: LOG_TARGET, "{}{}", self.prefix_msg, msg);
         }
     }
 }
@@ -75,7 +271,7 @@ impl<'a, S> MessageLoggingService<'a, S> {
 impl<S, R> Service<R> for MessageLoggingService<'_, S>
 where
     S: Service<R>,
-    R: Display,
+    R: LoggableMessage,
 {
     type Error = S::Error;
     type Future = S::Future;
@@ -86,7 +282,59 @@ where
     }
 
     fn call(&mut self, msg: R) -> Self::Future {
-        trace!(target: LOG_TARGET, "{}{}", self.prefix_msg, msg);
+        if self.filter.structured || log_enabled!(target: LOG_TARGET, Level::Trace) {
+            self.log_message(&msg);
+        }
         self.inner.call(msg)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use tari_utilities::ByteArray;
+
+    use super::*;
+
+    fn node_id(byte: u8) -> NodeId {
+        NodeId::from_bytes(&[byte; NodeId::byte_size()]).unwrap()
+    }
+
+    #[test]
+    fn it_matches_everything_by_default() {
+        let filter = MessageLogFilter::from_config(&MessageLoggingConfig::default());
+        assert!(filter.is_match(DhtMessageType::Join, &NodeDestination::Unknown, &node_id(1)));
+        assert!(filter.is_sampled());
+    }
+
+    #[test]
+    fn it_requires_all_configured_filters_to_match() {
+        let filter = MessageLogFilter::from_config(&MessageLoggingConfig {
+            message_types: vec!["discovery".to_string(), "None".to_string(), "NotAType".to_string()],
+            destinations: vec!["unknown".to_string(), "not hex".to_string()],
+            peers: vec![node_id(1).to_hex()],
+            ..Default::default()
+        });
+        assert_eq!(filter.message_types, vec![
+            DhtMessageType::Discovery,
+            DhtMessageType::None
+        ]);
+        assert!(filter.is_match(DhtMessageType::Discovery, &NodeDestination::Unknown, &node_id(1)));
+        assert!(filter.is_match(DhtMessageType::None, &NodeDestination::Unknown, &node_id(1)));
+        assert!(!filter.is_match(DhtMessageType::Join, &NodeDestination::Unknown, &node_id(1)));
+        assert!(!filter.is_match(DhtMessageType::Discovery, &NodeDestination::Unknown, &node_id(2)));
+        assert!(!filter.is_match(
+            DhtMessageType::Discovery,
+            &NodeDestination::NodeId(Box::new(node_id(3))),
+            &node_id(1)
+        ));
+    }
+
+    #[test]
+    fn it_never_samples_with_a_zero_rate() {
+        let filter = MessageLogFilter::from_config(&MessageLoggingConfig {
+            sample_rate: -1.0,
+            ..Default::default()
+        });
+        assert!((0..100).all(|_| !filter.is_sampled()));
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MisbehaviourReportConfig {
+    /// Set to true to exchange misbehaviour reports with the peers in `trusted_peers`.
+    /// Default: false
+    pub enabled: bool,
+    /// Hex public keys of the operator-linked peers that reports are sent to and accepted from.
+    /// Default: empty
+    pub trusted_peers: Vec<String>,
+    /// Set to true to send a report to the trusted peers whenever this node bans a peer.
+    /// Default: true
+    pub share_local_bans: bool,
+    /// What to do with a report received from a trusted peer.
+    /// Default: LogOnly
+    pub policy: MisbehaviourReportPolicy,
+    /// The number of distinct trusted peers that must report a peer within `report_window` before the policy is
+    /// applied.
+    /// Default: 1
+    pub min_reporters: usize,
+    /// The period over which reports for the same peer are counted.
+    /// Default: 1 hour
+    pub report_window: Duration,
+    /// The maximum length of a ban issued because of a report. The ban duration given in the report is used if it is
+    /// shorter.
+    /// Default: 6 hours
+    pub max_ban_duration: Duration,
+}
+
+impl Default for MisbehaviourReportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            trusted_peers: Vec::new(),
+            share_local_bans: true,
+            policy: MisbehaviourReportPolicy::default(),
+            min_reporters: 1,
+            report_window: Duration::from_secs(60 * 60),
+            max_ban_duration: Duration::from_secs(6 * 60 * 60),
+        }
+    }
+}
+
+/// The local policy for reports received from trusted peers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MisbehaviourReportPolicy {
+    /// Log the report without acting on it
+    LogOnly,
+    /// Ban the reported peer once enough trusted peers have reported it
+    Ban,
+}
+
+impl Default for MisbehaviourReportPolicy {
+    fn default() -> Self {
+        MisbehaviourReportPolicy::LogOnly
+    }
+}
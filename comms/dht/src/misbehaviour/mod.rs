@@ -0,0 +1,47 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! # Misbehaviour report exchange
+//!
+//! Operator-linked nodes (e.g. a cluster of seed nodes) may share the bans they issue with each other, so that an
+//! attacker banned by one node can be dealt with by the others before it reaches them.
+//!
+//! 1. When this node bans a peer, a [MisbehaviourReport](crate::envelope::DhtMessageType) message is sent, encrypted,
+//! directly to each of the configured trusted peers.
+//! 1. When a report is received, the authenticated origin of the message must be one of the trusted peers, otherwise
+//! the report is discarded.
+//! 1. The local [MisbehaviourReportPolicy] decides whether to act on the report. Bans issued because of a report are
+//! not shared again, so reports do not bounce between trusted peers.
+
+mod config;
+pub use config::{MisbehaviourReportConfig, MisbehaviourReportPolicy};
+
+mod error;
+pub use error::MisbehaviourReportError;
+
+mod requester;
+pub use requester::MisbehaviourReportRequester;
+
+pub(crate) use self::requester::MisbehaviourReportRequest;
+
+mod service;
+pub(crate) use service::MisbehaviourReportService;
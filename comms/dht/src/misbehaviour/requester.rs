@@ -0,0 +1,72 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::{Display, Error, Formatter};
+
+use tari_comms::types::CommsPublicKey;
+use tokio::sync::mpsc;
+
+use super::MisbehaviourReportError;
+use crate::proto::dht::MisbehaviourReportMessage;
+
+#[derive(Debug)]
+pub enum MisbehaviourReportRequest {
+    NotifyReportReceived(Box<CommsPublicKey>, Box<MisbehaviourReportMessage>),
+}
+
+impl Display for MisbehaviourReportRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        use MisbehaviourReportRequest::NotifyReportReceived;
+        match self {
+            NotifyReportReceived(reporter, report) => {
+                write!(f, "NotifyReportReceived({}, {})", reporter, report)
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MisbehaviourReportRequester {
+    sender: mpsc::Sender<MisbehaviourReportRequest>,
+}
+
+impl MisbehaviourReportRequester {
+    pub fn new(sender: mpsc::Sender<MisbehaviourReportRequest>) -> Self {
+        Self { sender }
+    }
+
+    /// Notify the misbehaviour report service that a report was received from the given (authenticated) origin
+    pub(crate) async fn notify_report_received(
+        &mut self,
+        reporter: CommsPublicKey,
+        report: MisbehaviourReportMessage,
+    ) -> Result<(), MisbehaviourReportError> {
+        self.sender
+            .send(MisbehaviourReportRequest::NotifyReportReceived(
+                Box::new(reporter),
+                Box::new(report),
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
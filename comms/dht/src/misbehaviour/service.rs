@@ -0,0 +1,356 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{
+    cmp,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use log::*;
+use tari_comms::{
+    connectivity::{ConnectivityEvent, ConnectivityEventRx, ConnectivityRequester},
+    log_if_error,
+    multiaddr::Multiaddr,
+    peer_manager::{NodeId, NodeIdentity, Peer, PeerFeatures, PeerFlags, PeerManager},
+    types::CommsPublicKey,
+};
+use tari_shutdown::ShutdownSignal;
+use tari_utilities::{hex::Hex, ByteArray};
+use tokio::{sync::mpsc, task};
+
+use super::{MisbehaviourReportError, MisbehaviourReportPolicy, MisbehaviourReportRequest};
+use crate::{
+    envelope::DhtMessageType,
+    outbound::{OutboundEncryption, OutboundMessageRequester, SendMessageParams},
+    proto::dht::MisbehaviourReportMessage,
+    DhtConfig,
+};
+
+const LOG_TARGET: &str = "comms::dht::misbehaviour_report";
+
+pub struct MisbehaviourReportService {
+    config: Arc<DhtConfig>,
+    node_identity: Arc<NodeIdentity>,
+    peer_manager: Arc<PeerManager>,
+    connectivity: ConnectivityRequester,
+    outbound_requester: OutboundMessageRequester,
+    request_rx: mpsc::Receiver<MisbehaviourReportRequest>,
+    shutdown_signal: ShutdownSignal,
+    trusted_peers: Vec<CommsPublicKey>,
+    tally: ReportTally,
+    /// Peers banned because of a report. These bans are not shared with the trusted peers.
+    reported_bans: HashSet<NodeId>,
+}
+
+impl MisbehaviourReportService {
+    pub fn new(
+        config: Arc<DhtConfig>,
+        node_identity: Arc<NodeIdentity>,
+        peer_manager: Arc<PeerManager>,
+        connectivity: ConnectivityRequester,
+        outbound_requester: OutboundMessageRequester,
+        request_rx: mpsc::Receiver<MisbehaviourReportRequest>,
+        shutdown_signal: ShutdownSignal,
+    ) -> Self {
+        let trusted_peers = config
+            .misbehaviour_reports
+            .trusted_peers
+            .iter()
+            .filter_map(|s| match CommsPublicKey::from_hex(s) {
+                Ok(pk) => Some(pk),
+                Err(err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Ignoring invalid trusted peer public key '{}': {}", s, err
+                    );
+                    None
+                },
+            })
+            .collect();
+
+        Self {
+            config,
+            node_identity,
+            peer_manager,
+            connectivity,
+            outbound_requester,
+            request_rx,
+            shutdown_signal,
+            trusted_peers,
+            tally: ReportTally::default(),
+            reported_bans: HashSet::new(),
+        }
+    }
+
+    pub fn spawn(self) {
+        // Listen to events as early as possible
+        let connectivity_events = self.connectivity.get_event_subscription();
+        let mut mdc = vec![];
+        log_mdc::iter(|k, v| mdc.push((k.to_owned(), v.to_owned())));
+        task::spawn(async move {
+            log_mdc::extend(mdc);
+            info!(target: LOG_TARGET, "Misbehaviour report service started");
+            self.run(connectivity_events).await
+        });
+    }
+
+    async fn run(mut self, mut connectivity_events: ConnectivityEventRx) {
+        debug!(
+            target: LOG_TARGET,
+            "Exchanging misbehaviour reports with {} trusted peer(s)",
+            self.trusted_peers.len()
+        );
+        loop {
+            tokio::select! {
+                biased;
+
+                _ = self.shutdown_signal.wait() => {
+                    info!(target: LOG_TARGET, "Misbehaviour report service is shutting down because the shutdown signal was received");
+                    break;
+                }
+
+                Some(request) = self.request_rx.recv() => {
+                    trace!(target: LOG_TARGET, "Received request '{}'", request);
+                    self.handle_request(request).await;
+                },
+
+                Ok(event) = connectivity_events.recv() => {
+                    if let ConnectivityEvent::PeerBanned(node_id) = event {
+                        log_if_error!(
+                            target: LOG_TARGET,
+                            self.share_local_ban(node_id).await,
+                            "Failed to share ban with trusted peers because '{error}'",
+                        );
+                    }
+                },
+            }
+        }
+    }
+
+    async fn handle_request(&mut self, request: MisbehaviourReportRequest) {
+        use MisbehaviourReportRequest::NotifyReportReceived;
+        match request {
+            NotifyReportReceived(reporter, report) => {
+                log_if_error!(
+                    target: LOG_TARGET,
+                    self.handle_report(*reporter, *report).await,
+                    "Failed to handle misbehaviour report because '{error}'",
+                );
+            },
+        }
+    }
+
+    async fn share_local_ban(&mut self, node_id: NodeId) -> Result<(), MisbehaviourReportError> {
+        if self.reported_bans.remove(&node_id) {
+            debug!(
+                target: LOG_TARGET,
+                "Not sharing ban for peer '{}' because it was issued for a misbehaviour report",
+                node_id.short_str()
+            );
+            return Ok(());
+        }
+
+        if !self.config.misbehaviour_reports.share_local_bans {
+            return Ok(());
+        }
+
+        let peer = match self.peer_manager.find_by_node_id(&node_id).await? {
+            Some(peer) => peer,
+            None => return Ok(()),
+        };
+        let ban_duration = match peer.banned_until() {
+            Some(banned_until) => (*banned_until - Utc::now().naive_utc()).to_std().unwrap_or_default(),
+            None => return Ok(()),
+        };
+
+        let report = MisbehaviourReportMessage {
+            public_key: peer.public_key.to_vec(),
+            reason: peer.banned_reason.clone(),
+            ban_duration: ban_duration.as_secs(),
+        };
+
+        for trusted_peer in &self.trusted_peers {
+            if *trusted_peer == peer.public_key {
+                continue;
+            }
+
+            debug!(
+                target: LOG_TARGET,
+                "Sending misbehaviour report for peer '{}' to trusted peer '{}'",
+                node_id.short_str(),
+                trusted_peer
+            );
+            self.outbound_requester
+                .send_message_no_header(
+                    SendMessageParams::new()
+                        .direct_public_key(trusted_peer.clone())
+                        .with_encryption(OutboundEncryption::encrypt_for(trusted_peer.clone()))
+                        .with_dht_message_type(DhtMessageType::MisbehaviourReport)
+                        .finish(),
+                    report.clone(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn handle_report(
+        &mut self,
+        reporter: CommsPublicKey,
+        report: MisbehaviourReportMessage,
+    ) -> Result<(), MisbehaviourReportError> {
+        if !self.trusted_peers.contains(&reporter) {
+            warn!(
+                target: LOG_TARGET,
+                "Discarding misbehaviour report from untrusted peer '{}'", reporter
+            );
+            return Ok(());
+        }
+
+        let public_key =
+            CommsPublicKey::from_bytes(&report.public_key).map_err(|_| MisbehaviourReportError::InvalidPublicKey)?;
+        if public_key == *self.node_identity.public_key() || self.trusted_peers.contains(&public_key) {
+            warn!(
+                target: LOG_TARGET,
+                "Discarding misbehaviour report from '{}' about this node or a trusted peer", reporter
+            );
+            return Ok(());
+        }
+
+        let config = &self.config.misbehaviour_reports;
+        let node_id = NodeId::from_public_key(&public_key);
+        let num_reporters = self
+            .tally
+            .record(node_id.clone(), reporter.clone(), config.report_window);
+        info!(
+            target: LOG_TARGET,
+            "Trusted peer '{}' reported peer '{}' ({}). {} trusted peer(s) have reported this peer",
+            reporter,
+            node_id,
+            report.reason,
+            num_reporters
+        );
+
+        if config.policy == MisbehaviourReportPolicy::LogOnly || num_reporters < config.min_reporters {
+            return Ok(());
+        }
+
+        match self.peer_manager.find_by_node_id(&node_id).await? {
+            Some(peer) if peer.is_banned() => {
+                debug!(target: LOG_TARGET, "Reported peer '{}' is already banned", node_id);
+                return Ok(());
+            },
+            Some(_) => {},
+            None => {
+                // Add the peer so that the ban applies if it connects to this node later
+                self.peer_manager
+                    .add_peer(Peer::new(
+                        public_key,
+                        node_id.clone(),
+                        Vec::<Multiaddr>::new().into(),
+                        PeerFlags::empty(),
+                        PeerFeatures::empty(),
+                        vec![],
+                        String::new(),
+                    ))
+                    .await?;
+            },
+        }
+
+        let ban_duration = cmp::min(Duration::from_secs(report.ban_duration), config.max_ban_duration);
+        if ban_duration.as_secs() == 0 {
+            return Ok(());
+        }
+
+        let reason = format!("Reported by {} trusted peer(s) ({})", num_reporters, report.reason);
+        self.tally.remove(&node_id);
+        self.reported_bans.insert(node_id.clone());
+        self.connectivity.ban_peer_until(node_id, ban_duration, reason).await?;
+
+        Ok(())
+    }
+}
+
+/// Counts the distinct trusted peers that reported each peer within a time window
+#[derive(Default)]
+struct ReportTally {
+    reports: HashMap<NodeId, Vec<(CommsPublicKey, Instant)>>,
+}
+
+impl ReportTally {
+    /// Records a report and returns the number of distinct peers that reported `node_id` within `window`
+    pub fn record(&mut self, node_id: NodeId, reporter: CommsPublicKey, window: Duration) -> usize {
+        self.prune(window);
+        let reports = self.reports.entry(node_id).or_insert_with(Vec::new);
+        reports.retain(|(pk, _)| *pk != reporter);
+        reports.push((reporter, Instant::now()));
+        reports.len()
+    }
+
+    pub fn remove(&mut self, node_id: &NodeId) {
+        self.reports.remove(node_id);
+    }
+
+    fn prune(&mut self, window: Duration) {
+        self.reports.retain(|_, reports| {
+            reports.retain(|(_, received_at)| received_at.elapsed() < window);
+            !reports.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::make_node_identity;
+
+    #[test]
+    fn it_counts_distinct_reporters() {
+        let mut tally = ReportTally::default();
+        let node_id = make_node_identity().node_id().clone();
+        let reporter1 = make_node_identity().public_key().clone();
+        let reporter2 = make_node_identity().public_key().clone();
+        let window = Duration::from_secs(60);
+
+        assert_eq!(tally.record(node_id.clone(), reporter1.clone(), window), 1);
+        assert_eq!(tally.record(node_id.clone(), reporter1, window), 1);
+        assert_eq!(tally.record(node_id.clone(), reporter2, window), 2);
+
+        tally.remove(&node_id);
+        assert!(tally.reports.is_empty());
+    }
+
+    #[test]
+    fn it_forgets_reports_outside_the_window() {
+        let mut tally = ReportTally::default();
+        let node_id = make_node_identity().node_id().clone();
+        let reporter1 = make_node_identity().public_key().clone();
+        let reporter2 = make_node_identity().public_key().clone();
+
+        assert_eq!(tally.record(node_id.clone(), reporter1, Duration::from_secs(60)), 1);
+        assert_eq!(tally.record(node_id, reporter2, Duration::from_secs(0)), 1);
+    }
+}
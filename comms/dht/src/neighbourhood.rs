@@ -0,0 +1,279 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Introspection of this node's view of the DHT neighbourhood, used to debug why messages are not reaching a peer.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use chrono::{NaiveDateTime, Utc};
+use tari_comms::{
+    connectivity::ConnectivityRequester,
+    peer_manager::{NodeId, Peer, PeerFeatures, PeerManager},
+    types::CommsPublicKey,
+};
+use tari_storage::IterationResult;
+
+use crate::{actor::DhtActorError, broadcast_strategy::BroadcastStrategy};
+
+/// The number of peer selections kept for introspection
+const SELECTION_LOG_CAPACITY: usize = 100;
+
+/// A snapshot of this node's view of the DHT neighbourhood
+#[derive(Debug, Clone)]
+pub struct NeighbourhoodInfo {
+    /// The node id of this node
+    pub node_id: NodeId,
+    /// Known peers grouped by the bucket index of their distance from this node, closest bucket first
+    pub buckets: Vec<RegionBucket>,
+    /// The closest known communication nodes, which make up this node's network region
+    pub neighbours: Vec<NeighbourPeer>,
+    /// The most recent peer selections made for messages sent to more than one peer, most recent first
+    pub recent_selections: Vec<PeerSelectionRecord>,
+}
+
+/// Known peers whose distance `d` from this node falls within `2^index <= d < 2^(index+1)`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegionBucket {
+    pub index: u8,
+    pub num_peers: usize,
+    pub num_connected: usize,
+    pub num_offline: usize,
+    pub num_banned: usize,
+}
+
+/// A known peer and the quality of this node's connection to it
+#[derive(Debug, Clone)]
+pub struct NeighbourPeer {
+    pub node_id: NodeId,
+    pub public_key: CommsPublicKey,
+    pub features: PeerFeatures,
+    /// The bucket index of the distance between this peer and this node
+    pub bucket: u8,
+    pub last_seen: Option<NaiveDateTime>,
+    pub is_connected: bool,
+    pub is_offline: bool,
+    pub is_banned: bool,
+    pub last_connected_at: Option<NaiveDateTime>,
+    /// The number of failed connection attempts since the last successful connection
+    pub failed_attempts: usize,
+}
+
+/// The peers chosen by a broadcast strategy for a single outbound message
+#[derive(Debug, Clone)]
+pub struct PeerSelectionRecord {
+    pub selected_at: NaiveDateTime,
+    /// The broadcast strategy, as displayed in logs
+    pub strategy: String,
+    /// The node that the message is intended to reach, if the strategy has one
+    pub target: Option<NodeId>,
+    pub selected_peers: Vec<NodeId>,
+}
+
+impl PeerSelectionRecord {
+    fn new(strategy: &BroadcastStrategy, selected_peers: Vec<NodeId>) -> Self {
+        Self {
+            selected_at: Utc::now().naive_utc(),
+            strategy: strategy.to_string(),
+            target: strategy.target_node_id(),
+            selected_peers,
+        }
+    }
+}
+
+/// A bounded log of the peers selected for messages sent to more than one peer. Direct sends are not recorded.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PeerSelectionLog {
+    records: Arc<Mutex<VecDeque<PeerSelectionRecord>>>,
+}
+
+impl PeerSelectionLog {
+    pub fn record(&self, strategy: &BroadcastStrategy, selected_peers: &[NodeId]) {
+        if !strategy.is_multi_message(selected_peers) {
+            return;
+        }
+        let mut records = self.records.lock().unwrap();
+        if records.len() >= SELECTION_LOG_CAPACITY {
+            records.pop_back();
+        }
+        records.push_front(PeerSelectionRecord::new(strategy, selected_peers.to_vec()));
+    }
+
+    pub fn records(&self) -> Vec<PeerSelectionRecord> {
+        self.records.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+pub(crate) async fn get_neighbourhood_info(
+    node_id: &NodeId,
+    num_neighbouring_nodes: usize,
+    peer_manager: &PeerManager,
+    connectivity: &mut ConnectivityRequester,
+    selection_log: &PeerSelectionLog,
+) -> Result<NeighbourhoodInfo, DhtActorError> {
+    let connected = connectivity
+        .get_active_connections()
+        .await?
+        .into_iter()
+        .map(|conn| conn.peer_node_id().clone())
+        .collect::<Vec<_>>();
+
+    // Bucket peers as they are read rather than loading the entire peer database into memory
+    let mut buckets = BTreeMap::new();
+    peer_manager
+        .for_each(|peer| {
+            add_to_region_bucket(&mut buckets, node_id, &peer, &connected);
+            IterationResult::Continue
+        })
+        .await?;
+    let buckets = buckets.into_iter().map(|(_, bucket)| bucket).collect();
+
+    let neighbours = peer_manager
+        .closest_peers(
+            node_id,
+            num_neighbouring_nodes,
+            &[],
+            Some(PeerFeatures::COMMUNICATION_NODE),
+        )
+        .await?
+        .into_iter()
+        .map(|peer| NeighbourPeer {
+            bucket: node_id.distance(&peer.node_id).get_bucket_index(),
+            is_connected: connected.contains(&peer.node_id),
+            is_offline: peer.is_offline(),
+            is_banned: peer.is_banned(),
+            last_seen: peer.last_seen(),
+            last_connected_at: peer.connection_stats.last_connected_at,
+            failed_attempts: peer.connection_stats.failed_attempts(),
+            features: peer.features,
+            public_key: peer.public_key,
+            node_id: peer.node_id,
+        })
+        .collect();
+
+    Ok(NeighbourhoodInfo {
+        node_id: node_id.clone(),
+        buckets,
+        neighbours,
+        recent_selections: selection_log.records(),
+    })
+}
+
+fn add_to_region_bucket(buckets: &mut BTreeMap<u8, RegionBucket>, node_id: &NodeId, peer: &Peer, connected: &[NodeId]) {
+    let index = node_id.distance(&peer.node_id).get_bucket_index();
+    let bucket = buckets.entry(index).or_insert_with(|| RegionBucket {
+        index,
+        ..Default::default()
+    });
+    bucket.num_peers += 1;
+    if connected.contains(&peer.node_id) {
+        bucket.num_connected += 1;
+    }
+    if peer.is_offline() {
+        bucket.num_offline += 1;
+    }
+    if peer.is_banned() {
+        bucket.num_banned += 1;
+    }
+}
+
+#[cfg(test)]
+fn region_buckets(node_id: &NodeId, peers: &[Peer], connected: &[NodeId]) -> Vec<RegionBucket> {
+    let mut buckets = BTreeMap::new();
+    for peer in peers {
+        add_to_region_bucket(&mut buckets, node_id, peer, connected);
+    }
+    buckets.into_iter().map(|(_, bucket)| bucket).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+
+    use tari_comms::{net_address::MultiaddressesWithStats, peer_manager::PeerFlags};
+
+    use super::*;
+    use crate::test_utils::make_node_identity;
+
+    fn make_peer(node_id: NodeId) -> Peer {
+        Peer::new(
+            CommsPublicKey::default(),
+            node_id,
+            MultiaddressesWithStats::default(),
+            PeerFlags::empty(),
+            PeerFeatures::COMMUNICATION_NODE,
+            Default::default(),
+            Default::default(),
+        )
+    }
+
+    #[test]
+    fn it_groups_peers_into_buckets_by_distance() {
+        let node_id = NodeId::default();
+        let mut near = [0u8; 13];
+        near[12] = 1;
+        let mut far = [0u8; 13];
+        far[0] = 0x80;
+        let mut far2 = far;
+        far2[12] = 1;
+        let peers = vec![
+            make_peer(NodeId::try_from(&near[..]).unwrap()),
+            make_peer(NodeId::try_from(&far[..]).unwrap()),
+            make_peer(NodeId::try_from(&far2[..]).unwrap()),
+        ];
+        let connected = vec![peers[1].node_id.clone()];
+
+        let buckets = region_buckets(&node_id, &peers, &connected);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0], RegionBucket {
+            index: 0,
+            num_peers: 1,
+            num_connected: 0,
+            num_offline: 0,
+            num_banned: 0,
+        });
+        assert_eq!(buckets[1].num_peers, 2);
+        assert_eq!(buckets[1].num_connected, 1);
+        assert!(buckets[1].index > buckets[0].index);
+    }
+
+    #[test]
+    fn it_only_records_fan_out_selections() {
+        let log = PeerSelectionLog::default();
+        let node_id = make_node_identity().node_id().clone();
+        log.record(&BroadcastStrategy::DirectNodeId(Box::new(node_id.clone())), &[
+            node_id.clone()
+        ]);
+        assert!(log.records().is_empty());
+
+        for _ in 0..SELECTION_LOG_CAPACITY + 1 {
+            log.record(&BroadcastStrategy::Propagate(node_id.clone().into(), vec![]), &[
+                node_id.clone(),
+            ]);
+        }
+        let records = log.records();
+        assert_eq!(records.len(), SELECTION_LOG_CAPACITY);
+        assert_eq!(records[0].target, Some(node_id));
+    }
+}
@@ -36,6 +36,7 @@ mod error;
 pub use error::NetworkDiscoveryError;
 
 mod initializing;
+mod metrics;
 mod on_connect;
 mod ready;
 
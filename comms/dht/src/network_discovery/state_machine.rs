@@ -44,6 +44,7 @@ use crate::{
     network_discovery::{
         discovering::Discovering,
         initializing::Initializing,
+        metrics,
         on_connect::OnConnect,
         ready::DiscoveryReady,
         waiting::Waiting,
@@ -219,6 +220,13 @@ impl DhtNetworkDiscovery {
             },
             (State::Ready(_), StateEvent::OnConnectMode) => State::OnConnect(OnConnect::new(self.context.clone())),
             (State::Discovering(_), StateEvent::DiscoveryComplete(stats)) => {
+                metrics::num_rounds().inc();
+                if !stats.is_success() {
+                    metrics::num_failed_rounds().inc();
+                }
+                metrics::num_new_peers().inc_by(stats.num_new_peers as u64);
+                metrics::num_new_neighbours().inc_by(stats.num_new_neighbours as u64);
+                metrics::num_duplicate_peers().set(stats.num_duplicate_peers as i64);
                 if stats.has_new_peers() {
                     self.context
                         .publish_event(DhtEvent::NetworkDiscoveryPeersAdded(stats.clone()));
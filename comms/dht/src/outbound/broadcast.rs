@@ -58,9 +58,11 @@ use crate::{
         message_send_state::MessageSendState,
         SendMessageResponse,
     },
+    padding,
     proto::envelope::{DhtMessageType, OriginMac},
     version::DhtProtocolVersion,
     DhtConfig,
+    MessagePaddingConfig,
 };
 
 const LOG_TARGET: &str = "comms::dht::outbound::broadcast_middleware";
@@ -71,6 +73,7 @@ pub struct BroadcastLayer {
     node_identity: Arc<NodeIdentity>,
     message_validity_window: chrono::Duration,
     protocol_version: DhtProtocolVersion,
+    message_padding: Arc<MessagePaddingConfig>,
 }
 
 impl BroadcastLayer {
@@ -87,6 +90,7 @@ impl BroadcastLayer {
             message_validity_window: chrono::Duration::from_std(config.saf.msg_validity)
                 .expect("message_validity_window is too large"),
             protocol_version: config.protocol_version,
+            message_padding: Arc::new(config.message_padding.clone()),
         }
     }
 }
@@ -102,6 +106,7 @@ impl<S> Layer<S> for BroadcastLayer {
             self.dht_discovery_requester.clone(),
             self.message_validity_window,
             self.protocol_version,
+            self.message_padding.clone(),
         )
     }
 }
@@ -116,6 +121,7 @@ pub struct BroadcastMiddleware<S> {
     node_identity: Arc<NodeIdentity>,
     message_validity_window: chrono::Duration,
     protocol_version: DhtProtocolVersion,
+    message_padding: Arc<MessagePaddingConfig>,
 }
 
 impl<S> BroadcastMiddleware<S> {
@@ -126,6 +132,7 @@ impl<S> BroadcastMiddleware<S> {
         dht_discovery_requester: DhtDiscoveryRequester,
         message_validity_window: chrono::Duration,
         protocol_version: DhtProtocolVersion,
+        message_padding: Arc<MessagePaddingConfig>,
     ) -> Self {
         Self {
             next_service: service,
@@ -134,6 +141,7 @@ impl<S> BroadcastMiddleware<S> {
             node_identity,
             message_validity_window,
             protocol_version,
+            message_padding,
         }
     }
 }
@@ -161,6 +169,7 @@ where
                 msg,
                 self.message_validity_window,
                 self.protocol_version,
+                self.message_padding.clone(),
             )
             .handle(),
         )
@@ -175,6 +184,7 @@ struct BroadcastTask<S> {
     request: Option<DhtOutboundRequest>,
     message_validity_window: chrono::Duration,
     protocol_version: DhtProtocolVersion,
+    message_padding: Arc<MessagePaddingConfig>,
 }
 type FinalMessageParts = (Option<Arc<CommsPublicKey>>, Option<Bytes>, Bytes);
 
@@ -189,6 +199,7 @@ where S: Service<DhtOutboundMessage, Response = (), Error = PipelineError>
         request: DhtOutboundRequest,
         message_validity_window: chrono::Duration,
         protocol_version: DhtProtocolVersion,
+        message_padding: Arc<MessagePaddingConfig>,
     ) -> Self {
         Self {
             service,
@@ -198,6 +209,7 @@ where S: Service<DhtOutboundMessage, Response = (), Error = PipelineError>
             request: Some(request),
             message_validity_window,
             protocol_version,
+            message_padding,
         }
     }
 
@@ -412,7 +424,10 @@ where S: Service<DhtOutboundMessage, Response = (), Error = PipelineError>
         expires: Option<DateTime<Utc>>,
         tag: Option<MessageTag>,
     ) -> Result<(Vec<DhtOutboundMessage>, Vec<MessageSendState>), DhtOutboundError> {
-        let dht_flags = encryption.flags() | extra_flags;
+        let mut dht_flags = encryption.flags() | extra_flags;
+        if dht_flags.is_encrypted() && self.message_padding.enabled {
+            dht_flags |= DhtMessageFlags::PADDED;
+        }
         let expires_epochtime = expires.map(datetime_to_epochtime);
 
         let (ephemeral_public_key, origin_mac, body) = self.process_encryption(
@@ -496,8 +511,13 @@ where S: Service<DhtOutboundMessage, Response = (), Error = PipelineError>
                 // Generate ephemeral public/private key pair and ECDH shared secret
                 let (e_secret_key, e_public_key) = CommsPublicKey::random_keypair(&mut OsRng);
                 let shared_ephemeral_secret = crypt::generate_ecdh_secret(&e_secret_key, &**public_key);
-                // Encrypt the message with the body
-                let encrypted_body = crypt::encrypt(&shared_ephemeral_secret, &body);
+                // Encrypt the message with the body, padding it first if required
+                let encrypted_body = if flags.is_padded() {
+                    let padded_body = padding::pad_message(&body, &self.message_padding.bucket_sizes);
+                    crypt::encrypt(&shared_ephemeral_secret, &padded_body)
+                } else {
+                    crypt::encrypt(&shared_ephemeral_secret, &body)
+                };
 
                 let mac_challenge = crypt::create_origin_mac_challenge_parts(
                     self.protocol_version,
@@ -621,6 +641,7 @@ mod test {
             dht_discover_requester,
             chrono::Duration::seconds(10800),
             DhtProtocolVersion::latest(),
+            Default::default(),
         );
         assert_send_static_service(&service);
         let (reply_tx, _reply_rx) = oneshot::channel();
@@ -664,6 +685,7 @@ mod test {
             dht_discover_requester,
             chrono::Duration::seconds(10800),
             DhtProtocolVersion::latest(),
+            Default::default(),
         );
         let (reply_tx, reply_rx) = oneshot::channel();
 
@@ -712,6 +734,7 @@ mod test {
             dht_discover_requester,
             chrono::Duration::seconds(10800),
             DhtProtocolVersion::latest(),
+            Default::default(),
         );
         let (reply_tx, reply_rx) = oneshot::channel();
 
@@ -0,0 +1,118 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Padding of encrypted message bodies to fixed size buckets, so that the size of an encrypted message reveals as
+//! little as possible about its contents.
+//!
+//! A padded body is the length of the original body as a little-endian u32, followed by the body and zero bytes up to
+//! the bucket size. Padding is applied before encryption, so the padding bytes are not distinguishable from the body.
+
+use std::{convert::TryFrom, mem::size_of};
+
+use serde::{Deserialize, Serialize};
+
+const LENGTH_PREFIX_SIZE: usize = size_of::<u32>();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MessagePaddingConfig {
+    /// Set to true to pad the bodies of encrypted messages. Nodes that do not support padding reject padded messages,
+    /// so this should only be enabled once the network has upgraded.
+    /// Default: false
+    pub enabled: bool,
+    /// The sizes, in bytes, that encrypted message bodies are padded to. A body is padded to the smallest bucket that
+    /// can hold it, or to a multiple of the largest bucket if it does not fit in any bucket.
+    /// Default: [1024, 4096, 16384, 65536]
+    pub bucket_sizes: Vec<usize>,
+}
+
+impl Default for MessagePaddingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bucket_sizes: vec![1024, 4 * 1024, 16 * 1024, 64 * 1024],
+        }
+    }
+}
+
+/// Returns the padded size for a message of `len` bytes (including the length prefix)
+fn padded_size(len: usize, bucket_sizes: &[usize]) -> usize {
+    let largest = match bucket_sizes.iter().copied().filter(|s| *s > 0).max() {
+        Some(largest) => largest,
+        None => return len,
+    };
+    bucket_sizes
+        .iter()
+        .copied()
+        .filter(|s| *s >= len)
+        .min()
+        .unwrap_or_else(|| ((len + largest - 1) / largest) * largest)
+}
+
+/// Pads `body` to the smallest of `bucket_sizes` that can hold it and its length prefix.
+pub(crate) fn pad_message(body: &[u8], bucket_sizes: &[usize]) -> Vec<u8> {
+    let size = padded_size(LENGTH_PREFIX_SIZE + body.len(), bucket_sizes);
+    let mut padded = Vec::with_capacity(size);
+    // Bodies are limited by the maximum frame size, which is far smaller than u32::MAX
+    padded.extend_from_slice(&u32::try_from(body.len()).unwrap_or(u32::MAX).to_le_bytes());
+    padded.extend_from_slice(body);
+    padded.resize(size, 0);
+    padded
+}
+
+/// Removes the length prefix and padding added by [pad_message]. Returns None if `padded` is not a valid padded body.
+pub(crate) fn unpad_message(padded: &[u8]) -> Option<&[u8]> {
+    if padded.len() < LENGTH_PREFIX_SIZE {
+        return None;
+    }
+    let (len, rest) = padded.split_at(LENGTH_PREFIX_SIZE);
+    let mut len_bytes = [0u8; LENGTH_PREFIX_SIZE];
+    len_bytes.copy_from_slice(len);
+    let len = usize::try_from(u32::from_le_bytes(len_bytes)).ok()?;
+    rest.get(..len)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_pads_to_the_smallest_bucket() {
+        let buckets = [64, 256, 1024];
+        assert_eq!(pad_message(&[1u8; 10], &buckets).len(), 64);
+        assert_eq!(pad_message(&[1u8; 60], &buckets).len(), 64);
+        assert_eq!(pad_message(&[1u8; 61], &buckets).len(), 256);
+        assert_eq!(pad_message(&[1u8; 1021], &buckets).len(), 2048);
+        assert_eq!(pad_message(&[1u8; 10], &[]).len(), 14);
+    }
+
+    #[test]
+    fn it_unpads_a_padded_message() {
+        let body = b"the quick brown fox".to_vec();
+        let padded = pad_message(&body, &[128]);
+        assert_eq!(unpad_message(&padded).unwrap(), body.as_slice());
+
+        assert!(unpad_message(&[1, 0]).is_none());
+        assert!(unpad_message(&[5, 0, 0, 0, 1, 2]).is_none());
+        assert_eq!(unpad_message(&[0, 0, 0, 0, 0, 0]).unwrap(), &[] as &[u8]);
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Pluggable peer selection for messages that fan out to more than one peer, i.e. messages sent using the
+//! [Broadcast](crate::broadcast_strategy::BroadcastStrategy::Broadcast) and
+//! [Propagate](crate::broadcast_strategy::BroadcastStrategy::Propagate) broadcast strategies.
+//!
+//! The default [ClosestNeighbourPropagation] strategy is used unless another [PropagationStrategy] is given to
+//! [DhtBuilder::with_propagation_strategy](crate::DhtBuilder::with_propagation_strategy).
+
+use std::{fmt, sync::Arc};
+
+use log::*;
+use tari_comms::{
+    async_trait,
+    connectivity::{ConnectivityRequester, ConnectivitySelection},
+    peer_manager::{NodeId, NodeIdentity, PeerManager},
+};
+
+use crate::{actor::DhtActorError, envelope::NodeDestination, DhtConfig};
+
+const LOG_TARGET: &str = "comms::dht::propagation";
+
+/// The resources available to a [PropagationStrategy] when selecting peers.
+pub struct PeerSelectionContext {
+    config: Arc<DhtConfig>,
+    node_identity: Arc<NodeIdentity>,
+    peer_manager: Arc<PeerManager>,
+    connectivity: ConnectivityRequester,
+}
+
+impl PeerSelectionContext {
+    pub(crate) fn new(
+        config: Arc<DhtConfig>,
+        node_identity: Arc<NodeIdentity>,
+        peer_manager: Arc<PeerManager>,
+        connectivity: ConnectivityRequester,
+    ) -> Self {
+        Self {
+            config,
+            node_identity,
+            peer_manager,
+            connectivity,
+        }
+    }
+
+    pub fn config(&self) -> &DhtConfig {
+        &self.config
+    }
+
+    pub fn node_identity(&self) -> &NodeIdentity {
+        &self.node_identity
+    }
+
+    pub fn peer_manager(&self) -> &PeerManager {
+        &self.peer_manager
+    }
+
+    pub fn connectivity(&mut self) -> &mut ConnectivityRequester {
+        &mut self.connectivity
+    }
+}
+
+/// Selects the peers to which a message that fans out to more than one peer is sent.
+#[async_trait]
+pub trait PropagationStrategy: Send + Sync + 'static {
+    /// Selects the peers to which a message is broadcast. Peers in `exclude` must not be selected.
+    async fn select_broadcast_peers(
+        &self,
+        context: &mut PeerSelectionContext,
+        exclude: &[NodeId],
+    ) -> Result<Vec<NodeId>, DhtActorError>;
+
+    /// Selects the peers to which a message is propagated in order to reach `destination`. Peers in `exclude` must not
+    /// be selected.
+    async fn select_propagation_peers(
+        &self,
+        context: &mut PeerSelectionContext,
+        destination: &NodeDestination,
+        exclude: &[NodeId],
+    ) -> Result<Vec<NodeId>, DhtActorError>;
+}
+
+impl fmt::Debug for dyn PropagationStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("dyn PropagationStrategy")
+    }
+}
+
+/// The default propagation strategy. Broadcasts are sent to `broadcast_factor` random connected peers. Propagated
+/// messages are sent directly to the destination if it is connected, otherwise to the `propagation_factor` connected
+/// peers closest to the destination, preferring peers that are closer to the destination than this node.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClosestNeighbourPropagation;
+
+#[async_trait]
+impl PropagationStrategy for ClosestNeighbourPropagation {
+    async fn select_broadcast_peers(
+        &self,
+        context: &mut PeerSelectionContext,
+        exclude: &[NodeId],
+    ) -> Result<Vec<NodeId>, DhtActorError> {
+        let broadcast_factor = context.config().broadcast_factor;
+        let connections = context
+            .connectivity()
+            .select_connections(ConnectivitySelection::random_nodes(broadcast_factor, exclude.to_vec()))
+            .await?;
+
+        let candidates = connections
+            .iter()
+            .map(|c| c.peer_node_id())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if candidates.is_empty() {
+            warn!(
+                target: LOG_TARGET,
+                "Broadcast requested but there are no node peer connections available"
+            );
+        }
+        debug!(
+            target: LOG_TARGET,
+            "{} candidate(s) selected for broadcast",
+            candidates.len()
+        );
+
+        Ok(candidates)
+    }
+
+    async fn select_propagation_peers(
+        &self,
+        context: &mut PeerSelectionContext,
+        destination: &NodeDestination,
+        exclude: &[NodeId],
+    ) -> Result<Vec<NodeId>, DhtActorError> {
+        let num_neighbouring_nodes = context.config().num_neighbouring_nodes;
+        let propagation_factor = context.config().propagation_factor;
+        let dest_node_id = destination
+            .node_id()
+            .cloned()
+            .or_else(|| destination.public_key().map(NodeId::from_public_key));
+
+        let connections = match dest_node_id {
+            Some(node_id) => {
+                let dest_connection = context.connectivity().get_connection(node_id.clone()).await?;
+                // If the peer was added to the exclude list, we don't want to send directly to the peer.
+                // This ensures that we don't just send a message back to the peer that sent it.
+                let dest_connection = dest_connection.filter(|c| !exclude.contains(c.peer_node_id()));
+                match dest_connection {
+                    Some(conn) => {
+                        // We're connected to the destination, so send the message directly
+                        vec![conn]
+                    },
+                    None => {
+                        // Select connections closer to the destination
+                        let mut connections = context
+                            .connectivity()
+                            .select_connections(ConnectivitySelection::closest_to(
+                                node_id.clone(),
+                                num_neighbouring_nodes,
+                                exclude.to_vec(),
+                            ))
+                            .await?;
+
+                        // Exclude candidates that are further away from the destination than this node
+                        // unless this node has not selected a big enough sample i.e. this node is not well
+                        // connected
+                        if connections.len() >= propagation_factor {
+                            let dist_from_dest = context.node_identity().node_id().distance(&node_id);
+                            let before_len = connections.len();
+                            connections = connections
+                                .into_iter()
+                                .filter(|conn| conn.peer_node_id().distance(&node_id) <= dist_from_dest)
+                                .collect::<Vec<_>>();
+
+                            debug!(
+                                target: LOG_TARGET,
+                                "Filtered out {} node(s) that are further away than this node.",
+                                before_len - connections.len()
+                            );
+                        }
+
+                        connections.truncate(propagation_factor);
+                        connections
+                    },
+                }
+            },
+            None => {
+                debug!(
+                    target: LOG_TARGET,
+                    "No destination for propagation, sending to {} random peers", propagation_factor
+                );
+                context
+                    .connectivity()
+                    .select_connections(ConnectivitySelection::random_nodes(
+                        propagation_factor,
+                        exclude.to_vec(),
+                    ))
+                    .await?
+            },
+        };
+
+        if connections.is_empty() {
+            info!(
+                target: LOG_TARGET,
+                "Propagation requested but there are no node peer connections available"
+            );
+        }
+
+        let candidates = connections
+            .iter()
+            .map(|c| c.peer_node_id())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        debug!(
+            target: LOG_TARGET,
+            "{} candidate(s) selected for propagation to {}",
+            candidates.len(),
+            destination
+        );
+
+        trace!(
+            target: LOG_TARGET,
+            "(ThisNode = {}) Candidates are {}",
+            context.node_identity().node_id().short_str(),
+            candidates.iter().map(|n| n.short_str()).collect::<Vec<_>>().join(", ")
+        );
+
+        Ok(candidates)
+    }
+}
@@ -69,7 +69,11 @@ impl<T: AsRef<NodeIdentity>> From<T> for JoinMessage {
         let node_identity = identity.as_ref();
         Self {
             node_id: node_identity.node_id().to_vec(),
-            addresses: vec![node_identity.public_address().to_string()],
+            addresses: node_identity
+                .public_addresses()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
             peer_features: node_identity.features().bits(),
             nonce: OsRng.next_u64(),
             identity_signature: node_identity.identity_signature_read().as_ref().map(Into::into),
@@ -89,6 +93,18 @@ impl fmt::Display for dht::JoinMessage {
     }
 }
 
+impl fmt::Display for dht::MisbehaviourReportMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MisbehaviourReportMessage(PublicKey = {}, Reason = {}, BanDuration = {}s)",
+            self.public_key.to_hex(),
+            self.reason,
+            self.ban_duration,
+        )
+    }
+}
+
 //---------------------------------- Rpc Message Conversions --------------------------------------------//
 
 impl From<Peer> for rpc::Peer {
@@ -20,7 +20,11 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{cmp, sync::Arc};
+use std::{
+    cmp,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use log::*;
 use tari_comms::{
@@ -30,7 +34,10 @@ use tari_comms::{
     PeerManager,
 };
 use tari_utilities::ByteArray;
-use tokio::{sync::mpsc, task};
+use tokio::{
+    sync::{mpsc, RwLock},
+    task,
+};
 
 use crate::{
     proto::rpc::{GetCloserPeersRequest, GetPeersRequest, GetPeersResponse},
@@ -44,11 +51,40 @@ const MAX_EXCLUDED_PEERS: usize = 1000;
 
 pub struct DhtRpcServiceImpl {
     peer_manager: Arc<PeerManager>,
+    peer_list_cache: Option<PeerListCache>,
 }
 
 impl DhtRpcServiceImpl {
-    pub fn new(peer_manager: Arc<PeerManager>) -> Self {
-        Self { peer_manager }
+    pub fn new(peer_manager: Arc<PeerManager>, peer_list_cache_ttl: Option<Duration>) -> Self {
+        Self {
+            peer_manager,
+            peer_list_cache: peer_list_cache_ttl.map(PeerListCache::new),
+        }
+    }
+
+    /// Returns all non-banned peers, reading from the peer database at most once per cache TTL
+    async fn get_cached_peers(&self, cache: &PeerListCache) -> Result<Arc<Vec<Peer>>, RpcStatus> {
+        if let Some(peers) = cache.get().await {
+            return Ok(peers);
+        }
+
+        let mut entry = cache.entry.write().await;
+        // Another request may have refreshed the cache while we waited for the lock
+        if let Some((ts, ref peers)) = *entry {
+            if ts.elapsed() < cache.ttl {
+                return Ok(peers.clone());
+            }
+        }
+
+        let query = PeerQuery::new().select_where(|peer| !peer.is_banned());
+        let peers = Arc::new(self.peer_manager.perform_query(query).await.map_err(RpcError::from)?);
+        debug!(
+            target: LOG_TARGET,
+            "[get_peers] Refreshed peer list cache with {} peer(s)",
+            peers.len()
+        );
+        *entry = Some((Instant::now(), peers.clone()));
+        Ok(peers)
     }
 
     pub fn stream_peers(&self, peers: Vec<Peer>) -> Streaming<GetPeersResponse> {
@@ -144,22 +180,39 @@ impl DhtRpcService for DhtRpcServiceImpl {
         let message = request.message();
         let requester_node_id = request.context().peer_node_id();
 
-        let mut query = PeerQuery::new().select_where(|peer| {
+        let is_selected = |peer: &Peer| {
             &peer.node_id != requester_node_id &&
                 (message.include_clients || !peer.features.is_client()) &&
                 !peer.is_banned()
-        });
-
-        if message.n > 0 {
-            query = query.limit(message.n as usize);
-        }
+        };
 
-        // TODO: This result set can/will be large
-        //       Ideally, we'd need a lazy-loaded iterator, however that requires a long-lived read transaction and
-        //       the lifetime of that transaction is proportional on the time it takes to send the peers.
-        //       Either we should not need to return all peers, or we can find a way to do an iterator which does not
-        //       require a long-lived read transaction (we don't strictly care about read consistency in this case).
-        let peers = self.peer_manager.perform_query(query).await.map_err(RpcError::from)?;
+        let peers = match self.peer_list_cache {
+            Some(ref cache) => {
+                let limit = if message.n > 0 { message.n as usize } else { usize::MAX };
+                self.get_cached_peers(cache)
+                    .await?
+                    .iter()
+                    .filter(|peer| is_selected(*peer))
+                    .take(limit)
+                    .cloned()
+                    .collect()
+            },
+            None => {
+                let mut query = PeerQuery::new().select_where(is_selected);
+
+                if message.n > 0 {
+                    query = query.limit(message.n as usize);
+                }
+
+                // TODO: This result set can/will be large
+                //       Ideally, we'd need a lazy-loaded iterator, however that requires a long-lived read transaction
+                //       and the lifetime of that transaction is proportional on the time it takes to send the peers.
+                //       Either we should not need to return all peers, or we can find a way to do an iterator which
+                //       does not require a long-lived read transaction (we don't strictly care about read
+                //       consistency in this case).
+                self.peer_manager.perform_query(query).await.map_err(RpcError::from)?
+            },
+        };
 
         let node_id = request.context().peer_node_id();
         debug!(
@@ -177,3 +230,25 @@ impl DhtRpcService for DhtRpcServiceImpl {
         Ok(self.stream_peers(peers))
     }
 }
+
+/// A time-bounded cache of the full peer list
+struct PeerListCache {
+    ttl: Duration,
+    entry: RwLock<Option<(Instant, Arc<Vec<Peer>>)>>,
+}
+
+impl PeerListCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entry: RwLock::new(None),
+        }
+    }
+
+    async fn get(&self) -> Option<Arc<Vec<Peer>>> {
+        match *self.entry.read().await {
+            Some((ts, ref peers)) if ts.elapsed() < self.ttl => Some(peers.clone()),
+            _ => None,
+        }
+    }
+}
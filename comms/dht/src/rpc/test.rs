@@ -42,7 +42,7 @@ use crate::{
 fn setup() -> (DhtRpcServiceImpl, RpcRequestMock, Arc<PeerManager>) {
     let peer_manager = build_peer_manager();
     let mock = RpcRequestMock::new(peer_manager.clone());
-    let service = DhtRpcServiceImpl::new(peer_manager.clone());
+    let service = DhtRpcServiceImpl::new(peer_manager.clone(), None);
 
     (service, mock, peer_manager)
 }
@@ -104,6 +104,39 @@ mod get_closer_peers {
         }
     }
 
+    #[runtime::test]
+    async fn it_serves_peers_from_the_cache() {
+        let peer_manager = build_peer_manager();
+        let mock = RpcRequestMock::new(peer_manager.clone());
+        let service = DhtRpcServiceImpl::new(peer_manager.clone(), Some(Duration::from_secs(60)));
+
+        let nodes = build_many_node_identities(3, PeerFeatures::COMMUNICATION_NODE);
+        for peer in &nodes {
+            peer_manager.add_peer(peer.to_peer()).await.unwrap();
+        }
+        let req = GetPeersRequest {
+            n: 0,
+            include_clients: false,
+        };
+        let peers_stream = service
+            .get_peers(mock.request_with_context(Default::default(), req.clone()))
+            .await
+            .unwrap();
+        let results = peers_stream.collect::<Vec<_>>().await;
+        assert_eq!(results.len(), 3);
+
+        // Peers added after the cache was populated are not returned until the cache expires
+        let node = build_node_identity(PeerFeatures::COMMUNICATION_NODE);
+        peer_manager.add_peer(node.to_peer()).await.unwrap();
+        // The requesting peer is never returned to itself
+        let peers_stream = service
+            .get_peers(mock.request_with_context(nodes[0].node_id().clone(), req))
+            .await
+            .unwrap();
+        let results = peers_stream.collect::<Vec<_>>().await;
+        assert_eq!(results.len(), 2);
+    }
+
     #[runtime::test]
     async fn it_returns_n_peers() {
         let (service, mock, peer_manager) = setup();
@@ -34,6 +34,7 @@ table! {
         priority -> Integer,
         stored_at -> Timestamp,
         body_hash -> Text,
+        source_peer -> Nullable<Text>,
     }
 }
 
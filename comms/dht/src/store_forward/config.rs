@@ -31,9 +31,20 @@ pub struct SafConfig {
     /// The amount of time added to the current time will be used to check if the message has expired or not
     /// Default: 3 hours
     pub msg_validity: Duration,
-    /// The maximum number of messages that can be stored using the Store-and-forward middleware.
+    /// The maximum number of messages that can be stored using the Store-and-forward middleware. The oldest messages
+    /// are removed once this is exceeded.
     /// Default: 100,000
     pub msg_storage_capacity: usize,
+    /// The maximum total size in bytes of all stored messages. The oldest messages are removed once this is exceeded.
+    /// Default: 256 MiB
+    pub msg_storage_max_bytes: usize,
+    /// The maximum number of stored messages that may have been received from a single peer. Further messages from
+    /// that peer are not stored until some of its messages are removed.
+    /// Default: 1,000
+    pub peer_msg_storage_capacity: usize,
+    /// The maximum total size in bytes of stored messages that may have been received from a single peer.
+    /// Default: 16 MiB
+    pub peer_msg_storage_max_bytes: usize,
     /// A request to retrieve stored messages will be ignored if the requesting node is
     /// not within one of this nodes _n_ closest nodes.
     /// Default 8
@@ -65,6 +76,9 @@ impl Default for SafConfig {
             num_closest_nodes: 10,
             max_returned_messages: 50,
             msg_storage_capacity: 100_000,
+            msg_storage_max_bytes: 256 * 1024 * 1024,
+            peer_msg_storage_capacity: 1_000,
+            peer_msg_storage_max_bytes: 16 * 1024 * 1024,
             low_priority_msg_storage_ttl: Duration::from_secs(6 * 60 * 60), // 6 hours
             high_priority_msg_storage_ttl: Duration::from_secs(3 * 24 * 60 * 60), // 3 days
             auto_request: true,
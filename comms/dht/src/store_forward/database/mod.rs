@@ -22,7 +22,15 @@
 
 mod stored_message;
 use chrono::{DateTime, NaiveDateTime, Utc};
-use diesel::{dsl, result::DatabaseErrorKind, BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl};
+use diesel::{
+    dsl,
+    result::DatabaseErrorKind,
+    sql_types,
+    BoolExpressionMethods,
+    ExpressionMethods,
+    QueryDsl,
+    RunQueryDsl,
+};
 pub use stored_message::{NewStoredMessage, StoredMessage};
 use tari_comms::{peer_manager::NodeId, types::CommsPublicKey};
 use tari_utilities::hex::Hex;
@@ -34,6 +42,59 @@ use crate::{
     store_forward::message::StoredMessagePriority,
 };
 
+/// The number of messages held in store and forward storage and their total size in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageUsage {
+    pub num_messages: usize,
+    pub num_bytes: usize,
+}
+
+/// Storage used by messages received from a single peer
+#[derive(Debug, Clone)]
+pub struct SourcePeerUsage {
+    /// Hex node id of the peer that the messages were received from
+    pub source_peer: String,
+    pub usage: StorageUsage,
+}
+
+#[derive(QueryableByName)]
+struct StorageUsageRow {
+    #[sql_type = "sql_types::BigInt"]
+    num_messages: i64,
+    #[sql_type = "sql_types::BigInt"]
+    num_bytes: i64,
+}
+
+impl StorageUsageRow {
+    fn into_usage(self) -> StorageUsage {
+        StorageUsage {
+            num_messages: self.num_messages as usize,
+            num_bytes: self.num_bytes as usize,
+        }
+    }
+}
+
+#[derive(QueryableByName)]
+struct SourcePeerUsageRow {
+    #[sql_type = "sql_types::Text"]
+    source_peer: String,
+    #[sql_type = "sql_types::BigInt"]
+    num_messages: i64,
+    #[sql_type = "sql_types::BigInt"]
+    num_bytes: i64,
+}
+
+#[derive(QueryableByName)]
+struct MessageSizeRow {
+    #[sql_type = "sql_types::Integer"]
+    id: i32,
+    #[sql_type = "sql_types::BigInt"]
+    size: i64,
+}
+
+/// Deleting by id is done in batches to stay within SQLite's limit on the number of bound variables
+const DELETE_BATCH_SIZE: usize = 500;
+
 pub struct StoreAndForwardDatabase {
     connection: DbConnection,
 }
@@ -171,6 +232,67 @@ impl StoreAndForwardDatabase {
             .map_err(Into::into)
     }
 
+    pub fn count_messages(&self) -> Result<usize, StorageError> {
+        let conn = self.connection.get_pooled_connection()?;
+        let count = stored_messages::table
+            .select(dsl::count(stored_messages::id))
+            .first::<i64>(&conn)?;
+        Ok(count as usize)
+    }
+
+    /// Returns the number of stored messages and their total size in bytes
+    pub fn get_storage_usage(&self) -> Result<StorageUsage, StorageError> {
+        let conn = self.connection.get_pooled_connection()?;
+        let row = diesel::sql_query(
+            "SELECT COUNT(*) AS num_messages, COALESCE(SUM(LENGTH(header) + LENGTH(body)), 0) AS num_bytes FROM \
+             stored_messages",
+        )
+        .get_result::<StorageUsageRow>(&conn)?;
+        Ok(row.into_usage())
+    }
+
+    /// Returns the number and total size in bytes of stored messages received from the given peer
+    pub fn get_storage_usage_for_source_peer(&self, source_peer: &str) -> Result<StorageUsage, StorageError> {
+        let conn = self.connection.get_pooled_connection()?;
+        let row = diesel::sql_query(
+            "SELECT COUNT(*) AS num_messages, COALESCE(SUM(LENGTH(header) + LENGTH(body)), 0) AS num_bytes FROM \
+             stored_messages WHERE source_peer = $1",
+        )
+        .bind::<sql_types::Text, _>(source_peer)
+        .get_result::<StorageUsageRow>(&conn)?;
+        Ok(row.into_usage())
+    }
+
+    /// Returns the `limit` peers whose stored messages take up the most space
+    pub fn get_top_source_peers(&self, limit: usize) -> Result<Vec<SourcePeerUsage>, StorageError> {
+        let conn = self.connection.get_pooled_connection()?;
+        let rows = diesel::sql_query(
+            "SELECT source_peer, COUNT(*) AS num_messages, SUM(LENGTH(header) + LENGTH(body)) AS num_bytes FROM \
+             stored_messages WHERE source_peer IS NOT NULL GROUP BY source_peer ORDER BY num_bytes DESC LIMIT $1",
+        )
+        .bind::<sql_types::BigInt, _>(limit as i64)
+        .load::<SourcePeerUsageRow>(&conn)?;
+        Ok(rows
+            .into_iter()
+            .map(|row| SourcePeerUsage {
+                source_peer: row.source_peer,
+                usage: StorageUsage {
+                    num_messages: row.num_messages as usize,
+                    num_bytes: row.num_bytes as usize,
+                },
+            })
+            .collect())
+    }
+
+    /// Returns the time at which the oldest message was stored, or None if there are no stored messages
+    pub fn get_oldest_stored_at(&self) -> Result<Option<NaiveDateTime>, StorageError> {
+        let conn = self.connection.get_pooled_connection()?;
+        stored_messages::table
+            .select(dsl::min(stored_messages::stored_at))
+            .first::<Option<NaiveDateTime>>(&conn)
+            .map_err(Into::into)
+    }
+
     #[cfg(test)]
     pub(crate) fn get_all_messages(&self) -> Result<Vec<StoredMessage>, StorageError> {
         let conn = self.connection.get_pooled_connection()?;
@@ -220,6 +342,37 @@ impl StoreAndForwardDatabase {
         }
         Ok(num_removed)
     }
+
+    /// Removes the oldest messages until the total size of stored messages is at most `max_bytes`
+    pub(crate) fn truncate_messages_to_size(&self, max_bytes: usize) -> Result<usize, StorageError> {
+        let usage = self.get_storage_usage()?;
+        if usage.num_bytes <= max_bytes {
+            return Ok(0);
+        }
+
+        let conn = self.connection.get_pooled_connection()?;
+        let sizes = diesel::sql_query(
+            "SELECT id, LENGTH(header) + LENGTH(body) AS size FROM stored_messages ORDER BY stored_at ASC, id ASC",
+        )
+        .load::<MessageSizeRow>(&conn)?;
+        let mut excess = usage.num_bytes - max_bytes;
+        let mut message_ids = Vec::new();
+        for row in sizes {
+            if excess == 0 {
+                break;
+            }
+            message_ids.push(row.id);
+            excess = excess.saturating_sub(row.size as usize);
+        }
+
+        let mut num_removed = 0;
+        for ids in message_ids.chunks(DELETE_BATCH_SIZE) {
+            num_removed += diesel::delete(stored_messages::table)
+                .filter(stored_messages::id.eq_any(ids.to_vec()))
+                .execute(&conn)?;
+        }
+        Ok(num_removed)
+    }
 }
 
 #[cfg(test)]
@@ -245,6 +398,7 @@ mod test {
         db.insert_message_if_unique(msg3.clone()).unwrap();
         let messages = db.get_all_messages().unwrap();
         assert_eq!(messages.len(), 2);
+        assert_eq!(db.count_messages().unwrap(), 2);
         assert_eq!(messages[0].body_hash, msg1.body_hash);
         assert_eq!(messages[1].body_hash, msg2.body_hash);
     }
@@ -300,4 +454,56 @@ mod test {
         assert_eq!(messages[0].body_hash, msg3.body_hash);
         assert_eq!(messages[1].body_hash, msg4.body_hash);
     }
+
+    #[runtime::test]
+    async fn storage_usage() {
+        let conn = DbConnection::connect_memory(random::string(8)).unwrap();
+        conn.migrate().unwrap();
+        let db = StoreAndForwardDatabase::new(conn);
+        for (i, source_peer) in ["aa", "aa", "bb"].iter().enumerate() {
+            let mut msg = NewStoredMessage::default();
+            msg.body_hash.push_str(&i.to_string());
+            msg.body = vec![0u8; 10];
+            msg.source_peer = Some(source_peer.to_string());
+            db.insert_message_if_unique(msg).unwrap();
+        }
+
+        assert_eq!(db.get_storage_usage().unwrap(), StorageUsage {
+            num_messages: 3,
+            num_bytes: 30
+        });
+        assert_eq!(db.get_storage_usage_for_source_peer("aa").unwrap(), StorageUsage {
+            num_messages: 2,
+            num_bytes: 20
+        });
+        assert_eq!(
+            db.get_storage_usage_for_source_peer("cc").unwrap(),
+            StorageUsage::default()
+        );
+        let top_peers = db.get_top_source_peers(1).unwrap();
+        assert_eq!(top_peers.len(), 1);
+        assert_eq!(top_peers[0].source_peer, "aa");
+        assert!(db.get_oldest_stored_at().unwrap().is_some());
+    }
+
+    #[runtime::test]
+    async fn truncate_messages_to_size() {
+        let conn = DbConnection::connect_memory(random::string(8)).unwrap();
+        conn.migrate().unwrap();
+        let db = StoreAndForwardDatabase::new(conn);
+        for i in 0..4 {
+            let mut msg = NewStoredMessage::default();
+            msg.body_hash.push_str(&i.to_string());
+            msg.body = vec![0u8; 10];
+            db.insert_message_if_unique(msg).unwrap();
+        }
+
+        assert_eq!(db.truncate_messages_to_size(40).unwrap(), 0);
+        let num_removed = db.truncate_messages_to_size(25).unwrap();
+        assert_eq!(num_removed, 2);
+        let messages = db.get_all_messages().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].body_hash, "2");
+        assert_eq!(messages[1].body_hash, "3");
+    }
 }
@@ -47,6 +47,7 @@ pub struct NewStoredMessage {
     pub is_encrypted: bool,
     pub priority: i32,
     pub body_hash: String,
+    pub source_peer: Option<String>,
 }
 
 impl NewStoredMessage {
@@ -55,6 +56,7 @@ impl NewStoredMessage {
             authenticated_origin,
             decryption_result,
             dht_header,
+            source_peer,
             ..
         } = message;
 
@@ -78,6 +80,7 @@ impl NewStoredMessage {
             },
             body_hash,
             body,
+            source_peer: Some(source_peer.node_id.to_hex()),
         })
     }
 }
@@ -96,4 +99,5 @@ pub struct StoredMessage {
     pub priority: i32,
     pub stored_at: NaiveDateTime,
     pub body_hash: String,
+    pub source_peer: Option<String>,
 }
@@ -85,4 +85,6 @@ pub enum StoreAndForwardError {
     SafMessagesReceivedAfterDeadline { peer: NodeId, message_age: Duration },
     #[error("Invalid SAF request: `stored_at` cannot be in the future")]
     StoredAtWasInFuture,
+    #[error("Peer '{0}' has exceeded its store and forward storage quota")]
+    PeerStorageQuotaExceeded(String),
 }
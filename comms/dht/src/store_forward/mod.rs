@@ -25,10 +25,10 @@
 type SafResult<T> = Result<T, StoreAndForwardError>;
 
 mod service;
-pub use service::{StoreAndForwardRequest, StoreAndForwardRequester, StoreAndForwardService};
+pub use service::{SafStats, StoreAndForwardRequest, StoreAndForwardRequester, StoreAndForwardService};
 
 mod database;
-pub use database::StoredMessage;
+pub use database::{SourcePeerUsage, StorageUsage, StoredMessage};
 
 mod error;
 pub use error::StoreAndForwardError;
@@ -44,6 +44,7 @@ use crate::{
     envelope::{timestamp_to_datetime, DhtMessageFlags, DhtMessageHeader, NodeDestination},
     inbound::{DecryptedDhtMessage, DhtInboundMessage},
     outbound::{OutboundMessageRequester, SendMessageParams},
+    padding,
     proto::{
         envelope::{DhtMessageType, OriginMac},
         store_forward::{
@@ -574,8 +575,13 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError>
                 body.len()
             );
             let decrypted_bytes = crypt::decrypt(&shared_secret, body)?;
+            let decrypted_bytes = if header.flags.is_padded() {
+                padding::unpad_message(&decrypted_bytes).ok_or(StoreAndForwardError::DecryptionFailed)?
+            } else {
+                decrypted_bytes.as_slice()
+            };
             let envelope_body =
-                EnvelopeBody::decode(decrypted_bytes.as_slice()).map_err(|_| StoreAndForwardError::DecryptionFailed)?;
+                EnvelopeBody::decode(decrypted_bytes).map_err(|_| StoreAndForwardError::DecryptionFailed)?;
             if envelope_body.is_empty() {
                 return Err(StoreAndForwardError::InvalidEnvelopeBody);
             }
@@ -670,6 +676,7 @@ mod test {
             priority: StoredMessagePriority::High as i32,
             stored_at,
             body_hash: msg_hash,
+            source_peer: None,
         }
     }
 
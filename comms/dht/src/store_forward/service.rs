@@ -39,7 +39,7 @@ use tokio::{
 };
 
 use super::{
-    database::{NewStoredMessage, StoreAndForwardDatabase, StoredMessage},
+    database::{NewStoredMessage, SourcePeerUsage, StorageUsage, StoreAndForwardDatabase, StoredMessage},
     message::StoredMessagePriority,
     SafResult,
     StoreAndForwardError,
@@ -98,6 +98,21 @@ impl FetchStoredMessageQuery {
     }
 }
 
+/// Store and forward storage statistics
+#[derive(Debug, Clone, Default)]
+pub struct SafStats {
+    /// Messages currently held for other peers
+    pub usage: StorageUsage,
+    /// The configured limits on `usage`
+    pub limits: StorageUsage,
+    /// The configured limits on the messages received from a single peer
+    pub peer_limits: StorageUsage,
+    /// When the oldest stored message was stored
+    pub oldest_stored_at: Option<NaiveDateTime>,
+    /// The peers whose stored messages take up the most space, largest first
+    pub top_source_peers: Vec<SourcePeerUsage>,
+}
+
 /// Request types for the SAF actor.
 #[derive(Debug)]
 pub enum StoreAndForwardRequest {
@@ -108,6 +123,8 @@ pub enum StoreAndForwardRequest {
     SendStoreForwardRequestToPeer(NodeId),
     SendStoreForwardRequestNeighbours,
     MarkSafResponseReceived(NodeId, oneshot::Sender<Option<Duration>>),
+    CountMessages(oneshot::Sender<SafResult<usize>>),
+    GetStats(usize, oneshot::Sender<SafResult<SafStats>>),
 }
 
 /// Store and forward actor handle.
@@ -177,6 +194,26 @@ impl StoreAndForwardRequester {
         Ok(())
     }
 
+    /// Returns the number of messages stored for other peers in the local DB.
+    pub async fn count_stored_messages(&mut self) -> SafResult<usize> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.sender
+            .send(StoreAndForwardRequest::CountMessages(reply_tx))
+            .await
+            .map_err(|_| StoreAndForwardError::RequesterChannelClosed)?;
+        reply_rx.await.map_err(|_| StoreAndForwardError::RequestCancelled)?
+    }
+
+    /// Returns storage statistics, including the `num_top_peers` peers whose stored messages take up the most space.
+    pub async fn get_stats(&mut self, num_top_peers: usize) -> SafResult<SafStats> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.sender
+            .send(StoreAndForwardRequest::GetStats(num_top_peers, reply_tx))
+            .await
+            .map_err(|_| StoreAndForwardError::RequesterChannelClosed)?;
+        reply_rx.await.map_err(|_| StoreAndForwardError::RequestCancelled)?
+    }
+
     /// Updates internal SAF state that a SAF response has been received, removing it from the pending list.
     pub(crate) async fn mark_saf_response_received(&mut self, peer: NodeId) -> SafResult<Option<Duration>> {
         let (reply_tx, reply_rx) = oneshot::channel();
@@ -300,7 +337,7 @@ impl StoreAndForwardService {
             InsertMessage(msg, reply_tx) => {
                 let public_key = msg.destination_pubkey.clone();
                 let node_id = msg.destination_node_id.clone();
-                match self.database.insert_message_if_unique(msg) {
+                match self.insert_message(msg) {
                     Ok(existed) => {
                         let pub_key = public_key
                             .map(|p| format!("public key '{}'", p))
@@ -313,9 +350,13 @@ impl StoreAndForwardService {
                         }
                         let _result = reply_tx.send(Ok(existed));
                     },
+                    // Not a failure of this service, the store layer logs that the message was not stored
+                    Err(err @ StoreAndForwardError::PeerStorageQuotaExceeded(_)) => {
+                        let _result = reply_tx.send(Err(err));
+                    },
                     Err(err) => {
                         error!(target: LOG_TARGET, "InsertMessage failed because '{:?}'", err);
-                        let _result = reply_tx.send(Err(err.into()));
+                        let _result = reply_tx.send(Err(err));
                     },
                 }
             },
@@ -345,7 +386,62 @@ impl StoreAndForwardService {
             MarkSafResponseReceived(peer, reply) => {
                 let _ = reply.send(self.local_state.mark_infight_response_received(peer));
             },
+            CountMessages(reply_tx) => {
+                let _result = reply_tx.send(self.database.count_messages().map_err(Into::into));
+            },
+            GetStats(num_top_peers, reply_tx) => {
+                let _result = reply_tx.send(self.get_stats(num_top_peers));
+            },
+        }
+    }
+
+    /// Stores the message if the peer it was received from is within its quota. If storing the message exceeds the
+    /// overall storage limits, the oldest messages are removed.
+    fn insert_message(&mut self, message: NewStoredMessage) -> SafResult<bool> {
+        if let Some(source_peer) = message.source_peer.as_deref() {
+            let usage = self.database.get_storage_usage_for_source_peer(source_peer)?;
+            let message_size = message.header.len() + message.body.len();
+            if usage.num_messages >= self.config.peer_msg_storage_capacity ||
+                usage.num_bytes + message_size > self.config.peer_msg_storage_max_bytes
+            {
+                return Err(StoreAndForwardError::PeerStorageQuotaExceeded(source_peer.to_string()));
+            }
         }
+
+        let existed = self.database.insert_message_if_unique(message)?;
+        if !existed {
+            self.truncate_to_storage_limits()?;
+        }
+        Ok(existed)
+    }
+
+    fn truncate_to_storage_limits(&mut self) -> SafResult<()> {
+        let num_removed = self.database.truncate_messages(self.config.msg_storage_capacity)? +
+            self.database
+                .truncate_messages_to_size(self.config.msg_storage_max_bytes)?;
+        if num_removed > 0 {
+            debug!(
+                target: LOG_TARGET,
+                "Storage limits exceeded, removing {} oldest messages", num_removed
+            );
+        }
+        Ok(())
+    }
+
+    fn get_stats(&self, num_top_peers: usize) -> SafResult<SafStats> {
+        Ok(SafStats {
+            usage: self.database.get_storage_usage()?,
+            limits: StorageUsage {
+                num_messages: self.config.msg_storage_capacity,
+                num_bytes: self.config.msg_storage_max_bytes,
+            },
+            peer_limits: StorageUsage {
+                num_messages: self.config.peer_msg_storage_capacity,
+                num_bytes: self.config.peer_msg_storage_max_bytes,
+            },
+            oldest_stored_at: self.database.get_oldest_stored_at()?,
+            top_source_peers: self.database.get_top_source_peers(num_top_peers)?,
+        })
     }
 
     async fn handle_connectivity_event(&mut self, event: &ConnectivityEvent) -> SafResult<()> {
@@ -506,15 +602,7 @@ impl StoreAndForwardService {
         )?;
         debug!(target: LOG_TARGET, "Cleaned {} old high priority messages", num_removed);
 
-        let num_removed = self.database.truncate_messages(self.config.msg_storage_capacity)?;
-        if num_removed > 0 {
-            debug!(
-                target: LOG_TARGET,
-                "Storage limits exceeded, removing {} oldest messages", num_removed
-            );
-        }
-
-        Ok(())
+        self.truncate_to_storage_limits()
     }
 
     fn publish_event(&mut self, event: DhtEvent) {
@@ -206,9 +206,20 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError> + Se
 
         message.set_saf_stored(false);
         if let Some(priority) = self.get_storage_priority(&message).await? {
-            message.set_saf_stored(true);
-            let existing = self.store(priority, message.clone()).await?;
-            message.set_already_forwarded(existing);
+            match self.store(priority, message.clone()).await {
+                Ok(existing) => {
+                    message.set_saf_stored(true);
+                    message.set_already_forwarded(existing);
+                },
+                // The message is still processed, it just isn't kept for the destination
+                Err(err @ StoreAndForwardError::PeerStorageQuotaExceeded(_)) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Not storing message {} (Trace: {}): {}", message.tag, message.dht_header.message_tag, err
+                    );
+                },
+                Err(err) => return Err(err.into()),
+            }
         }
 
         trace!(
@@ -258,6 +269,11 @@ where S: Service<DecryptedDhtMessage, Response = (), Error = PipelineError> + Se
             return Ok(None);
         }
 
+        if message.dht_header.message_type.is_misbehaviour_report() {
+            log_not_eligible("it is a misbehaviour report");
+            return Ok(None);
+        }
+
         if message
             .authenticated_origin()
             .map(|pk| pk == self.node_identity.public_key())
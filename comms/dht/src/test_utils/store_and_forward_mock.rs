@@ -36,7 +36,7 @@ use tokio::{
     sync::{mpsc, RwLock},
 };
 
-use crate::store_forward::{StoreAndForwardRequest, StoreAndForwardRequester, StoredMessage};
+use crate::store_forward::{SafStats, StorageUsage, StoreAndForwardRequest, StoreAndForwardRequester, StoredMessage};
 
 const LOG_TARGET: &str = "comms::dht::discovery_mock";
 
@@ -148,6 +148,7 @@ impl StoreAndForwardMock {
                     priority: msg.priority,
                     stored_at: Utc::now().naive_utc(),
                     body_hash: msg.body_hash,
+                    source_peer: msg.source_peer,
                 });
                 reply_tx.send(Ok(false)).unwrap();
             },
@@ -168,6 +169,20 @@ impl StoreAndForwardMock {
             MarkSafResponseReceived(_, reply) => {
                 let _ = reply.send(*self.state.inflight_request.read().await);
             },
+            CountMessages(reply_tx) => {
+                let _result = reply_tx.send(Ok(self.state.stored_messages.read().await.len()));
+            },
+            GetStats(_, reply_tx) => {
+                let msgs = self.state.stored_messages.read().await;
+                let _result = reply_tx.send(Ok(SafStats {
+                    usage: StorageUsage {
+                        num_messages: msgs.len(),
+                        num_bytes: msgs.iter().map(|m| m.header.len() + m.body.len()).sum(),
+                    },
+                    oldest_stored_at: msgs.iter().map(|m| m.stored_at).min(),
+                    ..Default::default()
+                }));
+            },
         }
     }
 }
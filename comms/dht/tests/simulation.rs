@@ -0,0 +1,372 @@
+// Copyright 2022, The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Deterministic network simulation tests. Nodes communicate over a `SimulatedNetwork` with scripted latencies and
+//! partitions, and the tokio clock is paused so that all timing is virtual and reproducible.
+
+use std::{sync::Arc, time::Duration};
+
+use rand::rngs::OsRng;
+use tari_comms::{
+    backoff::ConstantBackoff,
+    multiaddr::Multiaddr,
+    peer_manager::{NodeIdentity, Peer, PeerFeatures},
+    pipeline,
+    pipeline::SinkService,
+    protocol::messaging::MessagingProtocolExtension,
+    test_utils::simulation::SimulatedNetwork,
+    transports::MemoryTransport,
+    types::CommsDatabase,
+    CommsBuilder,
+    CommsNode,
+};
+use tari_comms_dht::{
+    domain_message::OutboundDomainMessage,
+    inbound::DecryptedDhtMessage,
+    outbound::OutboundEncryption,
+    DbConnectionUrl,
+    Dht,
+    DhtConfig,
+};
+use tari_shutdown::Shutdown;
+use tari_storage::{
+    lmdb_store::{LMDBBuilder, LMDBConfig},
+    LMDBWrapper,
+};
+use tari_test_utils::{paths::create_temporary_data_path, random};
+use tokio::{
+    sync::{broadcast, mpsc},
+    time,
+    time::Instant,
+};
+use tower::ServiceBuilder;
+
+/// The shape of the initial peer connections in a simulation
+#[derive(Debug, Clone, Copy)]
+enum Topology {
+    /// Each node is linked to the next
+    Line,
+    /// A line where the last node is linked to the first
+    Ring,
+    /// Every node is linked to the first node
+    Star,
+    /// Every node is linked to every other node
+    FullMesh,
+}
+
+impl Topology {
+    fn links(self, num_nodes: usize) -> Vec<(usize, usize)> {
+        match self {
+            Topology::Line => (1..num_nodes).map(|i| (i - 1, i)).collect(),
+            Topology::Ring => {
+                let mut links = Topology::Line.links(num_nodes);
+                if num_nodes > 2 {
+                    links.push((num_nodes - 1, 0));
+                }
+                links
+            },
+            Topology::Star => (1..num_nodes).map(|i| (0, i)).collect(),
+            Topology::FullMesh => (0..num_nodes)
+                .flat_map(|i| (i + 1..num_nodes).map(move |j| (i, j)))
+                .collect(),
+        }
+    }
+}
+
+struct SimNode {
+    comms: CommsNode,
+    dht: Dht,
+    inbound_messages: mpsc::Receiver<DecryptedDhtMessage>,
+    shutdown: Shutdown,
+}
+
+impl SimNode {
+    fn node_identity(&self) -> Arc<NodeIdentity> {
+        self.comms.node_identity()
+    }
+
+    fn address(&self) -> Multiaddr {
+        self.comms.node_identity().public_address()
+    }
+
+    async fn next_inbound_message(&mut self, timeout: Duration) -> Option<DecryptedDhtMessage> {
+        time::timeout(timeout, self.inbound_messages.recv()).await.ok()?
+    }
+}
+
+/// A set of in-process DHT nodes connected over a simulated network
+struct Simulation {
+    network: SimulatedNetwork,
+    links: Vec<(usize, usize)>,
+    nodes: Vec<SimNode>,
+}
+
+impl Simulation {
+    /// Creates `num_nodes` nodes where each node knows the peers it is linked to in the given topology
+    async fn new(num_nodes: usize, topology: Topology, config: DhtConfig) -> Self {
+        let network = SimulatedNetwork::new();
+        let identities = (0..num_nodes).map(|_| make_node_identity()).collect::<Vec<_>>();
+        let links = topology.links(num_nodes);
+
+        let mut nodes = Vec::with_capacity(num_nodes);
+        for (i, node_identity) in identities.iter().enumerate() {
+            let known_peers = links
+                .iter()
+                .filter_map(|(a, b)| {
+                    if *a == i {
+                        Some(identities[*b].to_peer())
+                    } else if *b == i {
+                        Some(identities[*a].to_peer())
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            nodes.push(make_node(&network, node_identity.clone(), config.clone(), known_peers).await);
+        }
+
+        Self { network, links, nodes }
+    }
+
+    /// Establishes connections along all topology links
+    async fn connect_links(&self) {
+        for (a, b) in &self.links {
+            self.nodes[*a]
+                .comms
+                .connectivity()
+                .dial_peer(self.nodes[*b].node_identity().node_id().clone())
+                .await
+                .unwrap();
+        }
+    }
+
+    fn set_latency(&self, latency: Duration) {
+        self.network.set_default_latency(latency);
+    }
+
+    /// Partitions the network into groups of node indexes
+    fn partition(&self, groups: &[&[usize]]) {
+        let groups = groups
+            .iter()
+            .map(|group| group.iter().map(|i| self.nodes[*i].address()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let groups = groups.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        self.network.partition(&groups);
+    }
+
+    fn heal(&self) {
+        self.network.heal();
+    }
+
+    /// Propagates a message from node `from` to node `to`
+    async fn propagate(&self, from: usize, to: usize, msg: TestMessage) {
+        let dest = self.nodes[to].node_identity();
+        self.nodes[from]
+            .dht
+            .outbound_requester()
+            .propagate(
+                dest.node_id().clone().into(),
+                OutboundEncryption::encrypt_for(dest.public_key().clone()),
+                vec![],
+                OutboundDomainMessage::new(&123, msg),
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn shutdown(self) {
+        for mut node in self.nodes {
+            node.shutdown.trigger();
+            node.comms.wait_until_shutdown().await;
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct TestMessage {
+    #[prost(string, tag = "1")]
+    text: String,
+}
+
+impl TestMessage {
+    fn new(text: &str) -> Self {
+        Self { text: text.to_string() }
+    }
+}
+
+fn decode_test_message(msg: DecryptedDhtMessage) -> TestMessage {
+    assert!(msg.decryption_succeeded());
+    msg.decryption_result
+        .unwrap()
+        .decode_part::<TestMessage>(1)
+        .unwrap()
+        .unwrap()
+}
+
+fn make_node_identity() -> Arc<NodeIdentity> {
+    let port = MemoryTransport::acquire_next_memsocket_port();
+    Arc::new(NodeIdentity::random(
+        &mut OsRng,
+        format!("/memory/{}", port).parse().unwrap(),
+        PeerFeatures::COMMUNICATION_NODE,
+    ))
+}
+
+fn create_peer_storage() -> CommsDatabase {
+    let database_name = random::string(8);
+    let datastore = LMDBBuilder::new()
+        .set_path(create_temporary_data_path())
+        .set_env_config(LMDBConfig::default())
+        .set_max_number_of_databases(1)
+        .add_database(&database_name, lmdb_zero::db::CREATE)
+        .build()
+        .unwrap();
+
+    let peer_database = datastore.get_handle(&database_name).unwrap();
+    LMDBWrapper::new(Arc::new(peer_database))
+}
+
+async fn make_node(
+    network: &SimulatedNetwork,
+    node_identity: Arc<NodeIdentity>,
+    dht_config: DhtConfig,
+    known_peers: Vec<Peer>,
+) -> SimNode {
+    let shutdown = Shutdown::new();
+    let (inbound_tx, inbound_messages) = mpsc::channel(10);
+    let (outbound_tx, outbound_rx) = mpsc::channel(10);
+    let transport = network.transport(&node_identity.public_address());
+
+    let comms = CommsBuilder::new()
+        .allow_test_addresses()
+        .with_listener_address(node_identity.public_address())
+        .with_shutdown_signal(shutdown.to_signal())
+        .with_node_identity(node_identity)
+        .with_peer_storage(create_peer_storage(), None)
+        .with_min_connectivity(1)
+        .with_dial_backoff(ConstantBackoff::new(Duration::from_millis(100)))
+        .build()
+        .unwrap();
+
+    let dht = Dht::builder()
+        .with_config(dht_config)
+        .with_database_url(DbConnectionUrl::MemoryShared(random::string(8)))
+        .with_outbound_sender(outbound_tx)
+        .build(
+            comms.node_identity(),
+            comms.peer_manager(),
+            comms.connectivity(),
+            comms.shutdown_signal(),
+        )
+        .await
+        .unwrap();
+
+    for peer in known_peers {
+        comms.peer_manager().add_peer(peer).await.unwrap();
+    }
+
+    let dht_outbound_layer = dht.outbound_middleware_layer();
+    let pipeline = pipeline::Builder::new()
+        .outbound_buffer_size(10)
+        .with_outbound_pipeline(outbound_rx, |sink| {
+            ServiceBuilder::new().layer(dht_outbound_layer).service(sink)
+        })
+        .max_concurrent_inbound_tasks(10)
+        .with_inbound_pipeline(
+            ServiceBuilder::new()
+                .layer(dht.inbound_middleware_layer())
+                .service(SinkService::new(inbound_tx)),
+        )
+        .build();
+
+    let (event_tx, _) = broadcast::channel(100);
+    let comms = comms
+        .add_protocol_extension(MessagingProtocolExtension::new(event_tx, pipeline))
+        .spawn_with_transport(transport)
+        .await
+        .unwrap();
+
+    SimNode {
+        comms,
+        dht,
+        inbound_messages,
+        shutdown,
+    }
+}
+
+fn dht_config() -> DhtConfig {
+    let mut config = DhtConfig::default_local_test();
+    config.saf.auto_request = false;
+    config.discovery_request_timeout = Duration::from_secs(60);
+    config
+}
+
+#[tokio::test(start_paused = true)]
+async fn propagation_is_delayed_by_link_latency() {
+    let mut sim = Simulation::new(5, Topology::Line, dht_config()).await;
+    sim.connect_links().await;
+    sim.set_latency(Duration::from_millis(50));
+
+    let start = Instant::now();
+    sim.propagate(0, 4, TestMessage::new("hello")).await;
+    let msg = sim.nodes[4]
+        .next_inbound_message(Duration::from_secs(10))
+        .await
+        .expect("message did not reach the end of the line");
+    assert_eq!(decode_test_message(msg).text, "hello");
+    // The message must have crossed 4 links
+    assert!(start.elapsed() >= Duration::from_millis(4 * 50));
+
+    sim.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn partition_prevents_propagation_until_healed() {
+    let mut sim = Simulation::new(3, Topology::Line, dht_config()).await;
+    sim.connect_links().await;
+
+    sim.partition(&[&[0, 1], &[2]]);
+    sim.propagate(0, 2, TestMessage::new("lost")).await;
+    assert!(sim.nodes[2]
+        .next_inbound_message(Duration::from_secs(30))
+        .await
+        .is_none());
+
+    sim.heal();
+    sim.connect_links().await;
+    sim.propagate(0, 2, TestMessage::new("found")).await;
+    let msg = sim.nodes[2]
+        .next_inbound_message(Duration::from_secs(30))
+        .await
+        .expect("message did not arrive after the partition healed");
+    assert_eq!(decode_test_message(msg).text, "found");
+
+    sim.shutdown().await;
+}
+
+#[test]
+fn topology_links() {
+    assert_eq!(Topology::Line.links(3), vec![(0, 1), (1, 2)]);
+    assert_eq!(Topology::Ring.links(3), vec![(0, 1), (1, 2), (2, 0)]);
+    assert_eq!(Topology::Star.links(3), vec![(0, 1), (0, 2)]);
+    assert_eq!(Topology::FullMesh.links(3), vec![(0, 1), (0, 2), (1, 2)]);
+}
@@ -27,7 +27,7 @@ use thiserror::Error;
 use tokio::sync::mpsc::error::SendError;
 
 use crate::{
-    models::{HotStuffMessage, ModelError, TariDanPayload},
+    models::{HotStuffMessage, ModelError, TariDanPayload, TemplateId},
     services::ValidatorNodeClientError,
     storage::StorageError,
     workers::StateSyncError,
@@ -41,6 +41,8 @@ pub enum DigitalAssetError {
     _MissingArgument { argument_name: String, position: usize },
     #[error("Invalid sig, TODO: fill in deets")]
     InvalidSignature,
+    #[error("Signing service unavailable: {0}")]
+    SigningServiceUnavailable(String),
     #[error("Peer sent an invalid message: {0}")]
     InvalidPeerMessage(String),
     #[error("Storage error: {0}")]
@@ -93,6 +95,18 @@ pub enum DigitalAssetError {
     PreparePhaseNodeNotSafe,
     #[error("Unsupported template method {name}")]
     TemplateUnsupportedMethod { name: String },
+    #[error("Template {template_id} is not registered")]
+    TemplateNotRegistered { template_id: TemplateId },
+    #[error(
+        "Template {template_id} version {version} is not supported. Supported versions are {min_supported_version} to \
+         {max_supported_version}"
+    )]
+    UnsupportedTemplateVersion {
+        template_id: TemplateId,
+        version: u32,
+        min_supported_version: u32,
+        max_supported_version: u32,
+    },
     #[error("Connection error: {0}")]
     GrpcConnection(#[from] tonic::transport::Error),
     #[error("GRPC error: {0}")]
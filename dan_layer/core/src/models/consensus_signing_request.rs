@@ -0,0 +1,100 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use digest::Digest;
+use tari_common_types::types::PublicKey;
+use tari_crypto::{common::Blake256, tari_utilities::ByteArray};
+
+use crate::models::{HotStuffMessageType, TreeNodeHash, ViewId};
+
+/// Domain separation tag of consensus signature challenges. The version must be incremented if the challenge changes.
+const CONSENSUS_SIGNATURE_DOMAIN: &[u8] = b"com.tari.dan.consensus.signature.v1";
+
+/// The fields of a HotStuff message that are covered by a validator's consensus signature. Signing services are given
+/// this request rather than a raw challenge so that they only ever sign consensus messages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsensusSigningRequest {
+    pub message_type: HotStuffMessageType,
+    pub view_number: ViewId,
+    pub asset_public_key: PublicKey,
+    pub node_hash: TreeNodeHash,
+}
+
+impl ConsensusSigningRequest {
+    /// Returns true if this is a message type that a replica votes on
+    pub fn is_vote(&self) -> bool {
+        matches!(
+            self.message_type,
+            HotStuffMessageType::Prepare | HotStuffMessageType::PreCommit | HotStuffMessageType::Commit
+        )
+    }
+
+    /// The domain separated challenge that is signed
+    pub fn challenge(&self) -> Vec<u8> {
+        Blake256::new()
+            .chain(CONSENSUS_SIGNATURE_DOMAIN)
+            .chain(&[self.message_type.as_u8()])
+            .chain(self.view_number.as_u64().to_le_bytes())
+            .chain(self.asset_public_key.as_bytes())
+            .chain(self.node_hash.as_bytes())
+            .finalize()
+            .to_vec()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn request() -> ConsensusSigningRequest {
+        ConsensusSigningRequest {
+            message_type: HotStuffMessageType::Prepare,
+            view_number: ViewId(1),
+            asset_public_key: PublicKey::default(),
+            node_hash: TreeNodeHash::zero(),
+        }
+    }
+
+    #[test]
+    fn it_commits_to_every_field() {
+        let challenge = request().challenge();
+        let mut other = request();
+        other.message_type = HotStuffMessageType::Commit;
+        assert_ne!(other.challenge(), challenge);
+        let mut other = request();
+        other.view_number = ViewId(2);
+        assert_ne!(other.challenge(), challenge);
+        let mut other = request();
+        other.node_hash = TreeNodeHash::from([1u8; 32]);
+        assert_ne!(other.challenge(), challenge);
+    }
+
+    #[test]
+    fn it_only_treats_votes_as_votes() {
+        assert!(request().is_vote());
+        let mut other = request();
+        other.message_type = HotStuffMessageType::NewView;
+        assert!(!other.is_vote());
+        other.message_type = HotStuffMessageType::Genesis;
+        assert!(!other.is_vote());
+    }
+}
@@ -20,11 +20,10 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use digest::Digest;
 use tari_common_types::types::PublicKey;
-use tari_crypto::common::Blake256;
 
 use crate::models::{
+    ConsensusSigningRequest,
     HotStuffMessageType,
     HotStuffTreeNode,
     Payload,
@@ -182,17 +181,23 @@ impl<TPayload: Payload> HotStuffMessage<TPayload> {
         }
     }
 
-    pub fn create_signature_challenge(&self) -> Vec<u8> {
-        let mut b = Blake256::new()
-            .chain(&[self.message_type.as_u8()])
-            .chain(self.view_number.as_u64().to_le_bytes());
-        if let Some(ref node) = self.node {
-            b = b.chain(node.calculate_hash().as_bytes());
-        } else if let Some(ref node_hash) = self.node_hash {
-            b = b.chain(node_hash.as_bytes());
-        } else {
+    /// The fields of this message that are covered by the sender's signature
+    pub fn signing_request(&self) -> ConsensusSigningRequest {
+        let node_hash = match (&self.node, &self.node_hash) {
+            (Some(node), _) => node.calculate_hash(),
+            (None, Some(node_hash)) => *node_hash,
+            (None, None) => TreeNodeHash::zero(),
+        };
+        ConsensusSigningRequest {
+            message_type: self.message_type,
+            view_number: self.view_number,
+            asset_public_key: self.asset_public_key.clone(),
+            node_hash,
         }
-        b.finalize().to_vec()
+    }
+
+    pub fn create_signature_challenge(&self) -> Vec<u8> {
+        self.signing_request().challenge()
     }
 
     pub fn view_number(&self) -> ViewId {
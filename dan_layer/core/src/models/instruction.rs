@@ -26,11 +26,15 @@ use digest::Digest;
 use tari_crypto::common::Blake256;
 use tari_utilities::hex::Hex;
 
-use crate::{fixed_hash::FixedHash, models::TemplateId};
+use crate::{fixed_hash::FixedHash, models::TemplateId, templates::INITIAL_TEMPLATE_VERSION};
+
+/// Prefixes the hash of instructions for versions of a template after the first
+const VERSIONED_INSTRUCTION_HASH_DOMAIN: &[u8] = b"tari.dan.instruction.versioned";
 
 #[derive(Clone, Debug)]
 pub struct Instruction {
     template_id: TemplateId,
+    template_version: u32,
     method: String,
     args: Vec<u8>,
     // from: TokenId,
@@ -47,6 +51,7 @@ impl PartialEq for Instruction {
 impl Instruction {
     pub fn new(
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
         /* from: TokenId,
@@ -54,6 +59,7 @@ impl Instruction {
     ) -> Self {
         let mut s = Self {
             template_id,
+            template_version,
             method,
             args,
             // from,
@@ -69,6 +75,11 @@ impl Instruction {
         self.template_id
     }
 
+    /// The version of the template that this instruction targets
+    pub fn template_version(&self) -> u32 {
+        self.template_version
+    }
+
     pub fn method(&self) -> &str {
         &self.method
     }
@@ -90,8 +101,19 @@ impl Instruction {
         &self.hash
     }
 
+    /// Instructions for the first version of a template are hashed as they were before templates were versioned, so
+    /// that the hashes of existing instructions do not change. Later versions include the template and its version in
+    /// the hash.
     pub fn calculate_hash(&self) -> FixedHash {
-        let b = Blake256::new().chain(self.method.as_bytes()).chain(&self.args);
+        let b = if self.template_version == INITIAL_TEMPLATE_VERSION {
+            Blake256::new()
+        } else {
+            Blake256::new()
+                .chain(VERSIONED_INSTRUCTION_HASH_DOMAIN)
+                .chain((self.template_id as u32).to_le_bytes())
+                .chain(self.template_version.to_le_bytes())
+        };
+        let b = b.chain(self.method.as_bytes()).chain(&self.args);
         // b.chain(self.from.as_bytes())
         //     .chain(com_sig_to_bytes(&self.signature))
         b.finalize().into()
@@ -102,11 +124,40 @@ impl Display for Instruction {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Method: {}, Hash: {}, Args: {} bytes, Template: {}",
+            "Method: {}, Hash: {}, Args: {} bytes, Template: {} v{}",
             self.method,
             self.hash.to_hex(),
             self.args.len(),
-            self.template_id
+            self.template_id,
+            self.template_version
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_keeps_the_unversioned_hash_for_the_initial_template_version() {
+        let instruction = Instruction::new(
+            TemplateId::Tip002,
+            INITIAL_TEMPLATE_VERSION,
+            "transfer".to_string(),
+            vec![1, 2, 3],
+        );
+        let expected: FixedHash = Blake256::new().chain(b"transfer").chain(&[1u8, 2, 3]).finalize().into();
+        assert_eq!(*instruction.hash(), expected);
+    }
+
+    #[test]
+    fn it_includes_the_template_version_in_the_hash() {
+        let v1 = Instruction::new(TemplateId::Tip002, 1, "transfer".to_string(), vec![1, 2, 3]);
+        let v2 = Instruction::new(TemplateId::Tip002, 2, "transfer".to_string(), vec![1, 2, 3]);
+        let v3 = Instruction::new(TemplateId::Tip002, 3, "transfer".to_string(), vec![1, 2, 3]);
+        let other_template = Instruction::new(TemplateId::Tip004, 2, "transfer".to_string(), vec![1, 2, 3]);
+        assert_ne!(v1.hash(), v2.hash());
+        assert_ne!(v2.hash(), v3.hash());
+        assert_ne!(v2.hash(), other_template.hash());
+    }
+}
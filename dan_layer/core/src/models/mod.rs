@@ -31,6 +31,7 @@ mod asset_definition;
 mod base_layer_metadata;
 mod base_layer_output;
 mod committee;
+mod consensus_signing_request;
 pub mod domain_events;
 mod error;
 mod hot_stuff_message;
@@ -53,6 +54,7 @@ pub use asset_definition::{AssetDefinition, InitialState, KeyValue, SchemaState}
 pub use base_layer_metadata::BaseLayerMetadata;
 pub use base_layer_output::{BaseLayerOutput, CheckpointOutput, CommitteeOutput};
 pub use committee::Committee;
+pub use consensus_signing_request::ConsensusSigningRequest;
 pub use error::ModelError;
 pub use hot_stuff_message::HotStuffMessage;
 pub use hot_stuff_tree_node::HotStuffTreeNode;
@@ -81,7 +83,7 @@ impl InstructionCaller {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum TemplateId {
     Tip002 = 2,
     Tip003 = 3,
@@ -29,7 +29,7 @@ use crate::{
     models::{Instruction, InstructionSet, TemplateId},
     storage::state::{StateDbUnitOfWork, StateDbUnitOfWorkReader},
     template_command::ExecutionResult,
-    templates::{tip002_template, tip004_template, tip721_template},
+    templates::{tip002_template, tip004_template, tip721_template, TemplateRegistry},
 };
 
 pub trait AssetProcessor: Sync + Send + 'static {
@@ -46,6 +46,9 @@ pub trait AssetProcessor: Sync + Send + 'static {
         instruction: &Instruction,
         state_db: &TUnitOfWorkReader,
     ) -> Result<Option<Vec<u8>>, DigitalAssetError>;
+
+    /// Returns the templates, and template versions, that this processor is able to execute
+    fn template_registry(&self) -> &TemplateRegistry;
 }
 
 #[derive(Default, Clone)]
@@ -69,12 +72,22 @@ impl AssetProcessor for ConcreteAssetProcessor {
     ) -> Result<Option<Vec<u8>>, DigitalAssetError> {
         self.template_factory.invoke_read_method(instruction, state_db)
     }
+
+    fn template_registry(&self) -> &TemplateRegistry {
+        self.template_factory.registry()
+    }
 }
 
 #[derive(Default, Clone)]
-pub struct TemplateFactory {}
+pub struct TemplateFactory {
+    registry: TemplateRegistry,
+}
 
 impl TemplateFactory {
+    pub fn registry(&self) -> &TemplateRegistry {
+        &self.registry
+    }
+
     pub fn initial_instructions(&self, template_param: &TemplateParameter) -> InstructionSet {
         use TemplateId::{EditableMetadata, Tip002, Tip003, Tip004, Tip721};
         // TODO: We may want to use the TemplateId type, so that we know it is known/valid
@@ -96,6 +109,7 @@ impl TemplateFactory {
         state_db: &TUnitOfWork,
     ) -> Result<Option<Vec<u8>>, DigitalAssetError> {
         use TemplateId::{EditableMetadata, Tip002, Tip003, Tip004, Tip721};
+        self.registry.validate_instruction(instruction)?;
         match instruction.template_id() {
             Tip002 => tip002_template::invoke_read_method(instruction.method(), instruction.args(), state_db),
             Tip003 => todo!(),
@@ -113,6 +127,7 @@ impl TemplateFactory {
         state_db: &mut TUnitOfWork,
     ) -> Result<(), DigitalAssetError> {
         use TemplateId::{EditableMetadata, Tip002, Tip003, Tip004, Tip721};
+        self.registry.validate_instruction(instruction)?;
         match instruction.template_id() {
             Tip002 => tip002_template::invoke_write_method(instruction.method(), instruction.args(), state_db),
             Tip003 => todo!(),
@@ -48,6 +48,7 @@ pub trait AssetProxy: Send + Sync {
         &self,
         asset_public_key: &PublicKey,
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
     ) -> Result<(), DigitalAssetError>;
@@ -56,6 +57,7 @@ pub trait AssetProxy: Send + Sync {
         &self,
         asset_public_key: &PublicKey,
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
     ) -> Result<Option<Vec<u8>>, DigitalAssetError>;
@@ -97,12 +99,13 @@ impl<TServiceSpecification: ServiceSpecification<Addr = PublicKey>> ConcreteAsse
         member: &TServiceSpecification::Addr,
         asset_public_key: &PublicKey,
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
     ) -> Result<Option<Vec<u8>>, DigitalAssetError> {
         let mut client = self.validator_node_client_factory.create_client(member);
         let resp = client
-            .invoke_read_method(asset_public_key, template_id, method, args)
+            .invoke_read_method(asset_public_key, template_id, template_version, method, args)
             .await?;
         Ok(resp)
     }
@@ -112,13 +115,14 @@ impl<TServiceSpecification: ServiceSpecification<Addr = PublicKey>> ConcreteAsse
         member: &TServiceSpecification::Addr,
         asset_public_key: &PublicKey,
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
     ) -> Result<Option<Vec<u8>>, DigitalAssetError> {
         debug!(target: LOG_TARGET, "Forwarding '{}' instruction to {}", member, method);
         let mut client = self.validator_node_client_factory.create_client(member);
         let resp = client
-            .invoke_method(asset_public_key, template_id, method, args)
+            .invoke_method(asset_public_key, template_id, template_version, method, args)
             .await?;
         Ok(resp)
     }
@@ -129,6 +133,7 @@ impl<TServiceSpecification: ServiceSpecification<Addr = PublicKey>> ConcreteAsse
         asset_public_key: &PublicKey,
         invoke_type: InvokeType,
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
     ) -> Result<Option<Vec<u8>>, DigitalAssetError> {
@@ -172,6 +177,7 @@ impl<TServiceSpecification: ServiceSpecification<Addr = PublicKey>> ConcreteAsse
                         member,
                         asset_public_key,
                         template_id,
+                        template_version,
                         method.clone(),
                         args.clone(),
                     ));
@@ -193,6 +199,7 @@ impl<TServiceSpecification: ServiceSpecification<Addr = PublicKey>> ConcreteAsse
                         member,
                         asset_public_key,
                         template_id,
+                        template_version,
                         method.clone(),
                         args.clone(),
                     ));
@@ -221,6 +228,7 @@ impl<TServiceSpecification: ServiceSpecification<Addr = PublicKey>> AssetProxy
         &self,
         asset_public_key: &PublicKey,
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
     ) -> Result<(), DigitalAssetError> {
@@ -228,6 +236,7 @@ impl<TServiceSpecification: ServiceSpecification<Addr = PublicKey>> AssetProxy
         if self.db_factory.get_state_db(asset_public_key)?.is_some() {
             let instruction = Instruction::new(
                 template_id,
+                template_version,
                 method.clone(),
                 args.clone(),
                 /* TokenId(request.token_id.clone()),
@@ -240,7 +249,14 @@ impl<TServiceSpecification: ServiceSpecification<Addr = PublicKey>> AssetProxy
             mempool.submit_instruction(instruction).await
         } else {
             let _result = self
-                .forward_to_committee(asset_public_key, InvokeType::InvokeMethod, template_id, method, args)
+                .forward_to_committee(
+                    asset_public_key,
+                    InvokeType::InvokeMethod,
+                    template_id,
+                    template_version,
+                    method,
+                    args,
+                )
                 .await?;
             Ok(())
         }
@@ -250,6 +266,7 @@ impl<TServiceSpecification: ServiceSpecification<Addr = PublicKey>> AssetProxy
         &self,
         asset_public_key: &PublicKey,
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
     ) -> Result<Option<Vec<u8>>, DigitalAssetError> {
@@ -257,6 +274,7 @@ impl<TServiceSpecification: ServiceSpecification<Addr = PublicKey>> AssetProxy
             asset_public_key,
             InvokeType::InvokeReadMethod,
             template_id,
+            template_version,
             method,
             args,
         )
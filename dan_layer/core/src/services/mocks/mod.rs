@@ -38,6 +38,7 @@ use crate::{
         BaseLayerMetadata,
         BaseLayerOutput,
         Committee,
+        ConsensusSigningRequest,
         Event,
         Instruction,
         Payload,
@@ -56,6 +57,7 @@ use crate::{
         SigningService,
     },
     storage::state::{StateDbUnitOfWork, StateDbUnitOfWorkReader},
+    templates::TemplateRegistry,
 };
 
 #[derive(Debug, Clone)]
@@ -176,8 +178,13 @@ pub struct MockSigningService<TAddr: NodeAddressable> {
     p: PhantomData<TAddr>,
 }
 
+#[async_trait]
 impl<TAddr: NodeAddressable> SigningService<TAddr> for MockSigningService<TAddr> {
-    fn sign(&self, _identity: &TAddr, _challenge: &[u8]) -> Result<Signature, DigitalAssetError> {
+    async fn sign(
+        &self,
+        _identity: &TAddr,
+        _request: &ConsensusSigningRequest,
+    ) -> Result<Signature, DigitalAssetError> {
         Ok(Signature {})
     }
 }
@@ -291,4 +298,8 @@ impl AssetProcessor for MockAssetProcessor {
     ) -> Result<Option<Vec<u8>>, DigitalAssetError> {
         todo!()
     }
+
+    fn template_registry(&self) -> &TemplateRegistry {
+        todo!()
+    }
 }
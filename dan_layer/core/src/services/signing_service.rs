@@ -20,16 +20,23 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use async_trait::async_trait;
 use tari_comms::{types::CommsPublicKey, NodeIdentity};
 
 use crate::{
     digital_assets_error::DigitalAssetError,
-    models::Signature,
+    models::{ConsensusSigningRequest, Signature},
     services::infrastructure_services::NodeAddressable,
 };
 
-pub trait SigningService<TAddr: NodeAddressable> {
-    fn sign(&self, identity: &TAddr, challenge: &[u8]) -> Result<Signature, DigitalAssetError>;
+/// Signs consensus messages on behalf of a node. Implementations that call out to a remote signer should return
+/// `DigitalAssetError::SigningServiceUnavailable` when the signer cannot be reached, so that the consensus worker can
+/// skip the current view instead of stopping.
+///
+/// The signature is made over `request.challenge()`. Implementations never sign arbitrary bytes.
+#[async_trait]
+pub trait SigningService<TAddr: NodeAddressable>: Send + Sync {
+    async fn sign(&self, identity: &TAddr, request: &ConsensusSigningRequest) -> Result<Signature, DigitalAssetError>;
 }
 
 pub struct NodeIdentitySigningService {
@@ -42,8 +49,13 @@ impl NodeIdentitySigningService {
     }
 }
 
+#[async_trait]
 impl SigningService<CommsPublicKey> for NodeIdentitySigningService {
-    fn sign(&self, identity: &CommsPublicKey, _challenge: &[u8]) -> Result<Signature, DigitalAssetError> {
+    async fn sign(
+        &self,
+        identity: &CommsPublicKey,
+        _request: &ConsensusSigningRequest,
+    ) -> Result<Signature, DigitalAssetError> {
         if identity != self.node_identity.public_key() {
             return Err(DigitalAssetError::InvalidSignature);
         }
@@ -46,6 +46,7 @@ pub trait ValidatorNodeRpcClient: Send + Sync {
         &mut self,
         asset_public_key: &PublicKey,
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
     ) -> Result<Option<Vec<u8>>, ValidatorNodeClientError>;
@@ -54,6 +55,7 @@ pub trait ValidatorNodeRpcClient: Send + Sync {
         &mut self,
         asset_public_key: &PublicKey,
         template_id: TemplateId,
+        template_version: u32,
         method: String,
         args: Vec<u8>,
     ) -> Result<Option<Vec<u8>>, ValidatorNodeClientError>;
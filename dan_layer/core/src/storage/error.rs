@@ -23,7 +23,7 @@
 use std::{io, sync::PoisonError};
 
 use lmdb_zero as lmdb;
-use tari_mmr::error::MerkleMountainRangeError;
+use tari_mmr::{error::MerkleMountainRangeError, MerkleProofError};
 use tari_storage::lmdb_store::LMDBError;
 
 #[derive(Debug, thiserror::Error)]
@@ -50,6 +50,8 @@ pub enum StorageError {
     FileSystemPathDoesNotExist,
     #[error("Merkle error:{0}")]
     MerkleMountainRangeError(#[from] MerkleMountainRangeError),
+    #[error("Merkle proof error:{0}")]
+    MerkleProofError(#[from] MerkleProofError),
     #[error("General storage error: {details}")]
     General { details: String },
     #[error("Lock error")]
@@ -31,5 +31,8 @@ pub use state_db::StateDb;
 mod state_db_backend_adapter;
 pub use state_db_backend_adapter::StateDbBackendAdapter;
 
+mod state_query;
+pub use state_query::{StateEntry, StateEntryProof, StatePage, StateQuery};
+
 mod state_op_log;
 pub use state_op_log::{DbStateOpLogEntry, DbStateOperation};
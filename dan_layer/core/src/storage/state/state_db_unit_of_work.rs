@@ -30,13 +30,22 @@ use digest::Digest;
 use log::*;
 use tari_common_types::types::{HashDigest, PublicKey};
 use tari_crypto::common::Blake256;
-use tari_mmr::{MemBackendVec, MerkleMountainRange};
+use tari_mmr::{MemBackendVec, MerkleMountainRange, MerkleProof};
 use tari_utilities::hex::Hex;
 
 use crate::{
     models::{KeyValue, SchemaState, StateOpLogEntry, StateRoot},
     storage::{
-        state::{db_key_value::DbKeyValue, DbStateOpLogEntry, StateDbBackendAdapter},
+        state::{
+            db_key_value::DbKeyValue,
+            state_query::select_page,
+            DbStateOpLogEntry,
+            StateDbBackendAdapter,
+            StateEntry,
+            StateEntryProof,
+            StatePage,
+            StateQuery,
+        },
         StorageError,
         UnitOfWorkTracker,
     },
@@ -44,6 +53,8 @@ use crate::{
 
 const LOG_TARGET: &str = "tari::dan::state_db";
 
+type StateMmr = MerkleMountainRange<Blake256, MemBackendVec<Vec<u8>>>;
+
 pub trait StateDbUnitOfWork: StateDbUnitOfWorkReader {
     fn set_value(&mut self, schema: String, key: Vec<u8>, value: Vec<u8>) -> Result<(), StorageError>;
     fn set_u64(&mut self, schema: &str, key: &[u8], value: u64) -> Result<(), StorageError>;
@@ -59,6 +70,7 @@ pub trait StateDbUnitOfWorkReader: Clone + Send + Sync {
     fn calculate_root(&self) -> Result<StateRoot, StorageError>;
     fn get_all_state(&self) -> Result<Vec<SchemaState>, StorageError>;
     fn get_op_logs_for_height(&self, height: u64) -> Result<Vec<StateOpLogEntry>, StorageError>;
+    fn get_state_page(&self, query: &StateQuery) -> Result<StatePage, StorageError>;
 }
 
 #[derive(Debug, Clone)]
@@ -215,49 +227,7 @@ impl<TBackendAdapter: StateDbBackendAdapter> StateDbUnitOfWorkReader for StateDb
 
     fn calculate_root(&self) -> Result<StateRoot, StorageError> {
         let inner = self.inner.read()?;
-        let tx = inner
-            .backend_adapter
-            .create_transaction()
-            .map_err(TBackendAdapter::Error::into)?;
-        // let root_node : Node<Vec<u8>> = inner.backend_adapter.get_current_state_tree(&tx).into();
-
-        // omg it's an MMR of MMRs
-        let mut top_level_mmr = MerkleMountainRange::<Blake256, _>::new(MemBackendVec::new());
-        let schemas = inner
-            .backend_adapter
-            .get_all_schemas(&tx)
-            .map_err(TBackendAdapter::Error::into)?;
-        debug!(
-            target: LOG_TARGET,
-            "calculate_root: {} key value schemas loaded",
-            schemas.len()
-        );
-
-        for schema in schemas {
-            let mut mmr = MerkleMountainRange::<Blake256, _>::new(MemBackendVec::new());
-            for key_value in inner
-                .backend_adapter
-                .get_all_values_for_schema(&schema, &tx)
-                .map_err(TBackendAdapter::Error::into)?
-            {
-                debug!(
-                    target: LOG_TARGET,
-                    "schema = {}, key = {}, value = {}",
-                    schema,
-                    key_value.key.to_hex(),
-                    key_value.value.to_hex()
-                );
-                if let Some(updated_value) = find_update(&inner, &schema, &key_value.key) {
-                    let hasher = HashDigest::new();
-                    mmr.push(hasher.chain(&key_value.key).chain(updated_value).finalize().to_vec())?;
-                } else {
-                    let hasher = HashDigest::new();
-                    mmr.push(hasher.chain(&key_value.key).chain(&key_value.value).finalize().to_vec())?;
-                }
-            }
-            let hasher = HashDigest::new();
-            top_level_mmr.push(hasher.chain(schema).chain(mmr.get_merkle_root()?).finalize().to_vec())?;
-        }
+        let (top_level_mmr, _) = build_state_mmrs(&inner)?;
         Ok(StateRoot::new(
             top_level_mmr
                 .get_merkle_root()?
@@ -312,6 +282,145 @@ impl<TBackendAdapter: StateDbBackendAdapter> StateDbUnitOfWorkReader for StateDb
         let op_logs = op_logs.into_iter().map(Into::into).collect();
         Ok(op_logs)
     }
+
+    fn get_state_page(&self, query: &StateQuery) -> Result<StatePage, StorageError> {
+        let inner = self.inner.read()?;
+        if !query.include_proofs {
+            let tx = inner
+                .backend_adapter
+                .create_transaction()
+                .map_err(TBackendAdapter::Error::into)?;
+            let key_values = inner
+                .backend_adapter
+                .get_all_values_for_schema(&query.schema, &tx)
+                .map_err(TBackendAdapter::Error::into)?
+                .into_iter()
+                .map(|kv| {
+                    let value = find_update(&inner, &query.schema, &kv.key).unwrap_or(kv.value);
+                    KeyValue { key: kv.key, value }
+                })
+                .collect::<Vec<_>>();
+            let (indexes, next_cursor) = select_page(&key_values, query);
+            let entries = indexes
+                .into_iter()
+                .map(|i| StateEntry {
+                    key: key_values[i].key.clone(),
+                    value: key_values[i].value.clone(),
+                    proof: None,
+                })
+                .collect();
+            return Ok(StatePage {
+                entries,
+                next_cursor,
+                state_root: None,
+            });
+        }
+
+        let (top_level_mmr, schema_mmrs) = build_state_mmrs(&inner)?;
+        let state_root = StateRoot::new(
+            top_level_mmr
+                .get_merkle_root()?
+                .try_into()
+                .expect("MMR output incorrect size"),
+        );
+        let (schema_index, schema_mmr) = match schema_mmrs.iter().enumerate().find(|(_, s)| s.name == query.schema) {
+            Some(found) => found,
+            None => {
+                return Ok(StatePage {
+                    entries: vec![],
+                    next_cursor: None,
+                    state_root: Some(state_root),
+                })
+            },
+        };
+        let schema_root = schema_mmr.mmr.get_merkle_root()?;
+        let schema_proof = MerkleProof::for_leaf_node(&top_level_mmr, schema_index)?;
+        let (indexes, next_cursor) = select_page(&schema_mmr.key_values, query);
+        let entries = indexes
+            .into_iter()
+            .map(|i| {
+                Ok(StateEntry {
+                    key: schema_mmr.key_values[i].key.clone(),
+                    value: schema_mmr.key_values[i].value.clone(),
+                    proof: Some(StateEntryProof {
+                        schema_root: schema_root.clone(),
+                        leaf_index: i,
+                        leaf_proof: MerkleProof::for_leaf_node(&schema_mmr.mmr, i)?,
+                        schema_index,
+                        schema_proof: schema_proof.clone(),
+                    }),
+                })
+            })
+            .collect::<Result<_, StorageError>>()?;
+
+        Ok(StatePage {
+            entries,
+            next_cursor,
+            state_root: Some(state_root),
+        })
+    }
+}
+
+struct SchemaMmr {
+    name: String,
+    key_values: Vec<KeyValue>,
+    mmr: StateMmr,
+}
+
+/// Builds the MMR of each schema and the top level MMR of schema roots that the state root is calculated from.
+fn build_state_mmrs<TBackendAdapter: StateDbBackendAdapter>(
+    inner: &RwLockReadGuard<StateDbUnitOfWorkInner<TBackendAdapter>>,
+) -> Result<(StateMmr, Vec<SchemaMmr>), StorageError> {
+    let tx = inner
+        .backend_adapter
+        .create_transaction()
+        .map_err(TBackendAdapter::Error::into)?;
+
+    // omg it's an MMR of MMRs
+    let mut top_level_mmr = StateMmr::new(MemBackendVec::new());
+    let schemas = inner
+        .backend_adapter
+        .get_all_schemas(&tx)
+        .map_err(TBackendAdapter::Error::into)?;
+    debug!(
+        target: LOG_TARGET,
+        "calculate_root: {} key value schemas loaded",
+        schemas.len()
+    );
+
+    let mut schema_mmrs = Vec::with_capacity(schemas.len());
+    for schema in schemas {
+        let mut mmr = StateMmr::new(MemBackendVec::new());
+        let mut key_values = vec![];
+        for key_value in inner
+            .backend_adapter
+            .get_all_values_for_schema(&schema, &tx)
+            .map_err(TBackendAdapter::Error::into)?
+        {
+            debug!(
+                target: LOG_TARGET,
+                "schema = {}, key = {}, value = {}",
+                schema,
+                key_value.key.to_hex(),
+                key_value.value.to_hex()
+            );
+            let value = find_update(inner, &schema, &key_value.key).unwrap_or(key_value.value);
+            let hasher = HashDigest::new();
+            mmr.push(hasher.chain(&key_value.key).chain(&value).finalize().to_vec())?;
+            key_values.push(KeyValue {
+                key: key_value.key,
+                value,
+            });
+        }
+        let hasher = HashDigest::new();
+        top_level_mmr.push(hasher.chain(&schema).chain(mmr.get_merkle_root()?).finalize().to_vec())?;
+        schema_mmrs.push(SchemaMmr {
+            name: schema,
+            key_values,
+            mmr,
+        });
+    }
+    Ok((top_level_mmr, schema_mmrs))
 }
 
 fn find_update<TBackendAdapter: StateDbBackendAdapter>(
@@ -0,0 +1,131 @@
+//  Copyright 2022. The Tari Project
+//
+//  Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//  following conditions are met:
+//
+//  1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//  disclaimer.
+//
+//  2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//  following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//  3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//  products derived from this software without specific prior written permission.
+//
+//  THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//  INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//  DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//  SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//  SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use tari_mmr::MerkleProof;
+
+use crate::models::{KeyValue, StateRoot};
+
+/// A request for a page of the key-value entries in a state schema.
+#[derive(Debug, Clone, Default)]
+pub struct StateQuery {
+    pub schema: String,
+    /// Only keys starting with this prefix are returned. An empty prefix matches all keys.
+    pub prefix: Vec<u8>,
+    /// Only keys that sort after the cursor are returned. This is the `next_cursor` of the previous page.
+    pub cursor: Option<Vec<u8>>,
+    pub limit: usize,
+    pub include_proofs: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct StatePage {
+    pub entries: Vec<StateEntry>,
+    /// The cursor for the next page, or `None` if this is the last page
+    pub next_cursor: Option<Vec<u8>>,
+    /// The state root that the entry proofs verify against. Only set if proofs were requested.
+    pub state_root: Option<StateRoot>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StateEntry {
+    pub key: Vec<u8>,
+    pub value: Vec<u8>,
+    pub proof: Option<StateEntryProof>,
+}
+
+/// Proves that a state entry is included in the state root. The state root is an MMR of schema roots, each of which
+/// is an MMR of key-value hashes, so the proof is in two parts: `H(key || value)` at `leaf_index` in the schema MMR,
+/// and `H(schema || schema_root)` at `schema_index` in the state MMR.
+#[derive(Debug, Clone)]
+pub struct StateEntryProof {
+    pub schema_root: Vec<u8>,
+    pub leaf_index: usize,
+    pub leaf_proof: MerkleProof,
+    pub schema_index: usize,
+    pub schema_proof: MerkleProof,
+}
+
+/// Returns the indexes into `key_values` that make up the page requested by `query`, along with the cursor for the
+/// next page. `key_values` must be sorted by key.
+pub(crate) fn select_page(key_values: &[KeyValue], query: &StateQuery) -> (Vec<usize>, Option<Vec<u8>>) {
+    let mut matching = key_values
+        .iter()
+        .enumerate()
+        .filter(|(_, kv)| kv.key.starts_with(&query.prefix))
+        .filter(|(_, kv)| query.cursor.as_ref().map(|c| kv.key > *c).unwrap_or(true));
+
+    let indexes = matching.by_ref().take(query.limit).map(|(i, _)| i).collect::<Vec<_>>();
+    let next_cursor = if matching.next().is_some() {
+        indexes.last().map(|i| key_values[*i].key.clone())
+    } else {
+        None
+    };
+    (indexes, next_cursor)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key_values(keys: &[&[u8]]) -> Vec<KeyValue> {
+        keys.iter()
+            .map(|k| KeyValue {
+                key: k.to_vec(),
+                value: vec![],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_pages_through_matching_keys() {
+        let kvs = key_values(&[b"a1", b"b1", b"b2", b"b3", b"c1"]);
+        let mut query = StateQuery {
+            schema: "test".to_string(),
+            prefix: b"b".to_vec(),
+            cursor: None,
+            limit: 2,
+            include_proofs: false,
+        };
+
+        let (indexes, next_cursor) = select_page(&kvs, &query);
+        assert_eq!(indexes, vec![1, 2]);
+        assert_eq!(next_cursor, Some(b"b2".to_vec()));
+
+        query.cursor = next_cursor;
+        let (indexes, next_cursor) = select_page(&kvs, &query);
+        assert_eq!(indexes, vec![3]);
+        assert_eq!(next_cursor, None);
+    }
+
+    #[test]
+    fn it_returns_no_cursor_when_the_page_is_exactly_full() {
+        let kvs = key_values(&[b"a1", b"a2"]);
+        let query = StateQuery {
+            schema: "test".to_string(),
+            limit: 2,
+            ..Default::default()
+        };
+        let (indexes, next_cursor) = select_page(&kvs, &query);
+        assert_eq!(indexes, vec![0, 1]);
+        assert_eq!(next_cursor, None);
+    }
+}
@@ -23,3 +23,6 @@
 pub mod tip002_template;
 pub mod tip004_template;
 pub mod tip721_template;
+
+mod template_registry;
+pub use template_registry::{TemplateRegistry, TemplateVersionInfo, INITIAL_TEMPLATE_VERSION};
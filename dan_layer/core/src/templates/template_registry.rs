@@ -0,0 +1,143 @@
+// Copyright 2021. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::collections::HashMap;
+
+use crate::{
+    models::{Instruction, TemplateId},
+    DigitalAssetError,
+};
+
+/// The first version of every template
+pub const INITIAL_TEMPLATE_VERSION: u32 = 1;
+
+/// The range of versions of a template that this node is able to execute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateVersionInfo {
+    pub min_supported_version: u32,
+    pub current_version: u32,
+}
+
+/// Keeps track of the templates, and the versions of each template, that this node supports.
+#[derive(Debug, Clone)]
+pub struct TemplateRegistry {
+    templates: HashMap<TemplateId, TemplateVersionInfo>,
+}
+
+impl TemplateRegistry {
+    pub fn empty() -> Self {
+        Self {
+            templates: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, template_id: TemplateId, min_supported_version: u32, current_version: u32) -> &mut Self {
+        self.templates.insert(template_id, TemplateVersionInfo {
+            min_supported_version,
+            current_version,
+        });
+        self
+    }
+
+    pub fn get(&self, template_id: TemplateId) -> Result<TemplateVersionInfo, DigitalAssetError> {
+        self.templates
+            .get(&template_id)
+            .copied()
+            .ok_or(DigitalAssetError::TemplateNotRegistered { template_id })
+    }
+
+    /// Returns the requested version, or the current version of the template if no version (zero) was requested
+    pub fn resolve_version(&self, template_id: TemplateId, requested_version: u32) -> Result<u32, DigitalAssetError> {
+        if requested_version == 0 {
+            return Ok(self.get(template_id)?.current_version);
+        }
+        Ok(requested_version)
+    }
+
+    /// Checks that the instruction targets a version of its template that is supported by this node
+    pub fn validate_instruction(&self, instruction: &Instruction) -> Result<(), DigitalAssetError> {
+        self.validate_version(instruction.template_id(), instruction.template_version())
+    }
+
+    pub fn validate_version(&self, template_id: TemplateId, version: u32) -> Result<(), DigitalAssetError> {
+        let info = self.get(template_id)?;
+        if version < info.min_supported_version || version > info.current_version {
+            return Err(DigitalAssetError::UnsupportedTemplateVersion {
+                template_id,
+                version,
+                min_supported_version: info.min_supported_version,
+                max_supported_version: info.current_version,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for TemplateRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register(TemplateId::Tip002, INITIAL_TEMPLATE_VERSION, INITIAL_TEMPLATE_VERSION)
+            .register(TemplateId::Tip004, INITIAL_TEMPLATE_VERSION, INITIAL_TEMPLATE_VERSION)
+            .register(TemplateId::Tip721, INITIAL_TEMPLATE_VERSION, INITIAL_TEMPLATE_VERSION);
+        registry
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_resolves_the_current_version() {
+        let mut registry = TemplateRegistry::empty();
+        registry.register(TemplateId::Tip002, 1, 3);
+        assert_eq!(registry.resolve_version(TemplateId::Tip002, 0).unwrap(), 3);
+        assert_eq!(registry.resolve_version(TemplateId::Tip002, 2).unwrap(), 2);
+        assert!(matches!(
+            registry.resolve_version(TemplateId::Tip721, 0),
+            Err(DigitalAssetError::TemplateNotRegistered {
+                template_id: TemplateId::Tip721
+            })
+        ));
+    }
+
+    #[test]
+    fn it_validates_the_template_version() {
+        let mut registry = TemplateRegistry::empty();
+        registry.register(TemplateId::Tip002, 2, 4);
+        registry.validate_version(TemplateId::Tip002, 2).unwrap();
+        registry.validate_version(TemplateId::Tip002, 4).unwrap();
+        // Older than the minimum supported version
+        assert!(matches!(
+            registry.validate_version(TemplateId::Tip002, 1),
+            Err(DigitalAssetError::UnsupportedTemplateVersion {
+                min_supported_version: 2,
+                max_supported_version: 4,
+                ..
+            })
+        ));
+        // Newer than this node supports
+        assert!(registry.validate_version(TemplateId::Tip002, 5).is_err());
+        assert!(registry.validate_version(TemplateId::Tip004, 1).is_err());
+    }
+}
@@ -28,12 +28,14 @@ use tari_utilities::{hex::Hex, ByteArray};
 use crate::{
     models::{Instruction, InstructionSet, TemplateId},
     storage::state::{StateDbUnitOfWork, StateDbUnitOfWorkReader},
+    templates::INITIAL_TEMPLATE_VERSION,
     DigitalAssetError,
 };
 
 pub fn initial_instructions(template_param: &TemplateParameter) -> InstructionSet {
     InstructionSet::from_vec(vec![Instruction::new(
         TemplateId::Tip002,
+        INITIAL_TEMPLATE_VERSION,
         "init".to_string(),
         template_param.template_data.clone(),
     )])
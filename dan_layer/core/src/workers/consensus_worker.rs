@@ -145,7 +145,21 @@ impl<TSpecification: ServiceSpecification<Addr = PublicKey>> ConsensusWorker<TSp
                 chain_db: &chain_db,
                 shutdown: &shutdown,
             };
-            let next_event = processor.next_state_event().await?;
+            let next_event = match processor.next_state_event().await {
+                Ok(event) => event,
+                // This node cannot vote without a signature, but it can take part again once the signer is back
+                Err(DigitalAssetError::SigningServiceUnavailable(err)) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Signing service unavailable in {:?} state for {}: {}. Moving to the next view.",
+                        self.state,
+                        self.current_view_id,
+                        err
+                    );
+                    ConsensusWorkerStateEvent::TimedOut
+                },
+                Err(err) => return Err(err),
+            };
             if next_event.must_shutdown() {
                 info!(
                     target: LOG_TARGET,
@@ -246,7 +246,8 @@ impl<TSpecification: ServiceSpecification> PreCommitState<TSpecification> {
         signing_service: &TSpecification::SigningService,
     ) -> Result<(), DigitalAssetError> {
         let mut message = HotStuffMessage::vote_pre_commit(node, view_number, self.asset_public_key.clone());
-        message.add_partial_sig(signing_service.sign(&self.node_id, &message.create_signature_challenge())?);
+        let signature = signing_service.sign(&self.node_id, &message.signing_request()).await?;
+        message.add_partial_sig(signature);
         outbound.send(self.node_id.clone(), view_leader.clone(), message).await
     }
 }
@@ -386,7 +386,8 @@ impl<TSpecification: ServiceSpecification> Prepare<TSpecification> {
     ) -> Result<(), DigitalAssetError> {
         // TODO: Only send node hash, not the full node
         let mut message = HotStuffMessage::vote_prepare(node, view_number, self.asset_public_key.clone());
-        message.add_partial_sig(signing_service.sign(&self.node_id, &message.create_signature_challenge())?);
+        let signature = signing_service.sign(&self.node_id, &message.signing_request()).await?;
+        message.add_partial_sig(signature);
         outbound.send(self.node_id.clone(), view_leader.clone(), message).await
     }
 }
@@ -0,0 +1 @@
+alter table instructions drop column template_version;
@@ -0,0 +1,2 @@
+-- Assets created before template versioning use version 1 of their template
+alter table instructions add column template_version int not null default 1;
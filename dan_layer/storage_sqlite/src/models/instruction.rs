@@ -33,6 +33,7 @@ pub struct Instruction {
     pub template_id: i32,
     pub method: String,
     pub args: Vec<u8>,
+    pub template_version: i32,
 }
 
 impl TryFrom<Instruction> for tari_dan_core::models::Instruction {
@@ -40,7 +41,15 @@ impl TryFrom<Instruction> for tari_dan_core::models::Instruction {
 
     fn try_from(instruction: Instruction) -> Result<Self, Self::Error> {
         let template_id = instruction.template_id.try_into()?;
-        Ok(Self::new(template_id, instruction.method, instruction.args))
+        let template_version = u32::try_from(instruction.template_version).map_err(|_| {
+            SqliteStorageError::MalformedDbData(format!("Invalid template version {}", instruction.template_version))
+        })?;
+        Ok(Self::new(
+            template_id,
+            template_version,
+            instruction.method,
+            instruction.args,
+        ))
     }
 }
 
@@ -52,4 +61,5 @@ pub struct NewInstruction {
     pub template_id: i32,
     pub method: String,
     pub args: Vec<u8>,
+    pub template_version: i32,
 }
@@ -9,6 +9,7 @@ table! {
         template_id -> Integer,
         method -> Text,
         args -> Binary,
+        template_version -> Integer,
     }
 }
 
@@ -417,6 +417,7 @@ impl ChainDbBackendAdapter for SqliteChainBackendAdapter {
             template_id: item.instruction.template_id() as i32,
             method: item.instruction.method().to_string(),
             args: Vec::from(item.instruction.args()),
+            template_version: item.instruction.template_version() as i32,
         };
         diesel::insert_into(instructions::table)
             .values(new_instruction)
@@ -108,6 +108,11 @@ impl TariScript {
         self.script.len()
     }
 
+    /// Returns the script op codes
+    pub fn opcodes(&self) -> &[Opcode] {
+        &self.script
+    }
+
     fn should_execute(&self, opcode: &Opcode, state: &ExecutionState) -> Result<bool, ScriptError> {
         use Opcode::{Else, EndIf, IfThen};
         match opcode {